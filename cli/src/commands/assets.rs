@@ -0,0 +1,756 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use arcane_core::renderer::bitmap_font::{BitmapFontManifest, ascii_charset};
+use arcane_core::renderer::aseprite::AsepriteSheet;
+use arcane_core::renderer::palette::{ColorHistogram, palette_gpl, palette_json, palette_strip};
+use arcane_core::renderer::thumbnail::{THUMBNAIL_SIZE, animation_frames, encode_gif, encode_png, thumbnail};
+
+use super::catalog;
+
+/// Manifest file name, written at the project root.
+const MANIFEST_FILE: &str = "asset-manifest.json";
+
+/// License of every catalog pack (the catalog only lists CC0 packs).
+const CATALOG_LICENSE: &str = "CC0-1.0";
+
+/// File extensions treated as asset references when found in TS string literals.
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "wav", "ogg", "mp3", "flac", "ttf", "otf", "fnt",
+];
+
+// ---------------------------------------------------------------------------
+// Data types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Manifest {
+    version: u32,
+    assets: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct ManifestEntry {
+    /// Path relative to the project root, with forward slashes.
+    path: String,
+    sha256: String,
+    size: u64,
+    /// Catalog pack the file came from, if known. Editable by hand.
+    #[serde(default)]
+    pack: Option<String>,
+    /// SPDX license identifier, if known. Editable by hand.
+    #[serde(default)]
+    license: Option<String>,
+}
+
+/// Differences between the manifest on disk and the files under `assets/`.
+#[derive(Debug, Default, PartialEq)]
+struct Drift {
+    changed: Vec<String>,
+    missing: Vec<String>,
+    untracked: Vec<String>,
+}
+
+impl Drift {
+    fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty() && self.untracked.is_empty()
+    }
+}
+
+/// An asset path in TS source that does not resolve to a file.
+#[derive(Debug)]
+struct BrokenReference {
+    file: String,
+    line: usize,
+    literal: String,
+}
+
+/// One file under `assets/`, as listed by `arcane assets inspect`.
+#[derive(Debug, Serialize)]
+struct InspectedAsset {
+    /// Path relative to the project root, with forward slashes.
+    path: String,
+    /// "image", "sheet" (Aseprite JSON), "sound", "font", "data" or "other".
+    kind: &'static str,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u32>,
+    /// Frame count of a sheet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frames: Option<usize>,
+    /// Animation tags of a sheet.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Thumbnails written for this asset.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    thumbnails: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Command
+// ---------------------------------------------------------------------------
+
+/// `arcane assets verify`: build or check `asset-manifest.json` and look for
+/// asset paths in TS that point at missing files. Fails on any problem, so it
+/// can gate CI. With `update`, the manifest is rewritten instead of checked.
+pub fn verify(path: Option<String>, update: bool) -> Result<()> {
+    let root = path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().expect("cannot read current directory"));
+    let manifest_path = root.join(MANIFEST_FILE);
+
+    let previous = if manifest_path.exists() {
+        let text = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Cannot read {}", manifest_path.display()))?;
+        let manifest: Manifest = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid {MANIFEST_FILE}"))?;
+        Some(manifest.assets)
+    } else {
+        None
+    };
+
+    let pack_ids = catalog::pack_ids();
+    let mut current = scan_assets(&root, &pack_ids)?;
+    // Keep hand-edited attribution for files that are still present
+    if let Some(ref previous) = previous {
+        for entry in &mut current {
+            if let Some(old) = previous.iter().find(|e| e.path == entry.path) {
+                entry.pack = old.pack.clone().or(entry.pack.take());
+                entry.license = old.license.clone().or(entry.license.take());
+            }
+        }
+    }
+
+    let mut problems = 0;
+    match previous {
+        Some(ref previous) if !update => {
+            let drift = diff_manifest(previous, &current);
+            for path in &drift.changed {
+                println!("changed    {path}");
+            }
+            for path in &drift.missing {
+                println!("missing    {path}");
+            }
+            for path in &drift.untracked {
+                println!("untracked  {path}");
+            }
+            if !drift.is_empty() {
+                println!("Run `arcane assets verify --update` after intentional asset changes.");
+            }
+            problems += drift.changed.len() + drift.missing.len() + drift.untracked.len();
+        }
+        _ => {
+            let manifest = Manifest {
+                version: 1,
+                assets: current.clone(),
+            };
+            let json = serde_json::to_string_pretty(&manifest)?;
+            fs::write(&manifest_path, json + "\n")
+                .with_context(|| format!("Cannot write {}", manifest_path.display()))?;
+            println!("Wrote {MANIFEST_FILE} ({} assets)", current.len());
+        }
+    }
+
+    let unattributed = current.iter().filter(|e| e.license.is_none()).count();
+    if unattributed > 0 {
+        println!("{unattributed} asset(s) have no license recorded in {MANIFEST_FILE}");
+    }
+
+    let known: Vec<&str> = current.iter().map(|e| e.path.as_str()).collect();
+    let broken = find_broken_references(&root, &known)?;
+    for b in &broken {
+        println!("broken     {}:{} \"{}\"", b.file, b.line, b.literal);
+    }
+    problems += broken.len();
+
+    if problems > 0 {
+        anyhow::bail!("{problems} asset problem(s) found");
+    }
+    println!("Assets OK ({} files)", current.len());
+    Ok(())
+}
+
+/// `arcane assets inspect`: list every file under `assets/` with its kind,
+/// size and image dimensions or sheet frames and tags. With `thumbnails`,
+/// small previews are written to that directory, mirroring `assets/`: a PNG
+/// per image and, for each Aseprite sheet, a GIF per tag (or one of all its
+/// frames) with the sheet's frame durations. Their paths are in the listing.
+pub fn inspect(path: Option<String>, json: bool, thumbnails: Option<String>) -> Result<()> {
+    let root = path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().expect("cannot read current directory"));
+    let mut files = Vec::new();
+    let assets_dir = root.join("assets");
+    if assets_dir.is_dir() {
+        collect_files(&assets_dir, &mut files)?;
+    }
+    files.sort();
+
+    let thumbnail_dir = thumbnails.map(PathBuf::from);
+    let mut assets = Vec::new();
+    for file in &files {
+        let mut asset = inspect_file(&root, file)?;
+        if let Some(ref dir) = thumbnail_dir {
+            // A broken image or sheet is still listed, just without previews
+            match write_thumbnails(&assets_dir, file, &asset, dir) {
+                Ok(written) => asset.thumbnails = written,
+                Err(e) => eprintln!("No thumbnail for {}: {e:#}", asset.path),
+            }
+        }
+        assets.push(asset);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&assets)?);
+        return Ok(());
+    }
+    for asset in &assets {
+        let detail = match (asset.width, asset.height, asset.frames) {
+            (_, _, Some(frames)) if asset.tags.is_empty() => format!("{frames} frames"),
+            (_, _, Some(frames)) => format!("{frames} frames, tags: {}", asset.tags.join(", ")),
+            (Some(w), Some(h), _) => format!("{w}x{h}"),
+            _ => String::new(),
+        };
+        println!("{:<6} {:>9}  {}  {detail}", asset.kind, asset.size, asset.path);
+        for thumbnail in &asset.thumbnails {
+            println!("{:<17} -> {thumbnail}", "");
+        }
+    }
+    println!("{} asset(s)", assets.len());
+    Ok(())
+}
+
+/// Kind, size and dimensions of one asset file.
+fn inspect_file(root: &Path, file: &Path) -> Result<InspectedAsset> {
+    let size = fs::metadata(file).with_context(|| format!("Cannot read {}", file.display()))?.len();
+    let extension = file.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let mut asset = InspectedAsset {
+        path: relative_path(root, file),
+        kind: "other",
+        size,
+        width: None,
+        height: None,
+        frames: None,
+        tags: Vec::new(),
+        thumbnails: Vec::new(),
+    };
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" => {
+            asset.kind = "image";
+            if let Ok((w, h)) = image::image_dimensions(file) {
+                (asset.width, asset.height) = (Some(w), Some(h));
+            }
+        }
+        "wav" | "ogg" | "mp3" | "flac" => asset.kind = "sound",
+        "ttf" | "otf" | "fnt" => asset.kind = "font",
+        "json" => {
+            asset.kind = "data";
+            let text = fs::read_to_string(file).unwrap_or_default();
+            if let Ok(sheet) = AsepriteSheet::parse(&text) {
+                asset.kind = "sheet";
+                (asset.width, asset.height) = (Some(sheet.width), Some(sheet.height));
+                asset.frames = Some(sheet.frames.len());
+                asset.tags = sheet.tags.iter().map(|t| t.name.clone()).collect();
+            }
+        }
+        _ => {}
+    }
+    Ok(asset)
+}
+
+/// Write the previews of one asset under `dir`, at its path below `assets/`.
+/// Returns the files written.
+fn write_thumbnails(assets_dir: &Path, file: &Path, asset: &InspectedAsset, dir: &Path) -> Result<Vec<String>> {
+    let relative = file.strip_prefix(assets_dir).unwrap_or(file);
+    let base = dir.join(relative);
+    let stem = base.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let mut outputs: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    match asset.kind {
+        "image" => {
+            let image = image::open(file).with_context(|| format!("Cannot read image {}", file.display()))?.to_rgba8();
+            outputs.push((base.with_file_name(format!("{stem}.png")), encode_png(&thumbnail(&image, THUMBNAIL_SIZE))?));
+        }
+        "sheet" => {
+            let sheet = AsepriteSheet::parse(&fs::read_to_string(file)?).map_err(|e| anyhow::anyhow!(e))?;
+            let image_path = file.with_file_name(&sheet.image);
+            let image = image::open(&image_path)
+                .with_context(|| format!("Cannot read sheet image {}", image_path.display()))?
+                .to_rgba8();
+            if sheet.tags.is_empty() {
+                let frames = animation_frames(&sheet, &image, None, THUMBNAIL_SIZE);
+                outputs.push((base.with_file_name(format!("{stem}.gif")), encode_gif(&frames)?));
+            }
+            for tag in &sheet.tags {
+                let frames = animation_frames(&sheet, &image, Some(tag), THUMBNAIL_SIZE);
+                outputs.push((base.with_file_name(format!("{stem}.{}.gif", tag.name)), encode_gif(&frames)?));
+            }
+        }
+        _ => {}
+    }
+
+    let mut written = Vec::new();
+    for (path, bytes) in outputs {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Cannot create {}", parent.display()))?;
+        }
+        fs::write(&path, bytes).with_context(|| format!("Cannot write {}", path.display()))?;
+        written.push(path.to_string_lossy().replace('\\', "/"));
+    }
+    Ok(written)
+}
+
+/// `arcane assets font-from-grid`: write a bitmap font manifest for an image
+/// of equally sized glyph cells, for use with `loadBitmapFont()`. With
+/// `proportional`, each glyph advances by the width of its ink instead of the
+/// full cell. The manifest goes next to the image as `<name>.font.json`
+/// unless `out` is given.
+pub fn font_from_grid(
+    image: String,
+    cell: String,
+    charset: Option<String>,
+    spacing: f32,
+    proportional: bool,
+    out: Option<String>,
+) -> Result<()> {
+    let image_path = PathBuf::from(&image);
+    let (cell_w, cell_h) = parse_cell(&cell)?;
+    let rgba = image::open(&image_path)
+        .with_context(|| format!("Cannot read image {}", image_path.display()))?
+        .to_rgba8();
+    let charset = charset.unwrap_or_else(ascii_charset);
+
+    let mut manifest = BitmapFontManifest::for_grid(rgba.width(), rgba.height(), cell_w, cell_h, &charset)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    manifest.image = image_path.file_name().map(|n| n.to_string_lossy().into_owned());
+    manifest.spacing = spacing;
+    if proportional {
+        manifest.measure_advances(rgba.as_raw(), rgba.width());
+    }
+
+    let out_path = out.map(PathBuf::from).unwrap_or_else(|| font_manifest_path(&image_path));
+    fs::write(&out_path, manifest.to_json() + "\n")
+        .with_context(|| format!("Cannot write {}", out_path.display()))?;
+    let cells = manifest.columns * manifest.rows;
+    println!(
+        "Wrote {} ({} glyphs, {}x{} grid of {cell_w}x{cell_h} cells)",
+        out_path.display(),
+        manifest.charset.len(),
+        manifest.columns,
+        manifest.rows,
+    );
+    if (manifest.charset.len() as u32) < cells {
+        println!("{} trailing cell(s) have no character", cells - manifest.charset.len() as u32);
+    }
+    Ok(())
+}
+
+/// `arcane assets palette`: extract the dominant colors of an image, or of
+/// every PNG in a downloaded pack, and write them as JSON, or as a GIMP
+/// palette when `out` ends in `.gpl`. With `lut`, the palette is also saved as
+/// an N×1 PNG strip for palette shaders.
+pub fn palette(source: String, colors: usize, out: Option<String>, lut: Option<String>) -> Result<()> {
+    if !(1..=256).contains(&colors) {
+        anyhow::bail!("--colors must be between 1 and 256");
+    }
+    let source_path = PathBuf::from(&source);
+    let mut histogram = ColorHistogram::new();
+    let mut images = 0;
+    let default_out = if source_path.is_file() {
+        let rgba = image::open(&source_path)
+            .with_context(|| format!("Cannot read image {}", source_path.display()))?
+            .to_rgba8();
+        histogram.add_rgba(rgba.as_raw());
+        images = 1;
+        palette_path(&source_path)
+    } else {
+        let pngs = catalog::downloaded_pack_pngs(&source).with_context(|| {
+            format!("\"{source}\" is not an image or a downloaded pack (try `arcane assets download {source}`)")
+        })?;
+        for png in &pngs {
+            // Packs sometimes ship previews in odd PNG flavours; skip what can't be read
+            match image::open(png) {
+                Ok(img) => {
+                    histogram.add_rgba(img.to_rgba8().as_raw());
+                    images += 1;
+                }
+                Err(e) => eprintln!("Skipping {}: {e}", png.display()),
+            }
+        }
+        PathBuf::from(format!("{}.palette.json", source.replace(':', "-")))
+    };
+    if histogram.pixel_count() == 0 {
+        anyhow::bail!("No opaque pixels found in {source}");
+    }
+
+    let swatches = histogram.palette(colors);
+    let out_path = out.map(PathBuf::from).unwrap_or(default_out);
+    let is_gpl = out_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gpl"));
+    let text = if is_gpl {
+        let name = out_path.file_stem().unwrap_or_default().to_string_lossy();
+        palette_gpl(name.trim_end_matches(".palette"), &swatches)
+    } else {
+        palette_json(&source, &swatches) + "\n"
+    };
+    fs::write(&out_path, text).with_context(|| format!("Cannot write {}", out_path.display()))?;
+    println!(
+        "Wrote {} ({} colors from {images} image(s))",
+        out_path.display(),
+        swatches.len()
+    );
+
+    if let Some(lut) = lut {
+        image::save_buffer(
+            &lut,
+            &palette_strip(&swatches),
+            swatches.len() as u32,
+            1,
+            image::ExtendedColorType::Rgba8,
+        )
+        .with_context(|| format!("Cannot write {lut}"))?;
+        println!("Wrote {lut} ({}x1 palette strip)", swatches.len());
+    }
+
+    for swatch in &swatches {
+        println!("  {}  {:5.1}%", swatch.hex(), swatch.share * 100.0);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Bitmap fonts
+// ---------------------------------------------------------------------------
+
+/// Parse a cell size like "8x8" or "6x10".
+fn parse_cell(cell: &str) -> Result<(u32, u32)> {
+    let parsed = cell
+        .split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+    match parsed {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => anyhow::bail!("Invalid cell size \"{cell}\" (expected WIDTHxHEIGHT, e.g. 8x8)"),
+    }
+}
+
+/// `fonts/pixel.png` → `fonts/pixel.font.json`
+fn font_manifest_path(image: &Path) -> PathBuf {
+    let stem = image.file_stem().unwrap_or_default().to_string_lossy();
+    image.with_file_name(format!("{stem}.font.json"))
+}
+
+/// `art/hero.png` → `art/hero.palette.json`
+fn palette_path(image: &Path) -> PathBuf {
+    let stem = image.file_stem().unwrap_or_default().to_string_lossy();
+    image.with_file_name(format!("{stem}.palette.json"))
+}
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+/// Hash every file under `root/assets/`, sorted by path.
+fn scan_assets(root: &Path, pack_ids: &[String]) -> Result<Vec<ManifestEntry>> {
+    let mut files = Vec::new();
+    let assets_dir = root.join("assets");
+    if assets_dir.is_dir() {
+        collect_files(&assets_dir, &mut files)?;
+    }
+    files.sort();
+
+    files
+        .iter()
+        .map(|file| {
+            let data = fs::read(file).with_context(|| format!("Cannot read {}", file.display()))?;
+            let path = relative_path(root, file);
+            let pack = infer_pack(&path, pack_ids);
+            Ok(ManifestEntry {
+                sha256: format!("{:x}", Sha256::digest(&data)),
+                size: data.len() as u64,
+                license: pack.as_ref().map(|_| CATALOG_LICENSE.to_string()),
+                pack,
+                path,
+            })
+        })
+        .collect()
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // Skip dotfiles (.DS_Store) and never follow symlinks
+        if entry.file_name().to_string_lossy().starts_with('.') || path.is_symlink() {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `root`-relative path with forward slashes, as stored in the manifest.
+fn relative_path(root: &Path, file: &Path) -> String {
+    let rel = file.strip_prefix(root).unwrap_or(file);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Catalog packs are copied to `assets/<pack-id>/`.
+fn infer_pack(path: &str, pack_ids: &[String]) -> Option<String> {
+    let dir = path.strip_prefix("assets/")?.split('/').next()?;
+    pack_ids.iter().find(|id| *id == dir).cloned()
+}
+
+fn diff_manifest(previous: &[ManifestEntry], current: &[ManifestEntry]) -> Drift {
+    let before: BTreeMap<&str, &ManifestEntry> =
+        previous.iter().map(|e| (e.path.as_str(), e)).collect();
+    let after: BTreeMap<&str, &ManifestEntry> =
+        current.iter().map(|e| (e.path.as_str(), e)).collect();
+
+    let mut drift = Drift::default();
+    for (path, old) in &before {
+        match after.get(path) {
+            Some(new) if new.sha256 != old.sha256 || new.size != old.size => {
+                drift.changed.push(path.to_string())
+            }
+            Some(_) => {}
+            None => drift.missing.push(path.to_string()),
+        }
+    }
+    for path in after.keys() {
+        if !before.contains_key(path) {
+            drift.untracked.push(path.to_string());
+        }
+    }
+    drift
+}
+
+// ---------------------------------------------------------------------------
+// TS references
+// ---------------------------------------------------------------------------
+
+/// Scan project TS files for asset path literals that do not resolve.
+///
+/// A literal resolves if it exists relative to the file that contains it or
+/// to the project root, or if it is a suffix of a known asset path (paths
+/// given relative to an atlas `basePath`).
+fn find_broken_references(root: &Path, known: &[&str]) -> Result<Vec<BrokenReference>> {
+    let mut files = Vec::new();
+    collect_sources(root, &mut files)?;
+    files.sort();
+
+    let mut broken = Vec::new();
+    for file in &files {
+        let Ok(source) = fs::read_to_string(file) else {
+            continue;
+        };
+        let dir = file.parent().unwrap_or(root);
+        let mut seen = HashSet::new();
+        for (line, literal) in asset_literals(&source) {
+            if !seen.insert((line, literal.clone())) {
+                continue;
+            }
+            let suffix = format!("/{}", literal.trim_start_matches("./"));
+            let resolves = dir.join(&literal).is_file()
+                || root.join(&literal).is_file()
+                || known.iter().any(|k| k.ends_with(&suffix));
+            if !resolves {
+                broken.push(BrokenReference {
+                    file: relative_path(root, file),
+                    line,
+                    literal,
+                });
+            }
+        }
+    }
+    Ok(broken)
+}
+
+/// Project TS sources, excluding declarations and tests.
+fn collect_sources(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_symlink() {
+            continue;
+        }
+        if path.is_dir() {
+            if name == "node_modules" || name == "target" || name == "assets" || name.starts_with('.') {
+                continue;
+            }
+            collect_sources(&path, out)?;
+        } else if (name.ends_with(".ts") || name.ends_with(".tsx"))
+            && !name.ends_with(".d.ts")
+            && !name.ends_with(".test.ts")
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// String literals in `source` that look like asset paths, with 1-based line
+/// numbers. Comments are skipped, as are URLs and interpolated template strings.
+fn asset_literals(source: &str) -> Vec<(usize, String)> {
+    let mut out = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            quote @ ('"' | '\'' | '`') => {
+                let start_line = line;
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        c if c == quote => break,
+                        '\n' => {
+                            line += 1;
+                            // Only template strings span lines
+                            if quote != '`' {
+                                break;
+                            }
+                            value.push(c);
+                        }
+                        _ => value.push(c),
+                    }
+                }
+                if is_asset_path(&value) {
+                    out.push((start_line, value));
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn is_asset_path(value: &str) -> bool {
+    if value.contains("://") || value.contains("${") || value.contains('\n') {
+        return false;
+    }
+    let Some((_, ext)) = value.rsplit_once('.') else {
+        return false;
+    };
+    ASSET_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, sha256: &str) -> ManifestEntry {
+        ManifestEntry {
+            path: path.to_string(),
+            sha256: sha256.to_string(),
+            size: 1,
+            pack: None,
+            license: None,
+        }
+    }
+
+    #[test]
+    fn parse_cell_accepts_width_x_height() {
+        assert_eq!(parse_cell("8x8").unwrap(), (8, 8));
+        assert_eq!(parse_cell("6X10").unwrap(), (6, 10));
+        assert!(parse_cell("8").is_err());
+        assert!(parse_cell("0x8").is_err());
+        assert_eq!(
+            font_manifest_path(Path::new("fonts/pixel.png")),
+            PathBuf::from("fonts/pixel.font.json")
+        );
+    }
+
+    #[test]
+    fn palette_path_sits_next_to_image() {
+        assert_eq!(
+            palette_path(Path::new("art/hero.png")),
+            PathBuf::from("art/hero.palette.json")
+        );
+    }
+
+    #[test]
+    fn asset_literals_skip_comments_urls_and_templates() {
+        let source = r#"
+// loadTexture("commented.png")
+const a = loadTexture("assets/hero.png");
+/* loadSound("block.wav")
+   still a comment */
+const b = loadSound('assets/sfx/jump.WAV');
+const c = `assets/${name}.png`;
+const d = "https://example.com/remote.png";
+const e = "not-an-asset.ts";
+"#;
+        let found = asset_literals(source);
+        assert_eq!(
+            found,
+            vec![
+                (3, "assets/hero.png".to_string()),
+                (6, "assets/sfx/jump.WAV".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_manifest_reports_changed_missing_and_untracked() {
+        let previous = vec![entry("assets/a.png", "1"), entry("assets/b.png", "2")];
+        let current = vec![entry("assets/a.png", "9"), entry("assets/c.png", "3")];
+        let drift = diff_manifest(&previous, &current);
+        assert_eq!(drift.changed, vec!["assets/a.png"]);
+        assert_eq!(drift.missing, vec!["assets/b.png"]);
+        assert_eq!(drift.untracked, vec!["assets/c.png"]);
+        assert!(diff_manifest(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn infer_pack_matches_catalog_directory() {
+        let ids = vec!["tiny-dungeon".to_string()];
+        assert_eq!(
+            infer_pack("assets/tiny-dungeon/Tiles/tile_0001.png", &ids),
+            Some("tiny-dungeon".to_string())
+        );
+        assert_eq!(infer_pack("assets/hero.png", &ids), None);
+        assert_eq!(infer_pack("assets/other/tiny-dungeon/x.png", &ids), None);
+    }
+}
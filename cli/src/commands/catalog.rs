@@ -1,9 +1,15 @@
 use anyhow::Result;
 use include_dir::{include_dir, Dir};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use super::config::{self, CatalogSource};
 
 static CATALOG_DIR: Dir<'static> = include_dir!("$OUT_DIR/catalog");
 
@@ -21,6 +27,7 @@ struct Catalog {
 struct CatalogPack {
     id: String,
     name: String,
+    #[serde(default)]
     source: String,
     download_url: String,
     #[serde(default)]
@@ -31,6 +38,12 @@ struct CatalogPack {
     grid_offset: Option<GridOffset>,
     #[serde(default)]
     tags: Vec<String>,
+    /// Alternate download URLs, tried in order when `download_url` fails.
+    #[serde(default)]
+    mirrors: Vec<String>,
+    /// Expected SHA-256 of the pack ZIP (hex). Checked after download when set.
+    #[serde(default)]
+    sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -122,6 +135,137 @@ fn load_catalog_json() -> String {
         .to_string()
 }
 
+/// IDs of all catalog packs. Used by `arcane assets verify` to attribute
+/// files under `assets/<pack-id>/` to their pack.
+pub(crate) fn pack_ids() -> Vec<String> {
+    load_packs()
+        .map(|packs| packs.into_iter().map(|p| p.id).collect())
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// User catalog sources
+// ---------------------------------------------------------------------------
+
+/// Separates the source name from the pack ID for packs from user sources.
+const SOURCE_SEPARATOR: char = ':';
+
+/// Built-in packs followed by the packs of every catalog source in
+/// `arcane.toml`, with IDs prefixed `<source>:`. A source that can't be
+/// loaded is skipped with a warning so one unreachable server doesn't hide
+/// the rest.
+fn load_packs() -> Result<Vec<CatalogPack>> {
+    let catalog: Catalog = serde_json::from_str(&load_catalog_json())
+        .map_err(|e| anyhow::anyhow!("Failed to parse catalog.json: {}", e))?;
+    let mut packs = catalog.packs;
+    for source in config::load_current()?.catalog.sources {
+        match load_source(&source) {
+            Ok(source_packs) => packs.extend(source_packs),
+            Err(e) => eprintln!("[catalog] Skipping source '{}': {}", source.name, e),
+        }
+    }
+    Ok(packs)
+}
+
+fn load_source(source: &CatalogSource) -> Result<Vec<CatalogPack>, String> {
+    let (json, base) = match (&source.url, &source.path) {
+        (Some(url), _) => (fetch_source_index(&source.name, url)?, url.clone()),
+        (None, Some(path)) => {
+            let json = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            (json, path.to_string_lossy().into_owned())
+        }
+        (None, None) => return Ok(Vec::new()),
+    };
+    let catalog: Catalog = serde_json::from_str(&json).map_err(|e| format!("Invalid catalog: {}", e))?;
+    Ok(catalog
+        .packs
+        .into_iter()
+        .filter(|p| {
+            let valid = is_valid_pack_id(&p.id);
+            if !valid {
+                eprintln!("[catalog] Skipping pack '{}' from '{}': invalid ID", p.id, source.name);
+            }
+            valid
+        })
+        .map(|p| prefix_pack(&source.name, &base, p))
+        .collect())
+}
+
+/// Fetch a remote source index. The last good copy is kept so the source's
+/// packs stay listed while its server is unreachable.
+fn fetch_source_index(name: &str, url: &str) -> Result<String, String> {
+    let cached = sources_cache_dir().join(format!("{}.json", name));
+    let fetched = http_client().and_then(|client| {
+        rate_limit(url);
+        client
+            .get(url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.text())
+            .map_err(|e| e.to_string())
+    });
+    match fetched {
+        Ok(json) => {
+            if serde_json::from_str::<Catalog>(&json).is_ok() {
+                let _ = fs::create_dir_all(sources_cache_dir());
+                let _ = fs::write(&cached, &json);
+            }
+            Ok(json)
+        }
+        Err(e) => {
+            let json = fs::read_to_string(&cached).map_err(|_| e.clone())?;
+            eprintln!("[catalog] {}: {} (using cached copy)", url, e);
+            Ok(json)
+        }
+    }
+}
+
+/// Pack IDs become directory names in the cache, so user sources are held
+/// to the characters the built-in catalog uses.
+fn is_valid_pack_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn prefix_pack(source: &str, base: &str, mut pack: CatalogPack) -> CatalogPack {
+    pack.id = format!("{}{}{}", source, SOURCE_SEPARATOR, pack.id);
+    pack.source = source.to_string();
+    pack.download_url = resolve_location(base, &pack.download_url);
+    pack.mirrors = pack.mirrors.iter().map(|m| resolve_location(base, m)).collect();
+    pack
+}
+
+fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Resolve a download location from a source index against the index's own
+/// URL or path. Absolute URLs and paths are kept as they are.
+fn resolve_location(base: &str, location: &str) -> String {
+    if location.is_empty() || is_remote(location) {
+        return location.to_string();
+    }
+    if is_remote(base) {
+        if let Some(rest) = location.strip_prefix('/') {
+            let host_start = base.find("://").map_or(0, |i| i + 3);
+            let host_end = base[host_start..].find('/').map_or(base.len(), |i| host_start + i);
+            return format!("{}/{}", &base[..host_end], rest);
+        }
+        let dir_end = base.rfind('/').map_or(base.len(), |i| i + 1);
+        return format!("{}{}", &base[..dir_end], location);
+    }
+    if Path::new(location).is_absolute() {
+        return location.to_string();
+    }
+    Path::new(base)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(location)
+        .to_string_lossy()
+        .into_owned()
+}
+
 fn load_html_template(name: &str) -> String {
     if let Some(dir) = find_catalog_dir() {
         let path = dir.join("html").join(name);
@@ -160,16 +304,41 @@ fn cache_dir() -> PathBuf {
     dirs_cache().join("arcane").join("packs")
 }
 
+/// Cached user source indexes and their packs.
+fn sources_cache_dir() -> PathBuf {
+    dirs_cache().join("arcane").join("sources")
+}
+
+/// Where a pack is extracted. Packs from user sources are kept per source
+/// (`sources/<source>/<id>`), so they can't collide with built-in packs and
+/// the `:` in their ID never reaches the file system.
+fn pack_dir(pack_id: &str) -> PathBuf {
+    match pack_id.split_once(SOURCE_SEPARATOR) {
+        Some((source, id)) => sources_cache_dir().join(source).join(id),
+        None => cache_dir().join(pack_id),
+    }
+}
+
+/// User cache directory, shared by every project: `$XDG_CACHE_HOME`, then
+/// `%LOCALAPPDATA%` on Windows, then `~/.cache`.
 fn dirs_cache() -> PathBuf {
+    if let Some(xdg) = std::env::var_os("XDG_CACHE_HOME").filter(|v| !v.is_empty()) {
+        return PathBuf::from(xdg);
+    }
+    if cfg!(target_os = "windows")
+        && let Some(local) = std::env::var_os("LOCALAPPDATA")
+    {
+        return PathBuf::from(local);
+    }
     if let Ok(home) = std::env::var("HOME") {
         PathBuf::from(home).join(".cache")
     } else {
-        PathBuf::from("/tmp")
+        std::env::temp_dir()
     }
 }
 
 fn is_pack_downloaded(pack_id: &str) -> bool {
-    let dir = cache_dir().join(pack_id);
+    let dir = pack_dir(pack_id);
     dir.exists() && dir.is_dir()
 }
 
@@ -178,7 +347,7 @@ fn is_pack_downloaded(pack_id: &str) -> bool {
 // ---------------------------------------------------------------------------
 
 fn find_pack_image(pack_id: &str) -> Option<PathBuf> {
-    let pack_dir = cache_dir().join(pack_id);
+    let pack_dir = pack_dir(pack_id);
     if !pack_dir.exists() {
         return None;
     }
@@ -253,7 +422,7 @@ fn image_to_base64(path: &Path) -> String {
 
 
 fn get_sheet_path(pack_id: &str) -> String {
-    let pack_dir = cache_dir().join(pack_id);
+    let pack_dir = pack_dir(pack_id);
     if let Some(img) = find_pack_image(pack_id) {
         if let Ok(rel) = img.strip_prefix(&pack_dir) {
             return rel.to_string_lossy().to_string();
@@ -263,7 +432,7 @@ fn get_sheet_path(pack_id: &str) -> String {
 }
 
 fn scan_individual_sprites(pack_id: &str) -> Vec<SpriteEntry> {
-    let pack_dir = cache_dir().join(pack_id);
+    let pack_dir = pack_dir(pack_id);
     if !pack_dir.exists() {
         return Vec::new();
     }
@@ -322,7 +491,7 @@ fn scan_sprites_recursive(
 }
 
 fn list_pack_pngs(pack_id: &str) -> Vec<String> {
-    let pack_dir = cache_dir().join(pack_id);
+    let pack_dir = pack_dir(pack_id);
     if !pack_dir.exists() {
         return Vec::new();
     }
@@ -332,6 +501,15 @@ fn list_pack_pngs(pack_id: &str) -> Vec<String> {
     files
 }
 
+/// Every PNG of a downloaded pack, or `None` if the pack isn't in the cache.
+pub fn downloaded_pack_pngs(pack_id: &str) -> Option<Vec<PathBuf>> {
+    if !is_pack_downloaded(pack_id) {
+        return None;
+    }
+    let dir = pack_dir(pack_id);
+    Some(list_pack_pngs(pack_id).into_iter().map(|rel| dir.join(rel)).collect())
+}
+
 fn collect_pngs_recursive(root: &Path, dir: &Path, out: &mut Vec<String>) {
     let entries = match fs::read_dir(dir) {
         Ok(e) => e,
@@ -360,14 +538,94 @@ fn collect_pngs_recursive(root: &Path, dir: &Path, out: &mut Vec<String>) {
 // Download manager
 // ---------------------------------------------------------------------------
 
-fn scrape_kenney_url(asset_id: &str) -> Option<String> {
-    let url = format!("https://kenney.nl/assets/{}", asset_id);
-    let output = Command::new("curl")
-        .args(["-s", &url])
-        .output()
-        .ok()?;
+/// Attempts per URL before moving on to the next mirror.
+const DOWNLOAD_ATTEMPTS: u32 = 4;
 
-    let html = String::from_utf8_lossy(&output.stdout);
+/// Minimum spacing between requests to the same host.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Spaces out requests to the same host so bulk downloads stay polite.
+struct RateLimiter {
+    interval: Duration,
+    last_request: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+    /// How long to wait before the next request to `host`.
+    fn delay(&self, host: &str, now: Instant) -> Duration {
+        self.last_request
+            .get(host)
+            .map(|last| (*last + self.interval).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    fn wait(&mut self, url: &str) {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let delay = self.delay(&host, Instant::now());
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        self.last_request.insert(host, Instant::now());
+    }
+}
+
+static RATE_LIMITER: LazyLock<Mutex<RateLimiter>> = LazyLock::new(|| {
+    Mutex::new(RateLimiter {
+        interval: REQUEST_INTERVAL,
+        last_request: HashMap::new(),
+    })
+});
+
+fn rate_limit(url: &str) {
+    RATE_LIMITER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .wait(url);
+}
+
+/// Wait before retry `attempt` (0-based): 1s, 2s, 4s, ... capped at 30s.
+fn backoff_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(5)).min(Duration::from_secs(30))
+}
+
+/// Parse a `Retry-After` header given in seconds. HTTP dates are ignored.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(|secs| Duration::from_secs(secs.min(120)))
+}
+
+/// A failed download attempt, and whether trying the same URL again may help.
+struct FetchError {
+    message: String,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
+
+impl FetchError {
+    fn retry(message: impl Into<String>) -> Self {
+        Self { message: message.into(), retryable: true, retry_after: None }
+    }
+
+    fn fatal(message: impl Into<String>) -> Self {
+        Self { message: message.into(), retryable: false, retry_after: None }
+    }
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .user_agent(concat!("arcane/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(None)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn scrape_kenney_url(client: &reqwest::blocking::Client, asset_id: &str) -> Option<String> {
+    let url = format!("https://kenney.nl/assets/{}", asset_id);
+    rate_limit(&url);
+    let html = client.get(&url).send().ok()?.error_for_status().ok()?.text().ok()?;
 
     // Look for /media/pages/assets/{id}/{hash}/kenney_{id}.zip
     let pattern = "/media/pages/assets/";
@@ -384,64 +642,286 @@ fn scrape_kenney_url(asset_id: &str) -> Option<String> {
     None
 }
 
+/// URLs to try in order: the freshly scraped URL (if any), the catalog URL,
+/// then the pack's mirrors. Duplicates are dropped.
+fn download_urls(pack: &CatalogPack, scraped: Option<String>) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    let candidates = scraped
+        .into_iter()
+        .chain(std::iter::once(pack.download_url.clone()))
+        .chain(pack.mirrors.iter().cloned());
+    for url in candidates {
+        if !url.is_empty() && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// What is known about a partial download, saved next to it so a later run
+/// only resumes bytes that came from the same file.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct PartInfo {
+    url: String,
+    /// Strong ETag or Last-Modified of the response, sent back as If-Range.
+    validator: Option<String>,
+    /// Full size of the file, when the server said.
+    length: Option<u64>,
+}
+
+fn part_info_path(part: &Path) -> PathBuf {
+    part.with_extension("part.json")
+}
+
+fn read_part_info(part: &Path) -> Option<PartInfo> {
+    let text = fs::read_to_string(part_info_path(part)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Remove a partial download and what is known about it.
+fn remove_part(part: &Path) {
+    let _ = fs::remove_file(part);
+    let _ = fs::remove_file(part_info_path(part));
+}
+
+/// The validator to send as If-Range: a strong ETag, else Last-Modified.
+fn response_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|tag| !tag.starts_with("W/"));
+    etag.or_else(|| headers.get(reqwest::header::LAST_MODIFIED).and_then(|v| v.to_str().ok()))
+        .map(str::to_string)
+}
+
+/// The full size from a `Content-Range: bytes a-b/total` header.
+fn content_range_total(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    value.rsplit('/').next()?.trim().parse().ok()
+}
+
+/// Fetch `url` into `part`, continuing from the bytes already there with a
+/// range request. Only bytes from the same URL are resumed, guarded by
+/// If-Range so a file changed upstream restarts instead of being stitched
+/// onto stale bytes; with a `checksummed` download, bytes from another
+/// mirror are resumed too, since the checksum catches a bad mix. Servers
+/// that ignore the range restart the file. Returns Ok only once the whole
+/// file is in `part`.
+fn fetch_to(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part: &Path,
+    checksummed: bool,
+) -> Result<(), FetchError> {
+    let info = read_part_info(part).unwrap_or_default();
+    let mut resume_from = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let same_source = info.url == url && info.validator.is_some();
+    if !same_source && !checksummed {
+        resume_from = 0;
+    }
+
+    rate_limit(url);
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+        if let Some(validator) = info.validator.as_ref().filter(|_| same_source) {
+            request = request.header(reqwest::header::IF_RANGE, validator.as_str());
+        }
+    }
+    let mut response = request.send().map_err(|e| FetchError::retry(e.to_string()))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 {
+        // The partial file already holds the whole body
+        return Ok(());
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        return Err(FetchError { message: format!("HTTP {}", status), retryable: true, retry_after });
+    }
+    if !status.is_success() {
+        return Err(FetchError::fatal(format!("HTTP {}", status)));
+    }
+
+    let resuming = status == reqwest::StatusCode::PARTIAL_CONTENT && resume_from > 0;
+    let info = if resuming {
+        eprintln!("[catalog] Resuming at {} KB", resume_from / 1024);
+        PartInfo { length: content_range_total(response.headers()).or(info.length), ..info }
+    } else {
+        PartInfo {
+            url: url.to_string(),
+            validator: response_validator(response.headers()),
+            length: response.content_length(),
+        }
+    };
+    let mut options = fs::OpenOptions::new();
+    if resuming {
+        options.append(true);
+    } else {
+        options.write(true).truncate(true);
+    }
+    let mut file = options
+        .create(true)
+        .open(part)
+        .map_err(|e| FetchError::fatal(format!("Failed to open {}: {}", part.display(), e)))?;
+    let info_json = serde_json::to_string(&info).unwrap_or_default();
+    fs::write(part_info_path(part), info_json)
+        .map_err(|e| FetchError::fatal(format!("Failed to write {}: {}", part.display(), e)))?;
+    // A dropped connection leaves the bytes received so far for the next attempt
+    std::io::copy(&mut response, &mut file).map_err(|e| FetchError::retry(e.to_string()))?;
+    let received = file.metadata().map(|m| m.len()).unwrap_or(0);
+    match info.length {
+        Some(length) if received < length => {
+            Err(FetchError::retry(format!("Download ended early ({} of {} bytes)", received, length)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `fetch_to` with retries and exponential backoff (or the server's Retry-After).
+fn fetch_with_retries(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part: &Path,
+    checksummed: bool,
+    attempts: u32,
+) -> Result<(), String> {
+    let mut attempt = 0;
+    loop {
+        match fetch_to(client, url, part, checksummed) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.retryable && attempt + 1 < attempts => {
+                let delay = e.retry_after.unwrap_or_else(|| backoff_delay(attempt));
+                eprintln!(
+                    "[catalog] {} (attempt {}/{}), retrying in {}s...",
+                    e.message,
+                    attempt + 1,
+                    attempts,
+                    delay.as_secs()
+                );
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(e) => return Err(e.message),
+        }
+    }
+}
+
+/// Check that a finished download is a ZIP and matches the catalog checksum, if any.
+fn verify_download(path: &Path, sha256: Option<&str>) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read zip: {}", e))?;
+    if bytes.len() < 2 || bytes[0] != 0x50 || bytes[1] != 0x4B {
+        return Err("Downloaded file is not a valid ZIP (URL may have changed)".into());
+    }
+    if let Some(expected) = sha256 {
+        let actual = format!("{:x}", Sha256::digest(&bytes));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(format!("Checksum mismatch: expected {}, got {}", expected, actual));
+        }
+    }
+    Ok(())
+}
+
+/// Download one source into `part` and verify it. An interrupted download
+/// keeps its part file so a later attempt or run resumes it; a complete file
+/// that fails verification is removed, since resuming can't fix it.
+fn fetch_source(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part: &Path,
+    sha256: Option<&str>,
+    attempts: u32,
+) -> Result<(), String> {
+    // User sources may point at ZIPs on a local or network drive
+    if is_remote(url) {
+        fetch_with_retries(client, url, part, sha256.is_some(), attempts)?;
+    } else {
+        remove_part(part);
+        fs::copy(url, part).map_err(|e| e.to_string())?;
+    }
+    verify_download(part, sha256).inspect_err(|_| remove_part(part))
+}
+
 fn download_pack(pack_id: &str, packs: &[CatalogPack]) -> Result<(), String> {
     let pack = packs
         .iter()
         .find(|p| p.id == pack_id)
         .ok_or_else(|| format!("Pack '{}' not found in catalog", pack_id))?;
 
-    let cache = cache_dir();
-    let pack_dir = cache.join(pack_id);
-    let zip_path = cache.join(format!("{}.zip", pack_id));
+    let pack_dir = pack_dir(pack_id);
+    let cache = pack_dir.parent().map(Path::to_path_buf).unwrap_or_else(cache_dir);
+    let name = pack_dir.file_name().and_then(|n| n.to_str()).unwrap_or(pack_id).to_string();
+    let zip_path = cache.join(format!("{}.zip", name));
+    // Survives interrupted runs so the next download resumes where it stopped
+    let part_path = cache.join(format!("{}.zip.part", name));
 
     fs::create_dir_all(&cache).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+    let client = http_client()?;
 
-    // For Kenney packs, try scraping current URL
-    let url = if pack.source == "kenney" {
+    // For built-in Kenney packs, try scraping current URL
+    let scraped = if pack.source == "kenney" && !pack_id.contains(SOURCE_SEPARATOR) {
         eprintln!("[catalog] Fetching current download URL for {}...", pack.name);
-        scrape_kenney_url(pack_id).unwrap_or_else(|| {
+        let url = scrape_kenney_url(&client, pack_id);
+        if url.is_none() {
             eprintln!("[catalog] Scraping failed, using catalog URL");
-            pack.download_url.clone()
-        })
+        }
+        url
     } else {
-        pack.download_url.clone()
+        None
     };
 
-    // Download via curl
-    eprintln!("[catalog] Downloading {}...", pack.name);
-    let status = Command::new("curl")
-        .args(["-L", "-o", zip_path.to_str().unwrap(), &url])
-        .status()
-        .map_err(|e| format!("curl failed: {}", e))?;
-
-    if !status.success() {
-        return Err("curl download failed".into());
+    let mut errors = Vec::new();
+    let mut downloaded = false;
+    for (i, url) in download_urls(pack, scraped).iter().enumerate() {
+        if i > 0 {
+            eprintln!("[catalog] Trying mirror {}...", url);
+        } else {
+            eprintln!("[catalog] Downloading {}...", pack.name);
+        }
+        match fetch_source(&client, url, &part_path, pack.sha256.as_deref(), DOWNLOAD_ATTEMPTS) {
+            Ok(()) => {
+                downloaded = true;
+                break;
+            }
+            Err(e) => {
+                eprintln!("[catalog] {}: {}", url, e);
+                errors.push(format!("{}: {}", url, e));
+            }
+        }
     }
-
-    // Validate ZIP magic bytes
-    let header = fs::read(&zip_path).map_err(|e| format!("Failed to read zip: {}", e))?;
-    if header.len() < 2 || header[0] != 0x50 || header[1] != 0x4B {
-        let _ = fs::remove_file(&zip_path);
-        return Err("Downloaded file is not a valid ZIP (URL may have changed)".into());
+    if !downloaded {
+        return Err(format!("All download sources failed:\n  {}", errors.join("\n  ")));
     }
+    fs::rename(&part_path, &zip_path).map_err(|e| format!("Failed to move download: {}", e))?;
+    let _ = fs::remove_file(part_info_path(&part_path));
 
-    // Extract
+    // Extract next to the cache entry and move it into place, so a failed
+    // extraction never looks like a downloaded pack
     eprintln!("[catalog] Extracting {}...", pack.name);
-    fs::create_dir_all(&pack_dir).map_err(|e| format!("Failed to create pack dir: {}", e))?;
+    let extract_dir = cache.join(format!("{}.extracting", name));
+    let _ = fs::remove_dir_all(&extract_dir);
+    fs::create_dir_all(&extract_dir).map_err(|e| format!("Failed to create pack dir: {}", e))?;
     let status = Command::new("unzip")
         .args([
             "-q",
             "-o",
             zip_path.to_str().unwrap(),
             "-d",
-            pack_dir.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
         ])
         .status()
         .map_err(|e| format!("unzip failed: {}", e))?;
 
     if !status.success() {
+        let _ = fs::remove_dir_all(&extract_dir);
         return Err("unzip extraction failed".into());
     }
+    let _ = fs::remove_dir_all(&pack_dir);
+    fs::rename(&extract_dir, &pack_dir).map_err(|e| format!("Failed to move pack into cache: {}", e))?;
 
     // Clean up zip
     let _ = fs::remove_file(&zip_path);
@@ -575,9 +1055,7 @@ fn load_sound_packs(catalog_packs: &[CatalogPack]) -> String {
 // ---------------------------------------------------------------------------
 
 pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Result<()> {
-    let catalog_json = load_catalog_json();
-    let catalog: Catalog = serde_json::from_str(&catalog_json)
-        .map_err(|e| anyhow::anyhow!("Failed to parse catalog.json: {}", e))?;
+    let packs = load_packs()?;
 
     let cache = cache_dir();
     fs::create_dir_all(&cache)?;
@@ -586,11 +1064,11 @@ pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Re
     if let Some(ref id) = pack_id {
         if !sounds && !is_pack_downloaded(id) {
             // Verify pack exists
-            if !catalog.packs.iter().any(|p| p.id == *id) {
+            if !packs.iter().any(|p| p.id == *id) {
                 anyhow::bail!("Pack '{}' not found in catalog", id);
             }
             eprintln!("[catalog] Pack '{}' not cached, downloading...", id);
-            download_pack(id, &catalog.packs)
+            download_pack(id, &packs)
                 .map_err(|e| anyhow::anyhow!("Download failed: {}", e))?;
         }
     }
@@ -642,20 +1120,20 @@ pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Re
             ("OPTIONS", _) => build_cors_preflight(),
 
             ("GET", "/") => {
-                let html = render_browse(&catalog.packs);
+                let html = render_browse(&packs);
                 respond_html(&html)
             }
 
             ("GET", "/sounds") => {
-                let html = render_sounds(&catalog.packs);
+                let html = render_sounds(&packs);
                 respond_html(&html)
             }
 
             ("GET", p) if p.starts_with("/pack/") => {
-                let id = p.strip_prefix("/pack/").unwrap_or("");
-                if is_pack_downloaded(id) {
+                let id = urlencoding_decode(p.strip_prefix("/pack/").unwrap_or(""));
+                if is_pack_downloaded(&id) {
                     // Always use unified view - supports both sheets and individual sprites
-                    let html = render_unified(id, &catalog.packs);
+                    let html = render_unified(&id, &packs);
                     respond_html(&html)
                 } else {
                     respond_404("Pack not found or not downloaded")
@@ -663,8 +1141,8 @@ pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Re
             }
 
             ("GET", p) if p.starts_with("/pack-files/") => {
-                let id = p.strip_prefix("/pack-files/").unwrap_or("");
-                let files = list_pack_pngs(id);
+                let id = urlencoding_decode(p.strip_prefix("/pack-files/").unwrap_or(""));
+                let files = list_pack_pngs(&id);
                 let json = serde_json::to_string(&files).unwrap_or_else(|_| "[]".to_string());
                 respond_json(&json)
             }
@@ -680,8 +1158,8 @@ pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Re
             }
 
             ("POST", p) if p.starts_with("/download/") => {
-                let id = p.strip_prefix("/download/").unwrap_or("");
-                match download_pack(id, &catalog.packs) {
+                let id = urlencoding_decode(p.strip_prefix("/download/").unwrap_or(""));
+                match download_pack(&id, &packs) {
                     Ok(()) => respond_json("{\"success\":true}"),
                     Err(e) => {
                         let msg = serde_json::json!({"success": false, "error": e});
@@ -709,6 +1187,95 @@ pub fn run(pack_id: Option<String>, sounds: bool, browser: Option<String>) -> Re
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// assets list / search / download
+// ---------------------------------------------------------------------------
+
+/// A pack as printed by `arcane assets list --json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackListing {
+    id: String,
+    name: String,
+    source: String,
+    tags: Vec<String>,
+    downloaded: bool,
+    /// Cache directory, when downloaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+}
+
+/// Every whitespace-separated term must appear (case-insensitively) in the
+/// pack's ID, name, source or one of its tags.
+fn matches_query(pack: &CatalogPack, query: &str) -> bool {
+    let fields: Vec<String> = [&pack.id, &pack.name, &pack.source]
+        .into_iter()
+        .chain(&pack.tags)
+        .map(|f| f.to_lowercase())
+        .collect();
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .all(|term| fields.iter().any(|f| f.contains(&term)))
+}
+
+/// `arcane assets list` / `arcane assets search <query>`: print the built-in
+/// catalog merged with the user sources from `arcane.toml`.
+pub fn list(query: Option<String>, json: bool) -> Result<()> {
+    let packs = load_packs()?;
+    let listings: Vec<PackListing> = packs
+        .iter()
+        .filter(|p| query.as_deref().is_none_or(|q| matches_query(p, q)))
+        .map(|p| {
+            let downloaded = is_pack_downloaded(&p.id);
+            PackListing {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                source: p.source.clone(),
+                tags: p.tags.clone(),
+                downloaded,
+                path: downloaded.then(|| pack_dir(&p.id).to_string_lossy().into_owned()),
+            }
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&listings)?);
+        return Ok(());
+    }
+    if listings.is_empty() {
+        println!("No packs found");
+        return Ok(());
+    }
+    let id_width = listings.iter().map(|l| l.id.len()).max().unwrap_or(0);
+    let name_width = listings.iter().map(|l| l.name.len()).max().unwrap_or(0);
+    for l in &listings {
+        let status = if l.downloaded { "  (downloaded)" } else { "" };
+        println!(
+            "{:id_width$}  {:name_width$}  {}{}",
+            l.id,
+            l.name,
+            l.tags.join(", "),
+            status
+        );
+    }
+    Ok(())
+}
+
+/// `arcane assets download <id>`: download a pack into the shared cache (if
+/// it isn't there yet) and print its directory.
+pub fn download(pack_id: String) -> Result<()> {
+    let packs = load_packs()?;
+    if !packs.iter().any(|p| p.id == pack_id) {
+        anyhow::bail!("Pack '{}' not found in catalog (see `arcane assets list`)", pack_id);
+    }
+    if !is_pack_downloaded(&pack_id) {
+        download_pack(&pack_id, &packs).map_err(|e| anyhow::anyhow!("Download failed: {}", e))?;
+    }
+    println!("{}", pack_dir(&pack_id).display());
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Template rendering
 // ---------------------------------------------------------------------------
@@ -754,7 +1321,7 @@ fn render_unified(pack_id: &str, packs: &[CatalogPack]) -> String {
     };
 
     let sheet_path = get_sheet_path(pack_id);
-    let cache_path = cache_dir().join(pack_id);
+    let cache_path = pack_dir(pack_id);
     let image_path = cache_path.join(&sheet_path);
     let image_data = if image_path.exists() {
         image_to_base64(&image_path)
@@ -809,7 +1376,7 @@ fn serve_sprite(path: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
     };
 
     let decoded = urlencoding_decode(relative);
-    let file_path = cache_dir().join(pack_id).join(&decoded);
+    let file_path = pack_dir(&urlencoding_decode(pack_id)).join(&decoded);
 
     respond_file(&file_path)
 }
@@ -821,7 +1388,7 @@ fn serve_audio(path: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
     };
 
     let decoded = urlencoding_decode(relative);
-    let file_path = cache_dir().join(pack_id).join(&decoded);
+    let file_path = pack_dir(&urlencoding_decode(pack_id)).join(&decoded);
 
     respond_file(&file_path)
 }
@@ -909,4 +1476,141 @@ mod tests {
         assert!(dir.to_string_lossy().contains("arcane"));
         assert!(dir.to_string_lossy().contains("packs"));
     }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(4));
+        assert_eq!(backoff_delay(10), Duration::from_secs(30));
+        assert_eq!(parse_retry_after(" 7 "), Some(Duration::from_secs(7)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn rate_limiter_spaces_requests_per_host() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter {
+            interval: Duration::from_secs(1),
+            last_request: HashMap::new(),
+        };
+        assert_eq!(limiter.delay("kenney.nl", now), Duration::ZERO);
+        limiter.last_request.insert("kenney.nl".into(), now);
+        assert_eq!(limiter.delay("kenney.nl", now), Duration::from_secs(1));
+        assert_eq!(limiter.delay("example.com", now), Duration::ZERO);
+        assert_eq!(limiter.delay("kenney.nl", now + Duration::from_secs(2)), Duration::ZERO);
+    }
+
+    #[test]
+    fn download_urls_try_scraped_then_catalog_then_mirrors() {
+        let pack: CatalogPack = serde_json::from_str(
+            r#"{"id":"p","name":"P","source":"kenney","downloadUrl":"https://a/p.zip","mirrors":["https://b/p.zip","https://a/p.zip"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            download_urls(&pack, Some("https://new/p.zip".into())),
+            vec!["https://new/p.zip", "https://a/p.zip", "https://b/p.zip"]
+        );
+        assert_eq!(download_urls(&pack, None), vec!["https://a/p.zip", "https://b/p.zip"]);
+    }
+
+    #[test]
+    fn verify_download_checks_zip_and_checksum() {
+        let dir = std::env::temp_dir().join(format!("arcane_catalog_verify_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("p.zip");
+        fs::write(&path, b"PK\x03\x04").unwrap();
+        let sha = format!("{:x}", Sha256::digest(b"PK\x03\x04"));
+        assert!(verify_download(&path, None).is_ok());
+        assert!(verify_download(&path, Some(&sha.to_uppercase())).is_ok());
+        assert!(verify_download(&path, Some("00")).is_err());
+        fs::write(&path, b"<html>").unwrap();
+        assert!(verify_download(&path, None).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Answer one HTTP request on `listener` with `response`, returning the request head.
+    fn serve_once(listener: &std::net::TcpListener, response: &[u8]) -> String {
+        use std::io::{Read, Write};
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap() == 1 {
+            head.push(byte[0]);
+        }
+        stream.write_all(response).unwrap();
+        String::from_utf8_lossy(&head).to_lowercase()
+    }
+
+    #[test]
+    fn interrupted_download_keeps_its_part_file() {
+        let dir = std::env::temp_dir().join(format!("arcane_catalog_resume_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let part = dir.join("p.zip.part");
+        remove_part(&part);
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/p.zip", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            // The connection drops after 4 of 10 bytes
+            serve_once(
+                &listener,
+                b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nETag: \"v1\"\r\nConnection: close\r\n\r\nPK\x03\x04",
+            );
+            serve_once(
+                &listener,
+                b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 4-9/10\r\nContent-Length: 6\r\nConnection: close\r\n\r\n123456",
+            )
+        });
+        let client = http_client().unwrap();
+
+        assert!(fetch_source(&client, &url, &part, None, 1).is_err());
+        assert_eq!(fs::read(&part).unwrap(), b"PK\x03\x04");
+        let info = read_part_info(&part).unwrap();
+        assert_eq!(info.validator.as_deref(), Some("\"v1\""));
+        assert_eq!(info.length, Some(10));
+
+        fetch_source(&client, &url, &part, None, 1).unwrap();
+        let resumed = server.join().unwrap();
+        assert!(resumed.contains("range: bytes=4-"), "{resumed}");
+        assert!(resumed.contains("if-range: \"v1\""), "{resumed}");
+        assert_eq!(fs::read(&part).unwrap(), b"PK\x03\x04123456");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn user_source_packs_are_prefixed_and_resolved() {
+        let pack: CatalogPack = serde_json::from_str(
+            r#"{"id":"heroes","name":"Heroes","downloadUrl":"packs/heroes.zip","mirrors":["/mirror/heroes.zip"]}"#,
+        )
+        .unwrap();
+        let pack = prefix_pack("studio", "https://assets.example.com/arcane/catalog.json", pack);
+        assert_eq!(pack.id, "studio:heroes");
+        assert_eq!(pack.source, "studio");
+        assert_eq!(pack.download_url, "https://assets.example.com/arcane/packs/heroes.zip");
+        assert_eq!(pack.mirrors, vec!["https://assets.example.com/mirror/heroes.zip"]);
+
+        let local = resolve_location("/srv/assets/catalog.json", "heroes.zip");
+        assert_eq!(Path::new(&local), Path::new("/srv/assets/heroes.zip"));
+        assert_eq!(resolve_location("/srv/catalog.json", "https://x/p.zip"), "https://x/p.zip");
+    }
+
+    #[test]
+    fn user_source_packs_are_cached_per_source() {
+        assert_eq!(pack_dir("tiny-dungeon"), cache_dir().join("tiny-dungeon"));
+        assert_eq!(pack_dir("studio:heroes"), sources_cache_dir().join("studio").join("heroes"));
+        assert!(is_valid_pack_id("heroes-1.2_final"));
+        assert!(!is_valid_pack_id("../escape"));
+        assert!(!is_valid_pack_id("a/b"));
+        assert!(!is_valid_pack_id(""));
+    }
+
+    #[test]
+    fn search_matches_every_term() {
+        let pack: CatalogPack = serde_json::from_str(
+            r#"{"id":"studio:heroes","name":"Hero Sprites","source":"studio","downloadUrl":"","tags":["rpg","32x32"]}"#,
+        )
+        .unwrap();
+        assert!(matches_query(&pack, "hero"));
+        assert!(matches_query(&pack, "STUDIO rpg"));
+        assert!(!matches_query(&pack, "rpg platformer"));
+    }
 }
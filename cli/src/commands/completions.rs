@@ -0,0 +1,9 @@
+use clap::Command;
+use clap_complete::Shell;
+
+/// Print a completion script for `shell`, generated from the clap definitions.
+pub fn run(shell: Shell, mut cmd: Command) -> anyhow::Result<()> {
+    let bin_name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut std::io::stdout());
+    Ok(())
+}
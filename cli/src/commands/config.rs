@@ -0,0 +1,204 @@
+//! Project configuration read from `arcane.toml` at the project root.
+//!
+//! Every section is optional; a project without the file gets the defaults.
+//!
+//! ```toml
+//! [[catalog.sources]]
+//! name = "studio"
+//! url = "https://assets.example.com/arcane/catalog.json"
+//!
+//! [[catalog.sources]]
+//! name = "shared"
+//! path = "../shared-assets/catalog.json"
+//!
+//! [plugins]
+//! native = ["plugins/libmy_plugin.so"]
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config file name, looked up from the current directory upwards.
+pub const CONFIG_FILE: &str = "arcane.toml";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CatalogConfig {
+    /// Extra asset catalogs, merged after the built-in one.
+    #[serde(default)]
+    pub sources: Vec<CatalogSource>,
+}
+
+/// A user-defined asset catalog: a JSON index in the same format as the
+/// built-in `catalog.json`, fetched from `url` or read from `path`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CatalogSource {
+    /// Prefix for pack IDs from this source (`<name>:<pack-id>`).
+    pub name: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Relative paths are resolved against the directory holding `arcane.toml`.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginsConfig {
+    /// Shared libraries exporting the native plugin C ABI, loaded at startup.
+    /// Relative paths are resolved against the directory holding `arcane.toml`.
+    #[serde(default)]
+    pub native: Vec<PathBuf>,
+}
+
+/// Walk up from `start` to the nearest directory containing `arcane.toml`.
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(CONFIG_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Load the config for the project containing the current directory.
+/// Returns the defaults when there is no `arcane.toml`.
+pub fn load_current() -> Result<ProjectConfig> {
+    load_containing(Path::new("."))
+}
+
+/// Load the config for the project containing `start`, a file or directory
+/// relative to the current directory (it need not exist). Returns the
+/// defaults when there is no `arcane.toml`.
+pub fn load_containing(start: &Path) -> Result<ProjectConfig> {
+    let cwd = std::env::current_dir().context("cannot read current directory")?;
+    let start = fs::canonicalize(start).unwrap_or_else(|_| cwd.join(start));
+    match find_project_root(&start) {
+        Some(root) => load(&root),
+        None => Ok(ProjectConfig::default()),
+    }
+}
+
+/// Load `root/arcane.toml`. Source and plugin paths come back absolute.
+pub fn load(root: &Path) -> Result<ProjectConfig> {
+    let path = root.join(CONFIG_FILE);
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let mut config = parse(&text).with_context(|| format!("Invalid {}", path.display()))?;
+    for source in &mut config.catalog.sources {
+        if let Some(p) = source.path.take() {
+            source.path = Some(root.join(p));
+        }
+    }
+    for path in &mut config.plugins.native {
+        *path = root.join(&*path);
+    }
+    Ok(config)
+}
+
+fn parse(text: &str) -> Result<ProjectConfig> {
+    let config: ProjectConfig = toml::from_str(text)?;
+    let mut names = Vec::new();
+    for source in &config.catalog.sources {
+        let valid_name = !source.name.is_empty()
+            && source
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid_name {
+            anyhow::bail!(
+                "catalog source name \"{}\" must be lowercase letters, digits, '-' or '_'",
+                source.name
+            );
+        }
+        if names.contains(&&source.name) {
+            anyhow::bail!("catalog source \"{}\" is defined twice", source.name);
+        }
+        names.push(&source.name);
+        if source.url.is_some() == source.path.is_some() {
+            anyhow::bail!(
+                "catalog source \"{}\" needs exactly one of `url` or `path`",
+                source.name
+            );
+        }
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_has_no_sources() {
+        assert!(parse("").unwrap().catalog.sources.is_empty());
+    }
+
+    #[test]
+    fn parses_catalog_sources() {
+        let config = parse(
+            r#"
+[[catalog.sources]]
+name = "studio"
+url = "https://assets.example.com/catalog.json"
+
+[[catalog.sources]]
+name = "shared"
+path = "../shared/catalog.json"
+"#,
+        )
+        .unwrap();
+        let sources = &config.catalog.sources;
+        assert_eq!(sources.len(), 2);
+        assert_eq!(
+            sources[0].url.as_deref(),
+            Some("https://assets.example.com/catalog.json")
+        );
+        assert_eq!(
+            sources[1].path.as_deref(),
+            Some(Path::new("../shared/catalog.json"))
+        );
+    }
+
+    #[test]
+    fn parses_native_plugins() {
+        let config = parse("[plugins]\nnative = [\"plugins/libfoo.so\"]\n").unwrap();
+        assert_eq!(config.plugins.native, vec![PathBuf::from("plugins/libfoo.so")]);
+        assert!(parse("").unwrap().plugins.native.is_empty());
+        assert!(parse("[plugins]\nstatic = []\n").is_err());
+    }
+
+    #[test]
+    fn loads_the_config_of_the_project_holding_a_path() {
+        let dir = std::env::temp_dir().join(format!("arcane_config_containing_{}", std::process::id()));
+        let game = dir.join("game");
+        fs::create_dir_all(game.join("src")).unwrap();
+        fs::write(game.join(CONFIG_FILE), "[plugins]\nnative = [\"libfoo.so\"]\n").unwrap();
+
+        // The entry file need not exist; its project is still found
+        let config = load_containing(&game.join("src").join("visual.ts")).unwrap();
+        assert_eq!(config.plugins.native.len(), 1);
+        assert!(config.plugins.native[0].ends_with("game/libfoo.so"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_sources() {
+        let bad_name = "[[catalog.sources]]\nname = \"My Studio\"\nurl = \"https://x\"\n";
+        assert!(parse(bad_name).is_err());
+        let both = "[[catalog.sources]]\nname = \"a\"\nurl = \"https://x\"\npath = \"y\"\n";
+        assert!(parse(both).is_err());
+        let twice = "[[catalog.sources]]\nname = \"a\"\nurl = \"https://x\"\n[[catalog.sources]]\nname = \"a\"\npath = \"y\"\n";
+        assert!(parse(twice).is_err());
+    }
+}
@@ -2,18 +2,53 @@ use std::cell::RefCell;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
+use arcane_core::agent::quicksave::{self, QuickSave, QuickSaveCommand, QuickSaveSlots};
 use arcane_core::audio::{self, AudioCommand, AudioSender};
 use arcane_core::platform::window::{DevConfig, RenderState};
-use arcane_core::scripting::render_ops::{BridgeAudioCommand, RenderBridgeState};
+use arcane_core::platform::HeadlessConfig;
+use arcane_core::renderer::debug_hud::{text_shader_params, DEBUG_HUD_KEY};
+use arcane_core::renderer::reload_diff::{
+    DiffTextures, ReloadDiff, ReloadDiffOutput, ReloadDiffStep, RELOAD_DIFF_DIR, RELOAD_DIFF_KEY,
+};
+use arcane_core::scripting::render_ops::{BridgeAudioCommand, RecordingRequest, RenderBridgeState};
+use arcane_core::scripting::op_profiler::{self, OpProfiler};
 use arcane_core::scripting::ArcaneRuntime;
 
 use super::{create_import_map, type_check};
 
+/// Settings for [`run`] beyond the entry file and agent ports.
+#[derive(Default)]
+pub struct DevOptions {
+    /// Requested point light capacity (see `Renderer::new`).
+    pub max_lights: usize,
+    /// Encode and present frames on a dedicated render thread.
+    pub render_thread: bool,
+    /// Report the left mouse button as a touch point.
+    pub emulate_touch: bool,
+    /// Draw the frame profiler's readings in the top-right corner of the window.
+    pub stats_overlay: bool,
+    /// Capture the frame before and after each hot reload, write both to
+    /// `.arcane/reload-diffs/` and show them as a wipe or difference overlay
+    /// (cycled with F4). Ignored when headless.
+    pub reload_diff: bool,
+    /// Run a fixed number of frames offscreen instead of opening a window.
+    pub headless: Option<HeadlessConfig>,
+}
+
 /// Run the dev server: open a window, load TS entry file, run game loop.
-pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) -> Result<()> {
+///
+/// With `options.headless`, no window is opened: the game runs a fixed number
+/// of frames offscreen at a fixed time step and each frame is written as a PNG
+/// (`arcane render`). Hot reload and gamepads are off in that mode.
+pub fn run(
+    entry: String,
+    inspector_port: Option<u16>,
+    mcp_port: Option<u16>,
+    options: DevOptions) -> Result<()> {
+    let DevOptions { max_lights, render_thread, emulate_touch, stats_overlay, reload_diff, headless } = options;
     let entry_path = std::fs::canonicalize(&entry)
         .with_context(|| format!("Cannot find entry file: {entry}"))?;
 
@@ -37,8 +72,11 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
     let config = DevConfig {
         entry_file: entry_path.clone(),
         title,
-        width: 800,
-        height: 600,
+        width: headless.as_ref().map_or(800, |h| h.width),
+        height: headless.as_ref().map_or(600, |h| h.height),
+        max_lights,
+        render_thread,
+        emulate_touch,
     };
 
     // Create shared render bridge state
@@ -47,6 +85,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
     // Create import map for resolving @arcane/runtime imports
     let import_map = create_import_map(&base_dir);
 
+    super::plugins::print_loaded();
+
     // Create the JS runtime with both base and render extensions
     // Wrapped in Option so hot-reload can drop the old V8 isolate before creating a new one
     let mut runtime: Option<ArcaneRuntime> = Some(
@@ -60,7 +100,9 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
 
     rt.block_on(async { runtime.as_mut().unwrap().execute_file(&entry_path).await })?;
 
-    println!("Entry file loaded. Opening window...");
+    if headless.is_none() {
+        println!("Entry file loaded. Opening window...");
+    }
 
     // Start HTTP inspector if requested
     let inspector_rx = inspector_port.map(|port| {
@@ -75,9 +117,15 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
         rx
     });
 
-    // Hot-reload: file watcher sets a flag when .ts files change
+    // Hot-reload: file watcher sets a flag when .ts files change, and another
+    // when any other file changes (textures, sounds, shader files)
     let reload_flag = Arc::new(AtomicBool::new(false));
-    let _watcher = start_file_watcher(&base_dir, &entry_path, reload_flag.clone());
+    let asset_flag = Arc::new(AtomicBool::new(false));
+    let _watcher = if headless.is_none() {
+        start_file_watcher(&base_dir, &entry_path, reload_flag.clone(), asset_flag.clone())
+    } else {
+        None
+    };
 
     // Start MCP server if requested (after reload_flag so it can bypass hung frames)
     let mcp_rx = mcp_port.map(|port| {
@@ -91,9 +139,15 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
         rx
     });
 
-    // Start audio thread
+    // Start audio thread (headless renders are silent: commands go nowhere)
     let (audio_tx, audio_rx) = audio::audio_channel();
-    let _audio_thread = audio::start_audio_thread(audio_rx);
+    let audio_levels = Arc::new(Mutex::new(audio::AudioLevels::default()));
+    let _audio_thread = if headless.is_none() {
+        Some(audio::start_audio_thread(audio_rx, audio_levels.clone()))
+    } else {
+        drop(audio_rx);
+        None
+    };
 
     // Watchdog: detects hung frames and triggers recovery via reload
     let frame_hung = Arc::new(AtomicBool::new(false));
@@ -133,42 +187,63 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
     }
 
     // Initialize gamepad manager (gilrs)
-    let mut gamepad_manager = arcane_core::platform::GamepadManager::new();
+    let mut gamepad_manager = if headless.is_none() {
+        arcane_core::platform::GamepadManager::new()
+    } else {
+        None
+    };
 
     // Create the render state for the window
     let render_state = Rc::new(RefCell::new(RenderState::new()));
 
     let bridge_for_loop = bridge_state.clone();
+    // Keeps the last good frame on screen while update() throws
+    let mut error_boundary = arcane_core::renderer::error_overlay::FrameErrorBoundary::new();
+    // Quick save slots (F5/F9) live here so they survive hot reloads
+    let mut quick_saves = QuickSaveSlots::new();
     let entry_for_reload = entry_path.clone();
     let base_for_reload = base_dir.clone();
+    // Audio output gain last sent for focus loss
+    let mut focus_gain = 1.0_f32;
+    let blocking_texture_loads = headless.is_some();
+    // Before/after captures around hot reloads, and the textures showing the last pair
+    let mut reload_diff = ReloadDiff::new(reload_diff && headless.is_none());
+    let mut reload_diff_textures: Option<DiffTextures> = None;
+    let mut thumbnail_pending = false;
 
     // Frame callback: sync input → call TS → collect sprite commands
     let frame_callback = Box::new(move |state: &mut RenderState| -> Result<()> {
         // Sync viewport (logical pixels), scale factor, and clear color between renderer and bridge.
-        // Also sync the renderer's clamped camera position back to the bridge so that
-        // getCamera() returns the position the GPU actually rendered with (after bounds clamping),
-        // not the unclamped position TS requested. Without this, screenToWorld() computes
-        // wrong world coordinates whenever camera bounds clamp the position.
-        if let Some(ref mut renderer) = state.renderer {
+        // Also sync the clamped camera position of the last submitted frame back to the bridge so
+        // that getCamera() returns the position the GPU actually rendered with (after bounds
+        // clamping), not the unclamped position TS requested. Without this, screenToWorld()
+        // computes wrong world coordinates whenever camera bounds clamp the position.
+        // These are mirrored in RenderState, so this never waits for the render thread.
+        if let Some(ref renderer) = state.renderer {
             let mut bridge = bridge_for_loop.borrow_mut();
-            bridge.viewport_width = renderer.camera.viewport_size[0];
-            bridge.viewport_height = renderer.camera.viewport_size[1];
-            bridge.scale_factor = renderer.scale_factor;
+            bridge.viewport_width = state.viewport_size[0];
+            bridge.viewport_height = state.viewport_size[1];
+            bridge.scale_factor = state.scale_factor;
             // Only sync clamped camera back if TS hasn't called setCamera() since last frame.
             // Without this guard, a setCamera() during module init gets clobbered by the
             // renderer's default (0, 0) before the renderer ever reads the TS value.
             if !bridge.camera_dirty {
-                bridge.camera_x = renderer.camera.x;
-                bridge.camera_y = renderer.camera.y;
+                bridge.camera_x = state.frame.camera_x;
+                bridge.camera_y = state.frame.camera_y;
+            }
+            if bridge.gpu_info.is_none() {
+                bridge.gpu_info = Some(renderer.lock().gpu_info());
             }
-            // Sync clear color from bridge → renderer (TS can set it via op)
-            renderer.clear_color = bridge.clear_color;
+            // Sync clear color from bridge → frame (TS can set it via op)
+            state.frame.clear_color = bridge.clear_color;
 
-            // Sync built-in shader uniform sources to renderer
-            renderer.elapsed_time = bridge.elapsed_time as f32;
-            renderer.delta_time = bridge.delta_time as f32;
-            renderer.mouse_pos = [bridge.mouse_x, bridge.mouse_y];
+            // Sync built-in shader uniform sources to the frame
+            state.frame.elapsed_time = bridge.elapsed_time as f32;
+            state.frame.delta_time = bridge.delta_time as f32;
+            state.frame.mouse_pos = [bridge.mouse_x, bridge.mouse_y];
         }
+        // Frame captured on request of the reload diff or a replay thumbnail
+        let capture = state.kept_capture.take();
 
         // Check for hung frame recovery (watchdog triggered)
         if frame_hung.swap(false, Ordering::SeqCst) {
@@ -185,9 +260,32 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             return Ok(());
         }
 
-        // Check for hot-reload
+        // Check for hot-reload. With --reload-diff, the reload waits until
+        // the current frame has been captured.
+        let mut reload_now = false;
         if reload_flag.swap(false, Ordering::Relaxed) {
             eprintln!("[hot-reload] File change detected, reloading...");
+            if reload_diff.hold_reload() {
+                state.frame.capture = true;
+                state.keep_capture = true;
+            } else {
+                reload_now = true;
+            }
+        }
+        match reload_diff.step(capture.as_deref()) {
+            ReloadDiffStep::Continue => {}
+            ReloadDiffStep::Capture => {
+                state.frame.capture = true;
+                state.keep_capture = true;
+            }
+            ReloadDiffStep::Reload => reload_now = true,
+            ReloadDiffStep::Done(output) => {
+                let mut bridge = bridge_for_loop.borrow_mut();
+                reload_diff_textures = Some(upload_reload_diff(&mut bridge, output, reload_diff_textures));
+            }
+            ReloadDiffStep::Failed(e) => eprintln!("[reload-diff] {e}"),
+        }
+        if reload_now {
             match reload_runtime(
                 &entry_for_reload,
                 &base_for_reload,
@@ -199,11 +297,47 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             }
         }
 
+        // Asset edits re-upload in place under their existing IDs; the
+        // runtime keeps running. Unrelated file changes find nothing to do.
+        if asset_flag.swap(false, Ordering::Relaxed) {
+            let requeued = bridge_for_loop.borrow_mut().requeue_changed_assets();
+            if requeued > 0 {
+                eprintln!("[hot-reload] Reloading {requeued} changed asset(s)");
+            }
+        }
+
         // Get runtime reference — skip frame if None (shouldn't happen in practice)
         let Some(ref mut rt) = runtime else {
             return Ok(());
         };
 
+        // Focus loss: queue focus events for TS and apply the game's policy.
+        // While paused the frame callback still runs (for pause menus), but
+        // delta time reads 0 and physics steps are skipped. A hit-stop
+        // freezes the frame the same way without reporting a pause.
+        let paused = {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            let policy = bridge.focus_policy;
+            let focused = state.focus.is_focused();
+            state.focus_policy = policy;
+            bridge.focus_events.extend(state.focus.take_events());
+            bridge.lifecycle_events.extend(state.lifecycle.take_events());
+            bridge.window_focused = focused;
+            bridge.simulation_paused = policy.paused(focused);
+            let gain = policy.audio_gain(focused);
+            if gain != focus_gain {
+                focus_gain = gain;
+                let _ = audio_tx.send(AudioCommand::SetFocusGain { gain });
+            }
+            let hitstop = bridge.hitstop.begin_frame(state.delta_time as f32);
+            bridge.simulation_paused || hitstop
+        };
+        {
+            use arcane_core::scripting::physics_ops::PhysicsPaused;
+            let op_state = rt.inner().op_state();
+            op_state.borrow_mut().borrow_mut::<PhysicsPaused>().0 = paused;
+        }
+
         // Sync input state to the bridge so TS ops can read it
         {
             let mut bridge = bridge_for_loop.borrow_mut();
@@ -213,14 +347,35 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             bridge.mouse_y = state.input.mouse_y;
             bridge.mouse_buttons_down = state.input.mouse_buttons.clone();
             bridge.mouse_buttons_pressed = state.input.mouse_buttons_pressed.clone();
-            bridge.delta_time = state.delta_time;
-            bridge.elapsed_time += state.delta_time;
+            bridge.input_events = state.input.events.clone();
+            if paused {
+                bridge.delta_time = 0.0;
+            } else {
+                bridge.delta_time = state.delta_time;
+                bridge.elapsed_time += state.delta_time;
+            }
+            // Advance sprite animations; their events are read during this frame
+            let dt = bridge.delta_time as f32;
+            bridge.animations.update(dt);
+            bridge.decals.update(dt);
+            bridge.flashes.borrow_mut().update(dt);
+            bridge.tilemaps.update(dt);
+            bridge.transition.update(dt);
+            // Save edited world chunks on the autosave interval
+            let b = &mut *bridge;
+            if let Err(e) = b.world.tick(dt, &b.tilemaps) {
+                eprintln!("[world] Failed to save chunks: {e}");
+            }
         }
 
         // Poll gamepad state and sync to bridge
+        let rumble = bridge_for_loop.borrow_mut().hitstop.take_rumble();
         if let Some(ref mut gpm) = gamepad_manager {
             gpm.begin_frame();
             gpm.update();
+            if let Some(rumble) = rumble {
+                gpm.rumble(rumble.strength, rumble.seconds);
+            }
 
             let mut bridge = bridge_for_loop.borrow_mut();
             bridge.gamepad_count = gpm.connected_count;
@@ -264,6 +419,35 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             }
         }
 
+        // Evaluate named actions against the synced keyboard, mouse and gamepad state
+        bridge_for_loop.borrow_mut().update_actions();
+
+        // Dev quick save / quick load hotkeys
+        let quick_command = {
+            let bridge = bridge_for_loop.borrow();
+            quicksave::hotkey_command(
+                &bridge.keys_down,
+                &bridge.keys_pressed,
+                &bridge.gamepad_buttons_down,
+                &bridge.gamepad_buttons_pressed,
+            )
+        };
+        if let Some(command) = quick_command {
+            run_quicksave_command(rt, &mut quick_saves, command);
+        }
+
+        // Debug HUD hotkey and frame time
+        {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            if bridge.keys_pressed.contains(DEBUG_HUD_KEY) {
+                bridge.debug_hud.toggle();
+            }
+            if bridge.keys_pressed.contains(RELOAD_DIFF_KEY) {
+                reload_diff.cycle_mode();
+            }
+            bridge.debug_hud.record_frame(state.delta_time);
+        }
+
         // Call the TS frame callback (timed for profiling, with watchdog)
         let _ = watchdog_tx.send(true); // signal frame start
         let frame_start = std::time::Instant::now();
@@ -281,6 +465,13 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             bridge.frame_time_ms = frame_elapsed_ms;
             bridge.draw_call_count = draw_calls;
         }
+        op_profiler(rt).borrow_mut().end_frame();
+        // The rest of the frame's profile is filled in as the subsystems run
+        let mut profile = arcane_core::renderer::FrameProfile {
+            script_ms: frame_elapsed_ms,
+            physics_ms: take_physics_step_time(rt),
+            ..Default::default()
+        };
 
         // Warn on slow frames (>32ms = below 30fps)
         if frame_elapsed_ms > 32.0 {
@@ -289,15 +480,29 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
 
         // Handle frame callback errors with error snapshots
         if let Err(ref e) = frame_result {
-            let error_msg = escape_js(&format!("{e}"));
-            let snapshot_script =
-                "JSON.stringify(globalThis.__arcaneAgent?.captureSnapshot())".to_string();
-            if let Ok(snapshot_json) = rt.eval_to_string(&snapshot_script) {
-                if snapshot_json != "undefined" && snapshot_json != "null" {
-                    write_error_snapshot(&snapshot_json, &error_msg);
+            // Log and snapshot each distinct error once, not on every failing frame
+            if error_boundary.record_error(&format!("{e}")) {
+                let error_msg = escape_js(&format!("{e}"));
+                let snapshot_script =
+                    "JSON.stringify(globalThis.__arcaneAgent?.captureSnapshot())".to_string();
+                if let Ok(snapshot_json) = rt.eval_to_string(&snapshot_script) {
+                    if snapshot_json != "undefined" && snapshot_json != "null" {
+                        write_error_snapshot(&snapshot_json, &error_msg);
+                    }
                 }
+                eprintln!("[frame] Error: {e}");
+                eprintln!("[frame] Showing last good frame until the error is fixed");
             }
-            eprintln!("[frame] Error: {e}");
+            // Make sure the overlay textures exist before the texture queues are processed
+            let mut bridge = bridge_for_loop.borrow_mut();
+            bridge.builtin_font_texture();
+            bridge.solid_texture("__arcane_error_panel__", [255, 255, 255, 255]);
+        } else if let Some(failed) = error_boundary.record_success() {
+            eprintln!("[frame] Recovered after {failed} failed frame(s)");
+        }
+        {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            error_boundary.settle_sprites(&mut bridge.sprite_commands);
         }
 
         // Process any pending texture loads
@@ -306,7 +511,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.texture_load_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (path, id) in pending_textures {
                 if path.starts_with("__solid__:") {
                     // Parse solid color: __solid__:name:r:g:b:a
@@ -327,25 +533,9 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                         );
                     }
                 } else {
-                    // For file textures, also use upload_raw with pre-assigned ID
-                    match std::fs::read(&path) {
-                        Ok(img_data) => match image::load_from_memory(&img_data) {
-                            Ok(img) => {
-                                let rgba = img.to_rgba8();
-                                let (w, h) = rgba.dimensions();
-                                renderer.textures.upload_raw(
-                                    &renderer.gpu.device, &renderer.gpu.queue,
-                                    &renderer.sprites.texture_bind_group_layout,
-                                    id,
-                                    &rgba,
-                                    w,
-                                    h,
-                                );
-                            }
-                            Err(e) => eprintln!("Failed to decode texture {path}: {e}"),
-                        },
-                        Err(e) => eprintln!("Failed to read texture {path}: {e}"),
-                    }
+                    // File textures decode on worker threads and upload over
+                    // the next frames into their pre-assigned ID
+                    renderer.texture_streams.request(id, path.into(), false);
                 }
             }
         }
@@ -356,7 +546,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.texture_load_queue_linear)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (path, id) in pending_textures_linear {
                 if path.starts_with("__solid__:") {
                     // Parse solid color: __solid__:name:r:g:b:a
@@ -376,35 +567,45 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                         );
                     }
                 } else {
-                    match std::fs::read(&path) {
-                        Ok(img_data) => match image::load_from_memory(&img_data) {
-                            Ok(img) => {
-                                let rgba = img.to_rgba8();
-                                let (w, h) = rgba.dimensions();
-                                renderer.textures.upload_raw_linear(
-                                    &renderer.gpu.device, &renderer.gpu.queue,
-                                    &renderer.sprites.texture_bind_group_layout,
-                                    id,
-                                    &rgba,
-                                    w,
-                                    h,
-                                );
-                            }
-                            Err(e) => eprintln!("Failed to decode texture {path}: {e}"),
-                        },
-                        Err(e) => eprintln!("Failed to read texture {path}: {e}"),
-                    }
+                    renderer.texture_streams.request(id, path.into(), true);
                 }
             }
         }
 
+        // Upload this frame's share of streamed textures. Headless renders
+        // load them whole, so captured frames don't depend on thread timing.
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
+            let mut bridge = bridge_for_loop.borrow_mut();
+            renderer.texture_streams.set_blocking(blocking_texture_loads);
+            renderer.texture_streams.set_budget(bridge.texture_upload_budget);
+            let events = renderer.pump_texture_streams();
+            bridge.texture_events.extend(events);
+            bridge.texture_stream_stats = renderer.texture_streams.stats();
+            profile.texture_upload_bytes = renderer.texture_streams.uploaded_bytes();
+        }
+
+        // Link normal maps to their albedo textures (from op_load_texture_pair)
+        let pending_normal_maps: Vec<(u32, u32)> = {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            std::mem::take(&mut bridge.normal_map_queue)
+        };
+
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
+            for (albedo_id, normal_id) in pending_normal_maps {
+                renderer.textures.set_normal_map(albedo_id, normal_id);
+            }
+        }
+
         // Process raw RGBA texture uploads (from op_upload_rgba_texture)
         let pending_raw_textures: Vec<(u32, u32, u32, Vec<u8>)> = {
             let mut bridge = bridge_for_loop.borrow_mut();
             std::mem::take(&mut bridge.raw_texture_upload_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (tex_id, w, h, pixels) in pending_raw_textures {
                 renderer.textures.upload_raw(
                     &renderer.gpu.device, &renderer.gpu.queue,
@@ -423,7 +624,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.font_texture_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for font_tex_id in pending_fonts {
                 let (pixels, width, height) =
                     arcane_core::renderer::font::generate_builtin_font();
@@ -444,7 +646,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.msdf_builtin_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (_font_id, tex_id) in pending_msdf_builtin {
                 let (pixels, width, height, _font) =
                     arcane_core::renderer::msdf::generate_builtin_msdf_font();
@@ -465,7 +668,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.msdf_texture_load_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (path, id) in pending_msdf_textures {
                 match std::fs::read(&path) {
                     Ok(img_data) => match image::load_from_memory(&img_data) {
@@ -488,18 +692,96 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             }
         }
 
+        // Pack glyphs generated for runtime fonts and upload the atlases that changed
+        let runtime_font_atlases: Vec<(u32, u32, Vec<u8>)> = {
+            let bridge = &mut *bridge_for_loop.borrow_mut();
+            bridge.runtime_fonts.borrow_mut().poll(&mut bridge.msdf_fonts)
+        };
+
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
+            for (tex_id, size, pixels) in runtime_font_atlases {
+                renderer.textures.upload_raw_linear(
+                    &renderer.gpu.device, &renderer.gpu.queue,
+                    &renderer.sprites.texture_bind_group_layout,
+                    tex_id,
+                    &pixels,
+                    size,
+                    size,
+                );
+            }
+        }
+
         // Process MSDF shader creation requests
         let pending_msdf_shaders: Vec<(u32, String)> = {
             let mut bridge = bridge_for_loop.borrow_mut();
             std::mem::take(&mut bridge.msdf_shader_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (id, source) in pending_msdf_shaders {
-                renderer.shaders.create(&renderer.gpu.device, id, "msdf", &source);
+                renderer.create_shader(id, "msdf", &source);
+            }
+        }
+
+        // Start and stop GIF recordings, and schedule layered captures
+        let recording_requests: Vec<RecordingRequest> = {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            std::mem::take(&mut bridge.recording_requests)
+        };
+
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
+            for request in recording_requests {
+                match request {
+                    RecordingRequest::Start { path, fps } => match renderer.start_recording(path.clone(), fps) {
+                        Ok(()) => println!("[record] Recording to {}", path.display()),
+                        Err(e) => {
+                            eprintln!("[record] {e:#}");
+                            bridge_for_loop.borrow_mut().recording = false;
+                        }
+                    },
+                    RecordingRequest::Stop => {
+                        if let Some((path, frames)) = renderer.stop_recording() {
+                            println!("[record] Saved {frames} frame(s) to {}", path.display());
+                        }
+                    }
+                    RecordingRequest::CaptureLayers { path } => renderer.layer_capture_pending = Some(path),
+                }
             }
         }
 
+        // Capture a replay thumbnail on request, and hand the capture to the bridge
+        {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            if thumbnail_pending && let Some(ref png) = capture {
+                thumbnail_pending = false;
+                bridge.replay_thumbnail = replay_thumbnail(png);
+            }
+            if std::mem::take(&mut bridge.replay_thumbnail_requested) && state.renderer.is_some() {
+                thumbnail_pending = true;
+                state.frame.capture = true;
+                state.keep_capture = true;
+            }
+        }
+
+        // Apply the MSAA setting; unsupported counts fall back to a lower one
+        let msaa_samples = bridge_for_loop.borrow().msaa_samples;
+        if let Some(ref renderer) = state.renderer {
+            let samples = renderer.lock().set_msaa(msaa_samples);
+            if samples != msaa_samples {
+                eprintln!("[msaa] {msaa_samples}x MSAA is not supported by this GPU; using {samples}x");
+                bridge_for_loop.borrow_mut().msaa_samples = samples;
+            }
+        }
+
+        // Pack small textures into runtime atlas pages when enabled
+        let runtime_atlas = bridge_for_loop.borrow().runtime_atlas;
+        if let Some(ref renderer) = state.renderer {
+            renderer.lock().set_runtime_atlas(runtime_atlas);
+        }
+
         // Process custom shader creation requests
         let pending_shaders: Vec<(u32, String, String)> = {
             let mut bridge = bridge_for_loop.borrow_mut();
@@ -512,9 +794,10 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             std::mem::take(&mut bridge.shader_param_queue)
         };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             for (id, name, source) in pending_shaders {
-                renderer.shaders.create(&renderer.gpu.device, id, &name, &source);
+                renderer.create_shader(id, &name, &source);
             }
             for (shader_id, index, values) in shader_params {
                 renderer
@@ -540,21 +823,51 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             let mut bridge = bridge_for_loop.borrow_mut();
             std::mem::replace(&mut bridge.effect_clear, false)
         };
+        // Render target effect chains share effect IDs with the screen effects
+        let (target_effects, target_effect_clears) = {
+            use arcane_core::scripting::target_ops::TargetState;
+            let op_state = rt.inner().op_state();
+            let op_state = op_state.borrow();
+            let ts = op_state.borrow::<Rc<RefCell<TargetState>>>();
+            let mut ts = ts.borrow_mut();
+            (
+                std::mem::take(&mut ts.effect_create_queue),
+                std::mem::take(&mut ts.effect_clear_queue),
+            )
+        };
+        let (transition_shaders, transition) = {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            (
+                std::mem::take(&mut bridge.transition_shader_queue),
+                bridge.transition.overlay(),
+            )
+        };
 
-        if let Some(ref mut renderer) = state.renderer {
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
             if effect_clear {
                 renderer.postprocess.clear();
+                renderer.target_postprocess.clear();
+            }
+            for id in target_effect_clears {
+                renderer.target_postprocess.remove(&id);
             }
             for id in effect_removes {
                 renderer.postprocess.remove(id);
+                renderer.remove_target_effect(id);
             }
             for (id, type_name) in pending_effects {
                 if let Some(effect_type) =
                     arcane_core::renderer::postprocess::EffectType::from_str(&type_name)
                 {
-                    renderer
-                        .postprocess
-                        .add(&renderer.gpu.device, id, effect_type);
+                    renderer.add_effect(id, effect_type);
+                }
+            }
+            for (target_id, id, type_name) in target_effects {
+                if let Some(effect_type) =
+                    arcane_core::renderer::postprocess::EffectType::from_str(&type_name)
+                {
+                    renderer.add_target_effect(target_id, id, effect_type);
                 }
             }
             for (effect_id, index, values) in effect_params {
@@ -566,7 +879,45 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                     values[2],
                     values[3],
                 );
+                renderer.set_target_effect_param(effect_id, index, values);
+            }
+            for (id, source) in transition_shaders {
+                renderer.add_transition_shader(id, &source);
+            }
+            renderer.postprocess.set_transition(transition);
+        }
+
+        // Process GPU particle emitter commands
+        let particle_cmds = {
+            let mut bridge = bridge_for_loop.borrow_mut();
+            std::mem::take(&mut bridge.gpu_particle_commands)
+        };
+
+        if let Some(ref renderer) = state.renderer {
+            let renderer = &mut *renderer.lock();
+            for cmd in particle_cmds {
+                renderer.particles.apply(&renderer.gpu.device, cmd);
+            }
+        }
+
+        // Apply adaptive quality settings and report the tier in use, sprite
+        // batching and atlas stats and new GPU validation errors back to TS
+        if let Some(ref renderer) = state.renderer {
+            let mut renderer = renderer.lock();
+            let mut bridge = bridge_for_loop.borrow_mut();
+            renderer.quality.configure(bridge.quality_config);
+            bridge.quality_status = renderer.quality.status_json();
+            bridge.sprite_stats = renderer.sprites.frame_stats();
+            bridge.atlas_stats = renderer.atlas.stats();
+            profile.encode_ms = renderer.render_timings.encode_ms;
+            profile.submit_ms = renderer.render_timings.submit_ms;
+            if bridge.debug_hud.enabled {
+                bridge.debug_hud.stats.texture_bytes = renderer.textures.memory_bytes();
             }
+            renderer.gpu_errors.capture = bridge.gpu_error_capture;
+            let errors = renderer.gpu_errors.take_pending();
+            bridge.gpu_errors.extend(errors);
+            bridge.recent_gpu_errors = renderer.gpu_errors.recent().to_vec();
         }
 
         // Drain audio commands from bridge and send to audio thread
@@ -574,23 +925,25 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             let mut bridge = bridge_for_loop.borrow_mut();
             std::mem::take(&mut bridge.audio_commands)
         };
+        profile.audio_commands = audio_cmds.len() as u32;
+        bridge_for_loop.borrow_mut().frame_profile.record(profile);
 
         for cmd in audio_cmds {
             let _ = process_audio_command(&audio_tx, cmd, &bridge_for_loop);
         }
+        bridge_for_loop.borrow_mut().audio_levels = *audio_levels.lock().unwrap_or_else(|e| e.into_inner());
 
         // Drain geometry commands from GeoState and pass to renderer
         {
             use arcane_core::scripting::geometry_ops::GeoState;
-            let geo_cmds = {
+            let mut geo_cmds = {
                 let op_state = rt.inner().op_state();
                 let op_state = op_state.borrow();
                 let geo = op_state.borrow::<Rc<RefCell<GeoState>>>();
                 std::mem::take(&mut geo.borrow_mut().commands)
             };
-            if let Some(ref mut renderer) = state.renderer {
-                renderer.set_geo_commands(geo_cmds);
-            }
+            error_boundary.settle_geo(&mut geo_cmds);
+            state.frame.geo = geo_cmds;
         }
 
         // Drain SDF commands from SdfState and pass to renderer
@@ -602,33 +955,64 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                 let sdf = op_state.borrow::<Rc<RefCell<SdfState>>>();
                 std::mem::take(&mut sdf.borrow_mut().commands)
             };
-            if let Some(ref mut renderer) = state.renderer {
-                renderer.set_sdf_commands(sdf_cmds);
-            }
+            state.frame.sdf = sdf_cmds;
         }
 
-        // Process render targets: create/destroy GPU resources, render target sprite queues
+        // Drain this frame's stencil masks from MaskState
+        {
+            use arcane_core::scripting::mask_ops::MaskState;
+            let op_state = rt.inner().op_state();
+            let op_state = op_state.borrow();
+            let masks = op_state.borrow::<Rc<RefCell<MaskState>>>();
+            state.frame.masks = masks.borrow_mut().take_frame();
+        }
+
+        // Process render targets: create/destroy GPU resources, render target queues
         {
             use arcane_core::scripting::target_ops::TargetState;
 
+            use arcane_core::scripting::minimap_ops::MinimapState;
+
             // Drain create/destroy/render queues from TargetState
-            let (create_queue, target_sprite_queues, destroy_queue) = {
+            let (create_queue, mut target_sprite_queues, target_geo_queues, target_sdf_queues, destroy_queue, target_cameras) = {
                 let op_state = rt.inner().op_state();
                 let op_state = op_state.borrow();
                 let ts = op_state.borrow::<Rc<RefCell<TargetState>>>();
                 let mut ts = ts.borrow_mut();
+                let mut queues = std::mem::take(&mut ts.target_sprite_queues);
+                // Scene-capturing target cameras re-render the main scene
+                ts.capture_scene(&bridge_for_loop.borrow().sprite_commands, &mut queues);
+                ts.capture_scene_shapes(&state.frame.geo, &state.frame.sdf);
                 (
                     std::mem::take(&mut ts.create_queue),
-                    std::mem::take(&mut ts.target_sprite_queues),
+                    queues,
+                    std::mem::take(&mut ts.target_geo_queues),
+                    std::mem::take(&mut ts.target_sdf_queues),
                     std::mem::take(&mut ts.destroy_queue),
+                    ts.cameras.clone(),
                 )
             };
 
-            if let Some(ref mut renderer) = state.renderer {
+            // Minimaps re-render the main scene's sprites into their targets
+            {
+                let op_state = rt.inner().op_state();
+                let op_state = op_state.borrow();
+                let ms = op_state.borrow::<Rc<RefCell<MinimapState>>>();
+                let bridge = bridge_for_loop.borrow();
+                ms.borrow_mut().capture(&bridge.sprite_commands, &mut target_sprite_queues);
+            }
+
+            if let Some(ref renderer) = state.renderer {
+                let renderer = &mut *renderer.lock();
                 for (id, w, h) in create_queue {
                     renderer.create_render_target(id, w, h);
                 }
-                renderer.render_targets_prepass(target_sprite_queues);
+                renderer.render_targets_prepass(
+                    target_sprite_queues,
+                    target_geo_queues,
+                    target_sdf_queues,
+                    &target_cameras,
+                );
                 for id in destroy_queue {
                     renderer.destroy_render_target(id);
                 }
@@ -636,59 +1020,125 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
         }
 
         // Collect sprite commands and lighting from bridge
+        let hud_bodies = bridge_for_loop.borrow().debug_hud.enabled.then(|| physics_body_count(rt));
         {
             let mut bridge = bridge_for_loop.borrow_mut();
-            state.sprite_commands.append(&mut bridge.sprite_commands);
-            state.camera_x = bridge.camera_x;
-            state.camera_y = bridge.camera_y;
-            state.camera_zoom = bridge.camera_zoom;
-            state.camera_bounds = bridge.camera_bounds;
+            state.frame.sprites.append(&mut bridge.sprite_commands);
+            state.frame.sprites.extend(bridge.sprite_store.sprite_commands());
+            // A push without its pop doesn't clip the next frame
+            bridge.clip_stack.clear();
+            state.frame.decals = bridge.decals.sprite_commands();
+            state.frame.camera_x = bridge.camera_x;
+            state.frame.camera_y = bridge.camera_y;
+            state.frame.camera_zoom = bridge.camera_zoom;
+            state.frame.camera_bounds = bridge.camera_bounds;
+            state.frame.camera_shake = bridge.hitstop.shake_offset();
+            state.frame.background = bridge.background_fill;
+            state.frame.letterbox = bridge.letterbox;
+            state.frame.ysort_layers.clone_from(&bridge.ysort_layers);
+            state.frame.pixel_snap = bridge.pixel_snap;
+            state.frame.viewports = bridge.viewports.values().copied().collect();
+            state.text_input = bridge.text_input;
             bridge.camera_dirty = false;
 
-            // Sync lighting state to renderer
-            if let Some(ref mut renderer) = state.renderer {
-                renderer.lighting.ambient = bridge.ambient_light;
-                renderer.lighting.lights = bridge.point_lights.drain(..).collect();
-
-                // Sync GI / radiance cascade state
-                renderer.radiance_state.enabled = bridge.gi_enabled;
-                renderer.radiance_state.gi_intensity = bridge.gi_intensity;
-                renderer.radiance_state.probe_spacing = bridge.gi_probe_spacing;
-                renderer.radiance_state.interval = bridge.gi_interval;
-                renderer.radiance_state.cascade_count = bridge.gi_cascade_count;
-
-                renderer.radiance_state.emissives = bridge.emissives.drain(..).map(|e| {
-                    arcane_core::renderer::EmissiveSurface {
-                        x: e[0], y: e[1], width: e[2], height: e[3],
-                        r: e[4], g: e[5], b: e[6], intensity: e[7],
-                    }
-                }).collect();
+            // Draw the error overlay on top of the replayed frame
+            if error_boundary.error().is_some() {
+                let font = bridge.builtin_font_texture();
+                let panel = bridge.solid_texture("__arcane_error_panel__", [255, 255, 255, 255]);
+                state.frame.sprites.extend(error_boundary.overlay_sprites(
+                    font,
+                    panel,
+                    bridge.camera_x,
+                    bridge.camera_y,
+                    bridge.camera_zoom,
+                    bridge.viewport_width,
+                    bridge.viewport_height,
+                ));
+            }
+            if stats_overlay {
+                let font = bridge.builtin_font_texture();
+                let panel = bridge.solid_texture("__arcane_stats_panel__", [255, 255, 255, 255]);
+                state.frame.sprites.extend(bridge.frame_profile.overlay_sprites(
+                    font,
+                    panel,
+                    bridge.camera_x,
+                    bridge.camera_y,
+                    bridge.camera_zoom,
+                    bridge.viewport_width,
+                ));
+            }
+            if let Some(textures) = reload_diff_textures {
+                let font = bridge.builtin_font_texture();
+                let panel = bridge.solid_texture("__arcane_reload_diff_panel__", [255, 255, 255, 255]);
+                state.frame.sprites.extend(reload_diff.overlay_sprites(
+                    textures,
+                    font,
+                    panel,
+                    bridge.camera_x,
+                    bridge.camera_y,
+                    bridge.camera_zoom,
+                    [bridge.viewport_width, bridge.viewport_height],
+                    bridge.mouse_x,
+                ));
+            }
+            if let Some(bodies) = hud_bodies {
+                let (font_id, shader_id) = bridge.debug_hud_resources();
+                let panel = bridge.solid_texture("__arcane_hud_panel__", [255, 255, 255, 255]);
+                let b = &mut *bridge;
+                let stats = &mut b.debug_hud.stats;
+                stats.bodies = bodies;
+                stats.draw_calls = b.sprite_stats.draw_calls;
+                stats.audio_instances = b.audio_levels.playing;
+                if let Some(font) = b.msdf_fonts.get(font_id) {
+                    b.shader_param_queue
+                        .extend(text_shader_params(font).map(|(index, values)| (shader_id, index, values)));
+                    state.frame.sprites.extend(b.debug_hud.sprites(
+                        font,
+                        panel,
+                        b.camera_x,
+                        b.camera_y,
+                        b.camera_zoom,
+                    ));
+                }
+            }
 
-                renderer.radiance_state.occluders = bridge.occluders.drain(..).map(|o| {
-                    arcane_core::renderer::Occluder {
-                        x: o[0], y: o[1], width: o[2], height: o[3],
-                    }
-                }).collect();
+            // Sync lighting state to the frame
+            let frame = &mut state.frame;
+            frame.lighting.ambient = bridge.ambient_light;
+            frame.lighting.lights = bridge.point_lights.drain(..).collect();
+
+            // Sync GI / radiance cascade state
+            frame.radiance.enabled = bridge.gi_enabled;
+            frame.radiance.gi_intensity = bridge.gi_intensity;
+            frame.radiance.probe_spacing = bridge.gi_probe_spacing;
+            frame.radiance.interval = bridge.gi_interval;
+            frame.radiance.cascade_count = bridge.gi_cascade_count;
+
+            frame.radiance.emissives = bridge.emissives.drain(..).map(|e| {
+                arcane_core::renderer::EmissiveSurface {
+                    x: e[0], y: e[1], width: e[2], height: e[3],
+                    r: e[4], g: e[5], b: e[6], intensity: e[7],
+                }
+            }).collect();
 
-                renderer.radiance_state.directional_lights = bridge.directional_lights.drain(..).map(|d| {
-                    arcane_core::renderer::DirectionalLight {
-                        angle: d[0], r: d[1], g: d[2], b: d[3], intensity: d[4],
-                    }
-                }).collect();
+            frame.radiance.occluders = bridge.occluders.drain(..).map(|o| {
+                arcane_core::renderer::Occluder {
+                    x: o[0], y: o[1], width: o[2], height: o[3],
+                }
+            }).collect();
 
-                renderer.radiance_state.spot_lights = bridge.spot_lights.drain(..).map(|s| {
-                    arcane_core::renderer::SpotLight {
-                        x: s[0], y: s[1], angle: s[2], spread: s[3], range: s[4],
-                        r: s[5], g: s[6], b: s[7], intensity: s[8],
-                    }
-                }).collect();
-            } else {
-                bridge.point_lights.clear();
-                bridge.emissives.clear();
-                bridge.occluders.clear();
-                bridge.directional_lights.clear();
-                bridge.spot_lights.clear();
-            }
+            frame.radiance.directional_lights = bridge.directional_lights.drain(..).map(|d| {
+                arcane_core::renderer::DirectionalLight {
+                    angle: d[0], r: d[1], g: d[2], b: d[3], intensity: d[4],
+                }
+            }).collect();
+
+            frame.radiance.spot_lights = bridge.spot_lights.drain(..).map(|(s, animation)| {
+                arcane_core::renderer::SpotLight {
+                    x: s[0], y: s[1], angle: s[2], spread: s[3], range: s[4],
+                    r: s[5], g: s[6], b: s[7], intensity: s[8], animation,
+                }
+            }).collect();
         }
 
         // Poll inspector requests (if inspector is active)
@@ -696,8 +1146,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             while let Ok((req, resp_tx)) = rx.try_recv() {
                 if matches!(req, arcane_core::agent::InspectorRequest::CaptureFrame) {
                     // Defer: set capture flag, store sender for window.rs to complete
-                    if let Some(ref mut renderer) = state.renderer {
-                        renderer.capture_pending = true;
+                    if state.renderer.is_some() {
+                        state.frame.capture = true;
                         state.pending_capture_tx = Some(resp_tx);
                     } else {
                         let _ = resp_tx.send(arcane_core::agent::InspectorResponse::error(
@@ -705,7 +1155,13 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                         ));
                     }
                 } else {
-                    let response = process_inspector_request(rt, req, &reload_flag, &bridge_for_loop);
+                    let response = process_inspector_request(
+                        rt,
+                        req,
+                        &reload_flag,
+                        &bridge_for_loop,
+                        &mut quick_saves,
+                    );
                     let _ = resp_tx.send(response);
                 }
             }
@@ -716,8 +1172,8 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
             while let Ok((req, resp_tx)) = rx.try_recv() {
                 if matches!(req, arcane_core::agent::InspectorRequest::CaptureFrame) {
                     // Defer: set capture flag, store sender for window.rs to complete
-                    if let Some(ref mut renderer) = state.renderer {
-                        renderer.capture_pending = true;
+                    if state.renderer.is_some() {
+                        state.frame.capture = true;
                         state.pending_capture_tx = Some(resp_tx);
                     } else {
                         let _ = resp_tx.send(arcane_core::agent::InspectorResponse::error(
@@ -725,7 +1181,13 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
                         ));
                     }
                 } else {
-                    let response = process_inspector_request(rt, req, &reload_flag, &bridge_for_loop);
+                    let response = process_inspector_request(
+                        rt,
+                        req,
+                        &reload_flag,
+                        &bridge_for_loop,
+                        &mut quick_saves,
+                    );
                     let _ = resp_tx.send(response);
                 }
             }
@@ -734,8 +1196,20 @@ pub fn run(entry: String, inspector_port: Option<u16>, mcp_port: Option<u16>) ->
         Ok(())
     });
 
+    if let Some(headless) = headless {
+        let frames = headless.frames;
+        let out_dir = headless.out_dir.clone();
+        arcane_core::platform::run_headless(config, headless, render_state.clone(), frame_callback)?;
+        finish_recording(&render_state);
+        flush_world(&bridge_state);
+        println!("Rendered {frames} frame(s) to {}", out_dir.display());
+        return Ok(());
+    }
+
     // Run the winit event loop (blocks until window closes)
-    arcane_core::platform::run_event_loop(config, render_state, frame_callback)?;
+    arcane_core::platform::run_event_loop(config, render_state.clone(), frame_callback)?;
+    finish_recording(&render_state);
+    flush_world(&bridge_state);
 
     // Clean up MCP port file on exit
     cleanup_mcp_port_file();
@@ -748,6 +1222,7 @@ fn process_inspector_request(
     req: arcane_core::agent::InspectorRequest,
     reload_flag: &Arc<AtomicBool>,
     bridge: &Rc<RefCell<RenderBridgeState>>,
+    quick_saves: &mut QuickSaveSlots,
 ) -> arcane_core::agent::InspectorResponse {
     use arcane_core::agent::{InspectorRequest, InspectorResponse};
 
@@ -823,21 +1298,130 @@ fn process_inspector_request(
             let b = bridge.borrow();
             let frame_time_ms = b.frame_time_ms;
             let draw_calls = b.draw_call_count;
+            let sprites = b.sprite_stats.to_json();
+            let atlas = b.atlas_stats.to_json();
+            let textures = b.texture_stream_stats.to_json();
+            let ops = op_profiler(runtime).borrow().to_json(op_profiler::DEFAULT_TOP);
+            let queries = physics_query_stats(runtime);
             let fps = if frame_time_ms > 0.0 {
                 1000.0 / frame_time_ms
             } else {
                 0.0
             };
             InspectorResponse::json(format!(
-                "{{\"frame_time_ms\":{frame_time_ms:.2},\"draw_calls\":{draw_calls},\"fps\":{fps:.1}}}"
+                "{{\"frame_time_ms\":{frame_time_ms:.2},\"draw_calls\":{draw_calls},\"fps\":{fps:.1},\"sprites\":{sprites},\"atlas\":{atlas},\"textures\":{textures},\"ops\":{ops},\"physics_queries\":{queries}}}"
             ))
         }
+        InspectorRequest::GetProfile => InspectorResponse::json(bridge.borrow().frame_profile.to_json()),
+        InspectorRequest::GetOpStats { top } => {
+            let top = top.map_or(op_profiler::DEFAULT_TOP, |n| n as usize);
+            InspectorResponse::json(op_profiler(runtime).borrow().to_json(top))
+        }
+        InspectorRequest::SetOpProfiling { enabled } => {
+            op_profiler(runtime).borrow_mut().set_enabled(enabled);
+            InspectorResponse::json(format!("{{\"ok\":true,\"enabled\":{enabled}}}"))
+        }
+        InspectorRequest::GetGpuErrors => {
+            let b = bridge.borrow();
+            InspectorResponse::json(arcane_core::renderer::gpu_errors::errors_json(&b.recent_gpu_errors))
+        }
         InspectorRequest::CaptureFrame => {
             // Should be handled as a deferred capture in the polling loop.
             // If we get here, it means capture was routed incorrectly.
             InspectorResponse::error(500, "Frame capture must be deferred to render loop".into())
         }
+        InspectorRequest::ListQuickSaves => InspectorResponse::json(quick_saves.summary_json()),
+        InspectorRequest::QuickSave { slot } => {
+            match quick_save(runtime, quick_saves, slot_index(slot)) {
+                Ok(slot) => InspectorResponse::json(format!("{{\"ok\":true,\"slot\":{}}}", slot + 1)),
+                Err(e) => InspectorResponse::error(400, format!("{e}")),
+            }
+        }
+        InspectorRequest::QuickLoad { slot } => {
+            match quick_load(runtime, quick_saves, slot_index(slot)) {
+                Ok(slot) => InspectorResponse::json(format!("{{\"ok\":true,\"slot\":{}}}", slot + 1)),
+                Err(e) => InspectorResponse::error(404, format!("{e}")),
+            }
+        }
+    }
+}
+
+/// Convert an inspector slot number (1-based, like Ctrl+1..9) to an index.
+/// Slot 0 maps out of range so it is rejected instead of wrapping.
+fn slot_index(slot: Option<u32>) -> Option<usize> {
+    slot.map(|s| (s as usize).wrapping_sub(1))
+}
+
+/// Run a quick save hotkey command, logging the outcome.
+fn run_quicksave_command(
+    runtime: &mut ArcaneRuntime,
+    slots: &mut QuickSaveSlots,
+    command: QuickSaveCommand,
+) {
+    match command {
+        QuickSaveCommand::Save => match quick_save(runtime, slots, None) {
+            Ok(slot) => eprintln!("[quicksave] Saved slot {}", slot + 1),
+            Err(e) => eprintln!("[quicksave] Save failed: {e}"),
+        },
+        QuickSaveCommand::Load => match quick_load(runtime, slots, None) {
+            Ok(slot) => eprintln!("[quicksave] Loaded slot {}", slot + 1),
+            Err(e) => eprintln!("[quicksave] Load failed: {e}"),
+        },
+        selection => {
+            slots.apply_selection(selection);
+            eprintln!("[quicksave] Slot {} selected", slots.current() + 1);
+        }
+    }
+}
+
+/// Capture the agent snapshot and physics world into a slot (None = current).
+/// Returns the slot index used.
+fn quick_save(
+    runtime: &mut ArcaneRuntime,
+    slots: &mut QuickSaveSlots,
+    slot: Option<usize>,
+) -> Result<usize> {
+    use arcane_core::scripting::physics_ops::PhysicsState;
+
+    let snapshot =
+        runtime.eval_to_string("JSON.stringify(globalThis.__arcaneAgent?.captureSnapshot())")?;
+    let state_json = (snapshot != "undefined" && snapshot != "null").then_some(snapshot);
+    let physics = {
+        let op_state = runtime.inner().op_state();
+        let op_state = op_state.borrow();
+        let physics = op_state.borrow::<Rc<RefCell<PhysicsState>>>();
+        physics.borrow().0.clone()
+    };
+    if state_json.is_none() && physics.is_none() {
+        anyhow::bail!("Nothing to save: no registered agent or physics world");
+    }
+    slots
+        .store(slot, QuickSave::new(state_json, physics))
+        .ok_or_else(|| anyhow::anyhow!("Invalid quick save slot"))
+}
+
+/// Restore the agent state and physics world from a slot (None = current).
+/// Returns the slot index used.
+fn quick_load(
+    runtime: &mut ArcaneRuntime,
+    slots: &QuickSaveSlots,
+    slot: Option<usize>,
+) -> Result<usize> {
+    use arcane_core::scripting::physics_ops::PhysicsState;
+
+    let index = slot.unwrap_or(slots.current());
+    let Some(save) = slots.get(Some(index)) else {
+        anyhow::bail!("No quick save in slot {}", index.wrapping_add(1));
+    };
+    if let Some(ref snapshot) = save.state_json {
+        // The snapshot is JSON, which is also a valid JS expression
+        runtime.eval_to_string(&format!("globalThis.__arcaneAgent?.restoreSnapshot({snapshot})"))?;
     }
+    let op_state = runtime.inner().op_state();
+    let op_state = op_state.borrow();
+    let physics = op_state.borrow::<Rc<RefCell<PhysicsState>>>();
+    physics.borrow_mut().0 = save.physics.clone();
+    Ok(index)
 }
 
 /// Evaluate a script that returns JSON and wrap it as an InspectorResponse.
@@ -851,6 +1435,47 @@ fn eval_json(
     }
 }
 
+/// Write a reload's captured frames to .arcane/reload-diffs/<timestamp>-{before,after}.png
+/// and upload them with their difference image, reusing the previous reload's texture IDs.
+fn upload_reload_diff(
+    bridge: &mut RenderBridgeState,
+    output: ReloadDiffOutput,
+    previous: Option<DiffTextures>,
+) -> DiffTextures {
+    let dir = std::path::PathBuf::from(RELOAD_DIFF_DIR);
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let written = std::fs::create_dir_all(&dir).and_then(|()| {
+        std::fs::write(dir.join(format!("{timestamp}-before.png")), &output.before_png)?;
+        std::fs::write(dir.join(format!("{timestamp}-after.png")), &output.after_png)
+    });
+    match written {
+        Ok(()) => eprintln!(
+            "[reload-diff] {} px changed ({:.2}%), frames saved to {}/{timestamp}-*.png",
+            output.summary.changed,
+            output.summary.percent(),
+            dir.display()
+        ),
+        Err(e) => eprintln!("[reload-diff] Failed to write frames: {e}"),
+    }
+
+    let textures = previous.unwrap_or_else(|| {
+        let first = bridge.next_texture_id;
+        bridge.next_texture_id += 3;
+        DiffTextures { before: first, after: first + 1, diff: first + 2 }
+    });
+    for (id, frame) in [
+        (textures.before, output.before),
+        (textures.after, output.after),
+        (textures.diff, output.diff),
+    ] {
+        bridge.raw_texture_upload_queue.push((id, frame.width, frame.height, frame.pixels));
+    }
+    textures
+}
+
 /// Escape single quotes and backslashes in a string for safe JS interpolation.
 fn escape_js(s: &str) -> String {
     s.replace('\\', "\\\\").replace('\'', "\\'")
@@ -973,19 +1598,60 @@ fn process_audio_command(
             let _ = audio_tx.send(AudioCommand::SetInstancePitch { instance_id, pitch });
         }
 
-        BridgeAudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y } => {
+        BridgeAudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y, occluded } => {
             let _ = audio_tx.send(AudioCommand::UpdateSpatialPositions {
                 updates,
                 listener_x,
                 listener_y,
+                occluded,
             });
         }
 
+        BridgeAudioCommand::SetOcclusion { instance_id, params } => {
+            let _ = audio_tx.send(AudioCommand::SetOcclusion { instance_id, params });
+        }
+
+        BridgeAudioCommand::SetDoppler { instance_id, params } => {
+            let _ = audio_tx.send(AudioCommand::SetDoppler { instance_id, params });
+        }
+
+        BridgeAudioCommand::SetSpatialParams { instance_id, curve, min_distance, max_distance, rolloff } => {
+            if let Some(curve) = arcane_core::audio::Attenuation::from_u32(curve) {
+                let params = arcane_core::audio::SpatialParams { curve, min_distance, max_distance, rolloff };
+                let _ = audio_tx.send(AudioCommand::SetSpatialParams { instance_id, params });
+            }
+        }
+
+        BridgeAudioCommand::FadeIn { instance_id, duration, curve } => {
+            let _ = audio_tx.send(AudioCommand::FadeIn { instance_id, duration, curve });
+        }
+
+        BridgeAudioCommand::FadeOut { instance_id, duration, curve } => {
+            let _ = audio_tx.send(AudioCommand::FadeOut { instance_id, duration, curve });
+        }
+
+        BridgeAudioCommand::CrossfadeTo { from_instance, to_instance, duration, curve } => {
+            let _ = audio_tx.send(AudioCommand::CrossfadeTo { from_instance, to_instance, duration, curve });
+        }
+
+        BridgeAudioCommand::StopAfter { instance_id, delay } => {
+            let _ = audio_tx.send(AudioCommand::StopAfter { instance_id, delay });
+        }
+
         BridgeAudioCommand::SetBusVolume { bus, volume } => {
             if let Some(bus_enum) = arcane_core::audio::AudioBus::from_u32(bus) {
                 let _ = audio_tx.send(AudioCommand::SetBusVolume { bus: bus_enum, volume });
             }
         }
+
+        BridgeAudioCommand::SetBusEffect { bus, effect, params } => {
+            if let (Some(bus), Some(effect)) = (
+                arcane_core::audio::AudioBus::from_u32(bus),
+                arcane_core::audio::BusEffect::from_parts(effect, params),
+            ) {
+                let _ = audio_tx.send(AudioCommand::SetBusEffect { bus, effect });
+            }
+        }
     }
     Ok(())
 }
@@ -999,6 +1665,74 @@ fn write_mcp_port_file(port: u16) {
     let _ = std::fs::write(dir.join("mcp-port"), port.to_string());
 }
 
+/// The runtime's op-call profiler (installed by the render bridge runtime).
+fn op_profiler(rt: &mut ArcaneRuntime) -> Rc<RefCell<OpProfiler>> {
+    let op_state = rt.inner().op_state();
+    let op_state = op_state.borrow();
+    op_state.borrow::<Rc<RefCell<OpProfiler>>>().clone()
+}
+
+/// Milliseconds spent stepping physics since the last call.
+fn take_physics_step_time(rt: &mut ArcaneRuntime) -> f64 {
+    use arcane_core::scripting::physics_ops::PhysicsStepTime;
+
+    let op_state = rt.inner().op_state();
+    let mut op_state = op_state.borrow_mut();
+    let time = std::mem::take(&mut op_state.borrow_mut::<PhysicsStepTime>().0);
+    time.as_secs_f64() * 1000.0
+}
+
+/// Bodies in the physics world, for the debug HUD.
+fn physics_body_count(rt: &mut ArcaneRuntime) -> u32 {
+    use arcane_core::scripting::physics_ops::PhysicsState;
+
+    let op_state = rt.inner().op_state();
+    let op_state = op_state.borrow();
+    let physics = op_state.borrow::<Rc<RefCell<PhysicsState>>>();
+    physics.borrow().0.as_ref().map_or(0, |world| world.body_count() as u32)
+}
+
+/// JSON of the physics world's scene query counts over the last frame.
+fn physics_query_stats(rt: &mut ArcaneRuntime) -> String {
+    use arcane_core::scripting::physics_ops::{query_stats_json, PhysicsState};
+
+    let op_state = rt.inner().op_state();
+    let op_state = op_state.borrow();
+    let physics = op_state.borrow::<Rc<RefCell<PhysicsState>>>();
+    let stats = physics.borrow().0.as_ref().map(|world| world.query_stats()).unwrap_or_default();
+    query_stats_json(&stats)
+}
+
+/// Longest side of a replay thumbnail in pixels.
+const REPLAY_THUMBNAIL_SIZE: u32 = 160;
+
+/// Shrink a captured frame to a replay thumbnail PNG.
+fn replay_thumbnail(png: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(png).ok()?;
+    let thumbnail = image.thumbnail(REPLAY_THUMBNAIL_SIZE, REPLAY_THUMBNAIL_SIZE);
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    thumbnail.write_to(&mut bytes, image::ImageFormat::Png).ok()?;
+    Some(bytes.into_inner())
+}
+
+/// Finish a recording the game left running, waiting for its file to be written.
+fn finish_recording(render_state: &Rc<RefCell<RenderState>>) {
+    let Some(renderer) = render_state.borrow().renderer.clone() else { return };
+    if let Some((path, frames)) = renderer.lock().finish_recording() {
+        println!("[record] Saved {frames} frame(s) to {}", path.display());
+    }
+}
+
+/// Save world chunks edited since the last autosave.
+fn flush_world(bridge: &Rc<RefCell<RenderBridgeState>>) {
+    let b = &mut *bridge.borrow_mut();
+    match b.world.flush(&b.tilemaps) {
+        Ok(0) => {}
+        Ok(written) => println!("[world] Saved {written} chunk(s)"),
+        Err(e) => eprintln!("[world] Failed to save chunks: {e}"),
+    }
+}
+
 /// Remove the MCP port file on shutdown.
 fn cleanup_mcp_port_file() {
     let _ = std::fs::remove_file(".arcane/mcp-port");
@@ -1019,6 +1753,9 @@ fn reload_runtime(
         type_check::check_types(entry_path)?;
     }
 
+    // Op profiling stays on across reloads
+    let op_profiling = runtime.as_mut().is_some_and(|rt| op_profiler(rt).borrow().is_enabled());
+
     // Drop the old V8 isolate BEFORE creating the new one.
     // This is the key fix: ensures only one isolate exists on the thread at a time.
     *runtime = None;
@@ -1029,9 +1766,21 @@ fn reload_runtime(
     {
         let mut b = bridge.borrow_mut();
         b.sprite_commands.clear();
+        b.clip_stack.clear();
+        b.batch_pixel_snap = None;
+        b.decals.clear();
+        b.sprite_store.clear();
+        b.flashes.borrow_mut().clear();
+        b.hitstop.clear();
+        // The reloaded script binds its actions again
+        b.action_map.clear();
+        // A text field from the old script no longer has focus
+        b.text_input = None;
         b.point_lights.clear();
         b.texture_load_queue.clear();
         b.texture_load_queue_linear.clear();
+        b.texture_events.clear();
+        b.normal_map_queue.clear();
         b.raw_texture_upload_queue.clear();
         b.font_texture_queue.clear();
         b.audio_commands.clear();
@@ -1041,7 +1790,12 @@ fn reload_runtime(
         b.effect_param_queue.clear();
         b.effect_remove_queue.clear();
         b.effect_clear = true;
+        b.transition.cancel();
+        b.transition_shader_queue.clear();
         b.elapsed_time = 0.0;
+        b.background_fill = None;
+        b.letterbox = None;
+        b.pixel_snap = false;
         b.emissives.clear();
         b.occluders.clear();
         b.directional_lights.clear();
@@ -1050,19 +1804,21 @@ fn reload_runtime(
         b.msdf_shader_queue.clear();
         b.msdf_texture_load_queue.clear();
 
-        // Clear solid texture cache so they can be recreated with new colors.
-        // Keep file texture cache to avoid re-uploading large images.
-        b.texture_path_to_id.retain(|k, _| !k.starts_with("__solid__"));
-
-        // Clear sound cache for the same reason (allow sound changes on reload).
-        // Sound files are typically small, so reloading is cheap.
-        b.sound_path_to_id.clear();
+        // Keep texture/sound ID maps so IDs stay stable across reloads (the GPU
+        // texture store and audio thread still hold the data). Files edited on
+        // disk are re-uploaded under their existing IDs; solid textures whose
+        // color changed are re-uploaded when the new code requests them.
+        let requeued = b.requeue_changed_assets();
+        if requeued > 0 {
+            eprintln!("[hot-reload] Reloading {requeued} changed asset(s)");
+        }
     }
 
     // Create new runtime with the SAME bridge Rc and import map
     let import_map = create_import_map(base_dir);
     let mut new_runtime =
         ArcaneRuntime::new_with_render_bridge_and_import_map(bridge.clone(), import_map);
+    op_profiler(&mut new_runtime).borrow_mut().set_enabled(op_profiling);
 
     // Re-execute entry file
     let tokio_rt = tokio::runtime::Builder::new_current_thread()
@@ -1084,11 +1840,13 @@ fn reload_runtime(
     Ok(())
 }
 
-/// Start a file watcher that sets the reload flag when .ts files change.
+/// Start a file watcher that sets the reload flag when .ts files change and
+/// the asset flag when any other file does.
 fn start_file_watcher(
     base_dir: &Path,
     entry_path: &Path,
     reload_flag: Arc<AtomicBool>,
+    asset_flag: Arc<AtomicBool>,
 ) -> Option<notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>> {
     use notify::RecursiveMode;
     use notify_debouncer_mini::new_debouncer;
@@ -1100,15 +1858,18 @@ fn start_file_watcher(
         move |res: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
             match res {
                 Ok(events) => {
-                    let has_ts_change = events.iter().any(|e| {
+                    let is_ts = |e: &notify_debouncer_mini::DebouncedEvent| {
                         e.path
                             .extension()
                             .map(|ext| ext == "ts")
                             .unwrap_or(false)
-                    });
-                    if has_ts_change {
+                    };
+                    if events.iter().any(is_ts) {
                         flag.store(true, Ordering::Relaxed);
                     }
+                    if events.iter().any(|e| !is_ts(e)) {
+                        asset_flag.store(true, Ordering::Relaxed);
+                    }
                 }
                 Err(e) => eprintln!("[watcher] Error: {e:?}"),
             }
@@ -9,6 +9,13 @@ pub mod init;
 pub mod mcp_bridge;
 pub mod catalog;
 pub mod screenshot;
+pub mod render;
+pub mod assets;
+pub mod config;
+pub mod completions;
+pub mod schema;
+pub mod plugins;
+pub mod replay;
 use std::path::Path;
 use arcane_core::scripting::ImportMap;
 
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use arcane_core::plugin::{self, Plugin};
+
+use super::config;
+
+/// Plugins compiled into this binary. To add one, depend on its crate behind
+/// a Cargo feature and push it here under `#[cfg(feature = "...")]`.
+fn static_plugins() -> Vec<Box<dyn Plugin>> {
+    Vec::new()
+}
+
+/// Register the static plugins and, if `allow_native` (`--allow-native-plugins`),
+/// load the native ones listed in the `arcane.toml` of the project containing
+/// `project_path` (a file or directory). Native plugins are arbitrary code,
+/// so a project someone sent you doesn't get to run them unasked. Failures
+/// are reported and skipped so a broken plugin never stops the CLI.
+pub fn load(project_path: &Path, allow_native: bool) {
+    for plugin in static_plugins() {
+        if let Err(e) = plugin::register(plugin) {
+            eprintln!("[plugins] {e}");
+        }
+    }
+    let config = match config::load_containing(project_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("[plugins] {e:#}");
+            return;
+        }
+    };
+    if !allow_native {
+        if !config.plugins.native.is_empty() {
+            eprintln!(
+                "[plugins] Skipping {} native plugin(s) listed in arcane.toml; pass --allow-native-plugins to load them",
+                config.plugins.native.len()
+            );
+        }
+        return;
+    }
+    for path in &config.plugins.native {
+        if let Err(e) = plugin::native::load(path) {
+            eprintln!("[plugins] Cannot load {e}");
+        }
+    }
+}
+
+/// Run a subcommand provided by a plugin. `args[0]` is the subcommand name.
+pub fn run_external(args: Vec<String>) -> anyhow::Result<()> {
+    let Some((name, rest)) = args.split_first() else {
+        anyhow::bail!("missing command");
+    };
+    match plugin::find_command(name) {
+        Some(command) => (command.run)(rest),
+        None => anyhow::bail!("unknown command '{name}' (see `arcane --help`)"),
+    }
+}
+
+/// One line per loaded plugin, for `arcane dev`.
+pub fn print_loaded() {
+    let loaded = plugin::loaded();
+    if loaded.is_empty() {
+        return;
+    }
+    eprintln!("[plugins] {} loaded:", loaded.len());
+    for info in loaded {
+        eprintln!("  {info}");
+    }
+}
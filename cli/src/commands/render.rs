@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use arcane_core::platform::HeadlessConfig;
+
+use super::dev::DevOptions;
+
+/// Run the `arcane render` command: run a game without a window for a fixed
+/// number of frames at a fixed time step, writing each frame as a PNG.
+pub fn run(
+    entry: String,
+    frames: u32,
+    out: String,
+    dt: f64,
+    size: String,
+    max_lights: usize,
+) -> Result<()> {
+    if frames == 0 {
+        anyhow::bail!("--frames must be at least 1");
+    }
+    if !(dt.is_finite() && dt >= 0.0) {
+        anyhow::bail!("Invalid --dt {dt} (expected seconds per frame, e.g. 0.016667)");
+    }
+    let (width, height) = parse_size(&size)?;
+    let headless = HeadlessConfig {
+        width,
+        height,
+        frames,
+        dt,
+        out_dir: PathBuf::from(out),
+    };
+    super::dev::run(entry, None, None, DevOptions { max_lights, headless: Some(headless), ..Default::default() })
+}
+
+/// Parse a frame size like "800x600".
+fn parse_size(size: &str) -> Result<(u32, u32)> {
+    let parsed = size
+        .split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+    match parsed {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => anyhow::bail!("Invalid size \"{size}\" (expected WIDTHxHEIGHT, e.g. 800x600)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_width_x_height() {
+        assert_eq!(parse_size("800x600").unwrap(), (800, 600));
+        assert_eq!(parse_size("320X180").unwrap(), (320, 180));
+        assert!(parse_size("800").is_err());
+        assert!(parse_size("0x600").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use arcane_core::replay::{content_hash, ReplayFile, ENGINE_VERSION};
+
+/// Print a replay file's header and whether it plays back with this engine
+/// and the game at `entry`'s directory.
+pub fn info(file: String, entry: String, json: bool, thumbnail: Option<String>) -> Result<()> {
+    let replay = ReplayFile::read(Path::new(&file)).map_err(|e| anyhow!(e))?;
+    let header = &replay.header;
+    let entry_path = fs::canonicalize(&entry).with_context(|| format!("Cannot find entry file: {entry}"))?;
+    let game_dir = entry_path.parent().unwrap_or_else(|| Path::new("."));
+    let hash = content_hash(game_dir).with_context(|| format!("Failed to hash the game in {}", game_dir.display()))?;
+    let problem = header.mismatch(ENGINE_VERSION, &hash);
+
+    if let Some(ref out) = thumbnail {
+        let png = replay.thumbnail.as_ref().ok_or_else(|| anyhow!("{file} has no thumbnail"))?;
+        fs::write(out, png).with_context(|| format!("Failed to write thumbnail to {out}"))?;
+    }
+
+    if json {
+        let mut value: serde_json::Value = serde_json::from_str(&header.to_json(replay.thumbnail.as_ref().map_or(0, Vec::len)))?;
+        value["playable"] = problem.is_none().into();
+        value["problem"] = problem.clone().into();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    let created = format_utc(header.created);
+    println!("{file}");
+    println!("  engine        {}{}", header.engine_version, if header.engine_version == ENGINE_VERSION { "" } else { " (differs)" });
+    println!("  content hash  {}{}", header.content_hash, if header.content_hash == hash { "" } else { " (differs)" });
+    println!("  frames        {}", header.frames);
+    println!("  duration      {:.2}s", header.duration);
+    println!("  recorded      {created}");
+    match replay.thumbnail {
+        Some(ref png) => println!("  thumbnail     {} bytes{}", png.len(), thumbnail.map(|out| format!(", written to {out}")).unwrap_or_default()),
+        None => println!("  thumbnail     none"),
+    }
+    match problem {
+        None => println!("Plays back with engine {ENGINE_VERSION} and the game in {}", game_dir.display()),
+        Some(problem) => println!("Not playable here: {problem}"),
+    }
+    Ok(())
+}
+
+/// `YYYY-MM-DD HH:MM UTC` for Unix milliseconds.
+fn format_utc(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02} {:02}:{:02} UTC", rest / 3600, rest % 3600 / 60)
+}
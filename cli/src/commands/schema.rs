@@ -0,0 +1,139 @@
+use clap::{Arg, ArgAction, Command};
+use serde_json::{Value, json};
+
+/// Print the full command/flag schema as JSON, for tools and agents that
+/// drive the CLI.
+pub fn run(mut cmd: Command) -> anyhow::Result<()> {
+    cmd.build();
+    println!("{}", serde_json::to_string_pretty(&command_schema(&cmd))?);
+    Ok(())
+}
+
+/// Schema of a built command and its subcommands. Clap's own `--help`,
+/// `--version` and `help` subcommand are left out.
+pub fn command_schema(cmd: &Command) -> Value {
+    let args: Vec<Value> = cmd
+        .get_arguments()
+        .filter(|arg| !is_builtin(arg))
+        .map(arg_schema)
+        .collect();
+    let subcommands: Vec<Value> = cmd
+        .get_subcommands()
+        .filter(|sub| sub.get_name() != "help")
+        .map(command_schema)
+        .collect();
+    json!({
+        "name": cmd.get_name(),
+        "version": cmd.get_version(),
+        "about": cmd.get_about().map(|s| s.to_string()),
+        "subcommandRequired": cmd.is_subcommand_required_set(),
+        "args": args,
+        "subcommands": subcommands,
+    })
+}
+
+fn is_builtin(arg: &Arg) -> bool {
+    matches!(
+        arg.get_action(),
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version
+    )
+}
+
+fn arg_schema(arg: &Arg) -> Value {
+    let takes_value = arg.get_action().takes_values();
+    let kind = if arg.is_positional() {
+        "positional"
+    } else if takes_value {
+        "option"
+    } else {
+        "flag"
+    };
+    // Flags get an implicit "false" default and a value name; neither is useful here
+    let default = arg
+        .get_default_values()
+        .first()
+        .filter(|_| takes_value)
+        .map(|v| v.to_string_lossy().into_owned());
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .filter(|_| takes_value)
+        .map(|n| n.as_str());
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect();
+    json!({
+        "name": arg.get_id().as_str(),
+        "kind": kind,
+        "long": arg.get_long(),
+        "short": arg.get_short().map(|c| c.to_string()),
+        "index": arg.get_index(),
+        "help": arg.get_help().map(|s| s.to_string()),
+        "required": arg.is_required_set(),
+        "valueName": value_name,
+        "default": default,
+        "possibleValues": possible,
+        "global": arg.is_global_set(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Command {
+        let mut cmd = Command::new("tool")
+            .version("1.0.0")
+            .about("Sample tool")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Run a thing")
+                    .arg(Arg::new("entry").help("Entry file").required(true))
+                    .arg(
+                        Arg::new("port")
+                            .long("port")
+                            .value_name("PORT")
+                            .default_value("0"),
+                    )
+                    .arg(Arg::new("mode").long("mode").value_parser(["fast", "slow"])),
+            );
+        cmd.build();
+        cmd
+    }
+
+    #[test]
+    fn describes_flags_options_and_positionals() {
+        let schema = command_schema(&sample());
+        assert_eq!(schema["name"], "tool");
+        assert_eq!(schema["version"], "1.0.0");
+
+        // --help / --version are clap's own and left out
+        let args = schema["args"].as_array().unwrap();
+        assert_eq!(args.len(), 1);
+        assert_eq!(args[0]["kind"], "flag");
+        assert_eq!(args[0]["short"], "v");
+        assert!(args[0]["default"].is_null());
+
+        let subs = schema["subcommands"].as_array().unwrap();
+        assert_eq!(subs.len(), 1, "help subcommand is left out");
+        let run = &subs[0];
+        assert_eq!(run["about"], "Run a thing");
+        let run_args = run["args"].as_array().unwrap();
+        assert_eq!(run_args[0]["kind"], "positional");
+        assert_eq!(run_args[0]["index"], 1);
+        assert_eq!(run_args[0]["required"], true);
+        assert_eq!(run_args[1]["kind"], "option");
+        assert_eq!(run_args[1]["valueName"], "PORT");
+        assert_eq!(run_args[1]["default"], "0");
+        assert_eq!(run_args[2]["possibleValues"], json!(["fast", "slow"]));
+    }
+}
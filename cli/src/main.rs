@@ -1,12 +1,21 @@
 mod commands;
 
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{CommandFactory, Parser, Subcommand};
 
 #[derive(Parser)]
-#[command(name = "arcane", version, about = "Arcane 2D game engine CLI")]
+#[command(name = "arcane", version, about = "Arcane 2D game engine CLI", arg_required_else_help = true)]
 struct Cli {
+    /// Print every command, flag and argument as JSON and exit
+    #[arg(long)]
+    schema_json: bool,
+    /// Load the native plugins listed in the project's arcane.toml. They run
+    /// with your permissions, so only pass this for projects you trust
+    #[arg(long, global = true)]
+    allow_native_plugins: bool,
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -29,6 +38,41 @@ enum Commands {
         /// Disable the MCP server
         #[arg(long)]
         no_mcp: bool,
+        /// Point lights shaded per frame; above 8 uses a storage buffer when the GPU supports it
+        #[arg(long, default_value = "8")]
+        max_lights: usize,
+        /// Encode and present frames on the main thread instead of a render thread
+        #[arg(long)]
+        no_render_thread: bool,
+        /// Report the left mouse button as a touch point, for testing touch controls
+        #[arg(long)]
+        emulate_touch: bool,
+        /// Show per-subsystem frame timings in the top-right corner of the window
+        #[arg(long)]
+        stats: bool,
+        /// Capture a frame before and after each hot reload and show a wipe/difference overlay
+        #[arg(long)]
+        reload_diff: bool,
+    },
+    /// Run a game without a window and write each frame as a PNG (for golden images and CI)
+    Render {
+        /// Path to the TypeScript entry file (defaults to src/visual.ts)
+        entry: Option<String>,
+        /// Number of frames to run and capture
+        #[arg(long, default_value_t = 1)]
+        frames: u32,
+        /// Output directory for frame_00000.png, frame_00001.png, ...
+        #[arg(long, default_value = "render")]
+        out: String,
+        /// Fixed time step per frame, in seconds
+        #[arg(long, default_value_t = 1.0 / 60.0)]
+        dt: f64,
+        /// Frame size in pixels, WIDTHxHEIGHT
+        #[arg(long, default_value = "800x600")]
+        size: String,
+        /// Point lights shaded per frame (as for `dev`)
+        #[arg(long, default_value = "8")]
+        max_lights: usize,
     },
     /// Stdio bridge for MCP (JSON-RPC over stdin/stdout)
     Mcp {
@@ -81,17 +125,169 @@ enum Commands {
         /// Output file path (e.g. "screenshot.png")
         output: String,
     },
+    /// Manage project assets
+    Assets {
+        #[command(subcommand)]
+        command: AssetsCommand,
+    },
+    /// Inspect recorded replay files
+    Replay {
+        #[command(subcommand)]
+        command: ReplayCommand,
+    },
+    /// Print a shell completion script (e.g. `arcane completions zsh > _arcane`)
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Subcommand provided by a plugin
+    #[command(external_subcommand)]
+    Plugin(Vec<String>),
+}
+
+#[derive(Subcommand)]
+enum AssetsCommand {
+    /// Check assets/ against asset-manifest.json and find broken asset paths in TS (fails on problems)
+    Verify {
+        /// Project directory (defaults to current directory)
+        path: Option<String>,
+        /// Rewrite the manifest from the current files instead of checking it
+        #[arg(long)]
+        update: bool,
+    },
+    /// List the files under assets/ with their kind, size and dimensions, or sheet frames and tags
+    Inspect {
+        /// Project directory (defaults to current directory)
+        path: Option<String>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Write PNG thumbnails of images and GIF previews of sheet animations to this directory
+        #[arg(long)]
+        thumbnails: Option<String>,
+    },
+    /// List catalog packs, including user sources from arcane.toml
+    List {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Search catalog packs by ID, name, source or tag
+    Search {
+        /// Search terms (all must match)
+        query: String,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download a catalog pack into the shared cache and print its directory
+    Download {
+        /// Pack ID (e.g. "tiny-dungeon" or "studio:heroes")
+        pack_id: String,
+    },
+    /// Write a bitmap font manifest for an image of equally sized glyph cells
+    FontFromGrid {
+        /// Font image (PNG)
+        image: String,
+        /// Glyph cell size in pixels, WIDTHxHEIGHT
+        #[arg(long)]
+        cell: String,
+        /// Characters in the cells, left to right, top to bottom (default: printable ASCII from space)
+        #[arg(long)]
+        charset: Option<String>,
+        /// Extra pixels between glyphs
+        #[arg(long, default_value_t = 0.0)]
+        spacing: f32,
+        /// Advance each glyph by the width of its pixels instead of the full cell
+        #[arg(long)]
+        proportional: bool,
+        /// Manifest path (default: <image>.font.json next to the image)
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Extract the dominant color palette of an image or a downloaded pack
+    Palette {
+        /// Image (PNG) or downloaded pack ID
+        source: String,
+        /// Number of colors
+        #[arg(long, default_value_t = 8)]
+        colors: usize,
+        /// Palette path; .gpl writes a GIMP palette (default: <image>.palette.json next to the image, <pack>.palette.json for packs)
+        #[arg(long)]
+        out: Option<String>,
+        /// Also write the palette as an N×1 PNG strip, one pixel per color
+        #[arg(long)]
+        lut: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReplayCommand {
+    /// Print a replay's engine version, content hash, length and thumbnail, and whether it plays back here
+    Info {
+        /// Replay file (.arcreplay)
+        file: String,
+        /// Entry file of the game to check the replay against (defaults to src/visual.ts)
+        #[arg(long)]
+        entry: Option<String>,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Write the thumbnail PNG to this path
+        #[arg(long)]
+        thumbnail: Option<String>,
+    },
+}
+
+/// Where to look for the `arcane.toml` that lists native plugins: the
+/// project being run for `dev`, `render` and `test`, else the current
+/// directory.
+fn plugin_project_path(command: &Commands) -> PathBuf {
+    match command {
+        Commands::Dev { entry, .. } | Commands::Render { entry, .. } => {
+            PathBuf::from(entry.as_deref().unwrap_or("src/visual.ts"))
+        }
+        Commands::Test { path: Some(path) } => PathBuf::from(path),
+        _ => PathBuf::from("."),
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    if cli.schema_json {
+        return commands::schema::run(Cli::command());
+    }
+    let Some(command) = cli.command else {
+        Cli::command().print_help()?;
+        return Ok(());
+    };
+    if !matches!(command, Commands::Completions { .. }) {
+        commands::plugins::load(&plugin_project_path(&command), cli.allow_native_plugins);
+    }
 
-    match cli.command {
+    match command {
         Commands::Test { path } => commands::test::run(path),
-        Commands::Dev { entry, inspector, mcp_port, no_mcp } => {
+        Commands::Dev { entry, inspector, mcp_port, no_mcp, max_lights, no_render_thread, emulate_touch, stats, reload_diff } => {
             let entry = entry.unwrap_or_else(|| "src/visual.ts".to_string());
             let mcp = if no_mcp { None } else { Some(mcp_port) };
-            commands::dev::run(entry, inspector, mcp)
+            commands::dev::run(
+                entry,
+                inspector,
+                mcp,
+                commands::dev::DevOptions {
+                    max_lights,
+                    render_thread: !no_render_thread,
+                    emulate_touch,
+                    stats_overlay: stats,
+                    reload_diff,
+                    headless: None,
+                },
+            )
+        },
+        Commands::Render { entry, frames, out, dt, size, max_lights } => {
+            let entry = entry.unwrap_or_else(|| "src/visual.ts".to_string());
+            commands::render::run(entry, frames, out, dt, size, max_lights)
         },
         Commands::Mcp { entry, port } => {
             let entry = entry.unwrap_or_else(|| "src/visual.ts".to_string());
@@ -104,5 +300,26 @@ fn main() -> anyhow::Result<()> {
         Commands::Check { path } => commands::check::run(path),
         Commands::Catalog { pack_id, sounds, browser } => commands::catalog::run(pack_id, sounds, browser),
         Commands::Screenshot { output } => commands::screenshot::run(output),
+        Commands::Assets { command } => match command {
+            AssetsCommand::Verify { path, update } => commands::assets::verify(path, update),
+            AssetsCommand::Inspect { path, json, thumbnails } => commands::assets::inspect(path, json, thumbnails),
+            AssetsCommand::List { json } => commands::catalog::list(None, json),
+            AssetsCommand::Search { query, json } => commands::catalog::list(Some(query), json),
+            AssetsCommand::Download { pack_id } => commands::catalog::download(pack_id),
+            AssetsCommand::FontFromGrid { image, cell, charset, spacing, proportional, out } => {
+                commands::assets::font_from_grid(image, cell, charset, spacing, proportional, out)
+            }
+            AssetsCommand::Palette { source, colors, out, lut } => {
+                commands::assets::palette(source, colors, out, lut)
+            }
+        },
+        Commands::Replay { command } => match command {
+            ReplayCommand::Info { file, entry, json, thumbnail } => {
+                let entry = entry.unwrap_or_else(|| "src/visual.ts".to_string());
+                commands::replay::info(file, entry, json, thumbnail)
+            }
+        },
+        Commands::Completions { shell } => commands::completions::run(shell, Cli::command()),
+        Commands::Plugin(args) => commands::plugins::run_external(args),
     }
 }
@@ -106,7 +106,22 @@ fn parse_route(method: &str, url: &str, body: &str) -> Option<InspectorRequest>
         ("GET", "/actions") => Some(InspectorRequest::ListActions),
         ("GET", "/history") => Some(InspectorRequest::GetHistory),
         ("GET", "/frame_stats") => Some(InspectorRequest::GetFrameStats),
+        ("GET", "/profile") => Some(InspectorRequest::GetProfile),
+        ("GET", "/op_stats") => {
+            let top = url
+                .split('?')
+                .nth(1)
+                .and_then(|qs| qs.split('&').find_map(|p| p.strip_prefix("top=")))
+                .and_then(|n| n.parse().ok());
+            Some(InspectorRequest::GetOpStats { top })
+        }
+        ("POST", "/op_stats") => Some(InspectorRequest::SetOpProfiling {
+            // Empty body starts profiling
+            enabled: extract_json_string(body, "enabled").is_none_or(|v| v != "false"),
+        }),
+        ("GET", "/gpu_errors") => Some(InspectorRequest::GetGpuErrors),
         ("GET", "/capture") => Some(InspectorRequest::CaptureFrame),
+        ("GET", "/quicksaves") => Some(InspectorRequest::ListQuickSaves),
         ("POST", "/action") => {
             // Parse action name and payload from JSON body
             // Simple JSON parsing: {"name": "...", "payload": ...}
@@ -124,6 +139,12 @@ fn parse_route(method: &str, url: &str, body: &str) -> Option<InspectorRequest>
                 action: body.to_string(),
             })
         }
+        ("POST", "/quicksave") => Some(InspectorRequest::QuickSave {
+            slot: parse_slot_body(body),
+        }),
+        ("POST", "/quickload") => Some(InspectorRequest::QuickLoad {
+            slot: parse_slot_body(body),
+        }),
         _ => None,
     }
 }
@@ -141,6 +162,10 @@ fn parse_rewind_body(body: &str) -> u32 {
         .unwrap_or(1)
 }
 
+fn parse_slot_body(body: &str) -> Option<u32> {
+    extract_json_string(body, "slot").and_then(|s| s.parse().ok())
+}
+
 /// Extract a string value for a given key from simple JSON.
 fn extract_json_string(json: &str, key: &str) -> Option<String> {
     let pattern = format!("\"{}\"", key);
@@ -263,6 +288,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_route_gpu_errors() {
+        assert!(matches!(
+            parse_route("GET", "/gpu_errors", ""),
+            Some(InspectorRequest::GetGpuErrors)
+        ));
+    }
+
+    #[test]
+    fn parse_route_profile() {
+        assert!(matches!(
+            parse_route("GET", "/profile", ""),
+            Some(InspectorRequest::GetProfile)
+        ));
+    }
+
     #[test]
     fn parse_route_unknown_returns_none() {
         assert!(parse_route("GET", "/unknown", "").is_none());
@@ -283,4 +324,40 @@ mod tests {
         assert_eq!(parse_rewind_body(r#"{"steps": "3"}"#), 3);
         assert_eq!(parse_rewind_body("{}"), 1); // default
     }
+
+    #[test]
+    fn parse_route_quicksave_slots() {
+        assert!(matches!(
+            parse_route("GET", "/quicksaves", "").unwrap(),
+            InspectorRequest::ListQuickSaves
+        ));
+        assert!(matches!(
+            parse_route("POST", "/quicksave", r#"{"slot": 3}"#).unwrap(),
+            InspectorRequest::QuickSave { slot: Some(3) }
+        ));
+        assert!(matches!(
+            parse_route("POST", "/quickload", "").unwrap(),
+            InspectorRequest::QuickLoad { slot: None }
+        ));
+    }
+
+    #[test]
+    fn parse_route_op_stats() {
+        assert!(matches!(
+            parse_route("GET", "/op_stats?top=5", "").unwrap(),
+            InspectorRequest::GetOpStats { top: Some(5) }
+        ));
+        assert!(matches!(
+            parse_route("GET", "/op_stats", "").unwrap(),
+            InspectorRequest::GetOpStats { top: None }
+        ));
+        assert!(matches!(
+            parse_route("POST", "/op_stats", r#"{"enabled": false}"#).unwrap(),
+            InspectorRequest::SetOpProfiling { enabled: false }
+        ));
+        assert!(matches!(
+            parse_route("POST", "/op_stats", "").unwrap(),
+            InspectorRequest::SetOpProfiling { enabled: true }
+        ));
+    }
 }
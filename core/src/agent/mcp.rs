@@ -69,7 +69,27 @@ static MCP_TOOLS: &[McpTool] = &[
     },
     McpTool {
         name: "get_frame_stats",
-        description: "Get frame timing statistics (frame time, draw calls, FPS)",
+        description: "Get frame timing statistics (frame time, draw calls, FPS, sprite batching and instance upload stats, runtime atlas occupancy, textures still streaming in, physics scene query counts)",
+        input_schema: r#"{"type":"object","properties":{}}"#,
+    },
+    McpTool {
+        name: "get_profile",
+        description: "Get where frame time goes per subsystem: script, physics step, render encode and GPU queue submit times in ms, audio commands sent and texture bytes uploaded, for the last frame with averages and peaks over the last 120 frames",
+        input_schema: r#"{"type":"object","properties":{}}"#,
+    },
+    McpTool {
+        name: "get_op_stats",
+        description: "Get calls and time per engine op over the last frame (e.g. op_draw_sprite, op_set_tile), most time first, to find which calls to batch. Start profiling with set_op_profiling first",
+        input_schema: r#"{"type":"object","properties":{"top":{"type":"number","description":"Number of ops to list (default 10)"}}}"#,
+    },
+    McpTool {
+        name: "set_op_profiling",
+        description: "Start or stop the op-call profiler (adds a little overhead to every op while on)",
+        input_schema: r#"{"type":"object","properties":{"enabled":{"type":"boolean","description":"true to start, false to stop"}},"required":["enabled"]}"#,
+    },
+    McpTool {
+        name: "get_gpu_errors",
+        description: "Get recent GPU validation errors (WGSL compile and pipeline errors) with the shader, SDF expression or effect that caused them",
         input_schema: r#"{"type":"object","properties":{}}"#,
     },
     McpTool {
@@ -289,6 +309,14 @@ fn call_tool(name: &str, arguments: &str, request_tx: &RequestSender, reload_fla
             }
         }
         "get_frame_stats" => InspectorRequest::GetFrameStats,
+        "get_profile" => InspectorRequest::GetProfile,
+        "get_op_stats" => InspectorRequest::GetOpStats {
+            top: extract_json_string(arguments, "top").and_then(|n| n.parse().ok()),
+        },
+        "set_op_profiling" => InspectorRequest::SetOpProfiling {
+            enabled: extract_json_string(arguments, "enabled").is_none_or(|v| v != "false"),
+        },
+        "get_gpu_errors" => InspectorRequest::GetGpuErrors,
         "capture_frame" => InspectorRequest::CaptureFrame,
         _ => {
             return ToolResult::Text(json_encode(&format!("Unknown tool: {name}")));
@@ -592,7 +620,7 @@ mod tests {
 
     #[test]
     fn tool_count() {
-        assert_eq!(MCP_TOOLS.len(), 12);
+        assert_eq!(MCP_TOOLS.len(), 16);
     }
 
     #[test]
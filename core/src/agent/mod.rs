@@ -1,5 +1,6 @@
 pub mod inspector;
 pub mod mcp;
+pub mod quicksave;
 
 use std::sync::mpsc;
 
@@ -15,7 +16,22 @@ pub enum InspectorRequest {
     Simulate { action: String },
     GetHistory,
     GetFrameStats,
+    /// Per-subsystem timings of the last frame, with averages and peaks
+    /// over recent frames.
+    GetProfile,
+    /// Calls and time per op over the last frame, most time first (top N, default 10).
+    GetOpStats { top: Option<u32> },
+    /// Start or stop the op-call profiler.
+    SetOpProfiling { enabled: bool },
+    /// Recent GPU validation errors with the op that raised them.
+    GetGpuErrors,
     CaptureFrame,
+    /// List dev-mode quick save slots.
+    ListQuickSaves,
+    /// Quick save into a 1-based slot (None = current slot).
+    QuickSave { slot: Option<u32> },
+    /// Quick load from a 1-based slot (None = current slot).
+    QuickLoad { slot: Option<u32> },
 }
 
 /// Response from the game loop back to the inspector HTTP server.
@@ -133,8 +149,15 @@ mod tests {
             },
             InspectorRequest::GetHistory,
             InspectorRequest::GetFrameStats,
+            InspectorRequest::GetProfile,
+            InspectorRequest::GetOpStats { top: Some(5) },
+            InspectorRequest::SetOpProfiling { enabled: true },
+            InspectorRequest::GetGpuErrors,
             InspectorRequest::CaptureFrame,
+            InspectorRequest::ListQuickSaves,
+            InspectorRequest::QuickSave { slot: None },
+            InspectorRequest::QuickLoad { slot: Some(2) },
         ];
-        assert_eq!(requests.len(), 11);
+        assert_eq!(requests.len(), 18);
     }
 }
@@ -0,0 +1,249 @@
+//! Dev-mode quick save / quick load.
+//!
+//! A quick save captures the TS game state (through the agent protocol) and a
+//! clone of the physics world, so a hard-to-reach situation can be restored
+//! instantly while iterating. Slots live in the dev loop's memory and survive
+//! hot reloads; they are not written to disk.
+//!
+//! Hotkeys: F5 saves to the current slot, F9 loads it, Ctrl+1..9 selects a
+//! slot. On a gamepad, hold Select and press LeftBumper (save), RightBumper
+//! (load) or DPadLeft/DPadRight (previous/next slot).
+
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::physics::world::PhysicsWorld;
+
+/// Number of quick save slots.
+pub const QUICKSAVE_SLOTS: usize = 9;
+
+/// A quick save action requested by a hotkey or the inspector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickSaveCommand {
+    Save,
+    Load,
+    /// Select a slot (0-based).
+    Select(usize),
+    Next,
+    Previous,
+}
+
+/// One captured game state.
+#[derive(Clone)]
+pub struct QuickSave {
+    /// JSON from `__arcaneAgent.captureSnapshot()`, if an agent is registered.
+    pub state_json: Option<String>,
+    /// The physics world at save time, if one exists.
+    pub physics: Option<PhysicsWorld>,
+    /// Save time in milliseconds since the Unix epoch.
+    pub saved_at_ms: u64,
+}
+
+impl QuickSave {
+    pub fn new(state_json: Option<String>, physics: Option<PhysicsWorld>) -> Self {
+        let saved_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            state_json,
+            physics,
+            saved_at_ms,
+        }
+    }
+}
+
+/// Fixed set of quick save slots plus the currently selected slot.
+pub struct QuickSaveSlots {
+    slots: Vec<Option<QuickSave>>,
+    current: usize,
+}
+
+impl Default for QuickSaveSlots {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; QUICKSAVE_SLOTS],
+            current: 0,
+        }
+    }
+}
+
+impl QuickSaveSlots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The selected slot (0-based).
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Select a slot. Out-of-range slots are ignored.
+    pub fn select(&mut self, slot: usize) {
+        if slot < self.slots.len() {
+            self.current = slot;
+        }
+    }
+
+    /// Apply a Select/Next/Previous command. Save and Load are no-ops here.
+    pub fn apply_selection(&mut self, command: QuickSaveCommand) {
+        let n = self.slots.len();
+        match command {
+            QuickSaveCommand::Select(slot) => self.select(slot),
+            QuickSaveCommand::Next => self.current = (self.current + 1) % n,
+            QuickSaveCommand::Previous => self.current = (self.current + n - 1) % n,
+            QuickSaveCommand::Save | QuickSaveCommand::Load => {}
+        }
+    }
+
+    /// Store a save in `slot` (or the current slot). Returns the slot used.
+    pub fn store(&mut self, slot: Option<usize>, save: QuickSave) -> Option<usize> {
+        let slot = slot.unwrap_or(self.current);
+        *self.slots.get_mut(slot)? = Some(save);
+        Some(slot)
+    }
+
+    /// The save in `slot` (or the current slot), if any.
+    pub fn get(&self, slot: Option<usize>) -> Option<&QuickSave> {
+        self.slots.get(slot.unwrap_or(self.current))?.as_ref()
+    }
+
+    /// JSON summary for the inspector. Slot numbers are 1-based, matching Ctrl+1..9:
+    /// `{"current":1,"slots":[{"slot":1,"savedAt":1700000000000,"hasState":true,"bodies":12},null,...]}`
+    pub fn summary_json(&self) -> String {
+        let slots: Vec<String> = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(i, s)| match s {
+                Some(save) => format!(
+                    "{{\"slot\":{},\"savedAt\":{},\"hasState\":{},\"bodies\":{}}}",
+                    i + 1,
+                    save.saved_at_ms,
+                    save.state_json.is_some(),
+                    save.physics.as_ref().map_or(0, |w| w.body_count()),
+                ),
+                None => "null".to_string(),
+            })
+            .collect();
+        format!(
+            "{{\"current\":{},\"slots\":[{}]}}",
+            self.current + 1,
+            slots.join(",")
+        )
+    }
+}
+
+/// Map this frame's keyboard and gamepad input to a quick save command.
+/// Key names follow the TS input API ("F5", "Control", "1"); gamepad buttons
+/// use `GamepadButton::as_str()` names.
+pub fn hotkey_command(
+    keys_down: &HashSet<String>,
+    keys_pressed: &HashSet<String>,
+    pad_down: &HashSet<String>,
+    pad_pressed: &HashSet<String>,
+) -> Option<QuickSaveCommand> {
+    if keys_pressed.contains("F5") {
+        return Some(QuickSaveCommand::Save);
+    }
+    if keys_pressed.contains("F9") {
+        return Some(QuickSaveCommand::Load);
+    }
+    if keys_down.contains("Control") {
+        for slot in 0..QUICKSAVE_SLOTS {
+            if keys_pressed.contains(&(slot + 1).to_string()) {
+                return Some(QuickSaveCommand::Select(slot));
+            }
+        }
+    }
+    if pad_down.contains("Select") {
+        let pressed = |b: &str| pad_pressed.contains(b);
+        if pressed("LeftBumper") {
+            return Some(QuickSaveCommand::Save);
+        }
+        if pressed("RightBumper") {
+            return Some(QuickSaveCommand::Load);
+        }
+        if pressed("DPadLeft") {
+            return Some(QuickSaveCommand::Previous);
+        }
+        if pressed("DPadRight") {
+            return Some(QuickSaveCommand::Next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::types::{BodyType, Material, Shape};
+
+    fn set(items: &[&str]) -> HashSet<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn hotkeys_map_keyboard_and_gamepad() {
+        let none = HashSet::new();
+        assert_eq!(
+            hotkey_command(&none, &set(&["F5"]), &none, &none),
+            Some(QuickSaveCommand::Save)
+        );
+        assert_eq!(
+            hotkey_command(&none, &set(&["F9"]), &none, &none),
+            Some(QuickSaveCommand::Load)
+        );
+        assert_eq!(
+            hotkey_command(&set(&["Control"]), &set(&["3"]), &none, &none),
+            Some(QuickSaveCommand::Select(2))
+        );
+        // Digits without Control belong to the game
+        assert_eq!(hotkey_command(&none, &set(&["3"]), &none, &none), None);
+        assert_eq!(
+            hotkey_command(&none, &none, &set(&["Select"]), &set(&["RightBumper"])),
+            Some(QuickSaveCommand::Load)
+        );
+        assert_eq!(
+            hotkey_command(&none, &none, &none, &set(&["LeftBumper"])),
+            None
+        );
+    }
+
+    #[test]
+    fn slots_store_in_current_slot_and_wrap_selection() {
+        let mut slots = QuickSaveSlots::new();
+        slots.apply_selection(QuickSaveCommand::Previous);
+        assert_eq!(slots.current(), QUICKSAVE_SLOTS - 1);
+        slots.apply_selection(QuickSaveCommand::Next);
+        assert_eq!(slots.current(), 0);
+
+        let mut world = PhysicsWorld::new(0.0, 100.0);
+        world.add_body(
+            BodyType::Dynamic,
+            Shape::Circle { radius: 1.0 },
+            0.0,
+            0.0,
+            1.0,
+            Material::default(),
+            0xFFFF,
+            0xFFFF,
+        );
+        slots.select(4);
+        let used = slots.store(None, QuickSave::new(Some("{\"hp\":3}".into()), Some(world)));
+        assert_eq!(used, Some(4));
+        assert_eq!(
+            slots.store(Some(QUICKSAVE_SLOTS), QuickSave::new(None, None)),
+            None
+        );
+
+        let save = slots.get(None).unwrap();
+        assert_eq!(save.state_json.as_deref(), Some("{\"hp\":3}"));
+        assert!(slots.get(Some(0)).is_none());
+
+        let json = slots.summary_json();
+        assert!(json.starts_with("{\"current\":5,\"slots\":[null,"));
+        assert!(json.contains("\"slot\":5,"));
+        assert!(json.contains("\"hasState\":true,\"bodies\":1}"));
+    }
+}
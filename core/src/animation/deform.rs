@@ -0,0 +1,356 @@
+//! Deformable sprite grids: an N×M vertex lattice laid over a sprite whose
+//! per-vertex offsets drive squash & stretch, jelly wobble and cloth-like flags.
+//!
+//! Offsets can be written directly (packed x/y pairs) or simulated: every free
+//! vertex is pulled back to its rest position (`stiffness`), towards the mean of
+//! its neighbours (`coupling`, which propagates waves across the grid), and by a
+//! constant `force` (gravity/wind). Pinned vertices never move.
+
+/// Maximum cells per axis. Keeps vertex indices within u16 for the mesh.
+pub const MAX_CELLS: usize = 128;
+
+/// Fixed simulation step; larger frame deltas are split into substeps.
+const SUBSTEP: f32 = 1.0 / 120.0;
+
+/// Longest frame simulated in one `step`. A hitch (breakpoint, window drag)
+/// longer than this is simulated as this long rather than in substeps too
+/// coarse to stay stable.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// Spring parameters for the grid simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpringConfig {
+    /// Pull towards the rest position (1/s²). 0 lets the grid hang freely.
+    pub stiffness: f32,
+    /// Pull towards neighbouring offsets (1/s²).
+    pub coupling: f32,
+    /// Velocity damping (1/s).
+    pub damping: f32,
+}
+
+impl Default for SpringConfig {
+    fn default() -> Self {
+        Self { stiffness: 120.0, coupling: 60.0, damping: 6.0 }
+    }
+}
+
+/// A deformable grid of `(cols + 1) × (rows + 1)` vertices over a `width × height` quad.
+/// Vertices are stored row-major from the top-left corner.
+#[derive(Debug, Clone)]
+pub struct DeformGrid {
+    pub cols: usize,
+    pub rows: usize,
+    pub width: f32,
+    pub height: f32,
+    /// Per-vertex displacement from the rest position.
+    pub offsets: Vec<[f32; 2]>,
+    pub velocities: Vec<[f32; 2]>,
+    pub pinned: Vec<bool>,
+    pub springs: SpringConfig,
+    /// Constant acceleration applied to free vertices (e.g. gravity, wind).
+    pub force: [f32; 2],
+    /// Texture region (uv_x, uv_y, uv_w, uv_h) mapped across the grid.
+    pub uv: [f32; 4],
+}
+
+impl DeformGrid {
+    /// Create a grid with `cols × rows` cells (clamped to 1..=MAX_CELLS).
+    pub fn new(cols: usize, rows: usize, width: f32, height: f32) -> Self {
+        let cols = cols.clamp(1, MAX_CELLS);
+        let rows = rows.clamp(1, MAX_CELLS);
+        let count = (cols + 1) * (rows + 1);
+        Self {
+            cols,
+            rows,
+            width,
+            height,
+            offsets: vec![[0.0; 2]; count],
+            velocities: vec![[0.0; 2]; count],
+            pinned: vec![false; count],
+            springs: SpringConfig::default(),
+            force: [0.0; 2],
+            uv: [0.0, 0.0, 1.0, 1.0],
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Vertex index for a grid coordinate, or None if out of range.
+    pub fn index(&self, col: usize, row: usize) -> Option<usize> {
+        (col <= self.cols && row <= self.rows).then_some(row * (self.cols + 1) + col)
+    }
+
+    /// Undeformed local position of a vertex (top-left origin).
+    pub fn rest_position(&self, i: usize) -> [f32; 2] {
+        let col = i % (self.cols + 1);
+        let row = i / (self.cols + 1);
+        [
+            col as f32 / self.cols as f32 * self.width,
+            row as f32 / self.rows as f32 * self.height,
+        ]
+    }
+
+    /// Deformed local position of a vertex.
+    pub fn position(&self, i: usize) -> [f32; 2] {
+        let rest = self.rest_position(i);
+        [rest[0] + self.offsets[i][0], rest[1] + self.offsets[i][1]]
+    }
+
+    /// Overwrite offsets from packed (dx, dy) pairs. Extra values are ignored;
+    /// missing vertices keep their current offsets.
+    pub fn set_offsets(&mut self, packed: &[f32]) {
+        for (offset, pair) in self.offsets.iter_mut().zip(packed.chunks_exact(2)) {
+            *offset = [pair[0], pair[1]];
+        }
+    }
+
+    /// Clear all offsets and velocities.
+    pub fn reset(&mut self) {
+        self.offsets.fill([0.0; 2]);
+        self.velocities.fill([0.0; 2]);
+    }
+
+    pub fn set_pinned(&mut self, col: usize, row: usize, pinned: bool) {
+        if let Some(i) = self.index(col, row) {
+            self.pinned[i] = pinned;
+            if pinned {
+                self.velocities[i] = [0.0; 2];
+            }
+        }
+    }
+
+    /// Add velocity to free vertices within `radius` of a local point, with
+    /// linear falloff towards the edge.
+    pub fn apply_impulse(&mut self, x: f32, y: f32, radius: f32, vx: f32, vy: f32) {
+        if radius <= 0.0 {
+            return;
+        }
+        for i in 0..self.vertex_count() {
+            if self.pinned[i] {
+                continue;
+            }
+            let p = self.position(i);
+            let dist = ((p[0] - x).powi(2) + (p[1] - y).powi(2)).sqrt();
+            if dist < radius {
+                let falloff = 1.0 - dist / radius;
+                self.velocities[i][0] += vx * falloff;
+                self.velocities[i][1] += vy * falloff;
+            }
+        }
+    }
+
+    /// Set offsets so the grid appears scaled by (sx, sy) around an anchor given
+    /// in normalized grid space (0.5, 1.0 = bottom centre). The springs then pull
+    /// it back, producing the classic squash & stretch rebound.
+    pub fn squash(&mut self, sx: f32, sy: f32, anchor_x: f32, anchor_y: f32) {
+        let ax = anchor_x * self.width;
+        let ay = anchor_y * self.height;
+        for i in 0..self.vertex_count() {
+            if self.pinned[i] {
+                continue;
+            }
+            let rest = self.rest_position(i);
+            self.offsets[i] = [(rest[0] - ax) * (sx - 1.0), (rest[1] - ay) * (sy - 1.0)];
+        }
+    }
+
+    /// Advance the spring simulation by `dt` seconds, at most
+    /// [`MAX_FRAME_TIME`]. Non-finite or non-positive `dt` does nothing.
+    pub fn step(&mut self, dt: f32) {
+        if !dt.is_finite() || dt <= 0.0 {
+            return;
+        }
+        let dt = dt.min(MAX_FRAME_TIME);
+        let steps = (dt / SUBSTEP).ceil() as usize;
+        let h = dt / steps as f32;
+        for _ in 0..steps {
+            self.substep(h);
+        }
+    }
+
+    fn substep(&mut self, h: f32) {
+        let SpringConfig { stiffness, coupling, damping } = self.springs;
+        let stride = self.cols + 1;
+        for i in 0..self.vertex_count() {
+            if self.pinned[i] {
+                continue;
+            }
+            let col = i % stride;
+            let row = i / stride;
+            let o = self.offsets[i];
+
+            // Mean neighbour offset (4-connected)
+            let mut sum = [0.0f32; 2];
+            let mut n = 0.0f32;
+            let mut add = |j: usize| {
+                sum[0] += self.offsets[j][0];
+                sum[1] += self.offsets[j][1];
+                n += 1.0;
+            };
+            if col > 0 {
+                add(i - 1);
+            }
+            if col < self.cols {
+                add(i + 1);
+            }
+            if row > 0 {
+                add(i - stride);
+            }
+            if row < self.rows {
+                add(i + stride);
+            }
+
+            let v = &mut self.velocities[i];
+            for axis in 0..2 {
+                let neighbour = if n > 0.0 { sum[axis] / n - o[axis] } else { 0.0 };
+                let accel = -stiffness * o[axis] + coupling * neighbour + self.force[axis]
+                    - damping * v[axis];
+                v[axis] += accel * h;
+            }
+        }
+        for i in 0..self.vertex_count() {
+            if !self.pinned[i] {
+                self.offsets[i][0] += self.velocities[i][0] * h;
+                self.offsets[i][1] += self.velocities[i][1] * h;
+            }
+        }
+    }
+
+    /// True when every offset and velocity is below `epsilon`.
+    pub fn is_settled(&self, epsilon: f32) -> bool {
+        self.offsets
+            .iter()
+            .chain(self.velocities.iter())
+            .all(|v| v[0].abs() < epsilon && v[1].abs() < epsilon)
+    }
+
+    /// Build the textured mesh at world position (x, y) (top-left corner).
+    /// Returns packed (x, y, u, v) vertices and triangle-list indices.
+    pub fn mesh(&self, x: f32, y: f32) -> (Vec<f32>, Vec<u16>) {
+        let [uv_x, uv_y, uv_w, uv_h] = self.uv;
+        let mut vertices = Vec::with_capacity(self.vertex_count() * 4);
+        for i in 0..self.vertex_count() {
+            let p = self.position(i);
+            let col = i % (self.cols + 1);
+            let row = i / (self.cols + 1);
+            vertices.push(x + p[0]);
+            vertices.push(y + p[1]);
+            vertices.push(uv_x + col as f32 / self.cols as f32 * uv_w);
+            vertices.push(uv_y + row as f32 / self.rows as f32 * uv_h);
+        }
+
+        let stride = (self.cols + 1) as u16;
+        let mut indices = Vec::with_capacity(self.cols * self.rows * 6);
+        for row in 0..self.rows as u16 {
+            for col in 0..self.cols as u16 {
+                let tl = row * stride + col;
+                let tr = tl + 1;
+                let bl = tl + stride;
+                let br = bl + 1;
+                indices.extend_from_slice(&[tl, tr, br, tl, br, bl]);
+            }
+        }
+        (vertices, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_dimensions_and_clamping() {
+        let g = DeformGrid::new(4, 2, 40.0, 20.0);
+        assert_eq!(g.vertex_count(), 15);
+        assert_eq!(g.index(4, 2), Some(14));
+        assert_eq!(g.index(5, 0), None);
+        assert_eq!(g.rest_position(14), [40.0, 20.0]);
+
+        let g = DeformGrid::new(0, 1000, 1.0, 1.0);
+        assert_eq!((g.cols, g.rows), (1, MAX_CELLS));
+    }
+
+    #[test]
+    fn test_mesh_layout() {
+        let mut g = DeformGrid::new(2, 1, 20.0, 10.0);
+        g.uv = [0.5, 0.0, 0.5, 1.0];
+        g.set_offsets(&[0.0, 0.0, 0.0, -3.0]);
+        let (verts, idx) = g.mesh(100.0, 50.0);
+        assert_eq!(verts.len(), 6 * 4);
+        assert_eq!(idx.len(), 2 * 6);
+        // Vertex 1: top middle, lifted by the offset
+        assert_eq!(&verts[4..8], &[110.0, 47.0, 0.75, 0.0]);
+        // Vertex 5: bottom right
+        assert_eq!(&verts[20..24], &[120.0, 60.0, 1.0, 1.0]);
+        assert!(idx.iter().all(|&i| (i as usize) < 6));
+    }
+
+    #[test]
+    fn test_springs_return_to_rest() {
+        let mut g = DeformGrid::new(3, 3, 30.0, 30.0);
+        g.apply_impulse(15.0, 15.0, 20.0, 0.0, 200.0);
+        assert!(!g.is_settled(0.01));
+        g.step(1.0 / 60.0);
+        assert!(g.offsets[g.index(1, 1).unwrap()][1] > 0.0);
+        for _ in 0..600 {
+            g.step(1.0 / 60.0);
+        }
+        assert!(g.is_settled(0.01));
+    }
+
+    #[test]
+    fn test_long_and_invalid_frames_stay_stable() {
+        let mut g = DeformGrid::new(3, 3, 30.0, 30.0);
+        g.springs = SpringConfig { stiffness: 2000.0, coupling: 2000.0, damping: 6.0 };
+        g.apply_impulse(15.0, 15.0, 20.0, 0.0, 200.0);
+        let before = g.offsets.clone();
+        g.step(f32::NAN);
+        g.step(f32::INFINITY);
+        assert_eq!(g.offsets, before);
+        for _ in 0..20 {
+            g.step(5.0);
+        }
+        assert!(g.offsets.iter().flatten().all(|o| o.is_finite() && o.abs() < 100.0));
+    }
+
+    #[test]
+    fn test_impulse_falloff_and_pins() {
+        let mut g = DeformGrid::new(4, 1, 40.0, 10.0);
+        g.set_pinned(0, 0, true);
+        g.apply_impulse(0.0, 0.0, 15.0, 10.0, 0.0);
+        assert_eq!(g.velocities[0], [0.0, 0.0]);
+        let near = g.velocities[g.index(1, 0).unwrap()][0];
+        assert!(near > 0.0 && near < 10.0);
+        assert_eq!(g.velocities[g.index(4, 0).unwrap()], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_squash_keeps_anchor_fixed() {
+        let mut g = DeformGrid::new(2, 2, 20.0, 20.0);
+        g.squash(1.5, 0.5, 0.5, 1.0);
+        // Bottom centre is the anchor
+        assert_eq!(g.offsets[g.index(1, 2).unwrap()], [0.0, 0.0]);
+        // Top-left moves outwards and down
+        assert_eq!(g.offsets[0], [-5.0, 10.0]);
+    }
+
+    #[test]
+    fn test_pinned_flag_droops_under_force() {
+        let mut g = DeformGrid::new(4, 2, 40.0, 20.0);
+        g.springs = SpringConfig { stiffness: 0.0, coupling: 200.0, damping: 4.0 };
+        g.force = [0.0, 50.0];
+        for row in 0..=2 {
+            g.set_pinned(0, row, true);
+        }
+        for _ in 0..120 {
+            g.step(1.0 / 60.0);
+        }
+        let pole = g.offsets[g.index(0, 1).unwrap()][1];
+        let near = g.offsets[g.index(1, 1).unwrap()][1];
+        let tip = g.offsets[g.index(4, 1).unwrap()][1];
+        assert_eq!(pole, 0.0);
+        assert!(tip > near && near > 0.0, "tip {} near {}", tip, near);
+    }
+}
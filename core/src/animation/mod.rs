@@ -0,0 +1,3 @@
+pub mod deform;
+pub mod skeleton;
+pub mod spine;
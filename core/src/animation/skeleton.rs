@@ -0,0 +1,998 @@
+//! Skeletal animation runtime: bone hierarchy, slots, region/mesh attachments,
+//! keyframed timelines and multi-track crossfading.
+//!
+//! Data (`SkeletonData`) is immutable and shared between instances. Each
+//! `Skeleton` instance owns its pose and an `AnimationState` with tracks.
+//! Coordinates follow Spine conventions (y-up, degrees); `Skeleton::y_down`
+//! flips the root so output matches Arcane's screen space.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Setup-pose data for one bone.
+#[derive(Debug, Clone)]
+pub struct BoneData {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub length: f32,
+    pub x: f32,
+    pub y: f32,
+    /// Degrees.
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub shear_x: f32,
+    pub shear_y: f32,
+}
+
+/// Setup-pose data for one slot (a draw-order entry bound to a bone).
+#[derive(Debug, Clone)]
+pub struct SlotData {
+    pub name: String,
+    pub bone: usize,
+    pub attachment: Option<String>,
+    pub color: [f32; 4],
+}
+
+/// Mesh vertex positions, either bone-local or weighted across several bones.
+#[derive(Debug, Clone)]
+pub enum MeshVertices {
+    /// Flat [x0, y0, x1, y1, ...] in the slot bone's space.
+    Unweighted(Vec<f32>),
+    /// Per vertex: list of (bone index, x, y, weight).
+    Weighted(Vec<Vec<(usize, f32, f32, f32)>>),
+}
+
+impl MeshVertices {
+    pub fn vertex_count(&self) -> usize {
+        match self {
+            MeshVertices::Unweighted(v) => v.len() / 2,
+            MeshVertices::Weighted(v) => v.len(),
+        }
+    }
+
+    /// Length of a deform offset array for this mesh (2 per bone influence).
+    pub fn deform_len(&self) -> usize {
+        match self {
+            MeshVertices::Unweighted(v) => v.len(),
+            MeshVertices::Weighted(v) => v.iter().map(|w| w.len() * 2).sum(),
+        }
+    }
+}
+
+/// A drawable attached to a slot.
+#[derive(Debug, Clone)]
+pub enum Attachment {
+    /// A textured quad positioned relative to the slot's bone.
+    Region {
+        path: String,
+        x: f32,
+        y: f32,
+        rotation: f32,
+        scale_x: f32,
+        scale_y: f32,
+        width: f32,
+        height: f32,
+        color: [f32; 4],
+    },
+    /// A deformable textured triangle mesh.
+    Mesh {
+        path: String,
+        /// Flat [u0, v0, u1, v1, ...], 0-1 within the region.
+        uvs: Vec<f32>,
+        triangles: Vec<u16>,
+        vertices: MeshVertices,
+        color: [f32; 4],
+    },
+}
+
+impl Attachment {
+    pub fn path(&self) -> &str {
+        match self {
+            Attachment::Region { path, .. } | Attachment::Mesh { path, .. } => path,
+        }
+    }
+}
+
+/// A named set of attachments keyed by (slot index, attachment name).
+#[derive(Debug, Clone, Default)]
+pub struct Skin {
+    pub name: String,
+    pub attachments: HashMap<(usize, String), Attachment>,
+}
+
+/// Interpolation from one keyframe to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear,
+    Stepped,
+    /// Cubic bezier control points (cx1, cy1, cx2, cy2) in normalized time/value.
+    Bezier(f32, f32, f32, f32),
+}
+
+impl Curve {
+    /// Map linear progress `t` (0-1) through the curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        match *self {
+            Curve::Linear => t,
+            Curve::Stepped => 0.0,
+            Curve::Bezier(cx1, cy1, cx2, cy2) => {
+                // Solve x(s) = t by bisection, then evaluate y(s)
+                let bez = |p1: f32, p2: f32, s: f32| {
+                    let inv = 1.0 - s;
+                    3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+                };
+                let (mut lo, mut hi) = (0.0f32, 1.0f32);
+                for _ in 0..20 {
+                    let mid = (lo + hi) * 0.5;
+                    if bez(cx1, cx2, mid) < t {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                bez(cy1, cy2, (lo + hi) * 0.5)
+            }
+        }
+    }
+}
+
+/// A keyframe with a value and the curve used to reach the next key.
+#[derive(Debug, Clone)]
+pub struct Key<T> {
+    pub time: f32,
+    pub value: T,
+    pub curve: Curve,
+}
+
+/// Find the segment containing `time`: returns (key index, progress 0-1 to next key).
+/// Before the first key returns (0, 0); after the last returns (last, 0).
+fn find_segment<T>(keys: &[Key<T>], time: f32) -> Option<(usize, f32)> {
+    if keys.is_empty() {
+        return None;
+    }
+    if time <= keys[0].time {
+        return Some((0, 0.0));
+    }
+    let last = keys.len() - 1;
+    if time >= keys[last].time {
+        return Some((last, 0.0));
+    }
+    // Keys are sorted; binary search for the last key <= time
+    let idx = keys.partition_point(|k| k.time <= time) - 1;
+    let span = keys[idx + 1].time - keys[idx].time;
+    let t = if span > 0.0 { (time - keys[idx].time) / span } else { 0.0 };
+    Some((idx, keys[idx].curve.apply(t)))
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Interpolate between two angles in degrees along the shortest arc.
+fn lerp_angle(a: f32, b: f32, t: f32) -> f32 {
+    let mut d = (b - a) % 360.0;
+    if d > 180.0 {
+        d -= 360.0;
+    } else if d < -180.0 {
+        d += 360.0;
+    }
+    a + d * t
+}
+
+fn sample_angle(keys: &[Key<f32>], time: f32) -> Option<f32> {
+    let (i, t) = find_segment(keys, time)?;
+    Some(match keys.get(i + 1) {
+        Some(next) => lerp_angle(keys[i].value, next.value, t),
+        None => keys[i].value,
+    })
+}
+
+fn sample_vec2(keys: &[Key<(f32, f32)>], time: f32) -> Option<(f32, f32)> {
+    let (i, t) = find_segment(keys, time)?;
+    Some(match keys.get(i + 1) {
+        Some(next) => (
+            lerp(keys[i].value.0, next.value.0, t),
+            lerp(keys[i].value.1, next.value.1, t),
+        ),
+        None => keys[i].value,
+    })
+}
+
+fn sample_color(keys: &[Key<[f32; 4]>], time: f32) -> Option<[f32; 4]> {
+    let (i, t) = find_segment(keys, time)?;
+    Some(match keys.get(i + 1) {
+        Some(next) => {
+            let (a, b) = (keys[i].value, next.value);
+            [lerp(a[0], b[0], t), lerp(a[1], b[1], t), lerp(a[2], b[2], t), lerp(a[3], b[3], t)]
+        }
+        None => keys[i].value,
+    })
+}
+
+/// Keyed bone channels. Values are relative to the setup pose
+/// (rotation/translation add, scale multiplies).
+#[derive(Debug, Clone, Default)]
+pub struct BoneTimeline {
+    pub bone: usize,
+    pub rotate: Vec<Key<f32>>,
+    pub translate: Vec<Key<(f32, f32)>>,
+    pub scale: Vec<Key<(f32, f32)>>,
+    pub shear: Vec<Key<(f32, f32)>>,
+}
+
+/// Keyed slot channels: attachment swaps (stepped) and color.
+#[derive(Debug, Clone, Default)]
+pub struct SlotTimeline {
+    pub slot: usize,
+    pub attachment: Vec<(f32, Option<String>)>,
+    pub color: Vec<Key<[f32; 4]>>,
+}
+
+/// Keyed mesh vertex offsets for one attachment in one skin.
+#[derive(Debug, Clone)]
+pub struct DeformTimeline {
+    pub slot: usize,
+    pub attachment: String,
+    /// Each key holds a full offset array (`MeshVertices::deform_len()` entries).
+    pub keys: Vec<Key<Vec<f32>>>,
+}
+
+/// A named animation made of bone, slot and deform timelines.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    pub name: String,
+    pub duration: f32,
+    pub bones: Vec<BoneTimeline>,
+    pub slots: Vec<SlotTimeline>,
+    pub deforms: Vec<DeformTimeline>,
+}
+
+/// Immutable skeleton definition shared by all instances.
+#[derive(Debug, Clone, Default)]
+pub struct SkeletonData {
+    pub bones: Vec<BoneData>,
+    pub slots: Vec<SlotData>,
+    pub skins: Vec<Skin>,
+    pub animations: Vec<Animation>,
+}
+
+impl SkeletonData {
+    pub fn find_bone(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|b| b.name == name)
+    }
+
+    pub fn find_slot(&self, name: &str) -> Option<usize> {
+        self.slots.iter().position(|s| s.name == name)
+    }
+
+    pub fn find_skin(&self, name: &str) -> Option<usize> {
+        self.skins.iter().position(|s| s.name == name)
+    }
+
+    pub fn find_animation(&self, name: &str) -> Option<usize> {
+        self.animations.iter().position(|a| a.name == name)
+    }
+}
+
+/// Local pose plus computed world matrix for one bone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BonePose {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub shear_x: f32,
+    pub shear_y: f32,
+    /// World matrix [a b; c d] and translation.
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub world_x: f32,
+    pub world_y: f32,
+}
+
+impl BonePose {
+    fn from_setup(data: &BoneData) -> Self {
+        Self {
+            x: data.x,
+            y: data.y,
+            rotation: data.rotation,
+            scale_x: data.scale_x,
+            scale_y: data.scale_y,
+            shear_x: data.shear_x,
+            shear_y: data.shear_y,
+            ..Default::default()
+        }
+    }
+
+    /// Transform a bone-local point into skeleton world space.
+    pub fn local_to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.b * y + self.world_x, self.c * x + self.d * y + self.world_y)
+    }
+
+    /// World rotation of the bone's x axis, in radians.
+    pub fn world_rotation(&self) -> f32 {
+        self.c.atan2(self.a)
+    }
+
+    pub fn world_scale_x(&self) -> f32 {
+        (self.a * self.a + self.c * self.c).sqrt()
+    }
+
+    pub fn world_scale_y(&self) -> f32 {
+        (self.b * self.b + self.d * self.d).sqrt()
+    }
+}
+
+/// Runtime slot state.
+#[derive(Debug, Clone)]
+pub struct SlotPose {
+    pub attachment: Option<String>,
+    pub color: [f32; 4],
+    /// Active mesh vertex offsets (empty = no deform).
+    pub deform: Vec<f32>,
+}
+
+/// One playing animation on a track, with an optional crossfade from the previous one.
+#[derive(Debug, Clone)]
+pub struct TrackEntry {
+    pub animation: usize,
+    pub time: f32,
+    pub looping: bool,
+    pub speed: f32,
+    /// Entry being faded out, if a crossfade is in progress.
+    pub mixing_from: Option<Box<TrackEntry>>,
+    pub mix_time: f32,
+    pub mix_duration: f32,
+}
+
+impl TrackEntry {
+    fn new(animation: usize, looping: bool) -> Self {
+        Self {
+            animation,
+            time: 0.0,
+            looping,
+            speed: 1.0,
+            mixing_from: None,
+            mix_time: 0.0,
+            mix_duration: 0.0,
+        }
+    }
+
+    fn advance(&mut self, dt: f32) {
+        self.time += dt * self.speed;
+        if let Some(from) = self.mixing_from.as_mut() {
+            from.advance(dt);
+        }
+        self.mix_time += dt;
+        if self.mix_time >= self.mix_duration {
+            self.mixing_from = None;
+        }
+    }
+
+    /// Crossfade weight of this entry (0-1).
+    fn mix_alpha(&self) -> f32 {
+        if self.mixing_from.is_none() || self.mix_duration <= 0.0 {
+            1.0
+        } else {
+            (self.mix_time / self.mix_duration).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// A layer of animation. Higher tracks apply on top of lower ones.
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub entry: Option<TrackEntry>,
+    /// Overall weight of this track (0-1).
+    pub alpha: f32,
+}
+
+/// Tracks per skeleton. Playing on a higher index does nothing.
+pub const MAX_TRACKS: usize = 16;
+
+/// Per-skeleton animation playback: tracks applied in index order.
+#[derive(Debug, Clone, Default)]
+pub struct AnimationState {
+    pub tracks: Vec<Track>,
+}
+
+impl AnimationState {
+    /// The track at `index`, created if needed. None past `MAX_TRACKS`.
+    fn track_mut(&mut self, index: usize) -> Option<&mut Track> {
+        if index >= MAX_TRACKS {
+            return None;
+        }
+        if self.tracks.len() <= index {
+            self.tracks.resize(index + 1, Track { entry: None, alpha: 1.0 });
+        }
+        Some(&mut self.tracks[index])
+    }
+
+    /// Start an animation on a track, crossfading from whatever was playing
+    /// over `mix_duration` seconds (0 = hard cut). Returns false if `track`
+    /// is not below `MAX_TRACKS`.
+    pub fn play(&mut self, track: usize, animation: usize, looping: bool, mix_duration: f32) -> bool {
+        let Some(t) = self.track_mut(track) else {
+            return false;
+        };
+        let mut entry = TrackEntry::new(animation, looping);
+        if mix_duration > 0.0
+            && let Some(mut prev) = t.entry.take()
+        {
+            // Chained crossfades: drop the oldest to keep depth bounded
+            prev.mixing_from = None;
+            entry.mixing_from = Some(Box::new(prev));
+            entry.mix_duration = mix_duration;
+        }
+        t.entry = Some(entry);
+        true
+    }
+
+    pub fn clear_track(&mut self, track: usize) {
+        if let Some(t) = self.tracks.get_mut(track) {
+            t.entry = None;
+        }
+    }
+
+    pub fn set_track_alpha(&mut self, track: usize, alpha: f32) {
+        if let Some(t) = self.track_mut(track) {
+            t.alpha = alpha.clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn set_track_speed(&mut self, track: usize, speed: f32) {
+        if let Some(entry) = self.track_mut(track).and_then(|t| t.entry.as_mut()) {
+            entry.speed = speed;
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for track in &mut self.tracks {
+            if let Some(entry) = track.entry.as_mut() {
+                entry.advance(dt);
+            }
+        }
+    }
+}
+
+/// A posed skeleton instance.
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    pub data: Rc<SkeletonData>,
+    pub bones: Vec<BonePose>,
+    pub slots: Vec<SlotPose>,
+    /// Active skin index (attachments fall back to the "default" skin).
+    pub skin: Option<usize>,
+    pub x: f32,
+    pub y: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    /// Flip the y axis so Spine's y-up data renders upright in y-down screen space.
+    pub y_down: bool,
+    pub state: AnimationState,
+}
+
+impl Skeleton {
+    pub fn new(data: Rc<SkeletonData>) -> Self {
+        let bones = data.bones.iter().map(BonePose::from_setup).collect();
+        let slots = data
+            .slots
+            .iter()
+            .map(|s| SlotPose { attachment: s.attachment.clone(), color: s.color, deform: Vec::new() })
+            .collect();
+        let mut sk = Self {
+            data,
+            bones,
+            slots,
+            skin: None,
+            x: 0.0,
+            y: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            y_down: true,
+            state: AnimationState::default(),
+        };
+        sk.update_world_transform();
+        sk
+    }
+
+    /// Reset bones and slots to the setup pose.
+    pub fn set_to_setup_pose(&mut self) {
+        for (pose, data) in self.bones.iter_mut().zip(&self.data.bones) {
+            *pose = BonePose::from_setup(data);
+        }
+        for (pose, data) in self.slots.iter_mut().zip(&self.data.slots) {
+            pose.attachment = data.attachment.clone();
+            pose.color = data.color;
+            pose.deform.clear();
+        }
+    }
+
+    /// Look up an attachment for a slot in the active skin, then the default skin.
+    pub fn get_attachment(&self, slot: usize, name: &str) -> Option<&Attachment> {
+        let key = (slot, name.to_string());
+        self.skin
+            .and_then(|s| self.data.skins.get(s))
+            .and_then(|skin| skin.attachments.get(&key))
+            .or_else(|| {
+                self.data
+                    .find_skin("default")
+                    .and_then(|s| self.data.skins[s].attachments.get(&key))
+            })
+    }
+
+    /// Advance all tracks by `dt` seconds and re-pose the skeleton.
+    pub fn update(&mut self, dt: f32) {
+        self.state.update(dt);
+        self.apply_animations();
+        self.update_world_transform();
+    }
+
+    /// Pose from the setup pose through every track, blending crossfades.
+    pub fn apply_animations(&mut self) {
+        self.set_to_setup_pose();
+        let data = Rc::clone(&self.data);
+        let tracks = self.state.tracks.clone();
+        for track in &tracks {
+            if let Some(entry) = &track.entry {
+                self.apply_entry(&data, entry, track.alpha);
+            }
+        }
+    }
+
+    fn apply_entry(&mut self, data: &SkeletonData, entry: &TrackEntry, alpha: f32) {
+        let mix = entry.mix_alpha();
+        let anim = data.animations.get(entry.animation);
+        if let Some(from) = &entry.mixing_from {
+            self.apply_entry(data, from, alpha);
+            // Channels only the outgoing animation keys fade back to the setup pose
+            if let (Some(from_anim), Some(to_anim)) = (data.animations.get(from.animation), anim) {
+                self.fade_to_setup(from_anim, to_anim, alpha * mix);
+            }
+        }
+        if let Some(anim) = anim {
+            let time = if entry.looping && anim.duration > 0.0 {
+                entry.time.rem_euclid(anim.duration)
+            } else {
+                entry.time.min(anim.duration)
+            };
+            self.apply_animation(anim, time, alpha * mix);
+        }
+    }
+
+    /// Blend bone/slot channels keyed by `from` but not by `to` toward the setup pose.
+    fn fade_to_setup(&mut self, from: &Animation, to: &Animation, weight: f32) {
+        for tl in &from.bones {
+            let other = to.bones.iter().find(|t| t.bone == tl.bone);
+            let (Some(pose), Some(setup)) = (self.bones.get_mut(tl.bone), self.data.bones.get(tl.bone)) else {
+                continue;
+            };
+            if !tl.rotate.is_empty() && other.is_none_or(|o| o.rotate.is_empty()) {
+                pose.rotation = lerp_angle(pose.rotation, setup.rotation, weight);
+            }
+            if !tl.translate.is_empty() && other.is_none_or(|o| o.translate.is_empty()) {
+                pose.x = lerp(pose.x, setup.x, weight);
+                pose.y = lerp(pose.y, setup.y, weight);
+            }
+            if !tl.scale.is_empty() && other.is_none_or(|o| o.scale.is_empty()) {
+                pose.scale_x = lerp(pose.scale_x, setup.scale_x, weight);
+                pose.scale_y = lerp(pose.scale_y, setup.scale_y, weight);
+            }
+            if !tl.shear.is_empty() && other.is_none_or(|o| o.shear.is_empty()) {
+                pose.shear_x = lerp(pose.shear_x, setup.shear_x, weight);
+                pose.shear_y = lerp(pose.shear_y, setup.shear_y, weight);
+            }
+        }
+        for tl in &from.slots {
+            let other = to.slots.iter().find(|t| t.slot == tl.slot);
+            let (Some(pose), Some(setup)) = (self.slots.get_mut(tl.slot), self.data.slots.get(tl.slot)) else {
+                continue;
+            };
+            if !tl.color.is_empty() && other.is_none_or(|o| o.color.is_empty()) {
+                for (dst, src) in pose.color.iter_mut().zip(setup.color) {
+                    *dst = lerp(*dst, src, weight);
+                }
+            }
+        }
+    }
+
+    /// Blend one animation at `time` into the current pose with weight `alpha`.
+    pub fn apply_animation(&mut self, anim: &Animation, time: f32, alpha: f32) {
+        for tl in &anim.bones {
+            let (Some(pose), Some(setup)) = (self.bones.get_mut(tl.bone), self.data.bones.get(tl.bone)) else {
+                continue;
+            };
+            if let Some(r) = sample_angle(&tl.rotate, time) {
+                pose.rotation = lerp_angle(pose.rotation, setup.rotation + r, alpha);
+            }
+            if let Some((x, y)) = sample_vec2(&tl.translate, time) {
+                pose.x = lerp(pose.x, setup.x + x, alpha);
+                pose.y = lerp(pose.y, setup.y + y, alpha);
+            }
+            if let Some((x, y)) = sample_vec2(&tl.scale, time) {
+                pose.scale_x = lerp(pose.scale_x, setup.scale_x * x, alpha);
+                pose.scale_y = lerp(pose.scale_y, setup.scale_y * y, alpha);
+            }
+            if let Some((x, y)) = sample_vec2(&tl.shear, time) {
+                pose.shear_x = lerp(pose.shear_x, setup.shear_x + x, alpha);
+                pose.shear_y = lerp(pose.shear_y, setup.shear_y + y, alpha);
+            }
+        }
+        for tl in &anim.slots {
+            let Some(pose) = self.slots.get_mut(tl.slot) else { continue };
+            // Attachment swaps are discrete: only take effect once this layer dominates
+            if alpha >= 0.5
+                && let Some(idx) = tl.attachment.iter().rposition(|(t, _)| *t <= time)
+            {
+                pose.attachment = tl.attachment[idx].1.clone();
+            }
+            if let Some(c) = sample_color(&tl.color, time) {
+                for (dst, src) in pose.color.iter_mut().zip(c) {
+                    *dst = lerp(*dst, src, alpha);
+                }
+            }
+        }
+        for tl in &anim.deforms {
+            let Some(pose) = self.slots.get(tl.slot) else { continue };
+            if pose.attachment.as_deref() != Some(tl.attachment.as_str()) {
+                continue;
+            }
+            let Some((i, t)) = find_segment(&tl.keys, time) else { continue };
+            let from = &tl.keys[i].value;
+            let target: Vec<f32> = match tl.keys.get(i + 1) {
+                Some(next) => from.iter().zip(&next.value).map(|(a, b)| lerp(*a, *b, t)).collect(),
+                None => from.clone(),
+            };
+            let deform = &mut self.slots[tl.slot].deform;
+            if deform.len() != target.len() {
+                deform.clear();
+                deform.resize(target.len(), 0.0);
+            }
+            for (d, v) in deform.iter_mut().zip(target) {
+                *d = lerp(*d, v, alpha);
+            }
+        }
+    }
+
+    /// Compute world matrices for all bones (parents precede children in data order).
+    pub fn update_world_transform(&mut self) {
+        let sy = if self.y_down { -self.scale_y } else { self.scale_y };
+        for i in 0..self.bones.len() {
+            let parent = self.data.bones[i].parent.and_then(|p| self.bones.get(p).copied());
+            let bone = &mut self.bones[i];
+            let rx = (bone.rotation + bone.shear_x).to_radians();
+            let ry = (bone.rotation + 90.0 + bone.shear_y).to_radians();
+            let la = rx.cos() * bone.scale_x;
+            let lb = ry.cos() * bone.scale_y;
+            let lc = rx.sin() * bone.scale_x;
+            let ld = ry.sin() * bone.scale_y;
+            match parent {
+                Some(p) => {
+                    bone.a = p.a * la + p.b * lc;
+                    bone.b = p.a * lb + p.b * ld;
+                    bone.c = p.c * la + p.d * lc;
+                    bone.d = p.c * lb + p.d * ld;
+                    bone.world_x = p.a * bone.x + p.b * bone.y + p.world_x;
+                    bone.world_y = p.c * bone.x + p.d * bone.y + p.world_y;
+                }
+                None => {
+                    bone.a = la * self.scale_x;
+                    bone.b = lb * self.scale_x;
+                    bone.c = lc * sy;
+                    bone.d = ld * sy;
+                    bone.world_x = bone.x * self.scale_x + self.x;
+                    bone.world_y = bone.y * sy + self.y;
+                }
+            }
+        }
+    }
+
+    /// Compute world-space vertices for a mesh attachment on a slot,
+    /// including any active deform offsets. Returns flat [x0, y0, ...].
+    pub fn mesh_world_vertices(&self, slot: usize, vertices: &MeshVertices) -> Vec<f32> {
+        let deform = &self.slots[slot].deform;
+        let deform = if deform.len() == vertices.deform_len() { Some(deform) } else { None };
+        let mut out = Vec::with_capacity(vertices.vertex_count() * 2);
+        match vertices {
+            MeshVertices::Unweighted(local) => {
+                let bone = &self.bones[self.data.slots[slot].bone];
+                for (i, xy) in local.chunks_exact(2).enumerate() {
+                    let (dx, dy) = deform.map_or((0.0, 0.0), |d| (d[i * 2], d[i * 2 + 1]));
+                    let (wx, wy) = bone.local_to_world(xy[0] + dx, xy[1] + dy);
+                    out.push(wx);
+                    out.push(wy);
+                }
+            }
+            MeshVertices::Weighted(weights) => {
+                let mut di = 0;
+                for influences in weights {
+                    let (mut wx, mut wy) = (0.0, 0.0);
+                    for &(bone_idx, bx, by, weight) in influences {
+                        let (dx, dy) = deform.map_or((0.0, 0.0), |d| (d[di], d[di + 1]));
+                        di += 2;
+                        if let Some(bone) = self.bones.get(bone_idx) {
+                            let (px, py) = bone.local_to_world(bx + dx, by + dy);
+                            wx += px * weight;
+                            wy += py * weight;
+                        }
+                    }
+                    out.push(wx);
+                    out.push(wy);
+                }
+            }
+        }
+        out
+    }
+
+    /// Resolve visible attachments in draw order.
+    pub fn draw_list(&self) -> Vec<SkeletonDrawItem> {
+        let mut items = Vec::new();
+        for (i, slot) in self.slots.iter().enumerate() {
+            let Some(name) = slot.attachment.as_deref() else { continue };
+            let Some(att) = self.get_attachment(i, name) else { continue };
+            let bone = &self.bones[self.data.slots[i].bone];
+            match att {
+                Attachment::Region { path, x, y, rotation, scale_x, scale_y, width, height, color } => {
+                    // Region axes in bone space
+                    let (sin, cos) = rotation.to_radians().sin_cos();
+                    let ux = (cos * scale_x, sin * scale_x);
+                    let uy = (-sin * scale_y, cos * scale_y);
+                    let (cx, cy) = bone.local_to_world(*x, *y);
+                    let wux = (bone.a * ux.0 + bone.b * ux.1, bone.c * ux.0 + bone.d * ux.1);
+                    let wuy = (bone.a * uy.0 + bone.b * uy.1, bone.c * uy.0 + bone.d * uy.1);
+                    let rotation = wux.1.atan2(wux.0);
+                    // Texture "up" is +y in Spine; compare with the sprite's up after rotation
+                    let (rs, rc) = rotation.sin_cos();
+                    let up_dot = wuy.0 * rs - wuy.1 * rc;
+                    items.push(SkeletonDrawItem::Region {
+                        path: path.clone(),
+                        center_x: cx,
+                        center_y: cy,
+                        width: width * (wux.0 * wux.0 + wux.1 * wux.1).sqrt(),
+                        height: height * (wuy.0 * wuy.0 + wuy.1 * wuy.1).sqrt(),
+                        rotation,
+                        flip_y: up_dot < 0.0,
+                        color: mul_color(slot.color, *color),
+                    });
+                }
+                Attachment::Mesh { path, uvs, triangles, vertices, color } => {
+                    items.push(SkeletonDrawItem::Mesh {
+                        path: path.clone(),
+                        vertices: self.mesh_world_vertices(i, vertices),
+                        uvs: uvs.clone(),
+                        triangles: triangles.clone(),
+                        color: mul_color(slot.color, *color),
+                    });
+                }
+            }
+        }
+        items
+    }
+}
+
+fn mul_color(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]]
+}
+
+/// A resolved, world-space drawable produced by `Skeleton::draw_list`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkeletonDrawItem {
+    Region {
+        path: String,
+        center_x: f32,
+        center_y: f32,
+        width: f32,
+        height: f32,
+        /// Radians.
+        rotation: f32,
+        flip_y: bool,
+        color: [f32; 4],
+    },
+    Mesh {
+        path: String,
+        /// World-space [x0, y0, x1, y1, ...].
+        vertices: Vec<f32>,
+        uvs: Vec<f32>,
+        triangles: Vec<u16>,
+        color: [f32; 4],
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bone(name: &str, parent: Option<usize>, x: f32, y: f32, rotation: f32) -> BoneData {
+        BoneData {
+            name: name.into(),
+            parent,
+            length: 10.0,
+            x,
+            y,
+            rotation,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            shear_x: 0.0,
+            shear_y: 0.0,
+        }
+    }
+
+    fn key<T>(time: f32, value: T) -> Key<T> {
+        Key { time, value, curve: Curve::Linear }
+    }
+
+    fn arm_data() -> Rc<SkeletonData> {
+        let mut skin = Skin { name: "default".into(), ..Default::default() };
+        skin.attachments.insert(
+            (0, "hand".into()),
+            Attachment::Region {
+                path: "hand".into(),
+                x: 0.0,
+                y: 0.0,
+                rotation: 0.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+                width: 8.0,
+                height: 4.0,
+                color: [1.0; 4],
+            },
+        );
+        Rc::new(SkeletonData {
+            bones: vec![bone("root", None, 0.0, 0.0, 0.0), bone("arm", Some(0), 10.0, 0.0, 90.0)],
+            slots: vec![SlotData { name: "hand".into(), bone: 1, attachment: Some("hand".into()), color: [1.0; 4] }],
+            skins: vec![skin],
+            animations: vec![
+                Animation {
+                    name: "swing".into(),
+                    duration: 1.0,
+                    bones: vec![BoneTimeline { bone: 0, rotate: vec![key(0.0, 0.0), key(1.0, 90.0)], ..Default::default() }],
+                    ..Default::default()
+                },
+                Animation {
+                    name: "move".into(),
+                    duration: 1.0,
+                    bones: vec![BoneTimeline { bone: 0, translate: vec![key(0.0, (0.0, 0.0)), key(1.0, (100.0, 0.0))], ..Default::default() }],
+                    ..Default::default()
+                },
+            ],
+        })
+    }
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_child_bone_inherits_parent_transform() {
+        let mut sk = Skeleton::new(arm_data());
+        sk.y_down = false;
+        sk.update_world_transform();
+        let arm = sk.bones[1];
+        assert!(approx(arm.world_x, 10.0));
+        assert!(approx(arm.world_rotation(), std::f32::consts::FRAC_PI_2));
+        // A point 5 units along the arm's x axis sits above the arm origin (y-up)
+        let (x, y) = arm.local_to_world(5.0, 0.0);
+        assert!(approx(x, 10.0) && approx(y, 5.0));
+    }
+
+    #[test]
+    fn test_y_down_flips_vertical_axis() {
+        let sk = Skeleton::new(arm_data());
+        let (x, y) = sk.bones[1].local_to_world(5.0, 0.0);
+        assert!(approx(x, 10.0) && approx(y, -5.0));
+    }
+
+    #[test]
+    fn test_rotate_timeline_interpolates() {
+        let mut sk = Skeleton::new(arm_data());
+        sk.state.play(0, 0, false, 0.0);
+        sk.update(0.5);
+        assert!(approx(sk.bones[0].rotation, 45.0));
+        sk.update(10.0);
+        assert!(approx(sk.bones[0].rotation, 90.0), "non-looping clamps at end");
+    }
+
+    #[test]
+    fn test_looping_wraps_time() {
+        let mut sk = Skeleton::new(arm_data());
+        sk.state.play(0, 0, true, 0.0);
+        sk.update(1.25);
+        assert!(approx(sk.bones[0].rotation, 22.5));
+    }
+
+    #[test]
+    fn test_crossfade_blends_then_finishes() {
+        let mut sk = Skeleton::new(arm_data());
+        sk.state.play(0, 1, false, 0.0);
+        sk.update(1.0);
+        assert!(approx(sk.bones[0].x, 100.0));
+
+        // Crossfade to "swing" over 1s: halfway, x is half of "move" held pose
+        sk.state.play(0, 0, false, 1.0);
+        sk.update(0.5);
+        assert!(approx(sk.bones[0].x, 50.0), "x = {}", sk.bones[0].x);
+        sk.update(0.6);
+        assert!(sk.state.tracks[0].entry.as_ref().unwrap().mixing_from.is_none());
+        assert!(approx(sk.bones[0].x, 0.0));
+    }
+
+    #[test]
+    fn test_track_alpha_layers() {
+        let mut sk = Skeleton::new(arm_data());
+        sk.state.play(1, 1, false, 0.0);
+        sk.state.set_track_alpha(1, 0.25);
+        sk.update(1.0);
+        assert!(approx(sk.bones[0].x, 25.0));
+    }
+
+    #[test]
+    fn test_track_index_is_capped() {
+        let mut sk = Skeleton::new(arm_data());
+        assert!(!sk.state.play(4_000_000_000, 0, false, 0.0));
+        assert!(!sk.state.play(MAX_TRACKS, 0, false, 0.0));
+        sk.state.set_track_alpha(MAX_TRACKS, 0.5);
+        sk.state.set_track_speed(MAX_TRACKS, 2.0);
+        assert!(sk.state.tracks.is_empty());
+        assert!(sk.state.play(MAX_TRACKS - 1, 0, false, 0.0));
+        assert_eq!(sk.state.tracks.len(), MAX_TRACKS);
+    }
+
+    #[test]
+    fn test_lerp_angle_takes_short_way() {
+        assert!(approx(lerp_angle(170.0, -170.0, 0.5), 180.0));
+        assert!(approx(lerp_angle(-170.0, 170.0, 0.5), -180.0));
+    }
+
+    #[test]
+    fn test_stepped_and_bezier_curves() {
+        assert_eq!(Curve::Stepped.apply(0.9), 0.0);
+        let ease = Curve::Bezier(0.25, 0.0, 0.75, 1.0);
+        assert!(ease.apply(0.25) < 0.25);
+        assert!(approx(ease.apply(0.5), 0.5));
+        assert!(approx(ease.apply(1.0), 1.0));
+    }
+
+    #[test]
+    fn test_draw_list_region_follows_bone() {
+        let sk = Skeleton::new(arm_data());
+        let items = sk.draw_list();
+        assert_eq!(items.len(), 1);
+        match &items[0] {
+            SkeletonDrawItem::Region { center_x, center_y, width, height, rotation, flip_y, .. } => {
+                assert!(approx(*center_x, 10.0) && approx(*center_y, 0.0));
+                assert!(approx(*width, 8.0) && approx(*height, 4.0));
+                // Bone points up on screen (y-down) → -90°
+                assert!(approx(*rotation, -std::f32::consts::FRAC_PI_2));
+                assert!(!flip_y);
+            }
+            other => panic!("expected region, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_weighted_mesh_with_deform() {
+        let mut data = (*arm_data()).clone();
+        data.animations.clear();
+        let verts = MeshVertices::Weighted(vec![
+            vec![(0, 0.0, 0.0, 0.5), (1, 0.0, 0.0, 0.5)],
+            vec![(1, 2.0, 0.0, 1.0)],
+        ]);
+        assert_eq!(verts.deform_len(), 6);
+        let mut sk = Skeleton::new(Rc::new(data));
+        sk.y_down = false;
+        sk.update_world_transform();
+        let out = sk.mesh_world_vertices(0, &verts);
+        // Vertex 0: midpoint of root origin and arm origin
+        assert!(approx(out[0], 5.0) && approx(out[1], 0.0));
+        // Vertex 1: 2 units along the arm (pointing up)
+        assert!(approx(out[2], 10.0) && approx(out[3], 2.0));
+
+        sk.slots[0].deform = vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let out = sk.mesh_world_vertices(0, &verts);
+        assert!(approx(out[3], 3.0));
+    }
+}
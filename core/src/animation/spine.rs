@@ -0,0 +1,409 @@
+//! Spine JSON loader (3.7 – 4.x export formats).
+//!
+//! Supported: bones, slots, skins (object and array forms), region and mesh
+//! attachments (weighted and unweighted), bone rotate/translate/scale/shear
+//! timelines, slot attachment/color timelines and mesh deform timelines.
+//! Unsupported features (IK, path/transform constraints, clipping, events,
+//! draw order timelines) are ignored rather than rejected.
+
+use std::collections::HashMap;
+
+use super::skeleton::*;
+use crate::json::JsonValue;
+
+/// Parse a Spine skeleton JSON export into `SkeletonData`.
+pub fn load_spine_json(src: &str) -> Result<SkeletonData, String> {
+    let root = JsonValue::parse(src)?;
+    let mut data = SkeletonData::default();
+
+    // Bones (parents always precede children in Spine exports)
+    let bones = root.get("bones").and_then(JsonValue::as_array).ok_or("Missing \"bones\" array")?;
+    for b in bones {
+        let name = b.str_of("name").ok_or("Bone without name")?.to_string();
+        let parent = match b.str_of("parent") {
+            Some(p) => Some(data.find_bone(p).ok_or_else(|| format!("Bone '{name}' has unknown parent '{p}'"))?),
+            None => None,
+        };
+        data.bones.push(BoneData {
+            name,
+            parent,
+            length: b.f32_or("length", 0.0),
+            x: b.f32_or("x", 0.0),
+            y: b.f32_or("y", 0.0),
+            rotation: b.f32_or("rotation", 0.0),
+            scale_x: b.f32_or("scaleX", 1.0),
+            scale_y: b.f32_or("scaleY", 1.0),
+            shear_x: b.f32_or("shearX", 0.0),
+            shear_y: b.f32_or("shearY", 0.0),
+        });
+    }
+
+    if let Some(slots) = root.get("slots").and_then(JsonValue::as_array) {
+        for s in slots {
+            let name = s.str_of("name").ok_or("Slot without name")?.to_string();
+            let bone_name = s.str_of("bone").ok_or_else(|| format!("Slot '{name}' has no bone"))?;
+            let bone = data.find_bone(bone_name).ok_or_else(|| format!("Slot '{name}' has unknown bone '{bone_name}'"))?;
+            data.slots.push(SlotData {
+                name,
+                bone,
+                attachment: s.str_of("attachment").map(str::to_string),
+                color: s.str_of("color").map(parse_color).unwrap_or([1.0; 4]),
+            });
+        }
+    }
+
+    // Skins: 3.8+ exports an array of {name, attachments}, older exports an object
+    match root.get("skins") {
+        Some(JsonValue::Array(skins)) => {
+            for skin in skins {
+                let name = skin.str_of("name").unwrap_or("default");
+                let attachments = skin.get("attachments").unwrap_or(&JsonValue::Null);
+                data.skins.push(parse_skin(&data, name, attachments));
+            }
+        }
+        Some(JsonValue::Object(skins)) => {
+            for (name, attachments) in skins {
+                data.skins.push(parse_skin(&data, name, attachments));
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(anims) = root.get("animations").and_then(JsonValue::as_object) {
+        for (name, anim) in anims {
+            let parsed = parse_animation(&data, name, anim);
+            data.animations.push(parsed);
+        }
+    }
+
+    Ok(data)
+}
+
+/// Parse "RRGGBBAA" (or "RRGGBB") hex into 0-1 floats.
+fn parse_color(hex: &str) -> [f32; 4] {
+    let channel = |i: usize| {
+        hex.get(i * 2..i * 2 + 2)
+            .and_then(|h| u8::from_str_radix(h, 16).ok())
+            .map(|v| v as f32 / 255.0)
+    };
+    [
+        channel(0).unwrap_or(1.0),
+        channel(1).unwrap_or(1.0),
+        channel(2).unwrap_or(1.0),
+        channel(3).unwrap_or(1.0),
+    ]
+}
+
+fn parse_skin(data: &SkeletonData, name: &str, slots: &JsonValue) -> Skin {
+    let mut skin = Skin { name: name.to_string(), attachments: HashMap::new() };
+    for (slot_name, atts) in slots.as_object().unwrap_or(&[]) {
+        let Some(slot) = data.find_slot(slot_name) else { continue };
+        for (att_name, att) in atts.as_object().unwrap_or(&[]) {
+            if let Some(parsed) = parse_attachment(att_name, att) {
+                skin.attachments.insert((slot, att_name.clone()), parsed);
+            }
+        }
+    }
+    skin
+}
+
+fn parse_attachment(key: &str, att: &JsonValue) -> Option<Attachment> {
+    // Image name: "path" (4.x) or "name" (3.x), defaulting to the attachment key
+    let path = att.str_of("path").or_else(|| att.str_of("name")).unwrap_or(key).to_string();
+    let color = att.str_of("color").map(parse_color).unwrap_or([1.0; 4]);
+    match att.str_of("type").unwrap_or("region") {
+        "region" => Some(Attachment::Region {
+            path,
+            x: att.f32_or("x", 0.0),
+            y: att.f32_or("y", 0.0),
+            rotation: att.f32_or("rotation", 0.0),
+            scale_x: att.f32_or("scaleX", 1.0),
+            scale_y: att.f32_or("scaleY", 1.0),
+            width: att.f32_or("width", 32.0),
+            height: att.f32_or("height", 32.0),
+            color,
+        }),
+        "mesh" => {
+            let uvs = att.f32_array("uvs");
+            let triangles = att.f32_array("triangles").into_iter().map(|i| i as u16).collect();
+            let raw = att.f32_array("vertices");
+            let vertices = if raw.len() == uvs.len() {
+                MeshVertices::Unweighted(raw)
+            } else {
+                // Weighted: per vertex [boneCount, (bone, x, y, weight) × boneCount]
+                let vertex_count = uvs.len() / 2;
+                let mut weights = Vec::with_capacity(vertex_count);
+                let mut i = 0;
+                while i < raw.len() && weights.len() < vertex_count {
+                    let count = raw[i] as usize;
+                    i += 1;
+                    let mut influences = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let entry = raw.get(i..i + 4)?;
+                        influences.push((entry[0] as usize, entry[1], entry[2], entry[3]));
+                        i += 4;
+                    }
+                    weights.push(influences);
+                }
+                MeshVertices::Weighted(weights)
+            };
+            Some(Attachment::Mesh { path, uvs, triangles, vertices, color })
+        }
+        // linkedmesh, boundingbox, path, point, clipping: not drawable here
+        _ => None,
+    }
+}
+
+/// Parse a keyframe's curve. Spine 3.8 uses `curve: cx1, c2, c3, c4`, older
+/// exports use a 4-element array, and any form may be the string "stepped".
+fn parse_curve(key: &JsonValue) -> Curve {
+    match key.get("curve") {
+        Some(JsonValue::String(s)) if s == "stepped" => Curve::Stepped,
+        Some(JsonValue::Number(cx1)) => Curve::Bezier(
+            *cx1 as f32,
+            key.f32_or("c2", 0.0),
+            key.f32_or("c3", 1.0),
+            key.f32_or("c4", 1.0),
+        ),
+        Some(JsonValue::Array(c)) if c.len() == 4 => {
+            let v: Vec<f32> = c.iter().filter_map(JsonValue::as_f32).collect();
+            // 4.x stores curves in absolute time/value space; only use normalized ones
+            if v.len() == 4 && v.iter().all(|x| (0.0..=1.0).contains(x)) {
+                Curve::Bezier(v[0], v[1], v[2], v[3])
+            } else {
+                Curve::Linear
+            }
+        }
+        _ => Curve::Linear,
+    }
+}
+
+fn keys<T>(frames: Option<&JsonValue>, duration: &mut f32, value: impl Fn(&JsonValue) -> T) -> Vec<Key<T>> {
+    let Some(frames) = frames.and_then(JsonValue::as_array) else {
+        return Vec::new();
+    };
+    frames
+        .iter()
+        .map(|f| {
+            let time = f.f32_or("time", 0.0);
+            *duration = duration.max(time);
+            Key { time, value: value(f), curve: parse_curve(f) }
+        })
+        .collect()
+}
+
+fn parse_animation(data: &SkeletonData, name: &str, anim: &JsonValue) -> Animation {
+    let mut out = Animation { name: name.to_string(), ..Default::default() };
+    let mut duration = 0.0f32;
+
+    for (bone_name, channels) in anim.get("bones").and_then(JsonValue::as_object).unwrap_or(&[]) {
+        let Some(bone) = data.find_bone(bone_name) else { continue };
+        out.bones.push(BoneTimeline {
+            bone,
+            // 3.x: "angle", 4.x: "value"
+            rotate: keys(channels.get("rotate"), &mut duration, |f| {
+                f.get("value").or_else(|| f.get("angle")).and_then(JsonValue::as_f32).unwrap_or(0.0)
+            }),
+            translate: keys(channels.get("translate"), &mut duration, |f| (f.f32_or("x", 0.0), f.f32_or("y", 0.0))),
+            scale: keys(channels.get("scale"), &mut duration, |f| (f.f32_or("x", 1.0), f.f32_or("y", 1.0))),
+            shear: keys(channels.get("shear"), &mut duration, |f| (f.f32_or("x", 0.0), f.f32_or("y", 0.0))),
+        });
+    }
+
+    for (slot_name, channels) in anim.get("slots").and_then(JsonValue::as_object).unwrap_or(&[]) {
+        let Some(slot) = data.find_slot(slot_name) else { continue };
+        let attachment = channels
+            .get("attachment")
+            .and_then(JsonValue::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .map(|f| {
+                let time = f.f32_or("time", 0.0);
+                duration = duration.max(time);
+                (time, f.str_of("name").map(str::to_string))
+            })
+            .collect();
+        let color_frames = channels.get("rgba").or_else(|| channels.get("color"));
+        out.slots.push(SlotTimeline {
+            slot,
+            attachment,
+            color: keys(color_frames, &mut duration, |f| f.str_of("color").map(parse_color).unwrap_or([1.0; 4])),
+        });
+    }
+
+    // Deform: 3.x "deform" (or "ffd") is skin → slot → attachment → keys;
+    // 4.x "attachments" is skin → slot → attachment → { "deform": keys }
+    let (deform_root, nested) = match anim.get("attachments") {
+        Some(v) => (Some(v), true),
+        None => (anim.get("deform").or_else(|| anim.get("ffd")), false),
+    };
+    for (skin_name, slots) in deform_root.and_then(JsonValue::as_object).unwrap_or(&[]) {
+        let Some(skin) = data.find_skin(skin_name) else { continue };
+        for (slot_name, atts) in slots.as_object().unwrap_or(&[]) {
+            let Some(slot) = data.find_slot(slot_name) else { continue };
+            for (att_name, frames) in atts.as_object().unwrap_or(&[]) {
+                let frames = if nested { frames.get("deform") } else { Some(frames) };
+                let Some(Attachment::Mesh { vertices, .. }) =
+                    data.skins[skin].attachments.get(&(slot, att_name.clone()))
+                else {
+                    continue;
+                };
+                let len = vertices.deform_len();
+                out.deforms.push(DeformTimeline {
+                    slot,
+                    attachment: att_name.clone(),
+                    keys: keys(frames, &mut duration, |f| {
+                        let mut offsets = vec![0.0; len];
+                        let start = f.f32_or("offset", 0.0) as usize;
+                        for (i, v) in f.f32_array("vertices").into_iter().enumerate() {
+                            if let Some(slot) = offsets.get_mut(start + i) {
+                                *slot = v;
+                            }
+                        }
+                        offsets
+                    }),
+                });
+            }
+        }
+    }
+
+    out.duration = duration;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPINE_38: &str = r#"{
+        "skeleton": { "spine": "3.8.99" },
+        "bones": [
+            { "name": "root" },
+            { "name": "torso", "parent": "root", "y": 20, "rotation": 90 },
+            { "name": "arm", "parent": "torso", "x": 10, "scaleX": 2 }
+        ],
+        "slots": [
+            { "name": "body", "bone": "torso", "attachment": "body" },
+            { "name": "flag", "bone": "arm", "attachment": "flag", "color": "ff000080" }
+        ],
+        "skins": [{
+            "name": "default",
+            "attachments": {
+                "body": { "body": { "x": 1, "width": 20, "height": 40 } },
+                "flag": {
+                    "flag": {
+                        "type": "mesh",
+                        "uvs": [0, 0, 1, 0, 0, 1],
+                        "triangles": [0, 1, 2],
+                        "vertices": [0, 0, 10, 0, 0, 10],
+                        "hull": 3
+                    }
+                }
+            }
+        }],
+        "animations": {
+            "wave": {
+                "bones": {
+                    "arm": {
+                        "rotate": [ { "angle": 0, "curve": "stepped" }, { "time": 0.5, "angle": 30 } ],
+                        "scale": [ { "time": 1.0, "x": 2 } ]
+                    }
+                },
+                "slots": {
+                    "flag": { "color": [ { "time": 0, "color": "ffffffff" } ] }
+                },
+                "deform": {
+                    "default": { "flag": { "flag": [ { "time": 0.25, "offset": 2, "vertices": [5, 5] } ] } }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_load_bones_and_slots() {
+        let data = load_spine_json(SPINE_38).unwrap();
+        assert_eq!(data.bones.len(), 3);
+        assert_eq!(data.bones[2].parent, Some(1));
+        assert_eq!(data.bones[2].scale_x, 2.0);
+        assert_eq!(data.bones[1].rotation, 90.0);
+        assert_eq!(data.slots[1].bone, 2);
+        assert!((data.slots[1].color[3] - 128.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_load_attachments() {
+        let data = load_spine_json(SPINE_38).unwrap();
+        let skin = &data.skins[0];
+        match &skin.attachments[&(0, "body".to_string())] {
+            Attachment::Region { x, width, path, .. } => {
+                assert_eq!(*x, 1.0);
+                assert_eq!(*width, 20.0);
+                assert_eq!(path, "body");
+            }
+            other => panic!("expected region, got {other:?}"),
+        }
+        match &skin.attachments[&(1, "flag".to_string())] {
+            Attachment::Mesh { vertices: MeshVertices::Unweighted(v), triangles, .. } => {
+                assert_eq!(v.len(), 6);
+                assert_eq!(triangles, &vec![0, 1, 2]);
+            }
+            other => panic!("expected unweighted mesh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_load_animation_timelines() {
+        let data = load_spine_json(SPINE_38).unwrap();
+        let wave = &data.animations[data.find_animation("wave").unwrap()];
+        assert_eq!(wave.duration, 1.0);
+        assert_eq!(wave.bones[0].rotate.len(), 2);
+        assert_eq!(wave.bones[0].rotate[0].curve, Curve::Stepped);
+        assert_eq!(wave.bones[0].scale[0].value, (2.0, 1.0));
+        assert_eq!(wave.slots[0].color.len(), 1);
+        assert_eq!(wave.deforms[0].keys[0].value, vec![0.0, 0.0, 5.0, 5.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_weighted_mesh_and_object_skins() {
+        let json = r#"{
+            "bones": [ { "name": "root" }, { "name": "b", "parent": "root", "x": 4 } ],
+            "slots": [ { "name": "s", "bone": "root", "attachment": "m" } ],
+            "skins": { "default": { "s": { "m": {
+                "type": "mesh",
+                "uvs": [0, 0, 1, 1],
+                "triangles": [],
+                "vertices": [1, 0, 2, 1, 1, 2, 0, 3, 4, 0.5, 1, 3, 4, 0.5]
+            } } } },
+            "animations": { "idle": { "bones": { "b": { "rotate": [ { "time": 0, "value": 10 } ] } } } }
+        }"#;
+        let data = load_spine_json(json).unwrap();
+        match &data.skins[0].attachments[&(0, "m".to_string())] {
+            Attachment::Mesh { vertices: MeshVertices::Weighted(w), .. } => {
+                assert_eq!(w.len(), 2);
+                assert_eq!(w[0], vec![(0, 2.0, 1.0, 1.0)]);
+                assert_eq!(w[1], vec![(0, 3.0, 4.0, 0.5), (1, 3.0, 4.0, 0.5)]);
+            }
+            other => panic!("expected weighted mesh, got {other:?}"),
+        }
+        assert_eq!(data.animations[0].bones[0].rotate[0].value, 10.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(load_spine_json("{}").is_err());
+        assert!(load_spine_json(r#"{"bones":[{"name":"a","parent":"missing"}]}"#).is_err());
+        assert!(load_spine_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_loaded_skeleton_animates() {
+        let data = std::rc::Rc::new(load_spine_json(SPINE_38).unwrap());
+        let mut sk = Skeleton::new(data.clone());
+        sk.state.play(0, data.find_animation("wave").unwrap(), true, 0.0);
+        sk.update(0.75);
+        // Stepped first key holds 0 until t=0.5, then the last key (30) holds
+        assert!((sk.bones[2].rotation - 30.0).abs() < 1e-4);
+        assert_eq!(sk.slots[1].deform.len(), 6);
+        assert_eq!(sk.draw_list().len(), 2);
+    }
+}
@@ -0,0 +1,103 @@
+//! Doppler pitch shift of moving spatial sounds.
+//!
+//! The audio thread estimates source and listener velocities from how far
+//! each `UpdateSpatialPositions` moved them since the last one, and plays the
+//! instance at its pitch times [`doppler_shift`]: higher while source and
+//! listener close in, lower while they separate. Velocities are smoothed
+//! over updates, so frame time jitter doesn't warble the pitch.
+
+/// Speed of sound in game pixels per second: 343 m/s at 10 pixels per metre.
+pub const SPEED_OF_SOUND: f32 = 3430.0;
+
+/// Fastest approach speed, as a fraction of [`SPEED_OF_SOUND`], that still
+/// shifts further; past it the formula would blow up.
+const MAX_SPEED: f32 = 0.9;
+
+/// Weight of the newest velocity estimate.
+const VELOCITY_SMOOTHING: f32 = 0.5;
+
+/// Updates closer together than this (seconds) keep the last velocities:
+/// two updates in one frame would otherwise measure a huge or zero speed.
+const MIN_UPDATE_GAP: f32 = 0.001;
+
+/// Updates further apart than this (seconds) reset the velocities, so a
+/// sound moved after a pause doesn't swoop.
+const MAX_UPDATE_GAP: f32 = 0.25;
+
+/// Doppler settings of an instance, as set by `op_set_sound_doppler`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DopplerParams {
+    /// Multiplier on both velocities: above 1 exaggerates the effect.
+    pub factor: f32,
+    /// Lowest pitch multiplier the shift can reach.
+    pub min_pitch: f32,
+    /// Highest pitch multiplier the shift can reach.
+    pub max_pitch: f32,
+}
+
+/// Pitch multiplier for a source and listener at `source`/`listener` (game
+/// pixels) moving at `source_vel`/`listener_vel` (pixels per second).
+pub fn doppler_shift(
+    source: [f32; 2],
+    source_vel: [f32; 2],
+    listener: [f32; 2],
+    listener_vel: [f32; 2],
+    params: &DopplerParams,
+) -> f32 {
+    let min = params.min_pitch.max(0.01);
+    let max = params.max_pitch.max(min);
+    let (dx, dy) = (listener[0] - source[0], listener[1] - source[1]);
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance < 1e-3 {
+        return 1.0f32.clamp(min, max);
+    }
+    let (ux, uy) = (dx / distance, dy / distance);
+    let limit = SPEED_OF_SOUND * MAX_SPEED;
+    // Speeds along the line between them, positive when closing in
+    let source_speed = ((source_vel[0] * ux + source_vel[1] * uy) * params.factor).clamp(-limit, limit);
+    let listener_speed = (-(listener_vel[0] * ux + listener_vel[1] * uy) * params.factor).clamp(-limit, limit);
+    ((SPEED_OF_SOUND + listener_speed) / (SPEED_OF_SOUND - source_speed)).clamp(min, max)
+}
+
+/// Per-instance Doppler state, kept by the audio thread.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Doppler {
+    pub params: DopplerParams,
+    pub source_vel: [f32; 2],
+    pub listener_vel: [f32; 2],
+    /// Current pitch multiplier.
+    pub shift: f32,
+}
+
+impl Doppler {
+    pub fn new(params: DopplerParams) -> Self {
+        Self { params, source_vel: [0.0; 2], listener_vel: [0.0; 2], shift: 1.0 }
+    }
+
+    /// Fold in a move of the source from `from_source` to `source` and of
+    /// the listener from `from_listener` to `listener` over `dt` seconds, and
+    /// return the new pitch multiplier.
+    pub fn update(
+        &mut self,
+        from_source: [f32; 2],
+        source: [f32; 2],
+        from_listener: [f32; 2],
+        listener: [f32; 2],
+        dt: f32,
+    ) -> f32 {
+        if dt > MAX_UPDATE_GAP {
+            self.source_vel = [0.0; 2];
+            self.listener_vel = [0.0; 2];
+        } else if dt >= MIN_UPDATE_GAP {
+            let blend = |vel: &mut [f32; 2], from: [f32; 2], to: [f32; 2]| {
+                for ((v, from), to) in vel.iter_mut().zip(from).zip(to) {
+                    *v += ((to - from) / dt - *v) * VELOCITY_SMOOTHING;
+                }
+            };
+            blend(&mut self.source_vel, from_source, source);
+            blend(&mut self.listener_vel, from_listener, listener);
+        }
+        self.shift = doppler_shift(source, self.source_vel, listener, self.listener_vel, &self.params);
+        self.shift
+    }
+}
@@ -0,0 +1,354 @@
+//! Effect chain for audio buses: low-pass → echo delay → reverb.
+//!
+//! Each bus owns a [`SharedEffects`] that the audio thread updates from
+//! `AudioCommand::SetBusEffect`. Every sound played on the bus is wrapped in
+//! an [`EffectSource`] that reads those settings, so changes apply to sounds
+//! already playing. The same chain with fixed settings implements the
+//! per-sound `reverb_mix`/`reverb_delay_ms` of `PlaySoundEx`.
+//!
+//! Delay lines run over the interleaved sample stream, so one line serves all
+//! channels and each channel only ever hears its own echoes.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::Source;
+
+/// Highest feedback for the echo and reverb, so tails always die out.
+const MAX_FEEDBACK: f32 = 0.95;
+
+/// Longest echo or reverb delay, in milliseconds.
+const MAX_DELAY_MS: f32 = 2000.0;
+
+/// Longest tail rendered after a sound ends, in seconds.
+const MAX_TAIL_SECS: f32 = 10.0;
+
+/// Reverb comb lengths relative to the reverb delay. Mutually prime-ish
+/// ratios keep the combs' echoes from lining up into a flutter.
+const COMB_RATIOS: [f32; 4] = [1.0, 1.17, 1.31, 1.47];
+
+/// Diffusing all-pass after the combs.
+const ALLPASS_MS: f32 = 5.0;
+const ALLPASS_GAIN: f32 = 0.5;
+
+/// One stage of a bus effect chain, as set by `op_set_bus_effect`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BusEffect {
+    /// One-pole low-pass at `cutoff_hz`. 0 disables the filter.
+    LowPass { cutoff_hz: f32 },
+    /// Repeating echo every `delay_ms`, each repeat `feedback` (0-0.95) as
+    /// loud as the last. `mix` 0 disables it.
+    Delay { mix: f32, delay_ms: f32, feedback: f32 },
+    /// Feedback-delay reverb: four combs around `delay_ms` ringing with
+    /// `decay` (0-0.95), then an all-pass. `mix` 0 disables it.
+    Reverb { mix: f32, delay_ms: f32, decay: f32 },
+}
+
+impl BusEffect {
+    /// Build an effect from the op's kind (0 = low-pass, 1 = delay,
+    /// 2 = reverb) and up to three parameters in declaration order.
+    pub fn from_parts(kind: u32, params: [f32; 3]) -> Option<Self> {
+        let [a, b, c] = params;
+        match kind {
+            0 => Some(Self::LowPass { cutoff_hz: a }),
+            1 => Some(Self::Delay { mix: a, delay_ms: b, feedback: c }),
+            2 => Some(Self::Reverb { mix: a, delay_ms: b, decay: c }),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for a whole chain. Stages with a zero cutoff or mix are bypassed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EffectSettings {
+    pub low_pass_hz: f32,
+    pub delay_mix: f32,
+    pub delay_ms: f32,
+    pub delay_feedback: f32,
+    pub reverb_mix: f32,
+    pub reverb_delay_ms: f32,
+    pub reverb_decay: f32,
+}
+
+impl EffectSettings {
+    /// Replace the settings of the stage `effect` targets.
+    pub fn apply(&mut self, effect: BusEffect) {
+        match effect {
+            BusEffect::LowPass { cutoff_hz } => self.low_pass_hz = cutoff_hz,
+            BusEffect::Delay { mix, delay_ms, feedback } => {
+                self.delay_mix = mix;
+                self.delay_ms = delay_ms;
+                self.delay_feedback = feedback;
+            }
+            BusEffect::Reverb { mix, delay_ms, decay } => {
+                self.reverb_mix = mix;
+                self.reverb_delay_ms = delay_ms;
+                self.reverb_decay = decay;
+            }
+        }
+    }
+
+    /// Settings with only `effect` enabled.
+    pub fn with(effect: BusEffect) -> Self {
+        let mut settings = Self::default();
+        settings.apply(effect);
+        settings
+    }
+}
+
+/// Effect settings shared between the audio thread and the sources playing
+/// on a bus. Sources poll `generation` per sample and only lock on a change.
+#[derive(Debug, Default)]
+pub struct SharedEffects {
+    generation: AtomicU32,
+    settings: Mutex<EffectSettings>,
+}
+
+impl SharedEffects {
+    pub fn new(settings: EffectSettings) -> Self {
+        Self { generation: AtomicU32::new(0), settings: Mutex::new(settings) }
+    }
+
+    pub fn set(&self, effect: BusEffect) {
+        let mut settings = self.settings.lock().unwrap_or_else(|e| e.into_inner());
+        settings.apply(effect);
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn settings(&self) -> EffectSettings {
+        *self.settings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
+
+/// Fixed-length delay over the interleaved sample stream.
+#[derive(Debug, Default)]
+struct DelayLine {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl DelayLine {
+    /// Resize to `len` samples, clearing it if the length changed.
+    fn resize(&mut self, len: usize) {
+        if self.buffer.len() != len {
+            self.buffer = vec![0.0; len];
+            self.pos = 0;
+        }
+    }
+
+    /// Push `input` and return the sample from `len` samples ago.
+    fn tap(&mut self, input: impl FnOnce(f32) -> f32) -> f32 {
+        let Some(slot) = self.buffer.get_mut(self.pos) else {
+            return 0.0;
+        };
+        let delayed = *slot;
+        *slot = input(delayed);
+        self.pos = (self.pos + 1) % self.buffer.len();
+        delayed
+    }
+}
+
+/// The DSP state of one chain for a given sample rate and channel count.
+#[derive(Debug)]
+pub struct EffectChain {
+    settings: EffectSettings,
+    sample_rate: u32,
+    channels: usize,
+    /// Next sample's channel in the interleaved stream.
+    channel: usize,
+    low_pass_coeff: f32,
+    low_pass_state: Vec<f32>,
+    echo: DelayLine,
+    combs: [DelayLine; 4],
+    allpass: DelayLine,
+}
+
+impl EffectChain {
+    pub fn new(settings: EffectSettings, sample_rate: u32, channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        let mut chain = Self {
+            settings: EffectSettings::default(),
+            sample_rate: sample_rate.max(1),
+            channels,
+            channel: 0,
+            low_pass_coeff: 1.0,
+            low_pass_state: vec![0.0; channels],
+            echo: DelayLine::default(),
+            combs: Default::default(),
+            allpass: DelayLine::default(),
+        };
+        chain.configure(settings);
+        chain
+    }
+
+    /// Switch to new settings. Delay lines keep their contents unless their
+    /// length changes.
+    pub fn configure(&mut self, settings: EffectSettings) {
+        self.settings = settings;
+        self.low_pass_coeff = if settings.low_pass_hz > 0.0 {
+            let cutoff = settings.low_pass_hz.min(self.sample_rate as f32 / 2.0);
+            1.0 - (-std::f32::consts::TAU * cutoff / self.sample_rate as f32).exp()
+        } else {
+            1.0
+        };
+        let echo_len = if settings.delay_mix > 0.0 { self.delay_len(settings.delay_ms) } else { 0 };
+        self.echo.resize(echo_len);
+        for (comb, ratio) in self.combs.iter_mut().zip(COMB_RATIOS) {
+            let len = if settings.reverb_mix > 0.0 {
+                let ms = settings.reverb_delay_ms.clamp(1.0, MAX_DELAY_MS) * ratio;
+                (ms / 1000.0 * self.sample_rate as f32).round().max(1.0) as usize * self.channels
+            } else {
+                0
+            };
+            comb.resize(len);
+        }
+        let allpass_len = if settings.reverb_mix > 0.0 { self.delay_len(ALLPASS_MS) } else { 0 };
+        self.allpass.resize(allpass_len);
+    }
+
+    /// Interleaved length of a delay of `ms` milliseconds, at least one frame.
+    fn delay_len(&self, ms: f32) -> usize {
+        let frames = (ms.clamp(1.0, MAX_DELAY_MS) / 1000.0 * self.sample_rate as f32).round().max(1.0);
+        frames as usize * self.channels
+    }
+
+    /// Whether any stage keeps ringing after its input stops.
+    pub fn has_tail(&self) -> bool {
+        !self.echo.buffer.is_empty() || !self.allpass.buffer.is_empty()
+    }
+
+    /// Samples until the echo and reverb fall below -60 dB once input stops.
+    pub fn tail_samples(&self) -> usize {
+        let ring = |len: usize, feedback: f32| -> f32 {
+            if len == 0 {
+                return 0.0;
+            }
+            let feedback = feedback.clamp(0.0, MAX_FEEDBACK);
+            let repeats = if feedback > 0.0 { 0.001f32.ln() / feedback.ln() } else { 1.0 };
+            len as f32 * repeats.max(1.0)
+        };
+        let longest_comb = self.combs.iter().map(|c| c.buffer.len()).max().unwrap_or(0);
+        let samples = ring(self.echo.buffer.len(), self.settings.delay_feedback)
+            .max(ring(longest_comb, self.settings.reverb_decay));
+        let cap = MAX_TAIL_SECS * self.sample_rate as f32 * self.channels as f32;
+        samples.min(cap) as usize
+    }
+
+    /// Process the next interleaved sample.
+    pub fn process(&mut self, input: f32) -> f32 {
+        let channel = self.channel;
+        self.channel = (channel + 1) % self.channels;
+        let s = &self.settings;
+
+        let mut out = input;
+        if self.low_pass_coeff < 1.0 {
+            let state = &mut self.low_pass_state[channel];
+            *state += self.low_pass_coeff * (out - *state);
+            out = *state;
+        } else {
+            // Track the signal while bypassed, so switching the filter on doesn't click
+            self.low_pass_state[channel] = out;
+        }
+
+        if !self.echo.buffer.is_empty() {
+            let feedback = s.delay_feedback.clamp(0.0, MAX_FEEDBACK);
+            let dry = out;
+            let echo = self.echo.tap(|delayed| dry + feedback * delayed);
+            let mix = s.delay_mix.clamp(0.0, 1.0);
+            out = dry * (1.0 - mix) + echo * mix;
+        }
+
+        if !self.allpass.buffer.is_empty() {
+            let decay = s.reverb_decay.clamp(0.0, MAX_FEEDBACK);
+            let dry = out;
+            let mut wet = 0.0;
+            for comb in &mut self.combs {
+                wet += comb.tap(|delayed| dry + decay * delayed);
+            }
+            wet *= 1.0 / COMB_RATIOS.len() as f32;
+            // Schroeder all-pass: smears the comb echoes into a denser tail
+            let mut diffused = 0.0;
+            self.allpass.tap(|delayed| {
+                diffused = delayed - ALLPASS_GAIN * wet;
+                wet + ALLPASS_GAIN * diffused
+            });
+            let mix = s.reverb_mix.clamp(0.0, 1.0);
+            out = dry * (1.0 - mix) + diffused * mix;
+        }
+
+        out
+    }
+}
+
+/// A source run through an [`EffectChain`] that follows [`SharedEffects`].
+/// When the inner source ends, the echo and reverb tails keep playing.
+pub struct EffectSource<S> {
+    inner: S,
+    shared: Arc<SharedEffects>,
+    generation: u32,
+    chain: EffectChain,
+    /// Samples left in the tail once `inner` has ended.
+    tail: Option<usize>,
+}
+
+impl<S: Source<Item = f32>> EffectSource<S> {
+    pub fn new(inner: S, shared: Arc<SharedEffects>) -> Self {
+        let generation = shared.generation();
+        let chain = EffectChain::new(shared.settings(), inner.sample_rate(), inner.channels());
+        Self { inner, shared, generation, chain, tail: None }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for EffectSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let generation = self.shared.generation();
+        if generation != self.generation {
+            self.generation = generation;
+            self.chain.configure(self.shared.settings());
+        }
+        if self.tail.is_none() {
+            if let Some(sample) = self.inner.next() {
+                return Some(self.chain.process(sample));
+            }
+            self.tail = Some(self.chain.tail_samples());
+        }
+        let left = self.tail.as_mut()?;
+        if *left == 0 {
+            return None;
+        }
+        *left -= 1;
+        Some(self.chain.process(0.0))
+    }
+}
+
+impl<S: Source<Item = f32>> Source for EffectSource<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        match self.tail {
+            Some(left) => Some(left),
+            // The inner source is about to end; the tail follows it
+            None => match self.inner.current_frame_len() {
+                Some(0) => Some(self.chain.tail_samples()),
+                len => len,
+            },
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        if self.chain.has_tail() { None } else { self.inner.total_duration() }
+    }
+}
@@ -0,0 +1,192 @@
+//! Output level metering per bus and for the master mix.
+//!
+//! Every playing sound is wrapped in a [`MeterSource`] that accumulates the
+//! energy and peak of its samples, scaled by the instance's current output
+//! volume, into the shared [`LevelMeters`]. The audio thread drains those on
+//! its mixer tick and publishes smoothed [`AudioLevels`].
+//!
+//! Sounds on a bus are assumed uncorrelated, so the bus energy is the sum of
+//! its sounds' energies. rodio's built-in spatial falloff (spatial sounds
+//! without `SpatialParams`) happens after the meter and is not reflected.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::Source;
+
+/// Samples a source accumulates before adding them to the shared meters.
+const FLUSH_SAMPLES: u32 = 512;
+
+/// Time constant of the RMS smoothing, in seconds.
+const RMS_SMOOTHING: f32 = 0.1;
+
+/// Peak level falls by this factor per second after the signal drops.
+const PEAK_RELEASE_PER_SEC: f32 = 0.05;
+
+/// Levels below this count as silence.
+const SILENCE: f32 = 1e-4;
+
+/// Signal level as linear amplitude (1.0 = full scale).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioLevel {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Smoothed levels of each bus (indexed by `AudioBus as usize`) and the master mix.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioLevels {
+    pub master: AudioLevel,
+    pub buses: [AudioLevel; 4],
+    /// Sound instances playing or paused (not yet finished).
+    pub playing: u32,
+}
+
+impl AudioLevels {
+    /// Whether any level is still above silence (so the meters keep decaying).
+    pub fn is_audible(&self) -> bool {
+        self.master.peak > SILENCE || self.master.rms > SILENCE
+    }
+
+    /// Fold in `window`, the signal measured over the last `dt` seconds.
+    pub fn update(&mut self, window: &[BusWindow; 4], dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        let smoothing = 1.0 - (-dt / RMS_SMOOTHING).exp();
+        let release = PEAK_RELEASE_PER_SEC.powf(dt);
+        let mut master_energy = 0.0;
+        let mut master_peak: f32 = 0.0;
+        for (level, w) in self.buses.iter_mut().zip(window) {
+            let mean_square = (w.energy / dt as f64) as f32;
+            master_energy += mean_square;
+            master_peak = master_peak.max(w.peak);
+            smooth(level, mean_square.sqrt(), w.peak, smoothing, release);
+        }
+        smooth(&mut self.master, master_energy.sqrt(), master_peak, smoothing, release);
+    }
+
+    /// Master then each bus as `[rms, peak]` pairs, for `op_get_audio_levels`.
+    pub fn to_array(&self) -> [f32; 10] {
+        let mut out = [0.0; 10];
+        for (i, level) in std::iter::once(&self.master).chain(&self.buses).enumerate() {
+            out[i * 2] = level.rms;
+            out[i * 2 + 1] = level.peak;
+        }
+        out
+    }
+}
+
+fn smooth(level: &mut AudioLevel, rms: f32, peak: f32, smoothing: f32, release: f32) {
+    level.rms += (rms - level.rms) * smoothing;
+    level.peak = peak.max(level.peak * release);
+    if level.rms < SILENCE {
+        level.rms = 0.0;
+    }
+    if level.peak < SILENCE {
+        level.peak = 0.0;
+    }
+}
+
+/// Signal accumulated on one bus since the last drain.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BusWindow {
+    /// Sum of squared samples divided by samples per second: mean square × seconds.
+    pub energy: f64,
+    pub peak: f32,
+}
+
+/// Accumulators shared by all metered sources.
+#[derive(Debug, Default)]
+pub struct LevelMeters {
+    buses: Mutex<[BusWindow; 4]>,
+}
+
+impl LevelMeters {
+    fn add(&self, bus: usize, energy: f64, peak: f32) {
+        let mut buses = self.buses.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(window) = buses.get_mut(bus) {
+            window.energy += energy;
+            window.peak = window.peak.max(peak);
+        }
+    }
+
+    /// Take what was measured since the last call.
+    pub fn drain(&self) -> [BusWindow; 4] {
+        std::mem::take(&mut *self.buses.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Where a [`MeterSource`] reports: its bus and the instance's output volume
+/// (`f32` bits, kept current by the audio thread).
+#[derive(Clone, Debug)]
+pub struct MeterTap {
+    pub meters: Arc<LevelMeters>,
+    pub bus: usize,
+    pub gain: Arc<AtomicU32>,
+}
+
+/// Passes samples through unchanged while metering them.
+pub struct MeterSource<S> {
+    inner: S,
+    tap: MeterTap,
+    sum_squares: f64,
+    peak: f32,
+    pending: u32,
+}
+
+impl<S: Source<Item = f32>> MeterSource<S> {
+    pub fn new(inner: S, tap: MeterTap) -> Self {
+        Self { inner, tap, sum_squares: 0.0, peak: 0.0, pending: 0 }
+    }
+
+    fn flush(&mut self) {
+        if self.pending == 0 {
+            return;
+        }
+        let gain = f32::from_bits(self.tap.gain.load(Ordering::Relaxed));
+        let samples_per_sec = self.inner.sample_rate().max(1) as f64 * self.inner.channels().max(1) as f64;
+        let energy = self.sum_squares * (gain * gain) as f64 / samples_per_sec;
+        self.tap.meters.add(self.tap.bus, energy, self.peak * gain);
+        self.sum_squares = 0.0;
+        self.peak = 0.0;
+        self.pending = 0;
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for MeterSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let Some(sample) = self.inner.next() else {
+            self.flush();
+            return None;
+        };
+        self.sum_squares += (sample * sample) as f64;
+        self.peak = self.peak.max(sample.abs());
+        self.pending += 1;
+        if self.pending >= FLUSH_SAMPLES {
+            self.flush();
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for MeterSource<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
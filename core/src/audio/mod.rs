@@ -1,9 +1,25 @@
 use std::collections::HashMap;
 use std::io::Cursor;
-use std::sync::{mpsc, Arc};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use rodio::Source;
 
+use crate::curve::Curve;
+
+pub mod doppler;
+pub mod effects;
+pub mod meter;
+pub mod occlusion;
+
+pub use doppler::{Doppler, DopplerParams};
+pub use effects::{BusEffect, EffectSettings};
+pub use meter::{AudioLevel, AudioLevels};
+pub use occlusion::{Occlusion, OcclusionParams};
+use effects::{EffectSource, SharedEffects};
+use meter::{LevelMeters, MeterSource, MeterTap};
+
 /// Audio bus for grouping sounds. Each bus has independent volume control.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AudioBus {
@@ -25,6 +41,110 @@ impl AudioBus {
     }
 }
 
+/// Distance falloff curve for spatial sounds with [`SpatialParams`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attenuation {
+    /// Straight line from full volume at `min_distance` to silence at `max_distance`.
+    Linear = 0,
+    /// `min / (min + rolloff * (d - min))`: natural falloff, loud up close.
+    Inverse = 1,
+    /// `(d / min) ^ -rolloff`: steeper than inverse for rolloff > 1.
+    Exponential = 2,
+}
+
+impl Attenuation {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Linear),
+            1 => Some(Self::Inverse),
+            2 => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+}
+
+/// Per-instance distance attenuation, in game pixels. Replaces rodio's
+/// built-in inverse-square falloff; the spatial sink then only pans.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpatialParams {
+    pub curve: Attenuation,
+    /// Full volume at or below this distance.
+    pub min_distance: f32,
+    /// Silent beyond this distance.
+    pub max_distance: f32,
+    /// Steepness of the curve; 0 disables falloff (until `max_distance`).
+    pub rolloff: f32,
+}
+
+impl SpatialParams {
+    /// Volume multiplier (0..=1) for a source `distance` pixels from the listener.
+    pub fn gain(&self, distance: f32) -> f32 {
+        let min = self.min_distance.max(0.0);
+        let max = self.max_distance.max(min);
+        if distance > max {
+            return 0.0;
+        }
+        if distance <= min {
+            return 1.0;
+        }
+        let rolloff = self.rolloff.max(0.0);
+        let gain = match self.curve {
+            Attenuation::Linear => 1.0 - rolloff * (distance - min) / (max - min),
+            // min == 0 would make everything past the listener silent
+            Attenuation::Inverse if min <= 0.0 => 1.0 / (1.0 + rolloff * distance),
+            Attenuation::Inverse => min / (min + rolloff * (distance - min)),
+            Attenuation::Exponential if min <= 0.0 => (1.0 + distance).powf(-rolloff),
+            Attenuation::Exponential => (distance / min).powf(-rolloff),
+        };
+        gain.clamp(0.0, 1.0)
+    }
+}
+
+/// A volume ramp on one instance, advanced by the audio thread's mixer tick.
+/// Times are in seconds. Linear unless given a curve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fade {
+    pub from: f32,
+    pub to: f32,
+    pub duration: f32,
+    pub elapsed: f32,
+    pub curve: Curve,
+}
+
+impl Fade {
+    pub fn new(from: f32, to: f32, duration: f32) -> Self {
+        Self { from, to, duration: duration.max(0.0), elapsed: 0.0, curve: Curve::default() }
+    }
+
+    /// Shape the ramp with an easing curve.
+    pub fn with_curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Current gain: `from` at the start, `to` once done.
+    pub fn gain(&self) -> f32 {
+        if self.is_done() {
+            return self.to;
+        }
+        self.from + (self.to - self.from) * self.curve.sample(self.elapsed / self.duration)
+    }
+
+    /// Advance by `dt` seconds and return the new gain.
+    pub fn advance(&mut self, dt: f32) -> f32 {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.gain()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// Interval of the audio thread's mixer tick while fades or scheduled stops
+/// are running. Idle otherwise.
+const MIXER_TICK: Duration = Duration::from_millis(10);
+
 /// Commands sent from the main thread to the audio thread.
 pub enum AudioCommand {
     LoadSound { id: u32, data: Vec<u8> },
@@ -63,8 +183,31 @@ pub enum AudioCommand {
         updates: Vec<(u64, f32, f32)>, // (instance_id, source_x, source_y)
         listener_x: f32,
         listener_y: f32,
+        /// Occlusion-enabled instances in `updates` whose line of sight to
+        /// the listener is blocked.
+        occluded: Vec<u64>,
     },
+    /// Use custom distance attenuation for a spatial instance.
+    SetSpatialParams { instance_id: u64, params: SpatialParams },
+    /// Muffle a spatial instance while its line of sight is blocked, or stop
+    /// doing so with `None`.
+    SetOcclusion { instance_id: u64, params: Option<OcclusionParams> },
+    /// Shift a spatial instance's pitch with its motion relative to the
+    /// listener, or stop doing so with `None`.
+    SetDoppler { instance_id: u64, params: Option<DopplerParams> },
+    /// Ramp an instance up from silence (or its current fade level) over `duration` seconds.
+    FadeIn { instance_id: u64, duration: f32, curve: Curve },
+    /// Ramp an instance down to silence over `duration` seconds, then stop it.
+    FadeOut { instance_id: u64, duration: f32, curve: Curve },
+    /// Fade `from_instance` out (then stop it) while fading `to_instance` in.
+    CrossfadeTo { from_instance: u64, to_instance: u64, duration: f32, curve: Curve },
+    /// Stop an instance after `delay` seconds.
+    StopAfter { instance_id: u64, delay: f32 },
     SetBusVolume { bus: AudioBus, volume: f32 },
+    /// Configure one stage of a bus's effect chain. Applies to sounds already playing.
+    SetBusEffect { bus: AudioBus, effect: BusEffect },
+    /// Output gain on top of the master volume while the window is unfocused.
+    SetFocusGain { gain: f32 },
 
     Shutdown,
 }
@@ -81,7 +224,100 @@ pub fn audio_channel() -> (AudioSender, AudioReceiver) {
 struct InstanceMetadata {
     bus: AudioBus,
     base_volume: f32,
+    /// Playback speed set by the game, before any Doppler shift.
+    pitch: f32,
     is_spatial: bool,
+    /// Custom attenuation; None keeps rodio's inverse-square falloff.
+    spatial_params: Option<SpatialParams>,
+    /// Last source and listener positions in game pixels (spatial instances).
+    source: [f32; 2],
+    listener: [f32; 2],
+    /// Volume multiplier from `spatial_params` at the current distance.
+    distance_gain: f32,
+    /// Volume multiplier driven by `fade` (1.0 when not fading).
+    fade_gain: f32,
+    fade: Option<Fade>,
+    /// Stop the instance once `fade` ends.
+    stop_after_fade: bool,
+    /// Seconds until a scheduled stop.
+    stop_in: Option<f32>,
+    /// The instance's own effect chain, ahead of its bus's.
+    effects: Arc<SharedEffects>,
+    /// Set on spatial instances with occlusion enabled.
+    occlusion: Option<Occlusion>,
+    /// Set on spatial instances with Doppler enabled.
+    doppler: Option<Doppler>,
+    /// When `source` and `listener` were last set, for Doppler velocities.
+    placed_at: Instant,
+    /// Last sink volume (`f32` bits), read by the instance's level meter.
+    meter_gain: Arc<AtomicU32>,
+}
+
+impl InstanceMetadata {
+    fn new(bus: AudioBus, base_volume: f32, pitch: f32) -> Self {
+        Self {
+            bus,
+            base_volume,
+            pitch,
+            is_spatial: false,
+            spatial_params: None,
+            source: [0.0, 0.0],
+            listener: [0.0, 0.0],
+            distance_gain: 1.0,
+            fade_gain: 1.0,
+            fade: None,
+            stop_after_fade: false,
+            stop_in: None,
+            effects: Arc::default(),
+            occlusion: None,
+            doppler: None,
+            placed_at: Instant::now(),
+            meter_gain: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Volume for the instance's sink. Also recorded for the level meter, so
+    /// call it whenever the sink volume is set.
+    fn output_volume(&self, bus_volumes: &[f32; 4], master_volume: f32) -> f32 {
+        let occlusion_gain = self.occlusion.map_or(1.0, |o| o.gain());
+        let volume = self.base_volume
+            * self.distance_gain
+            * self.fade_gain
+            * occlusion_gain
+            * bus_volumes[self.bus as usize]
+            * master_volume;
+        self.meter_gain.store(volume.to_bits(), Ordering::Relaxed);
+        volume
+    }
+
+    fn meter_tap(&self, meters: &Arc<LevelMeters>) -> MeterTap {
+        MeterTap { meters: meters.clone(), bus: self.bus as usize, gain: self.meter_gain.clone() }
+    }
+
+    /// Start a fade to `to` from the current fade level, or from `from` when
+    /// not already fading (so an interrupted fade doesn't jump).
+    fn start_fade(&mut self, from: f32, to: f32, duration: f32, curve: Curve, stop: bool) {
+        let from = if self.fade.is_some() { self.fade_gain } else { from };
+        let fade = Fade::new(from, to, duration).with_curve(curve);
+        self.fade_gain = fade.gain();
+        self.fade = Some(fade);
+        self.stop_after_fade = stop;
+    }
+
+    /// Playback speed for the instance's sink: its pitch times the Doppler shift.
+    fn speed(&self) -> f32 {
+        self.pitch * self.doppler.map_or(1.0, |d| d.shift)
+    }
+
+    /// Set the instance's low-pass to the occlusion's current cutoff.
+    fn apply_occlusion_filter(&self) {
+        let cutoff_hz = self.occlusion.map_or(0.0, |o| o.cutoff_hz());
+        self.effects.set(BusEffect::LowPass { cutoff_hz });
+    }
+
+    fn is_ticking(&self) -> bool {
+        self.fade.is_some() || self.stop_in.is_some() || self.occlusion.is_some_and(|o| !o.is_settled())
+    }
 }
 
 /// Scale factor to convert game pixel coordinates to audio-space coordinates.
@@ -90,8 +326,54 @@ struct InstanceMetadata {
 /// With SPATIAL_SCALE = 0.01, 100 game pixels = 1.0 audio unit.
 const SPATIAL_SCALE: f32 = 0.01;
 
+/// Half the distance between the listener's ears, in audio units.
+const EAR_OFFSET: f32 = 0.1;
+
+/// Position a spatial sink from the instance's stored source and listener.
+/// With custom attenuation the emitter is placed one audio unit from the
+/// listener in the source's direction, so rodio pans without attenuating, and
+/// the distance gain is recomputed.
+fn place_spatial(sink: &rodio::SpatialSink, meta: &mut InstanceMetadata) {
+    let [sx, sy] = meta.source;
+    let [lx, ly] = meta.listener;
+    let (ex, ey) = match meta.spatial_params {
+        Some(params) => {
+            let (dx, dy) = (sx - lx, sy - ly);
+            let distance = (dx * dx + dy * dy).sqrt();
+            meta.distance_gain = params.gain(distance);
+            // A source on the listener plays centered
+            let (nx, ny) = if distance > 1e-3 { (dx / distance, dy / distance) } else { (0.0, 1.0) };
+            ((lx * SPATIAL_SCALE) + nx, (ly * SPATIAL_SCALE) + ny)
+        }
+        None => {
+            meta.distance_gain = 1.0;
+            (sx * SPATIAL_SCALE, sy * SPATIAL_SCALE)
+        }
+    };
+    let (lx, ly) = (lx * SPATIAL_SCALE, ly * SPATIAL_SCALE);
+    sink.set_emitter_position([ex, ey, 0.0]);
+    sink.set_left_ear_position([lx - EAR_OFFSET, ly, 0.0]);
+    sink.set_right_ear_position([lx + EAR_OFFSET, ly, 0.0]);
+}
+
+/// Feedback of the per-sound reverb from `PlaySoundEx`.
+const SOUND_REVERB_DECAY: f32 = 0.5;
+
+/// Run a source through the per-sound effects, then its bus's chain, then
+/// the level meter.
+fn with_effects<S: Source<Item = f32>>(
+    source: S,
+    sound: &Arc<SharedEffects>,
+    bus: &Arc<SharedEffects>,
+    tap: MeterTap,
+) -> MeterSource<EffectSource<EffectSource<S>>> {
+    let source = EffectSource::new(source, sound.clone());
+    MeterSource::new(EffectSource::new(source, bus.clone()), tap)
+}
+
 /// Spawn the audio thread. It owns the rodio OutputStream and processes commands.
-pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
+/// Output levels and the number of playing sounds are published to `levels`.
+pub fn start_audio_thread(rx: AudioReceiver, levels: Arc<Mutex<AudioLevels>>) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
         // Initialize rodio output stream
         let stream_handle = match rodio::OutputStream::try_default() {
@@ -123,14 +405,56 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
         // Volume state
         let mut master_volume: f32 = 1.0;
         let mut bus_volumes: [f32; 4] = [1.0, 1.0, 1.0, 1.0]; // Sfx, Music, Ambient, Voice
+        let bus_effects: [Arc<SharedEffects>; 4] = std::array::from_fn(|_| Arc::default());
+        let meters = Arc::new(LevelMeters::default());
+        let mut current_levels = AudioLevels::default();
+        // Output gain applied while the window is unfocused (see platform::focus)
+        let mut focus_gain: f32 = 1.0;
 
         // Cleanup counter for periodic sink cleanup
         let mut cleanup_counter = 0;
 
+        // Mixer tick: wake every MIXER_TICK while fades or scheduled stops run,
+        // or while there is signal to meter
+        let mut last_tick = Instant::now();
+
         loop {
-            let cmd = match rx.recv() {
-                Ok(cmd) => cmd,
-                Err(_) => break, // Channel closed
+            let ticking = instance_metadata.values().any(InstanceMetadata::is_ticking)
+                || current_levels.is_audible()
+                || sinks.values().any(|sink| !sink.empty())
+                || spatial_sinks.values().any(|sink| !sink.empty());
+            let cmd = if ticking {
+                match rx.recv_timeout(MIXER_TICK) {
+                    Ok(cmd) => Some(cmd),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            } else {
+                match rx.recv() {
+                    Ok(cmd) => Some(cmd),
+                    Err(_) => break, // Channel closed
+                }
+            };
+
+            let now = Instant::now();
+            if ticking {
+                let dt = now.duration_since(last_tick).as_secs_f32();
+                tick_instances(
+                    dt,
+                    &mut sinks,
+                    &mut spatial_sinks,
+                    &mut instance_metadata,
+                    &bus_volumes,
+                    master_volume * focus_gain,
+                );
+                current_levels.update(&meters.drain(), dt);
+                current_levels.playing = playing_count(&sinks, &spatial_sinks);
+                *levels.lock().unwrap_or_else(|e| e.into_inner()) = current_levels;
+            }
+            last_tick = now;
+
+            let Some(cmd) = cmd else {
+                continue;
             };
 
             match cmd {
@@ -150,7 +474,7 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
 
                 AudioCommand::SetMasterVolume { volume } => {
                     master_volume = volume;
-                    update_all_volumes(&sinks, &spatial_sinks, &instance_metadata, &bus_volumes, master_volume);
+                    update_all_volumes(&sinks, &spatial_sinks, &instance_metadata, &bus_volumes, master_volume * focus_gain);
                 }
 
                 // Phase 20: New instance-based commands
@@ -163,8 +487,8 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                     pan,
                     pitch,
                     low_pass_freq,
-                    reverb_mix: _,
-                    reverb_delay_ms: _,
+                    reverb_mix,
+                    reverb_delay_ms,
                 } => {
                     if let Some(data) = sounds.get(&sound_id) {
                         match rodio::Sink::try_new(&stream_handle) {
@@ -182,15 +506,28 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                                             rodio::source::Source::low_pass(source, 20000) // No filtering
                                         };
 
-                                        // Note: rodio's reverb requires Clone, which BltFilter doesn't implement.
-                                        // For simplicity, skip reverb implementation for now (or use buffered source).
-                                        // In production, we'd buffer the source first.
+                                        let reverb = if reverb_mix > 0.0 {
+                                            EffectSettings::with(BusEffect::Reverb {
+                                                mix: reverb_mix,
+                                                delay_ms: reverb_delay_ms as f32,
+                                                decay: SOUND_REVERB_DECAY,
+                                            })
+                                        } else {
+                                            EffectSettings::default()
+                                        };
+                                        let bus_chain = &bus_effects[bus as usize];
+                                        let meta = InstanceMetadata {
+                                            effects: Arc::new(SharedEffects::new(reverb)),
+                                            ..InstanceMetadata::new(bus, volume, pitch)
+                                        };
+                                        let tap = meta.meter_tap(&meters);
 
-                                        // Apply looping
+                                        // Apply looping (before the effects, so echoes carry over the loop point)
                                         if looping {
-                                            sink.append(rodio::source::Source::repeat_infinite(source));
+                                            let source = rodio::source::Source::repeat_infinite(source);
+                                            sink.append(with_effects(source, &meta.effects, bus_chain, tap));
                                         } else {
-                                            sink.append(source);
+                                            sink.append(with_effects(source, &meta.effects, bus_chain, tap));
                                         }
 
                                         // Apply pan by adjusting left/right channel volumes
@@ -199,7 +536,7 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                                         // so pan is computed but not applied. Store for future reference.
                                         let (_left, _right) = pan_to_volumes(pan);
 
-                                        sink.set_volume(volume * bus_volumes[bus as usize] * master_volume);
+                                        sink.set_volume(meta.output_volume(&bus_volumes, master_volume * focus_gain));
 
                                         // Apply pitch
                                         sink.set_speed(pitch);
@@ -207,11 +544,7 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                                         sink.play();
 
                                         // Store metadata
-                                        instance_metadata.insert(instance_id, InstanceMetadata {
-                                            bus,
-                                            base_volume: volume,
-                                            is_spatial: false,
-                                        });
+                                        instance_metadata.insert(instance_id, meta);
 
                                         sinks.insert(instance_id, sink);
                                     }
@@ -250,28 +583,34 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                         match rodio::SpatialSink::try_new(
                             &stream_handle,
                             [sx, sy, 0.0],
-                            [lx - 0.1, ly, 0.0], // Left ear
-                            [lx + 0.1, ly, 0.0], // Right ear
+                            [lx - EAR_OFFSET, ly, 0.0], // Left ear
+                            [lx + EAR_OFFSET, ly, 0.0], // Right ear
                         ) {
                             Ok(sink) => {
                                 let cursor = Cursor::new((**data).clone());
                                 match rodio::Decoder::new(cursor) {
                                     Ok(source) => {
+                                        let source = source.convert_samples::<f32>();
+                                        let bus_chain = &bus_effects[bus as usize];
+                                        let meta = InstanceMetadata {
+                                            is_spatial: true,
+                                            source: [source_x, source_y],
+                                            listener: [listener_x, listener_y],
+                                            ..InstanceMetadata::new(bus, volume, pitch)
+                                        };
+                                        let tap = meta.meter_tap(&meters);
                                         if looping {
-                                            sink.append(rodio::source::Source::repeat_infinite(source));
+                                            let source = rodio::source::Source::repeat_infinite(source);
+                                            sink.append(with_effects(source, &meta.effects, bus_chain, tap));
                                         } else {
-                                            sink.append(source);
+                                            sink.append(with_effects(source, &meta.effects, bus_chain, tap));
                                         }
 
-                                        sink.set_volume(volume * bus_volumes[bus as usize] * master_volume);
+                                        sink.set_volume(meta.output_volume(&bus_volumes, master_volume * focus_gain));
                                         sink.set_speed(pitch);
                                         sink.play();
 
-                                        instance_metadata.insert(instance_id, InstanceMetadata {
-                                            bus,
-                                            base_volume: volume,
-                                            is_spatial: true,
-                                        });
+                                        instance_metadata.insert(instance_id, meta);
 
                                         spatial_sinks.insert(instance_id, sink);
                                     }
@@ -300,7 +639,7 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                 AudioCommand::SetInstanceVolume { instance_id, volume } => {
                     if let Some(metadata) = instance_metadata.get_mut(&instance_id) {
                         metadata.base_volume = volume;
-                        let final_volume = volume * bus_volumes[metadata.bus as usize] * master_volume;
+                        let final_volume = metadata.output_volume(&bus_volumes, master_volume * focus_gain);
 
                         if metadata.is_spatial {
                             if let Some(sink) = spatial_sinks.get(&instance_id) {
@@ -315,10 +654,11 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                 }
 
                 AudioCommand::SetInstancePitch { instance_id, pitch } => {
-                    if let Some(metadata) = instance_metadata.get(&instance_id) {
+                    if let Some(metadata) = instance_metadata.get_mut(&instance_id) {
+                        metadata.pitch = pitch;
                         if metadata.is_spatial {
                             if let Some(sink) = spatial_sinks.get(&instance_id) {
-                                sink.set_speed(pitch);
+                                sink.set_speed(metadata.speed());
                             }
                         } else {
                             if let Some(sink) = sinks.get(&instance_id) {
@@ -328,26 +668,126 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
                     }
                 }
 
-                AudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y } => {
-                    let lx = listener_x * SPATIAL_SCALE;
-                    let ly = listener_y * SPATIAL_SCALE;
+                AudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y, occluded } => {
                     for (instance_id, source_x, source_y) in updates {
-                        if let Some(sink) = spatial_sinks.get(&instance_id) {
-                            sink.set_emitter_position([source_x * SPATIAL_SCALE, source_y * SPATIAL_SCALE, 0.0]);
-                            sink.set_left_ear_position([lx - 0.1, ly, 0.0]);
-                            sink.set_right_ear_position([lx + 0.1, ly, 0.0]);
+                        if let (Some(sink), Some(meta)) =
+                            (spatial_sinks.get(&instance_id), instance_metadata.get_mut(&instance_id))
+                        {
+                            let (from_source, from_listener) = (meta.source, meta.listener);
+                            meta.source = [source_x, source_y];
+                            meta.listener = [listener_x, listener_y];
+                            place_spatial(sink, meta);
+                            if let Some(doppler) = meta.doppler.as_mut() {
+                                let dt = now.duration_since(meta.placed_at).as_secs_f32();
+                                doppler.update(from_source, meta.source, from_listener, meta.listener, dt);
+                                sink.set_speed(meta.speed());
+                            }
+                            meta.placed_at = now;
+                            // Smoothed changes are applied by the mixer tick
+                            let mut occlusion_jumped = false;
+                            if let Some(occlusion) = meta.occlusion.as_mut() {
+                                let amount = occlusion.amount;
+                                occlusion.set_occluded(occluded.contains(&instance_id));
+                                occlusion_jumped = occlusion.amount != amount;
+                            }
+                            if occlusion_jumped {
+                                meta.apply_occlusion_filter();
+                            }
+                            if meta.spatial_params.is_some() || occlusion_jumped {
+                                sink.set_volume(meta.output_volume(&bus_volumes, master_volume * focus_gain));
+                            }
                         }
                     }
                 }
 
+                AudioCommand::SetSpatialParams { instance_id, params } => {
+                    if let (Some(sink), Some(meta)) =
+                        (spatial_sinks.get(&instance_id), instance_metadata.get_mut(&instance_id))
+                    {
+                        meta.spatial_params = Some(params);
+                        place_spatial(sink, meta);
+                        sink.set_volume(meta.output_volume(&bus_volumes, master_volume * focus_gain));
+                    }
+                }
+
+                AudioCommand::SetOcclusion { instance_id, params } => {
+                    if let (Some(sink), Some(meta)) =
+                        (spatial_sinks.get(&instance_id), instance_metadata.get_mut(&instance_id))
+                    {
+                        // Keep the current amount, so retuning an occluded sound doesn't jump
+                        let current = meta.occlusion;
+                        meta.occlusion = params.map(|params| Occlusion { params, ..current.unwrap_or(Occlusion::new(params)) });
+                        meta.apply_occlusion_filter();
+                        sink.set_volume(meta.output_volume(&bus_volumes, master_volume * focus_gain));
+                    }
+                }
+
+                AudioCommand::SetDoppler { instance_id, params } => {
+                    if let (Some(sink), Some(meta)) =
+                        (spatial_sinks.get(&instance_id), instance_metadata.get_mut(&instance_id))
+                    {
+                        // Keep the measured velocities, so retuning a moving sound doesn't jump
+                        let current = meta.doppler;
+                        meta.doppler = params.map(|params| Doppler { params, ..current.unwrap_or(Doppler::new(params)) });
+                        sink.set_speed(meta.speed());
+                    }
+                }
+
+                AudioCommand::FadeIn { instance_id, duration, curve } => {
+                    if let Some(meta) = instance_metadata.get_mut(&instance_id) {
+                        meta.start_fade(0.0, 1.0, duration, curve, false);
+                        let volume = meta.output_volume(&bus_volumes, master_volume * focus_gain);
+                        set_instance_volume(&sinks, &spatial_sinks, instance_id, volume);
+                    }
+                }
+
+                AudioCommand::FadeOut { instance_id, duration, curve } => {
+                    if let Some(meta) = instance_metadata.get_mut(&instance_id) {
+                        meta.start_fade(1.0, 0.0, duration, curve, true);
+                    }
+                }
+
+                AudioCommand::CrossfadeTo { from_instance, to_instance, duration, curve } => {
+                    if let Some(meta) = instance_metadata.get_mut(&from_instance) {
+                        meta.start_fade(1.0, 0.0, duration, curve, true);
+                    }
+                    if let Some(meta) = instance_metadata.get_mut(&to_instance) {
+                        meta.start_fade(0.0, 1.0, duration, curve, false);
+                        let volume = meta.output_volume(&bus_volumes, master_volume * focus_gain);
+                        set_instance_volume(&sinks, &spatial_sinks, to_instance, volume);
+                    }
+                }
+
+                AudioCommand::StopAfter { instance_id, delay } => {
+                    if let Some(meta) = instance_metadata.get_mut(&instance_id) {
+                        meta.stop_in = Some(delay.max(0.0));
+                    }
+                }
+
                 AudioCommand::SetBusVolume { bus, volume } => {
                     bus_volumes[bus as usize] = volume;
-                    update_all_volumes(&sinks, &spatial_sinks, &instance_metadata, &bus_volumes, master_volume);
+                    update_all_volumes(&sinks, &spatial_sinks, &instance_metadata, &bus_volumes, master_volume * focus_gain);
+                }
+
+                AudioCommand::SetBusEffect { bus, effect } => {
+                    bus_effects[bus as usize].set(effect);
+                }
+
+                AudioCommand::SetFocusGain { gain } => {
+                    focus_gain = gain;
+                    update_all_volumes(&sinks, &spatial_sinks, &instance_metadata, &bus_volumes, master_volume * focus_gain);
                 }
 
                 AudioCommand::Shutdown => break,
             }
 
+            // Publish starts and stops right away, even when nothing ticks
+            let playing = playing_count(&sinks, &spatial_sinks);
+            if playing != current_levels.playing {
+                current_levels.playing = playing;
+                *levels.lock().unwrap_or_else(|e| e.into_inner()) = current_levels;
+            }
+
             // Periodic cleanup of finished sinks (every 100 commands)
             cleanup_counter += 1;
             if cleanup_counter >= 100 {
@@ -371,6 +811,13 @@ pub fn start_audio_thread(rx: AudioReceiver) -> std::thread::JoinHandle<()> {
     })
 }
 
+/// Instances whose sink still has sound queued.
+fn playing_count(sinks: &HashMap<u64, rodio::Sink>, spatial_sinks: &HashMap<u64, rodio::SpatialSink>) -> u32 {
+    let plain = sinks.values().filter(|sink| !sink.empty()).count();
+    let spatial = spatial_sinks.values().filter(|sink| !sink.empty()).count();
+    (plain + spatial) as u32
+}
+
 /// Convert pan value (-1.0 to +1.0) to left/right channel volumes.
 /// Pan -1.0 = full left (1.0, 0.0), 0.0 = center (0.707, 0.707), +1.0 = full right (0.0, 1.0)
 fn pan_to_volumes(pan: f32) -> (f32, f32) {
@@ -391,15 +838,78 @@ fn update_all_volumes(
 ) {
     for (id, sink) in sinks {
         if let Some(meta) = metadata.get(id) {
-            let final_volume = meta.base_volume * bus_volumes[meta.bus as usize] * master_volume;
-            sink.set_volume(final_volume);
+            sink.set_volume(meta.output_volume(bus_volumes, master_volume));
         }
     }
 
     for (id, sink) in spatial_sinks {
         if let Some(meta) = metadata.get(id) {
-            let final_volume = meta.base_volume * bus_volumes[meta.bus as usize] * master_volume;
-            sink.set_volume(final_volume);
+            sink.set_volume(meta.output_volume(bus_volumes, master_volume));
+        }
+    }
+}
+
+/// Set the volume of an instance, whichever kind of sink it plays on.
+fn set_instance_volume(
+    sinks: &HashMap<u64, rodio::Sink>,
+    spatial_sinks: &HashMap<u64, rodio::SpatialSink>,
+    instance_id: u64,
+    volume: f32,
+) {
+    if let Some(sink) = sinks.get(&instance_id) {
+        sink.set_volume(volume);
+    } else if let Some(sink) = spatial_sinks.get(&instance_id) {
+        sink.set_volume(volume);
+    }
+}
+
+/// Advance fades, occlusion smoothing and scheduled stops by `dt` seconds,
+/// updating sink volumes and stopping instances whose fade-out or stop delay
+/// has finished.
+fn tick_instances(
+    dt: f32,
+    sinks: &mut HashMap<u64, rodio::Sink>,
+    spatial_sinks: &mut HashMap<u64, rodio::SpatialSink>,
+    metadata: &mut HashMap<u64, InstanceMetadata>,
+    bus_volumes: &[f32; 4],
+    master_volume: f32,
+) {
+    let mut finished = Vec::new();
+    for (&id, meta) in metadata.iter_mut() {
+        if let Some(stop_in) = meta.stop_in.as_mut() {
+            *stop_in -= dt;
+            if *stop_in <= 0.0 {
+                finished.push(id);
+                continue;
+            }
+        }
+        let mut changed = false;
+        if let Some(occlusion) = meta.occlusion.as_mut().filter(|o| !o.is_settled()) {
+            occlusion.advance(dt);
+            meta.apply_occlusion_filter();
+            changed = true;
+        }
+        if let Some(fade) = meta.fade.as_mut() {
+            meta.fade_gain = fade.advance(dt);
+            if fade.is_done() {
+                meta.fade = None;
+                if meta.stop_after_fade {
+                    finished.push(id);
+                    continue;
+                }
+            }
+            changed = true;
+        }
+        if changed {
+            set_instance_volume(sinks, spatial_sinks, id, meta.output_volume(bus_volumes, master_volume));
+        }
+    }
+    for id in finished {
+        if let Some(sink) = sinks.remove(&id) {
+            sink.stop();
+        } else if let Some(sink) = spatial_sinks.remove(&id) {
+            sink.stop();
         }
+        metadata.remove(&id);
     }
 }
@@ -0,0 +1,78 @@
+//! Occlusion of spatial sounds by level geometry.
+//!
+//! The main thread raycasts from the listener to each occlusion-enabled
+//! source on every `UpdateSpatialPositions` and sends whether the line was
+//! blocked. The audio thread eases each instance's [`Occlusion`] toward that
+//! state on its mixer tick, so walls sliding in and out of the way never click.
+//! A fully occluded sound plays at `volume` through a low-pass at
+//! `cutoff_hz`; in between, the gain moves linearly and the cutoff
+//! geometrically from [`OPEN_CUTOFF_HZ`], which sounds even across the sweep.
+
+/// Low-pass cutoff of an unoccluded sound: above hearing, so effectively off.
+pub const OPEN_CUTOFF_HZ: f32 = 20_000.0;
+
+/// How an instance sounds when occluded, as set by `op_set_sound_occlusion`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OcclusionParams {
+    /// Low-pass cutoff when fully occluded.
+    pub cutoff_hz: f32,
+    /// Volume multiplier when fully occluded.
+    pub volume: f32,
+    /// Seconds to move between open and fully occluded. 0 switches at once.
+    pub smoothing: f32,
+}
+
+/// Per-instance occlusion state, kept by the audio thread.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Occlusion {
+    pub params: OcclusionParams,
+    /// 0 = open, 1 = fully occluded.
+    pub amount: f32,
+    /// Where `amount` is heading: 1 while the line of sight is blocked.
+    pub target: f32,
+}
+
+impl Occlusion {
+    pub fn new(params: OcclusionParams) -> Self {
+        Self { params, amount: 0.0, target: 0.0 }
+    }
+
+    /// Record the latest raycast result.
+    pub fn set_occluded(&mut self, occluded: bool) {
+        self.target = if occluded { 1.0 } else { 0.0 };
+        if self.params.smoothing <= 0.0 {
+            self.amount = self.target;
+        }
+    }
+
+    /// Move `amount` toward `target` by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        if self.is_settled() {
+            return;
+        }
+        let step = if self.params.smoothing > 0.0 { dt / self.params.smoothing } else { 1.0 };
+        self.amount = if self.target > self.amount {
+            (self.amount + step).min(self.target)
+        } else {
+            (self.amount - step).max(self.target)
+        };
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.amount == self.target
+    }
+
+    /// Volume multiplier at the current amount.
+    pub fn gain(&self) -> f32 {
+        1.0 + (self.params.volume.clamp(0.0, 1.0) - 1.0) * self.amount
+    }
+
+    /// Low-pass cutoff at the current amount; 0 (filter off) when open.
+    pub fn cutoff_hz(&self) -> f32 {
+        if self.amount <= 0.0 {
+            return 0.0;
+        }
+        let closed = self.params.cutoff_hz.clamp(1.0, OPEN_CUTOFF_HZ);
+        OPEN_CUTOFF_HZ * (closed / OPEN_CUTOFF_HZ).powf(self.amount)
+    }
+}
@@ -0,0 +1,330 @@
+//! Color space conversions, perceptual mixing and palette ramps.
+//!
+//! Colors are sRGB-encoded floats in 0-1 unless a function says otherwise,
+//! matching `Color` on the TS side. Perceptual work (mixing, ramps) happens in
+//! OKLab, where equal steps look like equal changes in lightness and hue;
+//! mixing sRGB values directly darkens and desaturates the midpoints.
+//!
+//! Conversions out of OKLCH reduce chroma until the color fits in sRGB, so
+//! lightness and hue are kept rather than clipping channels independently.
+
+use std::f32::consts::PI;
+
+/// Samples a ramp is resampled to before fitting a cosine palette.
+const FIT_SAMPLES: usize = 32;
+/// Cosine palette frequencies tried per channel: 1/4 to 1 in steps of 1/N.
+const FIT_FREQUENCIES: usize = 20;
+/// Bisection steps when pulling an OKLCH color into the sRGB gamut.
+const GAMUT_STEPS: usize = 20;
+
+/// Decode one sRGB channel to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode one linear-light channel as sRGB.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// RGB to HSV: hue in degrees (0-360), saturation and value 0-1.
+pub fn rgb_to_hsv([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max > 0.0 { delta / max } else { 0.0 };
+    [hue, saturation, max]
+}
+
+/// HSV to RGB. Hue wraps; saturation and value are clamped to 0-1.
+pub fn hsv_to_rgb([h, s, v]: [f32; 3]) -> [f32; 3] {
+    let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    let h = h.rem_euclid(360.0) / 60.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let [r, g, b] = match h as u32 {
+        0 => [c, x, 0.0],
+        1 => [x, c, 0.0],
+        2 => [0.0, c, x],
+        3 => [0.0, x, c],
+        4 => [x, 0.0, c],
+        _ => [c, 0.0, x],
+    };
+    let m = v - c;
+    [r + m, g + m, b + m]
+}
+
+/// sRGB to OKLab (L 0-1, a and b roughly -0.4 to 0.4).
+pub fn srgb_to_oklab(rgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_99 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// OKLab to sRGB, without gamut mapping: channels may fall outside 0-1.
+pub fn oklab_to_srgb_unclamped([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    let linear = [
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ];
+    linear.map(|c| linear_to_srgb(c.abs()).copysign(c))
+}
+
+/// OKLab to sRGB, clamped to 0-1.
+pub fn oklab_to_srgb(lab: [f32; 3]) -> [f32; 3] {
+    oklab_to_srgb_unclamped(lab).map(|c| c.clamp(0.0, 1.0))
+}
+
+/// sRGB to OKLCH: lightness 0-1, chroma 0 to about 0.37, hue in degrees (0-360).
+pub fn srgb_to_oklch(rgb: [f32; 3]) -> [f32; 3] {
+    let [l, a, b] = srgb_to_oklab(rgb);
+    let chroma = a.hypot(b);
+    let hue = if chroma < 1e-6 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    [l, chroma, hue]
+}
+
+/// OKLCH to sRGB. Colors outside the sRGB gamut keep their lightness and hue
+/// and lose chroma until they fit.
+pub fn oklch_to_srgb([l, c, h]: [f32; 3]) -> [f32; 3] {
+    let l = l.clamp(0.0, 1.0);
+    let (sin, cos) = h.to_radians().sin_cos();
+    let at = |chroma: f32| oklab_to_srgb_unclamped([l, chroma * cos, chroma * sin]);
+    let fits = |rgb: [f32; 3]| rgb.iter().all(|v| (-1e-4..=1.0 + 1e-4).contains(v));
+    let mut rgb = at(c.max(0.0));
+    if !fits(rgb) {
+        let (mut lo, mut hi) = (0.0, c.max(0.0));
+        rgb = at(0.0);
+        for _ in 0..GAMUT_STEPS {
+            let mid = (lo + hi) * 0.5;
+            let candidate = at(mid);
+            if fits(candidate) {
+                lo = mid;
+                rgb = candidate;
+            } else {
+                hi = mid;
+            }
+        }
+    }
+    rgb.map(|v| v.clamp(0.0, 1.0))
+}
+
+/// Mix two sRGB colors in OKLab. `t` = 0 gives `a`, 1 gives `b`; alpha mixes linearly.
+pub fn mix_oklab(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    let la = srgb_to_oklab([a[0], a[1], a[2]]);
+    let lb = srgb_to_oklab([b[0], b[1], b[2]]);
+    let lab = [0, 1, 2].map(|i| la[i] + (lb[i] - la[i]) * t);
+    let [r, g, b_] = oklab_to_srgb(lab);
+    [r, g, b_, a[3] + (b[3] - a[3]) * t]
+}
+
+/// `count` colors evenly spaced along `stops` (also evenly spaced), mixed in
+/// OKLab. The first and last colors are the first and last stops.
+pub fn ramp(stops: &[[f32; 4]], count: usize) -> Vec<[f32; 4]> {
+    match stops {
+        [] => Vec::new(),
+        [only] => vec![*only; count],
+        _ => (0..count)
+            .map(|i| {
+                let t = if count > 1 { i as f32 / (count - 1) as f32 } else { 0.0 };
+                let pos = t * (stops.len() - 1) as f32;
+                let seg = (pos as usize).min(stops.len() - 2);
+                mix_oklab(stops[seg], stops[seg + 1], pos - seg as f32)
+            })
+            .collect(),
+    }
+}
+
+/// Cosine palette parameters `[a, b, c, d]` (each RGB) approximating the ramp
+/// through `stops`, for `color = a + b * cos(2π * (c * t + d))` with t in 0-1.
+/// Each channel is a least-squares fit over a sampled ramp, trying
+/// frequencies `c` from 0.25 to 1 (a quarter to a whole period over the ramp;
+/// lower ones fit with huge offsets that blow up just outside 0-1).
+pub fn fit_cosine_palette(stops: &[[f32; 4]]) -> [[f32; 3]; 4] {
+    let samples = ramp(stops, FIT_SAMPLES);
+    let mut palette = [[0.0; 3], [0.0; 3], [0.5; 3], [0.0; 3]];
+    if samples.is_empty() {
+        return palette;
+    }
+    let ts: Vec<f32> = (0..samples.len())
+        .map(|i| i as f32 / (samples.len() - 1).max(1) as f32)
+        .collect();
+    for channel in 0..3 {
+        let values: Vec<f32> = samples.iter().map(|c| c[channel]).collect();
+        let mut best = (f32::INFINITY, [values[0], 0.0, 0.5, 0.0]);
+        for step in FIT_FREQUENCIES / 4..=FIT_FREQUENCIES {
+            let freq = step as f32 / FIT_FREQUENCIES as f32;
+            let Some(fit) = fit_cosine(&ts, &values, freq) else { continue };
+            let error: f32 = ts
+                .iter()
+                .zip(&values)
+                .map(|(&t, &v)| (eval_cosine(fit, t) - v).powi(2))
+                .sum();
+            if error < best.0 {
+                best = (error, fit);
+            }
+        }
+        for (param, value) in palette.iter_mut().zip(best.1) {
+            param[channel] = value;
+        }
+    }
+    palette
+}
+
+/// `[a, b, c, d]` for one channel with `c` fixed, or `None` when the fit is
+/// degenerate.
+fn fit_cosine(ts: &[f32], values: &[f32], freq: f32) -> Option<[f32; 4]> {
+    // Linear in (a, p, q): v ≈ a + p·cos x + q·sin x with x = 2π·c·t
+    let basis = |t: f32| {
+        let x = 2.0 * PI * freq * t;
+        [1.0, x.cos(), x.sin()]
+    };
+    let mut xtx = [[0.0f32; 3]; 3];
+    let mut xty = [0.0f32; 3];
+    for (&t, &v) in ts.iter().zip(values) {
+        let row = basis(t);
+        for i in 0..3 {
+            for j in 0..3 {
+                xtx[i][j] += row[i] * row[j];
+            }
+            xty[i] += row[i] * v;
+        }
+    }
+    let [offset, p, q] = solve3(xtx, xty)?;
+    // p·cos x + q·sin x = amp·cos(x + φ) with φ = atan2(-q, p)
+    let phase = (-q).atan2(p) / (2.0 * PI);
+    Some([offset, p.hypot(q), freq, phase.rem_euclid(1.0)])
+}
+
+fn eval_cosine([a, b, c, d]: [f32; 4], t: f32) -> f32 {
+    a + b * (2.0 * PI * (c * t + d)).cos()
+}
+
+/// Solve a 3×3 linear system by Cramer's rule; `None` when it is singular.
+fn solve3(m: [[f32; 3]; 3], y: [f32; 3]) -> Option<[f32; 3]> {
+    let det = |m: [[f32; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(m);
+    if d.abs() < 1e-9 {
+        return None;
+    }
+    Some([0, 1, 2].map(|col| {
+        let mut mc = m;
+        for row in 0..3 {
+            mc[row][col] = y[row];
+        }
+        det(mc) / d
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f32], b: &[f32], eps: f32) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() <= eps)
+    }
+
+    #[test]
+    fn test_srgb_linear_round_trip() {
+        assert!((srgb_to_linear(0.5) - 0.214_041).abs() < 1e-4);
+        for i in 0..=20 {
+            let c = i as f32 / 20.0;
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_hsv_known_colors_and_round_trip() {
+        assert!(close(&rgb_to_hsv([1.0, 0.0, 0.0]), &[0.0, 1.0, 1.0], 1e-5));
+        assert!(close(&rgb_to_hsv([0.0, 0.5, 0.5]), &[180.0, 1.0, 0.5], 1e-4));
+        assert!(close(&hsv_to_rgb([240.0, 1.0, 1.0]), &[0.0, 0.0, 1.0], 1e-5));
+        assert!(close(&hsv_to_rgb([-120.0, 1.0, 1.0]), &[0.0, 0.0, 1.0], 1e-5));
+        let rgb = [0.2, 0.7, 0.4];
+        assert!(close(&hsv_to_rgb(rgb_to_hsv(rgb)), &rgb, 1e-5));
+    }
+
+    #[test]
+    fn test_oklab_reference_values() {
+        // White and black sit on the L axis
+        assert!(close(&srgb_to_oklab([1.0, 1.0, 1.0]), &[1.0, 0.0, 0.0], 1e-3));
+        assert!(close(&srgb_to_oklab([0.0, 0.0, 0.0]), &[0.0, 0.0, 0.0], 1e-3));
+        // Published OKLCH for #ff0000: 0.628 0.258 29.2°
+        let [l, c, h] = srgb_to_oklch([1.0, 0.0, 0.0]);
+        assert!(close(&[l, c], &[0.628, 0.258], 1e-3));
+        assert!((h - 29.23).abs() < 0.05);
+        let rgb = [0.9, 0.3, 0.6];
+        assert!(close(&oklab_to_srgb(srgb_to_oklab(rgb)), &rgb, 1e-3));
+        assert!(close(&oklch_to_srgb(srgb_to_oklch(rgb)), &rgb, 1e-3));
+    }
+
+    #[test]
+    fn test_oklch_out_of_gamut_keeps_lightness_and_hue() {
+        let rgb = oklch_to_srgb([0.7, 0.5, 140.0]);
+        assert!(rgb.iter().all(|c| (0.0..=1.0).contains(c)));
+        let [l, c, h] = srgb_to_oklch(rgb);
+        assert!((l - 0.7).abs() < 0.01, "lightness {l}");
+        assert!((h - 140.0).abs() < 2.0, "hue {h}");
+        assert!(c < 0.5);
+    }
+
+    #[test]
+    fn test_mix_oklab_midpoint_is_brighter_than_srgb_mix() {
+        let mid = mix_oklab([1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 0.0], 0.5);
+        // A plain sRGB mix gives a muddy (0.5, 0.5, 0); OKLab keeps it light
+        assert!(srgb_to_oklab([mid[0], mid[1], mid[2]])[0] > srgb_to_oklab([0.5, 0.5, 0.0])[0]);
+        assert!((mid[3] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ramp_hits_every_stop() {
+        let stops = [[0.0, 0.0, 0.2, 1.0], [1.0, 0.5, 0.0, 1.0], [1.0, 1.0, 0.9, 1.0]];
+        let colors = ramp(&stops, 5);
+        assert_eq!(colors.len(), 5);
+        assert!(close(&colors[0], &stops[0], 1e-3));
+        assert!(close(&colors[2], &stops[1], 1e-3));
+        assert!(close(&colors[4], &stops[2], 1e-3));
+        assert_eq!(ramp(&stops[..1], 3), vec![stops[0]; 3]);
+        assert!(ramp(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_fit_cosine_palette_reproduces_a_two_stop_ramp() {
+        let stops = [[0.1, 0.0, 0.3, 1.0], [1.0, 0.8, 0.2, 1.0]];
+        let [a, b, c, d] = fit_cosine_palette(&stops);
+        let eval = |t: f32| [0, 1, 2].map(|i| eval_cosine([a[i], b[i], c[i], d[i]], t));
+        let expected = ramp(&stops, 5);
+        for (i, color) in expected.iter().enumerate() {
+            assert!(close(&eval(i as f32 / 4.0), &color[..3], 0.05), "t = {}", i as f32 / 4.0);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Easing curves shared by tweens, light animations, audio fades and camera
+//! moves.
+//!
+//! A [`Curve`] maps progress `t` in 0-1 to an eased value that starts at 0
+//! and ends at 1 (back and elastic easings overshoot in between). Curves are
+//! defined once in a [`CurveStore`] and referenced by ID; consumers copy the
+//! curve out, so the audio thread and renderer never touch the store.
+//!
+//! Preset easings match `runtime/tweening/easing.ts` by name and formula.
+
+use std::f32::consts::PI;
+
+/// Newton iterations when solving a cubic bezier for `t`.
+const BEZIER_NEWTON_STEPS: usize = 8;
+/// Bisection steps when Newton's method stalls on a flat part of the curve.
+const BEZIER_BISECT_STEPS: usize = 24;
+
+/// The preset easing functions, one per TS easing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InQuint,
+    OutQuint,
+    InOutQuint,
+    InSine,
+    OutSine,
+    InOutSine,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InCirc,
+    OutCirc,
+    InOutCirc,
+    InBack,
+    OutBack,
+    InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 31] = [
+        Self::Linear,
+        Self::InQuad, Self::OutQuad, Self::InOutQuad,
+        Self::InCubic, Self::OutCubic, Self::InOutCubic,
+        Self::InQuart, Self::OutQuart, Self::InOutQuart,
+        Self::InQuint, Self::OutQuint, Self::InOutQuint,
+        Self::InSine, Self::OutSine, Self::InOutSine,
+        Self::InExpo, Self::OutExpo, Self::InOutExpo,
+        Self::InCirc, Self::OutCirc, Self::InOutCirc,
+        Self::InBack, Self::OutBack, Self::InOutBack,
+        Self::InElastic, Self::OutElastic, Self::InOutElastic,
+        Self::InBounce, Self::OutBounce, Self::InOutBounce,
+    ];
+
+    /// The TS name of the easing ("linear", "easeInQuad", ...).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::InQuad => "easeInQuad",
+            Self::OutQuad => "easeOutQuad",
+            Self::InOutQuad => "easeInOutQuad",
+            Self::InCubic => "easeInCubic",
+            Self::OutCubic => "easeOutCubic",
+            Self::InOutCubic => "easeInOutCubic",
+            Self::InQuart => "easeInQuart",
+            Self::OutQuart => "easeOutQuart",
+            Self::InOutQuart => "easeInOutQuart",
+            Self::InQuint => "easeInQuint",
+            Self::OutQuint => "easeOutQuint",
+            Self::InOutQuint => "easeInOutQuint",
+            Self::InSine => "easeInSine",
+            Self::OutSine => "easeOutSine",
+            Self::InOutSine => "easeInOutSine",
+            Self::InExpo => "easeInExpo",
+            Self::OutExpo => "easeOutExpo",
+            Self::InOutExpo => "easeInOutExpo",
+            Self::InCirc => "easeInCirc",
+            Self::OutCirc => "easeOutCirc",
+            Self::InOutCirc => "easeInOutCirc",
+            Self::InBack => "easeInBack",
+            Self::OutBack => "easeOutBack",
+            Self::InOutBack => "easeInOutBack",
+            Self::InElastic => "easeInElastic",
+            Self::OutElastic => "easeOutElastic",
+            Self::InOutElastic => "easeInOutElastic",
+            Self::InBounce => "easeInBounce",
+            Self::OutBounce => "easeOutBounce",
+            Self::InOutBounce => "easeInOutBounce",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.name() == name)
+    }
+
+    /// Eased value at `t` (0-1).
+    pub fn eval(self, t: f32) -> f32 {
+        const C1: f32 = 1.70158;
+        const C2: f32 = C1 * 1.525;
+        const C3: f32 = C1 + 1.0;
+        const C4: f32 = 2.0 * PI / 3.0;
+        const C5: f32 = 2.0 * PI / 4.5;
+        match self {
+            Self::Linear => t,
+            Self::InQuad => t * t,
+            Self::OutQuad => t * (2.0 - t),
+            Self::InOutQuad => if t < 0.5 { 2.0 * t * t } else { -1.0 + (4.0 - 2.0 * t) * t },
+            Self::InCubic => t * t * t,
+            Self::OutCubic => (t - 1.0).powi(3) + 1.0,
+            Self::InOutCubic => if t < 0.5 { 4.0 * t * t * t } else { (t - 1.0) * (2.0 * t - 2.0).powi(2) + 1.0 },
+            Self::InQuart => t.powi(4),
+            Self::OutQuart => 1.0 - (t - 1.0).powi(4),
+            Self::InOutQuart => if t < 0.5 { 8.0 * t.powi(4) } else { 1.0 - 8.0 * (t - 1.0).powi(4) },
+            Self::InQuint => t.powi(5),
+            Self::OutQuint => 1.0 + (t - 1.0).powi(5),
+            Self::InOutQuint => if t < 0.5 { 16.0 * t.powi(5) } else { 1.0 + 16.0 * (t - 1.0).powi(5) },
+            Self::InSine => 1.0 - (t * PI / 2.0).cos(),
+            Self::OutSine => (t * PI / 2.0).sin(),
+            Self::InOutSine => -((PI * t).cos() - 1.0) / 2.0,
+            Self::InExpo => if t == 0.0 { 0.0 } else { 2f32.powf(10.0 * t - 10.0) },
+            Self::OutExpo => if t == 1.0 { 1.0 } else { 1.0 - 2f32.powf(-10.0 * t) },
+            Self::InOutExpo => match t {
+                0.0 => 0.0,
+                1.0 => 1.0,
+                t if t < 0.5 => 2f32.powf(20.0 * t - 10.0) / 2.0,
+                t => (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0,
+            },
+            Self::InCirc => 1.0 - (1.0 - t * t).max(0.0).sqrt(),
+            Self::OutCirc => (1.0 - (t - 1.0).powi(2)).max(0.0).sqrt(),
+            Self::InOutCirc => if t < 0.5 {
+                (1.0 - (1.0 - (2.0 * t).powi(2)).max(0.0).sqrt()) / 2.0
+            } else {
+                ((1.0 - (-2.0 * t + 2.0).powi(2)).max(0.0).sqrt() + 1.0) / 2.0
+            },
+            Self::InBack => C3 * t * t * t - C1 * t * t,
+            Self::OutBack => 1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2),
+            Self::InOutBack => if t < 0.5 {
+                (2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2) / 2.0
+            } else {
+                ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+            },
+            Self::InElastic => match t {
+                0.0 => 0.0,
+                1.0 => 1.0,
+                t => -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * C4).sin(),
+            },
+            Self::OutElastic => match t {
+                0.0 => 0.0,
+                1.0 => 1.0,
+                t => 2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0,
+            },
+            Self::InOutElastic => match t {
+                0.0 => 0.0,
+                1.0 => 1.0,
+                t if t < 0.5 => -(2f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0,
+                t => 2f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin() / 2.0 + 1.0,
+            },
+            Self::InBounce => 1.0 - out_bounce(1.0 - t),
+            Self::OutBounce => out_bounce(t),
+            Self::InOutBounce => if t < 0.5 {
+                (1.0 - out_bounce(1.0 - 2.0 * t)) / 2.0
+            } else {
+                (1.0 + out_bounce(2.0 * t - 1.0)) / 2.0
+            },
+        }
+    }
+}
+
+fn out_bounce(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+/// An easing curve: a preset, or a cubic bezier from (0, 0) to (1, 1) with
+/// two control points, as in CSS `cubic-bezier(x1, y1, x2, y2)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Easing(Easing),
+    /// Control x values are clamped to 0-1 so the curve is a function of
+    /// `t`; y values may leave 0-1 to overshoot.
+    Bezier { x1: f32, y1: f32, x2: f32, y2: f32 },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Easing(Easing::Linear)
+    }
+}
+
+impl Curve {
+    pub fn bezier(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Curve::Bezier { x1: x1.clamp(0.0, 1.0), y1, x2: x2.clamp(0.0, 1.0), y2 }
+    }
+
+    /// Eased value at `t`, clamped to 0-1.
+    pub fn sample(&self, t: f32) -> f32 {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        match *self {
+            Curve::Easing(easing) => easing.eval(t),
+            Curve::Bezier { x1, y1, x2, y2 } => {
+                let s = solve_bezier_x(x1, x2, t);
+                bezier(y1, y2, s)
+            }
+        }
+    }
+}
+
+/// One coordinate of a cubic bezier from 0 to 1 with control values `a`, `b`.
+fn bezier(a: f32, b: f32, s: f32) -> f32 {
+    let u = 1.0 - s;
+    3.0 * u * u * s * a + 3.0 * u * s * s * b + s * s * s
+}
+
+fn bezier_slope(a: f32, b: f32, s: f32) -> f32 {
+    let u = 1.0 - s;
+    3.0 * u * u * a + 6.0 * u * s * (b - a) + 3.0 * s * s * (1.0 - b)
+}
+
+/// Bezier parameter where the x coordinate equals `x`.
+fn solve_bezier_x(x1: f32, x2: f32, x: f32) -> f32 {
+    let mut s = x;
+    for _ in 0..BEZIER_NEWTON_STEPS {
+        let err = bezier(x1, x2, s) - x;
+        if err.abs() < 1e-6 {
+            return s;
+        }
+        let slope = bezier_slope(x1, x2, s);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        s = (s - err / slope).clamp(0.0, 1.0);
+    }
+    // x(s) is monotonic with control x values in 0-1
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    for _ in 0..BEZIER_BISECT_STEPS {
+        let mid = (lo + hi) / 2.0;
+        if bezier(x1, x2, mid) < x {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Curves defined by scripts, referenced by ID (from 1).
+#[derive(Debug, Default)]
+pub struct CurveStore {
+    curves: Vec<Curve>,
+}
+
+impl CurveStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a curve and return its ID.
+    pub fn define(&mut self, curve: Curve) -> u32 {
+        self.curves.push(curve);
+        self.curves.len() as u32
+    }
+
+    /// The curve with this ID; 0 and unknown IDs are None.
+    pub fn get(&self, id: u32) -> Option<Curve> {
+        self.curves.get((id as usize).checked_sub(1)?).copied()
+    }
+
+    /// The curve with this ID, or linear for 0 and unknown IDs.
+    pub fn get_or_linear(&self, id: u32) -> Curve {
+        self.get(id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easings_start_at_zero_and_end_at_one() {
+        for easing in Easing::ALL {
+            assert!(easing.eval(0.0).abs() < 1e-5, "{} at 0", easing.name());
+            assert!((easing.eval(1.0) - 1.0).abs() < 1e-5, "{} at 1", easing.name());
+            assert_eq!(Easing::from_name(easing.name()), Some(easing));
+        }
+        assert_eq!(Easing::from_name("easeSideways"), None);
+    }
+
+    #[test]
+    fn test_easing_values_match_ts_formulas() {
+        assert!((Easing::OutQuad.eval(0.5) - 0.75).abs() < 1e-6);
+        assert!((Easing::InOutCubic.eval(0.25) - 0.0625).abs() < 1e-6);
+        assert!((Easing::OutBounce.eval(0.5) - 0.765625).abs() < 1e-5);
+        assert!(Easing::OutBack.eval(0.7) > 1.0);
+    }
+
+    #[test]
+    fn test_bezier_matches_known_points() {
+        let linear = Curve::bezier(0.25, 0.25, 0.75, 0.75);
+        for t in [0.0, 0.2, 0.5, 0.9, 1.0] {
+            assert!((linear.sample(t) - t).abs() < 1e-4);
+        }
+        // CSS "ease" is slow at the start and ends exactly at 1
+        let ease = Curve::bezier(0.25, 0.1, 0.25, 1.0);
+        assert!((ease.sample(0.5) - 0.8024).abs() < 1e-3);
+        assert_eq!(ease.sample(1.0), 1.0);
+        // Steep ends don't break the solver
+        let steep = Curve::bezier(1.0, 0.0, 0.0, 1.0);
+        assert!((steep.sample(0.5) - 0.5).abs() < 1e-3);
+        assert!(steep.sample(0.1) < 0.05);
+    }
+
+    #[test]
+    fn test_sample_clamps_t() {
+        let curve = Curve::Easing(Easing::InQuad);
+        assert_eq!(curve.sample(-1.0), 0.0);
+        assert_eq!(curve.sample(2.0), 1.0);
+        assert_eq!(curve.sample(f32::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_store_ids_start_at_one() {
+        let mut store = CurveStore::new();
+        let id = store.define(Curve::Easing(Easing::OutSine));
+        assert_eq!(id, 1);
+        assert_eq!(store.get(id), Some(Curve::Easing(Easing::OutSine)));
+        assert_eq!(store.get(0), None);
+        assert_eq!(store.get_or_linear(7), Curve::Easing(Easing::Linear));
+    }
+}
@@ -0,0 +1,387 @@
+//! Locale-aware number and duration formatting for score displays and timers.
+//!
+//! An ICU-lite: a small table of locales with their decimal and group
+//! separators, grouping style and percent pattern. Formatting gives the same
+//! output on every platform, whatever ICU data the embedded V8 was built
+//! with. Unknown locales fall back to their language ("de-AT" to "de"),
+//! then to English.
+//!
+//! Option names and rounding follow `Intl.NumberFormat`: fractions round
+//! half away from zero, and trailing zeros are trimmed down to the minimum
+//! fraction digits.
+
+/// How the digits of the integer part are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Groups of three: 1,234,567.
+    Thousands,
+    /// Three, then groups of two: 12,34,567 (Indian numbering).
+    Indian,
+}
+
+/// Number formatting conventions for one locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub tag: &'static str,
+    pub decimal: char,
+    pub group: char,
+    pub grouping: Grouping,
+    /// Integer digits needed before grouping applies: 4 in most locales,
+    /// 5 where "1234" stays ungrouped but "12 345" does not.
+    pub min_grouping_digits: usize,
+    pub minus: char,
+    /// Percent pattern; `#` stands for the number.
+    pub percent: &'static str,
+}
+
+const fn locale(
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    grouping: Grouping,
+    min_grouping_digits: usize,
+    minus: char,
+    percent: &'static str,
+) -> Locale {
+    Locale { tag, decimal, group, grouping, min_grouping_digits, minus, percent }
+}
+
+const NBSP: char = '\u{a0}';
+const NARROW_NBSP: char = '\u{202f}';
+
+/// The built-in locales. The first entry is the fallback.
+pub const LOCALES: &[Locale] = &[
+    locale("en", '.', ',', Grouping::Thousands, 4, '-', "#%"),
+    locale("en-IN", '.', ',', Grouping::Indian, 4, '-', "#%"),
+    locale("hi", '.', ',', Grouping::Indian, 4, '-', "#%"),
+    locale("de", ',', '.', Grouping::Thousands, 4, '-', "#\u{a0}%"),
+    locale("de-CH", '.', '’', Grouping::Thousands, 4, '-', "#%"),
+    locale("fr", ',', NARROW_NBSP, Grouping::Thousands, 4, '-', "#\u{202f}%"),
+    locale("es", ',', '.', Grouping::Thousands, 5, '-', "#\u{a0}%"),
+    locale("it", ',', '.', Grouping::Thousands, 4, '-', "#%"),
+    locale("pt", ',', '.', Grouping::Thousands, 4, '-', "#%"),
+    locale("pt-PT", ',', NBSP, Grouping::Thousands, 5, '-', "#%"),
+    locale("nl", ',', '.', Grouping::Thousands, 4, '-', "#%"),
+    locale("ru", ',', NBSP, Grouping::Thousands, 4, '-', "#\u{a0}%"),
+    locale("pl", ',', NBSP, Grouping::Thousands, 5, '-', "#%"),
+    locale("sv", ',', NBSP, Grouping::Thousands, 4, '\u{2212}', "#\u{a0}%"),
+    locale("tr", ',', '.', Grouping::Thousands, 4, '-', "%#"),
+    locale("ja", '.', ',', Grouping::Thousands, 4, '-', "#%"),
+    locale("zh", '.', ',', Grouping::Thousands, 4, '-', "#%"),
+    locale("ko", '.', ',', Grouping::Thousands, 4, '-', "#%"),
+];
+
+/// Look up a BCP 47 tag ("en-US", "pt_PT"). Falls back to the language,
+/// then to English.
+pub fn find_locale(tag: &str) -> &'static Locale {
+    let tag = tag.replace('_', "-");
+    let find = |t: &str| LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(t));
+    let language = tag.split('-').next().unwrap_or("");
+    find(&tag).or_else(|| find(language)).unwrap_or(&LOCALES[0])
+}
+
+/// Plain number or percentage (the value times 100).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    Decimal,
+    Percent,
+}
+
+/// When to show the sign. Values that round to zero never get one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignDisplay {
+    /// Minus for negatives only.
+    Auto,
+    /// Plus for positives too ("+12" damage numbers).
+    Always,
+    /// No sign at all.
+    Never,
+}
+
+/// Options for [`format_number`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub style: NumberStyle,
+    pub min_fraction_digits: usize,
+    pub max_fraction_digits: usize,
+    pub use_grouping: bool,
+    pub sign: SignDisplay,
+}
+
+/// Fraction digits are capped like `Intl.NumberFormat`'s original limit.
+const MAX_FRACTION_DIGITS: usize = 20;
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            style: NumberStyle::Decimal,
+            min_fraction_digits: 0,
+            max_fraction_digits: 3,
+            use_grouping: true,
+            sign: SignDisplay::Auto,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Parse `Intl.NumberFormat`-style options: `style` ("decimal" or
+    /// "percent"), `minimumFractionDigits`, `maximumFractionDigits`,
+    /// `useGrouping` and `signDisplay` ("auto", "always", "never"). Missing
+    /// or invalid fields keep their defaults; percent defaults to 0 fraction
+    /// digits.
+    pub fn from_json(src: &str) -> Self {
+        let mut fmt = Self::default();
+        let Ok(opts) = crate::json::JsonValue::parse(src) else {
+            return fmt;
+        };
+        if opts.str_of("style") == Some("percent") {
+            fmt.style = NumberStyle::Percent;
+            fmt.max_fraction_digits = 0;
+        }
+        let digits = |key: &str| {
+            opts.get(key)
+                .and_then(|v| v.as_f64())
+                .map(|d| (d.max(0.0) as usize).min(MAX_FRACTION_DIGITS))
+        };
+        if let Some(min) = digits("minimumFractionDigits") {
+            fmt.min_fraction_digits = min;
+        }
+        match digits("maximumFractionDigits") {
+            Some(max) => fmt.max_fraction_digits = max.max(fmt.min_fraction_digits),
+            None => fmt.max_fraction_digits = fmt.max_fraction_digits.max(fmt.min_fraction_digits),
+        }
+        if let Some(grouping) = opts.get("useGrouping").and_then(|v| v.as_bool()) {
+            fmt.use_grouping = grouping;
+        }
+        fmt.sign = match opts.str_of("signDisplay") {
+            Some("always") => SignDisplay::Always,
+            Some("never") => SignDisplay::Never,
+            _ => SignDisplay::Auto,
+        };
+        fmt
+    }
+}
+
+/// Format a non-negative finite value with `digits` fraction digits,
+/// rounding ties away from zero.
+fn round_fixed(v: f64, digits: usize) -> String {
+    // Float formatting breaks exact ties to even (2.5 -> "2"); round them up instead.
+    let pow = 10f64.powi(digits as i32);
+    let scaled = v * pow;
+    if scaled < 9.0e15 && scaled - scaled.floor() == 0.5 {
+        return format!("{:.digits$}", (scaled.floor() + 1.0) / pow);
+    }
+    format!("{v:.digits$}")
+}
+
+/// Insert the locale's group separator into a string of integer digits.
+fn group_digits(digits: &str, locale: &Locale) -> String {
+    if digits.len() < locale.min_grouping_digits {
+        return digits.to_string();
+    }
+    let (head, last3) = digits.split_at(digits.len().saturating_sub(3));
+    let size = match locale.grouping {
+        Grouping::Thousands => 3,
+        Grouping::Indian => 2,
+    };
+    let mut groups: Vec<&str> = Vec::new();
+    let mut rest = head;
+    while !rest.is_empty() {
+        let (more, group) = rest.split_at(rest.len().saturating_sub(size));
+        groups.push(group);
+        rest = more;
+    }
+    groups.reverse();
+    groups.push(last3);
+    groups.join(&locale.group.to_string())
+}
+
+/// Format a number for display in a locale.
+pub fn format_number(value: f64, locale: &Locale, fmt: &NumberFormat) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    let value = match fmt.style {
+        NumberStyle::Decimal => value,
+        NumberStyle::Percent => value * 100.0,
+    };
+
+    let (body, is_zero) = if value.is_infinite() {
+        ("∞".to_string(), false)
+    } else {
+        let fixed = round_fixed(value.abs(), fmt.max_fraction_digits);
+        let is_zero = fixed.bytes().all(|b| b == b'0' || b == b'.');
+        let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let keep = frac.trim_end_matches('0').len().max(fmt.min_fraction_digits);
+        let int = if fmt.use_grouping { group_digits(int, locale) } else { int.to_string() };
+        let body = if keep > 0 {
+            format!("{int}{}{}", locale.decimal, &frac[..keep])
+        } else {
+            int
+        };
+        (body, is_zero)
+    };
+
+    let sign = match fmt.sign {
+        _ if is_zero => String::new(),
+        SignDisplay::Never => String::new(),
+        _ if value < 0.0 => locale.minus.to_string(),
+        SignDisplay::Always => "+".to_string(),
+        SignDisplay::Auto => String::new(),
+    };
+    match fmt.style {
+        NumberStyle::Decimal => format!("{sign}{body}"),
+        NumberStyle::Percent => format!("{sign}{}", locale.percent.replace('#', &body)),
+    }
+}
+
+/// How [`format_duration`] lays out a time span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// "1:05", "1:02:05" with hours.
+    Clock,
+    /// Clock with hundredths: "1:05.25" (race and speedrun timers).
+    Timer,
+    /// Units: "1h 2m 5s", "45s".
+    Short,
+}
+
+impl DurationStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "clock" => Some(Self::Clock),
+            "timer" => Some(Self::Timer),
+            "short" => Some(Self::Short),
+            _ => None,
+        }
+    }
+}
+
+/// Format a duration in milliseconds. Partial units are truncated, so a
+/// timer never shows a second that has not fully passed. NaN and infinite
+/// spans format as zero.
+pub fn format_duration(ms: f64, style: DurationStyle) -> String {
+    let ms = if ms.is_finite() { ms } else { 0.0 };
+    let total_cs = (ms.abs() / 10.0).floor() as u64;
+    let total_s = total_cs / 100;
+    let (h, m, s, cs) = (total_s / 3600, total_s / 60 % 60, total_s % 60, total_cs % 100);
+
+    let body = match style {
+        DurationStyle::Clock | DurationStyle::Timer => {
+            let clock = if h > 0 { format!("{h}:{m:02}:{s:02}") } else { format!("{m}:{s:02}") };
+            if style == DurationStyle::Timer {
+                format!("{clock}.{cs:02}")
+            } else {
+                clock
+            }
+        }
+        DurationStyle::Short => {
+            let parts: Vec<String> = [(h, "h"), (m, "m"), (s, "s")]
+                .iter()
+                .filter(|(n, _)| *n > 0)
+                .map(|(n, unit)| format!("{n}{unit}"))
+                .collect();
+            if parts.is_empty() { "0s".to_string() } else { parts.join(" ") }
+        }
+    };
+    let shown_zero = match style {
+        DurationStyle::Timer => total_cs == 0,
+        _ => total_s == 0,
+    };
+    if ms < 0.0 && !shown_zero {
+        format!("-{body}")
+    } else {
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(value: f64, tag: &str, opts: &str) -> String {
+        format_number(value, find_locale(tag), &NumberFormat::from_json(opts))
+    }
+
+    #[test]
+    fn test_grouping_and_separators_by_locale() {
+        assert_eq!(fmt(1234567.891, "en-US", "{}"), "1,234,567.891");
+        assert_eq!(fmt(1234567.891, "de", "{}"), "1.234.567,891");
+        assert_eq!(fmt(1234567.5, "fr-FR", "{}"), "1\u{202f}234\u{202f}567,5");
+        assert_eq!(fmt(1234567.0, "de-CH", "{}"), "1’234’567");
+        assert_eq!(fmt(1234567.0, "en-IN", "{}"), "12,34,567");
+        assert_eq!(fmt(999.0, "en", "{}"), "999");
+    }
+
+    #[test]
+    fn test_min_grouping_digits() {
+        assert_eq!(fmt(1234.0, "es", "{}"), "1234");
+        assert_eq!(fmt(12345.0, "es", "{}"), "12.345");
+        assert_eq!(fmt(1234.0, "en", "{}"), "1,234");
+        assert_eq!(fmt(1234.0, "en", r#"{"useGrouping":false}"#), "1234");
+    }
+
+    #[test]
+    fn test_unknown_locales_fall_back() {
+        assert_eq!(find_locale("de_AT").tag, "de");
+        assert_eq!(find_locale("zh-Hans-CN").tag, "zh");
+        assert_eq!(find_locale("PT-pt").tag, "pt-PT");
+        assert_eq!(find_locale("xx").tag, "en");
+        assert_eq!(find_locale("").tag, "en");
+    }
+
+    #[test]
+    fn test_fraction_digits_round_half_away_from_zero() {
+        assert_eq!(fmt(2.5, "en", r#"{"maximumFractionDigits":0}"#), "3");
+        assert_eq!(fmt(-2.5, "en", r#"{"maximumFractionDigits":0}"#), "-3");
+        assert_eq!(fmt(0.125, "en", r#"{"maximumFractionDigits":2}"#), "0.13");
+        assert_eq!(fmt(1.0, "en", r#"{"minimumFractionDigits":2}"#), "1.00");
+        assert_eq!(fmt(1.23456, "en", "{}"), "1.235");
+        assert_eq!(fmt(1.5, "en", r#"{"minimumFractionDigits":2,"maximumFractionDigits":1}"#), "1.50");
+    }
+
+    #[test]
+    fn test_percent_patterns() {
+        assert_eq!(fmt(0.256, "en", r#"{"style":"percent"}"#), "26%");
+        assert_eq!(fmt(0.256, "de", r#"{"style":"percent"}"#), "26\u{a0}%");
+        assert_eq!(fmt(-0.5, "tr", r#"{"style":"percent"}"#), "-%50");
+        assert_eq!(fmt(0.2567, "en", r#"{"style":"percent","maximumFractionDigits":1}"#), "25.7%");
+    }
+
+    #[test]
+    fn test_sign_display() {
+        assert_eq!(fmt(12.0, "en", r#"{"signDisplay":"always"}"#), "+12");
+        assert_eq!(fmt(-12.0, "en", r#"{"signDisplay":"never"}"#), "12");
+        assert_eq!(fmt(0.0, "en", r#"{"signDisplay":"always"}"#), "0");
+        assert_eq!(fmt(-0.0001, "en", "{}"), "0");
+        assert_eq!(fmt(-5.0, "sv", "{}"), "\u{2212}5");
+        assert_eq!(fmt(f64::NAN, "en", "{}"), "NaN");
+        assert_eq!(fmt(f64::NEG_INFINITY, "en", "{}"), "-∞");
+    }
+
+    #[test]
+    fn test_invalid_options_use_defaults() {
+        assert_eq!(NumberFormat::from_json(""), NumberFormat::default());
+        assert_eq!(NumberFormat::from_json("not json"), NumberFormat::default());
+        assert_eq!(NumberFormat::from_json(r#"{"maximumFractionDigits":-4}"#).max_fraction_digits, 0);
+    }
+
+    #[test]
+    fn test_duration_styles() {
+        assert_eq!(format_duration(65_000.0, DurationStyle::Clock), "1:05");
+        assert_eq!(format_duration(3_725_000.0, DurationStyle::Clock), "1:02:05");
+        assert_eq!(format_duration(65_259.0, DurationStyle::Timer), "1:05.25");
+        assert_eq!(format_duration(3_725_000.0, DurationStyle::Short), "1h 2m 5s");
+        assert_eq!(format_duration(45_999.0, DurationStyle::Short), "45s");
+        assert_eq!(format_duration(120_000.0, DurationStyle::Short), "2m");
+        assert_eq!(format_duration(400.0, DurationStyle::Short), "0s");
+    }
+
+    #[test]
+    fn test_negative_and_invalid_durations() {
+        assert_eq!(format_duration(-65_000.0, DurationStyle::Clock), "-1:05");
+        assert_eq!(format_duration(-500.0, DurationStyle::Clock), "0:00");
+        assert_eq!(format_duration(-500.0, DurationStyle::Timer), "-0:00.50");
+        assert_eq!(format_duration(f64::NAN, DurationStyle::Timer), "0:00.00");
+    }
+}
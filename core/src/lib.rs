@@ -1,4 +1,13 @@
+pub mod animation;
+pub mod color;
+pub mod curve;
+pub mod format;
+pub mod json;
 pub mod physics;
+pub mod plugin;
+pub mod procgen;
+pub mod replay;
+pub mod saves;
 pub mod scripting;
 
 #[cfg(feature = "renderer")]
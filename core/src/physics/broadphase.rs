@@ -7,6 +7,7 @@ use super::types::BodyId;
 /// speculative contacts to prevent tunneling.
 pub const SPECULATIVE_MARGIN: f32 = 5.0;
 
+#[derive(Clone)]
 pub struct SpatialHash {
     #[allow(dead_code)]
     cell_size: f32,
@@ -90,3 +91,242 @@ impl SpatialHash {
         pairs
     }
 }
+
+/// Bodies whose AABB spans more cells than this are kept in a list tested by
+/// every query instead of being inserted cell by cell.
+const QUERY_GRID_MAX_CELLS_PER_BODY: i64 = 256;
+
+/// Read-optimized index of body AABBs for scene queries (`query_aabb`,
+/// overlaps, raycasts), rebuilt once per fixed step from the settled
+/// positions. Cells are packed into one sorted array, so a query is a few
+/// hash lookups and slice reads instead of a walk over every body.
+///
+/// Bodies added or moved from game code between steps are recorded as stale
+/// and tested by every query until the next rebuild, so results always match
+/// a full scan. While invalid (during a step, or after too many edits) the
+/// grid answers nothing and callers fall back to a full scan.
+#[derive(Clone)]
+pub struct QueryGrid {
+    cell_size: f32,
+    inv_cell_size: f32,
+    /// `(start, len)` into `entries` per occupied cell.
+    cells: HashMap<(i32, i32), (u32, u32)>,
+    entries: Vec<BodyId>,
+    /// Bodies too large to insert per cell.
+    large: Vec<BodyId>,
+    /// Bodies changed since the last rebuild.
+    stale: Vec<BodyId>,
+    /// Union of every inserted AABB, for clipping rays.
+    bounds: Option<(f32, f32, f32, f32)>,
+    valid: bool,
+}
+
+impl QueryGrid {
+    pub fn new(cell_size: f32) -> Self {
+        let cell_size = if cell_size > 0.0 { cell_size } else { 64.0 };
+        Self {
+            cell_size,
+            inv_cell_size: 1.0 / cell_size,
+            cells: HashMap::new(),
+            entries: Vec::new(),
+            large: Vec::new(),
+            stale: Vec::new(),
+            bounds: None,
+            valid: false,
+        }
+    }
+
+    /// Whether queries can use the grid.
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Stop answering queries until the next `rebuild`.
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+        self.stale.clear();
+    }
+
+    /// Index `bodies` (ID and AABB), replacing the previous contents.
+    pub fn rebuild(&mut self, bodies: impl Iterator<Item = (BodyId, (f32, f32, f32, f32))>) {
+        self.cells.clear();
+        self.entries.clear();
+        self.large.clear();
+        self.stale.clear();
+        self.bounds = None;
+        let mut placed: Vec<((i32, i32), BodyId)> = Vec::new();
+        for (id, (min_x, min_y, max_x, max_y)) in bodies {
+            if !(min_x.is_finite() && min_y.is_finite() && max_x.is_finite() && max_y.is_finite()) {
+                self.large.push(id);
+                continue;
+            }
+            self.bounds = Some(match self.bounds {
+                Some((x0, y0, x1, y1)) => (x0.min(min_x), y0.min(min_y), x1.max(max_x), y1.max(max_y)),
+                None => (min_x, min_y, max_x, max_y),
+            });
+            let [x0, y0, x1, y1] = self.cell_range(min_x, min_y, max_x, max_y);
+            if (x1 as i64 - x0 as i64 + 1) * (y1 as i64 - y0 as i64 + 1) > QUERY_GRID_MAX_CELLS_PER_BODY {
+                self.large.push(id);
+                continue;
+            }
+            for cy in y0..=y1 {
+                for cx in x0..=x1 {
+                    placed.push(((cx, cy), id));
+                }
+            }
+        }
+        placed.sort_unstable();
+        let mut i = 0;
+        while i < placed.len() {
+            let cell = placed[i].0;
+            let start = i;
+            while i < placed.len() && placed[i].0 == cell {
+                self.entries.push(placed[i].1);
+                i += 1;
+            }
+            self.cells.insert(cell, (start as u32, (i - start) as u32));
+        }
+        self.valid = true;
+    }
+
+    /// Record that a body was added, moved or reshaped since the rebuild.
+    /// Past one stale body per indexed entry the grid gives up until the
+    /// next rebuild.
+    pub fn mark_stale(&mut self, id: BodyId) {
+        if !self.valid {
+            return;
+        }
+        if self.stale.len() > self.entries.len() + self.large.len() {
+            self.invalidate();
+            return;
+        }
+        self.stale.push(id);
+    }
+
+    fn cell_range(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> [i32; 4] {
+        [
+            (min_x * self.inv_cell_size).floor() as i32,
+            (min_y * self.inv_cell_size).floor() as i32,
+            (max_x * self.inv_cell_size).floor() as i32,
+            (max_y * self.inv_cell_size).floor() as i32,
+        ]
+    }
+
+    fn cell(&self, cx: i32, cy: i32) -> &[BodyId] {
+        match self.cells.get(&(cx, cy)) {
+            Some(&(start, len)) => &self.entries[start as usize..(start + len) as usize],
+            None => &[],
+        }
+    }
+
+    /// Bodies that may overlap the rectangle, sorted by ID without
+    /// duplicates. `None` while the grid is invalid.
+    pub fn candidates(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Option<Vec<BodyId>> {
+        if !self.valid {
+            return None;
+        }
+        let mut out: Vec<BodyId> = self.large.iter().chain(&self.stale).copied().collect();
+        if let Some((bx0, by0, bx1, by1)) = self.bounds {
+            // Clip to the indexed area so huge query rectangles stay cheap
+            let (min_x, min_y) = (min_x.max(bx0), min_y.max(by0));
+            let (max_x, max_y) = (max_x.min(bx1), max_y.min(by1));
+            if min_x <= max_x && min_y <= max_y {
+                let [x0, y0, x1, y1] = self.cell_range(min_x, min_y, max_x, max_y);
+                let area = (x1 as i64 - x0 as i64 + 1) * (y1 as i64 - y0 as i64 + 1);
+                if area as usize > self.cells.len() {
+                    // Fewer occupied cells than cells in range: scan those
+                    for (&(cx, cy), &(start, len)) in &self.cells {
+                        if cx >= x0 && cx <= x1 && cy >= y0 && cy <= y1 {
+                            out.extend_from_slice(&self.entries[start as usize..(start + len) as usize]);
+                        }
+                    }
+                } else {
+                    for cy in y0..=y1 {
+                        for cx in x0..=x1 {
+                            out.extend_from_slice(self.cell(cx, cy));
+                        }
+                    }
+                }
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+        Some(out)
+    }
+
+    /// Walk the cells along a ray (unit direction `(dx, dy)`) in order of
+    /// distance, calling `visit` with each cell's bodies and the distance at
+    /// which the ray enters the cell, until `visit` returns false or the ray
+    /// passes `max_dist` or leaves the indexed area. Large and stale bodies
+    /// come first, at distance 0. Bodies spanning several cells are visited
+    /// once per cell. Returns false while the grid is invalid.
+    pub fn walk_ray(
+        &self,
+        ox: f32,
+        oy: f32,
+        dx: f32,
+        dy: f32,
+        max_dist: f32,
+        mut visit: impl FnMut(&[BodyId], f32) -> bool,
+    ) -> bool {
+        if !self.valid {
+            return false;
+        }
+        if !visit(&self.large, 0.0) || !visit(&self.stale, 0.0) {
+            return true;
+        }
+        let Some(bounds) = self.bounds else { return true };
+        let Some((t0, t1)) = clip_ray(ox, oy, dx, dy, max_dist, bounds) else { return true };
+
+        let (px, py) = (ox + dx * t0, oy + dy * t0);
+        let (mut cx, mut cy) = ((px * self.inv_cell_size).floor() as i32, (py * self.inv_cell_size).floor() as i32);
+        let step_x = if dx > 0.0 { 1 } else { -1 };
+        let step_y = if dy > 0.0 { 1 } else { -1 };
+        // Distance along the ray to the next vertical / horizontal cell edge
+        let next_edge = |c: i32, step: i32, p: f32, d: f32| {
+            if d == 0.0 {
+                return (f32::INFINITY, f32::INFINITY);
+            }
+            let edge = (c + (step > 0) as i32) as f32 * self.cell_size;
+            (t0 + (edge - p) / d, self.cell_size / d.abs())
+        };
+        let (mut t_max_x, t_delta_x) = next_edge(cx, step_x, px, dx);
+        let (mut t_max_y, t_delta_y) = next_edge(cy, step_y, py, dy);
+        let mut t_enter = t0;
+        while t_enter <= t1 {
+            if !visit(self.cell(cx, cy), t_enter) {
+                break;
+            }
+            if t_max_x < t_max_y {
+                t_enter = t_max_x;
+                t_max_x += t_delta_x;
+                cx += step_x;
+            } else {
+                t_enter = t_max_y;
+                t_max_y += t_delta_y;
+                cy += step_y;
+            }
+        }
+        true
+    }
+}
+
+/// The part `[t0, t1]` of the ray from (ox, oy) along unit `(dx, dy)`, up to
+/// `max_dist`, inside the box, or `None` if it misses it.
+fn clip_ray(ox: f32, oy: f32, dx: f32, dy: f32, max_dist: f32, bounds: (f32, f32, f32, f32)) -> Option<(f32, f32)> {
+    let (min_x, min_y, max_x, max_y) = bounds;
+    let mut t0 = 0.0f32;
+    let mut t1 = max_dist;
+    for (o, d, lo, hi) in [(ox, dx, min_x, max_x), (oy, dy, min_y, max_y)] {
+        if d == 0.0 {
+            if o < lo || o > hi {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((lo - o) / d, (hi - o) / d);
+        t0 = t0.max(a.min(b));
+        t1 = t1.min(a.max(b));
+    }
+    (t0 <= t1).then_some((t0, t1))
+}
@@ -1,4 +1,5 @@
-use super::types::{BodyType, Constraint, RigidBody, SoftConstraintParams};
+use super::types::{BodyType, Constraint, JointMotor, RigidBody, SoftConstraintParams};
+use super::math;
 
 /// Solve all constraints velocity-level for this timestep.
 /// For soft constraints, position error is corrected via velocity bias.
@@ -29,10 +30,82 @@ pub fn solve_constraints(
                 anchor_b,
                 soft,
                 accumulated_impulse,
+                motor,
                 ..
-            } => solve_revolute_velocity_soft(
-                bodies, *body_a, *body_b, *anchor_a, *anchor_b,
-                soft.as_ref(), accumulated_impulse, dt,
+            } => {
+                if let Some(motor) = motor {
+                    solve_revolute_motor(bodies, *body_a, *body_b, motor, dt);
+                }
+                solve_revolute_velocity_soft(
+                    bodies, *body_a, *body_b, *anchor_a, *anchor_b,
+                    soft.as_ref(), accumulated_impulse, dt,
+                );
+            }
+            Constraint::Prismatic {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                axis,
+                lower,
+                upper,
+                motor,
+                accumulated_impulse,
+                ..
+            } => solve_prismatic_velocity(
+                bodies, *body_a, *body_b, *anchor_a, *anchor_b, *axis, (*lower, *upper),
+                motor.as_mut(), accumulated_impulse, dt,
+            ),
+            Constraint::Weld {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                accumulated_impulse,
+                ..
+            } => {
+                solve_angle_lock_velocity(bodies, *body_a, *body_b);
+                solve_revolute_velocity_soft(
+                    bodies, *body_a, *body_b, *anchor_a, *anchor_b,
+                    None, accumulated_impulse, dt,
+                );
+            }
+            Constraint::Rope {
+                body_a,
+                body_b,
+                max_length,
+                anchor_a,
+                anchor_b,
+                accumulated_impulse,
+                ..
+            } => solve_rope_velocity(
+                bodies, *body_a, *body_b, *max_length, *anchor_a, *anchor_b,
+                accumulated_impulse, dt,
+            ),
+            Constraint::Pulley {
+                body_a,
+                body_b,
+                ground_a,
+                ground_b,
+                anchor_a,
+                anchor_b,
+                ratio,
+                accumulated_impulse,
+                ..
+            } => solve_pulley_velocity(
+                bodies, *body_a, *body_b, *ground_a, *ground_b, *anchor_a, *anchor_b,
+                *ratio, accumulated_impulse,
+            ),
+            Constraint::Mouse {
+                body,
+                target,
+                anchor,
+                max_force,
+                soft,
+                accumulated_impulse,
+                ..
+            } => solve_mouse_velocity(
+                bodies, *body, *target, *anchor, *max_force, soft, accumulated_impulse, dt,
             ),
         }
     }
@@ -73,6 +146,633 @@ pub fn solve_constraints_position(
                     solve_revolute_position(bodies, *body_a, *body_b, *anchor_a, *anchor_b);
                 }
             }
+            Constraint::Prismatic {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                axis,
+                reference_angle,
+                lower,
+                upper,
+                ..
+            } => solve_prismatic_position(
+                bodies, *body_a, *body_b, *anchor_a, *anchor_b, *axis, *reference_angle,
+                (*lower, *upper),
+            ),
+            Constraint::Weld {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                reference_angle,
+                ..
+            } => {
+                solve_revolute_position(bodies, *body_a, *body_b, *anchor_a, *anchor_b);
+                correct_relative_angle(bodies, *body_a as usize, *body_b as usize, *reference_angle);
+            }
+            Constraint::Rope {
+                body_a,
+                body_b,
+                max_length,
+                anchor_a,
+                anchor_b,
+                ..
+            } => {
+                // Only correct a stretched rope; slack is free
+                let stretched = anchor_distance(bodies, *body_a, *body_b, *anchor_a, *anchor_b)
+                    .is_some_and(|d| d > *max_length);
+                if stretched {
+                    solve_distance_position(bodies, *body_a, *body_b, *max_length, *anchor_a, *anchor_b);
+                }
+            }
+            Constraint::Pulley {
+                body_a,
+                body_b,
+                ground_a,
+                ground_b,
+                anchor_a,
+                anchor_b,
+                ratio,
+                total_length,
+                ..
+            } => solve_pulley_position(
+                bodies, *body_a, *body_b, *ground_a, *ground_b, *anchor_a, *anchor_b,
+                *ratio, *total_length,
+            ),
+            // Always soft: error is corrected via velocity bias
+            Constraint::Mouse { .. } => {}
+        }
+    }
+}
+
+/// World-space position of a body-local anchor.
+fn world_anchor(body: &RigidBody, anchor: (f32, f32)) -> (f32, f32) {
+    let (sin, cos) = math::sin_cos(body.angle);
+    (
+        body.x + anchor.0 * cos - anchor.1 * sin,
+        body.y + anchor.0 * sin + anchor.1 * cos,
+    )
+}
+
+/// Distance between two bodies' world-space anchors (None if either is gone).
+fn anchor_distance(
+    bodies: &[Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+) -> Option<f32> {
+    let a = bodies.get(id_a as usize)?.as_ref()?;
+    let b = bodies.get(id_b as usize)?.as_ref()?;
+    let (ax, ay) = world_anchor(a, anchor_a);
+    let (bx, by) = world_anchor(b, anchor_b);
+    Some(((bx - ax) * (bx - ax) + (by - ay) * (by - ay)).sqrt())
+}
+
+/// Current rope length of one pulley side: fixed ground point to world anchor.
+/// Returns (length, unit direction from ground to anchor, lever arm from body center).
+fn pulley_side(body: &RigidBody, ground: (f32, f32), anchor: (f32, f32)) -> (f32, (f32, f32), (f32, f32)) {
+    let (px, py) = world_anchor(body, anchor);
+    let (dx, dy) = (px - ground.0, py - ground.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    let dir = if len > 1e-6 { (dx / len, dy / len) } else { (0.0, 0.0) };
+    (len, dir, (px - body.x, py - body.y))
+}
+
+/// Rope length of a pulley for the bodies' current positions:
+/// `length_a + ratio * length_b`. Used to set `total_length` at creation.
+#[allow(clippy::too_many_arguments)]
+pub fn pulley_length(
+    bodies: &[Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    ground_a: (f32, f32),
+    ground_b: (f32, f32),
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    ratio: f32,
+) -> Option<f32> {
+    let a = bodies.get(id_a as usize)?.as_ref()?;
+    let b = bodies.get(id_b as usize)?.as_ref()?;
+    let (len_a, _, _) = pulley_side(a, ground_a, anchor_a);
+    let (len_b, _, _) = pulley_side(b, ground_b, anchor_b);
+    Some(len_a + ratio * len_b)
+}
+
+/// Inverse mass and inertia as seen by joints: zero unless the body is dynamic.
+fn joint_inverse_mass(body: &RigidBody) -> (f32, f32) {
+    if body.body_type == BodyType::Dynamic {
+        (body.inv_mass, body.inv_inertia)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+fn cross(u: (f32, f32), v: (f32, f32)) -> f32 {
+    u.0 * v.1 - u.1 * v.0
+}
+
+fn dot(u: (f32, f32), v: (f32, f32)) -> f32 {
+    u.0 * v.0 + u.1 * v.1
+}
+
+/// Velocities of a joint's two bodies, solved on copies and written back once.
+struct PairVelocity {
+    va: (f32, f32),
+    wa: f32,
+    vb: (f32, f32),
+    wb: f32,
+    inv_ma: f32,
+    inv_ia: f32,
+    inv_mb: f32,
+    inv_ib: f32,
+}
+
+impl PairVelocity {
+    fn new(a: &RigidBody, b: &RigidBody) -> Self {
+        let (inv_ma, inv_ia) = joint_inverse_mass(a);
+        let (inv_mb, inv_ib) = joint_inverse_mass(b);
+        Self {
+            va: (a.vx, a.vy),
+            wa: a.angular_velocity,
+            vb: (b.vx, b.vy),
+            wb: b.angular_velocity,
+            inv_ma,
+            inv_ia,
+            inv_mb,
+            inv_ib,
+        }
+    }
+
+    /// Apply the linear impulse `p` to B and `-p` to A, plus the angular
+    /// impulses `lb` to B and `-la` to A.
+    fn apply(&mut self, p: (f32, f32), la: f32, lb: f32) {
+        self.va.0 -= p.0 * self.inv_ma;
+        self.va.1 -= p.1 * self.inv_ma;
+        self.wa -= la * self.inv_ia;
+        self.vb.0 += p.0 * self.inv_mb;
+        self.vb.1 += p.1 * self.inv_mb;
+        self.wb += lb * self.inv_ib;
+    }
+
+    fn store(&self, bodies: &mut [Option<RigidBody>], a_idx: usize, b_idx: usize) {
+        if let Some(a) = &mut bodies[a_idx]
+            && a.body_type == BodyType::Dynamic
+        {
+            (a.vx, a.vy, a.angular_velocity) = (self.va.0, self.va.1, self.wa);
+        }
+        if let Some(b) = &mut bodies[b_idx]
+            && b.body_type == BodyType::Dynamic
+        {
+            (b.vx, b.vy, b.angular_velocity) = (self.vb.0, self.vb.1, self.wb);
+        }
+    }
+}
+
+/// Revolute motor: drives B's angular velocity relative to A toward the
+/// motor speed, with the impulse capped by `max_force * dt`.
+fn solve_revolute_motor(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    motor: &mut JointMotor,
+    dt: f32,
+) {
+    let (a_idx, b_idx) = (id_a as usize, id_b as usize);
+    let mut pv = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => PairVelocity::new(a, b),
+        _ => return,
+    };
+    let k = pv.inv_ia + pv.inv_ib;
+    if k < 1e-8 {
+        return;
+    }
+    let cdot = pv.wb - pv.wa - motor.speed;
+    let max_impulse = motor.max_force * dt;
+    let old = motor.accumulated_impulse;
+    motor.accumulated_impulse = (old - cdot / k).clamp(-max_impulse, max_impulse);
+    let j = motor.accumulated_impulse - old;
+    pv.apply((0.0, 0.0), j, j);
+    pv.store(bodies, a_idx, b_idx);
+}
+
+/// Keeps the relative angular velocity of two bodies at zero (weld joints).
+fn solve_angle_lock_velocity(bodies: &mut [Option<RigidBody>], id_a: u32, id_b: u32) {
+    let (a_idx, b_idx) = (id_a as usize, id_b as usize);
+    let mut pv = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => PairVelocity::new(a, b),
+        _ => return,
+    };
+    let k = pv.inv_ia + pv.inv_ib;
+    if k < 1e-8 {
+        return;
+    }
+    let j = -(pv.wb - pv.wa) / k;
+    pv.apply((0.0, 0.0), j, j);
+    pv.store(bodies, a_idx, b_idx);
+}
+
+/// Prismatic joint geometry for the bodies' current poses.
+struct PrismaticFrame {
+    /// Slide axis in world space
+    axis: (f32, f32),
+    perp: (f32, f32),
+    /// B's anchor minus A's anchor
+    d: (f32, f32),
+    /// From A's center to B's anchor: the axis turns with A
+    ra: (f32, f32),
+    /// From B's center to B's anchor
+    rb: (f32, f32),
+}
+
+impl PrismaticFrame {
+    fn new(a: &RigidBody, b: &RigidBody, anchor_a: (f32, f32), anchor_b: (f32, f32), axis: (f32, f32)) -> Self {
+        let (sin, cos) = math::sin_cos(a.angle);
+        let axis = (axis.0 * cos - axis.1 * sin, axis.0 * sin + axis.1 * cos);
+        let (pa_x, pa_y) = world_anchor(a, anchor_a);
+        let (pb_x, pb_y) = world_anchor(b, anchor_b);
+        Self {
+            axis,
+            perp: (-axis.1, axis.0),
+            d: (pb_x - pa_x, pb_y - pa_y),
+            ra: (pb_x - a.x, pb_y - a.y),
+            rb: (pb_x - b.x, pb_y - b.y),
+        }
+    }
+}
+
+/// Prismatic velocity solver: motor and limits along the axis, then the
+/// perpendicular offset and relative rotation are held at zero velocity.
+#[allow(clippy::too_many_arguments)]
+fn solve_prismatic_velocity(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    axis: (f32, f32),
+    (lower, upper): (f32, f32),
+    motor: Option<&mut JointMotor>,
+    accumulated: &mut (f32, f32),
+    dt: f32,
+) {
+    let (a_idx, b_idx) = (id_a as usize, id_b as usize);
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    if a.body_type != BodyType::Dynamic && b.body_type != BodyType::Dynamic {
+        return;
+    }
+    let PrismaticFrame { axis: world_axis, perp, d, ra, rb } = PrismaticFrame::new(a, b, anchor_a, anchor_b, axis);
+    let mut pv = PairVelocity::new(a, b);
+
+    let (a1, a2) = (cross(ra, world_axis), cross(rb, world_axis));
+    let (s1, s2) = (cross(ra, perp), cross(rb, perp));
+    let axis_mass = pv.inv_ma + pv.inv_mb + pv.inv_ia * a1 * a1 + pv.inv_ib * a2 * a2;
+    let axis_mass = if axis_mass > 1e-8 { 1.0 / axis_mass } else { 0.0 };
+    let axis_speed = |pv: &PairVelocity| {
+        dot(world_axis, (pv.vb.0 - pv.va.0, pv.vb.1 - pv.va.1)) + a2 * pv.wb - a1 * pv.wa
+    };
+    let push = |pv: &mut PairVelocity, j: f32| {
+        pv.apply((world_axis.0 * j, world_axis.1 * j), a1 * j, a2 * j);
+    };
+
+    if let Some(motor) = motor {
+        let max_impulse = motor.max_force * dt;
+        let old = motor.accumulated_impulse;
+        let cdot = axis_speed(&pv) - motor.speed;
+        motor.accumulated_impulse = (old - cdot * axis_mass).clamp(-max_impulse, max_impulse);
+        push(&mut pv, motor.accumulated_impulse - old);
+    }
+
+    // Limits are speculative like ropes: the anchors may close the remaining
+    // gap this sub-step, but not pass it
+    let translation = dot(world_axis, d);
+    if lower.is_finite() {
+        let gap = translation - lower;
+        let bias = if gap > 0.0 && dt > 0.0 { gap / dt } else { 0.0 };
+        let old = accumulated.0;
+        accumulated.0 = (old - (axis_speed(&pv) + bias) * axis_mass).max(0.0);
+        push(&mut pv, accumulated.0 - old);
+    }
+    if upper.is_finite() {
+        let gap = upper - translation;
+        let bias = if gap > 0.0 && dt > 0.0 { gap / dt } else { 0.0 };
+        let old = accumulated.1;
+        accumulated.1 = (old - (-axis_speed(&pv) + bias) * axis_mass).max(0.0);
+        push(&mut pv, -(accumulated.1 - old));
+    }
+
+    // Perpendicular offset and relative angle, solved together
+    let cdot1 = dot(perp, (pv.vb.0 - pv.va.0, pv.vb.1 - pv.va.1)) + s2 * pv.wb - s1 * pv.wa;
+    let cdot2 = pv.wb - pv.wa;
+    let k11 = pv.inv_ma + pv.inv_mb + pv.inv_ia * s1 * s1 + pv.inv_ib * s2 * s2;
+    let k12 = pv.inv_ia * s1 + pv.inv_ib * s2;
+    let k22 = pv.inv_ia + pv.inv_ib;
+    if k22 < 1e-8 {
+        // Neither body can rotate: only the perpendicular offset is left
+        if k11 > 1e-8 {
+            let j = -cdot1 / k11;
+            pv.apply((perp.0 * j, perp.1 * j), s1 * j, s2 * j);
+        }
+    } else {
+        let det = k11 * k22 - k12 * k12;
+        if det.abs() > 1e-12 {
+            let j1 = -(k22 * cdot1 - k12 * cdot2) / det;
+            let j2 = -(k11 * cdot2 - k12 * cdot1) / det;
+            pv.apply((perp.0 * j1, perp.1 * j1), s1 * j1 + j2, s2 * j1 + j2);
+        }
+    }
+    pv.store(bodies, a_idx, b_idx);
+}
+
+/// Prismatic position correction (Baumgarte): perpendicular drift, limit
+/// violations and the relative angle.
+#[allow(clippy::too_many_arguments)]
+fn solve_prismatic_position(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    axis: (f32, f32),
+    reference_angle: f32,
+    (lower, upper): (f32, f32),
+) {
+    let (a_idx, b_idx) = (id_a as usize, id_b as usize);
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    if a.body_type != BodyType::Dynamic && b.body_type != BodyType::Dynamic {
+        return;
+    }
+    let (inv_ma, _) = joint_inverse_mass(a);
+    let (inv_mb, _) = joint_inverse_mass(b);
+    let PrismaticFrame { axis: world_axis, perp, d, .. } = PrismaticFrame::new(a, b, anchor_a, anchor_b, axis);
+
+    let translation = dot(world_axis, d);
+    let beyond = if translation < lower {
+        translation - lower
+    } else if translation > upper {
+        translation - upper
+    } else {
+        0.0
+    };
+    let off_axis = dot(perp, d);
+    let dx = perp.0 * off_axis + world_axis.0 * beyond;
+    let dy = perp.1 * off_axis + world_axis.1 * beyond;
+
+    let baumgarte = 0.2;
+    let max_correction = 0.2;
+    let inv_total = inv_ma + inv_mb;
+    if inv_total > 0.0 && dx * dx + dy * dy > 1e-8 {
+        let cx = (dx * baumgarte).clamp(-max_correction, max_correction) / inv_total;
+        let cy = (dy * baumgarte).clamp(-max_correction, max_correction) / inv_total;
+        if let Some(a) = &mut bodies[a_idx]
+            && a.body_type == BodyType::Dynamic
+        {
+            a.x += cx * inv_ma;
+            a.y += cy * inv_ma;
+        }
+        if let Some(b) = &mut bodies[b_idx]
+            && b.body_type == BodyType::Dynamic
+        {
+            b.x -= cx * inv_mb;
+            b.y -= cy * inv_mb;
+        }
+    }
+    correct_relative_angle(bodies, a_idx, b_idx, reference_angle);
+}
+
+/// Rotate two bodies toward `reference_angle` between them (B - A).
+fn correct_relative_angle(bodies: &mut [Option<RigidBody>], a_idx: usize, b_idx: usize, reference_angle: f32) {
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    let (_, inv_ia) = joint_inverse_mass(a);
+    let (_, inv_ib) = joint_inverse_mass(b);
+    let inv_total = inv_ia + inv_ib;
+    let error = b.angle - a.angle - reference_angle;
+    if inv_total < 1e-8 || error.abs() < 1e-4 {
+        return;
+    }
+
+    let baumgarte = 0.2;
+    let max_correction = 0.1;
+    let correction = (error * baumgarte).clamp(-max_correction, max_correction) / inv_total;
+    if let Some(a) = &mut bodies[a_idx] {
+        a.angle += correction * inv_ia;
+    }
+    if let Some(b) = &mut bodies[b_idx] {
+        b.angle -= correction * inv_ib;
+    }
+}
+
+/// Rope (max-distance) velocity solver. Only pulls the anchors together, and
+/// only once they would end the sub-step farther apart than `max_length`.
+#[allow(clippy::too_many_arguments)]
+fn solve_rope_velocity(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    max_length: f32,
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    accumulated: &mut f32,
+    dt: f32,
+) {
+    let a_idx = id_a as usize;
+    let b_idx = id_b as usize;
+
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    if a.body_type != BodyType::Dynamic && b.body_type != BodyType::Dynamic {
+        return;
+    }
+    let (inv_ma, inv_ia, inv_mb, inv_ib) = (a.inv_mass, a.inv_inertia, b.inv_mass, b.inv_inertia);
+
+    let (wa_x, wa_y) = world_anchor(a, anchor_a);
+    let (wb_x, wb_y) = world_anchor(b, anchor_b);
+    let dx = wb_x - wa_x;
+    let dy = wb_y - wa_y;
+    let current_distance = (dx * dx + dy * dy).sqrt();
+    if current_distance < 1e-8 {
+        return;
+    }
+    let nx = dx / current_distance;
+    let ny = dy / current_distance;
+
+    let ra_x = wa_x - a.x;
+    let ra_y = wa_y - a.y;
+    let rb_x = wb_x - b.x;
+    let rb_y = wb_y - b.y;
+
+    let va_x = a.vx - a.angular_velocity * ra_y;
+    let va_y = a.vy + a.angular_velocity * ra_x;
+    let vb_x = b.vx - b.angular_velocity * rb_y;
+    let vb_y = b.vy + b.angular_velocity * rb_x;
+    let rel_vn = (vb_x - va_x) * nx + (vb_y - va_y) * ny;
+
+    let ra_cross_n = ra_x * ny - ra_y * nx;
+    let rb_cross_n = rb_x * ny - rb_y * nx;
+    let inv_mass_sum = inv_ma + inv_mb
+        + ra_cross_n * ra_cross_n * inv_ia
+        + rb_cross_n * rb_cross_n * inv_ib;
+    if inv_mass_sum < 1e-8 {
+        return;
+    }
+
+    // Speculative: while slack, the anchors may separate by up to the
+    // remaining slack this sub-step before the rope engages
+    let slack = current_distance - max_length;
+    let bias = if slack < 0.0 && dt > 0.0 { slack / dt } else { 0.0 };
+    let lambda = -(rel_vn + bias) / inv_mass_sum;
+
+    // A rope can only pull (impulse on B toward A)
+    let old_accumulated = *accumulated;
+    *accumulated = (old_accumulated + lambda).min(0.0);
+    let j = *accumulated - old_accumulated;
+    if j == 0.0 {
+        return;
+    }
+
+    let impulse_x = j * nx;
+    let impulse_y = j * ny;
+    if let Some(a) = &mut bodies[a_idx] {
+        if a.body_type == BodyType::Dynamic {
+            a.vx -= impulse_x * inv_ma;
+            a.vy -= impulse_y * inv_ma;
+            a.angular_velocity -= ra_cross_n * j * inv_ia;
+        }
+    }
+    if let Some(b) = &mut bodies[b_idx] {
+        if b.body_type == BodyType::Dynamic {
+            b.vx += impulse_x * inv_mb;
+            b.vy += impulse_y * inv_mb;
+            b.angular_velocity += rb_cross_n * j * inv_ib;
+        }
+    }
+}
+
+/// Pulley velocity solver: d/dt (length_a + ratio * length_b) = 0.
+#[allow(clippy::too_many_arguments)]
+fn solve_pulley_velocity(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    ground_a: (f32, f32),
+    ground_b: (f32, f32),
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    ratio: f32,
+    accumulated: &mut f32,
+) {
+    let a_idx = id_a as usize;
+    let b_idx = id_b as usize;
+
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    if a.body_type != BodyType::Dynamic && b.body_type != BodyType::Dynamic {
+        return;
+    }
+    let (inv_ma, inv_ia, inv_mb, inv_ib) = (a.inv_mass, a.inv_inertia, b.inv_mass, b.inv_inertia);
+
+    let (_, (ua_x, ua_y), (ra_x, ra_y)) = pulley_side(a, ground_a, anchor_a);
+    let (_, (ub_x, ub_y), (rb_x, rb_y)) = pulley_side(b, ground_b, anchor_b);
+
+    let va_x = a.vx - a.angular_velocity * ra_y;
+    let va_y = a.vy + a.angular_velocity * ra_x;
+    let vb_x = b.vx - b.angular_velocity * rb_y;
+    let vb_y = b.vy + b.angular_velocity * rb_x;
+    // Rate at which the total rope length changes
+    let cdot = (va_x * ua_x + va_y * ua_y) + ratio * (vb_x * ub_x + vb_y * ub_y);
+
+    let ru_a = ra_x * ua_y - ra_y * ua_x;
+    let ru_b = rb_x * ub_y - rb_y * ub_x;
+    let inv_mass_sum = inv_ma + ru_a * ru_a * inv_ia + ratio * ratio * (inv_mb + ru_b * ru_b * inv_ib);
+    if inv_mass_sum < 1e-8 {
+        return;
+    }
+
+    let j = -cdot / inv_mass_sum;
+    *accumulated += j;
+
+    // The rope pulls each body along its side's direction
+    if let Some(a) = &mut bodies[a_idx] {
+        if a.body_type == BodyType::Dynamic {
+            a.vx += j * ua_x * inv_ma;
+            a.vy += j * ua_y * inv_ma;
+            a.angular_velocity += ru_a * j * inv_ia;
+        }
+    }
+    if let Some(b) = &mut bodies[b_idx] {
+        if b.body_type == BodyType::Dynamic {
+            let jb = ratio * j;
+            b.vx += jb * ub_x * inv_mb;
+            b.vy += jb * ub_y * inv_mb;
+            b.angular_velocity += ru_b * jb * inv_ib;
+        }
+    }
+}
+
+/// Pulley position correction (Baumgarte), linear only like the distance joint.
+#[allow(clippy::too_many_arguments)]
+fn solve_pulley_position(
+    bodies: &mut [Option<RigidBody>],
+    id_a: u32,
+    id_b: u32,
+    ground_a: (f32, f32),
+    ground_b: (f32, f32),
+    anchor_a: (f32, f32),
+    anchor_b: (f32, f32),
+    ratio: f32,
+    total_length: f32,
+) {
+    let a_idx = id_a as usize;
+    let b_idx = id_b as usize;
+
+    let (a, b) = match (&bodies[a_idx], &bodies[b_idx]) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return,
+    };
+    if a.body_type != BodyType::Dynamic && b.body_type != BodyType::Dynamic {
+        return;
+    }
+    let (inv_ma, inv_mb) = (a.inv_mass, b.inv_mass);
+
+    let (len_a, (ua_x, ua_y), _) = pulley_side(a, ground_a, anchor_a);
+    let (len_b, (ub_x, ub_y), _) = pulley_side(b, ground_b, anchor_b);
+    let error = len_a + ratio * len_b - total_length;
+
+    let slop = 0.005;
+    let baumgarte = 0.2;
+    let max_correction = 0.2;
+    let pen = if error > 0.0 { (error - slop).max(0.0) } else { (error + slop).min(0.0) };
+
+    let inv_total = inv_ma + ratio * ratio * inv_mb;
+    if inv_total == 0.0 {
+        return;
+    }
+    let correction = (pen * baumgarte).clamp(-max_correction, max_correction) / inv_total;
+
+    if let Some(a) = &mut bodies[a_idx] {
+        if a.body_type == BodyType::Dynamic {
+            a.x -= correction * inv_ma * ua_x;
+            a.y -= correction * inv_ma * ua_y;
+        }
+    }
+    if let Some(b) = &mut bodies[b_idx] {
+        if b.body_type == BodyType::Dynamic {
+            b.x -= correction * ratio * inv_mb * ub_x;
+            b.y -= correction * ratio * inv_mb * ub_y;
         }
     }
 }
@@ -95,7 +795,7 @@ fn solve_distance_velocity_soft(
     // Extract body data
     let (xa, ya, cos_a, sin_a, vax, vay, ava, inv_ma, inv_ia, type_a) = match &bodies[a_idx] {
         Some(b) => (
-            b.x, b.y, b.angle.cos(), b.angle.sin(),
+            b.x, b.y, math::cos(b.angle), math::sin(b.angle),
             b.vx, b.vy, b.angular_velocity,
             b.inv_mass, b.inv_inertia, b.body_type,
         ),
@@ -103,7 +803,7 @@ fn solve_distance_velocity_soft(
     };
     let (xb, yb, cos_b, sin_b, vbx, vby, avb, inv_mb, inv_ib, type_b) = match &bodies[b_idx] {
         Some(b) => (
-            b.x, b.y, b.angle.cos(), b.angle.sin(),
+            b.x, b.y, math::cos(b.angle), math::sin(b.angle),
             b.vx, b.vy, b.angular_velocity,
             b.inv_mass, b.inv_inertia, b.body_type,
         ),
@@ -234,11 +934,11 @@ fn solve_distance_position(
     let b_idx = id_b as usize;
 
     let (xa, ya, cos_a, sin_a, inv_ma, type_a) = match &bodies[a_idx] {
-        Some(b) => (b.x, b.y, b.angle.cos(), b.angle.sin(), b.inv_mass, b.body_type),
+        Some(b) => (b.x, b.y, math::cos(b.angle), math::sin(b.angle), b.inv_mass, b.body_type),
         None => return,
     };
     let (xb, yb, cos_b, sin_b, inv_mb, type_b) = match &bodies[b_idx] {
-        Some(b) => (b.x, b.y, b.angle.cos(), b.angle.sin(), b.inv_mass, b.body_type),
+        Some(b) => (b.x, b.y, math::cos(b.angle), math::sin(b.angle), b.inv_mass, b.body_type),
         None => return,
     };
 
@@ -306,7 +1006,7 @@ fn solve_revolute_velocity_soft(
 
     let (xa, ya, cos_a, sin_a, vax, vay, ava, inv_ma, inv_ia, type_a) = match &bodies[a_idx] {
         Some(b) => (
-            b.x, b.y, b.angle.cos(), b.angle.sin(),
+            b.x, b.y, math::cos(b.angle), math::sin(b.angle),
             b.vx, b.vy, b.angular_velocity,
             b.inv_mass, b.inv_inertia, b.body_type,
         ),
@@ -314,7 +1014,7 @@ fn solve_revolute_velocity_soft(
     };
     let (xb, yb, cos_b, sin_b, vbx, vby, avb, inv_mb, inv_ib, type_b) = match &bodies[b_idx] {
         Some(b) => (
-            b.x, b.y, b.angle.cos(), b.angle.sin(),
+            b.x, b.y, math::cos(b.angle), math::sin(b.angle),
             b.vx, b.vy, b.angular_velocity,
             b.inv_mass, b.inv_inertia, b.body_type,
         ),
@@ -420,11 +1120,11 @@ fn solve_revolute_position(
     let b_idx = id_b as usize;
 
     let (xa, ya, cos_a, sin_a, inv_ma, type_a) = match &bodies[a_idx] {
-        Some(b) => (b.x, b.y, b.angle.cos(), b.angle.sin(), b.inv_mass, b.body_type),
+        Some(b) => (b.x, b.y, math::cos(b.angle), math::sin(b.angle), b.inv_mass, b.body_type),
         None => return,
     };
     let (xb, yb, cos_b, sin_b, inv_mb, type_b) = match &bodies[b_idx] {
-        Some(b) => (b.x, b.y, b.angle.cos(), b.angle.sin(), b.inv_mass, b.body_type),
+        Some(b) => (b.x, b.y, math::cos(b.angle), math::sin(b.angle), b.inv_mass, b.body_type),
         None => return,
     };
 
@@ -469,3 +1169,68 @@ fn solve_revolute_position(
         }
     }
 }
+
+/// Mouse joint velocity solver: a soft 2D point constraint pulling the grab
+/// point toward the target, with the accumulated impulse capped by
+/// `max_force * dt` so a dragged body cannot tunnel through walls.
+#[allow(clippy::too_many_arguments)]
+fn solve_mouse_velocity(
+    bodies: &mut [Option<RigidBody>],
+    id: u32,
+    target: (f32, f32),
+    anchor: (f32, f32),
+    max_force: f32,
+    soft: &SoftConstraintParams,
+    accumulated: &mut (f32, f32),
+    dt: f32,
+) {
+    let Some(body) = bodies.get_mut(id as usize).and_then(|b| b.as_mut()) else {
+        return;
+    };
+    if body.body_type != BodyType::Dynamic || dt <= 0.0 {
+        return;
+    }
+
+    // Spring-damper in Box2D's soft form, scaled by the body's own mass
+    let omega = 2.0 * std::f32::consts::PI * soft.frequency_hz;
+    let damping = 2.0 * body.mass * soft.damping_ratio * omega;
+    let stiffness = body.mass * omega * omega;
+    let gamma = dt * (damping + dt * stiffness);
+    let gamma = if gamma > 0.0 { 1.0 / gamma } else { 0.0 };
+    let beta = dt * stiffness * gamma;
+
+    let (px, py) = world_anchor(body, anchor);
+    let (rx, ry) = (px - body.x, py - body.y);
+    let (inv_m, inv_i) = (body.inv_mass, body.inv_inertia);
+
+    // K = [invM + invI·ry² + γ, -invI·rx·ry; -invI·rx·ry, invM + invI·rx² + γ]
+    let k11 = inv_m + inv_i * ry * ry + gamma;
+    let k12 = -inv_i * rx * ry;
+    let k22 = inv_m + inv_i * rx * rx + gamma;
+    let det = k11 * k22 - k12 * k12;
+    if det.abs() < 1e-12 {
+        return;
+    }
+    let inv_det = 1.0 / det;
+
+    let cdot_x = body.vx - body.angular_velocity * ry + beta * (px - target.0) + gamma * accumulated.0;
+    let cdot_y = body.vy + body.angular_velocity * rx + beta * (py - target.1) + gamma * accumulated.1;
+    let jx = -inv_det * (k22 * cdot_x - k12 * cdot_y);
+    let jy = -inv_det * (-k12 * cdot_x + k11 * cdot_y);
+
+    let old = *accumulated;
+    let (mut ax, mut ay) = (old.0 + jx, old.1 + jy);
+    let max_impulse = max_force * dt;
+    let len = (ax * ax + ay * ay).sqrt();
+    if len > max_impulse {
+        let scale = max_impulse / len;
+        ax *= scale;
+        ay *= scale;
+    }
+    *accumulated = (ax, ay);
+    let (jx, jy) = (ax - old.0, ay - old.1);
+
+    body.vx += jx * inv_m;
+    body.vy += jy * inv_m;
+    body.angular_velocity += inv_i * (rx * jy - ry * jx);
+}
@@ -1,7 +1,8 @@
 use super::types::{BodyType, RigidBody};
 
 /// Semi-implicit Euler integration.
-/// Applies gravity, updates velocity from forces, updates position from velocity.
+/// Applies gravity, updates velocity from forces, damps dynamic bodies, updates
+/// position from velocity.
 pub fn integrate(body: &mut RigidBody, gravity_x: f32, gravity_y: f32, dt: f32) {
     if body.body_type == BodyType::Static || body.sleeping {
         body.fx = 0.0;
@@ -21,6 +22,21 @@ pub fn integrate(body: &mut RigidBody, gravity_x: f32, gravity_y: f32, dt: f32)
     body.vy += body.fy * body.inv_mass * dt;
     body.angular_velocity += body.torque * body.inv_inertia * dt;
 
+    // Damping (Box2D form: stable for any dt, never reverses velocity).
+    // Damped bodies slow below the sleep thresholds and fall asleep as usual.
+    if body.body_type == BodyType::Dynamic {
+        if body.linear_damping > 0.0 {
+            let factor = 1.0 / (1.0 + dt * body.linear_damping);
+            body.vx *= factor;
+            body.vy *= factor;
+        }
+        if body.angular_damping > 0.0 {
+            body.angular_velocity *= 1.0 / (1.0 + dt * body.angular_damping);
+        }
+    }
+
+    apply_velocity_limits(body);
+
     // Update position from velocity
     body.x += body.vx * dt;
     body.y += body.vy * dt;
@@ -31,3 +47,34 @@ pub fn integrate(body: &mut RigidBody, gravity_x: f32, gravity_y: f32, dt: f32)
     body.fy = 0.0;
     body.torque = 0.0;
 }
+
+/// Enforce per-body axis locks and speed caps on a dynamic body's velocity.
+/// Runs after integration and again after the solver, which may reintroduce
+/// motion along a locked axis.
+pub fn apply_velocity_limits(body: &mut RigidBody) {
+    if body.body_type != BodyType::Dynamic {
+        return;
+    }
+    if body.lock_x {
+        body.vx = 0.0;
+    }
+    if body.lock_y {
+        body.vy = 0.0;
+    }
+    if body.lock_rotation {
+        body.angular_velocity = 0.0;
+    }
+    if body.max_speed > 0.0 {
+        let speed_sq = body.vx * body.vx + body.vy * body.vy;
+        if speed_sq > body.max_speed * body.max_speed {
+            let scale = body.max_speed / speed_sq.sqrt();
+            body.vx *= scale;
+            body.vy *= scale;
+        }
+    }
+    if body.max_angular_speed > 0.0 {
+        body.angular_velocity = body
+            .angular_velocity
+            .clamp(-body.max_angular_speed, body.max_angular_speed);
+    }
+}
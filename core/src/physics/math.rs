@@ -0,0 +1,61 @@
+//! Transcendental functions used by the simulation.
+//!
+//! `f32::sin` and friends call the platform's libm, whose results may differ
+//! in the last bit between operating systems and CPUs. With the
+//! `deterministic-math` feature they come from the pure-Rust `libm` crate
+//! instead, so the same inputs give the same bits everywhere. Basic
+//! arithmetic and `sqrt` are exactly rounded by IEEE 754 and need no wrapper.
+
+#[cfg(feature = "deterministic-math")]
+mod imp {
+    #[inline]
+    pub fn sin(x: f32) -> f32 {
+        libm::sinf(x)
+    }
+
+    #[inline]
+    pub fn cos(x: f32) -> f32 {
+        libm::cosf(x)
+    }
+
+    #[inline]
+    pub fn powf(x: f32, y: f32) -> f32 {
+        libm::powf(x, y)
+    }
+
+    #[inline]
+    pub fn hypot(x: f32, y: f32) -> f32 {
+        libm::hypotf(x, y)
+    }
+}
+
+#[cfg(not(feature = "deterministic-math"))]
+mod imp {
+    #[inline]
+    pub fn sin(x: f32) -> f32 {
+        x.sin()
+    }
+
+    #[inline]
+    pub fn cos(x: f32) -> f32 {
+        x.cos()
+    }
+
+    #[inline]
+    pub fn powf(x: f32, y: f32) -> f32 {
+        x.powf(y)
+    }
+
+    #[inline]
+    pub fn hypot(x: f32, y: f32) -> f32 {
+        x.hypot(y)
+    }
+}
+
+pub use imp::{cos, hypot, powf, sin};
+
+/// `(sin(x), cos(x))`, like `f32::sin_cos`.
+#[inline]
+pub fn sin_cos(x: f32) -> (f32, f32) {
+    (sin(x), cos(x))
+}
@@ -6,3 +6,9 @@ pub mod resolve;
 pub mod constraints;
 pub mod sleep;
 pub mod world;
+pub mod sprite_collider;
+pub mod rig;
+pub mod vehicle;
+pub mod projectile;
+mod math;
+mod snapshot;
@@ -1,9 +1,17 @@
+use std::borrow::Cow;
+
 use super::types::{Contact, ContactID, ContactManifold, ManifoldPoint, RigidBody, Shape};
+use super::math;
 
 /// Test collision between two rigid bodies. Returns a contact if overlapping.
 /// Contact normal always points from body_a toward body_b.
 pub fn test_collision(a: &RigidBody, b: &RigidBody) -> Option<Contact> {
     match (&a.shape, &b.shape) {
+        (Shape::OBB { .. }, _) | (_, Shape::OBB { .. }) => {
+            test_collision(&box_as_polygon(a), &box_as_polygon(b))
+        }
+        (Shape::Capsule { .. }, _) => capsule_vs_shape(a, b, false),
+        (_, Shape::Capsule { .. }) => capsule_vs_shape(b, a, true),
         (Shape::Circle { .. }, Shape::Circle { .. }) => circle_vs_circle(a, b),
         (Shape::Circle { .. }, Shape::AABB { .. }) => circle_vs_aabb(a, b, false),
         (Shape::AABB { .. }, Shape::Circle { .. }) => circle_vs_aabb(b, a, true),
@@ -55,6 +63,8 @@ fn circle_vs_circle(a: &RigidBody, b: &RigidBody) -> Option<Contact> {
         accumulated_jt: 0.0,
         velocity_bias: 0.0,
         tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
     })
 }
 
@@ -121,6 +131,8 @@ fn circle_vs_aabb(circle: &RigidBody, aabb: &RigidBody, swapped: bool) -> Option
             accumulated_jt: 0.0,
             velocity_bias: 0.0,
             tangent: (0.0, 0.0),
+            impulse: 0.0,
+            relative_velocity: (0.0, 0.0),
         })
     } else {
         // Original: Circle=a, AABB=b. Normal should point from a to b (opposite).
@@ -134,6 +146,8 @@ fn circle_vs_aabb(circle: &RigidBody, aabb: &RigidBody, swapped: bool) -> Option
             accumulated_jt: 0.0,
             velocity_bias: 0.0,
             tangent: (0.0, 0.0),
+            impulse: 0.0,
+            relative_velocity: (0.0, 0.0),
         })
     }
 }
@@ -190,6 +204,8 @@ fn aabb_vs_aabb(a: &RigidBody, b: &RigidBody) -> Option<Contact> {
         accumulated_jt: 0.0,
         velocity_bias: 0.0,
         tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
     })
 }
 
@@ -199,8 +215,8 @@ fn get_world_vertices(body: &RigidBody) -> Vec<(f32, f32)> {
         Shape::Polygon { vertices } => vertices,
         _ => return Vec::new(),
     };
-    let cos = body.angle.cos();
-    let sin = body.angle.sin();
+    let cos = math::cos(body.angle);
+    let sin = math::sin(body.angle);
     verts
         .iter()
         .map(|&(vx, vy)| {
@@ -296,6 +312,8 @@ fn polygon_vs_polygon(a: &RigidBody, b: &RigidBody) -> Option<Contact> {
         accumulated_jt: 0.0,
         velocity_bias: 0.0,
         tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
     })
 }
 
@@ -374,6 +392,8 @@ fn circle_vs_polygon(circle: &RigidBody, poly: &RigidBody, swapped: bool) -> Opt
         accumulated_jt: 0.0,
         velocity_bias: 0.0,
         tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
     })
 }
 
@@ -404,6 +424,8 @@ fn aabb_vs_polygon(aabb: &RigidBody, poly: &RigidBody, swapped: bool) -> Option<
             accumulated_jt: 0.0,
             velocity_bias: 0.0,
             tangent: (0.0, 0.0),
+            impulse: 0.0,
+            relative_velocity: (0.0, 0.0),
         })
     } else {
         Some(Contact {
@@ -455,6 +477,11 @@ fn point_in_polygon(px: f32, py: f32, verts: &[(f32, f32)]) -> bool {
 /// Sutherland-Hodgman clipping for polygon-polygon collisions.
 pub fn test_collision_manifold(a: &RigidBody, b: &RigidBody) -> Option<ContactManifold> {
     match (&a.shape, &b.shape) {
+        (Shape::OBB { .. }, _) | (_, Shape::OBB { .. }) => {
+            test_collision_manifold(&box_as_polygon(a), &box_as_polygon(b))
+        }
+        (Shape::Capsule { .. }, _) => capsule_vs_shape_manifold(a, b, false),
+        (_, Shape::Capsule { .. }) => capsule_vs_shape_manifold(b, a, true),
         (Shape::Circle { .. }, Shape::Circle { .. }) => circle_vs_circle_manifold(a, b),
         (Shape::Circle { .. }, Shape::AABB { .. }) => circle_vs_aabb_manifold(a, b, false),
         (Shape::AABB { .. }, Shape::Circle { .. }) => circle_vs_aabb_manifold(b, a, true),
@@ -471,8 +498,8 @@ pub fn test_collision_manifold(a: &RigidBody, b: &RigidBody) -> Option<ContactMa
 fn world_to_local(body: &RigidBody, wx: f32, wy: f32) -> (f32, f32) {
     let dx = wx - body.x;
     let dy = wy - body.y;
-    let cos = body.angle.cos();
-    let sin = body.angle.sin();
+    let cos = math::cos(body.angle);
+    let sin = math::sin(body.angle);
     // Inverse rotation
     (dx * cos + dy * sin, -dx * sin + dy * cos)
 }
@@ -1021,6 +1048,24 @@ fn aabb_vs_polygon_manifold(aabb: &RigidBody, poly: &RigidBody, swapped: bool) -
     Some(manifold)
 }
 
+/// Minimum alignment (cosine) between a contact normal and a one-way body's
+/// open direction for the contact to count; about 45° either side.
+const ONE_WAY_MIN_ALIGNMENT: f32 = 0.7;
+
+/// Whether one-way bodies in this pair let the contact through: the other
+/// body must be on the open side, i.e. the normal (from A toward B) must
+/// point out of the one-way body's open face.
+pub fn one_way_allows(a: &RigidBody, b: &RigidBody, normal: (f32, f32)) -> bool {
+    let open_towards = |body: &RigidBody, (nx, ny): (f32, f32)| {
+        body.one_way.is_none_or(|(dx, dy)| {
+            let (sin, cos) = math::sin_cos(body.angle);
+            let (wx, wy) = (dx * cos - dy * sin, dx * sin + dy * cos);
+            nx * wx + ny * wy >= ONE_WAY_MIN_ALIGNMENT
+        })
+    };
+    open_towards(a, normal) && open_towards(b, (-normal.0, -normal.1))
+}
+
 // ============================================================================
 // Speculative Contact Detection (TGS Soft Phase 3)
 // ============================================================================
@@ -1034,6 +1079,11 @@ pub fn test_collision_manifold_speculative(
     b: &RigidBody,
     margin: f32,
 ) -> Option<ContactManifold> {
+    // Oriented boxes go through the polygon paths
+    if matches!(a.shape, Shape::OBB { .. }) || matches!(b.shape, Shape::OBB { .. }) {
+        return test_collision_manifold_speculative(&box_as_polygon(a), &box_as_polygon(b), margin);
+    }
+
     // First try normal collision detection
     if let Some(manifold) = test_collision_manifold(a, b) {
         return Some(manifold);
@@ -1042,6 +1092,10 @@ pub fn test_collision_manifold_speculative(
     // If no collision, check if bodies are close enough for speculative contact
     // Use shape-specific separation distance calculation
     match (&a.shape, &b.shape) {
+        // Converted to polygons above
+        (Shape::OBB { .. }, _) | (_, Shape::OBB { .. }) => None,
+        (Shape::Capsule { .. }, _) => capsule_vs_shape_speculative(a, b, margin, false),
+        (_, Shape::Capsule { .. }) => capsule_vs_shape_speculative(b, a, margin, true),
         (Shape::Circle { .. }, Shape::Circle { .. }) => {
             circle_vs_circle_speculative(a, b, margin)
         }
@@ -1420,3 +1474,383 @@ fn circle_vs_polygon_speculative(
         velocity_bias: 0.0,
     })
 }
+
+// ============================================================================
+// Capsules and Oriented Boxes
+// ============================================================================
+
+/// A capsule's core segment, world-space endpoints.
+type Segment = ((f32, f32), (f32, f32));
+
+/// Distance within which a feature still counts: keeps the second point of a
+/// resting contact and picks a face over a corner when they are this close.
+const FEATURE_TOLERANCE: f32 = 0.02;
+
+/// Segments closer than this to parallel (sine of the angle) are clipped for
+/// a two-point contact instead of touching at a single point.
+const PARALLEL_TOLERANCE: f32 = 0.05;
+
+/// AABBs and oriented boxes as 4-vertex polygons; other shapes unchanged.
+/// The body keeps its id and angle, so results need no fix-up.
+fn box_as_polygon(body: &RigidBody) -> Cow<'_, RigidBody> {
+    match body.shape {
+        Shape::AABB { half_w, half_h } | Shape::OBB { half_w, half_h } => Cow::Owned(RigidBody {
+            shape: Shape::Polygon {
+                vertices: vec![(-half_w, -half_h), (half_w, -half_h), (half_w, half_h), (-half_w, half_h)],
+            },
+            ..body.clone()
+        }),
+        _ => Cow::Borrowed(body),
+    }
+}
+
+/// Contact between a capsule and another shape, before it becomes a
+/// `Contact` or `ContactManifold`.
+struct CapsuleContact {
+    /// Unit normal from the capsule toward the other shape.
+    normal: (f32, f32),
+    /// World-space points with their separation (negative = overlapping).
+    points: Vec<((f32, f32), f32, ContactID)>,
+}
+
+impl CapsuleContact {
+    fn separation(&self) -> f32 {
+        self.points.iter().map(|p| p.1).fold(f32::MAX, f32::min)
+    }
+
+    fn deepest_point(&self) -> (f32, f32) {
+        self.points
+            .iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or((0.0, 0.0), |p| p.0)
+    }
+}
+
+/// World-space core segment and radius of a capsule body.
+fn capsule_segment(body: &RigidBody) -> Option<(Segment, f32)> {
+    let (half_height, radius) = match body.shape {
+        Shape::Capsule { half_height, radius } => (half_height, radius),
+        _ => return None,
+    };
+    // Local (0, half_height) rotated into world space
+    let (sin, cos) = math::sin_cos(body.angle);
+    let (ax, ay) = (-sin * half_height, cos * half_height);
+    Some((((body.x - ax, body.y - ay), (body.x + ax, body.y + ay)), radius))
+}
+
+/// Closest points between segments p0-p1 and q0-q1 (either may be a point).
+fn closest_points_on_segments(
+    p0: (f32, f32),
+    p1: (f32, f32),
+    q0: (f32, f32),
+    q1: (f32, f32),
+) -> ((f32, f32), (f32, f32)) {
+    const EPS: f32 = 1e-12;
+    let d1 = (p1.0 - p0.0, p1.1 - p0.1);
+    let d2 = (q1.0 - q0.0, q1.1 - q0.1);
+    let r = (p0.0 - q0.0, p0.1 - q0.1);
+    let a = d1.0 * d1.0 + d1.1 * d1.1;
+    let e = d2.0 * d2.0 + d2.1 * d2.1;
+    let f = d2.0 * r.0 + d2.1 * r.1;
+
+    let (s, t) = if a <= EPS && e <= EPS {
+        (0.0, 0.0)
+    } else if a <= EPS {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.0 * r.0 + d1.1 * r.1;
+        if e <= EPS {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.0 * d2.0 + d1.1 * d2.1;
+            let denom = a * e - b * b;
+            let s = if denom > EPS { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    ((p0.0 + d1.0 * s, p0.1 + d1.1 * s), (q0.0 + d2.0 * t, q0.1 + d2.1 * t))
+}
+
+/// Clip the incident segment to the side planes of the reference face
+/// `f0`-`f1` and measure each clipped point along `normal` (from the face
+/// toward the incident segment). Radii round either side. Points further
+/// apart than `margin` are dropped.
+#[allow(clippy::too_many_arguments)]
+fn clip_to_face(
+    f0: (f32, f32),
+    f1: (f32, f32),
+    normal: (f32, f32),
+    inc0: (f32, f32),
+    inc1: (f32, f32),
+    face_radius: f32,
+    inc_radius: f32,
+    margin: f32,
+    face_id: u8,
+) -> Vec<((f32, f32), f32, ContactID)> {
+    let (ex, ey) = (f1.0 - f0.0, f1.1 - f0.1);
+    let len = (ex * ex + ey * ey).sqrt();
+    if len < 1e-8 {
+        return Vec::new();
+    }
+    let tangent = (ex / len, ey / len);
+    let mut clipped = clip_segment_to_line(inc0, inc1, f0, tangent);
+    if clipped.len() >= 2 {
+        clipped = clip_segment_to_line(clipped[0], clipped[1], f1, (-tangent.0, -tangent.1));
+    }
+
+    let keep = margin.max(FEATURE_TOLERANCE);
+    clipped
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &q)| {
+            let sep = (q.0 - f0.0) * normal.0 + (q.1 - f0.1) * normal.1 - face_radius - inc_radius;
+            // Midway between the two surfaces
+            let offset = inc_radius + sep * 0.5;
+            let point = (q.0 - normal.0 * offset, q.1 - normal.1 * offset);
+            (sep <= keep).then_some((point, sep, ContactID::new(face_id, 0, i as u8)))
+        })
+        .collect()
+}
+
+/// Two rounded segments (capsules, or circles as zero-length segments).
+/// Normal points from `a` toward `b`.
+fn segment_vs_segment(a: Segment, ra: f32, b: Segment, rb: f32, margin: f32) -> Option<CapsuleContact> {
+    let (ca, cb) = closest_points_on_segments(a.0, a.1, b.0, b.1);
+    let (dx, dy) = (cb.0 - ca.0, cb.1 - ca.1);
+    let dist = (dx * dx + dy * dy).sqrt();
+    let sep = dist - ra - rb;
+    if sep > margin {
+        return None;
+    }
+
+    let (ax, ay) = (a.1.0 - a.0.0, a.1.1 - a.0.1);
+    let (bx, by) = (b.1.0 - b.0.0, b.1.1 - b.0.1);
+    let la = (ax * ax + ay * ay).sqrt();
+    let lb = (bx * bx + by * by).sqrt();
+
+    let normal = if dist > 1e-6 {
+        (dx / dist, dy / dist)
+    } else if la > 1e-6 {
+        // Cores cross: push out sideways, toward b's middle
+        let n = (ay / la, -ax / la);
+        let mid = ((b.0.0 + b.1.0) * 0.5 - a.0.0, (b.0.1 + b.1.1) * 0.5 - a.0.1);
+        if mid.0 * n.0 + mid.1 * n.1 < 0.0 { (-n.0, -n.1) } else { n }
+    } else {
+        (1.0, 0.0)
+    };
+
+    // Side by side and parallel: clip b against a for a two-point contact
+    if la > 1e-6 && lb > 1e-6 {
+        let sin = (ax * by - ay * bx) / (la * lb);
+        let along = (normal.0 * ax + normal.1 * ay) / la;
+        if sin.abs() < PARALLEL_TOLERANCE && along.abs() < PARALLEL_TOLERANCE {
+            let n = (ay / la, -ax / la);
+            let face_normal = if n.0 * normal.0 + n.1 * normal.1 < 0.0 { (-n.0, -n.1) } else { n };
+            let points = clip_to_face(a.0, a.1, face_normal, b.0, b.1, ra, rb, margin, 0);
+            if points.len() == 2 {
+                return Some(CapsuleContact { normal: face_normal, points });
+            }
+        }
+    }
+
+    let offset = ra + sep * 0.5;
+    let point = (ca.0 + normal.0 * offset, ca.1 + normal.1 * offset);
+    Some(CapsuleContact {
+        normal,
+        points: vec![(point, sep, ContactID::circle())],
+    })
+}
+
+/// Rounded segment vs convex polygon (world vertices). Normal points from the
+/// segment toward the polygon.
+fn segment_vs_polygon(seg: Segment, radius: f32, verts: &[(f32, f32)], margin: f32) -> Option<CapsuleContact> {
+    let n = verts.len();
+    if n < 3 {
+        return None;
+    }
+
+    // SAT on the cores: the polygon's faces, then the segment's two sides
+    let (face_sep, face) = find_max_separation(verts, &[seg.0, seg.1]);
+    let (ex, ey) = (seg.1.0 - seg.0.0, seg.1.1 - seg.0.1);
+    let seg_len = (ex * ex + ey * ey).sqrt();
+    let mut axis_sep = f32::MIN;
+    let mut axis = (0.0f32, 0.0f32);
+    if seg_len > 1e-6 {
+        for side in [(ey / seg_len, -ex / seg_len), (-ey / seg_len, ex / seg_len)] {
+            let s = verts
+                .iter()
+                .map(|v| (v.0 - seg.0.0) * side.0 + (v.1 - seg.0.1) * side.1)
+                .fold(f32::MAX, f32::min);
+            if s > axis_sep {
+                axis_sep = s;
+                axis = side;
+            }
+        }
+    }
+    if face_sep.max(axis_sep) - radius > margin {
+        return None;
+    }
+
+    if face_sep.max(axis_sep) > 0.0 {
+        // Cores apart: the closest features decide, so rounded ends meet
+        // polygon corners at the right distance
+        let mut best = (f32::MAX, seg.0, verts[0]);
+        for i in 0..n {
+            let (cs, cp) = closest_points_on_segments(seg.0, seg.1, verts[i], verts[(i + 1) % n]);
+            let d2 = (cp.0 - cs.0).powi(2) + (cp.1 - cs.1).powi(2);
+            if d2 < best.0 {
+                best = (d2, cs, cp);
+            }
+        }
+        let (d2, cs, cp) = best;
+        let dist = d2.sqrt();
+        let sep = dist - radius;
+        if sep > margin {
+            return None;
+        }
+        if dist - face_sep > FEATURE_TOLERANCE {
+            // A polygon corner, or the capsule's side resting on one
+            let normal = ((cp.0 - cs.0) / dist, (cp.1 - cs.1) / dist);
+            let point = (cp.0 - normal.0 * sep * 0.5, cp.1 - normal.1 * sep * 0.5);
+            return Some(CapsuleContact {
+                normal,
+                points: vec![(point, sep, ContactID::circle())],
+            });
+        }
+    } else if axis_sep > face_sep + FEATURE_TOLERANCE {
+        // A polygon corner digs into the capsule's side
+        let deepest = verts
+            .iter()
+            .copied()
+            .min_by(|a, b| {
+                let da = (a.0 - seg.0.0) * axis.0 + (a.1 - seg.0.1) * axis.1;
+                let db = (b.0 - seg.0.0) * axis.0 + (b.1 - seg.0.1) * axis.1;
+                da.total_cmp(&db)
+            })
+            .unwrap_or(verts[0]);
+        let sep = axis_sep - radius;
+        let point = (deepest.0 - axis.0 * sep * 0.5, deepest.1 - axis.1 * sep * 0.5);
+        return Some(CapsuleContact {
+            normal: axis,
+            points: vec![(point, sep, ContactID::circle())],
+        });
+    }
+
+    // Face contact: clip the segment to the polygon face (up to two points)
+    let f0 = verts[face];
+    let f1 = verts[(face + 1) % n];
+    let (fx, fy) = (f1.0 - f0.0, f1.1 - f0.1);
+    let face_len = (fx * fx + fy * fy).sqrt();
+    if face_len < 1e-8 {
+        return None;
+    }
+    let face_normal = (fy / face_len, -fx / face_len);
+    let points = clip_to_face(f0, f1, face_normal, seg.0, seg.1, 0.0, radius, margin, face as u8);
+    if points.is_empty() {
+        return None;
+    }
+    Some(CapsuleContact {
+        normal: (-face_normal.0, -face_normal.1),
+        points,
+    })
+}
+
+/// Capsule vs any shape. Normal points from the capsule toward `other`.
+fn capsule_contact(capsule: &RigidBody, other: &RigidBody, margin: f32) -> Option<CapsuleContact> {
+    let (seg, radius) = capsule_segment(capsule)?;
+    match other.shape {
+        Shape::Circle { radius: other_radius } => {
+            let center = (other.x, other.y);
+            segment_vs_segment(seg, radius, (center, center), other_radius, margin)
+        }
+        Shape::Capsule { .. } => {
+            let (other_seg, other_radius) = capsule_segment(other)?;
+            segment_vs_segment(seg, radius, other_seg, other_radius, margin)
+        }
+        _ => segment_vs_polygon(seg, radius, &get_world_vertices(&box_as_polygon(other)), margin),
+    }
+}
+
+fn capsule_vs_shape(capsule: &RigidBody, other: &RigidBody, swapped: bool) -> Option<Contact> {
+    let contact = capsule_contact(capsule, other, 0.0)?;
+    let separation = contact.separation();
+    if separation >= 0.0 {
+        return None;
+    }
+    let (body_a, body_b, normal) = if swapped {
+        (other, capsule, (-contact.normal.0, -contact.normal.1))
+    } else {
+        (capsule, other, contact.normal)
+    };
+    Some(Contact {
+        body_a: body_a.id,
+        body_b: body_b.id,
+        normal,
+        penetration: -separation,
+        contact_point: contact.deepest_point(),
+        accumulated_jn: 0.0,
+        accumulated_jt: 0.0,
+        velocity_bias: 0.0,
+        tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
+    })
+}
+
+fn capsule_manifold(
+    capsule: &RigidBody,
+    other: &RigidBody,
+    contact: CapsuleContact,
+    swapped: bool,
+) -> ContactManifold {
+    let (body_a, body_b, (nx, ny)) = if swapped {
+        (other, capsule, (-contact.normal.0, -contact.normal.1))
+    } else {
+        (capsule, other, contact.normal)
+    };
+    let points = contact
+        .points
+        .iter()
+        .map(|&(p, sep, id)| {
+            ManifoldPoint::new(world_to_local(body_a, p.0, p.1), world_to_local(body_b, p.0, p.1), -sep, id)
+        })
+        .collect();
+    ContactManifold {
+        body_a: body_a.id,
+        body_b: body_b.id,
+        normal: (nx, ny),
+        points,
+        tangent: (-ny, nx),
+        velocity_bias: 0.0,
+    }
+}
+
+fn capsule_vs_shape_manifold(capsule: &RigidBody, other: &RigidBody, swapped: bool) -> Option<ContactManifold> {
+    let contact = capsule_contact(capsule, other, 0.0)?;
+    if contact.separation() >= 0.0 {
+        return None;
+    }
+    Some(capsule_manifold(capsule, other, contact, swapped))
+}
+
+/// Speculative capsule vs any shape.
+fn capsule_vs_shape_speculative(
+    capsule: &RigidBody,
+    other: &RigidBody,
+    margin: f32,
+    swapped: bool,
+) -> Option<ContactManifold> {
+    let contact = capsule_contact(capsule, other, margin)?;
+    let separation = contact.separation();
+    if separation <= 0.0 || separation > margin {
+        return None;
+    }
+    Some(capsule_manifold(capsule, other, contact, swapped))
+}
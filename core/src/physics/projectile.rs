@@ -0,0 +1,174 @@
+//! Projectiles: bullets that move, age and hit bodies without being bodies.
+//!
+//! A batch holds up to `capacity` projectiles in a pool allocated once. Every
+//! fixed step, after the bodies move, each projectile accelerates, moves and
+//! sweeps its path against the bodies whose layer is in the batch's mask
+//! (sensors are ignored): a ray along the path, then a circle of the batch's
+//! radius at the new position. The first body hit ends the projectile and
+//! queues a [`ProjectileHit`]. Projectiles never push bodies; game code
+//! reacts to the hits.
+
+use super::narrowphase::test_collision;
+use super::types::*;
+use super::world::{query_probe, ray_vs_body, PhysicsWorld};
+
+pub type ProjectileBatchId = u32;
+
+/// Largest pool a batch can allocate.
+pub const MAX_BATCH_CAPACITY: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectileBatchDef {
+    /// Most projectiles alive at once (up to [`MAX_BATCH_CAPACITY`]). Spawns
+    /// beyond it are dropped.
+    pub capacity: usize,
+    /// Collision radius; 0 tests only the path's center line.
+    pub radius: f32,
+    /// Collision layers the projectiles hit.
+    pub mask: u16,
+}
+
+impl Default for ProjectileBatchDef {
+    fn default() -> Self {
+        Self { capacity: 1024, radius: 0.0, mask: 0xFFFF }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projectile {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    /// Constant acceleration (pixels/s²), e.g. gravity for arcing shots.
+    pub ax: f32,
+    pub ay: f32,
+    /// Seconds left to live. `f32::INFINITY` lives until it hits something.
+    pub life: f32,
+    /// Game-defined value reported with hits (owner, damage type, ...).
+    pub tag: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectileHit {
+    pub batch: ProjectileBatchId,
+    pub tag: u32,
+    pub body: BodyId,
+    /// Where the projectile struck.
+    pub point: (f32, f32),
+    /// Its velocity at impact.
+    pub velocity: (f32, f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectileBatch {
+    pub id: ProjectileBatchId,
+    pub def: ProjectileBatchDef,
+    projectiles: Vec<Projectile>,
+}
+
+impl ProjectileBatch {
+    /// Capacities above [`MAX_BATCH_CAPACITY`] are clamped to it.
+    pub fn new(id: ProjectileBatchId, mut def: ProjectileBatchDef) -> Self {
+        def.capacity = def.capacity.min(MAX_BATCH_CAPACITY);
+        Self { id, def, projectiles: Vec::with_capacity(def.capacity) }
+    }
+
+    /// Live projectiles, oldest first.
+    pub fn projectiles(&self) -> &[Projectile] {
+        &self.projectiles
+    }
+
+    /// Add a projectile. Returns false (and drops it) when the batch is full.
+    /// A `life` of 0 or less never expires.
+    pub fn spawn(&mut self, mut projectile: Projectile) -> bool {
+        if self.projectiles.len() >= self.def.capacity {
+            return false;
+        }
+        if projectile.life <= 0.0 || projectile.life.is_nan() {
+            projectile.life = f32::INFINITY;
+        }
+        self.projectiles.push(projectile);
+        true
+    }
+
+    /// Remove every projectile. The pool keeps its memory.
+    pub fn clear(&mut self) {
+        self.projectiles.clear();
+    }
+
+    /// Move, age and collide every projectile by one fixed step, appending
+    /// hits to `hits` in projectile order.
+    pub(super) fn step(&mut self, world: &PhysicsWorld, dt: f32, hits: &mut Vec<ProjectileHit>) {
+        if self.projectiles.is_empty() {
+            return;
+        }
+        let (radius, mask, batch) = (self.def.radius.max(0.0), self.def.mask, self.id);
+        let targets: Vec<_> = world
+            .all_bodies()
+            .into_iter()
+            .filter(|b| !b.is_sensor && b.layer & mask != 0)
+            .map(|b| (b, get_shape_aabb(b)))
+            .collect();
+        let probe = (radius > 0.0).then(|| query_probe(Shape::Circle { radius }, 0.0, 0.0, 0.0));
+
+        self.projectiles.retain_mut(|p| {
+            p.life -= dt;
+            if p.life <= 0.0 {
+                return false;
+            }
+            p.vx += p.ax * dt;
+            p.vy += p.ay * dt;
+            let (dx, dy) = (p.vx * dt, p.vy * dt);
+            let (nx, ny) = (p.x + dx, p.y + dy);
+            let min = (p.x.min(nx) - radius, p.y.min(ny) - radius);
+            let max = (p.x.max(nx) + radius, p.y.max(ny) + radius);
+            let near = targets.iter().filter(|(_, (x0, y0, x1, y1))| {
+                *x1 >= min.0 && *x0 <= max.0 && *y1 >= min.1 && *y0 <= max.1
+            });
+
+            // Earliest body crossed by the path's center line
+            let length = (dx * dx + dy * dy).sqrt();
+            let mut hit: Option<(BodyId, f32, f32, f32)> = None;
+            if length > 1e-6 {
+                let (ux, uy) = (dx / length, dy / length);
+                for (body, _) in near.clone() {
+                    if let Some(t) = ray_vs_body(p.x, p.y, ux, uy, body)
+                        && (0.0..=length).contains(&t)
+                        && hit.is_none_or(|(_, _, _, best)| t < best)
+                    {
+                        hit = Some((body.id, p.x + ux * t, p.y + uy * t, t));
+                    }
+                }
+            }
+            // Then the projectile's body at its new position
+            if hit.is_none()
+                && let Some(probe) = &probe
+            {
+                let probe = RigidBody { x: nx, y: ny, ..probe.clone() };
+                hit = near
+                    .filter_map(|(body, _)| test_collision(&probe, body).map(|c| (body.id, c.contact_point)))
+                    .min_by_key(|(id, _)| *id)
+                    .map(|(id, (hx, hy))| (id, hx, hy, length));
+            }
+
+            match hit {
+                Some((body, hx, hy, _)) => {
+                    hits.push(ProjectileHit {
+                        batch,
+                        tag: p.tag,
+                        body,
+                        point: (hx, hy),
+                        velocity: (p.vx, p.vy),
+                    });
+                    false
+                }
+                None => {
+                    p.x = nx;
+                    p.y = ny;
+                    true
+                }
+            }
+        });
+    }
+}
@@ -1,9 +1,10 @@
 use super::types::{BodyType, ContactManifold, ManifoldPoint, RigidBody};
+use super::math;
 
 /// Transform body-local point to world space
 fn local_to_world(body: &RigidBody, local: (f32, f32)) -> (f32, f32) {
-    let cos = body.angle.cos();
-    let sin = body.angle.sin();
+    let cos = math::cos(body.angle);
+    let sin = math::sin(body.angle);
     (
         local.0 * cos - local.1 * sin + body.x,
         local.0 * sin + local.1 * cos + body.y,
@@ -97,11 +98,11 @@ pub fn warm_start_manifolds(
 
             // Get world contact point (use average of both anchors)
             let (inv_ma, inv_ia, type_a, xa, ya, cos_a, sin_a) = match &bodies[id_a] {
-                Some(a) => (a.inv_mass, a.inv_inertia, a.body_type, a.x, a.y, a.angle.cos(), a.angle.sin()),
+                Some(a) => (a.inv_mass, a.inv_inertia, a.body_type, a.x, a.y, math::cos(a.angle), math::sin(a.angle)),
                 None => continue,
             };
             let (inv_mb, inv_ib, type_b, xb, yb, cos_b, sin_b) = match &bodies[id_b] {
-                Some(b) => (b.inv_mass, b.inv_inertia, b.body_type, b.x, b.y, b.angle.cos(), b.angle.sin()),
+                Some(b) => (b.inv_mass, b.inv_inertia, b.body_type, b.x, b.y, math::cos(b.angle), math::sin(b.angle)),
                 None => continue,
             };
 
@@ -171,7 +172,7 @@ fn resolve_manifold_velocity(bodies: &mut [Option<RigidBody>], manifold: &mut Co
         };
         (
             a.inv_mass, a.inv_inertia,
-            a.material.friction, a.body_type, a.x, a.y, a.angle.cos(), a.angle.sin(),
+            a.material.friction, a.body_type, a.x, a.y, math::cos(a.angle), math::sin(a.angle),
         )
     };
     let (inv_mb, inv_ib, fric_b, type_b, xb, yb, cos_b, sin_b) = {
@@ -181,7 +182,7 @@ fn resolve_manifold_velocity(bodies: &mut [Option<RigidBody>], manifold: &mut Co
         };
         (
             b.inv_mass, b.inv_inertia,
-            b.material.friction, b.body_type, b.x, b.y, b.angle.cos(), b.angle.sin(),
+            b.material.friction, b.body_type, b.x, b.y, math::cos(b.angle), math::sin(b.angle),
         )
     };
 
@@ -0,0 +1,356 @@
+//! Physics rigs: bodies and the joints between them, described in JSON.
+//!
+//! A rig (ragdoll, vehicle, chain bridge) is authored in rig space: body
+//! positions and joint anchors are points relative to the rig's origin, so a
+//! tool can save exactly what it shows. Spawning offsets everything by the
+//! spawn point and converts joint anchors into each body's local space, then
+//! returns the new body and joint IDs by name.
+//!
+//! ```json
+//! {
+//!   "bodies": [
+//!     { "name": "torso", "shape": { "type": "box", "halfW": 8, "halfH": 16 }, "x": 0, "y": 0, "mass": 3 },
+//!     { "name": "head", "shape": { "type": "circle", "radius": 6 }, "x": 0, "y": -24 }
+//!   ],
+//!   "joints": [
+//!     { "name": "neck", "type": "revolute", "bodyA": "torso", "bodyB": "head", "anchor": { "x": 0, "y": -17 } }
+//!   ]
+//! }
+//! ```
+//!
+//! Bodies take the fields of `createBody()` plus `angle` (radians). Joint types
+//! are `distance` and `rope` (`anchorA`/`anchorB`, default the body centers;
+//! `length`/`maxLength`, default their current distance), `revolute` and
+//! `weld` (`anchor`), and `prismatic` (`anchor`, `axis`, `lower`, `upper`).
+//! Distance and revolute joints take `soft: { frequencyHz, dampingRatio }`;
+//! revolute and prismatic joints take `motor: { speed, maxForce }`.
+
+use std::collections::HashMap;
+
+use crate::json::{escape, JsonValue};
+
+use super::math;
+use super::types::*;
+use super::world::PhysicsWorld;
+
+type Point = (f32, f32);
+
+/// A body as authored, positioned in rig space.
+#[derive(Debug, Clone)]
+pub struct RigBody {
+    pub name: String,
+    pub body_type: BodyType,
+    pub shape: Shape,
+    pub x: f32,
+    pub y: f32,
+    pub angle: f32,
+    pub mass: f32,
+    pub material: Material,
+    pub layer: u16,
+    pub mask: u16,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub lock_rotation: bool,
+    pub is_sensor: bool,
+}
+
+/// Joint settings by type. Points are in rig space.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RigJointKind {
+    Distance { anchor_a: Option<Point>, anchor_b: Option<Point>, length: Option<f32>, soft: Option<SoftConstraintParams> },
+    Rope { anchor_a: Option<Point>, anchor_b: Option<Point>, max_length: Option<f32> },
+    Revolute { anchor: Point, soft: Option<SoftConstraintParams>, motor: Option<JointMotor> },
+    Weld { anchor: Point },
+    Prismatic { anchor: Point, axis: Point, lower: f32, upper: f32, motor: Option<JointMotor> },
+}
+
+/// A joint between two of the rig's bodies (indices into `Rig::bodies`).
+#[derive(Debug, Clone)]
+pub struct RigJoint {
+    pub name: String,
+    pub body_a: usize,
+    pub body_b: usize,
+    pub kind: RigJointKind,
+}
+
+/// A parsed rig, ready to spawn any number of times.
+#[derive(Debug, Clone, Default)]
+pub struct Rig {
+    pub bodies: Vec<RigBody>,
+    pub joints: Vec<RigJoint>,
+}
+
+/// IDs of one spawned rig, by body and joint name in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RigInstance {
+    pub bodies: Vec<(String, BodyId)>,
+    pub joints: Vec<(String, ConstraintId)>,
+}
+
+impl RigInstance {
+    /// `{ "bodies": { name: id }, "joints": { name: id } }`
+    pub fn to_json(&self) -> String {
+        let map = |entries: &[(String, u32)]| {
+            entries.iter().map(|(name, id)| format!("\"{}\":{}", escape(name), id)).collect::<Vec<_>>().join(",")
+        };
+        format!("{{\"bodies\":{{{}}},\"joints\":{{{}}}}}", map(&self.bodies), map(&self.joints))
+    }
+}
+
+impl Rig {
+    /// Parse a rig file. Errors name the body or joint at fault.
+    pub fn parse(json: &str) -> Result<Rig, String> {
+        let root = JsonValue::parse(json)?;
+        let mut rig = Rig::default();
+        let mut by_name = HashMap::new();
+
+        let bodies = root.get("bodies").and_then(JsonValue::as_array).ok_or("Rig has no \"bodies\" array")?;
+        for (i, body) in bodies.iter().enumerate() {
+            let name = body.str_of("name").map_or_else(|| format!("body{i}"), str::to_string);
+            let parsed = parse_body(body, &name).map_err(|e| format!("Body '{name}': {e}"))?;
+            if by_name.insert(name.clone(), i).is_some() {
+                return Err(format!("Duplicate body name '{name}'"));
+            }
+            rig.bodies.push(parsed);
+        }
+
+        let joints = root.get("joints").and_then(JsonValue::as_array).unwrap_or(&[]);
+        for (i, joint) in joints.iter().enumerate() {
+            let name = joint.str_of("name").map_or_else(|| format!("joint{i}"), str::to_string);
+            let body = |key: &str| -> Result<usize, String> {
+                let body = joint.str_of(key).ok_or_else(|| format!("Joint '{name}': missing \"{key}\""))?;
+                by_name.get(body).copied().ok_or_else(|| format!("Joint '{name}': unknown body '{body}'"))
+            };
+            let (body_a, body_b) = (body("bodyA")?, body("bodyB")?);
+            let kind = parse_joint(joint).map_err(|e| format!("Joint '{name}': {e}"))?;
+            rig.joints.push(RigJoint { name, body_a, body_b, kind });
+        }
+        Ok(rig)
+    }
+
+    /// Create the rig's bodies and joints in `world` with its origin at (x, y).
+    pub fn spawn(&self, world: &mut PhysicsWorld, x: f32, y: f32) -> RigInstance {
+        let mut instance = RigInstance::default();
+        let mut ids = Vec::with_capacity(self.bodies.len());
+        for body in &self.bodies {
+            let id = world.add_body(
+                body.body_type,
+                body.shape.clone(),
+                x + body.x,
+                y + body.y,
+                body.mass,
+                body.material,
+                body.layer,
+                body.mask,
+            );
+            if let Some(b) = world.get_body_mut(id) {
+                b.angle = body.angle;
+            }
+            world.set_damping(id, body.linear_damping, body.angular_damping);
+            if body.lock_rotation {
+                world.set_locks(id, true, false, false);
+            }
+            if body.is_sensor {
+                world.set_sensor(id, true);
+            }
+            ids.push(id);
+            instance.bodies.push((body.name.clone(), id));
+        }
+
+        for joint in &self.joints {
+            let (a, b) = (ids[joint.body_a], ids[joint.body_b]);
+            let (rig_a, rig_b) = (&self.bodies[joint.body_a], &self.bodies[joint.body_b]);
+            let world_point = |p: Point| (x + p.0, y + p.1);
+            let local = |world: &PhysicsWorld, id: BodyId, p: Point| to_body_local(world.get_body(id), world_point(p));
+            let constraint = match &joint.kind {
+                RigJointKind::Distance { anchor_a, anchor_b, length, soft } => {
+                    let pa = anchor_a.unwrap_or((rig_a.x, rig_a.y));
+                    let pb = anchor_b.unwrap_or((rig_b.x, rig_b.y));
+                    Constraint::Distance {
+                        id: 0,
+                        body_a: a,
+                        body_b: b,
+                        distance: length.unwrap_or_else(|| distance(pa, pb)),
+                        anchor_a: local(world, a, pa),
+                        anchor_b: local(world, b, pb),
+                        soft: *soft,
+                        accumulated_impulse: 0.0,
+                    }
+                }
+                RigJointKind::Rope { anchor_a, anchor_b, max_length } => {
+                    let pa = anchor_a.unwrap_or((rig_a.x, rig_a.y));
+                    let pb = anchor_b.unwrap_or((rig_b.x, rig_b.y));
+                    Constraint::Rope {
+                        id: 0,
+                        body_a: a,
+                        body_b: b,
+                        max_length: max_length.unwrap_or_else(|| distance(pa, pb)),
+                        anchor_a: local(world, a, pa),
+                        anchor_b: local(world, b, pb),
+                        accumulated_impulse: 0.0,
+                    }
+                }
+                RigJointKind::Revolute { anchor, soft, motor } => Constraint::Revolute {
+                    id: 0,
+                    body_a: a,
+                    body_b: b,
+                    anchor_a: local(world, a, *anchor),
+                    anchor_b: local(world, b, *anchor),
+                    soft: *soft,
+                    accumulated_impulse: (0.0, 0.0),
+                    motor: *motor,
+                },
+                RigJointKind::Weld { anchor } => Constraint::Weld {
+                    id: 0,
+                    body_a: a,
+                    body_b: b,
+                    anchor_a: local(world, a, *anchor),
+                    anchor_b: local(world, b, *anchor),
+                    reference_angle: 0.0,
+                    accumulated_impulse: (0.0, 0.0),
+                },
+                RigJointKind::Prismatic { anchor, axis, lower, upper, motor } => {
+                    let body_a = world.get_body(a);
+                    Constraint::Prismatic {
+                        id: 0,
+                        body_a: a,
+                        body_b: b,
+                        anchor_a: local(world, a, *anchor),
+                        anchor_b: local(world, b, *anchor),
+                        axis: rotate_to_local(body_a, *axis),
+                        reference_angle: 0.0,
+                        lower: *lower,
+                        upper: *upper,
+                        motor: *motor,
+                        accumulated_impulse: (0.0, 0.0),
+                    }
+                }
+            };
+            instance.joints.push((joint.name.clone(), world.add_constraint(constraint)));
+        }
+        instance
+    }
+}
+
+fn parse_body(body: &JsonValue, name: &str) -> Result<RigBody, String> {
+    let body_type = match body.str_of("type").unwrap_or("dynamic") {
+        "static" => BodyType::Static,
+        "dynamic" => BodyType::Dynamic,
+        "kinematic" => BodyType::Kinematic,
+        other => return Err(format!("unknown body type '{other}'")),
+    };
+    let shape = body.get("shape").ok_or("missing \"shape\"")?;
+    let shape = match shape.str_of("type").unwrap_or("") {
+        "circle" => Shape::Circle { radius: positive(shape, "radius")? },
+        "aabb" => Shape::AABB { half_w: positive(shape, "halfW")?, half_h: positive(shape, "halfH")? },
+        "box" => Shape::OBB { half_w: positive(shape, "halfW")?, half_h: positive(shape, "halfH")? },
+        "capsule" => Shape::Capsule { half_height: shape.f32_or("halfHeight", 0.0).max(0.0), radius: positive(shape, "radius")? },
+        "polygon" => {
+            let vertices: Vec<Point> = shape
+                .get("vertices")
+                .and_then(JsonValue::as_array)
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(point_of)
+                .collect();
+            if vertices.len() < 3 {
+                return Err("polygon needs at least 3 vertices".into());
+            }
+            Shape::Polygon { vertices }
+        }
+        other => return Err(format!("unknown shape type '{other}'")),
+    };
+    let material = body.get("material");
+    Ok(RigBody {
+        name: name.to_string(),
+        body_type,
+        shape,
+        x: body.f32_or("x", 0.0),
+        y: body.f32_or("y", 0.0),
+        angle: body.f32_or("angle", 0.0),
+        mass: body.f32_or("mass", 1.0),
+        material: Material {
+            restitution: material.map_or(0.3, |m| m.f32_or("restitution", 0.3)),
+            friction: material.map_or(0.5, |m| m.f32_or("friction", 0.5)),
+        },
+        layer: body.f32_or("layer", 1.0) as u16,
+        mask: body.f32_or("mask", 65535.0) as u16,
+        linear_damping: body.f32_or("linearDamping", 0.0),
+        angular_damping: body.f32_or("angularDamping", 0.0),
+        lock_rotation: body.get("lockRotation").and_then(JsonValue::as_bool).unwrap_or(false),
+        is_sensor: body.get("isSensor").and_then(JsonValue::as_bool).unwrap_or(false),
+    })
+}
+
+fn parse_joint(joint: &JsonValue) -> Result<RigJointKind, String> {
+    let point = |key: &str| joint.get(key).and_then(point_of);
+    let anchor = || point("anchor").ok_or_else(|| "missing \"anchor\"".to_string());
+    let length = |key: &str| joint.get(key).and_then(JsonValue::as_f32);
+    let soft = joint.get("soft").map(|s| {
+        SoftConstraintParams::soft(s.f32_or("frequencyHz", 0.0), s.f32_or("dampingRatio", 1.0))
+    });
+    let motor = joint
+        .get("motor")
+        .map(|m| JointMotor::new(m.f32_or("speed", 0.0), m.f32_or("maxForce", 0.0)));
+    Ok(match joint.str_of("type").unwrap_or("") {
+        "distance" => RigJointKind::Distance {
+            anchor_a: point("anchorA"),
+            anchor_b: point("anchorB"),
+            length: length("length"),
+            soft,
+        },
+        "rope" => RigJointKind::Rope {
+            anchor_a: point("anchorA"),
+            anchor_b: point("anchorB"),
+            max_length: length("maxLength"),
+        },
+        "revolute" => RigJointKind::Revolute { anchor: anchor()?, soft, motor },
+        "weld" => RigJointKind::Weld { anchor: anchor()? },
+        "prismatic" => RigJointKind::Prismatic {
+            anchor: anchor()?,
+            axis: point("axis").ok_or("missing \"axis\"")?,
+            lower: length("lower").unwrap_or(f32::NEG_INFINITY),
+            upper: length("upper").unwrap_or(f32::INFINITY),
+            motor,
+        },
+        other => return Err(format!("unknown joint type '{other}'")),
+    })
+}
+
+/// A point written as `{ "x", "y" }` or `[x, y]`.
+fn point_of(value: &JsonValue) -> Option<Point> {
+    if let Some([x, y]) = value.as_array() {
+        return Some((x.as_f32()?, y.as_f32()?));
+    }
+    Some((value.get("x")?.as_f32()?, value.get("y")?.as_f32()?))
+}
+
+fn positive(value: &JsonValue, key: &str) -> Result<f32, String> {
+    match value.get(key).and_then(JsonValue::as_f32) {
+        Some(v) if v > 0.0 => Ok(v),
+        _ => Err(format!("shape needs a positive \"{key}\"")),
+    }
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+/// A world direction in a body's local space.
+fn rotate_to_local(body: Option<&RigidBody>, (x, y): Point) -> Point {
+    match body {
+        Some(b) => {
+            let (sin, cos) = math::sin_cos(b.angle);
+            (x * cos + y * sin, -x * sin + y * cos)
+        }
+        None => (x, y),
+    }
+}
+
+/// A world point in a body's local space.
+fn to_body_local(body: Option<&RigidBody>, (x, y): Point) -> Point {
+    match body {
+        Some(b) => rotate_to_local(Some(b), (x - b.x, y - b.y)),
+        None => (x, y),
+    }
+}
@@ -7,6 +7,28 @@ const SLEEP_VELOCITY_THRESHOLD: f32 = 8.0;
 const SLEEP_ANGULAR_THRESHOLD: f32 = 0.5;
 const SLEEP_TIME_THRESHOLD: f32 = 0.5;
 
+/// Thresholds below which an island counts as still, and how long it must stay
+/// still before it sleeps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SleepConfig {
+    /// Linear speed threshold (units/s).
+    pub velocity: f32,
+    /// Angular speed threshold (rad/s).
+    pub angular_velocity: f32,
+    /// Seconds below both thresholds before sleeping.
+    pub time: f32,
+}
+
+impl Default for SleepConfig {
+    fn default() -> Self {
+        Self {
+            velocity: SLEEP_VELOCITY_THRESHOLD,
+            angular_velocity: SLEEP_ANGULAR_THRESHOLD,
+            time: SLEEP_TIME_THRESHOLD,
+        }
+    }
+}
+
 /// Island-based sleep: groups of connected dynamic bodies sleep/wake together.
 /// This prevents cascading wake in stacks where individual bodies sleep at different
 /// times, corrupting the warm start cache and causing endless blinking.
@@ -14,6 +36,16 @@ pub fn update_sleep(
     bodies: &mut [Option<RigidBody>],
     contacts: &[Contact],
     dt: f32,
+) {
+    update_sleep_with(bodies, contacts, dt, &SleepConfig::default());
+}
+
+/// [`update_sleep`] with custom thresholds.
+pub fn update_sleep_with(
+    bodies: &mut [Option<RigidBody>],
+    contacts: &[Contact],
+    dt: f32,
+    config: &SleepConfig,
 ) {
     let len = bodies.len();
     if len == 0 {
@@ -73,7 +105,7 @@ pub fn update_sleep(
         island_map.entry(root).or_default().push(i);
     }
 
-    let threshold_sq = SLEEP_VELOCITY_THRESHOLD * SLEEP_VELOCITY_THRESHOLD;
+    let threshold_sq = config.velocity * config.velocity;
 
     // Process each island
     for (_root, members) in &island_map {
@@ -81,7 +113,7 @@ pub fn update_sleep(
         let all_below = members.iter().all(|&i| {
             let b = bodies[i].as_ref().unwrap();
             let speed_sq = b.vx * b.vx + b.vy * b.vy;
-            speed_sq < threshold_sq && b.angular_velocity.abs() < SLEEP_ANGULAR_THRESHOLD
+            speed_sq < threshold_sq && b.angular_velocity.abs() < config.angular_velocity
         });
 
         if all_below {
@@ -94,7 +126,7 @@ pub fn update_sleep(
             }
 
             // Only sleep the entire island when ALL members have been still long enough
-            if min_timer > SLEEP_TIME_THRESHOLD {
+            if min_timer > config.time {
                 for &i in members {
                     let b = bodies[i].as_mut().unwrap();
                     b.sleeping = true;
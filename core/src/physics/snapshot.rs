@@ -0,0 +1,611 @@
+//! Binary encoding for [`PhysicsWorld::snapshot`](super::world::PhysicsWorld::snapshot).
+//!
+//! Little-endian, floats stored by their bits so a restored world continues
+//! exactly where the original left off. The layout is private to the engine
+//! and versioned by [`VERSION`]; snapshots are meant for rewind, quick saves
+//! and netcode rollback within one engine build, not long-term storage.
+
+use super::projectile::*;
+use super::types::*;
+use super::vehicle::*;
+
+/// Leading bytes of every snapshot.
+pub(crate) const MAGIC: &[u8; 4] = b"APHY";
+/// Bumped whenever the layout changes. Older snapshots are rejected.
+pub(crate) const VERSION: u32 = 3;
+
+pub(crate) struct Writer(Vec<u8>);
+
+impl Writer {
+    pub fn new() -> Self {
+        let mut w = Self(Vec::new());
+        w.0.extend_from_slice(MAGIC);
+        w.u32(VERSION);
+        w
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    pub fn bool(&mut self, v: bool) {
+        self.u8(v as u8);
+    }
+
+    pub fn u16(&mut self, v: u16) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, v: u64) {
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn f32(&mut self, v: f32) {
+        self.u32(v.to_bits());
+    }
+
+    pub fn vec2(&mut self, v: (f32, f32)) {
+        self.f32(v.0);
+        self.f32(v.1);
+    }
+
+    pub fn count(&mut self, n: usize) {
+        self.u32(n as u32);
+    }
+
+    pub fn body(&mut self, b: &RigidBody) {
+        self.u32(b.id);
+        self.u8(match b.body_type {
+            BodyType::Static => 0,
+            BodyType::Dynamic => 1,
+            BodyType::Kinematic => 2,
+        });
+        self.shape(&b.shape);
+        self.f32(b.material.restitution);
+        self.f32(b.material.friction);
+        for v in [b.x, b.y, b.angle, b.vx, b.vy, b.angular_velocity, b.fx, b.fy, b.torque] {
+            self.f32(v);
+        }
+        for v in [b.mass, b.inv_mass, b.inertia, b.inv_inertia] {
+            self.f32(v);
+        }
+        for v in [b.linear_damping, b.angular_damping, b.max_speed, b.max_angular_speed] {
+            self.f32(v);
+        }
+        for v in [b.lock_rotation, b.lock_x, b.lock_y, b.is_sensor] {
+            self.bool(v);
+        }
+        self.bool(b.one_way.is_some());
+        if let Some(dir) = b.one_way {
+            self.vec2(dir);
+        }
+        self.u16(b.layer);
+        self.u16(b.mask);
+        self.bool(b.sleeping);
+        self.f32(b.sleep_timer);
+    }
+
+    fn shape(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Circle { radius } => {
+                self.u8(0);
+                self.f32(*radius);
+            }
+            Shape::AABB { half_w, half_h } => {
+                self.u8(1);
+                self.vec2((*half_w, *half_h));
+            }
+            Shape::Polygon { vertices } => {
+                self.u8(2);
+                self.count(vertices.len());
+                for &v in vertices {
+                    self.vec2(v);
+                }
+            }
+            Shape::Capsule { half_height, radius } => {
+                self.u8(3);
+                self.vec2((*half_height, *radius));
+            }
+            Shape::OBB { half_w, half_h } => {
+                self.u8(4);
+                self.vec2((*half_w, *half_h));
+            }
+        }
+    }
+
+    fn soft(&mut self, soft: SoftConstraintParams) {
+        self.f32(soft.frequency_hz);
+        self.f32(soft.damping_ratio);
+    }
+
+    fn opt_soft(&mut self, soft: Option<SoftConstraintParams>) {
+        self.bool(soft.is_some());
+        if let Some(soft) = soft {
+            self.soft(soft);
+        }
+    }
+
+    fn opt_motor(&mut self, motor: Option<JointMotor>) {
+        self.bool(motor.is_some());
+        if let Some(m) = motor {
+            self.f32(m.speed);
+            self.f32(m.max_force);
+            self.f32(m.accumulated_impulse);
+        }
+    }
+
+    pub fn constraint(&mut self, c: &Constraint) {
+        match *c {
+            Constraint::Distance { id, body_a, body_b, distance, anchor_a, anchor_b, soft, accumulated_impulse } => {
+                self.u8(0);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.f32(distance);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.opt_soft(soft);
+                self.f32(accumulated_impulse);
+            }
+            Constraint::Revolute { id, body_a, body_b, anchor_a, anchor_b, soft, accumulated_impulse, motor } => {
+                self.u8(1);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.opt_soft(soft);
+                self.vec2(accumulated_impulse);
+                self.opt_motor(motor);
+            }
+            Constraint::Prismatic {
+                id, body_a, body_b, anchor_a, anchor_b, axis, reference_angle, lower, upper, motor,
+                accumulated_impulse,
+            } => {
+                self.u8(2);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.vec2(axis);
+                self.f32(reference_angle);
+                self.f32(lower);
+                self.f32(upper);
+                self.opt_motor(motor);
+                self.vec2(accumulated_impulse);
+            }
+            Constraint::Weld { id, body_a, body_b, anchor_a, anchor_b, reference_angle, accumulated_impulse } => {
+                self.u8(3);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.f32(reference_angle);
+                self.vec2(accumulated_impulse);
+            }
+            Constraint::Rope { id, body_a, body_b, max_length, anchor_a, anchor_b, accumulated_impulse } => {
+                self.u8(4);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.f32(max_length);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.f32(accumulated_impulse);
+            }
+            Constraint::Pulley {
+                id, body_a, body_b, ground_a, ground_b, anchor_a, anchor_b, ratio, total_length,
+                accumulated_impulse,
+            } => {
+                self.u8(5);
+                self.u32(id);
+                self.u32(body_a);
+                self.u32(body_b);
+                self.vec2(ground_a);
+                self.vec2(ground_b);
+                self.vec2(anchor_a);
+                self.vec2(anchor_b);
+                self.f32(ratio);
+                self.f32(total_length);
+                self.f32(accumulated_impulse);
+            }
+            Constraint::Mouse { id, body, target, anchor, max_force, soft, accumulated_impulse } => {
+                self.u8(6);
+                self.u32(id);
+                self.u32(body);
+                self.vec2(target);
+                self.vec2(anchor);
+                self.f32(max_force);
+                self.soft(soft);
+                self.vec2(accumulated_impulse);
+            }
+        }
+    }
+
+    pub fn vehicle(&mut self, v: &Vehicle) {
+        self.u32(v.id);
+        self.u32(v.chassis);
+        let def = &v.def;
+        self.u8(match def.view {
+            VehicleView::Side => 0,
+            VehicleView::TopDown => 1,
+        });
+        for value in [def.acceleration, def.max_speed, def.braking, def.max_steer, def.load] {
+            self.f32(value);
+        }
+        self.f32(def.traction.peak_slip);
+        self.f32(def.traction.sliding);
+        self.u16(def.ground_mask);
+        self.count(def.wheels.len());
+        for (wheel, state) in def.wheels.iter().zip(&v.wheels) {
+            self.vec2(wheel.anchor);
+            for value in [wheel.radius, wheel.suspension_length, wheel.frequency, wheel.damping, wheel.friction] {
+                self.f32(value);
+            }
+            self.bool(wheel.drive);
+            self.bool(wheel.steer);
+            for value in [state.spin, state.rotation, state.length, state.slip] {
+                self.f32(value);
+            }
+            self.bool(state.ground.is_some());
+            if let Some(ground) = state.ground {
+                self.u32(ground);
+            }
+        }
+        self.f32(v.input.throttle);
+        self.f32(v.input.brake);
+        self.f32(v.input.steer);
+    }
+
+    pub fn projectile_batch(&mut self, b: &ProjectileBatch) {
+        self.u32(b.id);
+        self.count(b.def.capacity);
+        self.f32(b.def.radius);
+        self.u16(b.def.mask);
+        self.count(b.projectiles().len());
+        for p in b.projectiles() {
+            for value in [p.x, p.y, p.vx, p.vy, p.ax, p.ay, p.life] {
+                self.f32(value);
+            }
+            self.u32(p.tag);
+        }
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Check the header and position the reader after it.
+    pub fn new(data: &'a [u8]) -> Result<Self, String> {
+        let mut r = Self { data, pos: 0 };
+        if r.bytes(MAGIC.len())? != MAGIC {
+            return Err("not a physics snapshot".to_string());
+        }
+        let version = r.u32()?;
+        if version != VERSION {
+            return Err(format!(
+                "unsupported physics snapshot version {version} (expected {VERSION})"
+            ));
+        }
+        Ok(r)
+    }
+
+    /// Fail unless every byte was consumed.
+    pub fn finish(self) -> Result<(), String> {
+        if self.pos == self.data.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "physics snapshot has {} trailing bytes",
+                self.data.len() - self.pos
+            ))
+        }
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| "physics snapshot is truncated".to_string())?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.bytes(N)?);
+        Ok(out)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, String> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => Err(format!("invalid bool {v} in physics snapshot")),
+        }
+    }
+
+    pub fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    pub fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    pub fn vec2(&mut self) -> Result<(f32, f32), String> {
+        Ok((self.f32()?, self.f32()?))
+    }
+
+    /// An element count, checked against the bytes left so corrupt input
+    /// cannot trigger a huge allocation.
+    pub fn count(&mut self, min_item_size: usize) -> Result<usize, String> {
+        let n = self.u32()? as usize;
+        if n.saturating_mul(min_item_size) > self.data.len() - self.pos {
+            return Err("physics snapshot is truncated".to_string());
+        }
+        Ok(n)
+    }
+
+    pub fn body(&mut self) -> Result<RigidBody, String> {
+        let id = self.u32()?;
+        let body_type = match self.u8()? {
+            0 => BodyType::Static,
+            1 => BodyType::Dynamic,
+            2 => BodyType::Kinematic,
+            v => return Err(format!("invalid body type {v} in physics snapshot")),
+        };
+        let shape = self.shape()?;
+        let material = Material { restitution: self.f32()?, friction: self.f32()? };
+        Ok(RigidBody {
+            id,
+            body_type,
+            shape,
+            material,
+            x: self.f32()?,
+            y: self.f32()?,
+            angle: self.f32()?,
+            vx: self.f32()?,
+            vy: self.f32()?,
+            angular_velocity: self.f32()?,
+            fx: self.f32()?,
+            fy: self.f32()?,
+            torque: self.f32()?,
+            mass: self.f32()?,
+            inv_mass: self.f32()?,
+            inertia: self.f32()?,
+            inv_inertia: self.f32()?,
+            linear_damping: self.f32()?,
+            angular_damping: self.f32()?,
+            max_speed: self.f32()?,
+            max_angular_speed: self.f32()?,
+            lock_rotation: self.bool()?,
+            lock_x: self.bool()?,
+            lock_y: self.bool()?,
+            is_sensor: self.bool()?,
+            one_way: if self.bool()? { Some(self.vec2()?) } else { None },
+            layer: self.u16()?,
+            mask: self.u16()?,
+            sleeping: self.bool()?,
+            sleep_timer: self.f32()?,
+        })
+    }
+
+    fn shape(&mut self) -> Result<Shape, String> {
+        Ok(match self.u8()? {
+            0 => Shape::Circle { radius: self.f32()? },
+            1 => {
+                let (half_w, half_h) = self.vec2()?;
+                Shape::AABB { half_w, half_h }
+            }
+            2 => {
+                let n = self.count(8)?;
+                let vertices = (0..n).map(|_| self.vec2()).collect::<Result<_, _>>()?;
+                Shape::Polygon { vertices }
+            }
+            3 => {
+                let (half_height, radius) = self.vec2()?;
+                Shape::Capsule { half_height, radius }
+            }
+            4 => {
+                let (half_w, half_h) = self.vec2()?;
+                Shape::OBB { half_w, half_h }
+            }
+            v => return Err(format!("invalid shape type {v} in physics snapshot")),
+        })
+    }
+
+    fn soft(&mut self) -> Result<SoftConstraintParams, String> {
+        Ok(SoftConstraintParams { frequency_hz: self.f32()?, damping_ratio: self.f32()? })
+    }
+
+    fn opt_soft(&mut self) -> Result<Option<SoftConstraintParams>, String> {
+        if self.bool()? { self.soft().map(Some) } else { Ok(None) }
+    }
+
+    fn opt_motor(&mut self) -> Result<Option<JointMotor>, String> {
+        if !self.bool()? {
+            return Ok(None);
+        }
+        Ok(Some(JointMotor {
+            speed: self.f32()?,
+            max_force: self.f32()?,
+            accumulated_impulse: self.f32()?,
+        }))
+    }
+
+    pub fn constraint(&mut self) -> Result<Constraint, String> {
+        Ok(match self.u8()? {
+            0 => Constraint::Distance {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                distance: self.f32()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                soft: self.opt_soft()?,
+                accumulated_impulse: self.f32()?,
+            },
+            1 => Constraint::Revolute {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                soft: self.opt_soft()?,
+                accumulated_impulse: self.vec2()?,
+                motor: self.opt_motor()?,
+            },
+            2 => Constraint::Prismatic {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                axis: self.vec2()?,
+                reference_angle: self.f32()?,
+                lower: self.f32()?,
+                upper: self.f32()?,
+                motor: self.opt_motor()?,
+                accumulated_impulse: self.vec2()?,
+            },
+            3 => Constraint::Weld {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                reference_angle: self.f32()?,
+                accumulated_impulse: self.vec2()?,
+            },
+            4 => Constraint::Rope {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                max_length: self.f32()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                accumulated_impulse: self.f32()?,
+            },
+            5 => Constraint::Pulley {
+                id: self.u32()?,
+                body_a: self.u32()?,
+                body_b: self.u32()?,
+                ground_a: self.vec2()?,
+                ground_b: self.vec2()?,
+                anchor_a: self.vec2()?,
+                anchor_b: self.vec2()?,
+                ratio: self.f32()?,
+                total_length: self.f32()?,
+                accumulated_impulse: self.f32()?,
+            },
+            6 => Constraint::Mouse {
+                id: self.u32()?,
+                body: self.u32()?,
+                target: self.vec2()?,
+                anchor: self.vec2()?,
+                max_force: self.f32()?,
+                soft: self.soft()?,
+                accumulated_impulse: self.vec2()?,
+            },
+            v => return Err(format!("invalid constraint type {v} in physics snapshot")),
+        })
+    }
+
+    pub fn vehicle(&mut self) -> Result<Vehicle, String> {
+        let id = self.u32()?;
+        let chassis = self.u32()?;
+        let view = match self.u8()? {
+            0 => VehicleView::Side,
+            1 => VehicleView::TopDown,
+            v => return Err(format!("invalid vehicle view {v} in physics snapshot")),
+        };
+        let mut def = VehicleDef {
+            view,
+            wheels: Vec::new(),
+            acceleration: self.f32()?,
+            max_speed: self.f32()?,
+            braking: self.f32()?,
+            max_steer: self.f32()?,
+            load: self.f32()?,
+            traction: TractionCurve { peak_slip: self.f32()?, sliding: self.f32()? },
+            ground_mask: self.u16()?,
+        };
+        let count = self.count(47)?;
+        let mut wheels = Vec::with_capacity(count);
+        for _ in 0..count {
+            def.wheels.push(WheelDef {
+                anchor: self.vec2()?,
+                radius: self.f32()?,
+                suspension_length: self.f32()?,
+                frequency: self.f32()?,
+                damping: self.f32()?,
+                friction: self.f32()?,
+                drive: self.bool()?,
+                steer: self.bool()?,
+            });
+            wheels.push(WheelState {
+                spin: self.f32()?,
+                rotation: self.f32()?,
+                length: self.f32()?,
+                slip: self.f32()?,
+                ground: if self.bool()? { Some(self.u32()?) } else { None },
+            });
+        }
+        let input = VehicleInput { throttle: self.f32()?, brake: self.f32()?, steer: self.f32()? };
+        Ok(Vehicle { id, chassis, def, input, wheels })
+    }
+
+    pub fn projectile_batch(&mut self) -> Result<ProjectileBatch, String> {
+        let id = self.u32()?;
+        let def = ProjectileBatchDef {
+            capacity: self.u32()? as usize,
+            radius: self.f32()?,
+            mask: self.u16()?,
+        };
+        if def.capacity > MAX_BATCH_CAPACITY {
+            return Err(format!("projectile batch {id} capacity {} is over the limit", def.capacity));
+        }
+        let mut batch = ProjectileBatch::new(id, def);
+        let count = self.count(32)?;
+        if count > def.capacity {
+            return Err(format!("projectile batch {id} holds {count} projectiles, over its capacity"));
+        }
+        for _ in 0..count {
+            let mut values = [0.0; 7];
+            for value in &mut values {
+                *value = self.f32()?;
+            }
+            let [x, y, vx, vy, ax, ay, life] = values;
+            batch.spawn(Projectile { x, y, vx, vy, ax, ay, life, tag: self.u32()? });
+        }
+        Ok(batch)
+    }
+}
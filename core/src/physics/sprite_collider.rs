@@ -0,0 +1,350 @@
+//! Polygon colliders traced from a sprite's alpha channel.
+//!
+//! Marching squares finds the outline of the opaque pixels, Douglas-Peucker
+//! simplifies it, and the outline is cut into convex pieces (polygon bodies
+//! must be convex): ear clipping first, then neighbouring triangles are merged
+//! back together while the result stays convex and has at most 8 vertices.
+//! Holes are filled.
+//!
+//! Points are in pixels relative to the image center, wound like
+//! `Shape::Polygon` expects, so a sprite drawn at its natural size with the
+//! default centered origin lines up with a body at the same position.
+
+use std::collections::HashMap;
+
+use super::math;
+
+/// Outlines enclosing less than this many square pixels are dropped as noise.
+const MIN_AREA: f32 = 1.0;
+/// Vertex limit per piece, the documented maximum for polygon shapes.
+const MAX_PIECE_VERTICES: usize = 8;
+
+type Point = (f32, f32);
+
+/// Convex polygons covering the pixels of an RGBA8 image whose alpha is above
+/// `threshold`. `epsilon` is the Douglas-Peucker tolerance in pixels; 0 keeps
+/// every corner of the traced outline.
+pub fn colliders_from_rgba(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    threshold: u8,
+    epsilon: f32,
+) -> Vec<Vec<Point>> {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 || rgba.len() < w * h * 4 {
+        return Vec::new();
+    }
+    let solid = |x: i32, y: i32| -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < w
+            && (y as usize) < h
+            && rgba[(y as usize * w + x as usize) * 4 + 3] > threshold
+    };
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+
+    let mut pieces = Vec::new();
+    for outline in trace_outlines(w as i32, h as i32, solid) {
+        let outline = simplify_loop(&outline, epsilon.max(0.0));
+        // Holes wind the other way and are filled by their outer outline
+        if outline.len() < 3 || signed_area(&outline) < MIN_AREA {
+            continue;
+        }
+        for piece in convex_pieces(&outline) {
+            pieces.push(piece.iter().map(|&(x, y)| (x - cx, y - cy)).collect());
+        }
+    }
+    pieces
+}
+
+/// `[[x0,y0,x1,y1,...],...]`, one array per piece.
+pub fn pieces_json(pieces: &[Vec<Point>]) -> String {
+    let items: Vec<String> = pieces
+        .iter()
+        .map(|piece| {
+            let coords: Vec<String> = piece
+                .iter()
+                .flat_map(|&(x, y)| [x.to_string(), y.to_string()])
+                .collect();
+            format!("[{}]", coords.join(","))
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+// ---------------------------------------------------------------------------
+// Marching squares
+// ---------------------------------------------------------------------------
+
+/// Cell edges, as used by the segment table.
+const TOP: u8 = 0;
+const RIGHT: u8 = 1;
+const BOTTOM: u8 = 2;
+const LEFT: u8 = 3;
+
+/// Segments per cell case (bits: top-left 8, top-right 4, bottom-right 2,
+/// bottom-left 1), directed so the solid side is on the left of travel in
+/// y-down coordinates. Saddles keep their two corners apart.
+fn cell_segments(case: u8) -> &'static [(u8, u8)] {
+    match case {
+        1 => &[(LEFT, BOTTOM)],
+        2 => &[(BOTTOM, RIGHT)],
+        3 => &[(LEFT, RIGHT)],
+        4 => &[(RIGHT, TOP)],
+        5 => &[(LEFT, BOTTOM), (RIGHT, TOP)],
+        6 => &[(BOTTOM, TOP)],
+        7 => &[(LEFT, TOP)],
+        8 => &[(TOP, LEFT)],
+        9 => &[(TOP, BOTTOM)],
+        10 => &[(TOP, LEFT), (BOTTOM, RIGHT)],
+        11 => &[(TOP, RIGHT)],
+        12 => &[(RIGHT, LEFT)],
+        13 => &[(RIGHT, BOTTOM)],
+        14 => &[(BOTTOM, LEFT)],
+        _ => &[],
+    }
+}
+
+/// Closed outlines of the solid pixels, in pixel coordinates. Samples sit at
+/// pixel centers, so straight runs follow pixel edges and corners are cut
+/// diagonally.
+fn trace_outlines(w: i32, h: i32, solid: impl Fn(i32, i32) -> bool) -> Vec<Vec<Point>> {
+    // Edge midpoints in doubled cell coordinates, so they stay integers.
+    // Cell (x, y) has the pixel centers (x-1, y-1) .. (x, y) as corners.
+    let midpoint = |x: i32, y: i32, edge: u8| match edge {
+        TOP => (2 * x + 1, 2 * y),
+        RIGHT => (2 * x + 2, 2 * y + 1),
+        BOTTOM => (2 * x + 1, 2 * y + 2),
+        _ => (2 * x, 2 * y + 1),
+    };
+
+    let mut starts = Vec::new();
+    let mut next: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    for y in 0..=h {
+        for x in 0..=w {
+            let case = (solid(x - 1, y - 1) as u8) << 3
+                | (solid(x, y - 1) as u8) << 2
+                | (solid(x, y) as u8) << 1
+                | solid(x - 1, y) as u8;
+            for &(from, to) in cell_segments(case) {
+                let start = midpoint(x, y, from);
+                starts.push(start);
+                next.insert(start, midpoint(x, y, to));
+            }
+        }
+    }
+
+    let mut outlines = Vec::new();
+    for start in starts {
+        let mut outline = Vec::new();
+        let mut at = start;
+        while let Some(to) = next.remove(&at) {
+            // Back to pixel coordinates: cell corner x sits on pixel center x - 0.5
+            outline.push((at.0 as f32 / 2.0 - 0.5, at.1 as f32 / 2.0 - 0.5));
+            at = to;
+        }
+        if !outline.is_empty() {
+            outlines.push(outline);
+        }
+    }
+    outlines
+}
+
+// ---------------------------------------------------------------------------
+// Simplification
+// ---------------------------------------------------------------------------
+
+fn cross(o: Point, a: Point, b: Point) -> f32 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Shoelace area, positive for outer outlines.
+fn signed_area(points: &[Point]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+/// Douglas-Peucker over an open polyline; keeps both ends.
+fn simplify_polyline(points: &[Point], epsilon: f32, out: &mut Vec<Point>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let farthest = (1..points.len() - 1)
+        .map(|i| (i, distance_to_segment(points[i], first, last)))
+        .fold((0, 0.0f32), |best, x| if x.1 > best.1 { x } else { best });
+    if farthest.1 > epsilon {
+        simplify_polyline(&points[..=farthest.0], epsilon, out);
+        out.pop();
+        simplify_polyline(&points[farthest.0..], epsilon, out);
+    } else {
+        out.push(first);
+        out.push(last);
+    }
+}
+
+/// Douglas-Peucker over a closed outline, split at the point farthest from
+/// the first one. Collinear points go even with `epsilon` 0.
+fn simplify_loop(outline: &[Point], epsilon: f32) -> Vec<Point> {
+    if outline.len() < 4 {
+        return outline.to_vec();
+    }
+    let first = outline[0];
+    let split = (1..outline.len())
+        .max_by(|&a, &b| {
+            let da = math::hypot(outline[a].0 - first.0, outline[a].1 - first.1);
+            let db = math::hypot(outline[b].0 - first.0, outline[b].1 - first.1);
+            da.total_cmp(&db)
+        })
+        .unwrap_or(1);
+    let mut closed = outline.to_vec();
+    closed.push(first);
+
+    let mut points = Vec::new();
+    simplify_polyline(&closed[..=split], epsilon, &mut points);
+    points.pop();
+    simplify_polyline(&closed[split..], epsilon, &mut points);
+    points.pop();
+
+    // Drop points on a straight line between their neighbours
+    let mut i = 0;
+    while points.len() > 3 && i < points.len() {
+        let n = points.len();
+        let (prev, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+        if cross(prev, points[i], next).abs() < 1e-6 {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    points
+}
+
+// ---------------------------------------------------------------------------
+// Convex decomposition
+// ---------------------------------------------------------------------------
+
+fn in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+/// Ear clipping. Returns triangles as indices into `outline`, wound like it.
+fn triangulate(outline: &[Point]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..outline.len()).collect();
+    let mut triangles = Vec::with_capacity(outline.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            )
+        };
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+            let (pa, pb, pc) = (outline[a], outline[b], outline[c]);
+            cross(pa, pb, pc) > 0.0
+                && remaining
+                    .iter()
+                    .filter(|&&j| j != a && j != b && j != c)
+                    .all(|&j| {
+                        outline[j] == pa
+                            || outline[j] == pb
+                            || outline[j] == pc
+                            || !in_triangle(outline[j], pa, pb, pc)
+                    })
+        };
+        // A simplified outline can touch itself; clip a convex corner then
+        let ear = (0..n).find(|&i| is_ear(i)).or_else(|| {
+            (0..n).find(|&i| {
+                let (a, b, c) = corner(i);
+                cross(outline[a], outline[b], outline[c]) > 0.0
+            })
+        });
+        let Some(i) = ear else { break };
+        let (a, b, c) = corner(i);
+        triangles.push([a, b, c]);
+        remaining.remove(i);
+    }
+    if let [a, b, c] = remaining[..]
+        && cross(outline[a], outline[b], outline[c]) > 0.0
+    {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+fn is_convex(polygon: &[usize], outline: &[Point]) -> bool {
+    let n = polygon.len();
+    (0..n).all(|i| {
+        cross(
+            outline[polygon[i]],
+            outline[polygon[(i + 1) % n]],
+            outline[polygon[(i + 2) % n]],
+        ) >= -1e-4
+    })
+}
+
+/// Join `a` and `b` along their shared edge, if they have one.
+fn merge_along_shared_edge(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let (na, nb) = (a.len(), b.len());
+    for i in 0..na {
+        let (from, to) = (a[i], a[(i + 1) % na]);
+        // The neighbour runs the shared edge the other way
+        let Some(j) = (0..nb).find(|&j| b[j] == to && b[(j + 1) % nb] == from) else {
+            continue;
+        };
+        // a from `to` round to `from`, then b's vertices strictly between them
+        let mut merged: Vec<usize> = (0..na).map(|k| a[(i + 1 + k) % na]).collect();
+        merged.extend((2..nb).map(|k| b[(j + k) % nb]));
+        return Some(merged);
+    }
+    None
+}
+
+/// Hertel-Mehlhorn: triangulate, then drop diagonals whose two sides merge
+/// into a convex polygon of at most `MAX_PIECE_VERTICES`.
+fn convex_pieces(outline: &[Point]) -> Vec<Vec<Point>> {
+    let mut polygons: Vec<Vec<usize>> = triangulate(outline).iter().map(|t| t.to_vec()).collect();
+    let mut merged_any = true;
+    while merged_any {
+        merged_any = false;
+        let mut i = 0;
+        while i < polygons.len() {
+            let mut j = i + 1;
+            while j < polygons.len() {
+                match merge_along_shared_edge(&polygons[i], &polygons[j]) {
+                    Some(merged)
+                        if merged.len() <= MAX_PIECE_VERTICES && is_convex(&merged, outline) =>
+                    {
+                        polygons[i] = merged;
+                        polygons.remove(j);
+                        merged_any = true;
+                    }
+                    _ => j += 1,
+                }
+            }
+            i += 1;
+        }
+    }
+    polygons
+        .iter()
+        .map(|p| p.iter().map(|&k| outline[k]).collect())
+        .collect()
+}
@@ -1,3 +1,5 @@
+use super::math;
+
 pub type BodyId = u32;
 pub type ConstraintId = u32;
 
@@ -144,6 +146,14 @@ impl SoftConstraintParams {
         }
     }
 
+    /// Responsive but smooth drag spring used by mouse joints.
+    pub fn mouse() -> Self {
+        Self {
+            frequency_hz: 5.0,
+            damping_ratio: 0.7,
+        }
+    }
+
     /// Returns true if this is a rigid (non-soft) constraint.
     pub fn is_rigid(&self) -> bool {
         self.frequency_hz <= 0.0
@@ -233,6 +243,11 @@ pub enum Shape {
     Circle { radius: f32 },
     AABB { half_w: f32, half_h: f32 },
     Polygon { vertices: Vec<(f32, f32)> },
+    /// Segment from (0, -half_height) to (0, half_height) in body space,
+    /// swept by `radius`. Rotates with the body.
+    Capsule { half_height: f32, radius: f32 },
+    /// Box that rotates with the body, unlike `AABB`.
+    OBB { half_w: f32, half_h: f32 },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -269,12 +284,68 @@ pub struct RigidBody {
     pub inv_mass: f32,
     pub inertia: f32,
     pub inv_inertia: f32,
+    /// Linear velocity damping per second (0 = none). Top-down "friction".
+    pub linear_damping: f32,
+    /// Angular velocity damping per second (0 = none).
+    pub angular_damping: f32,
+    /// Keep the body from rotating (infinite inertia while set).
+    pub lock_rotation: bool,
+    /// Freeze the body's X coordinate (moves only vertically).
+    pub lock_x: bool,
+    /// Freeze the body's Y coordinate (e.g. lane-based games).
+    pub lock_y: bool,
+    /// Linear speed cap (0 = unlimited).
+    pub max_speed: f32,
+    /// Angular speed cap in rad/s (0 = unlimited).
+    pub max_angular_speed: f32,
+    /// Detect overlaps and report contacts and collision events, but never
+    /// push or get pushed (pickups, trigger zones, hitboxes).
+    pub is_sensor: bool,
+    /// One-way platform: unit direction, in the body's local frame, of the
+    /// side others may land on. Bodies reaching it from any other side pass
+    /// through until they have fully separated. `None` = solid from all sides.
+    pub one_way: Option<(f32, f32)>,
     pub layer: u16,
     pub mask: u16,
     pub sleeping: bool,
     pub sleep_timer: f32,
 }
 
+/// A body overlapping a query shape (see `PhysicsWorld::overlap_*`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlapHit {
+    pub body_id: BodyId,
+    /// Unit normal pointing from the query shape toward the body.
+    pub normal: (f32, f32),
+    /// Overlap depth along `normal`.
+    pub penetration: f32,
+    /// Approximate world-space contact point.
+    pub point: (f32, f32),
+}
+
+/// Scene query counters (see `PhysicsWorld::query_stats`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryStats {
+    /// `query_aabb` calls.
+    pub aabb: u32,
+    /// `overlap_circle`/`overlap_box`/`overlap_polygon` calls.
+    pub overlap: u32,
+    /// Raycasts, including those made by vehicles and projectiles.
+    pub raycast: u32,
+    /// Bodies tested against a query's exact shape.
+    pub candidates: u32,
+    /// Queries that scanned every body because the query grid was not
+    /// usable (during a step, or after many moves from game code).
+    pub full_scans: u32,
+}
+
+impl QueryStats {
+    /// Total number of queries.
+    pub fn total(&self) -> u32 {
+        self.aabb + self.overlap + self.raycast
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Contact {
     pub body_a: BodyId,
@@ -290,6 +361,64 @@ pub struct Contact {
     pub velocity_bias: f32,
     /// Friction tangent direction (computed once before solver iterations)
     pub tangent: (f32, f32),
+    /// Total normal impulse the solver applied to this pair over the frame
+    /// (N·s). Scale by the body's mass for "how hard did we hit".
+    pub impulse: f32,
+    /// Velocity of B relative to A at the contact point when it was detected.
+    /// Dot with `normal` for the closing speed (negative = approaching).
+    pub relative_velocity: (f32, f32),
+}
+
+/// How a pair's touching state changed, see [`CollisionEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionEventKind {
+    /// The bodies started touching.
+    Begin,
+    /// The bodies were already touching and still are.
+    Stay,
+    /// The bodies stopped touching, or one of them was removed.
+    End,
+}
+
+/// A change in which bodies touch, reported once per `PhysicsWorld::step()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionEvent {
+    pub kind: CollisionEventKind,
+    /// The lower body ID of the pair.
+    pub body_a: BodyId,
+    pub body_b: BodyId,
+    /// Contact normal pointing from A to B. For `End`, the last one seen.
+    pub normal: (f32, f32),
+    /// Contact point in world space. For `End`, the last one seen.
+    pub point: (f32, f32),
+}
+
+/// Default mouse joint force limit per unit of body mass. In pixel units this
+/// is ~100× typical gravity: the grab point follows the cursor closely while
+/// still giving up against walls and very heavy loads.
+pub const MOUSE_FORCE_PER_MASS: f32 = 50_000.0;
+
+/// Drives a revolute joint's relative rotation or a prismatic joint's
+/// translation toward a target speed, with a limited torque/force.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointMotor {
+    /// Target relative speed: rad/s for revolute joints, units/s along the
+    /// axis for prismatic joints.
+    pub speed: f32,
+    /// Maximum torque (revolute) or force (prismatic) the motor applies.
+    pub max_force: f32,
+    /// Impulse applied within the current sub-step
+    pub accumulated_impulse: f32,
+}
+
+impl JointMotor {
+    pub fn new(speed: f32, max_force: f32) -> Self {
+        Self {
+            speed,
+            max_force: max_force.max(0.0),
+            accumulated_impulse: 0.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -316,6 +445,85 @@ pub enum Constraint {
         soft: Option<SoftConstraintParams>,
         /// Accumulated impulse for warm starting (x, y)
         accumulated_impulse: (f32, f32),
+        /// Spins body B relative to body A (wheels, doors, windmills)
+        motor: Option<JointMotor>,
+    },
+    /// Slider: body B keeps its rotation relative to A and moves only along
+    /// an axis fixed in A (elevators, pistons, sliding doors).
+    Prismatic {
+        id: ConstraintId,
+        body_a: BodyId,
+        body_b: BodyId,
+        anchor_a: (f32, f32),
+        anchor_b: (f32, f32),
+        /// Slide direction in A's local space (normalized at creation)
+        axis: (f32, f32),
+        /// Relative angle (B - A), set from the bodies at creation
+        reference_angle: f32,
+        /// Translation limits along the axis, relative to the anchors
+        /// coinciding. Infinite = unlimited on that side.
+        lower: f32,
+        upper: f32,
+        /// Drives the translation along the axis
+        motor: Option<JointMotor>,
+        /// Lower and upper limit impulses within the current sub-step
+        accumulated_impulse: (f32, f32),
+    },
+    /// Glues two bodies together at an anchor, keeping their relative angle.
+    Weld {
+        id: ConstraintId,
+        body_a: BodyId,
+        body_b: BodyId,
+        anchor_a: (f32, f32),
+        anchor_b: (f32, f32),
+        /// Relative angle (B - A), set from the bodies at creation
+        reference_angle: f32,
+        /// Accumulated point impulse (x, y)
+        accumulated_impulse: (f32, f32),
+    },
+    /// Max-distance only: slack below `max_length`, pulls like a rope when taut.
+    Rope {
+        id: ConstraintId,
+        body_a: BodyId,
+        body_b: BodyId,
+        max_length: f32,
+        anchor_a: (f32, f32),
+        anchor_b: (f32, f32),
+        /// Accumulated (pulling, <= 0) impulse within the current sub-step
+        accumulated_impulse: f32,
+    },
+    /// Two bodies hung from fixed world points by one rope:
+    /// `length_a + ratio * length_b` stays equal to `total_length`.
+    Pulley {
+        id: ConstraintId,
+        body_a: BodyId,
+        body_b: BodyId,
+        /// Fixed world-space pulley points above each body
+        ground_a: (f32, f32),
+        ground_b: (f32, f32),
+        anchor_a: (f32, f32),
+        anchor_b: (f32, f32),
+        /// Mechanical advantage: > 1 makes side B move less than side A
+        ratio: f32,
+        /// `length_a + ratio * length_b`; <= 0 at creation = current length
+        total_length: f32,
+        accumulated_impulse: f32,
+    },
+    /// Drags a body's grab point toward a movable world target with a soft,
+    /// force-limited spring (click-and-drag). Released automatically when the
+    /// body is removed.
+    Mouse {
+        id: ConstraintId,
+        body: BodyId,
+        /// World-space point the grab point is pulled toward
+        target: (f32, f32),
+        /// Body-local grab point
+        anchor: (f32, f32),
+        /// Maximum pulling force. <= 0 at creation = `MOUSE_FORCE_PER_MASS` × body mass
+        max_force: f32,
+        /// Spring tuning. A rigid (0 Hz) setting falls back to the default 5 Hz
+        soft: SoftConstraintParams,
+        accumulated_impulse: (f32, f32),
     },
 }
 
@@ -324,6 +532,24 @@ impl Constraint {
         match self {
             Constraint::Distance { id, .. } => *id,
             Constraint::Revolute { id, .. } => *id,
+            Constraint::Prismatic { id, .. } => *id,
+            Constraint::Weld { id, .. } => *id,
+            Constraint::Rope { id, .. } => *id,
+            Constraint::Pulley { id, .. } => *id,
+            Constraint::Mouse { id, .. } => *id,
+        }
+    }
+
+    /// The bodies this constraint joins (a mouse joint drags just one).
+    pub fn bodies(&self) -> (BodyId, Option<BodyId>) {
+        match *self {
+            Constraint::Distance { body_a, body_b, .. }
+            | Constraint::Revolute { body_a, body_b, .. }
+            | Constraint::Prismatic { body_a, body_b, .. }
+            | Constraint::Weld { body_a, body_b, .. }
+            | Constraint::Rope { body_a, body_b, .. }
+            | Constraint::Pulley { body_a, body_b, .. } => (body_a, Some(body_b)),
+            Constraint::Mouse { body, .. } => (body, None),
         }
     }
 }
@@ -348,11 +574,32 @@ pub fn compute_mass_and_inertia(shape: &Shape, mass: f32, body_type: BodyType) -
             // Approximate inertia using polygon area moment
             compute_polygon_inertia(vertices, mass)
         }
+        Shape::Capsule { half_height, radius } => compute_capsule_inertia(*half_height, *radius, mass),
+        Shape::OBB { half_w, half_h } => mass * (half_w * half_w + half_h * half_h) / 3.0,
     };
     let inv_inertia = if inertia > 0.0 { 1.0 / inertia } else { 0.0 };
     (inv_mass, inertia, inv_inertia)
 }
 
+/// Inertia of a capsule: a `2r × 2h` box plus two half discs at its ends.
+fn compute_capsule_inertia(half_height: f32, radius: f32, mass: f32) -> f32 {
+    let box_area = 4.0 * radius * half_height;
+    let disc_area = std::f32::consts::PI * radius * radius;
+    let area = box_area + disc_area;
+    if area <= 0.0 {
+        return 0.0;
+    }
+    let box_mass = mass * box_area / area;
+    let disc_mass = mass * disc_area / area;
+    let r2 = radius * radius;
+    let h2 = half_height * half_height;
+    // Each half disc's centroid sits 4r/3π beyond the box end (parallel axis theorem)
+    let lc = 4.0 * radius / (3.0 * std::f32::consts::PI);
+    let box_inertia = box_mass * (r2 + h2) / 3.0;
+    let disc_inertia = disc_mass * (0.5 * r2 + h2 + 2.0 * half_height * lc);
+    box_inertia + disc_inertia
+}
+
 fn compute_polygon_inertia(vertices: &[(f32, f32)], mass: f32) -> f32 {
     let n = vertices.len();
     if n < 3 {
@@ -382,7 +629,7 @@ pub fn get_shape_aabb(body: &RigidBody) -> (f32, f32, f32, f32) {
             body.x + radius,
             body.y + radius,
         ),
-        Shape::AABB { half_w, half_h } => {
+        Shape::AABB { half_w, half_h } | Shape::OBB { half_w, half_h } => {
             if body.angle.abs() < 1e-6 {
                 (
                     body.x - half_w,
@@ -392,8 +639,8 @@ pub fn get_shape_aabb(body: &RigidBody) -> (f32, f32, f32, f32) {
                 )
             } else {
                 // Rotated AABB: compute bounding box of rotated corners
-                let cos = body.angle.cos();
-                let sin = body.angle.sin();
+                let cos = math::cos(body.angle);
+                let sin = math::sin(body.angle);
                 let hw = (half_w * cos.abs()) + (half_h * sin.abs());
                 let hh = (half_w * sin.abs()) + (half_h * cos.abs());
                 (body.x - hw, body.y - hh, body.x + hw, body.y + hh)
@@ -403,8 +650,8 @@ pub fn get_shape_aabb(body: &RigidBody) -> (f32, f32, f32, f32) {
             if vertices.is_empty() {
                 return (body.x, body.y, body.x, body.y);
             }
-            let cos = body.angle.cos();
-            let sin = body.angle.sin();
+            let cos = math::cos(body.angle);
+            let sin = math::sin(body.angle);
             let mut min_x = f32::MAX;
             let mut min_y = f32::MAX;
             let mut max_x = f32::MIN;
@@ -419,5 +666,10 @@ pub fn get_shape_aabb(body: &RigidBody) -> (f32, f32, f32, f32) {
             }
             (min_x, min_y, max_x, max_y)
         }
+        Shape::Capsule { half_height, radius } => {
+            let hw = half_height * math::sin(body.angle).abs() + radius;
+            let hh = half_height * math::cos(body.angle).abs() + radius;
+            (body.x - hw, body.y - hh, body.x + hw, body.y + hh)
+        }
     }
 }
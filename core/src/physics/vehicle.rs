@@ -0,0 +1,434 @@
+//! Vehicles: a dynamic chassis body carried and driven by raycast wheels.
+//!
+//! Wheels are not bodies, so fast vehicles stay stable where chains of joints
+//! would jitter. Every sub-step, before integration, each wheel pushes on the
+//! chassis:
+//!
+//! - Side view: the wheel casts a ray down its suspension (chassis-local +y).
+//!   A spring-damper holds the chassis up, and grip along the chassis'
+//!   forward axis (local +x) drives it.
+//! - Top down: every wheel is always on the ground, pressed down by the
+//!   vehicle's `load`. Grip along the wheel's heading drives the car, grip
+//!   across it stops it sliding sideways, and steering wheels turn with the
+//!   steer input.
+//!
+//! Each wheel spins on its own. The engine and brakes act on the spin, and
+//! tire friction closes the gap between the wheel's surface speed and the
+//! ground speed, up to the wheel's load times its friction. The traction
+//! curve lowers that limit once the tire slips, so flooring it spins the
+//! wheels and hard turns drift.
+
+use super::math;
+use super::types::*;
+use super::world::{point_velocity, PhysicsWorld};
+
+pub type VehicleId = u32;
+
+/// Share of a wheel's part of the chassis mass used as the wheel's own mass
+/// for its spin inertia.
+const WHEEL_MASS_FRACTION: f32 = 0.1;
+/// Ground speed (pixels/s) below which slip is measured against this speed
+/// instead, so it stays finite when starting from rest.
+const MIN_SLIP_SPEED: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VehicleView {
+    /// Platformer-style: gravity pulls the chassis onto its wheels.
+    Side,
+    /// Racing-game style: wheels are always on the ground.
+    TopDown,
+}
+
+/// How tire grip falls off with slip, the mismatch between wheel and ground
+/// speed relative to the ground speed (sideways speed for top-down cornering).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TractionCurve {
+    /// Slip up to which the tire keeps full grip.
+    pub peak_slip: f32,
+    /// Fraction of grip left once slip reaches twice `peak_slip`.
+    pub sliding: f32,
+}
+
+impl Default for TractionCurve {
+    fn default() -> Self {
+        Self { peak_slip: 0.2, sliding: 0.6 }
+    }
+}
+
+impl TractionCurve {
+    /// Fraction of full grip at `slip`: 1 up to the peak, then falling
+    /// linearly to `sliding`.
+    pub fn grip(&self, slip: f32) -> f32 {
+        if slip <= self.peak_slip {
+            return 1.0;
+        }
+        let t = ((slip - self.peak_slip) / self.peak_slip.max(1e-6)).min(1.0);
+        1.0 + (self.sliding - 1.0) * t
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WheelDef {
+    /// Mount point in chassis-local space (side view: the top of the suspension).
+    pub anchor: (f32, f32),
+    pub radius: f32,
+    /// Side view: distance from the mount to the wheel center with the
+    /// spring relaxed.
+    pub suspension_length: f32,
+    /// Side view: suspension spring frequency in Hz.
+    pub frequency: f32,
+    /// Side view: suspension damping ratio (1 = critically damped).
+    pub damping: f32,
+    /// Tire friction coefficient: the most force the tire transfers, as a
+    /// multiple of its load.
+    pub friction: f32,
+    /// Driven by the engine.
+    pub drive: bool,
+    /// Top down: turned by the steer input.
+    pub steer: bool,
+}
+
+impl Default for WheelDef {
+    fn default() -> Self {
+        Self {
+            anchor: (0.0, 0.0),
+            radius: 10.0,
+            suspension_length: 12.0,
+            frequency: 5.0,
+            damping: 0.7,
+            friction: 1.0,
+            drive: true,
+            steer: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleDef {
+    pub view: VehicleView,
+    pub wheels: Vec<WheelDef>,
+    /// Acceleration (pixels/s²) the engine gives the chassis at full throttle,
+    /// grip permitting. Scaled by the chassis mass, so it is mass-independent.
+    pub acceleration: f32,
+    /// Wheel surface speed (pixels/s) where the engine stops pushing.
+    pub max_speed: f32,
+    /// Deceleration (pixels/s²) at full brake, grip permitting.
+    pub braking: f32,
+    /// Top down: wheel angle at full steer, in radians.
+    pub max_steer: f32,
+    /// Top down: downward acceleration (pixels/s²) pressing the wheels onto
+    /// the ground. Side-view load comes from the suspension.
+    pub load: f32,
+    pub traction: TractionCurve,
+    /// Side view: collision layers the wheels roll on.
+    pub ground_mask: u16,
+}
+
+impl Default for VehicleDef {
+    fn default() -> Self {
+        Self {
+            view: VehicleView::Side,
+            wheels: Vec::new(),
+            acceleration: 400.0,
+            max_speed: 400.0,
+            braking: 800.0,
+            max_steer: 0.6,
+            load: 800.0,
+            traction: TractionCurve::default(),
+            ground_mask: 0xFFFF,
+        }
+    }
+}
+
+/// Driver controls, applied every step until changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VehicleInput {
+    /// -1 (full reverse) to 1 (full throttle).
+    pub throttle: f32,
+    /// 0 to 1.
+    pub brake: f32,
+    /// -1 to 1; positive turns clockwise (right on screen).
+    pub steer: f32,
+}
+
+impl VehicleInput {
+    /// Clamp the controls into their ranges (NaN counts as released).
+    pub fn clamped(self) -> Self {
+        let clamp = |v: f32, lo: f32| if v.is_nan() { 0.0 } else { v.clamp(lo, 1.0) };
+        Self {
+            throttle: clamp(self.throttle, -1.0),
+            brake: clamp(self.brake, 0.0),
+            steer: clamp(self.steer, -1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WheelState {
+    /// Spin in rad/s; positive rolls the vehicle forward.
+    pub spin: f32,
+    /// Spin angle in radians, for drawing the wheel.
+    pub rotation: f32,
+    /// Side view: current distance from the mount to the wheel center.
+    pub length: f32,
+    /// Body the wheel touched during the last sub-step, if any.
+    pub ground: Option<BodyId>,
+    /// Largest slip during the last sub-step, e.g. for skid marks and
+    /// tire squeal.
+    pub slip: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vehicle {
+    pub id: VehicleId,
+    pub chassis: BodyId,
+    pub def: VehicleDef,
+    pub input: VehicleInput,
+    /// One per `def.wheels`, in the same order.
+    pub wheels: Vec<WheelState>,
+}
+
+/// The chassis' motion during one sub-step, and the forces its wheels add.
+struct Chassis {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    angular_velocity: f32,
+    mass: f32,
+    inv_mass: f32,
+    inv_inertia: f32,
+    fx: f32,
+    fy: f32,
+    torque: f32,
+}
+
+impl Chassis {
+    fn point_velocity(&self, px: f32, py: f32) -> (f32, f32) {
+        let (rx, ry) = (px - self.x, py - self.y);
+        (self.vx - self.angular_velocity * ry, self.vy + self.angular_velocity * rx)
+    }
+
+    /// Mass a force along `dir` at (px, py) accelerates, rotation included.
+    fn effective_mass(&self, px: f32, py: f32, dir: (f32, f32)) -> f32 {
+        let arm = (px - self.x) * dir.1 - (py - self.y) * dir.0;
+        1.0 / (self.inv_mass + arm * arm * self.inv_inertia)
+    }
+
+    fn push(&mut self, px: f32, py: f32, fx: f32, fy: f32) {
+        self.fx += fx;
+        self.fy += fy;
+        self.torque += (px - self.x) * fy - (py - self.y) * fx;
+    }
+}
+
+impl Vehicle {
+    pub fn new(id: VehicleId, chassis: BodyId, def: VehicleDef) -> Self {
+        let wheels = def
+            .wheels
+            .iter()
+            .map(|w| WheelState { length: w.suspension_length, ..WheelState::default() })
+            .collect();
+        Self { id, chassis, def, input: VehicleInput::default(), wheels }
+    }
+
+    /// World position of wheel `index`'s center: its mount point, moved down
+    /// the suspension in side view.
+    pub fn wheel_position(&self, chassis: &RigidBody, index: usize) -> Option<(f32, f32)> {
+        let wheel = self.def.wheels.get(index)?;
+        let (sin, cos) = math::sin_cos(chassis.angle);
+        let (ax, ay) = wheel.anchor;
+        let (mut x, mut y) = (chassis.x + ax * cos - ay * sin, chassis.y + ax * sin + ay * cos);
+        if self.def.view == VehicleView::Side {
+            let length = self.wheels[index].length;
+            x -= sin * length;
+            y += cos * length;
+        }
+        Some((x, y))
+    }
+
+    /// Chassis speed along its forward axis (local +x).
+    pub fn forward_speed(chassis: &RigidBody) -> f32 {
+        let (sin, cos) = math::sin_cos(chassis.angle);
+        chassis.vx * cos + chassis.vy * sin
+    }
+
+    /// Apply one sub-step of wheel forces to the chassis (and, in side view,
+    /// the reaction to dynamic ground bodies). Sleeping vehicles without
+    /// input are left alone.
+    pub(super) fn step(&mut self, world: &mut PhysicsWorld, dt: f32) {
+        let Some(body) = world.get_body(self.chassis) else {
+            return;
+        };
+        let active = self.input != VehicleInput::default();
+        if body.body_type != BodyType::Dynamic || (body.sleeping && !active) || self.wheels.is_empty() {
+            return;
+        }
+        let angle = body.angle;
+        let (sin, cos) = math::sin_cos(angle);
+        let mut chassis = Chassis {
+            x: body.x,
+            y: body.y,
+            vx: body.vx,
+            vy: body.vy,
+            angular_velocity: body.angular_velocity,
+            mass: body.mass,
+            inv_mass: body.inv_mass,
+            inv_inertia: body.inv_inertia,
+            fx: 0.0,
+            fy: 0.0,
+            torque: 0.0,
+        };
+
+        let n = self.wheels.len() as f32;
+        let driven = self.def.wheels.iter().filter(|w| w.drive).count().max(1) as f32;
+        let mass_share = chassis.mass / n;
+        let input = self.input;
+        // Reactions on dynamic ground bodies: (body, point, force)
+        let mut reactions = Vec::new();
+
+        for (wheel, state) in self.def.wheels.iter().zip(self.wheels.iter_mut()) {
+            let (ax, ay) = wheel.anchor;
+            let mx = chassis.x + ax * cos - ay * sin;
+            let my = chassis.y + ax * sin + ay * cos;
+            let inertia = 0.5 * WHEEL_MASS_FRACTION * mass_share * wheel.radius * wheel.radius;
+            let engine = if wheel.drive { input.throttle * self.def.acceleration * chassis.mass / driven } else { 0.0 };
+            let brake = input.brake * self.def.braking * chassis.mass / n;
+            state.spin_up(wheel.radius, inertia, engine, brake, self.def.max_speed, dt);
+
+            match self.def.view {
+                VehicleView::Side => {
+                    let down = (-sin, cos);
+                    let forward = (cos, sin);
+                    let reach = wheel.suspension_length + wheel.radius;
+                    let mask = self.def.ground_mask;
+                    let chassis_id = self.chassis;
+                    let hit = world.raycast_where(mx, my, down.0, down.1, reach, |b| {
+                        b.id != chassis_id && !b.is_sensor && b.layer & mask != 0
+                    });
+                    let Some((ground_id, hx, hy, t)) = hit else {
+                        state.length = wheel.suspension_length;
+                        state.ground = None;
+                        state.slip = 0.0;
+                        state.rotation = (state.rotation + state.spin * dt) % std::f32::consts::TAU;
+                        continue;
+                    };
+                    let ground = world.get_body(ground_id);
+                    let ground_velocity = |px: f32, py: f32| ground.map_or((0.0, 0.0), |g| point_velocity(g, px, py));
+                    state.length = (t - wheel.radius).max(0.0);
+                    state.ground = Some(ground_id);
+
+                    // Suspension spring-damper, sized to the wheel's share of the mass
+                    let omega = std::f32::consts::TAU * wheel.frequency;
+                    let stiffness = mass_share * omega * omega;
+                    let damping = 2.0 * mass_share * wheel.damping * omega;
+                    let compression = wheel.suspension_length - (t - wheel.radius);
+                    let (cvx, cvy) = chassis.point_velocity(mx, my);
+                    let (gvx, gvy) = ground_velocity(hx, hy);
+                    let rate = (cvx - gvx) * down.0 + (cvy - gvy) * down.1;
+                    let load = (stiffness * compression + damping * rate).max(0.0);
+                    chassis.push(mx, my, -down.0 * load, -down.1 * load);
+
+                    // Traction along the chassis at the contact point
+                    let (cvx, cvy) = chassis.point_velocity(hx, hy);
+                    let ground_speed = (cvx - gvx) * forward.0 + (cvy - gvy) * forward.1;
+                    let m = chassis.effective_mass(hx, hy, forward) / n;
+                    let limit = wheel.friction * load;
+                    let force = state.grip(wheel.radius, inertia, ground_speed, limit, m, &self.def.traction, dt);
+                    chassis.push(hx, hy, forward.0 * force, forward.1 * force);
+
+                    if ground.is_some_and(|g| g.body_type == BodyType::Dynamic) {
+                        let (fx, fy) = (down.0 * load - forward.0 * force, down.1 * load - forward.1 * force);
+                        reactions.push((ground_id, hx, hy, fx, fy));
+                    }
+                }
+                VehicleView::TopDown => {
+                    let steer = if wheel.steer { input.steer * self.def.max_steer } else { 0.0 };
+                    let (hs, hc) = math::sin_cos(angle + steer);
+                    let forward = (hc, hs);
+                    let side = (-hs, hc);
+                    let (vx, vy) = chassis.point_velocity(mx, my);
+                    let ground_speed = vx * forward.0 + vy * forward.1;
+                    let side_speed = vx * side.0 + vy * side.1;
+                    let load = mass_share * self.def.load;
+                    let limit = wheel.friction * load;
+                    state.ground = None;
+
+                    // Cornering grip first; the engine and brakes get what is left
+                    let speed = math::hypot(vx, vy).max(MIN_SLIP_SPEED);
+                    let side_slip = side_speed.abs() / speed;
+                    let side_limit = limit * self.def.traction.grip(side_slip);
+                    let m_side = chassis.effective_mass(mx, my, side) / n;
+                    let lateral = (-side_speed * m_side / dt).clamp(-side_limit, side_limit);
+                    let remaining = (limit * limit - lateral * lateral).max(0.0).sqrt();
+                    let m = chassis.effective_mass(mx, my, forward) / n;
+                    let force = state.grip(wheel.radius, inertia, ground_speed, remaining, m, &self.def.traction, dt);
+                    state.slip = state.slip.max(side_slip);
+                    chassis.push(
+                        mx,
+                        my,
+                        forward.0 * force + side.0 * lateral,
+                        forward.1 * force + side.1 * lateral,
+                    );
+                }
+            }
+            state.rotation = (state.rotation + state.spin * dt) % std::f32::consts::TAU;
+        }
+
+        if let Some(body) = world.get_body_mut(self.chassis) {
+            body.fx += chassis.fx;
+            body.fy += chassis.fy;
+            body.torque += chassis.torque;
+            if active {
+                body.sleeping = false;
+                body.sleep_timer = 0.0;
+            }
+        }
+        for (id, px, py, fx, fy) in reactions {
+            if let Some(ground) = world.get_body_mut(id) {
+                ground.fx += fx;
+                ground.fy += fy;
+                ground.torque += (px - ground.x) * fy - (py - ground.y) * fx;
+            }
+        }
+    }
+}
+
+impl WheelState {
+    /// Spin the wheel up with `engine` (force at the tire) and slow it with
+    /// `brake`. The engine fades out as the surface speed nears `max_speed`.
+    fn spin_up(&mut self, radius: f32, inertia: f32, engine: f32, brake: f32, max_speed: f32, dt: f32) {
+        let surface = self.spin * radius;
+        let mut engine = engine;
+        if engine * surface > 0.0 && max_speed > 0.0 {
+            engine *= (1.0 - surface.abs() / max_speed).max(0.0);
+        }
+        self.spin += engine * radius / inertia * dt;
+        let slow = brake * radius / inertia * dt;
+        self.spin = if self.spin > 0.0 { (self.spin - slow).max(0.0) } else { (self.spin + slow).min(0.0) };
+    }
+
+    /// Tire friction: the force (along the wheel's heading, on the chassis)
+    /// that matches the wheel's surface speed to `ground_speed`, limited by
+    /// `limit` scaled by the traction curve. Spins the wheel by the reaction.
+    #[allow(clippy::too_many_arguments)]
+    fn grip(
+        &mut self,
+        radius: f32,
+        inertia: f32,
+        ground_speed: f32,
+        limit: f32,
+        mass: f32,
+        curve: &TractionCurve,
+        dt: f32,
+    ) -> f32 {
+        let slip_speed = self.spin * radius - ground_speed;
+        let slip = slip_speed.abs() / ground_speed.abs().max(MIN_SLIP_SPEED);
+        let max = limit * curve.grip(slip);
+        let effective = 1.0 / (1.0 / mass + radius * radius / inertia);
+        let force = (slip_speed * effective / dt).clamp(-max, max);
+        self.spin -= force * radius / inertia * dt;
+        self.slip = slip;
+        force
+    }
+}
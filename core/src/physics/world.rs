@@ -1,17 +1,80 @@
-use std::collections::{HashMap, HashSet};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use super::broadphase::SpatialHash;
-use super::constraints::{solve_constraints, solve_constraints_position};
-use super::integrate::integrate;
+use super::broadphase::{QueryGrid, SpatialHash};
+use super::constraints::{pulley_length, solve_constraints, solve_constraints_position};
+use super::integrate::{apply_velocity_limits, integrate};
+use super::math;
 use super::broadphase::SPECULATIVE_MARGIN;
-use super::narrowphase::test_collision_manifold_speculative;
+use super::narrowphase::{one_way_allows, test_collision, test_collision_manifold_speculative};
 use super::resolve::{
     initialize_manifolds, resolve_manifolds_position,
     resolve_manifolds_velocity_iteration, warm_start_manifolds,
 };
-use super::sleep::update_sleep;
+use super::sleep::{update_sleep_with, SleepConfig};
+use super::snapshot::{Reader, Writer};
 use super::types::*;
+use super::projectile::{Projectile, ProjectileBatch, ProjectileBatchDef, ProjectileBatchId, ProjectileHit};
+use super::vehicle::{Vehicle, VehicleDef, VehicleId, VehicleInput};
+
+/// Separation (pixels) under which a pair starts touching for collision events.
+const TOUCH_BEGIN_SEPARATION: f32 = 0.5;
+/// Separation over which a touching pair stops touching. Wider than the begin
+/// distance so resting contacts don't flicker between begin and end.
+const TOUCH_END_SEPARATION: f32 = 1.5;
+
+/// Accuracy/performance trade-offs for a world. The defaults favour stable
+/// stacks and chains; fewer sub-steps/iterations are cheaper but softer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsConfig {
+    /// Sub-steps per fixed 1/60s step (Box2D v3 style sub-stepping).
+    pub substeps: u32,
+    /// Velocity solver iterations per sub-step (contacts + joints).
+    pub velocity_iterations: u32,
+    /// Position correction iterations per sub-step.
+    pub position_iterations: u32,
+    pub sleep: SleepConfig,
+}
+
+impl PhysicsConfig {
+    pub const MAX_SUBSTEPS: u32 = 32;
+    pub const MAX_ITERATIONS: u32 = 64;
+
+    /// Clamp counts into supported ranges (at least one sub-step and one
+    /// velocity iteration; position correction may be disabled) and reject
+    /// negative or non-finite sleep thresholds.
+    pub fn sanitized(self) -> Self {
+        let defaults = SleepConfig::default();
+        let valid = |v: f32, d: f32| if v.is_finite() && v >= 0.0 { v } else { d };
+        Self {
+            substeps: self.substeps.clamp(1, Self::MAX_SUBSTEPS),
+            velocity_iterations: self.velocity_iterations.clamp(1, Self::MAX_ITERATIONS),
+            position_iterations: self.position_iterations.min(Self::MAX_ITERATIONS),
+            sleep: SleepConfig {
+                velocity: valid(self.sleep.velocity, defaults.velocity),
+                angular_velocity: valid(self.sleep.angular_velocity, defaults.angular_velocity),
+                time: valid(self.sleep.time, defaults.time),
+            },
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            substeps: 4,
+            // Increased from 6 to 10 for better constraint convergence (ropes/chains).
+            // Box2D uses 4 velocity + 2 position with sub-stepping; we use more iterations.
+            velocity_iterations: 10,
+            position_iterations: 3,
+            sleep: SleepConfig::default(),
+        }
+    }
+}
 
+/// Cloning a world captures its full simulation state (bodies, joints, warm-start
+/// cache), e.g. for dev-mode quick saves.
+#[derive(Clone)]
 pub struct PhysicsWorld {
     bodies: Vec<Option<RigidBody>>,
     free_ids: Vec<BodyId>,
@@ -29,16 +92,50 @@ pub struct PhysicsWorld {
     /// occurred during the frame, not just the last sub-step's contacts.
     /// De-duplicated by body pair (first contact per pair wins).
     frame_contacts: Vec<Contact>,
-    /// Tracks which body pairs already have a contact in frame_contacts.
-    frame_contact_pairs: HashSet<(BodyId, BodyId)>,
+    /// Index into frame_contacts for each body pair that already has a contact.
+    frame_contact_pairs: HashMap<(BodyId, BodyId), usize>,
     broadphase: SpatialHash,
-    solver_iterations: usize,
+    config: PhysicsConfig,
     /// Warm-start cache for manifolds: maps (body_a, body_b, ContactID) → (jn, jt)
     manifold_warm_cache: HashMap<(BodyId, BodyId, ContactID), (f32, f32)>,
+    /// Pairs (lower ID first) touching after the last step, with their normal and point.
+    touching: HashMap<(BodyId, BodyId), ((f32, f32), (f32, f32))>,
+    /// Pairs touching at any fixed step of the current step() call.
+    frame_touching: HashMap<(BodyId, BodyId), ((f32, f32), (f32, f32))>,
+    /// Begin/stay/end events not yet taken by game code.
+    collision_events: Vec<CollisionEvent>,
+    /// Pairs (lower ID first) passing through a one-way body: they met it
+    /// from a closed side and are ignored until they stop overlapping.
+    one_way_passing: HashSet<(BodyId, BodyId)>,
+    /// Visit broadphase pairs in sorted order so results do not depend on
+    /// hash map iteration order. See [`PhysicsWorld::set_deterministic`].
+    deterministic: bool,
+    /// Fixed steps run since the world was created.
+    step_count: u64,
+    /// Vehicles by ID, stepped in ID order.
+    vehicles: BTreeMap<VehicleId, Vehicle>,
+    next_vehicle_id: VehicleId,
+    /// Projectile batches by ID, stepped in ID order after the bodies move.
+    projectile_batches: BTreeMap<ProjectileBatchId, ProjectileBatch>,
+    next_projectile_batch_id: ProjectileBatchId,
+    /// Projectile hits not yet taken by game code.
+    projectile_hits: Vec<ProjectileHit>,
+    /// Body AABBs indexed for scene queries, rebuilt after every fixed step.
+    query_grid: QueryGrid,
+    /// Queries since the current step() call began. A `Cell` because
+    /// queries only borrow the world.
+    query_counts: Cell<QueryStats>,
+    /// Queries between the previous two step() calls.
+    last_query_counts: QueryStats,
 }
 
 impl PhysicsWorld {
     pub fn new(gravity_x: f32, gravity_y: f32) -> Self {
+        Self::with_config(gravity_x, gravity_y, PhysicsConfig::default())
+    }
+
+    /// Create a world with custom sub-step / iteration / sleep settings.
+    pub fn with_config(gravity_x: f32, gravity_y: f32, config: PhysicsConfig) -> Self {
         Self {
             bodies: Vec::new(),
             free_ids: Vec::new(),
@@ -51,18 +148,71 @@ impl PhysicsWorld {
             contacts: Vec::new(),
             manifolds: Vec::new(),
             frame_contacts: Vec::new(),
-            frame_contact_pairs: HashSet::new(),
+            frame_contact_pairs: HashMap::new(),
             broadphase: SpatialHash::new(64.0),
-            // Increased from 6 to 10 for better constraint convergence (ropes/chains).
-            // Box2D uses 4 velocity + 2 position with sub-stepping; we use more iterations.
-            solver_iterations: 10,
+            config: config.sanitized(),
             manifold_warm_cache: HashMap::new(),
+            touching: HashMap::new(),
+            frame_touching: HashMap::new(),
+            collision_events: Vec::new(),
+            one_way_passing: HashSet::new(),
+            deterministic: false,
+            step_count: 0,
+            vehicles: BTreeMap::new(),
+            next_vehicle_id: 0,
+            projectile_batches: BTreeMap::new(),
+            next_projectile_batch_id: 0,
+            projectile_hits: Vec::new(),
+            query_grid: QueryGrid::new(64.0),
+            query_counts: Cell::new(QueryStats::default()),
+            last_query_counts: QueryStats::default(),
         }
     }
 
+    /// Current solver settings.
+    pub fn config(&self) -> PhysicsConfig {
+        self.config
+    }
+
+    /// Replace all solver settings (values are clamped, see [`PhysicsConfig::sanitized`]).
+    pub fn set_config(&mut self, config: PhysicsConfig) {
+        self.config = config.sanitized();
+    }
+
+    pub fn set_substeps(&mut self, substeps: u32) {
+        self.set_config(PhysicsConfig { substeps, ..self.config });
+    }
+
+    pub fn set_solver_iterations(&mut self, velocity_iterations: u32, position_iterations: u32) {
+        self.set_config(PhysicsConfig { velocity_iterations, position_iterations, ..self.config });
+    }
+
+    pub fn set_sleep_config(&mut self, sleep: SleepConfig) {
+        self.set_config(PhysicsConfig { sleep, ..self.config });
+    }
+
+    /// Make the simulation reproducible: two worlds built the same way and
+    /// stepped with the same `dt` values end in bit-identical states. Costs a
+    /// sort of the broadphase pairs per sub-step. Across platforms this also
+    /// needs the `deterministic-math` feature, which replaces the system's
+    /// `sin`/`cos` with the portable `libm` implementations.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Number of fixed steps run since the world was created.
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
     /// Fixed-timestep physics step. Accumulates dt and runs sub-steps as needed.
-    /// Uses 4 sub-steps per fixed step (Box2D v3 approach) for improved stack
-    /// stability: sub-stepping is more effective than extra solver iterations.
+    /// Uses `config.substeps` sub-steps per fixed step (default 4, Box2D v3 approach)
+    /// for improved stack stability: sub-stepping is more effective than extra
+    /// solver iterations.
     ///
     /// TGS Soft Phase 4: Narrowphase runs ONCE per frame, sub-steps use
     /// analytical contact updating (O(contacts) vs O(contacts × geometry)).
@@ -71,24 +221,116 @@ impl PhysicsWorld {
     /// so that game code can see every collision via get_contacts().
     pub fn step(&mut self, dt: f32) {
         self.accumulator += dt;
+        self.last_query_counts = self.query_counts.take();
 
         // Clear frame-level contact accumulator at the start of each step call
         self.frame_contacts.clear();
         self.frame_contact_pairs.clear();
 
+        let mut stepped = false;
         while self.accumulator >= self.fixed_dt {
+            // Bodies move during the step, so queries made by vehicles scan
+            self.query_grid.invalidate();
             self.step_manifolds(self.fixed_dt);
+            self.refresh_query_grid();
+            self.step_projectiles(self.fixed_dt);
             self.accumulator -= self.fixed_dt;
+            self.step_count += 1;
+            stepped = true;
+        }
+        // Without a fixed step nothing was tested, so nothing began or ended
+        if stepped {
+            self.update_collision_events();
         }
     }
 
+    /// Re-index every body's AABB for scene queries.
+    fn refresh_query_grid(&mut self) {
+        let bodies = self.bodies.iter().flatten().map(|body| (body.id, get_shape_aabb(body)));
+        self.query_grid.rebuild(bodies);
+    }
+
+    /// Scene queries made between the previous two `step` calls, i.e. over
+    /// the last frame when the game steps once per frame.
+    pub fn query_stats(&self) -> QueryStats {
+        self.last_query_counts
+    }
+
+    /// Count a query and the bodies it tested.
+    fn count_query(&self, kind: fn(&mut QueryStats) -> &mut u32, candidates: usize, full_scan: bool) {
+        let mut counts = self.query_counts.get();
+        *kind(&mut counts) += 1;
+        counts.candidates = counts.candidates.saturating_add(candidates as u32);
+        counts.full_scans += full_scan as u32;
+        self.query_counts.set(counts);
+    }
+
+    /// Compare the pairs touching during this step with the previous step's
+    /// and queue begin, stay and end events, ordered by kind then pair.
+    fn update_collision_events(&mut self) {
+        let mut now = std::mem::take(&mut self.frame_touching);
+        // Pairs of sleeping bodies skip the narrowphase but still touch
+        for (&(a, b), &touch) in &self.touching {
+            let asleep = |id: BodyId| self.get_body(id).is_some_and(|body| body.sleeping);
+            if asleep(a) && asleep(b) {
+                now.entry((a, b)).or_insert(touch);
+            }
+        }
+
+        let mut events = Vec::new();
+        for (&(a, b), &(normal, point)) in &now {
+            let kind = if self.touching.contains_key(&(a, b)) {
+                CollisionEventKind::Stay
+            } else {
+                CollisionEventKind::Begin
+            };
+            events.push(CollisionEvent { kind, body_a: a, body_b: b, normal, point });
+        }
+        for (&(a, b), &(normal, point)) in &self.touching {
+            if !now.contains_key(&(a, b)) {
+                events.push(CollisionEvent {
+                    kind: CollisionEventKind::End,
+                    body_a: a,
+                    body_b: b,
+                    normal,
+                    point,
+                });
+            }
+        }
+        events.sort_by_key(|e| (e.kind as u8, e.body_a, e.body_b));
+        self.collision_events.extend(events);
+        self.touching = now;
+    }
+
+    /// Take the collision events queued since the last call: for every step(),
+    /// a `Begin` per pair that started touching, a `Stay` per pair still
+    /// touching and an `End` per pair that separated. Removing a body ends its
+    /// pairs right away. Events accumulate until taken.
+    pub fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
     /// TGS Soft Phase 4: Run narrowphase once per sub-step, but use analytical updating
     /// for position correction phase. This reduces narrowphase calls from 16x to 4x per frame.
     fn step_manifolds(&mut self, fixed_dt: f32) {
-        let sub_dt = fixed_dt / 4.0;
-
-        for sub_step in 0..4 {
-            // 1. Integrate
+        let substeps = self.config.substeps;
+        let sub_dt = fixed_dt / substeps as f32;
+
+        for sub_step in 0..substeps {
+            // 0. Remember axis-locked coordinates so the solver cannot move them
+            let axis_locks: Vec<(usize, f32, f32)> = self
+                .bodies
+                .iter()
+                .enumerate()
+                .filter_map(|(i, b)| {
+                    b.as_ref()
+                        .filter(|b| b.body_type == BodyType::Dynamic && (b.lock_x || b.lock_y))
+                        .map(|b| (i, b.x, b.y))
+                })
+                .collect();
+
+            // 1. Wheels push on vehicle chassis, then integrate
+            self.step_vehicles(sub_dt);
             for body in self.bodies.iter_mut().flatten() {
                 integrate(body, self.gravity.0, self.gravity.1, sub_dt);
             }
@@ -102,11 +344,17 @@ impl PhysicsWorld {
                     body.vx, body.vy, sub_dt,
                 );
             }
-            let pairs = self.broadphase.get_pairs();
+            let mut pairs = self.broadphase.get_pairs();
+            if self.deterministic {
+                pairs.sort_unstable();
+            }
 
             // 3. Narrowphase - generate contact manifolds
             self.manifolds.clear();
             self.contacts.clear();
+            // Overlaps involving a sensor: reported, never solved
+            let mut sensor_contacts = Vec::new();
+            let mut one_way_passing = HashSet::new();
             for (id_a, id_b) in &pairs {
                 let a_idx = *id_a as usize;
                 let b_idx = *id_b as usize;
@@ -125,20 +373,71 @@ impl PhysicsWorld {
                 }
 
                 if sleeping_a && sleeping_b {
+                    if self.one_way_passing.contains(&(*id_a, *id_b)) {
+                        one_way_passing.insert((*id_a, *id_b));
+                    }
                     continue;
                 }
 
                 let body_a = self.bodies[a_idx].as_ref().unwrap();
                 let body_b = self.bodies[b_idx].as_ref().unwrap();
 
-                let speculative_margin = SPECULATIVE_MARGIN + (body_a.vx.abs() + body_a.vy.abs() + body_b.vx.abs() + body_b.vy.abs()) * sub_dt;
+                let sensor = body_a.is_sensor || body_b.is_sensor;
+                if sensor && sub_step > 0 {
+                    continue;
+                }
+
+                let speculative_margin = if sensor {
+                    0.0
+                } else {
+                    SPECULATIVE_MARGIN + (body_a.vx.abs() + body_a.vy.abs() + body_b.vx.abs() + body_b.vy.abs()) * sub_dt
+                };
                 if let Some(manifold) = test_collision_manifold_speculative(body_a, body_b, speculative_margin) {
+                    if !sensor && (body_a.one_way.is_some() || body_b.one_way.is_some()) {
+                        let key = (*id_a, *id_b);
+                        let passing = self.one_way_passing.contains(&key)
+                            || !one_way_allows(body_a, body_b, manifold.normal);
+                        if passing {
+                            // Keep passing through while still overlapping
+                            if manifold.points.iter().any(|p| p.penetration > 0.0) {
+                                one_way_passing.insert(key);
+                            }
+                            continue;
+                        }
+                    }
+                    if sensor {
+                        let deepest = manifold.points.iter().max_by(|p, q| p.penetration.total_cmp(&q.penetration));
+                        if let Some(point) = deepest.filter(|p| p.penetration >= 0.0) {
+                            let cos_a = math::cos(body_a.angle);
+                            let sin_a = math::sin(body_a.angle);
+                            let cpx = point.local_a.0 * cos_a - point.local_a.1 * sin_a + body_a.x;
+                            let cpy = point.local_a.0 * sin_a + point.local_a.1 * cos_a + body_a.y;
+                            let (vax, vay) = point_velocity(body_a, cpx, cpy);
+                            let (vbx, vby) = point_velocity(body_b, cpx, cpy);
+                            sensor_contacts.push(Contact {
+                                body_a: manifold.body_a,
+                                body_b: manifold.body_b,
+                                normal: manifold.normal,
+                                penetration: point.penetration,
+                                contact_point: (cpx, cpy),
+                                accumulated_jn: 0.0,
+                                accumulated_jt: 0.0,
+                                velocity_bias: 0.0,
+                                tangent: manifold.tangent,
+                                impulse: 0.0,
+                                relative_velocity: (vbx - vax, vby - vay),
+                            });
+                        }
+                        continue;
+                    }
                     if !manifold.points.is_empty() {
                         let point = &manifold.points[0];
-                        let cos_a = body_a.angle.cos();
-                        let sin_a = body_a.angle.sin();
+                        let cos_a = math::cos(body_a.angle);
+                        let sin_a = math::sin(body_a.angle);
                         let cpx = point.local_a.0 * cos_a - point.local_a.1 * sin_a + body_a.x;
                         let cpy = point.local_a.0 * sin_a + point.local_a.1 * cos_a + body_a.y;
+                        let (vax, vay) = point_velocity(body_a, cpx, cpy);
+                        let (vbx, vby) = point_velocity(body_b, cpx, cpy);
                         self.contacts.push(Contact {
                             body_a: manifold.body_a,
                             body_b: manifold.body_b,
@@ -149,19 +448,40 @@ impl PhysicsWorld {
                             accumulated_jt: 0.0,
                             velocity_bias: 0.0,
                             tangent: manifold.tangent,
+                            impulse: 0.0,
+                            relative_velocity: (vbx - vax, vby - vay),
                         });
                     }
                     self.manifolds.push(manifold);
                 }
             }
 
+            self.one_way_passing = one_way_passing;
+
             // Accumulate contacts to frame_contacts (first sub-step only to avoid duplicates)
             if sub_step == 0 {
-                for contact in &self.contacts {
+                let solid = self.contacts.iter().map(|c| (c, false));
+                for (contact, sensor) in solid.chain(sensor_contacts.iter().map(|c| (c, true))) {
                     let key = (contact.body_a.min(contact.body_b), contact.body_a.max(contact.body_b));
-                    if self.frame_contact_pairs.insert(key) {
+                    if let std::collections::hash_map::Entry::Vacant(slot) = self.frame_contact_pairs.entry(key) {
+                        slot.insert(self.frame_contacts.len());
                         self.frame_contacts.push(contact.clone());
                     }
+
+                    // Speculative contacts can still be apart; only close ones
+                    // touch. Sensor contacts are real overlaps already.
+                    let limit = if sensor {
+                        0.0
+                    } else if self.touching.contains_key(&key) {
+                        TOUCH_END_SEPARATION
+                    } else {
+                        TOUCH_BEGIN_SEPARATION
+                    };
+                    if -contact.penetration <= limit {
+                        let (nx, ny) = contact.normal;
+                        let normal = if contact.body_a == key.0 { (nx, ny) } else { (-nx, -ny) };
+                        self.frame_touching.entry(key).or_insert((normal, contact.contact_point));
+                    }
                 }
             }
 
@@ -207,13 +527,32 @@ impl PhysicsWorld {
             }
             warm_start_manifolds(&mut self.bodies, &self.manifolds);
 
-            // 3e. Reset soft constraint accumulated impulses
+            // 3e. Reset soft, rope, limit, motor and mouse constraint accumulated impulses
             for constraint in &mut self.constraints {
                 match constraint {
                     Constraint::Distance { soft: Some(_), accumulated_impulse, .. } => {
                         *accumulated_impulse = 0.0;
                     }
-                    Constraint::Revolute { soft: Some(_), accumulated_impulse, .. } => {
+                    Constraint::Revolute { soft, accumulated_impulse, motor, .. } => {
+                        if soft.is_some() {
+                            *accumulated_impulse = (0.0, 0.0);
+                        }
+                        // Motor and limit impulses are clamped per sub-step
+                        if let Some(motor) = motor {
+                            motor.accumulated_impulse = 0.0;
+                        }
+                    }
+                    Constraint::Prismatic { accumulated_impulse, motor, .. } => {
+                        *accumulated_impulse = (0.0, 0.0);
+                        if let Some(motor) = motor {
+                            motor.accumulated_impulse = 0.0;
+                        }
+                    }
+                    // Rope impulses are clamped per sub-step, so they always restart
+                    Constraint::Rope { accumulated_impulse, .. } => {
+                        *accumulated_impulse = 0.0;
+                    }
+                    Constraint::Mouse { accumulated_impulse, .. } => {
                         *accumulated_impulse = (0.0, 0.0);
                     }
                     _ => {}
@@ -221,19 +560,23 @@ impl PhysicsWorld {
             }
 
             // 4. Velocity solve
-            for i in 0..self.solver_iterations {
+            for i in 0..self.config.velocity_iterations {
                 let reverse = i % 2 == 1;
                 resolve_manifolds_velocity_iteration(&mut self.bodies, &mut self.manifolds, reverse, sub_dt);
                 solve_constraints(&mut self.bodies, &mut self.constraints, sub_dt);
             }
 
-            // 4b. Save accumulated impulses to warm cache
+            // 4b. Save accumulated impulses to warm cache and report them on frame contacts
             self.manifold_warm_cache.clear();
             for manifold in &self.manifolds {
                 let pair_key = (
                     manifold.body_a.min(manifold.body_b),
                     manifold.body_a.max(manifold.body_b),
                 );
+                if let Some(&index) = self.frame_contact_pairs.get(&pair_key) {
+                    self.frame_contacts[index].impulse +=
+                        manifold.points.iter().map(|p| p.accumulated_jn).sum::<f32>();
+                }
                 for point in &manifold.points {
                     let key = (pair_key.0, pair_key.1, point.id);
                     self.manifold_warm_cache.insert(key, (point.accumulated_jn, point.accumulated_jt));
@@ -243,7 +586,7 @@ impl PhysicsWorld {
             // 5. Position correction - re-run narrowphase for accurate penetration
             // Note: We tried analytical updating but it doesn't converge well for
             // position correction. Keeping narrowphase here maintains correctness.
-            for i in 0..3 {
+            for i in 0..self.config.position_iterations {
                 for manifold in &mut self.manifolds {
                     let a = &self.bodies[manifold.body_a as usize];
                     let b = &self.bodies[manifold.body_b as usize];
@@ -301,10 +644,41 @@ impl PhysicsWorld {
                     }
                 }
             }
+
+            // 7. Re-apply axis locks and speed caps after the solver
+            for &(i, x, y) in &axis_locks {
+                if let Some(body) = &mut self.bodies[i] {
+                    if body.lock_x {
+                        body.x = x;
+                    }
+                    if body.lock_y {
+                        body.y = y;
+                    }
+                }
+            }
+            for body in self.bodies.iter_mut().flatten() {
+                apply_velocity_limits(body);
+            }
         }
 
         // Sleep update (once per frame)
-        update_sleep(&mut self.bodies, &self.contacts, fixed_dt);
+        update_sleep_with(&mut self.bodies, &self.contacts, fixed_dt, &self.config.sleep);
+
+        // A running motor keeps its bodies awake, even while still slow
+        for constraint in &self.constraints {
+            if let Constraint::Revolute { body_a, body_b, motor: Some(motor), .. }
+            | Constraint::Prismatic { body_a, body_b, motor: Some(motor), .. } = constraint
+                && motor.speed != 0.0
+                && motor.max_force > 0.0
+            {
+                for id in [*body_a, *body_b] {
+                    if let Some(Some(body)) = self.bodies.get_mut(id as usize) {
+                        body.sleeping = false;
+                        body.sleep_timer = 0.0;
+                    }
+                }
+            }
+        }
     }
 
     pub fn add_body(
@@ -346,6 +720,15 @@ impl PhysicsWorld {
             inv_mass,
             inertia,
             inv_inertia,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            lock_rotation: false,
+            lock_x: false,
+            lock_y: false,
+            max_speed: 0.0,
+            max_angular_speed: 0.0,
+            is_sensor: false,
+            one_way: None,
             layer,
             mask,
             sleeping: false,
@@ -357,6 +740,7 @@ impl PhysicsWorld {
             self.bodies.resize_with(idx + 1, || None);
         }
         self.bodies[idx] = Some(body);
+        self.query_grid.mark_stale(id);
         id
     }
 
@@ -365,6 +749,26 @@ impl PhysicsWorld {
         if idx < self.bodies.len() {
             self.bodies[idx] = None;
             self.free_ids.push(id);
+            // Release drags on the removed body so a reused ID is not grabbed
+            self.constraints
+                .retain(|c| !matches!(c, Constraint::Mouse { body, .. } if *body == id));
+            self.vehicles.retain(|_, v| v.chassis != id);
+            // End its contacts now, before a new body can reuse the ID
+            let mut ended: Vec<_> = self
+                .touching
+                .iter()
+                .filter(|((a, b), _)| *a == id || *b == id)
+                .map(|(&(a, b), &(normal, point))| CollisionEvent {
+                    kind: CollisionEventKind::End,
+                    body_a: a,
+                    body_b: b,
+                    normal,
+                    point,
+                })
+                .collect();
+            ended.sort_by_key(|e| (e.body_a, e.body_b));
+            self.touching.retain(|&(a, b), _| a != id && b != id);
+            self.collision_events.extend(ended);
         }
     }
 
@@ -372,12 +776,20 @@ impl PhysicsWorld {
         self.bodies.get(id as usize)?.as_ref()
     }
 
+    /// Mutable access to a body. Counts as moving it for scene queries;
+    /// prefer the setters for anything else.
     pub fn get_body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody> {
+        self.query_grid.mark_stale(id);
+        self.body_mut(id)
+    }
+
+    /// Mutable access for changes that keep the body's AABB.
+    fn body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody> {
         self.bodies.get_mut(id as usize)?.as_mut()
     }
 
     pub fn set_velocity(&mut self, id: BodyId, vx: f32, vy: f32) {
-        if let Some(body) = self.get_body_mut(id) {
+        if let Some(body) = self.body_mut(id) {
             body.vx = vx;
             body.vy = vy;
             body.sleeping = false;
@@ -386,7 +798,7 @@ impl PhysicsWorld {
     }
 
     pub fn set_angular_velocity(&mut self, id: BodyId, av: f32) {
-        if let Some(body) = self.get_body_mut(id) {
+        if let Some(body) = self.body_mut(id) {
             body.angular_velocity = av;
             body.sleeping = false;
             body.sleep_timer = 0.0;
@@ -394,7 +806,7 @@ impl PhysicsWorld {
     }
 
     pub fn apply_force(&mut self, id: BodyId, fx: f32, fy: f32) {
-        if let Some(body) = self.get_body_mut(id) {
+        if let Some(body) = self.body_mut(id) {
             body.fx += fx;
             body.fy += fy;
             body.sleeping = false;
@@ -403,7 +815,7 @@ impl PhysicsWorld {
     }
 
     pub fn apply_impulse(&mut self, id: BodyId, ix: f32, iy: f32) {
-        if let Some(body) = self.get_body_mut(id) {
+        if let Some(body) = self.body_mut(id) {
             body.vx += ix * body.inv_mass;
             body.vy += iy * body.inv_mass;
             body.sleeping = false;
@@ -411,6 +823,76 @@ impl PhysicsWorld {
         }
     }
 
+    /// Push dynamic bodies within `radius` of (x, y) away from the center, e.g. for
+    /// explosions. The impulse is `strength * (1 - d / radius)^falloff` where `d`
+    /// is the distance to the closest point of the body, so large bodies reaching
+    /// into the blast are pushed too (falloff 0 = constant, 1 = linear,
+    /// 2 = quadratic). With `occlusion`, static bodies between the center and a
+    /// body block the blast. Affected bodies are woken. Returns the IDs of the
+    /// bodies pushed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_radial_impulse(
+        &mut self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        strength: f32,
+        falloff: f32,
+        occlusion: bool,
+        mask: u16,
+    ) -> Vec<BodyId> {
+        if radius <= 0.0 {
+            return Vec::new();
+        }
+        let mut impulses = Vec::new();
+        for hit in self.overlap_circle(x, y, radius, mask) {
+            let Some(body) = self.get_body(hit.body_id) else {
+                continue;
+            };
+            if body.body_type != BodyType::Dynamic {
+                continue;
+            }
+            let dx = body.x - x;
+            let dy = body.y - y;
+            let dist = (dx * dx + dy * dy).sqrt();
+            // Bodies centered on the blast are pushed along the overlap normal
+            let (nx, ny) = if dist > 1e-6 { (dx / dist, dy / dist) } else { hit.normal };
+            if occlusion && self.segment_blocked(x, y, body.x, body.y, body.id) {
+                continue;
+            }
+            // The query circle reaches `penetration` into the body
+            let reach = (radius - hit.penetration).clamp(0.0, dist);
+            let scale = math::powf(1.0 - (reach / radius).min(1.0), falloff.max(0.0));
+            // Bodies only grazing the rim get no push
+            if scale <= 0.0 {
+                continue;
+            }
+            impulses.push((body.id, nx * strength * scale, ny * strength * scale));
+        }
+        for &(id, ix, iy) in &impulses {
+            self.apply_impulse(id, ix, iy);
+        }
+        impulses.into_iter().map(|(id, _, _)| id).collect()
+    }
+
+    /// True if a solid static body (other than `ignore`) intersects the
+    /// segment from (ox, oy) to (tx, ty).
+    fn segment_blocked(&self, ox: f32, oy: f32, tx: f32, ty: f32, ignore: BodyId) -> bool {
+        let dx = tx - ox;
+        let dy = ty - oy;
+        let len = (dx * dx + dy * dy).sqrt();
+        if len < 1e-6 {
+            return false;
+        }
+        let (ndx, ndy) = (dx / len, dy / len);
+        self.bodies.iter().flatten().any(|body| {
+            if body.id == ignore || body.body_type != BodyType::Static || body.is_sensor {
+                return false;
+            }
+            ray_vs_body(ox, oy, ndx, ndy, body).is_some_and(|t| t < len)
+        })
+    }
+
     pub fn set_position(&mut self, id: BodyId, x: f32, y: f32) {
         if let Some(body) = self.get_body_mut(id) {
             body.x = x;
@@ -420,8 +902,72 @@ impl PhysicsWorld {
         }
     }
 
+    /// Set linear and angular damping (per second, clamped to >= 0). Does not
+    /// wake the body: damping only matters while it moves.
+    pub fn set_damping(&mut self, id: BodyId, linear: f32, angular: f32) {
+        if let Some(body) = self.body_mut(id) {
+            body.linear_damping = linear.max(0.0);
+            body.angular_damping = angular.max(0.0);
+        }
+    }
+
+    /// Lock rotation and/or an axis. Rotation locks give the body infinite
+    /// inertia so contacts and joints cannot spin it.
+    pub fn set_locks(&mut self, id: BodyId, lock_rotation: bool, lock_x: bool, lock_y: bool) {
+        if let Some(body) = self.body_mut(id) {
+            body.lock_rotation = lock_rotation;
+            body.lock_x = lock_x;
+            body.lock_y = lock_y;
+            let (_, inertia, inv_inertia) =
+                compute_mass_and_inertia(&body.shape, body.mass, body.body_type);
+            if lock_rotation {
+                body.angular_velocity = 0.0;
+                body.inv_inertia = 0.0;
+            } else {
+                body.inertia = inertia;
+                body.inv_inertia = inv_inertia;
+            }
+            apply_velocity_limits(body);
+        }
+    }
+
+    /// Cap linear speed and angular speed (rad/s). 0 (or negative) = unlimited.
+    pub fn set_max_speed(&mut self, id: BodyId, linear: f32, angular: f32) {
+        if let Some(body) = self.body_mut(id) {
+            body.max_speed = linear.max(0.0);
+            body.max_angular_speed = angular.max(0.0);
+            apply_velocity_limits(body);
+        }
+    }
+
+    /// Turn a body into a sensor (or back). Sensors still report contacts
+    /// and collision events for what overlaps them, but nothing collides
+    /// with them.
+    pub fn set_sensor(&mut self, id: BodyId, is_sensor: bool) {
+        if let Some(body) = self.body_mut(id) {
+            body.is_sensor = is_sensor;
+            body.sleeping = false;
+            body.sleep_timer = 0.0;
+        }
+    }
+
+    /// Make a body a one-way platform whose open side faces `direction`
+    /// (in the body's local frame; normalized), or solid again with `None`.
+    /// A zero direction also makes it solid.
+    pub fn set_one_way(&mut self, id: BodyId, direction: Option<(f32, f32)>) {
+        let direction = direction.and_then(|(x, y)| {
+            let len = (x * x + y * y).sqrt();
+            (len > 1e-6).then(|| (x / len, y / len))
+        });
+        if let Some(body) = self.body_mut(id) {
+            body.one_way = direction;
+            body.sleeping = false;
+            body.sleep_timer = 0.0;
+        }
+    }
+
     pub fn set_collision_layers(&mut self, id: BodyId, layer: u16, mask: u16) {
-        if let Some(body) = self.get_body_mut(id) {
+        if let Some(body) = self.body_mut(id) {
             body.layer = layer;
             body.mask = mask;
         }
@@ -456,6 +1002,7 @@ impl PhysicsWorld {
                 anchor_a,
                 anchor_b,
                 soft,
+                motor,
                 ..
             } => Constraint::Revolute {
                 id,
@@ -465,7 +1012,118 @@ impl PhysicsWorld {
                 anchor_b,
                 soft,
                 accumulated_impulse: (0.0, 0.0),
+                motor: motor.map(|m| JointMotor::new(m.speed, m.max_force)),
             },
+            Constraint::Prismatic {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                axis,
+                lower,
+                upper,
+                motor,
+                ..
+            } => {
+                let len = (axis.0 * axis.0 + axis.1 * axis.1).sqrt();
+                let axis = if len > 1e-6 { (axis.0 / len, axis.1 / len) } else { (1.0, 0.0) };
+                Constraint::Prismatic {
+                    id,
+                    body_a,
+                    body_b,
+                    anchor_a,
+                    anchor_b,
+                    axis,
+                    reference_angle: self.relative_angle(body_a, body_b),
+                    lower: lower.min(upper),
+                    upper: upper.max(lower),
+                    motor: motor.map(|m| JointMotor::new(m.speed, m.max_force)),
+                    accumulated_impulse: (0.0, 0.0),
+                }
+            }
+            Constraint::Weld {
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                ..
+            } => Constraint::Weld {
+                id,
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                reference_angle: self.relative_angle(body_a, body_b),
+                accumulated_impulse: (0.0, 0.0),
+            },
+            Constraint::Rope {
+                body_a,
+                body_b,
+                max_length,
+                anchor_a,
+                anchor_b,
+                ..
+            } => Constraint::Rope {
+                id,
+                body_a,
+                body_b,
+                max_length: max_length.max(0.0),
+                anchor_a,
+                anchor_b,
+                accumulated_impulse: 0.0,
+            },
+            Constraint::Pulley {
+                body_a,
+                body_b,
+                ground_a,
+                ground_b,
+                anchor_a,
+                anchor_b,
+                ratio,
+                total_length,
+                ..
+            } => {
+                let ratio = ratio.max(1e-3);
+                // Non-positive length = keep the rope as long as it is right now
+                let total_length = if total_length > 0.0 {
+                    total_length
+                } else {
+                    pulley_length(&self.bodies, body_a, body_b, ground_a, ground_b, anchor_a, anchor_b, ratio)
+                        .unwrap_or(0.0)
+                };
+                Constraint::Pulley {
+                    id,
+                    body_a,
+                    body_b,
+                    ground_a,
+                    ground_b,
+                    anchor_a,
+                    anchor_b,
+                    ratio,
+                    total_length,
+                    accumulated_impulse: 0.0,
+                }
+            }
+            Constraint::Mouse {
+                body,
+                target,
+                anchor,
+                max_force,
+                soft,
+                ..
+            } => {
+                let mass = self.get_body(body).map_or(1.0, |b| b.mass);
+                self.wake(body);
+                Constraint::Mouse {
+                    id,
+                    body,
+                    target,
+                    anchor,
+                    max_force: if max_force > 0.0 { max_force } else { MOUSE_FORCE_PER_MASS * mass },
+                    soft: if soft.is_rigid() { SoftConstraintParams::mouse() } else { soft },
+                    accumulated_impulse: (0.0, 0.0),
+                }
+            }
         };
         self.constraints.push(constraint);
         id
@@ -475,17 +1133,282 @@ impl PhysicsWorld {
         self.constraints.retain(|c| c.id() != id);
     }
 
+    /// All active constraints (joints), e.g. for debug drawing.
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Current angle of body B minus body A (0 if either is missing).
+    fn relative_angle(&self, body_a: BodyId, body_b: BodyId) -> f32 {
+        match (self.get_body(body_a), self.get_body(body_b)) {
+            (Some(a), Some(b)) => b.angle - a.angle,
+            _ => 0.0,
+        }
+    }
+
+    /// Set or remove (`None`) the motor of a revolute or prismatic joint and
+    /// wake its bodies. Returns false if `id` is not such a joint.
+    pub fn set_joint_motor(&mut self, id: ConstraintId, motor: Option<JointMotor>) -> bool {
+        let bodies = self.constraints.iter_mut().find_map(|c| match c {
+            Constraint::Revolute { id: cid, body_a, body_b, motor: m, .. }
+            | Constraint::Prismatic { id: cid, body_a, body_b, motor: m, .. }
+                if *cid == id =>
+            {
+                *m = motor.map(|m| JointMotor::new(m.speed, m.max_force));
+                Some((*body_a, *body_b))
+            }
+            _ => None,
+        });
+        match bodies {
+            Some((a, b)) => {
+                self.wake(a);
+                self.wake(b);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move a mouse joint's target and wake its body. Returns false if `id`
+    /// is not a mouse joint (e.g. it was released when its body was removed).
+    pub fn set_mouse_target(&mut self, id: ConstraintId, x: f32, y: f32) -> bool {
+        let body = self.constraints.iter_mut().find_map(|c| match c {
+            Constraint::Mouse { id: cid, body, target, .. } if *cid == id => {
+                *target = (x, y);
+                Some(*body)
+            }
+            _ => None,
+        });
+        match body {
+            Some(body) => {
+                self.wake(body);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Retune a mouse joint. `max_force <= 0` keeps the current limit; a
+    /// rigid (0 Hz) spring falls back to the default.
+    pub fn set_mouse_params(&mut self, id: ConstraintId, soft: SoftConstraintParams, max_force: f32) {
+        for c in &mut self.constraints {
+            if let Constraint::Mouse { id: cid, soft: s, max_force: f, .. } = c {
+                if *cid == id {
+                    *s = if soft.is_rigid() { SoftConstraintParams::mouse() } else { soft };
+                    if max_force > 0.0 {
+                        *f = max_force;
+                    }
+                }
+            }
+        }
+    }
+
+    fn wake(&mut self, id: BodyId) {
+        if let Some(body) = self.body_mut(id) {
+            body.sleeping = false;
+            body.sleep_timer = 0.0;
+        }
+    }
+
+    /// Turn `chassis` into a vehicle driven by `def.wheels`. Returns `None`
+    /// unless the chassis is a dynamic body and there is at least one wheel.
+    pub fn add_vehicle(&mut self, chassis: BodyId, def: VehicleDef) -> Option<VehicleId> {
+        let dynamic = self.get_body(chassis).is_some_and(|b| b.body_type == BodyType::Dynamic);
+        if !dynamic || def.wheels.is_empty() {
+            return None;
+        }
+        let id = self.next_vehicle_id;
+        self.next_vehicle_id += 1;
+        self.vehicles.insert(id, Vehicle::new(id, chassis, def));
+        Some(id)
+    }
+
+    /// Stop driving a vehicle. Its chassis stays in the world.
+    pub fn remove_vehicle(&mut self, id: VehicleId) {
+        self.vehicles.remove(&id);
+    }
+
+    pub fn get_vehicle(&self, id: VehicleId) -> Option<&Vehicle> {
+        self.vehicles.get(&id)
+    }
+
+    /// Set a vehicle's controls (clamped to their ranges). They stay applied
+    /// until changed. Returns false for an unknown vehicle.
+    pub fn set_vehicle_input(&mut self, id: VehicleId, input: VehicleInput) -> bool {
+        match self.vehicles.get_mut(&id) {
+            Some(vehicle) => {
+                vehicle.input = input.clamped();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn step_vehicles(&mut self, dt: f32) {
+        if self.vehicles.is_empty() {
+            return;
+        }
+        let mut vehicles = std::mem::take(&mut self.vehicles);
+        for vehicle in vehicles.values_mut() {
+            vehicle.step(self, dt);
+        }
+        self.vehicles = vehicles;
+    }
+
+    /// Add an empty projectile batch with its pool allocated up front.
+    pub fn add_projectile_batch(&mut self, def: ProjectileBatchDef) -> ProjectileBatchId {
+        let id = self.next_projectile_batch_id;
+        self.next_projectile_batch_id += 1;
+        self.projectile_batches.insert(id, ProjectileBatch::new(id, def));
+        id
+    }
+
+    /// Remove a batch and its projectiles. Hits it already queued stay queued.
+    pub fn remove_projectile_batch(&mut self, id: ProjectileBatchId) {
+        self.projectile_batches.remove(&id);
+    }
+
+    pub fn get_projectile_batch(&self, id: ProjectileBatchId) -> Option<&ProjectileBatch> {
+        self.projectile_batches.get(&id)
+    }
+
+    /// Spawn projectiles into a batch, in order, until it is full. Returns
+    /// how many were spawned (0 for an unknown batch).
+    pub fn spawn_projectiles(
+        &mut self,
+        id: ProjectileBatchId,
+        projectiles: impl IntoIterator<Item = Projectile>,
+    ) -> usize {
+        let Some(batch) = self.projectile_batches.get_mut(&id) else {
+            return 0;
+        };
+        projectiles.into_iter().take_while(|&p| batch.spawn(p)).count()
+    }
+
+    pub fn clear_projectiles(&mut self, id: ProjectileBatchId) {
+        if let Some(batch) = self.projectile_batches.get_mut(&id) {
+            batch.clear();
+        }
+    }
+
+    /// Take the projectile hits queued since the last call, in step order,
+    /// then batch order, then projectile order. Hits accumulate until taken.
+    pub fn take_projectile_hits(&mut self) -> Vec<ProjectileHit> {
+        std::mem::take(&mut self.projectile_hits)
+    }
+
+    fn step_projectiles(&mut self, dt: f32) {
+        if self.projectile_batches.is_empty() {
+            return;
+        }
+        let mut batches = std::mem::take(&mut self.projectile_batches);
+        let mut hits = std::mem::take(&mut self.projectile_hits);
+        for batch in batches.values_mut() {
+            batch.step(self, dt, &mut hits);
+        }
+        self.projectile_batches = batches;
+        self.projectile_hits = hits;
+    }
+
+    /// Bodies whose AABB overlaps the rectangle, sorted by ID.
     pub fn query_aabb(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Vec<BodyId> {
-        let mut result = Vec::new();
-        for body in self.bodies.iter().flatten() {
+        let overlaps = |body: &RigidBody| {
             let (bmin_x, bmin_y, bmax_x, bmax_y) = get_shape_aabb(body);
-            if bmax_x >= min_x && bmin_x <= max_x && bmax_y >= min_y && bmin_y <= max_y {
-                result.push(body.id);
+            bmax_x >= min_x && bmin_x <= max_x && bmax_y >= min_y && bmin_y <= max_y
+        };
+        let (result, tested, full_scan) = match self.query_grid.candidates(min_x, min_y, max_x, max_y) {
+            Some(ids) => {
+                let tested = ids.len();
+                let hits = ids.into_iter().filter(|&id| self.get_body(id).is_some_and(overlaps)).collect();
+                (hits, tested, false)
             }
-        }
+            None => {
+                let hits = self.bodies.iter().flatten().filter(|b| overlaps(b)).map(|b| b.id).collect();
+                (hits, self.bodies.len(), true)
+            }
+        };
+        self.count_query(|c| &mut c.aabb, tested, full_scan);
         result
     }
 
+    /// Bodies that may overlap the rectangle, in ID order: the query grid's
+    /// candidates, or every body while the grid is not usable.
+    fn bodies_near(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> (Vec<&RigidBody>, bool) {
+        match self.query_grid.candidates(min_x, min_y, max_x, max_y) {
+            Some(ids) => (ids.into_iter().filter_map(|id| self.get_body(id)).collect(), false),
+            None => (self.bodies.iter().flatten().collect(), true),
+        }
+    }
+
+    /// Bodies overlapping a circle whose collision layer intersects `mask`.
+    pub fn overlap_circle(&self, x: f32, y: f32, radius: f32, mask: u16) -> Vec<OverlapHit> {
+        self.overlap_shape(query_probe(Shape::Circle { radius }, x, y, 0.0), mask)
+    }
+
+    /// Bodies overlapping a box centered at (x, y), rotated by `angle` radians.
+    pub fn overlap_box(
+        &self,
+        x: f32,
+        y: f32,
+        half_w: f32,
+        half_h: f32,
+        angle: f32,
+        mask: u16,
+    ) -> Vec<OverlapHit> {
+        let shape = if angle == 0.0 {
+            Shape::AABB { half_w, half_h }
+        } else {
+            Shape::Polygon {
+                vertices: vec![(-half_w, -half_h), (half_w, -half_h), (half_w, half_h), (-half_w, half_h)],
+            }
+        };
+        self.overlap_shape(query_probe(shape, x, y, angle), mask)
+    }
+
+    /// Bodies overlapping a convex polygon (local vertices, CCW) placed at (x, y)
+    /// and rotated by `angle` radians. Fewer than 3 vertices never overlap.
+    pub fn overlap_polygon(
+        &self,
+        x: f32,
+        y: f32,
+        vertices: &[(f32, f32)],
+        angle: f32,
+        mask: u16,
+    ) -> Vec<OverlapHit> {
+        if vertices.len() < 3 {
+            return Vec::new();
+        }
+        let shape = Shape::Polygon { vertices: vertices.to_vec() };
+        self.overlap_shape(query_probe(shape, x, y, angle), mask)
+    }
+
+    /// Narrowphase a query probe against every body whose layer matches `mask`.
+    /// Results are sorted by body ID.
+    fn overlap_shape(&self, probe: RigidBody, mask: u16) -> Vec<OverlapHit> {
+        let (min_x, min_y, max_x, max_y) = get_shape_aabb(&probe);
+        let (bodies, full_scan) = self.bodies_near(min_x, min_y, max_x, max_y);
+        self.count_query(|c| &mut c.overlap, bodies.len(), full_scan);
+        let mut hits = Vec::new();
+        for body in bodies {
+            if body.layer & mask == 0 {
+                continue;
+            }
+            let (bmin_x, bmin_y, bmax_x, bmax_y) = get_shape_aabb(body);
+            if bmax_x < min_x || bmin_x > max_x || bmax_y < min_y || bmin_y > max_y {
+                continue;
+            }
+            if let Some(contact) = test_collision(&probe, body) {
+                hits.push(OverlapHit {
+                    body_id: body.id,
+                    normal: contact.normal,
+                    penetration: contact.penetration,
+                    point: contact.contact_point,
+                });
+            }
+        }
+        hits
+    }
+
     pub fn raycast(
         &self,
         ox: f32,
@@ -493,6 +1416,19 @@ impl PhysicsWorld {
         dx: f32,
         dy: f32,
         max_dist: f32,
+    ) -> Option<(BodyId, f32, f32, f32)> {
+        self.raycast_where(ox, oy, dx, dy, max_dist, |_| true)
+    }
+
+    /// [`PhysicsWorld::raycast`] against only the bodies `keep` accepts.
+    pub fn raycast_where(
+        &self,
+        ox: f32,
+        oy: f32,
+        dx: f32,
+        dy: f32,
+        max_dist: f32,
+        keep: impl Fn(&RigidBody) -> bool,
     ) -> Option<(BodyId, f32, f32, f32)> {
         let dir_len = (dx * dx + dy * dy).sqrt();
         if dir_len < 1e-8 {
@@ -502,37 +1438,61 @@ impl PhysicsWorld {
         let ndy = dy / dir_len;
 
         let mut closest: Option<(BodyId, f32, f32, f32)> = None;
+        let mut tested = 0;
+        // Nearest hit wins; equal distances go to the lower ID, as in a scan
+        let test = |body: &RigidBody, closest: &mut Option<(BodyId, f32, f32, f32)>| {
+            if !keep(body) {
+                return;
+            }
+            if let Some(t) = ray_vs_body(ox, oy, ndx, ndy, body)
+                && t >= 0.0
+                && t <= max_dist
+                && closest.is_none_or(|(id, _, _, best)| t < best || (t == best && body.id < id))
+            {
+                *closest = Some((body.id, ox + ndx * t, oy + ndy * t, t));
+            }
+        };
 
-        for body in self.bodies.iter().flatten() {
-            let t = match &body.shape {
-                Shape::Circle { radius } => {
-                    ray_vs_circle(ox, oy, ndx, ndy, body.x, body.y, *radius)
-                }
-                Shape::AABB { half_w, half_h } => {
-                    ray_vs_aabb(ox, oy, ndx, ndy, body.x, body.y, *half_w, *half_h)
-                }
-                Shape::Polygon { vertices } => {
-                    ray_vs_polygon(ox, oy, ndx, ndy, body, vertices)
-                }
-            };
-
-            if let Some(t) = t {
-                if t >= 0.0 && t <= max_dist {
-                    let hit_x = ox + ndx * t;
-                    let hit_y = oy + ndy * t;
-                    if closest.is_none() || t < closest.unwrap().3 {
-                        closest = Some((body.id, hit_x, hit_y, t));
-                    }
+        let mut seen = HashSet::new();
+        let walked = self.query_grid.walk_ray(ox, oy, ndx, ndy, max_dist, |ids, t_enter| {
+            // Hits in this cell and beyond are no nearer than where it starts
+            if closest.is_some_and(|(_, _, _, best)| t_enter > best + 1e-4) {
+                return false;
+            }
+            for &id in ids {
+                if seen.insert(id)
+                    && let Some(body) = self.get_body(id)
+                {
+                    tested += 1;
+                    test(body, &mut closest);
                 }
             }
+            true
+        });
+        if !walked {
+            for body in self.bodies.iter().flatten() {
+                tested += 1;
+                test(body, &mut closest);
+            }
         }
+        self.count_query(|c| &mut c.raycast, tested, !walked);
         closest
     }
 
+    /// True if a non-sensor body whose collision layer intersects `mask`
+    /// crosses the segment from (ax, ay) to (bx, by).
+    pub fn segment_blocked_by(&self, ax: f32, ay: f32, bx: f32, by: f32, mask: u16) -> bool {
+        let (dx, dy) = (bx - ax, by - ay);
+        let length = (dx * dx + dy * dy).sqrt();
+        self.raycast_where(ax, ay, dx, dy, length, |body| !body.is_sensor && body.layer & mask != 0)
+            .is_some()
+    }
+
     /// Return all contacts that occurred during the last step() call.
     /// Accumulated across all sub-steps, de-duplicated by body pair.
     /// This ensures game code sees every collision, even if the bodies
-    /// separated during a later sub-step.
+    /// separated during a later sub-step. Each contact carries the total
+    /// normal impulse applied to its pair and the relative velocity at impact.
     pub fn get_contacts(&self) -> &[Contact] {
         &self.frame_contacts
     }
@@ -558,6 +1518,321 @@ impl PhysicsWorld {
     pub fn body_count(&self) -> usize {
         self.bodies.iter().filter(|b| b.is_some()).count()
     }
+
+    /// 64-bit FNV-1a hash of the simulation state: step count, time
+    /// accumulator, gravity, every body's ID, type, position, angle,
+    /// velocities and sleep state, every constraint, vehicle and projectile.
+    /// Compare hashes of two deterministic worlds (or of peers in lockstep
+    /// netcode) to detect the first step where they diverge. Floats are
+    /// hashed by their bits.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash = StateHasher::new();
+        hash.write_u64(self.step_count);
+        hash.write_f32(self.accumulator);
+        hash.write_f32(self.gravity.0);
+        hash.write_f32(self.gravity.1);
+        for body in self.bodies.iter().flatten() {
+            hash.write_u64(body.id as u64);
+            hash.write_u64(body.body_type as u64);
+            for value in [body.x, body.y, body.angle, body.vx, body.vy, body.angular_velocity] {
+                hash.write_f32(value);
+            }
+            hash.write_u64(body.sleeping as u64);
+        }
+        // The snapshot encoding writes every field, accumulated impulses
+        // included, with floats as their bits
+        let mut w = Writer::new();
+        for constraint in &self.constraints {
+            w.constraint(constraint);
+        }
+        for vehicle in self.vehicles.values() {
+            w.vehicle(vehicle);
+        }
+        for batch in self.projectile_batches.values() {
+            w.projectile_batch(batch);
+        }
+        hash.write_bytes(&w.finish());
+        hash.finish()
+    }
+
+    /// Serialize the whole simulation state: bodies (free slots included, so
+    /// IDs stay the same), joints, vehicles, projectiles, solver settings, the
+    /// time accumulator, warm-start impulses, touching pairs and one-way
+    /// pass-through state.
+    /// Restoring it with [`PhysicsWorld::restore`] continues the simulation
+    /// exactly as the original would. Per-step outputs (contacts, manifolds,
+    /// untaken collision events and projectile hits) are not included.
+    /// Identical worlds give identical bytes.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut w = Writer::new();
+        w.vec2(self.gravity);
+        w.f32(self.fixed_dt);
+        w.f32(self.accumulator);
+        w.u64(self.step_count);
+        w.bool(self.deterministic);
+        w.u32(self.config.substeps);
+        w.u32(self.config.velocity_iterations);
+        w.u32(self.config.position_iterations);
+        w.f32(self.config.sleep.velocity);
+        w.f32(self.config.sleep.angular_velocity);
+        w.f32(self.config.sleep.time);
+
+        w.u32(self.next_id);
+        w.count(self.bodies.len());
+        for slot in &self.bodies {
+            w.bool(slot.is_some());
+            if let Some(body) = slot {
+                w.body(body);
+            }
+        }
+        w.count(self.free_ids.len());
+        for &id in &self.free_ids {
+            w.u32(id);
+        }
+
+        w.u32(self.next_constraint_id);
+        w.count(self.constraints.len());
+        for constraint in &self.constraints {
+            w.constraint(constraint);
+        }
+
+        w.u32(self.next_vehicle_id);
+        w.count(self.vehicles.len());
+        for vehicle in self.vehicles.values() {
+            w.vehicle(vehicle);
+        }
+
+        w.u32(self.next_projectile_batch_id);
+        w.count(self.projectile_batches.len());
+        for batch in self.projectile_batches.values() {
+            w.projectile_batch(batch);
+        }
+
+        // Maps are written sorted so the bytes do not depend on hash order
+        let mut warm: Vec<_> = self.manifold_warm_cache.iter().collect();
+        warm.sort_unstable_by_key(|&(&(a, b, id), _)| (a, b, id.ref_edge, id.inc_edge, id.clip_index, id.flags));
+        w.count(warm.len());
+        for (&(a, b, id), &(jn, jt)) in warm {
+            w.u32(a);
+            w.u32(b);
+            for byte in [id.ref_edge, id.inc_edge, id.clip_index, id.flags] {
+                w.u8(byte);
+            }
+            w.f32(jn);
+            w.f32(jt);
+        }
+        let mut touching: Vec<_> = self.touching.iter().collect();
+        touching.sort_unstable_by_key(|&(&pair, _)| pair);
+        w.count(touching.len());
+        for (&(a, b), &(normal, point)) in touching {
+            w.u32(a);
+            w.u32(b);
+            w.vec2(normal);
+            w.vec2(point);
+        }
+        let mut passing: Vec<_> = self.one_way_passing.iter().copied().collect();
+        passing.sort_unstable();
+        w.count(passing.len());
+        for (a, b) in passing {
+            w.u32(a);
+            w.u32(b);
+        }
+        w.finish()
+    }
+
+    /// Build a world from [`PhysicsWorld::snapshot`] bytes.
+    pub fn from_snapshot(data: &[u8]) -> Result<Self, String> {
+        let mut r = Reader::new(data)?;
+        let (gx, gy) = r.vec2()?;
+        let mut world = Self::new(gx, gy);
+        world.fixed_dt = r.f32()?;
+        world.accumulator = r.f32()?;
+        world.step_count = r.u64()?;
+        world.deterministic = r.bool()?;
+        let mut config = PhysicsConfig {
+            substeps: r.u32()?,
+            velocity_iterations: r.u32()?,
+            position_iterations: r.u32()?,
+            ..PhysicsConfig::default()
+        };
+        config.sleep.velocity = r.f32()?;
+        config.sleep.angular_velocity = r.f32()?;
+        config.sleep.time = r.f32()?;
+        world.set_config(config);
+
+        world.next_id = r.u32()?;
+        let slots = r.count(1)?;
+        // A lower next ID would hand out the ID of a live body
+        if (world.next_id as usize) < slots {
+            return Err(format!("next body ID {} is below the {slots} body slots", world.next_id));
+        }
+        let in_slots = |id: BodyId| (id as usize) < slots;
+        for index in 0..slots {
+            let body = if r.bool()? { Some(r.body()?) } else { None };
+            if let Some(body) = &body
+                && body.id as usize != index
+            {
+                return Err(format!("body {} stored in slot {index}", body.id));
+            }
+            world.bodies.push(body);
+        }
+        let free = r.count(4)?;
+        for _ in 0..free {
+            let id = r.u32()?;
+            if world.bodies.get(id as usize).is_none_or(|slot| slot.is_some()) {
+                return Err(format!("free body ID {id} is not an empty slot"));
+            }
+            world.free_ids.push(id);
+        }
+
+        world.next_constraint_id = r.u32()?;
+        let constraints = r.count(1)?;
+        for _ in 0..constraints {
+            let constraint = r.constraint()?;
+            let (a, b) = constraint.bodies();
+            if !in_slots(a) || !b.is_none_or(in_slots) {
+                return Err(format!("constraint {} joins a body outside the body slots", constraint.id()));
+            }
+            world.constraints.push(constraint);
+        }
+
+        world.next_vehicle_id = r.u32()?;
+        let vehicles = r.count(1)?;
+        for _ in 0..vehicles {
+            let vehicle = r.vehicle()?;
+            if !in_slots(vehicle.chassis) {
+                return Err(format!("vehicle {} has chassis {} outside the body slots", vehicle.id, vehicle.chassis));
+            }
+            world.vehicles.insert(vehicle.id, vehicle);
+        }
+
+        world.next_projectile_batch_id = r.u32()?;
+        let batches = r.count(18)?;
+        for _ in 0..batches {
+            let batch = r.projectile_batch()?;
+            world.projectile_batches.insert(batch.id, batch);
+        }
+
+        let warm = r.count(20)?;
+        for _ in 0..warm {
+            let (a, b) = (r.u32()?, r.u32()?);
+            let id = ContactID {
+                ref_edge: r.u8()?,
+                inc_edge: r.u8()?,
+                clip_index: r.u8()?,
+                flags: r.u8()?,
+            };
+            world.manifold_warm_cache.insert((a, b, id), (r.f32()?, r.f32()?));
+        }
+        let touching = r.count(24)?;
+        for _ in 0..touching {
+            let pair = (r.u32()?, r.u32()?);
+            world.touching.insert(pair, (r.vec2()?, r.vec2()?));
+        }
+        let passing = r.count(8)?;
+        for _ in 0..passing {
+            world.one_way_passing.insert((r.u32()?, r.u32()?));
+        }
+        r.finish()?;
+        world.refresh_query_grid();
+        Ok(world)
+    }
+
+    /// Replace this world's state with a snapshot. On error the world is
+    /// left unchanged.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), String> {
+        *self = Self::from_snapshot(data)?;
+        Ok(())
+    }
+}
+
+/// FNV-1a, chosen over `DefaultHasher` because its output is specified and
+/// stable across Rust versions and platforms.
+struct StateHasher(u64);
+
+impl StateHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.write_bytes(&value.to_bits().to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Velocity of the point `(px, py)` moving with `body` (linear + angular part).
+pub(super) fn point_velocity(body: &RigidBody, px: f32, py: f32) -> (f32, f32) {
+    let (rx, ry) = (px - body.x, py - body.y);
+    (body.vx - body.angular_velocity * ry, body.vy + body.angular_velocity * rx)
+}
+
+/// A static, massless body used as the query shape for overlap tests.
+pub(super) fn query_probe(shape: Shape, x: f32, y: f32, angle: f32) -> RigidBody {
+    RigidBody {
+        id: BodyId::MAX,
+        body_type: BodyType::Static,
+        shape,
+        material: Material::default(),
+        x,
+        y,
+        angle,
+        vx: 0.0,
+        vy: 0.0,
+        angular_velocity: 0.0,
+        fx: 0.0,
+        fy: 0.0,
+        torque: 0.0,
+        mass: 0.0,
+        inv_mass: 0.0,
+        inertia: 0.0,
+        inv_inertia: 0.0,
+        linear_damping: 0.0,
+        angular_damping: 0.0,
+        lock_rotation: false,
+        lock_x: false,
+        lock_y: false,
+        max_speed: 0.0,
+        max_angular_speed: 0.0,
+        is_sensor: false,
+        one_way: None,
+        layer: 0xFFFF,
+        mask: 0xFFFF,
+        sleeping: false,
+        sleep_timer: 0.0,
+    }
+}
+
+/// Distance along the unit ray (dx, dy) to the first hit on `body`'s shape.
+pub(super) fn ray_vs_body(ox: f32, oy: f32, dx: f32, dy: f32, body: &RigidBody) -> Option<f32> {
+    match &body.shape {
+        Shape::Circle { radius } => ray_vs_circle(ox, oy, dx, dy, body.x, body.y, *radius),
+        Shape::AABB { half_w, half_h } => {
+            ray_vs_aabb(ox, oy, dx, dy, body.x, body.y, *half_w, *half_h)
+        }
+        Shape::Polygon { vertices } => ray_vs_polygon(ox, oy, dx, dy, body, vertices),
+        Shape::OBB { half_w, half_h } => {
+            let (hw, hh) = (*half_w, *half_h);
+            ray_vs_polygon(ox, oy, dx, dy, body, &[(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)])
+        }
+        Shape::Capsule { half_height, radius } => {
+            ray_vs_capsule(ox, oy, dx, dy, body, *half_height, *radius)
+        }
+    }
 }
 
 fn ray_vs_circle(
@@ -638,8 +1913,8 @@ fn ray_vs_polygon(
     body: &RigidBody,
     vertices: &[(f32, f32)],
 ) -> Option<f32> {
-    let cos = body.angle.cos();
-    let sin = body.angle.sin();
+    let cos = math::cos(body.angle);
+    let sin = math::sin(body.angle);
     let n = vertices.len();
     if n < 3 {
         return None;
@@ -666,6 +1941,32 @@ fn ray_vs_polygon(
     closest_t
 }
 
+/// Ray vs capsule: the two end circles and the two straight sides.
+fn ray_vs_capsule(
+    ox: f32, oy: f32,
+    dx: f32, dy: f32,
+    body: &RigidBody,
+    half_height: f32,
+    radius: f32,
+) -> Option<f32> {
+    let (sin, cos) = math::sin_cos(body.angle);
+    // Half axis and side offset in world space
+    let (ax, ay) = (-sin * half_height, cos * half_height);
+    let (sx, sy) = (cos * radius, sin * radius);
+    let (p0x, p0y) = (body.x - ax, body.y - ay);
+    let (p1x, p1y) = (body.x + ax, body.y + ay);
+
+    [
+        ray_vs_circle(ox, oy, dx, dy, p0x, p0y, radius),
+        ray_vs_circle(ox, oy, dx, dy, p1x, p1y, radius),
+        ray_vs_segment(ox, oy, dx, dy, p0x + sx, p0y + sy, p1x + sx, p1y + sy),
+        ray_vs_segment(ox, oy, dx, dy, p0x - sx, p0y - sy, p1x - sx, p1y - sy),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(f32::min)
+}
+
 fn ray_vs_segment(
     ox: f32, oy: f32,
     dx: f32, dy: f32,
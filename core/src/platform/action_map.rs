@@ -0,0 +1,436 @@
+//! Named input actions ("jump", "move_x") bound to keys, mouse buttons and
+//! gamepad buttons and axes.
+//!
+//! Bindings come from a JSON file (`op_load_action_map`) or are set per
+//! action (`op_set_action_bindings`), in the shape the TS `createInputMap`
+//! takes:
+//!
+//! ```json
+//! {"jump": ["Space", "GamepadA"],
+//!  "move_x": [{"type":"key","key":"a","scale":-1}, "d",
+//!             {"type":"gamepadAxis","axis":"LeftStickX","deadzone":0.25}]}
+//! ```
+//!
+//! The dev loop calls [`ActionMap::update`] once per frame after syncing
+//! input, so every query in a frame sees the same values. An action's value
+//! is the sum of its bindings' values clamped to -1..1 (opposite keys
+//! cancel); it is held while that is at least [`PRESS_THRESHOLD`] either way.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::json::{escape, JsonValue};
+
+/// Absolute value at which an action counts as held.
+pub const PRESS_THRESHOLD: f32 = 0.5;
+
+/// Stick and trigger travel ignored unless a binding sets its own.
+pub const DEFAULT_DEADZONE: f32 = 0.2;
+
+/// Gamepad button shorthands, as in the TS input map.
+const GAMEPAD_SHORTHANDS: [(&str, &str); 17] = [
+    ("GamepadA", "A"),
+    ("GamepadB", "B"),
+    ("GamepadX", "X"),
+    ("GamepadY", "Y"),
+    ("GamepadLB", "LeftBumper"),
+    ("GamepadRB", "RightBumper"),
+    ("GamepadLT", "LeftTrigger"),
+    ("GamepadRT", "RightTrigger"),
+    ("GamepadSelect", "Select"),
+    ("GamepadStart", "Start"),
+    ("GamepadLS", "LeftStick"),
+    ("GamepadRS", "RightStick"),
+    ("GamepadDPadUp", "DPadUp"),
+    ("GamepadDPadDown", "DPadDown"),
+    ("GamepadDPadLeft", "DPadLeft"),
+    ("GamepadDPadRight", "DPadRight"),
+    ("GamepadGuide", "Guide"),
+];
+
+/// Mouse button shorthands, as in the TS input map.
+const MOUSE_SHORTHANDS: [(&str, u8); 3] = [("MouseLeft", 0), ("MouseRight", 1), ("MouseMiddle", 2)];
+
+/// A physical input an action can be bound to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    Key(String),
+    MouseButton(u8),
+    GamepadButton(String),
+    /// A stick or trigger. `direction` 1 or -1 reads only that half of the
+    /// axis; 0 reads both.
+    GamepadAxis { axis: String, direction: i8, deadzone: f32 },
+}
+
+/// An input source and how much it moves its action: keys and buttons give
+/// `scale` while held, axes give their deadzoned value times `scale`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub source: InputSource,
+    pub scale: f32,
+}
+
+impl Binding {
+    /// Parse a shorthand string ("Space", "GamepadA", "MouseLeft") or a
+    /// `{"type": ...}` object.
+    pub fn parse(value: &JsonValue) -> Result<Self, String> {
+        if let Some(name) = value.as_str() {
+            let source = if let Some((_, button)) = GAMEPAD_SHORTHANDS.iter().find(|(s, _)| *s == name) {
+                InputSource::GamepadButton(button.to_string())
+            } else if let Some((_, button)) = MOUSE_SHORTHANDS.iter().find(|(s, _)| *s == name) {
+                InputSource::MouseButton(*button)
+            } else {
+                InputSource::Key(name.to_string())
+            };
+            return Ok(Self { source, scale: 1.0 });
+        }
+        let field = |key: &str| value.str_of(key).ok_or_else(|| format!("binding is missing \"{key}\""));
+        let source = match value.str_of("type") {
+            Some("key") => InputSource::Key(field("key")?.to_string()),
+            Some("mouseButton") => {
+                let button = value.get("button").and_then(JsonValue::as_f64).ok_or("binding is missing \"button\"")?;
+                InputSource::MouseButton(button as u8)
+            }
+            Some("gamepadButton") => InputSource::GamepadButton(field("button")?.to_string()),
+            Some("gamepadAxis") => InputSource::GamepadAxis {
+                axis: field("axis")?.to_string(),
+                direction: match value.f32_or("direction", 0.0) {
+                    d if d > 0.0 => 1,
+                    d if d < 0.0 => -1,
+                    _ => 0,
+                },
+                deadzone: value.f32_or("deadzone", DEFAULT_DEADZONE).clamp(0.0, 0.99),
+            },
+            Some(other) => return Err(format!("unknown binding type \"{other}\"")),
+            None => return Err("binding must be a string or an object with \"type\"".into()),
+        };
+        Ok(Self { source, scale: value.f32_or("scale", 1.0) })
+    }
+
+    /// Object form, readable by [`Binding::parse`].
+    pub fn to_json(&self) -> String {
+        let source = match &self.source {
+            InputSource::Key(key) => format!("\"type\":\"key\",\"key\":\"{}\"", escape(key)),
+            InputSource::MouseButton(button) => format!("\"type\":\"mouseButton\",\"button\":{button}"),
+            InputSource::GamepadButton(button) => {
+                format!("\"type\":\"gamepadButton\",\"button\":\"{}\"", escape(button))
+            }
+            InputSource::GamepadAxis { axis, direction, deadzone } => format!(
+                "\"type\":\"gamepadAxis\",\"axis\":\"{}\",\"direction\":{direction},\"deadzone\":{deadzone}",
+                escape(axis)
+            ),
+        };
+        format!("{{{source},\"scale\":{}}}", self.scale)
+    }
+
+    fn value(&self, inputs: &ActionInputs) -> f32 {
+        let held = |down: bool| if down { self.scale } else { 0.0 };
+        match &self.source {
+            InputSource::Key(key) => held(inputs.keys_down.contains(key)),
+            InputSource::MouseButton(button) => held(inputs.mouse_buttons_down.contains(button)),
+            InputSource::GamepadButton(button) => held(inputs.gamepad_buttons_down.contains(button)),
+            InputSource::GamepadAxis { axis, direction, deadzone } => {
+                let raw = inputs.gamepad_axes.get(axis).copied().unwrap_or(0.0);
+                let value = apply_deadzone(raw, *deadzone);
+                let value = match direction {
+                    1 => value.max(0.0),
+                    -1 => value.min(0.0),
+                    _ => value,
+                };
+                value * self.scale
+            }
+        }
+    }
+
+    /// Whether a key or button of this binding went down this frame, even if
+    /// it came back up before the frame ran.
+    fn pressed(&self, inputs: &ActionInputs) -> bool {
+        match &self.source {
+            InputSource::Key(key) => inputs.keys_pressed.contains(key),
+            InputSource::MouseButton(button) => inputs.mouse_buttons_pressed.contains(button),
+            InputSource::GamepadButton(button) => inputs.gamepad_buttons_pressed.contains(button),
+            InputSource::GamepadAxis { .. } => false,
+        }
+    }
+}
+
+/// Rescale `value` so travel inside `deadzone` reads 0 and full travel
+/// still reads 1.
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone {
+        return 0.0;
+    }
+    value.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+}
+
+/// Input state an [`ActionMap`] is evaluated against.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionInputs<'a> {
+    pub keys_down: &'a HashSet<String>,
+    pub keys_pressed: &'a HashSet<String>,
+    pub mouse_buttons_down: &'a HashSet<u8>,
+    pub mouse_buttons_pressed: &'a HashSet<u8>,
+    pub gamepad_buttons_down: &'a HashSet<String>,
+    pub gamepad_buttons_pressed: &'a HashSet<String>,
+    pub gamepad_axes: &'a HashMap<String, f32>,
+}
+
+/// An action's readings for the current frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionState {
+    /// Summed binding values, -1..1.
+    pub value: f32,
+    pub down: bool,
+    /// Became held this frame.
+    pub pressed: bool,
+    /// Stopped being held this frame.
+    pub released: bool,
+}
+
+/// Actions, their bindings and their state as of the last update.
+#[derive(Debug, Clone, Default)]
+pub struct ActionMap {
+    /// Actions in definition order.
+    actions: Vec<(String, Vec<Binding>)>,
+    states: HashMap<String, ActionState>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace an action's bindings. No bindings removes the action.
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) {
+        let existing = self.actions.iter().position(|(name, _)| name == action);
+        match (existing, bindings.is_empty()) {
+            (Some(i), true) => {
+                self.actions.remove(i);
+                self.states.remove(action);
+            }
+            (Some(i), false) => self.actions[i].1 = bindings,
+            (None, false) => self.actions.push((action.to_string(), bindings)),
+            (None, true) => {}
+        }
+    }
+
+    /// Parse bindings for one action: an array of bindings, or an object
+    /// with a `bindings` array (the TS `ActionDef` form).
+    pub fn parse_bindings(value: &JsonValue) -> Result<Vec<Binding>, String> {
+        let list = value
+            .as_array()
+            .or_else(|| value.get("bindings").and_then(JsonValue::as_array))
+            .ok_or("expected an array of bindings")?;
+        list.iter().map(Binding::parse).collect()
+    }
+
+    /// Set every action of a JSON action map, keeping actions it doesn't
+    /// mention. Nothing changes if any binding is invalid.
+    pub fn load_json(&mut self, json: &str) -> Result<usize, String> {
+        let root = JsonValue::parse(json)?;
+        let members = root.as_object().ok_or("action map must be an object of actions")?;
+        let mut parsed = Vec::with_capacity(members.len());
+        for (action, value) in members {
+            let bindings = Self::parse_bindings(value).map_err(|e| format!("action \"{action}\": {e}"))?;
+            parsed.push((action, bindings));
+        }
+        let count = parsed.len();
+        for (action, bindings) in parsed {
+            self.set_bindings(action, bindings);
+        }
+        Ok(count)
+    }
+
+    /// Every action with its bindings in object form, loadable with
+    /// [`ActionMap::load_json`] (for saving rebound controls).
+    pub fn to_json(&self) -> String {
+        let actions: Vec<String> = self
+            .actions
+            .iter()
+            .map(|(name, bindings)| {
+                let bindings: Vec<String> = bindings.iter().map(Binding::to_json).collect();
+                format!("\"{}\":[{}]", escape(name), bindings.join(","))
+            })
+            .collect();
+        format!("{{{}}}", actions.join(","))
+    }
+
+    pub fn bindings(&self, action: &str) -> Option<&[Binding]> {
+        self.actions.iter().find(|(name, _)| name == action).map(|(_, b)| b.as_slice())
+    }
+
+    pub fn clear(&mut self) {
+        self.actions.clear();
+        self.states.clear();
+    }
+
+    /// Evaluate every action against this frame's input.
+    pub fn update(&mut self, inputs: &ActionInputs) {
+        for (name, bindings) in &self.actions {
+            let value: f32 = bindings.iter().map(|b| b.value(inputs)).sum::<f32>().clamp(-1.0, 1.0);
+            let down = value.abs() >= PRESS_THRESHOLD;
+            let was_down = self.states.get(name).is_some_and(|s| s.down);
+            let tapped = bindings.iter().any(|b| b.pressed(inputs));
+            let state = ActionState { value, down, pressed: (down && !was_down) || tapped, released: was_down && !down };
+            self.states.insert(name.clone(), state);
+        }
+    }
+
+    /// State of `action` as of the last update; all zero for unknown actions.
+    pub fn state(&self, action: &str) -> ActionState {
+        self.states.get(action).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Input {
+        keys: HashSet<String>,
+        keys_pressed: HashSet<String>,
+        mouse: HashSet<u8>,
+        buttons: HashSet<String>,
+        axes: HashMap<String, f32>,
+    }
+
+    impl Input {
+        fn update(&self, map: &mut ActionMap) {
+            let none_u8 = HashSet::new();
+            let none = HashSet::new();
+            map.update(&ActionInputs {
+                keys_down: &self.keys,
+                keys_pressed: &self.keys_pressed,
+                mouse_buttons_down: &self.mouse,
+                mouse_buttons_pressed: &none_u8,
+                gamepad_buttons_down: &self.buttons,
+                gamepad_buttons_pressed: &none,
+                gamepad_axes: &self.axes,
+            });
+        }
+    }
+
+    const MAP: &str = r#"{
+        "jump": ["Space", "GamepadA", "MouseLeft"],
+        "move_x": [{"type":"key","key":"a","scale":-1}, "d",
+                   {"type":"gamepadAxis","axis":"LeftStickX","deadzone":0.25}],
+        "aim_left": {"bindings": [{"type":"gamepadAxis","axis":"RightStickX","direction":-1}]}
+    }"#;
+
+    #[test]
+    fn parses_shorthands_and_objects() {
+        let mut map = ActionMap::new();
+        assert_eq!(map.load_json(MAP), Ok(3));
+        let jump = map.bindings("jump").unwrap();
+        assert_eq!(jump[0].source, InputSource::Key("Space".into()));
+        assert_eq!(jump[1].source, InputSource::GamepadButton("A".into()));
+        assert_eq!(jump[2].source, InputSource::MouseButton(0));
+        let move_x = map.bindings("move_x").unwrap();
+        assert_eq!(move_x[0].scale, -1.0);
+        assert_eq!(
+            move_x[2].source,
+            InputSource::GamepadAxis { axis: "LeftStickX".into(), direction: 0, deadzone: 0.25 }
+        );
+        let InputSource::GamepadAxis { direction, deadzone, .. } = &map.bindings("aim_left").unwrap()[0].source else {
+            panic!("expected an axis binding");
+        };
+        assert_eq!((*direction, *deadzone), (-1, DEFAULT_DEADZONE));
+    }
+
+    #[test]
+    fn invalid_maps_change_nothing() {
+        let mut map = ActionMap::new();
+        map.load_json(MAP).unwrap();
+        let err = map.load_json(r#"{"jump": ["x"], "fire": [{"type":"pedal"}]}"#).unwrap_err();
+        assert!(err.contains("fire"), "{err}");
+        assert_eq!(map.bindings("jump").unwrap().len(), 3);
+        assert!(map.load_json("[1]").is_err());
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let mut map = ActionMap::new();
+        map.load_json(MAP).unwrap();
+        let mut copy = ActionMap::new();
+        copy.load_json(&map.to_json()).unwrap();
+        for action in ["jump", "move_x", "aim_left"] {
+            assert_eq!(copy.bindings(action), map.bindings(action), "{action}");
+        }
+    }
+
+    #[test]
+    fn digital_actions_press_hold_and_release() {
+        let mut map = ActionMap::new();
+        map.load_json(MAP).unwrap();
+        let mut input = Input::default();
+        input.buttons.insert("A".into());
+        input.update(&mut map);
+        assert_eq!(map.state("jump"), ActionState { value: 1.0, down: true, pressed: true, released: false });
+
+        input.mouse.insert(0);
+        input.update(&mut map);
+        let held = map.state("jump");
+        assert!(held.down && !held.pressed);
+        assert_eq!(held.value, 1.0);
+
+        input = Input::default();
+        input.update(&mut map);
+        assert_eq!(map.state("jump"), ActionState { value: 0.0, down: false, pressed: false, released: true });
+
+        // Tapped and let go between frames still presses
+        input.keys_pressed.insert("Space".into());
+        input.update(&mut map);
+        assert!(map.state("jump").pressed && !map.state("jump").down);
+        assert_eq!(map.state("missing"), ActionState::default());
+    }
+
+    #[test]
+    fn axes_sum_keys_and_deadzoned_sticks() {
+        let mut map = ActionMap::new();
+        map.load_json(MAP).unwrap();
+        let mut input = Input::default();
+        input.keys.insert("a".into());
+        input.update(&mut map);
+        assert_eq!(map.state("move_x").value, -1.0);
+
+        // Opposite keys cancel
+        input.keys.insert("d".into());
+        input.update(&mut map);
+        assert_eq!(map.state("move_x").value, 0.0);
+        assert!(map.state("move_x").released);
+
+        input = Input::default();
+        input.axes.insert("LeftStickX".into(), 0.2);
+        input.update(&mut map);
+        assert_eq!(map.state("move_x").value, 0.0);
+        input.axes.insert("LeftStickX".into(), 0.625);
+        input.update(&mut map);
+        assert_eq!(map.state("move_x").value, 0.5);
+        assert!(map.state("move_x").down);
+
+        // A half-axis binding ignores the other half
+        input.axes.insert("RightStickX".into(), 0.9);
+        input.update(&mut map);
+        assert_eq!(map.state("aim_left").value, 0.0);
+        input.axes.insert("RightStickX".into(), -1.0);
+        input.update(&mut map);
+        assert_eq!(map.state("aim_left").value, -1.0);
+    }
+
+    #[test]
+    fn empty_bindings_remove_an_action() {
+        let mut map = ActionMap::new();
+        map.load_json(MAP).unwrap();
+        map.set_bindings("jump", Vec::new());
+        assert!(map.bindings("jump").is_none());
+        assert_eq!(map.to_json().matches("\"type\":\"key\"").count(), 2);
+    }
+
+    #[test]
+    fn deadzone_rescales_travel() {
+        assert_eq!(apply_deadzone(0.1, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-1.0, 0.2), -1.0);
+        assert!((apply_deadzone(0.6, 0.2) - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,163 @@
+//! Window focus loss handling.
+//!
+//! The window reports focus changes into [`FocusTracker`]; the dev loop turns
+//! them into events for TS and applies the game's [`FocusPolicy`]: pausing the
+//! simulation (zero delta time, physics steps skipped), lowering the audio
+//! output gain, and capping the frame rate while the window is in the
+//! background. The frame callback keeps running while paused so the game can
+//! draw a pause menu.
+
+use std::time::Duration;
+
+/// What to do while the window does not have focus.
+/// The default keeps running as if focused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusPolicy {
+    /// Freeze the simulation: delta time reads 0 and physics does not step.
+    pub pause: bool,
+    /// Audio output gain while unfocused (1 = unchanged, 0 = muted).
+    pub audio_gain: f32,
+    /// Frame rate cap while unfocused. 0 = uncapped.
+    pub max_fps: u32,
+}
+
+impl Default for FocusPolicy {
+    fn default() -> Self {
+        Self {
+            pause: false,
+            audio_gain: 1.0,
+            max_fps: 0,
+        }
+    }
+}
+
+impl FocusPolicy {
+    /// Whether the simulation is paused given the current focus.
+    pub fn paused(&self, focused: bool) -> bool {
+        self.pause && !focused
+    }
+
+    /// Audio output gain given the current focus.
+    pub fn audio_gain(&self, focused: bool) -> f32 {
+        if focused {
+            1.0
+        } else {
+            self.audio_gain.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Minimum time between frames given the current focus, if capped.
+    pub fn frame_interval(&self, focused: bool) -> Option<Duration> {
+        if focused || self.max_fps == 0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(1.0 / self.max_fps as f64))
+    }
+}
+
+/// A focus change, as delivered to TS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusEvent {
+    pub focused: bool,
+    /// Whether the simulation is paused after this change.
+    pub paused: bool,
+}
+
+/// Tracks window focus and queues changes until the frame callback reads them.
+#[derive(Debug)]
+pub struct FocusTracker {
+    focused: bool,
+    events: Vec<FocusEvent>,
+}
+
+impl Default for FocusTracker {
+    fn default() -> Self {
+        Self {
+            focused: true,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl FocusTracker {
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Record a focus change from the window. Repeated reports are ignored.
+    pub fn set_focused(&mut self, focused: bool, policy: &FocusPolicy) {
+        if focused == self.focused {
+            return;
+        }
+        self.focused = focused;
+        self.events.push(FocusEvent {
+            focused,
+            paused: policy.paused(focused),
+        });
+    }
+
+    /// Take the changes recorded since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<FocusEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Focus events as a JSON array: `[{"focused":false,"paused":true},...]`.
+pub fn events_json(events: &[FocusEvent]) -> String {
+    let items: Vec<String> = events
+        .iter()
+        .map(|e| format!("{{\"focused\":{},\"paused\":{}}}", e.focused, e.paused))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_keeps_running() {
+        let policy = FocusPolicy::default();
+        assert!(!policy.paused(false));
+        assert_eq!(policy.audio_gain(false), 1.0);
+        assert_eq!(policy.frame_interval(false), None);
+    }
+
+    #[test]
+    fn test_policy_applies_only_while_unfocused() {
+        let policy = FocusPolicy {
+            pause: true,
+            audio_gain: 0.25,
+            max_fps: 10,
+        };
+        assert!(policy.paused(false));
+        assert!(!policy.paused(true));
+        assert_eq!(policy.audio_gain(false), 0.25);
+        assert_eq!(policy.audio_gain(true), 1.0);
+        assert_eq!(
+            policy.frame_interval(false),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(policy.frame_interval(true), None);
+    }
+
+    #[test]
+    fn test_tracker_queues_changes_once() {
+        let policy = FocusPolicy {
+            pause: true,
+            ..FocusPolicy::default()
+        };
+        let mut tracker = FocusTracker::default();
+        tracker.set_focused(true, &policy);
+        tracker.set_focused(false, &policy);
+        tracker.set_focused(false, &policy);
+        tracker.set_focused(true, &policy);
+        let events = tracker.take_events();
+        assert_eq!(
+            events_json(&events),
+            "[{\"focused\":false,\"paused\":true},{\"focused\":true,\"paused\":false}]"
+        );
+        assert!(tracker.take_events().is_empty());
+        assert!(tracker.is_focused());
+    }
+}
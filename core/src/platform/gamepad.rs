@@ -177,6 +177,8 @@ pub struct GamepadManager {
     id_to_slot: std::collections::HashMap<gilrs::GamepadId, usize>,
     /// Number of connected gamepads.
     pub connected_count: u32,
+    /// The rumble playing, if any. Dropping it stops the motors.
+    rumble: Option<gilrs::ff::Effect>,
 }
 
 impl GamepadManager {
@@ -194,6 +196,7 @@ impl GamepadManager {
             gamepads: Default::default(),
             id_to_slot: std::collections::HashMap::new(),
             connected_count: 0,
+            rumble: None,
         };
 
         // Register initially connected gamepads
@@ -284,6 +287,40 @@ impl GamepadManager {
         }
     }
 
+    /// Vibrate every connected gamepad that supports force feedback at
+    /// `strength` (0-1) for `seconds`, replacing any rumble still playing.
+    pub fn rumble(&mut self, strength: f32, seconds: f32) {
+        use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks};
+
+        self.rumble = None;
+        let ids: Vec<gilrs::GamepadId> = self
+            .id_to_slot
+            .keys()
+            .copied()
+            .filter(|&id| self.gilrs.gamepad(id).is_ff_supported())
+            .collect();
+        if ids.is_empty() || strength <= 0.0 {
+            return;
+        }
+        let magnitude = (strength.min(1.0) * u16::MAX as f32) as u16;
+        let duration = Ticks::from_ms((seconds * 1000.0) as u32);
+        let motor = |kind| BaseEffect {
+            kind,
+            scheduling: Replay { play_for: duration, ..Default::default() },
+            ..Default::default()
+        };
+        let effect = EffectBuilder::new()
+            .add_effect(motor(BaseEffectType::Strong { magnitude }))
+            .add_effect(motor(BaseEffectType::Weak { magnitude }))
+            .gamepads(&ids)
+            .repeat(Repeat::For(duration))
+            .finish(&mut self.gilrs);
+        match effect.and_then(|effect| effect.play().map(|()| effect)) {
+            Ok(effect) => self.rumble = Some(effect),
+            Err(e) => eprintln!("[gamepad] Rumble failed: {e}"),
+        }
+    }
+
     /// Get state of the first connected gamepad (convenience for single-player).
     pub fn primary(&self) -> &GamepadState {
         for gp in &self.gamepads {
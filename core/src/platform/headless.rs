@@ -0,0 +1,98 @@
+//! Windowless frame loop for `arcane render`.
+//!
+//! Runs the same frame callback as the dev window against an offscreen
+//! renderer, with a fixed time step and no input, and captures every frame as
+//! a PNG. Identical inputs give identical frames, so the output can serve as
+//! golden images or CI screenshots.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use anyhow::{Context, Result};
+
+use crate::renderer::{RenderThread, Renderer, SharedRenderer};
+
+use super::window::{DevConfig, FrameCallback, RenderState};
+
+/// What to render headlessly. Light capacity comes from [`DevConfig`].
+pub struct HeadlessConfig {
+    /// Frame size in pixels (scale factor 1).
+    pub width: u32,
+    pub height: u32,
+    /// Number of frames to run and capture.
+    pub frames: u32,
+    /// Fixed time step per frame, in seconds.
+    pub dt: f64,
+    /// Directory the PNGs are written to (created if missing).
+    pub out_dir: PathBuf,
+}
+
+/// File name of captured frame `index` (`frame_00000.png`, ...).
+pub fn frame_file_name(index: u32) -> String {
+    format!("frame_{index:05}.png")
+}
+
+/// Run `config.frames` frames offscreen and write each one to `out_dir`.
+/// Returns the paths written, in frame order.
+pub fn run_headless(
+    config: DevConfig,
+    headless: HeadlessConfig,
+    render_state: Rc<RefCell<RenderState>>,
+    mut frame_callback: FrameCallback,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(&headless.out_dir)
+        .with_context(|| format!("Cannot create output directory {}", headless.out_dir.display()))?;
+
+    let renderer = Renderer::new_headless(headless.width, headless.height, config.max_lights)?;
+    let shared = SharedRenderer::new(renderer);
+    {
+        let mut state = render_state.borrow_mut();
+        let renderer = shared.lock();
+        state.viewport_size = renderer.camera.viewport_size;
+        state.scale_factor = renderer.scale_factor;
+        let [width, height] = state.viewport_size;
+        state.lifecycle.resize(width, height, renderer.scale_factor);
+    }
+    render_state.borrow_mut().renderer = Some(shared.clone());
+    // Inline, so each submit returns its own frame's capture
+    let render_thread = RenderThread::inline(shared);
+
+    let mut written = Vec::with_capacity(headless.frames as usize);
+    for index in 0..headless.frames {
+        let mut state = render_state.borrow_mut();
+        state.delta_time = headless.dt;
+        if let Err(e) = frame_callback(&mut state) {
+            eprintln!("Frame callback error: {e}");
+        }
+        state.input.begin_frame();
+        state.touch.begin_frame();
+
+        let viewport_size = state.viewport_size;
+        state.frame.clamp_camera(viewport_size);
+        let mut frame = state.frame.take();
+        frame.capture = true;
+        let png = render_thread
+            .submit(frame)
+            .with_context(|| format!("Failed to capture frame {index}"))?;
+
+        let path = headless.out_dir.join(frame_file_name(index));
+        std::fs::write(&path, png).with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_file_names_sort_in_frame_order() {
+        assert_eq!(frame_file_name(0), "frame_00000.png");
+        assert_eq!(frame_file_name(42), "frame_00042.png");
+        let mut names: Vec<String> = [10, 9, 100].into_iter().map(frame_file_name).collect();
+        names.sort();
+        assert_eq!(names, ["frame_00009.png", "frame_00010.png", "frame_00100.png"]);
+    }
+}
@@ -1,5 +1,66 @@
 use std::collections::HashSet;
 
+use crate::json::escape;
+
+/// Events kept per frame; past this, further events that frame are dropped.
+pub const MAX_FRAME_EVENTS: usize = 512;
+
+/// A keyboard, text or wheel event, in the order the window received it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// `key` uses the names of `keys_down`; `code` is the physical key
+    /// ("KeyA", "Digit1") whatever the layout. `repeat` is set for the
+    /// presses a held key auto-repeats.
+    KeyDown { key: String, code: String, repeat: bool },
+    /// `code` is empty for releases made up on focus loss.
+    KeyUp { key: String, code: String },
+    /// Text typed, as the keyboard layout, dead keys and IME produced it.
+    Text(String),
+    /// IME composition in progress (empty when it ends), with the cursor as
+    /// a UTF-16 range of `text`, or None when hidden.
+    Composition { text: String, cursor: Option<(usize, usize)> },
+    /// Scroll amount, positive right and down (content moves up). In lines
+    /// when `lines`, else logical pixels.
+    Wheel { dx: f32, dy: f32, lines: bool },
+}
+
+impl InputEvent {
+    /// IME composition with its cursor converted from byte to UTF-16 offsets.
+    pub fn composition(text: String, cursor: Option<(usize, usize)>) -> Self {
+        let utf16 = |byte: usize| text.get(..byte).map_or(0, |s| s.encode_utf16().count());
+        let cursor = cursor.map(|(start, end)| (utf16(start), utf16(end)));
+        Self::Composition { text, cursor }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::KeyDown { key, code, repeat } => format!(
+                "{{\"type\":\"keydown\",\"key\":\"{}\",\"code\":\"{}\",\"repeat\":{repeat}}}",
+                escape(key),
+                escape(code)
+            ),
+            Self::KeyUp { key, code } => {
+                format!("{{\"type\":\"keyup\",\"key\":\"{}\",\"code\":\"{}\"}}", escape(key), escape(code))
+            }
+            Self::Text(text) => format!("{{\"type\":\"text\",\"text\":\"{}\"}}", escape(text)),
+            Self::Composition { text, cursor } => {
+                let cursor = cursor.map_or("null".to_string(), |(start, end)| format!("[{start},{end}]"));
+                format!("{{\"type\":\"composition\",\"text\":\"{}\",\"cursor\":{cursor}}}", escape(text))
+            }
+            Self::Wheel { dx, dy, lines } => {
+                let mode = if *lines { "line" } else { "pixel" };
+                format!("{{\"type\":\"wheel\",\"dx\":{dx},\"dy\":{dy},\"mode\":\"{mode}\"}}")
+            }
+        }
+    }
+}
+
+/// JSON array of `events`, oldest first.
+pub fn events_json(events: &[InputEvent]) -> String {
+    let items: Vec<String> = events.iter().map(InputEvent::to_json).collect();
+    format!("[{}]", items.join(","))
+}
+
 /// Tracks keyboard and mouse state each frame.
 #[derive(Debug, Default)]
 pub struct InputState {
@@ -18,6 +79,8 @@ pub struct InputState {
     pub mouse_buttons_pressed: HashSet<u8>,
     /// Mouse buttons released this frame.
     pub mouse_buttons_released: HashSet<u8>,
+    /// Keyboard, text and wheel events since the last frame, in order.
+    pub events: Vec<InputEvent>,
 }
 
 impl InputState {
@@ -27,6 +90,23 @@ impl InputState {
         self.keys_released.clear();
         self.mouse_buttons_pressed.clear();
         self.mouse_buttons_released.clear();
+        self.events.clear();
+    }
+
+    /// Queue an event for this frame.
+    pub fn push_event(&mut self, event: InputEvent) {
+        if self.events.len() < MAX_FRAME_EVENTS {
+            self.events.push(event);
+        }
+    }
+
+    /// Queue typed text. Control characters (Enter, Backspace, Tab, Ctrl
+    /// shortcuts) arrive as key events only.
+    pub fn text_input(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        if !text.is_empty() {
+            self.push_event(InputEvent::Text(text));
+        }
     }
 
     /// Record a key press event.
@@ -63,6 +143,24 @@ impl InputState {
         }
     }
 
+    /// Release every held key and mouse button. Used on focus loss, after
+    /// which the window no longer receives the matching release events.
+    pub fn release_all(&mut self) {
+        // Mouse buttons mirrored into keys_down never had key events
+        let mut held: Vec<String> = self
+            .keys_down
+            .iter()
+            .filter(|key| !matches!(key.as_str(), "MouseLeft" | "MouseRight" | "MouseMiddle"))
+            .cloned()
+            .collect();
+        held.sort();
+        for key in held {
+            self.push_event(InputEvent::KeyUp { key, code: String::new() });
+        }
+        self.keys_released.extend(self.keys_down.drain());
+        self.mouse_buttons_released.extend(self.mouse_buttons.drain());
+    }
+
     /// Check if a key is currently held.
     pub fn is_key_down(&self, key: &str) -> bool {
         self.keys_down.contains(key)
@@ -210,4 +308,71 @@ mod tests {
         assert_eq!(input.keys_down.len(), 0);
         assert_eq!(input.keys_pressed.len(), 0);
     }
+
+    #[test]
+    fn release_all_reports_held_input_as_released() {
+        let mut input = InputState::default();
+        input.key_down("a");
+        input.mouse_button_down(0);
+        input.begin_frame();
+
+        input.release_all();
+        assert!(!input.is_key_down("a"));
+        assert!(input.keys_released.contains("a"));
+        assert!(input.mouse_buttons.is_empty());
+        assert!(input.mouse_buttons_released.contains(&0));
+    }
+
+    #[test]
+    fn events_keep_order_until_the_frame_ends() {
+        let mut input = InputState::default();
+        input.push_event(InputEvent::KeyDown { key: "a".into(), code: "KeyA".into(), repeat: false });
+        input.text_input("a");
+        input.push_event(InputEvent::KeyDown { key: "a".into(), code: "KeyA".into(), repeat: true });
+        input.text_input("\r");
+        input.push_event(InputEvent::Wheel { dx: 0.0, dy: 3.0, lines: true });
+        assert_eq!(
+            events_json(&input.events),
+            concat!(
+                r#"[{"type":"keydown","key":"a","code":"KeyA","repeat":false},"#,
+                r#"{"type":"text","text":"a"},"#,
+                r#"{"type":"keydown","key":"a","code":"KeyA","repeat":true},"#,
+                r#"{"type":"wheel","dx":0,"dy":3,"mode":"line"}]"#
+            )
+        );
+        input.begin_frame();
+        assert!(input.events.is_empty());
+
+        for _ in 0..MAX_FRAME_EVENTS + 10 {
+            input.text_input("x");
+        }
+        assert_eq!(input.events.len(), MAX_FRAME_EVENTS);
+    }
+
+    #[test]
+    fn composition_cursor_is_in_utf16_units() {
+        // "にほ" is 6 bytes, 2 UTF-16 units; "😀" is 4 bytes, 2 units
+        let event = InputEvent::composition("にほ😀".into(), Some((6, 10)));
+        assert_eq!(event, InputEvent::Composition { text: "にほ😀".into(), cursor: Some((2, 4)) });
+        assert_eq!(
+            InputEvent::composition(String::new(), None).to_json(),
+            r#"{"type":"composition","text":"","cursor":null}"#
+        );
+    }
+
+    #[test]
+    fn release_all_queues_key_up_events() {
+        let mut input = InputState::default();
+        input.key_down("w");
+        input.key_down("a");
+        input.begin_frame();
+        input.release_all();
+        assert_eq!(
+            input.events,
+            vec![
+                InputEvent::KeyUp { key: "a".into(), code: String::new() },
+                InputEvent::KeyUp { key: "w".into(), code: String::new() },
+            ]
+        );
+    }
 }
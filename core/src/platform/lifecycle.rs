@@ -0,0 +1,182 @@
+//! App lifecycle events: suspend/resume, low-memory warnings, and resizes
+//! with the resulting orientation.
+//!
+//! The window reports these into [`LifecycleTracker`]; the dev loop hands the
+//! queued events to TS each frame (`op_take_lifecycle_events`). On desktop,
+//! suspend/resume and memory warnings are rare, but games handle them the same
+//! way they will on mobile: save on suspend, drop caches on a memory warning,
+//! and re-layout on resize.
+
+/// Screen orientation, from the window's aspect ratio. Square counts as landscape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+impl Orientation {
+    pub fn from_size(width: f32, height: f32) -> Self {
+        if height > width {
+            Self::Portrait
+        } else {
+            Self::Landscape
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Portrait => "portrait",
+            Self::Landscape => "landscape",
+        }
+    }
+}
+
+/// A lifecycle change, as delivered to TS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LifecycleEvent {
+    /// The app went to the background; rendering may stop until `Resume`.
+    Suspend,
+    Resume,
+    /// The OS is low on memory; free what can be reloaded.
+    MemoryWarning,
+    /// The viewport changed size (logical pixels).
+    Resize {
+        width: f32,
+        height: f32,
+        scale_factor: f32,
+        orientation: Orientation,
+        /// Whether the orientation differs from the previous size.
+        orientation_changed: bool,
+    },
+}
+
+/// Tracks lifecycle state and queues changes until the frame callback reads them.
+#[derive(Debug, Default)]
+pub struct LifecycleTracker {
+    suspended: bool,
+    orientation: Option<Orientation>,
+    events: Vec<LifecycleEvent>,
+}
+
+impl LifecycleTracker {
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Orientation of the last reported size, if any.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.orientation
+    }
+
+    /// Record a suspend. Repeated reports are ignored.
+    pub fn suspend(&mut self) {
+        if !self.suspended {
+            self.suspended = true;
+            self.events.push(LifecycleEvent::Suspend);
+        }
+    }
+
+    /// Record a resume. Ignored unless suspended.
+    pub fn resume(&mut self) {
+        if self.suspended {
+            self.suspended = false;
+            self.events.push(LifecycleEvent::Resume);
+        }
+    }
+
+    pub fn memory_warning(&mut self) {
+        self.events.push(LifecycleEvent::MemoryWarning);
+    }
+
+    /// Record a new viewport size. Resizes within one frame are coalesced into
+    /// the last one, keeping any orientation change among them.
+    pub fn resize(&mut self, width: f32, height: f32, scale_factor: f32) {
+        let orientation = Orientation::from_size(width, height);
+        let mut orientation_changed = self.orientation.is_some_and(|o| o != orientation);
+        if let Some(LifecycleEvent::Resize { orientation_changed: pending, .. }) = self.events.last() {
+            orientation_changed |= *pending;
+            self.events.pop();
+        }
+        self.orientation = Some(orientation);
+        self.events.push(LifecycleEvent::Resize {
+            width,
+            height,
+            scale_factor,
+            orientation,
+            orientation_changed,
+        });
+    }
+
+    /// Take the changes recorded since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<LifecycleEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Lifecycle events as a JSON array, e.g. `[{"type":"suspend"},{"type":"resize",
+/// "width":390,"height":844,"scaleFactor":3,"orientation":"portrait","orientationChanged":true}]`.
+pub fn events_json(events: &[LifecycleEvent]) -> String {
+    let items: Vec<String> = events
+        .iter()
+        .map(|e| match e {
+            LifecycleEvent::Suspend => "{\"type\":\"suspend\"}".to_string(),
+            LifecycleEvent::Resume => "{\"type\":\"resume\"}".to_string(),
+            LifecycleEvent::MemoryWarning => "{\"type\":\"memoryWarning\"}".to_string(),
+            LifecycleEvent::Resize { width, height, scale_factor, orientation, orientation_changed } => format!(
+                "{{\"type\":\"resize\",\"width\":{width},\"height\":{height},\"scaleFactor\":{scale_factor},\"orientation\":\"{}\",\"orientationChanged\":{orientation_changed}}}",
+                orientation.as_str()
+            ),
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_resume_pairs() {
+        let mut tracker = LifecycleTracker::default();
+        tracker.resume();
+        tracker.suspend();
+        tracker.suspend();
+        assert!(tracker.is_suspended());
+        tracker.resume();
+        tracker.memory_warning();
+        assert_eq!(
+            tracker.take_events(),
+            vec![LifecycleEvent::Suspend, LifecycleEvent::Resume, LifecycleEvent::MemoryWarning]
+        );
+        assert!(tracker.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_resizes_coalesce_and_report_orientation_changes() {
+        let mut tracker = LifecycleTracker::default();
+        tracker.resize(800.0, 600.0, 1.0);
+        assert_eq!(
+            events_json(&tracker.take_events()),
+            "[{\"type\":\"resize\",\"width\":800,\"height\":600,\"scaleFactor\":1,\"orientation\":\"landscape\",\"orientationChanged\":false}]"
+        );
+
+        // Rotating through several sizes in one frame yields one event that
+        // still reports the orientation change
+        tracker.resize(700.0, 650.0, 2.0);
+        tracker.resize(600.0, 800.0, 2.0);
+        tracker.resize(600.0, 820.0, 2.0);
+        let events = tracker.take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0],
+            LifecycleEvent::Resize {
+                width: 600.0,
+                height: 820.0,
+                scale_factor: 2.0,
+                orientation: Orientation::Portrait,
+                orientation_changed: true,
+            }
+        );
+        assert_eq!(tracker.orientation(), Some(Orientation::Portrait));
+    }
+}
@@ -1,9 +1,17 @@
 pub mod window;
+pub mod headless;
 pub mod input;
+pub mod action_map;
 pub mod gamepad;
 pub mod touch;
+pub mod focus;
+pub mod lifecycle;
 
-pub use input::InputState;
+pub use input::{InputEvent, InputState};
+pub use action_map::{ActionMap, ActionState, Binding, InputSource};
 pub use window::run_event_loop;
+pub use headless::{run_headless, HeadlessConfig};
 pub use gamepad::{GamepadManager, GamepadState, GamepadButton, GamepadAxis};
-pub use touch::TouchState;
+pub use touch::{TouchEmulator, TouchState};
+pub use focus::{FocusPolicy, FocusTracker};
+pub use lifecycle::{LifecycleEvent, LifecycleTracker, Orientation};
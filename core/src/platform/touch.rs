@@ -122,10 +122,64 @@ impl TouchState {
     }
 }
 
+/// Touch id used for mouse-emulated touches.
+pub const EMULATED_TOUCH_ID: u64 = u64::MAX;
+
+/// Turns left mouse button input into a single touch point, so touch controls
+/// can be tested on desktop (`arcane dev --emulate-touch`).
+#[derive(Debug, Default)]
+pub struct TouchEmulator {
+    pressed: bool,
+}
+
+impl TouchEmulator {
+    /// Phase for a left button press or release, if it changes the touch.
+    pub fn button(&mut self, pressed: bool) -> Option<TouchPhase> {
+        if pressed == self.pressed {
+            return None;
+        }
+        self.pressed = pressed;
+        Some(if pressed { TouchPhase::Start } else { TouchPhase::End })
+    }
+
+    /// Phase for a cursor move: the touch only moves while the button is held.
+    pub fn cursor_moved(&self) -> Option<TouchPhase> {
+        self.pressed.then_some(TouchPhase::Move)
+    }
+
+    /// Cancel a held touch, e.g. when the window loses focus mid-drag.
+    pub fn cancel(&mut self) -> Option<TouchPhase> {
+        std::mem::take(&mut self.pressed).then_some(TouchPhase::Cancel)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn emulator_drags_one_touch_point() {
+        let mut emulator = TouchEmulator::default();
+        let mut state = TouchState::default();
+        assert_eq!(emulator.cursor_moved(), None);
+
+        let feed = |state: &mut TouchState, phase: Option<TouchPhase>, x: f32| {
+            if let Some(phase) = phase {
+                state.touch_event(EMULATED_TOUCH_ID, x, 0.0, phase, 0.0);
+            }
+        };
+        feed(&mut state, emulator.button(true), 10.0);
+        feed(&mut state, emulator.button(true), 10.0);
+        feed(&mut state, emulator.cursor_moved(), 30.0);
+        assert_eq!(state.count(), 1);
+        assert_eq!(state.get_position(0), Some((30.0, 0.0)));
+
+        feed(&mut state, emulator.button(false), 30.0);
+        assert_eq!(state.count(), 0);
+        assert_eq!(emulator.cursor_moved(), None);
+        assert_eq!(emulator.cancel(), None);
+    }
+
     #[test]
     fn touch_start_adds_point() {
         let mut state = TouchState::default();
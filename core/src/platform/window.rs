@@ -6,30 +6,49 @@ use std::time::Instant;
 
 use anyhow::Result;
 use winit::application::ApplicationHandler;
-use winit::event::{ElementState, KeyEvent, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
-use winit::keyboard::{Key, NamedKey};
+use winit::event::{ElementState, Ime, KeyEvent, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::keyboard::{Key, NamedKey, PhysicalKey};
 use winit::window::{Window, WindowId};
 
-use crate::renderer::Renderer;
-use crate::renderer::camera::CameraBounds;
+use crate::renderer::{FrameSet, RenderThread, Renderer, SharedRenderer};
 
-use super::input::InputState;
-use super::touch::{TouchState, TouchPhase};
+use super::focus::{FocusPolicy, FocusTracker};
+use super::input::{InputEvent, InputState};
+use super::lifecycle::LifecycleTracker;
+use super::touch::{TouchEmulator, TouchState, TouchPhase, EMULATED_TOUCH_ID};
 
 /// Shared render state accessible from both the event loop and scripting ops.
 pub struct RenderState {
-    pub renderer: Option<Renderer>,
+    /// Shared with the render thread; locking waits for the frame in flight.
+    pub renderer: Option<SharedRenderer>,
     pub input: InputState,
     pub touch: TouchState,
-    pub sprite_commands: Vec<crate::renderer::SpriteCommand>,
-    pub camera_x: f32,
-    pub camera_y: f32,
-    pub camera_zoom: f32,
-    pub camera_bounds: Option<CameraBounds>,
+    /// Window focus changes, drained by the frame callback.
+    pub focus: FocusTracker,
+    /// The game's focus loss behavior, synced from the bridge by the frame callback.
+    pub focus_policy: FocusPolicy,
+    /// Suspend/resume, memory warnings and resizes, drained by the frame callback.
+    pub lifecycle: LifecycleTracker,
+    /// The frame being built by the frame callback, submitted after it returns.
+    /// The camera it keeps between frames is the clamped, rendered position.
+    pub frame: FrameSet,
+    /// Renderer viewport in logical pixels, mirrored so the frame callback
+    /// does not have to wait for the render thread to read it.
+    pub viewport_size: [f32; 2],
+    /// Display scale factor the renderer was last sized with.
+    pub scale_factor: f32,
     pub delta_time: f64,
     /// Response sender waiting for a frame capture result.
     pub pending_capture_tx: Option<crate::agent::ResponseSender>,
+    /// Keep the next frame capture in `kept_capture` (for replay thumbnails).
+    pub keep_capture: bool,
+    /// PNG of the frame captured for `keep_capture`, taken by the frame callback.
+    pub kept_capture: Option<Vec<u8>>,
+    /// Screen rect (x, y, w, h in logical pixels) of the text field the game
+    /// is typing into, synced from the bridge. While set, the IME is allowed
+    /// and its candidate window is placed next to this rect.
+    pub text_input: Option<[f32; 4]>,
 }
 
 impl RenderState {
@@ -38,13 +57,17 @@ impl RenderState {
             renderer: None,
             input: InputState::default(),
             touch: TouchState::default(),
-            sprite_commands: Vec::new(),
-            camera_x: 0.0,
-            camera_y: 0.0,
-            camera_zoom: 1.0,
-            camera_bounds: None,
+            focus: FocusTracker::default(),
+            focus_policy: FocusPolicy::default(),
+            lifecycle: LifecycleTracker::default(),
+            frame: FrameSet::default(),
+            viewport_size: [0.0, 0.0],
+            scale_factor: 1.0,
             delta_time: 0.0,
             pending_capture_tx: None,
+            keep_capture: false,
+            kept_capture: None,
+            text_input: None,
         }
     }
 }
@@ -55,6 +78,12 @@ pub struct DevConfig {
     pub title: String,
     pub width: u32,
     pub height: u32,
+    /// Requested point light capacity (see `Renderer::new`).
+    pub max_lights: usize,
+    /// Encode and present frames on a dedicated render thread.
+    pub render_thread: bool,
+    /// Report the left mouse button as a touch point instead of a mouse button.
+    pub emulate_touch: bool,
 }
 
 /// Callback invoked each frame to run the TS step function.
@@ -66,14 +95,21 @@ struct AppState {
     config: DevConfig,
     render_state: Rc<RefCell<RenderState>>,
     frame_callback: FrameCallback,
+    /// Created with the renderer; submits each frame set.
+    render_thread: Option<RenderThread>,
     last_frame: Instant,
     /// Display scale factor (e.g. 2.0 on Retina).
     scale_factor: f64,
+    /// Mouse-to-touch conversion, when `DevConfig::emulate_touch` is set.
+    touch_emulator: Option<TouchEmulator>,
+    /// `RenderState::text_input` as last applied to the window.
+    ime_area: Option<[f32; 4]>,
 }
 
 impl ApplicationHandler for AppState {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.window.is_some() {
+            self.render_state.borrow_mut().lifecycle.resume();
             return;
         }
 
@@ -92,9 +128,21 @@ impl ApplicationHandler for AppState {
 
         self.scale_factor = window.scale_factor();
 
-        match Renderer::new(window.clone()) {
+        match Renderer::new(window.clone(), self.config.max_lights) {
             Ok(renderer) => {
-                self.render_state.borrow_mut().renderer = Some(renderer);
+                let mut state = self.render_state.borrow_mut();
+                state.viewport_size = renderer.camera.viewport_size;
+                state.scale_factor = renderer.scale_factor;
+                // The initial size, so games can lay out from their resize handler
+                let [width, height] = state.viewport_size;
+                state.lifecycle.resize(width, height, renderer.scale_factor);
+                let shared = SharedRenderer::new(renderer);
+                state.renderer = Some(shared.clone());
+                self.render_thread = Some(if self.config.render_thread {
+                    RenderThread::spawn(shared)
+                } else {
+                    RenderThread::inline(shared)
+                });
             }
             Err(e) => {
                 eprintln!("Failed to initialize renderer: {e}");
@@ -107,6 +155,14 @@ impl ApplicationHandler for AppState {
         self.last_frame = Instant::now();
     }
 
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        self.render_state.borrow_mut().lifecycle.suspend();
+    }
+
+    fn memory_warning(&mut self, _event_loop: &ActiveEventLoop) {
+        self.render_state.borrow_mut().lifecycle.memory_warning();
+    }
+
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -120,8 +176,15 @@ impl ApplicationHandler for AppState {
 
             WindowEvent::Resized(new_size) => {
                 let mut state = self.render_state.borrow_mut();
-                if let Some(ref mut renderer) = state.renderer {
+                let resized = state.renderer.as_ref().map(|renderer| {
+                    let mut renderer = renderer.lock();
                     renderer.resize(new_size.width, new_size.height, self.scale_factor as f32);
+                    (renderer.camera.viewport_size, renderer.scale_factor)
+                });
+                if let Some((viewport_size, scale_factor)) = resized {
+                    state.viewport_size = viewport_size;
+                    state.scale_factor = scale_factor;
+                    state.lifecycle.resize(viewport_size[0], viewport_size[1], scale_factor);
                 }
             }
 
@@ -129,33 +192,99 @@ impl ApplicationHandler for AppState {
                 self.scale_factor = scale_factor;
             }
 
+            WindowEvent::Focused(focused) => {
+                let mut state = self.render_state.borrow_mut();
+                let policy = state.focus_policy;
+                state.focus.set_focused(focused, &policy);
+                if !focused {
+                    // Keys held at focus loss never get their release event
+                    state.input.release_all();
+                    if let Some(phase) = self.touch_emulator.as_mut().and_then(|e| e.cancel()) {
+                        let (x, y) = (state.input.mouse_x, state.input.mouse_y);
+                        let now = self.last_frame.elapsed().as_secs_f64();
+                        state.touch.touch_event(EMULATED_TOUCH_ID, x, y, phase, now);
+                    }
+                }
+            }
+
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
+                        physical_key,
                         logical_key,
+                        text,
                         state: key_state,
+                        repeat,
                         ..
                     },
                 ..
             } => {
                 let key_name = key_to_string(&logical_key);
+                let code = code_to_string(&physical_key);
                 let mut state = self.render_state.borrow_mut();
                 match key_state {
-                    ElementState::Pressed => state.input.key_down(&key_name),
-                    ElementState::Released => state.input.key_up(&key_name),
+                    ElementState::Pressed => {
+                        state.input.key_down(&key_name);
+                        state.input.push_event(InputEvent::KeyDown { key: key_name, code, repeat });
+                        if let Some(text) = text {
+                            state.input.text_input(&text);
+                        }
+                    }
+                    ElementState::Released => {
+                        state.input.key_up(&key_name);
+                        state.input.push_event(InputEvent::KeyUp { key: key_name, code });
+                    }
                 }
             }
 
+            WindowEvent::Ime(ime) => {
+                let mut state = self.render_state.borrow_mut();
+                match ime {
+                    Ime::Preedit(text, cursor) => state.input.push_event(InputEvent::composition(text, cursor)),
+                    Ime::Commit(text) => state.input.text_input(&text),
+                    // Drop any composition left on screen
+                    Ime::Disabled => state.input.push_event(InputEvent::composition(String::new(), None)),
+                    Ime::Enabled => {}
+                }
+            }
+
+            WindowEvent::MouseWheel { delta, .. } => {
+                // winit reports how far content moves; events report the scroll
+                let (dx, dy, lines) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (-x, -y, true),
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        let scale = self.scale_factor;
+                        ((-pos.x / scale) as f32, (-pos.y / scale) as f32, false)
+                    }
+                };
+                let mut state = self.render_state.borrow_mut();
+                state.input.push_event(InputEvent::Wheel { dx, dy, lines });
+            }
+
             WindowEvent::CursorMoved { position, .. } => {
                 // Convert from physical pixels to logical pixels
                 let logical_x = position.x as f32 / self.scale_factor as f32;
                 let logical_y = position.y as f32 / self.scale_factor as f32;
                 let mut state = self.render_state.borrow_mut();
                 state.input.mouse_move(logical_x, logical_y);
+                if let Some(phase) = self.touch_emulator.as_ref().and_then(|e| e.cursor_moved()) {
+                    let now = self.last_frame.elapsed().as_secs_f64();
+                    state.touch.touch_event(EMULATED_TOUCH_ID, logical_x, logical_y, phase, now);
+                }
             }
 
             WindowEvent::MouseInput { state: button_state, button, .. } => {
                 let mut state = self.render_state.borrow_mut();
+                if button == winit::event::MouseButton::Left
+                    && let Some(emulator) = self.touch_emulator.as_mut()
+                {
+                    if let Some(phase) = emulator.button(button_state == ElementState::Pressed) {
+                        let (x, y) = (state.input.mouse_x, state.input.mouse_y);
+                        let now = self.last_frame.elapsed().as_secs_f64();
+                        state.touch.touch_event(EMULATED_TOUCH_ID, x, y, phase, now);
+                    }
+                    return;
+                }
                 let button_id: u8 = match button {
                     winit::event::MouseButton::Left => 0,
                     winit::event::MouseButton::Right => 1,
@@ -222,50 +351,46 @@ impl ApplicationHandler for AppState {
                 }
 
                 // Clear per-frame input AFTER the callback has read it
-                {
+                let text_input = {
                     let mut state = self.render_state.borrow_mut();
                     state.input.begin_frame();
                     state.touch.begin_frame();
-                }
+                    state.text_input
+                };
+                self.apply_text_input(text_input);
 
-                // Transfer sprite commands and camera to renderer, then render
+                // Hand the frame to the renderer. With a render thread this returns
+                // as soon as the previous frame is done, so the next script frame
+                // runs while this one is encoded and presented.
                 {
                     let mut state = self.render_state.borrow_mut();
-                    // Extract values before borrowing renderer mutably
-                    let cam_x = state.camera_x;
-                    let cam_y = state.camera_y;
-                    let cam_zoom = state.camera_zoom;
-                    let cam_bounds = state.camera_bounds;
-                    let commands = std::mem::take(&mut state.sprite_commands);
-
-                    if let Some(ref mut renderer) = state.renderer {
-                        renderer.camera.x = cam_x;
-                        renderer.camera.y = cam_y;
-                        renderer.camera.zoom = cam_zoom;
-                        renderer.camera.bounds = cam_bounds;
-                        renderer.camera.clamp_to_bounds();
-                        renderer.frame_commands = commands;
-
-                        if let Err(e) = renderer.render_frame() {
-                            eprintln!("Render error: {e}");
+                    let viewport_size = state.viewport_size;
+                    state.frame.clamp_camera(viewport_size);
+                    let frame = state.frame.take();
+
+                    // Send capture result if a capture was completed
+                    if let Some(ref render_thread) = self.render_thread
+                        && let Some(png_bytes) = render_thread.submit(frame)
+                    {
+                        if let Some(tx) = state.pending_capture_tx.take() {
+                            let b64 = crate::agent::mcp::base64_encode(&png_bytes);
+                            let resp = crate::agent::InspectorResponse {
+                                status: 200,
+                                content_type: "image/png".into(),
+                                body: b64,
+                            };
+                            let _ = tx.send(resp);
                         }
-
-                        // Send capture result if a capture was completed
-                        if let Some(png_bytes) = renderer.capture_result.take() {
-                            if let Some(tx) = state.pending_capture_tx.take() {
-                                let b64 = crate::agent::mcp::base64_encode(&png_bytes);
-                                let resp = crate::agent::InspectorResponse {
-                                    status: 200,
-                                    content_type: "image/png".into(),
-                                    body: b64,
-                                };
-                                let _ = tx.send(resp);
-                            }
+                        if std::mem::take(&mut state.keep_capture) {
+                            state.kept_capture = Some(png_bytes);
                         }
                     }
                 }
 
-                if let Some(ref window) = self.window {
+                // While capped, about_to_wait schedules the next frame
+                if self.frame_interval().is_none()
+                    && let Some(ref window) = self.window
+                {
                     window.request_redraw();
                 }
             }
@@ -274,13 +399,61 @@ impl ApplicationHandler for AppState {
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(interval) = self.frame_interval() {
+            let next = self.last_frame + interval;
+            if Instant::now() < next {
+                event_loop.set_control_flow(ControlFlow::WaitUntil(next));
+                return;
+            }
+        }
+        event_loop.set_control_flow(ControlFlow::Wait);
         if let Some(ref window) = self.window {
             window.request_redraw();
         }
     }
 }
 
+impl AppState {
+    /// Minimum time between frames, if the focus policy caps the frame rate.
+    fn frame_interval(&self) -> Option<std::time::Duration> {
+        let state = self.render_state.borrow();
+        state.focus_policy.frame_interval(state.focus.is_focused())
+    }
+
+    /// Allow the IME while the game has a text field focused, and keep its
+    /// candidate window next to the field.
+    fn apply_text_input(&mut self, text_input: Option<[f32; 4]>) {
+        if text_input == self.ime_area {
+            return;
+        }
+        let Some(ref window) = self.window else {
+            return;
+        };
+        match text_input {
+            Some([x, y, w, h]) => {
+                if self.ime_area.is_none() {
+                    window.set_ime_allowed(true);
+                }
+                window.set_ime_cursor_area(
+                    winit::dpi::LogicalPosition::new(x, y),
+                    winit::dpi::LogicalSize::new(w, h),
+                );
+            }
+            None => window.set_ime_allowed(false),
+        }
+        self.ime_area = text_input;
+    }
+}
+
+/// Physical key name, as the DOM's `KeyboardEvent.code` ("KeyA", "Digit1").
+fn code_to_string(key: &PhysicalKey) -> String {
+    match key {
+        PhysicalKey::Code(code) => format!("{code:?}"),
+        PhysicalKey::Unidentified(_) => "Unidentified".to_string(),
+    }
+}
+
 /// Convert a winit logical key to a string name for the TS API.
 fn key_to_string(key: &Key) -> String {
     match key {
@@ -312,13 +485,17 @@ pub fn run_event_loop(
 ) -> Result<()> {
     let event_loop = EventLoop::new()?;
 
+    let touch_emulator = config.emulate_touch.then(TouchEmulator::default);
     let mut app = AppState {
         window: None,
         config,
         render_state,
         frame_callback,
+        render_thread: None,
         last_frame: Instant::now(),
         scale_factor: 1.0,
+        touch_emulator,
+        ime_area: None,
     };
 
     event_loop.run_app(&mut app)?;
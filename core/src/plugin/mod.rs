@@ -0,0 +1,361 @@
+//! Native plugins: engine extensions that live outside this crate.
+//!
+//! A [`Plugin`] can add deno ops to every script runtime, render passes drawn
+//! after the scene, CLI subcommands, remote save storage, and JSON functions
+//! callable from TypeScript through `callPlugin()`. Plugins are registered once at startup,
+//! before any runtime or renderer is created:
+//!
+//! - **Statically**: a crate implementing [`Plugin`] is compiled in (usually
+//!   behind a Cargo feature of the binary) and passed to [`register`].
+//! - **Dynamically**: a shared library exporting the stable C ABI in
+//!   [`native`] is loaded with `native::load` (feature `native-plugins`).
+//!   Native plugins provide JSON functions and CLI subcommands; deno ops,
+//!   render passes and save backends need the Rust API, whose types are not
+//!   ABI-stable.
+//!
+//! Static plugins that define ops must build against the same `deno_core`
+//! version as the engine.
+
+pub mod native;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use deno_core::{Extension, OpState};
+
+use crate::json::escape;
+use crate::saves::SaveBackend;
+
+/// An engine extension. Every method but [`Plugin::name`] has a no-op default.
+pub trait Plugin: Send + Sync {
+    /// Unique name, used by `callPlugin()` and in the `arcane dev` listing.
+    fn name(&self) -> &str;
+
+    fn version(&self) -> &str {
+        "0.0.0"
+    }
+
+    /// Extensions added to every script runtime. Called once per runtime, so
+    /// each call must build fresh extensions (e.g. `my_ext::init()`).
+    fn extensions(&self) -> Vec<Extension> {
+        Vec::new()
+    }
+
+    /// Put the state the plugin's ops expect into a new runtime's op state.
+    fn init_op_state(&self, _state: &mut OpState) {}
+
+    /// Passes drawn after the scene each frame, before post-processing.
+    /// Called once when the renderer is created.
+    #[cfg(feature = "renderer")]
+    fn render_passes(&self, _gpu: &crate::renderer::GpuContext) -> Vec<Box<dyn RenderPass>> {
+        Vec::new()
+    }
+
+    /// Remote save storage, e.g. Steam Cloud, that local saves can be synced
+    /// with (see [`crate::saves`]). Picked by name from TypeScript.
+    fn save_backends(&self) -> Vec<Arc<dyn SaveBackend>> {
+        Vec::new()
+    }
+
+    /// Subcommands run as `arcane <name> [args...]`.
+    fn commands(&self) -> Vec<PluginCommand> {
+        Vec::new()
+    }
+
+    /// Run a JSON function: `args` and the result are JSON text.
+    fn call(&self, function: &str, _args: &str) -> Result<String, String> {
+        Err(format!("plugin \"{}\" has no function \"{function}\"", self.name()))
+    }
+}
+
+/// Body of a [`PluginCommand`].
+pub type CommandFn = dyn Fn(&[String]) -> anyhow::Result<()> + Send + Sync;
+
+/// A CLI subcommand provided by a plugin.
+pub struct PluginCommand {
+    pub name: String,
+    /// One-line description for the plugin listing.
+    pub about: String,
+    /// Runs with the arguments after the subcommand name.
+    pub run: Box<CommandFn>,
+}
+
+/// Where a registered plugin came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginSource {
+    /// Compiled into the binary.
+    Static,
+    /// Loaded from a shared library.
+    Native(PathBuf),
+}
+
+/// A registered plugin, for listings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub source: PluginSource,
+    /// Names of the plugin's CLI subcommands.
+    pub commands: Vec<String>,
+}
+
+impl PluginInfo {
+    /// `{"name":..,"version":..,"source":"static"|"native","path":..|null,"commands":[..]}`.
+    pub fn to_json(&self) -> String {
+        let (source, path) = match &self.source {
+            PluginSource::Static => ("static", "null".to_string()),
+            PluginSource::Native(path) => ("native", json_string(&path.display().to_string())),
+        };
+        let commands: Vec<String> = self.commands.iter().map(|c| json_string(c)).collect();
+        format!(
+            "{{\"name\":{},\"version\":{},\"source\":\"{source}\",\"path\":{path},\"commands\":[{}]}}",
+            json_string(&self.name),
+            json_string(&self.version),
+            commands.join(",")
+        )
+    }
+}
+
+impl std::fmt::Display for PluginInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.version)?;
+        if let PluginSource::Native(path) = &self.source {
+            write!(f, " ({})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
+
+struct Entry {
+    plugin: Arc<dyn Plugin>,
+    source: PluginSource,
+}
+
+static REGISTRY: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn registry() -> MutexGuard<'static, Vec<Entry>> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Register a plugin compiled into the binary. Fails if the name is taken.
+pub fn register(plugin: Box<dyn Plugin>) -> Result<(), String> {
+    register_from(plugin.into(), PluginSource::Static)
+}
+
+pub(crate) fn register_from(plugin: Arc<dyn Plugin>, source: PluginSource) -> Result<(), String> {
+    let mut entries = registry();
+    if entries.iter().any(|e| e.plugin.name() == plugin.name()) {
+        return Err(format!("a plugin named \"{}\" is already registered", plugin.name()));
+    }
+    entries.push(Entry { plugin, source });
+    Ok(())
+}
+
+/// Registered plugins, in registration order.
+pub fn loaded() -> Vec<PluginInfo> {
+    let entries: Vec<_> = registry()
+        .iter()
+        .map(|e| (e.plugin.clone(), e.source.clone()))
+        .collect();
+    entries
+        .into_iter()
+        .map(|(plugin, source)| PluginInfo {
+            name: plugin.name().to_string(),
+            version: plugin.version().to_string(),
+            source,
+            commands: plugin.commands().into_iter().map(|c| c.name).collect(),
+        })
+        .collect()
+}
+
+/// Look up a plugin by name.
+pub fn get(name: &str) -> Option<Arc<dyn Plugin>> {
+    registry()
+        .iter()
+        .find(|e| e.plugin.name() == name)
+        .map(|e| e.plugin.clone())
+}
+
+/// The registered plugins, cloned out so plugin code never runs with the
+/// registry locked.
+fn plugins() -> Vec<Arc<dyn Plugin>> {
+    registry().iter().map(|e| e.plugin.clone()).collect()
+}
+
+/// Every plugin's runtime extensions (see [`Plugin::extensions`]).
+pub fn extensions() -> Vec<Extension> {
+    plugins().iter().flat_map(|p| p.extensions()).collect()
+}
+
+/// Let every plugin initialize a new runtime's op state.
+pub fn init_op_state(state: &mut OpState) {
+    for plugin in plugins() {
+        plugin.init_op_state(state);
+    }
+}
+
+/// Every plugin's render passes (see [`Plugin::render_passes`]).
+#[cfg(feature = "renderer")]
+pub fn render_passes(gpu: &crate::renderer::GpuContext) -> Vec<Box<dyn RenderPass>> {
+    plugins().iter().flat_map(|p| p.render_passes(gpu)).collect()
+}
+
+/// Names of every plugin's save backends, in registration order.
+pub fn save_backend_names() -> Vec<String> {
+    plugins()
+        .iter()
+        .flat_map(|p| p.save_backends())
+        .map(|b| b.name().to_string())
+        .collect()
+}
+
+/// The save backend called `name`, searching plugins in registration order.
+pub fn save_backend(name: &str) -> Option<Arc<dyn SaveBackend>> {
+    plugins().iter().flat_map(|p| p.save_backends()).find(|b| b.name() == name)
+}
+
+/// The subcommand called `name`, searching plugins in registration order.
+pub fn find_command(name: &str) -> Option<PluginCommand> {
+    plugins().iter().flat_map(|p| p.commands()).find(|c| c.name == name)
+}
+
+/// A render pass added by a plugin.
+#[cfg(feature = "renderer")]
+pub trait RenderPass: Send {
+    /// Record the pass. The target already holds the scene; draw on top of it
+    /// without clearing.
+    fn render(&mut self, ctx: &mut RenderPassContext<'_>);
+}
+
+/// What a [`RenderPass`] gets each frame.
+#[cfg(feature = "renderer")]
+pub struct RenderPassContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// The scene color target (the surface, or the post-process input).
+    pub target: &'a wgpu::TextureView,
+    pub format: wgpu::TextureFormat,
+    pub camera: &'a crate::renderer::Camera2D,
+    /// Seconds since start and since the last frame.
+    pub time: f32,
+    pub delta_time: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Echo(&'static str);
+
+    impl Plugin for Echo {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn version(&self) -> &str {
+            "1.2.0"
+        }
+
+        fn commands(&self) -> Vec<PluginCommand> {
+            vec![PluginCommand {
+                name: format!("{}-run", self.0),
+                about: "Count runs".into(),
+                run: Box::new(|args| {
+                    RUNS.fetch_add(args.len(), Ordering::SeqCst);
+                    Ok(())
+                }),
+            }]
+        }
+
+        fn call(&self, function: &str, args: &str) -> Result<String, String> {
+            match function {
+                "echo" => Ok(args.to_string()),
+                _ => Err(format!("no function \"{function}\"")),
+            }
+        }
+    }
+
+    struct EmptyBackend(&'static str);
+
+    impl SaveBackend for EmptyBackend {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn list(&self) -> Result<Vec<(String, u64)>, String> {
+            Ok(Vec::new())
+        }
+
+        fn read(&self, _key: &str) -> Result<Option<(String, u64)>, String> {
+            Ok(None)
+        }
+
+        fn write(&self, _key: &str, _value: &str, _modified: u64) -> Result<(), String> {
+            Err("read-only".into())
+        }
+
+        fn remove(&self, _key: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    struct Cloud;
+
+    impl Plugin for Cloud {
+        fn name(&self) -> &str {
+            "test-cloud"
+        }
+
+        fn save_backends(&self) -> Vec<Arc<dyn SaveBackend>> {
+            vec![Arc::new(EmptyBackend("test-cloud-saves"))]
+        }
+    }
+
+    // The registry is global: every test uses its own plugin names
+
+    #[test]
+    fn registers_and_rejects_duplicate_names() {
+        register(Box::new(Echo("test-dup"))).unwrap();
+        assert!(register(Box::new(Echo("test-dup"))).is_err());
+        let info = loaded().into_iter().find(|p| p.name == "test-dup").unwrap();
+        assert_eq!(info.version, "1.2.0");
+        assert_eq!(info.source, PluginSource::Static);
+        assert_eq!(info.commands, vec!["test-dup-run".to_string()]);
+        assert_eq!(
+            info.to_json(),
+            "{\"name\":\"test-dup\",\"version\":\"1.2.0\",\"source\":\"static\",\"path\":null,\"commands\":[\"test-dup-run\"]}"
+        );
+    }
+
+    #[test]
+    fn finds_commands_and_calls_functions() {
+        register(Box::new(Echo("test-call"))).unwrap();
+        let command = find_command("test-call-run").unwrap();
+        (command.run)(&["a".into(), "b".into()]).unwrap();
+        assert!(RUNS.load(Ordering::SeqCst) >= 2);
+        assert!(find_command("test-call-missing").is_none());
+
+        let plugin = get("test-call").unwrap();
+        assert_eq!(plugin.call("echo", "[1,2]"), Ok("[1,2]".to_string()));
+        assert!(plugin.call("nope", "{}").is_err());
+        assert!(get("test-call-missing").is_none());
+    }
+
+    #[test]
+    fn finds_save_backends_by_name() {
+        register(Box::new(Cloud)).unwrap();
+        assert!(save_backend_names().contains(&"test-cloud-saves".to_string()));
+        let backend = save_backend("test-cloud-saves").unwrap();
+        assert_eq!(backend.modified("slot").unwrap(), None);
+        assert!(backend.write("slot", "{}", 0).is_err());
+        assert!(save_backend("test-cloud-missing").is_none());
+    }
+}
@@ -0,0 +1,331 @@
+//! Stable C ABI for plugins loaded from shared libraries.
+//!
+//! A native plugin exports one function, `arcane_plugin_v1`, returning a
+//! pointer to a static [`ArcanePluginV1`] descriptor. In C:
+//!
+//! ```c
+//! typedef struct { const char *name; const char *about; } ArcaneCommandV1;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;  /* ARCANE_PLUGIN_ABI_VERSION = 1 */
+//!     const char *name;
+//!     const char *version;   /* may be NULL */
+//!     /* JSON function: 0 = success. *out is the JSON result, or the error
+//!        message on failure; freed with free_string. NULL if unsupported. */
+//!     int32_t (*call)(const char *function, const char *args_json, char **out);
+//!     void (*free_string)(char *s);
+//!     uint32_t command_count;
+//!     const ArcaneCommandV1 *commands;
+//!     /* Runs `arcane <name> argv...`; returns the exit code. */
+//!     int32_t (*run_command)(const char *name, int32_t argc, const char *const *argv);
+//! } ArcanePluginV1;
+//!
+//! const ArcanePluginV1 *arcane_plugin_v1(void);
+//! ```
+//!
+//! Strings are UTF-8 and NUL-terminated. The engine may call `call` from any
+//! thread, so it must be thread-safe. Libraries are never unloaded.
+
+use std::ffi::{CStr, CString, c_char};
+use std::path::PathBuf;
+
+use super::{Plugin, PluginCommand, PluginSource};
+
+/// Version of [`ArcanePluginV1`]; plugins built for another version are rejected.
+pub const ABI_VERSION: u32 = 1;
+
+/// Name of the function a native plugin exports.
+pub const ENTRY_SYMBOL: &str = "arcane_plugin_v1";
+
+/// Signature of [`ENTRY_SYMBOL`].
+pub type EntryFn = unsafe extern "C" fn() -> *const ArcanePluginV1;
+
+/// Plugin descriptor returned by the entry function. See the module docs.
+#[repr(C)]
+pub struct ArcanePluginV1 {
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub version: *const c_char,
+    pub call: Option<
+        unsafe extern "C" fn(function: *const c_char, args_json: *const c_char, out: *mut *mut c_char) -> i32,
+    >,
+    pub free_string: Option<unsafe extern "C" fn(s: *mut c_char)>,
+    pub command_count: u32,
+    pub commands: *const ArcaneCommandV1,
+    pub run_command:
+        Option<unsafe extern "C" fn(name: *const c_char, argc: i32, argv: *const *const c_char) -> i32>,
+}
+
+/// A subcommand in [`ArcanePluginV1::commands`].
+#[repr(C)]
+pub struct ArcaneCommandV1 {
+    pub name: *const c_char,
+    pub about: *const c_char,
+}
+
+type CallFn = unsafe extern "C" fn(*const c_char, *const c_char, *mut *mut c_char) -> i32;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+type RunFn = unsafe extern "C" fn(*const c_char, i32, *const *const c_char) -> i32;
+
+/// A [`Plugin`] backed by a C ABI descriptor.
+struct NativePlugin {
+    name: String,
+    version: String,
+    commands: Vec<(String, String)>,
+    call: Option<CallFn>,
+    free_string: Option<FreeFn>,
+    run_command: Option<RunFn>,
+    /// Keeps the library (and so the function pointers) loaded.
+    _library: Option<Box<dyn std::any::Any + Send + Sync>>,
+}
+
+/// Read a NUL-terminated string. `None` for NULL.
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string.
+unsafe fn read_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+    }
+}
+
+impl NativePlugin {
+    /// Copy what the engine needs out of a descriptor.
+    ///
+    /// # Safety
+    /// `desc` must be NULL or point to a valid descriptor whose pointers stay
+    /// valid as long as `library` is alive.
+    unsafe fn from_descriptor(
+        desc: *const ArcanePluginV1,
+        library: Option<Box<dyn std::any::Any + Send + Sync>>,
+    ) -> Result<Self, String> {
+        let Some(desc) = (unsafe { desc.as_ref() }) else {
+            return Err(format!("{ENTRY_SYMBOL}() returned NULL"));
+        };
+        if desc.abi_version != ABI_VERSION {
+            return Err(format!(
+                "plugin ABI version {} is not supported (expected {ABI_VERSION})",
+                desc.abi_version
+            ));
+        }
+        let name = unsafe { read_str(desc.name) }
+            .filter(|n| !n.is_empty())
+            .ok_or("plugin has no name")?;
+        let version = unsafe { read_str(desc.version) }.unwrap_or_else(|| "0.0.0".into());
+        let mut commands = Vec::new();
+        if !desc.commands.is_null() {
+            for i in 0..desc.command_count as usize {
+                let command = unsafe { &*desc.commands.add(i) };
+                let Some(command_name) = (unsafe { read_str(command.name) }) else {
+                    continue;
+                };
+                let about = unsafe { read_str(command.about) }.unwrap_or_default();
+                commands.push((command_name, about));
+            }
+        }
+        Ok(Self {
+            name,
+            version,
+            commands,
+            call: desc.call,
+            free_string: desc.free_string,
+            run_command: desc.run_command,
+            _library: library,
+        })
+    }
+
+    /// Take ownership of a string the plugin returned.
+    fn take_string(&self, ptr: *mut c_char) -> String {
+        let text = unsafe { read_str(ptr) }.unwrap_or_default();
+        if !ptr.is_null()
+            && let Some(free) = self.free_string
+        {
+            unsafe { free(ptr) };
+        }
+        text
+    }
+}
+
+impl Plugin for NativePlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn commands(&self) -> Vec<PluginCommand> {
+        let Some(run) = self.run_command else {
+            return Vec::new();
+        };
+        self.commands
+            .iter()
+            .map(|(name, about)| {
+                let command = name.clone();
+                PluginCommand {
+                    name: name.clone(),
+                    about: about.clone(),
+                    run: Box::new(move |args| {
+                        let c_name = CString::new(command.as_str())?;
+                        let c_args = args
+                            .iter()
+                            .map(|a| CString::new(a.as_str()))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let argv: Vec<*const c_char> = c_args.iter().map(|a| a.as_ptr()).collect();
+                        let code = unsafe { run(c_name.as_ptr(), argv.len() as i32, argv.as_ptr()) };
+                        if code != 0 {
+                            anyhow::bail!("{command} exited with code {code}");
+                        }
+                        Ok(())
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    fn call(&self, function: &str, args: &str) -> Result<String, String> {
+        let Some(call) = self.call else {
+            return Err(format!("plugin \"{}\" has no functions", self.name));
+        };
+        let c_function = CString::new(function).map_err(|e| e.to_string())?;
+        let c_args = CString::new(args).map_err(|e| e.to_string())?;
+        let mut out: *mut c_char = std::ptr::null_mut();
+        let status = unsafe { call(c_function.as_ptr(), c_args.as_ptr(), &mut out) };
+        let text = self.take_string(out);
+        match status {
+            0 if text.is_empty() => Ok("null".to_string()),
+            0 => Ok(text),
+            _ if text.is_empty() => Err(format!("{}.{function} failed ({status})", self.name)),
+            _ => Err(text),
+        }
+    }
+}
+
+/// Load a native plugin library and register it. Returns the plugin name.
+#[cfg(feature = "native-plugins")]
+pub fn load(path: &std::path::Path) -> Result<String, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+    // SAFETY: loading runs the library's initializers; native plugins are
+    // trusted code chosen by the project, like any other dependency
+    let library = unsafe { libloading::Library::new(path) }.map_err(|e| fail(&e))?;
+    let desc = unsafe {
+        let entry = library
+            .get::<EntryFn>(ENTRY_SYMBOL.as_bytes())
+            .map_err(|e| fail(&e))?;
+        entry()
+    };
+    let plugin = unsafe { NativePlugin::from_descriptor(desc, Some(Box::new(library))) }
+        .map_err(|e| fail(&e))?;
+    let name = plugin.name.clone();
+    super::register_from(std::sync::Arc::new(plugin), PluginSource::Native(path.to_path_buf()))
+        .map_err(|e| fail(&e))?;
+    Ok(name)
+}
+
+/// Without the `native-plugins` feature, loading always fails.
+#[cfg(not(feature = "native-plugins"))]
+pub fn load(path: &std::path::Path) -> Result<String, String> {
+    Err(format!(
+        "{}: this build cannot load native plugins (enable the `native-plugins` feature)",
+        path.display()
+    ))
+}
+
+/// Register a descriptor that is linked into the binary rather than loaded
+/// from a library (C or C++ plugins built into a custom engine binary).
+///
+/// # Safety
+/// `desc` must be NULL or point to a valid descriptor that lives for the rest
+/// of the program.
+pub unsafe fn register_descriptor(desc: *const ArcanePluginV1, origin: PathBuf) -> Result<String, String> {
+    let plugin = unsafe { NativePlugin::from_descriptor(desc, None) }?;
+    let name = plugin.name.clone();
+    super::register_from(std::sync::Arc::new(plugin), PluginSource::Native(origin))?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn call(function: *const c_char, args: *const c_char, out: *mut *mut c_char) -> i32 {
+        let function = unsafe { CStr::from_ptr(function) }.to_str().unwrap();
+        let args = unsafe { CStr::from_ptr(args) }.to_str().unwrap();
+        let (status, text) = match function {
+            "echo" => (0, args.to_string()),
+            _ => (1, format!("unknown function {function}")),
+        };
+        unsafe { *out = CString::new(text).unwrap().into_raw() };
+        status
+    }
+
+    unsafe extern "C" fn free_string(s: *mut c_char) {
+        drop(unsafe { CString::from_raw(s) });
+    }
+
+    unsafe extern "C" fn run_command(_name: *const c_char, argc: i32, _argv: *const *const c_char) -> i32 {
+        // Fail when called without arguments
+        if argc > 0 { 0 } else { 2 }
+    }
+
+    struct Shared<T>(T);
+    // SAFETY: only points at static strings and functions
+    unsafe impl<T> Sync for Shared<T> {}
+
+    static COMMANDS: Shared<[ArcaneCommandV1; 1]> = Shared([ArcaneCommandV1 {
+        name: c"native-test-cmd".as_ptr(),
+        about: c"Test command".as_ptr(),
+    }]);
+
+    static DESCRIPTOR: Shared<ArcanePluginV1> = Shared(ArcanePluginV1 {
+        abi_version: ABI_VERSION,
+        name: c"native-test".as_ptr(),
+        version: c"0.3.1".as_ptr(),
+        call: Some(call),
+        free_string: Some(free_string),
+        command_count: 1,
+        commands: COMMANDS.0.as_ptr(),
+        run_command: Some(run_command),
+    });
+
+    #[test]
+    fn calls_functions_and_commands_through_the_abi() {
+        let name = unsafe { register_descriptor(&DESCRIPTOR.0, PathBuf::from("builtin")) }.unwrap();
+        assert_eq!(name, "native-test");
+        let info = super::super::loaded()
+            .into_iter()
+            .find(|p| p.name == "native-test")
+            .unwrap();
+        assert_eq!(info.version, "0.3.1");
+        assert_eq!(info.source, PluginSource::Native(PathBuf::from("builtin")));
+
+        let plugin = super::super::get("native-test").unwrap();
+        assert_eq!(plugin.call("echo", "{\"a\":1}"), Ok("{\"a\":1}".to_string()));
+        assert_eq!(plugin.call("boom", "{}"), Err("unknown function boom".to_string()));
+
+        let command = super::super::find_command("native-test-cmd").unwrap();
+        assert_eq!(command.about, "Test command");
+        assert!((command.run)(&["x".into()]).is_ok());
+        assert!((command.run)(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_other_abi_versions_and_null() {
+        let desc = ArcanePluginV1 {
+            abi_version: ABI_VERSION + 1,
+            name: c"native-future".as_ptr(),
+            version: std::ptr::null(),
+            call: None,
+            free_string: None,
+            command_count: 0,
+            commands: std::ptr::null(),
+            run_command: None,
+        };
+        let err = unsafe { register_descriptor(&desc, PathBuf::new()) }.unwrap_err();
+        assert!(err.contains("ABI version"), "{err}");
+        assert!(unsafe { register_descriptor(std::ptr::null(), PathBuf::new()) }.is_err());
+    }
+}
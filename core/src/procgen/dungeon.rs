@@ -0,0 +1,648 @@
+//! Classic roguelike dungeon generators: BSP rooms + corridors, cellular
+//! automata caves, and drunkard's walk.
+//!
+//! All generators are seeded and deterministic: the same seed and config
+//! always produce the same grid. Output is a flat row-major tile grid plus
+//! structured metadata (room rects, door positions, spawn candidates).
+//!
+//! Tile values: 0 = wall, 1 = floor, 2 = door.
+
+pub const TILE_WALL: u8 = 0;
+pub const TILE_FLOOR: u8 = 1;
+pub const TILE_DOOR: u8 = 2;
+
+/// Axis-aligned room rectangle in tile coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Room {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Room {
+    /// Center tile of the room.
+    pub fn center(&self) -> (u32, u32) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    /// True if the tile (x, y) lies inside the room.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+
+    /// True if the two rooms share at least one tile.
+    pub fn intersects(&self, other: &Room) -> bool {
+        self.x < other.x + other.w
+            && other.x < self.x + self.w
+            && self.y < other.y + other.h
+            && other.y < self.y + self.h
+    }
+}
+
+/// A generated dungeon: tile grid plus metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dungeon {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major tiles, `width * height` entries. See TILE_* constants.
+    pub tiles: Vec<u8>,
+    /// Room rectangles (BSP only; caves and walks have no rooms).
+    pub rooms: Vec<Room>,
+    /// Door tiles where a corridor enters a room.
+    pub doors: Vec<(u32, u32)>,
+    /// Suggested spawn positions (room centers or open floor tiles).
+    pub spawns: Vec<(u32, u32)>,
+}
+
+impl Dungeon {
+    /// Create a dungeon filled entirely with walls.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![TILE_WALL; width as usize * height as usize],
+            rooms: Vec::new(),
+            doors: Vec::new(),
+            spawns: Vec::new(),
+        }
+    }
+
+    /// Get the tile at (x, y). Out-of-bounds reads return a wall.
+    pub fn get(&self, x: i32, y: i32) -> u8 {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return TILE_WALL;
+        }
+        self.tiles[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Set the tile at (x, y). Out-of-bounds writes are ignored.
+    pub fn set(&mut self, x: u32, y: u32, tile: u8) {
+        if x < self.width && y < self.height {
+            self.tiles[y as usize * self.width as usize + x as usize] = tile;
+        }
+    }
+
+    /// True if the tile is walkable (floor or door).
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        self.get(x, y) != TILE_WALL
+    }
+
+    /// Number of walkable tiles.
+    pub fn walkable_count(&self) -> usize {
+        self.tiles.iter().filter(|&&t| t != TILE_WALL).count()
+    }
+
+    /// Serialize to JSON:
+    /// `{"width":W,"height":H,"tiles":[...],"rooms":[[x,y,w,h],...],"doors":[[x,y],...],"spawns":[[x,y],...]}`
+    pub fn to_json(&self) -> String {
+        let tiles: Vec<String> = self.tiles.iter().map(|t| t.to_string()).collect();
+        let rooms: Vec<String> = self
+            .rooms
+            .iter()
+            .map(|r| format!("[{},{},{},{}]", r.x, r.y, r.w, r.h))
+            .collect();
+        let doors: Vec<String> = self.doors.iter().map(|(x, y)| format!("[{x},{y}]")).collect();
+        let spawns: Vec<String> = self.spawns.iter().map(|(x, y)| format!("[{x},{y}]")).collect();
+        format!(
+            "{{\"width\":{},\"height\":{},\"tiles\":[{}],\"rooms\":[{}],\"doors\":[{}],\"spawns\":[{}]}}",
+            self.width,
+            self.height,
+            tiles.join(","),
+            rooms.join(","),
+            doors.join(","),
+            spawns.join(","),
+        )
+    }
+}
+
+/// Simple xorshift32 PRNG so generation is reproducible across platforms.
+struct Rng(u32);
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        // Scramble the seed so small consecutive seeds diverge quickly
+        Self(seed.wrapping_mul(2654435761).max(1))
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut s = self.0;
+        s ^= s << 13;
+        s ^= s >> 17;
+        s ^= s << 5;
+        self.0 = s;
+        s
+    }
+
+    /// Uniform float in [0, 1).
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform integer in [min, max] (inclusive). Returns min if max < min.
+    fn range(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+        min + self.next_u32() % (max - min + 1)
+    }
+}
+
+// ============================================================================
+// BSP rooms + corridors
+// ============================================================================
+
+/// Parameters for BSP dungeon generation.
+#[derive(Debug, Clone, Copy)]
+pub struct BspConfig {
+    /// Minimum room width/height in tiles.
+    pub min_room_size: u32,
+    /// Maximum recursion depth (2^depth leaves at most).
+    pub max_depth: u32,
+}
+
+impl Default for BspConfig {
+    fn default() -> Self {
+        Self {
+            min_room_size: 4,
+            max_depth: 4,
+        }
+    }
+}
+
+/// Generate rooms by recursively splitting the map, then connect sibling
+/// partitions with L-shaped corridors. Every room is reachable.
+pub fn generate_bsp(width: u32, height: u32, seed: u32, config: &BspConfig) -> Dungeon {
+    let mut dungeon = Dungeon::new(width, height);
+    if width < 3 || height < 3 {
+        return dungeon;
+    }
+    let mut rng = Rng::new(seed);
+    let min_room = config.min_room_size.max(1);
+
+    // Leave a 1-tile wall border around the map
+    let root = Room { x: 1, y: 1, w: width - 2, h: height - 2 };
+    split_and_carve(&mut dungeon, &mut rng, root, 0, config.max_depth, min_room);
+
+    mark_doors(&mut dungeon);
+    dungeon.spawns = dungeon.rooms.iter().map(|r| r.center()).collect();
+    dungeon
+}
+
+/// Recursively split `area`, carving a room in each leaf.
+/// Returns the index of one room in this subtree (used to connect siblings).
+fn split_and_carve(
+    dungeon: &mut Dungeon,
+    rng: &mut Rng,
+    area: Room,
+    depth: u32,
+    max_depth: u32,
+    min_room: u32,
+) -> Option<usize> {
+    // A partition needs room for the room itself plus a 1-tile wall on each side
+    let min_leaf = min_room + 2;
+    let can_split_h = area.h >= min_leaf * 2;
+    let can_split_v = area.w >= min_leaf * 2;
+
+    if depth < max_depth && (can_split_h || can_split_v) {
+        // Prefer splitting across the longer axis to avoid long thin partitions
+        let split_vertical = if can_split_h && can_split_v {
+            if area.w as f32 > area.h as f32 * 1.25 {
+                true
+            } else if area.h as f32 > area.w as f32 * 1.25 {
+                false
+            } else {
+                rng.next_u32() & 1 == 0
+            }
+        } else {
+            can_split_v
+        };
+
+        let (a, b) = if split_vertical {
+            let cut = rng.range(min_leaf, area.w - min_leaf);
+            (
+                Room { x: area.x, y: area.y, w: cut, h: area.h },
+                Room { x: area.x + cut, y: area.y, w: area.w - cut, h: area.h },
+            )
+        } else {
+            let cut = rng.range(min_leaf, area.h - min_leaf);
+            (
+                Room { x: area.x, y: area.y, w: area.w, h: cut },
+                Room { x: area.x, y: area.y + cut, w: area.w, h: area.h - cut },
+            )
+        };
+
+        let left = split_and_carve(dungeon, rng, a, depth + 1, max_depth, min_room);
+        let right = split_and_carve(dungeon, rng, b, depth + 1, max_depth, min_room);
+        if let (Some(l), Some(r)) = (left, right) {
+            let from = dungeon.rooms[l].center();
+            let to = dungeon.rooms[r].center();
+            carve_corridor(dungeon, rng, from, to);
+        }
+        return left.or(right);
+    }
+
+    // Leaf: carve a room inset by one wall tile on every side
+    if area.w < min_leaf || area.h < min_leaf {
+        return None;
+    }
+    let max_w = area.w - 2;
+    let max_h = area.h - 2;
+    let w = rng.range(min_room, max_w);
+    let h = rng.range(min_room, max_h);
+    let x = area.x + 1 + rng.range(0, max_w - w);
+    let y = area.y + 1 + rng.range(0, max_h - h);
+    let room = Room { x, y, w, h };
+    for ty in room.y..room.y + room.h {
+        for tx in room.x..room.x + room.w {
+            dungeon.set(tx, ty, TILE_FLOOR);
+        }
+    }
+    dungeon.rooms.push(room);
+    Some(dungeon.rooms.len() - 1)
+}
+
+/// Carve an L-shaped corridor between two points. The bend direction is random.
+fn carve_corridor(dungeon: &mut Dungeon, rng: &mut Rng, from: (u32, u32), to: (u32, u32)) {
+    let (x0, y0) = from;
+    let (x1, y1) = to;
+    let horizontal_first = rng.next_u32() & 1 == 0;
+    let corner = if horizontal_first { (x1, y0) } else { (x0, y1) };
+    carve_line(dungeon, from, corner);
+    carve_line(dungeon, corner, (x1, y1));
+}
+
+/// Carve a straight horizontal or vertical line of floor.
+fn carve_line(dungeon: &mut Dungeon, from: (u32, u32), to: (u32, u32)) {
+    let (x0, x1) = (from.0.min(to.0), from.0.max(to.0));
+    let (y0, y1) = (from.1.min(to.1), from.1.max(to.1));
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            dungeon.set(x, y, TILE_FLOOR);
+        }
+    }
+}
+
+/// Mark corridor tiles directly outside a room's edge as doors.
+fn mark_doors(dungeon: &mut Dungeon) {
+    let rooms = dungeon.rooms.clone();
+    for room in &rooms {
+        let x0 = room.x as i32 - 1;
+        let y0 = room.y as i32 - 1;
+        let x1 = (room.x + room.w) as i32;
+        let y1 = (room.y + room.h) as i32;
+        let mut ring = Vec::new();
+        for x in room.x as i32..x1 {
+            ring.push((x, y0));
+            ring.push((x, y1));
+        }
+        for y in room.y as i32..y1 {
+            ring.push((x0, y));
+            ring.push((x1, y));
+        }
+        for (x, y) in ring {
+            if dungeon.get(x, y) != TILE_FLOOR {
+                continue;
+            }
+            let (ux, uy) = (x as u32, y as u32);
+            if rooms.iter().any(|r| r.contains(ux, uy)) {
+                continue;
+            }
+            dungeon.set(ux, uy, TILE_DOOR);
+            dungeon.doors.push((ux, uy));
+        }
+    }
+}
+
+// ============================================================================
+// Cellular automata caves
+// ============================================================================
+
+/// Parameters for cellular automata cave generation.
+#[derive(Debug, Clone, Copy)]
+pub struct CaveConfig {
+    /// Initial probability that an interior tile is a wall (0-1).
+    pub fill_percent: f32,
+    /// Number of smoothing passes.
+    pub iterations: u32,
+    /// Maximum number of spawn candidates to report.
+    pub max_spawns: u32,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        Self {
+            fill_percent: 0.45,
+            iterations: 5,
+            max_spawns: 16,
+        }
+    }
+}
+
+/// Generate organic caves by smoothing random noise with the 4-5 rule.
+/// Only the largest connected cave is kept, so every floor tile is reachable.
+pub fn generate_caves(width: u32, height: u32, seed: u32, config: &CaveConfig) -> Dungeon {
+    let mut dungeon = Dungeon::new(width, height);
+    if width < 3 || height < 3 {
+        return dungeon;
+    }
+    let mut rng = Rng::new(seed);
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            if rng.next_f32() >= config.fill_percent {
+                dungeon.set(x, y, TILE_FLOOR);
+            }
+        }
+    }
+
+    for _ in 0..config.iterations {
+        let prev = dungeon.clone();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let walls = count_wall_neighbors(&prev, x as i32, y as i32);
+                let is_wall = prev.get(x as i32, y as i32) == TILE_WALL;
+                let wall = walls >= 5 || (is_wall && walls >= 4);
+                dungeon.set(x, y, if wall { TILE_WALL } else { TILE_FLOOR });
+            }
+        }
+    }
+
+    keep_largest_region(&mut dungeon);
+    dungeon.spawns = pick_open_spawns(&dungeon, &mut rng, config.max_spawns);
+    dungeon
+}
+
+/// Count walls among the 8 neighbors (out of bounds counts as wall).
+fn count_wall_neighbors(dungeon: &Dungeon, x: i32, y: i32) -> u32 {
+    let mut count = 0;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if (dx != 0 || dy != 0) && dungeon.get(x + dx, y + dy) == TILE_WALL {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Flood fill every walkable region and wall off all but the largest.
+fn keep_largest_region(dungeon: &mut Dungeon) {
+    let w = dungeon.width as i32;
+    let h = dungeon.height as i32;
+    let mut region = vec![usize::MAX; dungeon.tiles.len()];
+    let mut sizes: Vec<usize> = Vec::new();
+
+    for start in 0..dungeon.tiles.len() {
+        if dungeon.tiles[start] == TILE_WALL || region[start] != usize::MAX {
+            continue;
+        }
+        let id = sizes.len();
+        let mut size = 0;
+        let mut stack = vec![start];
+        region[start] = id;
+        while let Some(idx) = stack.pop() {
+            size += 1;
+            let x = idx as i32 % w;
+            let y = idx as i32 / w;
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let nx = x + dx;
+                let ny = y + dy;
+                if nx < 0 || ny < 0 || nx >= w || ny >= h {
+                    continue;
+                }
+                let n = (ny * w + nx) as usize;
+                if dungeon.tiles[n] != TILE_WALL && region[n] == usize::MAX {
+                    region[n] = id;
+                    stack.push(n);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    let Some(largest) = sizes
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, &s)| (s, std::cmp::Reverse(i)))
+        .map(|(i, _)| i)
+    else {
+        return;
+    };
+    for (idx, tile) in dungeon.tiles.iter_mut().enumerate() {
+        if *tile != TILE_WALL && region[idx] != largest {
+            *tile = TILE_WALL;
+        }
+    }
+}
+
+/// Pick up to `max` floor tiles whose 8 neighbors are all floor, spread
+/// across the map by a seeded shuffle.
+fn pick_open_spawns(dungeon: &Dungeon, rng: &mut Rng, max: u32) -> Vec<(u32, u32)> {
+    let mut open = Vec::new();
+    for y in 0..dungeon.height as i32 {
+        for x in 0..dungeon.width as i32 {
+            if dungeon.is_walkable(x, y) && count_wall_neighbors(dungeon, x, y) == 0 {
+                open.push((x as u32, y as u32));
+            }
+        }
+    }
+    // Fisher-Yates shuffle, then take the first `max`
+    for i in (1..open.len()).rev() {
+        let j = rng.range(0, i as u32) as usize;
+        open.swap(i, j);
+    }
+    open.truncate(max as usize);
+    open
+}
+
+// ============================================================================
+// Drunkard's walk
+// ============================================================================
+
+/// Parameters for drunkard's walk generation.
+#[derive(Debug, Clone, Copy)]
+pub struct DrunkardConfig {
+    /// Fraction of interior tiles to carve into floor (0-1).
+    pub floor_percent: f32,
+    /// Maximum number of spawn candidates to report.
+    pub max_spawns: u32,
+}
+
+impl Default for DrunkardConfig {
+    fn default() -> Self {
+        Self {
+            floor_percent: 0.4,
+            max_spawns: 16,
+        }
+    }
+}
+
+/// Carve winding tunnels with a random walker starting at the map center.
+/// The walk is continuous, so every floor tile is reachable.
+pub fn generate_drunkard_walk(width: u32, height: u32, seed: u32, config: &DrunkardConfig) -> Dungeon {
+    let mut dungeon = Dungeon::new(width, height);
+    if width < 3 || height < 3 {
+        return dungeon;
+    }
+    let mut rng = Rng::new(seed);
+
+    let interior = (width - 2) as f32 * (height - 2) as f32;
+    let target = ((interior * config.floor_percent.clamp(0.0, 1.0)) as usize).max(1);
+    // Guard against unreachable targets on degenerate configs
+    let max_steps = target * 200;
+
+    let mut x = width / 2;
+    let mut y = height / 2;
+    dungeon.set(x, y, TILE_FLOOR);
+    let mut carved = 1;
+    let mut steps = 0;
+
+    while carved < target && steps < max_steps {
+        steps += 1;
+        match rng.next_u32() % 4 {
+            0 if x > 1 => x -= 1,
+            1 if x < width - 2 => x += 1,
+            2 if y > 1 => y -= 1,
+            3 if y < height - 2 => y += 1,
+            _ => continue,
+        }
+        if dungeon.get(x as i32, y as i32) == TILE_WALL {
+            dungeon.set(x, y, TILE_FLOOR);
+            carved += 1;
+        }
+    }
+
+    dungeon.spawns = pick_open_spawns(&dungeon, &mut rng, config.max_spawns);
+    dungeon
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Count walkable tiles reachable from the first walkable tile.
+    fn reachable_count(d: &Dungeon) -> usize {
+        let Some(start) = d.tiles.iter().position(|&t| t != TILE_WALL) else {
+            return 0;
+        };
+        let w = d.width as i32;
+        let mut seen = vec![false; d.tiles.len()];
+        let mut stack = vec![start];
+        seen[start] = true;
+        let mut count = 0;
+        while let Some(idx) = stack.pop() {
+            count += 1;
+            let x = idx as i32 % w;
+            let y = idx as i32 / w;
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if d.is_walkable(nx, ny) {
+                    let n = (ny * w + nx) as usize;
+                    if !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn border_is_wall(d: &Dungeon) -> bool {
+        let (w, h) = (d.width as i32, d.height as i32);
+        (0..w).all(|x| d.get(x, 0) == TILE_WALL && d.get(x, h - 1) == TILE_WALL)
+            && (0..h).all(|y| d.get(0, y) == TILE_WALL && d.get(w - 1, y) == TILE_WALL)
+    }
+
+    #[test]
+    fn test_bsp_is_deterministic() {
+        let a = generate_bsp(60, 40, 7, &BspConfig::default());
+        let b = generate_bsp(60, 40, 7, &BspConfig::default());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_bsp_different_seeds_differ() {
+        let a = generate_bsp(60, 40, 1, &BspConfig::default());
+        let b = generate_bsp(60, 40, 2, &BspConfig::default());
+        assert_ne!(a.tiles, b.tiles);
+    }
+
+    #[test]
+    fn test_bsp_rooms_valid_and_connected() {
+        let config = BspConfig { min_room_size: 4, max_depth: 4 };
+        let d = generate_bsp(80, 50, 42, &config);
+        assert!(d.rooms.len() >= 2, "expected several rooms, got {}", d.rooms.len());
+        for (i, room) in d.rooms.iter().enumerate() {
+            assert!(room.w >= 4 && room.h >= 4);
+            assert!(room.x >= 1 && room.y >= 1);
+            assert!(room.x + room.w <= 79 && room.y + room.h <= 49);
+            for other in &d.rooms[i + 1..] {
+                assert!(!room.intersects(other), "rooms overlap: {room:?} {other:?}");
+            }
+        }
+        assert!(border_is_wall(&d));
+        assert_eq!(reachable_count(&d), d.walkable_count(), "all floor should be connected");
+        assert_eq!(d.spawns.len(), d.rooms.len());
+    }
+
+    #[test]
+    fn test_bsp_doors_border_rooms() {
+        let d = generate_bsp(80, 50, 3, &BspConfig::default());
+        assert!(!d.doors.is_empty());
+        for &(x, y) in &d.doors {
+            assert_eq!(d.get(x as i32, y as i32), TILE_DOOR);
+            assert!(!d.rooms.iter().any(|r| r.contains(x, y)));
+        }
+    }
+
+    #[test]
+    fn test_bsp_tiny_map_is_all_wall() {
+        let d = generate_bsp(2, 2, 1, &BspConfig::default());
+        assert_eq!(d.walkable_count(), 0);
+        assert!(d.rooms.is_empty());
+    }
+
+    #[test]
+    fn test_caves_connected_with_wall_border() {
+        let d = generate_caves(64, 48, 99, &CaveConfig::default());
+        assert!(d.walkable_count() > 0);
+        assert!(border_is_wall(&d));
+        assert_eq!(reachable_count(&d), d.walkable_count());
+        assert!(d.spawns.len() <= 16);
+        for &(x, y) in &d.spawns {
+            assert!(d.is_walkable(x as i32, y as i32));
+        }
+    }
+
+    #[test]
+    fn test_caves_deterministic() {
+        let a = generate_caves(40, 30, 5, &CaveConfig::default());
+        let b = generate_caves(40, 30, 5, &CaveConfig::default());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_drunkard_reaches_target_floor() {
+        let config = DrunkardConfig { floor_percent: 0.3, max_spawns: 8 };
+        let d = generate_drunkard_walk(50, 40, 11, &config);
+        let target = (48.0 * 38.0 * 0.3) as usize;
+        assert!(d.walkable_count() >= target);
+        assert!(border_is_wall(&d));
+        assert_eq!(reachable_count(&d), d.walkable_count());
+        assert!(d.spawns.len() <= 8);
+    }
+
+    #[test]
+    fn test_to_json_shape() {
+        let mut d = Dungeon::new(2, 1);
+        d.set(1, 0, TILE_FLOOR);
+        d.rooms.push(Room { x: 1, y: 0, w: 1, h: 1 });
+        d.spawns.push((1, 0));
+        assert_eq!(
+            d.to_json(),
+            "{\"width\":2,\"height\":1,\"tiles\":[0,1],\"rooms\":[[1,0,1,1]],\"doors\":[],\"spawns\":[[1,0]]}"
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+
+use super::SpriteCommand;
+
+/// How an animation behaves after its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Wrap back to the first frame.
+    Loop,
+    /// Play backwards to the first frame, then forwards again.
+    PingPong,
+    /// Stop on the last frame and report completion.
+    Once,
+}
+
+impl LoopMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "loop" => Some(Self::Loop),
+            "pingpong" => Some(Self::PingPong),
+            "once" => Some(Self::Once),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationEventKind {
+    /// A looping or ping-pong animation started a new cycle.
+    Looped,
+    /// A one-shot animation reached its last frame.
+    Finished,
+}
+
+impl AnimationEventKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Looped => "loop",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationEvent {
+    pub id: u32,
+    pub kind: AnimationEventKind,
+}
+
+/// A frame animation over UV rects of one texture, with its playback state.
+#[derive(Debug, Clone)]
+pub struct SpriteAnimation {
+    pub texture_id: u32,
+    /// UV rect (x, y, w, h) of each frame.
+    pub frames: Vec<[f32; 4]>,
+    pub fps: f32,
+    /// Seconds each frame is shown. Empty: every frame lasts `1 / fps`.
+    pub durations: Vec<f32>,
+    pub mode: LoopMode,
+    elapsed: f32,
+    /// Frame steps taken since the start (not wrapped).
+    step: u64,
+    finished: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(texture_id: u32, frames: Vec<[f32; 4]>, fps: f32, mode: LoopMode) -> Self {
+        Self {
+            texture_id,
+            frames,
+            fps,
+            durations: Vec::new(),
+            mode,
+            elapsed: 0.0,
+            step: 0,
+            finished: false,
+        }
+    }
+
+    /// Number of steps in one full cycle (there and back for ping-pong).
+    fn cycle_len(&self) -> u64 {
+        let n = self.frames.len() as u64;
+        match self.mode {
+            LoopMode::PingPong if n > 1 => 2 * n - 2,
+            _ => n,
+        }
+    }
+
+    /// Show each frame for its own duration (seconds, one per frame) instead
+    /// of a constant rate.
+    pub fn with_durations(mut self, durations: Vec<f32>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Index into `frames` shown at the current step.
+    pub fn frame(&self) -> usize {
+        self.frame_at(self.step)
+    }
+
+    fn frame_at(&self, step: u64) -> usize {
+        let n = self.frames.len() as u64;
+        let frame = match self.mode {
+            LoopMode::Loop => step % n,
+            LoopMode::PingPong => {
+                let p = step % self.cycle_len();
+                if p < n { p } else { self.cycle_len() - p }
+            }
+            LoopMode::Once => step.min(n - 1),
+        };
+        frame as usize
+    }
+
+    /// Steps taken after `elapsed` seconds.
+    fn step_at(&self, elapsed: f32) -> u64 {
+        if self.durations.is_empty() {
+            return (elapsed * self.fps) as u64;
+        }
+        let cycle = self.cycle_len();
+        let duration = |step: u64| self.durations[self.frame_at(step)];
+        let cycle_time: f32 = (0..cycle).map(duration).sum();
+        if cycle_time <= 0.0 {
+            return 0;
+        }
+        let cycles = (elapsed / cycle_time) as u64;
+        let mut rest = elapsed - cycles as f32 * cycle_time;
+        let mut step = cycles * cycle;
+        for s in 0..cycle {
+            if rest < duration(s) {
+                break;
+            }
+            rest -= duration(s);
+            step += 1;
+        }
+        step
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.step = 0;
+        self.finished = false;
+    }
+
+    /// Advance by `dt` seconds. Returns the event this update produced, if
+    /// any; several cycles wrapped in one update report a single `Looped`.
+    pub fn advance(&mut self, dt: f32) -> Option<AnimationEventKind> {
+        if self.finished || (self.durations.is_empty() && self.fps <= 0.0) {
+            return None;
+        }
+        self.elapsed += dt.max(0.0);
+        let step = self.step_at(self.elapsed);
+        let previous = self.step;
+        self.step = step;
+        match self.mode {
+            LoopMode::Once => {
+                if step + 1 >= self.frames.len() as u64 {
+                    self.finished = true;
+                    return Some(AnimationEventKind::Finished);
+                }
+                None
+            }
+            LoopMode::Loop | LoopMode::PingPong => {
+                let cycle = self.cycle_len();
+                (step / cycle > previous / cycle).then_some(AnimationEventKind::Looped)
+            }
+        }
+    }
+
+    /// Sprite command for the current frame. Position, size, tint and the
+    /// rest come from `base`; texture and UVs from the animation.
+    pub fn sprite_command(&self, base: SpriteCommand) -> SpriteCommand {
+        let [uv_x, uv_y, uv_w, uv_h] = self.frames[self.frame()];
+        SpriteCommand {
+            texture_id: self.texture_id,
+            uv_x,
+            uv_y,
+            uv_w,
+            uv_h,
+            ..base
+        }
+    }
+}
+
+/// Manages sprite animations by ID and advances them each frame.
+#[derive(Clone)]
+pub struct AnimationStore {
+    animations: HashMap<u32, SpriteAnimation>,
+    next_id: u32,
+    events: Vec<AnimationEvent>,
+}
+
+impl Default for AnimationStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnimationStore {
+    pub fn new() -> Self {
+        Self {
+            animations: HashMap::new(),
+            next_id: 1,
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an animation, playing from its first frame. Returns `None` when
+    /// `frames` is empty.
+    pub fn create(
+        &mut self,
+        texture_id: u32,
+        frames: Vec<[f32; 4]>,
+        fps: f32,
+        mode: LoopMode,
+    ) -> Option<u32> {
+        if frames.is_empty() {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.animations
+            .insert(id, SpriteAnimation::new(texture_id, frames, fps, mode));
+        Some(id)
+    }
+
+    /// Add an animation whose frames each last their own number of seconds.
+    /// Returns `None` when `frames` is empty or `durations` does not have one
+    /// entry per frame.
+    pub fn create_timed(
+        &mut self,
+        texture_id: u32,
+        frames: Vec<[f32; 4]>,
+        durations: Vec<f32>,
+        mode: LoopMode,
+    ) -> Option<u32> {
+        if frames.is_empty() || durations.len() != frames.len() {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let animation = SpriteAnimation::new(texture_id, frames, 0.0, mode).with_durations(durations);
+        self.animations.insert(id, animation);
+        Some(id)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&SpriteAnimation> {
+        self.animations.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut SpriteAnimation> {
+        self.animations.get_mut(&id)
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.animations.remove(&id);
+    }
+
+    /// Advance every animation by `dt` seconds. Events from the previous
+    /// update that were not taken are dropped.
+    pub fn update(&mut self, dt: f32) {
+        self.events.clear();
+        let mut ids: Vec<u32> = self.animations.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            if let Some(kind) = self.animations.get_mut(&id).and_then(|a| a.advance(dt)) {
+                self.events.push(AnimationEvent { id, kind });
+            }
+        }
+    }
+
+    /// Take the events produced by the last update.
+    pub fn take_events(&mut self) -> Vec<AnimationEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Serialize events for TS: `[{"id":1,"event":"finished"}, ...]`.
+pub fn events_json(events: &[AnimationEvent]) -> String {
+    let items: Vec<String> = events
+        .iter()
+        .map(|e| format!("{{\"id\":{},\"event\":\"{}\"}}", e.id, e.kind.as_str()))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(n: usize) -> Vec<[f32; 4]> {
+        (0..n)
+            .map(|i| [i as f32 / n as f32, 0.0, 1.0 / n as f32, 1.0])
+            .collect()
+    }
+
+    fn frames_over(anim: &mut SpriteAnimation, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                anim.advance(0.1001);
+                anim.frame()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_loop_wraps_and_reports_each_cycle() {
+        let mut store = AnimationStore::new();
+        let id = store.create(1, strip(3), 10.0, LoopMode::Loop).unwrap();
+        let mut looped = 0;
+        let mut frames = Vec::new();
+        for _ in 0..7 {
+            store.update(0.1001);
+            looped += store.take_events().len();
+            frames.push(store.get(id).unwrap().frame());
+        }
+        assert_eq!(frames, vec![1, 2, 0, 1, 2, 0, 1]);
+        assert_eq!(looped, 2);
+    }
+
+    #[test]
+    fn test_ping_pong_reverses_without_repeating_ends() {
+        let mut anim = SpriteAnimation::new(1, strip(4), 10.0, LoopMode::PingPong);
+        assert_eq!(frames_over(&mut anim, 8), vec![1, 2, 3, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_once_stops_on_last_frame_and_finishes_once() {
+        let mut store = AnimationStore::new();
+        let id = store.create(1, strip(3), 10.0, LoopMode::Once).unwrap();
+        store.update(0.15);
+        assert!(store.take_events().is_empty());
+        store.update(0.1);
+        assert_eq!(
+            store.take_events(),
+            vec![AnimationEvent {
+                id,
+                kind: AnimationEventKind::Finished
+            }]
+        );
+        store.update(1.0);
+        assert!(store.take_events().is_empty());
+        assert_eq!(store.get(id).unwrap().frame(), 2);
+
+        store.get_mut(id).unwrap().restart();
+        assert_eq!(store.get(id).unwrap().frame(), 0);
+        assert!(!store.get(id).unwrap().is_finished());
+    }
+
+    #[test]
+    fn test_per_frame_durations() {
+        let mut store = AnimationStore::new();
+        let id = store
+            .create_timed(1, strip(3), vec![0.1, 0.3, 0.1], LoopMode::Loop)
+            .unwrap();
+        let mut frames = Vec::new();
+        for _ in 0..12 {
+            store.update(0.0501);
+            frames.push(store.get(id).unwrap().frame());
+        }
+        assert_eq!(frames, vec![0, 1, 1, 1, 1, 1, 1, 2, 2, 0, 0, 1]);
+        assert_eq!(store.create_timed(1, strip(3), vec![0.1], LoopMode::Loop), None);
+
+        let mut once = SpriteAnimation::new(1, strip(3), 0.0, LoopMode::Once).with_durations(vec![0.1, 0.2, 0.1]);
+        assert_eq!(once.advance(0.25), None);
+        assert_eq!(once.frame(), 1);
+        assert_eq!(once.advance(0.1), Some(AnimationEventKind::Finished));
+        assert_eq!(once.frame(), 2);
+    }
+
+    #[test]
+    fn test_sprite_command_uses_current_frame_uv() {
+        let mut anim = SpriteAnimation::new(7, strip(4), 10.0, LoopMode::Loop);
+        anim.advance(0.25);
+        let base = SpriteCommand {
+            texture_id: 0,
+            x: 5.0,
+            y: 6.0,
+            w: 16.0,
+            h: 16.0,
+            layer: 2,
+            uv_x: 0.0,
+            uv_y: 0.0,
+            uv_w: 1.0,
+            uv_h: 1.0,
+            tint_r: 1.0,
+            tint_g: 1.0,
+            tint_b: 1.0,
+            tint_a: 1.0,
+            rotation: 0.0,
+            origin_x: 0.5,
+            origin_y: 0.5,
+            flip_x: true,
+            flip_y: false,
+            opacity: 1.0,
+            blend_mode: 0,
+            shader_id: 0,
+            nine_slice: None,
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
+        };
+        let cmd = anim.sprite_command(base);
+        assert_eq!(cmd.texture_id, 7);
+        assert_eq!((cmd.uv_x, cmd.uv_w), (0.5, 0.25));
+        assert_eq!((cmd.x, cmd.layer), (5.0, 2));
+        assert!(cmd.flip_x);
+    }
+
+    #[test]
+    fn test_rejects_empty_frames_and_serializes_events() {
+        let mut store = AnimationStore::new();
+        assert_eq!(store.create(1, Vec::new(), 10.0, LoopMode::Loop), None);
+        let events = [
+            AnimationEvent {
+                id: 1,
+                kind: AnimationEventKind::Looped,
+            },
+            AnimationEvent {
+                id: 2,
+                kind: AnimationEventKind::Finished,
+            },
+        ];
+        assert_eq!(
+            events_json(&events),
+            "[{\"id\":1,\"event\":\"loop\"},{\"id\":2,\"event\":\"finished\"}]"
+        );
+        assert_eq!(LoopMode::parse("pingpong"), Some(LoopMode::PingPong));
+        assert_eq!(LoopMode::parse("bounce"), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! Sprite sheets exported from Aseprite (File > Export Sprite Sheet, with
+//! "JSON Data" checked).
+//!
+//! Both the "Hash" and "Array" frame layouts are read:
+//!
+//! ```json
+//! {"frames":{"hero 0.aseprite":{"frame":{"x":0,"y":0,"w":16,"h":16},"duration":100,...},...},
+//!  "meta":{"image":"hero.png","size":{"w":64,"h":16},
+//!          "frameTags":[{"name":"walk","from":0,"to":3,"direction":"pingpong","repeat":"2"}],
+//!          "slices":[{"name":"hitbox","keys":[{"frame":0,"bounds":{"x":4,"y":2,"w":8,"h":14}}]}]}}
+//! ```
+//!
+//! Each tag becomes an engine animation (see `AnimationStore::create_timed`)
+//! that keeps Aseprite's per-frame durations and playback direction.
+//! `.aseprite` files themselves are binary; the sheet is read from the JSON
+//! export next to them.
+
+use crate::json::{escape, JsonValue};
+
+use super::animation::LoopMode;
+
+/// One frame of the sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsepriteFrame {
+    pub name: String,
+    /// Pixel rect (x, y, w, h) in the sheet image.
+    pub rect: [u32; 4],
+    /// Size of the untrimmed sprite.
+    pub source_size: (u32, u32),
+    /// Where a trimmed frame sits inside the untrimmed sprite.
+    pub offset: (u32, u32),
+    /// Seconds the frame is shown.
+    pub duration: f32,
+}
+
+/// Playback direction of a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+impl TagDirection {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "forward" => Some(Self::Forward),
+            "reverse" => Some(Self::Reverse),
+            "pingpong" => Some(Self::PingPong),
+            "pingpong_reverse" => Some(Self::PingPongReverse),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Reverse => "reverse",
+            Self::PingPong => "pingpong",
+            Self::PingPongReverse => "pingpong_reverse",
+        }
+    }
+}
+
+/// A named frame range (Aseprite "frame tag").
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsepriteTag {
+    pub name: String,
+    /// First and last frame, inclusive.
+    pub from: usize,
+    pub to: usize,
+    pub direction: TagDirection,
+    /// Number of passes to play, 0 = forever.
+    pub repeat: u32,
+}
+
+/// A slice's shape from one frame on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceKey {
+    pub frame: usize,
+    /// Pixel rect (x, y, w, h) relative to the frame.
+    pub bounds: [i32; 4],
+    /// Nine-slice center rect, relative to `bounds`.
+    pub center: Option<[i32; 4]>,
+    /// Pivot point, relative to `bounds`.
+    pub pivot: Option<(i32, i32)>,
+}
+
+/// A named rect (hitboxes, attachment points, nine-slice borders).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsepriteSlice {
+    pub name: String,
+    /// User data text, if any.
+    pub data: Option<String>,
+    /// Keys in frame order.
+    pub keys: Vec<SliceKey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsepriteSheet {
+    /// Sheet image, relative to the JSON file.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<AsepriteFrame>,
+    pub tags: Vec<AsepriteTag>,
+    pub slices: Vec<AsepriteSlice>,
+}
+
+fn uint(value: &JsonValue, key: &str) -> Result<u32, String> {
+    value
+        .get(key)
+        .and_then(JsonValue::as_f64)
+        .filter(|n| *n >= 0.0)
+        .map(|n| n as u32)
+        .ok_or_else(|| format!("\"{key}\" must be a non-negative number"))
+}
+
+fn rect(value: &JsonValue, key: &str) -> Result<[i32; 4], String> {
+    let r = value.get(key).ok_or_else(|| format!("missing \"{key}\""))?;
+    let int = |k: &str| r.get(k).and_then(JsonValue::as_f64).map(|n| n as i32);
+    match (int("x"), int("y"), int("w"), int("h")) {
+        (Some(x), Some(y), Some(w), Some(h)) => Ok([x, y, w, h]),
+        _ => Err(format!("\"{key}\" must have numeric x, y, w and h")),
+    }
+}
+
+fn parse_frame(name: &str, value: &JsonValue) -> Result<AsepriteFrame, String> {
+    let context = |e: String| format!("frame \"{name}\": {e}");
+    let [x, y, w, h] = rect(value, "frame").map_err(context)?;
+    if value.get("rotated").and_then(JsonValue::as_bool) == Some(true) {
+        return Err(context("rotated frames are not supported".into()));
+    }
+    let source_size = match value.get("sourceSize") {
+        Some(size) => (uint(size, "w").map_err(context)?, uint(size, "h").map_err(context)?),
+        None => (w as u32, h as u32),
+    };
+    let offset = match value.get("spriteSourceSize") {
+        Some(_) => {
+            let [ox, oy, ..] = rect(value, "spriteSourceSize").map_err(context)?;
+            (ox.max(0) as u32, oy.max(0) as u32)
+        }
+        None => (0, 0),
+    };
+    Ok(AsepriteFrame {
+        name: name.to_string(),
+        rect: [x.max(0) as u32, y.max(0) as u32, w.max(0) as u32, h.max(0) as u32],
+        source_size,
+        offset,
+        duration: value.f32_or("duration", 100.0) / 1000.0,
+    })
+}
+
+impl AsepriteSheet {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let root = JsonValue::parse(json)?;
+        let frames = match root.get("frames") {
+            Some(JsonValue::Object(members)) => members
+                .iter()
+                .map(|(name, value)| parse_frame(name, value))
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let name = value.str_of("filename").map_or_else(|| i.to_string(), str::to_string);
+                    parse_frame(&name, value)
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err("\"frames\" must be an object or an array".into()),
+        };
+        if frames.is_empty() {
+            return Err("the sheet has no frames".into());
+        }
+
+        let meta = root.get("meta").ok_or("missing \"meta\"")?;
+        let size = meta.get("size").ok_or("missing \"meta.size\"")?;
+        let (width, height) = (uint(size, "w")?, uint(size, "h")?);
+        if width == 0 || height == 0 {
+            return Err("\"meta.size\" must be at least 1x1".into());
+        }
+
+        let mut tags = Vec::new();
+        for tag in meta.get("frameTags").and_then(JsonValue::as_array).unwrap_or_default() {
+            let name = tag.str_of("name").unwrap_or_default().to_string();
+            let context = |e: String| format!("tag \"{name}\": {e}");
+            let (from, to) = (uint(tag, "from").map_err(context)? as usize, uint(tag, "to").map_err(context)? as usize);
+            if from > to || to >= frames.len() {
+                return Err(context(format!("frames {from}..{to} are outside the sheet's {} frames", frames.len())));
+            }
+            let direction = tag.str_of("direction").unwrap_or("forward");
+            let direction = TagDirection::parse(direction)
+                .ok_or_else(|| context(format!("unknown direction \"{direction}\"")))?;
+            // Aseprite writes the repeat count as a string; absent means forever
+            let repeat = match tag.get("repeat") {
+                Some(JsonValue::String(s)) => s.trim().parse().map_err(|_| context(format!("bad repeat \"{s}\"")))?,
+                Some(JsonValue::Number(n)) => n.max(0.0) as u32,
+                _ => 0,
+            };
+            tags.push(AsepriteTag { name, from, to, direction, repeat });
+        }
+
+        let mut slices = Vec::new();
+        for slice in meta.get("slices").and_then(JsonValue::as_array).unwrap_or_default() {
+            let name = slice.str_of("name").unwrap_or_default().to_string();
+            let context = |e: String| format!("slice \"{name}\": {e}");
+            let mut keys = Vec::new();
+            for key in slice.get("keys").and_then(JsonValue::as_array).unwrap_or_default() {
+                let pivot = key.get("pivot").map(|p| (p.f32_or("x", 0.0) as i32, p.f32_or("y", 0.0) as i32));
+                keys.push(SliceKey {
+                    frame: uint(key, "frame").map_err(context)? as usize,
+                    bounds: rect(key, "bounds").map_err(context)?,
+                    center: key.get("center").map(|_| rect(key, "center")).transpose().map_err(context)?,
+                    pivot,
+                });
+            }
+            keys.sort_by_key(|k| k.frame);
+            let data = slice.str_of("data").map(str::to_string);
+            slices.push(AsepriteSlice { name, data, keys });
+        }
+
+        Ok(Self {
+            image: meta.str_of("image").unwrap_or_default().to_string(),
+            width,
+            height,
+            frames,
+            tags,
+            slices,
+        })
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&AsepriteTag> {
+        self.tags.iter().find(|t| t.name == name)
+    }
+
+    /// Normalized UV rect of a frame.
+    pub fn uv(&self, frame: usize) -> [f32; 4] {
+        let [x, y, w, h] = self.frames[frame].rect;
+        let (sw, sh) = (self.width as f32, self.height as f32);
+        [x as f32 / sw, y as f32 / sh, w as f32 / sw, h as f32 / sh]
+    }
+
+    /// Frames a tag plays, in order, and how the engine animation continues
+    /// after them. Tags that repeat forever loop (or ping-pong); tags with a
+    /// repeat count have every pass spelled out and play once.
+    pub fn tag_sequence(&self, tag: &AsepriteTag) -> (Vec<usize>, LoopMode) {
+        let forward: Vec<usize> = (tag.from..=tag.to).collect();
+        let backward: Vec<usize> = forward.iter().rev().copied().collect();
+        let (first, second, ping_pong) = match tag.direction {
+            TagDirection::Forward => (&forward, &forward, false),
+            TagDirection::Reverse => (&backward, &backward, false),
+            TagDirection::PingPong => (&forward, &backward, true),
+            TagDirection::PingPongReverse => (&backward, &forward, true),
+        };
+        if tag.repeat == 0 {
+            let mode = if ping_pong { LoopMode::PingPong } else { LoopMode::Loop };
+            return (first.clone(), mode);
+        }
+        let mut frames = first.clone();
+        for pass in 1..tag.repeat {
+            let next = if pass % 2 == 1 { second } else { first };
+            // A ping-pong turn does not show the end frame twice
+            let skip = usize::from(ping_pong && next.len() > 1);
+            frames.extend(&next[skip..]);
+        }
+        (frames, LoopMode::Once)
+    }
+
+    /// UV rects, durations and loop mode for an engine animation of `tag`,
+    /// or of every frame in order (looping) when `tag` is `None`.
+    pub fn animation(&self, tag: Option<&AsepriteTag>) -> (Vec<[f32; 4]>, Vec<f32>, LoopMode) {
+        let (sequence, mode) = match tag {
+            Some(tag) => self.tag_sequence(tag),
+            None => ((0..self.frames.len()).collect(), LoopMode::Loop),
+        };
+        let uvs = sequence.iter().map(|&i| self.uv(i)).collect();
+        let durations = sequence.iter().map(|&i| self.frames[i].duration).collect();
+        (uvs, durations, mode)
+    }
+
+    /// Sheet layout for TS. Rects are in pixels and durations in milliseconds,
+    /// as in the export.
+    pub fn to_json(&self) -> String {
+        let frames: Vec<String> = self
+            .frames
+            .iter()
+            .map(|f| {
+                let [x, y, w, h] = f.rect;
+                format!(
+                    "{{\"name\":\"{}\",\"x\":{x},\"y\":{y},\"w\":{w},\"h\":{h},\"offsetX\":{},\"offsetY\":{},\"sourceW\":{},\"sourceH\":{},\"duration\":{}}}",
+                    escape(&f.name),
+                    f.offset.0,
+                    f.offset.1,
+                    f.source_size.0,
+                    f.source_size.1,
+                    (f.duration * 1000.0).round()
+                )
+            })
+            .collect();
+        let tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|t| {
+                format!(
+                    "{{\"name\":\"{}\",\"from\":{},\"to\":{},\"direction\":\"{}\",\"repeat\":{}}}",
+                    escape(&t.name),
+                    t.from,
+                    t.to,
+                    t.direction.as_str(),
+                    t.repeat
+                )
+            })
+            .collect();
+        let rect_json = |[x, y, w, h]: [i32; 4]| format!("{{\"x\":{x},\"y\":{y},\"w\":{w},\"h\":{h}}}");
+        let slices: Vec<String> = self
+            .slices
+            .iter()
+            .map(|s| {
+                let keys: Vec<String> = s
+                    .keys
+                    .iter()
+                    .map(|k| {
+                        let mut key = format!("{{\"frame\":{},\"bounds\":{}", k.frame, rect_json(k.bounds));
+                        if let Some(center) = k.center {
+                            key.push_str(&format!(",\"center\":{}", rect_json(center)));
+                        }
+                        if let Some((x, y)) = k.pivot {
+                            key.push_str(&format!(",\"pivot\":{{\"x\":{x},\"y\":{y}}}"));
+                        }
+                        key.push('}');
+                        key
+                    })
+                    .collect();
+                let data = s.data.as_ref().map(|d| format!(",\"data\":\"{}\"", escape(d))).unwrap_or_default();
+                format!("{{\"name\":\"{}\"{data},\"keys\":[{}]}}", escape(&s.name), keys.join(","))
+            })
+            .collect();
+        format!(
+            "{{\"width\":{},\"height\":{},\"frames\":[{}],\"tags\":[{}],\"slices\":[{}]}}",
+            self.width,
+            self.height,
+            frames.join(","),
+            tags.join(","),
+            slices.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_SHEET: &str = r##"{
+        "frames": {
+            "hero 0.aseprite": { "frame": {"x":0,"y":0,"w":16,"h":16}, "rotated": false, "trimmed": false,
+                "spriteSourceSize": {"x":0,"y":0,"w":16,"h":16}, "sourceSize": {"w":16,"h":16}, "duration": 100 },
+            "hero 1.aseprite": { "frame": {"x":16,"y":0,"w":16,"h":16}, "duration": 200 },
+            "hero 2.aseprite": { "frame": {"x":32,"y":0,"w":16,"h":16}, "duration": 100 },
+            "hero 3.aseprite": { "frame": {"x":48,"y":0,"w":12,"h":14},
+                "spriteSourceSize": {"x":2,"y":1,"w":12,"h":14}, "sourceSize": {"w":16,"h":16}, "duration": 50 }
+        },
+        "meta": {
+            "app": "https://www.aseprite.org/", "image": "hero.png", "size": {"w":64,"h":16},
+            "frameTags": [
+                { "name": "idle", "from": 0, "to": 1, "direction": "forward", "color": "#000000ff" },
+                { "name": "walk", "from": 1, "to": 3, "direction": "pingpong", "repeat": "2" },
+                { "name": "back", "from": 0, "to": 2, "direction": "reverse" }
+            ],
+            "slices": [
+                { "name": "hitbox", "color": "#0000ffff", "data": "solid", "keys": [
+                    { "frame": 2, "bounds": {"x":4,"y":4,"w":8,"h":12}, "pivot": {"x":4,"y":12} },
+                    { "frame": 0, "bounds": {"x":3,"y":2,"w":10,"h":14}, "center": {"x":1,"y":1,"w":8,"h":12} }
+                ] }
+            ]
+        }
+    }"##;
+
+    #[test]
+    fn test_parses_hash_layout_tags_and_slices() {
+        let sheet = AsepriteSheet::parse(HASH_SHEET).unwrap();
+        assert_eq!(sheet.image, "hero.png");
+        assert_eq!((sheet.width, sheet.height), (64, 16));
+        assert_eq!(sheet.frames.len(), 4);
+        assert_eq!(sheet.frames[1].duration, 0.2);
+        assert_eq!(sheet.frames[3].offset, (2, 1));
+        assert_eq!(sheet.frames[3].source_size, (16, 16));
+        assert_eq!(sheet.uv(1), [0.25, 0.0, 0.25, 1.0]);
+
+        let walk = sheet.tag("walk").unwrap();
+        assert_eq!((walk.from, walk.to, walk.direction, walk.repeat), (1, 3, TagDirection::PingPong, 2));
+
+        let hitbox = &sheet.slices[0];
+        assert_eq!(hitbox.data.as_deref(), Some("solid"));
+        assert_eq!(hitbox.keys.iter().map(|k| k.frame).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(hitbox.keys[0].center, Some([1, 1, 8, 12]));
+        assert_eq!(hitbox.keys[1].pivot, Some((4, 12)));
+    }
+
+    #[test]
+    fn test_parses_array_layout() {
+        let json = r#"{"frames":[
+            {"filename":"a","frame":{"x":0,"y":0,"w":8,"h":8},"duration":80},
+            {"filename":"b","frame":{"x":8,"y":0,"w":8,"h":8}}],
+            "meta":{"image":"a.png","size":{"w":16,"h":8}}}"#;
+        let sheet = AsepriteSheet::parse(json).unwrap();
+        assert_eq!(sheet.frames[0].name, "a");
+        assert_eq!(sheet.frames[1].duration, 0.1);
+        assert!(sheet.tags.is_empty());
+        let (uvs, durations, mode) = sheet.animation(None);
+        assert_eq!(uvs.len(), 2);
+        assert_eq!(durations, vec![0.08, 0.1]);
+        assert_eq!(mode, LoopMode::Loop);
+    }
+
+    #[test]
+    fn test_tag_sequences_follow_direction_and_repeat() {
+        let sheet = AsepriteSheet::parse(HASH_SHEET).unwrap();
+        assert_eq!(sheet.tag_sequence(sheet.tag("idle").unwrap()), (vec![0, 1], LoopMode::Loop));
+        assert_eq!(sheet.tag_sequence(sheet.tag("back").unwrap()), (vec![2, 1, 0], LoopMode::Loop));
+        // Two ping-pong passes: there and back, then stop
+        assert_eq!(sheet.tag_sequence(sheet.tag("walk").unwrap()), (vec![1, 2, 3, 2, 1], LoopMode::Once));
+
+        let bounce = AsepriteTag {
+            name: "bounce".into(),
+            from: 0,
+            to: 1,
+            direction: TagDirection::PingPongReverse,
+            repeat: 0,
+        };
+        assert_eq!(sheet.tag_sequence(&bounce), (vec![1, 0], LoopMode::PingPong));
+        let twice = AsepriteTag { direction: TagDirection::Forward, repeat: 2, ..bounce };
+        assert_eq!(sheet.tag_sequence(&twice), (vec![0, 1, 0, 1], LoopMode::Once));
+
+        let (_, durations, _) = sheet.animation(sheet.tag("walk"));
+        assert_eq!(durations, vec![0.2, 0.1, 0.05, 0.1, 0.2]);
+    }
+
+    #[test]
+    fn test_rejects_bad_tags_and_round_trips_json() {
+        let bad = HASH_SHEET.replace("\"to\": 3", "\"to\": 9");
+        assert!(AsepriteSheet::parse(&bad).unwrap_err().contains("tag \"walk\""));
+        let bad = HASH_SHEET.replace("\"reverse\"", "\"sideways\"");
+        assert!(AsepriteSheet::parse(&bad).unwrap_err().contains("sideways"));
+        assert!(AsepriteSheet::parse("{\"frames\":{},\"meta\":{\"size\":{\"w\":1,\"h\":1}}}").is_err());
+
+        let sheet = AsepriteSheet::parse(HASH_SHEET).unwrap();
+        let json = JsonValue::parse(&sheet.to_json()).unwrap();
+        let frames = json.get("frames").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(frames[3].f32_or("offsetX", -1.0), 2.0);
+        assert_eq!(frames[1].f32_or("duration", 0.0), 200.0);
+        let tags = json.get("tags").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(tags[1].str_of("direction"), Some("pingpong"));
+        let slices = json.get("slices").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(slices[0].str_of("data"), Some("solid"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Background fill and letterbox bars.
+//!
+//! Both are plain sprite commands added to the frame after the camera is
+//! clamped: the fill covers the screen below every layer, in place of the flat
+//! clear color, and the bars cover everything outside the camera bounds (the
+//! area shown when the view is larger than the bounds) in their own color.
+//! Gradients are baked into a texture by the bridge, so a fill is always a
+//! texture, stretched over the screen or tiled from its top-left corner.
+
+use super::{Camera2D, SpriteCommand};
+
+/// Layer of the background fill: below everything else.
+pub const BACKGROUND_LAYER: i32 = i32::MIN;
+/// Layer of the letterbox bars: above the scene, below the error overlay.
+pub const LETTERBOX_LAYER: i32 = i32::MAX - 2;
+/// Most tiles drawn for a tiled fill; tiles beyond it are left out.
+const MAX_TILES: usize = 4096;
+/// Rows (or columns) of a baked gradient texture.
+pub const GRADIENT_STEPS: u32 = 256;
+
+/// A texture drawn behind everything each frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundFill {
+    pub texture_id: u32,
+    /// Tile size in screen pixels; `None` stretches the texture over the screen.
+    pub tile: Option<[f32; 2]>,
+}
+
+/// Color drawn outside the camera bounds, with the white texture it is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    pub color: [f32; 4],
+    pub texture_id: u32,
+}
+
+/// Pixels of a two-color gradient: `GRADIENT_STEPS`×1 when horizontal (left
+/// to right), 1×`GRADIENT_STEPS` when vertical (top to bottom). Colors are
+/// RGBA in 0.0-1.0. Returns (pixels, width, height).
+pub fn gradient_pixels(from: [f32; 4], to: [f32; 4], horizontal: bool) -> (Vec<u8>, u32, u32) {
+    let mut pixels = Vec::with_capacity(GRADIENT_STEPS as usize * 4);
+    for i in 0..GRADIENT_STEPS {
+        let t = i as f32 / (GRADIENT_STEPS - 1) as f32;
+        for c in 0..4 {
+            let v = from[c] + (to[c] - from[c]) * t;
+            pixels.push((v.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    if horizontal {
+        (pixels, GRADIENT_STEPS, 1)
+    } else {
+        (pixels, 1, GRADIENT_STEPS)
+    }
+}
+
+/// Sprite commands for the fill and the letterbox bars as seen by `camera`.
+pub fn fill_commands(
+    fill: Option<BackgroundFill>,
+    letterbox: Option<Letterbox>,
+    camera: &Camera2D,
+) -> Vec<SpriteCommand> {
+    let mut commands = Vec::new();
+    if let Some(fill) = fill {
+        background(&mut commands, fill, camera);
+    }
+    if let Some(letterbox) = letterbox {
+        bars(&mut commands, letterbox, camera);
+    }
+    commands
+}
+
+fn background(commands: &mut Vec<SpriteCommand>, fill: BackgroundFill, camera: &Camera2D) {
+    let [screen_w, screen_h] = camera.viewport_size;
+    let Some([tile_w, tile_h]) = fill.tile.filter(|[w, h]| *w > 0.0 && *h > 0.0) else {
+        commands.push(screen_quad(fill.texture_id, camera, 0.0, 0.0, screen_w, screen_h, [1.0; 4], BACKGROUND_LAYER));
+        return;
+    };
+    let columns = (screen_w / tile_w).ceil() as usize;
+    let rows = (screen_h / tile_h).ceil() as usize;
+    for i in 0..(columns * rows).min(MAX_TILES) {
+        let (column, row) = (i % columns, i / columns);
+        let (x, y) = (column as f32 * tile_w, row as f32 * tile_h);
+        commands.push(screen_quad(fill.texture_id, camera, x, y, tile_w, tile_h, [1.0; 4], BACKGROUND_LAYER));
+    }
+}
+
+fn bars(commands: &mut Vec<SpriteCommand>, letterbox: Letterbox, camera: &Camera2D) {
+    let Some(b) = camera.bounds else { return };
+    // Far enough past the bounds to cover the view at any rotation
+    let reach = (camera.viewport_size[0] + camera.viewport_size[1]) / camera.zoom * 2.0;
+    let rects = [
+        (b.min_x - reach, b.min_y - reach, reach, b.max_y - b.min_y + 2.0 * reach),
+        (b.max_x, b.min_y - reach, reach, b.max_y - b.min_y + 2.0 * reach),
+        (b.min_x, b.min_y - reach, b.max_x - b.min_x, reach),
+        (b.min_x, b.max_y, b.max_x - b.min_x, reach),
+    ];
+    for (x, y, w, h) in rects {
+        commands.push(quad(letterbox.texture_id, x, y, w, h, 0.0, letterbox.color, LETTERBOX_LAYER));
+    }
+}
+
+/// A quad given in screen pixels, placed in the world so it lands there
+/// under the camera's position, zoom and rotation.
+#[allow(clippy::too_many_arguments)]
+fn screen_quad(
+    texture_id: u32,
+    camera: &Camera2D,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    tint: [f32; 4],
+    layer: i32,
+) -> SpriteCommand {
+    let [screen_w, screen_h] = camera.viewport_size;
+    let zoom = camera.zoom;
+    // Offset of the quad's center from the screen center, in world units
+    let dx = (x + w / 2.0 - screen_w / 2.0) / zoom;
+    let dy = (y + h / 2.0 - screen_h / 2.0) / zoom;
+    let (sin, cos) = camera.rotation.sin_cos();
+    let center_x = camera.x + screen_w / zoom / 2.0 + dx * cos - dy * sin;
+    let center_y = camera.y + screen_h / zoom / 2.0 + dx * sin + dy * cos;
+    let (w, h) = (w / zoom, h / zoom);
+    quad(texture_id, center_x - w / 2.0, center_y - h / 2.0, w, h, camera.rotation, tint, layer)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn quad(texture_id: u32, x: f32, y: f32, w: f32, h: f32, rotation: f32, tint: [f32; 4], layer: i32) -> SpriteCommand {
+    SpriteCommand {
+        texture_id,
+        x,
+        y,
+        w,
+        h,
+        layer,
+        uv_x: 0.0,
+        uv_y: 0.0,
+        uv_w: 1.0,
+        uv_h: 1.0,
+        tint_r: tint[0],
+        tint_g: tint[1],
+        tint_b: tint[2],
+        tint_a: tint[3],
+        rotation,
+        origin_x: 0.5,
+        origin_y: 0.5,
+        flip_x: false,
+        flip_y: false,
+        opacity: 1.0,
+        blend_mode: 0,
+        shader_id: 0,
+        nine_slice: None,
+        outline: None,
+        clip: None,
+        mask: None,
+        normal_map: None,
+        flash: None,
+        pixel_snap: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::camera::CameraBounds;
+
+    fn camera() -> Camera2D {
+        Camera2D { x: 100.0, y: 50.0, zoom: 2.0, viewport_size: [800.0, 600.0], ..Camera2D::default() }
+    }
+
+    #[test]
+    fn test_stretched_fill_covers_the_view() {
+        let fill = BackgroundFill { texture_id: 4, tile: None };
+        let cmds = fill_commands(Some(fill), None, &camera());
+        assert_eq!(cmds.len(), 1);
+        let c = &cmds[0];
+        // Zoom 2: the view is 400×300 world units from (100, 50)
+        assert_eq!((c.x, c.y, c.w, c.h), (100.0, 50.0, 400.0, 300.0));
+        assert_eq!((c.texture_id, c.layer), (4, BACKGROUND_LAYER));
+    }
+
+    #[test]
+    fn test_rotated_fill_stays_centered_on_the_view() {
+        let cam = Camera2D { rotation: 0.5, ..camera() };
+        let c = fill_commands(Some(BackgroundFill { texture_id: 4, tile: None }), None, &cam).remove(0);
+        assert!((c.x + c.w / 2.0 - 300.0).abs() < 1e-3);
+        assert!((c.y + c.h / 2.0 - 200.0).abs() < 1e-3);
+        assert_eq!(c.rotation, 0.5);
+    }
+
+    #[test]
+    fn test_tiled_fill_starts_at_the_top_left() {
+        let fill = BackgroundFill { texture_id: 4, tile: Some([300.0, 300.0]) };
+        let cmds = fill_commands(Some(fill), None, &camera());
+        // 800×600 screen pixels in 300×300 tiles: 3 columns, 2 rows
+        assert_eq!(cmds.len(), 6);
+        assert_eq!((cmds[0].x, cmds[0].y, cmds[0].w), (100.0, 50.0, 150.0));
+        assert_eq!((cmds[1].x, cmds[3].y), (250.0, 200.0));
+    }
+
+    #[test]
+    fn test_bars_surround_the_bounds() {
+        let cam = Camera2D {
+            bounds: Some(CameraBounds { min_x: 0.0, min_y: 0.0, max_x: 320.0, max_y: 180.0 }),
+            ..camera()
+        };
+        let letterbox = Letterbox { color: [0.0, 0.0, 0.0, 1.0], texture_id: 9 };
+        assert!(fill_commands(None, Some(letterbox), &camera()).is_empty());
+        let cmds = fill_commands(None, Some(letterbox), &cam);
+        assert_eq!(cmds.len(), 4);
+        assert!(cmds.iter().all(|c| c.layer == LETTERBOX_LAYER && c.texture_id == 9));
+        // Left and right bars end and start at the bounds; top and bottom span them
+        assert_eq!(cmds[0].x + cmds[0].w, 0.0);
+        assert_eq!(cmds[1].x, 320.0);
+        assert_eq!((cmds[2].x, cmds[2].w, cmds[2].y + cmds[2].h), (0.0, 320.0, 0.0));
+        assert_eq!(cmds[3].y, 180.0);
+    }
+
+    #[test]
+    fn test_gradient_pixels_run_from_first_to_second_color() {
+        let (pixels, w, h) = gradient_pixels([1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0], false);
+        assert_eq!((w, h), (1, GRADIENT_STEPS));
+        assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[0, 0, 255, 255]);
+        assert_eq!(gradient_pixels([0.0; 4], [1.0; 4], true).1, GRADIENT_STEPS);
+    }
+}
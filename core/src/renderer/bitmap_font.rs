@@ -0,0 +1,253 @@
+//! Bitmap fonts cut from an image grid.
+//!
+//! A manifest describes the grid (cell size, columns, rows) and the characters
+//! in its cells, read left to right, top to bottom. `arcane assets
+//! font-from-grid` writes one next to the image:
+//!
+//! ```json
+//! {"image":"font.png","cellWidth":8,"cellHeight":8,"columns":16,"rows":6,
+//!  "charset":" !\"#...","advances":[4,2,4,...],"spacing":1}
+//! ```
+//!
+//! `advances` (optional) holds each glyph's width in pixels; without it every
+//! glyph advances by the cell width. `spacing` is added after every glyph.
+
+use crate::json::{JsonValue, escape};
+
+/// Grid layout and glyph widths of a bitmap font image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitmapFontManifest {
+    /// Image file the manifest was made for (informational).
+    pub image: Option<String>,
+    pub cell_width: u32,
+    pub cell_height: u32,
+    pub columns: u32,
+    pub rows: u32,
+    /// Character in each cell, in grid order.
+    pub charset: Vec<char>,
+    /// Advance in pixels per character. Empty = cell width for all.
+    pub advances: Vec<f32>,
+    /// Extra pixels after each glyph.
+    pub spacing: f32,
+}
+
+/// One glyph: UV rect of its cell in the image and how far it moves the cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitmapGlyph {
+    pub ch: char,
+    pub uv: [f32; 4],
+    pub advance: f32,
+}
+
+impl BitmapFontManifest {
+    /// Lay `charset` over a `image_width`×`image_height` image cut into
+    /// `cell_width`×`cell_height` cells.
+    pub fn for_grid(
+        image_width: u32,
+        image_height: u32,
+        cell_width: u32,
+        cell_height: u32,
+        charset: &str,
+    ) -> Result<Self, String> {
+        if cell_width == 0 || cell_height == 0 {
+            return Err("cell size must be at least 1x1".into());
+        }
+        if !image_width.is_multiple_of(cell_width) || !image_height.is_multiple_of(cell_height) {
+            return Err(format!(
+                "{image_width}x{image_height} image is not a whole number of {cell_width}x{cell_height} cells"
+            ));
+        }
+        let manifest = Self {
+            image: None,
+            cell_width,
+            cell_height,
+            columns: image_width / cell_width,
+            rows: image_height / cell_height,
+            charset: charset.chars().collect(),
+            advances: Vec::new(),
+            spacing: 0.0,
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.cell_width == 0 || self.cell_height == 0 || self.columns == 0 || self.rows == 0 {
+            return Err("cell size and grid must be at least 1x1".into());
+        }
+        if self.charset.is_empty() {
+            return Err("charset is empty".into());
+        }
+        let cells = (self.columns * self.rows) as usize;
+        if self.charset.len() > cells {
+            return Err(format!(
+                "charset has {} characters but the grid only has {cells} cells",
+                self.charset.len()
+            ));
+        }
+        if !self.advances.is_empty() && self.advances.len() != self.charset.len() {
+            return Err(format!(
+                "advances has {} entries, expected one per charset character ({})",
+                self.advances.len(),
+                self.charset.len()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let root = JsonValue::parse(json)?;
+        let uint = |key: &str| -> Result<u32, String> {
+            root.get(key)
+                .and_then(JsonValue::as_f64)
+                .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+                .map(|n| n as u32)
+                .ok_or_else(|| format!("\"{key}\" must be a whole number"))
+        };
+        let manifest = Self {
+            image: root.str_of("image").map(str::to_string),
+            cell_width: uint("cellWidth")?,
+            cell_height: uint("cellHeight")?,
+            columns: uint("columns")?,
+            rows: uint("rows")?,
+            charset: root
+                .str_of("charset")
+                .ok_or("\"charset\" must be a string")?
+                .chars()
+                .collect(),
+            advances: root.f32_array("advances"),
+            spacing: root.f32_or("spacing", 0.0),
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        if let Some(image) = &self.image {
+            out.push_str(&format!("\"image\":\"{}\",", escape(image)));
+        }
+        out.push_str(&format!(
+            "\"cellWidth\":{},\"cellHeight\":{},\"columns\":{},\"rows\":{},\"charset\":\"{}\"",
+            self.cell_width,
+            self.cell_height,
+            self.columns,
+            self.rows,
+            escape(&self.charset.iter().collect::<String>())
+        ));
+        if !self.advances.is_empty() {
+            let advances: Vec<String> = self.advances.iter().map(|a| a.to_string()).collect();
+            out.push_str(&format!(",\"advances\":[{}]", advances.join(",")));
+        }
+        if self.spacing != 0.0 {
+            out.push_str(&format!(",\"spacing\":{}", self.spacing));
+        }
+        out.push('}');
+        out
+    }
+
+    /// Set each glyph's advance to the width of its ink: up to the rightmost
+    /// column with a non-transparent pixel. Empty cells (like space) get half
+    /// the cell width. `rgba` is the whole image, `image_width` pixels wide.
+    pub fn measure_advances(&mut self, rgba: &[u8], image_width: u32) {
+        let (cw, ch) = (self.cell_width as usize, self.cell_height as usize);
+        let stride = image_width as usize * 4;
+        self.advances = (0..self.charset.len())
+            .map(|i| {
+                let x0 = (i % self.columns as usize) * cw;
+                let y0 = (i / self.columns as usize) * ch;
+                let inked = (0..cw).rev().find(|&x| {
+                    (0..ch).any(|y| {
+                        rgba.get((y0 + y) * stride + (x0 + x) * 4 + 3)
+                            .is_some_and(|&a| a > 0)
+                    })
+                });
+                match inked {
+                    Some(x) => (x + 1) as f32,
+                    None => (cw / 2).max(1) as f32,
+                }
+            })
+            .collect();
+    }
+
+    pub fn glyphs(&self) -> Vec<BitmapGlyph> {
+        let (uw, vh) = (1.0 / self.columns as f32, 1.0 / self.rows as f32);
+        self.charset
+            .iter()
+            .enumerate()
+            .map(|(i, &ch)| {
+                let col = (i as u32 % self.columns) as f32;
+                let row = (i as u32 / self.columns) as f32;
+                let width = self
+                    .advances
+                    .get(i)
+                    .copied()
+                    .unwrap_or(self.cell_width as f32);
+                BitmapGlyph {
+                    ch,
+                    uv: [col * uw, row * vh, uw, vh],
+                    advance: width + self.spacing,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Printable ASCII from space to tilde, the usual layout of grid fonts.
+pub fn ascii_charset() -> String {
+    (32u8..127).map(char::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_grid_checks_cells_and_charset() {
+        let m = BitmapFontManifest::for_grid(128, 48, 8, 8, &ascii_charset()).unwrap();
+        assert_eq!((m.columns, m.rows), (16, 6));
+        assert!(BitmapFontManifest::for_grid(130, 48, 8, 8, "AB").is_err());
+        assert!(BitmapFontManifest::for_grid(16, 8, 8, 8, "ABC").is_err());
+    }
+
+    #[test]
+    fn test_glyph_uvs_follow_grid_order() {
+        let m = BitmapFontManifest::for_grid(32, 16, 8, 8, "ABCDE").unwrap();
+        let glyphs = m.glyphs();
+        assert_eq!(glyphs.len(), 5);
+        assert_eq!(glyphs[1].uv, [0.25, 0.0, 0.25, 0.5]);
+        assert_eq!(glyphs[4].ch, 'E');
+        assert_eq!(glyphs[4].uv, [0.0, 0.5, 0.25, 0.5]);
+        assert_eq!(glyphs[4].advance, 8.0);
+    }
+
+    #[test]
+    fn test_measure_advances_from_ink() {
+        // 2 cells of 4x2: "I" inked in column 1, " " empty
+        let mut rgba = vec![0u8; 8 * 2 * 4];
+        rgba[4 + 3] = 255; // (1, 0)
+        rgba[(8 + 1) * 4 + 3] = 255; // (1, 1)
+        let mut m = BitmapFontManifest::for_grid(8, 2, 4, 2, "I ").unwrap();
+        m.measure_advances(&rgba, 8);
+        m.spacing = 1.0;
+        assert_eq!(m.advances, vec![2.0, 2.0]);
+        assert_eq!(m.glyphs()[0].advance, 3.0);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut m = BitmapFontManifest::for_grid(32, 8, 8, 8, "A\"\\b").unwrap();
+        m.image = Some("font.png".into());
+        m.advances = vec![5.0, 3.0, 4.0, 6.0];
+        m.spacing = 1.0;
+        assert_eq!(BitmapFontManifest::parse(&m.to_json()).unwrap(), m);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_manifests() {
+        assert!(BitmapFontManifest::parse("{\"cellWidth\":8}").is_err());
+        let short =
+            r#"{"cellWidth":8,"cellHeight":8,"columns":2,"rows":1,"charset":"AB","advances":[1]}"#;
+        assert!(BitmapFontManifest::parse(short).is_err());
+    }
+}
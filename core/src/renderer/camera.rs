@@ -13,6 +13,9 @@ pub struct Camera2D {
     pub x: f32,
     pub y: f32,
     pub zoom: f32,
+    /// Roll in radians around the center of the visible area. Positive turns
+    /// the camera clockwise, so the world appears to turn counter-clockwise.
+    pub rotation: f32,
     pub viewport_size: [f32; 2],
     pub bounds: Option<CameraBounds>,
 }
@@ -23,6 +26,7 @@ impl Default for Camera2D {
             x: 0.0,
             y: 0.0,
             zoom: 1.0,
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: None,
         }
@@ -55,11 +59,24 @@ impl Camera2D {
         }
     }
 
+    /// Round a world point to the nearest pixel of the view (logical pixels,
+    /// so `1 / zoom` world units), counted from the camera's top-left corner.
+    /// Unchanged while the camera is rotated, as its pixel grid is then not
+    /// axis-aligned in the world.
+    pub fn snap_point(&self, x: f32, y: f32) -> (f32, f32) {
+        if self.rotation != 0.0 || self.zoom <= 0.0 {
+            return (x, y);
+        }
+        let snap = |v: f32, origin: f32| origin + ((v - origin) * self.zoom).round() / self.zoom;
+        (snap(x, self.x), snap(y, self.y))
+    }
+
     /// Compute the view-projection matrix as a column-major 4x4 array.
     ///
     /// Maps world coordinates to clip space:
     /// - Camera position is the top-left corner of the visible area
     /// - Zoom scales the view (larger zoom = more zoomed in)
+    /// - Rotation turns the view around the center of the visible area
     /// - Y-axis points down (screen coordinates)
     pub fn view_proj(&self) -> [f32; 16] {
         let vis_w = self.viewport_size[0] / self.zoom;
@@ -76,11 +93,19 @@ impl Camera2D {
         let tx = -(right + left) / (right - left);
         let ty = -(top + bottom) / (top - bottom);
 
+        // Rotate world points by -rotation around the view center first.
+        // At rotation 0 this is exactly the identity (sin 0 = 0, cos 0 = 1).
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let cx = left + vis_w / 2.0;
+        let cy = top + vis_h / 2.0;
+        let ox = cx - (cos * cx - sin * cy);
+        let oy = cy - (sin * cx + cos * cy);
+
         [
-            sx, 0.0, 0.0, 0.0,
-            0.0, sy, 0.0, 0.0,
+            sx * cos, sy * sin, 0.0, 0.0,
+            -sx * sin, sy * cos, 0.0, 0.0,
             0.0, 0.0, 1.0, 0.0,
-            tx, ty, 0.0, 1.0,
+            sx * ox + tx, sy * oy + ty, 0.0, 1.0,
         ]
     }
 }
@@ -253,6 +278,7 @@ mod tests {
             x: -100.0,
             y: -100.0,
             zoom: 1.0,
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: Some(CameraBounds { min_x: 0.0, min_y: 0.0, max_x: 1600.0, max_y: 1200.0 }),
         };
@@ -268,6 +294,7 @@ mod tests {
             x: 1500.0,
             y: 1100.0,
             zoom: 1.0,
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: Some(CameraBounds { min_x: 0.0, min_y: 0.0, max_x: 1600.0, max_y: 1200.0 }),
         };
@@ -283,6 +310,7 @@ mod tests {
             x: 0.0,
             y: 0.0,
             zoom: 0.5, // vis_w=1600, vis_h=1200
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: Some(CameraBounds { min_x: 0.0, min_y: 0.0, max_x: 400.0, max_y: 300.0 }),
         };
@@ -298,6 +326,7 @@ mod tests {
             x: -999.0,
             y: 999.0,
             zoom: 1.0,
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: None,
         };
@@ -312,6 +341,7 @@ mod tests {
             x: 10.0,
             y: 10.0,
             zoom: 2.0, // vis_w=400, vis_h=300
+            rotation: 0.0,
             viewport_size: [800.0, 600.0],
             bounds: Some(CameraBounds { min_x: 0.0, min_y: 0.0, max_x: 1000.0, max_y: 800.0 }),
         };
@@ -338,4 +368,50 @@ mod tests {
         assert!((mat[0] - expected_sx).abs() < 1e-6);
         assert!((mat[5] - expected_sy).abs() < 1e-6);
     }
+
+    /// Apply a column-major view-projection to a world point.
+    fn to_clip(mat: &[f32; 16], x: f32, y: f32) -> (f32, f32) {
+        (mat[0] * x + mat[4] * y + mat[12], mat[1] * x + mat[5] * y + mat[13])
+    }
+
+    #[test]
+    fn rotation_turns_view_around_center() {
+        let cam = Camera2D {
+            x: 100.0,
+            y: 100.0,
+            zoom: 2.0,
+            rotation: std::f32::consts::FRAC_PI_2,
+            viewport_size: [400.0, 200.0],
+            ..Default::default()
+        };
+        let mat = cam.view_proj();
+        // Visible area 200x100 starting at (100, 100): center (200, 150)
+        let (cx, cy) = to_clip(&mat, 200.0, 150.0);
+        assert!(cx.abs() < 1e-5 && cy.abs() < 1e-5, "center stays at clip origin");
+        // Turning the camera clockwise moves a point right of center to the top
+        let (rx, ry) = to_clip(&mat, 250.0, 150.0);
+        assert!(rx.abs() < 1e-5, "rx = {rx}");
+        assert!((ry - 1.0).abs() < 1e-5, "ry = {ry}");
+    }
+
+    #[test]
+    fn snap_point_rounds_to_view_pixels() {
+        let mut cam = Camera2D { x: 10.25, y: 0.0, zoom: 4.0, ..Default::default() };
+        // 0.3 world units right of the camera is 1.2 pixels at zoom 4 → 1 pixel
+        assert_eq!(cam.snap_point(10.55, 7.9), (10.5, 8.0));
+        cam.zoom = 1.0;
+        assert_eq!(cam.snap_point(10.55, 7.4), (10.25, 7.0));
+        cam.rotation = 0.1;
+        assert_eq!(cam.snap_point(10.55, 7.4), (10.55, 7.4));
+    }
+
+    #[test]
+    fn zero_rotation_matches_unrotated_projection() {
+        let cam = Camera2D { x: 30.0, y: -20.0, zoom: 1.5, ..Default::default() };
+        let mat = cam.view_proj();
+        assert_eq!(mat[1], 0.0);
+        assert_eq!(mat[4], 0.0);
+        let (lx, ty) = to_clip(&mat, 30.0, -20.0);
+        assert!((lx + 1.0).abs() < 1e-6 && (ty - 1.0).abs() < 1e-6, "top-left maps to (-1, 1)");
+    }
 }
@@ -0,0 +1,211 @@
+//! Built-in debug HUD: fps, frame time, draw calls, physics bodies, playing
+//! sounds and texture memory, drawn by the engine so diagnostics need no game
+//! code.
+//!
+//! Toggled with `op_set_debug_hud` (`setDebugHud()` in TS) or
+//! [`DEBUG_HUD_KEY`] in `arcane dev`. The dev loop fills in the
+//! [`DebugHudStats`] each frame and appends the HUD sprites after the game's,
+//! in the top-left corner of the screen, above the game and below the error
+//! overlay. Text uses the built-in MSDF font through an MSDF shader of its own,
+//! so it stays crisp at any camera zoom and never takes a slot of the shader
+//! pool game text uses.
+
+use super::error_overlay::{quad, OVERLAY_LAYER};
+use super::msdf::MsdfFont;
+use super::SpriteCommand;
+
+/// Key that toggles the HUD in `arcane dev` (TS input API name).
+pub const DEBUG_HUD_KEY: &str = "F3";
+
+/// Scale of the built-in MSDF font (8 px glyphs drawn at 16 px).
+const TEXT_SCALE: f32 = 2.0;
+/// Line pitch as a multiple of the scaled line height.
+const LINE_SPACING: f32 = 1.25;
+/// Screen pixels between the panel edge and the text.
+const PADDING: f32 = 8.0;
+/// Weight of the newest frame in the smoothed frame time.
+const FRAME_SMOOTHING: f64 = 0.1;
+
+/// Readings shown by the HUD.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DebugHudStats {
+    /// Smoothed wall-clock time between frames.
+    pub frame_ms: f64,
+    /// Sprite draw calls of the last rendered frame.
+    pub draw_calls: u32,
+    /// Bodies in the physics world.
+    pub bodies: u32,
+    /// Sounds playing or paused.
+    pub audio_instances: u32,
+    /// GPU memory held by textures and render targets.
+    pub texture_bytes: u64,
+}
+
+impl DebugHudStats {
+    pub fn fps(&self) -> f64 {
+        if self.frame_ms > 0.0 { 1000.0 / self.frame_ms } else { 0.0 }
+    }
+
+    /// Text of the HUD, one reading per line.
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!("fps      {:>7.1}", self.fps()),
+            format!("frame    {:>7.2}ms", self.frame_ms),
+            format!("draws    {:>7}", self.draw_calls),
+            format!("bodies   {:>7}", self.bodies),
+            format!("sounds   {:>7}", self.audio_instances),
+            format!("textures {:>7.1}MB", self.texture_bytes as f64 / (1024.0 * 1024.0)),
+        ]
+    }
+}
+
+/// HUD state kept in the render bridge.
+#[derive(Debug, Clone, Default)]
+pub struct DebugHud {
+    pub enabled: bool,
+    /// MSDF shader drawing the HUD text; 0 until the HUD is first shown.
+    pub shader_id: u32,
+    pub stats: DebugHudStats,
+}
+
+impl DebugHud {
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Fold one frame's wall-clock duration into the smoothed frame time.
+    pub fn record_frame(&mut self, dt_seconds: f64) {
+        let ms = dt_seconds * 1000.0;
+        if ms <= 0.0 {
+            return;
+        }
+        let avg = &mut self.stats.frame_ms;
+        *avg = if *avg > 0.0 { *avg + (ms - *avg) * FRAME_SMOOTHING } else { ms };
+    }
+
+    /// Sprites drawing the HUD in the top-left corner of the screen (camera
+    /// top-left at `cam_x`/`cam_y`). Glyphs use `font` with `shader_id`,
+    /// whose parameters come from [`text_shader_params`].
+    pub fn sprites(&self, font: &MsdfFont, panel_texture: u32, cam_x: f32, cam_y: f32, zoom: f32) -> Vec<SpriteCommand> {
+        let zoom = if zoom > 0.0 { zoom } else { 1.0 };
+        let lines = self.stats.lines();
+        let size = font.font_size * TEXT_SCALE;
+        let pitch = font.line_height * TEXT_SCALE * LINE_SPACING;
+        let text_w = lines.iter().map(|l| font.measure_width(l, size)).fold(0.0, f32::max);
+        let panel_w = PADDING * 2.0 + text_w;
+        let panel_h = PADDING * 2.0 + pitch * lines.len() as f32;
+
+        // Screen pixels → world units under the current camera
+        let to_world = |sx: f32, sy: f32| (cam_x + sx / zoom, cam_y + sy / zoom);
+        let mut sprites = vec![quad(
+            panel_texture,
+            cam_x,
+            cam_y,
+            panel_w / zoom,
+            panel_h / zoom,
+            [0.0, 0.0, 1.0, 1.0],
+            [0.0, 0.0, 0.0, 0.6],
+            OVERLAY_LAYER - 2,
+        )];
+
+        // Built-in glyph cells include the distance field padding on every side
+        let pad = font.distance_range * TEXT_SCALE;
+        let cell = size + pad * 2.0;
+        for (row, line) in lines.iter().enumerate() {
+            let mut cursor = PADDING;
+            let top = PADDING + row as f32 * pitch;
+            for ch in line.chars() {
+                let Some(glyph) = font.get_glyph(ch) else { continue };
+                if ch != ' ' {
+                    let (x, y) = to_world(
+                        cursor - pad + glyph.offset_x * TEXT_SCALE,
+                        top - pad + glyph.offset_y * TEXT_SCALE,
+                    );
+                    let uv = [glyph.uv_x, glyph.uv_y, glyph.uv_w, glyph.uv_h];
+                    let mut sprite = quad(font.texture_id, x, y, cell / zoom, cell / zoom, uv, [1.0; 4], OVERLAY_LAYER - 1);
+                    sprite.shader_id = self.shader_id;
+                    sprites.push(sprite);
+                }
+                cursor += glyph.advance * TEXT_SCALE;
+            }
+        }
+        sprites
+    }
+}
+
+/// MSDF shader parameters (slot, value) for the HUD text: no outline and a
+/// one-pixel black shadow. Sizes are in screen pixels, so they hold at any zoom.
+pub fn text_shader_params(font: &MsdfFont) -> [(u32, [f32; 4]); 5] {
+    let screen_px_range = (2.0 * font.distance_range * TEXT_SCALE).max(1.0);
+    [
+        (0, [font.distance_range, font.font_size, screen_px_range, 0.0]),
+        (1, [0.0; 4]),
+        (2, [0.0; 4]),
+        (3, [1.0, 1.0, 1.0, 0.0]),
+        (4, [0.0, 0.0, 0.0, 1.0]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::msdf::generate_builtin_msdf_font;
+
+    fn font() -> MsdfFont {
+        let (_, _, _, mut font) = generate_builtin_msdf_font();
+        font.texture_id = 9;
+        font
+    }
+
+    #[test]
+    fn test_lines_show_every_reading() {
+        let stats = DebugHudStats {
+            frame_ms: 20.0,
+            draw_calls: 12,
+            bodies: 3,
+            audio_instances: 2,
+            texture_bytes: 3 * 1024 * 1024 / 2,
+        };
+        assert_eq!(
+            stats.lines(),
+            [
+                "fps         50.0",
+                "frame      20.00ms",
+                "draws         12",
+                "bodies         3",
+                "sounds         2",
+                "textures     1.5MB",
+            ]
+        );
+        assert_eq!(DebugHudStats::default().fps(), 0.0);
+    }
+
+    #[test]
+    fn test_frame_time_is_smoothed() {
+        let mut hud = DebugHud::default();
+        hud.record_frame(0.0);
+        assert_eq!(hud.stats.frame_ms, 0.0);
+        hud.record_frame(0.020);
+        assert!((hud.stats.frame_ms - 20.0).abs() < 1e-9);
+        hud.record_frame(0.030);
+        assert!((hud.stats.frame_ms - 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sprites_sit_in_the_top_left_corner() {
+        let font = font();
+        let hud = DebugHud { enabled: true, shader_id: 5, stats: DebugHudStats::default() };
+        let sprites = hud.sprites(&font, 4, 100.0, 50.0, 2.0);
+
+        let panel = &sprites[0];
+        assert_eq!((panel.texture_id, panel.x, panel.y), (4, 100.0, 50.0));
+        let glyphs = &sprites[1..];
+        let visible: usize = hud.stats.lines().iter().map(|l| l.chars().filter(|&c| c != ' ').count()).sum();
+        assert_eq!(glyphs.len(), visible);
+        assert!(glyphs.iter().all(|s| s.texture_id == 9 && s.shader_id == 5 && s.layer == OVERLAY_LAYER - 1));
+        // Glyphs stay inside the panel once the distance field padding is trimmed
+        let pad = font.distance_range * TEXT_SCALE / 2.0;
+        assert!(glyphs.iter().all(|s| s.x + pad >= panel.x && s.x + s.w - pad <= panel.x + panel.w));
+        assert!(glyphs.iter().all(|s| s.y + pad >= panel.y && s.y + s.h - pad <= panel.y + panel.h));
+    }
+}
@@ -0,0 +1,286 @@
+//! Decals: textures stamped onto the scene that stay put and fade out, like
+//! blood splats, scorch marks and footprints.
+//!
+//! Game code spawns a decal once instead of drawing it every frame. The store
+//! ages decals with the frame's delta time (so they freeze while the
+//! simulation is paused), fades each one out over the end of its lifetime and
+//! keeps at most [`DecalStore::max`] of them, evicting the oldest first. The
+//! renderer draws them as their own batched pass, under the sprites of their
+//! layer.
+
+use std::collections::VecDeque;
+
+use super::SpriteCommand;
+
+/// Decals kept before the oldest are evicted, unless changed with
+/// [`DecalStore::set_max`].
+pub const DEFAULT_MAX_DECALS: usize = 1024;
+
+/// A spawned decal and its age.
+#[derive(Debug, Clone)]
+pub struct Decal {
+    /// What to draw. Opacity is multiplied by the fade-out.
+    pub sprite: SpriteCommand,
+    /// Seconds until the decal is removed. 0 = until evicted or cleared.
+    pub lifetime: f32,
+    /// Seconds at the end of the lifetime over which opacity falls to 0.
+    pub fade_out: f32,
+    age: f32,
+}
+
+impl Decal {
+    pub fn new(sprite: SpriteCommand, lifetime: f32, fade_out: f32) -> Self {
+        Self {
+            sprite,
+            lifetime: lifetime.max(0.0),
+            fade_out: fade_out.max(0.0),
+            age: 0.0,
+        }
+    }
+
+    fn expired(&self) -> bool {
+        self.lifetime > 0.0 && self.age >= self.lifetime
+    }
+
+    /// Opacity multiplier from the fade-out: 1 until `fade_out` seconds are
+    /// left, then linear down to 0.
+    pub fn fade(&self) -> f32 {
+        let remaining = self.lifetime - self.age;
+        if self.lifetime <= 0.0 || self.fade_out <= 0.0 || remaining >= self.fade_out {
+            1.0
+        } else {
+            (remaining / self.fade_out).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Live decals, oldest first.
+#[derive(Debug, Clone)]
+pub struct DecalStore {
+    decals: VecDeque<Decal>,
+    max: usize,
+}
+
+impl Default for DecalStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecalStore {
+    pub fn new() -> Self {
+        Self {
+            decals: VecDeque::new(),
+            max: DEFAULT_MAX_DECALS,
+        }
+    }
+
+    /// Add a decal, evicting the oldest ones if the store is full.
+    pub fn spawn(&mut self, decal: Decal) {
+        if self.max == 0 {
+            return;
+        }
+        while self.decals.len() >= self.max {
+            self.decals.pop_front();
+        }
+        self.decals.push_back(decal);
+    }
+
+    /// Most decals kept at once.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Change the cap, evicting the oldest decals above it.
+    pub fn set_max(&mut self, max: usize) {
+        self.max = max;
+        let excess = self.decals.len().saturating_sub(max);
+        self.decals.drain(..excess);
+    }
+
+    pub fn len(&self) -> usize {
+        self.decals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decals.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.decals.clear();
+    }
+
+    /// Age every decal by `dt` seconds and remove the expired ones.
+    pub fn update(&mut self, dt: f32) {
+        for decal in &mut self.decals {
+            decal.age += dt.max(0.0);
+        }
+        self.decals.retain(|d| !d.expired());
+    }
+
+    /// Sprite commands for this frame, oldest first, faded.
+    pub fn sprite_commands(&self) -> Vec<SpriteCommand> {
+        self.decals
+            .iter()
+            .map(|d| SpriteCommand {
+                opacity: d.sprite.opacity * d.fade(),
+                ..d.sprite.clone()
+            })
+            .collect()
+    }
+}
+
+/// The frame's decal commands on the render side, sorted for batching and
+/// handed out layer by layer as the render schedule reaches them.
+#[derive(Debug, Default)]
+pub struct DecalBatch {
+    commands: Vec<SpriteCommand>,
+    /// Commands already handed out this frame.
+    drawn: usize,
+}
+
+impl DecalBatch {
+    /// Install a frame's decals. The sort is stable, so newer decals still
+    /// draw over older ones with the same layer, blend mode and texture.
+    pub fn set_commands(&mut self, mut commands: Vec<SpriteCommand>) {
+        commands.sort_by(|a, b| {
+            a.layer
+                .cmp(&b.layer)
+                .then(a.blend_mode.cmp(&b.blend_mode))
+                .then(a.texture_id.cmp(&b.texture_id))
+        });
+        self.commands = commands;
+        self.drawn = 0;
+    }
+
+    /// Decals not handed out yet whose layer is at most `layer`, or all the
+    /// remaining ones for `None`.
+    pub fn take_through(&mut self, layer: Option<i32>) -> &[SpriteCommand] {
+        let start = self.drawn;
+        self.drawn = match layer {
+            Some(layer) => {
+                start
+                    + self.commands[start..]
+                        .iter()
+                        .take_while(|c| c.layer <= layer)
+                        .count()
+            }
+            None => self.commands.len(),
+        };
+        &self.commands[start..self.drawn]
+    }
+
+    /// Hand decals out again from the first one at or above `layer`, to
+    /// draw the frame once more through another viewport.
+    pub fn restart(&mut self, layer: i32) {
+        self.drawn = self.commands.partition_point(|c| c.layer < layer);
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.drawn = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn splat(texture_id: u32) -> SpriteCommand {
+        SpriteCommand {
+            texture_id,
+            x: 0.0,
+            y: 0.0,
+            w: 16.0,
+            h: 16.0,
+            layer: 0,
+            uv_x: 0.0,
+            uv_y: 0.0,
+            uv_w: 1.0,
+            uv_h: 1.0,
+            tint_r: 1.0,
+            tint_g: 1.0,
+            tint_b: 1.0,
+            tint_a: 1.0,
+            rotation: 0.0,
+            origin_x: 0.5,
+            origin_y: 0.5,
+            flip_x: false,
+            flip_y: false,
+            opacity: 0.8,
+            blend_mode: 0,
+            shader_id: 0,
+            nine_slice: None,
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
+        }
+    }
+
+    fn textures(store: &DecalStore) -> Vec<u32> {
+        store
+            .sprite_commands()
+            .iter()
+            .map(|c| c.texture_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_fades_out_at_the_end_of_the_lifetime() {
+        let mut store = DecalStore::new();
+        store.spawn(Decal::new(splat(1), 2.0, 1.0));
+        store.update(0.5);
+        assert_eq!(store.sprite_commands()[0].opacity, 0.8);
+        store.update(1.0);
+        assert!((store.sprite_commands()[0].opacity - 0.4).abs() < 1e-6);
+        store.update(0.5);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_zero_lifetime_stays_until_evicted() {
+        let mut store = DecalStore::new();
+        store.spawn(Decal::new(splat(1), 0.0, 1.0));
+        store.update(1000.0);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.sprite_commands()[0].opacity, 0.8);
+    }
+
+    #[test]
+    fn test_cap_evicts_oldest_first() {
+        let mut store = DecalStore::new();
+        store.set_max(3);
+        for id in 1..=5 {
+            store.spawn(Decal::new(splat(id), 0.0, 0.0));
+        }
+        assert_eq!(textures(&store), vec![3, 4, 5]);
+        store.set_max(2);
+        assert_eq!(textures(&store), vec![4, 5]);
+        store.set_max(0);
+        store.spawn(Decal::new(splat(6), 0.0, 0.0));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_batch_hands_out_decals_by_layer() {
+        let mut batch = DecalBatch::default();
+        let at = |layer, texture_id| SpriteCommand {
+            layer,
+            ..splat(texture_id)
+        };
+        batch.set_commands(vec![at(5, 2), at(-1, 1), at(5, 1), at(2, 3), at(5, 2)]);
+        let ids = |cmds: &[SpriteCommand]| cmds.iter().map(|c| c.texture_id).collect::<Vec<_>>();
+        assert_eq!(ids(batch.take_through(Some(0))), vec![1]);
+        assert!(batch.take_through(Some(1)).is_empty());
+        assert_eq!(ids(batch.take_through(Some(5))), vec![3, 1, 2, 2]);
+        assert!(batch.take_through(None).is_empty());
+
+        batch.restart(2);
+        assert_eq!(ids(batch.take_through(Some(2))), vec![3]);
+        batch.restart(i32::MIN);
+        assert_eq!(batch.take_through(None).len(), 5);
+    }
+}
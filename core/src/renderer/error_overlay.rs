@@ -0,0 +1,260 @@
+//! Dev-mode error boundary around the TS frame callback.
+//!
+//! When `update()` throws, the dev loop keeps presenting the last good frame
+//! (sprites and geometry) and draws the error message on top using the
+//! built-in 8×8 font. The first successful frame — typically right after a hot
+//! reload that fixed the code — clears the overlay and resumes normally.
+
+use super::font::glyph_uv;
+use super::SpriteCommand;
+use crate::scripting::geometry_ops::GeoCommand;
+
+/// Overlay sprites sit above everything the game draws.
+pub(super) const OVERLAY_LAYER: i32 = i32::MAX - 1;
+/// Glyph size on screen, in logical pixels.
+pub(super) const GLYPH_SIZE: f32 = 8.0;
+pub(super) const LINE_HEIGHT: f32 = 10.0;
+pub(super) const PADDING: f32 = 8.0;
+
+/// Tracks the current frame error and the last frame that rendered cleanly.
+#[derive(Default)]
+pub struct FrameErrorBoundary {
+    error: Option<String>,
+    failed_frames: u32,
+    last_sprites: Vec<SpriteCommand>,
+    last_geo: Vec<GeoCommand>,
+}
+
+impl FrameErrorBoundary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The error currently shown, if the last frame failed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Record a failed frame. Returns true when the message differs from the
+    /// current one, so callers log each distinct error once instead of every frame.
+    pub fn record_error(&mut self, message: &str) -> bool {
+        self.failed_frames = self.failed_frames.saturating_add(1);
+        if self.error.as_deref() == Some(message) {
+            return false;
+        }
+        self.error = Some(message.to_string());
+        true
+    }
+
+    /// Record a successful frame. Returns the number of failed frames when this
+    /// recovers from an error, None otherwise.
+    pub fn record_success(&mut self) -> Option<u32> {
+        self.error.take()?;
+        Some(std::mem::take(&mut self.failed_frames))
+    }
+
+    /// Remember this frame's sprites, or replace a failed frame's partial
+    /// output with the last good one.
+    pub fn settle_sprites(&mut self, sprites: &mut Vec<SpriteCommand>) {
+        if self.error.is_some() {
+            *sprites = self.last_sprites.clone();
+        } else {
+            self.last_sprites.clone_from(sprites);
+        }
+    }
+
+    /// Same as [`settle_sprites`](Self::settle_sprites) for geometry commands.
+    pub fn settle_geo(&mut self, geo: &mut Vec<GeoCommand>) {
+        if self.error.is_some() {
+            *geo = self.last_geo.clone();
+        } else {
+            self.last_geo.clone_from(geo);
+        }
+    }
+
+    /// Sprites drawing the error panel in screen space (camera top-left at
+    /// `cam_x`/`cam_y`). Empty when there is no error.
+    #[allow(clippy::too_many_arguments)]
+    pub fn overlay_sprites(
+        &self,
+        font_texture: u32,
+        panel_texture: u32,
+        cam_x: f32,
+        cam_y: f32,
+        zoom: f32,
+        viewport_w: f32,
+        viewport_h: f32,
+    ) -> Vec<SpriteCommand> {
+        let Some(ref message) = self.error else {
+            return Vec::new();
+        };
+        let zoom = if zoom > 0.0 { zoom } else { 1.0 };
+        let columns = (((viewport_w - PADDING * 2.0) / GLYPH_SIZE).floor() as usize).max(1);
+        let max_lines = (((viewport_h - PADDING * 2.0) / LINE_HEIGHT).floor() as usize).max(2);
+
+        let header = if self.failed_frames > 1 {
+            format!("Frame error (x{}) - fix the code and save to reload", self.failed_frames)
+        } else {
+            "Frame error - fix the code and save to reload".to_string()
+        };
+        let mut lines = vec![header];
+        lines.extend(wrap_message(message, columns));
+        if lines.len() > max_lines {
+            lines.truncate(max_lines - 1);
+            lines.push("...".to_string());
+        }
+
+        // Screen pixels → world units under the current camera
+        let to_world = |sx: f32, sy: f32| (cam_x + sx / zoom, cam_y + sy / zoom);
+        let mut sprites = Vec::new();
+
+        let panel_h = PADDING * 2.0 + lines.len() as f32 * LINE_HEIGHT;
+        let (px, py) = to_world(0.0, 0.0);
+        sprites.push(quad(
+            panel_texture,
+            px,
+            py,
+            viewport_w / zoom,
+            panel_h / zoom,
+            [0.0, 0.0, 1.0, 1.0],
+            [0.08, 0.0, 0.0, 0.85],
+            OVERLAY_LAYER,
+        ));
+
+        for (row, line) in lines.iter().enumerate() {
+            // Header in red, message in white
+            let tint = if row == 0 { [1.0, 0.35, 0.35, 1.0] } else { [1.0, 1.0, 1.0, 1.0] };
+            let sy = PADDING + row as f32 * LINE_HEIGHT;
+            for (col, ch) in line.chars().enumerate() {
+                if ch == ' ' {
+                    continue;
+                }
+                let (x, y) = to_world(PADDING + col as f32 * GLYPH_SIZE, sy);
+                sprites.push(quad(
+                    font_texture,
+                    x,
+                    y,
+                    GLYPH_SIZE / zoom,
+                    GLYPH_SIZE / zoom,
+                    glyph_uv(ch),
+                    tint,
+                    OVERLAY_LAYER + 1,
+                ));
+            }
+        }
+        sprites
+    }
+}
+
+/// Split an error message into display lines of at most `columns` characters.
+/// Tabs become spaces and `file://` prefixes are dropped to save width.
+pub fn wrap_message(message: &str, columns: usize) -> Vec<String> {
+    let columns = columns.max(1);
+    let cleaned = message.replace("file://", "").replace('\t', "    ");
+    let mut lines = Vec::new();
+    for raw in cleaned.lines() {
+        let chars: Vec<char> = raw.trim_end().chars().collect();
+        if chars.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        for chunk in chars.chunks(columns) {
+            lines.push(chunk.iter().collect());
+        }
+    }
+    lines
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn quad(
+    texture_id: u32,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    uv: [f32; 4],
+    tint: [f32; 4],
+    layer: i32,
+) -> SpriteCommand {
+    SpriteCommand {
+        texture_id,
+        x,
+        y,
+        w,
+        h,
+        layer,
+        uv_x: uv[0],
+        uv_y: uv[1],
+        uv_w: uv[2],
+        uv_h: uv[3],
+        tint_r: tint[0],
+        tint_g: tint[1],
+        tint_b: tint[2],
+        tint_a: tint[3],
+        rotation: 0.0,
+        origin_x: 0.5,
+        origin_y: 0.5,
+        flip_x: false,
+        flip_y: false,
+        opacity: 1.0,
+        blend_mode: 0,
+        shader_id: 0,
+        nine_slice: None,
+        outline: None,
+        clip: None,
+        mask: None,
+        normal_map: None,
+        flash: None,
+        pixel_snap: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_message_splits_long_lines_and_strips_file_urls() {
+        let lines = wrap_message("Error: boom\n    at update (file:///game/main.ts:12:5)", 20);
+        assert_eq!(lines[0], "Error: boom");
+        assert_eq!(lines[1], "    at update (/game");
+        assert_eq!(lines[2], "/main.ts:12:5)");
+    }
+
+    #[test]
+    fn test_boundary_replays_last_good_frame_until_success() {
+        let mut boundary = FrameErrorBoundary::new();
+        let mut good = vec![quad(1, 0.0, 0.0, 4.0, 4.0, [0.0, 0.0, 1.0, 1.0], [1.0; 4], 0)];
+        boundary.settle_sprites(&mut good);
+
+        assert!(boundary.record_error("Error: boom"));
+        assert!(!boundary.record_error("Error: boom"));
+        let mut partial = Vec::new();
+        boundary.settle_sprites(&mut partial);
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial[0].texture_id, 1);
+
+        assert_eq!(boundary.record_success(), Some(2));
+        assert_eq!(boundary.record_success(), None);
+        assert!(boundary.error().is_none());
+    }
+
+    #[test]
+    fn test_overlay_is_screen_space_and_above_game_layers() {
+        let mut boundary = FrameErrorBoundary::new();
+        assert!(boundary.overlay_sprites(7, 8, 0.0, 0.0, 1.0, 800.0, 600.0).is_empty());
+
+        boundary.record_error("Hi");
+        let sprites = boundary.overlay_sprites(7, 8, 100.0, 50.0, 2.0, 800.0, 600.0);
+        let panel = &sprites[0];
+        assert_eq!(panel.texture_id, 8);
+        assert_eq!((panel.x, panel.y, panel.w), (100.0, 50.0, 400.0));
+        assert!(sprites.iter().all(|s| s.layer >= OVERLAY_LAYER));
+
+        // Last two glyphs are "Hi" on the second line
+        let h = &sprites[sprites.len() - 2];
+        assert_eq!(h.texture_id, 7);
+        assert_eq!((h.x, h.y), (100.0 + PADDING / 2.0, 50.0 + (PADDING + LINE_HEIGHT) / 2.0));
+        assert_eq!([h.uv_x, h.uv_y, h.uv_w, h.uv_h], glyph_uv('H'));
+    }
+}
@@ -0,0 +1,134 @@
+//! Hit flashes: sprites that briefly turn a solid color and fade back, the
+//! most common "you got hit" feedback.
+//!
+//! Game code flashes a key (an entity ID or any name it draws sprites with)
+//! once; every sprite drawn with that key gets the flash until it has faded.
+//! The store ages flashes with the frame's delta time, so they freeze while
+//! the simulation is paused. A cooldown stops rapid hits from restarting the
+//! flash every frame.
+
+use std::collections::HashMap;
+
+/// Color a sprite is mixed towards, unlit, by `amount` (0-1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteFlash {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub amount: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveFlash {
+    color: [f32; 3],
+    duration: f32,
+    /// Seconds from the start before the key can flash again.
+    cooldown: f32,
+    age: f32,
+}
+
+/// Flashes by key. Key 0 is "no key" and never flashes.
+#[derive(Debug, Clone, Default)]
+pub struct FlashStore {
+    flashes: HashMap<u32, ActiveFlash>,
+}
+
+impl FlashStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a flash on `key` that fades out over `duration` seconds. Returns
+    /// false, leaving the current flash alone, while the key is still within
+    /// the `cooldown` of its last flash.
+    pub fn trigger(&mut self, key: u32, color: [f32; 3], duration: f32, cooldown: f32) -> bool {
+        if key == 0 || duration <= 0.0 {
+            return false;
+        }
+        if let Some(active) = self.flashes.get(&key)
+            && active.age < active.cooldown
+        {
+            return false;
+        }
+        self.flashes.insert(key, ActiveFlash { color, duration, cooldown: cooldown.max(0.0), age: 0.0 });
+        true
+    }
+
+    /// Age flashes by `dt` seconds, dropping those faded and cooled down.
+    pub fn update(&mut self, dt: f32) {
+        self.flashes.retain(|_, f| {
+            f.age += dt;
+            f.age < f.duration || f.age < f.cooldown
+        });
+    }
+
+    /// The flash sprites drawn with `key` get now, if any: full strength at
+    /// the start, fading linearly to nothing over the duration.
+    pub fn sample(&self, key: u32) -> Option<SpriteFlash> {
+        if key == 0 {
+            return None;
+        }
+        let f = self.flashes.get(&key)?;
+        let amount = 1.0 - f.age / f.duration;
+        (amount > 0.0).then(|| SpriteFlash { r: f.color[0], g: f.color[1], b: f.color[2], amount })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flashes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.flashes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+
+    #[test]
+    fn test_flash_fades_out_over_its_duration() {
+        let mut store = FlashStore::new();
+        assert!(store.trigger(7, WHITE, 0.2, 0.0));
+        assert_eq!(store.sample(7).unwrap().amount, 1.0);
+        store.update(0.1);
+        assert!((store.sample(7).unwrap().amount - 0.5).abs() < 1e-5);
+        assert_eq!(store.sample(8), None);
+        store.update(0.1);
+        assert_eq!(store.sample(7), None);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_cooldown_blocks_retriggering() {
+        let mut store = FlashStore::new();
+        assert!(store.trigger(3, WHITE, 0.1, 0.5));
+        store.update(0.2);
+        // Faded, but still cooling down: no new flash
+        assert!(!store.trigger(3, [1.0, 0.0, 0.0], 0.1, 0.5));
+        assert_eq!(store.sample(3), None);
+        store.update(0.3);
+        assert!(store.trigger(3, [1.0, 0.0, 0.0], 0.1, 0.5));
+        assert_eq!(store.sample(3).unwrap().g, 0.0);
+    }
+
+    #[test]
+    fn test_no_cooldown_restarts_the_flash() {
+        let mut store = FlashStore::new();
+        store.trigger(1, WHITE, 0.2, 0.0);
+        store.update(0.15);
+        assert!(store.trigger(1, WHITE, 0.2, 0.0));
+        assert_eq!(store.sample(1).unwrap().amount, 1.0);
+    }
+
+    #[test]
+    fn test_key_zero_and_empty_durations_never_flash() {
+        let mut store = FlashStore::new();
+        assert!(!store.trigger(0, WHITE, 0.2, 0.0));
+        assert!(!store.trigger(5, WHITE, 0.0, 1.0));
+        assert_eq!(store.sample(0), None);
+        assert!(store.is_empty());
+    }
+}
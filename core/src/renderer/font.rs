@@ -243,6 +243,21 @@ pub fn generate_builtin_font() -> (Vec<u8>, u32, u32) {
     (pixels, width, height)
 }
 
+/// Normalized UV rect `[x, y, w, h]` of a glyph in the built-in font texture.
+/// Characters outside ASCII 32..127 map to `?`.
+pub fn glyph_uv(ch: char) -> [f32; 4] {
+    let code = ch as u32;
+    let glyph_idx = if (32..128).contains(&code) { code - 32 } else { '?' as u32 - 32 };
+    let col = glyph_idx % ATLAS_COLUMNS;
+    let row = glyph_idx / ATLAS_COLUMNS;
+    [
+        col as f32 / ATLAS_COLUMNS as f32,
+        row as f32 / ATLAS_ROWS as f32,
+        1.0 / ATLAS_COLUMNS as f32,
+        1.0 / ATLAS_ROWS as f32,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,4 +299,11 @@ mod tests {
         }
         assert!(has_opaque, "'A' glyph should have opaque pixels");
     }
+
+    #[test]
+    fn glyph_uv_matches_atlas_layout() {
+        // 'A' = glyph 33 → col 1, row 2
+        assert_eq!(glyph_uv('A'), [1.0 / 16.0, 2.0 / 6.0, 1.0 / 16.0, 1.0 / 6.0]);
+        assert_eq!(glyph_uv('é'), glyph_uv('?'));
+    }
 }
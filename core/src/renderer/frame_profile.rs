@@ -0,0 +1,277 @@
+//! Dev-mode frame profiler: where each frame's time went, per subsystem.
+//!
+//! The dev loop records one [`FrameProfile`] per frame: TS frame callback
+//! time (physics steps included), time spent inside `op_physics_step`, CPU
+//! time encoding the render passes and submitting them to the GPU queue,
+//! audio commands sent and texture bytes streamed to the GPU. With a render
+//! thread, encode and submit times are those of the previous frame, the one
+//! rendered while this frame's script ran.
+//!
+//! The last [`PROFILE_WINDOW`] frames are kept for averages and peaks,
+//! served by the inspector (`GET /profile`, MCP `get_profile`) and drawn in
+//! the corner of the window by `arcane dev --stats`.
+//!
+//! Output shape of `to_json`:
+//! `{"frames":n,"last":{..},"average":{..},"peak":{..}}`, each profile being
+//! `{"script_ms","physics_ms","encode_ms","submit_ms","audio_commands","texture_upload_bytes"}`.
+
+use std::collections::VecDeque;
+
+use super::error_overlay::{quad, GLYPH_SIZE, LINE_HEIGHT, OVERLAY_LAYER, PADDING};
+use super::font::glyph_uv;
+use super::SpriteCommand;
+
+/// Frames kept for averages and peaks (two seconds at 60 fps).
+pub const PROFILE_WINDOW: usize = 120;
+
+/// CPU time the renderer spent on its last frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderTimings {
+    /// Recording the frame's render passes.
+    pub encode_ms: f64,
+    /// `queue.submit` of the finished frame.
+    pub submit_ms: f64,
+}
+
+/// Where one frame's time went.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameProfile {
+    /// TS frame callback, physics steps included.
+    pub script_ms: f64,
+    /// `op_physics_step` calls.
+    pub physics_ms: f64,
+    pub encode_ms: f64,
+    pub submit_ms: f64,
+    pub audio_commands: u32,
+    /// Pixels streamed to textures this frame.
+    pub texture_upload_bytes: u64,
+}
+
+impl FrameProfile {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"script_ms\":{:.3},\"physics_ms\":{:.3},\"encode_ms\":{:.3},\"submit_ms\":{:.3},\"audio_commands\":{},\"texture_upload_bytes\":{}}}",
+            self.script_ms, self.physics_ms, self.encode_ms, self.submit_ms, self.audio_commands, self.texture_upload_bytes
+        )
+    }
+}
+
+/// The last [`PROFILE_WINDOW`] frame profiles.
+#[derive(Debug, Clone, Default)]
+pub struct FrameProfiler {
+    frames: VecDeque<FrameProfile>,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a frame, dropping the oldest once the window is full.
+    pub fn record(&mut self, profile: FrameProfile) {
+        if self.frames.len() == PROFILE_WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(profile);
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Frames in the window.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn last(&self) -> FrameProfile {
+        self.frames.back().copied().unwrap_or_default()
+    }
+
+    /// Mean of every field over the window (counts rounded down).
+    pub fn average(&self) -> FrameProfile {
+        let n = self.frames.len().max(1);
+        let mut sum = FrameProfile::default();
+        let (mut audio, mut upload) = (0u64, 0u64);
+        for f in &self.frames {
+            sum.script_ms += f.script_ms;
+            sum.physics_ms += f.physics_ms;
+            sum.encode_ms += f.encode_ms;
+            sum.submit_ms += f.submit_ms;
+            audio += f.audio_commands as u64;
+            upload += f.texture_upload_bytes;
+        }
+        FrameProfile {
+            script_ms: sum.script_ms / n as f64,
+            physics_ms: sum.physics_ms / n as f64,
+            encode_ms: sum.encode_ms / n as f64,
+            submit_ms: sum.submit_ms / n as f64,
+            audio_commands: (audio / n as u64) as u32,
+            texture_upload_bytes: upload / n as u64,
+        }
+    }
+
+    /// Largest value of every field over the window, each from its own frame.
+    pub fn peak(&self) -> FrameProfile {
+        self.frames.iter().fold(FrameProfile::default(), |p, f| FrameProfile {
+            script_ms: p.script_ms.max(f.script_ms),
+            physics_ms: p.physics_ms.max(f.physics_ms),
+            encode_ms: p.encode_ms.max(f.encode_ms),
+            submit_ms: p.submit_ms.max(f.submit_ms),
+            audio_commands: p.audio_commands.max(f.audio_commands),
+            texture_upload_bytes: p.texture_upload_bytes.max(f.texture_upload_bytes),
+        })
+    }
+
+    /// JSON report (see module docs).
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"frames\":{},\"last\":{},\"average\":{},\"peak\":{}}}",
+            self.frames.len(),
+            self.last().to_json(),
+            self.average().to_json(),
+            self.peak().to_json()
+        )
+    }
+
+    /// Text of the `--stats` overlay: averages with peaks over the window.
+    pub fn overlay_lines(&self) -> Vec<String> {
+        let (avg, peak) = (self.average(), self.peak());
+        let ms = |name: &str, a: f64, p: f64| format!("{name:<8}{a:>6.2}ms max{p:>6.2}");
+        vec![
+            format!("frame profile ({} frames)", self.frames.len()),
+            ms("script", avg.script_ms, peak.script_ms),
+            ms("physics", avg.physics_ms, peak.physics_ms),
+            ms("encode", avg.encode_ms, peak.encode_ms),
+            ms("submit", avg.submit_ms, peak.submit_ms),
+            format!("{:<8}{:>6} cmds max{:>4}", "audio", avg.audio_commands, peak.audio_commands),
+            format!("{:<8}{:>6}KB  max{:>6}KB", "upload", avg.texture_upload_bytes / 1024, peak.texture_upload_bytes / 1024),
+        ]
+    }
+
+    /// Sprites drawing the overlay in the top-right corner of the screen
+    /// (camera top-left at `cam_x`/`cam_y`), above the game and below the
+    /// error overlay.
+    pub fn overlay_sprites(
+        &self,
+        font_texture: u32,
+        panel_texture: u32,
+        cam_x: f32,
+        cam_y: f32,
+        zoom: f32,
+        viewport_w: f32,
+    ) -> Vec<SpriteCommand> {
+        let zoom = if zoom > 0.0 { zoom } else { 1.0 };
+        let lines = self.overlay_lines();
+        let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let panel_w = PADDING * 2.0 + columns as f32 * GLYPH_SIZE;
+        let panel_h = PADDING * 2.0 + lines.len() as f32 * LINE_HEIGHT;
+        let left = (viewport_w - panel_w).max(0.0);
+
+        // Screen pixels → world units under the current camera
+        let to_world = |sx: f32, sy: f32| (cam_x + sx / zoom, cam_y + sy / zoom);
+        let (px, py) = to_world(left, 0.0);
+        let mut sprites = vec![quad(
+            panel_texture,
+            px,
+            py,
+            panel_w / zoom,
+            panel_h / zoom,
+            [0.0, 0.0, 1.0, 1.0],
+            [0.0, 0.0, 0.0, 0.7],
+            OVERLAY_LAYER - 2,
+        )];
+        for (row, line) in lines.iter().enumerate() {
+            // Header in yellow, readings in white
+            let tint = if row == 0 { [1.0, 0.85, 0.3, 1.0] } else { [1.0, 1.0, 1.0, 1.0] };
+            let sy = PADDING + row as f32 * LINE_HEIGHT;
+            for (col, ch) in line.chars().enumerate() {
+                if ch == ' ' {
+                    continue;
+                }
+                let (x, y) = to_world(left + PADDING + col as f32 * GLYPH_SIZE, sy);
+                sprites.push(quad(
+                    font_texture,
+                    x,
+                    y,
+                    GLYPH_SIZE / zoom,
+                    GLYPH_SIZE / zoom,
+                    glyph_uv(ch),
+                    tint,
+                    OVERLAY_LAYER - 1,
+                ));
+            }
+        }
+        sprites
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(script_ms: f64, audio_commands: u32) -> FrameProfile {
+        FrameProfile { script_ms, audio_commands, ..Default::default() }
+    }
+
+    #[test]
+    fn test_window_keeps_the_most_recent_frames() {
+        let mut profiler = FrameProfiler::new();
+        assert_eq!(profiler.last(), FrameProfile::default());
+        for i in 0..PROFILE_WINDOW + 5 {
+            profiler.record(frame(i as f64, 0));
+        }
+        assert_eq!(profiler.len(), PROFILE_WINDOW);
+        assert_eq!(profiler.last().script_ms, (PROFILE_WINDOW + 4) as f64);
+        assert_eq!(profiler.peak().script_ms, (PROFILE_WINDOW + 4) as f64);
+        // Frames 5..=124 remain
+        assert_eq!(profiler.average().script_ms, (5 + PROFILE_WINDOW + 4) as f64 / 2.0);
+    }
+
+    #[test]
+    fn test_average_and_peak_per_field() {
+        let mut profiler = FrameProfiler::new();
+        profiler.record(FrameProfile { script_ms: 2.0, physics_ms: 1.0, audio_commands: 3, texture_upload_bytes: 100, ..Default::default() });
+        profiler.record(FrameProfile { script_ms: 4.0, encode_ms: 0.5, audio_commands: 0, texture_upload_bytes: 300, ..Default::default() });
+        let avg = profiler.average();
+        assert_eq!((avg.script_ms, avg.physics_ms, avg.encode_ms), (3.0, 0.5, 0.25));
+        assert_eq!((avg.audio_commands, avg.texture_upload_bytes), (1, 200));
+        let peak = profiler.peak();
+        assert_eq!((peak.script_ms, peak.physics_ms, peak.audio_commands, peak.texture_upload_bytes), (4.0, 1.0, 3, 300));
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let mut profiler = FrameProfiler::new();
+        profiler.record(frame(1.5, 2));
+        assert_eq!(
+            profiler.last().to_json(),
+            r#"{"script_ms":1.500,"physics_ms":0.000,"encode_ms":0.000,"submit_ms":0.000,"audio_commands":2,"texture_upload_bytes":0}"#
+        );
+        let json = profiler.to_json();
+        assert!(json.starts_with(r#"{"frames":1,"last":{"script_ms":1.500"#));
+        assert!(json.contains(r#""peak":{"script_ms":1.500"#));
+    }
+
+    #[test]
+    fn test_overlay_sits_in_the_top_right_corner() {
+        let mut profiler = FrameProfiler::new();
+        profiler.record(frame(1.0, 0));
+        let lines = profiler.overlay_lines();
+        assert_eq!(lines[1], "script    1.00ms max  1.00");
+        let columns = lines.iter().map(|l| l.len()).max().unwrap();
+
+        let sprites = profiler.overlay_sprites(7, 8, 100.0, 50.0, 2.0, 800.0);
+        let panel = &sprites[0];
+        let panel_w = PADDING * 2.0 + columns as f32 * GLYPH_SIZE;
+        assert_eq!(panel.texture_id, 8);
+        assert_eq!((panel.x + panel.w, panel.y), (100.0 + 800.0 / 2.0, 50.0));
+        assert_eq!(panel.w, panel_w / 2.0);
+        assert!(sprites.iter().all(|s| (OVERLAY_LAYER - 2..OVERLAY_LAYER).contains(&s.layer)));
+        assert!(sprites[1..].iter().all(|s| s.texture_id == 7));
+    }
+}
@@ -1,7 +1,11 @@
 /// Geometry batch renderer: draws colored triangles and thick lines
-/// without textures, using a single TriangleList pipeline.
+/// with an untextured TriangleList pipeline, plus textured triangle meshes
+/// (`GeoCommand::Mesh`) with a second pipeline that samples a texture.
+/// Uploaded custom meshes (`GeoCommand::CustomMesh`) carry per-vertex colors
+/// and use the colored pipeline, or the textured one when they have a texture.
 ///
-/// Lines are expanded into quads (2 triangles) on the CPU side.
+/// Lines are expanded into quads (2 triangles) and mesh indices into
+/// triangle lists on the CPU side, where custom meshes are also transformed.
 ///
 /// ## Integration (Phase 2 — renderer/mod.rs + dev.rs)
 ///
@@ -42,7 +46,9 @@
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
-use super::gpu::GpuContext;
+use super::gpu::{multisample_state, ColorTarget, GpuContext};
+use super::texture::TextureStore;
+use crate::scripting::geometry_ops::{CustomMesh, GeoCommand};
 
 /// Per-vertex data for the geometry pipeline: position + RGBA color.
 #[repr(C)]
@@ -52,12 +58,164 @@ pub struct GeoVertex {
     pub color: [f32; 4],
 }
 
+/// Per-vertex data for textured meshes: position + UV + RGBA color.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct TexturedGeoVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+/// A contiguous run of vertices drawn with one pipeline/texture.
+enum GeoRun {
+    Colored { start: u32, end: u32 },
+    Textured { texture_id: u32, start: u32, end: u32 },
+}
+
 /// Maximum number of vertices per frame before flush.
 /// 65536 vertices = ~21845 triangles, more than enough for shape primitives.
 const MAX_VERTICES: usize = 65536;
 
+/// Shader modules and layouts the geometry pipelines are built from.
+struct GeoShaders {
+    colored: wgpu::ShaderModule,
+    colored_layout: wgpu::PipelineLayout,
+    textured: wgpu::ShaderModule,
+    textured_layout: wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
+}
+
+/// Colored and textured pipelines for one sample count.
+struct GeoPipelines {
+    colored: wgpu::RenderPipeline,
+    textured: wgpu::RenderPipeline,
+}
+
+impl GeoShaders {
+    /// Build both pipelines for `samples` per pixel.
+    fn build(&self, device: &wgpu::Device, samples: u32) -> GeoPipelines {
+        let vertex_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GeoVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // position: vec2<f32> at location 0
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // color: vec4<f32> at location 1
+                wgpu::VertexAttribute {
+                    offset: 8,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        };
+
+        let colored = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("geom_pipeline_x{samples}")),
+            layout: Some(&self.colored_layout),
+            vertex: wgpu::VertexState {
+                module: &self.colored,
+                entry_point: Some("vs_main"),
+                buffers: &[vertex_layout],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.colored,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: self.surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: multisample_state(samples),
+            multiview: None,
+            cache: None,
+        });
+
+        let textured_vertex_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TexturedGeoVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // position: vec2<f32> at location 0
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // uv: vec2<f32> at location 1
+                wgpu::VertexAttribute {
+                    offset: 8,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // color: vec4<f32> at location 2
+                wgpu::VertexAttribute {
+                    offset: 16,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        };
+
+        let textured = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("geom_textured_pipeline_x{samples}")),
+            layout: Some(&self.textured_layout),
+            vertex: wgpu::VertexState {
+                module: &self.textured,
+                entry_point: Some("vs_main"),
+                buffers: &[textured_vertex_layout],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.textured,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: self.surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: multisample_state(samples),
+            multiview: None,
+            cache: None,
+        });
+
+        GeoPipelines { colored, textured }
+    }
+}
+
 pub struct GeometryBatch {
-    pipeline: wgpu::RenderPipeline,
+    shaders: GeoShaders,
+    pipelines: GeoPipelines,
+    /// Pipelines for the MSAA sample count in use, if any.
+    msaa_pipelines: Option<(u32, GeoPipelines)>,
     vertices: Vec<GeoVertex>,
 }
 
@@ -105,65 +263,81 @@ impl GeometryBatch {
                     push_constant_ranges: &[],
                 });
 
-        let vertex_layout = wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<GeoVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                // position: vec2<f32> at location 0
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x2,
-                },
-                // color: vec4<f32> at location 1
-                wgpu::VertexAttribute {
-                    offset: 8,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x4,
-                },
-            ],
-        };
-
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("geom_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[vertex_layout],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
+        // Textured mesh pipeline: same camera layout plus the sprite texture layout
+        // (identical layouts are compatible, so TextureStore bind groups work here).
+        let textured_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("geom_textured_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/geom_textured.wgsl").into()),
         });
 
+        let texture_bgl =
+            device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("geom_texture_bind_group_layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                });
+
+        let textured_layout =
+            device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("geom_textured_pipeline_layout"),
+                    bind_group_layouts: &[&camera_bgl, &texture_bgl],
+                    push_constant_ranges: &[],
+                });
+
+        let shaders = GeoShaders {
+            colored: shader,
+            colored_layout: pipeline_layout,
+            textured: textured_shader,
+            textured_layout,
+            surface_format,
+        };
+        let pipelines = shaders.build(device, 1);
+
         Self {
-            pipeline,
+            shaders,
+            pipelines,
+            msaa_pipelines: None,
             vertices: Vec::with_capacity(MAX_VERTICES),
         }
     }
 
+    /// Build pipelines for multisampled targets with `samples` per pixel;
+    /// 1 drops them. Single-sampled pipelines are always kept.
+    pub fn set_msaa_samples(&mut self, device: &wgpu::Device, samples: u32) {
+        if samples <= 1 {
+            self.msaa_pipelines = None;
+        } else if self.msaa_pipelines.as_ref().map(|(n, _)| *n) != Some(samples) {
+            self.msaa_pipelines = Some((samples, self.shaders.build(device, samples)));
+        }
+    }
+
+    /// Pipelines matching a target's sample count, if built.
+    fn pipelines_for(&self, samples: u32) -> Option<&GeoPipelines> {
+        match &self.msaa_pipelines {
+            _ if samples == 1 => Some(&self.pipelines),
+            Some((n, pipelines)) if *n == samples => Some(pipelines),
+            _ => None,
+        }
+    }
+
     /// Push a single colored triangle (3 vertices).
     pub fn add_triangle(
         &mut self,
@@ -222,13 +396,18 @@ impl GeometryBatch {
 
     /// Upload vertices and draw. Call after all add_triangle/add_line for this frame.
     /// Does NOT clear the render target (uses LoadOp::Load to layer over sprites).
-    pub fn flush(
+    pub fn flush<'t>(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
-        target: &wgpu::TextureView,
+        target: impl Into<ColorTarget<'t>>,
         camera_bind_group: &wgpu::BindGroup,
     ) {
+        let target = target.into();
+        let Some(pipelines) = self.pipelines_for(target.samples) else {
+            self.vertices.clear();
+            return;
+        };
         if self.vertices.is_empty() {
             return;
         }
@@ -245,7 +424,7 @@ impl GeometryBatch {
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("geom_render_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: target,
+                    view: target.view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Load, // don't clear — overlay on top of sprites
@@ -256,8 +435,9 @@ impl GeometryBatch {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
+            target.apply_viewport(&mut pass);
 
-            pass.set_pipeline(&self.pipeline);
+            pass.set_pipeline(&pipelines.colored);
             pass.set_bind_group(0, camera_bind_group, &[]);
             pass.set_vertex_buffer(0, vertex_buffer.slice(..));
             pass.draw(0..vertex_count, 0..1);
@@ -267,72 +447,117 @@ impl GeometryBatch {
     }
 
     /// Render a slice of GeoCommands with configurable load op.
+    /// Textured meshes are skipped (no texture store available);
+    /// use `flush_commands_textured` to draw them.
     ///
     /// `clear_color`: `Some(color)` → `LoadOp::Clear(color)` (first pass),
     ///                 `None` → `LoadOp::Load` (subsequent passes).
-    pub fn flush_commands(
+    pub fn flush_commands<'t>(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: impl Into<ColorTarget<'t>>,
+        camera_bind_group: &wgpu::BindGroup,
+        commands: &[GeoCommand],
+        clear_color: Option<wgpu::Color>,
+    ) {
+        self.render_commands(device, encoder, target.into(), camera_bind_group, None, commands, clear_color);
+    }
+
+    /// Render a slice of GeoCommands including textured meshes, which bind
+    /// their texture from `textures`. Meshes with unknown textures are skipped.
+    /// Submission order is preserved across colored and textured commands.
+    #[allow(clippy::too_many_arguments)]
+    pub fn flush_commands_textured<'t>(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
-        target: &wgpu::TextureView,
+        target: impl Into<ColorTarget<'t>>,
         camera_bind_group: &wgpu::BindGroup,
-        commands: &[crate::scripting::geometry_ops::GeoCommand],
+        textures: &TextureStore,
+        commands: &[GeoCommand],
+        clear_color: Option<wgpu::Color>,
+    ) {
+        self.render_commands(device, encoder, target.into(), camera_bind_group, Some(textures), commands, clear_color);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_commands(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: ColorTarget<'_>,
+        camera_bind_group: &wgpu::BindGroup,
+        textures: Option<&TextureStore>,
+        commands: &[GeoCommand],
         clear_color: Option<wgpu::Color>,
     ) {
         if commands.is_empty() {
             return;
         }
+        let Some(pipelines) = self.pipelines_for(target.samples) else { return };
 
-        // Convert GeoCommands to vertices
+        // Convert GeoCommands to vertices, grouped into runs per pipeline/texture
         let mut verts: Vec<GeoVertex> = Vec::new();
+        let mut tex_verts: Vec<TexturedGeoVertex> = Vec::new();
+        let mut runs: Vec<GeoRun> = Vec::new();
         for cmd in commands {
+            let Some(texture_id) = cmd.texture_id() else {
+                if push_colored_vertices(&mut verts, cmd) {
+                    push_colored_run(&mut runs, verts.len() as u32);
+                }
+                continue;
+            };
+            let Some(textures) = textures else { continue };
+            if textures.get_bind_group(texture_id).is_none() {
+                continue;
+            }
+            let start = tex_verts.len() as u32;
             match cmd {
-                crate::scripting::geometry_ops::GeoCommand::Triangle {
-                    x1, y1, x2, y2, x3, y3, r, g, b, a, ..
-                } => {
-                    let color = [*r, *g, *b, *a];
-                    verts.push(GeoVertex { position: [*x1, *y1], color });
-                    verts.push(GeoVertex { position: [*x2, *y2], color });
-                    verts.push(GeoVertex { position: [*x3, *y3], color });
+                GeoCommand::Mesh { vertices, indices, r, g, b, a, .. } => {
+                    expand_mesh(&mut tex_verts, vertices, indices, [*r, *g, *b, *a]);
                 }
-                crate::scripting::geometry_ops::GeoCommand::LineSeg {
-                    x1, y1, x2, y2, thickness, r, g, b, a, ..
-                } => {
-                    let dx = x2 - x1;
-                    let dy = y2 - y1;
-                    let len = (dx * dx + dy * dy).sqrt();
-                    if len < 1e-8 {
-                        continue;
-                    }
-                    let half = thickness * 0.5;
-                    let nx = -dy / len * half;
-                    let ny = dx / len * half;
-                    let color = [*r, *g, *b, *a];
-                    let a0 = GeoVertex { position: [x1 + nx, y1 + ny], color };
-                    let b0 = GeoVertex { position: [x1 - nx, y1 - ny], color };
-                    let c0 = GeoVertex { position: [x2 - nx, y2 - ny], color };
-                    let d0 = GeoVertex { position: [x2 + nx, y2 + ny], color };
-                    verts.push(a0);
-                    verts.push(b0);
-                    verts.push(c0);
-                    verts.push(a0);
-                    verts.push(c0);
-                    verts.push(d0);
+                GeoCommand::CustomMesh { mesh, transform, r, g, b, a, .. } => {
+                    let tint = [*r, *g, *b, *a];
+                    tex_verts.extend(mesh.indices.iter().map(|&i| {
+                        let (position, uv, color) = mesh.vertex(i, transform, tint);
+                        TexturedGeoVertex { position, uv, color }
+                    }));
+                }
+                _ => {}
+            }
+            let end = tex_verts.len() as u32;
+            if end == start {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(GeoRun::Textured { texture_id: id, end: run_end, .. })
+                    if *id == texture_id && *run_end == start =>
+                {
+                    *run_end = end;
                 }
+                _ => runs.push(GeoRun::Textured { texture_id, start, end }),
             }
         }
 
-        if verts.is_empty() {
+        if runs.is_empty() {
             return;
         }
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("geom_vertex_buffer"),
-            contents: bytemuck::cast_slice(&verts),
-            usage: wgpu::BufferUsages::VERTEX,
+        let vertex_buffer = (!verts.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("geom_vertex_buffer"),
+                contents: bytemuck::cast_slice(&verts),
+                usage: wgpu::BufferUsages::VERTEX,
+            })
+        });
+        let textured_buffer = (!tex_verts.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("geom_textured_vertex_buffer"),
+                contents: bytemuck::cast_slice(&tex_verts),
+                usage: wgpu::BufferUsages::VERTEX,
+            })
         });
-
-        let vertex_count = verts.len() as u32;
 
         let load_op = match clear_color {
             Some(color) => wgpu::LoadOp::Clear(color),
@@ -343,7 +568,7 @@ impl GeometryBatch {
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("geom_render_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: target,
+                    view: target.view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: load_op,
@@ -354,11 +579,34 @@ impl GeometryBatch {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
+            target.apply_viewport(&mut pass);
 
-            pass.set_pipeline(&self.pipeline);
             pass.set_bind_group(0, camera_bind_group, &[]);
-            pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            pass.draw(0..vertex_count, 0..1);
+            let mut textured_bound = None;
+            for run in &runs {
+                match run {
+                    GeoRun::Colored { start, end } => {
+                        let Some(buffer) = &vertex_buffer else { continue };
+                        pass.set_pipeline(&pipelines.colored);
+                        pass.set_vertex_buffer(0, buffer.slice(..));
+                        textured_bound = None;
+                        pass.draw(*start..*end, 0..1);
+                    }
+                    GeoRun::Textured { texture_id, start, end } => {
+                        let (Some(buffer), Some(textures)) = (&textured_buffer, textures) else { continue };
+                        let Some(bind_group) = textures.get_bind_group(*texture_id) else { continue };
+                        if textured_bound.is_none() {
+                            pass.set_pipeline(&pipelines.textured);
+                            pass.set_vertex_buffer(0, buffer.slice(..));
+                        }
+                        if textured_bound != Some(*texture_id) {
+                            pass.set_bind_group(1, bind_group, &[]);
+                            textured_bound = Some(*texture_id);
+                        }
+                        pass.draw(*start..*end, 0..1);
+                    }
+                }
+            }
         }
     }
 
@@ -368,6 +616,90 @@ impl GeometryBatch {
     }
 }
 
+/// Expand a triangle, line segment or untextured custom mesh into colored
+/// vertices. Returns false (and pushes nothing) for textured meshes and
+/// zero-length lines.
+pub(super) fn push_colored_vertices(out: &mut Vec<GeoVertex>, cmd: &GeoCommand) -> bool {
+    match cmd {
+        GeoCommand::Triangle {
+            x1, y1, x2, y2, x3, y3, r, g, b, a, ..
+        } => {
+            let color = [*r, *g, *b, *a];
+            out.push(GeoVertex { position: [*x1, *y1], color });
+            out.push(GeoVertex { position: [*x2, *y2], color });
+            out.push(GeoVertex { position: [*x3, *y3], color });
+            true
+        }
+        GeoCommand::LineSeg {
+            x1, y1, x2, y2, thickness, r, g, b, a, ..
+        } => {
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            let len = (dx * dx + dy * dy).sqrt();
+            if len < 1e-8 {
+                return false;
+            }
+            let half = thickness * 0.5;
+            let nx = -dy / len * half;
+            let ny = dx / len * half;
+            let color = [*r, *g, *b, *a];
+            let a0 = GeoVertex { position: [x1 + nx, y1 + ny], color };
+            let b0 = GeoVertex { position: [x1 - nx, y1 - ny], color };
+            let c0 = GeoVertex { position: [x2 - nx, y2 - ny], color };
+            let d0 = GeoVertex { position: [x2 + nx, y2 + ny], color };
+            out.extend([a0, b0, c0, a0, c0, d0]);
+            true
+        }
+        GeoCommand::CustomMesh { mesh, transform, r, g, b, a, .. } if mesh.texture_id == 0 => {
+            push_custom_mesh(out, mesh, transform, [*r, *g, *b, *a]);
+            true
+        }
+        GeoCommand::Mesh { .. } | GeoCommand::CustomMesh { .. } => false,
+    }
+}
+
+/// Expand an uploaded mesh into colored vertices under `transform`.
+fn push_custom_mesh(out: &mut Vec<GeoVertex>, mesh: &CustomMesh, transform: &[f32; 6], tint: [f32; 4]) {
+    out.extend(mesh.indices.iter().map(|&i| {
+        let (position, _, color) = mesh.vertex(i, transform, tint);
+        GeoVertex { position, color }
+    }));
+}
+
+/// Extend the trailing colored run to `end`, or start a new one.
+fn push_colored_run(runs: &mut Vec<GeoRun>, end: u32) {
+    match runs.last_mut() {
+        Some(GeoRun::Colored { end: run_end, .. }) => *run_end = end,
+        _ => {
+            // Colored vertices share one buffer: resume after the last colored run
+            let start = runs
+                .iter()
+                .rev()
+                .find_map(|r| match r {
+                    GeoRun::Colored { end, .. } => Some(*end),
+                    _ => None,
+                })
+                .unwrap_or(0);
+            runs.push(GeoRun::Colored { start, end });
+        }
+    }
+}
+
+/// Expand an indexed (x, y, u, v) mesh into a triangle list.
+/// Triangles referencing out-of-range vertices are dropped.
+fn expand_mesh(out: &mut Vec<TexturedGeoVertex>, vertices: &[f32], indices: &[u16], color: [f32; 4]) {
+    let count = vertices.len() / 4;
+    for tri in indices.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= count) {
+            continue;
+        }
+        for &i in tri {
+            let v = &vertices[i as usize * 4..i as usize * 4 + 4];
+            out.push(TexturedGeoVertex { position: [v[0], v[1]], uv: [v[2], v[3]], color });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,6 +710,63 @@ mod tests {
         assert_eq!(std::mem::size_of::<GeoVertex>(), 24);
     }
 
+    #[test]
+    fn textured_vertex_is_32_bytes() {
+        assert_eq!(std::mem::size_of::<TexturedGeoVertex>(), 32);
+    }
+
+    #[test]
+    fn expand_mesh_drops_out_of_range_triangles() {
+        let verts = [0.0, 0.0, 0.0, 0.0, 10.0, 0.0, 1.0, 0.0, 0.0, 10.0, 0.0, 1.0];
+        let mut out = Vec::new();
+        expand_mesh(&mut out, &verts, &[0, 1, 2, 0, 2, 7, 2, 1], [1.0; 4]);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[1].position, [10.0, 0.0]);
+        assert_eq!(out[2].uv, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn untextured_custom_mesh_expands_to_colored_vertices() {
+        let verts = vec![
+            0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0,
+            4.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0,
+            0.0, 4.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0,
+        ];
+        let mesh = std::sync::Arc::new(CustomMesh::new(verts, &[0, 1, 2, 2, 1, 0], 0).unwrap());
+        let cmd = GeoCommand::CustomMesh {
+            mesh: mesh.clone(),
+            transform: CustomMesh::transform(10.0, 20.0, 0.0, 2.0, 1.0),
+            r: 1.0, g: 1.0, b: 1.0, a: 0.5,
+            layer: 0,
+        };
+        let mut out = Vec::new();
+        assert!(push_colored_vertices(&mut out, &cmd));
+        assert_eq!(out.len(), 6);
+        assert_eq!(out[1].position, [18.0, 20.0]);
+        assert_eq!(out[1].color, [0.0, 1.0, 0.0, 0.5]);
+        assert_eq!(out[2].position, [10.0, 24.0]);
+
+        let textured = GeoCommand::CustomMesh {
+            mesh: std::sync::Arc::new(CustomMesh::new(mesh.vertices.clone(), &[0, 1, 2], 3).unwrap()),
+            transform: CustomMesh::transform(0.0, 0.0, 0.0, 1.0, 1.0),
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 0,
+        };
+        assert!(!push_colored_vertices(&mut out, &textured));
+        assert_eq!(out.len(), 6);
+    }
+
+    #[test]
+    fn colored_runs_continue_after_textured_run() {
+        let mut runs = Vec::new();
+        push_colored_run(&mut runs, 3);
+        runs.push(GeoRun::Textured { texture_id: 1, start: 0, end: 6 });
+        push_colored_run(&mut runs, 9);
+        push_colored_run(&mut runs, 12);
+        assert_eq!(runs.len(), 3);
+        assert!(matches!(runs[2], GeoRun::Colored { start: 3, end: 12 }));
+    }
+
     #[test]
     fn line_quad_geometry_is_correct() {
         // Verify the perpendicular math for a horizontal line
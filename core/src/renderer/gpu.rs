@@ -5,8 +5,79 @@ use std::sync::Arc;
 pub struct GpuContext {
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub surface: wgpu::Surface<'static>,
+    /// The window surface. None when rendering headless.
+    pub surface: Option<wgpu::Surface<'static>>,
+    /// Stands in for the surface when headless: sized and formatted by `config`.
+    pub offscreen: Option<wgpu::Texture>,
     pub config: wgpu::SurfaceConfiguration,
+    /// Adapter name, backend and driver (reported by `op_get_engine_info`).
+    pub adapter_info: wgpu::AdapterInfo,
+    /// MSAA sample counts the color format supports, ascending (1 is always there).
+    pub sample_counts: Vec<u32>,
+}
+
+/// The color attachment a pipeline draws into: a view and its sample count.
+/// Pipelines pick the variant built for that count. A bare view converts to
+/// a single-sampled target.
+#[derive(Clone, Copy)]
+pub struct ColorTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub samples: u32,
+    /// Mask stencil buffer with the same size and sample count, if masks are
+    /// in use (see `mask`). Only sprite passes with masked sprites attach it.
+    pub stencil: Option<&'a wgpu::TextureView>,
+    /// Pixel rect `(x, y, w, h)` passes draw into, for split-screen
+    /// viewports; `None` draws over the whole target. Clip space maps onto
+    /// the rect and nothing outside it is touched, except by clears.
+    pub viewport: Option<(u32, u32, u32, u32)>,
+}
+
+impl<'a> ColorTarget<'a> {
+    /// A multisampled target (resolved by whoever owns it).
+    pub fn multisampled(view: &'a wgpu::TextureView, samples: u32) -> Self {
+        Self { view, samples, stencil: None, viewport: None }
+    }
+
+    /// This target with a mask stencil buffer.
+    pub fn with_stencil(self, stencil: &'a wgpu::TextureView) -> Self {
+        Self { stencil: Some(stencil), ..self }
+    }
+
+    /// This target drawn only inside the pixel rect `(x, y, w, h)`.
+    pub fn with_viewport(self, rect: (u32, u32, u32, u32)) -> Self {
+        Self { viewport: Some(rect), ..self }
+    }
+
+    /// Point `pass` at the viewport rect, if any: call right after beginning
+    /// a pass on this target.
+    pub fn apply_viewport(&self, pass: &mut wgpu::RenderPass<'_>) {
+        if let Some((x, y, w, h)) = self.viewport {
+            pass.set_viewport(x as f32, y as f32, w as f32, h as f32, 0.0, 1.0);
+            pass.set_scissor_rect(x, y, w, h);
+        }
+    }
+
+    /// `rect` cut to the viewport rect; `None` when nothing of it is left.
+    pub fn scissor(&self, rect: (u32, u32, u32, u32)) -> Option<(u32, u32, u32, u32)> {
+        let Some((vx, vy, vw, vh)) = self.viewport else {
+            return (rect.2 > 0 && rect.3 > 0).then_some(rect);
+        };
+        let (x0, y0) = (rect.0.max(vx), rect.1.max(vy));
+        let x1 = (rect.0 + rect.2).min(vx + vw);
+        let y1 = (rect.1 + rect.3).min(vy + vh);
+        (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+    }
+}
+
+impl<'a> From<&'a wgpu::TextureView> for ColorTarget<'a> {
+    fn from(view: &'a wgpu::TextureView) -> Self {
+        Self { view, samples: 1, stencil: None, viewport: None }
+    }
+}
+
+/// Multisample state for `samples` per pixel.
+pub fn multisample_state(samples: u32) -> wgpu::MultisampleState {
+    wgpu::MultisampleState { count: samples, ..Default::default() }
 }
 
 impl GpuContext {
@@ -28,10 +99,14 @@ impl GpuContext {
         }))
         .context("No suitable GPU adapter found")?;
 
+        // Timestamp queries let adaptive quality measure GPU frame time;
+        // adapter-specific format features allow 2x and 8x MSAA
+        let optional_features = adapter.features() & OPTIONAL_FEATURES;
+
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("arcane_device"),
-                required_features: wgpu::Features::empty(),
+                required_features: optional_features,
                 required_limits: wgpu::Limits::default(),
                 ..Default::default()
             },
@@ -60,12 +135,108 @@ impl GpuContext {
         };
 
         surface.configure(&device, &config);
+        let sample_counts = supported_sample_counts(&adapter, optional_features, config.format);
 
         Ok(Self {
             device,
             queue,
-            surface,
+            surface: Some(surface),
+            offscreen: None,
             config,
+            adapter_info: adapter.get_info(),
+            sample_counts,
         })
     }
+
+    /// Initialize wgpu without a window, rendering into an offscreen texture
+    /// of `width`×`height` physical pixels (see `arcane render`).
+    pub fn new_headless(width: u32, height: u32) -> Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .context("No suitable GPU adapter found")?;
+
+        let optional_features = adapter.features() & OPTIONAL_FEATURES;
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("arcane_headless_device"),
+                required_features: optional_features,
+                required_limits: wgpu::Limits::default(),
+                ..Default::default()
+            },
+            None,
+        ))
+        .context("Failed to create GPU device")?;
+
+        // Same color handling as a window: sRGB, RGBA so captures need no swizzle
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        let offscreen = create_offscreen(&device, &config);
+        let sample_counts = supported_sample_counts(&adapter, optional_features, config.format);
+
+        Ok(Self {
+            device,
+            queue,
+            surface: None,
+            offscreen: Some(offscreen),
+            config,
+            adapter_info: adapter.get_info(),
+            sample_counts,
+        })
+    }
+
+    /// Apply a size change in `config` to the surface or offscreen texture.
+    pub fn reconfigure(&mut self) {
+        if let Some(ref surface) = self.surface {
+            surface.configure(&self.device, &self.config);
+        } else {
+            self.offscreen = Some(create_offscreen(&self.device, &self.config));
+        }
+    }
+}
+
+/// Device features used when the adapter has them.
+const OPTIONAL_FEATURES: wgpu::Features =
+    wgpu::Features::TIMESTAMP_QUERY.union(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+
+/// Sample counts usable for `format`: 1 and 4 (WebGPU's guarantee) unless
+/// adapter-specific format features are enabled, then what the adapter reports.
+fn supported_sample_counts(adapter: &wgpu::Adapter, features: wgpu::Features, format: wgpu::TextureFormat) -> Vec<u32> {
+    if !features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+        return vec![1, 4];
+    }
+    adapter.get_texture_format_features(format).flags.supported_sample_counts()
+}
+
+fn create_offscreen(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("headless_surface"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: config.usage,
+        view_formats: &[],
+    })
 }
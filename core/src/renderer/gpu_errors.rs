@@ -0,0 +1,270 @@
+//! Capture of wgpu validation errors, attributed to the op that caused them.
+//!
+//! Pipeline creation for custom shaders, SDF expressions and effects runs
+//! inside a wgpu error scope; a failure is recorded with its source (shader ID,
+//! SDF expression, effect) so the dev loop can forward it to the TS console and
+//! the inspector. Errors outside any scope arrive through the device's
+//! uncaptured error handler instead of panicking.
+
+use std::sync::{Arc, Mutex};
+
+/// Distinct errors kept for the inspector; older ones are dropped.
+const MAX_RECENT: usize = 32;
+
+/// How much GPU work runs inside error scopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum GpuErrorCapture {
+    /// No scopes: errors are reported unattributed by the uncaptured handler.
+    Off,
+    /// Scope pipeline creation (custom shaders, SDF expressions, effects).
+    #[default]
+    Pipelines,
+    /// Also scope each frame's render passes and submit.
+    All,
+}
+
+impl GpuErrorCapture {
+    /// Mode from its op value (0 = off, 1 = pipelines, 2 = all).
+    pub fn from_u32(v: u32) -> Option<Self> {
+        match v {
+            0 => Some(Self::Off),
+            1 => Some(Self::Pipelines),
+            2 => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+/// What the GPU was doing when an error was raised.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpuErrorSource {
+    /// `createShader()`. WGSL line numbers in the message count the generated
+    /// preamble; subtract `line_offset` to get lines of the user's source.
+    Shader { id: u32, name: String, line_offset: u32 },
+    /// An SDF shape whose expression failed to compile.
+    Sdf { expr: String },
+    /// A built-in post-process effect.
+    Effect { id: u32, effect: String },
+    /// A custom screen transition shader (same `line_offset` rule as shaders).
+    Transition { id: u32, line_offset: u32 },
+    /// The frame's render passes or submit (`GpuErrorCapture::All` only).
+    Frame,
+    /// Raised outside any error scope.
+    Uncaptured,
+}
+
+impl GpuErrorSource {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Shader { .. } => "shader",
+            Self::Sdf { .. } => "sdf",
+            Self::Effect { .. } => "effect",
+            Self::Transition { .. } => "transition",
+            Self::Frame => "frame",
+            Self::Uncaptured => "uncaptured",
+        }
+    }
+
+    /// Human-readable origin, e.g. `shader 3 "water"`.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Shader { id, name, .. } => format!("shader {id} \"{name}\""),
+            Self::Sdf { expr } => format!("SDF expression `{expr}`"),
+            Self::Effect { id, effect } => format!("effect {id} ({effect})"),
+            Self::Transition { id, .. } => format!("transition shader {id}"),
+            Self::Frame => "frame".into(),
+            Self::Uncaptured => "uncaptured".into(),
+        }
+    }
+
+    fn json_fields(&self) -> String {
+        use crate::json::escape;
+        match self {
+            Self::Shader { id, name, line_offset } => format!(
+                ",\"id\":{id},\"name\":\"{}\",\"lineOffset\":{line_offset}",
+                escape(name)
+            ),
+            Self::Sdf { expr } => format!(",\"expr\":\"{}\"", escape(expr)),
+            Self::Effect { id, effect } => format!(",\"id\":{id},\"effect\":\"{}\"", escape(effect)),
+            Self::Transition { id, line_offset } => format!(",\"id\":{id},\"lineOffset\":{line_offset}"),
+            Self::Frame | Self::Uncaptured => String::new(),
+        }
+    }
+}
+
+/// A validation error and how many times it has been raised.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuError {
+    pub source: GpuErrorSource,
+    pub message: String,
+    pub count: u32,
+}
+
+impl GpuError {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"source\":\"{}\"{},\"context\":\"{}\",\"message\":\"{}\",\"count\":{}}}",
+            self.source.kind(),
+            self.source.json_fields(),
+            crate::json::escape(&self.source.describe()),
+            crate::json::escape(&self.message),
+            self.count
+        )
+    }
+}
+
+/// Errors as a JSON array, for `op_take_gpu_errors` and the inspector.
+pub fn errors_json(errors: &[GpuError]) -> String {
+    let items: Vec<String> = errors.iter().map(GpuError::to_json).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// Number of lines in `wgsl` before `user_source` starts (0 if not found).
+pub fn line_offset(wgsl: &str, user_source: &str) -> u32 {
+    wgsl.find(user_source)
+        .map(|i| wgsl[..i].matches('\n').count() as u32)
+        .unwrap_or(0)
+}
+
+/// Recorded GPU errors: new ones wait in `pending` for the dev loop, and the
+/// last [`MAX_RECENT`] distinct ones are kept with a repeat count.
+#[derive(Debug, Default)]
+pub struct GpuErrorLog {
+    pub capture: GpuErrorCapture,
+    recent: Vec<GpuError>,
+    pending: Vec<GpuError>,
+    uncaptured: Arc<Mutex<Vec<String>>>,
+}
+
+impl GpuErrorLog {
+    /// Create a log that also receives `device`'s uncaptured errors.
+    pub fn new(device: &wgpu::Device) -> Self {
+        let log = Self::default();
+        let uncaptured = log.uncaptured.clone();
+        device.on_uncaptured_error(Box::new(move |error| {
+            uncaptured.lock().unwrap_or_else(|e| e.into_inner()).push(error.to_string());
+        }));
+        log
+    }
+
+    /// Push a validation scope if `level` is being captured. Returns whether
+    /// one was pushed; pass that to [`Self::pop_scope`].
+    pub fn push_scope(&self, device: &wgpu::Device, level: GpuErrorCapture) -> bool {
+        if self.capture < level {
+            return false;
+        }
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        true
+    }
+
+    /// Pop a scope pushed by [`Self::push_scope`], recording its error against
+    /// `source`. Returns whether there was one.
+    pub fn pop_scope(&mut self, device: &wgpu::Device, pushed: bool, source: GpuErrorSource) -> bool {
+        if !pushed {
+            return false;
+        }
+        match pollster::block_on(device.pop_error_scope()) {
+            Some(error) => {
+                self.record(source, error.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Run `f` inside a scope at `level`. Returns whether it raised an error.
+    pub fn scoped(
+        &mut self,
+        device: &wgpu::Device,
+        level: GpuErrorCapture,
+        source: GpuErrorSource,
+        f: impl FnOnce(),
+    ) -> bool {
+        let pushed = self.push_scope(device, level);
+        f();
+        self.pop_scope(device, pushed, source)
+    }
+
+    /// Record an error. Repeats of a recent error only bump its count.
+    pub fn record(&mut self, source: GpuErrorSource, message: String) {
+        if let Some(existing) = self
+            .recent
+            .iter_mut()
+            .find(|e| e.source == source && e.message == message)
+        {
+            existing.count += 1;
+            return;
+        }
+        let error = GpuError { source, message, count: 1 };
+        self.pending.push(error.clone());
+        if self.recent.len() >= MAX_RECENT {
+            self.recent.remove(0);
+        }
+        self.recent.push(error);
+    }
+
+    /// Errors not yet taken, including any raised outside a scope.
+    pub fn take_pending(&mut self) -> Vec<GpuError> {
+        let uncaptured = std::mem::take(&mut *self.uncaptured.lock().unwrap_or_else(|e| e.into_inner()));
+        for message in uncaptured {
+            self.record(GpuErrorSource::Uncaptured, message);
+        }
+        std::mem::take(&mut self.pending)
+    }
+
+    /// The most recent distinct errors, oldest first.
+    pub fn recent(&self) -> &[GpuError] {
+        &self.recent
+    }
+
+    /// Forget all recorded errors.
+    pub fn clear(&mut self) {
+        self.recent.clear();
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JsonValue;
+
+    #[test]
+    fn repeated_errors_are_counted_once() {
+        let mut log = GpuErrorLog::default();
+        log.record(GpuErrorSource::Sdf { expr: "bad(p)".into() }, "unknown function".into());
+        log.record(GpuErrorSource::Sdf { expr: "bad(p)".into() }, "unknown function".into());
+        log.record(GpuErrorSource::Frame, "unknown function".into());
+        assert_eq!(log.take_pending().len(), 2);
+        assert!(log.take_pending().is_empty());
+        assert_eq!(log.recent()[0].count, 2);
+
+        for i in 0..MAX_RECENT {
+            log.record(GpuErrorSource::Frame, format!("error {i}"));
+        }
+        assert_eq!(log.recent().len(), MAX_RECENT);
+        assert_eq!(log.recent()[0].message, "error 0");
+    }
+
+    #[test]
+    fn errors_json_includes_source_context() {
+        let errors = [GpuError {
+            source: GpuErrorSource::Shader { id: 3, name: "water \"v2\"".into(), line_offset: 57 },
+            message: "error: expected ';'\n  ┌─ wgsl:60:5".into(),
+            count: 1,
+        }];
+        let v = JsonValue::parse(&errors_json(&errors)).unwrap();
+        let e = &v.as_array().unwrap()[0];
+        assert_eq!(e.get("source").and_then(|v| v.as_str()), Some("shader"));
+        assert_eq!(e.get("name").and_then(|v| v.as_str()), Some("water \"v2\""));
+        assert_eq!(e.get("lineOffset").and_then(|v| v.as_f64()), Some(57.0));
+        assert_eq!(e.get("context").and_then(|v| v.as_str()), Some("shader 3 \"water \"v2\"\""));
+        assert!(e.get("message").and_then(|v| v.as_str()).unwrap().contains("wgsl:60:5"));
+    }
+
+    #[test]
+    fn line_offset_counts_preamble_lines() {
+        assert_eq!(line_offset("a\nb\nfn main() {}\n", "fn main() {}"), 2);
+        assert_eq!(line_offset("fn main() {}", "missing"), 0);
+    }
+}
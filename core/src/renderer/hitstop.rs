@@ -0,0 +1,164 @@
+//! Hit-stop: freeze the simulation for a few milliseconds on a heavy hit
+//! while frames keep rendering, with a camera shake and controller rumble
+//! started by the same call.
+//!
+//! The dev loop starts every frame with the real frame time and, while the
+//! hit-stop is frozen, runs the frame like a focus pause: delta time reads 0
+//! and physics steps are skipped. The shake keeps moving during the freeze, which
+//! is what sells the impact, and fades out after it. Rumble requests are
+//! handed to the gamepad manager once.
+
+/// Shortest shake and rumble. A hit-stop of a few frames would otherwise
+/// shake for too short a time to see or feel.
+pub const MIN_FEEDBACK_SECONDS: f32 = 0.2;
+
+/// Controller vibration to start: `strength` 0-1 for `seconds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rumble {
+    pub strength: f32,
+    pub seconds: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HitStop {
+    /// Seconds of freeze left.
+    freeze: f32,
+    shake_amplitude: f32,
+    shake_duration: f32,
+    shake_age: f32,
+    /// Frames since the shake started, seeding its jitter.
+    shake_frame: u32,
+    pending_rumble: Option<Rumble>,
+}
+
+impl HitStop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Freeze for `ms` milliseconds, shake the camera by up to
+    /// `shake_amplitude` world pixels and rumble gamepads at `rumble` (0-1).
+    /// Zero skips a part. An overlapping call extends the freeze to the
+    /// longer of the two and only replaces a shake or rumble that is weaker
+    /// than the new one.
+    pub fn trigger(&mut self, ms: f32, shake_amplitude: f32, rumble: f32) {
+        let seconds = (ms / 1000.0).max(0.0);
+        if seconds.is_finite() {
+            self.freeze = self.freeze.max(seconds);
+        }
+        let feedback = seconds.max(MIN_FEEDBACK_SECONDS);
+        if shake_amplitude > self.shake_strength() {
+            self.shake_amplitude = shake_amplitude;
+            self.shake_duration = feedback;
+            self.shake_age = 0.0;
+            self.shake_frame = 0;
+        }
+        let rumble = rumble.min(1.0);
+        if rumble > 0.0 && self.pending_rumble.is_none_or(|r| r.strength < rumble) {
+            self.pending_rumble = Some(Rumble { strength: rumble, seconds: feedback });
+        }
+    }
+
+    /// Start a frame, aging by `dt` seconds of real time. Returns whether
+    /// the frame's simulation is frozen: every frame that starts with some
+    /// freeze left is, so a freeze covers `ms / frame time` frames, rounded up.
+    pub fn begin_frame(&mut self, dt: f32) -> bool {
+        let frozen = self.frozen();
+        self.freeze = (self.freeze - dt).max(0.0);
+        if self.shake_age < self.shake_duration {
+            self.shake_age += dt;
+            self.shake_frame = self.shake_frame.wrapping_add(1);
+        }
+        frozen
+    }
+
+    /// Whether freeze time is left.
+    pub fn frozen(&self) -> bool {
+        self.freeze > 0.0
+    }
+
+    /// Seconds of freeze left.
+    pub fn remaining(&self) -> f32 {
+        self.freeze
+    }
+
+    /// Current shake amplitude, fading quadratically to 0 over the shake.
+    fn shake_strength(&self) -> f32 {
+        if self.shake_age >= self.shake_duration {
+            return 0.0;
+        }
+        let left = 1.0 - self.shake_age / self.shake_duration;
+        self.shake_amplitude * left * left
+    }
+
+    /// Camera offset for this frame in world pixels: a new direction every
+    /// frame at the current shake strength.
+    pub fn shake_offset(&self) -> (f32, f32) {
+        let strength = self.shake_strength();
+        if strength <= 0.0 {
+            return (0.0, 0.0);
+        }
+        // Golden-ratio steps spread consecutive directions around the circle
+        let angle = self.shake_frame as f32 * 2.399_963 + 0.5;
+        let (sin, cos) = angle.sin_cos();
+        (cos * strength, sin * strength)
+    }
+
+    /// The rumble to start, once.
+    pub fn take_rumble(&mut self) -> Option<Rumble> {
+        self.pending_rumble.take()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze_lasts_its_duration_in_frames() {
+        let mut stop = HitStop::new();
+        stop.trigger(45.0, 0.0, 0.0);
+        let frozen: Vec<bool> = (0..5).map(|_| stop.begin_frame(1.0 / 60.0)).collect();
+        assert_eq!(frozen, [true, true, true, false, false]);
+    }
+
+    #[test]
+    fn test_overlapping_hits_keep_the_longer_freeze() {
+        let mut stop = HitStop::new();
+        stop.trigger(100.0, 0.0, 0.0);
+        stop.begin_frame(0.02);
+        stop.trigger(30.0, 0.0, 0.0);
+        assert!((stop.remaining() - 0.08).abs() < 1e-6);
+        stop.trigger(200.0, 0.0, 0.0);
+        assert!((stop.remaining() - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_shake_moves_and_fades_after_the_freeze() {
+        let mut stop = HitStop::new();
+        stop.trigger(50.0, 8.0, 0.0);
+        let (x, y) = stop.shake_offset();
+        assert!((x.hypot(y) - 8.0).abs() < 1e-4);
+        assert!(stop.begin_frame(0.1));
+        let next = stop.shake_offset();
+        assert!(!stop.frozen());
+        assert!(next != (x, y) && next.0.hypot(next.1) < 8.0 && next.0.hypot(next.1) > 0.0);
+        stop.begin_frame(MIN_FEEDBACK_SECONDS);
+        assert_eq!(stop.shake_offset(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_rumble_is_taken_once_and_stronger_wins() {
+        let mut stop = HitStop::new();
+        stop.trigger(0.0, 0.0, 0.5);
+        stop.trigger(300.0, 0.0, 0.3);
+        stop.trigger(0.0, 0.0, 2.0);
+        assert_eq!(stop.take_rumble(), Some(Rumble { strength: 1.0, seconds: MIN_FEEDBACK_SECONDS }));
+        assert_eq!(stop.take_rumble(), None);
+        assert!(stop.frozen());
+    }
+}
@@ -0,0 +1,271 @@
+//! Layered frame captures: every draw layer of one frame as its own image.
+//!
+//! Meant for debugging draw order and for pulling a scene apart into layers
+//! for marketing art. A path ending in `.ora` is written as an OpenRaster file
+//! (opened by GIMP, Krita and most paint programs); any other path is a folder
+//! of PNGs with a `manifest.json` listing them bottom to top. Both include the
+//! merged frame as the game shows it.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::{ImageEncoder, RgbaImage};
+
+/// Longest side of the ORA thumbnail, as the format requires.
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// One draw layer on its own, over transparency.
+pub struct CapturedLayer {
+    pub layer: i32,
+    /// Tightly packed RGBA pixels of the capture size.
+    pub pixels: Vec<u8>,
+}
+
+/// A frame split into its draw layers.
+pub struct LayerCapture {
+    pub width: u32,
+    pub height: u32,
+    /// The frame as displayed, post-processing included.
+    pub merged: Vec<u8>,
+    /// Layers in ascending draw order.
+    pub layers: Vec<CapturedLayer>,
+}
+
+impl LayerCapture {
+    /// Write the capture to `path`: an OpenRaster file for `.ora`, otherwise a
+    /// folder of PNGs plus `manifest.json` (created if missing).
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let is_ora = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ora"));
+        if is_ora {
+            std::fs::write(path, self.to_ora()?).with_context(|| format!("Failed to write {}", path.display()))
+        } else {
+            self.write_folder(path)
+        }
+    }
+
+    /// The capture as an OpenRaster archive.
+    pub fn to_ora(&self) -> Result<Vec<u8>> {
+        let mut zip = StoredZip::default();
+        // The mimetype must come first and uncompressed so readers can sniff it
+        zip.add("mimetype", b"image/openraster");
+        zip.add("stack.xml", self.stack_xml().as_bytes());
+        for layer in &self.layers {
+            zip.add(&format!("data/{}", layer_file(layer.layer)), &self.png(&layer.pixels)?);
+        }
+        zip.add("mergedimage.png", &self.png(&self.merged)?);
+        zip.add("Thumbnails/thumbnail.png", &self.thumbnail()?);
+        Ok(zip.finish())
+    }
+
+    /// The ORA layer stack. OpenRaster lists the topmost layer first.
+    fn stack_xml(&self) -> String {
+        let mut xml = format!(
+            "<?xml version='1.0' encoding='UTF-8'?>\n<image version=\"0.0.5\" w=\"{}\" h=\"{}\">\n  <stack>\n",
+            self.width, self.height
+        );
+        for layer in self.layers.iter().rev() {
+            xml.push_str(&format!(
+                "    <layer name=\"layer {0}\" src=\"data/{1}\" x=\"0\" y=\"0\" opacity=\"1.0\" visibility=\"visible\"/>\n",
+                layer.layer,
+                layer_file(layer.layer)
+            ));
+        }
+        xml.push_str("  </stack>\n</image>\n");
+        xml
+    }
+
+    fn write_folder(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+        };
+        for layer in &self.layers {
+            write(&layer_file(layer.layer), &self.png(&layer.pixels)?)?;
+        }
+        write("merged.png", &self.png(&self.merged)?)?;
+        write("manifest.json", self.manifest_json().as_bytes())
+    }
+
+    /// Folder manifest: frame size, the merged image and the layers bottom to top.
+    fn manifest_json(&self) -> String {
+        let layers: Vec<String> = self
+            .layers
+            .iter()
+            .map(|l| format!("{{\"layer\":{},\"file\":\"{}\"}}", l.layer, layer_file(l.layer)))
+            .collect();
+        format!(
+            "{{\"width\":{},\"height\":{},\"merged\":\"merged.png\",\"layers\":[{}]}}\n",
+            self.width,
+            self.height,
+            layers.join(",")
+        )
+    }
+
+    fn png(&self, pixels: &[u8]) -> Result<Vec<u8>> {
+        encode_png(pixels, self.width, self.height)
+    }
+
+    fn thumbnail(&self) -> Result<Vec<u8>> {
+        let image = RgbaImage::from_raw(self.width, self.height, self.merged.clone())
+            .context("Merged image does not match the capture size")?;
+        let scale = (THUMBNAIL_SIZE as f32 / self.width.max(self.height) as f32).min(1.0);
+        let w = ((self.width as f32 * scale).round() as u32).max(1);
+        let h = ((self.height as f32 * scale).round() as u32).max(1);
+        let thumb = image::imageops::thumbnail(&image, w, h);
+        encode_png(thumb.as_raw(), w, h)
+    }
+}
+
+/// File name of a layer's image, e.g. `layer_-1.png`.
+fn layer_file(layer: i32) -> String {
+    format!("layer_{layer}.png")
+}
+
+fn encode_png(pixels: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(pixels, width, height, image::ExtendedColorType::Rgba8)
+        .context("Failed to encode PNG")?;
+    Ok(png)
+}
+
+/// Minimal zip writer with uncompressed entries. The PNGs inside are already
+/// compressed, and OpenRaster requires a stored mimetype anyway.
+#[derive(Default)]
+struct StoredZip {
+    bytes: Vec<u8>,
+    /// Central directory records, written by `finish`.
+    directory: Vec<u8>,
+    entries: u16,
+}
+
+impl StoredZip {
+    fn add(&mut self, name: &str, data: &[u8]) {
+        let offset = self.bytes.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+
+        // Local file header
+        self.bytes.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        write_entry_fields(&mut self.bytes, name, crc, size);
+        self.bytes.extend_from_slice(name.as_bytes());
+        self.bytes.extend_from_slice(data);
+
+        // Central directory record
+        let record = &mut self.directory;
+        record.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        record.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        write_entry_fields(record, name, crc, size);
+        record.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        record.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        record.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        record.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        record.extend_from_slice(&offset.to_le_bytes());
+        record.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.bytes.len() as u32;
+        let directory_size = self.directory.len() as u32;
+        self.bytes.append(&mut self.directory);
+        // End of central directory
+        self.bytes.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.bytes.extend_from_slice(&[0; 4]); // disk numbers
+        self.bytes.extend_from_slice(&self.entries.to_le_bytes());
+        self.bytes.extend_from_slice(&self.entries.to_le_bytes());
+        self.bytes.extend_from_slice(&directory_size.to_le_bytes());
+        self.bytes.extend_from_slice(&directory_offset.to_le_bytes());
+        self.bytes.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.bytes
+    }
+}
+
+/// The fields local headers and central records share: version needed, flags,
+/// method (stored), time, date, CRC, sizes, name length and extra length.
+fn write_entry_fields(out: &mut Vec<u8>, name: &str, crc: u32, size: u32) {
+    out.extend_from_slice(&20u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0x21u16.to_le_bytes()); // 1980-01-01
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+}
+
+/// CRC-32 (IEEE), as zip entries store it.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture() -> LayerCapture {
+        let solid = |r: u8| [r, 0, 0, 255].repeat(4);
+        LayerCapture {
+            width: 2,
+            height: 2,
+            merged: solid(255),
+            layers: vec![
+                CapturedLayer { layer: -1, pixels: solid(10) },
+                CapturedLayer { layer: 3, pixels: solid(20) },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_ora_starts_with_a_stored_mimetype() {
+        let ora = capture().to_ora().unwrap();
+        assert_eq!(&ora[0..4], &[0x50, 0x4b, 0x03, 0x04]);
+        // Method 0 (stored), then the name right after the 30-byte header
+        assert_eq!(&ora[8..10], &[0, 0]);
+        assert_eq!(&ora[30..38], b"mimetype");
+        assert_eq!(&ora[38..54], b"image/openraster");
+        // End of central directory: mimetype, stack, 2 layers, merged image, thumbnail
+        let eocd = ora.len() - 22;
+        assert_eq!(&ora[eocd..eocd + 4], &[0x50, 0x4b, 0x05, 0x06]);
+        assert_eq!(u16::from_le_bytes([ora[eocd + 10], ora[eocd + 11]]), 6);
+    }
+
+    #[test]
+    fn test_stack_lists_the_top_layer_first() {
+        let xml = capture().stack_xml();
+        let top = xml.find("data/layer_3.png").unwrap();
+        let bottom = xml.find("data/layer_-1.png").unwrap();
+        assert!(top < bottom);
+        assert!(xml.contains("w=\"2\" h=\"2\""));
+    }
+
+    #[test]
+    fn test_folder_has_layers_merged_image_and_manifest() {
+        let out = std::env::temp_dir().join(format!("arcane_layers_{}", std::process::id()));
+        capture().write(&out).unwrap();
+        for name in ["layer_-1.png", "layer_3.png", "merged.png"] {
+            assert!(out.join(name).exists(), "missing {name}");
+        }
+        let manifest = std::fs::read_to_string(out.join("manifest.json")).unwrap();
+        let json = crate::json::JsonValue::parse(&manifest).unwrap();
+        let layers = json.get("layers").unwrap().as_array().unwrap();
+        assert_eq!(layers[0].str_of("file"), Some("layer_-1.png"));
+        assert_eq!(layers[1].get("layer").unwrap().as_f64(), Some(3.0));
+        let _ = std::fs::remove_dir_all(&out);
+    }
+}
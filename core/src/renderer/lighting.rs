@@ -1,5 +1,8 @@
 use bytemuck::{Pod, Zeroable};
 
+use super::radiance::{DirectionalLight, Occluder};
+use crate::curve::Curve;
+
 #[derive(Debug, Clone)]
 pub struct PointLight {
     pub x: f32,
@@ -9,6 +12,131 @@ pub struct PointLight {
     pub g: f32,
     pub b: f32,
     pub intensity: f32,
+    /// Flicker / pulse / color cycling, baked in by [`LightingState::animate`].
+    pub animation: Option<LightAnimation>,
+    /// Cast shadows from the radiance occluders, with this softness: the
+    /// width of the light source in world units (0 = hard). None = no shadows.
+    pub shadow_softness: Option<f32>,
+}
+
+impl PointLight {
+    /// Apply the light's animation at `time` seconds (once; the animation is consumed).
+    pub fn animate(&mut self, time: f32) {
+        if let Some(animation) = self.animation.take() {
+            let ([r, g, b], intensity) = animation.apply([self.r, self.g, self.b], self.intensity, time);
+            (self.r, self.g, self.b, self.intensity) = (r, g, b, intensity);
+        }
+    }
+}
+
+/// Waveform of a [`LightAnimation`] pulse. Each starts a period at its peak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseCurve {
+    /// Smooth breathing.
+    Sine = 0,
+    /// Linear down and back up.
+    Triangle = 1,
+    /// On for half the period, dimmed for the other half.
+    Square = 2,
+    /// Full at the start of each period, fading linearly (a neon buzz or strobe).
+    Sawtooth = 3,
+}
+
+impl PulseCurve {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Sine),
+            1 => Some(Self::Triangle),
+            2 => Some(Self::Square),
+            3 => Some(Self::Sawtooth),
+            _ => None,
+        }
+    }
+
+    /// Level (0..=1) at `phase` in periods.
+    fn level(self, phase: f32) -> f32 {
+        let p = phase.rem_euclid(1.0);
+        match self {
+            Self::Sine => 0.5 + 0.5 * (p * std::f32::consts::TAU).cos(),
+            Self::Triangle => (2.0 * p - 1.0).abs(),
+            Self::Square => if p < 0.5 { 1.0 } else { 0.0 },
+            Self::Sawtooth => 1.0 - p,
+        }
+    }
+}
+
+/// Time-based modulation of a point or spot light. Evaluated by the renderer
+/// from the frame time, so torches and neon signs animate smoothly without the
+/// script recomputing them. Amounts are how far the light dims below its
+/// intensity, as a fraction (0..=1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightAnimation {
+    /// Random flicker amount.
+    pub flicker: f32,
+    /// Flicker changes per second.
+    pub flicker_speed: f32,
+    /// Periodic pulse amount.
+    pub pulse: f32,
+    /// Pulses per second.
+    pub pulse_speed: f32,
+    pub pulse_curve: PulseCurve,
+    /// Replaces `pulse_curve`: each period dims from full along this easing
+    /// curve, like a reshaped sawtooth.
+    pub pulse_shape: Option<Curve>,
+    /// Color cycling: hue turns per second (0 = off).
+    pub hue_speed: f32,
+    /// Time offset in seconds, so identical lights don't animate in sync.
+    pub phase: f32,
+}
+
+impl LightAnimation {
+    /// Intensity multiplier (0..=1) at `time` seconds.
+    pub fn intensity_scale(&self, time: f32) -> f32 {
+        let t = time + self.phase;
+        let flicker = self.flicker.clamp(0.0, 1.0) * value_noise(t * self.flicker_speed);
+        let phase = t * self.pulse_speed;
+        let level = match self.pulse_shape {
+            Some(curve) => 1.0 - curve.sample(phase.rem_euclid(1.0)),
+            None => self.pulse_curve.level(phase),
+        };
+        let pulse = self.pulse.clamp(0.0, 1.0) * (1.0 - level);
+        (1.0 - flicker) * (1.0 - pulse)
+    }
+
+    /// Animated color and intensity at `time` seconds.
+    pub fn apply(&self, color: [f32; 3], intensity: f32, time: f32) -> ([f32; 3], f32) {
+        let turns = (time + self.phase) * self.hue_speed;
+        let color = if turns == 0.0 { color } else { rotate_hue(color, turns * std::f32::consts::TAU) };
+        (color, intensity * self.intensity_scale(time))
+    }
+}
+
+/// Smooth 1D value noise in 0..=1, one random value per integer step.
+fn value_noise(x: f32) -> f32 {
+    fn hash(i: i32) -> f32 {
+        let mut h = (i as u32).wrapping_mul(0x9E37_79B9);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 13;
+        h as f32 / u32::MAX as f32
+    }
+    let i = x.floor();
+    let f = x - i;
+    let s = f * f * (3.0 - 2.0 * f);
+    let a = hash(i as i32);
+    a + (hash(i as i32 + 1) - a) * s
+}
+
+/// Rotate `color` around the gray axis by `angle` radians (keeps its brightness).
+fn rotate_hue([r, g, b]: [f32; 3], angle: f32) -> [f32; 3] {
+    let (sin, cos) = angle.sin_cos();
+    let k = (1.0 - cos) / 3.0;
+    let q = sin / 3.0f32.sqrt();
+    [
+        (r * (cos + k) + g * (k - q) + b * (k + q)).max(0.0),
+        (r * (k + q) + g * (cos + k) + b * (k - q)).max(0.0),
+        (r * (k - q) + g * (k + q) + b * (cos + k)).max(0.0),
+    ]
 }
 
 #[derive(Debug, Clone)]
@@ -26,48 +154,250 @@ impl Default for LightingState {
     }
 }
 
+/// Light slots in the uniform-buffer fallback (the `array<LightData, 8>` in
+/// sprite.wgsl). Also the default capacity.
 pub const MAX_LIGHTS: usize = 8;
 
-/// GPU-aligned light data. Each light = 32 bytes (2 x vec4).
+/// Directional light slots in the lighting header (the `directional` array in
+/// sprite.wgsl). Lights past this are dropped.
+pub const MAX_DIRECTIONAL_LIGHTS: usize = 4;
+
+/// Occluder slots in the lighting header (the `occluders` array in
+/// sprite.wgsl) for point light shadows.
+pub const MAX_SHADOW_OCCLUDERS: usize = 32;
+
+/// Smallest light source width for shadows. Keeps `pos_radius.w` positive
+/// (0 means no shadows) while staying sharp.
+const MIN_SHADOW_SOFTNESS: f32 = 1.0;
+
+/// How many point lights the renderer shades per frame, and whether they live
+/// in a storage buffer (any count) or the fixed-size uniform buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightCapacity {
+    pub max_lights: usize,
+    pub storage: bool,
+}
+
+impl Default for LightCapacity {
+    fn default() -> Self {
+        Self { max_lights: MAX_LIGHTS, storage: false }
+    }
+}
+
+impl LightCapacity {
+    /// Resolve a requested capacity against device support. Without storage
+    /// buffers the capacity is clamped to [`MAX_LIGHTS`]; the storage buffer is
+    /// only used when the request needs it.
+    pub fn resolve(requested: usize, storage_supported: bool) -> Self {
+        if requested > MAX_LIGHTS && storage_supported {
+            Self { max_lights: requested, storage: true }
+        } else {
+            Self { max_lights: requested.min(MAX_LIGHTS), storage: false }
+        }
+    }
+
+    /// Light slots in the GPU buffer. The uniform array is always full size.
+    pub fn slots(&self) -> usize {
+        if self.storage { self.max_lights } else { MAX_LIGHTS }
+    }
+
+    /// Size in bytes of the lighting buffer.
+    pub fn buffer_size(&self) -> u64 {
+        (std::mem::size_of::<LightingHeader>() + self.slots() * std::mem::size_of::<LightData>())
+            as u64
+    }
+
+    pub fn binding_type(&self) -> wgpu::BufferBindingType {
+        if self.storage {
+            wgpu::BufferBindingType::Storage { read_only: true }
+        } else {
+            wgpu::BufferBindingType::Uniform
+        }
+    }
+
+    pub fn buffer_usage(&self) -> wgpu::BufferUsages {
+        let binding = if self.storage { wgpu::BufferUsages::STORAGE } else { wgpu::BufferUsages::UNIFORM };
+        binding | wgpu::BufferUsages::COPY_DST
+    }
+
+    /// Rewrite the lighting declaration in sprite.wgsl (or its preamble) for a
+    /// storage buffer. Shader code keeps using `lighting.lights[i]` either way.
+    pub fn patch_wgsl(&self, wgsl: &str) -> String {
+        if !self.storage {
+            return wgsl.to_string();
+        }
+        wgsl.replace("lights: array<LightData, 8>,", "lights: array<LightData>,")
+            .replace("var<uniform> lighting: LightingUniform;", "var<storage, read> lighting: LightingUniform;")
+    }
+}
+
+/// GPU-aligned header in front of the light array: ambient, counts, the
+/// directional lights and the shadow occluders.
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
+pub struct LightingHeader {
+    pub ambient: [f32; 3],
+    pub light_count: u32,
+    pub directional_count: u32,
+    pub occluder_count: u32,
+    pub _pad: [u32; 2],
+    pub directional: [LightData; MAX_DIRECTIONAL_LIGHTS],
+    pub occluders: [[f32; 4]; MAX_SHADOW_OCCLUDERS],
+}
+
+/// GPU-aligned light data. Each light = 32 bytes (2 x vec4).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct LightData {
-    pub pos_radius: [f32; 4],     // x, y, radius, _padding
+    pub pos_radius: [f32; 4],     // x, y, radius, shadow softness (0 = no shadows)
     pub color_intensity: [f32; 4], // r, g, b, intensity
 }
 
-/// GPU uniform for lighting. Total size = 16 + 8*32 = 272 bytes.
-/// Must be 16-byte aligned.
-#[repr(C)]
-#[derive(Copy, Clone, Pod, Zeroable)]
+/// Lighting buffer contents: a 672-byte header followed by one `LightData`
+/// per slot (928 bytes for the default 8-slot uniform buffer).
+#[derive(Debug, Clone)]
 pub struct LightingUniform {
     pub ambient: [f32; 3],
     pub light_count: u32,
-    pub lights: [LightData; MAX_LIGHTS],
+    pub lights: Vec<LightData>,
+    /// Directional lights, which only normal-mapped sprites are lit by:
+    /// `pos_radius` holds the unit vector towards the light.
+    pub directional: Vec<LightData>,
+    /// Occluder rects `[min_x, min_y, max_x, max_y]` that block
+    /// shadow-casting point lights.
+    pub occluders: Vec<[f32; 4]>,
+}
+
+impl LightingUniform {
+    /// Add the frame's directional lights (the first [`MAX_DIRECTIONAL_LIGHTS`]).
+    /// Each shines down on the sprite plane at 45°, its rays heading along
+    /// `angle` (0 = right, π/2 = down).
+    pub fn with_directional(mut self, lights: &[DirectionalLight]) -> Self {
+        let elevation = std::f32::consts::FRAC_1_SQRT_2;
+        self.directional = lights
+            .iter()
+            .take(MAX_DIRECTIONAL_LIGHTS)
+            .map(|light| {
+                let (sin, cos) = light.angle.sin_cos();
+                LightData {
+                    pos_radius: [-cos * elevation, -sin * elevation, elevation, 0.0],
+                    color_intensity: [light.r, light.g, light.b, light.intensity],
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Add the occluders that can cast a shadow this frame: those within
+    /// reach of a shadow-casting light, up to [`MAX_SHADOW_OCCLUDERS`] in
+    /// registration order.
+    pub fn with_occluders(mut self, occluders: &[Occluder]) -> Self {
+        let casters: Vec<[f32; 3]> = self.lights[..self.light_count as usize]
+            .iter()
+            .filter(|light| light.pos_radius[3] > 0.0)
+            .map(|light| [light.pos_radius[0], light.pos_radius[1], light.pos_radius[2]])
+            .collect();
+        self.occluders = occluders
+            .iter()
+            .map(|o| [o.x, o.y, o.x + o.width, o.y + o.height])
+            .filter(|[x0, y0, x1, y1]| {
+                casters.iter().any(|&[x, y, r]| x + r > *x0 && x - r < *x1 && y + r > *y0 && y - r < *y1)
+            })
+            .take(MAX_SHADOW_OCCLUDERS)
+            .collect();
+        self
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut directional = [LightData::zeroed(); MAX_DIRECTIONAL_LIGHTS];
+        let count = self.directional.len().min(MAX_DIRECTIONAL_LIGHTS);
+        directional[..count].copy_from_slice(&self.directional[..count]);
+        let mut occluders = [[0.0; 4]; MAX_SHADOW_OCCLUDERS];
+        let occluder_count = self.occluders.len().min(MAX_SHADOW_OCCLUDERS);
+        occluders[..occluder_count].copy_from_slice(&self.occluders[..occluder_count]);
+        let header = LightingHeader {
+            ambient: self.ambient,
+            light_count: self.light_count,
+            directional_count: count as u32,
+            occluder_count: occluder_count as u32,
+            _pad: [0; 2],
+            directional,
+            occluders,
+        };
+        let mut bytes = bytemuck::bytes_of(&header).to_vec();
+        bytes.extend_from_slice(bytemuck::cast_slice(&self.lights));
+        bytes
+    }
 }
 
 impl LightingState {
+    /// Bake light animations at `time` seconds into colors and intensities.
+    pub fn animate(&mut self, time: f32) {
+        for light in &mut self.lights {
+            light.animate(time);
+        }
+    }
+
+    /// Default-capacity buffer with no view culling.
     pub fn to_uniform(&self) -> LightingUniform {
-        let mut uniform = LightingUniform {
-            ambient: self.ambient,
-            light_count: self.lights.len().min(MAX_LIGHTS) as u32,
-            lights: [LightData {
-                pos_radius: [0.0; 4],
-                color_intensity: [0.0; 4],
-            }; MAX_LIGHTS],
+        self.to_uniform_for(LightCapacity::default(), None)
+    }
+
+    /// Pack lights for the GPU. When there are more lights than the capacity,
+    /// the ones contributing most to the visible area `view` ([x, y, w, h] in
+    /// world units) are kept, rather than the first registered.
+    pub fn to_uniform_for(&self, capacity: LightCapacity, view: Option<[f32; 4]>) -> LightingUniform {
+        let selected: Vec<&PointLight> = if self.lights.len() <= capacity.max_lights {
+            self.lights.iter().collect()
+        } else {
+            let mut ranked: Vec<(usize, f32)> = self
+                .lights
+                .iter()
+                .enumerate()
+                .map(|(i, light)| (i, contribution(light, view)))
+                .collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            ranked.truncate(capacity.max_lights);
+            // Keep registration order among the survivors
+            ranked.sort_by_key(|&(i, _)| i);
+            ranked.into_iter().map(|(i, _)| &self.lights[i]).collect()
         };
 
-        for (i, light) in self.lights.iter().take(MAX_LIGHTS).enumerate() {
-            uniform.lights[i] = LightData {
-                pos_radius: [light.x, light.y, light.radius, 0.0],
+        let mut lights = vec![LightData::zeroed(); capacity.slots()];
+        for (slot, light) in lights.iter_mut().zip(selected.iter()) {
+            let shadow = light.shadow_softness.map_or(0.0, |s| s.max(MIN_SHADOW_SOFTNESS));
+            *slot = LightData {
+                pos_radius: [light.x, light.y, light.radius, shadow],
                 color_intensity: [light.r, light.g, light.b, light.intensity],
             };
         }
 
-        uniform
+        LightingUniform {
+            ambient: self.ambient,
+            light_count: selected.len().min(lights.len()) as u32,
+            lights,
+            directional: Vec::new(),
+            occluders: Vec::new(),
+        }
     }
 }
 
+/// Rough on-screen contribution of a light: peak brightness times the area of
+/// its bounding box inside the view. Lights entirely off-screen score 0.
+fn contribution(light: &PointLight, view: Option<[f32; 4]>) -> f32 {
+    let brightness = light.intensity.max(0.0) * light.r.max(light.g).max(light.b).max(0.0);
+    let r = light.radius.max(0.0);
+    let area = match view {
+        Some([vx, vy, vw, vh]) => {
+            let w = ((light.x + r).min(vx + vw) - (light.x - r).max(vx)).max(0.0);
+            let h = ((light.y + r).min(vy + vh) - (light.y - r).max(vy)).max(0.0);
+            w * h
+        }
+        None => 4.0 * r * r,
+    };
+    brightness * area
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +421,8 @@ mod tests {
                 g: 0.8,
                 b: 0.5,
                 intensity: 1.5,
+                animation: None,
+                shadow_softness: None,
             }],
         };
         let uniform = state.to_uniform();
@@ -113,6 +445,8 @@ mod tests {
                     g: 1.0,
                     b: 1.0,
                     intensity: 1.0,
+                    animation: None,
+                    shadow_softness: None,
                 })
                 .collect(),
         };
@@ -122,10 +456,53 @@ mod tests {
 
     #[test]
     fn test_gpu_alignment() {
-        // LightingUniform must be properly aligned for GPU upload
+        // Lighting buffer must match the WGSL layout: 672-byte header + 32-byte lights
         assert_eq!(std::mem::size_of::<LightData>(), 32);
-        assert_eq!(std::mem::size_of::<LightingUniform>(), 272);
-        assert_eq!(std::mem::align_of::<LightingUniform>(), 4);
+        assert_eq!(std::mem::size_of::<LightingHeader>(), 672);
+        assert_eq!(LightingState::default().to_uniform().to_bytes().len(), 928);
+        assert_eq!(LightCapacity::default().buffer_size(), 928);
+    }
+
+    fn light_at(x: f32, intensity: f32) -> PointLight {
+        PointLight { x, y: 50.0, radius: 20.0, r: 1.0, g: 1.0, b: 1.0, intensity, animation: None, shadow_softness: None }
+    }
+
+    #[test]
+    fn test_overflow_keeps_lights_that_matter_on_screen() {
+        // 8 dim on-screen lights registered first, then 4 off-screen, then 2 bright on-screen
+        let mut lights: Vec<PointLight> = (0..8).map(|i| light_at(20.0 + i as f32 * 10.0, 0.5)).collect();
+        lights.extend((0..4).map(|i| light_at(1000.0 + i as f32 * 50.0, 5.0)));
+        lights.push(light_at(40.0, 2.0));
+        lights.push(light_at(60.0, 3.0));
+        let state = LightingState { ambient: [0.1; 3], lights };
+
+        let uniform = state.to_uniform_for(LightCapacity::default(), Some([0.0, 0.0, 200.0, 100.0]));
+        assert_eq!(uniform.light_count, 8);
+        let kept: Vec<f32> = uniform.lights.iter().map(|l| l.color_intensity[3]).collect();
+        // Both bright lights survive, no off-screen light does
+        assert!(kept.contains(&2.0) && kept.contains(&3.0));
+        assert!(!kept.contains(&5.0));
+        assert_eq!(kept.iter().filter(|&&i| i == 0.5).count(), 6);
+    }
+
+    #[test]
+    fn test_capacity_resolution_and_storage_layout() {
+        assert_eq!(LightCapacity::resolve(4, true), LightCapacity { max_lights: 4, storage: false });
+        assert_eq!(LightCapacity::resolve(64, false), LightCapacity::default());
+        let storage = LightCapacity::resolve(64, true);
+        assert!(storage.storage);
+        assert_eq!(storage.buffer_size(), 672 + 64 * 32);
+
+        let lights = (0..40).map(|i| light_at(i as f32, 1.0)).collect();
+        let uniform = LightingState { ambient: [1.0; 3], lights }.to_uniform_for(storage, None);
+        assert_eq!(uniform.light_count, 40);
+        assert_eq!(uniform.to_bytes().len() as u64, storage.buffer_size());
+
+        let wgsl = storage.patch_wgsl(include_str!("shaders/sprite.wgsl"));
+        assert!(wgsl.contains("var<storage, read> lighting: LightingUniform;"));
+        assert!(wgsl.contains("lights: array<LightData>,"));
+        assert_eq!(LightCapacity::default().patch_wgsl("x"), "x");
+        assert!(naga::front::wgsl::parse_str(&wgsl).is_ok());
     }
 
     #[test]
@@ -143,4 +520,138 @@ mod tests {
             assert_eq!(light.color_intensity, [0.0; 4]);
         }
     }
+
+    #[test]
+    fn test_directional_lights_point_towards_the_light() {
+        let sun = |angle| DirectionalLight { angle, r: 1.0, g: 0.9, b: 0.8, intensity: 0.5 };
+        let lights: Vec<DirectionalLight> = (0..6).map(|i| sun(i as f32)).collect();
+        let uniform = LightingState::default().to_uniform().with_directional(&lights);
+        assert_eq!(uniform.directional.len(), MAX_DIRECTIONAL_LIGHTS);
+
+        // Rays heading down come from above the screen (-y), 45° over the plane
+        let down = LightingState::default().to_uniform().with_directional(&[sun(std::f32::consts::FRAC_PI_2)]);
+        let [x, y, z, _] = down.directional[0].pos_radius;
+        assert!(x.abs() < 1e-6 && (y + z).abs() < 1e-6 && z > 0.0);
+        assert!(((x * x + y * y + z * z) - 1.0).abs() < 1e-6);
+        assert_eq!(down.directional[0].color_intensity, [1.0, 0.9, 0.8, 0.5]);
+
+        // The count and the light land in the header after the counts
+        let bytes = down.to_bytes();
+        assert_eq!(u32::from_ne_bytes(bytes[16..20].try_into().unwrap()), 1);
+        assert_eq!(f32::from_ne_bytes(bytes[40..44].try_into().unwrap()), z);
+    }
+
+    #[test]
+    fn test_shadow_softness_packs_into_light_data() {
+        let hard = PointLight { shadow_softness: Some(0.0), ..light_at(10.0, 1.0) };
+        let soft = PointLight { shadow_softness: Some(12.0), ..light_at(20.0, 1.0) };
+        let uniform = LightingState { ambient: [0.1; 3], lights: vec![light_at(0.0, 1.0), hard, soft] }.to_uniform();
+        // 0 = no shadows; hard shadows still get a tiny positive source width
+        assert_eq!(uniform.lights[0].pos_radius[3], 0.0);
+        assert_eq!(uniform.lights[1].pos_radius[3], MIN_SHADOW_SOFTNESS);
+        assert_eq!(uniform.lights[2].pos_radius[3], 12.0);
+    }
+
+    #[test]
+    fn test_occluders_kept_near_shadow_casting_lights() {
+        let occ = |x: f32| Occluder { x, y: 40.0, width: 10.0, height: 20.0 };
+        let occluders = [occ(0.0), occ(100.0), occ(25.0)];
+
+        // Without a shadow-casting light no occluder is uploaded
+        let plain = LightingState { ambient: [0.1; 3], lights: vec![light_at(10.0, 1.0)] };
+        assert!(plain.to_uniform().with_occluders(&occluders).occluders.is_empty());
+
+        // A caster at x=10 (radius 20) reaches x -10..30: the far occluder is skipped
+        let uniform = LightingState { ambient: [0.1; 3], lights: vec![caster_at(10.0)] }.to_uniform().with_occluders(&occluders);
+        assert_eq!(uniform.occluders, vec![[0.0, 40.0, 10.0, 60.0], [25.0, 40.0, 35.0, 60.0]]);
+
+        // The count follows the directional array; rects follow the directional lights
+        let bytes = uniform.to_bytes();
+        assert_eq!(u32::from_ne_bytes(bytes[20..24].try_into().unwrap()), 2);
+        assert_eq!(f32::from_ne_bytes(bytes[160 + 16..164 + 16].try_into().unwrap()), 25.0);
+
+        let many: Vec<Occluder> = (0..40).map(|i| occ(i as f32 * 0.5)).collect();
+        let capped = LightingState { ambient: [0.1; 3], lights: vec![caster_at(10.0)] }.to_uniform().with_occluders(&many);
+        assert_eq!(capped.occluders.len(), MAX_SHADOW_OCCLUDERS);
+        assert_eq!(capped.to_bytes().len(), 928);
+    }
+
+    fn caster_at(x: f32) -> PointLight {
+        PointLight { shadow_softness: Some(4.0), ..light_at(x, 1.0) }
+    }
+
+    fn animation(pulse_curve: PulseCurve) -> LightAnimation {
+        LightAnimation {
+            flicker: 0.0, flicker_speed: 0.0, pulse: 0.5, pulse_speed: 1.0, pulse_curve,
+            pulse_shape: None, hue_speed: 0.0, phase: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_pulse_curves_start_at_peak() {
+        for curve in [PulseCurve::Sine, PulseCurve::Triangle, PulseCurve::Square, PulseCurve::Sawtooth] {
+            assert_eq!(animation(curve).intensity_scale(0.0), 1.0, "{curve:?}");
+        }
+        // Half a period in: sine and triangle bottom out at 1 - pulse
+        assert!((animation(PulseCurve::Sine).intensity_scale(0.5) - 0.5).abs() < 1e-5);
+        assert!((animation(PulseCurve::Triangle).intensity_scale(0.5) - 0.5).abs() < 1e-5);
+        assert!((animation(PulseCurve::Sawtooth).intensity_scale(0.5) - 0.75).abs() < 1e-5);
+        assert_eq!(animation(PulseCurve::Square).intensity_scale(0.75), 0.5);
+        assert_eq!(PulseCurve::from_u32(3), Some(PulseCurve::Sawtooth));
+        assert_eq!(PulseCurve::from_u32(4), None);
+    }
+
+    #[test]
+    fn test_pulse_shape_replaces_the_waveform() {
+        use crate::curve::Easing;
+        let shaped = LightAnimation {
+            pulse_shape: Some(Curve::Easing(Easing::InQuad)),
+            ..animation(PulseCurve::Square)
+        };
+        assert_eq!(shaped.intensity_scale(0.0), 1.0);
+        // Dims by 0.5² of the pulse amount halfway through the period
+        assert!((shaped.intensity_scale(0.5) - 0.875).abs() < 1e-5);
+        assert!((shaped.intensity_scale(1.5) - 0.875).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_flicker_stays_in_range_and_is_smooth() {
+        let anim = LightAnimation { flicker: 0.4, flicker_speed: 10.0, pulse: 0.0, ..animation(PulseCurve::Sine) };
+        let mut prev = anim.intensity_scale(0.0);
+        for i in 1..1000 {
+            let scale = anim.intensity_scale(i as f32 / 1000.0);
+            assert!((0.6..=1.0).contains(&scale));
+            // 1ms steps never jump by more than a small fraction
+            assert!((scale - prev).abs() < 0.02);
+            prev = scale;
+        }
+        // Phase shifts the pattern
+        let shifted = LightAnimation { phase: 0.35, ..anim };
+        assert_ne!(anim.intensity_scale(0.1), shifted.intensity_scale(0.1));
+    }
+
+    #[test]
+    fn test_hue_cycle_keeps_gray_and_completes_a_turn() {
+        let anim = LightAnimation { pulse: 0.0, hue_speed: 0.25, ..animation(PulseCurve::Sine) };
+        let (gray, _) = anim.apply([0.5, 0.5, 0.5], 1.0, 1.0);
+        assert!(gray.iter().all(|c| (c - 0.5).abs() < 1e-5));
+        // A third of a turn maps red to green
+        let (green, _) = LightAnimation { hue_speed: 1.0 / 3.0, ..anim }.apply([1.0, 0.0, 0.0], 1.0, 1.0);
+        assert!(green[1] > 0.99 && green[0] < 0.01 && green[2] < 0.01);
+        let (full, _) = anim.apply([1.0, 0.2, 0.0], 1.0, 4.0);
+        assert!((full[0] - 1.0).abs() < 1e-4 && (full[1] - 0.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_animate_bakes_once() {
+        let mut state = LightingState {
+            ambient: [1.0; 3],
+            lights: vec![PointLight { animation: Some(animation(PulseCurve::Sine)), ..light_at(0.0, 2.0) }],
+        };
+        state.animate(0.5);
+        assert!((state.lights[0].intensity - 1.0).abs() < 1e-5);
+        assert!(state.lights[0].animation.is_none());
+        state.animate(0.5);
+        assert!((state.lights[0].intensity - 1.0).abs() < 1e-5);
+    }
 }
@@ -0,0 +1,291 @@
+//! Stencil masks for the scene.
+//!
+//! Shapes drawn between `op_begin_mask` and `op_end_mask` don't draw; they
+//! are collected per mask and written into a stencil buffer the size of the
+//! scene before the scene passes, each with its mask ID as the stencil value.
+//! Sprites queued under `op_draw_masked` carry a [`SpriteMask`] and are drawn
+//! with a stencil test against that ID, in sprite passes that attach the
+//! stencil buffer. Geometry, SDF shapes and particles draw in passes without
+//! it, so their pipelines are unchanged.
+//!
+//! Masks are rebuilt every frame. Where two masks overlap, the later one owns
+//! the pixels. Render target pre-passes have no stencil buffer, so masks only
+//! apply to the scene.
+
+use std::ops::Range;
+
+use wgpu::util::DeviceExt;
+
+use super::geometry::{push_colored_vertices, GeoVertex};
+use super::gpu::multisample_state;
+use crate::scripting::geometry_ops::GeoCommand;
+
+/// Stencil format of the mask buffer.
+pub const MASK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Stencil8;
+
+/// Masks per frame (stencil values 1-255; 0 is "no mask").
+pub const MAX_MASKS: usize = 255;
+
+/// Which stencil mask a sprite is clipped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpriteMask {
+    /// Mask ID from `op_begin_mask` (1-255).
+    pub id: u8,
+    /// Draw only outside the mask's shapes instead of inside.
+    pub invert: bool,
+}
+
+/// Stencil state for sprites clipped to a mask: the stencil reference is the
+/// mask ID, and the buffer is only read.
+pub fn mask_test_state(invert: bool) -> wgpu::DepthStencilState {
+    let compare = if invert { wgpu::CompareFunction::NotEqual } else { wgpu::CompareFunction::Equal };
+    stencil_state(compare, wgpu::StencilOperation::Keep, 0)
+}
+
+fn stencil_state(
+    compare: wgpu::CompareFunction,
+    pass_op: wgpu::StencilOperation,
+    write_mask: u32,
+) -> wgpu::DepthStencilState {
+    let face = wgpu::StencilFaceState {
+        compare,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op,
+    };
+    wgpu::DepthStencilState {
+        format: MASK_FORMAT,
+        depth_write_enabled: false,
+        depth_compare: wgpu::CompareFunction::Always,
+        stencil: wgpu::StencilState { front: face, back: face, read_mask: 0xff, write_mask },
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
+/// Pack every mask's shapes into one vertex list; `ranges[i]` are the
+/// vertices of mask ID `i + 1`. Textured meshes are ignored.
+pub fn mask_vertices(masks: &[Vec<GeoCommand>]) -> (Vec<GeoVertex>, Vec<Range<u32>>) {
+    let mut vertices = Vec::new();
+    let mut ranges = Vec::with_capacity(masks.len());
+    for shapes in masks.iter().take(MAX_MASKS) {
+        let start = vertices.len() as u32;
+        for cmd in shapes {
+            push_colored_vertices(&mut vertices, cmd);
+        }
+        ranges.push(start..vertices.len() as u32);
+    }
+    (vertices, ranges)
+}
+
+struct StencilTexture {
+    #[allow(dead_code)]
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+    samples: u32,
+}
+
+/// The scene's stencil buffer, recreated when the scene target's size or
+/// sample count changes.
+#[derive(Default)]
+pub struct MaskTarget {
+    texture: Option<StencilTexture>,
+}
+
+impl MaskTarget {
+    /// The stencil view for a `width`×`height` scene drawn with `samples` per pixel.
+    pub fn view(&mut self, device: &wgpu::Device, width: u32, height: u32, samples: u32) -> wgpu::TextureView {
+        let stale = self
+            .texture
+            .as_ref()
+            .is_none_or(|t| (t.width, t.height, t.samples) != (width, height, samples));
+        if stale {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("mask_stencil"),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: samples,
+                dimension: wgpu::TextureDimension::D2,
+                format: MASK_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.texture = Some(StencilTexture { texture, view, width, height, samples });
+        }
+        self.texture.as_ref().expect("created above").view.clone()
+    }
+}
+
+/// Writes mask shapes into the stencil buffer (no color output).
+pub struct MaskPipeline {
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    /// Built per sample count on first use.
+    pipelines: Vec<(u32, wgpu::RenderPipeline)>,
+}
+
+impl MaskPipeline {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("mask_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/geom.wgsl").into()),
+        });
+        // Same layout as the sprite camera group, so its bind group works here
+        let camera_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("mask_camera_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("mask_pipeline_layout"),
+            bind_group_layouts: &[&camera_bgl],
+            push_constant_ranges: &[],
+        });
+        Self { shader, layout, pipelines: Vec::new() }
+    }
+
+    fn pipeline_for(&mut self, device: &wgpu::Device, samples: u32) -> &wgpu::RenderPipeline {
+        let index = match self.pipelines.iter().position(|(n, _)| *n == samples) {
+            Some(index) => index,
+            None => {
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(&format!("mask_pipeline_x{samples}")),
+                    layout: Some(&self.layout),
+                    vertex: wgpu::VertexState {
+                        module: &self.shader,
+                        entry_point: Some("vs_main"),
+                        buffers: &[wgpu::VertexBufferLayout {
+                            array_stride: std::mem::size_of::<GeoVertex>() as wgpu::BufferAddress,
+                            step_mode: wgpu::VertexStepMode::Vertex,
+                            attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+                        }],
+                        compilation_options: Default::default(),
+                    },
+                    // Stencil only: no fragment stage, no color target
+                    fragment: None,
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        cull_mode: None,
+                        ..Default::default()
+                    },
+                    depth_stencil: Some(stencil_state(
+                        wgpu::CompareFunction::Always,
+                        wgpu::StencilOperation::Replace,
+                        0xff,
+                    )),
+                    multisample: multisample_state(samples),
+                    multiview: None,
+                    cache: None,
+                });
+                self.pipelines.push((samples, pipeline));
+                self.pipelines.len() - 1
+            }
+        };
+        &self.pipelines[index].1
+    }
+
+    /// Clear `stencil` and write every mask's shapes into it, mask `i` with
+    /// stencil value `i + 1`. `samples` must match the stencil texture.
+    pub fn write(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        stencil: &wgpu::TextureView,
+        samples: u32,
+        camera_bind_group: &wgpu::BindGroup,
+        masks: &[Vec<GeoCommand>],
+    ) {
+        let (vertices, ranges) = mask_vertices(masks);
+        let vertex_buffer = (!vertices.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("mask_vertex_buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            })
+        });
+        let pipeline = self.pipeline_for(device, samples);
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("mask_pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: stencil,
+                depth_ops: None,
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Store,
+                }),
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let Some(vertex_buffer) = vertex_buffer else { return };
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, camera_bind_group, &[]);
+        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        for (i, range) in ranges.into_iter().enumerate() {
+            if !range.is_empty() {
+                pass.set_stencil_reference(i as u32 + 1);
+                pass.draw(range, 0..1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(x: f32) -> GeoCommand {
+        GeoCommand::Triangle {
+            x1: x, y1: 0.0, x2: x + 1.0, y2: 0.0, x3: x, y3: 1.0,
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 0,
+        }
+    }
+
+    #[test]
+    fn test_mask_vertices_keep_one_range_per_mask() {
+        let line = GeoCommand::LineSeg {
+            x1: 0.0, y1: 0.0, x2: 4.0, y2: 0.0, thickness: 2.0,
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 0,
+        };
+        let masks = vec![vec![triangle(0.0), triangle(2.0)], vec![], vec![line]];
+        let (vertices, ranges) = mask_vertices(&masks);
+        assert_eq!(ranges, vec![0..6, 6..6, 6..12]);
+        assert_eq!(vertices.len(), 12);
+        assert_eq!(vertices[3].position, [2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_mask_vertices_skip_meshes() {
+        let mesh = GeoCommand::Mesh {
+            texture_id: 1,
+            vertices: vec![0.0; 12],
+            indices: vec![0, 1, 2],
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 0,
+        };
+        let (vertices, ranges) = mask_vertices(&[vec![mesh, triangle(0.0)]]);
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(ranges, vec![0..3]);
+    }
+
+    #[test]
+    fn test_mask_test_state_inverts_the_comparison() {
+        assert_eq!(mask_test_state(false).stencil.front.compare, wgpu::CompareFunction::Equal);
+        assert_eq!(mask_test_state(true).stencil.back.compare, wgpu::CompareFunction::NotEqual);
+        assert_eq!(mask_test_state(true).stencil.write_mask, 0);
+    }
+}
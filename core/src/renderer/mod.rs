@@ -3,35 +3,86 @@ mod sprite;
 mod texture;
 pub mod camera;
 mod tilemap;
+pub mod animation;
+pub mod aseprite;
+pub mod decal;
+pub mod flash;
+pub mod hitstop;
 mod lighting;
 pub mod font;
 pub mod msdf;
+pub mod bitmap_font;
+pub mod runtime_font;
+pub mod palette;
+pub mod thumbnail;
 pub mod shader;
 pub mod postprocess;
+pub mod transition;
 pub mod radiance;
 pub mod geometry;
 pub mod rendertarget;
 pub mod sdf;
+pub mod error_overlay;
+pub mod render_thread;
+pub mod quality;
+pub mod particles;
+pub mod gpu_errors;
+pub mod background;
+pub mod recording;
+pub mod layer_capture;
+pub mod msaa;
+pub mod mask;
+pub mod world;
+pub mod viewport;
+pub mod runtime_atlas;
+pub mod sprite_store;
+pub mod texture_stream;
+pub mod frame_profile;
+pub mod debug_hud;
+pub mod reload_diff;
 // Test harness is always public for integration tests
 pub mod test_harness;
 
-pub use gpu::GpuContext;
-pub use sprite::{SpriteCommand, SpritePipeline};
+pub use gpu::{ColorTarget, GpuContext};
+pub use sprite::{ClipRect, NineSlice, SpriteCommand, SpriteOutline, SpritePipeline, SpriteStats};
 pub use texture::{TextureId, TextureStore};
 pub use camera::Camera2D;
-pub use tilemap::{Tilemap, TilemapStore};
-pub use lighting::{LightingState, LightingUniform, PointLight, LightData, MAX_LIGHTS};
+pub use tilemap::{chunk_range, view_rect, AutotileRule, TileAnimation, Tilemap, TilemapStore, TILEMAP_CHUNK_SIZE};
+pub use animation::{AnimationEvent, AnimationEventKind, AnimationStore, LoopMode, SpriteAnimation};
+pub use aseprite::AsepriteSheet;
+pub use decal::{Decal, DecalBatch, DecalStore};
+pub use flash::{FlashStore, SpriteFlash};
+pub use hitstop::{HitStop, Rumble};
+pub use lighting::{LightAnimation, LightCapacity, LightingState, LightingUniform, PointLight, PulseCurve, LightData, MAX_DIRECTIONAL_LIGHTS, MAX_LIGHTS};
 pub use msdf::{MsdfFont, MsdfFontStore, MsdfGlyph};
+pub use runtime_font::RuntimeFontStore;
 pub use shader::ShaderStore;
 pub use postprocess::PostProcessPipeline;
+pub use transition::{ScreenTransition, TransitionEvent, TransitionEventKind, TransitionKind, TransitionOverlay};
 pub use radiance::{RadiancePipeline, RadianceState, EmissiveSurface, Occluder, DirectionalLight, SpotLight};
 pub use geometry::GeometryBatch;
-pub use rendertarget::RenderTargetStore;
+pub use rendertarget::{RenderTargetStore, TargetCamera};
 pub use sdf::{SdfPipelineStore, SdfCommand, SdfFill};
+pub use render_thread::{FrameSet, RenderThread, SharedRenderer};
+pub use quality::{GpuTimer, QualityConfig, QualityController, QUALITY_TIERS};
+pub use particles::{EmitterDef, GpuParticleCommand, GpuParticles};
+pub use gpu_errors::{GpuError, GpuErrorCapture, GpuErrorLog, GpuErrorSource};
+pub use background::{BackgroundFill, Letterbox};
+pub use frame_profile::{FrameProfile, FrameProfiler, RenderTimings};
+pub use debug_hud::{DebugHud, DebugHudStats};
+pub use recording::Recording;
+pub use layer_capture::{CapturedLayer, LayerCapture};
+pub use msaa::{MsaaTarget, MSAA_SAMPLE_COUNTS};
+pub use mask::{MaskPipeline, MaskTarget, SpriteMask, MAX_MASKS};
+pub use world::{WorldStore, WORLD_CHUNK_SIZE};
+pub use viewport::Viewport;
+pub use runtime_atlas::{AtlasStats, RuntimeAtlas};
+pub use sprite_store::{SpriteStore, SPRITE_UPDATE_STRIDE};
+pub use texture_stream::{TextureLoadEvent, TextureStreamStats, TextureStreamer};
 
 use crate::scripting::geometry_ops::GeoCommand;
 use crate::scripting::sdf_ops::SdfDrawCommand;
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 /// Convert a scripting-layer SdfDrawCommand to a rendering-layer SdfCommand.
 fn convert_sdf_draw_command(c: SdfDrawCommand) -> SdfCommand {
@@ -75,6 +126,84 @@ enum RenderOp {
     Sdf { start: usize, end: usize },
 }
 
+impl RenderOp {
+    /// Layer of the first command this op draws.
+    fn layer(&self, sprites: &[SpriteCommand], geo: &[GeoCommand], sdf: &[SdfCommand]) -> i32 {
+        self.layer_at(self.range().0, sprites, geo, sdf)
+    }
+
+    /// Layer of the `i`th command of this op's kind.
+    fn layer_at(&self, i: usize, sprites: &[SpriteCommand], geo: &[GeoCommand], sdf: &[SdfCommand]) -> i32 {
+        match self {
+            RenderOp::Sprites { .. } => sprites[i].layer,
+            RenderOp::Geometry { .. } => geo[i].layer(),
+            RenderOp::Sdf { .. } => sdf[i].layer,
+        }
+    }
+
+    fn range(&self) -> (usize, usize) {
+        match *self {
+            RenderOp::Sprites { start, end } | RenderOp::Geometry { start, end } | RenderOp::Sdf { start, end } => {
+                (start, end)
+            }
+        }
+    }
+
+    /// The same kind of op over `start..end`.
+    fn with_range(&self, start: usize, end: usize) -> RenderOp {
+        match self {
+            RenderOp::Sprites { .. } => RenderOp::Sprites { start, end },
+            RenderOp::Geometry { .. } => RenderOp::Geometry { start, end },
+            RenderOp::Sdf { .. } => RenderOp::Sdf { start, end },
+        }
+    }
+}
+
+/// Compile the SDF pipelines `commands` need for targets with `samples` per
+/// pixel. Expressions that fail validation are logged and their commands skipped.
+fn compile_sdf_pipelines(
+    sdf_pipeline: &mut SdfPipelineStore,
+    gpu_errors: &mut GpuErrorLog,
+    device: &wgpu::Device,
+    commands: &[SdfCommand],
+    samples: u32,
+) {
+    for cmd in commands {
+        if sdf_pipeline.has_pipeline_for(&cmd.sdf_expr, &cmd.fill, samples) {
+            continue;
+        }
+        let source = GpuErrorSource::Sdf { expr: cmd.sdf_expr.clone() };
+        let failed = gpu_errors.scoped(device, GpuErrorCapture::Pipelines, source, || {
+            sdf_pipeline.get_or_create_pipeline_for(device, &cmd.sdf_expr, &cmd.fill, samples);
+        });
+        if failed {
+            sdf_pipeline.mark_failed(&cmd.sdf_expr, &cmd.fill);
+        }
+    }
+}
+
+/// Draw order of a sprite within its layer: the world y of its pivot
+/// (`y + h * origin_y`) on y-sorted layers, so sprites lower on screen draw
+/// in front; 0 on other layers, which keep submission order. An origin of
+/// `(0.5, 1)` sorts characters by their feet.
+fn ysort_key(cmd: &SpriteCommand, ysort_layers: &std::collections::BTreeSet<i32>) -> f32 {
+    if ysort_layers.contains(&cmd.layer) {
+        cmd.y + cmd.h * cmd.origin_y
+    } else {
+        0.0
+    }
+}
+
+/// Round sprite positions to `camera`'s pixel grid, for commands that ask for
+/// it or, when they don't say, if `default` is set. The top-left corner is
+/// snapped and the size kept, so a sprite moving at sub-pixel speed steps a
+/// whole pixel at a time instead of shimmering.
+fn snap_to_pixels(commands: &mut [SpriteCommand], camera: &Camera2D, default: bool) {
+    for cmd in commands.iter_mut().filter(|c| c.pixel_snap.unwrap_or(default)) {
+        (cmd.x, cmd.y) = camera.snap_point(cmd.x, cmd.y);
+    }
+}
+
 /// Build an interleaved render schedule from sorted sprite, geometry, and SDF commands.
 ///
 /// All input slices must be pre-sorted by layer. The schedule merges them so that
@@ -132,6 +261,231 @@ fn build_render_schedule(
     schedule
 }
 
+/// Split `schedule` into one schedule per draw layer, in ascending layer
+/// order, for layered captures. Ops spanning several layers are cut at the
+/// layer boundaries; within a layer, ops keep their order.
+fn layer_schedules(
+    schedule: &[RenderOp],
+    sprites: &[SpriteCommand],
+    geo: &[GeoCommand],
+    sdf: &[SdfCommand],
+) -> Vec<(i32, Vec<RenderOp>)> {
+    let mut layers: std::collections::BTreeMap<i32, Vec<RenderOp>> = std::collections::BTreeMap::new();
+    for op in schedule {
+        let (start, end) = op.range();
+        let layer_of = |i| op.layer_at(i, sprites, geo, sdf);
+        let mut from = start;
+        for i in start + 1..=end {
+            if i == end || layer_of(i) != layer_of(from) {
+                layers.entry(layer_of(from)).or_default().push(op.with_range(from, i));
+                from = i;
+            }
+        }
+    }
+    layers.into_iter().collect()
+}
+
+/// Record every plugin render pass onto `target`, which already holds the scene.
+fn render_plugin_passes(
+    passes: &mut [Box<dyn crate::plugin::RenderPass>],
+    gpu: &GpuContext,
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::TextureView,
+    camera: &Camera2D,
+    time: f32,
+    delta_time: f32,
+) {
+    let mut ctx = crate::plugin::RenderPassContext {
+        device: &gpu.device,
+        queue: &gpu.queue,
+        encoder,
+        target,
+        format: gpu.config.format,
+        camera,
+        time,
+        delta_time,
+    };
+    for pass in passes {
+        pass.render(&mut ctx);
+    }
+}
+
+/// The frame's command lists, each sorted by layer, or the part of them on
+/// a range of layers.
+#[derive(Clone, Copy)]
+struct SceneLists<'a> {
+    sprites: &'a [SpriteCommand],
+    geo: &'a [GeoCommand],
+    sdf: &'a [SdfCommand],
+    /// Inclusive layer range the lists hold.
+    layers: (i32, i32),
+}
+
+impl<'a> SceneLists<'a> {
+    fn new(sprites: &'a [SpriteCommand], geo: &'a [GeoCommand], sdf: &'a [SdfCommand]) -> Self {
+        Self { sprites, geo, sdf, layers: (i32::MIN, i32::MAX) }
+    }
+
+    /// The commands on layers `min..=max`.
+    fn layers(&self, min: i32, max: i32) -> Self {
+        let sprites = self.sprites.partition_point(|c| c.layer < min)..self.sprites.partition_point(|c| c.layer <= max);
+        let geo = self.geo.partition_point(|c| c.layer() < min)..self.geo.partition_point(|c| c.layer() <= max);
+        let sdf = self.sdf.partition_point(|c| c.layer < min)..self.sdf.partition_point(|c| c.layer <= max);
+        Self {
+            sprites: &self.sprites[sprites],
+            geo: &self.geo[geo],
+            sdf: &self.sdf[sdf],
+            layers: (min, max),
+        }
+    }
+
+    fn schedule(&self) -> Vec<RenderOp> {
+        build_render_schedule(self.sprites, self.geo, self.sdf)
+    }
+}
+
+/// The renderer's scene pipelines, borrowed apart from the target they draw
+/// into.
+struct SceneDraw<'r> {
+    gpu: &'r GpuContext,
+    textures: &'r TextureStore,
+    shaders: &'r ShaderStore,
+    sprites: &'r mut SpritePipeline,
+    geometry: &'r mut GeometryBatch,
+    sdf: &'r mut SdfPipelineStore,
+    particles: &'r mut GpuParticles,
+    decals: &'r mut DecalBatch,
+}
+
+impl SceneDraw<'_> {
+    /// Draw `lists` onto `scene` following `schedule` (built from `lists`),
+    /// with the emitters and decals of each layer under its commands. The
+    /// first pass clears the target to `clear_color`, if given.
+    fn draw(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: ColorTarget<'_>,
+        lists: &SceneLists,
+        schedule: &[RenderOp],
+        clear_color: Option<wgpu::Color>,
+    ) {
+        let (min_layer, max_layer) = lists.layers;
+        self.particles.restart(min_layer);
+        self.decals.restart(min_layer);
+        if schedule.is_empty() && clear_color.is_some() {
+            // No commands at all — still need to clear
+            self.sprites.render(
+                &self.gpu.device, &self.gpu.queue, self.textures, self.shaders,
+                &[], scene, encoder, clear_color,
+            );
+        }
+        let mut first = true;
+        for op in schedule {
+            let mut cc = if first { clear_color } else { None };
+            first = false;
+            let layer = op.layer(lists.sprites, lists.geo, lists.sdf);
+            if self.particles.render(
+                encoder, scene, self.sprites.camera_bind_group(),
+                self.textures, Some(layer), cc,
+            ) {
+                cc = None;
+            }
+            // Decals go under the sprites of their layer
+            let decals = self.decals.take_through(Some(layer));
+            if !decals.is_empty() {
+                self.sprites.render(
+                    &self.gpu.device, &self.gpu.queue, self.textures, self.shaders,
+                    decals, scene, encoder, cc,
+                );
+                cc = None;
+            }
+            match op {
+                RenderOp::Sprites { start, end } => {
+                    self.sprites.render(
+                        &self.gpu.device, &self.gpu.queue, self.textures, self.shaders,
+                        &lists.sprites[*start..*end],
+                        scene, encoder, cc,
+                    );
+                }
+                RenderOp::Geometry { start, end } => {
+                    self.geometry.flush_commands_textured(
+                        &self.gpu.device, encoder, scene,
+                        self.sprites.camera_bind_group(), self.textures, &lists.geo[*start..*end], cc,
+                    );
+                }
+                RenderOp::Sdf { start, end } => {
+                    self.sdf.render(
+                        &self.gpu.device, encoder, scene,
+                        &lists.sdf[*start..*end], cc,
+                    );
+                }
+            }
+        }
+        // Decals and emitters above every scheduled layer
+        let decals = self.decals.take_through(Some(max_layer));
+        if !decals.is_empty() {
+            self.sprites.render(
+                &self.gpu.device, &self.gpu.queue, self.textures, self.shaders,
+                decals, scene, encoder, None,
+            );
+        }
+        self.particles.render(
+            encoder, scene, self.sprites.camera_bind_group(),
+            self.textures, max_layer.checked_add(1), None,
+        );
+    }
+
+    /// Draw `lists` split-screen: the background layer over the whole
+    /// `pixels`-sized scene, each viewport's layers through its camera inside
+    /// its rect, then the layers above every viewport over the whole scene
+    /// with `main`. The camera uniforms are shared, so each viewport is
+    /// submitted on its own; `main` is back in them afterwards.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_viewports(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: ColorTarget<'_>,
+        lists: &SceneLists,
+        viewports: &[Viewport],
+        main: &Camera2D,
+        lighting: &LightingUniform,
+        pixels: (u32, u32),
+        clear_color: wgpu::Color,
+    ) {
+        let background = lists.layers(i32::MIN, viewport::VIEWPORT_LAYERS.0 - 1);
+        self.draw(encoder, scene, &background, &background.schedule(), Some(clear_color));
+        for viewport in viewports {
+            let Some(rect) = viewport.pixel_rect(main.viewport_size, pixels) else {
+                continue;
+            };
+            self.submit(encoder);
+            self.prepare(&viewport.camera(), lighting);
+            let shown = lists.layers(viewport.layer_min, viewport.layer_max);
+            self.draw(encoder, scene.with_viewport(rect), &shown, &shown.schedule(), None);
+        }
+        self.submit(encoder);
+        self.prepare(main, lighting);
+        let overlay = lists.layers(viewport::overlay_start(viewports), i32::MAX);
+        self.draw(encoder, scene, &overlay, &overlay.schedule(), None);
+    }
+
+    /// Submit what `encoder` recorded and carry on in a fresh one.
+    fn submit(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let next = self.gpu.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor { label: Some("frame_encoder") },
+        );
+        let done = std::mem::replace(encoder, next);
+        self.gpu.queue.submit(std::iter::once(done.finish()));
+        self.sprites.end_submit();
+    }
+
+    /// Write `camera` (and the lighting) into the uniforms passes draw with.
+    fn prepare(&self, camera: &Camera2D, lighting: &LightingUniform) {
+        self.sprites.prepare(&self.gpu.device, &self.gpu.queue, camera, lighting);
+        self.sdf.prepare(&self.gpu.queue, camera, 0.0);
+    }
+}
+
 /// Top-level renderer that owns the GPU context, sprite pipeline, and textures.
 pub struct Renderer {
     pub gpu: GpuContext,
@@ -139,6 +493,9 @@ pub struct Renderer {
     pub geometry: GeometryBatch,
     pub shaders: ShaderStore,
     pub postprocess: PostProcessPipeline,
+    /// Per-render-target effect chains, applied in the pre-pass before the
+    /// target is sampled. Keyed by render target ID.
+    pub target_postprocess: std::collections::BTreeMap<u32, PostProcessPipeline>,
     pub textures: TextureStore,
     pub camera: Camera2D,
     pub lighting: LightingState,
@@ -148,12 +505,16 @@ pub struct Renderer {
     pub render_targets: RenderTargetStore,
     /// Sprite commands queued for the current frame.
     pub frame_commands: Vec<SpriteCommand>,
+    /// Decals for the current frame, drawn under the sprites of their layer.
+    pub decals: DecalBatch,
     /// Geometry commands queued for the current frame (drained from GeoState).
     pub geo_commands: Vec<GeoCommand>,
     /// SDF commands queued for the current frame (drained from SdfState).
     pub sdf_commands: Vec<SdfCommand>,
     /// SDF pipeline store for rendering signed distance field shapes.
     pub sdf_pipeline: SdfPipelineStore,
+    /// GPU-simulated particle emitters, drawn by layer between the scheduled ops.
+    pub particles: GpuParticles,
     /// Display scale factor (e.g. 2.0 on Retina). Used to convert physical → logical pixels.
     pub scale_factor: f32,
     /// Clear color for the render pass background. Default: dark blue-gray.
@@ -168,20 +529,83 @@ pub struct Renderer {
     pub capture_pending: bool,
     /// PNG bytes from the last capture (taken by the frame callback).
     pub capture_result: Option<Vec<u8>>,
+    /// When set, the next render_frame() also writes each draw layer to its
+    /// own image at this path (see [`LayerCapture`]).
+    pub layer_capture_pending: Option<std::path::PathBuf>,
+    /// Active GIF recording, fed from rendered frames on game time.
+    recording: Option<Recording>,
+    /// Point lights shaded per frame (fixed at startup).
+    pub light_capacity: LightCapacity,
+    /// Set once the light overflow warning has been printed.
+    light_overflow_warned: bool,
+    /// Adaptive quality: picks render scale, GI cascades and effects from frame times.
+    pub quality: QualityController,
+    /// GPU frame timer (None without timestamp query support).
+    gpu_timer: Option<GpuTimer>,
+    /// Render passes from registered plugins, drawn after the scene and GI.
+    pub plugin_passes: Vec<Box<dyn crate::plugin::RenderPass>>,
+    /// Validation errors from pipeline creation and (optionally) frames.
+    pub gpu_errors: GpuErrorLog,
+    /// Multisampled scene texture (see `set_msaa`).
+    msaa: MsaaTarget,
+    /// Mask shapes for the current frame, by mask ID - 1 (see `mask`).
+    pub masks: Vec<Vec<GeoCommand>>,
+    /// Layers whose sprites are drawn in order of their pivot's y (see `ysort_key`).
+    pub ysort_layers: std::collections::BTreeSet<i32>,
+    /// Split-screen viewports, drawn in order (see `viewport`). Empty draws
+    /// the frame once with `camera`.
+    pub viewports: Vec<Viewport>,
+    /// Pages packing small textures for batching (see `runtime_atlas`).
+    pub atlas: RuntimeAtlas,
+    /// File textures decoded on worker threads and uploaded over several frames.
+    pub texture_streams: TextureStreamer,
+    /// CPU time spent encoding and submitting the last frame.
+    pub render_timings: RenderTimings,
+    /// Writes `masks` into the scene's stencil buffer.
+    mask_pipeline: MaskPipeline,
+    /// The scene's mask stencil buffer.
+    mask_target: MaskTarget,
 }
 
 impl Renderer {
-    /// Create a new renderer attached to a winit window.
-    pub fn new(window: std::sync::Arc<winit::window::Window>) -> Result<Self> {
+    /// Create a new renderer attached to a winit window. `max_lights` is the
+    /// requested point light capacity; above [`MAX_LIGHTS`] it needs storage
+    /// buffer support and is clamped otherwise.
+    pub fn new(window: std::sync::Arc<winit::window::Window>, max_lights: usize) -> Result<Self> {
         let scale_factor = window.scale_factor() as f32;
         let gpu = GpuContext::new(window)?;
-        let sprites = SpritePipeline::new(&gpu);
+        Self::with_gpu(gpu, scale_factor, max_lights)
+    }
+
+    /// Create a renderer without a window that draws into an offscreen
+    /// `width`×`height` texture at scale factor 1. Frames are read back with
+    /// `capture_pending`, as for a window.
+    pub fn new_headless(width: u32, height: u32, max_lights: usize) -> Result<Self> {
+        let gpu = GpuContext::new_headless(width, height)?;
+        Self::with_gpu(gpu, 1.0, max_lights)
+    }
+
+    fn with_gpu(gpu: GpuContext, scale_factor: f32, max_lights: usize) -> Result<Self> {
+        let storage_supported = gpu.device.limits().max_storage_buffers_per_shader_stage > 0;
+        let light_capacity = LightCapacity::resolve(max_lights, storage_supported);
+        if light_capacity.max_lights < max_lights {
+            eprintln!(
+                "[lighting] Storage buffers unavailable; point light capacity clamped to {}",
+                light_capacity.max_lights
+            );
+        }
+        let sprites = SpritePipeline::new(&gpu, light_capacity);
         let geometry = GeometryBatch::new(&gpu);
-        let shaders = ShaderStore::new(&gpu);
+        let shaders = ShaderStore::new(&gpu, light_capacity);
         let postprocess = PostProcessPipeline::new(&gpu);
         let sdf_pipeline = SdfPipelineStore::new(&gpu);
         let radiance_pipeline = RadiancePipeline::new(&gpu);
+        let particles = GpuParticles::new(&gpu);
+        let mask_pipeline = MaskPipeline::new(&gpu.device);
         let textures = TextureStore::new();
+        let gpu_timer = GpuTimer::new(&gpu.device, &gpu.queue);
+        let gpu_errors = GpuErrorLog::new(&gpu.device);
+        let plugin_passes = crate::plugin::render_passes(&gpu);
         // Set camera viewport to logical pixels so world units are DPI-independent
         let logical_w = gpu.config.width as f32 / scale_factor;
         let logical_h = gpu.config.height as f32 / scale_factor;
@@ -195,6 +619,7 @@ impl Renderer {
             geometry,
             shaders,
             postprocess,
+            target_postprocess: std::collections::BTreeMap::new(),
             radiance: radiance_pipeline,
             radiance_state: RadianceState::new(),
             textures,
@@ -202,9 +627,11 @@ impl Renderer {
             lighting: LightingState::default(),
             render_targets: RenderTargetStore::new(),
             frame_commands: Vec::new(),
+            decals: DecalBatch::default(),
             geo_commands: Vec::new(),
             sdf_commands: Vec::new(),
             sdf_pipeline,
+            particles,
             scale_factor,
             clear_color: [0.1, 0.1, 0.15, 1.0],
             elapsed_time: 0.0,
@@ -212,9 +639,84 @@ impl Renderer {
             mouse_pos: [0.0, 0.0],
             capture_pending: false,
             capture_result: None,
+            layer_capture_pending: None,
+            recording: None,
+            light_capacity,
+            light_overflow_warned: false,
+            quality: QualityController::new(),
+            gpu_timer,
+            plugin_passes,
+            gpu_errors,
+            msaa: MsaaTarget::default(),
+            masks: Vec::new(),
+            ysort_layers: std::collections::BTreeSet::new(),
+            viewports: Vec::new(),
+            atlas: RuntimeAtlas::new(),
+            texture_streams: TextureStreamer::new(),
+            render_timings: RenderTimings::default(),
+            mask_pipeline,
+            mask_target: MaskTarget::default(),
         })
     }
 
+    /// Adapter details and limits for `op_get_engine_info`.
+    pub fn gpu_info(&self) -> crate::scripting::engine_info::GpuInfo {
+        let info = &self.gpu.adapter_info;
+        crate::scripting::engine_info::GpuInfo {
+            name: info.name.clone(),
+            backend: format!("{:?}", info.backend),
+            device_type: format!("{:?}", info.device_type),
+            driver: format!("{} {}", info.driver, info.driver_info).trim().to_string(),
+            max_texture_size: self.gpu.device.limits().max_texture_dimension_2d,
+            max_lights: self.light_capacity.max_lights as u32,
+        }
+    }
+
+    /// Feed finished GPU timings to the quality controller and apply its tier.
+    fn update_quality(&mut self) {
+        if let Some(ref timer) = self.gpu_timer
+            && let Some(ms) = timer.poll(&self.gpu.device)
+        {
+            self.record_frame_time(ms);
+        }
+        let tier = self.quality.settings();
+        self.postprocess.set_render_scale(tier.render_scale);
+        self.postprocess.effects_enabled = tier.post_effects;
+        self.radiance.cascade_reduction = tier.gi_cascade_reduction;
+    }
+
+    fn record_frame_time(&mut self, ms: f32) {
+        if self.quality.record_frame(ms) {
+            let tier = self.quality.settings();
+            eprintln!(
+                "[quality] Frame time vs {:.1}ms budget: switched to \"{}\" ({}% resolution)",
+                self.quality.config().budget_ms,
+                tier.name,
+                (tier.render_scale * 100.0).round()
+            );
+        }
+    }
+
+    /// Pack this frame's point and directional lights and the occluders they
+    /// cast shadows from, keeping the most visible point lights when there are
+    /// more than the capacity (warns once).
+    fn lighting_uniform(&mut self) -> LightingUniform {
+        let count = self.lighting.lights.len();
+        if count > self.light_capacity.max_lights && !self.light_overflow_warned {
+            self.light_overflow_warned = true;
+            eprintln!(
+                "[lighting] {count} point lights exceed the capacity of {}; keeping the most visible each frame (raise with --max-lights)",
+                self.light_capacity.max_lights
+            );
+        }
+        let cam = &self.camera;
+        let view = [cam.x, cam.y, cam.viewport_size[0] / cam.zoom, cam.viewport_size[1] / cam.zoom];
+        self.lighting
+            .to_uniform_for(self.light_capacity, Some(view))
+            .with_directional(&self.radiance_state.directional_lights)
+            .with_occluders(&self.radiance_state.occluders)
+    }
+
     /// Set geometry commands for the current frame (drained from GeoState in dev.rs).
     pub fn set_geo_commands(&mut self, cmds: Vec<GeoCommand>) {
         self.geo_commands = cmds;
@@ -228,20 +730,38 @@ impl Renderer {
 
     /// Render the current frame's sprite, geometry, and SDF commands, interleaved by layer.
     pub fn render_frame(&mut self) -> Result<()> {
-        let output = self.gpu.surface.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.update_quality();
+        // Headless renderers draw into their offscreen texture instead
+        let output = match self.gpu.surface {
+            Some(ref surface) => Some(surface.get_current_texture()?),
+            None => None,
+        };
+        let frame_texture = match (&output, &self.gpu.offscreen) {
+            (Some(output), _) => output.texture.clone(),
+            (None, Some(offscreen)) => offscreen.clone(),
+            (None, None) => anyhow::bail!("Renderer has neither a surface nor an offscreen target"),
+        };
+        let view = frame_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let encode_start = std::time::Instant::now();
+        let frame_scope = self.gpu_errors.push_scope(&self.gpu.device, GpuErrorCapture::All);
 
         let mut encoder = self.gpu.device.create_command_encoder(
             &wgpu::CommandEncoderDescriptor { label: Some("frame_encoder") },
         );
+        let timed = self.quality.is_enabled()
+            && self.gpu_timer.as_ref().is_some_and(|t| t.begin(&mut encoder));
 
-        // Sort sprites by layer → shader_id → blend_mode → texture_id for batching
+        // Sort sprites by layer → y (y-sorted layers) → mask → shader_id → blend_mode → texture_id → normal_map for batching
+        let ysort = &self.ysort_layers;
         self.frame_commands.sort_by(|a, b| {
             a.layer
                 .cmp(&b.layer)
+                .then(ysort_key(a, ysort).total_cmp(&ysort_key(b, ysort)))
+                .then(a.mask.cmp(&b.mask))
                 .then(a.shader_id.cmp(&b.shader_id))
                 .then(a.blend_mode.cmp(&b.blend_mode))
                 .then(a.texture_id.cmp(&b.texture_id))
+                .then(a.normal_map.cmp(&b.normal_map))
         });
 
         // Sort geometry commands by layer
@@ -249,6 +769,7 @@ impl Renderer {
 
         // Sort SDF commands by layer
         self.sdf_commands.sort_by_key(|c| c.layer);
+        self.compile_sdf_pipelines();
 
         // Build interleaved render schedule
         let schedule = build_render_schedule(&self.frame_commands, &self.geo_commands, &self.sdf_commands);
@@ -262,7 +783,7 @@ impl Renderer {
             self.mouse_pos,
         );
 
-        let lighting_uniform = self.lighting.to_uniform();
+        let lighting_uniform = self.lighting_uniform();
         let clear_color = wgpu::Color {
             r: self.clear_color[0] as f64,
             g: self.clear_color[1] as f64,
@@ -273,6 +794,7 @@ impl Renderer {
         // Write camera + lighting uniforms once for the whole frame
         self.sprites.prepare(&self.gpu.device, &self.gpu.queue, &self.camera, &lighting_uniform);
         self.sdf_pipeline.prepare(&self.gpu.queue, &self.camera, 0.0);
+        self.particles.simulate(&self.gpu.queue, &mut encoder, self.delta_time);
 
         // Run radiance cascade GI compute pass (if enabled)
         let gi_active = self.radiance.compute(
@@ -286,113 +808,179 @@ impl Renderer {
             self.camera.viewport_size[1],
         );
 
-        if self.postprocess.has_effects() {
-            // Render to offscreen target, then apply effects to surface
-            {
-                let sprite_target = self.postprocess.sprite_target(&self.gpu);
-                let camera_bg = self.sprites.camera_bind_group();
-
-                if schedule.is_empty() {
-                    // No commands at all — still need to clear
-                    self.sprites.render(
-                        &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
-                        &[], sprite_target, &mut encoder, Some(clear_color),
-                    );
-                } else {
-                    let mut first = true;
-                    for op in &schedule {
-                        let cc = if first { Some(clear_color) } else { None };
-                        first = false;
-                        match op {
-                            RenderOp::Sprites { start, end } => {
-                                self.sprites.render(
-                                    &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
-                                    &self.frame_commands[*start..*end],
-                                    sprite_target, &mut encoder, cc,
-                                );
-                            }
-                            RenderOp::Geometry { start, end } => {
-                                self.geometry.flush_commands(
-                                    &self.gpu.device, &mut encoder, sprite_target,
-                                    camera_bg, &self.geo_commands[*start..*end], cc,
-                                );
-                            }
-                            RenderOp::Sdf { start, end } => {
-                                self.sdf_pipeline.render(
-                                    &self.gpu.device, &mut encoder, sprite_target,
-                                    &self.sdf_commands[*start..*end], cc,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-            // Apply GI light texture to the offscreen target before post-processing
-            if gi_active {
-                let sprite_target = self.postprocess.sprite_target(&self.gpu);
-                self.radiance.compose(&mut encoder, sprite_target);
-            }
-            self.postprocess.apply(&self.gpu, &mut encoder, &view);
+        // Draw into the offscreen target when effects (or upscaling) apply,
+        // otherwise straight into the surface
+        let postprocess = self.postprocess.is_active();
+        let (width, height) = if postprocess {
+            self.postprocess.sprite_target_size(&self.gpu)
         } else {
-            // No effects — render directly to surface
-            let camera_bg = self.sprites.camera_bind_group();
-
-            if schedule.is_empty() {
-                // No commands at all — still need to clear
-                self.sprites.render(
-                    &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
-                    &[], &view, &mut encoder, Some(clear_color),
-                );
-            } else {
-                let mut first = true;
-                for op in &schedule {
-                    let cc = if first { Some(clear_color) } else { None };
-                    first = false;
-                    match op {
-                        RenderOp::Sprites { start, end } => {
-                            self.sprites.render(
-                                &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
-                                &self.frame_commands[*start..*end],
-                                &view, &mut encoder, cc,
-                            );
-                        }
-                        RenderOp::Geometry { start, end } => {
-                            self.geometry.flush_commands(
-                                &self.gpu.device, &mut encoder, &view,
-                                camera_bg, &self.geo_commands[*start..*end], cc,
-                            );
-                        }
-                        RenderOp::Sdf { start, end } => {
-                            self.sdf_pipeline.render(
-                                &self.gpu.device, &mut encoder, &view,
-                                &self.sdf_commands[*start..*end], cc,
-                            );
-                        }
-                    }
-                }
-            }
-            // Apply GI light texture to the surface
-            if gi_active {
-                self.radiance.compose(&mut encoder, &view);
-            }
+            (self.gpu.config.width, self.gpu.config.height)
+        };
+        self.sprites.set_target_size(self.camera.viewport_size, (width, height));
+        let msaa_view = self.msaa.view(&self.gpu.device, self.gpu.config.format, width, height);
+        let stencil = self.write_masks(&mut encoder, width, height);
+        let target = if postprocess { self.postprocess.sprite_target(&self.gpu) } else { &view };
+        let scene = match msaa_view {
+            Some(ref msaa_view) => ColorTarget::multisampled(msaa_view, self.msaa.samples()),
+            None => ColorTarget::from(target),
+        };
+        let scene = match stencil {
+            Some(ref stencil) => scene.with_stencil(stencil),
+            None => scene,
+        };
+        let lists = SceneLists::new(&self.frame_commands, &self.geo_commands, &self.sdf_commands);
+        let mut draw = SceneDraw {
+            gpu: &self.gpu,
+            textures: &self.textures,
+            shaders: &self.shaders,
+            sprites: &mut self.sprites,
+            geometry: &mut self.geometry,
+            sdf: &mut self.sdf_pipeline,
+            particles: &mut self.particles,
+            decals: &mut self.decals,
+        };
+        if self.viewports.is_empty() {
+            draw.draw(&mut encoder, scene, &lists, &schedule, Some(clear_color));
+        } else {
+            draw.draw_viewports(
+                &mut encoder, scene, &lists, &self.viewports, &self.camera,
+                &lighting_uniform, (width, height), clear_color,
+            );
+        }
+        if let Some(ref msaa_view) = msaa_view {
+            msaa::resolve(&mut encoder, msaa_view, target);
+        }
+        // Apply GI light texture before post-processing
+        if gi_active {
+            self.radiance.compose(&mut encoder, target);
+        }
+        render_plugin_passes(
+            &mut self.plugin_passes, &self.gpu, &mut encoder, target,
+            &self.camera, self.elapsed_time, self.delta_time,
+        );
+        if postprocess {
+            self.postprocess.apply(&self.gpu, &mut encoder, &view);
         }
 
+        if timed && let Some(ref timer) = self.gpu_timer {
+            timer.end(&mut encoder);
+        }
+        let submit_start = std::time::Instant::now();
         self.gpu.queue.submit(std::iter::once(encoder.finish()));
+        self.render_timings = RenderTimings {
+            encode_ms: submit_start.duration_since(encode_start).as_secs_f64() * 1000.0,
+            submit_ms: submit_start.elapsed().as_secs_f64() * 1000.0,
+        };
+        self.gpu_errors.pop_scope(&self.gpu.device, frame_scope, GpuErrorSource::Frame);
+        self.sprites.end_frame();
+        if timed && let Some(ref timer) = self.gpu_timer {
+            timer.after_submit();
+        } else if self.quality.is_enabled() && self.gpu_timer.is_none() {
+            // No timestamp queries: approximate with CPU encode + submit time
+            self.record_frame_time(encode_start.elapsed().as_secs_f32() * 1000.0);
+        }
 
         // Capture the rendered frame if requested (before present consumes the surface)
-        if self.capture_pending {
-            self.capture_pending = false;
-            self.capture_result = self.capture_surface(&output.texture);
+        let record = self.recording.as_mut().is_some_and(|r| r.advance(self.delta_time));
+        if self.capture_pending || record {
+            let pixels = self.read_surface(&frame_texture);
+            if self.capture_pending {
+                self.capture_pending = false;
+                self.capture_result = pixels.as_deref().and_then(|p| self.encode_png(p));
+            }
+            if record && let Some(pixels) = pixels && let Some(ref mut recording) = self.recording {
+                recording.push(pixels, self.gpu.config.width, self.gpu.config.height);
+            }
+        }
+        if let Some(path) = self.layer_capture_pending.take() {
+            let merged = self.read_surface(&frame_texture);
+            match self.capture_layers(&schedule, merged).and_then(|capture| {
+                capture.write(&path)?;
+                Ok(capture.layers.len())
+            }) {
+                Ok(count) => println!("[capture] Saved {count} layer(s) to {}", path.display()),
+                Err(e) => eprintln!("[capture] {e:#}"),
+            }
         }
 
-        output.present();
+        if let Some(output) = output {
+            output.present();
+        }
 
         self.frame_commands.clear();
+        self.decals.clear();
         self.geo_commands.clear();
         self.sdf_commands.clear();
+        self.masks.clear();
         Ok(())
     }
 
+    /// Write this frame's masks into the stencil buffer of a `width`×`height`
+    /// scene. None (and no pass) when no sprite is masked.
+    fn write_masks(&mut self, encoder: &mut wgpu::CommandEncoder, width: u32, height: u32) -> Option<wgpu::TextureView> {
+        if !self.frame_commands.iter().any(|c| c.mask.is_some()) {
+            return None;
+        }
+        let samples = self.msaa.samples();
+        let view = self.mask_target.view(&self.gpu.device, width, height, samples);
+        self.mask_pipeline.write(
+            &self.gpu.device, encoder, &view, samples,
+            self.sprites.camera_bind_group(), &self.masks,
+        );
+        Some(view)
+    }
+
+    /// Compile pipelines for SDF expressions new this frame, each in its own
+    /// error scope so a bad expression is reported against itself. Its shapes
+    /// are skipped from then on.
+    fn compile_sdf_pipelines(&mut self) {
+        let samples = self.msaa.samples();
+        compile_sdf_pipelines(&mut self.sdf_pipeline, &mut self.gpu_errors, &self.gpu.device, &self.sdf_commands, samples);
+    }
+
+    /// Draw the scene with `samples` per pixel (1, 2, 4 or 8; 1 turns MSAA
+    /// off). Counts the adapter lacks fall back to the next lower supported
+    /// one. Returns the count in use.
+    pub fn set_msaa(&mut self, samples: u32) -> u32 {
+        let samples = msaa::pick_sample_count(samples, &self.gpu.sample_counts);
+        if samples != self.msaa.samples() {
+            let device = &self.gpu.device;
+            self.sprites.set_msaa_samples(device, samples);
+            self.shaders.set_msaa_samples(device, samples);
+            self.geometry.set_msaa_samples(device, samples);
+            self.sdf_pipeline.set_msaa_samples(samples);
+            self.particles.set_msaa_samples(device, samples);
+            self.msaa.set_samples(samples);
+        }
+        samples
+    }
+
+    /// Samples per pixel the scene is drawn with; 1 when MSAA is off.
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa.samples()
+    }
+
+    /// Pack small textures into shared atlas pages (see `runtime_atlas`), or
+    /// stop and drop the pages.
+    pub fn set_runtime_atlas(&mut self, enabled: bool) {
+        self.atlas.set_enabled(&self.gpu, &mut self.textures, &self.sprites.texture_bind_group_layout, enabled);
+    }
+
+    /// Pack textures uploaded since the last call into the runtime atlas and
+    /// repack it when it is idle and fragmented. Call once per frame.
+    pub fn sync_atlas(&mut self) {
+        let uploaded = self.textures.take_uploaded();
+        let layout = &self.sprites.texture_bind_group_layout;
+        self.atlas.place(&self.gpu, &mut self.textures, layout, &uploaded);
+        self.atlas.maintain(&self.gpu, &mut self.textures, layout);
+    }
+
+    /// Collect decoded textures and upload this frame's share of them (see
+    /// `texture_stream`). Call once per frame.
+    pub fn pump_texture_streams(&mut self) -> Vec<TextureLoadEvent> {
+        self.texture_streams.pump(&self.gpu, &mut self.textures, &self.sprites.texture_bind_group_layout)
+    }
+
     /// Resize the surface when the window size changes.
     /// GPU surface uses physical pixels; camera viewport uses logical pixels.
     pub fn resize(&mut self, physical_width: u32, physical_height: u32, scale_factor: f32) {
@@ -400,7 +988,7 @@ impl Renderer {
             self.scale_factor = scale_factor;
             self.gpu.config.width = physical_width;
             self.gpu.config.height = physical_height;
-            self.gpu.surface.configure(&self.gpu.device, &self.gpu.config);
+            self.gpu.reconfigure();
             // Camera uses logical pixels so 1 world unit ≈ 1 logical pixel at zoom 1
             self.camera.viewport_size = [
                 physical_width as f32 / scale_factor,
@@ -411,8 +999,119 @@ impl Renderer {
 
     // ── Frame capture ─────────────────────────────────────────────────────
 
-    /// Copy the surface texture to a CPU-side PNG. Returns None on failure.
-    fn capture_surface(&self, texture: &wgpu::Texture) -> Option<Vec<u8>> {
+    /// Start recording rendered frames to a GIF at `path`, sampled at `fps` on
+    /// game time. Replaces (and finishes) any recording in progress.
+    pub fn start_recording(&mut self, path: std::path::PathBuf, fps: f32) -> Result<()> {
+        self.stop_recording();
+        let recording = Recording::start(path, fps, self.gpu.config.width, self.gpu.config.height)?;
+        self.recording = Some(recording);
+        Ok(())
+    }
+
+    /// Stop the recording in progress, if any. The file is finished on the
+    /// encoder thread; returns its path and frame count.
+    pub fn stop_recording(&mut self) -> Option<(std::path::PathBuf, u32)> {
+        let recording = self.recording.take()?;
+        let summary = (recording.path().to_path_buf(), recording.frames());
+        let _ = recording.finish();
+        Some(summary)
+    }
+
+    /// Whether a recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Stop the recording in progress and wait until its file is written.
+    pub fn finish_recording(&mut self) -> Option<(std::path::PathBuf, u32)> {
+        let recording = self.recording.take()?;
+        let summary = (recording.path().to_path_buf(), recording.frames());
+        let _ = recording.finish().join();
+        Some(summary)
+    }
+
+    /// Draw each layer of the frame's `schedule` alone over transparency and
+    /// read it back. Post-processing, GI, decals, particles and plugin passes
+    /// only show in `merged`, the frame as displayed.
+    fn capture_layers(&mut self, schedule: &[RenderOp], merged: Option<Vec<u8>>) -> Result<LayerCapture> {
+        let merged = merged.context("Failed to read back the frame")?;
+        let (width, height) = (self.gpu.config.width, self.gpu.config.height);
+        let texture = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("layer_capture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.gpu.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.sprites.set_target_size(self.camera.viewport_size, (width, height));
+
+        let mut layers = Vec::new();
+        for (layer, ops) in layer_schedules(schedule, &self.frame_commands, &self.geo_commands, &self.sdf_commands) {
+            let mut encoder = self.gpu.device.create_command_encoder(
+                &wgpu::CommandEncoderDescriptor { label: Some("layer_capture_encoder") },
+            );
+            let msaa_view = self.msaa.view(&self.gpu.device, self.gpu.config.format, width, height);
+            let stencil = self.write_masks(&mut encoder, width, height);
+            let scene = match msaa_view {
+                Some(ref msaa_view) => ColorTarget::multisampled(msaa_view, self.msaa.samples()),
+                None => ColorTarget::from(&view),
+            };
+            let scene = match stencil {
+                Some(ref stencil) => scene.with_stencil(stencil),
+                None => scene,
+            };
+            let mut cc = Some(wgpu::Color::TRANSPARENT);
+            for op in &ops {
+                match op {
+                    RenderOp::Sprites { start, end } => {
+                        self.sprites.render(
+                            &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
+                            &self.frame_commands[*start..*end],
+                            scene, &mut encoder, cc,
+                        );
+                    }
+                    RenderOp::Geometry { start, end } => {
+                        self.geometry.flush_commands_textured(
+                            &self.gpu.device, &mut encoder, scene,
+                            self.sprites.camera_bind_group(), &self.textures, &self.geo_commands[*start..*end], cc,
+                        );
+                    }
+                    RenderOp::Sdf { start, end } => {
+                        self.sdf_pipeline.render(
+                            &self.gpu.device, &mut encoder, scene,
+                            &self.sdf_commands[*start..*end], cc,
+                        );
+                    }
+                }
+                cc = None;
+            }
+            if let Some(ref msaa_view) = msaa_view {
+                msaa::resolve(&mut encoder, msaa_view, &view);
+            }
+            self.gpu.queue.submit(std::iter::once(encoder.finish()));
+            self.sprites.end_submit();
+            let pixels = self.read_surface(&texture).context("Failed to read back a layer")?;
+            layers.push(CapturedLayer { layer, pixels });
+        }
+        Ok(LayerCapture { width, height, merged, layers })
+    }
+
+    /// Encode tightly packed RGBA pixels of the surface size as a PNG.
+    fn encode_png(&self, pixels: &[u8]) -> Option<Vec<u8>> {
+        use image::ImageEncoder;
+        let mut png_bytes = Vec::new();
+        let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
+        let (width, height) = (self.gpu.config.width, self.gpu.config.height);
+        encoder.write_image(pixels, width, height, image::ExtendedColorType::Rgba8).ok()?;
+        Some(png_bytes)
+    }
+
+    /// Copy the surface texture to CPU-side RGBA pixels. Returns None on failure.
+    fn read_surface(&self, texture: &wgpu::Texture) -> Option<Vec<u8>> {
         let width = self.gpu.config.width;
         let height = self.gpu.config.height;
         let bytes_per_pixel: u32 = 4;
@@ -483,16 +1182,7 @@ impl Renderer {
 
         drop(data);
         buffer.unmap();
-
-        // Encode to PNG using the `image` crate
-        use image::ImageEncoder;
-        let mut png_bytes = Vec::new();
-        let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
-        if encoder.write_image(&pixels, width, height, image::ExtendedColorType::Rgba8).is_err() {
-            return None;
-        }
-
-        Some(png_bytes)
+        Some(pixels)
     }
 
     // ── Render target helpers ──────────────────────────────────────────────
@@ -517,60 +1207,203 @@ impl Renderer {
     pub fn destroy_render_target(&mut self, id: u32) {
         self.render_targets.destroy(id);
         self.textures.unregister_render_target(id);
+        self.target_postprocess.remove(&id);
+    }
+
+    /// Compile a custom shader. If its WGSL fails validation the error is
+    /// logged against it and the previous version of the ID stays in use (a new
+    /// shader is dropped, so its sprites are skipped).
+    pub fn create_shader(&mut self, id: u32, name: &str, source: &str) {
+        let line_offset = self.shaders.user_line_offset(source);
+        let source_info = GpuErrorSource::Shader { id, name: name.to_string(), line_offset };
+        let failed = self.gpu_errors.scoped(&self.gpu.device, GpuErrorCapture::Pipelines, source_info, || {
+            self.shaders.create(&self.gpu.device, id, name, source);
+        });
+        if failed {
+            self.shaders.revert(id);
+        }
+    }
+
+    /// Append a built-in effect to the screen post-process chain.
+    pub fn add_effect(&mut self, effect_id: u32, effect_type: postprocess::EffectType) {
+        let source = GpuErrorSource::Effect { id: effect_id, effect: format!("{effect_type:?}") };
+        self.gpu_errors.scoped(&self.gpu.device, GpuErrorCapture::Pipelines, source, || {
+            self.postprocess.add(&self.gpu.device, effect_id, effect_type);
+        });
+    }
+
+    /// Compile a custom screen transition shader. A shader that fails
+    /// validation is logged and dropped, falling back to a fade.
+    pub fn add_transition_shader(&mut self, id: u32, source: &str) {
+        let line_offset = postprocess::transition_line_offset(source);
+        let source_info = GpuErrorSource::Transition { id, line_offset };
+        let failed = self.gpu_errors.scoped(&self.gpu.device, GpuErrorCapture::Pipelines, source_info, || {
+            self.postprocess.add_transition_shader(&self.gpu.device, id, source);
+        });
+        if failed {
+            self.postprocess.remove_transition_shader(id);
+        }
+    }
+
+    /// Append an effect to a render target's post-process chain. Effect IDs
+    /// share the bridge's counter with screen effects, so `set_target_effect_param`
+    /// and `remove_target_effect` need no target ID.
+    pub fn add_target_effect(&mut self, target_id: u32, effect_id: u32, effect_type: postprocess::EffectType) {
+        let chain = self
+            .target_postprocess
+            .entry(target_id)
+            .or_insert_with(|| PostProcessPipeline::new(&self.gpu));
+        let source = GpuErrorSource::Effect { id: effect_id, effect: format!("{effect_type:?}") };
+        self.gpu_errors.scoped(&self.gpu.device, GpuErrorCapture::Pipelines, source, || {
+            chain.add(&self.gpu.device, effect_id, effect_type);
+        });
+    }
+
+    /// Set a param slot on a render target effect. Unknown IDs are ignored.
+    pub fn set_target_effect_param(&mut self, effect_id: u32, index: u32, values: [f32; 4]) {
+        for chain in self.target_postprocess.values_mut() {
+            chain.set_param(effect_id, index, values[0], values[1], values[2], values[3]);
+        }
+    }
+
+    /// Remove an effect from whichever render target chain holds it.
+    pub fn remove_target_effect(&mut self, effect_id: u32) {
+        for chain in self.target_postprocess.values_mut() {
+            chain.remove(effect_id);
+        }
     }
 
-    /// Render sprite commands into each queued render target (off-screen pre-pass).
+    /// Render each queued render target's sprite, geometry and SDF commands
+    /// (off-screen pre-pass), interleaved by layer like the main scene.
+    /// Each target is drawn with its entry in `cameras`, or the default
+    /// [`TargetCamera`] ((0, 0) at the top-left) when it has none. Targets with
+    /// an effect chain in `target_postprocess` are drawn through it.
+    ///
+    /// Targets that sample other targets are drawn after them (see
+    /// [`rendertarget::render_order`]), otherwise in ascending ID order.
+    /// Commands sampling the target they are drawn into are skipped.
     ///
     /// Call this BEFORE `render_frame()` so targets are ready as sprite inputs.
-    /// Uses a separate command encoder + GPU submit to avoid ordering conflicts.
+    /// Each target gets its own command encoder + GPU submit, so the camera
+    /// uniforms written for one target are not overwritten by the next.
     pub fn render_targets_prepass(
         &mut self,
-        target_queues: std::collections::HashMap<u32, Vec<SpriteCommand>>,
+        mut sprite_queues: std::collections::BTreeMap<u32, Vec<SpriteCommand>>,
+        mut geo_queues: std::collections::BTreeMap<u32, Vec<GeoCommand>>,
+        mut sdf_queues: std::collections::BTreeMap<u32, Vec<SdfDrawCommand>>,
+        cameras: &std::collections::BTreeMap<u32, TargetCamera>,
     ) {
-        if target_queues.is_empty() {
+        use std::collections::{BTreeMap, BTreeSet};
+
+        // Which other targets each queued target samples
+        let mut samples: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+        for (&id, cmds) in &sprite_queues {
+            samples.entry(id).or_default().extend(cmds.iter().map(|c| c.texture_id));
+        }
+        for (&id, cmds) in &geo_queues {
+            samples.entry(id).or_default().extend(cmds.iter().filter_map(GeoCommand::texture_id));
+        }
+        for &id in sdf_queues.keys() {
+            samples.entry(id).or_default();
+        }
+        for (id, sampled) in samples.iter_mut() {
+            sampled.retain(|t| t != id && self.render_targets.get_view(*t).is_some());
+        }
+        if samples.is_empty() {
             return;
         }
 
-        let mut encoder = self.gpu.device.create_command_encoder(
-            &wgpu::CommandEncoderDescriptor { label: Some("rt_encoder") },
-        );
-        let lighting_uniform = self.lighting.to_uniform();
-
-        for (target_id, mut cmds) in target_queues {
-            let view = self.render_targets.get_view(target_id);
-            let dims = self.render_targets.get_dims(target_id);
-            if let (Some(view), Some((tw, th))) = (view, dims) {
-                // Sort by layer → shader_id → blend_mode → texture_id
-                cmds.sort_by(|a, b| {
-                    a.layer
-                        .cmp(&b.layer)
-                        .then(a.shader_id.cmp(&b.shader_id))
-                        .then(a.blend_mode.cmp(&b.blend_mode))
-                        .then(a.texture_id.cmp(&b.texture_id))
-                });
-                // Orthographic camera: (0,0) = top-left of the render target
-                let target_camera = Camera2D {
-                    x: tw as f32 / 2.0,
-                    y: th as f32 / 2.0,
-                    zoom: 1.0,
-                    viewport_size: [tw as f32, th as f32],
-                    ..Camera2D::default()
-                };
-                self.sprites.prepare(&self.gpu.device, &self.gpu.queue, &target_camera, &lighting_uniform);
+        let lighting_uniform = self.lighting_uniform();
+        let clear = wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+
+        for target_id in rendertarget::render_order(&samples) {
+            let mut cmds = sprite_queues.remove(&target_id).unwrap_or_default();
+            let mut geo = geo_queues.remove(&target_id).unwrap_or_default();
+            let sdf = sdf_queues.remove(&target_id).unwrap_or_default();
+            let Some((tw, th)) = self.render_targets.get_dims(target_id) else {
+                continue;
+            };
+            let target_camera = cameras.get(&target_id).copied().unwrap_or_default();
+            cmds.retain(|c| target_camera.shows_layer(c.layer) && c.texture_id != target_id);
+            geo.retain(|c| target_camera.shows_layer(c.layer()) && c.texture_id() != Some(target_id));
+            let mut sdf: Vec<SdfCommand> = sdf
+                .into_iter()
+                .filter(|c| target_camera.shows_layer(c.layer))
+                .map(convert_sdf_draw_command)
+                .collect();
+            // Sort by layer → y (y-sorted layers) → shader_id → blend_mode → texture_id → normal_map
+            let ysort = &self.ysort_layers;
+            cmds.sort_by(|a, b| {
+                a.layer
+                    .cmp(&b.layer)
+                    .then(ysort_key(a, ysort).total_cmp(&ysort_key(b, ysort)))
+                    .then(a.shader_id.cmp(&b.shader_id))
+                    .then(a.blend_mode.cmp(&b.blend_mode))
+                    .then(a.texture_id.cmp(&b.texture_id))
+                    .then(a.normal_map.cmp(&b.normal_map))
+            });
+            geo.sort_by_key(|c| c.layer());
+            sdf.sort_by_key(|c| c.layer);
+            compile_sdf_pipelines(&mut self.sdf_pipeline, &mut self.gpu_errors, &self.gpu.device, &sdf, 1);
+            let schedule = build_render_schedule(&cmds, &geo, &sdf);
+
+            let mut encoder = self.gpu.device.create_command_encoder(
+                &wgpu::CommandEncoderDescriptor { label: Some("rt_encoder") },
+            );
+            let camera = target_camera.camera(tw, th);
+            self.sprites.prepare(&self.gpu.device, &self.gpu.queue, &camera, &lighting_uniform);
+            self.sprites.set_target_size(camera.viewport_size, (tw, th));
+            self.sdf_pipeline.prepare(&self.gpu.queue, &camera, 0.0);
+
+            let Some(view) = self.render_targets.get_view(target_id) else {
+                continue;
+            };
+            // With an effect chain, draw into its target-sized intermediate
+            // and let the chain write the result into the target
+            let mut post = self.target_postprocess.get_mut(&target_id);
+            if let Some(chain) = post.as_mut() {
+                chain.effects_enabled = self.postprocess.effects_enabled;
+                chain.set_target_size(tw, th);
+            }
+            let mut post = post.filter(|chain| chain.is_active());
+            let dest = match post.as_mut() {
+                Some(chain) => chain.sprite_target(&self.gpu),
+                None => view,
+            };
+
+            if schedule.is_empty() {
                 self.sprites.render(
-                    &self.gpu.device,
-                    &self.gpu.queue,
-                    &self.textures,
-                    &self.shaders,
-                    &cmds,
-                    view,
-                    &mut encoder,
-                    Some(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 }),
+                    &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
+                    &[], dest, &mut encoder, Some(clear),
                 );
             }
-        }
+            for (i, op) in schedule.iter().enumerate() {
+                let cc = if i == 0 { Some(clear) } else { None };
+                match op {
+                    RenderOp::Sprites { start, end } => {
+                        self.sprites.render(
+                            &self.gpu.device, &self.gpu.queue, &self.textures, &self.shaders,
+                            &cmds[*start..*end], dest, &mut encoder, cc,
+                        );
+                    }
+                    RenderOp::Geometry { start, end } => {
+                        self.geometry.flush_commands_textured(
+                            &self.gpu.device, &mut encoder, dest,
+                            self.sprites.camera_bind_group(), &self.textures, &geo[*start..*end], cc,
+                        );
+                    }
+                    RenderOp::Sdf { start, end } => {
+                        self.sdf_pipeline.render(&self.gpu.device, &mut encoder, dest, &sdf[*start..*end], cc);
+                    }
+                }
+            }
+            if let Some(chain) = post {
+                chain.apply(&self.gpu, &mut encoder, view);
+            }
 
-        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+            self.gpu.queue.submit(std::iter::once(encoder.finish()));
+            self.sprites.end_submit();
+        }
     }
 }
 
@@ -587,7 +1420,7 @@ mod tests {
             tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
             rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
             flip_x: false, flip_y: false, opacity: 1.0,
-            blend_mode: 0, shader_id: 0,
+            blend_mode: 0, shader_id: 0, nine_slice: None, outline: None, clip: None, mask: None, normal_map: None, flash: None, pixel_snap: None,
         }
     }
 
@@ -621,6 +1454,60 @@ mod tests {
         }
     }
 
+    // ── y-sort tests ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_ysort_key_uses_the_pivot_on_ysorted_layers_only() {
+        let ysort = std::collections::BTreeSet::from([2]);
+        let mut feet = sprite(2);
+        feet.y = 100.0;
+        feet.h = 32.0;
+        feet.origin_y = 1.0;
+        assert_eq!(ysort_key(&feet, &ysort), 132.0);
+        feet.layer = 1;
+        assert_eq!(ysort_key(&feet, &ysort), 0.0);
+    }
+
+    #[test]
+    fn test_snap_to_pixels_follows_the_default_unless_overridden() {
+        let camera = Camera2D { x: 0.0, y: 0.0, zoom: 2.0, ..Default::default() };
+        let mut cmds = vec![sprite(0), sprite(0), sprite(0)];
+        for cmd in &mut cmds {
+            (cmd.x, cmd.y) = (10.3, 5.8);
+        }
+        cmds[1].pixel_snap = Some(false);
+        cmds[2].pixel_snap = Some(true);
+        snap_to_pixels(&mut cmds, &camera, true);
+        let positions: Vec<(f32, f32)> = cmds.iter().map(|c| (c.x, c.y)).collect();
+        assert_eq!(positions, [(10.5, 6.0), (10.3, 5.8), (10.5, 6.0)]);
+
+        for cmd in &mut cmds {
+            (cmd.x, cmd.y) = (10.3, 5.8);
+        }
+        snap_to_pixels(&mut cmds, &camera, false);
+        assert_eq!((cmds[0].x, cmds[1].x, cmds[2].x), (10.3, 10.3, 10.5));
+    }
+
+    #[test]
+    fn test_ysort_orders_by_feet_within_the_layer() {
+        let ysort = std::collections::BTreeSet::from([0]);
+        // A tall tree whose base is below a short character's feet draws in front
+        let mut tree = sprite(0);
+        tree.texture_id = 1;
+        tree.y = 0.0;
+        tree.h = 96.0;
+        tree.origin_y = 1.0;
+        let mut hero = sprite(0);
+        hero.texture_id = 2;
+        hero.y = 60.0;
+        hero.h = 24.0;
+        hero.origin_y = 1.0;
+        let mut cmds = vec![tree, hero, sprite(-1)];
+        cmds.sort_by(|a, b| a.layer.cmp(&b.layer).then(ysort_key(a, &ysort).total_cmp(&ysort_key(b, &ysort))));
+        let order: Vec<(i32, u32)> = cmds.iter().map(|c| (c.layer, c.texture_id)).collect();
+        assert_eq!(order, [(-1, 1), (0, 2), (0, 1)]);
+    }
+
     // ── build_render_schedule tests ──────────────────────────────────────
 
     #[test]
@@ -636,6 +1523,39 @@ mod tests {
         assert_eq!(schedule, vec![RenderOp::Sprites { start: 0, end: 2 }]);
     }
 
+    #[test]
+    fn test_layer_schedules_split_ops_at_layer_boundaries() {
+        // Sprites 0-2 form one op across layers 0 and 1; geo sits on layer 1
+        let sprites = vec![sprite(0), sprite(0), sprite(1), sprite(3)];
+        let geo_cmds = vec![geo(1)];
+        let sdf_cmds = vec![sdf(3)];
+        let schedule = build_render_schedule(&sprites, &geo_cmds, &sdf_cmds);
+        let layers = layer_schedules(&schedule, &sprites, &geo_cmds, &sdf_cmds);
+        assert_eq!(
+            layers,
+            vec![
+                (0, vec![RenderOp::Sprites { start: 0, end: 2 }]),
+                (1, vec![RenderOp::Sprites { start: 2, end: 3 }, RenderOp::Geometry { start: 0, end: 1 }]),
+                (3, vec![RenderOp::Sprites { start: 3, end: 4 }, RenderOp::Sdf { start: 0, end: 1 }]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scene_lists_cut_to_a_layer_range() {
+        let sprites = vec![sprite(-5), sprite(0), sprite(3), sprite(100)];
+        let geo_cmds = vec![geo(1), geo(200)];
+        let sdf_cmds = vec![sdf(3)];
+        let lists = SceneLists::new(&sprites, &geo_cmds, &sdf_cmds);
+        let world = lists.layers(0, 99);
+        assert_eq!((world.sprites.len(), world.geo.len(), world.sdf.len()), (2, 1, 1));
+        assert_eq!(world.sprites[0].layer, 0);
+        assert_eq!(world.schedule().len(), 4);
+        let hud = lists.layers(100, i32::MAX);
+        assert_eq!((hud.sprites.len(), hud.geo.len(), hud.sdf.len()), (1, 1, 0));
+        assert!(lists.layers(4, 99).schedule().is_empty());
+    }
+
     #[test]
     fn test_schedule_geo_only() {
         let geo_cmds = vec![geo(0), geo(1)];
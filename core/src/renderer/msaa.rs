@@ -0,0 +1,129 @@
+//! Multisample anti-aliasing for the scene.
+//!
+//! With MSAA on, the scene passes (sprites, geometry, SDF shapes, particles)
+//! draw into a multisampled texture the size of the scene target instead of
+//! the target itself, and one resolve pass averages it into the target before
+//! GI composition, plugin passes and post-processing, which all keep working
+//! on single-sampled textures. Render target pre-passes stay single-sampled.
+
+/// Sample counts `setMsaa` accepts; the adapter may support fewer.
+pub const MSAA_SAMPLE_COUNTS: [u32; 4] = [1, 2, 4, 8];
+
+/// The largest count in `supported` not above `requested` (at least 1).
+pub fn pick_sample_count(requested: u32, supported: &[u32]) -> u32 {
+    supported
+        .iter()
+        .copied()
+        .filter(|&n| n <= requested && MSAA_SAMPLE_COUNTS.contains(&n))
+        .max()
+        .unwrap_or(1)
+}
+
+struct MsaaTexture {
+    #[allow(dead_code)]
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+/// The multisampled scene texture, recreated when the scene target's size or
+/// the sample count changes.
+pub struct MsaaTarget {
+    samples: u32,
+    texture: Option<MsaaTexture>,
+}
+
+impl Default for MsaaTarget {
+    fn default() -> Self {
+        Self { samples: 1, texture: None }
+    }
+}
+
+impl MsaaTarget {
+    /// Samples per pixel; 1 when MSAA is off.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Set the sample count (already checked against the adapter).
+    pub fn set_samples(&mut self, samples: u32) {
+        if samples != self.samples {
+            self.samples = samples.max(1);
+            self.texture = None;
+        }
+    }
+
+    /// The view to draw the scene into for a `width`×`height` target, or
+    /// `None` when MSAA is off.
+    pub fn view(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Option<wgpu::TextureView> {
+        if self.samples <= 1 {
+            return None;
+        }
+        let stale = self.texture.as_ref().is_none_or(|t| (t.width, t.height) != (width, height));
+        if stale {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("msaa_scene"),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: self.samples,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.texture = Some(MsaaTexture { texture, view, width, height });
+        }
+        self.texture.as_ref().map(|t| t.view.clone())
+    }
+}
+
+/// Resolve the multisampled scene into `target` with an empty pass.
+pub fn resolve(encoder: &mut wgpu::CommandEncoder, msaa_view: &wgpu::TextureView, target: &wgpu::TextureView) {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("msaa_resolve_pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: msaa_view,
+            resolve_target: Some(target),
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: wgpu::StoreOp::Discard,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_sample_count_falls_back_to_supported() {
+        assert_eq!(pick_sample_count(4, &[1, 4]), 4);
+        assert_eq!(pick_sample_count(8, &[1, 4]), 4);
+        assert_eq!(pick_sample_count(2, &[1, 4]), 1);
+        assert_eq!(pick_sample_count(8, &[1, 2, 4, 8, 16]), 8);
+        assert_eq!(pick_sample_count(16, &[1, 2, 4, 8, 16]), 8);
+        assert_eq!(pick_sample_count(0, &[1, 4]), 1);
+    }
+
+    #[test]
+    fn test_set_samples_drops_the_texture() {
+        let mut target = MsaaTarget::default();
+        assert_eq!(target.samples(), 1);
+        target.set_samples(4);
+        assert_eq!(target.samples(), 4);
+        target.set_samples(0);
+        assert_eq!(target.samples(), 1);
+    }
+}
@@ -68,6 +68,107 @@ impl MsdfFont {
         }
         width
     }
+
+    /// Lay out `text` at `font_size` pixels the way `drawText` places it:
+    /// each glyph advances the cursor, characters the font lacks take no
+    /// space. Lines end at `\n` and, when `max_width` is positive, wrap at the
+    /// last space or CJK character boundary that fits. A word wider than
+    /// `max_width` keeps a line of its own rather than being split.
+    pub fn layout(&self, text: &str, font_size: f32, max_width: f32) -> TextLayout {
+        let scale = font_size / self.font_size;
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for paragraph in text.split('\n') {
+            self.wrap_paragraph(paragraph, offset, scale, max_width, &mut lines);
+            offset += paragraph.encode_utf16().count() + 1;
+        }
+        let line_height = self.line_height * scale;
+        TextLayout {
+            width: lines.iter().map(|l| l.width).fold(0.0, f32::max),
+            height: line_height * lines.len() as f32,
+            line_height,
+            lines,
+        }
+    }
+
+    /// Greedy wrap of one paragraph (no `\n`) starting at UTF-16 `offset`.
+    fn wrap_paragraph(&self, text: &str, offset: usize, scale: f32, max_width: f32, lines: &mut Vec<TextLine>) {
+        let mut start = offset;
+        let mut pos = offset;
+        let mut width = 0.0f32;
+        // Last place the line can end: (end, line width there, next line start, width before it)
+        let mut wrap: Option<(usize, f32, usize, f32)> = None;
+        for ch in text.chars() {
+            let advance = self.get_glyph(ch).map_or(0.0, |g| g.advance * scale);
+            if is_cjk(ch) && pos > start {
+                wrap = Some((pos, width, pos, width));
+            }
+            if max_width > 0.0
+                && width + advance > max_width
+                && ch != ' '
+                && let Some((end, end_width, next, next_from)) = wrap.take()
+            {
+                lines.push(TextLine { start, end, width: end_width });
+                start = next;
+                width -= next_from;
+            }
+            let len = ch.len_utf16();
+            if ch == ' ' {
+                wrap = Some((pos, width, pos + len, width + advance));
+            }
+            width += advance;
+            pos += len;
+            if is_cjk(ch) {
+                wrap = Some((pos, width, pos, width));
+            }
+        }
+        lines.push(TextLine { start, end: pos, width });
+    }
+}
+
+/// CJK ideographs, kana and fullwidth forms, which lines can wrap between
+/// without spaces.
+fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF | 0x20000..=0x2FA1F)
+}
+
+/// Text measured by [`MsdfFont::layout`], in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    /// Width of the widest line.
+    pub width: f32,
+    /// `line_height` times the number of lines.
+    pub height: f32,
+    pub line_height: f32,
+    pub lines: Vec<TextLine>,
+}
+
+/// One laid-out line. `start..end` are UTF-16 offsets into the text (what
+/// `String.prototype.slice` takes), without the space or `\n` it broke at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub start: usize,
+    pub end: usize,
+    pub width: f32,
+}
+
+impl TextLayout {
+    /// The layout as JSON: `{"width","height","lineHeight","lines":[{"start","end","width"}]}`.
+    pub fn to_json(&self) -> String {
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|l| format!("{{\"start\":{},\"end\":{},\"width\":{}}}", l.start, l.end, l.width))
+            .collect();
+        format!(
+            "{{\"width\":{},\"height\":{},\"lineHeight\":{},\"lines\":[{}]}}",
+            self.width,
+            self.height,
+            self.line_height,
+            lines.join(","),
+        )
+    }
 }
 
 /// MSDF font storage, keyed by a font ID.
@@ -105,6 +206,11 @@ impl MsdfFontStore {
     pub fn get(&self, id: u32) -> Option<&MsdfFont> {
         self.fonts.get(&id)
     }
+
+    /// Get a font by ID for adding glyphs.
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut MsdfFont> {
+        self.fonts.get_mut(&id)
+    }
 }
 
 /// The MSDF fragment shader source (used with ShaderStore::create).
@@ -673,6 +779,65 @@ mod tests {
         assert!((width - 32.0).abs() < 0.01, "Expected ~32, got {width}");
     }
 
+    fn line_texts<'a>(text: &'a str, layout: &TextLayout) -> Vec<&'a str> {
+        // Test strings are ASCII, so UTF-16 offsets are byte offsets
+        layout.lines.iter().map(|l| &text[l.start..l.end]).collect()
+    }
+
+    #[test]
+    fn layout_wraps_at_spaces_and_newlines() {
+        let (_, _, _, font) = generate_builtin_msdf_font();
+        // 8px per glyph: 10 glyphs fit in 80px
+        let text = "the quick brown fox\njumps";
+        let layout = font.layout(text, 8.0, 80.0);
+        assert_eq!(line_texts(text, &layout), ["the quick", "brown fox", "jumps"]);
+        assert_eq!(layout.width, 72.0);
+        assert_eq!(layout.line_height, 8.0);
+        assert_eq!(layout.height, 24.0);
+        assert_eq!(layout.lines[2].width, 40.0);
+
+        // No max width: only newlines break, and width matches measure_width
+        let single = font.layout("the quick brown fox", 16.0, 0.0);
+        assert_eq!(single.lines.len(), 1);
+        assert_eq!(single.width, font.measure_width("the quick brown fox", 16.0));
+    }
+
+    #[test]
+    fn layout_keeps_long_words_whole() {
+        let (_, _, _, font) = generate_builtin_msdf_font();
+        let text = "a extraordinarily b";
+        let layout = font.layout(text, 8.0, 40.0);
+        assert_eq!(line_texts(text, &layout), ["a", "extraordinarily", "b"]);
+        assert_eq!(layout.width, 120.0);
+        assert_eq!(font.layout("", 8.0, 40.0).lines, [TextLine { start: 0, end: 0, width: 0.0 }]);
+    }
+
+    #[test]
+    fn layout_counts_utf16_offsets_and_breaks_between_cjk() {
+        let mut font = generate_builtin_msdf_font().3;
+        let glyph = font.get_glyph('A').unwrap().clone();
+        for ch in ['日', '本', '語', '😀'] {
+            font.glyphs.insert(ch as u32, glyph.clone());
+        }
+        let layout = font.layout("日本語日本", 8.0, 24.0);
+        let spans: Vec<(usize, usize)> = layout.lines.iter().map(|l| (l.start, l.end)).collect();
+        assert_eq!(spans, [(0, 3), (3, 5)]);
+
+        // The emoji is one glyph but two UTF-16 units; glyphs the font lacks take no space
+        let layout = font.layout("😀é A", 8.0, 0.0);
+        assert_eq!(layout.lines[0].end, 5);
+        assert_eq!(layout.width, 24.0);
+    }
+
+    #[test]
+    fn layout_json_shape() {
+        let (_, _, _, font) = generate_builtin_msdf_font();
+        assert_eq!(
+            font.layout("hi", 8.0, 0.0).to_json(),
+            r#"{"width":16,"height":8,"lineHeight":8,"lines":[{"start":0,"end":2,"width":16}]}"#
+        );
+    }
+
     #[test]
     fn parse_metrics_basic() {
         let json = r#"{
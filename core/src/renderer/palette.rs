@@ -0,0 +1,328 @@
+//! Dominant color palettes, extracted with k-means.
+//!
+//! Pixels are first binned into a 15-bit color histogram, so palettes of
+//! whole art packs cost the same to cluster as one small sprite. Fully or
+//! mostly transparent pixels are ignored.
+//!
+//! `arcane assets palette` writes the result as JSON, as a GIMP palette
+//! (`.gpl`) or as a palette strip: an N×1 image with one pixel per color, the
+//! usual lookup texture for palette-swap and palette-snapping shaders.
+
+use crate::json::escape;
+
+/// Channel bits kept per color in the histogram.
+const BIN_BITS: u32 = 5;
+/// Pixels with alpha below this don't count.
+const MIN_ALPHA: u8 = 128;
+/// Lloyd iterations before giving up on convergence.
+const MAX_ITERATIONS: usize = 24;
+
+/// One palette color and the share of counted pixels closest to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swatch {
+    pub rgb: [u8; 3],
+    /// 0-1; the shares of a palette add up to 1.
+    pub share: f32,
+}
+
+impl Swatch {
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.rgb[0], self.rgb[1], self.rgb[2])
+    }
+}
+
+/// Color histogram fed from any number of images.
+#[derive(Clone)]
+pub struct ColorHistogram {
+    /// Per bin: pixel count and channel sums (for the bin's mean color).
+    bins: Vec<(u32, [u64; 3])>,
+    total: u64,
+}
+
+impl Default for ColorHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ColorHistogram {
+    pub fn new() -> Self {
+        Self {
+            bins: vec![(0, [0; 3]); 1 << (3 * BIN_BITS)],
+            total: 0,
+        }
+    }
+
+    /// Count the opaque pixels of an RGBA8 buffer.
+    pub fn add_rgba(&mut self, rgba: &[u8]) {
+        let shift = 8 - BIN_BITS;
+        for px in rgba.chunks_exact(4) {
+            if px[3] < MIN_ALPHA {
+                continue;
+            }
+            let index = ((px[0] >> shift) as usize) << (2 * BIN_BITS)
+                | ((px[1] >> shift) as usize) << BIN_BITS
+                | (px[2] >> shift) as usize;
+            let bin = &mut self.bins[index];
+            bin.0 += 1;
+            bin.1[0] += px[0] as u64;
+            bin.1[1] += px[1] as u64;
+            bin.1[2] += px[2] as u64;
+            self.total += 1;
+        }
+    }
+
+    /// Number of pixels counted so far.
+    pub fn pixel_count(&self) -> u64 {
+        self.total
+    }
+
+    /// Up to `count` dominant colors, most common first. Deterministic: the
+    /// same pixels always give the same palette.
+    pub fn palette(&self, count: usize) -> Vec<Swatch> {
+        // Mean color and pixel count of each non-empty bin
+        let points: Vec<([f32; 3], f32)> = self
+            .bins
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|&(n, sums)| {
+                let n_f = n as f32;
+                (
+                    [
+                        sums[0] as f32 / n_f,
+                        sums[1] as f32 / n_f,
+                        sums[2] as f32 / n_f,
+                    ],
+                    n_f,
+                )
+            })
+            .collect();
+        if points.is_empty() || count == 0 {
+            return Vec::new();
+        }
+
+        let mut centroids = seed_centroids(&points, count);
+        let mut assignment = vec![usize::MAX; points.len()];
+        for _ in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            for (i, (color, _)) in points.iter().enumerate() {
+                let nearest = nearest(&centroids, color);
+                if assignment[i] != nearest {
+                    assignment[i] = nearest;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+            let mut sums = vec![([0.0f64; 3], 0.0f64); centroids.len()];
+            for (i, (color, weight)) in points.iter().enumerate() {
+                let sum = &mut sums[assignment[i]];
+                for (total, channel) in sum.0.iter_mut().zip(color) {
+                    *total += (channel * weight) as f64;
+                }
+                sum.1 += *weight as f64;
+            }
+            for (centroid, (sum, weight)) in centroids.iter_mut().zip(&sums) {
+                if *weight > 0.0 {
+                    *centroid = [
+                        (sum[0] / weight) as f32,
+                        (sum[1] / weight) as f32,
+                        (sum[2] / weight) as f32,
+                    ];
+                }
+            }
+        }
+
+        let mut weights = vec![0.0f32; centroids.len()];
+        for (i, (_, weight)) in points.iter().enumerate() {
+            weights[assignment[i]] += weight;
+        }
+        let total: f32 = weights.iter().sum();
+        let mut swatches: Vec<Swatch> = centroids
+            .iter()
+            .zip(&weights)
+            .filter(|(_, w)| **w > 0.0)
+            .map(|(c, w)| Swatch {
+                rgb: [c[0].round() as u8, c[1].round() as u8, c[2].round() as u8],
+                share: w / total,
+            })
+            .collect();
+        swatches.sort_by(|a, b| b.share.total_cmp(&a.share).then(a.rgb.cmp(&b.rgb)));
+        swatches
+    }
+}
+
+fn distance_sq(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn nearest(centroids: &[[f32; 3]], color: &[f32; 3]) -> usize {
+    let mut best = 0;
+    for (i, c) in centroids.iter().enumerate() {
+        if distance_sq(c, color) < distance_sq(&centroids[best], color) {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Deterministic k-means++ seeding: start from the most common color, then
+/// repeatedly add the color with the largest weight × distance² to the
+/// centroids so far.
+fn seed_centroids(points: &[([f32; 3], f32)], count: usize) -> Vec<[f32; 3]> {
+    let first = points
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|p| p.0)
+        .unwrap_or_default();
+    let mut centroids = vec![first];
+    let mut closest: Vec<f32> = points.iter().map(|p| distance_sq(&p.0, &first)).collect();
+    while centroids.len() < count {
+        let (best, score) = points
+            .iter()
+            .zip(&closest)
+            .enumerate()
+            .map(|(i, (p, d))| (i, p.1 * d))
+            .fold((0, 0.0f32), |acc, x| if x.1 > acc.1 { x } else { acc });
+        if score <= 0.0 {
+            // Fewer distinct colors than requested
+            break;
+        }
+        let next = points[best].0;
+        centroids.push(next);
+        for (d, p) in closest.iter_mut().zip(points) {
+            *d = d.min(distance_sq(&p.0, &next));
+        }
+    }
+    centroids
+}
+
+/// `{"source":"...","colors":[{"hex":"#1a1c2c","rgb":[26,28,44],"share":0.31},...]}`
+pub fn palette_json(source: &str, swatches: &[Swatch]) -> String {
+    let colors: Vec<String> = swatches
+        .iter()
+        .map(|s| {
+            format!(
+                "{{\"hex\":\"{}\",\"rgb\":[{},{},{}],\"share\":{:.4}}}",
+                s.hex(),
+                s.rgb[0],
+                s.rgb[1],
+                s.rgb[2],
+                s.share
+            )
+        })
+        .collect();
+    format!(
+        "{{\"source\":\"{}\",\"colors\":[{}]}}",
+        escape(source),
+        colors.join(",")
+    )
+}
+
+/// GIMP palette file, readable by GIMP, Aseprite, Krita and Inkscape.
+pub fn palette_gpl(name: &str, swatches: &[Swatch]) -> String {
+    let mut out = format!(
+        "GIMP Palette\nName: {name}\nColumns: {}\n#\n",
+        swatches.len().min(16)
+    );
+    for s in swatches {
+        out.push_str(&format!(
+            "{:3} {:3} {:3}\t{}\n",
+            s.rgb[0],
+            s.rgb[1],
+            s.rgb[2],
+            s.hex()
+        ));
+    }
+    out
+}
+
+/// RGBA8 pixels of an N×1 palette strip, one opaque pixel per swatch.
+pub fn palette_strip(swatches: &[Swatch]) -> Vec<u8> {
+    swatches
+        .iter()
+        .flat_map(|s| [s.rgb[0], s.rgb[1], s.rgb[2], 255])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(colors: &[([u8; 4], usize)]) -> Vec<u8> {
+        colors
+            .iter()
+            .flat_map(|&(c, n)| std::iter::repeat_n(c, n).flatten())
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_dominant_colors_in_order() {
+        let mut h = ColorHistogram::new();
+        h.add_rgba(&image(&[
+            ([200, 30, 30, 255], 60),
+            ([205, 35, 28, 255], 20),
+            ([20, 20, 200, 255], 15),
+            ([250, 250, 250, 255], 5),
+        ]));
+        let palette = h.palette(3);
+        assert_eq!(palette.len(), 3);
+        // The two reds merge into one swatch
+        assert!(palette[0].rgb[0] > 195 && palette[0].rgb[2] < 40);
+        assert!((palette[0].share - 0.8).abs() < 1e-4);
+        assert_eq!(palette[1].rgb, [20, 20, 200]);
+        assert_eq!(palette[2].hex(), "#fafafa");
+    }
+
+    #[test]
+    fn test_ignores_transparent_pixels_and_caps_count() {
+        let mut h = ColorHistogram::new();
+        h.add_rgba(&image(&[([0, 0, 0, 0], 100), ([10, 200, 10, 255], 4)]));
+        assert_eq!(h.pixel_count(), 4);
+        let palette = h.palette(8);
+        assert_eq!(palette.len(), 1);
+        assert_eq!(palette[0].rgb, [10, 200, 10]);
+        assert_eq!(palette[0].share, 1.0);
+        assert!(ColorHistogram::new().palette(8).is_empty());
+    }
+
+    #[test]
+    fn test_accumulates_several_images_deterministically() {
+        let a = image(&[([255, 0, 0, 255], 10), ([0, 255, 0, 255], 30)]);
+        let b = image(&[([0, 0, 255, 255], 20), ([230, 0, 0, 255], 5)]);
+        let mut h = ColorHistogram::new();
+        h.add_rgba(&a);
+        h.add_rgba(&b);
+        let first = h.palette(3);
+        assert_eq!(first, h.palette(3));
+        assert_eq!(first[0].rgb, [0, 255, 0]);
+        assert_eq!(first[1].rgb, [0, 0, 255]);
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let swatches = [
+            Swatch {
+                rgb: [26, 28, 44],
+                share: 0.75,
+            },
+            Swatch {
+                rgb: [255, 205, 117],
+                share: 0.25,
+            },
+        ];
+        assert_eq!(
+            palette_json("a\"b.png", &swatches),
+            "{\"source\":\"a\\\"b.png\",\"colors\":[{\"hex\":\"#1a1c2c\",\"rgb\":[26,28,44],\"share\":0.7500},\
+             {\"hex\":\"#ffcd75\",\"rgb\":[255,205,117],\"share\":0.2500}]}"
+        );
+        let gpl = palette_gpl("sweetie", &swatches);
+        assert!(gpl.starts_with("GIMP Palette\nName: sweetie\nColumns: 2\n#\n"));
+        assert!(gpl.ends_with("255 205 117\t#ffcd75\n"));
+        assert_eq!(
+            palette_strip(&swatches),
+            vec![26, 28, 44, 255, 255, 205, 117, 255]
+        );
+    }
+}
@@ -0,0 +1,760 @@
+//! GPU particle simulation.
+//!
+//! Each emitter owns a storage buffer of particles that a compute pass advances
+//! every frame; the same buffer is drawn as instanced quads, so particles never
+//! pass through the scripting layer. TS only sends emitter definitions, bursts
+//! and parameter changes (`op_create_gpu_emitter`, `op_emit_gpu_burst`,
+//! `op_set_gpu_emitter_param`), queued as [`GpuParticleCommand`]s and applied
+//! by the dev loop.
+//!
+//! Particles are allocated from a ring buffer. The CPU decides how many start
+//! each frame (continuous rate plus queued bursts); the compute shader
+//! initializes those slots from a hash of slot and frame number. When more
+//! particles are alive than the emitter holds, the oldest are replaced.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::gpu::{multisample_state, ColorTarget, GpuContext};
+use super::texture::TextureStore;
+use crate::json::JsonValue;
+
+/// Particle slots allocated when an emitter does not set `maxParticles`.
+const DEFAULT_MAX_PARTICLES: u32 = 1024;
+
+/// Largest particle buffer a single emitter may allocate.
+const MAX_PARTICLES_LIMIT: u32 = 1 << 20;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Emitter definition. Field names in JSON and `set_param` match the TS
+/// `GpuEmitterDef` keys; pairs and colors are passed as arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmitterDef {
+    pub x: f32,
+    pub y: f32,
+    /// Particle slots (fixed at creation).
+    pub max_particles: u32,
+    /// Particles spawned per second. 0 = bursts only.
+    pub rate: f32,
+    /// Lifetime range in seconds.
+    pub lifetime: [f32; 2],
+    /// Initial speed range in pixels/second.
+    pub speed: [f32; 2],
+    /// Emission direction in radians.
+    pub direction: f32,
+    /// Full cone angle around `direction`, in radians.
+    pub spread: f32,
+    /// Particles start at a random point within this radius of the emitter.
+    pub spawn_radius: f32,
+    /// Acceleration in pixels/second².
+    pub gravity: [f32; 2],
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+    /// Velocity multiplier at birth and at death (linear in between).
+    pub speed_curve: [f32; 2],
+    /// Quad size in pixels at birth and at death.
+    pub size: [f32; 2],
+    pub color_start: [f32; 4],
+    /// Color at `color_mid_time`. Defaults to halfway between start and end.
+    pub color_mid: Option<[f32; 4]>,
+    pub color_end: [f32; 4],
+    /// Point in life (0-1) where `color_mid` applies.
+    pub color_mid_time: f32,
+    /// Texture for each quad. 0 = soft round dot.
+    pub texture_id: u32,
+    pub additive: bool,
+    pub layer: i32,
+}
+
+impl Default for EmitterDef {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            max_particles: DEFAULT_MAX_PARTICLES,
+            rate: 50.0,
+            lifetime: [0.5, 1.5],
+            speed: [20.0, 80.0],
+            direction: -std::f32::consts::FRAC_PI_2, // upward
+            spread: std::f32::consts::PI,
+            spawn_radius: 0.0,
+            gravity: [0.0, 0.0],
+            drag: 0.0,
+            speed_curve: [1.0, 1.0],
+            size: [4.0, 4.0],
+            color_start: [1.0, 1.0, 1.0, 1.0],
+            color_mid: None,
+            color_end: [1.0, 1.0, 1.0, 0.0],
+            color_mid_time: 0.5,
+            texture_id: 0,
+            additive: false,
+            layer: 0,
+        }
+    }
+}
+
+impl EmitterDef {
+    /// Build a definition from a JSON object, starting from the defaults.
+    /// Unknown keys are ignored.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value = JsonValue::parse(json)?;
+        let fields = value.as_object().ok_or("emitter definition must be an object")?;
+        let mut def = Self::default();
+        for (name, field) in fields {
+            let mut v = [0.0f32; 4];
+            match field {
+                JsonValue::Array(items) => {
+                    for (slot, item) in v.iter_mut().zip(items) {
+                        *slot = item.as_f32().unwrap_or(0.0);
+                    }
+                }
+                JsonValue::Bool(b) => v[0] = if *b { 1.0 } else { 0.0 },
+                JsonValue::String(s) => v[0] = if s == "additive" { 1.0 } else { 0.0 },
+                other => v[0] = other.as_f32().unwrap_or(0.0),
+            }
+            def.set_param(name, v);
+        }
+        Ok(def)
+    }
+
+    /// Set one parameter by its TS name. Returns false for unknown names.
+    pub fn set_param(&mut self, name: &str, v: [f32; 4]) -> bool {
+        let pair = [v[0], v[1]];
+        match name {
+            "x" => self.x = v[0],
+            "y" => self.y = v[0],
+            "maxParticles" => self.max_particles = (v[0].max(1.0) as u32).min(MAX_PARTICLES_LIMIT),
+            "rate" => self.rate = v[0].max(0.0),
+            "lifetime" => self.lifetime = [pair[0].max(0.001), pair[1].max(pair[0]).max(0.001)],
+            "speed" => self.speed = pair,
+            "direction" => self.direction = v[0],
+            "spread" => self.spread = v[0],
+            "spawnRadius" => self.spawn_radius = v[0].max(0.0),
+            "gravity" => self.gravity = pair,
+            "drag" => self.drag = v[0].max(0.0),
+            "speedCurve" => self.speed_curve = pair,
+            "size" => self.size = pair,
+            "colorStart" => self.color_start = v,
+            "colorMid" => self.color_mid = Some(v),
+            "colorEnd" => self.color_end = v,
+            "colorMidTime" => self.color_mid_time = v[0].clamp(0.0, 1.0),
+            "textureId" => self.texture_id = v[0].max(0.0) as u32,
+            "blend" | "additive" => self.additive = v[0] > 0.5,
+            "layer" => self.layer = v[0] as i32,
+            _ => return false,
+        }
+        true
+    }
+
+    fn color_mid_or_default(&self) -> [f32; 4] {
+        self.color_mid.unwrap_or_else(|| {
+            std::array::from_fn(|i| (self.color_start[i] + self.color_end[i]) * 0.5)
+        })
+    }
+}
+
+/// Emitter changes queued by the scripting ops, applied by the dev loop.
+#[derive(Debug, Clone)]
+pub enum GpuParticleCommand {
+    Create { id: u32, def: EmitterDef },
+    Burst { id: u32, count: u32 },
+    SetParam { id: u32, name: String, value: [f32; 4] },
+    Destroy { id: u32 },
+}
+
+/// Decides which ring buffer slots start a new particle each frame.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Spawner {
+    /// Fractional particles carried over from the continuous rate.
+    carry: f32,
+    /// Burst particles waiting for the next frame.
+    pending: u32,
+    /// Next slot to spawn into.
+    cursor: u32,
+}
+
+impl Spawner {
+    /// Advance by `dt` and return the `(start, count)` window of slots to
+    /// (re)initialize this frame. Never more than `capacity` slots.
+    fn advance(&mut self, dt: f32, rate: f32, capacity: u32) -> (u32, u32) {
+        self.carry += rate * dt;
+        let continuous = self.carry.floor();
+        self.carry -= continuous;
+        let count = (continuous as u32).saturating_add(self.pending).min(capacity);
+        self.pending = 0;
+        let start = self.cursor;
+        self.cursor = (self.cursor + count) % capacity;
+        (start, count)
+    }
+}
+
+/// Uniform data for one emitter. Layout matches `EmitterParams` in particles.wgsl.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct EmitterUniform {
+    origin_dt: [f32; 4],
+    spawn: [u32; 4],
+    lifetime_speed: [f32; 4],
+    emit: [f32; 4],
+    gravity_curve: [f32; 4],
+    size: [f32; 4],
+    color_start: [f32; 4],
+    color_mid: [f32; 4],
+    color_end: [f32; 4],
+}
+
+impl EmitterUniform {
+    fn new(def: &EmitterDef, dt: f32, spawn: (u32, u32), frame: u32) -> Self {
+        let shape = if def.texture_id == 0 { 1.0 } else { 0.0 };
+        Self {
+            origin_dt: [def.x, def.y, dt, shape],
+            spawn: [spawn.0, spawn.1, def.max_particles, frame],
+            lifetime_speed: [def.lifetime[0], def.lifetime[1], def.speed[0], def.speed[1]],
+            emit: [def.direction, def.spread, def.spawn_radius, def.drag],
+            gravity_curve: [def.gravity[0], def.gravity[1], def.speed_curve[0], def.speed_curve[1]],
+            size: [def.size[0], def.size[1], def.color_mid_time, 0.0],
+            color_start: def.color_start,
+            color_mid: def.color_mid_or_default(),
+            color_end: def.color_end,
+        }
+    }
+}
+
+/// One particle as stored on the GPU. Layout matches `Particle` in particles.wgsl.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct GpuParticle {
+    pos: [f32; 2],
+    vel: [f32; 2],
+    age: f32,
+    life: f32,
+    _pad: [f32; 2],
+}
+
+/// Per-vertex data for the centered unit quad.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct QuadVertex {
+    position: [f32; 2],
+    uv: [f32; 2],
+}
+
+const QUAD_VERTICES: &[QuadVertex] = &[
+    QuadVertex { position: [-0.5, -0.5], uv: [0.0, 0.0] },
+    QuadVertex { position: [0.5, -0.5], uv: [1.0, 0.0] },
+    QuadVertex { position: [0.5, 0.5], uv: [1.0, 1.0] },
+    QuadVertex { position: [-0.5, 0.5], uv: [0.0, 1.0] },
+];
+
+const QUAD_INDICES: &[u16] = &[0, 1, 2, 0, 2, 3];
+
+struct GpuEmitter {
+    id: u32,
+    def: EmitterDef,
+    spawner: Spawner,
+    particles: wgpu::Buffer,
+    params: wgpu::Buffer,
+    sim_bind_group: wgpu::BindGroup,
+    draw_bind_group: wgpu::BindGroup,
+}
+
+/// Build the particle draw pipelines ([alpha, additive]) for `samples` per pixel.
+fn build_draw_pipelines(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
+    samples: u32,
+) -> [wgpu::RenderPipeline; 2] {
+    let vertex_layout = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &[
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+            wgpu::VertexAttribute {
+                offset: 8,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+        ],
+    };
+
+    // The particle storage buffer doubles as the instance buffer
+    let particle_layout = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<GpuParticle>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Instance,
+        attributes: &[
+            // pos
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 2,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+            // vel
+            wgpu::VertexAttribute {
+                offset: 8,
+                shader_location: 3,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+            // age, life
+            wgpu::VertexAttribute {
+                offset: 16,
+                shader_location: 4,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+        ],
+    };
+
+    let blends = [
+        wgpu::BlendState::ALPHA_BLENDING,
+        wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        },
+    ];
+    blends.map(|blend| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("particle_draw_pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[vertex_layout.clone(), particle_layout.clone()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(blend),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: multisample_state(samples),
+            multiview: None,
+            cache: None,
+        })
+    })
+}
+
+pub struct GpuParticles {
+    sim_pipeline: wgpu::ComputePipeline,
+    sim_bind_group_layout: wgpu::BindGroupLayout,
+    /// [alpha, additive]
+    draw_pipelines: [wgpu::RenderPipeline; 2],
+    /// The same for the MSAA sample count in use, if any.
+    msaa_draw_pipelines: Option<(u32, [wgpu::RenderPipeline; 2])>,
+    shader: wgpu::ShaderModule,
+    draw_layout: wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
+    params_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    /// Holds the 1x1 white texture drawn by untextured emitters.
+    white: TextureStore,
+    white_id: u32,
+    /// Sorted by layer, then creation order.
+    emitters: Vec<GpuEmitter>,
+    /// Emitters already drawn this frame (see `render`).
+    drawn: usize,
+    frame: u32,
+}
+
+impl GpuParticles {
+    pub fn new(gpu: &GpuContext) -> Self {
+        Self::new_internal(&gpu.device, &gpu.queue, gpu.config.format)
+    }
+
+    /// Create for headless testing (no window/surface required).
+    pub fn new_headless(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        Self::new_internal(device, queue, format)
+    }
+
+    fn new_internal(device: &wgpu::Device, queue: &wgpu::Queue, surface_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("particle_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/particles.wgsl").into()),
+        });
+
+        let uniform_entry = |binding, visibility| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let sim_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle_sim_bind_group_layout"),
+            entries: &[
+                uniform_entry(1, wgpu::ShaderStages::COMPUTE),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let sim_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("particle_sim_layout"),
+            bind_group_layouts: &[&sim_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sim_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("particle_simulate"),
+            layout: Some(&sim_layout),
+            module: &shader,
+            entry_point: Some("simulate"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        // Same camera and texture layouts as the sprite pipeline, so its camera
+        // bind group and TextureStore bind groups work here.
+        let camera_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle_camera_bind_group_layout"),
+            entries: &[uniform_entry(0, wgpu::ShaderStages::VERTEX)],
+        });
+
+        let texture_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle_texture_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let params_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle_params_bind_group_layout"),
+            entries: &[uniform_entry(0, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT)],
+        });
+
+        let draw_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("particle_draw_layout"),
+            bind_group_layouts: &[&camera_bgl, &texture_bgl, &params_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let draw_pipelines = build_draw_pipelines(device, &shader, &draw_layout, surface_format, 1);
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("particle_quad_vertex_buffer"),
+            contents: bytemuck::cast_slice(QUAD_VERTICES),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("particle_quad_index_buffer"),
+            contents: bytemuck::cast_slice(QUAD_INDICES),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let mut white = TextureStore::new();
+        let white_id = white.create_solid_color(device, queue, &texture_bgl, "particle_white", 255, 255, 255, 255);
+
+        Self {
+            sim_pipeline,
+            sim_bind_group_layout,
+            draw_pipelines,
+            msaa_draw_pipelines: None,
+            shader,
+            draw_layout,
+            surface_format,
+            params_bind_group_layout,
+            vertex_buffer,
+            index_buffer,
+            white,
+            white_id,
+            emitters: Vec::new(),
+            drawn: 0,
+            frame: 0,
+        }
+    }
+
+    /// Build draw pipelines for multisampled targets with `samples` per
+    /// pixel; 1 drops them. Single-sampled pipelines are always kept.
+    pub fn set_msaa_samples(&mut self, device: &wgpu::Device, samples: u32) {
+        if samples <= 1 {
+            self.msaa_draw_pipelines = None;
+        } else if self.msaa_draw_pipelines.as_ref().map(|(n, _)| *n) != Some(samples) {
+            let pipelines = build_draw_pipelines(device, &self.shader, &self.draw_layout, self.surface_format, samples);
+            self.msaa_draw_pipelines = Some((samples, pipelines));
+        }
+    }
+
+    /// Number of live emitters.
+    pub fn emitter_count(&self) -> usize {
+        self.emitters.len()
+    }
+
+    /// Apply a command queued by the scripting ops.
+    pub fn apply(&mut self, device: &wgpu::Device, command: GpuParticleCommand) {
+        match command {
+            GpuParticleCommand::Create { id, def } => self.create(device, id, def),
+            GpuParticleCommand::Burst { id, count } => {
+                if let Some(e) = self.emitters.iter_mut().find(|e| e.id == id) {
+                    e.spawner.pending = e.spawner.pending.saturating_add(count);
+                }
+            }
+            GpuParticleCommand::SetParam { id, name, value } => {
+                if let Some(e) = self.emitters.iter_mut().find(|e| e.id == id) {
+                    if name == "maxParticles" {
+                        eprintln!("[particles] maxParticles can only be set when creating an emitter");
+                    } else if !e.def.set_param(&name, value) {
+                        eprintln!("[particles] Unknown GPU emitter parameter \"{name}\"");
+                    }
+                }
+                self.emitters.sort_by_key(|e| e.def.layer);
+            }
+            GpuParticleCommand::Destroy { id } => self.emitters.retain(|e| e.id != id),
+        }
+    }
+
+    fn create(&mut self, device: &wgpu::Device, id: u32, def: EmitterDef) {
+        let particles = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("particle_buffer"),
+            contents: bytemuck::cast_slice(&vec![GpuParticle::zeroed(); def.max_particles as usize]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+        });
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("particle_params_buffer"),
+            contents: bytemuck::cast_slice(&[EmitterUniform::new(&def, 0.0, (0, 0), 0)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let sim_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle_sim_bind_group"),
+            layout: &self.sim_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: particles.as_entire_binding(),
+                },
+            ],
+        });
+        let draw_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle_params_bind_group"),
+            layout: &self.params_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: params.as_entire_binding(),
+            }],
+        });
+        self.emitters.retain(|e| e.id != id);
+        self.emitters.push(GpuEmitter {
+            id,
+            def,
+            spawner: Spawner::default(),
+            particles,
+            params,
+            sim_bind_group,
+            draw_bind_group,
+        });
+        self.emitters.sort_by_key(|e| e.def.layer);
+    }
+
+    /// Spawn and integrate every emitter's particles by `dt` seconds.
+    /// Call once per frame, before `render`.
+    pub fn simulate(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, dt: f32) {
+        self.drawn = 0;
+        if self.emitters.is_empty() {
+            return;
+        }
+        self.frame = self.frame.wrapping_add(1);
+
+        for emitter in &mut self.emitters {
+            let spawn = emitter.spawner.advance(dt, emitter.def.rate, emitter.def.max_particles);
+            let uniform = EmitterUniform::new(&emitter.def, dt, spawn, self.frame);
+            queue.write_buffer(&emitter.params, 0, bytemuck::cast_slice(&[uniform]));
+        }
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("particle_simulate_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.sim_pipeline);
+        for emitter in &self.emitters {
+            pass.set_bind_group(0, &emitter.sim_bind_group, &[]);
+            pass.dispatch_workgroups(emitter.def.max_particles.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+    }
+
+    /// Draw emitters again from the first one at or above `layer`, to draw
+    /// the frame once more through another viewport.
+    pub fn restart(&mut self, layer: i32) {
+        self.drawn = self.emitters.partition_point(|e| e.def.layer < layer);
+    }
+
+    /// Draw the emitters not yet drawn this frame whose layer is below
+    /// `below_layer` (all remaining when `None`). Returns whether anything was
+    /// drawn, i.e. whether `clear_color` was used.
+    pub fn render<'t>(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: impl Into<ColorTarget<'t>>,
+        camera_bind_group: &wgpu::BindGroup,
+        textures: &TextureStore,
+        below_layer: Option<i32>,
+        clear_color: Option<wgpu::Color>,
+    ) -> bool {
+        let target = target.into();
+        let start = self.drawn;
+        let end = match below_layer {
+            Some(layer) => start + self.emitters[start..].iter().take_while(|e| e.def.layer < layer).count(),
+            None => self.emitters.len(),
+        };
+        if start == end {
+            return false;
+        }
+        self.drawn = end;
+
+        let load = match clear_color {
+            Some(color) => wgpu::LoadOp::Clear(color),
+            None => wgpu::LoadOp::Load,
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("particle_render_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        target.apply_viewport(&mut pass);
+        pass.set_bind_group(0, camera_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+
+        let draw_pipelines = match (target.samples, &self.msaa_draw_pipelines) {
+            (1, _) => &self.draw_pipelines,
+            (samples, Some((n, pipelines))) if *n == samples => pipelines,
+            _ => return true,
+        };
+        let Some(white) = self.white.get_bind_group(self.white_id) else {
+            return true;
+        };
+        for emitter in &self.emitters[start..end] {
+            let texture = match emitter.def.texture_id {
+                0 => white,
+                id => textures.get_bind_group(id).unwrap_or(white),
+            };
+            pass.set_pipeline(&draw_pipelines[emitter.def.additive as usize]);
+            pass.set_bind_group(1, texture, &[]);
+            pass.set_bind_group(2, &emitter.draw_bind_group, &[]);
+            pass.set_vertex_buffer(1, emitter.particles.slice(..));
+            pass.draw_indexed(0..6, 0, 0..emitter.def.max_particles);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_def_from_json_overrides_defaults() {
+        let def = EmitterDef::from_json(
+            r#"{"x":10,"rate":200,"lifetime":[1,2],"colorStart":[1,0.5,0,1],"blend":"additive","layer":3,"bogus":1}"#,
+        )
+        .unwrap();
+        assert_eq!(def.x, 10.0);
+        assert_eq!(def.rate, 200.0);
+        assert_eq!(def.lifetime, [1.0, 2.0]);
+        assert_eq!(def.color_start, [1.0, 0.5, 0.0, 1.0]);
+        assert!(def.additive);
+        assert_eq!(def.layer, 3);
+        assert_eq!(def.max_particles, DEFAULT_MAX_PARTICLES);
+        assert!(EmitterDef::from_json("[1]").is_err());
+    }
+
+    #[test]
+    fn test_set_param_clamps() {
+        let mut def = EmitterDef::default();
+        assert!(def.set_param("maxParticles", [1e9, 0.0, 0.0, 0.0]));
+        assert_eq!(def.max_particles, MAX_PARTICLES_LIMIT);
+        assert!(def.set_param("lifetime", [2.0, 1.0, 0.0, 0.0]));
+        assert_eq!(def.lifetime, [2.0, 2.0]);
+        assert!(!def.set_param("nope", [0.0; 4]));
+        assert_eq!(def.color_mid_or_default(), [1.0, 1.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_spawner_rate_bursts_and_wrap() {
+        let mut s = Spawner::default();
+        // 10/s for 0.25s: 2 now, 0.5 carried
+        assert_eq!(s.advance(0.25, 10.0, 8), (0, 2));
+        s.pending = 3;
+        assert_eq!(s.advance(0.25, 10.0, 8), (2, 6));
+        // Cursor wraps around the ring
+        assert_eq!(s.advance(0.25, 10.0, 8), (0, 2));
+        // A burst larger than the buffer is capped
+        s.pending = 100;
+        assert_eq!(s.advance(0.0, 0.0, 8), (2, 8));
+        assert_eq!(s.cursor, 2);
+    }
+
+    #[test]
+    fn test_particle_shader_validates() {
+        let module = naga::front::wgsl::parse_str(include_str!("shaders/particles.wgsl"))
+            .expect("particles.wgsl should parse");
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+            .validate(&module)
+            .expect("particles.wgsl should validate");
+        assert_eq!(std::mem::size_of::<GpuParticle>(), 32);
+        assert_eq!(std::mem::size_of::<EmitterUniform>(), 144);
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use wgpu::util::DeviceExt;
 
 use super::gpu::GpuContext;
+use super::transition::{TransitionKind, TransitionOverlay};
 
 /// Maximum user-settable vec4 param slots per effect.
 const MAX_EFFECT_PARAMS: usize = 4;
@@ -79,12 +82,18 @@ struct OffscreenTarget {
     texture: wgpu::Texture,
     view: wgpu::TextureView,
     bind_group: wgpu::BindGroup,
+    /// Same texture with nearest filtering, for the sharp upscale pass.
+    nearest_bind_group: wgpu::BindGroup,
     width: u32,
     height: u32,
 }
 
 /// Post-processing pipeline: renders sprites to offscreen texture,
 /// applies fullscreen effects (ping-pong), outputs to surface.
+///
+/// Also implements dynamic resolution: below a render scale of 1.0 the scene
+/// is drawn to smaller offscreen targets and upscaled to the surface with
+/// nearest filtering, even when no effects are active.
 pub struct PostProcessPipeline {
     /// Ordered list of (id, effect). Applied in insertion order.
     effects: Vec<(u32, EffectEntry)>,
@@ -96,7 +105,23 @@ pub struct PostProcessPipeline {
     params_bind_group_layout: wgpu::BindGroupLayout,
     pipeline_layout: wgpu::PipelineLayout,
     sampler: wgpu::Sampler,
+    nearest_sampler: wgpu::Sampler,
     surface_format: wgpu::TextureFormat,
+    /// Offscreen resolution relative to the surface (see `set_render_scale`).
+    render_scale: f32,
+    /// Fixed offscreen size for a render target's chain (see `set_target_size`).
+    target_size: Option<(u32, u32)>,
+    /// When false, effects are kept but skipped (adaptive quality).
+    pub effects_enabled: bool,
+    upscale_pipeline: wgpu::RenderPipeline,
+    /// Unused params for the upscale pass (the pipeline layout expects group 1).
+    upscale_params: wgpu::BindGroup,
+    /// Screen transition drawn this frame, after the effects.
+    transition: Option<TransitionOverlay>,
+    /// Built-in transition pipelines (created on first use) and custom ones.
+    transition_pipelines: HashMap<TransitionKind, wgpu::RenderPipeline>,
+    transition_param_buffer: wgpu::Buffer,
+    transition_params: wgpu::BindGroup,
 }
 
 impl PostProcessPipeline {
@@ -175,6 +200,51 @@ impl PostProcessPipeline {
             min_filter: wgpu::FilterMode::Linear,
             ..Default::default()
         });
+        let nearest_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("postprocess_nearest_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let upscale_pipeline = create_effect_pipeline(
+            device,
+            &pipeline_layout,
+            surface_format,
+            &build_effect_wgsl(UPSCALE_FRAGMENT),
+        );
+        let upscale_param_buffer =
+            device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("postprocess_upscale_params"),
+                    contents: bytemuck::cast_slice(&[0.0f32; PARAM_FLOATS]),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+        let upscale_params = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("postprocess_upscale_param_bind_group"),
+            layout: &params_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: upscale_param_buffer.as_entire_binding(),
+            }],
+        });
+        let transition_param_buffer =
+            device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("postprocess_transition_params"),
+                    contents: bytemuck::cast_slice(&[0.0f32; PARAM_FLOATS]),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+        let transition_params = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("postprocess_transition_param_bind_group"),
+            layout: &params_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: transition_param_buffer.as_entire_binding(),
+            }],
+        });
 
         Self {
             effects: Vec::new(),
@@ -184,7 +254,17 @@ impl PostProcessPipeline {
             params_bind_group_layout,
             pipeline_layout,
             sampler,
+            nearest_sampler,
             surface_format,
+            render_scale: 1.0,
+            target_size: None,
+            effects_enabled: true,
+            upscale_pipeline,
+            upscale_params,
+            transition: None,
+            transition_pipelines: HashMap::new(),
+            transition_param_buffer,
+            transition_params,
         }
     }
 
@@ -193,47 +273,39 @@ impl PostProcessPipeline {
         !self.effects.is_empty()
     }
 
+    /// True when the scene must be drawn to `sprite_target()` and finished with
+    /// `apply()`: effects or a screen transition are running, or the render
+    /// scale is below 1.0.
+    pub fn is_active(&self) -> bool {
+        self.active_effect_count() > 0 || self.render_scale < 1.0 || self.transition.is_some()
+    }
+
+    fn active_effect_count(&self) -> usize {
+        if self.effects_enabled { self.effects.len() } else { 0 }
+    }
+
+    /// Render the scene at `scale` × the surface resolution (clamped to
+    /// 0.25..=1.0). Offscreen targets are resized on the next frame.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale.clamp(0.25, 1.0);
+    }
+
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    /// Size the offscreen targets to a render target instead of the surface,
+    /// for a chain applied to that target before it is sampled.
+    pub fn set_target_size(&mut self, width: u32, height: u32) {
+        self.target_size = Some((width.max(1), height.max(1)));
+    }
+
     /// Add an effect. The id is pre-assigned by the bridge.
     pub fn add(&mut self, device: &wgpu::Device, id: u32, effect_type: EffectType) {
         let wgsl = build_effect_wgsl(effect_type.fragment_source());
 
-        let shader_module =
-            device
-                .create_shader_module(wgpu::ShaderModuleDescriptor {
-                    label: Some("postprocess_shader"),
-                    source: wgpu::ShaderSource::Wgsl(wgsl.into()),
-                });
-
         let pipeline =
-            device
-                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                    label: Some("postprocess_pipeline"),
-                    layout: Some(&self.pipeline_layout),
-                    vertex: wgpu::VertexState {
-                        module: &shader_module,
-                        entry_point: Some("vs_main"),
-                        buffers: &[], // fullscreen triangle via vertex_index
-                        compilation_options: Default::default(),
-                    },
-                    fragment: Some(wgpu::FragmentState {
-                        module: &shader_module,
-                        entry_point: Some("fs_main"),
-                        targets: &[Some(wgpu::ColorTargetState {
-                            format: self.surface_format,
-                            blend: None,
-                            write_mask: wgpu::ColorWrites::ALL,
-                        })],
-                        compilation_options: Default::default(),
-                    }),
-                    primitive: wgpu::PrimitiveState {
-                        topology: wgpu::PrimitiveTopology::TriangleList,
-                        ..Default::default()
-                    },
-                    depth_stencil: None,
-                    multisample: wgpu::MultisampleState::default(),
-                    multiview: None,
-                    cache: None,
-                });
+            create_effect_pipeline(device, &self.pipeline_layout, self.surface_format, &wgsl);
 
         let param_data = effect_type.defaults();
 
@@ -287,10 +359,70 @@ impl PostProcessPipeline {
         self.effects.clear();
     }
 
-    /// Ensure offscreen targets exist and match surface dimensions.
+    /// Set the screen transition to draw over the next frames, or `None`.
+    /// It runs after the effects, so it also covers UI and post effects.
+    pub fn set_transition(&mut self, overlay: Option<TransitionOverlay>) {
+        self.transition = overlay;
+    }
+
+    /// Compile a custom transition fragment shader, used by
+    /// `TransitionKind::Custom(id)`. See `TRANSITION_FADE_FRAGMENT` for the
+    /// params layout.
+    pub fn add_transition_shader(&mut self, device: &wgpu::Device, id: u32, source: &str) {
+        let pipeline = create_effect_pipeline(
+            device,
+            &self.pipeline_layout,
+            self.surface_format,
+            &build_effect_wgsl(source),
+        );
+        self.transition_pipelines
+            .insert(TransitionKind::Custom(id), pipeline);
+    }
+
+    /// Drop a custom transition shader; transitions using it fall back to a fade.
+    pub fn remove_transition_shader(&mut self, id: u32) {
+        self.transition_pipelines.remove(&TransitionKind::Custom(id));
+    }
+
+    /// Pipeline key for `kind`, compiling built-ins on first use. Unknown
+    /// custom shaders fall back to a fade.
+    fn transition_pipeline_key(
+        &mut self,
+        device: &wgpu::Device,
+        kind: TransitionKind,
+    ) -> TransitionKind {
+        let source = match kind {
+            TransitionKind::Fade => TRANSITION_FADE_FRAGMENT,
+            TransitionKind::CircleWipe => TRANSITION_CIRCLE_FRAGMENT,
+            TransitionKind::Pixelate => TRANSITION_PIXELATE_FRAGMENT,
+            TransitionKind::Custom(_) => {
+                if self.transition_pipelines.contains_key(&kind) {
+                    return kind;
+                }
+                return self.transition_pipeline_key(device, TransitionKind::Fade);
+            }
+        };
+        if !self.transition_pipelines.contains_key(&kind) {
+            let pipeline = create_effect_pipeline(
+                device,
+                &self.pipeline_layout,
+                self.surface_format,
+                &build_effect_wgsl(source),
+            );
+            self.transition_pipelines.insert(kind, pipeline);
+        }
+        kind
+    }
+
+    /// Ensure offscreen targets exist and match the scaled surface dimensions
+    /// (or the render target size, if set).
     fn ensure_targets(&mut self, gpu: &GpuContext) {
-        let w = gpu.config.width;
-        let h = gpu.config.height;
+        let (w, h) = self.target_size.unwrap_or_else(|| {
+            (
+                ((gpu.config.width as f32 * self.render_scale).round() as u32).max(1),
+                ((gpu.config.height as f32 * self.render_scale).round() as u32).max(1),
+            )
+        });
 
         let needs_recreate = self
             .target_a
@@ -343,11 +475,26 @@ impl PostProcessPipeline {
                 },
             ],
         });
+        let nearest_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("{label}_nearest_bind_group")),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.nearest_sampler),
+                },
+            ],
+        });
 
         OffscreenTarget {
             texture,
             view,
             bind_group,
+            nearest_bind_group,
             width,
             height,
         }
@@ -360,7 +507,15 @@ impl PostProcessPipeline {
         &self.target_a.as_ref().unwrap().view
     }
 
-    /// Apply all effects and output to the surface.
+    /// Size of `sprite_target()` in pixels.
+    pub fn sprite_target_size(&mut self, gpu: &GpuContext) -> (u32, u32) {
+        self.ensure_targets(gpu);
+        let target = self.target_a.as_ref().unwrap();
+        (target.width, target.height)
+    }
+
+    /// Apply all effects and output to the surface, upscaling last when the
+    /// render scale is below 1.0.
     /// Call after sprites have been rendered to sprite_target().
     pub fn apply(
         &mut self,
@@ -368,12 +523,19 @@ impl PostProcessPipeline {
         encoder: &mut wgpu::CommandEncoder,
         surface_view: &wgpu::TextureView,
     ) {
-        let n = self.effects.len();
-        if n == 0 {
+        let n = self.active_effect_count();
+        let transition = self
+            .transition
+            .map(|t| (t, self.transition_pipeline_key(&gpu.device, t.kind)));
+        let passes = n + usize::from(transition.is_some()) + usize::from(self.render_scale < 1.0);
+        if passes == 0 {
             return;
         }
+        self.ensure_targets(gpu);
 
-        let resolution = [gpu.config.width as f32, gpu.config.height as f32];
+        // Effects run at the offscreen resolution
+        let target = self.target_a.as_ref().unwrap();
+        let resolution = [target.width as f32, target.height as f32];
 
         // Flush all param buffers with current resolution
         for (_, entry) in self.effects.iter_mut() {
@@ -385,19 +547,31 @@ impl PostProcessPipeline {
                 bytemuck::cast_slice(&entry.param_data),
             );
         }
+        if let Some((t, _)) = transition {
+            let mut data = [0.0f32; PARAM_FLOATS];
+            data[..4].copy_from_slice(&[resolution[0], resolution[1], t.coverage, t.progress]);
+            data[4..8].copy_from_slice(&t.color);
+            data[8..12].copy_from_slice(&t.params);
+            gpu.queue.write_buffer(
+                &self.transition_param_buffer,
+                0,
+                bytemuck::cast_slice(&data),
+            );
+        }
 
         // Ping-pong: sprites were rendered to target_a.
         // Effect 0: read A -> write B (or surface if last)
         // Effect 1: read B -> write A (or surface if last)
         // ...
-        for i in 0..n {
-            let is_last = i == n - 1;
+        // The transition pass, if any, follows the effects; the upscale pass,
+        // if any, comes last and samples with nearest filtering.
+        for i in 0..passes {
+            let is_last = i == passes - 1;
 
-            // Source bind group (for sampling)
-            let source_bg = if i % 2 == 0 {
-                &self.target_a.as_ref().unwrap().bind_group
+            let source = if i % 2 == 0 {
+                self.target_a.as_ref().unwrap()
             } else {
-                &self.target_b.as_ref().unwrap().bind_group
+                self.target_b.as_ref().unwrap()
             };
 
             // Destination view
@@ -409,7 +583,19 @@ impl PostProcessPipeline {
                 &self.target_a.as_ref().unwrap().view
             };
 
-            let (_, entry) = &self.effects[i];
+            let (pipeline, source_bg, params_bg) = if i < n {
+                let (_, entry) = &self.effects[i];
+                (&entry.pipeline, &source.bind_group, &entry.param_bind_group)
+            } else if let Some((_, key)) = transition.filter(|_| i == n) {
+                let source_bg = if key == TransitionKind::Pixelate {
+                    &source.nearest_bind_group
+                } else {
+                    &source.bind_group
+                };
+                (&self.transition_pipelines[&key], source_bg, &self.transition_params)
+            } else {
+                (&self.upscale_pipeline, &source.nearest_bind_group, &self.upscale_params)
+            };
 
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("postprocess_pass"),
@@ -426,9 +612,9 @@ impl PostProcessPipeline {
                 occlusion_query_set: None,
             });
 
-            pass.set_pipeline(&entry.pipeline);
+            pass.set_pipeline(pipeline);
             pass.set_bind_group(0, source_bg, &[]);
-            pass.set_bind_group(1, &entry.param_bind_group, &[]);
+            pass.set_bind_group(1, params_bg, &[]);
             pass.draw(0..3, 0..1); // fullscreen triangle
         }
     }
@@ -439,6 +625,53 @@ fn build_effect_wgsl(fragment_source: &str) -> String {
     format!("{}\n{}\n", EFFECT_PREAMBLE, fragment_source)
 }
 
+/// Lines of generated preamble before a transition shader's own source.
+pub fn transition_line_offset(source: &str) -> u32 {
+    super::gpu_errors::line_offset(&build_effect_wgsl(source), source)
+}
+
+/// Fullscreen-triangle pipeline for one effect pass.
+fn create_effect_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    wgsl: &str,
+) -> wgpu::RenderPipeline {
+    let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("postprocess_shader"),
+        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("postprocess_pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader_module,
+            entry_point: Some("vs_main"),
+            buffers: &[], // fullscreen triangle via vertex_index
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_module,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
 /// Shared declarations + fullscreen vertex shader for all effects.
 const EFFECT_PREAMBLE: &str = r#"
 @group(0) @binding(0)
@@ -471,6 +704,67 @@ fn vs_main(@builtin(vertex_index) idx: u32) -> VertexOutput {
 }
 "#;
 
+/// Plain copy, used to upscale the reduced-resolution scene to the surface.
+const UPSCALE_FRAGMENT: &str = r#"
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_input, s_input, in.uv);
+}
+"#;
+
+/// Transition fade: blends the frame towards the cover color.
+/// Params for every transition pass, built-in or custom:
+/// resolution.xy = target size, resolution.z = coverage (0 → 1 at the
+/// midpoint → 0), resolution.w = progress (0 → 1), values[0] = cover color
+/// (RGBA), values[1] = kind-specific values.
+const TRANSITION_FADE_FRAGMENT: &str = r#"
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(t_input, s_input, in.uv);
+    let cover = params.values[0];
+    return vec4<f32>(mix(scene.rgb, cover.rgb, params.resolution.z * cover.a), scene.a);
+}
+"#;
+
+/// Transition circle wipe: a circle around values[1].xy (UV, 0-1) shrinks to
+/// nothing as coverage rises; outside it is the cover color.
+const TRANSITION_CIRCLE_FRAGMENT: &str = r#"
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(t_input, s_input, in.uv);
+    let cover = params.values[0];
+    let resolution = params.resolution.xy;
+    let coverage = params.resolution.z;
+
+    // Work in pixels so the circle stays round on any aspect ratio
+    let center = params.values[1].xy * resolution;
+    let far_corner = max(center, resolution - center);
+    let radius = length(far_corner) * (1.0 - coverage);
+    let edge = clamp(length(in.uv * resolution - center) - radius + 0.5, 0.0, 1.0);
+    let outside = select(edge, 1.0, coverage >= 1.0);
+
+    return vec4<f32>(mix(scene.rgb, cover.rgb, outside * cover.a), scene.a);
+}
+"#;
+
+/// Transition pixelate: blocks grow up to values[1].x pixels, and the cover
+/// color takes over during the second half of the coverage ramp.
+const TRANSITION_PIXELATE_FRAGMENT: &str = r#"
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let resolution = params.resolution.xy;
+    let coverage = params.resolution.z;
+    let cover = params.values[0];
+
+    let block = max(1.0, params.values[1].x * coverage);
+    let cell_uv = (floor(in.uv * resolution / block) + 0.5) * block / resolution;
+    let scene = textureSample(t_input, s_input, cell_uv);
+    let blend = smoothstep(0.5, 1.0, coverage) * cover.a;
+
+    return vec4<f32>(mix(scene.rgb, cover.rgb, blend), scene.a);
+}
+"#;
+
 /// Simplified single-pass bloom: bright-pass + weighted blur + additive composite.
 /// Params: values[0].x = threshold, values[0].y = intensity, values[0].z = radius.
 const BLOOM_FRAGMENT: &str = r#"
@@ -660,4 +954,22 @@ mod tests {
         let d = EffectType::Bloom.defaults();
         assert_eq!(d.len(), PARAM_FLOATS);
     }
+
+    #[test]
+    fn test_upscale_shader_parses() {
+        let wgsl = build_effect_wgsl(UPSCALE_FRAGMENT);
+        naga::front::wgsl::parse_str(&wgsl).expect("upscale WGSL should parse");
+    }
+
+    #[test]
+    fn test_transition_shaders_parse() {
+        for source in [
+            TRANSITION_FADE_FRAGMENT,
+            TRANSITION_CIRCLE_FRAGMENT,
+            TRANSITION_PIXELATE_FRAGMENT,
+        ] {
+            let wgsl = build_effect_wgsl(source);
+            naga::front::wgsl::parse_str(&wgsl).expect("transition WGSL should parse");
+        }
+    }
 }
@@ -0,0 +1,412 @@
+//! Adaptive quality scaling.
+//!
+//! When enabled, the renderer measures how long the GPU takes per frame and
+//! steps down through [`QUALITY_TIERS`] while frames stay over budget: lower
+//! render resolution (upscaled with nearest filtering to stay sharp), fewer GI
+//! cascades, and finally no post-process effects. It steps back up once frames
+//! are comfortably under budget again. Both directions require a sustained
+//! trend (hysteresis), so a single hitch never changes the tier.
+//!
+//! GPU time comes from timestamp queries when the device supports them
+//! ([`GpuTimer`]); otherwise the CPU time spent encoding and submitting the
+//! frame is used as an approximation.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::json::escape;
+
+/// One step of the quality ladder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityTier {
+    pub name: &'static str,
+    /// Scene resolution relative to the window (1.0 = native).
+    pub render_scale: f32,
+    /// GI cascades dropped from the configured count (at least one is kept).
+    pub gi_cascade_reduction: u32,
+    /// Whether post-process effects run.
+    pub post_effects: bool,
+}
+
+/// Quality tiers from best (0) to cheapest.
+pub const QUALITY_TIERS: [QualityTier; 4] = [
+    QualityTier {
+        name: "high",
+        render_scale: 1.0,
+        gi_cascade_reduction: 0,
+        post_effects: true,
+    },
+    QualityTier {
+        name: "medium",
+        render_scale: 0.75,
+        gi_cascade_reduction: 1,
+        post_effects: true,
+    },
+    QualityTier {
+        name: "low",
+        render_scale: 0.5,
+        gi_cascade_reduction: 2,
+        post_effects: true,
+    },
+    QualityTier {
+        name: "minimum",
+        render_scale: 0.5,
+        gi_cascade_reduction: 3,
+        post_effects: false,
+    },
+];
+
+/// Smoothing factor for the frame time moving average.
+const FRAME_TIME_SMOOTHING: f32 = 0.1;
+/// Consecutive over-budget frames before stepping down.
+const DOWNGRADE_AFTER_FRAMES: u32 = 20;
+/// Consecutive frames under [`UPGRADE_HEADROOM`] of the budget before stepping up.
+const UPGRADE_AFTER_FRAMES: u32 = 120;
+/// Fraction of the budget frames must stay under before stepping up.
+const UPGRADE_HEADROOM: f32 = 0.7;
+
+/// Settings for the controller, set from TS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityConfig {
+    /// Adjust the tier automatically from measured frame times.
+    pub enabled: bool,
+    /// GPU time budget per frame in milliseconds.
+    pub budget_ms: f32,
+    /// Fixed tier, overriding the automatic one (also applies when disabled).
+    pub override_tier: Option<usize>,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_ms: 1000.0 / 60.0,
+            override_tier: None,
+        }
+    }
+}
+
+/// Picks the quality tier from measured GPU frame times, with hysteresis.
+#[derive(Debug, Clone, Default)]
+pub struct QualityController {
+    config: QualityConfig,
+    auto_tier: usize,
+    avg_ms: Option<f32>,
+    over_frames: u32,
+    under_frames: u32,
+}
+
+impl QualityController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn config(&self) -> QualityConfig {
+        self.config
+    }
+
+    /// Apply new settings. Disabling resets the automatic tier to full quality.
+    pub fn configure(&mut self, config: QualityConfig) {
+        let config = QualityConfig {
+            override_tier: config.override_tier.map(|t| t.min(QUALITY_TIERS.len() - 1)),
+            ..config
+        };
+        if config == self.config {
+            return;
+        }
+        if !config.enabled {
+            self.auto_tier = 0;
+            self.reset_trend();
+        }
+        self.config = config;
+    }
+
+    /// Whether frame times are being used (timing can be skipped otherwise).
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Smoothed frame time in milliseconds, once measured.
+    pub fn frame_ms(&self) -> Option<f32> {
+        self.avg_ms
+    }
+
+    /// Record one frame's GPU time. Returns true when the automatic tier changed.
+    pub fn record_frame(&mut self, frame_ms: f32) -> bool {
+        if !self.config.enabled || !frame_ms.is_finite() {
+            return false;
+        }
+        let budget = self.config.budget_ms;
+        // Clamp spikes so one long frame (e.g. a shader compile) cannot drag
+        // the average over budget for dozens of frames
+        let sample = frame_ms.min(budget * 2.0);
+        let avg = match self.avg_ms {
+            Some(avg) => avg + (sample - avg) * FRAME_TIME_SMOOTHING,
+            None => sample,
+        };
+        self.avg_ms = Some(avg);
+
+        if avg > budget {
+            self.over_frames += 1;
+            self.under_frames = 0;
+        } else if avg < budget * UPGRADE_HEADROOM {
+            self.under_frames += 1;
+            self.over_frames = 0;
+        } else {
+            self.reset_counters();
+        }
+
+        let last = QUALITY_TIERS.len() - 1;
+        if self.over_frames >= DOWNGRADE_AFTER_FRAMES && self.auto_tier < last {
+            self.auto_tier += 1;
+            self.reset_trend();
+            return true;
+        }
+        if self.under_frames >= UPGRADE_AFTER_FRAMES && self.auto_tier > 0 {
+            self.auto_tier -= 1;
+            self.reset_trend();
+            return true;
+        }
+        false
+    }
+
+    /// The tier in effect: the override if set, else the automatic tier.
+    pub fn tier(&self) -> usize {
+        match self.config.override_tier {
+            Some(tier) => tier,
+            None if self.config.enabled => self.auto_tier,
+            None => 0,
+        }
+    }
+
+    pub fn settings(&self) -> QualityTier {
+        QUALITY_TIERS[self.tier()]
+    }
+
+    /// Status for `op_get_quality`:
+    /// `{"tier":1,"name":"medium","auto":true,"enabled":true,"budgetMs":16.67,"frameMs":18.2,"renderScale":0.75,"postEffects":true,"tierCount":4}`
+    pub fn status_json(&self) -> String {
+        let tier = self.settings();
+        let frame_ms = match self.avg_ms {
+            Some(ms) => format!("{ms:.2}"),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"tier\":{},\"name\":\"{}\",\"auto\":{},\"enabled\":{},\"budgetMs\":{:.2},\"frameMs\":{},\"renderScale\":{},\"postEffects\":{},\"tierCount\":{}}}",
+            self.tier(),
+            escape(tier.name),
+            self.config.override_tier.is_none(),
+            self.config.enabled,
+            self.config.budget_ms,
+            frame_ms,
+            tier.render_scale,
+            tier.post_effects,
+            QUALITY_TIERS.len(),
+        )
+    }
+
+    fn reset_counters(&mut self) {
+        self.over_frames = 0;
+        self.under_frames = 0;
+    }
+
+    /// Forget the measured trend so a new tier is judged on its own frames.
+    fn reset_trend(&mut self) {
+        self.avg_ms = None;
+        self.reset_counters();
+    }
+}
+
+const TIMER_IDLE: u8 = 0;
+const TIMER_PENDING: u8 = 1;
+const TIMER_READY: u8 = 2;
+
+/// Measures the GPU time of a frame with two timestamp queries that bracket
+/// the frame's commands. One measurement is in flight at a time; frames that
+/// start while the previous readback is pending are not timed.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    state: Arc<AtomicU8>,
+    /// Nanoseconds per timestamp tick.
+    period_ns: f32,
+}
+
+impl GpuTimer {
+    /// None when the device was created without `TIMESTAMP_QUERY`.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("quality_timer_queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let size = 2 * std::mem::size_of::<u64>() as u64;
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("quality_timer_resolve"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("quality_timer_readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            state: Arc::new(AtomicU8::new(TIMER_IDLE)),
+            period_ns: queue.get_timestamp_period(),
+        })
+    }
+
+    /// Write the start timestamp. Returns false (and writes nothing) while the
+    /// previous measurement is still being read back.
+    pub fn begin(&self, encoder: &mut wgpu::CommandEncoder) -> bool {
+        if self.state.load(Ordering::Acquire) != TIMER_IDLE {
+            return false;
+        }
+        self.state.store(TIMER_PENDING, Ordering::Release);
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("quality_timer_begin"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: None,
+            }),
+        });
+        true
+    }
+
+    /// Write the end timestamp and copy both into the readback buffer.
+    pub fn end(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("quality_timer_end"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: None,
+                end_of_pass_write_index: Some(1),
+            }),
+        });
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            self.resolve_buffer.size(),
+        );
+    }
+
+    /// Start reading the timestamps back. Call after the frame was submitted.
+    pub fn after_submit(&self) {
+        let state = self.state.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let next = if result.is_ok() {
+                    TIMER_READY
+                } else {
+                    TIMER_IDLE
+                };
+                state.store(next, Ordering::Release);
+            });
+    }
+
+    /// Poll for a finished measurement, in milliseconds. Never blocks.
+    pub fn poll(&self, device: &wgpu::Device) -> Option<f32> {
+        if self.state.load(Ordering::Acquire) == TIMER_PENDING {
+            device.poll(wgpu::Maintain::Poll);
+        }
+        if self.state.load(Ordering::Acquire) != TIMER_READY {
+            return None;
+        }
+        let ticks = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let stamps: &[u64] = bytemuck::cast_slice(&data);
+            stamps[1].saturating_sub(stamps[0])
+        };
+        self.readback_buffer.unmap();
+        self.state.store(TIMER_IDLE, Ordering::Release);
+        Some(ticks as f32 * self.period_ns / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(budget_ms: f32) -> QualityController {
+        let mut controller = QualityController::new();
+        controller.configure(QualityConfig {
+            enabled: true,
+            budget_ms,
+            override_tier: None,
+        });
+        controller
+    }
+
+    #[test]
+    fn test_sustained_overload_steps_down_one_tier_at_a_time() {
+        let mut controller = enabled(16.0);
+        // A single hitch does not change anything
+        assert!(!controller.record_frame(100.0));
+        assert!((0..DOWNGRADE_AFTER_FRAMES * 2).all(|_| !controller.record_frame(8.0)));
+        assert_eq!(controller.tier(), 0);
+
+        let changed = (0..DOWNGRADE_AFTER_FRAMES * 3)
+            .filter(|_| controller.record_frame(30.0))
+            .count();
+        assert_eq!(changed, 2);
+        assert_eq!(controller.tier(), 2);
+        assert_eq!(controller.settings().render_scale, 0.5);
+    }
+
+    #[test]
+    fn test_steps_back_up_only_with_headroom() {
+        let mut controller = enabled(16.0);
+        for _ in 0..DOWNGRADE_AFTER_FRAMES {
+            controller.record_frame(30.0);
+        }
+        assert_eq!(controller.tier(), 1);
+
+        // Just under budget is not enough headroom
+        for _ in 0..UPGRADE_AFTER_FRAMES * 2 {
+            controller.record_frame(15.0);
+        }
+        assert_eq!(controller.tier(), 1);
+
+        for _ in 0..UPGRADE_AFTER_FRAMES * 2 {
+            controller.record_frame(5.0);
+        }
+        assert_eq!(controller.tier(), 0);
+    }
+
+    #[test]
+    fn test_override_and_disable() {
+        let mut controller = enabled(16.0);
+        for _ in 0..DOWNGRADE_AFTER_FRAMES {
+            controller.record_frame(30.0);
+        }
+        controller.configure(QualityConfig {
+            override_tier: Some(99),
+            ..controller.config()
+        });
+        assert_eq!(controller.tier(), QUALITY_TIERS.len() - 1);
+        assert!(!controller.settings().post_effects);
+
+        controller.configure(QualityConfig::default());
+        assert_eq!(controller.tier(), 0);
+        assert!(!controller.record_frame(100.0));
+
+        let json = crate::json::JsonValue::parse(&controller.status_json()).unwrap();
+        assert_eq!(json.get("name").and_then(|v| v.as_str()), Some("high"));
+        assert_eq!(json.get("frameMs"), Some(&crate::json::JsonValue::Null));
+    }
+}
@@ -14,7 +14,7 @@ use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
 use super::gpu::GpuContext;
-use super::lighting::LightingState;
+use super::lighting::{LightAnimation, LightingState};
 
 /// Default base ray count for cascade 0 (4 rays = 2x2 block per probe).
 const DEFAULT_BASE_RAYS: u32 = 4;
@@ -86,6 +86,8 @@ pub struct SpotLight {
     pub g: f32,
     pub b: f32,
     pub intensity: f32,
+    /// Flicker / pulse / color cycling, baked in by [`RadianceState::animate`].
+    pub animation: Option<LightAnimation>,
 }
 
 /// Radiance state gathered from TypeScript each frame.
@@ -125,6 +127,16 @@ impl RadianceState {
             cascade_count: None,
         }
     }
+
+    /// Bake spot light animations at `time` seconds (see [`LightingState::animate`]).
+    pub fn animate(&mut self, time: f32) {
+        for spot in &mut self.spot_lights {
+            if let Some(animation) = spot.animation.take() {
+                let ([r, g, b], intensity) = animation.apply([spot.r, spot.g, spot.b], spot.intensity, time);
+                (spot.r, spot.g, spot.b, spot.intensity) = (r, g, b, intensity);
+            }
+        }
+    }
 }
 
 /// The radiance cascade compute pipeline.
@@ -158,6 +170,8 @@ pub struct RadiancePipeline {
     pub probe_spacing: f32,
     pub interval: f32,
     pub cascade_count: u32,
+    /// Cascades dropped by adaptive quality (at least one always runs).
+    pub cascade_reduction: u32,
 
     // Sampler for the composition pass
     sampler: wgpu::Sampler,
@@ -412,6 +426,7 @@ impl RadiancePipeline {
             probe_spacing: DEFAULT_PROBE_SPACING,
             interval: DEFAULT_INTERVAL,
             cascade_count: 4,
+            cascade_reduction: 0,
             sampler,
             surface_format,
         }
@@ -737,7 +752,10 @@ impl RadiancePipeline {
             },
         );
 
-        let cascade_count = self.cascade_count.min(MAX_CASCADES as u32);
+        let cascade_count = self
+            .cascade_count
+            .saturating_sub(self.cascade_reduction)
+            .clamp(1, MAX_CASCADES as u32);
 
         // === Pass 1: Ray-march each cascade (highest first) ===
         // We write each cascade to tex_a using a dedicated bind group,
@@ -1057,6 +1075,7 @@ mod tests {
             g: 1.0,
             b: 0.8,
             intensity: 1.5,
+            animation: None,
         };
         assert_eq!(sl.range, 300.0);
     }
@@ -0,0 +1,201 @@
+//! Recording rendered frames to an animated GIF.
+//!
+//! Frames are sampled on game time rather than wall time: each rendered frame
+//! advances the recording by the frame's delta time and a frame is captured
+//! whenever a full `1/fps` interval has passed. Clips therefore play back at
+//! game speed whatever the window's frame rate, and a recording made under
+//! `arcane render` is the same on every run. Captured pixels go over a channel
+//! to an encoder thread, so quantizing and compressing never stalls the frame.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use anyhow::{Context, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+
+/// Highest frame rate a GIF can hold: frame delays are in hundredths of a second.
+pub const MAX_RECORDING_FPS: f32 = 50.0;
+/// Quantizer speed for the GIF palette (1 = best, 30 = fastest).
+const QUANTIZE_SPEED: i32 = 10;
+/// Slack when comparing game time to the interval, so 60 Hz frames land on
+/// every second frame of a 30 fps recording despite rounding.
+const SAMPLE_EPSILON: f32 = 1e-4;
+
+/// An in-progress recording.
+pub struct Recording {
+    path: PathBuf,
+    /// Seconds of game time per captured frame.
+    interval: f32,
+    /// Game time since the last captured frame; `None` until the first.
+    pending: Option<f32>,
+    width: u32,
+    height: u32,
+    frames: u32,
+    size_warned: bool,
+    sender: mpsc::Sender<Vec<u8>>,
+    encoder: JoinHandle<()>,
+}
+
+impl Recording {
+    /// Start recording `width`×`height` frames to `path` at `fps` (clamped to
+    /// 1-[`MAX_RECORDING_FPS`]). Only `.gif` output is supported.
+    pub fn start(path: PathBuf, fps: f32, width: u32, height: u32) -> Result<Self> {
+        check_extension(&path)?;
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let fps = if fps.is_finite() { fps.clamp(1.0, MAX_RECORDING_FPS) } else { 30.0 };
+        let interval = 1.0 / fps;
+        let delay = Delay::from_numer_denom_ms(1000, fps.round() as u32);
+
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+        let out = path.clone();
+        let encoder = std::thread::Builder::new()
+            .name("arcane-recording".into())
+            .spawn(move || {
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), QUANTIZE_SPEED);
+                if let Err(e) = encoder.set_repeat(Repeat::Infinite) {
+                    eprintln!("[record] Failed to write {}: {e}", out.display());
+                    return;
+                }
+                for mut pixels in receiver {
+                    // GIF has 1-bit alpha; the swapchain's alpha is meaningless here
+                    for alpha in pixels.iter_mut().skip(3).step_by(4) {
+                        *alpha = 255;
+                    }
+                    let Some(image) = RgbaImage::from_raw(width, height, pixels) else { continue };
+                    if let Err(e) = encoder.encode_frame(Frame::from_parts(image, 0, 0, delay)) {
+                        eprintln!("[record] Failed to write {}: {e}", out.display());
+                        return;
+                    }
+                }
+            })
+            .context("Failed to start the recording encoder thread")?;
+
+        Ok(Self {
+            path,
+            interval,
+            pending: None,
+            width,
+            height,
+            frames: 0,
+            size_warned: false,
+            sender,
+            encoder,
+        })
+    }
+
+    /// Output file of this recording.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Frames captured so far.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Advance game time by `dt` seconds. Returns true when the frame being
+    /// rendered should be captured. When frames are longer than the interval
+    /// at most one is captured per frame, so slow frames shorten the clip
+    /// instead of repeating the same image.
+    pub fn advance(&mut self, dt: f32) -> bool {
+        // The first frame after starting is always captured
+        let pending = self.pending.map_or(self.interval, |p| p + dt.max(0.0));
+        if pending + SAMPLE_EPSILON < self.interval {
+            self.pending = Some(pending);
+            return false;
+        }
+        self.pending = Some((pending - self.interval).min(self.interval));
+        true
+    }
+
+    /// Queue a captured frame (tightly packed RGBA) for encoding. Frames of a
+    /// different size than the recording (after a window resize) are dropped.
+    pub fn push(&mut self, pixels: Vec<u8>, width: u32, height: u32) {
+        if (width, height) != (self.width, self.height) {
+            if !self.size_warned {
+                self.size_warned = true;
+                eprintln!(
+                    "[record] Window resized to {width}x{height}; skipping frames until it is {}x{} again",
+                    self.width, self.height
+                );
+            }
+            return;
+        }
+        if self.sender.send(pixels).is_ok() {
+            self.frames += 1;
+        }
+    }
+
+    /// Stop capturing. The encoder thread finishes the queued frames and
+    /// closes the file; the returned handle joins it.
+    pub fn finish(self) -> JoinHandle<()> {
+        drop(self.sender);
+        self.encoder
+    }
+}
+
+fn check_extension(path: &Path) -> Result<()> {
+    let ext = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("gif") => Ok(()),
+        Some("mp4") => anyhow::bail!(
+            "MP4 recording is not supported; record to a .gif instead ({})",
+            path.display()
+        ),
+        _ => anyhow::bail!("Recording path must end in .gif ({})", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording(name: &str, fps: f32) -> (Recording, PathBuf) {
+        let path = std::env::temp_dir().join(format!("arcane_recording_{name}_{}.gif", std::process::id()));
+        (Recording::start(path.clone(), fps, 2, 2).unwrap(), path)
+    }
+
+    #[test]
+    fn test_frames_are_sampled_on_game_time() {
+        let (mut rec, path) = recording("sampling", 20.0);
+        // 60 fps game, 20 fps recording: the first frame, then every third
+        let captured: Vec<bool> = (0..7).map(|_| rec.advance(1.0 / 60.0)).collect();
+        assert_eq!(captured, [true, false, false, true, false, false, true]);
+        assert!(!rec.advance(0.02));
+        // A paused game (dt = 0) captures nothing
+        assert!(!rec.advance(0.0));
+        // A long frame captures once, without a backlog
+        assert!(rec.advance(1.0));
+        assert!(rec.advance(0.0));
+        assert!(!rec.advance(0.0));
+        rec.finish().join().unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_recording_writes_a_gif() {
+        let (mut rec, path) = recording("write", 30.0);
+        rec.push(vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 0], 2, 2);
+        rec.push(vec![0; 16], 2, 2);
+        rec.push(vec![0; 36], 3, 3);
+        assert_eq!(rec.frames(), 2);
+        rec.finish().join().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!(bytes.last(), Some(&0x3B));
+    }
+
+    #[test]
+    fn test_only_gif_paths_are_accepted() {
+        assert!(check_extension(Path::new("clip.gif")).is_ok());
+        assert!(check_extension(Path::new("clip.GIF")).is_ok());
+        assert!(check_extension(Path::new("clip.mp4")).unwrap_err().to_string().contains("MP4"));
+        assert!(check_extension(Path::new("clip")).is_err());
+    }
+}
@@ -0,0 +1,441 @@
+//! Visual diff across hot reloads (`arcane dev --reload-diff`).
+//!
+//! When a reload is requested, the dev loop holds it back until the current
+//! frame is captured, reloads, lets the new code run [`SETTLE_FRAMES`] frames
+//! so its assets can load, then captures again. Both PNGs are written to
+//! [`RELOAD_DIFF_DIR`] and the pair is shown over the game: a wipe between
+//! before (left) and after (right) that follows the mouse, or a difference
+//! image with changed pixels in magenta over a dimmed copy of the new frame.
+//! [`RELOAD_DIFF_KEY`] cycles wipe → difference → hidden. Saves in quick
+//! succession keep the first "before" frame and restart the settling.
+
+use super::error_overlay::{quad, GLYPH_SIZE, LINE_HEIGHT, OVERLAY_LAYER, PADDING};
+use super::font::glyph_uv;
+use super::SpriteCommand;
+
+/// Key that cycles the overlay in `arcane dev` (TS input API name).
+pub const RELOAD_DIFF_KEY: &str = "F4";
+
+/// Where the captured frames are written, relative to the working directory.
+pub const RELOAD_DIFF_DIR: &str = ".arcane/reload-diffs";
+
+/// Frames the reloaded game runs before its frame is captured.
+pub const SETTLE_FRAMES: u32 = 30;
+
+/// Frames to wait for a requested capture before giving up on it.
+const CAPTURE_TIMEOUT_FRAMES: u32 = 30;
+
+/// Largest per-channel difference still counted as unchanged (dithering,
+/// blending rounding).
+const CHANNEL_TOLERANCE: u8 = 8;
+
+/// Brightness of unchanged pixels in the difference image.
+const DIM: f32 = 0.35;
+
+/// Color of changed pixels in the difference image.
+const CHANGED: [u8; 4] = [255, 0, 255, 255];
+
+/// A decoded RGBA8 frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbaFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaFrame {
+    pub fn decode_png(png: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(png).map_err(|e| e.to_string())?.to_rgba8();
+        Ok(Self { width: image.width(), height: image.height(), pixels: image.into_raw() })
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> Option<&[u8]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y * self.width + x) as usize * 4;
+        self.pixels.get(i..i + 4)
+    }
+}
+
+/// Pixels that changed between two frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiffSummary {
+    pub changed: u64,
+    pub total: u64,
+}
+
+impl DiffSummary {
+    /// Changed share of the frame, 0-100.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.changed as f64 * 100.0 / self.total as f64 }
+    }
+}
+
+/// Difference image of `after` against `before`, at the size of `after`.
+/// Pixels outside `before` (the window was resized) count as changed.
+pub fn diff_frames(before: &RgbaFrame, after: &RgbaFrame) -> (RgbaFrame, DiffSummary) {
+    let mut pixels = Vec::with_capacity(after.pixels.len());
+    let mut changed = 0;
+    for y in 0..after.height {
+        for x in 0..after.width {
+            let Some(a) = after.pixel(x, y) else { continue };
+            let same = before.pixel(x, y).is_some_and(|b| {
+                a.iter().zip(b).all(|(&a, &b)| a.abs_diff(b) <= CHANNEL_TOLERANCE)
+            });
+            if same {
+                let luma = 0.2126 * a[0] as f32 + 0.7152 * a[1] as f32 + 0.0722 * a[2] as f32;
+                let v = (luma * DIM) as u8;
+                pixels.extend_from_slice(&[v, v, v, 255]);
+            } else {
+                changed += 1;
+                pixels.extend_from_slice(&CHANGED);
+            }
+        }
+    }
+    let summary = DiffSummary { changed, total: after.width as u64 * after.height as u64 };
+    (RgbaFrame { width: after.width, height: after.height, pixels }, summary)
+}
+
+/// What the overlay shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlayMode {
+    #[default]
+    Hidden,
+    Wipe,
+    Difference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Idle,
+    /// Reload held back until the current frame is captured.
+    Before { waited: u32 },
+    /// Reloaded; running frames before the capture.
+    Settling { frames_left: u32 },
+    /// Waiting for the reloaded frame's capture.
+    After { waited: u32 },
+}
+
+/// What the dev loop should do this frame.
+#[derive(Debug)]
+pub enum ReloadDiffStep {
+    Continue,
+    /// Capture this frame and pass the PNG to the next `step`.
+    Capture,
+    /// Reload now.
+    Reload,
+    /// Both frames are in: write them and upload the overlay textures.
+    Done(ReloadDiffOutput),
+    /// The diff was abandoned; the message says why.
+    Failed(String),
+}
+
+/// Captured frames of one reload and their difference image.
+#[derive(Debug)]
+pub struct ReloadDiffOutput {
+    pub before_png: Vec<u8>,
+    pub after_png: Vec<u8>,
+    pub before: RgbaFrame,
+    pub after: RgbaFrame,
+    pub diff: RgbaFrame,
+    pub summary: DiffSummary,
+}
+
+/// Overlay textures uploaded from a [`ReloadDiffOutput`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffTextures {
+    pub before: u32,
+    pub after: u32,
+    pub diff: u32,
+}
+
+/// Reload diff state kept by the dev loop.
+#[derive(Debug, Clone)]
+pub struct ReloadDiff {
+    pub enabled: bool,
+    pub mode: OverlayMode,
+    /// Result of the last finished diff.
+    pub summary: Option<DiffSummary>,
+    stage: Stage,
+    before_png: Option<Vec<u8>>,
+}
+
+impl ReloadDiff {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, mode: OverlayMode::Hidden, summary: None, stage: Stage::Idle, before_png: None }
+    }
+
+    /// A reload was requested. Returns true when it must wait for a capture
+    /// of the current frame (request one); false to reload right away.
+    pub fn hold_reload(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match self.stage {
+            Stage::Idle => {
+                self.mode = OverlayMode::Hidden;
+                self.stage = Stage::Before { waited: 0 };
+                true
+            }
+            Stage::Before { .. } => true,
+            // Keep the frame from before the first of several quick saves
+            Stage::Settling { .. } | Stage::After { .. } => {
+                self.stage = Stage::Settling { frames_left: SETTLE_FRAMES };
+                false
+            }
+        }
+    }
+
+    /// Advance one frame with the capture that arrived since the last step.
+    pub fn step(&mut self, capture: Option<&[u8]>) -> ReloadDiffStep {
+        match self.stage {
+            Stage::Idle => ReloadDiffStep::Continue,
+            Stage::Before { waited } => {
+                if let Some(png) = capture {
+                    self.before_png = Some(png.to_vec());
+                    self.stage = Stage::Settling { frames_left: SETTLE_FRAMES };
+                    ReloadDiffStep::Reload
+                } else if waited >= CAPTURE_TIMEOUT_FRAMES {
+                    // Don't hold the reload hostage to a missing capture
+                    self.stage = Stage::Idle;
+                    ReloadDiffStep::Reload
+                } else {
+                    self.stage = Stage::Before { waited: waited + 1 };
+                    ReloadDiffStep::Continue
+                }
+            }
+            Stage::Settling { frames_left } => {
+                if frames_left > 1 {
+                    self.stage = Stage::Settling { frames_left: frames_left - 1 };
+                    ReloadDiffStep::Continue
+                } else {
+                    self.stage = Stage::After { waited: 0 };
+                    ReloadDiffStep::Capture
+                }
+            }
+            Stage::After { waited } => {
+                if let Some(after_png) = capture {
+                    self.stage = Stage::Idle;
+                    let before_png = self.before_png.take().unwrap_or_default();
+                    self.finish(before_png, after_png.to_vec())
+                } else if waited >= CAPTURE_TIMEOUT_FRAMES {
+                    self.stage = Stage::Idle;
+                    self.before_png = None;
+                    ReloadDiffStep::Failed("no frame was captured after the reload".into())
+                } else {
+                    self.stage = Stage::After { waited: waited + 1 };
+                    ReloadDiffStep::Continue
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, before_png: Vec<u8>, after_png: Vec<u8>) -> ReloadDiffStep {
+        let decoded = RgbaFrame::decode_png(&before_png)
+            .and_then(|before| Ok((before, RgbaFrame::decode_png(&after_png)?)));
+        let (before, after) = match decoded {
+            Ok(frames) => frames,
+            Err(e) => return ReloadDiffStep::Failed(format!("cannot decode a captured frame: {e}")),
+        };
+        let (diff, summary) = diff_frames(&before, &after);
+        self.summary = Some(summary);
+        self.mode = OverlayMode::Wipe;
+        ReloadDiffStep::Done(ReloadDiffOutput { before_png, after_png, before, after, diff, summary })
+    }
+
+    /// Wipe → difference → hidden → wipe. Does nothing before the first diff.
+    pub fn cycle_mode(&mut self) {
+        if self.summary.is_none() {
+            return;
+        }
+        self.mode = match self.mode {
+            OverlayMode::Hidden => OverlayMode::Wipe,
+            OverlayMode::Wipe => OverlayMode::Difference,
+            OverlayMode::Difference => OverlayMode::Hidden,
+        };
+    }
+
+    /// Text of the overlay's label.
+    pub fn label(&self) -> String {
+        let summary = self.summary.unwrap_or_default();
+        let changed = format!("{} px changed ({:.2}%)", summary.changed, summary.percent());
+        match self.mode {
+            OverlayMode::Wipe => format!("before | after  {changed}  {RELOAD_DIFF_KEY}: difference"),
+            OverlayMode::Difference => format!("difference  {changed}  {RELOAD_DIFF_KEY}: hide"),
+            OverlayMode::Hidden => String::new(),
+        }
+    }
+
+    /// Sprites drawing the overlay over the whole screen (camera top-left at
+    /// `cam_x`/`cam_y`), above the game and below the debug HUD, `--stats`
+    /// and the error overlay. The wipe splits at screen x `split_x`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn overlay_sprites(
+        &self,
+        textures: DiffTextures,
+        font_texture: u32,
+        panel_texture: u32,
+        cam_x: f32,
+        cam_y: f32,
+        zoom: f32,
+        viewport: [f32; 2],
+        split_x: f32,
+    ) -> Vec<SpriteCommand> {
+        let zoom = if zoom > 0.0 { zoom } else { 1.0 };
+        let [vw, vh] = viewport;
+        let (w, h) = (vw / zoom, vh / zoom);
+        let mut sprites = Vec::new();
+        match self.mode {
+            OverlayMode::Hidden => return sprites,
+            OverlayMode::Wipe => {
+                let s = if vw > 0.0 { (split_x / vw).clamp(0.0, 1.0) } else { 0.5 };
+                let layer = OVERLAY_LAYER - 5;
+                let white = [1.0; 4];
+                let split = cam_x + w * s;
+                sprites.push(quad(textures.before, cam_x, cam_y, w * s, h, [0.0, 0.0, s, 1.0], white, layer));
+                sprites.push(quad(textures.after, split, cam_y, w * (1.0 - s), h, [s, 0.0, 1.0 - s, 1.0], white, layer));
+                let line = 2.0 / zoom;
+                let full = [0.0, 0.0, 1.0, 1.0];
+                sprites.push(quad(panel_texture, split - line / 2.0, cam_y, line, h, full, white, OVERLAY_LAYER - 4));
+            }
+            OverlayMode::Difference => {
+                let full = [0.0, 0.0, 1.0, 1.0];
+                sprites.push(quad(textures.diff, cam_x, cam_y, w, h, full, [1.0; 4], OVERLAY_LAYER - 5));
+            }
+        }
+
+        // Label along the bottom edge
+        let label = self.label();
+        let panel_w = PADDING * 2.0 + label.chars().count() as f32 * GLYPH_SIZE;
+        let panel_h = PADDING * 2.0 + LINE_HEIGHT;
+        let top = (vh - panel_h).max(0.0);
+        let to_world = |sx: f32, sy: f32| (cam_x + sx / zoom, cam_y + sy / zoom);
+        let (px, py) = to_world(0.0, top);
+        let (pw, ph) = (panel_w / zoom, panel_h / zoom);
+        let shade = [0.0, 0.0, 0.0, 0.7];
+        sprites.push(quad(panel_texture, px, py, pw, ph, [0.0, 0.0, 1.0, 1.0], shade, OVERLAY_LAYER - 4));
+        for (col, ch) in label.chars().enumerate() {
+            if ch == ' ' {
+                continue;
+            }
+            let (x, y) = to_world(PADDING + col as f32 * GLYPH_SIZE, top + PADDING);
+            let size = GLYPH_SIZE / zoom;
+            sprites.push(quad(font_texture, x, y, size, size, glyph_uv(ch), [1.0; 4], OVERLAY_LAYER - 3));
+        }
+        sprites
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba(color));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    fn frame(width: u32, height: u32, pixels: &[[u8; 4]]) -> RgbaFrame {
+        RgbaFrame { width, height, pixels: pixels.concat() }
+    }
+
+    #[test]
+    fn test_diff_marks_changed_pixels() {
+        let before = frame(2, 1, &[[100, 100, 100, 255], [0, 0, 0, 255]]);
+        let after = frame(2, 1, &[[104, 100, 97, 255], [200, 0, 0, 255]]);
+        let (diff, summary) = diff_frames(&before, &after);
+        assert_eq!(summary, DiffSummary { changed: 1, total: 2 });
+        assert_eq!(summary.percent(), 50.0);
+        // Within tolerance: dimmed gray; changed: magenta
+        assert_eq!(&diff.pixels[0..4], &[35, 35, 35, 255]);
+        assert_eq!(&diff.pixels[4..8], &CHANGED);
+
+        // A resized window counts the new area as changed
+        let wider = frame(3, 1, &[[100, 100, 100, 255], [0, 0, 0, 255], [0, 0, 0, 255]]);
+        assert_eq!(diff_frames(&before, &wider).1.changed, 1);
+    }
+
+    #[test]
+    fn test_reload_waits_for_the_before_capture() {
+        let mut diff = ReloadDiff::new(true);
+        assert!(diff.hold_reload());
+        assert!(matches!(diff.step(None), ReloadDiffStep::Continue));
+        assert!(matches!(diff.step(Some(&png(2, 2, [0, 0, 0, 255]))), ReloadDiffStep::Reload));
+        for _ in 1..SETTLE_FRAMES {
+            assert!(matches!(diff.step(None), ReloadDiffStep::Continue));
+        }
+        assert!(matches!(diff.step(None), ReloadDiffStep::Capture));
+        let ReloadDiffStep::Done(output) = diff.step(Some(&png(2, 2, [255, 0, 0, 255]))) else {
+            panic!("expected the diff to finish");
+        };
+        assert_eq!(output.summary, DiffSummary { changed: 4, total: 4 });
+        assert_eq!((output.before.width, output.after.height), (2, 2));
+        assert_eq!(diff.mode, OverlayMode::Wipe);
+        assert!(matches!(diff.step(None), ReloadDiffStep::Continue));
+    }
+
+    #[test]
+    fn test_disabled_or_missing_captures_never_block_reloads() {
+        assert!(!ReloadDiff::new(false).hold_reload());
+
+        let mut diff = ReloadDiff::new(true);
+        diff.hold_reload();
+        let mut step = diff.step(None);
+        for _ in 0..CAPTURE_TIMEOUT_FRAMES {
+            step = diff.step(None);
+        }
+        assert!(matches!(step, ReloadDiffStep::Reload));
+        assert!(matches!(diff.step(None), ReloadDiffStep::Continue));
+    }
+
+    #[test]
+    fn test_quick_saves_keep_the_first_before_frame() {
+        let mut diff = ReloadDiff::new(true);
+        diff.hold_reload();
+        diff.step(Some(&png(1, 1, [0, 0, 0, 255])));
+        diff.step(None);
+        // Another save while settling reloads at once and restarts the count
+        assert!(!diff.hold_reload());
+        for _ in 1..SETTLE_FRAMES {
+            assert!(matches!(diff.step(None), ReloadDiffStep::Continue));
+        }
+        assert!(matches!(diff.step(None), ReloadDiffStep::Capture));
+    }
+
+    #[test]
+    fn test_mode_cycles_after_the_first_diff() {
+        let mut diff = ReloadDiff::new(true);
+        diff.cycle_mode();
+        assert_eq!(diff.mode, OverlayMode::Hidden);
+        diff.summary = Some(DiffSummary { changed: 3, total: 100 });
+        diff.cycle_mode();
+        assert_eq!(diff.mode, OverlayMode::Wipe);
+        assert_eq!(diff.label(), "before | after  3 px changed (3.00%)  F4: difference");
+        diff.cycle_mode();
+        assert_eq!(diff.mode, OverlayMode::Difference);
+        diff.cycle_mode();
+        assert_eq!(diff.mode, OverlayMode::Hidden);
+    }
+
+    #[test]
+    fn test_wipe_splits_at_the_mouse() {
+        let mut diff = ReloadDiff::new(true);
+        diff.summary = Some(DiffSummary::default());
+        let textures = DiffTextures { before: 1, after: 2, diff: 3 };
+        assert!(diff.overlay_sprites(textures, 7, 8, 0.0, 0.0, 1.0, [800.0, 600.0], 200.0).is_empty());
+
+        diff.mode = OverlayMode::Wipe;
+        let sprites = diff.overlay_sprites(textures, 7, 8, 100.0, 50.0, 2.0, [800.0, 600.0], 200.0);
+        let (before, after) = (&sprites[0], &sprites[1]);
+        assert_eq!((before.texture_id, before.x, before.w, before.uv_w), (1, 100.0, 100.0, 0.25));
+        assert_eq!((after.texture_id, after.x, after.w, after.uv_x), (2, 200.0, 300.0, 0.25));
+        assert!(sprites.iter().all(|s| s.layer < OVERLAY_LAYER - 2));
+
+        diff.mode = OverlayMode::Difference;
+        let sprites = diff.overlay_sprites(textures, 7, 8, 0.0, 0.0, 1.0, [800.0, 600.0], 200.0);
+        assert_eq!((sprites[0].texture_id, sprites[0].w, sprites[0].h), (3, 800.0, 600.0));
+    }
+}
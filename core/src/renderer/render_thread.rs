@@ -0,0 +1,353 @@
+//! Off-main-thread render submission.
+//!
+//! The game thread (winit event loop + TS frame callback) fills a [`FrameSet`]
+//! with the frame's sprites, geometry, SDF shapes, lighting and camera, then
+//! hands it to a dedicated render thread that installs it into the
+//! [`Renderer`], encodes and presents. Frame sets are double-buffered: while
+//! frame N is being encoded and presented, the script runs frame N+1. The
+//! hand-off is a rendezvous, so the game is never more than one frame ahead of
+//! the GPU and input latency stays at a single frame.
+//!
+//! Resource work (texture uploads, shader creation, render targets, resize)
+//! stays on the game thread and goes through [`SharedRenderer::lock`], which
+//! waits for the frame in flight.
+
+use std::collections::BTreeSet;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use super::camera::CameraBounds;
+use super::{background, BackgroundFill, Camera2D, Letterbox, LightingState, RadianceState, Renderer, SpriteCommand, Viewport};
+use crate::scripting::geometry_ops::GeoCommand;
+use crate::scripting::sdf_ops::SdfDrawCommand;
+
+/// A renderer shared between the game thread and the render thread.
+#[derive(Clone)]
+pub struct SharedRenderer(Arc<Mutex<Renderer>>);
+
+impl SharedRenderer {
+    pub fn new(renderer: Renderer) -> Self {
+        Self(Arc::new(Mutex::new(renderer)))
+    }
+
+    /// Lock the renderer, waiting for the frame being rendered (if any).
+    /// A panic on the render thread does not lock the game thread out.
+    pub fn lock(&self) -> MutexGuard<'_, Renderer> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Everything the renderer needs to draw one frame.
+#[derive(Clone)]
+pub struct FrameSet {
+    pub sprites: Vec<SpriteCommand>,
+    /// Decals, drawn under the sprites of their layer.
+    pub decals: Vec<SpriteCommand>,
+    pub geo: Vec<GeoCommand>,
+    pub sdf: Vec<SdfDrawCommand>,
+    /// Mask shapes, by mask ID - 1 (see `mask`).
+    pub masks: Vec<Vec<GeoCommand>>,
+    /// Layers drawn in order of sprite pivot y (see `op_set_layer_ysort`).
+    pub ysort_layers: BTreeSet<i32>,
+    pub lighting: LightingState,
+    pub radiance: RadianceState,
+    pub camera_x: f32,
+    pub camera_y: f32,
+    pub camera_zoom: f32,
+    pub camera_bounds: Option<CameraBounds>,
+    /// Offset added to the camera after clamping (hit-stop shake), so the
+    /// shake never pushes the clamped position the game reads back.
+    pub camera_shake: (f32, f32),
+    /// Split-screen viewports in draw order (see `viewport`).
+    pub viewports: Vec<Viewport>,
+    /// Round sprite positions to the camera's pixel grid unless a sprite
+    /// says otherwise (see `SpriteCommand::pixel_snap`).
+    pub pixel_snap: bool,
+    pub clear_color: [f32; 4],
+    /// Texture drawn behind everything instead of the flat clear color.
+    pub background: Option<BackgroundFill>,
+    /// Color of the area outside the camera bounds.
+    pub letterbox: Option<Letterbox>,
+    /// Shader built-ins (see `ShaderStore::flush`).
+    pub elapsed_time: f32,
+    pub delta_time: f32,
+    pub mouse_pos: [f32; 2],
+    /// Capture this frame to PNG (returned by [`RenderThread::submit`]).
+    pub capture: bool,
+}
+
+impl Default for FrameSet {
+    fn default() -> Self {
+        Self {
+            sprites: Vec::new(),
+            decals: Vec::new(),
+            geo: Vec::new(),
+            sdf: Vec::new(),
+            masks: Vec::new(),
+            ysort_layers: BTreeSet::new(),
+            lighting: LightingState::default(),
+            radiance: RadianceState::new(),
+            camera_x: 0.0,
+            camera_y: 0.0,
+            camera_zoom: 1.0,
+            camera_bounds: None,
+            camera_shake: (0.0, 0.0),
+            viewports: Vec::new(),
+            pixel_snap: false,
+            clear_color: [0.1, 0.1, 0.15, 1.0],
+            background: None,
+            letterbox: None,
+            elapsed_time: 0.0,
+            delta_time: 0.0,
+            mouse_pos: [0.0, 0.0],
+            capture: false,
+        }
+    }
+}
+
+impl FrameSet {
+    /// Clamp the camera to its bounds for a viewport of `viewport_size` logical
+    /// pixels, so the game thread knows the position that will be rendered.
+    pub fn clamp_camera(&mut self, viewport_size: [f32; 2]) {
+        let mut camera = Camera2D {
+            x: self.camera_x,
+            y: self.camera_y,
+            zoom: self.camera_zoom,
+            rotation: 0.0,
+            viewport_size,
+            bounds: self.camera_bounds,
+        };
+        camera.clamp_to_bounds();
+        self.camera_x = camera.x;
+        self.camera_y = camera.y;
+    }
+
+    /// Take this set for rendering. The set left behind has no draw commands
+    /// but keeps the camera, viewports, pixel snapping, clear color, background fill, y-sorted
+    /// layers, lighting and shader inputs, so a frame callback that bails out
+    /// early still renders with the last known state.
+    pub fn take(&mut self) -> FrameSet {
+        let next = FrameSet {
+            lighting: self.lighting.clone(),
+            radiance: self.radiance.clone(),
+            camera_x: self.camera_x,
+            camera_y: self.camera_y,
+            camera_zoom: self.camera_zoom,
+            camera_bounds: self.camera_bounds,
+            viewports: self.viewports.clone(),
+            pixel_snap: self.pixel_snap,
+            clear_color: self.clear_color,
+            background: self.background,
+            letterbox: self.letterbox,
+            ysort_layers: self.ysort_layers.clone(),
+            elapsed_time: self.elapsed_time,
+            delta_time: self.delta_time,
+            mouse_pos: self.mouse_pos,
+            ..FrameSet::default()
+        };
+        std::mem::replace(self, next)
+    }
+}
+
+/// Install `set` into the renderer and draw it. Returns the PNG capture if the
+/// set requested one.
+pub fn render_frame_set(renderer: &mut Renderer, set: FrameSet) -> Option<Vec<u8>> {
+    renderer.camera.x = set.camera_x;
+    renderer.camera.y = set.camera_y;
+    renderer.camera.zoom = set.camera_zoom;
+    renderer.camera.bounds = set.camera_bounds;
+    renderer.camera.clamp_to_bounds();
+    renderer.camera.x += set.camera_shake.0;
+    renderer.camera.y += set.camera_shake.1;
+    renderer.viewports = set.viewports;
+    for viewport in &mut renderer.viewports {
+        viewport.x += set.camera_shake.0;
+        viewport.y += set.camera_shake.1;
+    }
+    renderer.frame_commands = set.sprites;
+    super::snap_to_pixels(&mut renderer.frame_commands, &renderer.camera, set.pixel_snap);
+    // Fill and bars follow the clamped camera, so they are built here
+    renderer.frame_commands.extend(background::fill_commands(set.background, set.letterbox, &renderer.camera));
+    renderer.sync_atlas();
+    renderer.atlas.remap(&mut renderer.frame_commands, &renderer.textures);
+    let mut decals = set.decals;
+    super::snap_to_pixels(&mut decals, &renderer.camera, set.pixel_snap);
+    renderer.atlas.remap(&mut decals, &renderer.textures);
+    renderer.decals.set_commands(decals);
+    renderer.set_geo_commands(set.geo);
+    renderer.set_sdf_commands(set.sdf);
+    renderer.masks = set.masks;
+    renderer.ysort_layers = set.ysort_layers;
+    renderer.lighting = set.lighting;
+    renderer.lighting.animate(set.elapsed_time);
+    renderer.radiance_state = set.radiance;
+    renderer.radiance_state.animate(set.elapsed_time);
+    renderer.clear_color = set.clear_color;
+    renderer.elapsed_time = set.elapsed_time;
+    renderer.delta_time = set.delta_time;
+    renderer.mouse_pos = set.mouse_pos;
+    renderer.capture_pending |= set.capture;
+
+    if let Err(e) = renderer.render_frame() {
+        eprintln!("Render error: {e}");
+    }
+    renderer.capture_result.take()
+}
+
+struct Worker {
+    frames: SyncSender<FrameSet>,
+    captures: Receiver<Vec<u8>>,
+    handle: JoinHandle<()>,
+}
+
+/// Hands frame sets to a dedicated render thread, or renders them inline on
+/// the calling thread when threading is disabled.
+pub struct RenderThread {
+    renderer: SharedRenderer,
+    worker: Option<Worker>,
+}
+
+impl RenderThread {
+    /// Start a render thread for `renderer`. Falls back to inline rendering if
+    /// the thread cannot be spawned.
+    pub fn spawn(renderer: SharedRenderer) -> Self {
+        // Zero capacity: a send completes only once the thread has finished the
+        // previous frame and picked up this one, so at most two sets are alive.
+        let (frames_tx, frames_rx) = mpsc::sync_channel::<FrameSet>(0);
+        let (captures_tx, captures_rx) = mpsc::channel();
+        let shared = renderer.clone();
+        let spawned = std::thread::Builder::new()
+            .name("arcane-render".into())
+            .spawn(move || {
+                for set in frames_rx {
+                    let capture = render_frame_set(&mut shared.lock(), set);
+                    if let Some(png) = capture {
+                        let _ = captures_tx.send(png);
+                    }
+                }
+            });
+        match spawned {
+            Ok(handle) => Self {
+                renderer,
+                worker: Some(Worker {
+                    frames: frames_tx,
+                    captures: captures_rx,
+                    handle,
+                }),
+            },
+            Err(e) => {
+                eprintln!(
+                    "[render] Could not start the render thread ({e}); rendering on the main thread"
+                );
+                Self::inline(renderer)
+            }
+        }
+    }
+
+    /// Render every submitted set immediately on the calling thread.
+    pub fn inline(renderer: SharedRenderer) -> Self {
+        Self {
+            renderer,
+            worker: None,
+        }
+    }
+
+    /// True when frames are rendered on a dedicated thread.
+    pub fn is_threaded(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    /// Hand a frame to the renderer and return a finished capture, if any.
+    ///
+    /// Threaded, this waits until the previous frame has been rendered and
+    /// returns that frame's capture; a frame's capture therefore arrives one
+    /// submit later. Inline, the frame is rendered before returning.
+    pub fn submit(&self, set: FrameSet) -> Option<Vec<u8>> {
+        let Some(ref worker) = self.worker else {
+            return render_frame_set(&mut self.renderer.lock(), set);
+        };
+        match worker.frames.send(set) {
+            Ok(()) => worker.captures.try_iter().last(),
+            // The render thread died (a panic was already printed): keep going inline
+            Err(mpsc::SendError(set)) => render_frame_set(&mut self.renderer.lock(), set),
+        }
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            // Closing the channel ends the render loop after the frame in flight
+            drop(worker.frames);
+            let _ = worker.handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::PointLight;
+
+    #[test]
+    fn test_take_clears_draw_commands_but_keeps_frame_state() {
+        let mut frame = FrameSet {
+            camera_x: 12.0,
+            camera_zoom: 2.0,
+            clear_color: [1.0, 0.0, 0.0, 1.0],
+            capture: true,
+            ..FrameSet::default()
+        };
+        frame.lighting.lights.push(PointLight {
+            x: 1.0,
+            y: 2.0,
+            radius: 50.0,
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            intensity: 1.0,
+            animation: None,
+            shadow_softness: None,
+        });
+        frame.geo.push(GeoCommand::Triangle {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 1.0,
+            y2: 0.0,
+            x3: 0.0,
+            y3: 1.0,
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+            layer: 0,
+        });
+
+        let taken = frame.take();
+        assert_eq!(taken.geo.len(), 1);
+        assert!(taken.capture);
+        assert!(frame.geo.is_empty());
+        assert!(!frame.capture);
+        assert_eq!((frame.camera_x, frame.camera_zoom), (12.0, 2.0));
+        assert_eq!(frame.clear_color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(frame.lighting.lights.len(), 1);
+    }
+
+    #[test]
+    fn test_clamp_camera_uses_viewport() {
+        let mut frame = FrameSet {
+            camera_x: -50.0,
+            camera_y: 500.0,
+            camera_bounds: Some(CameraBounds {
+                min_x: 0.0,
+                min_y: 0.0,
+                max_x: 1000.0,
+                max_y: 1000.0,
+            }),
+            ..FrameSet::default()
+        };
+        frame.clamp_camera([200.0, 600.0]);
+        assert_eq!((frame.camera_x, frame.camera_y), (0.0, 400.0));
+    }
+}
@@ -9,8 +9,90 @@
 /// 1. `create()` — allocates GPU texture + view
 /// 2. `get_view()` — returns the view for render pass target
 /// 3. `destroy()` — drops GPU resources
+///
+/// Each target is drawn with a [`TargetCamera`]; the default one puts (0, 0)
+/// at the target's top-left.
+///
+/// Targets may sample other targets (a CRT screen showing a minimap). The
+/// pre-pass draws them in [`render_order`] so each one sees this frame's
+/// contents of the targets it samples.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::camera::Camera2D;
+
+/// How a render target views the world: position, zoom, rotation, which
+/// layers it shows, and whether it also captures the main scene's sprites.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetCamera {
+    /// World position of the target's top-left corner (before rotation).
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    /// Roll in radians around the center of the target (see [`Camera2D::rotation`]).
+    pub rotation: f32,
+    /// Inclusive layer range drawn into the target.
+    pub layer_min: i32,
+    pub layer_max: i32,
+    /// Also draw the main scene's sprites (mirrors, portals, picture-in-picture).
+    pub capture_scene: bool,
+}
+
+impl Default for TargetCamera {
+    /// Top-left at the world origin, 1:1 pixels, every layer, own sprites only.
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            zoom: 1.0,
+            rotation: 0.0,
+            layer_min: i32::MIN,
+            layer_max: i32::MAX,
+            capture_scene: false,
+        }
+    }
+}
 
-use std::collections::HashMap;
+impl TargetCamera {
+    /// The camera used to render into a `width` × `height` target.
+    pub fn camera(&self, width: u32, height: u32) -> Camera2D {
+        Camera2D {
+            x: self.x,
+            y: self.y,
+            zoom: if self.zoom > 0.0 { self.zoom } else { 1.0 },
+            rotation: self.rotation,
+            viewport_size: [width as f32, height as f32],
+            bounds: None,
+        }
+    }
+
+    /// Whether sprites on `layer` are drawn into the target.
+    pub fn shows_layer(&self, layer: i32) -> bool {
+        layer >= self.layer_min && layer <= self.layer_max
+    }
+}
+
+/// The order to draw targets in: every target after the targets it samples
+/// (`samples[id]`), otherwise by ascending ID. Targets in a cycle are drawn in
+/// ID order and see last frame's contents of the targets drawn after them.
+/// Sampled IDs that are not keys of `samples` are ignored.
+pub fn render_order(samples: &BTreeMap<u32, BTreeSet<u32>>) -> Vec<u32> {
+    let mut order = Vec::with_capacity(samples.len());
+    let mut done = BTreeSet::new();
+    while order.len() < samples.len() {
+        let pending = || samples.iter().filter(|(id, _)| !done.contains(*id));
+        let ready = pending()
+            .find(|(id, deps)| {
+                deps.iter()
+                    .all(|d| d == *id || done.contains(d) || !samples.contains_key(d))
+            })
+            .or_else(|| pending().next());
+        let Some((&id, _)) = ready else { break };
+        done.insert(id);
+        order.push(id);
+    }
+    order
+}
 
 /// A single off-screen render target.
 pub struct RenderTargetEntry {
@@ -89,3 +171,25 @@ impl RenderTargetStore {
         self.targets.remove(&id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(u32, &[u32])]) -> BTreeMap<u32, BTreeSet<u32>> {
+        edges.iter().map(|(id, deps)| (*id, deps.iter().copied().collect())).collect()
+    }
+
+    #[test]
+    fn test_render_order_draws_sampled_targets_first() {
+        // 3 shows 9 (a minimap on a CRT), 9 shows 5; 7 stands alone
+        let order = render_order(&graph(&[(3, &[9]), (5, &[]), (7, &[]), (9, &[5, 42])]));
+        assert_eq!(order, vec![5, 7, 9, 3]);
+    }
+
+    #[test]
+    fn test_render_order_breaks_cycles_by_id() {
+        let order = render_order(&graph(&[(2, &[4]), (4, &[2]), (6, &[6])]));
+        assert_eq!(order, vec![6, 2, 4]);
+    }
+}
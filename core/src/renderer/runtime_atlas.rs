@@ -0,0 +1,569 @@
+//! Runtime texture atlas: packs small sprite textures into shared pages so
+//! sprites drawn from different images batch into one draw call.
+//!
+//! Opt-in with [`Renderer::set_runtime_atlas`](super::Renderer::set_runtime_atlas).
+//! Textures uploaded with [`TextureStore::upload_raw`] up to
+//! [`MAX_ATLAS_TEXTURE`] pixels a side are copied on the GPU into
+//! [`ATLAS_PAGE_SIZE`] pages, with a one-pixel border repeating their edges.
+//! Each frame's sprite and decal commands are then remapped to the page and
+//! the texture's rect in it, so games keep using their texture IDs. Commands
+//! whose drawing depends on the original texture are left alone: custom
+//! shaders, nine-slice panels, outlines, normal-mapped sprites and UVs
+//! outside the texture. The original textures stay loaded for everything
+//! else that samples them.
+//!
+//! Pages are filled in shelves and a slot is never reused, so re-uploads
+//! (hot-reloaded art) leave holes. Once holes make up more than
+//! [`DEFRAG_WASTE`] of the packed area and no texture has been placed for
+//! [`DEFRAG_IDLE_FRAMES`] frames, every live texture is copied into fresh,
+//! tightly packed pages and the old pages are dropped. Remapping happens per
+//! frame from the current layout, so the move is invisible to the game.
+
+use std::collections::HashMap;
+
+use super::sprite::SpriteCommand;
+use super::texture::{TextureId, TextureStore};
+
+/// Width and height of an atlas page in pixels.
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+/// Largest texture side packed into the atlas; bigger textures stay on their own.
+pub const MAX_ATLAS_TEXTURE: u32 = 256;
+/// Most pages the atlas creates; textures that don't fit stay on their own.
+pub const MAX_ATLAS_PAGES: usize = 8;
+/// Share of the packed area left as holes above which the atlas is repacked.
+pub const DEFRAG_WASTE: f32 = 0.25;
+/// Frames without a texture being placed before a repack may run.
+pub const DEFRAG_IDLE_FRAMES: u32 = 60;
+/// Texture ID of the first atlas page; later pages count up from it.
+pub const FIRST_PAGE_ID: TextureId = 0xF000_0000;
+
+/// Pixels repeated around each texture so filtering at its edge never
+/// samples a neighbour.
+const BORDER: u32 = 1;
+
+/// Where a texture is in the atlas: its page and its rect there, without
+/// the border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasSlot {
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl AtlasSlot {
+    /// Pixels the slot takes in its page, border included.
+    fn padded_area(&self) -> u64 {
+        u64::from(self.w + 2 * BORDER) * u64::from(self.h + 2 * BORDER)
+    }
+}
+
+/// Fill state of one page: textures go left to right in rows (shelves).
+#[derive(Debug, Clone, Copy, Default)]
+struct Shelf {
+    x: u32,
+    y: u32,
+    row_height: u32,
+}
+
+impl Shelf {
+    /// Top-left corner for a `w`×`h` cell, or None when the page is full.
+    /// A cell that doesn't fit leaves the shelf as it was.
+    fn place(&mut self, size: u32, w: u32, h: u32) -> Option<(u32, u32)> {
+        let mut next = *self;
+        if next.x + w > size {
+            next = Shelf { x: 0, y: next.y + next.row_height, row_height: 0 };
+        }
+        if next.y + h > size {
+            return None;
+        }
+        let spot = (next.x, next.y);
+        *self = Shelf { x: next.x + w, y: next.y, row_height: next.row_height.max(h) };
+        Some(spot)
+    }
+}
+
+/// Which texture is where in the atlas pages.
+#[derive(Debug, Clone)]
+pub struct AtlasLayout {
+    size: u32,
+    max_pages: usize,
+    shelves: Vec<Shelf>,
+    slots: HashMap<TextureId, AtlasSlot>,
+    /// Padded pixels handed out since the pages were created, freed or not.
+    allocated: u64,
+}
+
+impl AtlasLayout {
+    /// An empty layout of up to `max_pages` pages of `size`×`size` pixels.
+    pub fn new(size: u32, max_pages: usize) -> Self {
+        Self { size, max_pages, shelves: Vec::new(), slots: HashMap::new(), allocated: 0 }
+    }
+
+    /// Place texture `id` of `w`×`h` pixels, replacing its old slot (which
+    /// becomes a hole). None when it is too large or every page is full, in
+    /// which case it has no slot.
+    pub fn insert(&mut self, id: TextureId, w: u32, h: u32) -> Option<AtlasSlot> {
+        self.remove(id);
+        let (pw, ph) = (w + 2 * BORDER, h + 2 * BORDER);
+        if w == 0 || h == 0 || pw > self.size || ph > self.size {
+            return None;
+        }
+        let mut placed = None;
+        for (page, shelf) in self.shelves.iter_mut().enumerate() {
+            if let Some(spot) = shelf.place(self.size, pw, ph) {
+                placed = Some((page, spot));
+                break;
+            }
+        }
+        if placed.is_none() && self.shelves.len() < self.max_pages {
+            let mut shelf = Shelf::default();
+            let spot = shelf.place(self.size, pw, ph)?;
+            self.shelves.push(shelf);
+            placed = Some((self.shelves.len() - 1, spot));
+        }
+        let (page, (x, y)) = placed?;
+        let slot = AtlasSlot { page, x: x + BORDER, y: y + BORDER, w, h };
+        self.allocated += slot.padded_area();
+        self.slots.insert(id, slot);
+        Some(slot)
+    }
+
+    /// Drop texture `id`'s slot, leaving a hole.
+    pub fn remove(&mut self, id: TextureId) -> Option<AtlasSlot> {
+        self.slots.remove(&id)
+    }
+
+    pub fn slot(&self, id: TextureId) -> Option<AtlasSlot> {
+        self.slots.get(&id).copied()
+    }
+
+    /// Pages in use.
+    pub fn pages(&self) -> usize {
+        self.shelves.len()
+    }
+
+    /// Textures with a slot.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Share of the pages' pixels taken by live textures (borders included).
+    pub fn occupancy(&self) -> f32 {
+        let total = self.shelves.len() as u64 * u64::from(self.size) * u64::from(self.size);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.live() as f64 / total as f64) as f32
+    }
+
+    /// Share of the packed area left as holes by replaced or removed textures.
+    pub fn waste(&self) -> f32 {
+        if self.allocated == 0 {
+            return 0.0;
+        }
+        (1.0 - self.live() as f64 / self.allocated as f64) as f32
+    }
+
+    fn live(&self) -> u64 {
+        self.slots.values().map(AtlasSlot::padded_area).sum()
+    }
+
+    /// The live textures packed again into fresh pages, tallest first (then
+    /// by ID, so the result doesn't depend on map order). Textures that no
+    /// longer fit are left out.
+    pub fn repacked(&self) -> AtlasLayout {
+        let mut textures: Vec<(TextureId, AtlasSlot)> = self.slots.iter().map(|(&id, &s)| (id, s)).collect();
+        textures.sort_by(|(a_id, a), (b_id, b)| b.h.cmp(&a.h).then(b.w.cmp(&a.w)).then(a_id.cmp(b_id)));
+        let mut packed = AtlasLayout::new(self.size, self.max_pages);
+        for (id, slot) in textures {
+            packed.insert(id, slot.w, slot.h);
+        }
+        packed
+    }
+
+    /// Texture IDs and slots, in ID order.
+    pub fn slots(&self) -> Vec<(TextureId, AtlasSlot)> {
+        let mut slots: Vec<(TextureId, AtlasSlot)> = self.slots.iter().map(|(&id, &s)| (id, s)).collect();
+        slots.sort_by_key(|(id, _)| *id);
+        slots
+    }
+}
+
+/// Copies that fill a slot from a `w`×`h` texture: the texture itself, then
+/// its edge rows, columns and corner pixels one pixel outside it. Each is
+/// `(src_x, src_y, w, h, dst_x, dst_y)` with the destination relative to
+/// the slot's top-left corner.
+pub fn slot_copies(w: u32, h: u32) -> [(u32, u32, u32, u32, i32, i32); 9] {
+    let (r, b) = (w - 1, h - 1);
+    let (wi, hi) = (w as i32, h as i32);
+    [
+        (0, 0, w, h, 0, 0),
+        (0, 0, w, 1, 0, -1),
+        (0, b, w, 1, 0, hi),
+        (0, 0, 1, h, -1, 0),
+        (r, 0, 1, h, wi, 0),
+        (0, 0, 1, 1, -1, -1),
+        (r, 0, 1, 1, wi, -1),
+        (0, b, 1, 1, -1, hi),
+        (r, b, 1, 1, wi, hi),
+    ]
+}
+
+/// Whether `cmd` can be drawn from an atlas slot instead of its texture.
+pub fn can_remap(cmd: &SpriteCommand, has_normal_map: bool) -> bool {
+    const EPSILON: f32 = 1e-4;
+    cmd.shader_id == 0
+        && cmd.nine_slice.is_none()
+        && cmd.outline.is_none()
+        && cmd.normal_map.is_none()
+        && !has_normal_map
+        && cmd.uv_x >= -EPSILON
+        && cmd.uv_y >= -EPSILON
+        && cmd.uv_x + cmd.uv_w <= 1.0 + EPSILON
+        && cmd.uv_y + cmd.uv_h <= 1.0 + EPSILON
+}
+
+/// Point `cmd` at `page` and map its UVs into `slot` of a page `size` pixels wide.
+pub fn remap_command(cmd: &mut SpriteCommand, slot: AtlasSlot, page: TextureId, size: u32) {
+    let size = size as f32;
+    cmd.texture_id = page;
+    cmd.uv_x = (slot.x as f32 + cmd.uv_x * slot.w as f32) / size;
+    cmd.uv_y = (slot.y as f32 + cmd.uv_y * slot.h as f32) / size;
+    cmd.uv_w = cmd.uv_w * slot.w as f32 / size;
+    cmd.uv_h = cmd.uv_h * slot.h as f32 / size;
+}
+
+/// Atlas counters for the inspector. See [`RuntimeAtlas::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtlasStats {
+    pub enabled: bool,
+    pub pages: u32,
+    /// Textures packed into the pages.
+    pub textures: u32,
+    /// Share of the pages' pixels in use (0-1).
+    pub occupancy: f32,
+    /// Share of the packed area left as holes (0-1).
+    pub waste: f32,
+    /// Repacks since startup.
+    pub defragmentations: u32,
+}
+
+impl AtlasStats {
+    /// Stats as a JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"enabled\":{},\"pages\":{},\"textures\":{},\"occupancy\":{:.4},\"waste\":{:.4},\"defragmentations\":{}}}",
+            self.enabled, self.pages, self.textures, self.occupancy, self.waste, self.defragmentations
+        )
+    }
+}
+
+/// The atlas pages on the GPU and the layout of the textures in them.
+pub struct RuntimeAtlas {
+    enabled: bool,
+    layout: AtlasLayout,
+    /// Texture ID of each page, by page index.
+    pages: Vec<TextureId>,
+    next_page_id: TextureId,
+    /// Frames since a texture was last placed.
+    idle_frames: u32,
+    defragmentations: u32,
+}
+
+impl Default for RuntimeAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuntimeAtlas {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            layout: AtlasLayout::new(ATLAS_PAGE_SIZE, MAX_ATLAS_PAGES),
+            pages: Vec::new(),
+            next_page_id: FIRST_PAGE_ID,
+            idle_frames: 0,
+            defragmentations: 0,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn the atlas on, packing every texture that fits, or off, dropping
+    /// its pages.
+    pub fn set_enabled(&mut self, gpu: &super::GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if enabled {
+            let ids = textures.atlas_candidates();
+            self.place(gpu, textures, layout, &ids);
+        } else {
+            for page in self.pages.drain(..) {
+                textures.remove(page);
+            }
+            self.layout = AtlasLayout::new(ATLAS_PAGE_SIZE, MAX_ATLAS_PAGES);
+        }
+    }
+
+    /// Copy textures `ids` (new or re-uploaded) into the pages. Textures that
+    /// can't be packed lose their old slot and are drawn on their own.
+    pub fn place(&mut self, gpu: &super::GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout, ids: &[TextureId]) {
+        if !self.enabled || ids.is_empty() {
+            return;
+        }
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("atlas_place") });
+        for &id in ids {
+            let source = textures.copy_source(id).filter(|(_, w, h)| *w <= MAX_ATLAS_TEXTURE && *h <= MAX_ATLAS_TEXTURE);
+            let Some((texture, w, h)) = source else {
+                self.layout.remove(id);
+                continue;
+            };
+            let Some(slot) = self.layout.insert(id, w, h) else {
+                continue;
+            };
+            while self.pages.len() <= slot.page {
+                let page = self.next_page_id;
+                self.next_page_id += 1;
+                textures.create_atlas_page(&gpu.device, layout, page, ATLAS_PAGE_SIZE);
+                self.pages.push(page);
+            }
+            let Some(page) = textures.copy_source(self.pages[slot.page]).map(|(t, _, _)| t) else {
+                continue;
+            };
+            for (sx, sy, cw, ch, dx, dy) in slot_copies(w, h) {
+                copy_rect(&mut encoder, &texture, (sx, sy), &page, ((slot.x as i32 + dx) as u32, (slot.y as i32 + dy) as u32), (cw, ch));
+            }
+        }
+        gpu.queue.submit(std::iter::once(encoder.finish()));
+        self.idle_frames = 0;
+    }
+
+    /// Count an idle frame and repack the pages once they are idle and
+    /// wasteful enough.
+    pub fn maintain(&mut self, gpu: &super::GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout) {
+        if !self.enabled {
+            return;
+        }
+        self.idle_frames = self.idle_frames.saturating_add(1);
+        if self.idle_frames >= DEFRAG_IDLE_FRAMES && self.layout.waste() > DEFRAG_WASTE {
+            self.defragment(gpu, textures, layout);
+        }
+    }
+
+    /// Copy every live texture into fresh, tightly packed pages and drop the
+    /// old ones.
+    pub fn defragment(&mut self, gpu: &super::GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout) {
+        let packed = self.layout.repacked();
+        let mut pages = Vec::with_capacity(packed.pages());
+        for _ in 0..packed.pages() {
+            let page = self.next_page_id;
+            self.next_page_id += 1;
+            textures.create_atlas_page(&gpu.device, layout, page, ATLAS_PAGE_SIZE);
+            pages.push(page);
+        }
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("atlas_defragment") });
+        for (id, to) in packed.slots() {
+            let Some(from) = self.layout.slot(id) else { continue };
+            let source = textures.copy_source(self.pages[from.page]).map(|(t, _, _)| t);
+            let dest = textures.copy_source(pages[to.page]).map(|(t, _, _)| t);
+            if let (Some(source), Some(dest)) = (source, dest) {
+                // Border included, so the edges come along
+                let size = (from.w + 2 * BORDER, from.h + 2 * BORDER);
+                copy_rect(&mut encoder, &source, (from.x - BORDER, from.y - BORDER), &dest, (to.x - BORDER, to.y - BORDER), size);
+            }
+        }
+        gpu.queue.submit(std::iter::once(encoder.finish()));
+        for page in std::mem::replace(&mut self.pages, pages) {
+            textures.remove(page);
+        }
+        self.layout = packed;
+        self.idle_frames = 0;
+        self.defragmentations += 1;
+    }
+
+    /// Point commands at the atlas pages of their textures. `textures`
+    /// answers which textures have a linked normal map.
+    pub fn remap(&self, commands: &mut [SpriteCommand], textures: &TextureStore) {
+        if !self.enabled || self.layout.is_empty() {
+            return;
+        }
+        for cmd in commands {
+            let Some(slot) = self.layout.slot(cmd.texture_id) else { continue };
+            if can_remap(cmd, textures.normal_map(cmd.texture_id).is_some()) {
+                remap_command(cmd, slot, self.pages[slot.page], ATLAS_PAGE_SIZE);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> AtlasStats {
+        AtlasStats {
+            enabled: self.enabled,
+            pages: self.layout.pages() as u32,
+            textures: self.layout.len() as u32,
+            occupancy: self.layout.occupancy(),
+            waste: self.layout.waste(),
+            defragmentations: self.defragmentations,
+        }
+    }
+}
+
+/// Record a copy of a `size` rect at `from` in `source` to `to` in `dest`.
+fn copy_rect(
+    encoder: &mut wgpu::CommandEncoder,
+    source: &wgpu::Texture,
+    from: (u32, u32),
+    dest: &wgpu::Texture,
+    to: (u32, u32),
+    size: (u32, u32),
+) {
+    let origin = |(x, y): (u32, u32)| wgpu::Origin3d { x, y, z: 0 };
+    encoder.copy_texture_to_texture(
+        wgpu::TexelCopyTextureInfo { texture: source, mip_level: 0, origin: origin(from), aspect: wgpu::TextureAspect::All },
+        wgpu::TexelCopyTextureInfo { texture: dest, mip_level: 0, origin: origin(to), aspect: wgpu::TextureAspect::All },
+        wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(texture_id: TextureId) -> SpriteCommand {
+        SpriteCommand {
+            texture_id,
+            x: 0.0,
+            y: 0.0,
+            w: 16.0,
+            h: 16.0,
+            layer: 0,
+            uv_x: 0.0,
+            uv_y: 0.0,
+            uv_w: 1.0,
+            uv_h: 1.0,
+            tint_r: 1.0,
+            tint_g: 1.0,
+            tint_b: 1.0,
+            tint_a: 1.0,
+            rotation: 0.0,
+            origin_x: 0.5,
+            origin_y: 0.5,
+            flip_x: false,
+            flip_y: false,
+            opacity: 1.0,
+            blend_mode: 0,
+            shader_id: 0,
+            nine_slice: None,
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
+        }
+    }
+
+    #[test]
+    fn test_layout_packs_shelves_and_opens_pages() {
+        let mut layout = AtlasLayout::new(64, 2);
+        let a = layout.insert(1, 30, 10).unwrap();
+        let b = layout.insert(2, 30, 20).unwrap();
+        assert_eq!((a.page, a.x, a.y), (0, 1, 1));
+        assert_eq!((b.page, b.x, b.y), (0, 33, 1));
+        // Next shelf starts under the tallest cell of the first
+        let c = layout.insert(3, 62, 30).unwrap();
+        assert_eq!((c.page, c.x, c.y), (0, 1, 23));
+        let d = layout.insert(4, 40, 40).unwrap();
+        assert_eq!((d.page, d.x, d.y), (1, 1, 1));
+        assert_eq!(layout.insert(5, 63, 10), None);
+        assert_eq!(layout.insert(6, 40, 40), None, "both pages are full");
+        assert_eq!(layout.pages(), 2);
+    }
+
+    #[test]
+    fn test_replacing_textures_leaves_waste_that_repacking_removes() {
+        let mut layout = AtlasLayout::new(64, 4);
+        for id in 1..=4 {
+            layout.insert(id, 14, 14);
+        }
+        assert_eq!(layout.waste(), 0.0);
+        assert_eq!(layout.occupancy(), 4.0 * 256.0 / 4096.0);
+        // A hot reload re-inserts the texture; the old slot becomes a hole
+        let moved = layout.insert(2, 14, 14).unwrap();
+        assert_eq!((moved.x, moved.y), (1, 17));
+        assert_eq!(layout.waste(), 0.2);
+        assert_eq!(layout.len(), 4);
+
+        let packed = layout.repacked();
+        assert_eq!(packed.waste(), 0.0);
+        assert_eq!(packed.occupancy(), layout.occupancy());
+        let slots: Vec<(u32, u32)> = packed.slots().iter().map(|(_, s)| (s.x, s.y)).collect();
+        assert_eq!(slots, [(1, 1), (17, 1), (33, 1), (49, 1)]);
+    }
+
+    #[test]
+    fn test_repacking_puts_tall_textures_first() {
+        let mut layout = AtlasLayout::new(64, 4);
+        layout.insert(1, 10, 4);
+        layout.insert(2, 10, 30);
+        layout.insert(3, 10, 12);
+        let packed = layout.repacked();
+        let columns: Vec<u32> = packed.slots().iter().map(|(_, s)| s.x).collect();
+        assert_eq!(columns, [25, 1, 13]);
+    }
+
+    #[test]
+    fn test_slot_copies_cover_the_padded_rect() {
+        let (w, h) = (3, 2);
+        let mut covered = vec![0u32; ((w + 2) * (h + 2)) as usize];
+        for (sx, sy, cw, ch, dx, dy) in slot_copies(w, h) {
+            assert!(sx + cw <= w && sy + ch <= h);
+            for y in 0..ch as i32 {
+                for x in 0..cw as i32 {
+                    covered[((dy + y + 1) * (w as i32 + 2) + dx + x + 1) as usize] += 1;
+                }
+            }
+        }
+        assert!(covered.iter().all(|&n| n == 1));
+    }
+
+    #[test]
+    fn test_remap_maps_uvs_into_the_slot() {
+        let slot = AtlasSlot { page: 0, x: 17, y: 33, w: 32, h: 16 };
+        let mut cmd = sprite(5);
+        cmd.uv_x = 0.5;
+        cmd.uv_w = 0.5;
+        remap_command(&mut cmd, slot, FIRST_PAGE_ID, 64);
+        assert_eq!(cmd.texture_id, FIRST_PAGE_ID);
+        assert_eq!((cmd.uv_x * 64.0, cmd.uv_y * 64.0), (33.0, 33.0));
+        assert_eq!((cmd.uv_w * 64.0, cmd.uv_h * 64.0), (16.0, 16.0));
+
+        assert!(can_remap(&sprite(5), false));
+        assert!(!can_remap(&sprite(5), true));
+        let mut tiled = sprite(5);
+        tiled.uv_w = 2.0;
+        assert!(!can_remap(&tiled, false));
+        let mut shaded = sprite(5);
+        shaded.shader_id = 3;
+        assert!(!can_remap(&shaded, false));
+    }
+
+    #[test]
+    fn test_stats_json() {
+        let stats = AtlasStats { enabled: true, pages: 1, textures: 3, occupancy: 0.5, waste: 0.125, defragmentations: 2 };
+        assert_eq!(
+            stats.to_json(),
+            r#"{"enabled":true,"pages":1,"textures":3,"occupancy":0.5000,"waste":0.1250,"defragmentations":2}"#
+        );
+    }
+}
@@ -0,0 +1,668 @@
+//! MSDF fonts generated at runtime from TrueType/OpenType files.
+//!
+//! `op_load_font` registers a font with its metrics right away and starts a
+//! worker thread that turns glyph outlines into multi-channel distance fields.
+//! Printable ASCII is queued first; any other character is queued the first
+//! time text asks for it. Finished glyphs are packed into the font's atlas by
+//! [`RuntimeFontStore::poll`] once per frame, which also hands back the atlas
+//! pixels to re-upload. Until its glyph arrives a character is simply missing
+//! from the text, usually for a frame or two.
+//!
+//! The generator follows msdfgen's approach: contours are split into edges at
+//! corners, edges are colored so neighbours across a corner never share all
+//! three channels, and each channel stores the signed pseudo-distance to its
+//! nearest edge. The shader's median of the channels then keeps corners sharp.
+//! Curves are flattened into short line segments first.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
+use std::sync::Arc;
+
+use ttf_parser::OutlineBuilder;
+
+use super::msdf::{MsdfFont, MsdfFontStore, MsdfGlyph};
+
+/// Side of a runtime font's square atlas texture in pixels.
+pub const RUNTIME_ATLAS_SIZE: u32 = 1024;
+/// Distance field range in pixels; also the padding around each glyph cell.
+const DISTANCE_RANGE: f32 = 4.0;
+/// Limits for the pixel size glyphs are generated at.
+const MIN_GLYPH_PX: f32 = 16.0;
+const MAX_GLYPH_PX: f32 = 64.0;
+/// Line segments each curve is flattened into.
+const CURVE_STEPS: usize = 8;
+/// Sine of the smallest direction change counted as a corner (about 8°).
+const CORNER_SIN: f32 = 0.14;
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const WHITE: u8 = RED | GREEN | BLUE;
+/// Colors for the edge runs between corners, cycled around each contour.
+const CORNER_COLORS: [u8; 3] = [GREEN | BLUE, RED | BLUE, RED | GREEN];
+
+/// Pixel size to generate glyphs at for text drawn between `min_size` and
+/// `max_size` pixels. Distance fields stay sharp up to roughly twice their
+/// size, so this is half the largest size, within 16-64 px.
+pub fn glyph_px_for_sizes(min_size: f32, max_size: f32) -> f32 {
+    (max_size.max(min_size) * 0.5).clamp(MIN_GLYPH_PX, MAX_GLYPH_PX).round()
+}
+
+/// One rendered glyph, ready to pack into an atlas.
+#[derive(Debug, Clone)]
+pub struct GlyphBitmap {
+    pub codepoint: u32,
+    /// Cell size in pixels, padding included (0 for glyphs without outline).
+    pub width: u32,
+    pub height: u32,
+    /// RGBA distance field, `width * height * 4` bytes.
+    pub pixels: Vec<u8>,
+    /// Advance in pixels.
+    pub advance: f32,
+    /// Left edge of the glyph's outline relative to the cursor, in pixels.
+    pub offset_x: f32,
+    /// Top edge of the glyph's outline below the top of the line, in pixels.
+    pub offset_y: f32,
+}
+
+/// Line and size metrics of a face at a pixel size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceMetrics {
+    pub ascender: f32,
+    pub line_height: f32,
+}
+
+/// Metrics of `face` at `px` pixels per em.
+pub fn face_metrics(face: &ttf_parser::Face, px: f32) -> FaceMetrics {
+    let scale = px / face.units_per_em() as f32;
+    let ascender = face.ascender() as f32 * scale;
+    let line_height = (face.ascender() as f32 - face.descender() as f32 + face.line_gap() as f32) * scale;
+    FaceMetrics { ascender, line_height }
+}
+
+/// Render the distance field of `ch` at `px` pixels per em. None if the face
+/// has no glyph for it; glyphs without outline (spaces) get an empty cell.
+pub fn render_glyph(face: &ttf_parser::Face, ch: char, px: f32) -> Option<GlyphBitmap> {
+    let glyph = face.glyph_index(ch)?;
+    let scale = px / face.units_per_em() as f32;
+    let advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+    let ascender = face_metrics(face, px).ascender;
+
+    let mut builder = ShapeBuilder { scale, contours: Vec::new(), current: Vec::new() };
+    let empty = GlyphBitmap {
+        codepoint: ch as u32,
+        width: 0,
+        height: 0,
+        pixels: Vec::new(),
+        advance,
+        offset_x: 0.0,
+        offset_y: 0.0,
+    };
+    let Some(bbox) = face.outline_glyph(glyph, &mut builder) else { return Some(empty) };
+    builder.close();
+    if builder.contours.is_empty() {
+        return Some(empty);
+    }
+
+    let pad = DISTANCE_RANGE.ceil();
+    let left = (bbox.x_min as f32 * scale).floor();
+    let right = (bbox.x_max as f32 * scale).ceil();
+    let top = (bbox.y_max as f32 * scale).ceil();
+    let bottom = (bbox.y_min as f32 * scale).floor();
+    let width = (right - left + 2.0 * pad) as u32;
+    let height = (top - bottom + 2.0 * pad) as u32;
+
+    // Font units are y-up; move the outline into the cell's y-down pixels
+    let contours: Vec<Vec<Point>> = builder
+        .contours
+        .into_iter()
+        .map(|c| c.into_iter().map(|p| Point { x: p.x - left + pad, y: top - p.y + pad, ..p }).collect())
+        .collect();
+    let shape = Shape::new(&contours);
+
+    Some(GlyphBitmap {
+        codepoint: ch as u32,
+        width,
+        height,
+        pixels: shape.distance_field(width, height, DISTANCE_RANGE),
+        advance,
+        offset_x: left,
+        offset_y: ascender - top,
+    })
+}
+
+/// An outline point. `corner_ok` is false for points inside a flattened
+/// curve, where the direction change is never a real corner.
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: f32,
+    y: f32,
+    corner_ok: bool,
+}
+
+/// Collects glyph outlines as closed polylines, in pixels (still y-up).
+struct ShapeBuilder {
+    scale: f32,
+    contours: Vec<Vec<Point>>,
+    current: Vec<Point>,
+}
+
+impl ShapeBuilder {
+    fn push(&mut self, x: f32, y: f32, corner_ok: bool) {
+        let p = Point { x: x * self.scale, y: y * self.scale, corner_ok };
+        if let Some(last) = self.current.last()
+            && (last.x - p.x).abs() < 1e-4
+            && (last.y - p.y).abs() < 1e-4
+        {
+            return;
+        }
+        self.current.push(p);
+    }
+
+    fn last(&self) -> (f32, f32) {
+        self.current.last().map_or((0.0, 0.0), |p| (p.x / self.scale, p.y / self.scale))
+    }
+}
+
+impl OutlineBuilder for ShapeBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.push(x, y, true);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(x, y, true);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            let px = u * u * x0 + 2.0 * u * t * x1 + t * t * x;
+            let py = u * u * y0 + 2.0 * u * t * y1 + t * t * y;
+            self.push(px, py, i == CURVE_STEPS);
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            let px = u * u * u * x0 + 3.0 * u * u * t * x1 + 3.0 * u * t * t * x2 + t * t * t * x;
+            let py = u * u * u * y0 + 3.0 * u * u * t * y1 + 3.0 * u * t * t * y2 + t * t * t * y;
+            self.push(px, py, i == CURVE_STEPS);
+        }
+    }
+
+    fn close(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        // The closing point repeats the start
+        if contour.len() > 1 {
+            let (first, last) = (contour[0], contour[contour.len() - 1]);
+            if (first.x - last.x).abs() < 1e-4 && (first.y - last.y).abs() < 1e-4 {
+                contour.pop();
+            }
+        }
+        if contour.len() >= 3 {
+            self.contours.push(contour);
+        }
+    }
+}
+
+/// A straight edge with the channels it contributes to.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    a: [f32; 2],
+    b: [f32; 2],
+    color: u8,
+}
+
+/// Signed distance candidate, compared like msdfgen's: nearer first, then the
+/// edge the point is more perpendicular to.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    dot: f32,
+    edge: usize,
+}
+
+impl Candidate {
+    const NONE: Candidate = Candidate { distance: f32::MAX, dot: f32::MAX, edge: usize::MAX };
+
+    fn closer_than(&self, other: &Candidate) -> bool {
+        self.distance < other.distance || (self.distance == other.distance && self.dot < other.dot)
+    }
+}
+
+/// Colored edges of a glyph, with the orientation that makes insides positive.
+struct Shape {
+    edges: Vec<Edge>,
+    orientation: f32,
+}
+
+impl Shape {
+    fn new(contours: &[Vec<Point>]) -> Self {
+        let mut edges = Vec::new();
+        let mut area = 0.0;
+        for contour in contours {
+            let n = contour.len();
+            for i in 0..n {
+                let (p, q) = (contour[i], contour[(i + 1) % n]);
+                area += p.x * q.y - q.x * p.y;
+            }
+            let colors = color_edges(contour);
+            for i in 0..n {
+                let (p, q) = (contour[i], contour[(i + 1) % n]);
+                edges.push(Edge { a: [p.x, p.y], b: [q.x, q.y], color: colors[i] });
+            }
+        }
+        Self { edges, orientation: if area < 0.0 { -1.0 } else { 1.0 } }
+    }
+
+    /// RGBA field of a `width`×`height` cell: 0.5 on the outline, rising
+    /// inwards by 0.5 per `range` pixels.
+    fn distance_field(&self, width: u32, height: u32, range: f32) -> Vec<u8> {
+        let mut pixels = vec![255u8; (width * height * 4) as usize];
+        for y in 0..height {
+            for x in 0..width {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+                let mut best = [Candidate::NONE; 3];
+                for (i, edge) in self.edges.iter().enumerate() {
+                    let (distance, dot) = true_distance(edge, p);
+                    let candidate = Candidate { distance, dot, edge: i };
+                    for (channel, best) in best.iter_mut().enumerate() {
+                        if edge.color & (1 << channel) != 0 && candidate.closer_than(best) {
+                            *best = candidate;
+                        }
+                    }
+                }
+                let offset = ((y * width + x) * 4) as usize;
+                for (channel, best) in best.iter().enumerate() {
+                    let signed = match self.edges.get(best.edge) {
+                        Some(edge) => pseudo_distance(edge, p) * self.orientation,
+                        None => -range,
+                    };
+                    let value = (0.5 + signed / (2.0 * range)).clamp(0.0, 1.0);
+                    pixels[offset + channel] = (value * 255.0).round() as u8;
+                }
+            }
+        }
+        pixels
+    }
+}
+
+/// Unsigned distance from `p` to the edge, and how far from perpendicular the
+/// approach is at an endpoint (0 inside the edge).
+fn true_distance(edge: &Edge, p: [f32; 2]) -> (f32, f32) {
+    let ab = [edge.b[0] - edge.a[0], edge.b[1] - edge.a[1]];
+    let ap = [p[0] - edge.a[0], p[1] - edge.a[1]];
+    let len_sq = (ab[0] * ab[0] + ab[1] * ab[1]).max(1e-12);
+    let t = (ap[0] * ab[0] + ap[1] * ab[1]) / len_sq;
+    let endpoint = if t <= 0.0 {
+        edge.a
+    } else if t >= 1.0 {
+        edge.b
+    } else {
+        let q = [edge.a[0] + ab[0] * t, edge.a[1] + ab[1] * t];
+        return (((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2)).sqrt(), 0.0);
+    };
+    let d = [p[0] - endpoint[0], p[1] - endpoint[1]];
+    let dist = (d[0] * d[0] + d[1] * d[1]).sqrt();
+    let dot = if dist > 0.0 { (d[0] * ab[0] + d[1] * ab[1]).abs() / (dist * len_sq.sqrt()) } else { 0.0 };
+    (dist, dot)
+}
+
+/// Signed distance from `p` to the line through the edge (its pseudo-
+/// distance). Positive on the edge's left in y-down space.
+fn pseudo_distance(edge: &Edge, p: [f32; 2]) -> f32 {
+    let ab = [edge.b[0] - edge.a[0], edge.b[1] - edge.a[1]];
+    let ap = [p[0] - edge.a[0], p[1] - edge.a[1]];
+    let len = (ab[0] * ab[0] + ab[1] * ab[1]).sqrt().max(1e-6);
+    (ab[0] * ap[1] - ab[1] * ap[0]) / len
+}
+
+/// Edge colors for a closed contour: all channels for smooth contours,
+/// otherwise the runs between corners cycle through two-channel colors so
+/// the edges meeting at a corner always differ.
+fn color_edges(contour: &[Point]) -> Vec<u8> {
+    let n = contour.len();
+    let direction = |i: usize| {
+        let (p, q) = (contour[i % n], contour[(i + 1) % n]);
+        let (dx, dy) = (q.x - p.x, q.y - p.y);
+        let len = (dx * dx + dy * dy).sqrt().max(1e-6);
+        (dx / len, dy / len)
+    };
+    // Edge i starts at point i; a corner at point i sits between edges i-1 and i
+    let corners: Vec<usize> = (0..n)
+        .filter(|&i| {
+            if !contour[i].corner_ok {
+                return false;
+            }
+            let (a, b) = (direction(i + n - 1), direction(i));
+            a.0 * b.0 + a.1 * b.1 <= 0.0 || (a.0 * b.1 - a.1 * b.0).abs() > CORNER_SIN
+        })
+        .collect();
+    let mut colors = vec![WHITE; n];
+    match corners.len() {
+        0 => {}
+        // A single corner (a teardrop): split the contour into three runs
+        1 => {
+            let start = corners[0];
+            for k in 0..n {
+                colors[(start + k) % n] = [RED | BLUE, WHITE, RED | GREEN][(k * 3 / n).min(2)];
+            }
+        }
+        count => {
+            for (run, &start) in corners.iter().enumerate() {
+                // The last run must differ from the first, which it wraps into
+                let mut color = CORNER_COLORS[run % 3];
+                if run == count - 1 && run % 3 == 0 {
+                    color = CORNER_COLORS[1];
+                }
+                let end = corners.get(run + 1).copied().unwrap_or(corners[0] + n);
+                for i in start..end {
+                    colors[i % n] = color;
+                }
+            }
+        }
+    }
+    colors
+}
+
+/// Places glyph cells in rows from the top-left of a square atlas.
+#[derive(Debug, Clone)]
+struct ShelfPacker {
+    size: u32,
+    x: u32,
+    y: u32,
+    row_height: u32,
+}
+
+impl ShelfPacker {
+    /// Pixels left empty between cells, so filtering never bleeds.
+    const GAP: u32 = 1;
+
+    fn new(size: u32) -> Self {
+        Self { size, x: 0, y: 0, row_height: 0 }
+    }
+
+    /// Top-left corner for a `w`×`h` cell, or None when the atlas is full.
+    fn place(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        if w > self.size || h > self.size {
+            return None;
+        }
+        if self.x + w > self.size {
+            self.x = 0;
+            self.y += self.row_height + Self::GAP;
+            self.row_height = 0;
+        }
+        if self.y + h > self.size {
+            return None;
+        }
+        let spot = (self.x, self.y);
+        self.x += w + Self::GAP;
+        self.row_height = self.row_height.max(h);
+        Some(spot)
+    }
+}
+
+/// A runtime font: its atlas and the worker generating its glyphs.
+struct RuntimeFont {
+    texture_id: u32,
+    data: Arc<Vec<u8>>,
+    atlas: Vec<u8>,
+    packer: ShelfPacker,
+    /// Atlas changed since the last upload.
+    dirty: bool,
+    full_warned: bool,
+    /// Codepoints sent to the worker (finished or not).
+    requested: HashSet<u32>,
+    requests: mpsc::Sender<Vec<char>>,
+    results: mpsc::Receiver<GlyphBitmap>,
+}
+
+/// Runtime fonts by MSDF font ID.
+#[derive(Default)]
+pub struct RuntimeFontStore {
+    fonts: HashMap<u32, RuntimeFont>,
+    /// Font ID by file path and glyph pixel size, so loading twice is free.
+    by_source: HashMap<(String, u32), u32>,
+}
+
+impl RuntimeFontStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Font already loaded from `path` at `px`, if any.
+    pub fn find(&self, path: &str, px: f32) -> Option<u32> {
+        self.by_source.get(&(path.to_string(), px as u32)).copied()
+    }
+
+    /// Register font file `data` (read from `path`) with `fonts` and start
+    /// generating printable ASCII at `px` pixels per em into texture
+    /// `texture_id`. Returns the MSDF font ID.
+    pub fn load(
+        &mut self,
+        fonts: &mut MsdfFontStore,
+        path: &str,
+        data: Vec<u8>,
+        px: f32,
+        texture_id: u32,
+    ) -> Result<u32, String> {
+        let metrics = {
+            let face = ttf_parser::Face::parse(&data, 0).map_err(|e| format!("Invalid font file {path}: {e}"))?;
+            face_metrics(&face, px)
+        };
+        let data = Arc::new(data);
+        let (requests, worker_requests) = mpsc::channel::<Vec<char>>();
+        let (worker_results, results) = mpsc::channel();
+        let worker_data = Arc::clone(&data);
+        std::thread::Builder::new()
+            .name("arcane-font".into())
+            .spawn(move || {
+                let Ok(face) = ttf_parser::Face::parse(&worker_data, 0) else { return };
+                for batch in worker_requests {
+                    for ch in batch {
+                        if let Some(glyph) = render_glyph(&face, ch, px)
+                            && worker_results.send(glyph).is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start the font worker: {e}"))?;
+
+        let font_id = fonts.register(MsdfFont {
+            texture_id,
+            atlas_width: RUNTIME_ATLAS_SIZE,
+            atlas_height: RUNTIME_ATLAS_SIZE,
+            font_size: px,
+            line_height: metrics.line_height,
+            distance_range: DISTANCE_RANGE,
+            glyphs: HashMap::new(),
+        });
+        let mut font = RuntimeFont {
+            texture_id,
+            data,
+            atlas: vec![0; (RUNTIME_ATLAS_SIZE * RUNTIME_ATLAS_SIZE * 4) as usize],
+            packer: ShelfPacker::new(RUNTIME_ATLAS_SIZE),
+            dirty: true,
+            full_warned: false,
+            requested: HashSet::new(),
+            requests,
+            results,
+        };
+        font.request((' '..='~').collect());
+        self.fonts.insert(font_id, font);
+        self.by_source.insert((path.to_string(), px as u32), font_id);
+        Ok(font_id)
+    }
+
+    /// Whether `font_id` is a runtime font.
+    pub fn contains(&self, font_id: u32) -> bool {
+        self.fonts.contains_key(&font_id)
+    }
+
+    /// Queue the characters of `text` that `font_id` has no glyph for yet.
+    pub fn request_missing(&mut self, font_id: u32, text: &str) {
+        let Some(font) = self.fonts.get_mut(&font_id) else { return };
+        let Ok(face) = ttf_parser::Face::parse(&font.data, 0) else { return };
+        let missing: Vec<char> = text
+            .chars()
+            .filter(|&ch| !font.requested.contains(&(ch as u32)) && face.glyph_index(ch).is_some())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        font.request(missing);
+    }
+
+    /// Pack glyphs the workers have finished into their atlases and add their
+    /// metrics to `fonts`. Returns `(texture_id, size, rgba)` for every atlas
+    /// that changed and needs uploading.
+    pub fn poll(&mut self, fonts: &mut MsdfFontStore) -> Vec<(u32, u32, Vec<u8>)> {
+        let mut uploads = Vec::new();
+        for (&font_id, font) in &mut self.fonts {
+            let Some(msdf) = fonts.get_mut(font_id) else { continue };
+            while let Ok(glyph) = font.results.try_recv() {
+                font.add(msdf, glyph);
+            }
+            if font.dirty {
+                font.dirty = false;
+                uploads.push((font.texture_id, RUNTIME_ATLAS_SIZE, font.atlas.clone()));
+            }
+        }
+        uploads
+    }
+}
+
+impl RuntimeFont {
+    fn request(&mut self, chars: Vec<char>) {
+        let chars: Vec<char> = chars.into_iter().filter(|&ch| self.requested.insert(ch as u32)).collect();
+        if !chars.is_empty() {
+            let _ = self.requests.send(chars);
+        }
+    }
+
+    /// Copy a finished glyph into the atlas and register its metrics.
+    fn add(&mut self, msdf: &mut MsdfFont, glyph: GlyphBitmap) {
+        let size = RUNTIME_ATLAS_SIZE as f32;
+        let (x, y) = if glyph.width == 0 || glyph.height == 0 {
+            (0, 0)
+        } else if let Some(spot) = self.packer.place(glyph.width, glyph.height) {
+            spot
+        } else {
+            if !self.full_warned {
+                self.full_warned = true;
+                eprintln!("[font] Atlas of font {} is full; further glyphs are skipped", msdf.texture_id);
+            }
+            return;
+        };
+        for row in 0..glyph.height {
+            let src = (row * glyph.width * 4) as usize;
+            let dst = (((y + row) * RUNTIME_ATLAS_SIZE + x) * 4) as usize;
+            let len = (glyph.width * 4) as usize;
+            self.atlas[dst..dst + len].copy_from_slice(&glyph.pixels[src..src + len]);
+        }
+        self.dirty |= glyph.width > 0;
+        msdf.glyphs.insert(
+            glyph.codepoint,
+            MsdfGlyph {
+                uv_x: x as f32 / size,
+                uv_y: y as f32 / size,
+                uv_w: glyph.width as f32 / size,
+                uv_h: glyph.height as f32 / size,
+                advance: glyph.advance,
+                width: glyph.width as f32,
+                height: glyph.height as f32,
+                offset_x: glyph.offset_x,
+                offset_y: glyph.offset_y,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32) -> Point {
+        Point { x, y, corner_ok: true }
+    }
+
+    /// A 12px square with 4px of padding around it in a 20px cell.
+    fn square(clockwise: bool) -> Shape {
+        let mut contour = vec![point(4.0, 4.0), point(16.0, 4.0), point(16.0, 16.0), point(4.0, 16.0)];
+        if clockwise {
+            contour.reverse();
+        }
+        Shape::new(&[contour])
+    }
+
+    fn channels(field: &[u8], x: u32, y: u32) -> [u8; 3] {
+        let o = ((y * 20 + x) * 4) as usize;
+        [field[o], field[o + 1], field[o + 2]]
+    }
+
+    fn median(c: [u8; 3]) -> u8 {
+        c[0].max(c[1]).min(c[0].min(c[1]).max(c[2]))
+    }
+
+    #[test]
+    fn test_square_is_positive_inside_either_winding() {
+        for clockwise in [false, true] {
+            let field = square(clockwise).distance_field(20, 20, 4.0);
+            assert!(median(channels(&field, 10, 10)) > 200, "inside");
+            assert!(median(channels(&field, 1, 10)) < 60, "outside");
+            // One pixel inside the left edge: just above the outline value
+            let edge = median(channels(&field, 4, 10));
+            assert!((130..=160).contains(&edge), "near edge: {edge}");
+        }
+    }
+
+    #[test]
+    fn test_corners_stay_sharp_in_the_median() {
+        let field = square(false).distance_field(20, 20, 4.0);
+        // Diagonally outside the corner the median is the outside distance
+        // along the nearer edge, not the rounded distance to the corner point
+        assert!(median(channels(&field, 2, 2)) < 128);
+        // The square's corners get differently colored edges
+        let colors = color_edges(&[point(4.0, 4.0), point(16.0, 4.0), point(16.0, 16.0), point(4.0, 16.0)]);
+        for i in 0..4 {
+            assert_ne!(colors[i], colors[(i + 1) % 4]);
+            assert_ne!(colors[i], WHITE);
+        }
+    }
+
+    #[test]
+    fn test_smooth_contours_use_all_channels() {
+        // A flattened circle: every joint is inside a curve
+        let contour: Vec<Point> = (0..16)
+            .map(|i| {
+                let a = i as f32 / 16.0 * std::f32::consts::TAU;
+                Point { x: 10.0 + 6.0 * a.cos(), y: 10.0 + 6.0 * a.sin(), corner_ok: false }
+            })
+            .collect();
+        assert!(color_edges(&contour).iter().all(|&c| c == WHITE));
+    }
+
+    #[test]
+    fn test_shelf_packer_wraps_rows_and_fills_up() {
+        let mut packer = ShelfPacker::new(32);
+        assert_eq!(packer.place(20, 10), Some((0, 0)));
+        assert_eq!(packer.place(20, 8), Some((0, 11)));
+        assert_eq!(packer.place(10, 12), Some((21, 11)));
+        assert_eq!(packer.place(30, 10), None);
+        assert_eq!(packer.place(64, 1), None);
+    }
+
+    #[test]
+    fn test_glyph_px_follows_the_largest_size() {
+        assert_eq!(glyph_px_for_sizes(12.0, 64.0), 32.0);
+        assert_eq!(glyph_px_for_sizes(8.0, 10.0), 16.0);
+        assert_eq!(glyph_px_for_sizes(12.0, 400.0), 64.0);
+    }
+}
@@ -39,13 +39,13 @@
 /// | color    | Float32x4   | Primary color from fill (passed to shader)|
 
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
-use super::gpu::GpuContext;
+use super::gpu::{multisample_state, ColorTarget, GpuContext};
 
 // ---------------------------------------------------------------------------
 // Types
@@ -703,8 +703,11 @@ struct CameraUniform {
 
 /// Manages cached SDF pipelines and renders SDF commands.
 pub struct SdfPipelineStore {
-    /// Cached render pipelines keyed by `compute_pipeline_key(expr, fill)`.
-    pipelines: HashMap<u64, wgpu::RenderPipeline>,
+    /// Cached render pipelines keyed by `compute_pipeline_key(expr, fill)` and
+    /// sample count.
+    pipelines: HashMap<(u64, u32), wgpu::RenderPipeline>,
+    /// Keys whose shader failed validation; their commands are skipped.
+    failed: HashSet<u64>,
     /// Shared pipeline layout (all SDF pipelines use the same bind group layout).
     pipeline_layout: wgpu::PipelineLayout,
     /// Camera bind group layout (group 0).
@@ -728,6 +731,8 @@ pub struct SdfPipelineStore {
     index_buffer: wgpu::Buffer,
     /// Surface texture format (needed when creating new pipelines).
     surface_format: wgpu::TextureFormat,
+    /// Sample count of the scene (see `set_msaa_samples`).
+    samples: u32,
 }
 
 impl SdfPipelineStore {
@@ -836,6 +841,7 @@ impl SdfPipelineStore {
 
         Self {
             pipelines: HashMap::new(),
+            failed: HashSet::new(),
             pipeline_layout,
             camera_bind_group_layout,
             camera_buffer,
@@ -846,6 +852,17 @@ impl SdfPipelineStore {
             vertex_buffer,
             index_buffer,
             surface_format,
+            samples: 1,
+        }
+    }
+
+    /// Build scene pipelines for `samples` per pixel. Changing it drops the
+    /// pipelines of the old count, except the single-sampled ones render
+    /// targets use; they recompile on their next draw.
+    pub fn set_msaa_samples(&mut self, samples: u32) {
+        if samples != self.samples {
+            self.samples = samples;
+            self.pipelines.retain(|&(_, n), _| n == 1 || n == samples);
         }
     }
 
@@ -867,16 +884,27 @@ impl SdfPipelineStore {
         queue.write_buffer(&self.time_buffer, 0, bytemuck::cast_slice(&[time]));
     }
 
-    /// Get or create the render pipeline for the given SDF expression + fill.
-    /// Returns a reference to the cached pipeline.
+    /// Get or create the scene's render pipeline for the given SDF expression + fill.
+    /// Returns the pipeline key.
     pub fn get_or_create_pipeline(
         &mut self,
         device: &wgpu::Device,
         sdf_expr: &str,
         fill: &SdfFill,
+    ) -> u64 {
+        self.get_or_create_pipeline_for(device, sdf_expr, fill, self.samples)
+    }
+
+    /// Get or create the pipeline for targets with `samples` per pixel.
+    pub fn get_or_create_pipeline_for(
+        &mut self,
+        device: &wgpu::Device,
+        sdf_expr: &str,
+        fill: &SdfFill,
+        samples: u32,
     ) -> u64 {
         let key = compute_pipeline_key(sdf_expr, fill);
-        if self.pipelines.contains_key(&key) {
+        if self.pipelines.contains_key(&(key, samples)) || self.failed.contains(&key) {
             return key;
         }
 
@@ -976,15 +1004,35 @@ impl SdfPipelineStore {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: multisample_state(samples),
             multiview: None,
             cache: None,
         });
 
-        self.pipelines.insert(key, pipeline);
+        self.pipelines.insert((key, samples), pipeline);
         key
     }
 
+    /// Whether a scene pipeline for this expression + fill was already
+    /// compiled (or failed to compile).
+    pub fn has_pipeline(&self, sdf_expr: &str, fill: &SdfFill) -> bool {
+        self.has_pipeline_for(sdf_expr, fill, self.samples)
+    }
+
+    /// [`has_pipeline`](Self::has_pipeline) for targets with `samples` per pixel.
+    pub fn has_pipeline_for(&self, sdf_expr: &str, fill: &SdfFill, samples: u32) -> bool {
+        let key = compute_pipeline_key(sdf_expr, fill);
+        self.pipelines.contains_key(&(key, samples)) || self.failed.contains(&key)
+    }
+
+    /// Drop a pipeline that failed validation so its commands are skipped
+    /// instead of raising an error every frame.
+    pub fn mark_failed(&mut self, sdf_expr: &str, fill: &SdfFill) {
+        let key = compute_pipeline_key(sdf_expr, fill);
+        self.pipelines.retain(|&(k, _), _| k != key);
+        self.failed.insert(key);
+    }
+
     /// Return the camera bind group layout for sharing with the sprite pipeline.
     pub fn camera_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
         &self.camera_bind_group_layout
@@ -997,21 +1045,23 @@ impl SdfPipelineStore {
     ///
     /// Call `prepare()` once per frame before calling `render()`.
     /// `clear_color`: `Some(color)` -> `LoadOp::Clear`, `None` -> `LoadOp::Load`.
-    pub fn render(
+    /// Pipelines are built for the target's sample count.
+    pub fn render<'t>(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
-        target: &wgpu::TextureView,
+        target: impl Into<ColorTarget<'t>>,
         commands: &[SdfCommand],
         clear_color: Option<wgpu::Color>,
     ) {
+        let target = target.into();
         if commands.is_empty() {
             return;
         }
 
         // Ensure all pipelines are compiled
         for cmd in commands {
-            self.get_or_create_pipeline(device, &cmd.sdf_expr, &cmd.fill);
+            self.get_or_create_pipeline_for(device, &cmd.sdf_expr, &cmd.fill, target.samples);
         }
 
         let load_op = match clear_color {
@@ -1022,7 +1072,7 @@ impl SdfPipelineStore {
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("sdf_render_pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: target,
+                view: target.view,
                 resolve_target: None,
                 ops: wgpu::Operations {
                     load: load_op,
@@ -1033,6 +1083,7 @@ impl SdfPipelineStore {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_viewport(&mut render_pass);
 
         render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
         render_pass.set_bind_group(1, &self.time_bind_group, &[]);
@@ -1054,9 +1105,9 @@ impl SdfPipelineStore {
             }
 
             let batch = &commands[batch_start..i];
-            let pipeline = match self.pipelines.get(&key) {
+            let pipeline = match self.pipelines.get(&(key, target.samples)) {
                 Some(p) => p,
-                None => continue, // should not happen after ensure step
+                None => continue, // failed to compile
             };
 
             // Build instance data for this batch
@@ -1096,6 +1147,7 @@ impl SdfPipelineStore {
     /// Remove all cached pipelines (e.g. after a hot-reload).
     pub fn clear(&mut self) {
         self.pipelines.clear();
+        self.failed.clear();
     }
 }
 
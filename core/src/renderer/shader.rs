@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use wgpu::util::DeviceExt;
 
-use super::gpu::GpuContext;
+use super::gpu::{multisample_state, GpuContext};
+use super::lighting::LightCapacity;
 
 /// Maximum number of vec4 uniform slots per custom shader (built-in + user).
 /// Layout: 2 vec4s for built-ins (time, delta, resolution, mouse, padding) + 14 user vec4s.
@@ -14,19 +15,19 @@ const UNIFORM_BUFFER_SIZE: usize = MAX_PARAM_SLOTS * 16;
 
 /// Extract the vertex shader + shared declarations from sprite.wgsl.
 /// Everything before `@fragment` is the preamble.
-fn shader_preamble() -> &'static str {
+fn shader_preamble(lights: LightCapacity) -> String {
     let wgsl = include_str!("shaders/sprite.wgsl");
     let idx = wgsl
         .find("@fragment")
         .expect("sprite.wgsl must contain @fragment");
-    &wgsl[..idx]
+    lights.patch_wgsl(&wgsl[..idx])
 }
 
 /// Build complete WGSL for a custom shader by combining:
 /// 1. Standard preamble (camera, texture, lighting, vertex shader)
 /// 2. Custom uniform params declaration (group 3)
 /// 3. User's fragment shader code
-fn build_custom_wgsl(user_fragment: &str) -> String {
+fn build_custom_wgsl(user_fragment: &str, lights: LightCapacity) -> String {
     format!(
         r#"{}
 // Custom shader params: 2 built-in vec4s + 14 user vec4 slots = 256 bytes
@@ -44,13 +45,15 @@ var<uniform> shader_params: ShaderParams;
 
 {}
 "#,
-        shader_preamble(),
+        shader_preamble(lights),
         user_fragment,
     )
 }
 
 struct ShaderEntry {
-    pipeline: wgpu::RenderPipeline,
+    module: wgpu::ShaderModule,
+    /// Pipeline per sample count in `ShaderStore::sample_counts`.
+    pipelines: BTreeMap<u32, wgpu::RenderPipeline>,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
     param_data: [f32; MAX_PARAM_SLOTS * 4],
@@ -60,23 +63,33 @@ struct ShaderEntry {
 /// Manages custom user-defined fragment shaders.
 /// Each shader gets its own render pipeline and uniform buffer.
 pub struct ShaderStore {
-    shaders: HashMap<u32, ShaderEntry>,
+    shaders: BTreeMap<u32, ShaderEntry>,
     pipeline_layout: wgpu::PipelineLayout,
     params_bind_group_layout: wgpu::BindGroupLayout,
     surface_format: wgpu::TextureFormat,
+    /// Must match the sprite pipeline so custom shaders share its lighting bind group.
+    lights: LightCapacity,
+    /// Entry replaced by the last `create`, restored by `revert` if the new one fails.
+    replaced: Option<(u32, ShaderEntry)>,
+    /// Sample counts pipelines are built for: 1, plus the MSAA count in use.
+    sample_counts: Vec<u32>,
 }
 
 impl ShaderStore {
     /// Create a shader store for headless testing.
     pub fn new_headless(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
-        Self::new_internal(device, format)
+        Self::new_internal(device, format, LightCapacity::default())
     }
 
-    pub fn new(gpu: &GpuContext) -> Self {
-        Self::new_internal(&gpu.device, gpu.config.format)
+    pub fn new(gpu: &GpuContext, lights: LightCapacity) -> Self {
+        Self::new_internal(&gpu.device, gpu.config.format, lights)
     }
 
-    fn new_internal(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+    fn new_internal(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        lights: LightCapacity,
+    ) -> Self {
         // Create bind group layouts matching SpritePipeline's groups 0-2
         let camera_layout =
             device
@@ -126,7 +139,7 @@ impl ShaderStore {
                         binding: 0,
                         visibility: wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
+                            ty: lights.binding_type(),
                             has_dynamic_offset: false,
                             min_binding_size: None,
                         },
@@ -165,10 +178,13 @@ impl ShaderStore {
                 });
 
         Self {
-            shaders: HashMap::new(),
+            shaders: BTreeMap::new(),
             pipeline_layout,
             params_bind_group_layout,
             surface_format,
+            lights,
+            replaced: None,
+            sample_counts: vec![1],
         }
     }
 
@@ -176,8 +192,9 @@ impl ShaderStore {
     /// The source must contain a `@fragment fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>`.
     /// Standard declarations (camera, texture, lighting, vertex shader) are prepended automatically.
     /// Custom uniforms are available as `shader_params.values[0..15]` (vec4 array).
+    /// Re-creating an existing ID (hot reload) keeps its param values.
     pub fn create(&mut self, device: &wgpu::Device, id: u32, _name: &str, source: &str) {
-        let full_wgsl = build_custom_wgsl(source);
+        let full_wgsl = build_custom_wgsl(source, self.lights);
 
         let shader_module = device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -185,6 +202,47 @@ impl ShaderStore {
                 source: wgpu::ShaderSource::Wgsl(full_wgsl.into()),
             });
 
+        let pipelines = self
+            .sample_counts
+            .iter()
+            .map(|&samples| (samples, self.build_pipeline(device, &shader_module, samples)))
+            .collect();
+
+        // Create uniform buffer (zero-initialized)
+        let uniform_buffer =
+            device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("shader_params_buffer"),
+                    contents: &[0u8; UNIFORM_BUFFER_SIZE],
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shader_params_bind_group"),
+            layout: &self.params_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let previous = self.shaders.get(&id).map(|e| e.param_data);
+        let replaced = self.shaders.insert(
+            id,
+            ShaderEntry {
+                module: shader_module,
+                pipelines,
+                uniform_buffer,
+                uniform_bind_group,
+                param_data: previous.unwrap_or([0.0; MAX_PARAM_SLOTS * 4]),
+                dirty: previous.is_some(),
+            },
+        );
+        self.replaced = replaced.map(|entry| (id, entry));
+    }
+
+    /// Build a custom shader's pipeline for `samples` per pixel.
+    fn build_pipeline(&self, device: &wgpu::Device, module: &wgpu::ShaderModule, samples: u32) -> wgpu::RenderPipeline {
         let vertex_layout = wgpu::VertexBufferLayout {
             array_stride: 16, // QuadVertex: 2×f32 + 2×f32 = 16 bytes
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -203,7 +261,7 @@ impl ShaderStore {
         };
 
         let instance_layout = wgpu::VertexBufferLayout {
-            array_stride: 64, // SpriteInstance: 16 floats × 4 bytes = 64
+            array_stride: 96, // SpriteInstance: 24 floats × 4 bytes = 96
             step_mode: wgpu::VertexStepMode::Instance,
             attributes: &[
                 wgpu::VertexAttribute {
@@ -236,73 +294,89 @@ impl ShaderStore {
                     shader_location: 7,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: 64,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: 80,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         };
 
-        let pipeline =
-            device
-                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                    label: Some("custom_shader_pipeline"),
-                    layout: Some(&self.pipeline_layout),
-                    vertex: wgpu::VertexState {
-                        module: &shader_module,
-                        entry_point: Some("vs_main"),
-                        buffers: &[vertex_layout, instance_layout],
-                        compilation_options: Default::default(),
-                    },
-                    fragment: Some(wgpu::FragmentState {
-                        module: &shader_module,
-                        entry_point: Some("fs_main"),
-                        targets: &[Some(wgpu::ColorTargetState {
-                            format: self.surface_format,
-                            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                            write_mask: wgpu::ColorWrites::ALL,
-                        })],
-                        compilation_options: Default::default(),
-                    }),
-                    primitive: wgpu::PrimitiveState {
-                        topology: wgpu::PrimitiveTopology::TriangleList,
-                        strip_index_format: None,
-                        front_face: wgpu::FrontFace::Ccw,
-                        cull_mode: None,
-                        polygon_mode: wgpu::PolygonMode::Fill,
-                        unclipped_depth: false,
-                        conservative: false,
-                    },
-                    depth_stencil: None,
-                    multisample: wgpu::MultisampleState::default(),
-                    multiview: None,
-                    cache: None,
-                });
+        device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("custom_shader_pipeline"),
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module,
+                    entry_point: Some("vs_main"),
+                    buffers: &[vertex_layout, instance_layout],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: self.surface_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: multisample_state(samples),
+                multiview: None,
+                cache: None,
+            })
+    }
 
-        // Create uniform buffer (zero-initialized)
-        let uniform_buffer =
-            device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("shader_params_buffer"),
-                    contents: &[0u8; UNIFORM_BUFFER_SIZE],
-                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                });
+    /// Build pipelines for `samples` (besides single-sampled) for existing
+    /// and future shaders; 1 drops the multisampled variants.
+    pub fn set_msaa_samples(&mut self, device: &wgpu::Device, samples: u32) {
+        self.sample_counts = if samples > 1 { vec![1, samples] } else { vec![1] };
+        let counts = self.sample_counts.clone();
+        let mut shaders = std::mem::take(&mut self.shaders);
+        for entry in shaders.values_mut() {
+            entry.pipelines.retain(|count, _| counts.contains(count));
+            for &count in &counts {
+                if !entry.pipelines.contains_key(&count) {
+                    let pipeline = self.build_pipeline(device, &entry.module, count);
+                    entry.pipelines.insert(count, pipeline);
+                }
+            }
+        }
+        self.shaders = shaders;
+    }
 
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("shader_params_bind_group"),
-            layout: &self.params_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-        });
+    /// Lines of generated preamble before `source` in a custom shader's WGSL.
+    pub fn user_line_offset(&self, source: &str) -> u32 {
+        super::gpu_errors::line_offset(&build_custom_wgsl(source, self.lights), source)
+    }
 
-        self.shaders.insert(
-            id,
-            ShaderEntry {
-                pipeline,
-                uniform_buffer,
-                uniform_bind_group,
-                param_data: [0.0; MAX_PARAM_SLOTS * 4],
-                dirty: false,
-            },
-        );
+    /// Undo the last `create` of `id` after it failed validation: the version
+    /// it replaced comes back, or the shader is dropped (its sprites are skipped).
+    pub fn revert(&mut self, id: u32) {
+        match self.replaced.take() {
+            Some((replaced_id, entry)) if replaced_id == id => {
+                self.shaders.insert(id, entry);
+            }
+            _ => {
+                self.shaders.remove(&id);
+            }
+        }
     }
 
     /// Set a vec4 user parameter slot for a shader. Index 0-13 maps to WGSL `values[0..13]`.
@@ -350,9 +424,14 @@ impl ShaderStore {
         }
     }
 
-    /// Get the pipeline for a custom shader.
+    /// Get the single-sampled pipeline for a custom shader.
     pub fn get_pipeline(&self, id: u32) -> Option<&wgpu::RenderPipeline> {
-        self.shaders.get(&id).map(|e| &e.pipeline)
+        self.get_pipeline_for(id, 1)
+    }
+
+    /// Get a custom shader's pipeline for a target with `samples` per pixel.
+    pub fn get_pipeline_for(&self, id: u32, samples: u32) -> Option<&wgpu::RenderPipeline> {
+        self.shaders.get(&id).and_then(|e| e.pipelines.get(&samples))
     }
 
     /// Get the uniform bind group for a custom shader (group 3).
@@ -0,0 +1,45 @@
+// Geometry pipeline shader: textured triangle meshes (deform grids, skeleton meshes).
+//
+// Bind groups:
+// @group(0) — Camera uniform (vertex), same layout as sprite.wgsl group 0
+// @group(1) — Texture + sampler (fragment), same layout as sprite.wgsl group 1
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var t_diffuse: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s_diffuse: sampler;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let world = vec4<f32>(vertex.position.x, vertex.position.y, 0.0, 1.0);
+    out.clip_position = camera.view_proj * world;
+    out.uv = vertex.uv;
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, in.uv) * in.color;
+}
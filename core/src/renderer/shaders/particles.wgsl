@@ -0,0 +1,167 @@
+// GPU particle simulation and drawing
+//
+// simulate: one invocation per particle slot. Slots in the frame's spawn
+// range (a window of the ring buffer) are re-initialized; live particles are
+// integrated; dead ones are left alone.
+//
+// vs_main/fs_main: the particle buffer is bound as an instance vertex buffer.
+// Dead particles collapse to a point outside the clip volume.
+//
+// Bind groups (render):
+// @group(0) — Camera uniform (vertex), shared with the sprite pipeline
+// @group(1) — Texture + sampler (fragment)
+// @group(2) — Emitter params (vertex + fragment)
+//
+// Bind groups (compute):
+// @group(0) @binding(1) — Emitter params, @binding(2) — particle storage
+// (bindings start at 1 so they don't collide with the camera in this module)
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    age: f32,
+    life: f32,
+    _pad: vec2<f32>,
+};
+
+struct EmitterParams {
+    // x, y, dt, shape (0 = texture, 1 = soft dot)
+    origin_dt: vec4<f32>,
+    // spawn start, spawn count, capacity, frame
+    spawn: vec4<u32>,
+    // lifetime min/max, speed min/max
+    lifetime_speed: vec4<f32>,
+    // direction, spread, spawn radius, drag
+    emit: vec4<f32>,
+    // gravity x/y, speed multiplier at birth/death
+    gravity_curve: vec4<f32>,
+    // size at birth/death, color mid time, unused
+    size: vec4<f32>,
+    color_start: vec4<f32>,
+    color_mid: vec4<f32>,
+    color_end: vec4<f32>,
+};
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+
+@group(0) @binding(1)
+var<uniform> sim_params: EmitterParams;
+
+@group(0) @binding(2)
+var<storage, read_write> particles: array<Particle>;
+
+// PCG hash
+fn hash(v: u32) -> u32 {
+    let state = v * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn rand(seed: ptr<function, u32>) -> f32 {
+    *seed = hash(*seed);
+    return f32(*seed) / 4294967295.0;
+}
+
+@compute @workgroup_size(64)
+fn simulate(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let i = gid.x;
+    let capacity = sim_params.spawn.z;
+    if (i >= capacity) {
+        return;
+    }
+
+    var p = particles[i];
+    let dt = sim_params.origin_dt.z;
+    // Position of this slot within the frame's spawn window (ring buffer)
+    let rel = (i + capacity - sim_params.spawn.x) % capacity;
+
+    if (rel < sim_params.spawn.y) {
+        var seed = hash(i ^ hash(sim_params.spawn.w));
+        let angle = sim_params.emit.x + (rand(&seed) - 0.5) * sim_params.emit.y;
+        let speed = mix(sim_params.lifetime_speed.z, sim_params.lifetime_speed.w, rand(&seed));
+        let offset_angle = rand(&seed) * 6.2831853;
+        let offset = sqrt(rand(&seed)) * sim_params.emit.z;
+        p.pos = sim_params.origin_dt.xy + vec2<f32>(cos(offset_angle), sin(offset_angle)) * offset;
+        p.vel = vec2<f32>(cos(angle), sin(angle)) * speed;
+        p.age = 0.0;
+        p.life = mix(sim_params.lifetime_speed.x, sim_params.lifetime_speed.y, rand(&seed));
+    } else if (p.age < p.life) {
+        let t = p.age / p.life;
+        let speed_scale = mix(sim_params.gravity_curve.z, sim_params.gravity_curve.w, t);
+        p.vel = p.vel + sim_params.gravity_curve.xy * dt;
+        p.vel = p.vel * max(1.0 - sim_params.emit.w * dt, 0.0);
+        p.pos = p.pos + p.vel * speed_scale * dt;
+        p.age = p.age + dt;
+    }
+
+    particles[i] = p;
+}
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var t_diffuse: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s_diffuse: sampler;
+
+@group(2) @binding(0)
+var<uniform> params: EmitterParams;
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct ParticleInput {
+    @location(2) pos: vec2<f32>,
+    @location(3) vel: vec2<f32>,
+    @location(4) age_life: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+fn color_over_life(t: f32) -> vec4<f32> {
+    let mid = params.size.z;
+    if (t < mid) {
+        return mix(params.color_start, params.color_mid, t / max(mid, 0.0001));
+    }
+    return mix(params.color_mid, params.color_end, (t - mid) / max(1.0 - mid, 0.0001));
+}
+
+@vertex
+fn vs_main(vertex: VertexInput, particle: ParticleInput) -> VertexOutput {
+    var out: VertexOutput;
+    if (particle.age_life.x >= particle.age_life.y) {
+        out.clip_position = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+        out.uv = vertex.uv;
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+
+    let t = clamp(particle.age_life.x / particle.age_life.y, 0.0, 1.0);
+    let size = mix(params.size.x, params.size.y, t);
+    let world = particle.pos + vertex.position * size;
+    out.clip_position = camera.view_proj * vec4<f32>(world, 0.0, 1.0);
+    out.uv = vertex.uv;
+    out.color = color_over_life(t);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(t_diffuse, s_diffuse, in.uv) * in.color;
+    if (params.origin_dt.w > 0.5) {
+        // Soft round dot for untextured emitters
+        let d = length(in.uv - vec2<f32>(0.5)) * 2.0;
+        color.a = color.a * clamp(1.0 - d * d, 0.0, 1.0);
+    }
+    return color;
+}
@@ -4,6 +4,7 @@
 // @group(0) — Camera uniform (vertex)
 // @group(1) — Texture + sampler (fragment)
 // @group(2) — Lighting uniform (fragment)
+// @group(3) — Normal map texture + sampler (fs_normal only)
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
@@ -19,13 +20,22 @@ var t_diffuse: texture_2d<f32>;
 var s_diffuse: sampler;
 
 struct LightData {
-    pos_radius: vec4<f32>,      // xy = position, z = radius, w = padding
+    pos_radius: vec4<f32>,      // xy = position, z = radius, w = shadow softness (0 = no shadows)
     color_intensity: vec4<f32>, // rgb = color, a = intensity
 };
 
 struct LightingUniform {
     ambient: vec3<f32>,
     light_count: u32,
+    directional_count: u32,
+    occluder_count: u32,
+    _pad1: u32,
+    _pad2: u32,
+    // Directional lights: pos_radius.xyz = unit vector towards the light.
+    // Only normal-mapped sprites are lit by them.
+    directional: array<LightData, 4>,
+    // Occluder rects (min xy, max xy) blocking shadow-casting point lights
+    occluders: array<vec4<f32>, 32>,
     lights: array<LightData, 8>,
 };
 
@@ -44,6 +54,8 @@ struct InstanceInput {
     @location(5) uv_size: vec2<f32>,
     @location(6) tint: vec4<f32>,
     @location(7) rotation_origin: vec4<f32>,
+    @location(8) outline_rect: vec4<f32>,
+    @location(9) flash: vec4<f32>,
 };
 
 struct VertexOutput {
@@ -51,6 +63,13 @@ struct VertexOutput {
     @location(0) tex_coords: vec2<f32>,
     @location(1) tint: vec4<f32>,
     @location(2) world_position: vec2<f32>,
+    // Outline instances: source UV rect and outline width in UV units
+    @location(3) @interpolate(flat) outline_rect: vec4<f32>,
+    @location(4) @interpolate(flat) outline_radius: vec2<f32>,
+    // Normal map basis: cos/sin of the rotation, then the x/y flip signs
+    @location(5) @interpolate(flat) normal_basis: vec4<f32>,
+    // Hit flash: rgb = color, a = how far to mix towards it
+    @location(6) @interpolate(flat) flash: vec4<f32>,
 };
 
 @vertex
@@ -84,6 +103,11 @@ fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
     out.tex_coords = instance.uv_offset + vertex.uv * instance.uv_size;
     out.tint = instance.tint;
     out.world_position = world_xy;
+    out.outline_rect = instance.outline_rect;
+    out.outline_radius = (abs(instance.uv_size) - (instance.outline_rect.zw - instance.outline_rect.xy)) * 0.5;
+    let mirrored = instance.uv_size * instance.size < vec2<f32>(0.0);
+    out.normal_basis = vec4<f32>(cos_r, sin_r, select(1.0, -1.0, mirrored.x), select(1.0, -1.0, mirrored.y));
+    out.flash = instance.flash;
 
     return out;
 }
@@ -92,6 +116,12 @@ fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
 
+    // Outline instances draw the outline color, unlit, around the silhouette
+    if (in.outline_rect.z > in.outline_rect.x) {
+        let alpha = outline_alpha(in.tex_coords, in.outline_rect, in.outline_radius);
+        return vec4<f32>(in.tint.rgb, in.tint.a * alpha);
+    }
+
     // Accumulate lighting
     var light_color = lighting.ambient;
 
@@ -103,12 +133,148 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         let intensity = light.color_intensity.a;
 
         let dist = length(in.world_position - light_pos);
-        let atten = smoothstep(radius, 0.0, dist) * intensity;
+        var atten = smoothstep(radius, 0.0, dist) * intensity;
+        if (atten > 0.0) {
+            atten = atten * light_visibility(light_pos, light.pos_radius.w, in.world_position);
+        }
         light_color = light_color + color * atten;
     }
 
     // Clamp light contribution to [0, 1] per channel
     light_color = clamp(light_color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(tex_color.rgb * in.tint.rgb * light_color, tex_color.a * in.tint.a);
+    let lit = mix(tex_color.rgb * in.tint.rgb * light_color, in.flash.rgb, in.flash.a);
+    return vec4<f32>(lit, tex_color.a * in.tint.a);
+}
+
+// Alpha of the sprite at `uv`, transparent outside its own UV rect so
+// neighbouring atlas frames don't leak into the outline.
+fn silhouette_alpha(uv: vec2<f32>, rect: vec4<f32>) -> f32 {
+    if (any(uv < rect.xy) || any(uv > rect.zw)) {
+        return 0.0;
+    }
+    return textureSampleLevel(t_diffuse, s_diffuse, uv, 0.0).a;
+}
+
+// Dilated silhouette minus the sprite itself: the highest alpha found on
+// three rings of 12 samples within `radius`, wherever the sprite is clear.
+fn outline_alpha(uv: vec2<f32>, rect: vec4<f32>, radius: vec2<f32>) -> f32 {
+    var dilated = 0.0;
+    for (var ring = 1u; ring <= 3u; ring = ring + 1u) {
+        let r = radius * (f32(ring) / 3.0);
+        for (var i = 0u; i < 12u; i = i + 1u) {
+            let angle = f32(i) * 0.5235988 + f32(ring) * 0.2617994;
+            let offset = vec2<f32>(cos(angle), sin(angle)) * r;
+            dilated = max(dilated, silhouette_alpha(uv + offset, rect));
+        }
+    }
+    let solid = smoothstep(0.25, 0.75, dilated);
+    return solid * (1.0 - silhouette_alpha(uv, rect));
+}
+
+// Rays per fragment for soft shadows, spread across the light's width
+const SHADOW_SAMPLES: u32 = 5u;
+
+// Whether the segment a -> b crosses an occluder. Occluders containing `a`
+// are skipped, so walls are lit on their own surface rather than self-shadowed.
+fn segment_blocked(a: vec2<f32>, b: vec2<f32>) -> bool {
+    let d = b - a;
+    let inv = 1.0 / select(d, vec2<f32>(1e-6), abs(d) < vec2<f32>(1e-6));
+    for (var i = 0u; i < lighting.occluder_count; i = i + 1u) {
+        let rect = lighting.occluders[i];
+        if (all(a >= rect.xy) && all(a <= rect.zw)) {
+            continue;
+        }
+        let t0 = (rect.xy - a) * inv;
+        let t1 = (rect.zw - a) * inv;
+        let t_near = max(max(min(t0.x, t1.x), min(t0.y, t1.y)), 0.0);
+        let t_far = min(min(max(t0.x, t1.x), max(t0.y, t1.y)), 1.0);
+        if (t_near <= t_far) {
+            return true;
+        }
+    }
+    return false;
+}
+
+// Fraction of a point light reaching `p` past the occluders (1 = unshadowed).
+// `softness` is the light's width: rays go to points across it, perpendicular
+// to the light direction, so shadow edges fade over a penumbra.
+fn light_visibility(light_pos: vec2<f32>, softness: f32, p: vec2<f32>) -> f32 {
+    if (softness <= 0.0 || lighting.occluder_count == 0u) {
+        return 1.0;
+    }
+    let to_light = light_pos - p;
+    let dist = length(to_light);
+    if (dist < 1e-4) {
+        return 1.0;
+    }
+    let side = vec2<f32>(-to_light.y, to_light.x) / dist;
+    var lit = 0.0;
+    for (var s = 0u; s < SHADOW_SAMPLES; s = s + 1u) {
+        let offset = (f32(s) / f32(SHADOW_SAMPLES - 1u) - 0.5) * softness;
+        if (!segment_blocked(p, light_pos + side * offset)) {
+            lit = lit + 1.0;
+        }
+    }
+    return lit / f32(SHADOW_SAMPLES);
+}
+
+// Normal-mapped sprites (fs_normal): the tangent-space normal map is sampled
+// with the sprite's own UVs, so it must match the albedo texture's layout.
+@group(3) @binding(0)
+var t_normal: texture_2d<f32>;
+
+@group(3) @binding(1)
+var s_normal: sampler;
+
+// Height of a point light above the sprite plane, as a fraction of its radius
+const LIGHT_HEIGHT: f32 = 0.25;
+
+// World-space normal from a green-up (OpenGL-style) normal map texel, turned
+// with the sprite's flips and rotation. The world's y axis points down.
+fn sprite_normal(encoded: vec3<f32>, basis: vec4<f32>) -> vec3<f32> {
+    let n = (encoded * 2.0 - 1.0) * vec3<f32>(basis.z, -basis.w, 1.0);
+    return normalize(vec3<f32>(
+        n.x * basis.x - n.y * basis.y,
+        n.x * basis.y + n.y * basis.x,
+        n.z,
+    ));
+}
+
+@fragment
+fn fs_normal(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let encoded = textureSample(t_normal, s_normal, in.tex_coords).rgb;
+
+    if (in.outline_rect.z > in.outline_rect.x) {
+        let alpha = outline_alpha(in.tex_coords, in.outline_rect, in.outline_radius);
+        return vec4<f32>(in.tint.rgb, in.tint.a * alpha);
+    }
+
+    let normal = sprite_normal(encoded, in.normal_basis);
+    var light_color = lighting.ambient;
+
+    // Point lights: same falloff as flat sprites, scaled by the facing
+    for (var i = 0u; i < lighting.light_count; i = i + 1u) {
+        let light = lighting.lights[i];
+        let radius = light.pos_radius.z;
+        let to_light = vec3<f32>(light.pos_radius.xy - in.world_position, radius * LIGHT_HEIGHT);
+        var atten = smoothstep(radius, 0.0, length(to_light.xy)) * light.color_intensity.a;
+        if (atten > 0.0) {
+            atten = atten * light_visibility(light.pos_radius.xy, light.pos_radius.w, in.world_position);
+        }
+        let facing = max(dot(normal, normalize(to_light)), 0.0);
+        light_color = light_color + light.color_intensity.rgb * atten * facing;
+    }
+
+    for (var i = 0u; i < lighting.directional_count; i = i + 1u) {
+        let light = lighting.directional[i];
+        let facing = max(dot(normal, light.pos_radius.xyz), 0.0);
+        light_color = light_color + light.color_intensity.rgb * light.color_intensity.a * facing;
+    }
+
+    light_color = clamp(light_color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    let lit = mix(tex_color.rgb * in.tint.rgb * light_color, in.flash.rgb, in.flash.a);
+    return vec4<f32>(lit, tex_color.a * in.tint.a);
 }
@@ -2,8 +2,10 @@ use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
 use super::camera::Camera2D;
-use super::gpu::GpuContext;
-use super::lighting::LightingUniform;
+use super::flash::SpriteFlash;
+use super::gpu::{multisample_state, ColorTarget, GpuContext};
+use super::lighting::{LightCapacity, LightingState, LightingUniform};
+use super::mask::{mask_test_state, SpriteMask};
 use super::texture::TextureStore;
 
 /// Blend mode constants. Matches TS enum order.
@@ -37,6 +39,143 @@ pub struct SpriteCommand {
     pub opacity: f32,
     pub blend_mode: u8,
     pub shader_id: u32,
+    /// Draw as a nine-slice panel with these border insets.
+    pub nine_slice: Option<NineSlice>,
+    /// Draw an outline around the sprite's alpha silhouette first.
+    pub outline: Option<SpriteOutline>,
+    /// Only draw the pixels inside this screen rectangle.
+    pub clip: Option<ClipRect>,
+    /// Only draw where this stencil mask allows (see `mask`). Ignored for
+    /// custom shaders and outside the scene.
+    pub mask: Option<SpriteMask>,
+    /// Texture ID of a tangent-space normal map sharing the sprite's UVs, for
+    /// directional shading. None uses the normal map linked to `texture_id`
+    /// (see [`TextureStore::set_normal_map`]), if any. Ignored for custom shaders.
+    pub normal_map: Option<u32>,
+    /// Hit flash: mix towards a solid color after lighting (see
+    /// [`FlashStore`](super::FlashStore)). Ignored for custom shaders.
+    pub flash: Option<SpriteFlash>,
+    /// Round the position to the camera's pixel grid (see
+    /// [`Camera2D::snap_point`](super::Camera2D::snap_point)). None follows
+    /// the frame's pixel snap setting.
+    pub pixel_snap: Option<bool>,
+}
+
+/// Screen-space clip rectangle in viewport units (the camera's
+/// `viewport_size`), top-left origin, for scroll views and masked panels.
+/// Applied as a GPU scissor rect, so it ignores the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl ClipRect {
+    /// The overlap of two rects (zero-sized when they don't overlap).
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.w).min(other.x + other.w);
+        let bottom = (self.y + self.h).min(other.y + other.h);
+        ClipRect { x, y, w: (right - x).max(0.0), h: (bottom - y).max(0.0) }
+    }
+
+    /// Scissor rect `(x, y, w, h)` in target pixels for a target of `pixels`
+    /// showing `viewport` units, clamped to the target. None when nothing
+    /// of the rect is on the target.
+    pub fn scissor(&self, viewport: [f32; 2], pixels: (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+        let sx = pixels.0 as f32 / viewport[0].max(1e-6);
+        let sy = pixels.1 as f32 / viewport[1].max(1e-6);
+        let x0 = (self.x * sx).round().clamp(0.0, pixels.0 as f32) as u32;
+        let y0 = (self.y * sy).round().clamp(0.0, pixels.1 as f32) as u32;
+        let x1 = ((self.x + self.w) * sx).round().clamp(0.0, pixels.0 as f32) as u32;
+        let y1 = ((self.y + self.h) * sy).round().clamp(0.0, pixels.1 as f32) as u32;
+        (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+    }
+}
+
+/// Outline traced around the opaque pixels of a sprite (alpha ≥ 0.5), not its
+/// quad. Drawn just behind the sprite with the same blend mode and opacity.
+/// Only the built-in sprite shader draws outlines, and nine-slice panels
+/// ignore them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteOutline {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+    /// Outline width in world units.
+    pub thickness: f32,
+}
+
+/// Nine-slice border insets in texture pixels, measured inward from the edges
+/// of the command's UV rect. Corners keep their size, edges stretch along one
+/// axis and the center along both. When the panel is smaller than two
+/// opposite borders, both shrink proportionally.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NineSlice {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    /// World units per texture pixel for the borders. 1 = native size.
+    pub scale: f32,
+}
+
+/// Edges of the three slices along one axis: `[start, lo, hi, end]`, with the
+/// borders scaled down if they don't fit in `len`.
+fn slice_edges(start: f32, len: f32, lo: f32, hi: f32) -> [f32; 4] {
+    let (lo, hi) = (lo.max(0.0), hi.max(0.0));
+    let scale = if lo + hi > len.abs() && lo + hi > 0.0 { len.abs() / (lo + hi) } else { 1.0 };
+    let dir = len.signum();
+    [start, start + lo * scale * dir, start + len - hi * scale * dir, start + len]
+}
+
+/// Split a nine-slice command into plain sprite commands, skipping empty
+/// slices. Flips mirror the panel as a whole and rotation pivots around the
+/// panel's origin, as for a single sprite.
+fn nine_slice_parts(cmd: &SpriteCommand, insets: NineSlice, texture_size: (u32, u32)) -> Vec<SpriteCommand> {
+    let (tex_w, tex_h) = (texture_size.0.max(1) as f32, texture_size.1.max(1) as f32);
+    let uv_x = [cmd.uv_x, cmd.uv_x + insets.left / tex_w, cmd.uv_x + cmd.uv_w - insets.right / tex_w, cmd.uv_x + cmd.uv_w];
+    let uv_y = [cmd.uv_y, cmd.uv_y + insets.top / tex_h, cmd.uv_y + cmd.uv_h - insets.bottom / tex_h, cmd.uv_y + cmd.uv_h];
+    // A flipped panel draws its right border on the left
+    let (left, right) = if cmd.flip_x { (insets.right, insets.left) } else { (insets.left, insets.right) };
+    let (top, bottom) = if cmd.flip_y { (insets.bottom, insets.top) } else { (insets.top, insets.bottom) };
+    let xs = slice_edges(cmd.x, cmd.w, left * insets.scale, right * insets.scale);
+    let ys = slice_edges(cmd.y, cmd.h, top * insets.scale, bottom * insets.scale);
+    let pivot_x = cmd.x + cmd.origin_x * cmd.w;
+    let pivot_y = cmd.y + cmd.origin_y * cmd.h;
+
+    let mut parts = Vec::with_capacity(9);
+    for row in 0..3 {
+        let v = if cmd.flip_y { 2 - row } else { row };
+        for col in 0..3 {
+            let u = if cmd.flip_x { 2 - col } else { col };
+            let (w, h) = (xs[col + 1] - xs[col], ys[row + 1] - ys[row]);
+            let (uv_w, uv_h) = (uv_x[u + 1] - uv_x[u], uv_y[v + 1] - uv_y[v]);
+            if w == 0.0 || h == 0.0 || uv_w <= 0.0 || uv_h <= 0.0 {
+                continue;
+            }
+            parts.push(SpriteCommand {
+                x: xs[col],
+                y: ys[row],
+                w,
+                h,
+                uv_x: uv_x[u],
+                uv_y: uv_y[v],
+                uv_w,
+                uv_h,
+                origin_x: (pivot_x - xs[col]) / w,
+                origin_y: (pivot_y - ys[row]) / h,
+                nine_slice: None,
+                outline: None,
+                ..cmd.clone()
+            });
+        }
+    }
+    parts
 }
 
 /// Per-vertex data for the unit quad.
@@ -58,6 +197,206 @@ struct SpriteInstance {
     tint: [f32; 4],
     /// [rotation_radians, origin_x (0-1), origin_y (0-1), padding]
     rotation_origin: [f32; 4],
+    /// Outline instances: `[min_u, min_v, max_u, max_v]` of the sprite's UV
+    /// rect, whose silhouette the shader dilates. Zero for plain sprites.
+    outline_rect: [f32; 4],
+    /// Hit flash `[r, g, b, amount]`. Zero for no flash.
+    flash: [f32; 4],
+}
+
+impl SpriteInstance {
+    fn from_command(cmd: &SpriteCommand) -> Self {
+        // Apply flip by negating UV and shifting offset
+        let mut uv_x = cmd.uv_x;
+        let mut uv_y = cmd.uv_y;
+        let mut uv_w = cmd.uv_w;
+        let mut uv_h = cmd.uv_h;
+        if cmd.flip_x {
+            uv_x += uv_w;
+            uv_w = -uv_w;
+        }
+        if cmd.flip_y {
+            uv_y += uv_h;
+            uv_h = -uv_h;
+        }
+        SpriteInstance {
+            world_pos: [cmd.x, cmd.y],
+            size: [cmd.w, cmd.h],
+            uv_offset: [uv_x, uv_y],
+            uv_size: [uv_w, uv_h],
+            tint: [cmd.tint_r, cmd.tint_g, cmd.tint_b, cmd.tint_a * cmd.opacity],
+            rotation_origin: [cmd.rotation, cmd.origin_x, cmd.origin_y, 0.0],
+            outline_rect: [0.0; 4],
+            flash: cmd.flash.map_or([0.0; 4], |f| [f.r, f.g, f.b, f.amount]),
+        }
+    }
+
+    /// The outline drawn behind `cmd`: its quad and UV rect grown by the
+    /// thickness on every side (same pivot, same texels per world unit),
+    /// tinted with the outline color.
+    fn outline(cmd: &SpriteCommand, outline: SpriteOutline) -> Self {
+        let t = outline.thickness;
+        let (w, h) = (cmd.w + 2.0 * t, cmd.h + 2.0 * t);
+        let du = t * cmd.uv_w / cmd.w.abs().max(f32::EPSILON);
+        let dv = t * cmd.uv_h / cmd.h.abs().max(f32::EPSILON);
+        let mut instance = Self::from_command(&SpriteCommand {
+            x: cmd.x - t,
+            y: cmd.y - t,
+            w,
+            h,
+            uv_x: cmd.uv_x - du,
+            uv_y: cmd.uv_y - dv,
+            uv_w: cmd.uv_w + 2.0 * du,
+            uv_h: cmd.uv_h + 2.0 * dv,
+            origin_x: (cmd.origin_x * cmd.w + t) / w,
+            origin_y: (cmd.origin_y * cmd.h + t) / h,
+            tint_r: outline.r,
+            tint_g: outline.g,
+            tint_b: outline.b,
+            tint_a: outline.a,
+            ..cmd.clone()
+        });
+        instance.outline_rect = [cmd.uv_x, cmd.uv_y, cmd.uv_x + cmd.uv_w, cmd.uv_y + cmd.uv_h];
+        instance
+    }
+}
+
+const INSTANCE_SIZE: u64 = std::mem::size_of::<SpriteInstance>() as u64;
+
+/// Smallest instance buffer allocation, in sprites.
+const MIN_INSTANCE_CAPACITY: usize = 1024;
+
+/// Unchanged instances between two changed ones are uploaded anyway when the
+/// gap is at most this long, trading a few redundant bytes for fewer writes.
+const DIRTY_MERGE_GAP: usize = 16;
+
+/// Sprite drawing counters. See [`SpritePipeline::frame_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpriteStats {
+    /// Instanced draw calls issued.
+    pub draw_calls: u32,
+    /// Sprites drawn.
+    pub instances: u32,
+    /// Bytes written to the instance buffer.
+    pub upload_bytes: u64,
+    /// Instance buffer (re)allocations since startup.
+    pub buffer_allocations: u32,
+    /// Current instance buffer capacity, in sprites.
+    pub capacity: u32,
+}
+
+impl SpriteStats {
+    /// Stats as a JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"drawCalls\":{},\"instances\":{},\"uploadBytes\":{},\"bufferAllocations\":{},\"capacity\":{}}}",
+            self.draw_calls,
+            self.instances,
+            self.upload_bytes,
+            self.buffer_allocations,
+            self.capacity
+        )
+    }
+}
+
+/// Index ranges where `new` differs from `old`. Indices past the end of `old`
+/// are always dirty. Ranges closer than [`DIRTY_MERGE_GAP`] are merged.
+fn dirty_ranges(old: &[SpriteInstance], new: &[SpriteInstance]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (i, instance) in new.iter().enumerate() {
+        let unchanged = old
+            .get(i)
+            .is_some_and(|o| bytemuck::bytes_of(o) == bytemuck::bytes_of(instance));
+        if unchanged {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if i - last.end <= DIRTY_MERGE_GAP => last.end = i + 1,
+            _ => ranges.push(i..i + 1),
+        }
+    }
+    ranges
+}
+
+/// Instance buffer kept across frames.
+///
+/// Each `render()` call appends its instances after the previous call's, since
+/// `queue.write_buffer` only lands at the next submit and every pass recorded
+/// before it must still see its own data. The cursor returns to the front after
+/// a submit. A CPU copy of the buffer contents lets unchanged ranges (static
+/// sprites drawn in the same order as last frame) skip the upload entirely.
+struct InstanceBuffer {
+    buffer: wgpu::Buffer,
+    capacity: usize,
+    /// Mirrors the GPU buffer contents from index 0.
+    shadow: Vec<SpriteInstance>,
+    /// Next free instance slot for this submit.
+    cursor: usize,
+}
+
+impl InstanceBuffer {
+    fn new(device: &wgpu::Device, capacity: usize) -> Self {
+        Self {
+            buffer: Self::allocate(device, capacity),
+            capacity,
+            shadow: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    fn allocate(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sprite_instance_buffer"),
+            size: capacity as u64 * INSTANCE_SIZE,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Upload `instances` after the ones already written this submit and
+    /// return their byte range in the buffer.
+    fn write(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        instances: &[SpriteInstance],
+        stats: &mut SpriteStats,
+    ) -> std::ops::Range<u64> {
+        let start = self.cursor;
+        let end = start + instances.len();
+
+        if end > self.capacity {
+            // Passes already recorded keep the old buffer alive. Carry over this
+            // submit's instances so the CPU copy still mirrors the new buffer.
+            self.capacity = end.next_power_of_two().max(self.capacity * 2);
+            self.buffer = Self::allocate(device, self.capacity);
+            self.shadow.truncate(start);
+            if start > 0 {
+                queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.shadow));
+                stats.upload_bytes += start as u64 * INSTANCE_SIZE;
+            }
+            stats.buffer_allocations += 1;
+        }
+        stats.capacity = self.capacity as u32;
+
+        let known = self.shadow.len().min(end).max(start);
+        for range in dirty_ranges(&self.shadow[start..known], instances) {
+            let data = &instances[range.clone()];
+            queue.write_buffer(
+                &self.buffer,
+                (start + range.start) as u64 * INSTANCE_SIZE,
+                bytemuck::cast_slice(data),
+            );
+            stats.upload_bytes += data.len() as u64 * INSTANCE_SIZE;
+        }
+        if self.shadow.len() < end {
+            self.shadow.resize(end, SpriteInstance::zeroed());
+        }
+        self.shadow[start..end].copy_from_slice(instances);
+
+        self.cursor = end;
+        start as u64 * INSTANCE_SIZE..end as u64 * INSTANCE_SIZE
+    }
 }
 
 /// Camera uniform buffer data.
@@ -122,9 +461,190 @@ fn blend_state_for(mode: u8) -> wgpu::BlendState {
     }
 }
 
+/// Which sprite pipelines a batch needs, besides its blend mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PipelineVariant {
+    samples: u32,
+    /// Stencil mask test, and whether it's inverted.
+    mask: Option<bool>,
+    /// Shade with a normal map (`fs_normal`, bound at group 3).
+    normal_mapped: bool,
+}
+
+impl PipelineVariant {
+    fn plain(samples: u32) -> Self {
+        Self { samples, mask: None, normal_mapped: false }
+    }
+}
+
+/// Build the sprite pipelines, one per blend mode, for `variant`.
+fn build_pipelines(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
+    variant: PipelineVariant,
+) -> [wgpu::RenderPipeline; 4] {
+    let samples = variant.samples;
+    // Vertex buffer layouts
+    let vertex_layout = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &[
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+            wgpu::VertexAttribute {
+                offset: 8,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float32x2,
+            },
+        ],
+    };
+
+    let instance_layout = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Instance,
+        attributes: &[
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 2,
+                format: wgpu::VertexFormat::Float32x2, // world_pos
+            },
+            wgpu::VertexAttribute {
+                offset: 8,
+                shader_location: 3,
+                format: wgpu::VertexFormat::Float32x2, // size
+            },
+            wgpu::VertexAttribute {
+                offset: 16,
+                shader_location: 4,
+                format: wgpu::VertexFormat::Float32x2, // uv_offset
+            },
+            wgpu::VertexAttribute {
+                offset: 24,
+                shader_location: 5,
+                format: wgpu::VertexFormat::Float32x2, // uv_size
+            },
+            wgpu::VertexAttribute {
+                offset: 32,
+                shader_location: 6,
+                format: wgpu::VertexFormat::Float32x4, // tint
+            },
+            wgpu::VertexAttribute {
+                offset: 48,
+                shader_location: 7,
+                format: wgpu::VertexFormat::Float32x4, // rotation_origin
+            },
+            wgpu::VertexAttribute {
+                offset: 64,
+                shader_location: 8,
+                format: wgpu::VertexFormat::Float32x4, // outline_rect
+            },
+            wgpu::VertexAttribute {
+                offset: 80,
+                shader_location: 9,
+                format: wgpu::VertexFormat::Float32x4, // flash
+            },
+        ],
+    };
+
+    // Create one pipeline per blend mode
+    let blend_names = ["alpha", "additive", "multiply", "screen"];
+    let stencil = variant.mask.map(mask_test_state);
+    let fragment_entry = if variant.normal_mapped { "fs_normal" } else { "fs_main" };
+    let suffix = format!(
+        "{}{}",
+        if variant.mask.is_some() { "_masked" } else { "" },
+        if variant.normal_mapped { "_normal" } else { "" },
+    );
+    let pipelines: Vec<wgpu::RenderPipeline> = (0..4u8)
+        .map(|mode| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("sprite_pipeline_{}_x{samples}{suffix}", blend_names[mode as usize])),
+                layout: Some(layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[vertex_layout.clone(), instance_layout.clone()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: Some(fragment_entry),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: surface_format,
+                        blend: Some(blend_state_for(mode)),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: stencil.clone(),
+                multisample: multisample_state(samples),
+                multiview: None,
+                cache: None,
+            })
+        })
+        .collect();
+
+    pipelines.try_into().unwrap()
+}
+
+/// Begin a sprite pass on `target`, attaching its mask stencil buffer
+/// (read-only) when `masked`, and limited to its viewport rect.
+fn begin_pass<'e>(
+    encoder: &'e mut wgpu::CommandEncoder,
+    target: ColorTarget<'_>,
+    load: wgpu::LoadOp<wgpu::Color>,
+    masked: bool,
+) -> wgpu::RenderPass<'e> {
+    let stencil = target.stencil.filter(|_| masked);
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("sprite_render_pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target.view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: stencil.map(|view| wgpu::RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: None,
+            stencil_ops: None,
+        }),
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    target.apply_viewport(&mut pass);
+    pass
+}
+
 pub struct SpritePipeline {
     /// One pipeline per blend mode: [alpha, additive, multiply, screen]
     pipelines: [wgpu::RenderPipeline; 4],
+    /// The same for the MSAA sample count in use, if any.
+    msaa_pipelines: Option<(u32, [wgpu::RenderPipeline; 4])>,
+    /// Pipelines with a mask stencil test or normal mapping, built the first
+    /// time a sprite needs them.
+    variant_pipelines: Vec<(PipelineVariant, [wgpu::RenderPipeline; 4])>,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    /// `pipeline_layout` plus the normal map texture at group 3.
+    normal_pipeline_layout: wgpu::PipelineLayout,
+    surface_format: wgpu::TextureFormat,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     camera_buffer: wgpu::Buffer,
@@ -132,6 +652,14 @@ pub struct SpritePipeline {
     pub texture_bind_group_layout: wgpu::BindGroupLayout,
     lighting_buffer: wgpu::Buffer,
     lighting_bind_group: wgpu::BindGroup,
+    instances: InstanceBuffer,
+    /// Counters accumulated since the last `end_frame()`.
+    stats: SpriteStats,
+    /// Counters for the last finished frame.
+    frame_stats: SpriteStats,
+    /// Viewport units and pixel size of the target `render()` draws into,
+    /// for clip rects. Clip rects are ignored until it's set.
+    clip_space: Option<([f32; 2], (u32, u32))>,
 }
 
 impl SpritePipeline {
@@ -142,22 +670,23 @@ impl SpritePipeline {
         queue: &wgpu::Queue,
         format: wgpu::TextureFormat,
     ) -> Self {
-        Self::new_internal(device, queue, format)
+        Self::new_internal(device, queue, format, LightCapacity::default())
     }
 
-    pub fn new(gpu: &GpuContext) -> Self {
-        Self::new_internal(&gpu.device, &gpu.queue, gpu.config.format)
+    pub fn new(gpu: &GpuContext, lights: LightCapacity) -> Self {
+        Self::new_internal(&gpu.device, &gpu.queue, gpu.config.format, lights)
     }
 
     fn new_internal(
         device: &wgpu::Device,
         _queue: &wgpu::Queue,
         surface_format: wgpu::TextureFormat,
+        lights: LightCapacity,
     ) -> Self {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("sprite_shader"),
             source: wgpu::ShaderSource::Wgsl(
-                include_str!("shaders/sprite.wgsl").into(),
+                lights.patch_wgsl(include_str!("shaders/sprite.wgsl")).into(),
             ),
         });
 
@@ -203,7 +732,7 @@ impl SpritePipeline {
                     ],
                 });
 
-        // Lighting uniform bind group layout (group 2)
+        // Lighting bind group layout (group 2): uniform, or storage for large capacities
         let lighting_bind_group_layout =
             device
                 .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -212,7 +741,7 @@ impl SpritePipeline {
                         binding: 0,
                         visibility: wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
+                            ty: lights.binding_type(),
                             has_dynamic_offset: false,
                             min_binding_size: None,
                         },
@@ -232,102 +761,21 @@ impl SpritePipeline {
                     push_constant_ranges: &[],
                 });
 
-        // Vertex buffer layouts
-        let vertex_layout = wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x2,
-                },
-                wgpu::VertexAttribute {
-                    offset: 8,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x2,
-                },
-            ],
-        };
-
-        let instance_layout = wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Instance,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Float32x2, // world_pos
-                },
-                wgpu::VertexAttribute {
-                    offset: 8,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32x2, // size
-                },
-                wgpu::VertexAttribute {
-                    offset: 16,
-                    shader_location: 4,
-                    format: wgpu::VertexFormat::Float32x2, // uv_offset
-                },
-                wgpu::VertexAttribute {
-                    offset: 24,
-                    shader_location: 5,
-                    format: wgpu::VertexFormat::Float32x2, // uv_size
-                },
-                wgpu::VertexAttribute {
-                    offset: 32,
-                    shader_location: 6,
-                    format: wgpu::VertexFormat::Float32x4, // tint
-                },
-                wgpu::VertexAttribute {
-                    offset: 48,
-                    shader_location: 7,
-                    format: wgpu::VertexFormat::Float32x4, // rotation_origin
-                },
-            ],
-        };
-
-        // Create one pipeline per blend mode
-        let blend_names = ["alpha", "additive", "multiply", "screen"];
-        let pipelines: Vec<wgpu::RenderPipeline> = (0..4u8)
-            .map(|mode| {
-                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                    label: Some(&format!("sprite_pipeline_{}", blend_names[mode as usize])),
-                    layout: Some(&pipeline_layout),
-                    vertex: wgpu::VertexState {
-                        module: &shader,
-                        entry_point: Some("vs_main"),
-                        buffers: &[vertex_layout.clone(), instance_layout.clone()],
-                        compilation_options: Default::default(),
-                    },
-                    fragment: Some(wgpu::FragmentState {
-                        module: &shader,
-                        entry_point: Some("fs_main"),
-                        targets: &[Some(wgpu::ColorTargetState {
-                            format: surface_format,
-                            blend: Some(blend_state_for(mode)),
-                            write_mask: wgpu::ColorWrites::ALL,
-                        })],
-                        compilation_options: Default::default(),
-                    }),
-                    primitive: wgpu::PrimitiveState {
-                        topology: wgpu::PrimitiveTopology::TriangleList,
-                        strip_index_format: None,
-                        front_face: wgpu::FrontFace::Ccw,
-                        cull_mode: None,
-                        polygon_mode: wgpu::PolygonMode::Fill,
-                        unclipped_depth: false,
-                        conservative: false,
-                    },
-                    depth_stencil: None,
-                    multisample: wgpu::MultisampleState::default(),
-                    multiview: None,
-                    cache: None,
-                })
-            })
-            .collect();
+        let normal_pipeline_layout =
+            device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("sprite_normal_pipeline_layout"),
+                    bind_group_layouts: &[
+                        &camera_bind_group_layout,
+                        &texture_bind_group_layout,
+                        &lighting_bind_group_layout,
+                        &texture_bind_group_layout,
+                    ],
+                    push_constant_ranges: &[],
+                });
 
-        let pipelines: [wgpu::RenderPipeline; 4] = pipelines.try_into().unwrap();
+        let pipelines =
+            build_pipelines(device, &shader, &pipeline_layout, surface_format, PipelineVariant::plain(1));
 
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("quad_vertex_buffer"),
@@ -360,20 +808,13 @@ impl SpritePipeline {
             }],
         });
 
-        // Lighting uniform buffer (272 bytes = LightingUniform size)
-        let default_lighting = LightingUniform {
-            ambient: [1.0, 1.0, 1.0],
-            light_count: 0,
-            lights: [super::lighting::LightData {
-                pos_radius: [0.0; 4],
-                color_intensity: [0.0; 4],
-            }; super::lighting::MAX_LIGHTS],
-        };
+        // Lighting buffer (416 bytes for the default 8-slot uniform)
+        let default_lighting = LightingState::default().to_uniform_for(lights, None);
 
         let lighting_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("lighting_uniform_buffer"),
-            contents: bytemuck::cast_slice(&[default_lighting]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            label: Some("lighting_buffer"),
+            contents: &default_lighting.to_bytes(),
+            usage: lights.buffer_usage(),
         });
 
         let lighting_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -387,6 +828,12 @@ impl SpritePipeline {
 
         Self {
             pipelines,
+            msaa_pipelines: None,
+            variant_pipelines: Vec::new(),
+            shader,
+            pipeline_layout,
+            normal_pipeline_layout,
+            surface_format,
             vertex_buffer,
             index_buffer,
             camera_buffer,
@@ -394,9 +841,43 @@ impl SpritePipeline {
             texture_bind_group_layout,
             lighting_buffer,
             lighting_bind_group,
+            instances: InstanceBuffer::new(device, MIN_INSTANCE_CAPACITY),
+            stats: SpriteStats::default(),
+            frame_stats: SpriteStats::default(),
+            clip_space: None,
         }
     }
 
+    /// Build pipelines for multisampled targets with `samples` per pixel;
+    /// 1 drops them. Single-sampled pipelines are always kept.
+    pub fn set_msaa_samples(&mut self, device: &wgpu::Device, samples: u32) {
+        if samples <= 1 {
+            self.msaa_pipelines = None;
+        } else if self.msaa_pipelines.as_ref().map(|(n, _)| *n) != Some(samples) {
+            let variant = PipelineVariant::plain(samples);
+            let pipelines = build_pipelines(device, &self.shader, &self.pipeline_layout, self.surface_format, variant);
+            self.msaa_pipelines = Some((samples, pipelines));
+        }
+        self.variant_pipelines.retain(|(v, _)| v.samples == 1 || v.samples == samples);
+    }
+
+    /// Build the pipelines for a masked or normal-mapped `variant` if they're missing.
+    fn ensure_variant_pipelines(&mut self, device: &wgpu::Device, variant: PipelineVariant) {
+        if !self.variant_pipelines.iter().any(|(v, _)| *v == variant) {
+            let layout = if variant.normal_mapped { &self.normal_pipeline_layout } else { &self.pipeline_layout };
+            let pipelines = build_pipelines(device, &self.shader, layout, self.surface_format, variant);
+            self.variant_pipelines.push((variant, pipelines));
+        }
+    }
+
+    /// Bind the state every sprite pass shares.
+    fn bind_pass(&self, pass: &mut wgpu::RenderPass<'_>) {
+        pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        pass.set_bind_group(2, &self.lighting_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+    }
+
     /// Return the camera uniform bind group (group 0).
     /// Used by other pipelines (e.g. GeometryBatch) that share the same view-proj matrix.
     pub fn camera_bind_group(&self) -> &wgpu::BindGroup {
@@ -421,81 +902,198 @@ impl SpritePipeline {
             0,
             bytemuck::cast_slice(&[camera_uniform]),
         );
-        queue.write_buffer(
-            &self.lighting_buffer,
-            0,
-            bytemuck::cast_slice(&[*lighting]),
-        );
+        queue.write_buffer(&self.lighting_buffer, 0, &lighting.to_bytes());
+    }
+
+    /// Set the target the next `render()` calls draw into: `viewport` is its
+    /// size in the units clip rects use (the camera's viewport) and `pixels`
+    /// its texture size.
+    pub fn set_target_size(&mut self, viewport: [f32; 2], pixels: (u32, u32)) {
+        self.clip_space = Some((viewport, pixels));
     }
 
     /// Render a sorted list of sprite commands.
-    /// Commands should be sorted by layer → shader_id → blend_mode → texture_id.
+    /// Commands should be sorted by layer → mask → shader_id → blend_mode → texture_id → normal_map.
+    /// Masked sprites are drawn with a stencil test when the target has a
+    /// mask stencil buffer, and unmasked otherwise. Normal-mapped sprites
+    /// draw flat until their normal map is loaded.
+    ///
+    /// Instances are appended to a buffer shared by every `render()` call until
+    /// the encoder is submitted; call `end_submit()` (or `end_frame()`) after
+    /// `queue.submit` so the next frame reuses it from the start.
     ///
     /// `clear_color`: `Some(color)` → `LoadOp::Clear(color)` (first pass),
     ///                 `None` → `LoadOp::Load` (subsequent passes).
-    pub fn render(
-        &self,
+    pub fn render<'t>(
+        &mut self,
         device: &wgpu::Device,
-        _queue: &wgpu::Queue,
+        queue: &wgpu::Queue,
         textures: &TextureStore,
         shaders: &super::shader::ShaderStore,
         commands: &[SpriteCommand],
-        target: &wgpu::TextureView,
+        target: impl Into<ColorTarget<'t>>,
         encoder: &mut wgpu::CommandEncoder,
         clear_color: Option<wgpu::Color>,
     ) {
+        let target = target.into();
         let load_op = match clear_color {
             Some(color) => wgpu::LoadOp::Clear(color),
             None => wgpu::LoadOp::Load,
         };
 
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("sprite_render_pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: target,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: load_op,
-                    store: wgpu::StoreOp::Store,
-                },
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
+        // Upload instance data for the whole call at once; batches draw sub-ranges.
+        // Nine-slice commands expand to several instances and outlined sprites
+        // to two, so batches find their instances through `starts`.
+        let mut instance_data = Vec::with_capacity(commands.len());
+        let mut starts = Vec::with_capacity(commands.len() + 1);
+        for cmd in commands {
+            starts.push(instance_data.len());
+            match (cmd.nine_slice, textures.get_dimensions(cmd.texture_id)) {
+                (Some(insets), Some(size)) => instance_data.extend(
+                    nine_slice_parts(cmd, insets, size).iter().map(SpriteInstance::from_command),
+                ),
+                (Some(_), None) => {} // texture not loaded; the batch is skipped
+                (None, _) => {
+                    let outline = cmd
+                        .outline
+                        .filter(|o| o.thickness > 0.0 && o.a > 0.0 && cmd.shader_id == 0);
+                    if let Some(outline) = outline {
+                        instance_data.push(SpriteInstance::outline(cmd, outline));
+                    }
+                    instance_data.push(SpriteInstance::from_command(cmd));
+                }
+            }
+        }
+        starts.push(instance_data.len());
+        let instance_bytes = self
+            .instances
+            .write(device, queue, &instance_data, &mut self.stats);
 
-        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-        render_pass.set_bind_group(2, &self.lighting_bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        // Masks need the target's stencil buffer and a built-in pipeline
+        let mask_of = |cmd: &SpriteCommand| cmd.mask.filter(|_| target.stencil.is_some() && cmd.shader_id == 0);
+        let normal_of = |cmd: &SpriteCommand| {
+            cmd.normal_map
+                .or_else(|| textures.normal_map(cmd.texture_id))
+                .filter(|&id| cmd.shader_id == 0 && textures.get_bind_group(id).is_some())
+        };
+        let variant_of = |cmd: &SpriteCommand| PipelineVariant {
+            samples: target.samples,
+            mask: mask_of(cmd).map(|m| m.invert),
+            normal_mapped: normal_of(cmd).is_some(),
+        };
+        let mut variants: Vec<PipelineVariant> = Vec::new();
+        for cmd in commands.iter().filter(|c| c.shader_id == 0) {
+            let variant = variant_of(cmd);
+            if variant != PipelineVariant::plain(target.samples) && !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+        for variant in variants {
+            self.ensure_variant_pipelines(device, variant);
+        }
+        let instance_buffer = &self.instances.buffer;
+
+        // Masked batches draw in passes that attach the stencil buffer
+        let mut pass_masked = commands.first().and_then(mask_of).is_some();
+        let mut render_pass = begin_pass(encoder, target, load_op, pass_masked);
+        self.bind_pass(&mut render_pass);
+
+        // Pipelines must match the target's sample count
+        let builtin = match (target.samples, &self.msaa_pipelines) {
+            (1, _) => Some(&self.pipelines),
+            (samples, Some((n, pipelines))) if *n == samples => Some(pipelines),
+            _ => None,
+        };
 
-        // Batch by shader_id + blend_mode + texture_id (commands pre-sorted)
+        // Batch by shader_id + blend_mode + texture_id (commands pre-sorted),
+        // splitting where the clip rect, mask or normal map changes
         let mut current_shader: Option<u32> = None;
         let mut current_blend: Option<u8> = None;
+        let mut current_clip: Option<ClipRect> = None;
+        let mut current_mask: Option<SpriteMask> = None;
+        let mut current_normal_mapped = false;
         let mut i = 0;
         while i < commands.len() {
             let shader = commands[i].shader_id;
             let blend = commands[i].blend_mode.min(3);
             let tex_id = commands[i].texture_id;
+            let clip = commands[i].clip;
+            let mask = mask_of(&commands[i]);
+            let normal = normal_of(&commands[i]);
             let batch_start = i;
             while i < commands.len()
                 && commands[i].shader_id == shader
                 && commands[i].blend_mode.min(3) == blend
                 && commands[i].texture_id == tex_id
+                && commands[i].clip == clip
+                && mask_of(&commands[i]) == mask
+                && normal_of(&commands[i]) == normal
             {
                 i += 1;
             }
-            let batch = &commands[batch_start..i];
+
+            // Switch passes between masked and unmasked batches; a new pass
+            // starts with no pipeline, full scissor and stencil reference 0
+            if mask.is_some() != pass_masked {
+                drop(render_pass);
+                pass_masked = mask.is_some();
+                render_pass = begin_pass(encoder, target, wgpu::LoadOp::Load, pass_masked);
+                self.bind_pass(&mut render_pass);
+                current_shader = None;
+                current_clip = None;
+                current_mask = None;
+            }
+            if mask != current_mask {
+                if let Some(mask) = mask {
+                    render_pass.set_stencil_reference(mask.id as u32);
+                }
+                current_mask = mask;
+                current_shader = None;
+            }
+
+            // Scissor to the clip rect; a batch clipped away entirely is skipped
+            if let Some((viewport, pixels)) = self.clip_space
+                && clip != current_clip
+            {
+                let rect = match clip {
+                    Some(clip) => clip.scissor(viewport, pixels),
+                    None => Some((0, 0, pixels.0, pixels.1)),
+                };
+                let Some(rect) = rect.and_then(|rect| target.scissor(rect)) else {
+                    continue;
+                };
+                render_pass.set_scissor_rect(rect.0, rect.1, rect.2, rect.3);
+                current_clip = clip;
+            }
+
+            if normal.is_some() != current_normal_mapped {
+                current_normal_mapped = normal.is_some();
+                current_shader = None;
+            }
 
             // Switch pipeline: built-in (shader_id 0) vs custom
             if shader == 0 {
+                let variant = PipelineVariant {
+                    samples: target.samples,
+                    mask: mask.map(|m| m.invert),
+                    normal_mapped: normal.is_some(),
+                };
+                let pipelines = if variant == PipelineVariant::plain(target.samples) {
+                    builtin
+                } else {
+                    self.variant_pipelines
+                        .iter()
+                        .find(|(v, _)| *v == variant)
+                        .map(|(_, pipelines)| pipelines)
+                };
+                let Some(pipelines) = pipelines else { continue };
                 if current_shader != Some(0) || current_blend != Some(blend) {
-                    render_pass.set_pipeline(&self.pipelines[blend as usize]);
+                    render_pass.set_pipeline(&pipelines[blend as usize]);
                     current_shader = Some(0);
                     current_blend = Some(blend);
                 }
             } else if current_shader != Some(shader) {
-                if let Some(pipeline) = shaders.get_pipeline(shader) {
+                if let Some(pipeline) = shaders.get_pipeline_for(shader, target.samples) {
                     render_pass.set_pipeline(pipeline);
                     if let Some(bg) = shaders.get_bind_group(shader) {
                         render_pass.set_bind_group(3, bg, &[]);
@@ -513,45 +1111,208 @@ impl SpritePipeline {
                 None => continue, // skip if texture not loaded
             };
 
-            // Build instance buffer for this batch
-            let instances: Vec<SpriteInstance> = batch
-                .iter()
-                .map(|cmd| {
-                    // Apply flip by negating UV and shifting offset
-                    let mut uv_x = cmd.uv_x;
-                    let mut uv_y = cmd.uv_y;
-                    let mut uv_w = cmd.uv_w;
-                    let mut uv_h = cmd.uv_h;
-                    if cmd.flip_x {
-                        uv_x += uv_w;
-                        uv_w = -uv_w;
-                    }
-                    if cmd.flip_y {
-                        uv_y += uv_h;
-                        uv_h = -uv_h;
-                    }
-                    SpriteInstance {
-                        world_pos: [cmd.x, cmd.y],
-                        size: [cmd.w, cmd.h],
-                        uv_offset: [uv_x, uv_y],
-                        uv_size: [uv_w, uv_h],
-                        tint: [cmd.tint_r, cmd.tint_g, cmd.tint_b, cmd.tint_a * cmd.opacity],
-                        rotation_origin: [cmd.rotation, cmd.origin_x, cmd.origin_y, 0.0],
-                    }
-                })
-                .collect();
+            let (first, end) = (starts[batch_start], starts[i]);
+            if first == end {
+                continue;
+            }
+            let batch_bytes = instance_bytes.start + first as u64 * INSTANCE_SIZE
+                ..instance_bytes.start + end as u64 * INSTANCE_SIZE;
+            let count = (end - first) as u32;
+            render_pass.set_bind_group(1, bind_group, &[]);
+            if let Some(normal_bg) = normal.and_then(|id| textures.get_bind_group(id)) {
+                render_pass.set_bind_group(3, normal_bg, &[]);
+            }
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(batch_bytes));
+            render_pass.draw_indexed(0..6, 0, 0..count);
+            self.stats.draw_calls += 1;
+            self.stats.instances += count;
+        }
+    }
 
-            let instance_buffer =
-                device
-                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: Some("sprite_instance_buffer"),
-                        contents: bytemuck::cast_slice(&instances),
-                        usage: wgpu::BufferUsages::VERTEX,
-                    });
+    /// Mark everything rendered so far as submitted: the next `render()` writes
+    /// from the start of the instance buffer again. Call after `queue.submit`.
+    pub fn end_submit(&mut self) {
+        self.instances.cursor = 0;
+    }
 
-            render_pass.set_bind_group(1, bind_group, &[]);
-            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
-            render_pass.draw_indexed(0..6, 0, 0..instances.len() as u32);
+    /// `end_submit()`, then close the frame's stats (see `frame_stats()`).
+    pub fn end_frame(&mut self) {
+        self.end_submit();
+        self.frame_stats = std::mem::take(&mut self.stats);
+        self.stats.buffer_allocations = self.frame_stats.buffer_allocations;
+        self.stats.capacity = self.frame_stats.capacity;
+    }
+
+    /// Draw calls, sprites and instance upload bytes for the last finished
+    /// frame, including render target passes submitted during it.
+    pub fn frame_stats(&self) -> SpriteStats {
+        self.frame_stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(x: f32) -> SpriteInstance {
+        SpriteInstance {
+            world_pos: [x, 0.0],
+            ..SpriteInstance::zeroed()
+        }
+    }
+
+    fn instances(xs: &[f32]) -> Vec<SpriteInstance> {
+        xs.iter().map(|&x| instance(x)).collect()
+    }
+
+    #[test]
+    fn test_dirty_ranges_skip_unchanged() {
+        let old = instances(&[1.0, 2.0, 3.0]);
+        assert!(dirty_ranges(&old, &old).is_empty());
+        assert_eq!(dirty_ranges(&[], &old), vec![0..3]);
+    }
+
+    #[test]
+    fn test_dirty_ranges_merge_small_gaps() {
+        let old: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let mut new = old.clone();
+        new[2] = -1.0;
+        new[10] = -1.0;
+        new[90] = -1.0;
+        assert_eq!(
+            dirty_ranges(&instances(&old), &instances(&new)),
+            vec![2..11, 90..91]
+        );
+    }
+
+    #[test]
+    fn test_dirty_ranges_include_growth() {
+        let old = instances(&[1.0, 2.0]);
+        let new = instances(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(dirty_ranges(&old, &new), vec![2..4]);
+    }
+
+    fn panel(w: f32, h: f32) -> SpriteCommand {
+        SpriteCommand {
+            texture_id: 1,
+            x: 10.0, y: 20.0, w, h,
+            layer: 0,
+            uv_x: 0.0, uv_y: 0.0, uv_w: 1.0, uv_h: 1.0,
+            tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
+            rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
+            flip_x: false, flip_y: false,
+            opacity: 1.0,
+            blend_mode: 0, shader_id: 0,
+            nine_slice: Some(NineSlice { left: 8.0, top: 4.0, right: 8.0, bottom: 4.0, scale: 1.0 }),
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
         }
     }
+
+    #[test]
+    fn test_nine_slice_keeps_corners_at_pixel_size() {
+        let cmd = panel(100.0, 50.0);
+        let parts = nine_slice_parts(&cmd, cmd.nine_slice.unwrap(), (32, 32));
+        assert_eq!(parts.len(), 9);
+        let (tl, center, br) = (&parts[0], &parts[4], &parts[8]);
+        assert_eq!((tl.x, tl.y, tl.w, tl.h), (10.0, 20.0, 8.0, 4.0));
+        assert_eq!((tl.uv_w, tl.uv_h), (0.25, 0.125));
+        assert_eq!((center.w, center.h), (84.0, 42.0));
+        assert_eq!((center.uv_x, center.uv_w), (0.25, 0.5));
+        assert_eq!((br.x + br.w, br.y + br.h), (110.0, 70.0));
+        // Every part rotates around the panel center
+        assert_eq!(br.x + br.origin_x * br.w, 60.0);
+        assert_eq!(tl.y + tl.origin_y * tl.h, 45.0);
+        assert!(parts.iter().all(|p| p.nine_slice.is_none()));
+    }
+
+    #[test]
+    fn test_nine_slice_shrinks_borders_and_skips_empty_slices() {
+        let cmd = panel(8.0, 50.0);
+        let parts = nine_slice_parts(&cmd, cmd.nine_slice.unwrap(), (32, 32));
+        // No center column: 3 rows x 2 columns
+        assert_eq!(parts.len(), 6);
+        assert!(parts.iter().all(|p| p.w == 4.0));
+    }
+
+    #[test]
+    fn test_nine_slice_scales_borders_not_uvs() {
+        let mut cmd = panel(100.0, 50.0);
+        cmd.nine_slice = Some(NineSlice { scale: 2.0, ..cmd.nine_slice.unwrap() });
+        let parts = nine_slice_parts(&cmd, cmd.nine_slice.unwrap(), (32, 32));
+        assert_eq!((parts[0].w, parts[0].h), (16.0, 8.0));
+        assert_eq!((parts[0].uv_w, parts[0].uv_h), (0.25, 0.125));
+        assert_eq!(parts[4].w, 68.0);
+    }
+
+    #[test]
+    fn test_nine_slice_flip_mirrors_the_panel() {
+        let mut cmd = panel(100.0, 50.0);
+        cmd.nine_slice = Some(NineSlice { left: 4.0, top: 0.0, right: 12.0, bottom: 0.0, scale: 1.0 });
+        cmd.flip_x = true;
+        let parts = nine_slice_parts(&cmd, cmd.nine_slice.unwrap(), (32, 32));
+        assert_eq!(parts.len(), 3);
+        // The texture's right border (12px) is drawn on the left
+        assert_eq!((parts[0].w, parts[0].uv_x), (12.0, 0.625));
+        assert_eq!((parts[2].w, parts[2].uv_x), (4.0, 0.0));
+        assert!(parts[0].flip_x);
+    }
+
+    #[test]
+    fn test_outline_instance_grows_quad_and_uvs_around_the_same_pivot() {
+        let mut cmd = panel(64.0, 32.0);
+        cmd.nine_slice = None;
+        (cmd.uv_x, cmd.uv_w, cmd.uv_h) = (0.5, 0.5, 0.25);
+        cmd.opacity = 0.5;
+        let outline = SpriteOutline { r: 1.0, g: 0.8, b: 0.0, a: 1.0, thickness: 2.0 };
+        let instance = SpriteInstance::outline(&cmd, outline);
+        assert_eq!(instance.world_pos, [8.0, 18.0]);
+        assert_eq!(instance.size, [68.0, 36.0]);
+        // 2 world units = 1/64 of the UV width and 1/64 of the UV height
+        assert_eq!(instance.uv_offset, [0.5 - 0.5 / 32.0, -0.25 / 16.0]);
+        assert_eq!(instance.uv_size, [0.5 + 0.5 / 16.0, 0.25 + 0.25 / 8.0]);
+        assert_eq!(instance.outline_rect, [0.5, 0.0, 1.0, 0.25]);
+        assert_eq!(instance.tint, [1.0, 0.8, 0.0, 0.5]);
+        // Rotation still pivots around the sprite's center
+        let pivot = |i: &SpriteInstance| {
+            [i.world_pos[0] + i.rotation_origin[1] * i.size[0], i.world_pos[1] + i.rotation_origin[2] * i.size[1]]
+        };
+        assert_eq!(pivot(&instance), pivot(&SpriteInstance::from_command(&cmd)));
+        assert_eq!(SpriteInstance::from_command(&cmd).outline_rect, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_outline_instance_keeps_flips_mirrored() {
+        let mut cmd = panel(32.0, 32.0);
+        cmd.nine_slice = None;
+        cmd.flip_x = true;
+        let outline = SpriteOutline { r: 1.0, g: 1.0, b: 1.0, a: 1.0, thickness: 4.0 };
+        let instance = SpriteInstance::outline(&cmd, outline);
+        assert_eq!(instance.uv_offset[0], 1.125);
+        assert_eq!(instance.uv_size[0], -1.25);
+        assert_eq!(instance.outline_rect, [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_clip_rect_intersect() {
+        let a = ClipRect { x: 0.0, y: 0.0, w: 100.0, h: 50.0 };
+        let b = ClipRect { x: 80.0, y: 20.0, w: 40.0, h: 40.0 };
+        assert_eq!(a.intersect(&b), ClipRect { x: 80.0, y: 20.0, w: 20.0, h: 30.0 });
+        let apart = ClipRect { x: 200.0, y: 0.0, w: 10.0, h: 10.0 };
+        assert_eq!(a.intersect(&apart).w, 0.0);
+    }
+
+    #[test]
+    fn test_clip_rect_scissor_scales_and_clamps_to_target() {
+        let clip = ClipRect { x: 10.0, y: 20.0, w: 100.0, h: 50.0 };
+        assert_eq!(clip.scissor([800.0, 600.0], (1600, 1200)), Some((20, 40, 200, 100)));
+        let overhang = ClipRect { x: -10.0, y: 580.0, w: 50.0, h: 100.0 };
+        assert_eq!(overhang.scissor([800.0, 600.0], (800, 600)), Some((0, 580, 40, 20)));
+        let offscreen = ClipRect { x: 900.0, y: 0.0, w: 50.0, h: 50.0 };
+        assert_eq!(offscreen.scissor([800.0, 600.0], (800, 600)), None);
+    }
 }
@@ -0,0 +1,191 @@
+//! Retained sprites: sprites created once and drawn every frame until
+//! destroyed, for entity-heavy games.
+//!
+//! `drawSprite` sends a sprite's whole record across the op bridge every
+//! frame. The store keeps the authoritative command list in Rust instead:
+//! game code creates a sprite once and afterwards sends only what changes,
+//! usually position and rotation, with one op call for all of them. Retained
+//! sprites are drawn in world space on the main surface, in creation order
+//! within their layer, after the sprites drawn that frame.
+
+use std::collections::BTreeMap;
+
+use super::SpriteCommand;
+
+/// Number of f32 values per record in a bulk update:
+/// [id (u32 bits), x, y, rotation]. NaN leaves that field unchanged.
+pub const SPRITE_UPDATE_STRIDE: usize = 4;
+
+#[derive(Debug, Clone)]
+struct RetainedSprite {
+    sprite: SpriteCommand,
+    visible: bool,
+}
+
+/// Retained sprites by ID, in creation order.
+#[derive(Debug, Clone)]
+pub struct SpriteStore {
+    sprites: BTreeMap<u32, RetainedSprite>,
+    next_id: u32,
+}
+
+impl Default for SpriteStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpriteStore {
+    pub fn new() -> Self {
+        Self {
+            sprites: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Keep `sprite` and draw it every frame. Returns its ID (never 0).
+    pub fn create(&mut self, sprite: SpriteCommand) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sprites.insert(id, RetainedSprite { sprite, visible: true });
+        id
+    }
+
+    /// Replace everything about a sprite. False if it doesn't exist.
+    pub fn set(&mut self, id: u32, sprite: SpriteCommand) -> bool {
+        match self.sprites.get_mut(&id) {
+            Some(retained) => {
+                retained.sprite = sprite;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move and rotate a sprite; NaN leaves a field unchanged. False if it
+    /// doesn't exist.
+    pub fn update(&mut self, id: u32, x: f32, y: f32, rotation: f32) -> bool {
+        let Some(retained) = self.sprites.get_mut(&id) else {
+            return false;
+        };
+        let sprite = &mut retained.sprite;
+        for (field, value) in [(&mut sprite.x, x), (&mut sprite.y, y), (&mut sprite.rotation, rotation)] {
+            if !value.is_nan() {
+                *field = value;
+            }
+        }
+        true
+    }
+
+    /// Apply packed [`SPRITE_UPDATE_STRIDE`] records. Unknown sprites are
+    /// skipped and a trailing partial record is ignored. Returns how many
+    /// sprites were updated.
+    pub fn update_bulk(&mut self, records: &[f32]) -> usize {
+        records
+            .chunks_exact(SPRITE_UPDATE_STRIDE)
+            .filter(|r| self.update(r[0].to_bits(), r[1], r[2], r[3]))
+            .count()
+    }
+
+    /// Show or hide a sprite without losing it. False if it doesn't exist.
+    pub fn set_visible(&mut self, id: u32, visible: bool) -> bool {
+        match self.sprites.get_mut(&id) {
+            Some(retained) => {
+                retained.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop drawing a sprite and forget it.
+    pub fn destroy(&mut self, id: u32) -> bool {
+        self.sprites.remove(&id).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Sprite commands for this frame: the visible sprites in creation order.
+    pub fn sprite_commands(&self) -> impl Iterator<Item = SpriteCommand> + '_ {
+        self.sprites.values().filter(|r| r.visible).map(|r| r.sprite.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(texture_id: u32) -> SpriteCommand {
+        SpriteCommand {
+            texture_id, x: 0.0, y: 0.0, w: 16.0, h: 16.0, layer: 0,
+            uv_x: 0.0, uv_y: 0.0, uv_w: 1.0, uv_h: 1.0,
+            tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
+            rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
+            flip_x: false, flip_y: false, opacity: 1.0,
+            blend_mode: 0, shader_id: 0, nine_slice: None, outline: None, clip: None, mask: None, normal_map: None, flash: None, pixel_snap: None,
+        }
+    }
+
+    fn textures(store: &SpriteStore) -> Vec<u32> {
+        store.sprite_commands().map(|c| c.texture_id).collect()
+    }
+
+    #[test]
+    fn test_sprites_draw_in_creation_order_until_destroyed() {
+        let mut store = SpriteStore::new();
+        let a = store.create(sprite(1));
+        let b = store.create(sprite(2));
+        let c = store.create(sprite(3));
+        assert_eq!((a, b, c), (1, 2, 3));
+        assert_eq!(textures(&store), [1, 2, 3]);
+
+        assert!(store.set_visible(b, false));
+        assert_eq!(textures(&store), [1, 3]);
+        assert_eq!(store.len(), 3);
+
+        assert!(store.destroy(a));
+        assert!(!store.destroy(a));
+        assert!(store.set(c, sprite(7)));
+        assert!(!store.set(99, sprite(8)));
+        assert_eq!(textures(&store), [7]);
+        assert_eq!(store.create(sprite(4)), 4);
+    }
+
+    #[test]
+    fn test_updates_change_only_the_given_fields() {
+        let mut store = SpriteStore::new();
+        let id = store.create(sprite(1));
+        assert!(store.update(id, 10.0, 20.0, 0.5));
+        assert!(store.update(id, f32::NAN, 25.0, f32::NAN));
+        assert!(!store.update(99, 1.0, 1.0, 1.0));
+        let cmd = store.sprite_commands().next().unwrap();
+        assert_eq!((cmd.x, cmd.y, cmd.rotation, cmd.w), (10.0, 25.0, 0.5, 16.0));
+    }
+
+    #[test]
+    fn test_bulk_update_applies_packed_records() {
+        let mut store = SpriteStore::new();
+        let a = store.create(sprite(1));
+        let b = store.create(sprite(2));
+        let id = |n: u32| f32::from_bits(n);
+        let updated = store.update_bulk(&[
+            id(a), 1.0, 2.0, f32::NAN,
+            id(99), 5.0, 5.0, 5.0,
+            id(b), 3.0, 4.0, 1.5,
+            id(a), // partial record
+        ]);
+        assert_eq!(updated, 2);
+        let positions: Vec<(f32, f32, f32)> = store.sprite_commands().map(|c| (c.x, c.y, c.rotation)).collect();
+        assert_eq!(positions, [(1.0, 2.0, 0.0), (3.0, 4.0, 1.5)]);
+    }
+}
@@ -7,6 +7,7 @@ use anyhow::{Context, Result};
 
 use super::camera::Camera2D;
 use super::geometry::GeometryBatch;
+use super::particles::GpuParticles;
 use super::postprocess::PostProcessPipeline;
 use super::radiance::RadiancePipeline;
 use super::rendertarget::RenderTargetStore;
@@ -128,6 +129,11 @@ impl TestGpu {
 
     // ── Pipeline factory methods ──────────────────────────────────────────────
 
+    /// Create a GpuParticles system for headless testing.
+    pub fn create_gpu_particles(&self) -> GpuParticles {
+        GpuParticles::new_headless(&self.device, &self.queue, self.format)
+    }
+
     /// Create a SpritePipeline for headless testing.
     pub fn create_sprite_pipeline(&self) -> SpritePipeline {
         SpritePipeline::new_headless(&self.device, &self.queue, self.format)
@@ -4,16 +4,19 @@ use std::path::Path;
 use anyhow::{Context, Result};
 
 use super::gpu::GpuContext;
+use super::runtime_atlas::FIRST_PAGE_ID;
 
 /// Opaque handle to a loaded texture.
 pub type TextureId = u32;
 
 /// Entry for a single loaded texture.
 struct TextureEntry {
-    _texture: wgpu::Texture,
+    texture: wgpu::Texture,
     bind_group: wgpu::BindGroup,
     width: u32,
     height: u32,
+    /// Created with `COPY_SRC`, so the runtime atlas can copy from it.
+    copyable: bool,
 }
 
 /// Handle-based texture store. Loads PNGs, uploads to GPU, returns opaque handles.
@@ -25,6 +28,10 @@ pub struct TextureStore {
     /// owned by `RenderTargetStore`; we only hold the bind group (which keeps the
     /// GPU resource alive via wgpu's internal reference counting).
     render_target_bgs: HashMap<TextureId, (wgpu::BindGroup, u32, u32)>,
+    /// Normal map linked to each albedo texture (see `set_normal_map`).
+    normal_maps: HashMap<TextureId, TextureId>,
+    /// Textures uploaded or replaced by ID since `take_uploaded`.
+    uploaded: Vec<TextureId>,
 }
 
 impl TextureStore {
@@ -33,6 +40,8 @@ impl TextureStore {
             textures: HashMap::new(),
             path_to_id: HashMap::new(),
             render_target_bgs: HashMap::new(),
+            normal_maps: HashMap::new(),
+            uploaded: Vec::new(),
             next_id: 1, // 0 reserved for "no texture"
         }
     }
@@ -125,10 +134,11 @@ impl TextureStore {
         self.textures.insert(
             id,
             TextureEntry {
-                _texture: texture,
+                texture,
                 bind_group,
                 width,
                 height,
+                copyable: false,
             },
         );
         self.path_to_id.insert(path_str, id);
@@ -216,10 +226,11 @@ impl TextureStore {
         self.textures.insert(
             id,
             TextureEntry {
-                _texture: texture,
+                texture,
                 bind_group,
                 width: 1,
                 height: 1,
+                copyable: false,
             },
         );
         self.path_to_id.insert(path_key, id);
@@ -250,7 +261,7 @@ impl TextureStore {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
 
@@ -299,12 +310,14 @@ impl TextureStore {
         self.textures.insert(
             id,
             TextureEntry {
-                _texture: texture,
+                texture,
                 bind_group,
                 width,
                 height,
+                copyable: true,
             },
         );
+        self.uploaded.push(id);
     }
 
     /// Upload raw RGBA pixels as a linear (non-sRGB) texture with bilinear filtering.
@@ -379,12 +392,90 @@ impl TextureStore {
         self.textures.insert(
             id,
             TextureEntry {
-                _texture: texture,
+                texture,
                 bind_group,
                 width,
                 height,
+                copyable: false,
             },
         );
+        self.uploaded.push(id);
+    }
+
+    /// Create an empty texture for `texture_stream` to copy strips into, in
+    /// the format `upload_raw` (or `upload_raw_linear` when `linear`) uses.
+    pub fn create_streamed(&self, device: &wgpu::Device, id: TextureId, width: u32, height: u32, linear: bool) -> wgpu::Texture {
+        let (format, usage) = if linear {
+            (wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+        } else {
+            (
+                wgpu::TextureFormat::Rgba8UnormSrgb,
+                wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            )
+        };
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&format!("streamed_texture_{id}")),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        })
+    }
+
+    /// Make a texture from `create_streamed` drawable as `id` once all of its
+    /// rows have been copied, replacing any texture with that ID.
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_streamed(
+        &mut self,
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        id: TextureId,
+        texture: wgpu::Texture,
+        width: u32,
+        height: u32,
+        linear: bool,
+    ) {
+        let filter = if linear { wgpu::FilterMode::Linear } else { wgpu::FilterMode::Nearest };
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: filter,
+            min_filter: filter,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("streamed_texture_bind_group_{id}")),
+            layout: bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        self.textures.insert(
+            id,
+            TextureEntry {
+                texture,
+                bind_group,
+                width,
+                height,
+                copyable: !linear,
+            },
+        );
+        self.uploaded.push(id);
     }
 
     /// Get the bind group for a texture handle (regular textures and render targets).
@@ -403,6 +494,14 @@ impl TextureStore {
             .or_else(|| self.render_target_bgs.get(&id).map(|&(_, w, h)| (w, h)))
     }
 
+    /// GPU memory held by textures and render targets, in bytes, at 4 bytes
+    /// per pixel.
+    pub fn memory_bytes(&self) -> u64 {
+        let textures = self.textures.values().map(|e| (e.width, e.height));
+        let targets = self.render_target_bgs.values().map(|&(_, w, h)| (w, h));
+        textures.chain(targets).map(|(w, h)| w as u64 * h as u64 * 4).sum()
+    }
+
     /// Register a render target's TextureView as a samplable texture.
     ///
     /// The texture itself is owned by `RenderTargetStore`; we only create the
@@ -443,4 +542,102 @@ impl TextureStore {
     pub fn unregister_render_target(&mut self, id: TextureId) {
         self.render_target_bgs.remove(&id);
     }
+
+    /// Shade sprites drawn with `albedo` using the normal map `normal`
+    /// (a linear texture with the same layout), unless they name their own.
+    pub fn set_normal_map(&mut self, albedo: TextureId, normal: TextureId) {
+        self.normal_maps.insert(albedo, normal);
+    }
+
+    /// The normal map linked to `albedo`, if any.
+    pub fn normal_map(&self, albedo: TextureId) -> Option<TextureId> {
+        self.normal_maps.get(&albedo).copied()
+    }
+
+    /// IDs of textures uploaded or replaced since the last call.
+    pub fn take_uploaded(&mut self) -> Vec<TextureId> {
+        std::mem::take(&mut self.uploaded)
+    }
+
+    /// Textures the runtime atlas may pack, in ID order.
+    pub fn atlas_candidates(&self) -> Vec<TextureId> {
+        let mut ids: Vec<TextureId> = self
+            .textures
+            .iter()
+            .filter(|(id, e)| e.copyable && **id < FIRST_PAGE_ID)
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The GPU texture and size of a texture the runtime atlas can copy from.
+    pub fn copy_source(&self, id: TextureId) -> Option<(wgpu::Texture, u32, u32)> {
+        self.textures
+            .get(&id)
+            .filter(|e| e.copyable)
+            .map(|e| (e.texture.clone(), e.width, e.height))
+    }
+
+    /// Create an empty `size`×`size` runtime atlas page with ID `id`.
+    pub fn create_atlas_page(
+        &mut self,
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        id: TextureId,
+        size: u32,
+    ) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&format!("atlas_page_{id}")),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("atlas_page_bind_group_{id}")),
+            layout: bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        self.textures.insert(
+            id,
+            TextureEntry {
+                texture,
+                bind_group,
+                width: size,
+                height: size,
+                copyable: true,
+            },
+        );
+    }
+
+    /// Drop a texture (used for runtime atlas pages).
+    pub fn remove(&mut self, id: TextureId) {
+        self.textures.remove(&id);
+    }
 }
@@ -0,0 +1,484 @@
+//! Streaming texture loads, so a big tileset never stalls the frame.
+//!
+//! File textures are read and decoded on a pool of worker threads. Decoded
+//! images are copied to the GPU a strip of rows at a time through a ring of
+//! staging buffers, at most [`TextureStreamer::budget`] bytes per frame, so a
+//! 4096×4096 tileset (64 MB) arrives over several frames instead of one long
+//! `write_texture`. A texture becomes drawable once its last strip is copied;
+//! until then sprites using it are skipped, as for any texture that hasn't
+//! loaded yet. Every frame reports progress, loaded and failed events.
+//!
+//! A staging buffer is reused once the GPU has finished copying out of it
+//! (it is mapped again asynchronously); when the whole ring is still in
+//! flight, uploads wait for the next frame.
+//!
+//! Headless renders (`arcane render`) turn on [`TextureStreamer::set_blocking`]:
+//! textures are then decoded and uploaded whole in the frame they were
+//! requested, so captured frames don't depend on thread timing.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use image::RgbaImage;
+
+use super::gpu::GpuContext;
+use super::texture::{TextureId, TextureStore};
+
+/// Bytes copied to textures per frame unless changed with
+/// [`TextureStreamer::set_budget`].
+pub const DEFAULT_UPLOAD_BUDGET: u64 = 8 * 1024 * 1024;
+/// Smallest budget: one row of a 16384-pixel-wide texture.
+pub const MIN_UPLOAD_BUDGET: u64 = 16384 * 4;
+/// Staging buffers in the ring.
+pub const STAGING_BUFFERS: usize = 3;
+/// Most decode worker threads.
+const MAX_DECODE_THREADS: usize = 4;
+
+/// Something that happened to a streamed texture this frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextureLoadEvent {
+    /// Part of the texture is on the GPU: `progress` is the share of rows copied.
+    Progress { id: TextureId, progress: f32 },
+    /// The texture is fully uploaded and drawable.
+    Loaded { id: TextureId, width: u32, height: u32 },
+    /// The file couldn't be read or decoded.
+    Failed { id: TextureId, error: String },
+}
+
+/// Events as a JSON array for `op_take_texture_events`.
+pub fn events_json(events: &[TextureLoadEvent]) -> String {
+    let items: Vec<String> = events
+        .iter()
+        .map(|e| match e {
+            TextureLoadEvent::Progress { id, progress } => {
+                format!("{{\"id\":{id},\"event\":\"progress\",\"progress\":{progress:.3}}}")
+            }
+            TextureLoadEvent::Loaded { id, width, height } => {
+                format!("{{\"id\":{id},\"event\":\"loaded\",\"width\":{width},\"height\":{height}}}")
+            }
+            TextureLoadEvent::Failed { id, error } => {
+                format!("{{\"id\":{id},\"event\":\"failed\",\"error\":\"{}\"}}", crate::json::escape(error))
+            }
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+/// Textures still on their way, for stats.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextureStreamStats {
+    /// Being read and decoded on worker threads.
+    pub decoding: usize,
+    /// Decoded and waiting for or partway through their upload.
+    pub uploading: usize,
+    /// Bytes of decoded pixels not yet copied to the GPU.
+    pub bytes_remaining: u64,
+}
+
+impl TextureStreamStats {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"decoding\":{},\"uploading\":{},\"bytesRemaining\":{}}}",
+            self.decoding, self.uploading, self.bytes_remaining
+        )
+    }
+}
+
+/// Bytes of one row of a `width`-pixel RGBA texture in a staging buffer,
+/// padded to wgpu's copy alignment.
+pub fn padded_row_bytes(width: u32) -> u64 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+    (width as u64 * 4).div_ceil(align) * align
+}
+
+/// Split `room` staging bytes between uploads, given as `(width, rows left)`
+/// in queue order. Returns `(upload index, rows)` strips: whole uploads
+/// while they fit, then as many rows of the next one as fit.
+pub fn plan_strips(uploads: &[(u32, u32)], room: u64) -> Vec<(usize, u32)> {
+    let mut room = room;
+    let mut strips = Vec::new();
+    for (index, &(width, rows_left)) in uploads.iter().enumerate() {
+        let row = padded_row_bytes(width);
+        let rows = (room / row).min(rows_left as u64) as u32;
+        if rows == 0 {
+            break;
+        }
+        strips.push((index, rows));
+        room -= rows as u64 * row;
+        if rows < rows_left {
+            break;
+        }
+    }
+    strips
+}
+
+struct DecodeJob {
+    id: TextureId,
+    path: PathBuf,
+    linear: bool,
+}
+
+struct Decoded {
+    id: TextureId,
+    linear: bool,
+    image: Result<RgbaImage, String>,
+}
+
+fn decode(path: &std::path::Path) -> Result<RgbaImage, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read texture {}: {e}", path.display()))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode texture {}: {e}", path.display()))?;
+    Ok(image.to_rgba8())
+}
+
+/// Worker threads reading and decoding image files.
+struct DecodePool {
+    jobs: mpsc::Sender<DecodeJob>,
+    results: mpsc::Receiver<Decoded>,
+    in_flight: usize,
+}
+
+impl DecodePool {
+    fn new() -> Self {
+        let (jobs, job_rx) = mpsc::channel::<DecodeJob>();
+        let (result_tx, results) = mpsc::channel();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get().saturating_sub(1)).clamp(1, MAX_DECODE_THREADS);
+        for i in 0..threads {
+            let job_rx = Arc::clone(&job_rx);
+            let result_tx = result_tx.clone();
+            let spawned = std::thread::Builder::new().name(format!("arcane-decode-{i}")).spawn(move || loop {
+                // The lock is only held while waiting, not while decoding
+                let job = match job_rx.lock() {
+                    Ok(rx) => rx.recv(),
+                    Err(_) => return,
+                };
+                let Ok(job) = job else { return };
+                let image = decode(&job.path);
+                if result_tx.send(Decoded { id: job.id, linear: job.linear, image }).is_err() {
+                    return;
+                }
+            });
+            if let Err(e) = spawned {
+                eprintln!("[texture] Failed to start a decode thread: {e}");
+            }
+        }
+        Self { jobs, results, in_flight: 0 }
+    }
+
+    fn submit(&mut self, job: DecodeJob) {
+        if self.jobs.send(job).is_ok() {
+            self.in_flight += 1;
+        }
+    }
+
+    /// Images decoded so far, waiting for every one in flight when `wait` is set.
+    fn finished(&mut self, wait: bool) -> Vec<Decoded> {
+        let mut done = Vec::new();
+        while self.in_flight > 0 {
+            let next = if wait { self.results.recv().ok() } else { self.results.try_recv().ok() };
+            let Some(decoded) = next else { break };
+            self.in_flight -= 1;
+            done.push(decoded);
+        }
+        done
+    }
+}
+
+/// A decoded image being copied to its texture a strip at a time.
+struct Upload {
+    id: TextureId,
+    linear: bool,
+    image: RgbaImage,
+    next_row: u32,
+    /// Created with the first strip.
+    texture: Option<wgpu::Texture>,
+}
+
+impl Upload {
+    fn rows_left(&self) -> u32 {
+        self.image.height() - self.next_row
+    }
+}
+
+/// A staging buffer and whether it is mapped and free to write.
+struct StagingBuffer {
+    buffer: wgpu::Buffer,
+    ready: Arc<AtomicBool>,
+}
+
+/// Decodes and uploads file textures in the background. See the module docs.
+pub struct TextureStreamer {
+    decoder: Option<DecodePool>,
+    uploads: VecDeque<Upload>,
+    ring: Vec<StagingBuffer>,
+    next_buffer: usize,
+    budget: u64,
+    blocking: bool,
+    /// Pixel bytes copied by the last `pump`.
+    uploaded: u64,
+}
+
+impl Default for TextureStreamer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextureStreamer {
+    pub fn new() -> Self {
+        Self {
+            decoder: None,
+            uploads: VecDeque::new(),
+            ring: Vec::new(),
+            next_buffer: 0,
+            budget: DEFAULT_UPLOAD_BUDGET,
+            blocking: false,
+            uploaded: 0,
+        }
+    }
+
+    /// Load the image at `path` into texture `id`, replacing it once uploaded.
+    pub fn request(&mut self, id: TextureId, path: PathBuf, linear: bool) {
+        self.decoder.get_or_insert_with(DecodePool::new).submit(DecodeJob { id, path, linear });
+    }
+
+    /// Bytes copied to textures per frame.
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Set the per-frame upload budget (at least [`MIN_UPLOAD_BUDGET`]).
+    /// The staging ring is rebuilt at the new size.
+    pub fn set_budget(&mut self, bytes: u64) {
+        let bytes = bytes.max(MIN_UPLOAD_BUDGET);
+        if bytes != self.budget {
+            self.budget = bytes;
+            self.ring.clear();
+            self.next_buffer = 0;
+        }
+    }
+
+    /// Decode and upload every texture in the frame it was requested.
+    pub fn set_blocking(&mut self, blocking: bool) {
+        self.blocking = blocking;
+    }
+
+    /// Pixel bytes copied to textures by the last [`Self::pump`].
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.uploaded
+    }
+
+    pub fn stats(&self) -> TextureStreamStats {
+        TextureStreamStats {
+            decoding: self.decoder.as_ref().map_or(0, |d| d.in_flight),
+            uploading: self.uploads.len(),
+            bytes_remaining: self.uploads.iter().map(|u| u.rows_left() as u64 * u.image.width() as u64 * 4).sum(),
+        }
+    }
+
+    /// Collect decoded images and copy this frame's share of pixels to the
+    /// GPU. Call once per frame.
+    pub fn pump(&mut self, gpu: &GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout) -> Vec<TextureLoadEvent> {
+        let mut events = Vec::new();
+        self.uploaded = 0;
+        let decoded = self.decoder.as_mut().map(|d| d.finished(self.blocking)).unwrap_or_default();
+        for Decoded { id, linear, image } in decoded {
+            match image {
+                Ok(image) if image.width() == 0 || image.height() == 0 => {
+                    events.push(TextureLoadEvent::Failed { id, error: format!("Texture {id} is empty") });
+                }
+                Ok(image) => {
+                    // A newer request for the same texture replaces one still uploading
+                    self.uploads.retain(|u| u.id != id);
+                    self.uploads.push_back(Upload { id, linear, image, next_row: 0, texture: None });
+                }
+                Err(error) => {
+                    eprintln!("[texture] {error}");
+                    events.push(TextureLoadEvent::Failed { id, error });
+                }
+            }
+        }
+
+        if self.blocking {
+            for upload in self.uploads.drain(..) {
+                let (width, height) = upload.image.dimensions();
+                self.uploaded += width as u64 * height as u64 * 4;
+                if upload.linear {
+                    textures.upload_raw_linear(&gpu.device, &gpu.queue, layout, upload.id, &upload.image, width, height);
+                } else {
+                    textures.upload_raw(&gpu.device, &gpu.queue, layout, upload.id, &upload.image, width, height);
+                }
+                events.push(TextureLoadEvent::Loaded { id: upload.id, width, height });
+            }
+            return events;
+        }
+
+        if !self.uploads.is_empty() {
+            self.upload_strips(gpu, textures, layout, &mut events);
+        }
+        events
+    }
+
+    /// Copy as many rows as fit in the next free staging buffer.
+    fn upload_strips(&mut self, gpu: &GpuContext, textures: &mut TextureStore, layout: &wgpu::BindGroupLayout, events: &mut Vec<TextureLoadEvent>) {
+        // Run the callbacks of buffers the GPU has finished copying from
+        let _ = gpu.device.poll(wgpu::Maintain::Poll);
+        if self.ring.is_empty() {
+            self.ring = (0..STAGING_BUFFERS).map(|i| staging_buffer(&gpu.device, i, self.budget)).collect();
+        }
+        let slot = self.next_buffer;
+        if !self.ring[slot].ready.load(Ordering::Acquire) {
+            return;
+        }
+
+        let queued: Vec<(u32, u32)> = self.uploads.iter().map(|u| (u.image.width(), u.rows_left())).collect();
+        let strips = plan_strips(&queued, self.budget);
+        if strips.is_empty() {
+            // Wider than a staging buffer; the GPU can't hold it either
+            let upload = self.uploads.pop_front().expect("uploads is not empty");
+            let error = format!("Texture {} is {} pixels wide, too wide to upload", upload.id, upload.image.width());
+            eprintln!("[texture] {error}");
+            events.push(TextureLoadEvent::Failed { id: upload.id, error });
+            return;
+        }
+
+        let staging = &self.ring[slot];
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("texture_stream_encoder") });
+        {
+            let mut mapped = staging.buffer.slice(..).get_mapped_range_mut();
+            let mut offset = 0u64;
+            for &(index, rows) in &strips {
+                let upload = &mut self.uploads[index];
+                let (width, height) = upload.image.dimensions();
+                let (id, linear) = (upload.id, upload.linear);
+                let padded = padded_row_bytes(width);
+                let tight = width as usize * 4;
+                for row in 0..rows {
+                    let src = (upload.next_row + row) as usize * tight;
+                    let dst = (offset + row as u64 * padded) as usize;
+                    mapped[dst..dst + tight].copy_from_slice(&upload.image.as_raw()[src..src + tight]);
+                }
+                let texture = upload
+                    .texture
+                    .get_or_insert_with(|| textures.create_streamed(&gpu.device, id, width, height, linear));
+                encoder.copy_buffer_to_texture(
+                    wgpu::TexelCopyBufferInfo {
+                        buffer: &staging.buffer,
+                        layout: wgpu::TexelCopyBufferLayout {
+                            offset,
+                            bytes_per_row: Some(padded as u32),
+                            rows_per_image: Some(rows),
+                        },
+                    },
+                    wgpu::TexelCopyTextureInfo {
+                        texture: &*texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d { x: 0, y: upload.next_row, z: 0 },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::Extent3d { width, height: rows, depth_or_array_layers: 1 },
+                );
+                upload.next_row += rows;
+                offset += rows as u64 * padded;
+                self.uploaded += rows as u64 * tight as u64;
+                events.push(TextureLoadEvent::Progress { id: upload.id, progress: upload.next_row as f32 / height as f32 });
+            }
+        }
+        staging.buffer.unmap();
+        staging.ready.store(false, Ordering::Release);
+        gpu.queue.submit(Some(encoder.finish()));
+        let ready = Arc::clone(&staging.ready);
+        staging.buffer.slice(..).map_async(wgpu::MapMode::Write, move |result| {
+            ready.store(result.is_ok(), Ordering::Release);
+        });
+        self.next_buffer = (slot + 1) % self.ring.len();
+
+        // Later frames' draws are submitted after these copies, so a finished
+        // texture can be drawn right away
+        while self.uploads.front().is_some_and(|u| u.rows_left() == 0) {
+            let upload = self.uploads.pop_front().expect("front exists");
+            let (width, height) = upload.image.dimensions();
+            if let Some(texture) = upload.texture {
+                textures.insert_streamed(&gpu.device, layout, upload.id, texture, width, height, upload.linear);
+                events.push(TextureLoadEvent::Loaded { id: upload.id, width, height });
+            }
+        }
+    }
+}
+
+fn staging_buffer(device: &wgpu::Device, index: usize, size: u64) -> StagingBuffer {
+    let align = wgpu::COPY_BUFFER_ALIGNMENT;
+    StagingBuffer {
+        buffer: device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("texture_staging_{index}")),
+            size: size.div_ceil(align) * align,
+            usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: true,
+        }),
+        ready: Arc::new(AtomicBool::new(true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_are_padded_to_the_copy_alignment() {
+        assert_eq!(padded_row_bytes(1), 256);
+        assert_eq!(padded_row_bytes(64), 256);
+        assert_eq!(padded_row_bytes(65), 512);
+        assert_eq!(padded_row_bytes(4096), 16384);
+    }
+
+    #[test]
+    fn test_strips_fill_the_room_in_queue_order() {
+        // 64-wide rows take 256 bytes, 4096-wide rows 16 KB
+        assert_eq!(plan_strips(&[(64, 10), (4096, 4096)], 256 * 10 + 16384 * 3 + 100), [(0, 10), (1, 3)]);
+        // A partial strip stops the plan, so uploads finish in order
+        assert_eq!(plan_strips(&[(4096, 4096), (64, 1)], 16384 * 2), [(0, 2)]);
+        assert_eq!(plan_strips(&[(64, 2), (64, 2)], 256 * 8), [(0, 2), (1, 2)]);
+        assert!(plan_strips(&[(4096, 1)], 1000).is_empty());
+        assert!(plan_strips(&[], 1 << 20).is_empty());
+    }
+
+    #[test]
+    fn test_a_big_tileset_spreads_over_frames_at_the_default_budget() {
+        let mut rows_left = 4096u32;
+        let mut frames = 0;
+        while rows_left > 0 {
+            let strips = plan_strips(&[(4096, rows_left)], DEFAULT_UPLOAD_BUDGET);
+            rows_left -= strips[0].1;
+            frames += 1;
+        }
+        assert_eq!(frames, 8);
+    }
+
+    #[test]
+    fn test_events_json() {
+        let events = [
+            TextureLoadEvent::Progress { id: 3, progress: 0.25 },
+            TextureLoadEvent::Loaded { id: 3, width: 4096, height: 2048 },
+            TextureLoadEvent::Failed { id: 4, error: "bad \"png\"".into() },
+        ];
+        assert_eq!(
+            events_json(&events),
+            r#"[{"id":3,"event":"progress","progress":0.250},{"id":3,"event":"loaded","width":4096,"height":2048},{"id":4,"event":"failed","error":"bad \"png\""}]"#
+        );
+        assert_eq!(events_json(&[]), "[]");
+    }
+
+    #[test]
+    fn test_decode_pool_reports_missing_files() {
+        let mut pool = DecodePool::new();
+        pool.submit(DecodeJob { id: 7, path: PathBuf::from("/nonexistent/arcane.png"), linear: false });
+        let done = pool.finished(true);
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].id, 7);
+        assert!(done[0].image.as_ref().is_err_and(|e| e.contains("Failed to read texture")));
+        assert_eq!(pool.in_flight, 0);
+    }
+}
@@ -0,0 +1,153 @@
+//! Small previews of art assets for `arcane assets inspect --thumbnails`.
+//!
+//! Images are scaled with nearest-neighbour sampling, like the sprite
+//! pipeline samples pixel art, so a thumbnail shows the pixels the game
+//! draws: large images shrink to fit, tiny sprites grow by a whole factor.
+//! Aseprite sheet frames are cut out of the sheet image the way animations
+//! draw them (trimmed frames put back at their offset in the untrimmed
+//! sprite) and a tag's frames become an animated GIF that keeps each frame's
+//! duration. Everything runs on the CPU, so no GPU is needed.
+
+use std::io::Cursor;
+
+use anyhow::{Context, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, RgbaImage};
+
+use super::aseprite::{AsepriteSheet, AsepriteTag};
+
+/// Longest side of a thumbnail in pixels.
+pub const THUMBNAIL_SIZE: u32 = 64;
+/// Most frames put in an animation thumbnail.
+pub const MAX_THUMBNAIL_FRAMES: usize = 32;
+
+/// Thumbnail size for a `width`×`height` image: scaled down to fit `max`, or
+/// up by the largest whole factor that fits.
+pub fn thumbnail_size(width: u32, height: u32, max: u32) -> (u32, u32) {
+    let (width, height, max) = (width.max(1), height.max(1), max.max(1));
+    let longest = width.max(height);
+    if longest <= max {
+        let factor = max / longest;
+        return (width * factor, height * factor);
+    }
+    let scale = max as f64 / longest as f64;
+    (((width as f64 * scale).round() as u32).max(1), ((height as f64 * scale).round() as u32).max(1))
+}
+
+/// `image` resized to fit `max` with nearest-neighbour sampling.
+pub fn thumbnail(image: &RgbaImage, max: u32) -> RgbaImage {
+    let (width, height) = thumbnail_size(image.width(), image.height(), max);
+    imageops::resize(image, width, height, FilterType::Nearest)
+}
+
+/// Frame `index` of `sheet` as drawn: its rect in `image`, placed at its
+/// trim offset in a canvas of the untrimmed sprite size.
+pub fn sheet_frame(sheet: &AsepriteSheet, image: &RgbaImage, index: usize) -> RgbaImage {
+    let frame = &sheet.frames[index];
+    let [x, y, w, h] = frame.rect;
+    let (canvas_w, canvas_h) = (frame.source_size.0.max(w).max(1), frame.source_size.1.max(h).max(1));
+    let mut canvas = RgbaImage::new(canvas_w, canvas_h);
+    let cut = imageops::crop_imm(image, x, y, w, h).to_image();
+    imageops::overlay(&mut canvas, &cut, frame.offset.0 as i64, frame.offset.1 as i64);
+    canvas
+}
+
+/// The first pass of `tag` (every frame in order when `None`), at most
+/// [`MAX_THUMBNAIL_FRAMES`], as thumbnails with their durations in seconds.
+pub fn animation_frames(sheet: &AsepriteSheet, image: &RgbaImage, tag: Option<&AsepriteTag>, max: u32) -> Vec<(RgbaImage, f32)> {
+    let sequence: Vec<usize> = match tag {
+        Some(tag) => {
+            let (frames, _) = sheet.tag_sequence(&AsepriteTag { repeat: 1, ..tag.clone() });
+            frames
+        }
+        None => (0..sheet.frames.len()).collect(),
+    };
+    sequence
+        .into_iter()
+        .take(MAX_THUMBNAIL_FRAMES)
+        .map(|i| (thumbnail(&sheet_frame(sheet, image, i), max), sheet.frames[i].duration))
+        .collect()
+}
+
+/// Encode `frames` as a looping GIF. GIF delays are in hundredths of a
+/// second, so durations are rounded to 10 ms.
+pub fn encode_gif(frames: &[(RgbaImage, f32)]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut bytes);
+        encoder.set_repeat(Repeat::Infinite).context("Failed to encode GIF")?;
+        for (image, seconds) in frames {
+            let centiseconds = (seconds * 100.0).round().max(1.0) as u32;
+            let delay = Delay::from_numer_denom_ms(centiseconds * 10, 1);
+            encoder
+                .encode_frame(Frame::from_parts(image.clone(), 0, 0, delay))
+                .context("Failed to encode GIF")?;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Encode `image` as PNG.
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, image::ImageFormat::Png).context("Failed to encode PNG")?;
+    Ok(bytes.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const SHEET: &str = r#"{"frames":[
+        {"filename":"a","frame":{"x":0,"y":0,"w":2,"h":2},"sourceSize":{"w":4,"h":4},"spriteSourceSize":{"x":1,"y":2,"w":2,"h":2},"duration":100},
+        {"filename":"b","frame":{"x":2,"y":0,"w":2,"h":2},"duration":50},
+        {"filename":"c","frame":{"x":4,"y":0,"w":2,"h":2},"duration":250}],
+        "meta":{"image":"hero.png","size":{"w":6,"h":2},
+            "frameTags":[{"name":"idle","from":1,"to":2,"direction":"pingpong","repeat":"3"}]}}"#;
+
+    fn sheet_image() -> RgbaImage {
+        RgbaImage::from_fn(6, 2, |x, _| Rgba([(x * 40) as u8, 0, 0, 255]))
+    }
+
+    #[test]
+    fn test_thumbnail_sizes_shrink_to_fit_or_grow_by_whole_factors() {
+        assert_eq!(thumbnail_size(256, 128, 64), (64, 32));
+        assert_eq!(thumbnail_size(16, 12, 64), (64, 48));
+        assert_eq!(thumbnail_size(40, 10, 64), (40, 10));
+        assert_eq!(thumbnail_size(1000, 1, 64), (64, 1));
+        let grown = thumbnail(&RgbaImage::from_pixel(2, 1, Rgba([9, 8, 7, 255])), 8);
+        assert_eq!(grown.dimensions(), (8, 4));
+        assert!(grown.pixels().all(|p| *p == Rgba([9, 8, 7, 255])));
+    }
+
+    #[test]
+    fn test_sheet_frames_restore_trim_offsets() {
+        let sheet = AsepriteSheet::parse(SHEET).unwrap();
+        let frame = sheet_frame(&sheet, &sheet_image(), 0);
+        assert_eq!(frame.dimensions(), (4, 4));
+        assert_eq!(frame.get_pixel(0, 0).0[3], 0);
+        assert_eq!(*frame.get_pixel(1, 2), Rgba([0, 0, 0, 255]));
+        assert_eq!(*frame.get_pixel(2, 3), Rgba([40, 0, 0, 255]));
+        assert_eq!(*sheet_frame(&sheet, &sheet_image(), 2).get_pixel(0, 0), Rgba([160, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_animation_frames_play_one_pass_with_durations() {
+        let sheet = AsepriteSheet::parse(SHEET).unwrap();
+        let frames = animation_frames(&sheet, &sheet_image(), sheet.tag("idle"), 8);
+        let durations: Vec<f32> = frames.iter().map(|(_, d)| *d).collect();
+        assert_eq!(durations, [0.05, 0.25]);
+        assert_eq!(frames[0].0.dimensions(), (8, 8));
+        assert_eq!(animation_frames(&sheet, &sheet_image(), None, 8).len(), 3);
+
+        let gif = encode_gif(&frames).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        let decoded = image::codecs::gif::GifDecoder::new(Cursor::new(gif)).unwrap();
+        let delays: Vec<(u32, u32)> = image::AnimationDecoder::into_frames(decoded)
+            .map(|f| f.unwrap().delay().numer_denom_ms())
+            .collect();
+        assert_eq!(delays, [(50, 1), (250, 1)]);
+    }
+}
@@ -1,16 +1,83 @@
+use std::collections::HashMap;
+
 use super::SpriteCommand;
 
+/// Autotile neighbour bits, matching `runtime/rendering/autotile.ts`.
+pub const AUTOTILE_N: u8 = 1;
+pub const AUTOTILE_E: u8 = 2;
+pub const AUTOTILE_S: u8 = 4;
+pub const AUTOTILE_W: u8 = 8;
+pub const AUTOTILE_NE: u8 = 16;
+pub const AUTOTILE_SE: u8 = 32;
+pub const AUTOTILE_SW: u8 = 64;
+pub const AUTOTILE_NW: u8 = 128;
+
+/// Terrain auto-tiling rule: cells holding the terrain tile are drawn with the
+/// tile its neighbour bitmask maps to.
+#[derive(Clone, Debug)]
+pub struct AutotileRule {
+    /// 4 (edges only) or 8 (edges and corners; a corner counts only when both
+    /// adjacent edges connect).
+    pub neighbors: u8,
+    /// Display tile per bitmask (16 or 256 entries). 0 entries use `fallback`.
+    pub tiles: Vec<u16>,
+    /// Tile for unmapped masks. 0 keeps the terrain tile itself.
+    pub fallback: u16,
+    /// Other tile IDs that count as connected, besides the terrain itself.
+    pub connects: Vec<u16>,
+}
+
+/// A looping tile animation: the tile is drawn as `frames` in turn.
+#[derive(Clone, Debug)]
+pub struct TileAnimation {
+    pub frames: Vec<u16>,
+    pub fps: f32,
+}
+
+/// Tiles per chunk side in chunked tilemaps.
+pub const TILEMAP_CHUNK_SIZE: u32 = 32;
+const CHUNK_CELLS: usize = (TILEMAP_CHUNK_SIZE * TILEMAP_CHUNK_SIZE) as usize;
+
+#[derive(Clone)]
+enum TileStorage {
+    /// `width * height` tiles, row-major.
+    Dense(Vec<u16>),
+    /// Unbounded: `TILEMAP_CHUNK_SIZE`² chunks by chunk coordinates,
+    /// allocated when first written. Missing chunks read as empty.
+    Chunked(HashMap<(i32, i32), Box<[u16; CHUNK_CELLS]>>),
+}
+
+/// Chunk coordinates of cell (gx, gy) and the cell's index in that chunk.
+fn chunk_cell(gx: i32, gy: i32) -> ((i32, i32), usize) {
+    let size = TILEMAP_CHUNK_SIZE as i32;
+    let (cx, cy) = (gx.div_euclid(size), gy.div_euclid(size));
+    let index = gy.rem_euclid(size) * size + gx.rem_euclid(size);
+    ((cx, cy), index as usize)
+}
+
 /// A tile-based map that references a texture atlas.
 /// Tile ID 0 = empty (not drawn). IDs 1+ map to atlas positions (1-indexed).
+///
+/// Dense maps hold `width` × `height` tiles from (0, 0). Chunked maps
+/// (`Tilemap::chunked`) have no bounds, negative cells included, and only
+/// allocate the 32×32 chunks that are written, so procedurally generated
+/// worlds can grow without one huge grid.
 #[derive(Clone)]
 pub struct Tilemap {
+    /// Map size in tiles; 0 for chunked maps.
     pub width: u32,
     pub height: u32,
     pub tile_size: f32,
     pub texture_id: u32,
     pub atlas_columns: u32,
     pub atlas_rows: u32,
-    tiles: Vec<u16>, // width * height, row-major
+    tiles: TileStorage,
+    /// Auto-tiling rules by terrain tile ID, resolved when baking.
+    autotile: HashMap<u16, AutotileRule>,
+    /// Animations by tile ID, applied after auto-tiling.
+    animations: HashMap<u16, TileAnimation>,
+    /// Seconds of tile animation played, advanced by `TilemapStore::update`.
+    anim_time: f32,
 }
 
 impl Tilemap {
@@ -29,25 +96,291 @@ impl Tilemap {
             texture_id,
             atlas_columns,
             atlas_rows,
-            tiles: vec![0; (width * height) as usize],
+            tiles: TileStorage::Dense(vec![0; (width * height) as usize]),
+            autotile: HashMap::new(),
+            animations: HashMap::new(),
+            anim_time: 0.0,
+        }
+    }
+
+    /// An empty unbounded map stored in `TILEMAP_CHUNK_SIZE`² chunks.
+    pub fn chunked(texture_id: u32, tile_size: f32, atlas_columns: u32, atlas_rows: u32) -> Self {
+        Self {
+            tiles: TileStorage::Chunked(HashMap::new()),
+            ..Self::new(texture_id, 0, 0, tile_size, atlas_columns, atlas_rows)
+        }
+    }
+
+    pub fn is_chunked(&self) -> bool {
+        matches!(self.tiles, TileStorage::Chunked(_))
+    }
+
+    /// Whether (gx, gy) is a cell of the map. Always true for chunked maps.
+    fn contains(&self, gx: i32, gy: i32) -> bool {
+        match self.tiles {
+            TileStorage::Dense(_) => gx >= 0 && gy >= 0 && (gx as u32) < self.width && (gy as u32) < self.height,
+            TileStorage::Chunked(_) => true,
+        }
+    }
+
+    /// Set a tile. Dense maps ignore cells past the edge; chunked maps
+    /// allocate the cell's chunk unless `tile_id` is 0.
+    pub fn set_tile(&mut self, gx: i32, gy: i32, tile_id: u16) {
+        if !self.contains(gx, gy) {
+            return;
+        }
+        match &mut self.tiles {
+            TileStorage::Dense(tiles) => tiles[(gy as u32 * self.width + gx as u32) as usize] = tile_id,
+            TileStorage::Chunked(chunks) => {
+                let (key, index) = chunk_cell(gx, gy);
+                if let Some(chunk) = chunks.get_mut(&key) {
+                    chunk[index] = tile_id;
+                } else if tile_id != 0 {
+                    chunks.entry(key).or_insert_with(|| Box::new([0; CHUNK_CELLS]))[index] = tile_id;
+                }
+            }
+        }
+    }
+
+    pub fn get_tile(&self, gx: i32, gy: i32) -> u16 {
+        if !self.contains(gx, gy) {
+            return 0;
+        }
+        match &self.tiles {
+            TileStorage::Dense(tiles) => tiles[(gy as u32 * self.width + gx as u32) as usize],
+            TileStorage::Chunked(chunks) => {
+                let (key, index) = chunk_cell(gx, gy);
+                chunks.get(&key).map_or(0, |chunk| chunk[index])
+            }
         }
     }
 
-    pub fn set_tile(&mut self, gx: u32, gy: u32, tile_id: u16) {
-        if gx < self.width && gy < self.height {
-            self.tiles[(gy * self.width + gx) as usize] = tile_id;
+    /// Grid cells `[min_gx, min_gy, max_gx, max_gy)` overlapping the world
+    /// rectangle `view` (`[left, top, right, bottom]`), clamped to the map.
+    pub fn grid_range(&self, world_offset_x: f32, world_offset_y: f32, view: [f32; 4]) -> [i32; 4] {
+        let [left, top, right, bottom] = view;
+        let mut range = [
+            ((left - world_offset_x) / self.tile_size).floor() as i32,
+            ((top - world_offset_y) / self.tile_size).floor() as i32,
+            ((right - world_offset_x) / self.tile_size).ceil() as i32,
+            ((bottom - world_offset_y) / self.tile_size).ceil() as i32,
+        ];
+        if !self.is_chunked() {
+            let (w, h) = (self.width.min(i32::MAX as u32) as i32, self.height.min(i32::MAX as u32) as i32);
+            range = [range[0].max(0), range[1].max(0), range[2].min(w), range[3].min(h)];
         }
+        range
     }
 
-    pub fn get_tile(&self, gx: u32, gy: u32) -> u16 {
-        if gx < self.width && gy < self.height {
-            self.tiles[(gy * self.width + gx) as usize]
+    /// Tiles of the `size`×`size` chunk at chunk coordinates (cx, cy),
+    /// row-major. Cells past the map edge read as 0.
+    pub fn chunk(&self, cx: i32, cy: i32, size: u32) -> Vec<u16> {
+        let size = size as i32;
+        let mut tiles = Vec::with_capacity((size * size) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                tiles.push(self.get_tile(cx * size + x, cy * size + y));
+            }
+        }
+        tiles
+    }
+
+    /// Overwrite a chunk with tiles laid out as by `chunk`; missing tiles are
+    /// 0. Cells past the map edge are ignored. On a chunked map, a
+    /// `TILEMAP_CHUNK_SIZE` chunk is allocated even if it is all empty, so
+    /// it no longer counts as missing.
+    pub fn set_chunk(&mut self, cx: i32, cy: i32, size: u32, tiles: &[u16]) {
+        if let TileStorage::Chunked(chunks) = &mut self.tiles
+            && size == TILEMAP_CHUNK_SIZE
+        {
+            let mut chunk = Box::new([0; CHUNK_CELLS]);
+            let n = tiles.len().min(CHUNK_CELLS);
+            chunk[..n].copy_from_slice(&tiles[..n]);
+            chunks.insert((cx, cy), chunk);
+            return;
+        }
+        let size = size as i32;
+        for i in 0..size * size {
+            let tile = tiles.get(i as usize).copied().unwrap_or(0);
+            self.set_tile(cx * size + i % size, cy * size + i / size, tile);
+        }
+    }
+
+    /// Whether a chunked map has allocated chunk (cx, cy). False for dense maps.
+    pub fn has_chunk(&self, cx: i32, cy: i32) -> bool {
+        match &self.tiles {
+            TileStorage::Dense(_) => false,
+            TileStorage::Chunked(chunks) => chunks.contains_key(&(cx, cy)),
+        }
+    }
+
+    /// Free a chunk of a chunked map; its cells read as empty again.
+    /// Returns false if it wasn't allocated.
+    pub fn remove_chunk(&mut self, cx: i32, cy: i32) -> bool {
+        match &mut self.tiles {
+            TileStorage::Dense(_) => false,
+            TileStorage::Chunked(chunks) => chunks.remove(&(cx, cy)).is_some(),
+        }
+    }
+
+    /// Number of allocated chunks (0 for dense maps).
+    pub fn chunk_count(&self) -> usize {
+        match &self.tiles {
+            TileStorage::Dense(_) => 0,
+            TileStorage::Chunked(chunks) => chunks.len(),
+        }
+    }
+
+    /// Chunk coordinates of every allocated chunk, sorted by row then column.
+    pub fn chunk_coords(&self) -> Vec<(i32, i32)> {
+        let TileStorage::Chunked(chunks) = &self.tiles else {
+            return Vec::new();
+        };
+        let mut coords: Vec<(i32, i32)> = chunks.keys().copied().collect();
+        coords.sort_unstable_by_key(|&(cx, cy)| (cy, cx));
+        coords
+    }
+
+    /// Allocated chunks overlapping the grid range `[min_gx, min_gy, max_gx,
+    /// max_gy)`, sorted by row then column. Only looks up the chunks in the
+    /// range, or scans the allocated ones when there are fewer of those.
+    pub fn chunks_in_range(&self, range: [i32; 4]) -> Vec<(i32, i32)> {
+        let TileStorage::Chunked(chunks) = &self.tiles else {
+            return Vec::new();
+        };
+        let [min_cx, min_cy, max_cx, max_cy] = chunk_range(range);
+        let area = (max_cx as i64 - min_cx as i64).max(0) * (max_cy as i64 - min_cy as i64).max(0);
+        if area as usize <= chunks.len() {
+            (min_cy..max_cy)
+                .flat_map(|cy| (min_cx..max_cx).map(move |cx| (cx, cy)))
+                .filter(|key| chunks.contains_key(key))
+                .collect()
         } else {
-            0
+            let mut coords: Vec<(i32, i32)> = chunks
+                .keys()
+                .copied()
+                .filter(|&(cx, cy)| cx >= min_cx && cx < max_cx && cy >= min_cy && cy < max_cy)
+                .collect();
+            coords.sort_unstable_by_key(|&(cx, cy)| (cy, cx));
+            coords
         }
     }
 
-    /// Bake visible tiles into sprite commands. Only emits tiles within camera view.
+    /// Chunks overlapping the grid range that a chunked map hasn't allocated,
+    /// sorted by row then column: the ones a streaming game still has to
+    /// generate or load.
+    pub fn missing_chunks(&self, range: [i32; 4]) -> Vec<(i32, i32)> {
+        let TileStorage::Chunked(chunks) = &self.tiles else {
+            return Vec::new();
+        };
+        let [min_cx, min_cy, max_cx, max_cy] = chunk_range(range);
+        (min_cy..max_cy)
+            .flat_map(|cy| (min_cx..max_cx).map(move |cx| (cx, cy)))
+            .filter(|key| !chunks.contains_key(key))
+            .collect()
+    }
+
+    /// Free every chunk outside the grid range. Returns the freed chunks'
+    /// coordinates, sorted by row then column.
+    pub fn retain_chunks(&mut self, range: [i32; 4]) -> Vec<(i32, i32)> {
+        let TileStorage::Chunked(chunks) = &mut self.tiles else {
+            return Vec::new();
+        };
+        let [min_cx, min_cy, max_cx, max_cy] = chunk_range(range);
+        let mut removed: Vec<(i32, i32)> = chunks
+            .keys()
+            .copied()
+            .filter(|&(cx, cy)| cx < min_cx || cx >= max_cx || cy < min_cy || cy >= max_cy)
+            .collect();
+        for key in &removed {
+            chunks.remove(key);
+        }
+        removed.sort_unstable_by_key(|&(cx, cy)| (cy, cx));
+        removed
+    }
+
+    /// Draw cells holding `terrain` by their neighbour bitmask. Stored tiles
+    /// are unchanged, so `get_tile` still returns the terrain ID.
+    pub fn set_autotile_rule(&mut self, terrain: u16, rule: AutotileRule) {
+        self.autotile.insert(terrain, rule);
+    }
+
+    pub fn clear_autotile_rule(&mut self, terrain: u16) {
+        self.autotile.remove(&terrain);
+    }
+
+    /// Animate every cell that displays `tile`, including auto-tiled ones.
+    /// An empty frame list or non-positive fps removes the animation.
+    pub fn set_tile_animation(&mut self, tile: u16, animation: TileAnimation) {
+        if animation.frames.is_empty() || animation.fps <= 0.0 {
+            self.animations.remove(&tile);
+        } else {
+            self.animations.insert(tile, animation);
+        }
+    }
+
+    /// Advance tile animations by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        self.anim_time += dt;
+    }
+
+    /// Neighbour bitmask of (gx, gy) for `rule`. Cells past the map edge
+    /// don't connect.
+    pub fn autotile_mask(&self, gx: i32, gy: i32, terrain: u16, rule: &AutotileRule) -> u8 {
+        let connects = |dx: i32, dy: i32| {
+            let (x, y) = (gx.wrapping_add(dx), gy.wrapping_add(dy));
+            if !self.contains(x, y) {
+                return false;
+            }
+            let tile = self.get_tile(x, y);
+            tile == terrain || rule.connects.contains(&tile)
+        };
+        let mut mask = 0;
+        for (dx, dy, bit) in [(0, -1, AUTOTILE_N), (1, 0, AUTOTILE_E), (0, 1, AUTOTILE_S), (-1, 0, AUTOTILE_W)] {
+            if connects(dx, dy) {
+                mask |= bit;
+            }
+        }
+        if rule.neighbors == 8 {
+            let corners = [
+                (1, -1, AUTOTILE_NE, AUTOTILE_N | AUTOTILE_E),
+                (1, 1, AUTOTILE_SE, AUTOTILE_S | AUTOTILE_E),
+                (-1, 1, AUTOTILE_SW, AUTOTILE_S | AUTOTILE_W),
+                (-1, -1, AUTOTILE_NW, AUTOTILE_N | AUTOTILE_W),
+            ];
+            for (dx, dy, bit, edges) in corners {
+                if mask & edges == edges && connects(dx, dy) {
+                    mask |= bit;
+                }
+            }
+        }
+        mask
+    }
+
+    /// The tile drawn at (gx, gy): the stored tile after auto-tiling and the
+    /// current animation frame.
+    pub fn display_tile(&self, gx: i32, gy: i32) -> u16 {
+        let mut tile = self.get_tile(gx, gy);
+        if let Some(rule) = self.autotile.get(&tile) {
+            let mask = self.autotile_mask(gx, gy, tile, rule);
+            let mapped = rule.tiles.get(mask as usize).copied().unwrap_or(0);
+            tile = if mapped != 0 {
+                mapped
+            } else if rule.fallback != 0 {
+                rule.fallback
+            } else {
+                tile
+            };
+        }
+        if let Some(anim) = self.animations.get(&tile) {
+            let frame = (self.anim_time * anim.fps) as usize % anim.frames.len();
+            tile = anim.frames[frame];
+        }
+        tile
+    }
+
+    /// Bake visible tiles into sprite commands. Only emits tiles within camera
+    /// view; chunked maps only visit the allocated chunks in view.
     pub fn bake_visible(
         &self,
         world_offset_x: f32,
@@ -59,86 +392,109 @@ impl Tilemap {
         viewport_w: f32,
         viewport_h: f32,
     ) -> Vec<SpriteCommand> {
-        let half_w = viewport_w / (2.0 * camera_zoom);
-        let half_h = viewport_h / (2.0 * camera_zoom);
-
-        // Visible world bounds
-        let view_left = camera_x - half_w;
-        let view_right = camera_x + half_w;
-        let view_top = camera_y - half_h;
-        let view_bottom = camera_y + half_h;
-
-        // Convert to tile grid indices (clamp to tilemap bounds)
-        let min_gx = ((view_left - world_offset_x) / self.tile_size)
-            .floor()
-            .max(0.0) as u32;
-        let max_gx = ((view_right - world_offset_x) / self.tile_size)
-            .ceil()
-            .min(self.width as f32) as u32;
-        let min_gy = ((view_top - world_offset_y) / self.tile_size)
-            .floor()
-            .max(0.0) as u32;
-        let max_gy = ((view_bottom - world_offset_y) / self.tile_size)
-            .ceil()
-            .min(self.height as f32) as u32;
-
-        let uv_tile_w = 1.0 / self.atlas_columns as f32;
-        let uv_tile_h = 1.0 / self.atlas_rows as f32;
-
+        let view = view_rect(camera_x, camera_y, camera_zoom, viewport_w, viewport_h);
+        let [min_gx, min_gy, max_gx, max_gy] = self.grid_range(world_offset_x, world_offset_y, view);
         let mut commands = Vec::new();
-
-        for gy in min_gy..max_gy {
-            for gx in min_gx..max_gx {
-                let tile_id = self.tiles[(gy * self.width + gx) as usize];
-                if tile_id == 0 {
-                    continue;
+        let mut bake_cells = |xs: std::ops::Range<i32>, ys: std::ops::Range<i32>| {
+            for gy in ys {
+                for gx in xs.clone() {
+                    let tile_id = self.display_tile(gx, gy);
+                    if tile_id != 0 {
+                        commands.push(self.tile_command(tile_id, world_offset_x, world_offset_y, gx, gy, layer));
+                    }
                 }
-
-                let atlas_x = (tile_id as u32 - 1) % self.atlas_columns;
-                let atlas_y = (tile_id as u32 - 1) / self.atlas_columns;
-
-                commands.push(SpriteCommand {
-                    texture_id: self.texture_id,
-                    x: world_offset_x + gx as f32 * self.tile_size,
-                    y: world_offset_y + gy as f32 * self.tile_size,
-                    w: self.tile_size,
-                    h: self.tile_size,
-                    layer,
-                    uv_x: atlas_x as f32 * uv_tile_w,
-                    uv_y: atlas_y as f32 * uv_tile_h,
-                    uv_w: uv_tile_w,
-                    uv_h: uv_tile_h,
-                    tint_r: 1.0,
-                    tint_g: 1.0,
-                    tint_b: 1.0,
-                    tint_a: 1.0,
-                    rotation: 0.0,
-                    origin_x: 0.5,
-                    origin_y: 0.5,
-                    flip_x: false,
-                    flip_y: false,
-                    opacity: 1.0,
-                    blend_mode: 0,
-                    shader_id: 0,
-                });
             }
+        };
+
+        if self.is_chunked() {
+            let size = TILEMAP_CHUNK_SIZE as i32;
+            for (cx, cy) in self.chunks_in_range([min_gx, min_gy, max_gx, max_gy]) {
+                let xs = (cx * size).max(min_gx)..(cx * size + size).min(max_gx);
+                let ys = (cy * size).max(min_gy)..(cy * size + size).min(max_gy);
+                bake_cells(xs, ys);
+            }
+        } else {
+            bake_cells(min_gx..max_gx, min_gy..max_gy);
         }
-
         commands
     }
+
+    /// Sprite for `tile_id` drawn in cell (gx, gy).
+    fn tile_command(&self, tile_id: u16, world_offset_x: f32, world_offset_y: f32, gx: i32, gy: i32, layer: i32) -> SpriteCommand {
+        let uv_tile_w = 1.0 / self.atlas_columns as f32;
+        let uv_tile_h = 1.0 / self.atlas_rows as f32;
+        let atlas_x = (tile_id as u32 - 1) % self.atlas_columns;
+        let atlas_y = (tile_id as u32 - 1) / self.atlas_columns;
+
+        SpriteCommand {
+            texture_id: self.texture_id,
+            x: world_offset_x + gx as f32 * self.tile_size,
+            y: world_offset_y + gy as f32 * self.tile_size,
+            w: self.tile_size,
+            h: self.tile_size,
+            layer,
+            uv_x: atlas_x as f32 * uv_tile_w,
+            uv_y: atlas_y as f32 * uv_tile_h,
+            uv_w: uv_tile_w,
+            uv_h: uv_tile_h,
+            tint_r: 1.0,
+            tint_g: 1.0,
+            tint_b: 1.0,
+            tint_a: 1.0,
+            rotation: 0.0,
+            origin_x: 0.5,
+            origin_y: 0.5,
+            flip_x: false,
+            flip_y: false,
+            opacity: 1.0,
+            blend_mode: 0,
+            shader_id: 0,
+            nine_slice: None,
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
+        }
+    }
+}
+
+/// Chunks `[min_cx, min_cy, max_cx, max_cy)` overlapping the grid range
+/// `[min_gx, min_gy, max_gx, max_gy)`. Empty ranges give empty chunk ranges.
+pub fn chunk_range(range: [i32; 4]) -> [i32; 4] {
+    let [min_gx, min_gy, max_gx, max_gy] = range;
+    if min_gx >= max_gx || min_gy >= max_gy {
+        return [0; 4];
+    }
+    let size = TILEMAP_CHUNK_SIZE as i32;
+    [
+        min_gx.div_euclid(size),
+        min_gy.div_euclid(size),
+        (max_gx - 1).div_euclid(size) + 1,
+        (max_gy - 1).div_euclid(size) + 1,
+    ]
+}
+
+/// World rectangle `[left, top, right, bottom]` seen by a camera centered on
+/// (camera_x, camera_y).
+pub fn view_rect(camera_x: f32, camera_y: f32, camera_zoom: f32, viewport_w: f32, viewport_h: f32) -> [f32; 4] {
+    let half_w = viewport_w / (2.0 * camera_zoom);
+    let half_h = viewport_h / (2.0 * camera_zoom);
+    [camera_x - half_w, camera_y - half_h, camera_x + half_w, camera_y + half_h]
 }
 
 /// Manages tilemap instances by ID.
 #[derive(Clone)]
 pub struct TilemapStore {
-    tilemaps: std::collections::HashMap<u32, Tilemap>,
+    tilemaps: std::collections::BTreeMap<u32, Tilemap>,
     next_id: u32,
 }
 
 impl TilemapStore {
     pub fn new() -> Self {
         Self {
-            tilemaps: std::collections::HashMap::new(),
+            tilemaps: std::collections::BTreeMap::new(),
             next_id: 1,
         }
     }
@@ -161,6 +517,14 @@ impl TilemapStore {
         id
     }
 
+    /// Create an unbounded chunked tilemap (see `Tilemap::chunked`).
+    pub fn create_chunked(&mut self, texture_id: u32, tile_size: f32, atlas_columns: u32, atlas_rows: u32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tilemaps.insert(id, Tilemap::chunked(texture_id, tile_size, atlas_columns, atlas_rows));
+        id
+    }
+
     pub fn get(&self, id: u32) -> Option<&Tilemap> {
         self.tilemaps.get(&id)
     }
@@ -168,6 +532,13 @@ impl TilemapStore {
     pub fn get_mut(&mut self, id: u32) -> Option<&mut Tilemap> {
         self.tilemaps.get_mut(&id)
     }
+
+    /// Advance every tilemap's tile animations by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        for tilemap in self.tilemaps.values_mut() {
+            tilemap.advance(dt);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -330,4 +701,159 @@ mod tests {
         assert!((c.tint_b - 1.0).abs() < 1e-5);
         assert!((c.tint_a - 1.0).abs() < 1e-5);
     }
+
+    fn rule4(tiles: &[(u8, u16)], fallback: u16) -> AutotileRule {
+        let mut table = vec![0; 16];
+        for &(mask, tile) in tiles {
+            table[mask as usize] = tile;
+        }
+        AutotileRule { neighbors: 4, tiles: table, fallback, connects: Vec::new() }
+    }
+
+    #[test]
+    fn test_autotile_resolves_by_neighbor_mask() {
+        let mut tm = Tilemap::new(1, 3, 1, 16.0, 4, 4);
+        for gx in 0..3 {
+            tm.set_tile(gx, 0, 1);
+        }
+        tm.set_autotile_rule(1, rule4(&[(AUTOTILE_E, 2), (AUTOTILE_E | AUTOTILE_W, 3), (AUTOTILE_W, 4)], 9));
+
+        assert_eq!(tm.display_tile(0, 0), 2);
+        assert_eq!(tm.display_tile(1, 0), 3);
+        assert_eq!(tm.display_tile(2, 0), 4);
+        // Stored tiles keep the terrain ID
+        assert_eq!(tm.get_tile(1, 0), 1);
+
+        // A lone cell has mask 0, which falls back
+        tm.set_tile(1, 0, 0);
+        assert_eq!(tm.display_tile(0, 0), 9);
+
+        tm.clear_autotile_rule(1);
+        assert_eq!(tm.display_tile(0, 0), 1);
+    }
+
+    #[test]
+    fn test_autotile_corners_need_both_edges() {
+        let mut tm = Tilemap::new(1, 2, 2, 16.0, 4, 4);
+        tm.set_tile(0, 0, 1);
+        tm.set_tile(1, 0, 1);
+        tm.set_tile(1, 1, 1);
+        let rule = AutotileRule { neighbors: 8, tiles: vec![0; 256], fallback: 0, connects: vec![5] };
+        // (0,0): E connects, S is empty, so the SE corner doesn't count
+        assert_eq!(tm.autotile_mask(0, 0, 1, &rule), AUTOTILE_E);
+        tm.set_tile(0, 1, 5);
+        assert_eq!(tm.autotile_mask(0, 0, 1, &rule), AUTOTILE_E | AUTOTILE_S | AUTOTILE_SE);
+    }
+
+    #[test]
+    fn test_tile_animation_advances_with_store() {
+        let mut store = TilemapStore::new();
+        let id = store.create(1, 1, 1, 16.0, 4, 4);
+        let tm = store.get_mut(id).unwrap();
+        tm.set_tile(0, 0, 1);
+        tm.set_tile_animation(1, TileAnimation { frames: vec![5, 6, 7], fps: 10.0 });
+        assert_eq!(store.get(id).unwrap().display_tile(0, 0), 5);
+
+        store.update(0.15);
+        assert_eq!(store.get(id).unwrap().display_tile(0, 0), 6);
+        store.update(0.1);
+        let cmds = store.get(id).unwrap().bake_visible(0.0, 0.0, 0, 8.0, 8.0, 1.0, 100.0, 100.0);
+        // Tile 7 -> index 6 -> col 2, row 1 in the 4x4 atlas
+        assert!((cmds[0].uv_x - 0.5).abs() < 1e-5);
+        assert!((cmds[0].uv_y - 0.25).abs() < 1e-5);
+        store.update(0.12);
+        assert_eq!(store.get(id).unwrap().display_tile(0, 0), 5);
+
+        // Removing the animation shows the tile itself again
+        store.get_mut(id).unwrap().set_tile_animation(1, TileAnimation { frames: Vec::new(), fps: 10.0 });
+        assert_eq!(store.get(id).unwrap().display_tile(0, 0), 1);
+    }
+
+    #[test]
+    fn test_animation_applies_to_autotiled_tiles() {
+        let mut tm = Tilemap::new(1, 1, 1, 16.0, 4, 4);
+        tm.set_tile(0, 0, 1);
+        tm.set_autotile_rule(1, rule4(&[], 3));
+        tm.set_tile_animation(3, TileAnimation { frames: vec![3, 4], fps: 2.0 });
+        assert_eq!(tm.display_tile(0, 0), 3);
+        tm.advance(0.5);
+        assert_eq!(tm.display_tile(0, 0), 4);
+    }
+
+    #[test]
+    fn test_chunked_map_allocates_chunks_on_demand() {
+        let mut tm = Tilemap::chunked(1, 16.0, 4, 4);
+        assert!(tm.is_chunked());
+        assert_eq!(tm.get_tile(1_000_000, -1_000_000), 0);
+        // Clearing an empty cell doesn't allocate
+        tm.set_tile(5, 5, 0);
+        assert_eq!(tm.chunk_count(), 0);
+
+        tm.set_tile(-1, -1, 3);
+        tm.set_tile(40, 0, 4);
+        tm.set_tile(41, 0, 5);
+        assert_eq!(tm.chunk_coords(), vec![(-1, -1), (1, 0)]);
+        assert_eq!(tm.get_tile(-1, -1), 3);
+        assert_eq!(tm.get_tile(41, 0), 5);
+        assert_eq!(tm.chunk(-1, -1, TILEMAP_CHUNK_SIZE)[(31 * 32 + 31) as usize], 3);
+
+        assert!(tm.remove_chunk(-1, -1));
+        assert!(!tm.remove_chunk(-1, -1));
+        assert_eq!(tm.get_tile(-1, -1), 0);
+    }
+
+    #[test]
+    fn test_set_chunk_fills_a_whole_chunk() {
+        let mut tm = Tilemap::chunked(1, 16.0, 4, 4);
+        let tiles: Vec<u16> = (0..CHUNK_CELLS as u16).collect();
+        tm.set_chunk(2, -3, TILEMAP_CHUNK_SIZE, &tiles);
+        assert_eq!(tm.get_tile(64, -96), 0);
+        assert_eq!(tm.get_tile(65, -96), 1);
+        assert_eq!(tm.get_tile(64, -95), 32);
+        assert_eq!(tm.chunk(2, -3, TILEMAP_CHUNK_SIZE), tiles);
+
+        // An all-empty chunk still counts as present; short input pads with 0
+        tm.set_chunk(0, 0, TILEMAP_CHUNK_SIZE, &[]);
+        assert!(tm.has_chunk(0, 0));
+        tm.set_chunk(2, -3, TILEMAP_CHUNK_SIZE, &[7]);
+        assert_eq!(tm.get_tile(64, -96), 7);
+        assert_eq!(tm.get_tile(65, -96), 0);
+
+        // Dense maps clip a chunk to their bounds
+        let mut dense = Tilemap::new(1, 40, 40, 16.0, 4, 4);
+        dense.set_chunk(1, 1, TILEMAP_CHUNK_SIZE, &vec![2; CHUNK_CELLS]);
+        assert_eq!(dense.get_tile(39, 39), 2);
+        assert_eq!(dense.get_tile(31, 31), 0);
+        assert_eq!(dense.chunk_count(), 0);
+    }
+
+    #[test]
+    fn test_chunked_bake_culls_to_allocated_chunks_in_view() {
+        let mut tm = Tilemap::chunked(1, 16.0, 4, 4);
+        tm.set_chunk(0, 0, TILEMAP_CHUNK_SIZE, &vec![1; CHUNK_CELLS]);
+        tm.set_chunk(-1, 0, TILEMAP_CHUNK_SIZE, &vec![2; CHUNK_CELLS]);
+        // Far away, never visited
+        tm.set_tile(100_000, 100_000, 3);
+
+        // A 64x64 view centred on the seam between chunks -1 and 0
+        let cmds = tm.bake_visible(0.0, 0.0, 0, 0.0, 96.0, 1.0, 64.0, 64.0);
+        assert_eq!(cmds.len(), 4 * 4);
+        assert!(cmds.iter().all(|c| c.x >= -32.0 && c.x < 32.0 && c.y >= 64.0 && c.y < 128.0));
+        let left = cmds.iter().filter(|c| c.x < 0.0).count();
+        assert_eq!(left, 8);
+        assert_eq!(tm.chunks_in_range([-2, 4, 2, 8]), vec![(-1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn test_missing_and_retained_chunks() {
+        let mut tm = Tilemap::chunked(1, 16.0, 4, 4);
+        tm.set_tile(0, 0, 1);
+        tm.set_tile(-40, 0, 1);
+        tm.set_tile(500, 500, 1);
+        // Cells [-32, 64) x [0, 32) cover chunks -1..2 on row 0
+        assert_eq!(tm.missing_chunks([-32, 0, 64, 32]), vec![(-1, 0), (1, 0)]);
+        assert_eq!(tm.retain_chunks([-32, 0, 64, 32]), vec![(-2, 0), (15, 15)]);
+        assert_eq!(tm.chunk_coords(), vec![(0, 0)]);
+        assert_eq!(chunk_range([0, 0, 0, 10]), [0; 4]);
+    }
 }
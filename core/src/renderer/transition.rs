@@ -0,0 +1,311 @@
+/// How a screen transition covers the frame. Drawn by the post-process
+/// pipeline as its last pass, so it covers UI and effects too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionKind {
+    /// Blend towards the transition color.
+    Fade,
+    /// A circle around a screen point closes to nothing, leaving the color outside.
+    CircleWipe,
+    /// Blocks grow while the frame blends towards the color.
+    Pixelate,
+    /// A user fragment shader registered with `op_create_transition_shader`.
+    Custom(u32),
+}
+
+impl TransitionKind {
+    /// Parse a kind name. `shader_id` is only used by "custom".
+    pub fn parse(name: &str, shader_id: u32) -> Option<Self> {
+        match name {
+            "fade" => Some(Self::Fade),
+            "circle" => Some(Self::CircleWipe),
+            "pixelate" => Some(Self::Pixelate),
+            "custom" => Some(Self::Custom(shader_id)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEventKind {
+    /// The screen is fully covered; swap scenes now.
+    Midpoint,
+    /// The transition has uncovered the screen and ended.
+    Complete,
+}
+
+impl TransitionEventKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Midpoint => "midpoint",
+            Self::Complete => "complete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionEvent {
+    pub id: u32,
+    pub kind: TransitionEventKind,
+}
+
+/// What the post-process pass needs to draw one frame of a transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionOverlay {
+    pub kind: TransitionKind,
+    /// 0 = screen untouched, 1 = fully covered. Rises over the first half
+    /// of the duration and falls over the second.
+    pub coverage: f32,
+    /// Elapsed fraction of the whole transition, 0-1.
+    pub progress: f32,
+    /// Cover color, RGBA 0-1.
+    pub color: [f32; 4],
+    /// Kind-specific values (circle center, pixelate block size, custom use).
+    pub params: [f32; 4],
+}
+
+#[derive(Debug, Clone)]
+struct RunningTransition {
+    id: u32,
+    kind: TransitionKind,
+    duration: f32,
+    elapsed: f32,
+    color: [f32; 4],
+    params: [f32; 4],
+    midpoint_reported: bool,
+}
+
+/// The screen transition in progress, if any, advanced by the dev loop each
+/// frame. Only one runs at a time.
+#[derive(Debug, Clone)]
+pub struct ScreenTransition {
+    current: Option<RunningTransition>,
+    next_id: u32,
+    events: Vec<TransitionEvent>,
+}
+
+impl Default for ScreenTransition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScreenTransition {
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            next_id: 1,
+            events: Vec::new(),
+        }
+    }
+
+    /// Start a transition lasting `duration` seconds. A running transition
+    /// is replaced without reporting further events. Returns the new ID.
+    pub fn start(
+        &mut self,
+        kind: TransitionKind,
+        duration: f32,
+        color: [f32; 4],
+        params: [f32; 4],
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.current = Some(RunningTransition {
+            id,
+            kind,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+            color,
+            params,
+            midpoint_reported: false,
+        });
+        id
+    }
+
+    /// Stop the running transition immediately, without events.
+    pub fn cancel(&mut self) {
+        self.current = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Advance by `dt` seconds. Events from the previous update that were
+    /// not taken are dropped.
+    ///
+    /// The update that reaches the midpoint stops exactly on it, so the frame
+    /// in which the midpoint is reported is drawn fully covered.
+    pub fn update(&mut self, dt: f32) {
+        self.events.clear();
+        let Some(t) = self.current.as_mut() else {
+            return;
+        };
+        let half = t.duration * 0.5;
+        t.elapsed += dt.max(0.0);
+        if !t.midpoint_reported && t.elapsed >= half {
+            t.midpoint_reported = true;
+            t.elapsed = half;
+            self.events.push(TransitionEvent {
+                id: t.id,
+                kind: TransitionEventKind::Midpoint,
+            });
+        }
+        if t.elapsed >= t.duration {
+            self.events.push(TransitionEvent {
+                id: t.id,
+                kind: TransitionEventKind::Complete,
+            });
+            self.current = None;
+        }
+    }
+
+    /// Coverage of the running transition (see `TransitionOverlay::coverage`),
+    /// or 0 when none is running.
+    pub fn coverage(&self) -> f32 {
+        self.overlay().map_or(0.0, |o| o.coverage)
+    }
+
+    /// The frame to draw, or `None` when no transition is running.
+    pub fn overlay(&self) -> Option<TransitionOverlay> {
+        let t = self.current.as_ref()?;
+        let progress = if t.duration > 0.0 {
+            (t.elapsed / t.duration).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        Some(TransitionOverlay {
+            kind: t.kind,
+            coverage: 1.0 - (progress * 2.0 - 1.0).abs(),
+            progress,
+            color: t.color,
+            params: t.params,
+        })
+    }
+
+    /// Take the events produced by the last update.
+    pub fn take_events(&mut self) -> Vec<TransitionEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Serialize events for TS: `[{"id":1,"event":"midpoint"}, ...]`.
+pub fn events_json(events: &[TransitionEvent]) -> String {
+    let items: Vec<String> = events
+        .iter()
+        .map(|e| format!("{{\"id\":{},\"event\":\"{}\"}}", e.id, e.kind.as_str()))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    #[test]
+    fn test_coverage_rises_then_falls() {
+        let mut tr = ScreenTransition::new();
+        tr.start(TransitionKind::Fade, 1.0, BLACK, [0.0; 4]);
+        assert_eq!(tr.coverage(), 0.0);
+        tr.update(0.25);
+        assert!((tr.coverage() - 0.5).abs() < 1e-5);
+        tr.update(0.25);
+        assert_eq!(tr.coverage(), 1.0);
+        tr.update(0.25);
+        assert!((tr.coverage() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_midpoint_frame_is_fully_covered() {
+        let mut tr = ScreenTransition::new();
+        let id = tr.start(TransitionKind::CircleWipe, 1.0, BLACK, [0.5, 0.5, 0.0, 0.0]);
+        tr.update(0.3);
+        assert!(tr.take_events().is_empty());
+        // Overshoots the midpoint by 0.2s; the frame still shows full cover
+        tr.update(0.4);
+        assert_eq!(
+            tr.take_events(),
+            vec![TransitionEvent {
+                id,
+                kind: TransitionEventKind::Midpoint
+            }]
+        );
+        assert_eq!(tr.coverage(), 1.0);
+        tr.update(0.4);
+        assert!(tr.take_events().is_empty());
+        tr.update(0.2);
+        assert_eq!(
+            tr.take_events(),
+            vec![TransitionEvent {
+                id,
+                kind: TransitionEventKind::Complete
+            }]
+        );
+        assert!(!tr.is_active());
+        assert!(tr.overlay().is_none());
+    }
+
+    #[test]
+    fn test_zero_duration_reports_both_events_in_one_update() {
+        let mut tr = ScreenTransition::new();
+        let id = tr.start(TransitionKind::Fade, 0.0, BLACK, [0.0; 4]);
+        assert_eq!(tr.coverage(), 1.0);
+        tr.update(0.016);
+        let kinds: Vec<_> = tr
+            .take_events()
+            .into_iter()
+            .map(|e| (e.id, e.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (id, TransitionEventKind::Midpoint),
+                (id, TransitionEventKind::Complete)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_restart_and_cancel_drop_events() {
+        let mut tr = ScreenTransition::new();
+        let first = tr.start(TransitionKind::Fade, 1.0, BLACK, [0.0; 4]);
+        tr.update(0.4);
+        let second = tr.start(TransitionKind::Pixelate, 1.0, BLACK, [32.0, 0.0, 0.0, 0.0]);
+        assert_ne!(first, second);
+        tr.update(0.2);
+        assert!(tr.take_events().is_empty());
+        assert_eq!(tr.overlay().unwrap().kind, TransitionKind::Pixelate);
+        tr.cancel();
+        tr.update(1.0);
+        assert!(tr.take_events().is_empty());
+        assert_eq!(tr.coverage(), 0.0);
+    }
+
+    #[test]
+    fn test_parses_kinds_and_serializes_events() {
+        assert_eq!(
+            TransitionKind::parse("circle", 0),
+            Some(TransitionKind::CircleWipe)
+        );
+        assert_eq!(
+            TransitionKind::parse("custom", 4),
+            Some(TransitionKind::Custom(4))
+        );
+        assert_eq!(TransitionKind::parse("wipe", 0), None);
+        let events = [
+            TransitionEvent {
+                id: 1,
+                kind: TransitionEventKind::Midpoint,
+            },
+            TransitionEvent {
+                id: 1,
+                kind: TransitionEventKind::Complete,
+            },
+        ];
+        assert_eq!(
+            events_json(&events),
+            "[{\"id\":1,\"event\":\"midpoint\"},{\"id\":1,\"event\":\"complete\"}]"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Split-screen viewports: rects of the window that each show the scene
+//! through their own camera.
+//!
+//! Without viewports the frame is drawn once with the main camera. With them,
+//! `Renderer::render_frame` draws the frame's command lists once per
+//! viewport, in ID order, through the viewport's camera and scissored to its
+//! rect, so two players each get a view of the same world. The layers above
+//! every viewport's range are then drawn over the whole window with the main
+//! camera, which is where a shared HUD goes. The background fill covers the
+//! window behind the viewports; masks, GI, post-processing and plugin passes
+//! follow the main camera.
+//!
+//! Every viewport re-submits the frame's commands with its camera in the
+//! shared camera uniform, so each one costs a command buffer submission.
+
+use super::background::{BACKGROUND_LAYER, LETTERBOX_LAYER};
+use super::{Camera2D, ClipRect};
+
+/// Lowest and highest layer a viewport can show: every layer between the
+/// background fill and the letterbox bars, which are drawn over the whole
+/// window with the main camera.
+pub const VIEWPORT_LAYERS: (i32, i32) = (BACKGROUND_LAYER + 1, LETTERBOX_LAYER - 1);
+
+/// A rect of the window and the camera the scene is drawn with inside it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// Where the viewport is, in logical window pixels from the top-left.
+    pub rect: ClipRect,
+    /// World position of the viewport's top-left corner (before rotation).
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    /// Roll in radians around the center of the viewport (see [`Camera2D::rotation`]).
+    pub rotation: f32,
+    /// Inclusive layer range drawn in the viewport, within [`VIEWPORT_LAYERS`].
+    pub layer_min: i32,
+    pub layer_max: i32,
+}
+
+impl Viewport {
+    /// A viewport at `rect` looking at the world origin at 1:1, showing every layer.
+    pub fn new(rect: ClipRect) -> Self {
+        Self {
+            rect,
+            x: 0.0,
+            y: 0.0,
+            zoom: 1.0,
+            rotation: 0.0,
+            layer_min: VIEWPORT_LAYERS.0,
+            layer_max: VIEWPORT_LAYERS.1,
+        }
+    }
+
+    /// Show layers `min..=max` (in either order), limited to [`VIEWPORT_LAYERS`].
+    pub fn set_layers(&mut self, min: i32, max: i32) {
+        let (lo, hi) = VIEWPORT_LAYERS;
+        self.layer_min = min.min(max).clamp(lo, hi);
+        self.layer_max = min.max(max).clamp(lo, hi);
+    }
+
+    /// The camera the viewport's layers are drawn with.
+    pub fn camera(&self) -> Camera2D {
+        Camera2D {
+            x: self.x,
+            y: self.y,
+            zoom: if self.zoom > 0.0 { self.zoom } else { 1.0 },
+            rotation: self.rotation,
+            viewport_size: [self.rect.w, self.rect.h],
+            bounds: None,
+        }
+    }
+
+    /// The viewport's rect in a target of `pixels` showing a window of
+    /// `window` logical pixels, as `(x, y, w, h)`. `None` when it is off
+    /// the target or empty.
+    pub fn pixel_rect(&self, window: [f32; 2], pixels: (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+        self.rect.scissor(window, pixels)
+    }
+}
+
+/// First layer drawn over the whole window after `viewports`: the one above
+/// the highest layer any of them shows.
+pub fn overlay_start(viewports: &[Viewport]) -> i32 {
+    viewports.iter().map(|v| v.layer_max).max().unwrap_or(VIEWPORT_LAYERS.1) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> ClipRect {
+        ClipRect { x, y, w, h }
+    }
+
+    #[test]
+    fn test_camera_fills_the_rect() {
+        let mut viewport = Viewport::new(rect(400.0, 0.0, 400.0, 600.0));
+        viewport.x = 120.0;
+        viewport.zoom = 0.0;
+        let camera = viewport.camera();
+        assert_eq!((camera.x, camera.y, camera.zoom), (120.0, 0.0, 1.0));
+        assert_eq!(camera.viewport_size, [400.0, 600.0]);
+    }
+
+    #[test]
+    fn test_pixel_rect_scales_to_the_target() {
+        let viewport = Viewport::new(rect(400.0, 0.0, 400.0, 300.0));
+        assert_eq!(viewport.pixel_rect([800.0, 600.0], (1600, 1200)), Some((800, 0, 800, 600)));
+        assert_eq!(viewport.pixel_rect([800.0, 600.0], (400, 300)), Some((200, 0, 200, 150)));
+        let off_screen = Viewport::new(rect(900.0, 0.0, 100.0, 100.0));
+        assert_eq!(off_screen.pixel_rect([800.0, 600.0], (800, 600)), None);
+    }
+
+    #[test]
+    fn test_layers_stay_between_background_and_letterbox() {
+        let mut viewport = Viewport::new(rect(0.0, 0.0, 10.0, 10.0));
+        assert_eq!((viewport.layer_min, viewport.layer_max), VIEWPORT_LAYERS);
+        viewport.set_layers(99, 0);
+        assert_eq!((viewport.layer_min, viewport.layer_max), (0, 99));
+        viewport.set_layers(i32::MIN, i32::MAX);
+        assert_eq!((viewport.layer_min, viewport.layer_max), VIEWPORT_LAYERS);
+
+        let mut hud = Viewport::new(rect(0.0, 0.0, 10.0, 10.0));
+        hud.set_layers(0, 49);
+        viewport.set_layers(0, 99);
+        assert_eq!(overlay_start(&[hud, viewport]), 100);
+        assert_eq!(overlay_start(&[]), LETTERBOX_LAYER);
+    }
+}
@@ -0,0 +1,352 @@
+//! Persistent world streaming for tilemaps.
+//!
+//! A tilemap attached to a world under a name keeps its edits on disk in
+//! `.arcane/world/<name>/`, one file per modified `WORLD_CHUNK_SIZE`² chunk.
+//! Chunks are loaded lazily, the first time a tile in them is read, written
+//! or drawn after attaching, and overwrite whatever the game generated there.
+//! Edited chunks are marked dirty and written on an interval (`tick`) or on
+//! `flush`. Untouched chunks never reach the disk, so a huge generated map
+//! only stores what the player changed. Chunked tilemaps that free chunks
+//! far from the camera call `unload_chunk`, which saves a dirty chunk first
+//! and loads it again when it comes back into range.
+//!
+//! ## Chunk file format (`<cx>_<cy>.chunk`, little-endian)
+//! - `ARCW` magic, format version (u8), chunk size (u16)
+//! - run-length encoded tiles, row-major: `(count: u16, tile: u16)` pairs
+//!   covering exactly size² cells. Cells past the map edge are stored as 0.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use super::tilemap::{TILEMAP_CHUNK_SIZE, TilemapStore, chunk_range};
+
+/// Tiles per chunk side, the same as chunked tilemaps use.
+pub const WORLD_CHUNK_SIZE: u32 = TILEMAP_CHUNK_SIZE;
+/// Seconds between automatic saves of dirty chunks.
+pub const DEFAULT_AUTOSAVE_SECS: f32 = 10.0;
+
+const MAGIC: &[u8; 4] = b"ARCW";
+const FORMAT_VERSION: u8 = 1;
+
+/// Streaming state of one attached tilemap.
+#[derive(Debug, Clone)]
+struct WorldMap {
+    name: String,
+    /// Chunks whose saved state has been applied (or found missing).
+    loaded: BTreeSet<(i32, i32)>,
+    /// Chunks edited since they were last saved.
+    dirty: BTreeSet<(i32, i32)>,
+}
+
+/// Tilemaps attached to persistent worlds, keyed by tilemap ID.
+#[derive(Debug, Clone)]
+pub struct WorldStore {
+    dir: PathBuf,
+    maps: BTreeMap<u32, WorldMap>,
+    autosave: f32,
+    since_save: f32,
+}
+
+impl WorldStore {
+    /// Store worlds under `dir` (normally `.arcane/world/` in the game directory).
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, maps: BTreeMap::new(), autosave: DEFAULT_AUTOSAVE_SECS, since_save: 0.0 }
+    }
+
+    /// Persist `tilemap_id` under `name` (letters, digits, `_` and `-`).
+    /// Attaching again under another name starts over from that world.
+    pub fn attach(&mut self, tilemap_id: u32, name: &str) -> bool {
+        if !valid_name(name) {
+            return false;
+        }
+        self.maps.insert(
+            tilemap_id,
+            WorldMap { name: name.to_string(), loaded: BTreeSet::new(), dirty: BTreeSet::new() },
+        );
+        true
+    }
+
+    /// Stop persisting a tilemap. Unsaved edits are dropped; `flush` first to keep them.
+    pub fn detach(&mut self, tilemap_id: u32) {
+        self.maps.remove(&tilemap_id);
+    }
+
+    pub fn is_attached(&self, tilemap_id: u32) -> bool {
+        self.maps.contains_key(&tilemap_id)
+    }
+
+    /// Seconds between automatic saves; 0 or less saves only on `flush`.
+    pub fn set_autosave(&mut self, seconds: f32) {
+        self.autosave = seconds;
+    }
+
+    /// Number of chunks edited since they were last saved.
+    pub fn dirty_count(&self) -> usize {
+        self.maps.values().map(|m| m.dirty.len()).sum()
+    }
+
+    /// Load the chunk holding tile (gx, gy) if it hasn't been yet; mark it
+    /// dirty when `edit` is set. Call before the tile is read or written.
+    pub fn touch(&mut self, tilemaps: &mut TilemapStore, tilemap_id: u32, gx: i32, gy: i32, edit: bool) {
+        let size = WORLD_CHUNK_SIZE as i32;
+        let chunk = (gx.div_euclid(size), gy.div_euclid(size));
+        self.load_chunks(tilemaps, tilemap_id, [chunk.0, chunk.1, chunk.0 + 1, chunk.1 + 1]);
+        if edit && let Some(map) = self.maps.get_mut(&tilemap_id) {
+            map.dirty.insert(chunk);
+        }
+    }
+
+    /// Load every chunk overlapping the grid range `[min_gx, min_gy, max_gx, max_gy)`.
+    pub fn load_range(&mut self, tilemaps: &mut TilemapStore, tilemap_id: u32, range: [i32; 4]) {
+        self.load_chunks(tilemaps, tilemap_id, chunk_range(range));
+    }
+
+    /// Forget chunk (cx, cy) of a tilemap that is about to free it, saving it
+    /// first if it was edited. It is loaded again the next time it is touched.
+    pub fn unload_chunk(&mut self, tilemaps: &TilemapStore, tilemap_id: u32, cx: i32, cy: i32) -> std::io::Result<()> {
+        let Some(map) = self.maps.get_mut(&tilemap_id) else { return Ok(()) };
+        if map.dirty.remove(&(cx, cy))
+            && let Some(tilemap) = tilemaps.get(tilemap_id)
+        {
+            std::fs::create_dir_all(self.dir.join(&map.name))?;
+            let bytes = encode_chunk(&tilemap.chunk(cx, cy, WORLD_CHUNK_SIZE));
+            if let Err(e) = std::fs::write(chunk_path(&self.dir, &map.name, cx, cy), bytes) {
+                map.dirty.insert((cx, cy));
+                return Err(e);
+            }
+        }
+        map.loaded.remove(&(cx, cy));
+        Ok(())
+    }
+
+    fn load_chunks(&mut self, tilemaps: &mut TilemapStore, tilemap_id: u32, chunks: [i32; 4]) {
+        let Some(map) = self.maps.get_mut(&tilemap_id) else { return };
+        let Some(tilemap) = tilemaps.get_mut(tilemap_id) else { return };
+        let [min_cx, min_cy, max_cx, max_cy] = chunks;
+        for cy in min_cy..max_cy {
+            for cx in min_cx..max_cx {
+                if !map.loaded.insert((cx, cy)) {
+                    continue;
+                }
+                let path = chunk_path(&self.dir, &map.name, cx, cy);
+                let Ok(bytes) = std::fs::read(&path) else { continue };
+                match decode_chunk(&bytes) {
+                    Some(tiles) => tilemap.set_chunk(cx, cy, WORLD_CHUNK_SIZE, &tiles),
+                    None => eprintln!("[world] Ignoring corrupt chunk {}", path.display()),
+                }
+            }
+        }
+    }
+
+    /// Advance the autosave timer by `dt` seconds, saving dirty chunks when
+    /// it runs out. Returns the number of chunks written.
+    pub fn tick(&mut self, dt: f32, tilemaps: &TilemapStore) -> std::io::Result<usize> {
+        if self.autosave <= 0.0 {
+            return Ok(0);
+        }
+        self.since_save += dt;
+        if self.since_save < self.autosave {
+            return Ok(0);
+        }
+        self.flush(tilemaps)
+    }
+
+    /// Write every dirty chunk now. Returns the number of chunks written.
+    pub fn flush(&mut self, tilemaps: &TilemapStore) -> std::io::Result<usize> {
+        self.since_save = 0.0;
+        let mut written = 0;
+        for (&tilemap_id, map) in &mut self.maps {
+            let Some(tilemap) = tilemaps.get(tilemap_id) else {
+                map.dirty.clear();
+                continue;
+            };
+            if map.dirty.is_empty() {
+                continue;
+            }
+            let dir = self.dir.join(&map.name);
+            std::fs::create_dir_all(&dir)?;
+            while let Some((cx, cy)) = map.dirty.pop_first() {
+                let bytes = encode_chunk(&tilemap.chunk(cx, cy, WORLD_CHUNK_SIZE));
+                if let Err(e) = std::fs::write(chunk_path(&self.dir, &map.name, cx, cy), bytes) {
+                    // Keep it dirty so the next save retries
+                    map.dirty.insert((cx, cy));
+                    return Err(e);
+                }
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Delete the saved chunks of world `name`, or of every world when `None`.
+    /// Attached tilemaps keep their current tiles but forget pending edits,
+    /// so nothing is written back until they change again.
+    pub fn clear(&mut self, name: Option<&str>) -> std::io::Result<()> {
+        let dir = match name {
+            Some(name) if !valid_name(name) => return Ok(()),
+            Some(name) => self.dir.join(name),
+            None => self.dir.clone(),
+        };
+        match std::fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        for map in self.maps.values_mut() {
+            if name.is_none_or(|n| n == map.name) {
+                map.dirty.clear();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// World names double as directory names.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn chunk_path(dir: &Path, name: &str, cx: i32, cy: i32) -> PathBuf {
+    dir.join(name).join(format!("{cx}_{cy}.chunk"))
+}
+
+/// Encode a chunk's tiles (see the module docs for the format).
+pub fn encode_chunk(tiles: &[u16]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(16);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&(WORLD_CHUNK_SIZE as u16).to_le_bytes());
+    let mut i = 0;
+    while i < tiles.len() {
+        let tile = tiles[i];
+        let run = tiles[i..].iter().take(u16::MAX as usize).take_while(|&&t| t == tile).count();
+        out.extend_from_slice(&(run as u16).to_le_bytes());
+        out.extend_from_slice(&tile.to_le_bytes());
+        i += run;
+    }
+    out
+}
+
+/// Decode a chunk written by `encode_chunk`. `None` if it is malformed or
+/// was saved with a different chunk size.
+pub fn decode_chunk(bytes: &[u8]) -> Option<Vec<u16>> {
+    let header = bytes.get(..7)?;
+    if &header[..4] != MAGIC || header[4] != FORMAT_VERSION {
+        return None;
+    }
+    let size = u16::from_le_bytes([header[5], header[6]]) as u32;
+    if size != WORLD_CHUNK_SIZE {
+        return None;
+    }
+    let cells = (size * size) as usize;
+    let mut tiles = Vec::with_capacity(cells);
+    for run in bytes[7..].chunks(4) {
+        let [c0, c1, t0, t1] = *run else { return None };
+        let count = u16::from_le_bytes([c0, c1]) as usize;
+        tiles.extend(std::iter::repeat_n(u16::from_le_bytes([t0, t1]), count));
+        if tiles.len() > cells {
+            return None;
+        }
+    }
+    (tiles.len() == cells).then_some(tiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_world(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("arcane_world_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_chunk_encoding_round_trips_and_compresses() {
+        let mut tiles = vec![0u16; (WORLD_CHUNK_SIZE * WORLD_CHUNK_SIZE) as usize];
+        tiles[5] = 3;
+        tiles[6] = 3;
+        tiles[1000] = 65535;
+        let bytes = encode_chunk(&tiles);
+        assert!(bytes.len() < 40);
+        assert_eq!(decode_chunk(&bytes), Some(tiles));
+    }
+
+    #[test]
+    fn test_malformed_chunks_are_rejected() {
+        let tiles = vec![1u16; (WORLD_CHUNK_SIZE * WORLD_CHUNK_SIZE) as usize];
+        let bytes = encode_chunk(&tiles);
+        assert_eq!(decode_chunk(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(decode_chunk(b"ARCW"), None);
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 9;
+        assert_eq!(decode_chunk(&wrong_version), None);
+    }
+
+    #[test]
+    fn test_edits_persist_and_load_lazily() {
+        let dir = temp_world("persist");
+        let mut tilemaps = TilemapStore::new();
+        let id = tilemaps.create(1, 100, 100, 16.0, 4, 4);
+        let mut world = WorldStore::new(dir.clone());
+        assert!(world.attach(id, "overworld"));
+
+        world.touch(&mut tilemaps, id, 40, 5, true);
+        tilemaps.get_mut(id).unwrap().set_tile(40, 5, 7);
+        assert_eq!(world.dirty_count(), 1);
+        assert_eq!(world.flush(&tilemaps).unwrap(), 1);
+        assert_eq!(world.dirty_count(), 0);
+        assert!(dir.join("overworld").join("1_0.chunk").exists());
+
+        // A fresh session regenerates the map; saved chunks win once touched
+        let mut tilemaps = TilemapStore::new();
+        let id = tilemaps.create(1, 100, 100, 16.0, 4, 4);
+        tilemaps.get_mut(id).unwrap().set_tile(40, 5, 2);
+        let mut world = WorldStore::new(dir.clone());
+        world.attach(id, "overworld");
+        assert_eq!(tilemaps.get(id).unwrap().get_tile(40, 5), 2);
+        world.load_range(&mut tilemaps, id, [0, 0, 50, 10]);
+        assert_eq!(tilemaps.get(id).unwrap().get_tile(40, 5), 7);
+
+        world.clear(Some("overworld")).unwrap();
+        assert!(!dir.join("overworld").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_autosave_interval() {
+        let dir = temp_world("autosave");
+        let mut tilemaps = TilemapStore::new();
+        let id = tilemaps.create(1, 10, 10, 16.0, 4, 4);
+        let mut world = WorldStore::new(dir.clone());
+        world.attach(id, "cave");
+        world.set_autosave(1.0);
+        world.touch(&mut tilemaps, id, 0, 0, true);
+        assert_eq!(world.tick(0.5, &tilemaps).unwrap(), 0);
+        assert_eq!(world.tick(0.5, &tilemaps).unwrap(), 1);
+        assert!(!world.attach(id, "../escape"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unloaded_chunks_of_chunked_maps_reload_with_edits() {
+        let dir = temp_world("chunked");
+        let mut tilemaps = TilemapStore::new();
+        let id = tilemaps.create_chunked(1, 16.0, 4, 4);
+        let mut world = WorldStore::new(dir.clone());
+        world.attach(id, "endless");
+
+        world.touch(&mut tilemaps, id, -3, -40, true);
+        tilemaps.get_mut(id).unwrap().set_tile(-3, -40, 9);
+        assert!(!dir.join("endless").exists());
+
+        // Streaming the chunk out saves it; streaming it back in restores it
+        world.unload_chunk(&tilemaps, id, -1, -2).unwrap();
+        assert_eq!(world.dirty_count(), 0);
+        assert!(dir.join("endless").join("-1_-2.chunk").exists());
+        assert!(tilemaps.get_mut(id).unwrap().remove_chunk(-1, -2));
+        assert_eq!(tilemaps.get(id).unwrap().get_tile(-3, -40), 0);
+        world.load_range(&mut tilemaps, id, [-10, -50, 0, -30]);
+        assert_eq!(tilemaps.get(id).unwrap().get_tile(-3, -40), 9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,329 @@
+//! Replay files: a recorded input session with the metadata needed to share
+//! it and to refuse playing it where it would desync.
+//!
+//! Layout (`.arcreplay`):
+//!
+//! ```text
+//! ARCANE-REPLAY 1\n
+//! {"engine_version":..,"content_hash":..,"frames":..,"duration":..,"created":..,"thumbnail_bytes":N}\n
+//! <N bytes: PNG thumbnail of the first frame, or nothing>
+//! <the recording as JSON, to the end of the file>
+//! ```
+//!
+//! The header line can be read without parsing the recording, which is what
+//! `arcane replay info` does. A replay only plays back deterministically on
+//! the engine version and game content it was recorded with, so both are in
+//! the header and [`ReplayHeader::mismatch`] explains what differs.
+
+use std::path::Path;
+
+use crate::json::{escape, JsonValue};
+
+/// First line of every replay file, followed by the format version.
+pub const REPLAY_MAGIC: &str = "ARCANE-REPLAY";
+
+/// Version of the layout above.
+pub const REPLAY_FORMAT: u32 = 1;
+
+/// Version of the engine writing replays.
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// File extension of replays, skipped by [`content_hash`].
+pub const REPLAY_EXTENSION: &str = "arcreplay";
+
+/// Extensions of files the engine writes into a game's directory, skipped
+/// by [`content_hash`]: replays, GIF recordings and OpenRaster layer captures.
+const GENERATED_EXTENSIONS: [&str; 3] = [REPLAY_EXTENSION, "gif", "ora"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayHeader {
+    pub engine_version: String,
+    /// [`content_hash`] of the game's directory when recorded.
+    pub content_hash: String,
+    pub frames: u32,
+    /// Game time covered, in seconds.
+    pub duration: f64,
+    /// When it was recorded, in Unix milliseconds.
+    pub created: u64,
+}
+
+impl ReplayHeader {
+    /// A header for `recording` (the JSON of a TypeScript `Recording`),
+    /// taking its frame count and duration from the recording itself.
+    pub fn for_recording(recording: &str, content_hash: &str, created: u64) -> Result<Self, String> {
+        let (frames, duration) = recording_length(recording)?;
+        Ok(Self {
+            engine_version: ENGINE_VERSION.to_string(),
+            content_hash: content_hash.to_string(),
+            frames,
+            duration,
+            created,
+        })
+    }
+
+    /// Header fields as a JSON object, with the thumbnail size.
+    pub fn to_json(&self, thumbnail_bytes: usize) -> String {
+        format!(
+            "{{\"engine_version\":\"{}\",\"content_hash\":\"{}\",\"frames\":{},\"duration\":{},\"created\":{},\"thumbnail_bytes\":{}}}",
+            escape(&self.engine_version),
+            escape(&self.content_hash),
+            self.frames,
+            self.duration,
+            self.created,
+            thumbnail_bytes
+        )
+    }
+
+    /// Why this replay would desync on `engine_version` with the game
+    /// content hashing to `content_hash`, or `None` if it matches both.
+    pub fn mismatch(&self, engine_version: &str, content_hash: &str) -> Option<String> {
+        let mut problems = Vec::new();
+        if self.engine_version != engine_version {
+            problems.push(format!(
+                "it was recorded with engine {} but this is engine {engine_version}",
+                self.engine_version
+            ));
+        }
+        if self.content_hash != content_hash {
+            problems.push(format!(
+                "the game content changed since it was recorded (content hash {}, now {content_hash})",
+                self.content_hash
+            ));
+        }
+        if problems.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "replay would desync: {}. Re-record it, or play it with the engine and game version it was made with",
+            problems.join(", and ")
+        ))
+    }
+}
+
+/// A decoded replay file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFile {
+    pub header: ReplayHeader,
+    /// PNG of the first frame, if one was captured.
+    pub thumbnail: Option<Vec<u8>>,
+    /// The recording as JSON.
+    pub recording: String,
+}
+
+impl ReplayFile {
+    pub fn encode(&self) -> Vec<u8> {
+        let thumbnail = self.thumbnail.as_deref().unwrap_or_default();
+        let mut bytes = format!(
+            "{REPLAY_MAGIC} {REPLAY_FORMAT}\n{}\n",
+            self.header.to_json(thumbnail.len())
+        )
+        .into_bytes();
+        bytes.extend_from_slice(thumbnail);
+        bytes.extend_from_slice(self.recording.as_bytes());
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let (header, thumbnail_bytes, body) = split_header(bytes)?;
+        if body.len() < thumbnail_bytes {
+            return Err("replay file is truncated".into());
+        }
+        let (thumbnail, recording) = body.split_at(thumbnail_bytes);
+        let recording = String::from_utf8(recording.to_vec()).map_err(|_| "replay recording is not UTF-8".to_string())?;
+        Ok(Self {
+            header,
+            thumbnail: (!thumbnail.is_empty()).then(|| thumbnail.to_vec()),
+            recording,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::decode(&bytes).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+/// Split a replay file into its header, thumbnail size and the bytes after
+/// the header line.
+fn split_header(bytes: &[u8]) -> Result<(ReplayHeader, usize, &[u8]), String> {
+    let mut lines = bytes.splitn(3, |&b| b == b'\n');
+    let magic = std::str::from_utf8(lines.next().unwrap_or_default()).unwrap_or_default();
+    let Some(format) = magic.strip_prefix(REPLAY_MAGIC).map(str::trim) else {
+        return Err("not an Arcane replay file".into());
+    };
+    if format != REPLAY_FORMAT.to_string() {
+        return Err(format!(
+            "replay format {format} is not supported by engine {ENGINE_VERSION} (format {REPLAY_FORMAT})"
+        ));
+    }
+    let line = lines.next().ok_or("replay header is missing")?;
+    let json = JsonValue::parse(std::str::from_utf8(line).map_err(|_| "replay header is not UTF-8")?)
+        .map_err(|e| format!("replay header: {e}"))?;
+    let number = |key: &str| json.get(key).and_then(JsonValue::as_f64).ok_or(format!("replay header has no {key}"));
+    let text = |key: &str| json.str_of(key).map(str::to_string).ok_or(format!("replay header has no {key}"));
+    let header = ReplayHeader {
+        engine_version: text("engine_version")?,
+        content_hash: text("content_hash")?,
+        frames: number("frames")? as u32,
+        duration: number("duration")?,
+        created: number("created")? as u64,
+    };
+    let thumbnail_bytes = number("thumbnail_bytes")? as usize;
+    Ok((header, thumbnail_bytes, lines.next().unwrap_or_default()))
+}
+
+/// Frame count and total game time of a TypeScript `Recording`: each frame's
+/// `dt`, or the recording's `defaultDt` for frames without one.
+pub fn recording_length(recording: &str) -> Result<(u32, f64), String> {
+    let json = JsonValue::parse(recording).map_err(|e| format!("recording is not valid JSON: {e}"))?;
+    let frames = json.get("frames").and_then(JsonValue::as_array).ok_or("recording has no frames")?;
+    let default_dt = json.get("defaultDt").and_then(JsonValue::as_f64).unwrap_or(1.0 / 60.0);
+    let duration = frames
+        .iter()
+        .map(|f| f.get("dt").and_then(JsonValue::as_f64).unwrap_or(default_dt))
+        .sum();
+    Ok((frames.len() as u32, duration))
+}
+
+/// Fingerprint of a game's content: every file under `dir` except hidden
+/// entries (`.arcane/`, `.git/`, ...), `node_modules/`, `target/` and what
+/// the engine generates (replays, recordings, layer captures and frames
+/// from `arcane render`), so saving a replay, a save or a capture doesn't
+/// change it. 16 hex digits of FNV-1a over the sorted relative paths and
+/// file contents.
+pub fn content_hash(dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_content(dir, dir, &mut files)?;
+    files.sort();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+    for relative in files {
+        let contents = std::fs::read(dir.join(&relative))?;
+        feed(relative.as_bytes());
+        feed(&[0]);
+        feed(&(contents.len() as u64).to_le_bytes());
+        feed(&contents);
+    }
+    Ok(format!("{hash:016x}"))
+}
+
+fn collect_content(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name == "node_modules" || name == "target" {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !is_layer_capture(&path) {
+                collect_content(root, &path, files)?;
+            }
+        } else if file_type.is_file() && !is_generated_file(&path, &name) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn is_generated_file(path: &Path, name: &str) -> bool {
+    let generated = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| GENERATED_EXTENSIONS.iter().any(|g| ext.eq_ignore_ascii_case(g)));
+    // `frame_00000.png`, ...
+    let frame = name
+        .strip_prefix("frame_")
+        .and_then(|rest| rest.strip_suffix(".png"))
+        .is_some_and(|index| index.len() >= 5 && index.bytes().all(|b| b.is_ascii_digit()));
+    generated || frame
+}
+
+/// A folder of PNGs written by a layer capture, recognised by its manifest
+/// and merged image.
+fn is_layer_capture(dir: &Path) -> bool {
+    dir.join("manifest.json").is_file() && dir.join("merged.png").is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORDING: &str = r#"{"frames":[{"keysDown":[],"keysPressed":[],"mouseX":0,"mouseY":0},{"keysDown":["a"],"keysPressed":["a"],"mouseX":1,"mouseY":2,"dt":0.5}],"snapshots":[],"frameCount":2,"defaultDt":0.25}"#;
+
+    #[test]
+    fn test_round_trips_header_thumbnail_and_recording() {
+        let header = ReplayHeader::for_recording(RECORDING, "00ff", 1_700_000_000_000).unwrap();
+        assert_eq!((header.frames, header.duration), (2, 0.75));
+        let file = ReplayFile { header, thumbnail: Some(vec![0x89, b'P', b'N', b'G', b'\n']), recording: RECORDING.into() };
+        let bytes = file.encode();
+        assert!(bytes.starts_with(b"ARCANE-REPLAY 1\n{\"engine_version\""));
+        assert_eq!(ReplayFile::decode(&bytes).unwrap(), file);
+
+        let bare = ReplayFile { thumbnail: None, ..file };
+        assert_eq!(ReplayFile::decode(&bare.encode()).unwrap().thumbnail, None);
+    }
+
+    #[test]
+    fn test_rejects_other_files_and_formats() {
+        assert!(ReplayFile::decode(b"{\"frames\":[]}").unwrap_err().contains("not an Arcane replay"));
+        assert!(ReplayFile::decode(b"ARCANE-REPLAY 9\n{}\n").unwrap_err().contains("format 9"));
+        let header = ReplayHeader::for_recording(RECORDING, "00ff", 0).unwrap();
+        let mut bytes = ReplayFile { header, thumbnail: Some(vec![1; 64]), recording: String::new() }.encode();
+        bytes.truncate(bytes.len() - 1);
+        assert!(ReplayFile::decode(&bytes).unwrap_err().contains("truncated"));
+    }
+
+    #[test]
+    fn test_mismatch_names_what_differs() {
+        let header = ReplayHeader::for_recording(RECORDING, "aaaa", 0).unwrap();
+        assert_eq!(header.mismatch(ENGINE_VERSION, "aaaa"), None);
+        let both = header.mismatch("0.0.1", "bbbb").unwrap();
+        assert!(both.contains(&format!("recorded with engine {ENGINE_VERSION} but this is engine 0.0.1")));
+        assert!(both.contains("content hash aaaa, now bbbb"));
+        assert!(!header.mismatch(ENGINE_VERSION, "bbbb").unwrap().contains("engine 0.0.1"));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_hidden_dirs_and_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/game.ts"), "export const speed = 1;").unwrap();
+        let before = content_hash(dir.path()).unwrap();
+        std::fs::create_dir_all(dir.path().join(".arcane/saves")).unwrap();
+        std::fs::write(dir.path().join(".arcane/saves/slot.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("run.arcreplay"), "ARCANE-REPLAY 1\n").unwrap();
+        assert_eq!(content_hash(dir.path()).unwrap(), before);
+        std::fs::write(dir.path().join("src/game.ts"), "export const speed = 2;").unwrap();
+        assert_ne!(content_hash(dir.path()).unwrap(), before);
+    }
+
+    #[test]
+    fn test_content_hash_ignores_recordings_captures_and_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("game.ts"), "export const speed = 1;").unwrap();
+        std::fs::write(dir.path().join("hero.png"), [1, 2, 3]).unwrap();
+        let before = content_hash(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("clip.gif"), [4]).unwrap();
+        std::fs::write(dir.path().join("scene.ora"), [5]).unwrap();
+        std::fs::create_dir_all(dir.path().join("render")).unwrap();
+        std::fs::write(dir.path().join("render/frame_00000.png"), [6]).unwrap();
+        std::fs::create_dir_all(dir.path().join("capture")).unwrap();
+        for file in ["manifest.json", "merged.png", "layer_0.png"] {
+            std::fs::write(dir.path().join("capture").join(file), [7]).unwrap();
+        }
+        assert_eq!(content_hash(dir.path()).unwrap(), before);
+
+        std::fs::write(dir.path().join("hero.png"), [1, 2, 4]).unwrap();
+        assert_ne!(content_hash(dir.path()).unwrap(), before);
+    }
+}
@@ -0,0 +1,529 @@
+//! Save storage: where save files live, and keeping them in step with a
+//! second copy such as Steam Cloud or a game's own HTTP endpoint.
+//!
+//! A save is a JSON string under a key (letters, digits, `_` and `-`) with a
+//! modified time in Unix milliseconds. [`LocalDirBackend`] keeps saves as
+//! `<key>.json` files in `.arcane/saves/`; that copy is the one the game
+//! reads and writes. Remote backends implement [`SaveBackend`] in a plugin
+//! (see [`Plugin::save_backends`](crate::plugin::Plugin::save_backends)) and
+//! are attached with a [`SaveSync`], which reconciles the two copies key by
+//! key:
+//!
+//! - A save changed on one side only since the last sync is copied over,
+//!   keeping its modified time.
+//! - A save deleted on one side and unchanged on the other is deleted.
+//! - A save changed on both sides is a conflict. [`ConflictPolicy::LatestWins`]
+//!   keeps the newer copy (or the one that still exists);
+//!   [`ConflictPolicy::Prompt`] leaves both alone until the game picks one
+//!   with [`SaveSync::resolve`].
+//!
+//! Keys that aren't valid, such as a stray file copied into the save
+//! directory by hand, are skipped and listed in the [`SyncReport`]; a save
+//! that fails to copy is reported too, and the rest of the sync goes on.
+//!
+//! The modified time each key had after the last sync is kept in a manifest
+//! file next to the local saves, so changes and conflicts are detected
+//! across runs.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Whether `key` can name a save: non-empty, letters (any script), digits,
+/// `_` and `-`.
+pub fn valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The current time in Unix milliseconds.
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Where saves are stored. Errors are messages for the game's log; a
+/// backend that is offline should fail rather than block for long, since
+/// syncs run on the game thread.
+pub trait SaveBackend: Send + Sync {
+    /// Unique name, used to pick the backend from TypeScript.
+    fn name(&self) -> &str;
+
+    /// Key and modified time of every save, in any order.
+    fn list(&self) -> Result<Vec<(String, u64)>, String>;
+
+    /// A save's contents and modified time, or `None` if it doesn't exist.
+    fn read(&self, key: &str) -> Result<Option<(String, u64)>, String>;
+
+    /// Store a save with the given modified time, replacing any copy. The
+    /// time must be reported back unchanged by `list`, `read` and `modified`.
+    fn write(&self, key: &str, value: &str, modified: u64) -> Result<(), String>;
+
+    /// Delete a save. Deleting a save that doesn't exist is not an error.
+    fn remove(&self, key: &str) -> Result<(), String>;
+
+    /// A save's modified time without its contents. The default reads it.
+    fn modified(&self, key: &str) -> Result<Option<u64>, String> {
+        Ok(self.read(key)?.map(|(_, modified)| modified))
+    }
+}
+
+/// Saves as `<key>.json` files in a directory, with the file's modification
+/// time as the save's. Files not named after a valid key aren't saves and
+/// are left out of `list`.
+pub struct LocalDirBackend {
+    dir: PathBuf,
+}
+
+impl LocalDirBackend {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, String> {
+        if !valid_key(key) {
+            return Err(format!("invalid save key \"{key}\""));
+        }
+        Ok(self.dir.join(format!("{key}.json")))
+    }
+}
+
+fn modified_ms(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+impl SaveBackend for LocalDirBackend {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn list(&self) -> Result<Vec<(String, u64)>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("{}: {e}", self.dir.display())),
+        };
+        let mut saves = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            if !valid_key(key) {
+                continue;
+            }
+            if let Ok(metadata) = entry.metadata() {
+                saves.push((key.to_string(), modified_ms(&metadata)));
+            }
+        }
+        saves.sort();
+        Ok(saves)
+    }
+
+    fn read(&self, key: &str) -> Result<Option<(String, u64)>, String> {
+        let path = self.path(key)?;
+        match std::fs::read_to_string(&path) {
+            Ok(value) => {
+                let modified = std::fs::metadata(&path).map(|m| modified_ms(&m)).unwrap_or(0);
+                Ok(Some((value, modified)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {e}", path.display())),
+        }
+    }
+
+    fn write(&self, key: &str, value: &str, modified: u64) -> Result<(), String> {
+        let path = self.path(key)?;
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(&path, value))
+            .and_then(|()| {
+                let file = std::fs::File::options().write(true).open(&path)?;
+                file.set_modified(UNIX_EPOCH + Duration::from_millis(modified))
+            });
+        result.map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        let path = self.path(key)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("{}: {e}", path.display())),
+        }
+    }
+
+    fn modified(&self, key: &str) -> Result<Option<u64>, String> {
+        let path = self.path(key)?;
+        match std::fs::metadata(&path) {
+            Ok(metadata) => Ok(Some(modified_ms(&metadata))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {e}", path.display())),
+        }
+    }
+}
+
+/// What to do with a save changed on both sides since the last sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the copy modified last; a copy beats a deletion. Ties keep the local copy.
+    LatestWins,
+    /// Keep both until [`SaveSync::resolve`] picks one.
+    Prompt,
+}
+
+impl ConflictPolicy {
+    /// `"latest"` or `"prompt"`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "latest" => Some(Self::LatestWins),
+            "prompt" => Some(Self::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// A save changed on both sides. `None` is a deleted copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveConflict {
+    pub key: String,
+    pub local_modified: Option<u64>,
+    pub remote_modified: Option<u64>,
+}
+
+/// What a sync did, by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Copied from local to remote.
+    pub pushed: Vec<String>,
+    /// Copied from remote to local.
+    pub pulled: Vec<String>,
+    /// Deleted on one side because they were deleted on the other.
+    pub removed: Vec<String>,
+    /// Left alone until resolved (only with [`ConflictPolicy::Prompt`]).
+    pub conflicts: Vec<SaveConflict>,
+    /// Listed by a backend but not valid keys, so left alone.
+    pub skipped: Vec<String>,
+    /// Saves that couldn't be reconciled, with the error. They are retried
+    /// on the next sync.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Keeps local saves in step with a remote backend. See the module docs.
+pub struct SaveSync {
+    remote: Arc<dyn SaveBackend>,
+    policy: ConflictPolicy,
+    /// Where `base` is kept between runs.
+    manifest: PathBuf,
+    /// Modified time of each save after the last sync, on both sides.
+    base: BTreeMap<String, u64>,
+    /// Unresolved conflicts, by key.
+    conflicts: BTreeMap<String, SaveConflict>,
+}
+
+impl SaveSync {
+    /// Sync with `remote`, keeping the manifest at `manifest`. A missing or
+    /// unreadable manifest starts empty, so every save on both sides that
+    /// differs is treated as a conflict once.
+    pub fn new(remote: Arc<dyn SaveBackend>, policy: ConflictPolicy, manifest: impl Into<PathBuf>) -> Self {
+        let manifest = manifest.into();
+        let base = std::fs::read_to_string(&manifest)
+            .map(|text| {
+                text.lines()
+                    .filter_map(|line| {
+                        let (key, modified) = line.split_once(' ')?;
+                        Some((key.to_string(), modified.parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { remote, policy, manifest, base, conflicts: BTreeMap::new() }
+    }
+
+    pub fn remote_name(&self) -> &str {
+        self.remote.name()
+    }
+
+    pub fn policy(&self) -> ConflictPolicy {
+        self.policy
+    }
+
+    /// Conflicts waiting for [`SaveSync::resolve`], sorted by key.
+    pub fn conflicts(&self) -> Vec<SaveConflict> {
+        self.conflicts.values().cloned().collect()
+    }
+
+    /// Reconcile every save on both sides. Errors only if a side can't be
+    /// listed; a save that fails is reported and the others still sync.
+    pub fn sync(&mut self, local: &dyn SaveBackend) -> Result<SyncReport, String> {
+        let local_saves: BTreeMap<String, u64> = local.list()?.into_iter().collect();
+        let remote_saves: BTreeMap<String, u64> = self.remote.list()?.into_iter().collect();
+        let keys: BTreeSet<String> = local_saves
+            .keys()
+            .chain(remote_saves.keys())
+            .chain(self.base.keys())
+            .cloned()
+            .collect();
+        let mut report = SyncReport::default();
+        for key in keys {
+            if !valid_key(&key) {
+                report.skipped.push(key);
+                continue;
+            }
+            let (l, r) = (local_saves.get(&key).copied(), remote_saves.get(&key).copied());
+            if let Err(e) = self.reconcile(local, &key, l, r, &mut report) {
+                report.failed.push((key, e));
+            }
+        }
+        self.save_manifest()?;
+        Ok(report)
+    }
+
+    /// Reconcile one save, e.g. right after the game wrote or deleted it.
+    pub fn sync_key(&mut self, local: &dyn SaveBackend, key: &str) -> Result<SyncReport, String> {
+        let l = local.modified(key)?;
+        let r = self.remote.modified(key)?;
+        let mut report = SyncReport::default();
+        let result = self.reconcile(local, key, l, r, &mut report);
+        self.save_manifest()?;
+        result.map(|()| report)
+    }
+
+    /// Settle a conflict by keeping the local (`keep_local`) or the remote
+    /// copy on both sides. Errors if `key` has no pending conflict.
+    pub fn resolve(&mut self, local: &dyn SaveBackend, key: &str, keep_local: bool) -> Result<(), String> {
+        if !self.conflicts.contains_key(key) {
+            return Err(format!("save \"{key}\" has no conflict to resolve"));
+        }
+        let stamp = if keep_local {
+            copy(local, self.remote.as_ref(), key)?
+        } else {
+            copy(self.remote.as_ref(), local, key)?
+        };
+        self.conflicts.remove(key);
+        self.set_base(key, stamp);
+        self.save_manifest()
+    }
+
+    fn reconcile(
+        &mut self,
+        local: &dyn SaveBackend,
+        key: &str,
+        l: Option<u64>,
+        r: Option<u64>,
+        report: &mut SyncReport,
+    ) -> Result<(), String> {
+        let base = self.base.get(key).copied();
+        let (local_changed, remote_changed) = (l != base, r != base);
+        self.conflicts.remove(key);
+        if !local_changed && !remote_changed {
+            return Ok(());
+        }
+        let push = if local_changed && remote_changed {
+            if l == r {
+                // Same change on both sides
+                self.set_base(key, l);
+                return Ok(());
+            }
+            match self.policy {
+                ConflictPolicy::LatestWins => match (l, r) {
+                    (Some(l), Some(r)) => l >= r,
+                    (l, _) => l.is_some(),
+                },
+                ConflictPolicy::Prompt => {
+                    let conflict = SaveConflict { key: key.to_string(), local_modified: l, remote_modified: r };
+                    report.conflicts.push(conflict.clone());
+                    self.conflicts.insert(key.to_string(), conflict);
+                    return Ok(());
+                }
+            }
+        } else {
+            local_changed
+        };
+        let (stamp, deleted) = if push {
+            (copy(local, self.remote.as_ref(), key)?, l.is_none())
+        } else {
+            (copy(self.remote.as_ref(), local, key)?, r.is_none())
+        };
+        let list = match (deleted, push) {
+            (true, _) => &mut report.removed,
+            (false, true) => &mut report.pushed,
+            (false, false) => &mut report.pulled,
+        };
+        list.push(key.to_string());
+        self.set_base(key, stamp);
+        Ok(())
+    }
+
+    fn set_base(&mut self, key: &str, modified: Option<u64>) {
+        match modified {
+            Some(modified) => self.base.insert(key.to_string(), modified),
+            None => self.base.remove(key),
+        };
+    }
+
+    fn save_manifest(&self) -> Result<(), String> {
+        let text: String = self.base.iter().map(|(key, modified)| format!("{key} {modified}\n")).collect();
+        if let Some(dir) = self.manifest.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        std::fs::write(&self.manifest, text).map_err(|e| format!("{}: {e}", self.manifest.display()))
+    }
+}
+
+/// Make `to`'s copy of `key` match `from`'s, deleting it if `from` has none.
+/// Returns the modified time both now share.
+fn copy(from: &dyn SaveBackend, to: &dyn SaveBackend, key: &str) -> Result<Option<u64>, String> {
+    match from.read(key)? {
+        Some((value, modified)) => {
+            to.write(key, &value, modified)?;
+            Ok(Some(modified))
+        }
+        None => {
+            to.remove(key)?;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backends() -> (tempfile::TempDir, LocalDirBackend, Arc<LocalDirBackend>, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let local = LocalDirBackend::new(dir.path().join("saves"));
+        let remote = Arc::new(LocalDirBackend::new(dir.path().join("cloud")));
+        let manifest = dir.path().join("saves").join(".sync-cloud");
+        (dir, local, remote, manifest)
+    }
+
+    #[test]
+    fn test_local_dir_round_trips_contents_and_modified_time() {
+        let (_dir, local, _, _) = backends();
+        assert_eq!(local.list().unwrap(), vec![]);
+        local.write("slot_1", "{\"hp\":3}", 1_700_000_000_123).unwrap();
+        assert_eq!(local.read("slot_1").unwrap(), Some(("{\"hp\":3}".to_string(), 1_700_000_000_123)));
+        assert_eq!(local.list().unwrap(), vec![("slot_1".to_string(), 1_700_000_000_123)]);
+        assert!(local.write("../escape", "{}", 0).is_err());
+        local.remove("slot_1").unwrap();
+        local.remove("slot_1").unwrap();
+        assert_eq!(local.read("slot_1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_one_sided_changes_are_copied_and_deletions_follow() {
+        let (_dir, local, remote, manifest) = backends();
+        local.write("a", "local a", 1000).unwrap();
+        remote.write("b", "remote b", 2000).unwrap();
+        let mut sync = SaveSync::new(remote.clone(), ConflictPolicy::Prompt, &manifest);
+        let report = sync.sync(&local).unwrap();
+        assert_eq!((report.pushed, report.pulled), (vec!["a".to_string()], vec!["b".to_string()]));
+        assert_eq!(remote.read("a").unwrap(), Some(("local a".to_string(), 1000)));
+        assert_eq!(local.read("b").unwrap(), Some(("remote b".to_string(), 2000)));
+
+        // A fresh sync reads the manifest: deleting locally deletes remotely
+        local.remove("a").unwrap();
+        let mut sync = SaveSync::new(remote.clone(), ConflictPolicy::Prompt, &manifest);
+        assert_eq!(sync.sync_key(&local, "a").unwrap().removed, vec!["a".to_string()]);
+        assert_eq!(remote.read("a").unwrap(), None);
+        assert_eq!(sync.sync(&local).unwrap(), SyncReport::default());
+    }
+
+    #[test]
+    fn test_latest_wins_keeps_the_newer_copy() {
+        let (_dir, local, remote, manifest) = backends();
+        local.write("slot", "v1", 1000).unwrap();
+        let mut sync = SaveSync::new(remote.clone(), ConflictPolicy::LatestWins, &manifest);
+        sync.sync(&local).unwrap();
+        local.write("slot", "played offline", 3000).unwrap();
+        remote.write("slot", "played on the other machine", 2000).unwrap();
+        let report = sync.sync(&local).unwrap();
+        assert_eq!(report.pushed, vec!["slot".to_string()]);
+        assert_eq!(remote.read("slot").unwrap().unwrap().0, "played offline");
+
+        // A copy beats a deletion
+        local.remove("slot").unwrap();
+        remote.write("slot", "newer remote", 4000).unwrap();
+        assert_eq!(sync.sync(&local).unwrap().pulled, vec!["slot".to_string()]);
+        assert_eq!(local.read("slot").unwrap(), Some(("newer remote".to_string(), 4000)));
+    }
+
+    #[test]
+    fn test_prompt_holds_conflicts_until_resolved() {
+        let (_dir, local, remote, manifest) = backends();
+        local.write("slot", "mine", 1000).unwrap();
+        remote.write("slot", "theirs", 2000).unwrap();
+        let mut sync = SaveSync::new(remote.clone(), ConflictPolicy::Prompt, &manifest);
+        let report = sync.sync(&local).unwrap();
+        let conflict = SaveConflict { key: "slot".into(), local_modified: Some(1000), remote_modified: Some(2000) };
+        assert_eq!(report.conflicts, vec![conflict.clone()]);
+        assert_eq!(sync.conflicts(), vec![conflict]);
+        assert_eq!(local.read("slot").unwrap().unwrap().0, "mine");
+        assert_eq!(remote.read("slot").unwrap().unwrap().0, "theirs");
+
+        assert!(sync.resolve(&local, "other", true).is_err());
+        sync.resolve(&local, "slot", true).unwrap();
+        assert_eq!(remote.read("slot").unwrap(), Some(("mine".to_string(), 1000)));
+        assert!(sync.conflicts().is_empty());
+        assert_eq!(sync.sync(&local).unwrap(), SyncReport::default());
+    }
+
+    #[test]
+    fn test_stray_files_are_skipped_without_stopping_the_sync() {
+        let (_dir, local, remote, manifest) = backends();
+        local.write("slot", "mine", 1000).unwrap();
+        local.write("sauvegarde_é", "unicode key", 1000).unwrap();
+        std::fs::write(local.dir().join("my save.json"), "{}").unwrap();
+        assert_eq!(
+            local.list().unwrap(),
+            vec![("sauvegarde_é".to_string(), 1000), ("slot".to_string(), 1000)]
+        );
+
+        // A remote backend can list keys that aren't valid too
+        struct Listing(Arc<LocalDirBackend>);
+        impl SaveBackend for Listing {
+            fn name(&self) -> &str {
+                "listing"
+            }
+            fn list(&self) -> Result<Vec<(String, u64)>, String> {
+                Ok(vec![("my save".to_string(), 500)])
+            }
+            fn read(&self, key: &str) -> Result<Option<(String, u64)>, String> {
+                self.0.read(key)
+            }
+            fn write(&self, key: &str, value: &str, modified: u64) -> Result<(), String> {
+                self.0.write(key, value, modified)
+            }
+            fn remove(&self, key: &str) -> Result<(), String> {
+                self.0.remove(key)
+            }
+        }
+        let mut sync = SaveSync::new(Arc::new(Listing(remote.clone())), ConflictPolicy::Prompt, &manifest);
+        let report = sync.sync(&local).unwrap();
+        assert_eq!(report.skipped, vec!["my save".to_string()]);
+        assert_eq!(report.pushed, vec!["sauvegarde_é".to_string(), "slot".to_string()]);
+        assert_eq!(remote.read("slot").unwrap(), Some(("mine".to_string(), 1000)));
+    }
+
+    #[test]
+    fn test_a_failing_save_does_not_stop_the_others() {
+        let (_dir, local, remote, manifest) = backends();
+        local.write("a", "first", 1000).unwrap();
+        local.write("b", "second", 1000).unwrap();
+        // "a" can't be written remotely: a directory is in its way
+        std::fs::create_dir_all(remote.dir().join("a.json")).unwrap();
+        let mut sync = SaveSync::new(remote.clone(), ConflictPolicy::LatestWins, &manifest);
+        let report = sync.sync(&local).unwrap();
+        assert_eq!(report.failed.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(report.pushed, vec!["b".to_string()]);
+        assert_eq!(remote.read("b").unwrap(), Some(("second".to_string(), 1000)));
+    }
+}
@@ -0,0 +1,141 @@
+//! Reload-stable asset bookkeeping.
+//!
+//! Texture, sound and shader file IDs are assigned once per path and survive
+//! hot reloads (the GPU stores and audio thread keep their data across reloads).
+//! Each file-backed asset records a cheap disk fingerprint (mtime + size) so a
+//! reload, or an asset file change picked up by the dev watcher, can re-upload
+//! only the files that changed, under their existing IDs.
+
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+
+/// How an asset was loaded, so it can be re-queued identically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Texture,
+    TextureLinear,
+    Sound,
+    /// WGSL source of a custom shader (`op_create_shader_from_file`).
+    Shader,
+}
+
+/// A file-backed asset with its ID and last seen fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackedAsset {
+    pub id: u32,
+    pub kind: AssetKind,
+    pub fingerprint: Option<u64>,
+}
+
+/// Tracks file-backed assets by (kind, resolved path).
+#[derive(Debug, Clone, Default)]
+pub struct AssetTracker {
+    assets: HashMap<(AssetKind, String), TrackedAsset>,
+}
+
+impl AssetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a newly queued asset and its current fingerprint.
+    pub fn track(&mut self, kind: AssetKind, path: &str, id: u32) {
+        self.assets.insert(
+            (kind, path.to_string()),
+            TrackedAsset { id, kind, fingerprint: file_fingerprint(path) },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Re-fingerprint every tracked file and return those that changed on disk
+    /// (sorted by ID for a stable reload order). Missing files are left alone so
+    /// the last good upload stays in use.
+    pub fn take_changed(&mut self) -> Vec<(String, TrackedAsset)> {
+        let mut changed = Vec::new();
+        for ((_, path), asset) in self.assets.iter_mut() {
+            let current = file_fingerprint(path);
+            if current.is_some() && current != asset.fingerprint {
+                asset.fingerprint = current;
+                changed.push((path.clone(), *asset));
+            }
+        }
+        changed.sort_by_key(|(_, a)| a.id);
+        changed
+    }
+}
+
+/// Cheap content fingerprint: modification time and size. None if the file is unreadable.
+pub fn file_fingerprint(path: &str) -> Option<u64> {
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((modified.as_nanos() as u64) ^ meta.len().rotate_left(32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("arcane_asset_cache_{}_{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_unchanged_files_are_not_reported() {
+        let path = temp_file("same.png", b"abc");
+        let mut tracker = AssetTracker::new();
+        tracker.track(AssetKind::Texture, &path, 3);
+        assert!(tracker.take_changed().is_empty());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_changed_file_is_reported_once_with_same_id() {
+        let path = temp_file("changed.ogg", b"abc");
+        let mut tracker = AssetTracker::new();
+        tracker.track(AssetKind::Sound, &path, 7);
+        std::fs::write(&path, b"abcdef").unwrap();
+
+        let changed = tracker.take_changed();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, path);
+        assert_eq!((changed[0].1.id, changed[0].1.kind), (7, AssetKind::Sound));
+        assert!(tracker.take_changed().is_empty());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_file_created_after_tracking_is_reported() {
+        let path = std::env::temp_dir()
+            .join(format!("arcane_asset_cache_{}_late.wgsl", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let mut tracker = AssetTracker::new();
+        tracker.track(AssetKind::Shader, &path, 2);
+        assert!(tracker.take_changed().is_empty());
+
+        std::fs::write(&path, b"@fragment fn fs_main() {}").unwrap();
+        let changed = tracker.take_changed();
+        assert_eq!(changed.len(), 1);
+        assert_eq!((changed[0].1.id, changed[0].1.kind), (2, AssetKind::Shader));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_missing_file_keeps_last_upload() {
+        let path = temp_file("gone.png", b"abc");
+        let mut tracker = AssetTracker::new();
+        tracker.track(AssetKind::TextureLinear, &path, 1);
+        std::fs::remove_file(&path).unwrap();
+        assert!(tracker.take_changed().is_empty());
+        assert_eq!(tracker.len(), 1);
+    }
+}
@@ -0,0 +1,116 @@
+//! Color math ops: run the conversions in `crate::color` for TS.
+//!
+//! Single colors go in as separate channels and come back as `[c0, c1, c2]`
+//! (or `[r, g, b, a]` for mixes). Ramp stops go in as a packed RGBA
+//! Float32Array. Available in headless runs too.
+
+use crate::color;
+
+/// Unpack an RGBA Float32Array into colors, ignoring a trailing partial color.
+fn unpack_rgba(data: &[u8]) -> Vec<[f32; 4]> {
+    data.chunks_exact(16)
+        .map(|b| std::array::from_fn(|i| f32::from_le_bytes([b[i * 4], b[i * 4 + 1], b[i * 4 + 2], b[i * 4 + 3]])))
+        .collect()
+}
+
+fn to_f64(values: impl IntoIterator<Item = f32>) -> Vec<f64> {
+    values.into_iter().map(|v| v as f64).collect()
+}
+
+/// Decode sRGB channels to linear light.
+#[deno_core::op2]
+#[serde]
+fn op_srgb_to_linear(r: f64, g: f64, b: f64) -> Vec<f64> {
+    to_f64([r, g, b].map(|c| color::srgb_to_linear(c as f32)))
+}
+
+/// Encode linear-light channels as sRGB.
+#[deno_core::op2]
+#[serde]
+fn op_linear_to_srgb(r: f64, g: f64, b: f64) -> Vec<f64> {
+    to_f64([r, g, b].map(|c| color::linear_to_srgb(c as f32)))
+}
+
+/// RGB to `[hue°, saturation, value]`.
+#[deno_core::op2]
+#[serde]
+fn op_rgb_to_hsv(r: f64, g: f64, b: f64) -> Vec<f64> {
+    to_f64(color::rgb_to_hsv([r as f32, g as f32, b as f32]))
+}
+
+/// HSV (hue in degrees) to RGB.
+#[deno_core::op2]
+#[serde]
+fn op_hsv_to_rgb(h: f64, s: f64, v: f64) -> Vec<f64> {
+    to_f64(color::hsv_to_rgb([h as f32, s as f32, v as f32]))
+}
+
+/// sRGB to `[lightness, chroma, hue°]` in OKLCH.
+#[deno_core::op2]
+#[serde]
+fn op_rgb_to_oklch(r: f64, g: f64, b: f64) -> Vec<f64> {
+    to_f64(color::srgb_to_oklch([r as f32, g as f32, b as f32]))
+}
+
+/// OKLCH to sRGB, reducing chroma for colors outside the sRGB gamut.
+#[deno_core::op2]
+#[serde]
+fn op_oklch_to_rgb(l: f64, c: f64, h: f64) -> Vec<f64> {
+    to_f64(color::oklch_to_srgb([l as f32, c as f32, h as f32]))
+}
+
+/// Mix two RGBA colors in OKLab; returns `[r, g, b, a]`.
+#[deno_core::op2]
+#[serde]
+fn op_mix_oklab(
+    r1: f64, g1: f64, b1: f64, a1: f64,
+    r2: f64, g2: f64, b2: f64, a2: f64,
+    t: f64,
+) -> Vec<f64> {
+    let from = [r1, g1, b1, a1].map(|c| c as f32);
+    let to = [r2, g2, b2, a2].map(|c| c as f32);
+    to_f64(color::mix_oklab(from, to, t as f32))
+}
+
+/// `count` colors along the packed RGBA stops, as packed RGBA.
+#[deno_core::op2]
+#[serde]
+fn op_color_ramp(#[buffer] stops: &[u8], count: u32) -> Vec<f64> {
+    let stops = unpack_rgba(stops);
+    to_f64(color::ramp(&stops, count as usize).into_iter().flatten())
+}
+
+/// Cosine palette `[a, b, c, d]` (12 floats, RGB each) fitted to the packed RGBA stops.
+#[deno_core::op2]
+#[serde]
+fn op_fit_cosine_palette(#[buffer] stops: &[u8]) -> Vec<f64> {
+    let stops = unpack_rgba(stops);
+    to_f64(color::fit_cosine_palette(&stops).into_iter().flatten())
+}
+
+deno_core::extension!(
+    color_ext,
+    ops = [
+        op_srgb_to_linear,
+        op_linear_to_srgb,
+        op_rgb_to_hsv,
+        op_hsv_to_rgb,
+        op_rgb_to_oklch,
+        op_oklch_to_rgb,
+        op_mix_oklab,
+        op_color_ramp,
+        op_fit_cosine_palette,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpack_rgba_drops_partial_colors() {
+        let floats = [0.1f32, 0.2, 0.3, 1.0, 0.5, 0.5];
+        let bytes: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+        assert_eq!(unpack_rgba(&bytes), vec![[0.1, 0.2, 0.3, 1.0]]);
+    }
+}
@@ -0,0 +1,80 @@
+//! Easing curve ops: define curves once, then pass their IDs to tweens, light
+//! pulses, audio fades and camera moves.
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const snappy = defineBezierCurve(0.2, 0.9, 0.3, 1.2); // overshoots a little
+//! const slow = defineEasingCurve("easeInOutSine");
+//! tween(panel, { y: 0 }, 0.4, { easing: snappy });
+//! fadeOutInstance(music, 2, slow);
+//! addPointLight(x, y, 120, 1, 0.6, 0.2, 1, { pulse: 0.5, pulseCurve: slow });
+//! ```
+//!
+//! ## Design
+//! - Curve math lives in `crate::curve` (pure, testable).
+//! - Ops that take a curve ID copy the curve out of the store, so the audio
+//!   thread and renderer get plain values. ID 0 means linear (or the op's
+//!   built-in shape).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use crate::curve::{Curve, CurveStore, Easing};
+
+/// The curve with this ID, or None for 0 and unknown IDs.
+pub fn curve(state: &OpState, id: u32) -> Option<Curve> {
+    if id == 0 {
+        return None;
+    }
+    let store = state.borrow::<Rc<RefCell<CurveStore>>>();
+    store.borrow().get(id)
+}
+
+/// The curve with this ID, or linear for 0 and unknown IDs.
+pub fn resolve_curve(state: &OpState, id: u32) -> Curve {
+    curve(state, id).unwrap_or_default()
+}
+
+fn define(state: &mut OpState, curve: Curve) -> u32 {
+    let store = state.borrow_mut::<Rc<RefCell<CurveStore>>>();
+    store.borrow_mut().define(curve)
+}
+
+/// Define a preset easing by its TS name ("easeOutQuad", ...). Returns the
+/// curve ID, or 0 for unknown names.
+#[deno_core::op2(fast)]
+fn op_define_easing_curve(state: &mut OpState, #[string] name: &str) -> u32 {
+    match Easing::from_name(name) {
+        Some(easing) => define(state, Curve::Easing(easing)),
+        None => 0,
+    }
+}
+
+/// Define a cubic bezier easing (CSS `cubic-bezier(x1, y1, x2, y2)`).
+/// Returns the curve ID.
+#[deno_core::op2(fast)]
+fn op_define_bezier_curve(state: &mut OpState, x1: f64, y1: f64, x2: f64, y2: f64) -> u32 {
+    define(state, Curve::bezier(x1 as f32, y1 as f32, x2 as f32, y2 as f32))
+}
+
+/// Sample a curve at every `t` in a packed Float32Array. Unknown IDs sample
+/// as linear.
+#[deno_core::op2]
+#[serde]
+fn op_curve_sample(state: &mut OpState, id: u32, #[buffer] ts: &[u8]) -> Vec<f64> {
+    let curve = resolve_curve(state, id);
+    ts.chunks_exact(4)
+        .map(|b| curve.sample(f32::from_le_bytes([b[0], b[1], b[2], b[3]])) as f64)
+        .collect()
+}
+
+deno_core::extension!(
+    curve_ext,
+    ops = [
+        op_define_easing_curve,
+        op_define_bezier_curve,
+        op_curve_sample,
+    ],
+);
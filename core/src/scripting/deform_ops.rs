@@ -0,0 +1,229 @@
+//! Deformable sprite grid ops: squash & stretch, jelly wobble and cloth-like
+//! flags drawn as textured meshes through the geometry pipeline.
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const jelly = createDeformGrid(6, 6, 64, 64);
+//! deformSquash(jelly, 1.3, 0.7);                  // landing squash, springs rebound
+//! onFrame(() => {
+//!   stepDeformGrid(jelly, getDeltaTime());
+//!   drawDeformGrid(jelly, tex, x, y, { layer: 5 });
+//! });
+//! setDeformOffsets(jelly, offsets);                // or drive vertices directly
+//! ```
+//!
+//! ## Design
+//! - Simulation lives in `crate::animation::deform` (pure, testable).
+//! - Drawing pushes a `GeoCommand::Mesh` into `GeoState`, so grids sort by layer
+//!   with other geometry and sample the sprite's texture.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use super::geometry_ops::{queue_geo_commands, GeoCommand};
+use crate::animation::deform::{DeformGrid, SpringConfig};
+
+/// Live deform grids keyed by id.
+pub struct DeformState {
+    pub grids: HashMap<u32, DeformGrid>,
+    pub next_id: u32,
+}
+
+impl Default for DeformState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeformState {
+    pub fn new() -> Self {
+        Self { grids: HashMap::new(), next_id: 1 }
+    }
+
+    /// Build the geometry command for a grid drawn at (x, y).
+    pub fn mesh_command(&self, id: u32, texture_id: u32, x: f32, y: f32, layer: i32, opacity: f32) -> Option<GeoCommand> {
+        let grid = self.grids.get(&id)?;
+        let (vertices, indices) = grid.mesh(x, y);
+        Some(GeoCommand::Mesh {
+            texture_id,
+            vertices,
+            indices,
+            r: 1.0, g: 1.0, b: 1.0, a: opacity,
+            layer,
+        })
+    }
+}
+
+/// Create a grid of cols × rows cells covering width × height pixels.
+#[deno_core::op2(fast)]
+fn op_create_deform_grid(state: &mut OpState, cols: u32, rows: u32, width: f64, height: f64) -> u32 {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    let mut ds = ds.borrow_mut();
+    let id = ds.next_id;
+    ds.next_id += 1;
+    ds.grids.insert(id, DeformGrid::new(cols as usize, rows as usize, width as f32, height as f32));
+    id
+}
+
+#[deno_core::op2(fast)]
+fn op_destroy_deform_grid(state: &mut OpState, id: u32) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    ds.borrow_mut().grids.remove(&id);
+}
+
+/// Overwrite vertex offsets from a packed Float32Array of (dx, dy) pairs, row-major.
+#[deno_core::op2(fast)]
+fn op_set_deform_offsets(state: &mut OpState, id: u32, #[buffer] data: &[u8]) {
+    let floats: Vec<f32> = data
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.set_offsets(&floats);
+    }
+}
+
+/// Current offsets as packed (dx, dy) pairs, or empty for unknown grids.
+#[deno_core::op2]
+#[serde]
+fn op_get_deform_offsets(state: &mut OpState, id: u32) -> Vec<f64> {
+    let ds = state.borrow::<Rc<RefCell<DeformState>>>();
+    let ds = ds.borrow();
+    let Some(grid) = ds.grids.get(&id) else { return vec![] };
+    grid.offsets.iter().flat_map(|o| [o[0] as f64, o[1] as f64]).collect()
+}
+
+#[deno_core::op2(fast)]
+fn op_set_deform_springs(state: &mut OpState, id: u32, stiffness: f64, coupling: f64, damping: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.springs = SpringConfig {
+            stiffness: stiffness.max(0.0) as f32,
+            coupling: coupling.max(0.0) as f32,
+            damping: damping.max(0.0) as f32,
+        };
+    }
+}
+
+/// Constant acceleration on free vertices (gravity, wind).
+#[deno_core::op2(fast)]
+fn op_set_deform_force(state: &mut OpState, id: u32, fx: f64, fy: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.force = [fx as f32, fy as f32];
+    }
+}
+
+#[deno_core::op2(fast)]
+fn op_pin_deform_vertex(state: &mut OpState, id: u32, col: u32, row: u32, pinned: bool) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.set_pinned(col as usize, row as usize, pinned);
+    }
+}
+
+/// Add velocity (vx, vy) around a local point with linear falloff over radius.
+#[deno_core::op2(fast)]
+fn op_deform_impulse(state: &mut OpState, id: u32, x: f64, y: f64, radius: f64, vx: f64, vy: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.apply_impulse(x as f32, y as f32, radius as f32, vx as f32, vy as f32);
+    }
+}
+
+/// Scale the grid around a normalized anchor; springs pull it back to rest.
+#[deno_core::op2(fast)]
+fn op_deform_squash(state: &mut OpState, id: u32, sx: f64, sy: f64, anchor_x: f64, anchor_y: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.squash(sx as f32, sy as f32, anchor_x as f32, anchor_y as f32);
+    }
+}
+
+#[deno_core::op2(fast)]
+fn op_reset_deform_grid(state: &mut OpState, id: u32) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.reset();
+    }
+}
+
+/// Advance the spring simulation by dt seconds.
+#[deno_core::op2(fast)]
+fn op_step_deform_grid(state: &mut OpState, id: u32, dt: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.step(dt as f32);
+    }
+}
+
+/// Set the texture region (0-1 UVs) mapped across the grid. Pass uv_w = 0 for the whole texture.
+#[deno_core::op2(fast)]
+fn op_set_deform_uv(state: &mut OpState, id: u32, uv_x: f64, uv_y: f64, uv_w: f64, uv_h: f64) {
+    let ds = state.borrow_mut::<Rc<RefCell<DeformState>>>();
+    if let Some(grid) = ds.borrow_mut().grids.get_mut(&id) {
+        grid.uv = if uv_w > 0.0 {
+            [uv_x as f32, uv_y as f32, uv_w as f32, uv_h as f32]
+        } else {
+            [0.0, 0.0, 1.0, 1.0]
+        };
+    }
+}
+
+/// Queue the grid as a textured mesh with its top-left corner at (x, y).
+#[deno_core::op2(fast)]
+fn op_draw_deform_grid(state: &mut OpState, id: u32, texture_id: u32, x: f64, y: f64, layer: i32, opacity: f64) {
+    let cmd = {
+        let ds = state.borrow::<Rc<RefCell<DeformState>>>();
+        ds.borrow().mesh_command(id, texture_id, x as f32, y as f32, layer, opacity as f32)
+    };
+    if let Some(cmd) = cmd {
+        queue_geo_commands(state, [cmd]);
+    }
+}
+
+deno_core::extension!(
+    deform_ext,
+    ops = [
+        op_create_deform_grid,
+        op_destroy_deform_grid,
+        op_set_deform_offsets,
+        op_get_deform_offsets,
+        op_set_deform_springs,
+        op_set_deform_force,
+        op_pin_deform_vertex,
+        op_deform_impulse,
+        op_deform_squash,
+        op_reset_deform_grid,
+        op_step_deform_grid,
+        op_set_deform_uv,
+        op_draw_deform_grid,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mesh_command_for_grid() {
+        let mut ds = DeformState::new();
+        ds.grids.insert(1, DeformGrid::new(2, 2, 32.0, 32.0));
+        let Some(GeoCommand::Mesh { texture_id, vertices, indices, a, layer, .. }) =
+            ds.mesh_command(1, 9, 10.0, 20.0, 4, 0.5)
+        else {
+            panic!("expected mesh command");
+        };
+        assert_eq!(texture_id, 9);
+        assert_eq!(layer, 4);
+        assert_eq!(a, 0.5);
+        assert_eq!(vertices.len(), 9 * 4);
+        assert_eq!(indices.len(), 4 * 6);
+        assert_eq!(&vertices[0..2], &[10.0, 20.0]);
+        assert!(ds.mesh_command(2, 9, 0.0, 0.0, 0, 1.0).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! Engine version and capability report.
+//!
+//! `op_get_engine_info` returns a JSON description of this build and the
+//! device it runs on, so TS code can feature-detect (e.g. skip lighting on a
+//! headless test run) instead of calling ops that do not exist.
+//!
+//! Output shape:
+//! `{"version","platform":{"os","arch"},"features":[...],"audio":{"backend"}|null,`
+//! `"gpu":{"name","backend","deviceType","driver"}|null,"limits":{"maxTextureSize","maxLights"}}`
+//!
+//! Features reflect what this runtime can actually do: a headless runtime
+//! (`arcane test`) reports no features even when the renderer is compiled in.
+//! `gpu` is null until the renderer has created its device (first frame) and in
+//! headless runs; `maxTextureSize` is 0 in that case.
+
+use deno_core::OpState;
+
+use crate::json::escape;
+
+/// GPU adapter details, captured once the renderer has a device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuInfo {
+    pub name: String,
+    pub backend: String,
+    pub device_type: String,
+    pub driver: String,
+    pub max_texture_size: u32,
+    /// Point light capacity the renderer was created with.
+    pub max_lights: u32,
+}
+
+/// Everything `op_get_engine_info` reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineInfo {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// Available engine features ("renderer", "audio", "gamepad").
+    pub features: Vec<&'static str>,
+    pub audio_backend: Option<&'static str>,
+    pub gpu: Option<GpuInfo>,
+    pub max_lights: u32,
+}
+
+impl EngineInfo {
+    /// Describe this build. `windowed` is true for runtimes with the render
+    /// bridge (`arcane dev`); `gpu` comes from the live renderer, if any.
+    pub fn current(windowed: bool, gpu: Option<GpuInfo>) -> Self {
+        let renderer = windowed && cfg!(feature = "renderer");
+        let features = if renderer { vec!["renderer", "audio", "gamepad"] } else { Vec::new() };
+        // Before the renderer exists, report the default capacity
+        #[cfg(feature = "renderer")]
+        let default_lights = if renderer { crate::renderer::MAX_LIGHTS as u32 } else { 0 };
+        #[cfg(not(feature = "renderer"))]
+        let default_lights = 0;
+        let max_lights = gpu.as_ref().map_or(default_lights, |g| g.max_lights);
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            features,
+            audio_backend: renderer.then_some("rodio"),
+            gpu,
+            max_lights,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let features: Vec<String> = self.features.iter().map(|f| format!("\"{f}\"")).collect();
+        let audio = match self.audio_backend {
+            Some(backend) => format!("{{\"backend\":\"{backend}\"}}"),
+            None => "null".to_string(),
+        };
+        let gpu = match self.gpu {
+            Some(ref g) => format!(
+                "{{\"name\":\"{}\",\"backend\":\"{}\",\"deviceType\":\"{}\",\"driver\":\"{}\"}}",
+                escape(&g.name),
+                escape(&g.backend),
+                escape(&g.device_type),
+                escape(&g.driver),
+            ),
+            None => "null".to_string(),
+        };
+        let max_texture_size = self.gpu.as_ref().map_or(0, |g| g.max_texture_size);
+        format!(
+            "{{\"version\":\"{}\",\"platform\":{{\"os\":\"{}\",\"arch\":\"{}\"}},\"features\":[{}],\"audio\":{},\"gpu\":{},\"limits\":{{\"maxTextureSize\":{},\"maxLights\":{}}}}}",
+            self.version,
+            self.os,
+            self.arch,
+            features.join(","),
+            audio,
+            gpu,
+            max_texture_size,
+            self.max_lights,
+        )
+    }
+}
+
+/// Engine version, features, GPU adapter and limits as JSON (see module docs).
+#[deno_core::op2]
+#[string]
+fn op_get_engine_info(state: &mut OpState) -> String {
+    #[cfg(feature = "renderer")]
+    {
+        use super::render_ops::RenderBridgeState;
+        let bridge = state.try_borrow::<std::rc::Rc<std::cell::RefCell<RenderBridgeState>>>();
+        let gpu = bridge.and_then(|b| b.borrow().gpu_info.clone());
+        EngineInfo::current(bridge.is_some(), gpu).to_json()
+    }
+    #[cfg(not(feature = "renderer"))]
+    {
+        let _ = state;
+        EngineInfo::current(false, None).to_json()
+    }
+}
+
+deno_core::extension!(
+    engine_ext,
+    ops = [op_get_engine_info],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JsonValue;
+
+    #[test]
+    fn test_engine_info_json_parses_without_gpu() {
+        let json = EngineInfo::current(false, None).to_json();
+        let v = JsonValue::parse(&json).unwrap();
+        assert_eq!(v.get("version").and_then(|v| v.as_str()), Some(env!("CARGO_PKG_VERSION")));
+        assert_eq!(
+            v.get("platform").and_then(|p| p.get("os")).and_then(|v| v.as_str()),
+            Some(std::env::consts::OS)
+        );
+        assert_eq!(v.get("gpu"), Some(&JsonValue::Null));
+        assert_eq!(v.get("features").and_then(|f| f.as_array()).map(|f| f.len()), Some(0));
+        let limits = v.get("limits").unwrap();
+        assert_eq!(limits.get("maxTextureSize").and_then(|v| v.as_f64()), Some(0.0));
+    }
+
+    #[test]
+    fn test_engine_info_json_escapes_gpu_strings() {
+        let gpu = GpuInfo {
+            name: "Fake \"GPU\"".into(),
+            backend: "Vulkan".into(),
+            device_type: "DiscreteGpu".into(),
+            driver: "1.0\\beta".into(),
+            max_texture_size: 8192,
+            max_lights: 64,
+        };
+        let json = EngineInfo::current(true, Some(gpu)).to_json();
+        let v = JsonValue::parse(&json).unwrap();
+        let g = v.get("gpu").unwrap();
+        assert_eq!(g.get("name").and_then(|v| v.as_str()), Some("Fake \"GPU\""));
+        assert_eq!(g.get("driver").and_then(|v| v.as_str()), Some("1.0\\beta"));
+        let limits = v.get("limits").unwrap();
+        assert_eq!(limits.get("maxTextureSize").and_then(|v| v.as_f64()), Some(8192.0));
+        assert_eq!(limits.get("maxLights").and_then(|v| v.as_f64()), Some(64.0));
+    }
+}
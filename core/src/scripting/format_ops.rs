@@ -0,0 +1,29 @@
+//! Number and duration formatting ops over `crate::format`, so score
+//! displays and timers don't depend on the ICU data compiled into V8.
+//! Available in headless runs too.
+
+use crate::format::{self, DurationStyle, NumberFormat};
+
+/// Format a number for a locale tag ("en-US", "de"). `options` is a JSON
+/// object of `Intl.NumberFormat`-style options; "" or "{}" for defaults.
+#[deno_core::op2]
+#[string]
+fn op_format_number(value: f64, #[string] locale: &str, #[string] options: &str) -> String {
+    format::format_number(value, format::find_locale(locale), &NumberFormat::from_json(options))
+}
+
+/// Format a duration in milliseconds as "clock", "timer" or "short".
+/// Unknown styles format as "clock".
+#[deno_core::op2]
+#[string]
+fn op_format_duration(ms: f64, #[string] style: &str) -> String {
+    format::format_duration(ms, DurationStyle::from_name(style).unwrap_or(DurationStyle::Clock))
+}
+
+deno_core::extension!(
+    format_ext,
+    ops = [
+        op_format_number,
+        op_format_duration,
+    ],
+);
@@ -6,12 +6,26 @@
 /// ## Command format
 /// Each GeoCommand is a tagged enum collected into GeoState.commands per frame.
 /// The frame callback in dev.rs drains GeoState and passes to GeometryBatch::flush().
+/// While a render target is active, commands go to its queue in `TargetState` instead,
+/// and while a mask is being recorded (`mask_ops`), they shape the mask.
+///
+/// ## Custom meshes
+/// `op_geo_upload_mesh` keeps a mesh (packed vertices with per-vertex UVs and
+/// colors, plus triangle indices) in `GeoState::meshes`; `op_geo_draw_mesh`
+/// then queues it with a transform, tint and layer in one call. Commands share
+/// the uploaded data, and the renderer transforms the vertices when it builds
+/// the frame's vertex buffers.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use deno_core::OpState;
 
+use super::mask_ops::MaskState;
+use super::target_ops::{active_target, TargetState};
+
 /// A single geometry draw command queued from TS.
 #[derive(Clone, Debug)]
 pub enum GeoCommand {
@@ -29,6 +43,23 @@ pub enum GeoCommand {
         r: f32, g: f32, b: f32, a: f32,
         layer: i32,
     },
+    /// Textured triangle mesh. `vertices` is packed (x, y, u, v) per vertex;
+    /// `indices` list triangles. The color multiplies the sampled texel.
+    Mesh {
+        texture_id: u32,
+        vertices: Vec<f32>,
+        indices: Vec<u16>,
+        r: f32, g: f32, b: f32, a: f32,
+        layer: i32,
+    },
+    /// An uploaded mesh drawn with `transform` (see [`CustomMesh::transform`]).
+    /// The tint multiplies each vertex color.
+    CustomMesh {
+        mesh: Arc<CustomMesh>,
+        transform: [f32; 6],
+        r: f32, g: f32, b: f32, a: f32,
+        layer: i32,
+    },
 }
 
 impl GeoCommand {
@@ -36,18 +67,108 @@ impl GeoCommand {
         match self {
             GeoCommand::Triangle { layer, .. } => *layer,
             GeoCommand::LineSeg { layer, .. } => *layer,
+            GeoCommand::Mesh { layer, .. } => *layer,
+            GeoCommand::CustomMesh { layer, .. } => *layer,
         }
     }
+
+    /// Texture the command samples, if any.
+    pub fn texture_id(&self) -> Option<u32> {
+        match self {
+            GeoCommand::Mesh { texture_id, .. } => Some(*texture_id),
+            GeoCommand::CustomMesh { mesh, .. } if mesh.texture_id != 0 => Some(mesh.texture_id),
+            _ => None,
+        }
+    }
+}
+
+/// Number of f32 values per vertex of an uploaded mesh: x, y, u, v, r, g, b, a.
+pub const MESH_VERTEX_STRIDE: usize = 8;
+
+/// A mesh uploaded once with `op_geo_upload_mesh` and drawn any number of times.
+#[derive(Debug)]
+pub struct CustomMesh {
+    /// Packed [`MESH_VERTEX_STRIDE`] floats per vertex, in mesh-local space.
+    pub vertices: Vec<f32>,
+    /// Triangles as vertex index triples.
+    pub indices: Vec<u32>,
+    /// Texture sampled at each vertex's UV; 0 draws vertex colors only.
+    pub texture_id: u32,
+}
+
+impl CustomMesh {
+    /// Build a mesh from packed vertices and indices. A trailing partial
+    /// vertex or triangle is dropped, as are triangles that reference missing
+    /// vertices. None when no triangle is left.
+    pub fn new(mut vertices: Vec<f32>, indices: &[u32], texture_id: u32) -> Option<Self> {
+        vertices.truncate(vertices.len() / MESH_VERTEX_STRIDE * MESH_VERTEX_STRIDE);
+        let count = (vertices.len() / MESH_VERTEX_STRIDE) as u32;
+        let indices: Vec<u32> = indices
+            .chunks_exact(3)
+            .filter(|tri| tri.iter().all(|&i| i < count))
+            .flatten()
+            .copied()
+            .collect();
+        (!indices.is_empty()).then_some(Self { vertices, indices, texture_id })
+    }
+
+    /// Affine transform `[a, b, c, d, tx, ty]` mapping a local point to
+    /// `(a·x + b·y + tx, c·x + d·y + ty)`: scale, then rotate (radians,
+    /// clockwise on screen), then move to (x, y).
+    pub fn transform(x: f32, y: f32, rotation: f32, scale_x: f32, scale_y: f32) -> [f32; 6] {
+        let (sin, cos) = rotation.sin_cos();
+        [cos * scale_x, -sin * scale_y, sin * scale_x, cos * scale_y, x, y]
+    }
+
+    /// Vertex `index` as world position, UV and color under `transform` and `tint`.
+    pub fn vertex(&self, index: u32, transform: &[f32; 6], tint: [f32; 4]) -> ([f32; 2], [f32; 2], [f32; 4]) {
+        let v = &self.vertices[index as usize * MESH_VERTEX_STRIDE..][..MESH_VERTEX_STRIDE];
+        let [a, b, c, d, tx, ty] = *transform;
+        let position = [a * v[0] + b * v[1] + tx, c * v[0] + d * v[1] + ty];
+        let color = [v[4] * tint[0], v[5] * tint[1], v[6] * tint[2], v[7] * tint[3]];
+        (position, [v[2], v[3]], color)
+    }
 }
 
 /// Geometry command queue: collected by TS ops, drained by the frame callback.
 pub struct GeoState {
     pub commands: Vec<GeoCommand>,
+    /// Uploaded custom meshes by ID.
+    pub meshes: HashMap<u32, Arc<CustomMesh>>,
+    pub next_mesh_id: u32,
 }
 
 impl GeoState {
     pub fn new() -> Self {
-        Self { commands: Vec::new() }
+        Self { commands: Vec::new(), meshes: HashMap::new(), next_mesh_id: 1 }
+    }
+}
+
+fn decode_f32s(data: &[u8]) -> Vec<f32> {
+    data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+fn decode_u32s(data: &[u8]) -> Vec<u32> {
+    data.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+/// Queue geometry on the active render target, or the main pass when none is active.
+/// While a mask is being recorded, triangles and lines shape it instead.
+pub fn queue_geo_commands(state: &mut OpState, cmds: impl IntoIterator<Item = GeoCommand>) {
+    {
+        let masks = state.borrow::<Rc<RefCell<MaskState>>>();
+        let mut masks = masks.borrow_mut();
+        if let Some(shapes) = masks.recording_shapes() {
+            shapes.extend(cmds.into_iter().filter(|c| c.texture_id().is_none()));
+            return;
+        }
+    }
+    if let Some(target_id) = active_target(state) {
+        let ts = state.borrow::<Rc<RefCell<TargetState>>>();
+        ts.borrow_mut().target_geo_queues.entry(target_id).or_default().extend(cmds);
+    } else {
+        let geo = state.borrow::<Rc<RefCell<GeoState>>>();
+        geo.borrow_mut().commands.extend(cmds);
     }
 }
 
@@ -62,14 +183,14 @@ fn op_geo_triangle(
     r: f64, g: f64, b: f64, a: f64,
     layer: f64,
 ) {
-    let geo = state.borrow::<Rc<RefCell<GeoState>>>();
-    geo.borrow_mut().commands.push(GeoCommand::Triangle {
+    let cmd = GeoCommand::Triangle {
         x1: x1 as f32, y1: y1 as f32,
         x2: x2 as f32, y2: y2 as f32,
         x3: x3 as f32, y3: y3 as f32,
         r: r as f32, g: g as f32, b: b as f32, a: a as f32,
         layer: layer as i32,
-    });
+    };
+    queue_geo_commands(state, [cmd]);
 }
 
 /// Push a thick line segment to the geometry command queue.
@@ -84,14 +205,77 @@ fn op_geo_line(
     r: f64, g: f64, b: f64, a: f64,
     layer: f64,
 ) {
-    let geo = state.borrow::<Rc<RefCell<GeoState>>>();
-    geo.borrow_mut().commands.push(GeoCommand::LineSeg {
+    let cmd = GeoCommand::LineSeg {
         x1: x1 as f32, y1: y1 as f32,
         x2: x2 as f32, y2: y2 as f32,
         thickness: thickness as f32,
         r: r as f32, g: g as f32, b: b as f32, a: a as f32,
         layer: layer as i32,
-    });
+    };
+    queue_geo_commands(state, [cmd]);
+}
+
+/// Upload a mesh from a packed Float32Array of (x, y, u, v, r, g, b, a)
+/// vertices and a Uint32Array of triangle indices. `texture_id` 0 draws
+/// vertex colors only. Returns the mesh ID, or 0 when it has no triangles.
+#[deno_core::op2(fast)]
+fn op_geo_upload_mesh(state: &mut OpState, #[buffer] vertices: &[u8], #[buffer] indices: &[u8], texture_id: u32) -> u32 {
+    let Some(mesh) = CustomMesh::new(decode_f32s(vertices), &decode_u32s(indices), texture_id) else {
+        return 0;
+    };
+    let geo = state.borrow::<Rc<RefCell<GeoState>>>();
+    let mut geo = geo.borrow_mut();
+    let id = geo.next_mesh_id;
+    geo.next_mesh_id += 1;
+    geo.meshes.insert(id, Arc::new(mesh));
+    id
+}
+
+/// Replace an uploaded mesh's data, keeping its ID. Returns false for
+/// unknown meshes and when the new data has no triangles.
+#[deno_core::op2(fast)]
+fn op_geo_update_mesh(state: &mut OpState, id: u32, #[buffer] vertices: &[u8], #[buffer] indices: &[u8], texture_id: u32) -> bool {
+    let geo = state.borrow::<Rc<RefCell<GeoState>>>();
+    let mut geo = geo.borrow_mut();
+    let Some(slot) = geo.meshes.get_mut(&id) else { return false };
+    let Some(mesh) = CustomMesh::new(decode_f32s(vertices), &decode_u32s(indices), texture_id) else {
+        return false;
+    };
+    // Commands already queued this frame keep drawing the old data
+    *slot = Arc::new(mesh);
+    true
+}
+
+#[deno_core::op2(fast)]
+fn op_geo_destroy_mesh(state: &mut OpState, id: u32) {
+    let geo = state.borrow::<Rc<RefCell<GeoState>>>();
+    geo.borrow_mut().meshes.remove(&id);
+}
+
+/// Draw an uploaded mesh scaled, rotated (radians) and moved to (x, y),
+/// with its vertex colors multiplied by the tint. Unknown meshes are ignored.
+#[deno_core::op2(fast)]
+fn op_geo_draw_mesh(
+    state: &mut OpState,
+    id: u32,
+    x: f64, y: f64,
+    rotation: f64,
+    scale_x: f64, scale_y: f64,
+    r: f64, g: f64, b: f64, a: f64,
+    layer: f64,
+) {
+    let mesh = {
+        let geo = state.borrow::<Rc<RefCell<GeoState>>>();
+        let Some(mesh) = geo.borrow().meshes.get(&id).cloned() else { return };
+        mesh
+    };
+    let cmd = GeoCommand::CustomMesh {
+        mesh,
+        transform: CustomMesh::transform(x as f32, y as f32, rotation as f32, scale_x as f32, scale_y as f32),
+        r: r as f32, g: g as f32, b: b as f32, a: a as f32,
+        layer: layer as i32,
+    };
+    queue_geo_commands(state, [cmd]);
 }
 
 deno_core::extension!(
@@ -99,6 +283,10 @@ deno_core::extension!(
     ops = [
         op_geo_triangle,
         op_geo_line,
+        op_geo_upload_mesh,
+        op_geo_update_mesh,
+        op_geo_destroy_mesh,
+        op_geo_draw_mesh,
     ],
 );
 
@@ -130,6 +318,56 @@ mod tests {
         assert_eq!(cmd.layer(), 10);
     }
 
+    #[test]
+    fn test_geo_command_mesh_layer() {
+        let cmd = GeoCommand::Mesh {
+            texture_id: 1,
+            vertices: vec![0.0, 0.0, 0.0, 0.0, 10.0, 0.0, 1.0, 0.0, 0.0, 10.0, 0.0, 1.0],
+            indices: vec![0, 1, 2],
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 3,
+        };
+        assert_eq!(cmd.layer(), 3);
+    }
+
+    #[test]
+    fn test_custom_mesh_drops_partial_and_out_of_range_triangles() {
+        // Two whole vertices and one partial
+        let mut verts = vec![0.0; MESH_VERTEX_STRIDE * 3];
+        verts.truncate(MESH_VERTEX_STRIDE * 3 - 1);
+        assert!(CustomMesh::new(verts.clone(), &[0, 1, 2], 0).is_none());
+
+        verts.resize(MESH_VERTEX_STRIDE * 3, 0.0);
+        let mesh = CustomMesh::new(verts, &[0, 1, 2, 0, 2, 3, 1, 2], 4).unwrap();
+        assert_eq!(mesh.indices, [0, 1, 2]);
+        assert_eq!(mesh.vertices.len(), MESH_VERTEX_STRIDE * 3);
+        let cmd = GeoCommand::CustomMesh {
+            mesh: Arc::new(mesh),
+            transform: CustomMesh::transform(0.0, 0.0, 0.0, 1.0, 1.0),
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+            layer: 2,
+        };
+        assert_eq!((cmd.layer(), cmd.texture_id()), (2, Some(4)));
+    }
+
+    #[test]
+    fn test_custom_mesh_vertex_transform_and_tint() {
+        let verts = vec![
+            10.0, 0.0, 0.25, 0.5, 1.0, 0.5, 0.0, 1.0,
+            0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0,
+            0.0, 10.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+        ];
+        let mesh = CustomMesh::new(verts, &[0, 1, 2], 0).unwrap();
+        // Quarter turn clockwise on screen, doubled, moved to (100, 50)
+        let t = CustomMesh::transform(100.0, 50.0, std::f32::consts::FRAC_PI_2, 2.0, 2.0);
+        let (pos, uv, color) = mesh.vertex(0, &t, [1.0, 1.0, 1.0, 0.5]);
+        assert!((pos[0] - 100.0).abs() < 1e-4 && (pos[1] - 70.0).abs() < 1e-4);
+        assert_eq!(uv, [0.25, 0.5]);
+        assert_eq!(color, [1.0, 0.5, 0.0, 0.5]);
+        let (pos, _, _) = mesh.vertex(2, &t, [1.0; 4]);
+        assert!((pos[0] - 80.0).abs() < 1e-4 && (pos[1] - 50.0).abs() < 1e-4);
+    }
+
     #[test]
     fn test_geo_state_new() {
         let state = GeoState::new();
@@ -0,0 +1,173 @@
+//! Stencil mask ops: clip sprites to arbitrary shapes (circular minimaps,
+//! flashlight cones, dissolve reveals).
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const lens = beginMask();
+//! drawCircle(x, y, 80);            // shapes the mask instead of drawing
+//! endMask();
+//! drawMasked(lens, () => drawSprite({ ... }));
+//! drawMasked(lens, () => drawSprite({ ... }), { invert: true });
+//! ```
+//!
+//! ## Design
+//! - Between `op_begin_mask` and `op_end_mask`, `queue_geo_commands` routes
+//!   triangles and lines into the mask instead of the frame (textured meshes
+//!   are dropped). Masks are rebuilt every frame; IDs restart at 1.
+//! - `op_draw_masked` sets the mask sprites queued after it carry, like
+//!   `op_push_clip_rect`. Sprites drawn into render targets ignore it.
+//! - The renderer writes the masks into a stencil buffer before the scene
+//!   (see `renderer::mask`).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use super::geometry_ops::GeoCommand;
+use crate::renderer::{SpriteMask, MAX_MASKS};
+
+/// This frame's masks, and the one being recorded or applied.
+#[derive(Default)]
+pub struct MaskState {
+    /// Shapes of each mask, by ID - 1.
+    pub masks: Vec<Vec<GeoCommand>>,
+    /// Index of the mask between `op_begin_mask` and `op_end_mask`.
+    recording: Option<usize>,
+    /// The mask sprites queued now are clipped to.
+    active: Option<SpriteMask>,
+}
+
+impl MaskState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start recording a new mask. Returns its ID, or None while another is
+    /// being recorded or when the frame's masks are used up.
+    pub fn begin(&mut self) -> Option<u8> {
+        if self.recording.is_some() || self.masks.len() >= MAX_MASKS {
+            return None;
+        }
+        self.masks.push(Vec::new());
+        self.recording = Some(self.masks.len() - 1);
+        Some(self.masks.len() as u8)
+    }
+
+    /// Stop recording shapes.
+    pub fn end(&mut self) {
+        self.recording = None;
+    }
+
+    /// Clip sprites queued from now on to mask `id`; 0 and unknown IDs turn
+    /// masking off.
+    pub fn set_active(&mut self, id: u32, invert: bool) {
+        self.active = (id >= 1 && id as usize <= self.masks.len()).then_some(SpriteMask { id: id as u8, invert });
+    }
+
+    pub fn active(&self) -> Option<SpriteMask> {
+        self.active
+    }
+
+    /// The shapes of the mask being recorded, if any.
+    pub fn recording_shapes(&mut self) -> Option<&mut Vec<GeoCommand>> {
+        self.recording.map(|i| &mut self.masks[i])
+    }
+
+    /// Take the frame's masks and reset for the next frame.
+    pub fn take_frame(&mut self) -> Vec<Vec<GeoCommand>> {
+        self.recording = None;
+        self.active = None;
+        std::mem::take(&mut self.masks)
+    }
+}
+
+/// The mask sprites queued now get, if any.
+pub fn active_mask(state: &OpState) -> Option<SpriteMask> {
+    let masks = state.borrow::<Rc<RefCell<MaskState>>>();
+    masks.borrow().active()
+}
+
+/// Start a mask: geometry drawn until `op_end_mask` shapes it instead of
+/// drawing. Returns the mask ID, or 0 while another mask is being recorded or
+/// when the frame's 255 masks are used up.
+#[deno_core::op2(fast)]
+fn op_begin_mask(state: &mut OpState) -> u32 {
+    let masks = state.borrow::<Rc<RefCell<MaskState>>>();
+    masks.borrow_mut().begin().map_or(0, u32::from)
+}
+
+/// Stop shaping the current mask; geometry draws again.
+#[deno_core::op2(fast)]
+fn op_end_mask(state: &mut OpState) {
+    let masks = state.borrow::<Rc<RefCell<MaskState>>>();
+    masks.borrow_mut().end();
+}
+
+/// Clip sprites queued after this to mask `id`, or to everything outside it
+/// when `invert`. 0 turns masking off.
+#[deno_core::op2(fast)]
+fn op_draw_masked(state: &mut OpState, id: u32, invert: bool) {
+    let masks = state.borrow::<Rc<RefCell<MaskState>>>();
+    masks.borrow_mut().set_active(id, invert);
+}
+
+deno_core::extension!(
+    mask_ext,
+    ops = [
+        op_begin_mask,
+        op_end_mask,
+        op_draw_masked,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_begin_assigns_ids_and_refuses_nesting() {
+        let mut state = MaskState::new();
+        assert_eq!(state.begin(), Some(1));
+        assert_eq!(state.begin(), None);
+        state.end();
+        assert_eq!(state.begin(), Some(2));
+        assert!(state.recording_shapes().is_some());
+        state.end();
+        assert!(state.recording_shapes().is_none());
+    }
+
+    #[test]
+    fn test_set_active_ignores_unknown_ids() {
+        let mut state = MaskState::new();
+        state.begin();
+        state.end();
+        state.set_active(1, true);
+        assert_eq!(state.active(), Some(SpriteMask { id: 1, invert: true }));
+        state.set_active(2, false);
+        assert_eq!(state.active(), None);
+        state.set_active(1, false);
+        state.set_active(0, false);
+        assert_eq!(state.active(), None);
+    }
+
+    #[test]
+    fn test_take_frame_resets_ids() {
+        let mut state = MaskState::new();
+        state.begin();
+        state.set_active(1, false);
+        assert_eq!(state.take_frame().len(), 1);
+        assert_eq!(state.active(), None);
+        assert_eq!(state.begin(), Some(1));
+    }
+
+    #[test]
+    fn test_masks_run_out_after_255() {
+        let mut state = MaskState::new();
+        for _ in 0..MAX_MASKS {
+            assert!(state.begin().is_some());
+            state.end();
+        }
+        assert_eq!(state.begin(), None);
+    }
+}
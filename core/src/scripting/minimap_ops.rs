@@ -0,0 +1,307 @@
+//! Minimap ops: re-render selected layers of the main scene, scaled down into
+//! a render target, every N frames — no duplicated draw code in TS.
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const map = createMinimap(160, 120, { x: 0, y: 0, w: 3200, h: 2400 }, { layers: [0, 10], interval: 4 });
+//! onFrame(() => {
+//!   // ...draw the world as usual...
+//!   drawSprite({ textureId: map, x: 8, y: 8, w: 160, h: 120, layer: 100, screenSpace: true });
+//! });
+//! ```
+//!
+//! ## Design
+//! - A minimap owns a render target; its ID doubles as a `TextureId`.
+//! - Each frame dev.rs calls `MinimapState::capture` with the main sprite list
+//!   before draining target queues. Due minimaps cull sprites outside their
+//!   world bounds, map the rest into target space and queue them for the target.
+//! - Frames where a minimap is not due queue nothing, so the target keeps its
+//!   previous contents.
+//! - Only sprites are captured (not geometry/SDF). Sprites sampling the minimap's
+//!   own texture are skipped to avoid feedback.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use crate::renderer::SpriteCommand;
+use crate::scripting::render_ops::RenderBridgeState;
+use crate::scripting::target_ops::TargetState;
+
+/// One minimap: target size, captured world rectangle, layer range and refresh interval.
+#[derive(Debug, Clone)]
+pub struct Minimap {
+    pub width: f32,
+    pub height: f32,
+    /// World rectangle [x, y, w, h] mapped onto the whole target.
+    pub world: [f32; 4],
+    /// Inclusive layer range captured from the main scene.
+    pub layer_min: i32,
+    pub layer_max: i32,
+    /// Re-render every `interval` frames (1 = every frame).
+    pub interval: u32,
+    /// Frames since the last capture; the minimap is due when it reaches `interval`.
+    pub frames_since: u32,
+}
+
+impl Minimap {
+    pub fn new(width: f32, height: f32, world: [f32; 4]) -> Self {
+        Self {
+            width,
+            height,
+            world,
+            layer_min: i32::MIN,
+            layer_max: i32::MAX,
+            interval: 1,
+            // Due on the first frame
+            frames_since: u32::MAX,
+        }
+    }
+
+    /// Map a main-scene sprite into target space, or None if it is culled.
+    pub fn map_sprite(&self, cmd: &SpriteCommand) -> Option<SpriteCommand> {
+        if cmd.layer < self.layer_min || cmd.layer > self.layer_max {
+            return None;
+        }
+        let [wx, wy, ww, wh] = self.world;
+        if ww <= 0.0 || wh <= 0.0 {
+            return None;
+        }
+        // Conservative AABB: rotated sprites may extend to their diagonal
+        let (min_x, min_y, max_x, max_y) = if cmd.rotation != 0.0 {
+            let r = (cmd.w * cmd.w + cmd.h * cmd.h).sqrt();
+            let cx = cmd.x + cmd.w * cmd.origin_x;
+            let cy = cmd.y + cmd.h * cmd.origin_y;
+            (cx - r, cy - r, cx + r, cy + r)
+        } else {
+            (cmd.x, cmd.y, cmd.x + cmd.w, cmd.y + cmd.h)
+        };
+        if max_x < wx || max_y < wy || min_x > wx + ww || min_y > wy + wh {
+            return None;
+        }
+        let sx = self.width / ww;
+        let sy = self.height / wh;
+        Some(SpriteCommand {
+            x: (cmd.x - wx) * sx,
+            y: (cmd.y - wy) * sy,
+            w: cmd.w * sx,
+            h: cmd.h * sy,
+            ..cmd.clone()
+        })
+    }
+}
+
+/// All live minimaps keyed by their render target / texture ID.
+pub struct MinimapState {
+    pub minimaps: BTreeMap<u32, Minimap>,
+}
+
+impl Default for MinimapState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MinimapState {
+    pub fn new() -> Self {
+        Self { minimaps: BTreeMap::new() }
+    }
+
+    /// Advance frame counters and queue mapped sprites for every minimap due this frame.
+    pub fn capture(&mut self, sprites: &[SpriteCommand], queues: &mut BTreeMap<u32, Vec<SpriteCommand>>) {
+        for (&id, map) in self.minimaps.iter_mut() {
+            map.frames_since = map.frames_since.saturating_add(1);
+            if map.frames_since < map.interval.max(1) {
+                continue;
+            }
+            map.frames_since = 0;
+            let queue = queues.entry(id).or_default();
+            queue.extend(
+                sprites
+                    .iter()
+                    .filter(|c| c.texture_id != id)
+                    .filter_map(|c| map.map_sprite(c)),
+            );
+        }
+    }
+}
+
+/// Create a minimap of width × height pixels showing the given world rectangle.
+/// Returns its ID, usable as a TextureId.
+#[deno_core::op2(fast)]
+fn op_create_minimap(
+    state: &mut OpState,
+    width: f64,
+    height: f64,
+    world_x: f64,
+    world_y: f64,
+    world_w: f64,
+    world_h: f64,
+) -> u32 {
+    // Share the texture ID pool with render targets and textures
+    let id = {
+        let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+        let mut b = bridge.borrow_mut();
+        let id = b.next_texture_id;
+        b.next_texture_id += 1;
+        id
+    };
+    {
+        let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+        ts.borrow_mut().create_queue.push((id, width as u32, height as u32));
+    }
+    let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+    ms.borrow_mut().minimaps.insert(
+        id,
+        Minimap::new(
+            width as f32,
+            height as f32,
+            [world_x as f32, world_y as f32, world_w as f32, world_h as f32],
+        ),
+    );
+    id
+}
+
+/// Change the captured world rectangle (e.g. when the level changes).
+#[deno_core::op2(fast)]
+fn op_set_minimap_world(state: &mut OpState, id: u32, x: f64, y: f64, w: f64, h: f64) {
+    let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+    if let Some(map) = ms.borrow_mut().minimaps.get_mut(&id) {
+        map.world = [x as f32, y as f32, w as f32, h as f32];
+    }
+}
+
+/// Capture only layers in [min, max] (inclusive).
+#[deno_core::op2(fast)]
+fn op_set_minimap_layers(state: &mut OpState, id: u32, min: i32, max: i32) {
+    let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+    if let Some(map) = ms.borrow_mut().minimaps.get_mut(&id) {
+        map.layer_min = min.min(max);
+        map.layer_max = min.max(max);
+    }
+}
+
+/// Re-render every `frames` frames (minimum 1).
+#[deno_core::op2(fast)]
+fn op_set_minimap_interval(state: &mut OpState, id: u32, frames: u32) {
+    let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+    if let Some(map) = ms.borrow_mut().minimaps.get_mut(&id) {
+        map.interval = frames.max(1);
+    }
+}
+
+/// Force a re-render on the next frame regardless of the interval.
+#[deno_core::op2(fast)]
+fn op_refresh_minimap(state: &mut OpState, id: u32) {
+    let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+    if let Some(map) = ms.borrow_mut().minimaps.get_mut(&id) {
+        map.frames_since = u32::MAX;
+    }
+}
+
+/// Destroy a minimap and its render target.
+#[deno_core::op2(fast)]
+fn op_destroy_minimap(state: &mut OpState, id: u32) {
+    let removed = {
+        let ms = state.borrow_mut::<Rc<RefCell<MinimapState>>>();
+        ms.borrow_mut().minimaps.remove(&id).is_some()
+    };
+    if removed {
+        let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+        let mut ts = ts.borrow_mut();
+        ts.destroy_queue.push(id);
+        ts.target_sprite_queues.remove(&id);
+    }
+}
+
+deno_core::extension!(
+    minimap_ext,
+    ops = [
+        op_create_minimap,
+        op_set_minimap_world,
+        op_set_minimap_layers,
+        op_set_minimap_interval,
+        op_refresh_minimap,
+        op_destroy_minimap,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(x: f32, y: f32, w: f32, h: f32, layer: i32) -> SpriteCommand {
+        SpriteCommand {
+            texture_id: 1,
+            x,
+            y,
+            w,
+            h,
+            layer,
+            uv_x: 0.0,
+            uv_y: 0.0,
+            uv_w: 1.0,
+            uv_h: 1.0,
+            tint_r: 1.0,
+            tint_g: 1.0,
+            tint_b: 1.0,
+            tint_a: 1.0,
+            rotation: 0.0,
+            origin_x: 0.5,
+            origin_y: 0.5,
+            flip_x: false,
+            flip_y: false,
+            opacity: 1.0,
+            blend_mode: 0,
+            shader_id: 0,
+            nine_slice: None,
+            outline: None,
+            clip: None,
+            mask: None,
+            normal_map: None,
+            flash: None,
+            pixel_snap: None,
+        }
+    }
+
+    #[test]
+    fn test_map_sprite_scales_into_target() {
+        let map = Minimap::new(100.0, 50.0, [1000.0, 0.0, 1000.0, 500.0]);
+        let out = map.map_sprite(&sprite(1500.0, 100.0, 20.0, 20.0, 0)).unwrap();
+        assert_eq!((out.x, out.y, out.w, out.h), (50.0, 10.0, 2.0, 2.0));
+        assert_eq!(out.texture_id, 1);
+    }
+
+    #[test]
+    fn test_map_sprite_culls_outside_bounds_and_layers() {
+        let mut map = Minimap::new(100.0, 100.0, [0.0, 0.0, 100.0, 100.0]);
+        assert!(map.map_sprite(&sprite(200.0, 0.0, 10.0, 10.0, 0)).is_none());
+        // Partially overlapping sprites are kept
+        assert!(map.map_sprite(&sprite(-5.0, 0.0, 10.0, 10.0, 0)).is_some());
+        map.layer_min = 0;
+        map.layer_max = 5;
+        assert!(map.map_sprite(&sprite(10.0, 10.0, 10.0, 10.0, 6)).is_none());
+        assert!(map.map_sprite(&sprite(10.0, 10.0, 10.0, 10.0, 5)).is_some());
+    }
+
+    #[test]
+    fn test_capture_respects_interval_and_skips_own_texture() {
+        let mut ms = MinimapState::new();
+        let mut map = Minimap::new(64.0, 64.0, [0.0, 0.0, 64.0, 64.0]);
+        map.interval = 3;
+        ms.minimaps.insert(9, map);
+        let mut own = sprite(0.0, 0.0, 8.0, 8.0, 0);
+        own.texture_id = 9;
+        let sprites = vec![sprite(0.0, 0.0, 8.0, 8.0, 0), own];
+
+        let mut due = Vec::new();
+        for _ in 0..7 {
+            let mut queues = BTreeMap::new();
+            ms.capture(&sprites, &mut queues);
+            due.push(queues.get(&9).map(|q| q.len()));
+        }
+        assert_eq!(due, vec![Some(1), None, None, Some(1), None, None, Some(1)]);
+    }
+}
@@ -1,7 +1,15 @@
 mod module_loader;
 mod runtime;
 mod test_runner;
+pub mod asset_cache;
+pub mod color_ops;
+pub mod curve_ops;
+pub mod engine_info;
+pub mod format_ops;
+pub mod op_profiler;
 pub mod physics_ops;
+pub mod plugin_ops;
+pub mod procgen_ops;
 pub mod replay_ops;
 
 #[cfg(feature = "renderer")]
@@ -19,6 +27,21 @@ pub mod target_ops;
 #[cfg(feature = "renderer")]
 pub mod sdf_ops;
 
+#[cfg(feature = "renderer")]
+pub mod node_ops;
+
+#[cfg(feature = "renderer")]
+pub mod skeleton_ops;
+
+#[cfg(feature = "renderer")]
+pub mod deform_ops;
+
+#[cfg(feature = "renderer")]
+pub mod minimap_ops;
+
+#[cfg(feature = "renderer")]
+pub mod mask_ops;
+
 pub use module_loader::{ImportMap, TsModuleLoader};
 pub use runtime::ArcaneRuntime;
 pub use test_runner::{TestResult, TestSummary, run_test_file, run_test_file_with_import_map};
@@ -6,6 +6,8 @@ use deno_core::ModuleLoader;
 use deno_core::ModuleSourceCode;
 use deno_core::ModuleSpecifier;
 use deno_error::JsErrorBox;
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 /// Import map for resolving bare specifiers to file paths
@@ -121,19 +123,23 @@ mod import_map_tests {
 /// Loads `.ts` and `.js` files from the filesystem with import map support.
 /// TypeScript files are transpiled via `deno_ast` (type stripping).
 /// JavaScript files pass through unchanged.
+/// Source maps of transpiled files are kept so JS error stacks point at the
+/// original `.ts` lines.
 pub struct TsModuleLoader {
     import_map: ImportMap,
+    source_maps: RefCell<HashMap<String, Vec<u8>>>,
 }
 
 impl TsModuleLoader {
     pub fn new() -> Self {
-        Self {
-            import_map: ImportMap::new(),
-        }
+        Self::with_import_map(ImportMap::new())
     }
 
     pub fn with_import_map(import_map: ImportMap) -> Self {
-        Self { import_map }
+        Self {
+            import_map,
+            source_maps: RefCell::new(HashMap::new()),
+        }
     }
 }
 
@@ -164,7 +170,22 @@ impl ModuleLoader for TsModuleLoader {
     ) -> ModuleLoadResponse {
         let module_specifier = module_specifier.clone();
 
-        ModuleLoadResponse::Sync(load_module(&module_specifier))
+        let result = load_module(&module_specifier).map(|(module, source_map)| {
+            if let Some(source_map) = source_map {
+                self.source_maps
+                    .borrow_mut()
+                    .insert(module_specifier.to_string(), source_map);
+            }
+            module
+        });
+        ModuleLoadResponse::Sync(result)
+    }
+
+    fn get_source_map(&self, specifier: &str) -> Option<Cow<'_, [u8]>> {
+        self.source_maps
+            .borrow()
+            .get(specifier)
+            .map(|map| Cow::Owned(map.clone()))
     }
 }
 
@@ -205,9 +226,10 @@ impl TsModuleLoader {
     }
 }
 
+/// Load a module, returning its source and (for transpiled files) a separate source map.
 fn load_module(
     specifier: &ModuleSpecifier,
-) -> Result<deno_core::ModuleSource, deno_core::error::ModuleLoaderError> {
+) -> Result<(deno_core::ModuleSource, Option<Vec<u8>>), deno_core::error::ModuleLoaderError> {
     let path = specifier.to_file_path().map_err(|_| {
         JsErrorBox::generic(format!(
             "Cannot convert module specifier to file path: {specifier}"
@@ -241,7 +263,7 @@ fn load_module(
         JsErrorBox::generic(format!("Failed to read {}: {e}", path.display()))
     })?;
 
-    let code = if should_transpile {
+    let (code, source_map) = if should_transpile {
         let parsed = deno_ast::parse_module(ParseParams {
             specifier: specifier.clone(),
             text: code.into(),
@@ -256,13 +278,18 @@ fn load_module(
             .transpile(
                 &deno_ast::TranspileOptions::default(),
                 &TranspileModuleOptions::default(),
-                &deno_ast::EmitOptions::default(),
+                &deno_ast::EmitOptions {
+                    source_map: deno_ast::SourceMapOption::Separate,
+                    inline_sources: true,
+                    ..Default::default()
+                },
             )
             .map_err(|e| JsErrorBox::generic(format!("Transpile error: {e}")))?;
 
-        transpiled.into_source().text
+        let source = transpiled.into_source();
+        (source.text, source.source_map.map(|m| m.into_bytes()))
     } else {
-        code
+        (code, None)
     };
 
     let module = deno_core::ModuleSource::new(
@@ -272,5 +299,5 @@ fn load_module(
         None,
     );
 
-    Ok(module)
+    Ok((module, source_map))
 }
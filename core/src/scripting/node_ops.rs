@@ -0,0 +1,478 @@
+//! Hierarchical transform ops: parent/child scene nodes whose world transforms
+//! are composed in Rust and applied to attached sprite commands.
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const body = createSpriteNode();
+//! const hand = createSpriteNode(body);
+//! setSpriteNodeTransform(hand, 12, -4, 0.3);
+//! attachSprites(hand);
+//!   drawSprite({ textureId: sword, x: 0, y: 0, w: 16, h: 32 }); // local to hand
+//! attachSprites(0);
+//!
+//! // A rig's parts, all set in one op call
+//! setSpriteNodeTransforms([{ id: body, x: 200, y: 120 }, { id: hand, x: 12, y: -4, rotation: 0.3 }]);
+//! ```
+//!
+//! ## Design
+//! - Nodes store a local transform (x, y, rotation, scale) and an optional parent.
+//! - `op_attach_sprite_to_node(node_id)` sets `active_node`. While active,
+//!   `op_submit_sprite_batch` maps each command through the node's world transform.
+//! - World transforms are composed root-to-leaf on demand and cached until any
+//!   node changes, so a frame with many attached sprites walks each chain once.
+//! - `op_set_node_transforms` sets many local transforms from one packed
+//!   buffer, so animating a many-part character costs one op call per frame.
+//! - Composition matches `runtime/game/transform.ts` (rotation adds, scale multiplies).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use crate::renderer::SpriteCommand;
+
+/// A node in the transform hierarchy. Transform is local to the parent.
+#[derive(Debug, Clone)]
+pub struct SceneNode {
+    pub parent: Option<u32>,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+/// A composed world-space transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldTransform {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+impl WorldTransform {
+    pub const IDENTITY: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        rotation: 0.0,
+        scale_x: 1.0,
+        scale_y: 1.0,
+    };
+
+    /// Map a point from node-local space into world space.
+    pub fn transform_point(&self, px: f32, py: f32) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let sx = px * self.scale_x;
+        let sy = py * self.scale_y;
+        (self.x + cos * sx - sin * sy, self.y + sin * sx + cos * sy)
+    }
+
+    /// Map a sprite command from node-local space into world space.
+    /// The sprite's pivot (origin) is transformed; size is scaled, rotation is added,
+    /// and negative scale flips the sprite around its pivot.
+    pub fn apply_to_sprite(&self, cmd: &mut SpriteCommand) {
+        let (px, py) = self.transform_point(
+            cmd.x + cmd.origin_x * cmd.w,
+            cmd.y + cmd.origin_y * cmd.h,
+        );
+        cmd.w *= self.scale_x.abs();
+        cmd.h *= self.scale_y.abs();
+        if self.scale_x < 0.0 {
+            cmd.flip_x = !cmd.flip_x;
+            cmd.origin_x = 1.0 - cmd.origin_x;
+        }
+        if self.scale_y < 0.0 {
+            cmd.flip_y = !cmd.flip_y;
+            cmd.origin_y = 1.0 - cmd.origin_y;
+        }
+        cmd.x = px - cmd.origin_x * cmd.w;
+        cmd.y = py - cmd.origin_y * cmd.h;
+        cmd.rotation += self.rotation;
+    }
+}
+
+/// Number of f32 values per record in `op_set_node_transforms`:
+/// [node_id (u32 bits), x, y, rotation, scale_x, scale_y].
+pub const NODE_TRANSFORM_STRIDE: usize = 6;
+
+/// All scene nodes plus the node currently receiving sprite submissions.
+pub struct NodeState {
+    pub nodes: HashMap<u32, SceneNode>,
+    pub next_id: u32,
+    /// If Some, submitted sprite commands are transformed by this node.
+    pub active_node: Option<u32>,
+    /// Composed world transforms, invalidated whenever any node changes.
+    world_cache: HashMap<u32, WorldTransform>,
+}
+
+impl Default for NodeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeState {
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            next_id: 1,
+            active_node: None,
+            world_cache: HashMap::new(),
+        }
+    }
+
+    /// Create a node with an identity transform. Unknown parents are ignored.
+    pub fn create(&mut self, parent: Option<u32>) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let parent = parent.filter(|p| self.nodes.contains_key(p));
+        self.nodes.insert(
+            id,
+            SceneNode {
+                parent,
+                x: 0.0,
+                y: 0.0,
+                rotation: 0.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+            },
+        );
+        id
+    }
+
+    /// Remove a node. Its children become roots.
+    pub fn destroy(&mut self, id: u32) {
+        if self.nodes.remove(&id).is_none() {
+            return;
+        }
+        for node in self.nodes.values_mut() {
+            if node.parent == Some(id) {
+                node.parent = None;
+            }
+        }
+        if self.active_node == Some(id) {
+            self.active_node = None;
+        }
+        self.world_cache.clear();
+    }
+
+    /// Set a node's local transform.
+    pub fn set_transform(&mut self, id: u32, x: f32, y: f32, rotation: f32, scale_x: f32, scale_y: f32) {
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.x = x;
+            node.y = y;
+            node.rotation = rotation;
+            node.scale_x = scale_x;
+            node.scale_y = scale_y;
+            self.world_cache.clear();
+        }
+    }
+
+    /// Set local transforms from packed [`NODE_TRANSFORM_STRIDE`] records.
+    /// Unknown nodes are skipped; a trailing partial record is ignored.
+    pub fn set_transforms(&mut self, records: &[f32]) {
+        for r in records.chunks_exact(NODE_TRANSFORM_STRIDE) {
+            if let Some(node) = self.nodes.get_mut(&r[0].to_bits()) {
+                node.x = r[1];
+                node.y = r[2];
+                node.rotation = r[3];
+                node.scale_x = r[4];
+                node.scale_y = r[5];
+            }
+        }
+        self.world_cache.clear();
+    }
+
+    /// Reparent a node (None = make root). Returns false if either node is
+    /// missing or the new parent is a descendant of the node (would form a cycle).
+    pub fn set_parent(&mut self, id: u32, parent: Option<u32>) -> bool {
+        if !self.nodes.contains_key(&id) {
+            return false;
+        }
+        if let Some(p) = parent {
+            if !self.nodes.contains_key(&p) {
+                return false;
+            }
+            let mut current = Some(p);
+            while let Some(c) = current {
+                if c == id {
+                    return false;
+                }
+                current = self.nodes.get(&c).and_then(|n| n.parent);
+            }
+        }
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.parent = parent;
+        }
+        self.world_cache.clear();
+        true
+    }
+
+    /// Compose the world transform for a node by walking its parent chain.
+    /// Returns None for unknown nodes.
+    pub fn world_transform(&mut self, id: u32) -> Option<WorldTransform> {
+        if let Some(wt) = self.world_cache.get(&id) {
+            return Some(*wt);
+        }
+        let node = self.nodes.get(&id)?;
+        let parent = match node.parent {
+            Some(p) => self.world_transform(p).unwrap_or(WorldTransform::IDENTITY),
+            None => WorldTransform::IDENTITY,
+        };
+        let node = &self.nodes[&id];
+        let (x, y) = parent.transform_point(node.x, node.y);
+        let wt = WorldTransform {
+            x,
+            y,
+            rotation: parent.rotation + node.rotation,
+            scale_x: parent.scale_x * node.scale_x,
+            scale_y: parent.scale_y * node.scale_y,
+        };
+        self.world_cache.insert(id, wt);
+        Some(wt)
+    }
+
+    /// World transform of the active node, if one is attached and still exists.
+    pub fn active_transform(&mut self) -> Option<WorldTransform> {
+        let id = self.active_node?;
+        self.world_transform(id)
+    }
+}
+
+/// Create a scene node. parent_id = 0 creates a root node.
+/// Returns the new node ID (never 0).
+#[deno_core::op2(fast)]
+fn op_create_node(state: &mut OpState, parent_id: u32) -> u32 {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    let parent = if parent_id == 0 { None } else { Some(parent_id) };
+    ns.borrow_mut().create(parent)
+}
+
+/// Destroy a scene node. Its children become root nodes.
+#[deno_core::op2(fast)]
+fn op_destroy_node(state: &mut OpState, id: u32) {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    ns.borrow_mut().destroy(id);
+}
+
+/// Set a node's local transform relative to its parent.
+#[deno_core::op2(fast)]
+fn op_set_node_transform(
+    state: &mut OpState,
+    id: u32,
+    x: f64,
+    y: f64,
+    rotation: f64,
+    scale_x: f64,
+    scale_y: f64,
+) {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    ns.borrow_mut().set_transform(
+        id,
+        x as f32,
+        y as f32,
+        rotation as f32,
+        scale_x as f32,
+        scale_y as f32,
+    );
+}
+
+/// Set many local transforms from a packed Float32Array of
+/// NODE_TRANSFORM_STRIDE (6) f32 values each: [node_id as u32 bits, x, y,
+/// rotation, scale_x, scale_y].
+#[deno_core::op2(fast)]
+fn op_set_node_transforms(state: &mut OpState, #[buffer] data: &[u8]) {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    ns.borrow_mut().set_transforms(floats);
+}
+
+/// Reparent a node. parent_id = 0 detaches it (makes it a root).
+/// Returns false if the change would create a cycle or a node is missing.
+#[deno_core::op2(fast)]
+fn op_set_node_parent(state: &mut OpState, id: u32, parent_id: u32) -> bool {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    let parent = if parent_id == 0 { None } else { Some(parent_id) };
+    ns.borrow_mut().set_parent(id, parent)
+}
+
+/// Transform subsequently submitted sprites by this node's world transform.
+/// node_id = 0 detaches (sprites are drawn in world space again).
+#[deno_core::op2(fast)]
+fn op_attach_sprite_to_node(state: &mut OpState, node_id: u32) {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    ns.borrow_mut().active_node = if node_id == 0 { None } else { Some(node_id) };
+}
+
+/// Returns [x, y, rotation, scale_x, scale_y] or empty vec for unknown nodes.
+#[deno_core::op2]
+#[serde]
+fn op_get_node_world_transform(state: &mut OpState, id: u32) -> Vec<f64> {
+    let ns = state.borrow_mut::<Rc<RefCell<NodeState>>>();
+    match ns.borrow_mut().world_transform(id) {
+        Some(wt) => vec![
+            wt.x as f64,
+            wt.y as f64,
+            wt.rotation as f64,
+            wt.scale_x as f64,
+            wt.scale_y as f64,
+        ],
+        None => vec![],
+    }
+}
+
+deno_core::extension!(
+    node_ext,
+    ops = [
+        op_create_node,
+        op_destroy_node,
+        op_set_node_transform,
+        op_set_node_transforms,
+        op_set_node_parent,
+        op_attach_sprite_to_node,
+        op_get_node_world_transform,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite() -> SpriteCommand {
+        SpriteCommand {
+            texture_id: 1, x: 0.0, y: 0.0, w: 16.0, h: 16.0, layer: 0,
+            uv_x: 0.0, uv_y: 0.0, uv_w: 1.0, uv_h: 1.0,
+            tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
+            rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
+            flip_x: false, flip_y: false, opacity: 1.0,
+            blend_mode: 0, shader_id: 0, nine_slice: None, outline: None, clip: None, mask: None, normal_map: None, flash: None, pixel_snap: None,
+        }
+    }
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_root_node_world_equals_local() {
+        let mut ns = NodeState::new();
+        let id = ns.create(None);
+        ns.set_transform(id, 10.0, 20.0, 0.5, 2.0, 3.0);
+        let wt = ns.world_transform(id).unwrap();
+        assert_eq!(wt, WorldTransform { x: 10.0, y: 20.0, rotation: 0.5, scale_x: 2.0, scale_y: 3.0 });
+    }
+
+    #[test]
+    fn test_child_composes_parent_rotation_and_scale() {
+        let mut ns = NodeState::new();
+        let parent = ns.create(None);
+        let child = ns.create(Some(parent));
+        ns.set_transform(parent, 100.0, 50.0, std::f32::consts::FRAC_PI_2, 2.0, 2.0);
+        ns.set_transform(child, 10.0, 0.0, 0.25, 1.0, 1.0);
+
+        let wt = ns.world_transform(child).unwrap();
+        // Local (10, 0) scaled by 2 then rotated 90° → (0, 20)
+        assert!(approx(wt.x, 100.0));
+        assert!(approx(wt.y, 70.0));
+        assert!(approx(wt.rotation, std::f32::consts::FRAC_PI_2 + 0.25));
+        assert!(approx(wt.scale_x, 2.0));
+    }
+
+    #[test]
+    fn test_cache_invalidated_on_parent_move() {
+        let mut ns = NodeState::new();
+        let parent = ns.create(None);
+        let child = ns.create(Some(parent));
+        ns.set_transform(child, 5.0, 0.0, 0.0, 1.0, 1.0);
+        assert!(approx(ns.world_transform(child).unwrap().x, 5.0));
+
+        ns.set_transform(parent, 100.0, 0.0, 0.0, 1.0, 1.0);
+        assert!(approx(ns.world_transform(child).unwrap().x, 105.0));
+    }
+
+    #[test]
+    fn test_set_transforms_applies_packed_records() {
+        let mut ns = NodeState::new();
+        let parent = ns.create(None);
+        let child = ns.create(Some(parent));
+        assert!(approx(ns.world_transform(child).unwrap().x, 0.0));
+
+        let id = |n: u32| f32::from_bits(n);
+        ns.set_transforms(&[
+            id(parent), 100.0, 0.0, 0.0, 2.0, 2.0,
+            id(child), 5.0, 1.0, 0.5, 1.0, 1.0,
+            id(99), 1.0, 1.0, 1.0, 1.0, 1.0,
+            id(parent), // partial record
+        ]);
+        let wt = ns.world_transform(child).unwrap();
+        assert!(approx(wt.x, 110.0));
+        assert!(approx(wt.y, 2.0));
+        assert!(approx(wt.rotation, 0.5));
+        assert!(!ns.nodes.contains_key(&99));
+    }
+
+    #[test]
+    fn test_set_parent_rejects_cycles() {
+        let mut ns = NodeState::new();
+        let a = ns.create(None);
+        let b = ns.create(Some(a));
+        let c = ns.create(Some(b));
+        assert!(!ns.set_parent(a, Some(c)));
+        assert!(!ns.set_parent(a, Some(a)));
+        assert!(ns.set_parent(c, Some(a)));
+        assert!(ns.set_parent(c, None));
+        assert!(!ns.set_parent(99, None));
+    }
+
+    #[test]
+    fn test_destroy_orphans_children_and_clears_active() {
+        let mut ns = NodeState::new();
+        let parent = ns.create(None);
+        let child = ns.create(Some(parent));
+        ns.set_transform(parent, 50.0, 0.0, 0.0, 1.0, 1.0);
+        ns.active_node = Some(parent);
+
+        ns.destroy(parent);
+        assert!(ns.active_node.is_none());
+        assert!(ns.nodes[&child].parent.is_none());
+        assert!(approx(ns.world_transform(child).unwrap().x, 0.0));
+    }
+
+    #[test]
+    fn test_unknown_parent_creates_root() {
+        let mut ns = NodeState::new();
+        let id = ns.create(Some(42));
+        assert!(ns.nodes[&id].parent.is_none());
+        assert!(ns.world_transform(999).is_none());
+    }
+
+    #[test]
+    fn test_apply_to_sprite_translates_and_rotates_pivot() {
+        let wt = WorldTransform { x: 100.0, y: 100.0, rotation: std::f32::consts::PI, scale_x: 1.0, scale_y: 1.0 };
+        let mut cmd = sprite();
+        cmd.x = 10.0; // pivot at local (18, 8)
+        wt.apply_to_sprite(&mut cmd);
+        // Pivot (18, 8) rotated 180° → (-18, -8), plus (100, 100)
+        assert!(approx(cmd.x + 8.0, 82.0));
+        assert!(approx(cmd.y + 8.0, 92.0));
+        assert!(approx(cmd.rotation, std::f32::consts::PI));
+        assert!(approx(cmd.w, 16.0));
+    }
+
+    #[test]
+    fn test_apply_to_sprite_negative_scale_flips() {
+        let wt = WorldTransform { x: 0.0, y: 0.0, rotation: 0.0, scale_x: -2.0, scale_y: 1.0 };
+        let mut cmd = sprite();
+        cmd.origin_x = 0.0; // pivot at left edge (0, 8)
+        wt.apply_to_sprite(&mut cmd);
+        assert!(cmd.flip_x);
+        assert!(!cmd.flip_y);
+        assert!(approx(cmd.w, 32.0));
+        // Mirrored: sprite now extends to the left of the pivot
+        assert!(approx(cmd.origin_x, 1.0));
+        assert!(approx(cmd.x, -32.0));
+    }
+}
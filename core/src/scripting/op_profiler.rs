@@ -0,0 +1,267 @@
+//! Dev-mode op-call profiler.
+//!
+//! Counts calls and cumulative time per op through deno_core's op metrics
+//! hooks, so a game can see which TS→Rust crossings dominate a frame (e.g.
+//! thousands of `op_draw_sprite` or `op_set_tile` calls) and batch them.
+//! The hooks are installed for every op of the dev runtime but only record
+//! while profiling is enabled; a disabled profiler costs a flag check per call.
+//!
+//! Counters cover one frame: `end_frame` snapshots them (most time first) and
+//! starts over. Async ops are counted when dispatched; their time is not.
+//!
+//! Output shape of `to_json`:
+//! `{"enabled":bool,"ops":[{"name","calls","total_ms","avg_us"}, ...]}`
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use deno_core::{OpCtx, OpDecl, OpMetricsEvent, OpMetricsFactoryFn, OpMetricsFn, OpMetricsSource, OpState};
+
+use crate::json::escape;
+
+/// Ops listed in frame stats when no count is given.
+pub const DEFAULT_TOP: usize = 10;
+
+/// Calls and time of one op over a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpSample {
+    pub name: &'static str,
+    pub calls: u64,
+    pub time: Duration,
+}
+
+#[derive(Debug)]
+struct OpCounter {
+    name: &'static str,
+    calls: u64,
+    time: Duration,
+    started: Option<Instant>,
+}
+
+/// Per-op call counts and timings, shared between the metrics hooks and the
+/// frame callback as `Rc<RefCell<OpProfiler>>` in `OpState`.
+#[derive(Debug, Default)]
+pub struct OpProfiler {
+    enabled: bool,
+    counters: Vec<OpCounter>,
+    last_frame: Vec<OpSample>,
+}
+
+impl OpProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start or stop recording. Stopping clears the counters and the last frame.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.last_frame.clear();
+            self.reset_counters();
+        }
+    }
+
+    /// Register an op; returns the index its hook reports with.
+    fn register(&mut self, name: &'static str) -> usize {
+        self.counters.push(OpCounter { name, calls: 0, time: Duration::ZERO, started: None });
+        self.counters.len() - 1
+    }
+
+    fn dispatched(&mut self, index: usize, timed: bool) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(counter) = self.counters.get_mut(index) {
+            counter.calls += 1;
+            counter.started = timed.then(Instant::now);
+        }
+    }
+
+    fn completed(&mut self, index: usize) {
+        if let Some(counter) = self.counters.get_mut(index)
+            && let Some(started) = counter.started.take()
+        {
+            counter.time += started.elapsed();
+        }
+    }
+
+    /// Add a finished call of `name` directly.
+    #[cfg(test)]
+    fn record(&mut self, name: &'static str, time: Duration) {
+        if !self.enabled {
+            return;
+        }
+        let index = match self.counters.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => self.register(name),
+        };
+        let counter = &mut self.counters[index];
+        counter.calls += 1;
+        counter.time += time;
+    }
+
+    /// Snapshot this frame's counters, most time first, and reset them.
+    pub fn end_frame(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        let mut samples: Vec<OpSample> = self
+            .counters
+            .iter()
+            .filter(|c| c.calls > 0)
+            .map(|c| OpSample { name: c.name, calls: c.calls, time: c.time })
+            .collect();
+        samples.sort_by(|a, b| b.time.cmp(&a.time).then(b.calls.cmp(&a.calls)).then(a.name.cmp(b.name)));
+        self.last_frame = samples;
+        self.reset_counters();
+    }
+
+    /// The `top` ops of the last finished frame.
+    pub fn top(&self, top: usize) -> &[OpSample] {
+        &self.last_frame[..top.min(self.last_frame.len())]
+    }
+
+    fn reset_counters(&mut self) {
+        for counter in &mut self.counters {
+            counter.calls = 0;
+            counter.time = Duration::ZERO;
+            counter.started = None;
+        }
+    }
+
+    /// JSON report of the `top` ops of the last frame.
+    pub fn to_json(&self, top: usize) -> String {
+        let ops: Vec<String> = self
+            .top(top)
+            .iter()
+            .map(|s| {
+                let total_ms = s.time.as_secs_f64() * 1000.0;
+                let avg_us = s.time.as_secs_f64() * 1e6 / s.calls as f64;
+                format!(
+                    "{{\"name\":\"{}\",\"calls\":{},\"total_ms\":{total_ms:.3},\"avg_us\":{avg_us:.2}}}",
+                    escape(s.name),
+                    s.calls
+                )
+            })
+            .collect();
+        format!("{{\"enabled\":{},\"ops\":[{}]}}", self.enabled, ops.join(","))
+    }
+}
+
+/// Metrics factory for `RuntimeOptions::op_metrics_factory_fn` that feeds
+/// every op into `profiler`.
+pub fn metrics_factory(profiler: Rc<RefCell<OpProfiler>>) -> OpMetricsFactoryFn {
+    Box::new(move |_, _, decl: &OpDecl| {
+        let index = profiler.borrow_mut().register(decl.name);
+        let profiler = profiler.clone();
+        let hook: OpMetricsFn = Rc::new(move |_: &OpCtx, event: OpMetricsEvent, source: OpMetricsSource| {
+            // An op reading the profiler holds no borrow across its own hooks,
+            // but skip rather than panic if that ever changes
+            let Ok(mut profiler) = profiler.try_borrow_mut() else { return };
+            match event {
+                OpMetricsEvent::Dispatched => {
+                    profiler.dispatched(index, !matches!(source, OpMetricsSource::Async));
+                }
+                OpMetricsEvent::Completed | OpMetricsEvent::Error => profiler.completed(index),
+                _ => {}
+            }
+        });
+        Some(hook)
+    })
+}
+
+deno_core::extension!(
+    op_profiler_ext,
+    ops = [op_set_op_profiling, op_get_op_stats],
+);
+
+/// Start or stop op profiling.
+#[deno_core::op2(fast)]
+fn op_set_op_profiling(state: &mut OpState, enabled: bool) {
+    if let Some(profiler) = state.try_borrow::<Rc<RefCell<OpProfiler>>>() {
+        profiler.borrow_mut().set_enabled(enabled);
+    }
+}
+
+/// JSON report of the `top` ops of the last frame (see module docs).
+#[deno_core::op2]
+#[string]
+fn op_get_op_stats(state: &mut OpState, top: u32) -> String {
+    match state.try_borrow::<Rc<RefCell<OpProfiler>>>() {
+        Some(profiler) => profiler.borrow().to_json(top as usize),
+        None => OpProfiler::new().to_json(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_disabled_profiler_records_nothing() {
+        let mut profiler = OpProfiler::new();
+        profiler.record("op_draw_sprite", ms(1));
+        profiler.end_frame();
+        assert!(profiler.top(DEFAULT_TOP).is_empty());
+    }
+
+    #[test]
+    fn test_frames_are_sorted_by_time_and_reset() {
+        let mut profiler = OpProfiler::new();
+        profiler.set_enabled(true);
+        for _ in 0..3 {
+            profiler.record("op_draw_sprite", ms(1));
+        }
+        profiler.record("op_set_tile", ms(5));
+        profiler.end_frame();
+        let top = profiler.top(DEFAULT_TOP);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].name, top[0].calls, top[0].time), ("op_set_tile", 1, ms(5)));
+        assert_eq!((top[1].name, top[1].calls, top[1].time), ("op_draw_sprite", 3, ms(3)));
+        assert_eq!(profiler.top(1).len(), 1);
+
+        // The next frame starts from zero; ops without calls are left out
+        profiler.record("op_draw_sprite", ms(2));
+        profiler.end_frame();
+        assert_eq!(profiler.top(DEFAULT_TOP), &[OpSample { name: "op_draw_sprite", calls: 1, time: ms(2) }]);
+    }
+
+    #[test]
+    fn test_hooks_count_calls_and_time_sync_ops() {
+        let mut profiler = OpProfiler::new();
+        let index = profiler.register("op_raycast");
+        profiler.set_enabled(true);
+        profiler.dispatched(index, true);
+        profiler.completed(index);
+        // Async dispatches count without time
+        profiler.dispatched(index, false);
+        profiler.completed(index);
+        profiler.end_frame();
+        assert_eq!(profiler.top(1)[0].calls, 2);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let mut profiler = OpProfiler::new();
+        assert_eq!(profiler.to_json(DEFAULT_TOP), r#"{"enabled":false,"ops":[]}"#);
+        profiler.set_enabled(true);
+        profiler.record("op_draw_sprite", Duration::from_micros(1500));
+        profiler.record("op_draw_sprite", Duration::from_micros(500));
+        profiler.end_frame();
+        assert_eq!(
+            profiler.to_json(DEFAULT_TOP),
+            r#"{"enabled":true,"ops":[{"name":"op_draw_sprite","calls":2,"total_ms":2.000,"avg_us":1000.00}]}"#
+        );
+        profiler.set_enabled(false);
+        assert_eq!(profiler.to_json(DEFAULT_TOP), r#"{"enabled":false,"ops":[]}"#);
+    }
+}
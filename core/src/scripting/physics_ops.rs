@@ -1,14 +1,29 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use deno_core::OpState;
 
+use crate::json::escape;
+use crate::physics::projectile::{Projectile, ProjectileBatchDef};
+use crate::physics::rig::Rig;
 use crate::physics::types::*;
-use crate::physics::world::PhysicsWorld;
+use crate::physics::vehicle::{TractionCurve, Vehicle, VehicleDef, VehicleInput, VehicleView, WheelDef};
+use crate::physics::world::{PhysicsConfig, PhysicsWorld};
 
 /// Wrapper for physics state in OpState.
 pub struct PhysicsState(pub Option<PhysicsWorld>);
 
+/// Set by the dev loop while the simulation is paused (focus loss);
+/// `op_physics_step` does nothing while it is true.
+#[derive(Default)]
+pub struct PhysicsPaused(pub bool);
+
+/// Time spent in `op_physics_step` since the dev loop last took it, for the
+/// frame profiler.
+#[derive(Default)]
+pub struct PhysicsStepTime(pub Duration);
+
 #[deno_core::op2(fast)]
 fn op_create_physics_world(state: &mut OpState, gravity_x: f64, gravity_y: f64) {
     let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
@@ -23,15 +38,131 @@ fn op_destroy_physics_world(state: &mut OpState) {
 
 #[deno_core::op2(fast)]
 fn op_physics_step(state: &mut OpState, dt: f64) {
+    if state.try_borrow::<PhysicsPaused>().is_some_and(|p| p.0) {
+        return;
+    }
+    let start = Instant::now();
     let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
     if let Some(world) = physics.borrow_mut().0.as_mut() {
         world.step(dt as f32);
     }
+    if let Some(time) = state.try_borrow_mut::<PhysicsStepTime>() {
+        time.0 += start.elapsed();
+    }
+}
+
+/// Tune the current world's solver. Negative values keep the current setting.
+/// Counts are clamped (substeps 1..=32, iterations up to 64, velocity iterations >= 1).
+#[deno_core::op2(fast)]
+fn op_physics_config(
+    state: &mut OpState,
+    substeps: f64,
+    velocity_iterations: f64,
+    position_iterations: f64,
+    sleep_velocity: f64,
+    sleep_angular_velocity: f64,
+    sleep_time: f64,
+) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    let Some(world) = ps.0.as_mut() else {
+        return;
+    };
+    let current = world.config();
+    let count = |v: f64, cur: u32| if v >= 0.0 { v as u32 } else { cur };
+    let value = |v: f64, cur: f32| if v >= 0.0 { v as f32 } else { cur };
+    let mut config = PhysicsConfig {
+        substeps: count(substeps, current.substeps),
+        velocity_iterations: count(velocity_iterations, current.velocity_iterations),
+        position_iterations: count(position_iterations, current.position_iterations),
+        ..current
+    };
+    config.sleep.velocity = value(sleep_velocity, current.sleep.velocity);
+    config.sleep.angular_velocity = value(sleep_angular_velocity, current.sleep.angular_velocity);
+    config.sleep.time = value(sleep_time, current.sleep.time);
+    world.set_config(config);
+}
+
+/// Current solver settings as
+/// [substeps, velocity_iterations, position_iterations, sleep_velocity, sleep_angular_velocity, sleep_time].
+/// Empty if no world exists.
+#[deno_core::op2]
+#[serde]
+fn op_get_physics_config(state: &mut OpState) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    match ps.0.as_ref() {
+        Some(world) => {
+            let c = world.config();
+            vec![
+                c.substeps as f64,
+                c.velocity_iterations as f64,
+                c.position_iterations as f64,
+                c.sleep.velocity as f64,
+                c.sleep.angular_velocity as f64,
+                c.sleep.time as f64,
+            ]
+        }
+        None => vec![],
+    }
+}
+
+/// Toggle deterministic stepping (sorted pair order) on the current world.
+#[deno_core::op2(fast)]
+fn op_set_physics_deterministic(state: &mut OpState, enabled: bool) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_deterministic(enabled);
+    }
+}
+
+/// Hash of the current world's state as 16 hex digits. Empty if no world exists.
+/// A string because a u64 does not fit in a JS number.
+#[deno_core::op2]
+#[string]
+fn op_physics_state_hash(state: &mut OpState) -> String {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    match ps.0.as_ref() {
+        Some(world) => format!("{:016x}", world.state_hash()),
+        None => String::new(),
+    }
+}
+
+/// Binary snapshot of the whole current world (see `PhysicsWorld::snapshot`).
+/// Empty if no world exists.
+#[deno_core::op2]
+#[buffer]
+fn op_physics_snapshot(state: &mut OpState) -> Vec<u8> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    ps.0.as_ref().map(|world| world.snapshot()).unwrap_or_default()
+}
+
+/// Replace the world with a snapshot, creating one if none exists.
+/// Returns false (world unchanged) if the data is not a valid snapshot.
+#[deno_core::op2(fast)]
+fn op_physics_restore(state: &mut OpState, #[buffer] data: &[u8]) -> bool {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match PhysicsWorld::from_snapshot(data) {
+        Ok(world) => {
+            ps.0 = Some(world);
+            true
+        }
+        Err(e) => {
+            eprintln!("[physics] Cannot restore snapshot: {e}");
+            false
+        }
+    }
 }
 
-/// Create a body. shape_type: 0=circle, 1=aabb. body_type: 0=static, 1=dynamic, 2=kinematic.
+/// Create a body. shape_type: 0=circle, 1=aabb, 3=capsule, 4=oriented box
+/// (2 is polygon, created with op_create_polygon_body). body_type: 0=static, 1=dynamic, 2=kinematic.
 /// For circle: shape_p1=radius, shape_p2 unused.
-/// For AABB: shape_p1=half_w, shape_p2=half_h.
+/// For AABB and oriented box: shape_p1=half_w, shape_p2=half_h.
+/// For capsule: shape_p1=half_height (of the straight part), shape_p2=radius.
 #[deno_core::op2(fast)]
 fn op_create_body(
     state: &mut OpState,
@@ -69,6 +200,14 @@ fn op_create_body(
             half_w: shape_p1 as f32,
             half_h: shape_p2 as f32,
         },
+        3 => Shape::Capsule {
+            half_height: shape_p1 as f32,
+            radius: shape_p2 as f32,
+        },
+        4 => Shape::OBB {
+            half_w: shape_p1 as f32,
+            half_h: shape_p2 as f32,
+        },
         _ => return u32::MAX,
     };
 
@@ -145,6 +284,90 @@ fn op_apply_impulse(state: &mut OpState, id: u32, ix: f64, iy: f64) {
     }
 }
 
+/// Explosion-style impulse: pushes dynamic bodies within `radius` away from (x, y),
+/// scaled by (1 - d/radius)^falloff. With `occlusion`, static bodies block the blast.
+/// Wakes affected bodies and returns how many were pushed.
+#[deno_core::op2(fast)]
+fn op_apply_radial_impulse(
+    state: &mut OpState,
+    x: f64,
+    y: f64,
+    radius: f64,
+    strength: f64,
+    falloff: f64,
+    occlusion: bool,
+    mask: u32,
+) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => world
+            .apply_radial_impulse(
+                x as f32,
+                y as f32,
+                radius as f32,
+                strength as f32,
+                falloff as f32,
+                occlusion,
+                mask as u16,
+            )
+            .len() as u32,
+        None => 0,
+    }
+}
+
+/// Set per-body linear and angular damping (per second, 0 = none).
+#[deno_core::op2(fast)]
+fn op_set_body_damping(state: &mut OpState, id: u32, linear: f64, angular: f64) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_damping(id, linear as f32, angular as f32);
+    }
+}
+
+/// Lock rotation and/or the X / Y axis of a body.
+#[deno_core::op2(fast)]
+fn op_set_body_locks(state: &mut OpState, id: u32, lock_rotation: bool, lock_x: bool, lock_y: bool) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_locks(id, lock_rotation, lock_x, lock_y);
+    }
+}
+
+/// Make a body a sensor: it reports contacts and collision events but
+/// produces no collision response.
+#[deno_core::op2(fast)]
+fn op_set_body_sensor(state: &mut OpState, id: u32, is_sensor: bool) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_sensor(id, is_sensor);
+    }
+}
+
+/// Make a body a one-way platform whose open side faces (nx, ny) in its
+/// local frame. (0, 0) makes it solid from all sides again.
+#[deno_core::op2(fast)]
+fn op_set_body_one_way(state: &mut OpState, id: u32, nx: f64, ny: f64) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_one_way(id, Some((nx as f32, ny as f32)));
+    }
+}
+
+/// Cap a body's linear and angular speed. 0 = unlimited.
+#[deno_core::op2(fast)]
+fn op_set_body_max_speed(state: &mut OpState, id: u32, linear: f64, angular: f64) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_max_speed(id, linear as f32, angular as f32);
+    }
+}
+
 #[deno_core::op2(fast)]
 fn op_set_body_position(state: &mut OpState, id: u32, x: f64, y: f64) {
     let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
@@ -236,6 +459,7 @@ fn op_create_revolute_joint(
                 anchor_b,
                 soft: None,
                 accumulated_impulse: (0.0, 0.0),
+                motor: None,
             })
         },
         None => u32::MAX,
@@ -320,12 +544,242 @@ fn op_create_soft_revolute_joint(
                 anchor_b,
                 soft: Some(SoftConstraintParams::soft(frequency_hz as f32, damping_ratio as f32)),
                 accumulated_impulse: (0.0, 0.0),
+                motor: None,
             })
         },
         None => u32::MAX,
     }
 }
 
+/// A world point (or direction, with `translate` false) in a body's local space.
+fn to_body_local(body: Option<&RigidBody>, x: f32, y: f32, translate: bool) -> (f32, f32) {
+    match body {
+        Some(b) => {
+            let (sin, cos) = b.angle.sin_cos();
+            let (dx, dy) = if translate { (x - b.x, y - b.y) } else { (x, y) };
+            (dx * cos + dy * sin, -dx * sin + dy * cos)
+        }
+        None => (x, y),
+    }
+}
+
+/// Create a prismatic (slider) joint: body_b slides relative to body_a along
+/// the world-space axis through (x, y), without rotating relative to it.
+/// Translation limits apply when `limited` is set; an infinite bound is open.
+#[deno_core::op2(fast)]
+fn op_create_prismatic_joint(
+    state: &mut OpState,
+    body_a: u32,
+    body_b: u32,
+    x: f64,
+    y: f64,
+    axis_x: f64,
+    axis_y: f64,
+    limited: bool,
+    lower: f64,
+    upper: f64,
+) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => {
+            let (x, y) = (x as f32, y as f32);
+            let a = world.get_body(body_a);
+            let anchor_a = to_body_local(a, x, y, true);
+            let axis = to_body_local(a, axis_x as f32, axis_y as f32, false);
+            let anchor_b = to_body_local(world.get_body(body_b), x, y, true);
+            let (lower, upper) = if limited {
+                (lower as f32, upper as f32)
+            } else {
+                (f32::NEG_INFINITY, f32::INFINITY)
+            };
+            world.add_constraint(Constraint::Prismatic {
+                id: 0,
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                axis,
+                reference_angle: 0.0,
+                lower,
+                upper,
+                motor: None,
+                accumulated_impulse: (0.0, 0.0),
+            })
+        }
+        None => u32::MAX,
+    }
+}
+
+/// Create a weld joint gluing body_a and body_b together at the world point
+/// (x, y), keeping their current relative angle.
+#[deno_core::op2(fast)]
+fn op_create_weld_joint(state: &mut OpState, body_a: u32, body_b: u32, x: f64, y: f64) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => {
+            let (x, y) = (x as f32, y as f32);
+            let anchor_a = to_body_local(world.get_body(body_a), x, y, true);
+            let anchor_b = to_body_local(world.get_body(body_b), x, y, true);
+            world.add_constraint(Constraint::Weld {
+                id: 0,
+                body_a,
+                body_b,
+                anchor_a,
+                anchor_b,
+                reference_angle: 0.0,
+                accumulated_impulse: (0.0, 0.0),
+            })
+        }
+        None => u32::MAX,
+    }
+}
+
+/// Enable (or disable) the motor of a revolute or prismatic joint. Speed is
+/// rad/s or units/s along the axis; max_force is a torque for revolute joints.
+/// Returns false if `id` is not a revolute or prismatic joint.
+#[deno_core::op2(fast)]
+fn op_set_joint_motor(state: &mut OpState, id: u32, enabled: bool, speed: f64, max_force: f64) -> bool {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => {
+            let motor = enabled.then(|| JointMotor::new(speed as f32, max_force as f32));
+            world.set_joint_motor(id, motor)
+        }
+        None => false,
+    }
+}
+
+/// Create a rope joint: slack up to max_length, pulls like a rope when taut.
+#[deno_core::op2(fast)]
+fn op_create_rope_joint(
+    state: &mut OpState,
+    body_a: u32,
+    body_b: u32,
+    max_length: f64,
+) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => world.add_constraint(Constraint::Rope {
+            id: 0,
+            body_a,
+            body_b,
+            max_length: max_length as f32,
+            anchor_a: (0.0, 0.0),
+            anchor_b: (0.0, 0.0),
+            accumulated_impulse: 0.0,
+        }),
+        None => u32::MAX,
+    }
+}
+
+/// Create a pulley joint hanging body_a from (ground_ax, ground_ay) and body_b
+/// from (ground_bx, ground_by). The rope length is taken from the current positions.
+#[deno_core::op2(fast)]
+fn op_create_pulley_joint(
+    state: &mut OpState,
+    body_a: u32,
+    body_b: u32,
+    ground_ax: f64,
+    ground_ay: f64,
+    ground_bx: f64,
+    ground_by: f64,
+    ratio: f64,
+) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => world.add_constraint(Constraint::Pulley {
+            id: 0,
+            body_a,
+            body_b,
+            ground_a: (ground_ax as f32, ground_ay as f32),
+            ground_b: (ground_bx as f32, ground_by as f32),
+            anchor_a: (0.0, 0.0),
+            anchor_b: (0.0, 0.0),
+            ratio: ratio as f32,
+            total_length: 0.0,
+            accumulated_impulse: 0.0,
+        }),
+        None => u32::MAX,
+    }
+}
+
+/// Create a mouse joint grabbing `body` at the world point (target_x, target_y)
+/// and pulling that point toward the target. max_force <= 0 = 50000 × body mass.
+#[deno_core::op2(fast)]
+fn op_create_mouse_joint(
+    state: &mut OpState,
+    body: u32,
+    target_x: f64,
+    target_y: f64,
+    max_force: f64,
+) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => {
+            let target = (target_x as f32, target_y as f32);
+            // Grab point in body-local space
+            let anchor = match world.get_body(body) {
+                Some(b) => {
+                    let cos = b.angle.cos();
+                    let sin = b.angle.sin();
+                    let dx = target.0 - b.x;
+                    let dy = target.1 - b.y;
+                    (dx * cos + dy * sin, -dx * sin + dy * cos)
+                }
+                None => return u32::MAX,
+            };
+            world.add_constraint(Constraint::Mouse {
+                id: 0,
+                body,
+                target,
+                anchor,
+                max_force: max_force as f32,
+                soft: SoftConstraintParams::mouse(),
+                accumulated_impulse: (0.0, 0.0),
+            })
+        }
+        None => u32::MAX,
+    }
+}
+
+/// Move a mouse joint's target (call every frame while dragging).
+/// Returns false if the joint no longer exists.
+#[deno_core::op2(fast)]
+fn op_set_mouse_joint_target(state: &mut OpState, id: u32, x: f64, y: f64) -> bool {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => world.set_mouse_target(id, x as f32, y as f32),
+        None => false,
+    }
+}
+
+/// Retune a mouse joint's spring and force limit. max_force <= 0 keeps the current limit.
+#[deno_core::op2(fast)]
+fn op_set_mouse_joint_params(
+    state: &mut OpState,
+    id: u32,
+    frequency_hz: f64,
+    damping_ratio: f64,
+    max_force: f64,
+) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    if let Some(world) = ps.0.as_mut() {
+        world.set_mouse_params(
+            id,
+            SoftConstraintParams::soft(frequency_hz as f32, damping_ratio as f32),
+            max_force as f32,
+        );
+    }
+}
+
 #[deno_core::op2(fast)]
 fn op_remove_constraint(state: &mut OpState, id: u32) {
     let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
@@ -353,6 +807,149 @@ fn op_query_aabb(
     }
 }
 
+/// Many `op_query_aabb` calls in one: `rects` is a packed Float32Array of
+/// [min_x, min_y, max_x, max_y] per query. Returns, for each query in order,
+/// its hit count followed by that many body IDs.
+#[deno_core::op2]
+#[serde]
+fn op_query_aabb_batch(state: &mut OpState, #[buffer] rects: &[u8]) -> Vec<u32> {
+    let floats: Vec<f32> = rects.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    let Some(world) = ps.0.as_ref() else { return vec![] };
+    let mut result = Vec::new();
+    for r in floats.chunks_exact(4) {
+        let ids = world.query_aabb(r[0], r[1], r[2], r[3]);
+        result.push(ids.len() as u32);
+        result.extend(ids);
+    }
+    result
+}
+
+/// Many `op_raycast` calls in one: `rays` is a packed Float32Array of
+/// [origin_x, origin_y, dir_x, dir_y, max_dist] per ray. Returns 4 values per
+/// ray in order: [body_id, hit_x, hit_y, distance], with body_id -1 for a miss.
+#[deno_core::op2]
+#[serde]
+fn op_raycast_batch(state: &mut OpState, #[buffer] rays: &[u8]) -> Vec<f64> {
+    let floats: Vec<f32> = rays.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    let Some(world) = ps.0.as_ref() else { return vec![] };
+    let mut result = Vec::with_capacity(floats.len() / 5 * 4);
+    for r in floats.chunks_exact(5) {
+        match world.raycast(r[0], r[1], r[2], r[3], r[4]) {
+            Some((id, hx, hy, dist)) => result.extend([id as f64, hx as f64, hy as f64, dist as f64]),
+            None => result.extend([-1.0, 0.0, 0.0, 0.0]),
+        }
+    }
+    result
+}
+
+/// Scene queries made between the last two physics steps as JSON:
+/// {aabb, overlap, raycast, total, candidates, full_scans}. Zeros without a world.
+#[deno_core::op2]
+#[string]
+fn op_get_query_stats(state: &mut OpState) -> String {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let stats = physics.borrow().0.as_ref().map(|world| world.query_stats()).unwrap_or_default();
+    query_stats_json(&stats)
+}
+
+/// JSON for [`QueryStats`], shared by `op_get_query_stats` and the inspector's
+/// frame stats.
+pub fn query_stats_json(stats: &QueryStats) -> String {
+    format!(
+        "{{\"aabb\":{},\"overlap\":{},\"raycast\":{},\"total\":{},\"candidates\":{},\"full_scans\":{}}}",
+        stats.aabb,
+        stats.overlap,
+        stats.raycast,
+        stats.total(),
+        stats.candidates,
+        stats.full_scans
+    )
+}
+
+/// Flatten overlap hits as [body_id, normal_x, normal_y, penetration, point_x, point_y] per hit.
+fn flatten_overlaps(hits: Vec<OverlapHit>) -> Vec<f64> {
+    let mut result = Vec::with_capacity(hits.len() * 6);
+    for hit in hits {
+        result.push(hit.body_id as f64);
+        result.push(hit.normal.0 as f64);
+        result.push(hit.normal.1 as f64);
+        result.push(hit.penetration as f64);
+        result.push(hit.point.0 as f64);
+        result.push(hit.point.1 as f64);
+    }
+    result
+}
+
+/// Bodies overlapping a circle. Only bodies whose layer intersects `mask` are returned.
+/// Returns 6 values per hit, see `flatten_overlaps`.
+#[deno_core::op2]
+#[serde]
+fn op_overlap_circle(state: &mut OpState, x: f64, y: f64, radius: f64, mask: u32) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    match ps.0.as_ref() {
+        Some(world) => flatten_overlaps(world.overlap_circle(x as f32, y as f32, radius as f32, mask as u16)),
+        None => vec![],
+    }
+}
+
+/// Bodies overlapping an oriented box centered at (x, y). angle in radians.
+#[deno_core::op2]
+#[serde]
+fn op_overlap_box(
+    state: &mut OpState,
+    x: f64,
+    y: f64,
+    half_w: f64,
+    half_h: f64,
+    angle: f64,
+    mask: u32,
+) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    match ps.0.as_ref() {
+        Some(world) => flatten_overlaps(world.overlap_box(
+            x as f32,
+            y as f32,
+            half_w as f32,
+            half_h as f32,
+            angle as f32,
+            mask as u16,
+        )),
+        None => vec![],
+    }
+}
+
+/// Bodies overlapping a convex polygon. vertices is a flat [x0, y0, x1, y1, ...]
+/// array relative to (x, y), rotated by angle (radians).
+#[deno_core::op2]
+#[serde]
+fn op_overlap_polygon(
+    state: &mut OpState,
+    #[serde] vertices: Vec<f64>,
+    x: f64,
+    y: f64,
+    angle: f64,
+    mask: u32,
+) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    match ps.0.as_ref() {
+        Some(world) => {
+            let verts: Vec<(f32, f32)> = vertices
+                .chunks_exact(2)
+                .map(|c| (c[0] as f32, c[1] as f32))
+                .collect();
+            flatten_overlaps(world.overlap_polygon(x as f32, y as f32, &verts, angle as f32, mask as u16))
+        }
+        None => vec![],
+    }
+}
+
 /// Returns [] for no hit, [body_id, hit_x, hit_y, distance] for hit.
 #[deno_core::op2]
 #[serde]
@@ -433,7 +1030,271 @@ fn op_create_polygon_body(
     world.add_body(bt, shape, x as f32, y as f32, mass as f32, material, layer as u16, mask as u16)
 }
 
-/// Returns flattened contacts: [bodyA, bodyB, nx, ny, penetration, contactX, contactY, ...].
+/// Spawn a rig of bodies and joints from JSON (raw string or path relative
+/// to the game directory, see `physics::rig` for the format) with its origin
+/// at (x, y). Returns JSON: { "bodies": { name: id }, "joints": { name: id } }
+/// or { "error": "..." }.
+#[deno_core::op2]
+#[string]
+fn op_load_physics_rig(state: &mut OpState, #[string] json_or_path: &str, x: f64, y: f64) -> String {
+    let source = if json_or_path.trim_start().starts_with('{') {
+        json_or_path.to_string()
+    } else {
+        let path = game_path(state, json_or_path);
+        match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                return format!(
+                    "{{\"error\":\"{}\"}}",
+                    escape(&format!("Failed to read rig {}: {}", path.display(), e))
+                );
+            }
+        }
+    };
+    let rig = match Rig::parse(&source) {
+        Ok(rig) => rig,
+        Err(e) => return format!("{{\"error\":\"{}\"}}", escape(&e)),
+    };
+
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    match ps.0.as_mut() {
+        Some(world) => rig.spawn(world, x as f32, y as f32).to_json(),
+        None => "{\"error\":\"No physics world\"}".to_string(),
+    }
+}
+
+/// A path relative to the game directory. Without the renderer (headless
+/// tests) paths stay relative to the working directory.
+#[cfg_attr(not(feature = "renderer"), allow(unused_variables))]
+fn game_path(state: &OpState, path: &str) -> std::path::PathBuf {
+    #[cfg(feature = "renderer")]
+    if let Some(bridge) = state.try_borrow::<Rc<RefCell<super::render_ops::RenderBridgeState>>>() {
+        return bridge.borrow().base_dir.join(path);
+    }
+    std::path::PathBuf::from(path)
+}
+
+/// Values per wheel in `op_create_vehicle`'s wheel array.
+const VEHICLE_WHEEL_STRIDE: usize = 9;
+
+/// Drive `chassis` with raycast wheels (see `physics::vehicle`). `params` is
+/// [view (0=side, 1=top-down), acceleration, max_speed, braking, max_steer,
+/// load, peak_slip, sliding, ground_mask]; `wheels` holds 9 values per wheel:
+/// [anchor_x, anchor_y, radius, suspension_length, frequency, damping,
+/// friction, drive, steer], anchors local to the chassis. Returns the vehicle
+/// ID, or u32::MAX if the chassis is not a dynamic body or there are no wheels.
+#[deno_core::op2]
+fn op_create_vehicle(state: &mut OpState, chassis: u32, #[serde] params: Vec<f64>, #[serde] wheels: Vec<f64>) -> u32 {
+    let [view, acceleration, max_speed, braking, max_steer, load, peak_slip, sliding, ground_mask] = params[..] else {
+        return u32::MAX;
+    };
+    let def = VehicleDef {
+        view: if view == 1.0 { VehicleView::TopDown } else { VehicleView::Side },
+        wheels: wheels
+            .chunks_exact(VEHICLE_WHEEL_STRIDE)
+            .map(|w| WheelDef {
+                anchor: (w[0] as f32, w[1] as f32),
+                radius: w[2] as f32,
+                suspension_length: w[3] as f32,
+                frequency: w[4] as f32,
+                damping: w[5] as f32,
+                friction: w[6] as f32,
+                drive: w[7] != 0.0,
+                steer: w[8] != 0.0,
+            })
+            .collect(),
+        acceleration: acceleration as f32,
+        max_speed: max_speed as f32,
+        braking: braking as f32,
+        max_steer: max_steer as f32,
+        load: load as f32,
+        traction: TractionCurve { peak_slip: peak_slip as f32, sliding: sliding as f32 },
+        ground_mask: ground_mask as u16,
+    };
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    ps.0.as_mut().and_then(|world| world.add_vehicle(chassis, def)).unwrap_or(u32::MAX)
+}
+
+/// Set a vehicle's controls: throttle -1..1, brake 0..1, steer -1..1 (values
+/// are clamped). Returns false for an unknown vehicle.
+#[deno_core::op2(fast)]
+fn op_set_vehicle_input(state: &mut OpState, id: u32, throttle: f64, brake: f64, steer: f64) -> bool {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    let input = VehicleInput { throttle: throttle as f32, brake: brake as f32, steer: steer as f32 };
+    ps.0.as_mut().is_some_and(|world| world.set_vehicle_input(id, input))
+}
+
+#[deno_core::op2(fast)]
+fn op_remove_vehicle(state: &mut OpState, id: u32) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    if let Some(world) = physics.borrow_mut().0.as_mut() {
+        world.remove_vehicle(id);
+    }
+}
+
+/// Returns [forward_speed, then per wheel: x, y, rotation, spin, grounded,
+/// slip] or an empty vec for an unknown vehicle.
+#[deno_core::op2]
+#[serde]
+fn op_get_vehicle_state(state: &mut OpState, id: u32) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    let Some(world) = ps.0.as_ref() else {
+        return vec![];
+    };
+    let Some((vehicle, chassis)) = world.get_vehicle(id).and_then(|v| Some((v, world.get_body(v.chassis)?))) else {
+        return vec![];
+    };
+    let mut out = vec![Vehicle::forward_speed(chassis) as f64];
+    for (i, wheel) in vehicle.wheels.iter().enumerate() {
+        let (x, y) = vehicle.wheel_position(chassis, i).unwrap_or((chassis.x, chassis.y));
+        let grounded = match vehicle.def.view {
+            VehicleView::Side => wheel.ground.is_some(),
+            VehicleView::TopDown => true,
+        };
+        out.extend([x, y, wheel.rotation, wheel.spin].map(|v| v as f64));
+        out.push(if grounded { 1.0 } else { 0.0 });
+        out.push(wheel.slip as f64);
+    }
+    out
+}
+
+/// Values per projectile in `op_spawn_projectiles`' buffer.
+const PROJECTILE_STRIDE: usize = 8;
+
+/// Create a projectile batch (see `physics::projectile`) with room for
+/// `capacity` projectiles of collision `radius` that hit bodies on the layers
+/// in `mask`. Returns the batch ID, or u32::MAX without a world.
+#[deno_core::op2(fast)]
+fn op_create_projectile_batch(state: &mut OpState, capacity: u32, radius: f64, mask: u32) -> u32 {
+    let def = ProjectileBatchDef { capacity: capacity as usize, radius: radius as f32, mask: mask as u16 };
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    ps.0.as_mut().map(|world| world.add_projectile_batch(def)).unwrap_or(u32::MAX)
+}
+
+#[deno_core::op2(fast)]
+fn op_remove_projectile_batch(state: &mut OpState, id: u32) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    if let Some(world) = physics.borrow_mut().0.as_mut() {
+        world.remove_projectile_batch(id);
+    }
+}
+
+/// Spawn projectiles from a packed Float32Array of PROJECTILE_STRIDE (8) f32
+/// values each: [x, y, vx, vy, ax, ay, life, tag] (tag as its u32 bits).
+/// Returns how many fit in the batch.
+#[deno_core::op2(fast)]
+fn op_spawn_projectiles(state: &mut OpState, id: u32, #[buffer] data: &[u8]) -> u32 {
+    let floats: Vec<f32> = data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+    let projectiles = floats.chunks_exact(PROJECTILE_STRIDE).map(|p| Projectile {
+        x: p[0],
+        y: p[1],
+        vx: p[2],
+        vy: p[3],
+        ax: p[4],
+        ay: p[5],
+        life: p[6],
+        tag: p[7].to_bits(),
+    });
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    ps.0.as_mut().map(|world| world.spawn_projectiles(id, projectiles) as u32).unwrap_or(0)
+}
+
+#[deno_core::op2(fast)]
+fn op_clear_projectiles(state: &mut OpState, id: u32) {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    if let Some(world) = physics.borrow_mut().0.as_mut() {
+        world.clear_projectiles(id);
+    }
+}
+
+/// Live projectiles in a batch, packed as 4 f32 values each: [x, y, vx, vy].
+/// Empty for an unknown batch.
+#[deno_core::op2]
+#[buffer]
+fn op_get_projectiles(state: &mut OpState, id: u32) -> Vec<u8> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    let Some(batch) = ps.0.as_ref().and_then(|world| world.get_projectile_batch(id)) else {
+        return Vec::new();
+    };
+    batch
+        .projectiles()
+        .iter()
+        .flat_map(|p| [p.x, p.y, p.vx, p.vy])
+        .flat_map(f32::to_le_bytes)
+        .collect()
+}
+
+#[deno_core::op2(fast)]
+fn op_get_projectile_count(state: &mut OpState, id: u32) -> u32 {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let ps = physics.borrow();
+    ps.0.as_ref()
+        .and_then(|world| world.get_projectile_batch(id))
+        .map(|batch| batch.projectiles().len() as u32)
+        .unwrap_or(0)
+}
+
+/// Take the projectile hits queued since the last call, flattened:
+/// [batch, tag, body, pointX, pointY, vx, vy, ...].
+#[deno_core::op2]
+#[serde]
+fn op_take_projectile_hits(state: &mut OpState) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    let Some(world) = ps.0.as_mut() else {
+        return vec![];
+    };
+    world
+        .take_projectile_hits()
+        .into_iter()
+        .flat_map(|h| {
+            [h.batch as f64, h.tag as f64, h.body as f64]
+                .into_iter()
+                .chain([h.point.0, h.point.1, h.velocity.0, h.velocity.1].map(|v| v as f64))
+        })
+        .collect()
+}
+
+/// Take the collision events queued since the last call, flattened:
+/// [kind, bodyA, bodyB, nx, ny, pointX, pointY, ...] with kind 0=begin, 1=stay, 2=end.
+#[deno_core::op2]
+#[serde]
+fn op_get_collision_events(state: &mut OpState) -> Vec<f64> {
+    let physics = state.borrow_mut::<Rc<RefCell<PhysicsState>>>();
+    let mut ps = physics.borrow_mut();
+    let Some(world) = ps.0.as_mut() else {
+        return vec![];
+    };
+    let events = world.take_collision_events();
+    let mut result = Vec::with_capacity(events.len() * 7);
+    for e in events {
+        let kind = match e.kind {
+            CollisionEventKind::Begin => 0.0,
+            CollisionEventKind::Stay => 1.0,
+            CollisionEventKind::End => 2.0,
+        };
+        result.extend_from_slice(&[
+            kind,
+            e.body_a as f64,
+            e.body_b as f64,
+            e.normal.0 as f64,
+            e.normal.1 as f64,
+            e.point.0 as f64,
+            e.point.1 as f64,
+        ]);
+    }
+    result
+}
+
+/// Returns flattened contacts: [bodyA, bodyB, nx, ny, penetration, contactX, contactY,
+/// impulse, relVelX, relVelY, ...].
 #[deno_core::op2]
 #[serde]
 fn op_get_contacts(state: &mut OpState) -> Vec<f64> {
@@ -442,7 +1303,7 @@ fn op_get_contacts(state: &mut OpState) -> Vec<f64> {
     match ps.0.as_ref() {
         Some(world) => {
             let contacts = world.get_contacts();
-            let mut result = Vec::with_capacity(contacts.len() * 7);
+            let mut result = Vec::with_capacity(contacts.len() * 10);
             for c in contacts {
                 result.push(c.body_a as f64);
                 result.push(c.body_b as f64);
@@ -451,6 +1312,9 @@ fn op_get_contacts(state: &mut OpState) -> Vec<f64> {
                 result.push(c.penetration as f64);
                 result.push(c.contact_point.0 as f64);
                 result.push(c.contact_point.1 as f64);
+                result.push(c.impulse as f64);
+                result.push(c.relative_velocity.0 as f64);
+                result.push(c.relative_velocity.1 as f64);
             }
             result
         }
@@ -526,6 +1390,12 @@ deno_core::extension!(
         op_create_physics_world,
         op_destroy_physics_world,
         op_physics_step,
+        op_physics_config,
+        op_get_physics_config,
+        op_set_physics_deterministic,
+        op_physics_state_hash,
+        op_physics_snapshot,
+        op_physics_restore,
         op_create_body,
         op_create_polygon_body,
         op_remove_body,
@@ -534,16 +1404,49 @@ deno_core::extension!(
         op_set_body_angular_velocity,
         op_apply_force,
         op_apply_impulse,
+        op_apply_radial_impulse,
+        op_set_body_damping,
+        op_set_body_locks,
+        op_set_body_max_speed,
+        op_set_body_sensor,
+        op_set_body_one_way,
         op_set_body_position,
         op_set_collision_layers,
         op_create_distance_joint,
         op_create_revolute_joint,
         op_create_soft_distance_joint,
         op_create_soft_revolute_joint,
+        op_create_rope_joint,
+        op_create_pulley_joint,
+        op_create_prismatic_joint,
+        op_create_weld_joint,
+        op_set_joint_motor,
+        op_create_mouse_joint,
+        op_set_mouse_joint_target,
+        op_set_mouse_joint_params,
         op_remove_constraint,
+        op_load_physics_rig,
+        op_create_vehicle,
+        op_set_vehicle_input,
+        op_remove_vehicle,
+        op_get_vehicle_state,
+        op_create_projectile_batch,
+        op_remove_projectile_batch,
+        op_spawn_projectiles,
+        op_clear_projectiles,
+        op_get_projectiles,
+        op_get_projectile_count,
+        op_take_projectile_hits,
         op_query_aabb,
+        op_query_aabb_batch,
+        op_overlap_circle,
+        op_overlap_box,
+        op_overlap_polygon,
         op_raycast,
+        op_raycast_batch,
+        op_get_query_stats,
         op_get_contacts,
+        op_get_collision_events,
         op_get_manifolds,
         op_get_all_body_states,
     ],
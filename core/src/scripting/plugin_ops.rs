@@ -0,0 +1,40 @@
+//! Plugin ops: list registered plugins and call their JSON functions.
+//!
+//! `op_list_plugins` returns a JSON array of [`PluginInfo::to_json`] objects.
+//! `op_call_plugin` returns `{"ok":true,"result":<json>}` or
+//! `{"ok":false,"error":"..."}`; the plugin's result is embedded as-is, so it
+//! must be valid JSON (an empty result becomes `null`).
+//!
+//! [`PluginInfo::to_json`]: crate::plugin::PluginInfo::to_json
+
+use crate::json::escape;
+
+#[deno_core::op2]
+#[string]
+fn op_list_plugins() -> String {
+    let plugins: Vec<String> = crate::plugin::loaded().iter().map(|p| p.to_json()).collect();
+    format!("[{}]", plugins.join(","))
+}
+
+#[deno_core::op2]
+#[string]
+fn op_call_plugin(
+    #[string] plugin: &str,
+    #[string] function: &str,
+    #[string] args: &str,
+) -> String {
+    let result = match crate::plugin::get(plugin) {
+        Some(p) => p.call(function, args),
+        None => Err(format!("no plugin named \"{plugin}\"")),
+    };
+    match result {
+        Ok(json) if json.trim().is_empty() => "{\"ok\":true,\"result\":null}".to_string(),
+        Ok(json) => format!("{{\"ok\":true,\"result\":{json}}}"),
+        Err(e) => format!("{{\"ok\":false,\"error\":\"{}\"}}", escape(&e)),
+    }
+}
+
+deno_core::extension!(
+    plugin_ext,
+    ops = [op_list_plugins, op_call_plugin],
+);
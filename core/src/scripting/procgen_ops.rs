@@ -0,0 +1,87 @@
+//! Dungeon generation ops: run the Rust generators in `crate::procgen::dungeon`
+//! and return the result as a JSON string.
+//!
+//! Output shape (all generators):
+//! `{"width","height","tiles":[...],"rooms":[[x,y,w,h]],"doors":[[x,y]],"spawns":[[x,y]]}`
+//! Tiles are row-major: 0 = wall, 1 = floor, 2 = door.
+
+use crate::procgen::dungeon::{self, BspConfig, CaveConfig, DrunkardConfig};
+
+/// Largest dungeon side the ops generate; larger widths and heights are
+/// clamped to it.
+const MAX_DUNGEON_SIZE: u32 = 1024;
+
+/// Most smoothing passes a cave gets; the automaton has settled long before.
+const MAX_CAVE_ITERATIONS: u32 = 32;
+
+/// BSP rooms connected by L-shaped corridors.
+/// Pass 0 for min_room_size / max_depth to use the defaults (4 / 4).
+#[deno_core::op2]
+#[string]
+fn op_dungeon_bsp(
+    width: u32,
+    height: u32,
+    seed: u32,
+    min_room_size: u32,
+    max_depth: u32,
+) -> String {
+    let defaults = BspConfig::default();
+    let config = BspConfig {
+        min_room_size: if min_room_size == 0 { defaults.min_room_size } else { min_room_size },
+        max_depth: if max_depth == 0 { defaults.max_depth } else { max_depth },
+    };
+    let (width, height) = (width.min(MAX_DUNGEON_SIZE), height.min(MAX_DUNGEON_SIZE));
+    dungeon::generate_bsp(width, height, seed, &config).to_json()
+}
+
+/// Cellular automata caves. fill_percent is the initial wall probability (0-1).
+/// Pass 0 for fill_percent / iterations to use the defaults (0.45 / 5);
+/// iterations are clamped to MAX_CAVE_ITERATIONS.
+#[deno_core::op2]
+#[string]
+fn op_dungeon_caves(
+    width: u32,
+    height: u32,
+    seed: u32,
+    fill_percent: f64,
+    iterations: u32,
+    max_spawns: u32,
+) -> String {
+    let defaults = CaveConfig::default();
+    let config = CaveConfig {
+        fill_percent: if fill_percent > 0.0 { fill_percent as f32 } else { defaults.fill_percent },
+        iterations: if iterations == 0 { defaults.iterations } else { iterations.min(MAX_CAVE_ITERATIONS) },
+        max_spawns: if max_spawns == 0 { defaults.max_spawns } else { max_spawns },
+    };
+    let (width, height) = (width.min(MAX_DUNGEON_SIZE), height.min(MAX_DUNGEON_SIZE));
+    dungeon::generate_caves(width, height, seed, &config).to_json()
+}
+
+/// Drunkard's walk tunnels. floor_percent is the fraction of the interior to carve (0-1).
+/// Pass 0 for floor_percent to use the default (0.4).
+#[deno_core::op2]
+#[string]
+fn op_dungeon_drunkard(
+    width: u32,
+    height: u32,
+    seed: u32,
+    floor_percent: f64,
+    max_spawns: u32,
+) -> String {
+    let defaults = DrunkardConfig::default();
+    let config = DrunkardConfig {
+        floor_percent: if floor_percent > 0.0 { floor_percent as f32 } else { defaults.floor_percent },
+        max_spawns: if max_spawns == 0 { defaults.max_spawns } else { max_spawns },
+    };
+    let (width, height) = (width.min(MAX_DUNGEON_SIZE), height.min(MAX_DUNGEON_SIZE));
+    dungeon::generate_drunkard_walk(width, height, seed, &config).to_json()
+}
+
+deno_core::extension!(
+    procgen_ext,
+    ops = [
+        op_dungeon_bsp,
+        op_dungeon_caves,
+        op_dungeon_drunkard,
+    ],
+);
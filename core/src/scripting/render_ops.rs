@@ -4,11 +4,27 @@ use std::rc::Rc;
 
 use deno_core::OpState;
 
-use crate::renderer::SpriteCommand;
-use crate::renderer::TilemapStore;
-use crate::renderer::PointLight;
+use crate::renderer::{ClipRect, NineSlice, SpriteCommand, SpriteOutline, Viewport};
+use crate::renderer::{TilemapStore, WorldStore};
+use crate::renderer::{AnimationStore, AsepriteSheet, Decal, DecalStore, FlashStore, HitStop, LoopMode, ScreenTransition, SpriteStore, TransitionKind};
+use crate::renderer::{LightAnimation, PointLight, PulseCurve};
 use crate::renderer::camera::CameraBounds;
 use crate::renderer::msdf::MsdfFontStore;
+use crate::renderer::runtime_font::{glyph_px_for_sizes, RuntimeFontStore};
+use crate::platform::action_map::{ActionInputs, ActionMap};
+use crate::platform::input::{events_json, InputEvent};
+use crate::platform::focus::{FocusEvent, FocusPolicy};
+use crate::platform::lifecycle::LifecycleEvent;
+use crate::renderer::{EmitterDef, GpuParticleCommand, QualityConfig, QualityController, SpriteStats};
+use crate::renderer::{AtlasStats, DebugHud, FrameProfiler};
+use crate::renderer::texture_stream::{TextureLoadEvent, TextureStreamStats, DEFAULT_UPLOAD_BUDGET};
+use crate::renderer::{GpuError, GpuErrorCapture};
+use crate::renderer::{BackgroundFill, Letterbox};
+use crate::scripting::asset_cache::{AssetKind, AssetTracker};
+use crate::scripting::curve_ops::resolve_curve;
+use crate::curve::Curve;
+use crate::saves::{ConflictPolicy, LocalDirBackend, SaveBackend, SaveSync, SyncReport};
+use crate::replay::{ReplayFile, ReplayHeader};
 
 /// Audio command queued from TS ops, drained by the frame callback.
 #[derive(Clone, Debug)]
@@ -49,8 +65,35 @@ pub enum BridgeAudioCommand {
         updates: Vec<(u64, f32, f32)>, // (instance_id, source_x, source_y)
         listener_x: f32,
         listener_y: f32,
+        /// Occlusion-enabled instances whose line of sight was blocked.
+        occluded: Vec<u64>,
     },
+    SetOcclusion { instance_id: u64, params: Option<crate::audio::OcclusionParams> },
+    SetDoppler { instance_id: u64, params: Option<crate::audio::DopplerParams> },
+    SetSpatialParams {
+        instance_id: u64,
+        curve: u32,
+        min_distance: f32,
+        max_distance: f32,
+        rolloff: f32,
+    },
+    FadeIn { instance_id: u64, duration: f32, curve: Curve },
+    FadeOut { instance_id: u64, duration: f32, curve: Curve },
+    CrossfadeTo { from_instance: u64, to_instance: u64, duration: f32, curve: Curve },
+    StopAfter { instance_id: u64, delay: f32 },
     SetBusVolume { bus: u32, volume: f32 },
+    /// Effect kind and parameters as passed to `op_set_bus_effect`.
+    SetBusEffect { bus: u32, effect: u32, params: [f32; 3] },
+}
+
+/// Recording request queued from TS ops, drained by the frame callback.
+#[derive(Clone, Debug)]
+pub enum RecordingRequest {
+    /// Start recording to `path` (resolved against the game directory).
+    Start { path: PathBuf, fps: f32 },
+    Stop,
+    /// Write each draw layer of the next frame to `path` (see `LayerCapture`).
+    CaptureLayers { path: PathBuf },
 }
 
 /// Shared state between render ops and the main loop.
@@ -73,6 +116,11 @@ pub struct RenderBridgeState {
     pub mouse_y: f32,
     pub mouse_buttons_down: std::collections::HashSet<u8>,
     pub mouse_buttons_pressed: std::collections::HashSet<u8>,
+    /// Keyboard, text and wheel events since the last frame, drained by `op_poll_input_events`.
+    pub input_events: Vec<InputEvent>,
+    /// Screen rect (x, y, w, h) of the focused text field, set by
+    /// `op_start_text_input`. Enables the IME while set.
+    pub text_input: Option<[f32; 4]>,
     /// Gamepad state: buttons down per button name string.
     pub gamepad_buttons_down: std::collections::HashSet<String>,
     /// Gamepad buttons pressed this frame.
@@ -83,6 +131,8 @@ pub struct RenderBridgeState {
     pub gamepad_count: u32,
     /// Name of the primary gamepad.
     pub gamepad_name: String,
+    /// Named actions and their bindings, evaluated once per frame by the dev loop.
+    pub action_map: ActionMap,
     /// Touch state: active touch points as (id, x, y).
     pub touch_points: Vec<(u64, f32, f32)>,
     /// Number of active touches.
@@ -91,23 +141,52 @@ pub struct RenderBridgeState {
     pub texture_load_queue: Vec<(String, u32)>,
     /// Pending texture load requests with linear filtering.
     pub texture_load_queue_linear: Vec<(String, u32)>,
+    /// Pending normal map links from `op_load_texture_pair` (albedo ID, normal map ID).
+    pub normal_map_queue: Vec<(u32, u32)>,
     /// Base directory for resolving relative texture paths.
     pub base_dir: PathBuf,
     /// Next texture ID to assign (for pre-registration before GPU load).
     pub next_texture_id: u32,
-    /// Map of path → already-assigned texture ID.
+    /// Map of path → already-assigned texture ID. Preserved across hot reloads.
     pub texture_path_to_id: std::collections::HashMap<String, u32>,
+    /// Last requested color per solid texture name (re-uploaded in place on change).
+    pub solid_texture_colors: std::collections::HashMap<String, [u32; 4]>,
+    /// Disk fingerprints of file-backed textures and sounds, for reload invalidation.
+    pub asset_tracker: AssetTracker,
+    /// Colliders traced from texture alpha as JSON, by (texture ID, alpha threshold, simplify epsilon bits).
+    pub collider_cache: std::collections::HashMap<(u32, u8, u32), String>,
     /// Tilemap storage (managed by tilemap ops).
     pub tilemaps: TilemapStore,
+    /// Tilemaps persisted to `.arcane/world/` (see `op_attach_world`).
+    pub world: WorldStore,
+    /// Sprite animations, advanced by the dev loop each frame.
+    pub animations: AnimationStore,
+    /// Aseprite sheets loaded by `op_load_aseprite` and their texture IDs. Sheet ID = index + 1.
+    pub aseprite_sheets: Vec<(AsepriteSheet, u32)>,
+    /// Map of resolved JSON path → sheet ID.
+    pub aseprite_paths: std::collections::HashMap<String, u32>,
+    /// Decals, aged by the dev loop each frame.
+    pub decals: DecalStore,
+    /// Retained sprites, drawn every frame until destroyed.
+    pub sprite_store: SpriteStore,
+    /// Hit flashes by sprite key, aged by the dev loop each frame. Shared so
+    /// sprite ops can read it while queueing into the bridge.
+    pub flashes: Rc<RefCell<FlashStore>>,
+    /// Hit-stop freeze, shake and rumble, started each frame by the dev loop.
+    pub hitstop: HitStop,
     /// Lighting: ambient color (0-1 per channel). Default white = no darkening.
     pub ambient_light: [f32; 3],
     /// Lighting: point lights for this frame.
     pub point_lights: Vec<PointLight>,
     /// Audio commands queued by TS, drained each frame.
     pub audio_commands: Vec<BridgeAudioCommand>,
+    /// Output levels last published by the audio thread.
+    pub audio_levels: crate::audio::AudioLevels,
+    /// Collision layer mask of each spatial instance with occlusion enabled.
+    pub occlusion_masks: std::collections::HashMap<u64, u16>,
     /// Next sound ID to assign.
     pub next_sound_id: u32,
-    /// Map of sound path → assigned sound ID.
+    /// Map of sound path → assigned sound ID. Preserved across hot reloads.
     pub sound_path_to_id: std::collections::HashMap<String, u32>,
     /// Font texture creation queue (texture IDs to create as built-in font).
     pub font_texture_queue: Vec<u32>,
@@ -118,14 +197,49 @@ pub struct RenderBridgeState {
     pub scale_factor: f32,
     /// Clear/background color [r, g, b, a] in 0.0-1.0 range.
     pub clear_color: [f32; 4],
+    /// Texture drawn behind everything instead of the clear color (see `op_set_background_texture`).
+    pub background_fill: Option<BackgroundFill>,
+    /// Texture a background gradient is baked into, reused when it changes.
+    pub background_gradient_texture: Option<u32>,
+    /// Color drawn outside the camera bounds.
+    pub letterbox: Option<Letterbox>,
+    /// Layers whose sprites draw in order of their pivot's y (see `op_set_layer_ysort`).
+    pub ysort_layers: std::collections::BTreeSet<i32>,
+    /// Round sprite positions to the camera's pixel grid (see `op_set_pixel_snap`).
+    pub pixel_snap: bool,
+    /// Pixel snapping for sprites submitted through the sprite batch, when
+    /// they override `pixel_snap` (see `op_set_batch_pixel_snap`).
+    pub batch_pixel_snap: Option<bool>,
+    /// Split-screen viewports by ID, drawn in ID order (see `op_create_viewport`).
+    pub viewports: std::collections::BTreeMap<u32, Viewport>,
+    pub next_viewport_id: u32,
+    /// Recording start/stop and layer capture requests for the renderer.
+    pub recording_requests: Vec<RecordingRequest>,
+    /// Whether a recording is in progress (cleared by the frame callback if starting fails).
+    pub recording: bool,
+    /// MSAA samples per pixel for the scene (1 = off). The frame callback
+    /// lowers it to what the GPU supports.
+    pub msaa_samples: u32,
+    /// Pack small textures into runtime atlas pages (applied to the renderer each frame).
+    pub runtime_atlas: bool,
+    /// GPU adapter details (synced from the renderer once it exists).
+    pub gpu_info: Option<super::engine_info::GpuInfo>,
     /// Directory for save files (.arcane/saves/ relative to game entry file).
     pub save_dir: PathBuf,
+    /// Sync of the save files with a plugin's remote backend (see `op_enable_save_sync`).
+    pub save_sync: Option<SaveSync>,
+    /// Capture the next rendered frame as the replay thumbnail (see `op_request_replay_thumbnail`).
+    pub replay_thumbnail_requested: bool,
+    /// Downscaled PNG of the frame captured for the replay being recorded.
+    pub replay_thumbnail: Option<Vec<u8>>,
     /// Custom shader creation queue: (id, name, wgsl_source).
     pub shader_create_queue: Vec<(u32, String, String)>,
     /// Custom shader param updates: (shader_id, index, [x, y, z, w]).
     pub shader_param_queue: Vec<(u32, u32, [f32; 4])>,
     /// Next shader ID to assign.
     pub next_shader_id: u32,
+    /// File-backed custom shaders: resolved path -> (shader ID, name).
+    pub shader_files: std::collections::HashMap<String, (u32, String)>,
     /// Post-process effect creation queue: (id, effect_type_name).
     pub effect_create_queue: Vec<(u32, String)>,
     /// Post-process effect param updates: (effect_id, index, [x, y, z, w]).
@@ -136,6 +250,12 @@ pub struct RenderBridgeState {
     pub effect_clear: bool,
     /// Next effect ID to assign.
     pub next_effect_id: u32,
+    /// Screen transition in progress, advanced by the dev loop each frame.
+    pub transition: ScreenTransition,
+    /// Custom transition shader creation queue: (id, wgsl_source).
+    pub transition_shader_queue: Vec<(u32, String)>,
+    /// Next transition shader ID to assign.
+    pub next_transition_shader_id: u32,
     /// Camera bounds (world-space limits).
     pub camera_bounds: Option<CameraBounds>,
     /// Whether global illumination (radiance cascades) is enabled.
@@ -154,8 +274,22 @@ pub struct RenderBridgeState {
     pub occluders: Vec<[f32; 4]>,
     /// Directional lights: (angle, r, g, b, intensity).
     pub directional_lights: Vec<[f32; 5]>,
-    /// Spot lights: (x, y, angle, spread, range, r, g, b, intensity).
-    pub spot_lights: Vec<[f32; 9]>,
+    /// Spot lights: (x, y, angle, spread, range, r, g, b, intensity) and animation.
+    pub spot_lights: Vec<([f32; 9], Option<LightAnimation>)>,
+    /// Adaptive quality settings requested by TS (applied to the renderer each frame).
+    pub quality_config: QualityConfig,
+    /// Adaptive quality status JSON (synced from the renderer each frame).
+    pub quality_status: String,
+    /// Focus loss behavior requested by TS (applied by the dev loop).
+    pub focus_policy: FocusPolicy,
+    /// Whether the window has focus (synced each frame).
+    pub window_focused: bool,
+    /// Whether the simulation is paused by the focus policy (synced each frame).
+    pub simulation_paused: bool,
+    /// Focus changes not yet read by TS.
+    pub focus_events: Vec<FocusEvent>,
+    /// Lifecycle changes (suspend/resume, memory warnings, resizes) not yet read by TS.
+    pub lifecycle_events: Vec<LifecycleEvent>,
     /// MSDF font storage.
     pub msdf_fonts: MsdfFontStore,
     /// Queue for creating built-in MSDF font: (font_id, texture_id).
@@ -166,17 +300,46 @@ pub struct RenderBridgeState {
     pub msdf_shader_pool: Vec<u32>,
     /// Pending MSDF texture loads (needs linear sampling, not sRGB).
     pub msdf_texture_load_queue: Vec<(String, u32)>,
+    /// MSDF fonts generated from TTF/OTF files; their atlases are uploaded as glyphs finish.
+    pub runtime_fonts: Rc<RefCell<RuntimeFontStore>>,
     /// Raw RGBA texture upload queue: (texture_id, width, height, pixels).
     pub raw_texture_upload_queue: Vec<(u32, u32, u32, Vec<u8>)>,
     /// Frame timing: milliseconds elapsed during the last frame's script execution.
     pub frame_time_ms: f64,
     /// Frame timing: number of draw calls (sprite commands) queued last frame.
     pub draw_call_count: usize,
+    /// Per-subsystem timings of recent frames (recorded by the dev loop).
+    pub frame_profile: FrameProfiler,
+    /// Built-in debug HUD: toggle, shader and readings (filled in by the dev loop).
+    pub debug_hud: DebugHud,
+    /// Sprite draw calls and instance uploads for the last rendered frame (synced from the renderer).
+    pub sprite_stats: SpriteStats,
+    /// Runtime atlas pages and occupancy (synced from the renderer).
+    pub atlas_stats: AtlasStats,
+    /// Bytes of streamed texture pixels uploaded per frame (applied to the renderer each frame).
+    pub texture_upload_budget: u64,
+    /// Streamed texture progress, loads and failures since the last `op_take_texture_events`.
+    pub texture_events: Vec<TextureLoadEvent>,
+    /// Textures still decoding or uploading (synced from the renderer).
+    pub texture_stream_stats: TextureStreamStats,
+    /// How much GPU work runs inside validation error scopes (applied to the renderer each frame).
+    pub gpu_error_capture: GpuErrorCapture,
+    /// GPU validation errors not yet taken by TS.
+    pub gpu_errors: Vec<GpuError>,
+    /// Most recent distinct GPU errors (synced from the renderer, for the inspector).
+    pub recent_gpu_errors: Vec<GpuError>,
+    /// GPU particle emitter commands, applied by the frame callback before rendering.
+    pub gpu_particle_commands: Vec<GpuParticleCommand>,
+    pub next_gpu_emitter_id: u32,
+    /// Pushed clip rects, each already intersected with the one below. The
+    /// top clips every sprite queued; cleared each frame.
+    pub clip_stack: Vec<ClipRect>,
 }
 
 impl RenderBridgeState {
     pub fn new(base_dir: PathBuf) -> Self {
         let save_dir = base_dir.join(".arcane").join("saves");
+        let world_dir = base_dir.join(".arcane").join("world");
         Self {
             sprite_commands: Vec::new(),
             camera_x: 0.0,
@@ -191,22 +354,39 @@ impl RenderBridgeState {
             mouse_y: 0.0,
             mouse_buttons_down: std::collections::HashSet::new(),
             mouse_buttons_pressed: std::collections::HashSet::new(),
+            input_events: Vec::new(),
+            text_input: None,
             gamepad_buttons_down: std::collections::HashSet::new(),
             gamepad_buttons_pressed: std::collections::HashSet::new(),
             gamepad_axes: std::collections::HashMap::new(),
             gamepad_count: 0,
             gamepad_name: String::new(),
+            action_map: ActionMap::new(),
             touch_points: Vec::new(),
             touch_count: 0,
             texture_load_queue: Vec::new(),
             texture_load_queue_linear: Vec::new(),
+            normal_map_queue: Vec::new(),
             base_dir,
             next_texture_id: 1,
             texture_path_to_id: std::collections::HashMap::new(),
+            solid_texture_colors: std::collections::HashMap::new(),
+            asset_tracker: AssetTracker::new(),
+            collider_cache: std::collections::HashMap::new(),
             tilemaps: TilemapStore::new(),
+            world: WorldStore::new(world_dir),
+            animations: AnimationStore::new(),
+            aseprite_sheets: Vec::new(),
+            aseprite_paths: std::collections::HashMap::new(),
+            decals: DecalStore::new(),
+            sprite_store: SpriteStore::new(),
+            flashes: Rc::new(RefCell::new(FlashStore::new())),
+            hitstop: HitStop::new(),
             ambient_light: [1.0, 1.0, 1.0],
             point_lights: Vec::new(),
             audio_commands: Vec::new(),
+            audio_levels: crate::audio::AudioLevels::default(),
+            occlusion_masks: std::collections::HashMap::new(),
             next_sound_id: 1,
             sound_path_to_id: std::collections::HashMap::new(),
             font_texture_queue: Vec::new(),
@@ -214,15 +394,35 @@ impl RenderBridgeState {
             viewport_height: 600.0,
             scale_factor: 1.0,
             clear_color: [0.1, 0.1, 0.15, 1.0],
+            background_fill: None,
+            background_gradient_texture: None,
+            letterbox: None,
+            ysort_layers: std::collections::BTreeSet::new(),
+            pixel_snap: false,
+            batch_pixel_snap: None,
+            viewports: std::collections::BTreeMap::new(),
+            next_viewport_id: 1,
+            recording_requests: Vec::new(),
+            recording: false,
+            msaa_samples: 1,
+            runtime_atlas: false,
+            gpu_info: None,
             save_dir,
+            save_sync: None,
+            replay_thumbnail_requested: false,
+            replay_thumbnail: None,
             shader_create_queue: Vec::new(),
             shader_param_queue: Vec::new(),
             next_shader_id: 1,
+            shader_files: std::collections::HashMap::new(),
             effect_create_queue: Vec::new(),
             effect_param_queue: Vec::new(),
             effect_remove_queue: Vec::new(),
             effect_clear: false,
             next_effect_id: 1,
+            transition: ScreenTransition::new(),
+            transition_shader_queue: Vec::new(),
+            next_transition_shader_id: 1,
             camera_bounds: None,
             gi_enabled: false,
             gi_intensity: 1.0,
@@ -233,15 +433,189 @@ impl RenderBridgeState {
             occluders: Vec::new(),
             directional_lights: Vec::new(),
             spot_lights: Vec::new(),
+            quality_config: QualityConfig::default(),
+            quality_status: QualityController::new().status_json(),
+            focus_policy: FocusPolicy::default(),
+            window_focused: true,
+            simulation_paused: false,
+            focus_events: Vec::new(),
+            lifecycle_events: Vec::new(),
             msdf_fonts: MsdfFontStore::new(),
             msdf_builtin_queue: Vec::new(),
             msdf_shader_queue: Vec::new(),
             msdf_shader_pool: Vec::new(),
             msdf_texture_load_queue: Vec::new(),
+            runtime_fonts: Rc::new(RefCell::new(RuntimeFontStore::new())),
             raw_texture_upload_queue: Vec::new(),
             frame_time_ms: 0.0,
             draw_call_count: 0,
+            frame_profile: FrameProfiler::new(),
+            debug_hud: DebugHud::default(),
+            sprite_stats: SpriteStats::default(),
+            atlas_stats: AtlasStats::default(),
+            texture_upload_budget: DEFAULT_UPLOAD_BUDGET,
+            texture_events: Vec::new(),
+            texture_stream_stats: TextureStreamStats::default(),
+            gpu_error_capture: GpuErrorCapture::default(),
+            gpu_errors: Vec::new(),
+            recent_gpu_errors: Vec::new(),
+            gpu_particle_commands: Vec::new(),
+            next_gpu_emitter_id: 1,
+            clip_stack: Vec::new(),
+        }
+    }
+
+    /// Re-queue file-backed textures and sounds whose files changed on disk,
+    /// keeping their IDs. Called on hot reload so IDs held by game code (and by
+    /// the GPU/audio stores) stay valid. Returns the number of assets re-queued.
+    pub fn requeue_changed_assets(&mut self) -> usize {
+        let changed = self.asset_tracker.take_changed();
+        for (path, asset) in &changed {
+            match asset.kind {
+                AssetKind::Texture => self.texture_load_queue.push((path.clone(), asset.id)),
+                AssetKind::TextureLinear => self.texture_load_queue_linear.push((path.clone(), asset.id)),
+                AssetKind::Sound => self
+                    .audio_commands
+                    .push(BridgeAudioCommand::LoadSound { id: asset.id, path: path.clone() }),
+                AssetKind::Shader => {
+                    if let Some((_, name)) = self.shader_files.get(path)
+                        && let Ok(source) = std::fs::read_to_string(path)
+                    {
+                        self.shader_create_queue.push((asset.id, name.clone(), source));
+                    }
+                }
+            }
+        }
+        self.collider_cache
+            .retain(|(id, ..), _| !changed.iter().any(|(_, asset)| asset.id == *id));
+        changed.len()
+    }
+
+    /// ID of the texture at `path` (relative to `base_dir`), queueing a load
+    /// the first time the path is seen. `linear` loads it as linear (non-sRGB)
+    /// data with bilinear filtering; the first load of a path decides.
+    pub fn load_texture(&mut self, path: &str, linear: bool) -> u32 {
+        // Resolve relative paths against base_dir
+        let resolved = if std::path::Path::new(path).is_absolute() {
+            path.to_string()
+        } else {
+            self.base_dir.join(path).to_string_lossy().to_string()
+        };
+
+        // Check cache (note: linear textures share the same ID space but use separate queue)
+        if let Some(&id) = self.texture_path_to_id.get(&resolved) {
+            return id;
+        }
+
+        let id = self.next_texture_id;
+        self.next_texture_id += 1;
+        self.texture_path_to_id.insert(resolved.clone(), id);
+        if linear {
+            self.asset_tracker.track(AssetKind::TextureLinear, &resolved, id);
+            self.texture_load_queue_linear.push((resolved, id));
+        } else {
+            self.asset_tracker.track(AssetKind::Texture, &resolved, id);
+            self.texture_load_queue.push((resolved, id));
+        }
+        id
+    }
+
+    /// ID of the built-in 8×8 font texture, queueing its creation on first use.
+    pub fn builtin_font_texture(&mut self) -> u32 {
+        let key = "__builtin_font__".to_string();
+        if let Some(&id) = self.texture_path_to_id.get(&key) {
+            return id;
+        }
+
+        let id = self.next_texture_id;
+        self.next_texture_id += 1;
+        self.texture_path_to_id.insert(key, id);
+        self.font_texture_queue.push(id);
+        id
+    }
+
+    /// IDs of the built-in MSDF font and its atlas texture, `(font_id,
+    /// texture_id)`, registering the font and queueing the atlas upload on
+    /// first use.
+    pub fn builtin_msdf_font(&mut self) -> (u32, u32) {
+        let key = "__msdf_builtin__".to_string();
+        if let Some(&tex_id) = self.texture_path_to_id.get(&key) {
+            // The font was registered with the texture_id as the lookup key
+            if let Some(&font_id) = self.texture_path_to_id.get(&format!("__msdf_font_{tex_id}__")) {
+                return (font_id, tex_id);
+            }
+        }
+
+        // Assign texture ID
+        let tex_id = self.next_texture_id;
+        self.next_texture_id += 1;
+        self.texture_path_to_id.insert(key, tex_id);
+
+        // Generate MSDF atlas data and register font
+        let (_pixels, _width, _height, mut font) =
+            crate::renderer::msdf::generate_builtin_msdf_font();
+        font.texture_id = tex_id;
+        let font_id = self.msdf_fonts.register(font);
+        self.texture_path_to_id
+            .insert(format!("__msdf_font_{tex_id}__"), font_id);
+
+        // Queue the texture for GPU upload.
+        // dev.rs will call generate_builtin_msdf_font() again and upload pixels.
+        self.msdf_builtin_queue.push((font_id, tex_id));
+        (font_id, tex_id)
+    }
+
+    /// Built-in MSDF font ID and the debug HUD's own MSDF shader ID, creating
+    /// both on first use.
+    pub fn debug_hud_resources(&mut self) -> (u32, u32) {
+        let (font_id, _) = self.builtin_msdf_font();
+        if self.debug_hud.shader_id == 0 {
+            let id = self.next_shader_id;
+            self.next_shader_id += 1;
+            self.msdf_shader_queue
+                .push((id, crate::renderer::msdf::MSDF_FRAGMENT_SOURCE.to_string()));
+            self.debug_hud.shader_id = id;
+        }
+        (font_id, self.debug_hud.shader_id)
+    }
+
+    /// ID of a named 1×1 solid-color texture, queueing an upload when the name
+    /// is new or its color changed.
+    pub fn solid_texture(&mut self, name: &str, color: [u32; 4]) -> u32 {
+        let key = format!("__solid__{name}");
+        let [r, g, b, a] = color;
+        if let Some(&id) = self.texture_path_to_id.get(&key) {
+            // Same name, new color (e.g. edited before a hot reload): re-upload under the same ID
+            if self.solid_texture_colors.get(name) != Some(&color) {
+                self.solid_texture_colors.insert(name.to_string(), color);
+                self.texture_load_queue
+                    .push((format!("__solid__:{name}:{r}:{g}:{b}:{a}"), id));
+            }
+            return id;
         }
+
+        let id = self.next_texture_id;
+        self.next_texture_id += 1;
+        self.texture_path_to_id.insert(key, id);
+        self.solid_texture_colors.insert(name.to_string(), color);
+        // Encode color in the path as a signal to the loader
+        self.texture_load_queue
+            .push((format!("__solid__:{name}:{r}:{g}:{b}:{a}"), id));
+        id
+    }
+
+    /// Evaluate the action map against this frame's synced input.
+    pub fn update_actions(&mut self) {
+        let inputs = ActionInputs {
+            keys_down: &self.keys_down,
+            keys_pressed: &self.keys_pressed,
+            mouse_buttons_down: &self.mouse_buttons_down,
+            mouse_buttons_pressed: &self.mouse_buttons_pressed,
+            gamepad_buttons_down: &self.gamepad_buttons_down,
+            gamepad_buttons_pressed: &self.gamepad_buttons_pressed,
+            gamepad_axes: &self.gamepad_axes,
+        };
+        self.action_map.update(&inputs);
     }
 }
 
@@ -255,25 +629,105 @@ pub fn op_clear_sprites(state: &mut OpState) {
 /// Number of f32 values per sprite in the batch buffer.
 /// Layout: [texture_id, x, y, w, h, layer, uv_x, uv_y, uv_w, uv_h,
 ///          tint_r, tint_g, tint_b, tint_a, rotation, origin_x, origin_y,
-///          flip_x, flip_y, opacity, blend_mode, shader_id]
-pub const SPRITE_STRIDE: usize = 22;
+///          flip_x, flip_y, opacity, blend_mode, shader_id, flash_key]
+pub const SPRITE_STRIDE: usize = 23;
 
 /// Submit a batch of sprites from a packed Float32Array.
-/// Each sprite is SPRITE_STRIDE (22) f32 values. See layout above.
-/// Called from TS sprites.ts flush path for bulk submission.
+/// Each sprite is SPRITE_STRIDE (23) f32 values. See layout above.
+/// Called from the TS sprites.ts flush path.
 #[deno_core::op2(fast)]
 pub fn op_submit_sprite_batch(state: &mut OpState, #[buffer] data: &[u8]) {
-    let floats: &[f32] = bytemuck::cast_slice(data);
-    let sprite_count = floats.len() / SPRITE_STRIDE;
+    queue_sprite_records(state, &packed_f32s(data));
+}
 
-    // Check if a render target is active — batch goes to target or main surface
-    let active_target = {
-        use super::target_ops::TargetState;
-        let ts = state.borrow::<Rc<RefCell<TargetState>>>();
-        ts.borrow().active_target
-    };
+/// Draw the first `count` sprites of a Float32Array packed by game code,
+/// SPRITE_STRIDE (23) values each (see layout above), decoded in place
+/// rather than copied out of a byte view. `count` is clamped to the whole
+/// records in `data`. Called by `drawSpriteBuffer`.
+#[deno_core::op2(fast)]
+pub fn op_draw_sprites_bulk(state: &mut OpState, #[buffer] data: &[f32], count: u32) {
+    let count = (count as usize).min(data.len() / SPRITE_STRIDE);
+    queue_sprite_records(state, &data[..count * SPRITE_STRIDE]);
+}
+
+/// Queue one sprite command per SPRITE_STRIDE record, following the batch
+/// pixel snap mode.
+fn queue_sprite_records(state: &mut OpState, floats: &[f32]) {
+    let flashes = flash_store(state);
+    let flashes = flashes.borrow();
+    let snap = batch_pixel_snap(state);
+    let cmds = floats
+        .chunks_exact(SPRITE_STRIDE)
+        .map(|s| SpriteCommand { pixel_snap: snap, ..with_flash(parse_sprite_record(s), s, &flashes) });
+    queue_sprite_commands(state, cmds);
+}
+
+/// Number of f32 values per nine-slice panel: a sprite record (see
+/// `SPRITE_STRIDE`) followed by the border insets in texture pixels and the
+/// border scale: [left, top, right, bottom, scale].
+pub const NINE_SLICE_STRIDE: usize = SPRITE_STRIDE + 5;
+
+/// Draw nine-slice panels from a packed Float32Array of NINE_SLICE_STRIDE
+/// (28) f32 values each. The renderer splits every panel into its corner,
+/// edge and center quads using the texture's real size.
+#[deno_core::op2(fast)]
+pub fn op_draw_nine_slice(state: &mut OpState, #[buffer] data: &[u8]) {
+    let floats = packed_f32s(data);
+    let flashes = flash_store(state);
+    let flashes = flashes.borrow();
+    let cmds = floats.chunks_exact(NINE_SLICE_STRIDE).map(|s| SpriteCommand {
+        nine_slice: Some(NineSlice {
+            left: s[SPRITE_STRIDE],
+            top: s[SPRITE_STRIDE + 1],
+            right: s[SPRITE_STRIDE + 2],
+            bottom: s[SPRITE_STRIDE + 3],
+            scale: s[SPRITE_STRIDE + 4],
+        }),
+        ..with_flash(parse_sprite_record(s), s, &flashes)
+    });
+    queue_sprite_commands(state, cmds);
+}
+
+/// Number of f32 values per outlined sprite: a sprite record (see
+/// `SPRITE_STRIDE`) followed by the outline color and width in world units:
+/// [r, g, b, a, thickness].
+pub const OUTLINED_SPRITE_STRIDE: usize = SPRITE_STRIDE + 5;
+
+/// Draw sprites with an outline around their alpha silhouette, from a packed
+/// Float32Array of OUTLINED_SPRITE_STRIDE (28) f32 values each.
+#[deno_core::op2(fast)]
+pub fn op_draw_outlined_sprites(state: &mut OpState, #[buffer] data: &[u8]) {
+    let floats = packed_f32s(data);
+    let snap = batch_pixel_snap(state);
+    let flashes = flash_store(state);
+    let flashes = flashes.borrow();
+    let cmds = floats.chunks_exact(OUTLINED_SPRITE_STRIDE).map(|s| SpriteCommand {
+        outline: Some(SpriteOutline {
+            r: s[SPRITE_STRIDE],
+            g: s[SPRITE_STRIDE + 1],
+            b: s[SPRITE_STRIDE + 2],
+            a: s[SPRITE_STRIDE + 3],
+            thickness: s[SPRITE_STRIDE + 4],
+        }),
+        pixel_snap: snap,
+        ..with_flash(parse_sprite_record(s), s, &flashes)
+    });
+    queue_sprite_commands(state, cmds);
+}
 
-    let parse_cmd = |s: &[f32]| SpriteCommand {
+/// View a buffer from script as f32 values. Trailing bytes short of a whole
+/// f32 are ignored, and an unaligned buffer is copied, so a malformed buffer
+/// can't panic the runtime.
+fn packed_f32s(data: &[u8]) -> std::borrow::Cow<'_, [f32]> {
+    let data = &data[..data.len() - data.len() % 4];
+    match bytemuck::try_cast_slice(data) {
+        Ok(floats) => std::borrow::Cow::Borrowed(floats),
+        Err(_) => std::borrow::Cow::Owned(data.chunks_exact(4).map(bytemuck::pod_read_unaligned).collect()),
+    }
+}
+
+fn parse_sprite_record(s: &[f32]) -> SpriteCommand {
+    SpriteCommand {
         texture_id: s[0].to_bits(),
         x: s[1],
         y: s[2],
@@ -296,29 +750,195 @@ pub fn op_submit_sprite_batch(state: &mut OpState, #[buffer] data: &[u8]) {
         opacity: s[19],
         blend_mode: (s[20] as u8).min(3),
         shader_id: s[21].to_bits(),
+        nine_slice: None,
+        outline: None,
+        clip: None,
+        mask: None,
+        normal_map: None,
+        flash: None,
+        pixel_snap: None,
+    }
+}
+
+/// The bridge's hit flash store (see `op_entity_flash`).
+fn flash_store(state: &OpState) -> Rc<RefCell<FlashStore>> {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().flashes.clone()
+}
+
+/// Apply the hit flash of a sprite record's `flash_key`, if it is flashing.
+fn with_flash(cmd: SpriteCommand, record: &[f32], flashes: &FlashStore) -> SpriteCommand {
+    SpriteCommand { flash: flashes.sample(record[SPRITE_STRIDE - 1].to_bits()), ..cmd }
+}
+
+/// The clip rect from `op_push_clip_rect` that sprites queued now get, if any.
+pub fn active_clip(state: &OpState) -> Option<ClipRect> {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().clip_stack.last().copied()
+}
+
+/// Queue sprites on the active render target, or the main surface when none
+/// is active. If a scene node is attached, sprites are local to that node.
+fn queue_sprite_commands(
+    state: &mut OpState,
+    cmds: impl ExactSizeIterator<Item = SpriteCommand>,
+) {
+    let active_target = super::target_ops::active_target(state);
+
+    let node_transform = {
+        use super::node_ops::NodeState;
+        let ns = state.borrow::<Rc<RefCell<NodeState>>>();
+        ns.borrow_mut().active_transform()
+    };
+
+    let clip = active_clip(state);
+    // Render targets have no stencil buffer, so masks only apply to the scene
+    let mask = match active_target {
+        Some(_) => None,
+        None => super::mask_ops::active_mask(state),
     };
 
+    let cmds = cmds.map(|mut cmd| {
+        if let Some(wt) = &node_transform {
+            wt.apply_to_sprite(&mut cmd);
+        }
+        if clip.is_some() {
+            cmd.clip = clip;
+        }
+        if mask.is_some() {
+            cmd.mask = mask;
+        }
+        cmd
+    });
+
     if let Some(target_id) = active_target {
         use super::target_ops::TargetState;
         let ts = state.borrow::<Rc<RefCell<TargetState>>>();
         let mut ts = ts.borrow_mut();
-        let queue = ts.target_sprite_queues.entry(target_id).or_default();
-        queue.reserve(sprite_count);
-        for i in 0..sprite_count {
-            let base = i * SPRITE_STRIDE;
-            queue.push(parse_cmd(&floats[base..base + SPRITE_STRIDE]));
-        }
+        ts.target_sprite_queues.entry(target_id).or_default().extend(cmds);
     } else {
         let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
-        let mut b = bridge.borrow_mut();
-        b.sprite_commands.reserve(sprite_count);
-        for i in 0..sprite_count {
-            let base = i * SPRITE_STRIDE;
-            b.sprite_commands.push(parse_cmd(&floats[base..base + SPRITE_STRIDE]));
-        }
+        bridge.borrow_mut().sprite_commands.extend(cmds);
+    }
+}
+
+/// Clip sprites queued after this to a screen rect in viewport units (top-left
+/// origin), within any clip rect already pushed. Pair with `op_pop_clip_rect`.
+#[deno_core::op2(fast)]
+pub fn op_push_clip_rect(state: &mut OpState, x: f64, y: f64, w: f64, h: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let rect = ClipRect { x: x as f32, y: y as f32, w: w.max(0.0) as f32, h: h.max(0.0) as f32 };
+    let rect = match b.clip_stack.last() {
+        Some(outer) => outer.intersect(&rect),
+        None => rect,
+    };
+    b.clip_stack.push(rect);
+}
+
+/// Restore the clip rect from before the last `op_push_clip_rect`.
+#[deno_core::op2(fast)]
+pub fn op_pop_clip_rect(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().clip_stack.pop();
+}
+
+/// Draw a layer's sprites in order of their pivot's world y (`y + h * origin_y`)
+/// instead of submission order, so top-down characters pass behind and in
+/// front of props on the same layer.
+#[deno_core::op2(fast)]
+pub fn op_set_layer_ysort(state: &mut OpState, layer: i32, enabled: bool) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    if enabled {
+        b.ysort_layers.insert(layer);
+    } else {
+        b.ysort_layers.remove(&layer);
+    }
+}
+
+/// Round sprite positions to the camera's pixel grid after the camera moves
+/// (bounds and shake included), so pixel art doesn't shimmer at sub-pixel
+/// positions. Sprites can override it (see `op_set_batch_pixel_snap`).
+#[deno_core::op2(fast)]
+pub fn op_set_pixel_snap(state: &mut OpState, enabled: bool) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().pixel_snap = enabled;
+}
+
+/// Pixel snapping for the sprite batches submitted next: 0 follows
+/// `op_set_pixel_snap`, 1 snaps and 2 doesn't.
+#[deno_core::op2(fast)]
+pub fn op_set_batch_pixel_snap(state: &mut OpState, mode: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().batch_pixel_snap = match mode {
+        1 => Some(true),
+        2 => Some(false),
+        _ => None,
+    };
+}
+
+fn batch_pixel_snap(state: &OpState) -> Option<bool> {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().batch_pixel_snap
+}
+
+/// Add a split-screen viewport covering `(x, y, w, h)` of the window in
+/// logical pixels. Once any exist, the frame is drawn once per viewport,
+/// in ID order, through the viewport's camera (see `renderer::viewport`).
+/// Returns the viewport ID.
+#[deno_core::op2(fast)]
+pub fn op_create_viewport(state: &mut OpState, x: f64, y: f64, w: f64, h: f64) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let id = b.next_viewport_id;
+    b.next_viewport_id += 1;
+    let rect = ClipRect { x: x as f32, y: y as f32, w: w as f32, h: h as f32 };
+    b.viewports.insert(id, Viewport::new(rect));
+    id
+}
+
+/// Set the camera a viewport draws with: `(x, y)` is the world position of
+/// its top-left corner, and only layers `min..=max` are drawn in it. Layers
+/// above every viewport's range are drawn over the whole window with the
+/// main camera. Unknown IDs are ignored.
+#[deno_core::op2(fast)]
+pub fn op_set_viewport_camera(
+    state: &mut OpState,
+    id: u32,
+    x: f64,
+    y: f64,
+    zoom: f64,
+    rotation: f64,
+    layer_min: i32,
+    layer_max: i32,
+) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    if let Some(viewport) = bridge.borrow_mut().viewports.get_mut(&id) {
+        viewport.x = x as f32;
+        viewport.y = y as f32;
+        viewport.zoom = zoom as f32;
+        viewport.rotation = rotation as f32;
+        viewport.set_layers(layer_min, layer_max);
+    }
+}
+
+/// Move or resize a viewport (logical window pixels). Unknown IDs are ignored.
+#[deno_core::op2(fast)]
+pub fn op_set_viewport_rect(state: &mut OpState, id: u32, x: f64, y: f64, w: f64, h: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    if let Some(viewport) = bridge.borrow_mut().viewports.get_mut(&id) {
+        viewport.rect = ClipRect { x: x as f32, y: y as f32, w: w as f32, h: h as f32 };
     }
 }
 
+/// Remove a viewport. With none left, the frame is drawn once with the main camera.
+#[deno_core::op2(fast)]
+pub fn op_destroy_viewport(state: &mut OpState, id: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().viewports.remove(&id);
+}
+
 /// Update the camera position and zoom.
 /// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
 #[deno_core::op2(fast)]
@@ -340,30 +960,20 @@ pub fn op_get_camera(state: &mut OpState) -> Vec<f64> {
     vec![b.camera_x as f64, b.camera_y as f64, b.camera_zoom as f64]
 }
 
+/// One past the highest texture ID issued so far (IDs start at 1), so dev-mode
+/// sprite validation can spot IDs that were never created.
+#[deno_core::op2(fast)]
+pub fn op_get_texture_id_limit(state: &mut OpState) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().next_texture_id
+}
+
 /// Register a texture to be loaded. Returns a texture ID immediately.
 /// The actual GPU upload happens on the main thread before the next render.
 #[deno_core::op2(fast)]
 pub fn op_load_texture(state: &mut OpState, #[string] path: &str) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut b = bridge.borrow_mut();
-
-    // Resolve relative paths against base_dir
-    let resolved = if std::path::Path::new(path).is_absolute() {
-        path.to_string()
-    } else {
-        b.base_dir.join(path).to_string_lossy().to_string()
-    };
-
-    // Check cache
-    if let Some(&id) = b.texture_path_to_id.get(&resolved) {
-        return id;
-    }
-
-    let id = b.next_texture_id;
-    b.next_texture_id += 1;
-    b.texture_path_to_id.insert(resolved.clone(), id);
-    b.texture_load_queue.push((resolved, id));
-    id
+    bridge.borrow_mut().load_texture(path, false)
 }
 
 /// Load a texture with linear filtering (smooth, blended).
@@ -372,25 +982,21 @@ pub fn op_load_texture(state: &mut OpState, #[string] path: &str) -> u32 {
 #[deno_core::op2(fast)]
 pub fn op_load_texture_linear(state: &mut OpState, #[string] path: &str) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut b = bridge.borrow_mut();
-
-    // Resolve relative paths against base_dir
-    let resolved = if std::path::Path::new(path).is_absolute() {
-        path.to_string()
-    } else {
-        b.base_dir.join(path).to_string_lossy().to_string()
-    };
-
-    // Check cache (note: linear textures share the same ID space but use separate queue)
-    if let Some(&id) = b.texture_path_to_id.get(&resolved) {
-        return id;
-    }
+    bridge.borrow_mut().load_texture(path, true)
+}
 
-    let id = b.next_texture_id;
-    b.next_texture_id += 1;
-    b.texture_path_to_id.insert(resolved.clone(), id);
-    b.texture_load_queue_linear.push((resolved, id));
-    id
+/// Load an albedo texture and its normal map, and shade sprites drawn with the
+/// albedo using the normal map (see `SpriteCommand::normal_map`). The normal
+/// map loads as a linear texture and must have the albedo's layout. Returns
+/// the albedo texture ID.
+#[deno_core::op2(fast)]
+pub fn op_load_texture_pair(state: &mut OpState, #[string] albedo: &str, #[string] normal: &str) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let albedo_id = b.load_texture(albedo, false);
+    let normal_id = b.load_texture(normal, true);
+    b.normal_map_queue.push((albedo_id, normal_id));
+    albedo_id
 }
 
 /// Check if a key is currently held down.
@@ -432,6 +1038,32 @@ pub fn op_is_mouse_button_pressed(state: &mut OpState, button: u8) -> bool {
     bridge.borrow().mouse_buttons_pressed.contains(&button)
 }
 
+/// Keyboard, text and wheel events since the last frame as a JSON array,
+/// oldest first. Each event is returned once; later calls in the same frame
+/// return `[]`.
+#[deno_core::op2]
+#[string]
+pub fn op_poll_input_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = std::mem::take(&mut bridge.borrow_mut().input_events);
+    events_json(&events)
+}
+
+/// Mark a text field as focused at a screen rect: enables the IME and places
+/// its candidate window beside the rect. Call again when the field moves.
+#[deno_core::op2(fast)]
+pub fn op_start_text_input(state: &mut OpState, x: f64, y: f64, w: f64, h: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().text_input = Some([x as f32, y as f32, w as f32, h as f32]);
+}
+
+/// Mark no text field as focused, disabling the IME.
+#[deno_core::op2(fast)]
+pub fn op_stop_text_input(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().text_input = None;
+}
+
 /// Get the delta time (seconds since last frame).
 #[deno_core::op2(fast)]
 pub fn op_get_delta_time(state: &mut OpState) -> f64 {
@@ -439,6 +1071,50 @@ pub fn op_get_delta_time(state: &mut OpState) -> f64 {
     bridge.borrow().delta_time
 }
 
+/// Set what happens while the window is unfocused: pause the simulation,
+/// scale audio output by `audio_gain` (0 = mute), cap the frame rate (0 = uncapped).
+#[deno_core::op2(fast)]
+pub fn op_set_focus_behavior(state: &mut OpState, pause: bool, audio_gain: f64, max_fps: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().focus_policy = FocusPolicy {
+        pause,
+        audio_gain: audio_gain.clamp(0.0, 1.0) as f32,
+        max_fps: max_fps.max(0.0) as u32,
+    };
+}
+
+/// Whether the window currently has focus.
+#[deno_core::op2(fast)]
+pub fn op_is_window_focused(state: &mut OpState) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().window_focused
+}
+
+/// Whether the simulation is paused by the focus loss behavior.
+#[deno_core::op2(fast)]
+pub fn op_is_simulation_paused(state: &mut OpState) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().simulation_paused
+}
+
+/// Take the focus changes since the last call as JSON (see `focus::events_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_focus_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = std::mem::take(&mut bridge.borrow_mut().focus_events);
+    crate::platform::focus::events_json(&events)
+}
+
+/// Take the lifecycle changes since the last call as JSON (see `lifecycle::events_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_lifecycle_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = std::mem::take(&mut bridge.borrow_mut().lifecycle_events);
+    crate::platform::lifecycle::events_json(&events)
+}
+
 /// Create a solid-color texture from TS. Returns texture ID.
 /// The actual GPU upload happens on the main thread.
 #[deno_core::op2(fast)]
@@ -451,20 +1127,7 @@ pub fn op_create_solid_texture(
     a: u32,
 ) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut br = bridge.borrow_mut();
-
-    let key = format!("__solid__{name}");
-    if let Some(&id) = br.texture_path_to_id.get(&key) {
-        return id;
-    }
-
-    let id = br.next_texture_id;
-    br.next_texture_id += 1;
-    br.texture_path_to_id.insert(key.clone(), id);
-    // Encode color in the path as a signal to the loader
-    br.texture_load_queue
-        .push((format!("__solid__:{name}:{r}:{g}:{b}:{a}"), id));
-    id
+    bridge.borrow_mut().solid_texture(name, [r, g, b, a])
 }
 
 /// Upload a raw RGBA texture from a pixel buffer. Cached by name.
@@ -497,6 +1160,52 @@ pub fn op_upload_rgba_texture(
     id
 }
 
+/// Trace polygon colliders from the alpha channel of a file-backed texture.
+/// Pixels with alpha above `alpha_threshold` (0-1) are solid; the outline is
+/// simplified by `simplify_epsilon` pixels and cut into convex pieces.
+/// Returns JSON `[[x0,y0,x1,y1,...],...]`: vertices in pixels relative to the
+/// texture center, ready for op_create_polygon_body. "[]" if the texture is
+/// unknown, not loaded from a file or fully transparent. Cached per texture
+/// until its file changes.
+#[deno_core::op2]
+#[string]
+pub fn op_generate_collider_from_texture(
+    state: &mut OpState,
+    texture_id: u32,
+    alpha_threshold: f64,
+    simplify_epsilon: f64,
+) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let threshold = (alpha_threshold.clamp(0.0, 1.0) * 255.0) as u8;
+    let epsilon = simplify_epsilon.max(0.0) as f32;
+    let key = (texture_id, threshold, epsilon.to_bits());
+    if let Some(json) = b.collider_cache.get(&key) {
+        return json.clone();
+    }
+
+    // Built-in, solid and raw textures are keyed "__...", files by path
+    let path = b
+        .texture_path_to_id
+        .iter()
+        .find(|(path, id)| **id == texture_id && !path.starts_with("__"))
+        .map(|(path, _)| path.clone());
+    let Some(img) = path.and_then(|p| image::open(p).ok()) else {
+        return "[]".to_string();
+    };
+    let rgba = img.to_rgba8();
+    let pieces = crate::physics::sprite_collider::colliders_from_rgba(
+        rgba.as_raw(),
+        rgba.width(),
+        rgba.height(),
+        threshold,
+        epsilon,
+    );
+    let json = crate::physics::sprite_collider::pieces_json(&pieces);
+    b.collider_cache.insert(key, json.clone());
+    json
+}
+
 /// Create a tilemap. Returns tilemap ID.
 #[deno_core::op2(fast)]
 pub fn op_create_tilemap(
@@ -517,55 +1226,669 @@ pub fn op_create_tilemap(
 
 /// Set a tile in a tilemap.
 #[deno_core::op2(fast)]
-pub fn op_set_tile(state: &mut OpState, tilemap_id: u32, gx: u32, gy: u32, tile_id: u32) {
+pub fn op_set_tile(state: &mut OpState, tilemap_id: u32, gx: i32, gy: i32, tile_id: u32) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    if let Some(tm) = bridge.borrow_mut().tilemaps.get_mut(tilemap_id) {
+    let b = &mut *bridge.borrow_mut();
+    b.world.touch(&mut b.tilemaps, tilemap_id, gx, gy, true);
+    if let Some(tm) = b.tilemaps.get_mut(tilemap_id) {
         tm.set_tile(gx, gy, tile_id as u16);
     }
 }
 
 /// Get a tile from a tilemap.
 #[deno_core::op2(fast)]
-pub fn op_get_tile(state: &mut OpState, tilemap_id: u32, gx: u32, gy: u32) -> u32 {
+pub fn op_get_tile(state: &mut OpState, tilemap_id: u32, gx: i32, gy: i32) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge
-        .borrow()
-        .tilemaps
+    let b = &mut *bridge.borrow_mut();
+    b.world.touch(&mut b.tilemaps, tilemap_id, gx, gy, false);
+    b.tilemaps
         .get(tilemap_id)
         .map(|tm| tm.get_tile(gx, gy) as u32)
         .unwrap_or(0)
 }
 
-/// Draw a tilemap's visible tiles as sprite commands (camera-culled).
-/// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
+/// Create an unbounded tilemap stored in 32×32 chunks allocated on demand.
+/// Returns tilemap ID.
 #[deno_core::op2(fast)]
-pub fn op_draw_tilemap(state: &mut OpState, tilemap_id: u32, world_x: f64, world_y: f64, layer: i32) {
+pub fn op_create_chunked_tilemap(
+    state: &mut OpState,
+    texture_id: u32,
+    tile_size: f64,
+    atlas_columns: u32,
+    atlas_rows: u32,
+) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut b = bridge.borrow_mut();
-    let cam_x = b.camera_x;
-    let cam_y = b.camera_y;
-    let cam_zoom = b.camera_zoom;
-    // Default viewport for culling; actual viewport is synced by renderer
-    let vp_w = 800.0;
-    let vp_h = 600.0;
+    bridge
+        .borrow_mut()
+        .tilemaps
+        .create_chunked(texture_id, tile_size as f32, atlas_columns, atlas_rows)
+}
 
-    if let Some(tm) = b.tilemaps.get(tilemap_id) {
-        let cmds = tm.bake_visible(world_x as f32, world_y as f32, layer, cam_x, cam_y, cam_zoom, vp_w, vp_h);
-        b.sprite_commands.extend(cmds);
+/// Overwrite a whole chunk from a packed Uint16Array of tile IDs, row-major
+/// (missing entries are 0). On a chunked tilemap the chunk is 32×32 and is
+/// allocated even when empty; on a dense tilemap it is the 32×32 block of
+/// cells at (cx * 32, cy * 32), clipped to the map. Marks the chunk dirty on
+/// an attached world.
+#[deno_core::op2(fast)]
+pub fn op_set_tilemap_chunk(state: &mut OpState, tilemap_id: u32, cx: i32, cy: i32, #[buffer] tiles: &[u8]) {
+    let tiles: Vec<u16> = tiles.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+    let size = crate::renderer::TILEMAP_CHUNK_SIZE as i32;
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = &mut *bridge.borrow_mut();
+    b.world.touch(&mut b.tilemaps, tilemap_id, cx.saturating_mul(size), cy.saturating_mul(size), true);
+    if let Some(tm) = b.tilemaps.get_mut(tilemap_id) {
+        tm.set_chunk(cx, cy, crate::renderer::TILEMAP_CHUNK_SIZE, &tiles);
     }
 }
 
-// --- Lighting ops ---
-
-/// Set the ambient light color (0-1 per channel).
-/// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
+/// Free a chunk of a chunked tilemap, saving it first if an attached world
+/// has unsaved edits in it. Returns false if it wasn't allocated.
 #[deno_core::op2(fast)]
-pub fn op_set_ambient_light(state: &mut OpState, r: f64, g: f64, b: f64) {
+pub fn op_remove_tilemap_chunk(state: &mut OpState, tilemap_id: u32, cx: i32, cy: i32) -> bool {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().ambient_light = [r as f32, g as f32, b as f32];
+    let b = &mut *bridge.borrow_mut();
+    if let Err(e) = b.world.unload_chunk(&b.tilemaps, tilemap_id, cx, cy) {
+        eprintln!("[world] Failed to save chunk {cx},{cy}: {e}");
+    }
+    b.tilemaps.get_mut(tilemap_id).is_some_and(|tm| tm.remove_chunk(cx, cy))
 }
 
-/// Add a point light at world position (x,y) with radius, color, and intensity.
+/// Number of allocated chunks of a chunked tilemap (0 for dense or unknown).
+#[deno_core::op2(fast)]
+pub fn op_get_tilemap_chunk_count(state: &mut OpState, tilemap_id: u32) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = bridge.borrow();
+    b.tilemaps.get(tilemap_id).map_or(0, |tm| tm.chunk_count() as u32)
+}
+
+/// Grid range seen by the camera around a tilemap drawn at (world_x,
+/// world_y), grown by `margin` tiles on every side.
+fn camera_tile_range(b: &RenderBridgeState, tilemap_id: u32, world_x: f64, world_y: f64, margin: i32) -> Option<[i32; 4]> {
+    let tm = b.tilemaps.get(tilemap_id)?;
+    let view = crate::renderer::view_rect(b.camera_x, b.camera_y, b.camera_zoom, b.viewport_width, b.viewport_height);
+    let [x0, y0, x1, y1] = tm.grid_range(world_x as f32, world_y as f32, view);
+    let margin = margin.max(0);
+    Some([x0.saturating_sub(margin), y0.saturating_sub(margin), x1.saturating_add(margin), y1.saturating_add(margin)])
+}
+
+/// Chunks of a chunked tilemap in the camera view (plus `margin` tiles) that
+/// aren't allocated, as flat `[cx, cy, ...]` pairs, row by row. Chunks saved
+/// by an attached world are loaded first, so only chunks the game still has
+/// to generate are returned.
+#[deno_core::op2]
+#[serde]
+pub fn op_get_missing_tilemap_chunks(
+    state: &mut OpState,
+    tilemap_id: u32,
+    world_x: f64,
+    world_y: f64,
+    margin: i32,
+) -> Vec<i32> {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = &mut *bridge.borrow_mut();
+    let Some(range) = camera_tile_range(b, tilemap_id, world_x, world_y, margin) else {
+        return Vec::new();
+    };
+    b.world.load_range(&mut b.tilemaps, tilemap_id, range);
+    b.tilemaps
+        .get(tilemap_id)
+        .map(|tm| tm.missing_chunks(range).into_iter().flat_map(|(cx, cy)| [cx, cy]).collect())
+        .unwrap_or_default()
+}
+
+/// Free every chunk of a chunked tilemap outside the camera view (plus
+/// `margin` tiles), saving edited ones on an attached world first. Returns
+/// the number of chunks freed.
+#[deno_core::op2(fast)]
+pub fn op_retain_tilemap_chunks(state: &mut OpState, tilemap_id: u32, world_x: f64, world_y: f64, margin: i32) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = &mut *bridge.borrow_mut();
+    let Some(range) = camera_tile_range(b, tilemap_id, world_x, world_y, margin) else {
+        return 0;
+    };
+    let Some(tm) = b.tilemaps.get(tilemap_id) else { return 0 };
+    let [min_cx, min_cy, max_cx, max_cy] = crate::renderer::chunk_range(range);
+    let outside: Vec<(i32, i32)> = tm
+        .chunk_coords()
+        .into_iter()
+        .filter(|&(cx, cy)| cx < min_cx || cx >= max_cx || cy < min_cy || cy >= max_cy)
+        .collect();
+    for &(cx, cy) in &outside {
+        if let Err(e) = b.world.unload_chunk(&b.tilemaps, tilemap_id, cx, cy) {
+            eprintln!("[world] Failed to save chunk {cx},{cy}: {e}");
+        }
+    }
+    b.tilemaps.get_mut(tilemap_id).map_or(0, |tm| tm.retain_chunks(range).len() as u32)
+}
+
+/// Draw a tilemap's visible tiles as sprite commands (camera-culled).
+/// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
+#[deno_core::op2(fast)]
+pub fn op_draw_tilemap(state: &mut OpState, tilemap_id: u32, world_x: f64, world_y: f64, layer: i32) {
+    let mask = super::mask_ops::active_mask(state);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = &mut *bridge.borrow_mut();
+    let cam_x = b.camera_x;
+    let cam_y = b.camera_y;
+    let cam_zoom = b.camera_zoom;
+    // Default viewport for culling; actual viewport is synced by renderer
+    let vp_w = 800.0;
+    let vp_h = 600.0;
+
+    // Stream in saved chunks before they first become visible
+    if b.world.is_attached(tilemap_id) && let Some(tm) = b.tilemaps.get(tilemap_id) {
+        let view = crate::renderer::view_rect(cam_x, cam_y, cam_zoom, vp_w, vp_h);
+        let range = tm.grid_range(world_x as f32, world_y as f32, view);
+        b.world.load_range(&mut b.tilemaps, tilemap_id, range);
+    }
+
+    if let Some(tm) = b.tilemaps.get(tilemap_id) {
+        let mut cmds = tm.bake_visible(world_x as f32, world_y as f32, layer, cam_x, cam_y, cam_zoom, vp_w, vp_h);
+        let clip = b.clip_stack.last().copied();
+        cmds.iter_mut().for_each(|cmd| {
+            cmd.clip = clip;
+            cmd.mask = mask;
+        });
+        b.sprite_commands.extend(cmds);
+    }
+}
+
+/// Draw cells holding `terrain` by their neighbour bitmask. `neighbors` is 4
+/// or 8; `tiles` is a packed Uint16Array of display tiles indexed by mask (16
+/// or 256 entries, 0 = use `fallback`); `connects` lists other tile IDs that
+/// count as neighbours. Returns false for an unknown tilemap or bad mode.
+#[deno_core::op2(fast)]
+pub fn op_set_autotile_rule(
+    state: &mut OpState,
+    tilemap_id: u32,
+    terrain: u32,
+    neighbors: u32,
+    fallback: u32,
+    #[buffer] tiles: &[u8],
+    #[buffer] connects: &[u8],
+) -> bool {
+    if neighbors != 4 && neighbors != 8 {
+        return false;
+    }
+    let rule = crate::renderer::AutotileRule {
+        neighbors: neighbors as u8,
+        tiles: bytemuck::cast_slice::<u8, u16>(tiles).to_vec(),
+        fallback: fallback as u16,
+        connects: bytemuck::cast_slice::<u8, u16>(connects).to_vec(),
+    };
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    match b.tilemaps.get_mut(tilemap_id) {
+        Some(tm) => {
+            tm.set_autotile_rule(terrain as u16, rule);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Remove the auto-tiling rule for a terrain tile.
+#[deno_core::op2(fast)]
+pub fn op_clear_autotile_rule(state: &mut OpState, tilemap_id: u32, terrain: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    if let Some(tm) = bridge.borrow_mut().tilemaps.get_mut(tilemap_id) {
+        tm.clear_autotile_rule(terrain as u16);
+    }
+}
+
+/// Animate a tile: cells displaying it cycle through `frames` (a packed
+/// Uint16Array of tile IDs) at `fps`, advanced by the renderer each frame.
+/// Empty frames remove the animation.
+#[deno_core::op2(fast)]
+pub fn op_set_tile_animation(state: &mut OpState, tilemap_id: u32, tile: u32, fps: f64, #[buffer] frames: &[u8]) {
+    let animation = crate::renderer::TileAnimation {
+        frames: bytemuck::cast_slice::<u8, u16>(frames).to_vec(),
+        fps: fps as f32,
+    };
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    if let Some(tm) = bridge.borrow_mut().tilemaps.get_mut(tilemap_id) {
+        tm.set_tile_animation(tile as u16, animation);
+    }
+}
+
+// --- World persistence ops ---
+
+/// Persist a tilemap's edits under `.arcane/world/<name>/`. Saved chunks are
+/// loaded as they are first read, written or drawn. Returns false for names
+/// other than letters, digits, `_` and `-`.
+#[deno_core::op2(fast)]
+pub fn op_attach_world(state: &mut OpState, tilemap_id: u32, #[string] name: &str) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().world.attach(tilemap_id, name)
+}
+
+/// Stop persisting a tilemap; unsaved edits are dropped.
+#[deno_core::op2(fast)]
+pub fn op_detach_world(state: &mut OpState, tilemap_id: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().world.detach(tilemap_id);
+}
+
+/// Write all dirty chunks now. Returns the number written, or -1 on an I/O error.
+#[deno_core::op2(fast)]
+pub fn op_flush_world(state: &mut OpState) -> i32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = &mut *bridge.borrow_mut();
+    match b.world.flush(&b.tilemaps) {
+        Ok(written) => written as i32,
+        Err(e) => {
+            eprintln!("[world] Failed to save chunks: {e}");
+            -1
+        }
+    }
+}
+
+/// Delete the saved chunks of world `name` (every world when empty).
+#[deno_core::op2(fast)]
+pub fn op_clear_world(state: &mut OpState, #[string] name: &str) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let name = (!name.is_empty()).then_some(name);
+    match bridge.borrow_mut().world.clear(name) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("[world] Failed to clear saved chunks: {e}");
+            false
+        }
+    }
+}
+
+/// Seconds between automatic saves of dirty chunks; 0 saves only on flush.
+#[deno_core::op2(fast)]
+pub fn op_set_world_autosave(state: &mut OpState, seconds: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().world.set_autosave(seconds as f32);
+}
+
+// --- Decal ops ---
+
+/// Number of f32 values per decal: a sprite record (see `SPRITE_STRIDE`)
+/// followed by [lifetime, fade_out] in seconds.
+pub const DECAL_STRIDE: usize = SPRITE_STRIDE + 2;
+
+/// Spawn decals from a packed Float32Array of DECAL_STRIDE (25) f32 values
+/// each. The oldest decals are evicted once the cap is reached. Decals
+/// ignore the record's flash key.
+#[deno_core::op2(fast)]
+pub fn op_spawn_decals(state: &mut OpState, #[buffer] data: &[u8]) {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    for s in floats.chunks_exact(DECAL_STRIDE) {
+        let decal = Decal::new(parse_sprite_record(s), s[SPRITE_STRIDE], s[SPRITE_STRIDE + 1]);
+        b.decals.spawn(decal);
+    }
+}
+
+/// Remove every decal.
+#[deno_core::op2(fast)]
+pub fn op_clear_decals(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().decals.clear();
+}
+
+/// Set how many decals are kept before the oldest are evicted.
+#[deno_core::op2(fast)]
+pub fn op_set_max_decals(state: &mut OpState, max: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().decals.set_max(max as usize);
+}
+
+/// Number of live decals.
+#[deno_core::op2(fast)]
+pub fn op_get_decal_count(state: &mut OpState) -> u32 {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().decals.len() as u32
+}
+
+// --- Retained sprite ops ---
+
+/// Create a retained sprite from one sprite record (see `SPRITE_STRIDE`),
+/// drawn every frame until destroyed. Returns its ID, or 0 for a short
+/// record. Retained sprites ignore the record's flash key and the active
+/// node, clip, mask and render target.
+#[deno_core::op2(fast)]
+pub fn op_sprite_create(state: &mut OpState, #[buffer] data: &[u8]) -> u32 {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    if floats.len() < SPRITE_STRIDE {
+        return 0;
+    }
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.create(parse_sprite_record(floats))
+}
+
+/// Replace a retained sprite with one sprite record. Returns false for an
+/// unknown sprite or a short record.
+#[deno_core::op2(fast)]
+pub fn op_sprite_set(state: &mut OpState, id: u32, #[buffer] data: &[u8]) -> bool {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    if floats.len() < SPRITE_STRIDE {
+        return false;
+    }
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.set(id, parse_sprite_record(floats))
+}
+
+/// Move and rotate a retained sprite. NaN leaves a field unchanged.
+#[deno_core::op2(fast)]
+pub fn op_sprite_update(state: &mut OpState, id: u32, x: f64, y: f64, rotation: f64) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.update(id, x as f32, y as f32, rotation as f32)
+}
+
+/// Move many retained sprites from a packed Float32Array of
+/// SPRITE_UPDATE_STRIDE (4) f32 values each: [id as u32 bits, x, y,
+/// rotation], NaN for unchanged. Returns how many sprites were updated.
+#[deno_core::op2(fast)]
+pub fn op_sprite_update_bulk(state: &mut OpState, #[buffer] data: &[u8]) -> u32 {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.update_bulk(floats) as u32
+}
+
+/// Show or hide a retained sprite without destroying it.
+#[deno_core::op2(fast)]
+pub fn op_sprite_set_visible(state: &mut OpState, id: u32, visible: bool) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.set_visible(id, visible)
+}
+
+/// Stop drawing a retained sprite.
+#[deno_core::op2(fast)]
+pub fn op_sprite_destroy(state: &mut OpState, id: u32) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().sprite_store.destroy(id)
+}
+
+/// Number of retained sprites, visible or not.
+#[deno_core::op2(fast)]
+pub fn op_sprite_count(state: &mut OpState) -> u32 {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().sprite_store.len() as u32
+}
+
+// --- Hit-stop ops ---
+
+/// Freeze the simulation for `ms` milliseconds while frames keep rendering,
+/// shake the camera by up to `shake_amplitude` world pixels and rumble
+/// connected gamepads at `rumble` (0-1). Zero skips a part. See
+/// `renderer::hitstop`.
+#[deno_core::op2(fast)]
+pub fn op_hitstop(state: &mut OpState, ms: f64, shake_amplitude: f64, rumble: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().hitstop.trigger(ms as f32, shake_amplitude as f32, rumble as f32);
+}
+
+/// Milliseconds of hit-stop freeze left (0 when not frozen).
+#[deno_core::op2(fast)]
+pub fn op_get_hitstop_remaining(state: &mut OpState) -> f64 {
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().hitstop.remaining() as f64 * 1000.0
+}
+
+// --- Projectile ops ---
+
+/// Draw every projectile of a physics projectile batch as one sprite each.
+/// `data` is one sprite record (see `SPRITE_STRIDE`) used as the template:
+/// its x/y offset the sprite so that its origin point sits on the projectile.
+/// With `face_velocity` the sprite is turned to point along the projectile's
+/// velocity, added to the record's rotation.
+#[deno_core::op2(fast)]
+pub fn op_draw_projectiles(state: &mut OpState, batch_id: u32, face_velocity: bool, #[buffer] data: &[u8]) {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    if floats.len() < SPRITE_STRIDE {
+        return;
+    }
+    let template = with_flash(parse_sprite_record(floats), floats, &flash_store(state).borrow());
+    let cmds: Vec<SpriteCommand> = {
+        let Some(physics) = state.try_borrow::<Rc<RefCell<super::physics_ops::PhysicsState>>>() else {
+            return;
+        };
+        let ps = physics.borrow();
+        let Some(batch) = ps.0.as_ref().and_then(|world| world.get_projectile_batch(batch_id)) else {
+            return;
+        };
+        batch
+            .projectiles()
+            .iter()
+            .map(|p| SpriteCommand {
+                x: p.x + template.x - template.w * template.origin_x,
+                y: p.y + template.y - template.h * template.origin_y,
+                rotation: if face_velocity { template.rotation + p.vy.atan2(p.vx) } else { template.rotation },
+                ..template.clone()
+            })
+            .collect()
+    };
+    queue_sprite_commands(state, cmds.into_iter());
+}
+
+// --- Hit flash ops ---
+
+/// Flash every sprite drawn with `key` (the record's `flash_key`) towards a
+/// color, fading out over `duration` seconds. Returns false if the key is still
+/// within `cooldown` seconds of its last flash.
+#[deno_core::op2(fast)]
+pub fn op_entity_flash(
+    state: &mut OpState,
+    key: u32,
+    r: f64,
+    g: f64,
+    b: f64,
+    duration: f64,
+    cooldown: f64,
+) -> bool {
+    let flashes = flash_store(state);
+    let mut flashes = flashes.borrow_mut();
+    flashes.trigger(key, [r as f32, g as f32, b as f32], duration as f32, cooldown as f32)
+}
+
+/// Stop all hit flashes and reset their cooldowns.
+#[deno_core::op2(fast)]
+pub fn op_clear_flashes(state: &mut OpState) {
+    flash_store(state).borrow_mut().clear();
+}
+
+// --- Sprite animation ops ---
+
+/// Create a frame animation over `frames`, a packed Float32Array of UV rects
+/// (x, y, w, h per frame) on `texture_id`. `loop_mode` is "loop", "pingpong"
+/// or "once". The animation starts playing on the next frame.
+/// Returns the animation ID, or 0 if there are no frames or the mode is unknown.
+#[deno_core::op2(fast)]
+pub fn op_create_animation(
+    state: &mut OpState,
+    texture_id: u32,
+    #[buffer] frames: &[u8],
+    fps: f64,
+    #[string] loop_mode: &str,
+) -> u32 {
+    let Some(mode) = LoopMode::parse(loop_mode) else {
+        eprintln!("[animation] Unknown loop mode \"{loop_mode}\"");
+        return 0;
+    };
+    let floats: &[f32] = bytemuck::cast_slice(frames);
+    let frames = floats.chunks_exact(4).map(|f| [f[0], f[1], f[2], f[3]]).collect();
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge
+        .borrow_mut()
+        .animations
+        .create(texture_id, frames, fps as f32, mode)
+        .unwrap_or(0)
+}
+
+/// Draw an animation's current frame. `data` is one sprite record (see
+/// `SPRITE_STRIDE`); its texture and UV fields are replaced by the animation's.
+#[deno_core::op2(fast)]
+pub fn op_draw_animated(state: &mut OpState, anim_id: u32, #[buffer] data: &[u8]) {
+    let floats: &[f32] = bytemuck::cast_slice(data);
+    if floats.len() < SPRITE_STRIDE {
+        return;
+    }
+    let cmd = {
+        let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+        let b = bridge.borrow();
+        let Some(anim) = b.animations.get(anim_id) else { return };
+        let flashes = b.flashes.borrow();
+        anim.sprite_command(with_flash(parse_sprite_record(floats), floats, &flashes))
+    };
+    queue_sprite_commands(state, std::iter::once(cmd));
+}
+
+/// Restart an animation from its first frame.
+#[deno_core::op2(fast)]
+pub fn op_restart_animation(state: &mut OpState, anim_id: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    if let Some(anim) = bridge.borrow_mut().animations.get_mut(anim_id) {
+        anim.restart();
+    }
+}
+
+/// Index of the frame an animation is showing, or -1 for an unknown ID.
+#[deno_core::op2(fast)]
+pub fn op_get_animation_frame(state: &mut OpState, anim_id: u32) -> i32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge
+        .borrow()
+        .animations
+        .get(anim_id)
+        .map(|a| a.frame() as i32)
+        .unwrap_or(-1)
+}
+
+/// Destroy an animation. Its ID is not reused.
+#[deno_core::op2(fast)]
+pub fn op_destroy_animation(state: &mut OpState, anim_id: u32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().animations.remove(anim_id);
+}
+
+/// Load a sprite sheet exported from Aseprite: the JSON data file, or an
+/// `.aseprite`/`.ase` file with its export saved next to it as `.json`. The
+/// sheet image (`meta.image`, relative to the JSON) loads as a texture. The
+/// same path returns the same sheet. Returns JSON: { "sheetId": N,
+/// "textureId": M, "width", "height", "frames", "tags", "slices" } (see
+/// `AsepriteSheet::to_json`) or { "error": "..." }
+#[deno_core::op2]
+#[string]
+pub fn op_load_aseprite(state: &mut OpState, #[string] path: &str) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+
+    let mut json_path = b.base_dir.join(path);
+    if json_path.extension().is_some_and(|ext| ext == "aseprite" || ext == "ase") {
+        json_path.set_extension("json");
+    }
+    let resolved = json_path.to_string_lossy().to_string();
+
+    let sheet_id = match b.aseprite_paths.get(&resolved) {
+        Some(&id) => id,
+        None => {
+            let sheet = match std::fs::read_to_string(&json_path) {
+                Ok(json) => AsepriteSheet::parse(&json),
+                Err(e) => Err(format!("Failed to read {resolved}: {e}")),
+            };
+            let sheet = match sheet {
+                Ok(sheet) => sheet,
+                Err(e) => return format!("{{\"error\":\"{}\"}}", crate::json::escape(&e)),
+            };
+            if sheet.image.is_empty() {
+                return format!("{{\"error\":\"{} has no meta.image\"}}", crate::json::escape(&resolved));
+            }
+            let image = json_path.with_file_name(&sheet.image);
+            let texture_id = b.load_texture(&image.to_string_lossy(), false);
+            b.aseprite_sheets.push((sheet, texture_id));
+            let id = b.aseprite_sheets.len() as u32;
+            b.aseprite_paths.insert(resolved, id);
+            id
+        }
+    };
+
+    let (sheet, texture_id) = &b.aseprite_sheets[sheet_id as usize - 1];
+    // Splice the IDs into the sheet's own JSON object
+    format!("{{\"sheetId\":{sheet_id},\"textureId\":{texture_id},{}", &sheet.to_json()[1..])
+}
+
+/// Create an engine animation from an Aseprite tag, keeping its per-frame
+/// durations and direction. Tags with a repeat count play once; others loop.
+/// An empty `tag` plays every frame of the sheet in a loop.
+/// Returns the animation ID, or 0 for an unknown sheet or tag.
+#[deno_core::op2(fast)]
+pub fn op_create_aseprite_animation(state: &mut OpState, sheet_id: u32, #[string] tag: &str) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let Some((sheet, texture_id)) = b.aseprite_sheets.get((sheet_id as usize).wrapping_sub(1)) else {
+        return 0;
+    };
+    let tag = match tag {
+        "" => None,
+        name => match sheet.tag(name) {
+            Some(tag) => Some(tag),
+            None => {
+                eprintln!("[aseprite] Unknown tag \"{name}\"");
+                return 0;
+            }
+        },
+    };
+    let (frames, durations, mode) = sheet.animation(tag);
+    let texture_id = *texture_id;
+    b.animations.create_timed(texture_id, frames, durations, mode).unwrap_or(0)
+}
+
+/// Take this frame's loop and completion events as JSON (see `animation::events_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_animation_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = bridge.borrow_mut().animations.take_events();
+    crate::renderer::animation::events_json(&events)
+}
+
+// --- Lighting ops ---
+
+/// Set the ambient light color (0-1 per channel).
+/// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
+#[deno_core::op2(fast)]
+pub fn op_set_ambient_light(state: &mut OpState, r: f64, g: f64, b: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().ambient_light = [r as f32, g as f32, b as f32];
+}
+
+/// Light animation from op args, or None when nothing animates.
+#[allow(clippy::too_many_arguments)]
+fn light_animation(
+    flicker: f64,
+    flicker_speed: f64,
+    pulse: f64,
+    pulse_speed: f64,
+    pulse_curve: u32,
+    pulse_shape: Option<Curve>,
+    hue_speed: f64,
+    phase: f64,
+) -> Option<LightAnimation> {
+    let animated = (flicker > 0.0 && flicker_speed > 0.0)
+        || (pulse > 0.0 && pulse_speed > 0.0)
+        || hue_speed != 0.0;
+    animated.then(|| LightAnimation {
+        flicker: flicker as f32,
+        flicker_speed: flicker_speed as f32,
+        pulse: pulse as f32,
+        pulse_speed: pulse_speed as f32,
+        pulse_curve: PulseCurve::from_u32(pulse_curve).unwrap_or(PulseCurve::Sine),
+        pulse_shape,
+        hue_speed: hue_speed as f32,
+        phase: phase as f32,
+    })
+}
+
+/// Add a point light at world position (x,y) with radius, color, and intensity.
+/// The trailing args animate it (flicker, pulse, hue cycling; all 0 = static),
+/// evaluated by the renderer each frame. A nonzero `pulse_curve_id` shapes the
+/// pulse with that curve instead of `pulse_curve`. `shadow_softness` >= 0 makes
+/// the light cast shadows from the occluders (negative = no shadows).
 /// Accepts f64 (JavaScript's native number type), converts to f32 for GPU.
 #[deno_core::op2(fast)]
 pub fn op_add_point_light(
@@ -577,7 +1900,17 @@ pub fn op_add_point_light(
     g: f64,
     b: f64,
     intensity: f64,
+    flicker: f64,
+    flicker_speed: f64,
+    pulse: f64,
+    pulse_speed: f64,
+    pulse_curve: u32,
+    pulse_curve_id: u32,
+    hue_speed: f64,
+    phase: f64,
+    shadow_softness: f64,
 ) {
+    let pulse_shape = super::curve_ops::curve(state, pulse_curve_id);
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
     bridge.borrow_mut().point_lights.push(PointLight {
         x: x as f32,
@@ -587,6 +1920,8 @@ pub fn op_add_point_light(
         g: g as f32,
         b: b as f32,
         intensity: intensity as f32,
+        animation: light_animation(flicker, flicker_speed, pulse, pulse_speed, pulse_curve, pulse_shape, hue_speed, phase),
+        shadow_softness: (shadow_softness >= 0.0).then_some(shadow_softness as f32),
     });
 }
 
@@ -618,91 +1953,195 @@ pub fn op_load_sound(state: &mut OpState, #[string] path: &str) -> u32 {
     let id = b.next_sound_id;
     b.next_sound_id += 1;
     b.sound_path_to_id.insert(resolved.clone(), id);
+    b.asset_tracker.track(AssetKind::Sound, &resolved, id);
     b.audio_commands.push(BridgeAudioCommand::LoadSound { id, path: resolved });
     id
 }
 
-/// Stop all sounds.
+/// Stop all sounds.
+#[deno_core::op2(fast)]
+pub fn op_stop_all_sounds(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    b.occlusion_masks.clear();
+    b.audio_commands.push(BridgeAudioCommand::StopAll);
+}
+
+/// Set the master volume.
+/// Accepts f64 (JavaScript's native number type), converts to f32 for audio.
+#[deno_core::op2(fast)]
+pub fn op_set_master_volume(state: &mut OpState, volume: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::SetMasterVolume { volume: volume as f32 });
+}
+
+// --- Font ops ---
+
+/// Create the built-in font texture. Returns a texture ID.
+#[deno_core::op2(fast)]
+pub fn op_create_font_texture(state: &mut OpState) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().builtin_font_texture()
+}
+
+// --- Viewport ops ---
+
+/// Get the current viewport size as [width, height].
+#[deno_core::op2]
+#[serde]
+pub fn op_get_viewport_size(state: &mut OpState) -> Vec<f64> {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = bridge.borrow();
+    vec![b.viewport_width as f64, b.viewport_height as f64]
+}
+
+/// Get the display scale factor (e.g. 2.0 on Retina).
+#[deno_core::op2(fast)]
+pub fn op_get_scale_factor(state: &mut OpState) -> f64 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().scale_factor as f64
+}
+
+/// Set the background/clear color (r, g, b in 0.0-1.0 range).
+#[deno_core::op2(fast)]
+pub fn op_set_background_color(state: &mut OpState, r: f64, g: f64, b: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut br = bridge.borrow_mut();
+    br.clear_color = [r as f32, g as f32, b as f32, 1.0];
+}
+
+/// Draw a texture behind everything instead of the flat clear color.
+/// Stretched over the screen, or tiled from its top-left corner when
+/// `tile_w` and `tile_h` (screen pixels) are positive.
+#[deno_core::op2(fast)]
+pub fn op_set_background_texture(state: &mut OpState, texture_id: u32, tile_w: f64, tile_h: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let tile = (tile_w > 0.0 && tile_h > 0.0).then_some([tile_w as f32, tile_h as f32]);
+    bridge.borrow_mut().background_fill = Some(BackgroundFill { texture_id, tile });
+}
+
+/// Draw a two-color gradient behind everything: top to bottom, or left to
+/// right when `horizontal`. Colors are RGBA in 0.0-1.0.
+#[deno_core::op2(fast)]
+pub fn op_set_background_gradient(
+    state: &mut OpState,
+    r1: f64, g1: f64, b1: f64, a1: f64,
+    r2: f64, g2: f64, b2: f64, a2: f64,
+    horizontal: bool,
+) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let from = [r1 as f32, g1 as f32, b1 as f32, a1 as f32];
+    let to = [r2 as f32, g2 as f32, b2 as f32, a2 as f32];
+    let (pixels, w, h) = crate::renderer::background::gradient_pixels(from, to, horizontal);
+    let texture_id = match b.background_gradient_texture {
+        Some(id) => id,
+        None => {
+            let id = b.next_texture_id;
+            b.next_texture_id += 1;
+            b.background_gradient_texture = Some(id);
+            id
+        }
+    };
+    b.raw_texture_upload_queue.push((texture_id, w, h, pixels));
+    b.background_fill = Some(BackgroundFill { texture_id, tile: None });
+}
+
+/// Go back to the flat clear color.
+#[deno_core::op2(fast)]
+pub fn op_clear_background_fill(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().background_fill = None;
+}
+
+/// Color the area outside the camera bounds (RGBA in 0.0-1.0), independent of
+/// the clear color. `enabled = false` shows the clear color there again.
+#[deno_core::op2(fast)]
+pub fn op_set_letterbox_color(state: &mut OpState, enabled: bool, r: f64, g: f64, b: f64, a: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut br = bridge.borrow_mut();
+    br.letterbox = if enabled {
+        let texture_id = br.solid_texture("__arcane_letterbox__", [255, 255, 255, 255]);
+        Some(Letterbox { color: [r as f32, g as f32, b as f32, a as f32], texture_id })
+    } else {
+        None
+    };
+}
+
+// --- Recording ops ---
+
+/// Start recording rendered frames to a GIF at `path` (relative to the game
+/// directory), sampled at `fps` on game time.
 #[deno_core::op2(fast)]
-pub fn op_stop_all_sounds(state: &mut OpState) {
+pub fn op_start_recording(state: &mut OpState, #[string] path: &str, fps: f64) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::StopAll);
+    let mut b = bridge.borrow_mut();
+    let path = b.base_dir.join(path);
+    b.recording_requests.push(RecordingRequest::Start { path, fps: fps as f32 });
+    b.recording = true;
 }
 
-/// Set the master volume.
-/// Accepts f64 (JavaScript's native number type), converts to f32 for audio.
+/// Stop the recording in progress; the file is finished in the background.
 #[deno_core::op2(fast)]
-pub fn op_set_master_volume(state: &mut OpState, volume: f64) {
+pub fn op_stop_recording(state: &mut OpState) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::SetMasterVolume { volume: volume as f32 });
+    let mut b = bridge.borrow_mut();
+    b.recording_requests.push(RecordingRequest::Stop);
+    b.recording = false;
 }
 
-// --- Font ops ---
-
-/// Create the built-in font texture. Returns a texture ID.
+/// Whether a recording is in progress.
 #[deno_core::op2(fast)]
-pub fn op_create_font_texture(state: &mut OpState) -> u32 {
+pub fn op_is_recording(state: &mut OpState) -> bool {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut b = bridge.borrow_mut();
-
-    let key = "__builtin_font__".to_string();
-    if let Some(&id) = b.texture_path_to_id.get(&key) {
-        return id;
-    }
-
-    let id = b.next_texture_id;
-    b.next_texture_id += 1;
-    b.texture_path_to_id.insert(key, id);
-    b.font_texture_queue.push(id);
-    id
+    bridge.borrow().recording
 }
 
-// --- Viewport ops ---
-
-/// Get the current viewport size as [width, height].
-#[deno_core::op2]
-#[serde]
-pub fn op_get_viewport_size(state: &mut OpState) -> Vec<f64> {
+/// Write every draw layer of the next frame to its own image at `path`
+/// (relative to the game directory): a layered OpenRaster file for `.ora`,
+/// otherwise a folder of PNGs with a manifest.
+#[deno_core::op2(fast)]
+pub fn op_capture_layers(state: &mut OpState, #[string] path: &str) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let b = bridge.borrow();
-    vec![b.viewport_width as f64, b.viewport_height as f64]
+    let mut b = bridge.borrow_mut();
+    let path = b.base_dir.join(path);
+    b.recording_requests.push(RecordingRequest::CaptureLayers { path });
 }
 
-/// Get the display scale factor (e.g. 2.0 on Retina).
+// --- MSAA ops ---
+
+/// Draw the scene with `samples` per pixel (1, 2, 4 or 8; 1 turns MSAA off).
 #[deno_core::op2(fast)]
-pub fn op_get_scale_factor(state: &mut OpState) -> f64 {
+pub fn op_set_msaa(state: &mut OpState, samples: u32) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow().scale_factor as f64
+    bridge.borrow_mut().msaa_samples = samples.clamp(1, 8);
 }
 
-/// Set the background/clear color (r, g, b in 0.0-1.0 range).
+/// Samples per pixel in use: the requested count, lowered to what the GPU
+/// supports once the renderer has applied it.
 #[deno_core::op2(fast)]
-pub fn op_set_background_color(state: &mut OpState, r: f64, g: f64, b: f64) {
+pub fn op_get_msaa(state: &mut OpState) -> u32 {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut br = bridge.borrow_mut();
-    br.clear_color = [r as f32, g as f32, b as f32, 1.0];
+    bridge.borrow().msaa_samples
 }
 
 // --- File I/O ops (save/load) ---
 
-/// Write a save file. Returns true on success.
+/// Write a save file. Returns true on success. With a save sync enabled the
+/// write is pushed to the remote backend too; if that fails (offline, or a
+/// conflict) the next `op_sync_saves` catches up.
 #[deno_core::op2(fast)]
 pub fn op_save_file(state: &mut OpState, #[string] key: &str, #[string] value: &str) -> bool {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let save_dir = bridge.borrow().save_dir.clone();
-
-    // Sanitize key: only allow alphanumeric, underscore, dash
-    if !key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+    let mut b = bridge.borrow_mut();
+    let local = LocalDirBackend::new(&b.save_dir);
+    if local.write(key, value, crate::saves::now_ms()).is_err() {
         return false;
     }
-
-    // Ensure save directory exists
-    if std::fs::create_dir_all(&save_dir).is_err() {
-        return false;
+    if let Some(sync) = b.save_sync.as_mut() {
+        let _ = sync.sync_key(&local, key);
     }
-
-    let path = save_dir.join(format!("{key}.json"));
-    std::fs::write(path, value).is_ok()
+    true
 }
 
 /// Load a save file. Returns the contents or empty string if not found.
@@ -710,20 +2149,24 @@ pub fn op_save_file(state: &mut OpState, #[string] key: &str, #[string] value: &
 #[string]
 pub fn op_load_file(state: &mut OpState, #[string] key: &str) -> String {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let save_dir = bridge.borrow().save_dir.clone();
-
-    let path = save_dir.join(format!("{key}.json"));
-    std::fs::read_to_string(path).unwrap_or_default()
+    let local = LocalDirBackend::new(&bridge.borrow().save_dir);
+    local.read(key).ok().flatten().map(|(value, _)| value).unwrap_or_default()
 }
 
-/// Delete a save file. Returns true on success.
+/// Delete a save file. Returns true on success. With a save sync enabled
+/// the remote copy is deleted too.
 #[deno_core::op2(fast)]
 pub fn op_delete_file(state: &mut OpState, #[string] key: &str) -> bool {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let save_dir = bridge.borrow().save_dir.clone();
-
-    let path = save_dir.join(format!("{key}.json"));
-    std::fs::remove_file(path).is_ok()
+    let mut b = bridge.borrow_mut();
+    let local = LocalDirBackend::new(&b.save_dir);
+    if !matches!(local.modified(key), Ok(Some(_))) || local.remove(key).is_err() {
+        return false;
+    }
+    if let Some(sync) = b.save_sync.as_mut() {
+        let _ = sync.sync_key(&local, key);
+    }
+    true
 }
 
 /// List all save file keys (filenames without .json extension).
@@ -731,21 +2174,181 @@ pub fn op_delete_file(state: &mut OpState, #[string] key: &str) -> bool {
 #[serde]
 pub fn op_list_save_files(state: &mut OpState) -> Vec<String> {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let save_dir = bridge.borrow().save_dir.clone();
+    let local = LocalDirBackend::new(&bridge.borrow().save_dir);
+    local.list().unwrap_or_default().into_iter().map(|(key, _)| key).collect()
+}
 
-    let mut keys = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(&save_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().map_or(false, |ext| ext == "json") {
-                if let Some(stem) = path.file_stem() {
-                    keys.push(stem.to_string_lossy().to_string());
-                }
+/// Names of the save backends registered by plugins.
+#[deno_core::op2]
+#[serde]
+pub fn op_list_save_backends() -> Vec<String> {
+    crate::plugin::save_backend_names()
+}
+
+/// Sync the save files with the plugin save backend `backend` from now on.
+/// `policy` is "latest" or "prompt" (see [`ConflictPolicy`]). Nothing is
+/// copied until the next `op_sync_saves` or save write. Returns false for an
+/// unknown backend or policy.
+#[deno_core::op2(fast)]
+pub fn op_enable_save_sync(state: &mut OpState, #[string] backend: &str, #[string] policy: &str) -> bool {
+    let (Some(remote), Some(policy)) = (crate::plugin::save_backend(backend), ConflictPolicy::parse(policy)) else {
+        return false;
+    };
+    if !crate::saves::valid_key(backend) {
+        return false;
+    }
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let manifest = b.save_dir.join(format!(".sync-{backend}"));
+    b.save_sync = Some(SaveSync::new(remote, policy, manifest));
+    true
+}
+
+/// Stop syncing the save files. Pending conflicts are forgotten.
+#[deno_core::op2(fast)]
+pub fn op_disable_save_sync(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().save_sync = None;
+}
+
+/// Reconcile every save with the remote backend. Returns
+/// `{"ok":true,"pushed":[..],"pulled":[..],"removed":[..],"conflicts":[..],"skipped":[..],"failed":[..]}`
+/// with conflicts as `{"key":..,"local":ms|null,"remote":ms|null}` and
+/// failures as `{"key":..,"error":..}`, or `{"ok":false,"error":".."}`.
+#[deno_core::op2]
+#[string]
+pub fn op_sync_saves(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let local = LocalDirBackend::new(&b.save_dir);
+    match b.save_sync.as_mut() {
+        Some(sync) => match sync.sync(&local) {
+            Ok(report) => sync_report_json(&report),
+            Err(e) => format!("{{\"ok\":false,\"error\":\"{}\"}}", crate::json::escape(&e)),
+        },
+        None => "{\"ok\":false,\"error\":\"save sync is not enabled\"}".to_string(),
+    }
+}
+
+/// Settle a save conflict held by the "prompt" policy, keeping the local
+/// copy (`keep_local`) or the remote one on both sides. Returns an error
+/// message, or an empty string on success.
+#[deno_core::op2]
+#[string]
+pub fn op_resolve_save_conflict(state: &mut OpState, #[string] key: &str, keep_local: bool) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let local = LocalDirBackend::new(&b.save_dir);
+    match b.save_sync.as_mut() {
+        Some(sync) => sync.resolve(&local, key, keep_local).err().unwrap_or_default(),
+        None => "save sync is not enabled".to_string(),
+    }
+}
+
+fn sync_report_json(report: &SyncReport) -> String {
+    let keys = |keys: &[String]| -> String {
+        let quoted: Vec<String> = keys.iter().map(|k| format!("\"{}\"", crate::json::escape(k))).collect();
+        format!("[{}]", quoted.join(","))
+    };
+    let stamp = |modified: Option<u64>| modified.map_or("null".to_string(), |m| m.to_string());
+    let conflicts: Vec<String> = report
+        .conflicts
+        .iter()
+        .map(|c| {
+            format!(
+                "{{\"key\":\"{}\",\"local\":{},\"remote\":{}}}",
+                crate::json::escape(&c.key),
+                stamp(c.local_modified),
+                stamp(c.remote_modified)
+            )
+        })
+        .collect();
+    let failed: Vec<String> = report
+        .failed
+        .iter()
+        .map(|(key, error)| {
+            format!("{{\"key\":\"{}\",\"error\":\"{}\"}}", crate::json::escape(key), crate::json::escape(error))
+        })
+        .collect();
+    format!(
+        "{{\"ok\":true,\"pushed\":{},\"pulled\":{},\"removed\":{},\"conflicts\":[{}],\"skipped\":{},\"failed\":[{}]}}",
+        keys(&report.pushed),
+        keys(&report.pulled),
+        keys(&report.removed),
+        conflicts.join(","),
+        keys(&report.skipped),
+        failed.join(",")
+    )
+}
+
+// --- Replay file ops ---
+
+/// Capture the next rendered frame as the thumbnail of the replay being
+/// recorded, replacing the previous one. Only frames rendered to a window
+/// are captured.
+#[deno_core::op2(fast)]
+pub fn op_request_replay_thumbnail(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    b.replay_thumbnail = None;
+    b.replay_thumbnail_requested = true;
+}
+
+/// Write `recording` (a TS `Recording` as JSON) to a replay file at `path`
+/// (relative to the game directory), with the engine version, the game's
+/// content hash, the duration and the captured thumbnail in its header.
+/// Returns an error message, or an empty string on success.
+#[deno_core::op2]
+#[string]
+pub fn op_save_replay(state: &mut OpState, #[string] path: &str, #[string] recording: &str) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = bridge.borrow();
+    let path = b.base_dir.join(path);
+    let result = crate::replay::content_hash(&b.base_dir)
+        .map_err(|e| format!("hashing the game content: {e}"))
+        .and_then(|hash| ReplayHeader::for_recording(recording, &hash, crate::saves::now_ms()))
+        .and_then(|header| {
+            let file = ReplayFile { header, thumbnail: b.replay_thumbnail.clone(), recording: recording.to_string() };
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
             }
+            std::fs::write(&path, file.encode()).map_err(|e| format!("{}: {e}", path.display()))
+        });
+    result.err().unwrap_or_default()
+}
+
+/// Read the replay file at `path` (relative to the game directory). Returns
+/// `{"ok":true,"header":{..},"recording":{..}}`, or `{"ok":false,"error":".."}`.
+/// A replay recorded with another engine version or other game content
+/// would desync, so it is refused with the reason unless `force` is set.
+#[deno_core::op2]
+#[string]
+pub fn op_load_replay(state: &mut OpState, #[string] path: &str, force: bool) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = bridge.borrow();
+    let error = |e: &str| format!("{{\"ok\":false,\"error\":\"{}\"}}", crate::json::escape(e));
+    let file = match ReplayFile::read(&b.base_dir.join(path)) {
+        Ok(file) => file,
+        Err(e) => return error(&e),
+    };
+    if let Err(e) = crate::replay::recording_length(&file.recording) {
+        return error(&e);
+    }
+    if !force {
+        let hash = match crate::replay::content_hash(&b.base_dir) {
+            Ok(hash) => hash,
+            Err(e) => return error(&format!("hashing the game content: {e}")),
+        };
+        if let Some(reason) = file.header.mismatch(crate::replay::ENGINE_VERSION, &hash) {
+            return error(&reason);
         }
     }
-    keys.sort();
-    keys
+    let thumbnail_bytes = file.thumbnail.as_ref().map_or(0, Vec::len);
+    format!(
+        "{{\"ok\":true,\"header\":{},\"recording\":{}}}",
+        file.header.to_json(thumbnail_bytes),
+        file.recording
+    )
 }
 
 // --- Shader ops ---
@@ -762,6 +2365,36 @@ pub fn op_create_shader(state: &mut OpState, #[string] name: &str, #[string] sou
     id
 }
 
+/// Create a custom fragment shader from a WGSL file. Returns a shader ID;
+/// the same path returns the same ID, and `arcane dev` recompiles it in
+/// place when the file changes.
+#[deno_core::op2(fast)]
+pub fn op_create_shader_from_file(state: &mut OpState, #[string] name: &str, #[string] path: &str) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+
+    let resolved = if std::path::Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        b.base_dir.join(path).to_string_lossy().to_string()
+    };
+
+    if let Some((id, _)) = b.shader_files.get(&resolved) {
+        return *id;
+    }
+
+    let id = b.next_shader_id;
+    b.next_shader_id += 1;
+    b.shader_files.insert(resolved.clone(), (id, name.to_string()));
+    b.asset_tracker.track(AssetKind::Shader, &resolved, id);
+    match std::fs::read_to_string(&resolved) {
+        Ok(source) => b.shader_create_queue.push((id, name.to_string(), source)),
+        // Compiled once the file exists (the tracker sees it appear)
+        Err(e) => eprintln!("Failed to read shader {resolved}: {e}"),
+    }
+    id
+}
+
 /// Set a vec4 parameter slot on a custom shader. Index 0-15.
 #[deno_core::op2(fast)]
 pub fn op_set_shader_param(
@@ -828,6 +2461,75 @@ pub fn op_clear_effects(state: &mut OpState) {
     bridge.borrow_mut().effect_clear = true;
 }
 
+// --- Screen transition ops ---
+
+/// Start a screen transition, replacing any running one. `kind` is "fade",
+/// "circle", "pixelate" or "custom" (drawn with `shader_id`). The cover
+/// color is RGBA 0-1; `x`-`w` are kind-specific (see `TransitionOverlay`).
+/// Returns the transition ID, or 0 for an unknown kind.
+#[deno_core::op2(fast)]
+pub fn op_start_transition(
+    state: &mut OpState,
+    #[string] kind: &str,
+    duration: f64,
+    shader_id: u32,
+    r: f64,
+    g: f64,
+    b: f64,
+    a: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+) -> u32 {
+    let Some(kind) = TransitionKind::parse(kind, shader_id) else {
+        eprintln!("[transition] Unknown transition kind \"{kind}\"");
+        return 0;
+    };
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().transition.start(
+        kind,
+        duration as f32,
+        [r as f32, g as f32, b as f32, a as f32],
+        [x as f32, y as f32, z as f32, w as f32],
+    )
+}
+
+/// Stop the running screen transition immediately, without events.
+#[deno_core::op2(fast)]
+pub fn op_cancel_transition(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().transition.cancel();
+}
+
+/// How much of the screen the running transition covers (0-1), 0 when idle.
+#[deno_core::op2(fast)]
+pub fn op_get_transition_coverage(state: &mut OpState) -> f64 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().transition.coverage() as f64
+}
+
+/// Take this frame's midpoint and completion events as JSON (see `transition::events_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_transition_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = bridge.borrow_mut().transition.take_events();
+    crate::renderer::transition::events_json(&events)
+}
+
+/// Register a custom transition fragment shader (WGSL `fs_main` using the
+/// post-process preamble). Returns its ID for `op_start_transition`.
+#[deno_core::op2(fast)]
+pub fn op_create_transition_shader(state: &mut OpState, #[string] source: &str) -> u32 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let id = b.next_transition_shader_id;
+    b.next_transition_shader_id += 1;
+    b.transition_shader_queue.push((id, source.to_string()));
+    id
+}
+
 // --- Camera bounds ops ---
 
 /// Set camera bounds (world-space limits).
@@ -866,44 +2568,205 @@ pub fn op_get_camera_bounds(state: &mut OpState) -> Vec<f64> {
     }
 }
 
-// --- Global Illumination ops ---
-
-/// Enable radiance cascades global illumination.
+// --- Global Illumination ops ---
+
+/// Enable radiance cascades global illumination.
+#[deno_core::op2(fast)]
+pub fn op_enable_gi(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().gi_enabled = true;
+}
+
+/// Disable radiance cascades global illumination.
+#[deno_core::op2(fast)]
+pub fn op_disable_gi(state: &mut OpState) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().gi_enabled = false;
+}
+
+/// Set the GI intensity multiplier.
+#[deno_core::op2(fast)]
+pub fn op_set_gi_intensity(state: &mut OpState, intensity: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().gi_intensity = intensity as f32;
+}
+
+/// Set GI quality parameters (probe spacing, interval, cascade count).
+/// Pass 0 for any parameter to keep the current/default value.
+#[deno_core::op2(fast)]
+pub fn op_set_gi_quality(state: &mut OpState, probe_spacing: f64, interval: f64, cascade_count: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    if probe_spacing > 0.0 {
+        b.gi_probe_spacing = Some(probe_spacing as f32);
+    }
+    if interval > 0.0 {
+        b.gi_interval = Some(interval as f32);
+    }
+    if cascade_count > 0.0 {
+        b.gi_cascade_count = Some(cascade_count as u32);
+    }
+}
+
+/// Enable or disable adaptive quality scaling.
+/// `budget_ms` is the GPU time budget per frame; pass 0 to keep the current one.
+#[deno_core::op2(fast)]
+pub fn op_set_adaptive_quality(state: &mut OpState, enabled: bool, budget_ms: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    b.quality_config.enabled = enabled;
+    if budget_ms > 0.0 {
+        b.quality_config.budget_ms = budget_ms as f32;
+    }
+}
+
+/// Pin the quality tier (0 = best). A negative tier returns to automatic selection.
+#[deno_core::op2(fast)]
+pub fn op_set_quality_tier(state: &mut OpState, tier: i32) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().quality_config.override_tier = usize::try_from(tier).ok();
+}
+
+/// Current quality tier and measured frame time as JSON (see `QualityController::status_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_get_quality(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().quality_status.clone()
+}
+
+/// Set how much GPU work runs inside validation error scopes
+/// (0 = off, 1 = pipeline creation, 2 = pipelines and every frame).
+#[deno_core::op2(fast)]
+pub fn op_set_gpu_error_capture(state: &mut OpState, mode: f64) {
+    if let Some(mode) = GpuErrorCapture::from_u32(mode as u32) {
+        let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+        bridge.borrow_mut().gpu_error_capture = mode;
+    }
+}
+
+/// Take the GPU validation errors raised since the last call as JSON (see `gpu_errors::errors_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_gpu_errors(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let errors = std::mem::take(&mut bridge.borrow_mut().gpu_errors);
+    crate::renderer::gpu_errors::errors_json(&errors)
+}
+
+/// Sprite draw calls, instance count and instance buffer uploads for the last
+/// rendered frame as JSON (see `SpriteStats::to_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_get_sprite_stats(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().sprite_stats.to_json()
+}
+
+/// Pack small textures into shared runtime atlas pages so sprites batch
+/// across images, or turn the atlas off.
+#[deno_core::op2(fast)]
+pub fn op_set_runtime_atlas(state: &mut OpState, enabled: bool) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().runtime_atlas = enabled;
+}
+
+/// Runtime atlas pages, packed textures, occupancy, waste and repacks as JSON
+/// (see `AtlasStats::to_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_get_atlas_stats(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().atlas_stats.to_json()
+}
+
+/// Set how many bytes of streamed texture pixels are copied to the GPU per
+/// frame. Lower spreads big textures over more frames.
+#[deno_core::op2(fast)]
+pub fn op_set_texture_upload_budget(state: &mut OpState, bytes: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().texture_upload_budget = bytes.max(0.0) as u64;
+}
+
+/// Take streamed texture events as JSON (see `texture_stream::events_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_take_texture_events(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let events = std::mem::take(&mut bridge.borrow_mut().texture_events);
+    crate::renderer::texture_stream::events_json(&events)
+}
+
+/// Textures still decoding or uploading, and the bytes left to upload, as
+/// JSON (see `TextureStreamStats::to_json`).
+#[deno_core::op2]
+#[string]
+pub fn op_get_texture_stream_stats(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().texture_stream_stats.to_json()
+}
+
+/// Create a GPU-simulated particle emitter from a JSON definition
+/// (see `EmitterDef::from_json`). Returns the emitter ID, or 0 if the
+/// definition is invalid.
 #[deno_core::op2(fast)]
-pub fn op_enable_gi(state: &mut OpState) {
+pub fn op_create_gpu_emitter(state: &mut OpState, #[string] def_json: &str) -> u32 {
+    let def = match EmitterDef::from_json(def_json) {
+        Ok(def) => def,
+        Err(e) => {
+            eprintln!("[particles] Invalid GPU emitter definition: {e}");
+            return 0;
+        }
+    };
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().gi_enabled = true;
+    let mut b = bridge.borrow_mut();
+    let id = b.next_gpu_emitter_id;
+    b.next_gpu_emitter_id += 1;
+    b.gpu_particle_commands.push(GpuParticleCommand::Create { id, def });
+    id
 }
 
-/// Disable radiance cascades global illumination.
+/// Spawn `count` particles from a GPU emitter on the next frame.
 #[deno_core::op2(fast)]
-pub fn op_disable_gi(state: &mut OpState) {
+pub fn op_emit_gpu_burst(state: &mut OpState, id: u32, count: u32) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().gi_enabled = false;
+    bridge
+        .borrow_mut()
+        .gpu_particle_commands
+        .push(GpuParticleCommand::Burst { id, count });
 }
 
-/// Set the GI intensity multiplier.
+/// Update one GPU emitter parameter by its TS name (see `EmitterDef::set_param`).
+/// Scalars use `a`; vectors and colors use as many components as they need.
 #[deno_core::op2(fast)]
-pub fn op_set_gi_intensity(state: &mut OpState, intensity: f64) {
+pub fn op_set_gpu_emitter_param(
+    state: &mut OpState,
+    id: u32,
+    #[string] name: &str,
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().gi_intensity = intensity as f32;
+    bridge
+        .borrow_mut()
+        .gpu_particle_commands
+        .push(GpuParticleCommand::SetParam {
+            id,
+            name: name.to_string(),
+            value: [a as f32, b as f32, c as f32, d as f32],
+        });
 }
 
-/// Set GI quality parameters (probe spacing, interval, cascade count).
-/// Pass 0 for any parameter to keep the current/default value.
+/// Destroy a GPU emitter and free its particle buffer.
 #[deno_core::op2(fast)]
-pub fn op_set_gi_quality(state: &mut OpState, probe_spacing: f64, interval: f64, cascade_count: f64) {
+pub fn op_destroy_gpu_emitter(state: &mut OpState, id: u32) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    let mut b = bridge.borrow_mut();
-    if probe_spacing > 0.0 {
-        b.gi_probe_spacing = Some(probe_spacing as f32);
-    }
-    if interval > 0.0 {
-        b.gi_interval = Some(interval as f32);
-    }
-    if cascade_count > 0.0 {
-        b.gi_cascade_count = Some(cascade_count as u32);
-    }
+    bridge
+        .borrow_mut()
+        .gpu_particle_commands
+        .push(GpuParticleCommand::Destroy { id });
 }
 
 /// Add an emissive surface (light source) for GI.
@@ -973,7 +2836,8 @@ pub fn op_add_directional_light(
     ]);
 }
 
-/// Add a spot light with position, direction, and spread.
+/// Add a spot light with position, direction, and spread. The trailing args
+/// animate it, as for `op_add_point_light`.
 #[deno_core::op2(fast)]
 pub fn op_add_spot_light(
     state: &mut OpState,
@@ -986,19 +2850,31 @@ pub fn op_add_spot_light(
     g: f64,
     b: f64,
     intensity: f64,
+    flicker: f64,
+    flicker_speed: f64,
+    pulse: f64,
+    pulse_speed: f64,
+    pulse_curve: u32,
+    pulse_curve_id: u32,
+    hue_speed: f64,
+    phase: f64,
 ) {
-    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().spot_lights.push([
-        x as f32,
-        y as f32,
-        angle as f32,
-        spread as f32,
-        range as f32,
-        r as f32,
-        g as f32,
-        b as f32,
-        intensity as f32,
-    ]);
+    let pulse_shape = super::curve_ops::curve(state, pulse_curve_id);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().spot_lights.push((
+        [
+            x as f32,
+            y as f32,
+            angle as f32,
+            spread as f32,
+            range as f32,
+            r as f32,
+            g as f32,
+            b as f32,
+            intensity as f32,
+        ],
+        light_animation(flicker, flicker_speed, pulse, pulse_speed, pulse_curve, pulse_shape, hue_speed, phase),
+    ));
 }
 
 // --- Phase 20: New audio ops ---
@@ -1070,8 +2946,64 @@ pub fn op_play_sound_spatial(
 #[deno_core::op2(fast)]
 pub fn op_stop_instance(state: &mut OpState, instance_id: f64) {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
-    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::StopInstance {
+    let mut b = bridge.borrow_mut();
+    b.occlusion_masks.remove(&(instance_id as u64));
+    b.audio_commands.push(BridgeAudioCommand::StopInstance {
+        instance_id: instance_id as u64,
+    });
+}
+
+/// Fade an audio instance in from silence over `duration` seconds, shaped by
+/// curve `curve_id` (0 = linear). The ramp runs on the audio thread.
+/// Accepts f64 (deno_core convention).
+#[deno_core::op2(fast)]
+pub fn op_fade_in_instance(state: &mut OpState, instance_id: f64, duration: f64, curve_id: u32) {
+    let curve = resolve_curve(state, curve_id);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::FadeIn {
+        instance_id: instance_id as u64,
+        duration: duration as f32,
+        curve,
+    });
+}
+
+/// Fade an audio instance out over `duration` seconds along curve `curve_id`
+/// (0 = linear), then stop it.
+/// Accepts f64 (deno_core convention), converts to u64/f32 internally.
+#[deno_core::op2(fast)]
+pub fn op_fade_out_instance(state: &mut OpState, instance_id: f64, duration: f64, curve_id: u32) {
+    let curve = resolve_curve(state, curve_id);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::FadeOut {
+        instance_id: instance_id as u64,
+        duration: duration as f32,
+        curve,
+    });
+}
+
+/// Fade one instance out (then stop it) while fading another in, over `duration` seconds
+/// along curve `curve_id` (0 = linear).
+/// Accepts f64 (deno_core convention), converts to u64/f32 internally.
+#[deno_core::op2(fast)]
+pub fn op_crossfade_instances(state: &mut OpState, from_instance: f64, to_instance: f64, duration: f64, curve_id: u32) {
+    let curve = resolve_curve(state, curve_id);
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::CrossfadeTo {
+        from_instance: from_instance as u64,
+        to_instance: to_instance as u64,
+        duration: duration as f32,
+        curve,
+    });
+}
+
+/// Stop an audio instance after `delay` seconds.
+/// Accepts f64 (deno_core convention), converts to u64/f32 internally.
+#[deno_core::op2(fast)]
+pub fn op_stop_instance_after(state: &mut OpState, instance_id: f64, delay: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::StopAfter {
         instance_id: instance_id as u64,
+        delay: delay as f32,
     });
 }
 
@@ -1097,12 +3029,34 @@ pub fn op_set_instance_pitch(state: &mut OpState, instance_id: f64, pitch: f64)
     });
 }
 
+/// Set distance attenuation for a spatial audio instance.
+/// curve: 0 = linear, 1 = inverse, 2 = exponential. Distances are in world pixels.
+/// Accepts f64 (deno_core convention), converts to u64/f32 internally.
+#[deno_core::op2(fast)]
+pub fn op_set_spatial_params(
+    state: &mut OpState,
+    instance_id: f64,
+    curve: u32,
+    min_distance: f64,
+    max_distance: f64,
+    rolloff: f64,
+) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::SetSpatialParams {
+        instance_id: instance_id as u64,
+        curve,
+        min_distance: min_distance as f32,
+        max_distance: max_distance as f32,
+        rolloff: rolloff as f32,
+    });
+}
+
 /// Update positions for multiple spatial audio instances in a batch.
 /// Uses JSON string for variable-length data (simplest approach with deno_core 0.385.0).
 /// Format: {"instanceIds": [id1, id2, ...], "sourceXs": [x1, x2, ...], "sourceYs": [y1, y2, ...], "listenerX": x, "listenerY": y}
 #[deno_core::op2(fast)]
 pub fn op_update_spatial_positions(state: &mut OpState, #[string] data_json: &str, listener_x: f64, listener_y: f64) {
-    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
 
     // Parse JSON (simple ad-hoc parsing for array data)
     // In production, we'd use serde_json, but for minimal dependencies, parse manually
@@ -1140,10 +3094,93 @@ pub fn op_update_spatial_positions(state: &mut OpState, #[string] data_json: &st
         }
     }
 
+    let (listener_x, listener_y) = (listener_x as f32, listener_y as f32);
+    let occluded = {
+        let b = bridge.borrow();
+        let masks = &b.occlusion_masks;
+        match state.try_borrow::<Rc<RefCell<super::physics_ops::PhysicsState>>>() {
+            Some(physics) if !masks.is_empty() => {
+                let ps = physics.borrow();
+                occluded_instances(ps.0.as_ref(), masks, &updates, listener_x, listener_y)
+            }
+            _ => Vec::new(),
+        }
+    };
     bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::UpdateSpatialPositions {
         updates,
-        listener_x: listener_x as f32,
-        listener_y: listener_y as f32,
+        listener_x,
+        listener_y,
+        occluded,
+    });
+}
+
+/// Instances in `updates` with an occlusion mask whose line of sight from the
+/// listener is blocked by a body on one of the mask's layers.
+fn occluded_instances(
+    world: Option<&crate::physics::world::PhysicsWorld>,
+    masks: &std::collections::HashMap<u64, u16>,
+    updates: &[(u64, f32, f32)],
+    listener_x: f32,
+    listener_y: f32,
+) -> Vec<u64> {
+    let Some(world) = world else {
+        return Vec::new();
+    };
+    updates
+        .iter()
+        .filter(|&&(id, x, y)| {
+            masks.get(&id).is_some_and(|&mask| world.segment_blocked_by(listener_x, listener_y, x, y, mask))
+        })
+        .map(|&(id, _, _)| id)
+        .collect()
+}
+
+/// Muffle a spatial instance while physics bodies on `layer_mask` block the
+/// line from the listener to it: its volume falls to `volume` and a low-pass
+/// closes to `cutoff_hz`, easing over `smoothing` seconds. Checked on every
+/// `op_update_spatial_positions`. A mask of 0 turns occlusion off.
+#[deno_core::op2(fast)]
+pub fn op_set_sound_occlusion(
+    state: &mut OpState,
+    instance_id: f64,
+    layer_mask: u32,
+    cutoff_hz: f64,
+    volume: f64,
+    smoothing: f64,
+) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let instance_id = instance_id as u64;
+    let mask = (layer_mask & 0xFFFF) as u16;
+    let params = if mask == 0 {
+        b.occlusion_masks.remove(&instance_id);
+        None
+    } else {
+        b.occlusion_masks.insert(instance_id, mask);
+        Some(crate::audio::OcclusionParams {
+            cutoff_hz: cutoff_hz as f32,
+            volume: volume as f32,
+            smoothing: smoothing as f32,
+        })
+    };
+    b.audio_commands.push(BridgeAudioCommand::SetOcclusion { instance_id, params });
+}
+
+/// Shift a spatial instance's pitch with its speed toward or away from the
+/// listener, measured from `op_update_spatial_positions` moves. `factor`
+/// scales both velocities; the shift stays within `min_pitch..=max_pitch`.
+/// A factor of 0 turns Doppler off.
+#[deno_core::op2(fast)]
+pub fn op_set_sound_doppler(state: &mut OpState, instance_id: f64, factor: f64, min_pitch: f64, max_pitch: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let params = (factor > 0.0).then(|| crate::audio::DopplerParams {
+        factor: factor as f32,
+        min_pitch: min_pitch as f32,
+        max_pitch: max_pitch as f32,
+    });
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::SetDoppler {
+        instance_id: instance_id as u64,
+        params,
     });
 }
 
@@ -1158,6 +3195,29 @@ pub fn op_set_bus_volume(state: &mut OpState, bus: u32, volume: f64) {
     });
 }
 
+/// Configure one stage of a bus's effect chain: effect 0 = low-pass
+/// (cutoff Hz), 1 = delay (mix, delay ms, feedback), 2 = reverb (mix, delay
+/// ms, decay). Unused parameters are ignored.
+#[deno_core::op2(fast)]
+pub fn op_set_bus_effect(state: &mut OpState, bus: u32, effect: u32, a: f64, b: f64, c: f64) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().audio_commands.push(BridgeAudioCommand::SetBusEffect {
+        bus,
+        effect,
+        params: [a as f32, b as f32, c as f32],
+    });
+}
+
+/// Current output levels as `[rms, peak]` pairs: master, then the sfx, music,
+/// ambient and voice buses. Linear amplitude, updated by the audio thread.
+#[deno_core::op2]
+#[serde]
+pub fn op_get_audio_levels(state: &mut OpState) -> Vec<f64> {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let levels = bridge.borrow().audio_levels.to_array();
+    levels.iter().map(|&v| v as f64).collect()
+}
+
 // --- MSDF text ops ---
 
 /// Create the built-in MSDF font (from CP437 bitmap data converted to SDF).
@@ -1167,42 +3227,7 @@ pub fn op_set_bus_volume(state: &mut OpState, bus: u32, volume: f64) {
 pub fn op_create_msdf_builtin_font(state: &mut OpState) -> String {
     let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
     let mut b = bridge.borrow_mut();
-
-    // Check if already created
-    let key = "__msdf_builtin__".to_string();
-    if let Some(&tex_id) = b.texture_path_to_id.get(&key) {
-        // Already created — find the font ID
-        // The font was registered with the texture_id as the lookup key
-        let font_id_key = format!("__msdf_font_{tex_id}__");
-        if let Some(&font_id) = b.texture_path_to_id.get(&font_id_key) {
-            let pool = &b.msdf_shader_pool;
-            let shader_id = pool.first().copied().unwrap_or(0);
-            let pool_json: Vec<String> = pool.iter().map(|id| id.to_string()).collect();
-            return format!(
-                "{{\"fontId\":{},\"textureId\":{},\"shaderId\":{},\"shaderPool\":[{}]}}",
-                font_id, tex_id, shader_id, pool_json.join(",")
-            );
-        }
-    }
-
-    // Assign texture ID
-    let tex_id = b.next_texture_id;
-    b.next_texture_id += 1;
-    b.texture_path_to_id.insert(key, tex_id);
-
-    // Generate MSDF atlas data and register font
-    let (_pixels, _width, _height, mut font) =
-        crate::renderer::msdf::generate_builtin_msdf_font();
-    font.texture_id = tex_id;
-
-    // Register font in the store
-    let font_id = b.msdf_fonts.register(font);
-    b.texture_path_to_id
-        .insert(format!("__msdf_font_{tex_id}__"), font_id);
-
-    // Queue the texture for GPU upload.
-    // dev.rs will call generate_builtin_msdf_font() again and upload pixels.
-    b.msdf_builtin_queue.push((font_id, tex_id));
+    let (font_id, tex_id) = b.builtin_msdf_font();
 
     // Ensure MSDF shader pool exists
     let pool = ensure_msdf_shader_pool(&mut b);
@@ -1231,6 +3256,10 @@ pub fn op_get_msdf_glyphs(
         Some(f) => f,
         None => return "[]".to_string(),
     };
+    // Runtime fonts generate glyphs on first use; they show up a frame or two later
+    if text.chars().any(|ch| font.get_glyph(ch).is_none()) {
+        b.runtime_fonts.borrow_mut().request_missing(font_id, text);
+    }
 
     let mut entries = Vec::new();
     for ch in text.chars() {
@@ -1264,6 +3293,27 @@ pub fn op_get_msdf_font_info(state: &mut OpState, font_id: u32) -> String {
     }
 }
 
+/// Measure text in an MSDF font at `size` pixels with the layout `drawText`
+/// uses, wrapping at `max_width` pixels (0 = only at newlines). Returns JSON:
+/// { "width": N, "height": N, "lineHeight": N, "lines": [{ "start": N, "end": N, "width": N }] }
+/// with UTF-16 line offsets, or null for an unknown font.
+#[deno_core::op2]
+#[string]
+pub fn op_measure_text(
+    state: &mut OpState,
+    font_id: u32,
+    #[string] text: &str,
+    size: f64,
+    max_width: f64,
+) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let b = bridge.borrow();
+    match b.msdf_fonts.get(font_id) {
+        Some(font) => font.layout(text, size as f32, max_width as f32).to_json(),
+        None => "null".to_string(),
+    }
+}
+
 /// Load an MSDF font from an atlas image path + metrics JSON (string or file path).
 /// Returns a JSON string: { "fontId": N, "textureId": M, "shaderId": S }
 #[deno_core::op2]
@@ -1335,6 +3385,134 @@ pub fn op_load_msdf_font(
     )
 }
 
+/// Load a TrueType/OpenType font file and generate its MSDF atlas at runtime,
+/// sized for text drawn between `min_size` and `max_size` pixels. Glyphs are
+/// generated on a worker thread: printable ASCII right away, other characters
+/// when text first uses them. Loading the same file and size again returns the
+/// same font. Returns JSON: { "fontId": N, "textureId": M, "shaderId": S,
+/// "shaderPool": [...] } or { "error": "..." }
+#[deno_core::op2]
+#[string]
+pub fn op_load_font(
+    state: &mut OpState,
+    #[string] path: &str,
+    min_size: f64,
+    max_size: f64,
+) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+
+    let resolved = if std::path::Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        b.base_dir.join(path).to_string_lossy().to_string()
+    };
+    let px = glyph_px_for_sizes(min_size as f32, max_size as f32);
+
+    let runtime_fonts = b.runtime_fonts.clone();
+    let mut runtime_fonts = runtime_fonts.borrow_mut();
+    let font_id = match runtime_fonts.find(&resolved, px) {
+        Some(id) => id,
+        None => {
+            let data = match std::fs::read(&resolved) {
+                Ok(data) => data,
+                Err(e) => return format!("{{\"error\":\"Failed to read font {}: {}\"}}", resolved, e),
+            };
+            let tex_id = b.next_texture_id;
+            match runtime_fonts.load(&mut b.msdf_fonts, &resolved, data, px, tex_id) {
+                Ok(id) => {
+                    b.next_texture_id += 1;
+                    id
+                }
+                Err(e) => return format!("{{\"error\":\"{}\"}}", e),
+            }
+        }
+    };
+    let tex_id = b.msdf_fonts.get(font_id).map_or(0, |f| f.texture_id);
+
+    let pool = ensure_msdf_shader_pool(&mut b);
+    let shader_id = pool.first().copied().unwrap_or(0);
+    let pool_json: Vec<String> = pool.iter().map(|id| id.to_string()).collect();
+
+    format!(
+        "{{\"fontId\":{},\"textureId\":{},\"shaderId\":{},\"shaderPool\":[{}]}}",
+        font_id, tex_id, shader_id, pool_json.join(",")
+    )
+}
+
+/// Load a bitmap font: a glyph grid image plus its manifest (JSON string or
+/// file path, see `renderer::bitmap_font`). The image loads with nearest filtering.
+/// Returns JSON: { "textureId": N, "cellWidth": N, "cellHeight": N,
+/// "glyphs": [{ "char": N, "uv": [x, y, w, h], "advance": N }] } or { "error": "..." }
+#[deno_core::op2]
+#[string]
+pub fn op_load_bitmap_font(
+    state: &mut OpState,
+    #[string] path: &str,
+    #[string] manifest_json_or_path: &str,
+) -> String {
+    use crate::json::escape;
+    use crate::renderer::bitmap_font::BitmapFontManifest;
+
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+
+    let resolve = |p: &str| {
+        if std::path::Path::new(p).is_absolute() {
+            p.to_string()
+        } else {
+            b.base_dir.join(p).to_string_lossy().to_string()
+        }
+    };
+
+    let manifest_json = if manifest_json_or_path.trim_start().starts_with('{') {
+        manifest_json_or_path.to_string()
+    } else {
+        let manifest_path = resolve(manifest_json_or_path);
+        match std::fs::read_to_string(&manifest_path) {
+            Ok(content) => content,
+            Err(e) => {
+                let msg = format!("Failed to read font manifest {manifest_path}: {e}");
+                return format!("{{\"error\":\"{}\"}}", escape(&msg));
+            }
+        }
+    };
+    let manifest = match BitmapFontManifest::parse(&manifest_json) {
+        Ok(m) => m,
+        Err(e) => return format!("{{\"error\":\"{}\"}}", escape(&e)),
+    };
+
+    let resolved = resolve(path);
+    let tex_id = if let Some(&id) = b.texture_path_to_id.get(&resolved) {
+        id
+    } else {
+        let id = b.next_texture_id;
+        b.next_texture_id += 1;
+        b.texture_path_to_id.insert(resolved.clone(), id);
+        b.asset_tracker.track(AssetKind::Texture, &resolved, id);
+        b.texture_load_queue.push((resolved, id));
+        id
+    };
+
+    let glyphs: Vec<String> = manifest
+        .glyphs()
+        .iter()
+        .map(|g| {
+            format!(
+                "{{\"char\":{},\"uv\":[{},{},{},{}],\"advance\":{}}}",
+                g.ch as u32, g.uv[0], g.uv[1], g.uv[2], g.uv[3], g.advance
+            )
+        })
+        .collect();
+    format!(
+        "{{\"textureId\":{},\"cellWidth\":{},\"cellHeight\":{},\"glyphs\":[{}]}}",
+        tex_id,
+        manifest.cell_width,
+        manifest.cell_height,
+        glyphs.join(",")
+    )
+}
+
 /// Pool size for MSDF shaders (same WGSL, different uniform buffers).
 const MSDF_SHADER_POOL_SIZE: usize = 8;
 
@@ -1358,6 +3536,94 @@ fn ensure_msdf_shader_pool(b: &mut RenderBridgeState) -> Vec<u32> {
     pool
 }
 
+// --- Debug HUD ops ---
+
+/// Show or hide the built-in debug HUD (fps, frame time, draw calls, physics
+/// bodies, playing sounds, texture memory). Stays as set across hot reloads.
+#[deno_core::op2(fast)]
+pub fn op_set_debug_hud(state: &mut OpState, enabled: bool) {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow_mut().debug_hud.enabled = enabled;
+}
+
+/// Whether the debug HUD is shown (also toggled by F3 in `arcane dev`).
+#[deno_core::op2(fast)]
+pub fn op_is_debug_hud_enabled(state: &mut OpState) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().debug_hud.enabled
+}
+
+// --- Action map ops ---
+
+/// Load actions from a JSON file (relative to the game directory), keeping
+/// actions it doesn't mention. Returns "" on success, else the error.
+#[deno_core::op2]
+#[string]
+pub fn op_load_action_map(state: &mut OpState, #[string] path: &str) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let mut b = bridge.borrow_mut();
+    let full_path = b.base_dir.join(path);
+    let loaded = std::fs::read_to_string(&full_path)
+        .map_err(|e| format!("Failed to read {}: {e}", full_path.display()))
+        .and_then(|json| b.action_map.load_json(&json));
+    match loaded {
+        Ok(_) => String::new(),
+        Err(e) => e,
+    }
+}
+
+/// Replace an action's bindings with a JSON array of bindings; an empty
+/// array removes the action. Returns "" on success, else the error.
+#[deno_core::op2]
+#[string]
+pub fn op_set_action_bindings(state: &mut OpState, #[string] action: &str, #[string] bindings_json: &str) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    let parsed = crate::json::JsonValue::parse(bindings_json).and_then(|v| ActionMap::parse_bindings(&v));
+    match parsed {
+        Ok(bindings) => {
+            bridge.borrow_mut().action_map.set_bindings(action, bindings);
+            String::new()
+        }
+        Err(e) => format!("action \"{action}\": {e}"),
+    }
+}
+
+/// Every action and its bindings as JSON, loadable with `op_load_action_map`.
+#[deno_core::op2]
+#[string]
+pub fn op_get_action_map(state: &mut OpState) -> String {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().action_map.to_json()
+}
+
+/// Whether an action is held this frame.
+#[deno_core::op2(fast)]
+pub fn op_get_action(state: &mut OpState, #[string] action: &str) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().action_map.state(action).down
+}
+
+/// Whether an action became held this frame.
+#[deno_core::op2(fast)]
+pub fn op_is_action_pressed(state: &mut OpState, #[string] action: &str) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().action_map.state(action).pressed
+}
+
+/// Whether an action stopped being held this frame.
+#[deno_core::op2(fast)]
+pub fn op_is_action_released(state: &mut OpState, #[string] action: &str) -> bool {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().action_map.state(action).released
+}
+
+/// An action's value this frame, -1 to 1.
+#[deno_core::op2(fast)]
+pub fn op_get_axis(state: &mut OpState, #[string] action: &str) -> f64 {
+    let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+    bridge.borrow().action_map.state(action).value as f64
+}
+
 // --- Gamepad ops ---
 
 /// Get the number of connected gamepads.
@@ -1432,22 +3698,83 @@ deno_core::extension!(
     ops = [
         op_clear_sprites,
         op_submit_sprite_batch,
+        op_draw_sprites_bulk,
+        op_draw_nine_slice,
+        op_draw_outlined_sprites,
+        op_push_clip_rect,
+        op_pop_clip_rect,
+        op_set_layer_ysort,
+        op_set_pixel_snap,
+        op_set_batch_pixel_snap,
+        op_create_viewport,
+        op_set_viewport_camera,
+        op_set_viewport_rect,
+        op_destroy_viewport,
         op_set_camera,
         op_get_camera,
         op_load_texture,
         op_load_texture_linear,
+        op_load_texture_pair,
         op_upload_rgba_texture,
+        op_generate_collider_from_texture,
         op_is_key_down,
         op_is_key_pressed,
         op_get_mouse_position,
         op_is_mouse_button_down,
         op_is_mouse_button_pressed,
+        op_poll_input_events,
+        op_start_text_input,
+        op_stop_text_input,
         op_get_delta_time,
+        op_set_focus_behavior,
+        op_is_window_focused,
+        op_is_simulation_paused,
+        op_take_focus_events,
+        op_take_lifecycle_events,
         op_create_solid_texture,
+        op_get_texture_id_limit,
         op_create_tilemap,
+        op_create_chunked_tilemap,
+        op_set_tilemap_chunk,
+        op_remove_tilemap_chunk,
+        op_get_tilemap_chunk_count,
+        op_get_missing_tilemap_chunks,
+        op_retain_tilemap_chunks,
         op_set_tile,
         op_get_tile,
         op_draw_tilemap,
+        op_set_autotile_rule,
+        op_clear_autotile_rule,
+        op_set_tile_animation,
+        op_attach_world,
+        op_detach_world,
+        op_flush_world,
+        op_clear_world,
+        op_set_world_autosave,
+        op_spawn_decals,
+        op_draw_projectiles,
+        op_hitstop,
+        op_get_hitstop_remaining,
+        op_clear_decals,
+        op_set_max_decals,
+        op_get_decal_count,
+        op_sprite_create,
+        op_sprite_set,
+        op_sprite_update,
+        op_sprite_update_bulk,
+        op_sprite_set_visible,
+        op_sprite_destroy,
+        op_sprite_count,
+        op_entity_flash,
+        op_clear_flashes,
+        op_create_animation,
+        op_draw_animated,
+        op_restart_animation,
+        op_get_animation_frame,
+        op_destroy_animation,
+        op_take_animation_events,
+        op_load_aseprite,
+        op_create_aseprite_animation,
         op_set_ambient_light,
         op_add_point_light,
         op_clear_lights,
@@ -1457,24 +3784,57 @@ deno_core::extension!(
         op_play_sound_ex,
         op_play_sound_spatial,
         op_stop_instance,
+        op_fade_in_instance,
+        op_fade_out_instance,
+        op_crossfade_instances,
+        op_stop_instance_after,
         op_set_instance_volume,
         op_set_instance_pitch,
         op_update_spatial_positions,
+        op_set_spatial_params,
+        op_set_sound_occlusion,
+        op_set_sound_doppler,
         op_set_bus_volume,
+        op_set_bus_effect,
+        op_get_audio_levels,
         op_create_font_texture,
         op_get_viewport_size,
         op_get_scale_factor,
         op_set_background_color,
+        op_set_background_texture,
+        op_set_background_gradient,
+        op_clear_background_fill,
+        op_set_letterbox_color,
+        op_start_recording,
+        op_stop_recording,
+        op_is_recording,
+        op_capture_layers,
+        op_set_msaa,
+        op_get_msaa,
         op_save_file,
         op_load_file,
         op_delete_file,
         op_list_save_files,
+        op_list_save_backends,
+        op_enable_save_sync,
+        op_disable_save_sync,
+        op_sync_saves,
+        op_resolve_save_conflict,
+        op_request_replay_thumbnail,
+        op_save_replay,
+        op_load_replay,
         op_create_shader,
+        op_create_shader_from_file,
         op_set_shader_param,
         op_add_effect,
         op_set_effect_param,
         op_remove_effect,
         op_clear_effects,
+        op_start_transition,
+        op_cancel_transition,
+        op_get_transition_coverage,
+        op_take_transition_events,
+        op_create_transition_shader,
         op_set_camera_bounds,
         op_clear_camera_bounds,
         op_get_camera_bounds,
@@ -1482,6 +3842,21 @@ deno_core::extension!(
         op_disable_gi,
         op_set_gi_intensity,
         op_set_gi_quality,
+        op_set_adaptive_quality,
+        op_set_quality_tier,
+        op_get_quality,
+        op_set_gpu_error_capture,
+        op_take_gpu_errors,
+        op_get_sprite_stats,
+        op_set_runtime_atlas,
+        op_get_atlas_stats,
+        op_set_texture_upload_budget,
+        op_take_texture_events,
+        op_get_texture_stream_stats,
+        op_create_gpu_emitter,
+        op_emit_gpu_burst,
+        op_set_gpu_emitter_param,
+        op_destroy_gpu_emitter,
         op_add_emissive,
         op_clear_emissives,
         op_add_occluder,
@@ -1491,14 +3866,74 @@ deno_core::extension!(
         op_create_msdf_builtin_font,
         op_get_msdf_glyphs,
         op_get_msdf_font_info,
+        op_measure_text,
         op_load_msdf_font,
+        op_load_font,
+        op_load_bitmap_font,
+        op_set_debug_hud,
+        op_is_debug_hud_enabled,
         op_get_gamepad_count,
         op_get_gamepad_name,
         op_is_gamepad_button_down,
         op_is_gamepad_button_pressed,
         op_get_gamepad_axis,
+        op_load_action_map,
+        op_set_action_bindings,
+        op_get_action_map,
+        op_get_action,
+        op_is_action_pressed,
+        op_is_action_released,
+        op_get_axis,
         op_get_touch_count,
         op_get_touch_position,
         op_is_touch_active,
     ],
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_sprite_record_decodes() {
+        let mut record = [0.0f32; SPRITE_STRIDE];
+        record[0] = f32::from_bits(7);
+        record[1..5].copy_from_slice(&[10.0, 20.0, 16.0, 32.0]);
+        record[5] = f32::from_bits((-2i32) as u32);
+        record[6..10].copy_from_slice(&[0.25, 0.5, 0.25, 0.5]);
+        record[10..14].copy_from_slice(&[1.0, 0.5, 0.0, 1.0]);
+        record[14] = 1.5;
+        record[17] = 1.0;
+        record[19] = 0.75;
+        record[20] = 9.0;
+        record[21] = f32::from_bits(3);
+
+        let mut bytes: Vec<u8> = bytemuck::cast_slice(&record).to_vec();
+        // Part of a second record and a stray byte are ignored
+        bytes.extend_from_slice(&bytes.clone()[..SPRITE_STRIDE * 2]);
+        bytes.push(0xff);
+        let floats = packed_f32s(&bytes);
+        let cmds: Vec<SpriteCommand> = floats.chunks_exact(SPRITE_STRIDE).map(parse_sprite_record).collect();
+        assert_eq!(cmds.len(), 1);
+
+        let cmd = &cmds[0];
+        assert_eq!(cmd.texture_id, 7);
+        assert_eq!((cmd.x, cmd.y, cmd.w, cmd.h), (10.0, 20.0, 16.0, 32.0));
+        assert_eq!(cmd.layer, -2);
+        assert_eq!((cmd.uv_x, cmd.uv_y, cmd.uv_w, cmd.uv_h), (0.25, 0.5, 0.25, 0.5));
+        assert_eq!((cmd.tint_r, cmd.tint_g, cmd.tint_b, cmd.tint_a), (1.0, 0.5, 0.0, 1.0));
+        assert_eq!(cmd.rotation, 1.5);
+        assert!(cmd.flip_x && !cmd.flip_y);
+        assert_eq!(cmd.opacity, 0.75);
+        assert_eq!(cmd.blend_mode, 3);
+        assert_eq!(cmd.shader_id, 3);
+    }
+
+    #[test]
+    fn test_packed_f32s_copies_unaligned_buffers() {
+        let floats = [1.0f32, -2.5, 3.25];
+        let mut bytes = vec![0u8];
+        bytes.extend_from_slice(bytemuck::cast_slice(&floats));
+        assert_eq!(&*packed_f32s(&bytes[1..]), &floats);
+    }
+}
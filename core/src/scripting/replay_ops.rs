@@ -65,6 +65,16 @@ fn op_serialize_physics_state(state: &mut OpState) -> Vec<f64> {
                 result.push(0.0);
                 result.push(0.0);
             }
+            Shape::Capsule { half_height, radius } => {
+                result.push(3.0); // shape_type
+                result.push(*half_height as f64); // shape_p1
+                result.push(*radius as f64); // shape_p2
+            }
+            Shape::OBB { half_w, half_h } => {
+                result.push(4.0); // shape_type
+                result.push(*half_w as f64); // shape_p1
+                result.push(*half_h as f64); // shape_p2
+            }
         }
         result.push(body.x as f64);
         result.push(body.y as f64);
@@ -134,6 +144,8 @@ fn op_restore_physics_state(state: &mut OpState, #[serde] data: Vec<f64>) {
         let shape = match shape_type {
             0 => Shape::Circle { radius: shape_p1 },
             1 => Shape::AABB { half_w: shape_p1, half_h: shape_p2 },
+            3 => Shape::Capsule { half_height: shape_p1, radius: shape_p2 },
+            4 => Shape::OBB { half_w: shape_p1, half_h: shape_p2 },
             _ => Shape::AABB { half_w: shape_p1, half_h: shape_p2 },
         };
 
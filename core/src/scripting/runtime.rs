@@ -91,9 +91,20 @@ impl ArcaneRuntime {
 
     /// Create a new runtime with a custom import map for module resolution.
     pub fn new_with_import_map(import_map: ImportMap) -> Self {
+        let mut extensions = vec![
+            arcane_ext::init(),
+            super::engine_info::engine_ext::init(),
+            super::physics_ops::physics_ext::init(),
+            super::procgen_ops::procgen_ext::init(),
+            super::color_ops::color_ext::init(),
+            super::format_ops::format_ext::init(),
+            super::curve_ops::curve_ext::init(),
+            super::plugin_ops::plugin_ext::init(),
+        ];
+        extensions.extend(crate::plugin::extensions());
         let runtime = JsRuntime::new(RuntimeOptions {
             module_loader: Some(Rc::new(TsModuleLoader::with_import_map(import_map))),
-            extensions: vec![arcane_ext::init(), super::physics_ops::physics_ext::init()],
+            extensions,
             ..Default::default()
         });
 
@@ -102,9 +113,12 @@ impl ArcaneRuntime {
         // Store physics state in op_state
         {
             let op_state = rt.runtime.op_state();
-            op_state
-                .borrow_mut()
-                .put(Rc::new(RefCell::new(super::physics_ops::PhysicsState(None))));
+            let mut state = op_state.borrow_mut();
+            state.put(Rc::new(RefCell::new(super::physics_ops::PhysicsState(None))));
+            state.put(super::physics_ops::PhysicsPaused::default());
+            state.put(super::physics_ops::PhysicsStepTime::default());
+            state.put(Rc::new(RefCell::new(crate::curve::CurveStore::new())));
+            crate::plugin::init_op_state(&mut state);
         }
 
         rt.runtime
@@ -172,17 +186,33 @@ impl ArcaneRuntime {
         bridge: Rc<RefCell<super::render_ops::RenderBridgeState>>,
         import_map: ImportMap,
     ) -> Self {
+        let mut extensions = vec![
+            arcane_ext::init(),
+            super::engine_info::engine_ext::init(),
+            super::render_ops::render_ext::init(),
+            super::physics_ops::physics_ext::init(),
+            super::procgen_ops::procgen_ext::init(),
+            super::color_ops::color_ext::init(),
+            super::format_ops::format_ext::init(),
+            super::curve_ops::curve_ext::init(),
+            super::plugin_ops::plugin_ext::init(),
+            super::geometry_ops::geometry_ext::init(),
+            super::particle_ops::particle_ext::init(),
+            super::target_ops::target_ext::init(),
+            super::sdf_ops::sdf_ext::init(),
+            super::node_ops::node_ext::init(),
+            super::skeleton_ops::skeleton_ext::init(),
+            super::deform_ops::deform_ext::init(),
+            super::minimap_ops::minimap_ext::init(),
+            super::mask_ops::mask_ext::init(),
+            super::op_profiler::op_profiler_ext::init(),
+        ];
+        extensions.extend(crate::plugin::extensions());
+        let op_profiler = Rc::new(RefCell::new(super::op_profiler::OpProfiler::new()));
         let runtime = JsRuntime::new(RuntimeOptions {
             module_loader: Some(Rc::new(TsModuleLoader::with_import_map(import_map))),
-            extensions: vec![
-                arcane_ext::init(),
-                super::render_ops::render_ext::init(),
-                super::physics_ops::physics_ext::init(),
-                super::geometry_ops::geometry_ext::init(),
-                super::particle_ops::particle_ext::init(),
-                super::target_ops::target_ext::init(),
-                super::sdf_ops::sdf_ext::init(),
-            ],
+            extensions,
+            op_metrics_factory_fn: Some(super::op_profiler::metrics_factory(op_profiler.clone())),
             ..Default::default()
         });
 
@@ -194,10 +224,20 @@ impl ArcaneRuntime {
             let mut state = op_state.borrow_mut();
             state.put(bridge);
             state.put(Rc::new(RefCell::new(super::physics_ops::PhysicsState(None))));
+            state.put(super::physics_ops::PhysicsPaused::default());
+            state.put(super::physics_ops::PhysicsStepTime::default());
+            state.put(Rc::new(RefCell::new(crate::curve::CurveStore::new())));
             state.put(Rc::new(RefCell::new(super::geometry_ops::GeoState::new())));
             state.put(Rc::new(RefCell::new(super::particle_ops::ParticleState::new())));
             state.put(Rc::new(RefCell::new(super::target_ops::TargetState::new())));
             state.put(Rc::new(RefCell::new(super::sdf_ops::SdfState::new())));
+            state.put(Rc::new(RefCell::new(super::node_ops::NodeState::new())));
+            state.put(Rc::new(RefCell::new(super::skeleton_ops::SkeletonState::new())));
+            state.put(Rc::new(RefCell::new(super::deform_ops::DeformState::new())));
+            state.put(Rc::new(RefCell::new(super::minimap_ops::MinimapState::new())));
+            state.put(Rc::new(RefCell::new(super::mask_ops::MaskState::new())));
+            state.put(op_profiler);
+            crate::plugin::init_op_state(&mut state);
         }
 
         rt.runtime
@@ -4,13 +4,16 @@
 /// ## Command format
 /// Each SdfDrawCommand holds the WGSL expression, fill parameters, and
 /// transform data. The frame callback drains the queue and feeds it to
-/// the SDF rendering pipeline.
+/// the SDF rendering pipeline. While a render target is active, commands go
+/// to its queue in `TargetState` instead.
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use deno_core::OpState;
 
+use super::target_ops::{active_target, TargetState};
+
 /// A single SDF draw command queued from TypeScript.
 #[derive(Clone, Debug)]
 pub struct SdfDrawCommand {
@@ -55,6 +58,34 @@ impl SdfState {
     }
 }
 
+/// Queue an SDF command on the active render target, or the main pass.
+fn queue_sdf_command(state: &mut OpState, cmd: SdfDrawCommand) {
+    if let Some(target_id) = active_target(state) {
+        let ts = state.borrow::<Rc<RefCell<TargetState>>>();
+        ts.borrow_mut().target_sdf_queues.entry(target_id).or_default().push(cmd);
+    } else {
+        let sdf_state = state.borrow::<Rc<RefCell<SdfState>>>();
+        sdf_state.borrow_mut().commands.push(cmd);
+    }
+}
+
+/// Update the most recently queued SDF command, on whichever queue
+/// `queue_sdf_command` put it.
+fn update_last_sdf_command(state: &mut OpState, f: impl FnOnce(&mut SdfDrawCommand)) {
+    if let Some(target_id) = active_target(state) {
+        let ts = state.borrow::<Rc<RefCell<TargetState>>>();
+        let mut ts = ts.borrow_mut();
+        if let Some(cmd) = ts.target_sdf_queues.get_mut(&target_id).and_then(|q| q.last_mut()) {
+            f(cmd);
+        }
+    } else {
+        let sdf_state = state.borrow::<Rc<RefCell<SdfState>>>();
+        if let Some(cmd) = sdf_state.borrow_mut().commands.last_mut() {
+            f(cmd);
+        }
+    }
+}
+
 /// Queue an SDF draw command from TypeScript.
 ///
 /// Parameters are split across multiple op calls to stay within the fast-op
@@ -79,8 +110,7 @@ fn op_sdf_draw(
     scale: f64,
     opacity: f64,
 ) {
-    let sdf_state = state.borrow::<Rc<RefCell<SdfState>>>();
-    sdf_state.borrow_mut().commands.push(SdfDrawCommand {
+    let cmd = SdfDrawCommand {
         sdf_expr: sdf_expr.to_string(),
         fill_type: fill_type as u32,
         color: [r as f32, g as f32, b as f32, a as f32],
@@ -95,7 +125,8 @@ fn op_sdf_draw(
         rotation: rotation as f32,
         scale: scale as f32,
         opacity: opacity as f32,
-    });
+    };
+    queue_sdf_command(state, cmd);
 }
 
 /// Set cosine palette parameters for the most recently queued SDF command.
@@ -108,16 +139,14 @@ fn op_sdf_set_palette(
     c_r: f64, c_g: f64, c_b: f64,
     d_r: f64, d_g: f64, d_b: f64,
 ) {
-    let sdf_state = state.borrow::<Rc<RefCell<SdfState>>>();
-    let mut borrowed = sdf_state.borrow_mut();
-    if let Some(cmd) = borrowed.commands.last_mut() {
+    update_last_sdf_command(state, |cmd| {
         cmd.palette_params = [
             a_r as f32, a_g as f32, a_b as f32,
             b_r as f32, b_g as f32, b_b as f32,
             c_r as f32, c_g as f32, c_b as f32,
             d_r as f32, d_g as f32, d_b as f32,
         ];
-    }
+    });
 }
 
 /// Set gradient scale for the most recently queued SDF command.
@@ -125,11 +154,7 @@ fn op_sdf_set_palette(
 /// Scale > 1 makes the gradient span a smaller region (tighter fit to shape).
 #[deno_core::op2(fast)]
 fn op_sdf_set_gradient_scale(state: &mut OpState, scale: f64) {
-    let sdf_state = state.borrow::<Rc<RefCell<SdfState>>>();
-    let mut borrowed = sdf_state.borrow_mut();
-    if let Some(cmd) = borrowed.commands.last_mut() {
-        cmd.gradient_scale = scale as f32;
-    }
+    update_last_sdf_command(state, |cmd| cmd.gradient_scale = scale as f32);
 }
 
 /// Clear all queued SDF commands (called at start of each frame).
@@ -0,0 +1,522 @@
+//! Skeletal animation ops: load Spine JSON, create posed instances, play and
+//! crossfade animations, and emit region attachments as sprite commands.
+//!
+//! ## API (TS-side)
+//! ```ts
+//! const data = loadSkeleton("hero.json")!;          // parse once
+//! setSkeletonRegion(data.id, "head", headTex);       // attachment path → texture
+//! const hero = createSkeleton(data.id);
+//! playSkeletonAnimation(hero, "run", { loop: true, mix: 0.2 });
+//! onFrame(() => {
+//!   updateSkeleton(hero, getDeltaTime());
+//!   drawSkeleton(hero, 5);
+//!   const hand = getSkeletonBone(hero, "hand");      // attach a weapon here
+//! });
+//! ```
+//!
+//! ## Design
+//! - Data is parsed by `crate::animation::spine` and shared (`Rc`) between instances.
+//! - Posing, timeline sampling and crossfades run in `crate::animation::skeleton`.
+//! - Region attachments become `SpriteCommand`s (routed to the active render
+//!   target if one is set). Regions without a registered texture are skipped.
+//! - Mesh attachments are posed and deformed in Rust and drawn as textured
+//!   `GeoCommand::Mesh`es (main surface only); `op_get_skeleton_meshes` also
+//!   returns their world-space vertices for custom drawing.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use deno_core::OpState;
+
+use crate::animation::skeleton::{Skeleton, SkeletonData, SkeletonDrawItem};
+use crate::animation::spine::load_spine_json;
+use crate::json::escape;
+use crate::renderer::SpriteCommand;
+use crate::scripting::geometry_ops::{queue_geo_commands, GeoCommand};
+use crate::scripting::render_ops::RenderBridgeState;
+
+/// Texture and UV rect for one attachment path.
+#[derive(Debug, Clone, Copy)]
+pub struct RegionTexture {
+    pub texture_id: u32,
+    pub uv: [f32; 4],
+}
+
+/// Loaded skeleton data, region textures, and live instances.
+pub struct SkeletonState {
+    pub data: HashMap<u32, Rc<SkeletonData>>,
+    /// (data id, attachment path) → texture.
+    pub regions: HashMap<(u32, String), RegionTexture>,
+    /// Instance id → (data id, skeleton).
+    pub instances: HashMap<u32, (u32, Skeleton)>,
+    pub next_data_id: u32,
+    pub next_instance_id: u32,
+}
+
+impl Default for SkeletonState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SkeletonState {
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+            regions: HashMap::new(),
+            instances: HashMap::new(),
+            next_data_id: 1,
+            next_instance_id: 1,
+        }
+    }
+
+    /// Build sprite commands for every textured region attachment of an instance.
+    pub fn sprite_commands(&self, id: u32, layer: i32) -> Vec<SpriteCommand> {
+        let Some((data_id, skeleton)) = self.instances.get(&id) else {
+            return Vec::new();
+        };
+        let mut cmds = Vec::new();
+        for item in skeleton.draw_list() {
+            let SkeletonDrawItem::Region { path, center_x, center_y, width, height, rotation, flip_y, color } = item
+            else {
+                continue;
+            };
+            let Some(region) = self.regions.get(&(*data_id, path)) else { continue };
+            cmds.push(SpriteCommand {
+                texture_id: region.texture_id,
+                x: center_x - width * 0.5,
+                y: center_y - height * 0.5,
+                w: width,
+                h: height,
+                layer,
+                uv_x: region.uv[0],
+                uv_y: region.uv[1],
+                uv_w: region.uv[2],
+                uv_h: region.uv[3],
+                tint_r: color[0],
+                tint_g: color[1],
+                tint_b: color[2],
+                tint_a: color[3],
+                rotation,
+                origin_x: 0.5,
+                origin_y: 0.5,
+                flip_x: false,
+                flip_y,
+                opacity: 1.0,
+                blend_mode: 0,
+                shader_id: 0,
+                nine_slice: None,
+                outline: None,
+                clip: None,
+                mask: None,
+                normal_map: None,
+                flash: None,
+                pixel_snap: None,
+            });
+        }
+        cmds
+    }
+
+    /// Build textured geometry for every mesh attachment with a registered region.
+    /// Attachment UVs are relative to the region and remapped into its atlas rect.
+    pub fn mesh_commands(&self, id: u32, layer: i32) -> Vec<GeoCommand> {
+        let Some((data_id, skeleton)) = self.instances.get(&id) else {
+            return Vec::new();
+        };
+        let mut cmds = Vec::new();
+        for item in skeleton.draw_list() {
+            let SkeletonDrawItem::Mesh { path, vertices, uvs, triangles, color } = item else { continue };
+            let Some(region) = self.regions.get(&(*data_id, path)) else { continue };
+            let [uv_x, uv_y, uv_w, uv_h] = region.uv;
+            let packed = vertices
+                .chunks_exact(2)
+                .enumerate()
+                .flat_map(|(i, p)| {
+                    let u = uvs.get(i * 2).copied().unwrap_or(0.0);
+                    let v = uvs.get(i * 2 + 1).copied().unwrap_or(0.0);
+                    [p[0], p[1], uv_x + u * uv_w, uv_y + v * uv_h]
+                })
+                .collect();
+            cmds.push(GeoCommand::Mesh {
+                texture_id: region.texture_id,
+                vertices: packed,
+                indices: triangles,
+                r: color[0], g: color[1], b: color[2], a: color[3],
+                layer,
+            });
+        }
+        cmds
+    }
+}
+
+/// Load Spine JSON (raw string or path relative to the game directory).
+/// Returns JSON: { "dataId", "bones": [..], "slots": [..], "skins": [..],
+/// "animations": [{ "name", "duration" }] } or { "error": "..." }.
+#[deno_core::op2]
+#[string]
+fn op_load_skeleton(state: &mut OpState, #[string] json_or_path: &str) -> String {
+    let source = if json_or_path.trim_start().starts_with('{') {
+        json_or_path.to_string()
+    } else {
+        let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+        let path = bridge.borrow().base_dir.join(json_or_path);
+        match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                return format!(
+                    "{{\"error\":\"{}\"}}",
+                    escape(&format!("Failed to read skeleton {}: {}", path.display(), e))
+                );
+            }
+        }
+    };
+
+    let data = match load_spine_json(&source) {
+        Ok(d) => d,
+        Err(e) => return format!("{{\"error\":\"{}\"}}", escape(&e)),
+    };
+
+    let names = |it: &mut dyn Iterator<Item = &String>| {
+        it.map(|n| format!("\"{}\"", escape(n))).collect::<Vec<_>>().join(",")
+    };
+    let bones = names(&mut data.bones.iter().map(|b| &b.name));
+    let slots = names(&mut data.slots.iter().map(|s| &s.name));
+    let skins = names(&mut data.skins.iter().map(|s| &s.name));
+    let animations: Vec<String> = data
+        .animations
+        .iter()
+        .map(|a| format!("{{\"name\":\"{}\",\"duration\":{}}}", escape(&a.name), a.duration))
+        .collect();
+
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    let mut ss = ss.borrow_mut();
+    let id = ss.next_data_id;
+    ss.next_data_id += 1;
+    ss.data.insert(id, Rc::new(data));
+
+    format!(
+        "{{\"dataId\":{},\"bones\":[{}],\"slots\":[{}],\"skins\":[{}],\"animations\":[{}]}}",
+        id,
+        bones,
+        slots,
+        skins,
+        animations.join(","),
+    )
+}
+
+/// Map an attachment path to a texture (and optional atlas UV rect, 0-1).
+/// Pass uv_w = 0 to use the whole texture.
+#[deno_core::op2(fast)]
+fn op_set_skeleton_region(
+    state: &mut OpState,
+    data_id: u32,
+    #[string] path: &str,
+    texture_id: u32,
+    uv_x: f64,
+    uv_y: f64,
+    uv_w: f64,
+    uv_h: f64,
+) {
+    let uv = if uv_w > 0.0 {
+        [uv_x as f32, uv_y as f32, uv_w as f32, uv_h as f32]
+    } else {
+        [0.0, 0.0, 1.0, 1.0]
+    };
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    ss.borrow_mut()
+        .regions
+        .insert((data_id, path.to_string()), RegionTexture { texture_id, uv });
+}
+
+/// Create a skeleton instance in its setup pose. Returns 0 for unknown data.
+#[deno_core::op2(fast)]
+fn op_create_skeleton(state: &mut OpState, data_id: u32) -> u32 {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    let mut ss = ss.borrow_mut();
+    let Some(data) = ss.data.get(&data_id).cloned() else {
+        return 0;
+    };
+    let id = ss.next_instance_id;
+    ss.next_instance_id += 1;
+    ss.instances.insert(id, (data_id, Skeleton::new(data)));
+    id
+}
+
+#[deno_core::op2(fast)]
+fn op_destroy_skeleton(state: &mut OpState, id: u32) {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    ss.borrow_mut().instances.remove(&id);
+}
+
+/// Set the skeleton's root position and scale (negative scale_x mirrors).
+#[deno_core::op2(fast)]
+fn op_set_skeleton_transform(state: &mut OpState, id: u32, x: f64, y: f64, scale_x: f64, scale_y: f64) {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    if let Some((_, sk)) = ss.borrow_mut().instances.get_mut(&id) {
+        sk.x = x as f32;
+        sk.y = y as f32;
+        sk.scale_x = scale_x as f32;
+        sk.scale_y = scale_y as f32;
+        sk.update_world_transform();
+    }
+}
+
+/// Switch the active skin. Returns false if the skin does not exist.
+#[deno_core::op2(fast)]
+fn op_set_skeleton_skin(state: &mut OpState, id: u32, #[string] name: &str) -> bool {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    let mut ss = ss.borrow_mut();
+    let Some((_, sk)) = ss.instances.get_mut(&id) else { return false };
+    match sk.data.find_skin(name) {
+        Some(skin) => {
+            sk.skin = Some(skin);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Play an animation on a track, crossfading over mix_duration seconds.
+/// Returns false if the skeleton or animation does not exist, or `track` is
+/// not below `MAX_TRACKS`.
+#[deno_core::op2(fast)]
+fn op_play_skeleton_animation(
+    state: &mut OpState,
+    id: u32,
+    track: u32,
+    #[string] name: &str,
+    looping: bool,
+    mix_duration: f64,
+) -> bool {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    let mut ss = ss.borrow_mut();
+    let Some((_, sk)) = ss.instances.get_mut(&id) else { return false };
+    let Some(anim) = sk.data.find_animation(name) else { return false };
+    sk.state.play(track as usize, anim, looping, mix_duration as f32)
+}
+
+#[deno_core::op2(fast)]
+fn op_clear_skeleton_track(state: &mut OpState, id: u32, track: u32) {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    if let Some((_, sk)) = ss.borrow_mut().instances.get_mut(&id) {
+        sk.state.clear_track(track as usize);
+    }
+}
+
+/// Set a track's blend weight (0-1) and playback speed.
+#[deno_core::op2(fast)]
+fn op_set_skeleton_track_mix(state: &mut OpState, id: u32, track: u32, alpha: f64, speed: f64) {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    if let Some((_, sk)) = ss.borrow_mut().instances.get_mut(&id) {
+        sk.state.set_track_alpha(track as usize, alpha as f32);
+        sk.state.set_track_speed(track as usize, speed as f32);
+    }
+}
+
+/// Advance animations by dt seconds and recompute the pose.
+#[deno_core::op2(fast)]
+fn op_update_skeleton(state: &mut OpState, id: u32, dt: f64) {
+    let ss = state.borrow_mut::<Rc<RefCell<SkeletonState>>>();
+    if let Some((_, sk)) = ss.borrow_mut().instances.get_mut(&id) {
+        sk.update(dt as f32);
+    }
+}
+
+/// Queue sprite commands for the skeleton's region attachments and textured
+/// geometry for its mesh attachments.
+#[deno_core::op2(fast)]
+fn op_draw_skeleton(state: &mut OpState, id: u32, layer: i32) {
+    let (mut cmds, meshes) = {
+        let ss = state.borrow::<Rc<RefCell<SkeletonState>>>();
+        let ss = ss.borrow();
+        (ss.sprite_commands(id, layer), ss.mesh_commands(id, layer))
+    };
+    if let Some(clip) = super::render_ops::active_clip(state) {
+        cmds.iter_mut().for_each(|cmd| cmd.clip = Some(clip));
+    }
+    if !meshes.is_empty() {
+        queue_geo_commands(state, meshes);
+    }
+    if cmds.is_empty() {
+        return;
+    }
+
+    if let Some(target_id) = super::target_ops::active_target(state) {
+        use super::target_ops::TargetState;
+        let ts = state.borrow::<Rc<RefCell<TargetState>>>();
+        ts.borrow_mut().target_sprite_queues.entry(target_id).or_default().extend(cmds);
+    } else {
+        if let Some(mask) = super::mask_ops::active_mask(state) {
+            cmds.iter_mut().for_each(|cmd| cmd.mask = Some(mask));
+        }
+        let bridge = state.borrow::<Rc<RefCell<RenderBridgeState>>>();
+        bridge.borrow_mut().sprite_commands.extend(cmds);
+    }
+}
+
+/// World-space bone transform: [x, y, rotation_radians, scale_x, scale_y],
+/// or empty vec for unknown skeletons/bones.
+#[deno_core::op2]
+#[serde]
+fn op_get_skeleton_bone(state: &mut OpState, id: u32, #[string] name: &str) -> Vec<f64> {
+    let ss = state.borrow::<Rc<RefCell<SkeletonState>>>();
+    let ss = ss.borrow();
+    let Some((_, sk)) = ss.instances.get(&id) else { return vec![] };
+    let Some(bone) = sk.data.find_bone(name).map(|i| sk.bones[i]) else { return vec![] };
+    vec![
+        bone.world_x as f64,
+        bone.world_y as f64,
+        bone.world_rotation() as f64,
+        bone.world_scale_x() as f64,
+        bone.world_scale_y() as f64,
+    ]
+}
+
+/// Posed mesh attachments, packed per mesh as:
+/// [vertex_count, index_count, r, g, b, a, (x, y, u, v) × vertex_count, index × index_count].
+#[deno_core::op2]
+#[serde]
+fn op_get_skeleton_meshes(state: &mut OpState, id: u32) -> Vec<f64> {
+    let ss = state.borrow::<Rc<RefCell<SkeletonState>>>();
+    let ss = ss.borrow();
+    let Some((_, sk)) = ss.instances.get(&id) else { return vec![] };
+    let mut out = Vec::new();
+    for item in sk.draw_list() {
+        let SkeletonDrawItem::Mesh { vertices, uvs, triangles, color, .. } = item else { continue };
+        let count = vertices.len() / 2;
+        out.push(count as f64);
+        out.push(triangles.len() as f64);
+        out.extend(color.iter().map(|&c| c as f64));
+        for i in 0..count {
+            out.push(vertices[i * 2] as f64);
+            out.push(vertices[i * 2 + 1] as f64);
+            out.push(uvs.get(i * 2).copied().unwrap_or(0.0) as f64);
+            out.push(uvs.get(i * 2 + 1).copied().unwrap_or(0.0) as f64);
+        }
+        out.extend(triangles.iter().map(|&t| t as f64));
+    }
+    out
+}
+
+deno_core::extension!(
+    skeleton_ext,
+    ops = [
+        op_load_skeleton,
+        op_set_skeleton_region,
+        op_create_skeleton,
+        op_destroy_skeleton,
+        op_set_skeleton_transform,
+        op_set_skeleton_skin,
+        op_play_skeleton_animation,
+        op_clear_skeleton_track,
+        op_set_skeleton_track_mix,
+        op_update_skeleton,
+        op_draw_skeleton,
+        op_get_skeleton_bone,
+        op_get_skeleton_meshes,
+    ],
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::skeleton::*;
+
+    fn state_with_instance() -> (SkeletonState, u32) {
+        let mut skin = Skin { name: "default".into(), ..Default::default() };
+        skin.attachments.insert(
+            (0, "head".into()),
+            Attachment::Region {
+                path: "head".into(),
+                x: 0.0,
+                y: 10.0,
+                rotation: 0.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+                width: 16.0,
+                height: 16.0,
+                color: [1.0; 4],
+            },
+        );
+        skin.attachments.insert(
+            (1, "cape".into()),
+            Attachment::Mesh {
+                path: "cape".into(),
+                uvs: vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+                triangles: vec![0, 1, 2],
+                vertices: MeshVertices::Unweighted(vec![0.0, 0.0, 8.0, 0.0, 0.0, -8.0]),
+                color: [1.0; 4],
+            },
+        );
+        let data = Rc::new(SkeletonData {
+            bones: vec![BoneData {
+                name: "root".into(),
+                parent: None,
+                length: 0.0,
+                x: 0.0,
+                y: 0.0,
+                rotation: 0.0,
+                scale_x: 1.0,
+                scale_y: 1.0,
+                shear_x: 0.0,
+                shear_y: 0.0,
+            }],
+            slots: vec![
+                SlotData { name: "head".into(), bone: 0, attachment: Some("head".into()), color: [1.0; 4] },
+                SlotData { name: "cape".into(), bone: 0, attachment: Some("cape".into()), color: [1.0; 4] },
+            ],
+            skins: vec![skin],
+            animations: vec![],
+        });
+        let mut ss = SkeletonState::new();
+        ss.data.insert(1, data.clone());
+        let mut sk = Skeleton::new(data);
+        sk.x = 100.0;
+        sk.y = 100.0;
+        sk.update_world_transform();
+        ss.instances.insert(7, (1, sk));
+        (ss, 7)
+    }
+
+    #[test]
+    fn test_untextured_regions_are_skipped() {
+        let (ss, id) = state_with_instance();
+        assert!(ss.sprite_commands(id, 0).is_empty());
+        assert!(ss.sprite_commands(999, 0).is_empty());
+    }
+
+    #[test]
+    fn test_region_becomes_sprite_command() {
+        let (mut ss, id) = state_with_instance();
+        ss.regions.insert((1, "head".into()), RegionTexture { texture_id: 3, uv: [0.5, 0.0, 0.5, 1.0] });
+        let cmds = ss.sprite_commands(id, 4);
+        assert_eq!(cmds.len(), 1);
+        let c = &cmds[0];
+        assert_eq!(c.texture_id, 3);
+        assert_eq!(c.layer, 4);
+        assert_eq!(c.uv_x, 0.5);
+        // y-up offset of 10 becomes 10 pixels above the root on screen
+        assert!((c.x + c.w * 0.5 - 100.0).abs() < 1e-3);
+        assert!((c.y + c.h * 0.5 - 90.0).abs() < 1e-3);
+        assert!(!c.flip_y);
+    }
+
+    #[test]
+    fn test_mesh_attachment_becomes_textured_geometry() {
+        let (mut ss, id) = state_with_instance();
+        assert!(ss.mesh_commands(id, 2).is_empty());
+        ss.regions.insert((1, "cape".into()), RegionTexture { texture_id: 5, uv: [0.5, 0.5, 0.5, 0.5] });
+        let cmds = ss.mesh_commands(id, 2);
+        assert_eq!(cmds.len(), 1);
+        let GeoCommand::Mesh { texture_id, vertices, indices, layer, .. } = &cmds[0] else {
+            panic!("expected mesh");
+        };
+        assert_eq!((*texture_id, *layer), (5, 2));
+        assert_eq!(indices, &vec![0, 1, 2]);
+        // Second vertex: 8 px right of the root, UV remapped into the atlas rect
+        assert_eq!(&vertices[4..8], &[108.0, 100.0, 1.0, 0.5]);
+        // Third vertex: y-up -8 becomes 8 px below the root on screen
+        assert_eq!(&vertices[8..12], &[100.0, 108.0, 0.5, 1.0]);
+    }
+}
@@ -6,50 +6,166 @@
 /// const rt = createRenderTarget(256, 256);  // → RenderTargetId (also usable as TextureId)
 /// beginRenderTarget(rt);
 ///   drawSprite(...);  // renders into rt's texture, camera: (0,0) = top-left
+///   drawCircle(...);  // shapes and SDF too
 /// endRenderTarget();
 /// drawSprite({ textureId: rt, x: 0, y: 0, w: 256, h: 256 });
+///
+/// // Nesting: draw a minimap target, then show it on a CRT target
+/// beginRenderTarget(crt);
+///   beginRenderTarget(map); drawRect(...); endRenderTarget();  // back to crt
+///   drawSprite({ textureId: map, ... });
+/// endRenderTarget();
+///
+/// // Security camera: re-render layers 0..20 of the main scene around (800, 400)
+/// setRenderTargetCamera(rt, { x: 800, y: 400, zoom: 0.5, layers: [0, 20], captureScene: true });
+///
+/// // Blur just this target before it is sampled
+/// const blur = addRenderTargetEffect(rt, "blur");
+/// setEffectParam(blur, 0, 2.0);
 /// ```
 ///
 /// ## Design
 /// - `op_create_render_target` allocates an ID from the shared `next_texture_id` counter
 ///   (avoiding any collision with regular textures) and queues GPU resource creation.
-/// - `op_begin_render_target` sets `active_target = Some(id)`. While active,
-///   `op_submit_sprite_batch` routes commands to the target's
-///   queue instead of the main bridge sprite list.
-/// - `op_end_render_target` clears `active_target`.
-/// - dev.rs drains `create_queue`, `target_sprite_queues`, and `destroy_queue`
-///   each frame before the main render pass.
+/// - `op_begin_render_target` sets `active_target = Some(id)`, pushing the
+///   previous target onto `outer_targets`. While active, sprite batches,
+///   geometry and SDF shapes route to the target's queues instead of the main
+///   pass (see `active_target()`).
+/// - `op_end_render_target` resumes the enclosing target, or the main pass.
+/// - dev.rs drains `create_queue`, the target queues and `destroy_queue` each
+///   frame before the main render pass. Queues and cameras are `BTreeMap`s
+///   so targets render in ID order on every run (replays, golden images),
+///   except that a target sampling another target renders after it.
+/// - `op_set_render_target_camera` stores a `TargetCamera` in `cameras`; dev.rs
+///   passes them to the pre-pass. Targets without one keep the default camera.
+/// - Cameras with `capture_scene` get the main scene's sprites, geometry and SDF
+///   shapes appended to their queues by `TargetState::capture_scene` and
+///   `capture_scene_shapes` (commands sampling the target itself are skipped to
+///   avoid feedback). Layer filtering happens in the pre-pass.
+/// - `op_add_render_target_effect` allocates from the bridge's `next_effect_id`,
+///   so the regular effect param/remove ops work on target effects too. dev.rs
+///   drains `effect_create_queue` and `effect_clear_queue` with the screen effects;
+///   the pre-pass runs each target's chain at the target's size.
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
 use deno_core::OpState;
 
-use crate::renderer::SpriteCommand;
+use crate::renderer::{SpriteCommand, TargetCamera};
+use crate::scripting::geometry_ops::GeoCommand;
 use crate::scripting::render_ops::RenderBridgeState;
+use crate::scripting::sdf_ops::SdfDrawCommand;
 
 /// State for all live render targets and the currently active one.
 pub struct TargetState {
-    /// If Some, sprite commands route to this target instead of the main pass.
+    /// If Some, draw commands route to this target instead of the main pass.
     pub active_target: Option<u32>,
+    /// Targets suspended by a nested begin, innermost last.
+    pub outer_targets: Vec<u32>,
     /// GPU resource creation requests, drained by dev.rs each frame.
     pub create_queue: Vec<(u32, u32, u32)>, // (id, width, height)
     /// GPU resource destroy requests, drained by dev.rs each frame.
     pub destroy_queue: Vec<u32>,
     /// Per-target sprite command queues, drained by dev.rs for off-screen rendering.
-    pub target_sprite_queues: HashMap<u32, Vec<SpriteCommand>>,
+    /// Ordered by target ID so the pre-pass renders targets in the same order every run.
+    pub target_sprite_queues: BTreeMap<u32, Vec<SpriteCommand>>,
+    /// Per-target geometry queues (triangles, lines, meshes), drained with the sprite queues.
+    pub target_geo_queues: BTreeMap<u32, Vec<GeoCommand>>,
+    /// Per-target SDF shape queues, drained with the sprite queues.
+    pub target_sdf_queues: BTreeMap<u32, Vec<SdfDrawCommand>>,
+    /// Per-target cameras. Persist across frames until cleared or destroyed.
+    pub cameras: BTreeMap<u32, TargetCamera>,
+    /// Effects to append to a target's post-process chain, drained by dev.rs.
+    pub effect_create_queue: Vec<(u32, u32, String)>, // (target id, effect id, type)
+    /// Targets whose effect chains should be removed, drained by dev.rs.
+    pub effect_clear_queue: Vec<u32>,
 }
 
 impl TargetState {
     pub fn new() -> Self {
         Self {
             active_target: None,
+            outer_targets: Vec::new(),
             create_queue: Vec::new(),
             destroy_queue: Vec::new(),
-            target_sprite_queues: HashMap::new(),
+            target_sprite_queues: BTreeMap::new(),
+            target_geo_queues: BTreeMap::new(),
+            target_sdf_queues: BTreeMap::new(),
+            cameras: BTreeMap::new(),
+            effect_create_queue: Vec::new(),
+            effect_clear_queue: Vec::new(),
+        }
+    }
+
+    /// Queue the main scene's sprites for every target whose camera captures
+    /// the scene. Call before draining `target_sprite_queues`.
+    pub fn capture_scene(&self, sprites: &[SpriteCommand], queues: &mut BTreeMap<u32, Vec<SpriteCommand>>) {
+        for (&id, camera) in &self.cameras {
+            if !camera.capture_scene {
+                continue;
+            }
+            queues
+                .entry(id)
+                .or_default()
+                .extend(sprites.iter().filter(|c| c.texture_id != id).cloned());
+        }
+    }
+
+    /// Queue the main scene's geometry and SDF shapes for every target whose
+    /// camera captures the scene. Call before draining the target queues.
+    pub fn capture_scene_shapes(&mut self, geo: &[GeoCommand], sdf: &[SdfDrawCommand]) {
+        for (&id, camera) in &self.cameras {
+            if !camera.capture_scene {
+                continue;
+            }
+            self.target_geo_queues.entry(id).or_default().extend(
+                geo.iter()
+                    .filter(|c| c.texture_id() != Some(id))
+                    .cloned(),
+            );
+            self.target_sdf_queues.entry(id).or_default().extend(sdf.iter().cloned());
         }
     }
+
+    /// Start drawing into `id`; the current target (if any) resumes on `end`.
+    pub fn begin(&mut self, id: u32) {
+        if let Some(current) = self.active_target.replace(id) {
+            self.outer_targets.push(current);
+        }
+    }
+
+    /// Stop drawing into the active target and resume the enclosing one.
+    pub fn end(&mut self) {
+        self.active_target = self.outer_targets.pop();
+    }
+
+    /// Drop a destroyed target's queues, camera and pending effects, and stop
+    /// drawing into it.
+    pub fn forget(&mut self, id: u32) {
+        self.target_sprite_queues.remove(&id);
+        self.target_geo_queues.remove(&id);
+        self.target_sdf_queues.remove(&id);
+        self.cameras.remove(&id);
+        self.effect_create_queue.retain(|(target, _, _)| *target != id);
+        self.outer_targets.retain(|&t| t != id);
+        if self.active_target == Some(id) {
+            self.end();
+        }
+    }
+
+    /// Drop a target's pending effects and queue removal of its chain.
+    pub fn clear_effects(&mut self, id: u32) {
+        self.effect_create_queue.retain(|(target, _, _)| *target != id);
+        self.effect_clear_queue.push(id);
+    }
+}
+
+/// The render target draw commands currently route to, if any.
+pub fn active_target(state: &OpState) -> Option<u32> {
+    let ts = state.borrow::<Rc<RefCell<TargetState>>>();
+    ts.borrow().active_target
 }
 
 /// Create an off-screen render target of the given pixel dimensions.
@@ -74,19 +190,83 @@ fn op_create_render_target(state: &mut OpState, w: f64, h: f64) -> u32 {
     id
 }
 
-/// Route subsequent drawSprite calls into this render target.
+/// Route subsequent sprite, geometry and SDF draws into this render target.
 /// Coordinate system inside the target: (0, 0) = top-left of target.
+/// Calls nest: the current target resumes on the matching end.
 #[deno_core::op2(fast)]
 fn op_begin_render_target(state: &mut OpState, id: u32) {
     let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
-    ts.borrow_mut().active_target = Some(id);
+    ts.borrow_mut().begin(id);
 }
 
-/// Return to rendering into the main surface.
+/// Return to rendering into the enclosing render target, or the main surface.
 #[deno_core::op2(fast)]
 fn op_end_render_target(state: &mut OpState) {
     let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
-    ts.borrow_mut().active_target = None;
+    ts.borrow_mut().end();
+}
+
+/// Set the camera a render target is drawn with. `(x, y)` is the world
+/// position of the target's top-left corner; only sprites on layers
+/// `min..=max` are drawn. With `capture_scene`, the main scene's sprites are
+/// drawn into the target too.
+#[deno_core::op2(fast)]
+fn op_set_render_target_camera(
+    state: &mut OpState,
+    id: u32,
+    x: f64,
+    y: f64,
+    zoom: f64,
+    rotation: f64,
+    layer_min: i32,
+    layer_max: i32,
+    capture_scene: bool,
+) {
+    let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+    ts.borrow_mut().cameras.insert(
+        id,
+        TargetCamera {
+            x: x as f32,
+            y: y as f32,
+            zoom: zoom as f32,
+            rotation: rotation as f32,
+            layer_min: layer_min.min(layer_max),
+            layer_max: layer_min.max(layer_max),
+            capture_scene,
+        },
+    );
+}
+
+/// Go back to the default camera: (0, 0) at the top-left, all layers, own sprites only.
+#[deno_core::op2(fast)]
+fn op_clear_render_target_camera(state: &mut OpState, id: u32) {
+    let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+    ts.borrow_mut().cameras.remove(&id);
+}
+
+/// Append a post-process effect to a render target's chain. Returns an effect
+/// ID for `op_set_effect_param` / `op_remove_effect`.
+#[deno_core::op2(fast)]
+fn op_add_render_target_effect(state: &mut OpState, id: u32, #[string] effect_type: &str) -> u32 {
+    let effect_id = {
+        let bridge = state.borrow_mut::<Rc<RefCell<RenderBridgeState>>>();
+        let mut b = bridge.borrow_mut();
+        let effect_id = b.next_effect_id;
+        b.next_effect_id += 1;
+        effect_id
+    };
+    let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+    ts.borrow_mut()
+        .effect_create_queue
+        .push((id, effect_id, effect_type.to_string()));
+    effect_id
+}
+
+/// Remove every effect from a render target's chain.
+#[deno_core::op2(fast)]
+fn op_clear_render_target_effects(state: &mut OpState, id: u32) {
+    let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
+    ts.borrow_mut().clear_effects(id);
 }
 
 /// Free the GPU resources for a render target.
@@ -96,11 +276,7 @@ fn op_destroy_render_target(state: &mut OpState, id: u32) {
     let ts = state.borrow_mut::<Rc<RefCell<TargetState>>>();
     let mut ts = ts.borrow_mut();
     ts.destroy_queue.push(id);
-    ts.target_sprite_queues.remove(&id);
-    // If this target was active, end it
-    if ts.active_target == Some(id) {
-        ts.active_target = None;
-    }
+    ts.forget(id);
 }
 
 deno_core::extension!(
@@ -109,6 +285,10 @@ deno_core::extension!(
         op_create_render_target,
         op_begin_render_target,
         op_end_render_target,
+        op_set_render_target_camera,
+        op_clear_render_target_camera,
+        op_add_render_target_effect,
+        op_clear_render_target_effects,
         op_destroy_render_target,
     ],
 );
@@ -121,9 +301,13 @@ mod tests {
     fn test_target_state_new() {
         let state = TargetState::new();
         assert!(state.active_target.is_none());
+        assert!(state.outer_targets.is_empty());
         assert!(state.create_queue.is_empty());
         assert!(state.destroy_queue.is_empty());
         assert!(state.target_sprite_queues.is_empty());
+        assert!(state.cameras.is_empty());
+        assert!(state.effect_create_queue.is_empty());
+        assert!(state.effect_clear_queue.is_empty());
     }
 
     #[test]
@@ -191,4 +375,111 @@ mod tests {
 
         assert!(state.active_target.is_none());
     }
+
+    #[test]
+    fn test_nested_targets_resume_the_enclosing_one() {
+        let mut state = TargetState::new();
+        state.begin(3);
+        state.begin(9);
+        assert_eq!(state.active_target, Some(9));
+        state.end();
+        assert_eq!(state.active_target, Some(3));
+        state.end();
+        assert!(state.active_target.is_none());
+        // Unbalanced ends stay on the main pass
+        state.end();
+        assert!(state.active_target.is_none());
+    }
+
+    #[test]
+    fn test_forget_drops_a_target_from_the_nesting() {
+        let mut state = TargetState::new();
+        state.begin(3);
+        state.begin(9);
+        state.begin(12);
+        state.target_geo_queues.insert(9, Vec::new());
+        state.forget(9);
+        assert_eq!(state.active_target, Some(12));
+        assert!(!state.target_geo_queues.contains_key(&9));
+        state.forget(12);
+        assert_eq!(state.active_target, Some(3));
+    }
+
+    fn sprite(texture_id: u32, layer: i32) -> SpriteCommand {
+        SpriteCommand {
+            texture_id, x: 0.0, y: 0.0, w: 16.0, h: 16.0, layer,
+            uv_x: 0.0, uv_y: 0.0, uv_w: 1.0, uv_h: 1.0,
+            tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
+            rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
+            flip_x: false, flip_y: false, opacity: 1.0,
+            blend_mode: 0, shader_id: 0, nine_slice: None, outline: None, clip: None, mask: None, normal_map: None, flash: None, pixel_snap: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_scene_only_for_capturing_cameras() {
+        let mut state = TargetState::new();
+        state.cameras.insert(7, TargetCamera { capture_scene: true, ..TargetCamera::default() });
+        state.cameras.insert(8, TargetCamera::default());
+
+        let scene = [sprite(1, 0), sprite(7, 0), sprite(2, 30)];
+        let mut queues = BTreeMap::new();
+        queues.insert(7, vec![sprite(3, 5)]);
+        state.capture_scene(&scene, &mut queues);
+
+        // Own sprites first, then the scene minus sprites sampling the target itself
+        let ids: Vec<u32> = queues[&7].iter().map(|c| c.texture_id).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert!(!queues.contains_key(&8));
+    }
+
+    #[test]
+    fn test_capture_scene_queues_in_target_id_order() {
+        let mut state = TargetState::new();
+        for id in [42, 5, 17, 30, 9] {
+            state.cameras.insert(id, TargetCamera { capture_scene: true, ..TargetCamera::default() });
+        }
+        let mut queues = BTreeMap::new();
+        state.capture_scene(&[sprite(1, 0)], &mut queues);
+
+        let order: Vec<u32> = queues.keys().copied().collect();
+        assert_eq!(order, vec![5, 9, 17, 30, 42]);
+    }
+
+    #[test]
+    fn test_clear_effects_drops_pending_for_that_target() {
+        let mut state = TargetState::new();
+        state.effect_create_queue.push((1, 10, "blur".to_string()));
+        state.effect_create_queue.push((2, 11, "vignette".to_string()));
+        state.effect_create_queue.push((1, 12, "crt".to_string()));
+
+        state.clear_effects(1);
+
+        assert_eq!(state.effect_create_queue, vec![(2, 11, "vignette".to_string())]);
+        assert_eq!(state.effect_clear_queue, vec![1]);
+    }
+
+    #[test]
+    fn test_capture_scene_shapes_skips_meshes_sampling_the_target() {
+        let mut state = TargetState::new();
+        state.cameras.insert(7, TargetCamera { capture_scene: true, ..TargetCamera::default() });
+        state.cameras.insert(8, TargetCamera::default());
+        let mesh = |texture_id| GeoCommand::Mesh {
+            texture_id, vertices: Vec::new(), indices: Vec::new(),
+            r: 1.0, g: 1.0, b: 1.0, a: 1.0, layer: 0,
+        };
+
+        state.capture_scene_shapes(&[mesh(1), mesh(7)], &[]);
+
+        let ids: Vec<u32> = state.target_geo_queues[&7]
+            .iter()
+            .filter_map(|c| match c {
+                GeoCommand::Mesh { texture_id, .. } => Some(*texture_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec![1]);
+        assert!(state.target_sdf_queues[&7].is_empty());
+        assert!(!state.target_geo_queues.contains_key(&8));
+    }
 }
@@ -1,4 +1,15 @@
-use arcane_core::audio::{AudioBus, AudioCommand};
+use std::sync::Arc;
+use std::sync::atomic::AtomicU32;
+
+use arcane_core::audio::effects::EffectChain;
+use arcane_core::audio::meter::{BusWindow, LevelMeters, MeterSource, MeterTap};
+use arcane_core::audio::doppler::{doppler_shift, SPEED_OF_SOUND};
+use arcane_core::audio::occlusion::OPEN_CUTOFF_HZ;
+use arcane_core::audio::{
+    Attenuation, AudioBus, AudioCommand, AudioLevels, BusEffect, Doppler, DopplerParams, EffectSettings, Fade, Occlusion,
+    OcclusionParams, SpatialParams,
+};
+use arcane_core::curve::{Curve, Easing};
 
 #[test]
 fn test_audio_bus_from_u32() {
@@ -218,15 +229,17 @@ fn test_audio_command_update_spatial_positions() {
         updates: vec![(100, 10.0, 20.0), (200, 30.0, 40.0)],
         listener_x: 5.0,
         listener_y: 15.0,
+        occluded: vec![200],
     };
 
     match cmd {
-        AudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y } => {
+        AudioCommand::UpdateSpatialPositions { updates, listener_x, listener_y, occluded } => {
             assert_eq!(updates.len(), 2);
             assert_eq!(updates[0], (100, 10.0, 20.0));
             assert_eq!(updates[1], (200, 30.0, 40.0));
             assert!((listener_x - 5.0).abs() < 0.001);
             assert!((listener_y - 15.0).abs() < 0.001);
+            assert_eq!(occluded, [200]);
         }
         _ => panic!("Wrong variant"),
     }
@@ -257,3 +270,307 @@ fn test_bus_volume_array_indexing() {
     assert_eq!(bus_volumes[AudioBus::Ambient as usize], 0.6);
     assert_eq!(bus_volumes[AudioBus::Voice as usize], 0.4);
 }
+
+fn params(curve: Attenuation, rolloff: f32) -> SpatialParams {
+    SpatialParams { curve, min_distance: 100.0, max_distance: 1000.0, rolloff }
+}
+
+#[test]
+fn test_attenuation_from_u32() {
+    assert_eq!(Attenuation::from_u32(0), Some(Attenuation::Linear));
+    assert_eq!(Attenuation::from_u32(1), Some(Attenuation::Inverse));
+    assert_eq!(Attenuation::from_u32(2), Some(Attenuation::Exponential));
+    assert_eq!(Attenuation::from_u32(3), None);
+}
+
+#[test]
+fn test_spatial_gain_full_inside_min_and_silent_past_max() {
+    for curve in [Attenuation::Linear, Attenuation::Inverse, Attenuation::Exponential] {
+        let p = params(curve, 1.0);
+        assert_eq!(p.gain(0.0), 1.0);
+        assert_eq!(p.gain(100.0), 1.0);
+        assert_eq!(p.gain(1000.1), 0.0);
+        // Monotonic falloff in between
+        assert!(p.gain(300.0) < 1.0);
+        assert!(p.gain(600.0) < p.gain(300.0));
+    }
+}
+
+#[test]
+fn test_spatial_gain_curves() {
+    let linear = params(Attenuation::Linear, 1.0);
+    assert!((linear.gain(550.0) - 0.5).abs() < 1e-6);
+    assert!(linear.gain(1000.0).abs() < 1e-6);
+
+    // min / (min + rolloff * (d - min)) = 100 / (100 + 100)
+    let inverse = params(Attenuation::Inverse, 1.0);
+    assert!((inverse.gain(200.0) - 0.5).abs() < 1e-6);
+
+    // (d / min) ^ -rolloff = 2 ^ -2
+    let exponential = params(Attenuation::Exponential, 2.0);
+    assert!((exponential.gain(200.0) - 0.25).abs() < 1e-6);
+
+    // Zero rolloff: no falloff until the cutoff
+    let flat = params(Attenuation::Inverse, 0.0);
+    assert_eq!(flat.gain(900.0), 1.0);
+}
+
+#[test]
+fn test_spatial_gain_zero_min_distance() {
+    let p = SpatialParams { curve: Attenuation::Inverse, min_distance: 0.0, max_distance: 500.0, rolloff: 0.01 };
+    assert_eq!(p.gain(0.0), 1.0);
+    assert!((p.gain(100.0) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_audio_command_set_spatial_params() {
+    let cmd = AudioCommand::SetSpatialParams {
+        instance_id: 42,
+        params: params(Attenuation::Linear, 1.0),
+    };
+    match cmd {
+        AudioCommand::SetSpatialParams { instance_id, params } => {
+            assert_eq!(instance_id, 42);
+            assert_eq!(params.curve, Attenuation::Linear);
+            assert_eq!(params.max_distance, 1000.0);
+        }
+        _ => panic!("Wrong variant"),
+    }
+}
+
+#[test]
+fn test_fade_ramps_linearly() {
+    let mut fade = Fade::new(1.0, 0.0, 2.0);
+    assert_eq!(fade.gain(), 1.0);
+    assert!((fade.advance(0.5) - 0.75).abs() < 1e-6);
+    assert!((fade.advance(1.0) - 0.25).abs() < 1e-6);
+    assert!(!fade.is_done());
+
+    // Overshooting the duration clamps to the target
+    assert_eq!(fade.advance(5.0), 0.0);
+    assert!(fade.is_done());
+}
+
+#[test]
+fn test_fade_follows_its_curve() {
+    let mut fade = Fade::new(0.0, 2.0, 1.0).with_curve(Curve::Easing(Easing::InQuad));
+    assert!((fade.advance(0.5) - 0.5).abs() < 1e-6);
+    assert_eq!(fade.advance(0.5), 2.0);
+}
+
+#[test]
+fn test_fade_zero_duration_is_immediate() {
+    let fade = Fade::new(0.0, 1.0, 0.0);
+    assert!(fade.is_done());
+    assert_eq!(fade.gain(), 1.0);
+
+    // Negative durations are treated as zero
+    assert!(Fade::new(0.0, 1.0, -1.0).is_done());
+}
+
+#[test]
+fn test_occlusion_eases_toward_the_raycast_result() {
+    let mut occlusion = Occlusion::new(OcclusionParams { cutoff_hz: 500.0, volume: 0.4, smoothing: 0.2 });
+    assert_eq!((occlusion.gain(), occlusion.cutoff_hz()), (1.0, 0.0));
+
+    occlusion.set_occluded(true);
+    assert!(!occlusion.is_settled());
+    occlusion.advance(0.1);
+    assert!((occlusion.amount - 0.5).abs() < 1e-6);
+    assert!((occlusion.gain() - 0.7).abs() < 1e-6);
+    // Halfway in amount is halfway in pitch between open and closed
+    assert!((occlusion.cutoff_hz() - (OPEN_CUTOFF_HZ * 500.0).sqrt()).abs() < 1.0);
+
+    occlusion.advance(1.0);
+    assert!(occlusion.is_settled());
+    assert!((occlusion.gain() - 0.4).abs() < 1e-6);
+    assert!((occlusion.cutoff_hz() - 500.0).abs() < 0.5);
+
+    // Clearing the line of sight eases back the same way
+    occlusion.set_occluded(false);
+    occlusion.advance(0.05);
+    assert!((occlusion.amount - 0.75).abs() < 1e-6);
+}
+
+#[test]
+fn test_occlusion_without_smoothing_switches_at_once() {
+    let mut occlusion = Occlusion::new(OcclusionParams { cutoff_hz: 800.0, volume: 0.5, smoothing: 0.0 });
+    occlusion.set_occluded(true);
+    assert!(occlusion.is_settled());
+    assert_eq!(occlusion.gain(), 0.5);
+    occlusion.set_occluded(false);
+    assert_eq!((occlusion.amount, occlusion.cutoff_hz()), (0.0, 0.0));
+}
+
+const DOPPLER: DopplerParams = DopplerParams { factor: 1.0, min_pitch: 0.5, max_pitch: 2.0 };
+
+#[test]
+fn test_doppler_shift_rises_while_closing_in() {
+    let c = SPEED_OF_SOUND;
+    // Source at the origin racing toward a listener on the right at a tenth of c
+    let approaching = doppler_shift([0.0, 0.0], [c / 10.0, 0.0], [100.0, 0.0], [0.0, 0.0], &DOPPLER);
+    assert!((approaching - 1.0 / 0.9).abs() < 1e-5);
+    let receding = doppler_shift([0.0, 0.0], [-c / 10.0, 0.0], [100.0, 0.0], [0.0, 0.0], &DOPPLER);
+    assert!((receding - 1.0 / 1.1).abs() < 1e-5);
+    // A listener moving toward the source
+    let listener = doppler_shift([0.0, 0.0], [0.0, 0.0], [100.0, 0.0], [-c / 10.0, 0.0], &DOPPLER);
+    assert!((listener - 1.1).abs() < 1e-5);
+    // Sideways motion doesn't shift
+    assert_eq!(doppler_shift([0.0, 0.0], [0.0, 500.0], [100.0, 0.0], [0.0, 0.0], &DOPPLER), 1.0);
+}
+
+#[test]
+fn test_doppler_shift_is_scaled_and_clamped() {
+    let c = SPEED_OF_SOUND;
+    let doubled = DopplerParams { factor: 2.0, ..DOPPLER };
+    let shift = doppler_shift([0.0, 0.0], [c / 20.0, 0.0], [100.0, 0.0], [0.0, 0.0], &doubled);
+    assert!((shift - 1.0 / 0.9).abs() < 1e-5);
+    // Supersonic sources stay finite and within the pitch range
+    assert_eq!(doppler_shift([0.0, 0.0], [c * 5.0, 0.0], [100.0, 0.0], [0.0, 0.0], &DOPPLER), 2.0);
+    let receding = doppler_shift([0.0, 0.0], [-c * 5.0, 0.0], [100.0, 0.0], [0.0, 0.0], &DOPPLER);
+    assert!((receding - 1.0 / 1.9).abs() < 1e-5);
+    let narrow = DopplerParams { min_pitch: 0.8, max_pitch: 1.2, ..DOPPLER };
+    assert_eq!(doppler_shift([0.0, 0.0], [-c * 5.0, 0.0], [100.0, 0.0], [0.0, 0.0], &narrow), 0.8);
+}
+
+#[test]
+fn test_doppler_velocity_from_position_updates() {
+    let mut doppler = Doppler::new(DOPPLER);
+    // 100 px in 0.1 s toward the listener: 1000 px/s, smoothed to half the first time
+    let shift = doppler.update([0.0, 0.0], [100.0, 0.0], [1000.0, 0.0], [1000.0, 0.0], 0.1);
+    assert_eq!(doppler.source_vel, [500.0, 0.0]);
+    assert!((shift - SPEED_OF_SOUND / (SPEED_OF_SOUND - 500.0)).abs() < 1e-5);
+    doppler.update([100.0, 0.0], [200.0, 0.0], [1000.0, 0.0], [1000.0, 0.0], 0.1);
+    assert_eq!(doppler.source_vel, [750.0, 0.0]);
+
+    // Back-to-back updates keep the estimate; a long gap resets it
+    doppler.update([200.0, 0.0], [200.0, 0.0], [1000.0, 0.0], [1000.0, 0.0], 0.0);
+    assert_eq!(doppler.source_vel, [750.0, 0.0]);
+    assert_eq!(doppler.update([200.0, 0.0], [900.0, 0.0], [1000.0, 0.0], [1000.0, 0.0], 2.0), 1.0);
+    assert_eq!(doppler.source_vel, [0.0, 0.0]);
+}
+
+#[test]
+fn test_audio_command_crossfade() {
+    let cmd = AudioCommand::CrossfadeTo { from_instance: 1, to_instance: 2, duration: 1.5, curve: Curve::default() };
+    match cmd {
+        AudioCommand::CrossfadeTo { from_instance, to_instance, duration, .. } => {
+            assert_eq!(from_instance, 1);
+            assert_eq!(to_instance, 2);
+            assert_eq!(duration, 1.5);
+        }
+        _ => panic!("Wrong variant"),
+    }
+}
+
+#[test]
+fn test_bus_effect_from_parts() {
+    assert_eq!(BusEffect::from_parts(0, [800.0, 0.0, 0.0]), Some(BusEffect::LowPass { cutoff_hz: 800.0 }));
+    assert_eq!(
+        BusEffect::from_parts(1, [0.5, 250.0, 0.4]),
+        Some(BusEffect::Delay { mix: 0.5, delay_ms: 250.0, feedback: 0.4 })
+    );
+    assert_eq!(
+        BusEffect::from_parts(2, [0.3, 40.0, 0.7]),
+        Some(BusEffect::Reverb { mix: 0.3, delay_ms: 40.0, decay: 0.7 })
+    );
+    assert_eq!(BusEffect::from_parts(3, [0.0; 3]), None);
+}
+
+#[test]
+fn test_effect_chain_bypassed_by_default() {
+    let mut chain = EffectChain::new(EffectSettings::default(), 1000, 2);
+    for x in [0.5, -0.25, 1.0, 0.0] {
+        assert_eq!(chain.process(x), x);
+    }
+    assert_eq!(chain.tail_samples(), 0);
+}
+
+#[test]
+fn test_effect_chain_low_pass_smooths_steps() {
+    let settings = EffectSettings::with(BusEffect::LowPass { cutoff_hz: 50.0 });
+    let mut chain = EffectChain::new(settings, 1000, 1);
+    let first = chain.process(1.0);
+    assert!(first > 0.0 && first < 0.5);
+    let mut last = first;
+    for _ in 0..100 {
+        let y = chain.process(1.0);
+        assert!(y >= last);
+        last = y;
+    }
+    assert!(last > 0.99);
+}
+
+#[test]
+fn test_effect_chain_delay_echoes_per_channel() {
+    // 10 ms at 1 kHz = 10 frames; stereo interleaves to 20 samples
+    let settings = EffectSettings::with(BusEffect::Delay { mix: 0.5, delay_ms: 10.0, feedback: 0.5 });
+    let mut chain = EffectChain::new(settings, 1000, 2);
+    let mut out = vec![chain.process(1.0), chain.process(0.0)];
+    for _ in 0..40 {
+        out.push(chain.process(0.0));
+    }
+    assert_eq!(out[0], 0.5);
+    assert_eq!(out[20], 0.5); // first echo, left channel only
+    assert_eq!(out[21], 0.0);
+    assert_eq!(out[40], 0.25); // second echo at half the level
+    assert!(chain.tail_samples() > 40);
+}
+
+#[test]
+fn test_effect_chain_reverb_tail_decays() {
+    let settings = EffectSettings::with(BusEffect::Reverb { mix: 1.0, delay_ms: 20.0, decay: 0.6 });
+    let mut chain = EffectChain::new(settings, 1000, 1);
+    chain.process(1.0);
+    let tail = chain.tail_samples();
+    let energy = |chain: &mut EffectChain, n: usize| (0..n).map(|_| chain.process(0.0).abs()).sum::<f32>();
+    let early = energy(&mut chain, 100);
+    assert!(early > 0.1);
+    energy(&mut chain, tail);
+    assert!(energy(&mut chain, 100) < early * 0.01);
+}
+
+#[test]
+fn test_meter_source_measures_output_level() {
+    let meters = Arc::new(LevelMeters::default());
+    let tap = MeterTap { meters: meters.clone(), bus: AudioBus::Voice as usize, gain: Arc::new(AtomicU32::new(0.5f32.to_bits())) };
+    // One second of a +/-0.5 square wave, mono at 1 kHz
+    let samples: Vec<f32> = (0..1000).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }).collect();
+    let source = MeterSource::new(rodio::buffer::SamplesBuffer::new(1, 1000, samples.clone()), tap);
+    assert_eq!(source.collect::<Vec<f32>>(), samples); // passes audio through untouched
+
+    let window = meters.drain();
+    assert_eq!(window[AudioBus::Sfx as usize], BusWindow::default());
+    let voice = window[AudioBus::Voice as usize];
+    assert!((voice.energy - 0.0625).abs() < 1e-6); // (0.5 * 0.5)^2 for one second
+    assert!((voice.peak - 0.25).abs() < 1e-6);
+    assert_eq!(meters.drain()[AudioBus::Voice as usize], BusWindow::default());
+}
+
+#[test]
+fn test_audio_levels_smooth_and_release() {
+    let mut levels = AudioLevels::default();
+    let mut window = [BusWindow::default(); 4];
+    window[AudioBus::Music as usize] = BusWindow { energy: 0.25 * 0.01, peak: 0.8 };
+    window[AudioBus::Voice as usize] = BusWindow { energy: 0.25 * 0.01, peak: 0.6 };
+    for _ in 0..200 {
+        levels.update(&window, 0.01);
+    }
+    let music = levels.buses[AudioBus::Music as usize];
+    assert!((music.rms - 0.5).abs() < 1e-3);
+    assert_eq!(music.peak, 0.8);
+    // Two uncorrelated buses at 0.5 RMS sum to sqrt(0.5) on the master
+    assert!((levels.master.rms - 0.5f32.sqrt()).abs() < 1e-3);
+    assert_eq!(levels.master.peak, 0.8);
+
+    let array = levels.to_array();
+    assert_eq!(array[0], levels.master.rms);
+    assert_eq!(array[1 + 2 * 2], music.peak);
+
+    // Silence: levels fall back to zero
+    for _ in 0..300 {
+        levels.update(&[BusWindow::default(); 4], 0.01);
+    }
+    assert!(!levels.is_audible());
+    assert_eq!(levels.to_array(), [0.0; 10]);
+}
@@ -2,10 +2,14 @@
 
 use arcane_core::physics::broadphase::SpatialHash;
 use arcane_core::physics::integrate::integrate;
-use arcane_core::physics::narrowphase::test_collision;
-use arcane_core::physics::sleep::update_sleep;
+use arcane_core::physics::narrowphase::{test_collision, test_collision_manifold};
+use arcane_core::physics::projectile::{Projectile, ProjectileBatchDef};
+use arcane_core::physics::rig::{Rig, RigJointKind};
+use arcane_core::physics::sleep::{update_sleep, SleepConfig};
+use arcane_core::physics::sprite_collider::{colliders_from_rgba, pieces_json};
 use arcane_core::physics::types::*;
-use arcane_core::physics::world::PhysicsWorld;
+use arcane_core::physics::vehicle::{VehicleDef, VehicleInput, VehicleView, WheelDef};
+use arcane_core::physics::world::{PhysicsConfig, PhysicsWorld};
 
 fn make_body(id: BodyId, body_type: BodyType, shape: Shape, x: f32, y: f32, mass: f32) -> RigidBody {
     let (inv_mass, inertia, inv_inertia) = compute_mass_and_inertia(&shape, mass, body_type);
@@ -27,6 +31,15 @@ fn make_body(id: BodyId, body_type: BodyType, shape: Shape, x: f32, y: f32, mass
         inv_mass,
         inertia,
         inv_inertia,
+        linear_damping: 0.0,
+        angular_damping: 0.0,
+        lock_rotation: false,
+        lock_x: false,
+        lock_y: false,
+        max_speed: 0.0,
+        max_angular_speed: 0.0,
+        is_sensor: false,
+        one_way: None,
         layer: 0xFFFF,
         mask: 0xFFFF,
         sleeping: false,
@@ -192,6 +205,51 @@ fn test_angular_velocity_updates_angle() {
     assert!((body.angle - std::f32::consts::PI).abs() < 1e-4);
 }
 
+#[test]
+fn test_linear_and_angular_damping_slow_body() {
+    let mut body = make_body(0, BodyType::Dynamic, Shape::Circle { radius: 1.0 }, 0.0, 0.0, 1.0);
+    body.vx = 100.0;
+    body.angular_velocity = 10.0;
+    body.linear_damping = 2.0;
+    body.angular_damping = 1.0;
+    for _ in 0..60 {
+        integrate(&mut body, 0.0, 0.0, 1.0 / 60.0);
+    }
+    // 1 / (1 + dt·c)^60 ≈ e^-c for one second
+    assert!((body.vx - 100.0 * (-2.0f32).exp()).abs() < 1.0, "vx={}", body.vx);
+    assert!((body.angular_velocity - 10.0 * (-1.0f32).exp()).abs() < 0.1, "av={}", body.angular_velocity);
+    assert!(body.vx > 0.0, "damping must never reverse velocity");
+}
+
+#[test]
+fn test_damping_ignores_kinematic_bodies() {
+    let mut body = make_body(0, BodyType::Kinematic, Shape::Circle { radius: 1.0 }, 0.0, 0.0, 1.0);
+    body.vx = 50.0;
+    body.linear_damping = 10.0;
+    integrate(&mut body, 0.0, 0.0, 1.0 / 60.0);
+    assert_eq!(body.vx, 50.0);
+}
+
+#[test]
+fn test_velocity_limits_clamp_speed_and_zero_locked_axes() {
+    let mut body = make_body(0, BodyType::Dynamic, Shape::Circle { radius: 1.0 }, 0.0, 0.0, 1.0);
+    body.vx = 300.0;
+    body.vy = 400.0;
+    body.angular_velocity = -20.0;
+    body.max_speed = 100.0;
+    body.max_angular_speed = 5.0;
+    integrate(&mut body, 0.0, 0.0, 1.0 / 60.0);
+    // Direction is preserved, magnitude capped
+    assert!((body.vx - 60.0).abs() < 1e-3 && (body.vy - 80.0).abs() < 1e-3, "v=({}, {})", body.vx, body.vy);
+    assert_eq!(body.angular_velocity, -5.0);
+
+    body.lock_y = true;
+    body.lock_rotation = true;
+    integrate(&mut body, 0.0, 500.0, 1.0 / 60.0);
+    assert_eq!((body.vy, body.angular_velocity), (0.0, 0.0));
+    assert!(body.vx > 0.0);
+}
+
 // =========================================================================
 // Broadphase
 // =========================================================================
@@ -549,6 +607,8 @@ fn test_sleeping_body_wakes_on_contact_with_awake_body() {
         accumulated_jt: 0.0,
         velocity_bias: 0.0,
         tangent: (0.0, 0.0),
+        impulse: 0.0,
+        relative_velocity: (0.0, 0.0),
     }];
 
     update_sleep(&mut bodies, &contacts, 0.016);
@@ -790,6 +850,111 @@ fn test_world_set_angular_velocity() {
     assert_eq!(body.angular_velocity, 2.0);
 }
 
+#[test]
+fn test_world_damped_body_comes_to_rest_and_sleeps() {
+    // Top-down: no gravity, a pushed body should glide to a stop and sleep
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let damped = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let free = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 100.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_damping(damped, 5.0, 5.0);
+    world.set_velocity(damped, 200.0, 0.0);
+    world.set_velocity(free, 200.0, 0.0);
+
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+    }
+    let d = world.get_body(damped).unwrap();
+    assert!(d.sleeping, "damped body should sleep once nearly still (vx={})", d.vx);
+    // Glide distance is bounded: v0 / c = 40
+    assert!(d.x > 30.0 && d.x < 45.0, "x={}", d.x);
+    let f = world.get_body(free).unwrap();
+    assert!(!f.sleeping && (f.vx - 200.0).abs() < 1e-3);
+
+    world.set_damping(damped, -1.0, -2.0);
+    let d = world.get_body(damped).unwrap();
+    assert_eq!((d.linear_damping, d.angular_damping), (0.0, 0.0));
+}
+
+#[test]
+fn test_world_y_locked_body_ignores_gravity_and_slides_along_lane() {
+    let mut world = PhysicsWorld::new(0.0, 500.0);
+    let runner = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // A wall in the lane: the runner hits it without ever leaving y = 0
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 50.0 },
+        100.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_locks(runner, false, false, true);
+    world.set_velocity(runner, 120.0, 0.0);
+
+    let mut max_x = f32::MIN;
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+        let b = world.get_body(runner).unwrap();
+        assert_eq!((b.y, b.vy), (0.0, 0.0));
+        max_x = max_x.max(b.x);
+    }
+    assert!(max_x > 80.0 && max_x < 91.0, "runner should reach but not pass the wall, max x={}", max_x);
+}
+
+#[test]
+fn test_world_rotation_lock_keeps_box_upright() {
+    let box_shape = Shape::Polygon {
+        vertices: vec![(-10.0, -10.0), (10.0, -10.0), (10.0, 10.0), (-10.0, 10.0)],
+    };
+    let mut results = Vec::new();
+    for lock in [false, true] {
+        let mut world = PhysicsWorld::new(0.0, 400.0);
+        // Box lands with its corner on a post, which tips it over unless locked
+        let b = world.add_body(
+            BodyType::Dynamic, box_shape.clone(),
+            8.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+        );
+        world.add_body(
+            BodyType::Static, Shape::Circle { radius: 4.0 },
+            0.0, 30.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+        );
+        world.set_locks(b, lock, false, false);
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+        results.push(world.get_body(b).unwrap().angle);
+    }
+    assert!(results[0].abs() > 0.05, "unlocked box should tip, angle={}", results[0]);
+    assert_eq!(results[1], 0.0);
+}
+
+#[test]
+fn test_world_unlocking_rotation_restores_inertia_and_max_speed_clamps() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let id = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        0.0, 0.0, 4.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_angular_velocity(id, 3.0);
+    world.set_locks(id, true, false, false);
+    let b = world.get_body(id).unwrap();
+    assert_eq!((b.inv_inertia, b.angular_velocity), (0.0, 0.0));
+
+    world.set_locks(id, false, false, false);
+    assert!((world.get_body(id).unwrap().inv_inertia - 0.125).abs() < 1e-6);
+
+    world.set_max_speed(id, 50.0, 0.0);
+    world.apply_impulse(id, 4000.0, 0.0);
+    world.step(1.0 / 60.0);
+    let b = world.get_body(id).unwrap();
+    assert!((b.vx - 50.0).abs() < 1e-3, "vx={}", b.vx);
+}
+
 // =========================================================================
 // Query AABB
 // =========================================================================
@@ -839,6 +1004,263 @@ fn test_query_aabb_empty_region() {
     assert!(results.is_empty());
 }
 
+#[test]
+fn test_queries_match_a_full_scan_between_steps() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let mut ids = Vec::new();
+    for i in 0..60 {
+        let (x, y) = ((i % 10) as f32 * 47.0 - 100.0, (i / 10) as f32 * 53.0 - 80.0);
+        let shape = if i % 2 == 0 { Shape::Circle { radius: 6.0 } } else { Shape::AABB { half_w: 9.0, half_h: 4.0 } };
+        ids.push(world.add_body(BodyType::Static, shape, x, y, 0.0, Material::default(), 0xFFFF, 0xFFFF));
+    }
+    // A floor far bigger than a grid cell
+    let floor = world.add_body(BodyType::Static, Shape::AABB { half_w: 5000.0, half_h: 5.0 }, 0.0, 400.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    let scan = |world: &PhysicsWorld, rect: (f32, f32, f32, f32)| -> Vec<BodyId> {
+        let mut hits: Vec<BodyId> = world
+            .all_bodies()
+            .into_iter()
+            .filter(|b| {
+                let (x0, y0, x1, y1) = get_shape_aabb(b);
+                x1 >= rect.0 && x0 <= rect.2 && y1 >= rect.1 && y0 <= rect.3
+            })
+            .map(|b| b.id)
+            .collect();
+        hits.sort_unstable();
+        hits
+    };
+    let rects = [(-120.0, -90.0, 20.0, 30.0), (0.0, 0.0, 500.0, 500.0), (-1e6, -1e6, 1e6, 1e6), (1000.0, 390.0, 1001.0, 391.0)];
+
+    world.step(1.0 / 60.0);
+    for rect in rects {
+        assert_eq!(world.query_aabb(rect.0, rect.1, rect.2, rect.3), scan(&world, rect));
+    }
+    assert_eq!(world.query_aabb(999.0, 399.0, 1000.0, 400.0), vec![floor]);
+
+    // Bodies moved, added and removed from game code are found before the next step
+    world.set_position(ids[0], 900.0, -700.0);
+    world.remove_body(ids[1]);
+    let added = world.add_body(BodyType::Static, Shape::Circle { radius: 3.0 }, -2000.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    assert_eq!(world.query_aabb(890.0, -710.0, 910.0, -690.0), vec![ids[0]]);
+    assert_eq!(world.query_aabb(-2001.0, -1.0, -1999.0, 1.0), vec![added]);
+    for rect in rects {
+        assert_eq!(world.query_aabb(rect.0, rect.1, rect.2, rect.3), scan(&world, rect));
+    }
+    assert_eq!(world.raycast(-2100.0, 0.0, 1.0, 0.0, 1000.0).map(|h| h.0), Some(added));
+    assert_eq!(world.overlap_circle(900.0, -700.0, 1.0, 0xFFFF).len(), 1);
+}
+
+#[test]
+fn test_grid_raycast_finds_the_nearest_hit_in_any_direction() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let mut targets = Vec::new();
+    for i in 0..16 {
+        let angle = i as f32 * std::f32::consts::TAU / 16.0;
+        let r = 150.0 + 37.0 * i as f32;
+        let id = world.add_body(BodyType::Static, Shape::Circle { radius: 5.0 }, angle.cos() * r, angle.sin() * r, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+        targets.push((id, angle, r));
+    }
+    // Two bodies at the same distance: the lower ID wins, as with a scan
+    let near = world.add_body(BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 20.0 }, -150.0, -300.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    world.add_body(BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 20.0 }, -150.0, -300.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    world.step(1.0 / 60.0);
+
+    for &(id, angle, r) in &targets {
+        let hit = world.raycast(0.0, 0.0, angle.cos(), angle.sin(), 5000.0).unwrap();
+        assert_eq!(hit.0, id);
+        assert!((hit.3 - (r - 5.0)).abs() < 0.01, "distance {} for r {}", hit.3, r);
+        // From outside the occupied area, back toward the origin
+        let (ox, oy) = (angle.cos() * 3000.0, angle.sin() * 3000.0);
+        let back = world.raycast(ox, oy, -angle.cos(), -angle.sin(), 1e9).unwrap();
+        assert_eq!(back.0, id);
+    }
+    assert_eq!(world.raycast(-300.0, -300.0, 1.0, 0.0, 500.0).map(|h| h.0), Some(near));
+    assert!(world.raycast(0.0, 0.0, 1.0, 0.0, 100.0).is_none());
+}
+
+#[test]
+fn test_query_stats_count_the_last_frame() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    world.add_body(BodyType::Static, Shape::Circle { radius: 5.0 }, 0.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    for i in 0..50 {
+        world.add_body(BodyType::Static, Shape::Circle { radius: 5.0 }, 1000.0 + i as f32 * 20.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF);
+    }
+    // Before the first step nothing is indexed: queries scan every body
+    world.query_aabb(-1.0, -1.0, 1.0, 1.0);
+    world.step(1.0 / 60.0);
+    let stats = world.query_stats();
+    assert_eq!((stats.aabb, stats.full_scans, stats.candidates), (1, 1, 51));
+
+    world.query_aabb(-1.0, -1.0, 1.0, 1.0);
+    world.overlap_circle(0.0, 0.0, 2.0, 0xFFFF);
+    world.raycast(-50.0, 0.0, 1.0, 0.0, 100.0);
+    world.step(1.0 / 60.0);
+    let stats = world.query_stats();
+    assert_eq!((stats.aabb, stats.overlap, stats.raycast, stats.total()), (1, 1, 1, 3));
+    assert_eq!(stats.full_scans, 0);
+    assert_eq!(stats.candidates, 3);
+}
+
+// =========================================================================
+// Overlap queries
+// =========================================================================
+
+#[test]
+fn test_overlap_circle_finds_bodies_with_penetration() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let near = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        12.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let _far = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 5.0 },
+        100.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+
+    let hits = world.overlap_circle(0.0, 0.0, 10.0, 0xFFFF);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].body_id, near);
+    // Normal points from the query toward the body
+    assert!(hits[0].normal.0 > 0.99, "normal {:?}", hits[0].normal);
+    assert!((hits[0].penetration - 3.0).abs() < 1e-3, "penetration {}", hits[0].penetration);
+}
+
+#[test]
+fn test_overlap_queries_respect_layer_mask() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let enemy = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 4.0 },
+        0.0, 0.0, 1.0, Material::default(), 0x0002, 0xFFFF,
+    );
+    let _wall = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 4.0, half_h: 4.0 },
+        2.0, 0.0, 0.0, Material::default(), 0x0001, 0xFFFF,
+    );
+
+    let hits = world.overlap_box(0.0, 0.0, 5.0, 5.0, 0.0, 0x0002);
+    assert_eq!(hits.iter().map(|h| h.body_id).collect::<Vec<_>>(), vec![enemy]);
+    assert_eq!(world.overlap_circle(0.0, 0.0, 5.0, 0xFFFF).len(), 2);
+    assert!(world.overlap_circle(0.0, 0.0, 5.0, 0x0004).is_empty());
+}
+
+#[test]
+fn test_overlap_box_is_oriented() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    // Small body above a wide, flat 20×4 query box
+    let id = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 1.0 },
+        0.0, 10.5, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    assert!(world.overlap_box(0.0, 0.0, 10.0, 2.0, 0.0, 0xFFFF).is_empty());
+    // Rotated 90°, the long side now reaches it
+    let hits = world.overlap_box(0.0, 0.0, 10.0, 2.0, std::f32::consts::FRAC_PI_2, 0xFFFF);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].body_id, id);
+}
+
+#[test]
+fn test_overlap_polygon_uses_local_vertices() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let inside = world.add_body(
+        BodyType::Dynamic, Shape::AABB { half_w: 1.0, half_h: 1.0 },
+        52.0, 52.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let _outside = world.add_body(
+        BodyType::Dynamic, Shape::AABB { half_w: 1.0, half_h: 1.0 },
+        58.0, 58.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Right triangle with legs of 10 placed at (50, 50)
+    let triangle = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)];
+    let hits = world.overlap_polygon(50.0, 50.0, &triangle, 0.0, 0xFFFF);
+    assert_eq!(hits.iter().map(|h| h.body_id).collect::<Vec<_>>(), vec![inside]);
+    assert!(world.overlap_polygon(50.0, 50.0, &triangle[..2], 0.0, 0xFFFF).is_empty());
+}
+
+#[test]
+fn test_radial_impulse_pushes_away_with_falloff() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let near = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        20.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let far = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        0.0, -60.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let outside = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        200.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let wall = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 5.0 },
+        -10.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+
+    let affected = world.apply_radial_impulse(0.0, 0.0, 100.0, 100.0, 1.0, false, 0xFFFF);
+    assert_eq!(affected, vec![near, far]);
+
+    let n = world.get_body(near).unwrap();
+    let f = world.get_body(far).unwrap();
+    // Linear falloff from each body's closest point:
+    // 100 * (1 - 18/100) = 82 along +x, 100 * (1 - 58/100) = 42 along -y
+    assert!((n.vx - 82.0).abs() < 1e-3 && n.vy.abs() < 1e-3, "near v=({}, {})", n.vx, n.vy);
+    assert!((f.vy + 42.0).abs() < 1e-3 && f.vx.abs() < 1e-3, "far v=({}, {})", f.vx, f.vy);
+    assert_eq!(world.get_body(outside).unwrap().vx, 0.0);
+    assert_eq!(world.get_body(wall).unwrap().vx, 0.0);
+}
+
+#[test]
+fn test_radial_impulse_pushes_large_bodies_centered_outside() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    // Center 120 away, but the near edge is 70 from the blast
+    let slab = world.add_body(
+        BodyType::Dynamic, Shape::AABB { half_w: 50.0, half_h: 10.0 },
+        120.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Circle edge exactly on the rim: overlaps but gets no push
+    let grazing = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        0.0, 102.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+
+    let affected = world.apply_radial_impulse(0.0, 0.0, 100.0, 100.0, 1.0, false, 0xFFFF);
+    assert_eq!(affected, vec![slab]);
+    let s = world.get_body(slab).unwrap();
+    // 100 * (1 - 70/100) = 30, spread over the slab's mass
+    assert!(s.vx > 0.0 && s.vy.abs() < 1e-3, "slab v=({}, {})", s.vx, s.vy);
+    assert!((s.vx * s.mass - 30.0).abs() < 1e-2, "slab impulse={}", s.vx * s.mass);
+    assert_eq!(world.get_body(grazing).unwrap().vy, 0.0);
+}
+
+#[test]
+fn test_radial_impulse_occlusion_and_wake() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let hidden = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        40.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let exposed = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+        -40.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Wall between the blast and `hidden`
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 2.0, half_h: 20.0 },
+        20.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.get_body_mut(exposed).unwrap().sleeping = true;
+
+    let affected = world.apply_radial_impulse(0.0, 0.0, 100.0, 50.0, 0.0, true, 0xFFFF);
+    assert_eq!(affected, vec![exposed]);
+    let e = world.get_body(exposed).unwrap();
+    assert!(!e.sleeping, "blast should wake sleeping bodies");
+    assert!((e.vx + 50.0).abs() < 1e-3, "constant falloff: vx={}", e.vx);
+    assert_eq!(world.get_body(hidden).unwrap().vx, 0.0);
+
+    // Without occlusion the wall is ignored
+    let affected = world.apply_radial_impulse(0.0, 0.0, 100.0, 50.0, 0.0, false, 0xFFFF);
+    assert_eq!(affected, vec![hidden, exposed]);
+}
+
 // =========================================================================
 // Raycast
 // =========================================================================
@@ -948,6 +1370,31 @@ fn test_raycast_closest_body() {
     assert_eq!(result.unwrap().0, near);
 }
 
+#[test]
+fn test_segment_blocked_by_layer() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    // A wall on layer 2 between x = 9 and x = 11
+    world.add_body(
+        BodyType::Static,
+        Shape::AABB { half_w: 1.0, half_h: 10.0 },
+        10.0,
+        0.0,
+        0.0,
+        Material::default(),
+        0x0002,
+        0xFFFF,
+    );
+    assert!(world.segment_blocked_by(0.0, 0.0, 20.0, 0.0, 0x0002));
+    assert!(world.segment_blocked_by(20.0, 0.0, 0.0, 0.0, 0xFFFF));
+    // Other layers see through it
+    assert!(!world.segment_blocked_by(0.0, 0.0, 20.0, 0.0, 0x0001));
+    // Segments ending short of it or passing beside it are clear
+    assert!(!world.segment_blocked_by(0.0, 0.0, 8.0, 0.0, 0x0002));
+    assert!(!world.segment_blocked_by(0.0, 20.0, 20.0, 20.0, 0x0002));
+    // A zero-length segment is never blocked
+    assert!(!world.segment_blocked_by(0.0, 0.0, 0.0, 0.0, 0xFFFF));
+}
+
 // =========================================================================
 // Constraints
 // =========================================================================
@@ -1026,7 +1473,7 @@ fn test_revolute_constraint() {
     );
     // Body A at (0,0), body B at (5,0), pivot at (2.5, 0)
     // Local anchors: A: (2.5, 0), B: (-2.5, 0)
-    let cid = world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0),
+    let cid = world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0), motor: None,
         id: 0,
         body_a: a,
         body_b: b,
@@ -1075,90 +1522,448 @@ fn test_remove_constraint() {
     world.step(1.0 / 60.0);
 }
 
-// =========================================================================
-// World with collisions
-// =========================================================================
+fn rope(body_a: BodyId, body_b: BodyId, max_length: f32) -> Constraint {
+    Constraint::Rope {
+        id: 0,
+        body_a,
+        body_b,
+        max_length,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        accumulated_impulse: 0.0,
+    }
+}
 
 #[test]
-fn test_ball_on_ground_contacts() {
-    let mut world = PhysicsWorld::new(0.0, 100.0);
-    // Ball starting just above ground
-    let _ball = world.add_body(
-        BodyType::Dynamic,
-        Shape::Circle { radius: 1.0 },
-        0.0,
-        -3.0,
-        1.0,
-        Material { restitution: 0.5, friction: 0.5 },
-        0xFFFF,
-        0xFFFF,
+fn test_rope_constraint_is_slack_until_taut() {
+    let mut world = PhysicsWorld::new(0.0, 200.0);
+    let anchor = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 1.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
     );
-    // Ground
-    let _ground = world.add_body(
-        BodyType::Static,
-        Shape::AABB { half_w: 100.0, half_h: 1.0 },
-        0.0,
-        0.0,
-        0.0,
-        Material { restitution: 0.5, friction: 0.5 },
-        0xFFFF,
-        0xFFFF,
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+        0.0, 5.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
     );
+    world.add_constraint(rope(anchor, ball, 30.0));
 
-    // Step a few frames to let ball fall
-    for _ in 0..10 {
+    // Free fall while slack: v = g·t
+    for _ in 0..12 {
         world.step(1.0 / 60.0);
     }
-    // Eventually there should be contacts
-    // Note: contacts are only from the last sub-step
-    let _contacts = world.get_contacts();
-    // The ball should have fallen and hit the ground by now
-    let ball = world.get_body(_ball).unwrap();
-    assert!(ball.y > -3.0, "Ball should have fallen");
-}
+    let b = world.get_body(ball).unwrap();
+    assert!((b.vy - 40.0).abs() < 1.0, "slack rope must not slow the fall, vy={}", b.vy);
 
-#[test]
-fn test_world_multiple_bodies() {
-    let mut world = PhysicsWorld::new(0.0, 0.0);
-    let ids: Vec<u32> = (0..10)
-        .map(|i| {
-            world.add_body(
-                BodyType::Dynamic,
-                Shape::Circle { radius: 1.0 },
-                i as f32 * 100.0,
-                0.0,
-                1.0,
-                Material::default(),
-                0xFFFF,
-                0xFFFF,
-            )
-        })
-        .collect();
-    assert_eq!(ids.len(), 10);
-    for (i, &id) in ids.iter().enumerate() {
-        let body = world.get_body(id).unwrap();
-        assert!((body.x - i as f32 * 100.0).abs() < 1e-6);
+    // Then it catches the ball at the rope length
+    let mut max_y = 0.0f32;
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+        max_y = max_y.max(world.get_body(ball).unwrap().y);
     }
+    let b = world.get_body(ball).unwrap();
+    assert!((b.y - 30.0).abs() < 1.0, "ball should hang at the rope length, y={}", b.y);
+    assert!(max_y < 31.5, "rope stretched to {}", max_y);
 }
 
 #[test]
-fn test_world_get_body_mut() {
+fn test_rope_constraint_never_pushes() {
     let mut world = PhysicsWorld::new(0.0, 0.0);
-    let id = world.add_body(
-        BodyType::Dynamic,
-        Shape::Circle { radius: 1.0 },
-        0.0,
-        0.0,
-        1.0,
-        Material::default(),
-        0xFFFF,
-        0xFFFF,
+    let a = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
     );
-    {
-        let body = world.get_body_mut(id).unwrap();
-        body.x = 42.0;
+    let b = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+        20.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.add_constraint(rope(a, b, 10.0));
+    // Starts stretched: the rope pulls the bodies together, then goes slack
+    world.step(1.0 / 60.0);
+    let (ax, bx) = (world.get_body(a).unwrap().x, world.get_body(b).unwrap().x);
+    assert!(bx - ax < 20.0);
+
+    // Moving toward each other inside the rope length is unconstrained
+    world.set_velocity(a, 0.0, 0.0);
+    world.set_velocity(b, -30.0, 0.0);
+    world.step(1.0 / 60.0);
+    assert!((world.get_body(b).unwrap().vx + 30.0).abs() < 1e-3);
+    assert_eq!(world.get_body(a).unwrap().vx, 0.0);
+}
+
+fn pulley(body_a: BodyId, body_b: BodyId, ground_a: (f32, f32), ground_b: (f32, f32), ratio: f32) -> Constraint {
+    Constraint::Pulley {
+        id: 0,
+        body_a,
+        body_b,
+        ground_a,
+        ground_b,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        ratio,
+        total_length: 0.0,
+        accumulated_impulse: 0.0,
     }
-    assert_eq!(world.get_body(id).unwrap().x, 42.0);
+}
+
+#[test]
+fn test_pulley_heavier_side_descends_and_rope_length_is_kept() {
+    for ratio in [1.0f32, 2.0] {
+        let mut world = PhysicsWorld::new(0.0, 100.0);
+        let heavy = world.add_body(
+            BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+            0.0, 50.0, 3.0, Material::default(), 0xFFFF, 0xFFFF,
+        );
+        let light = world.add_body(
+            BodyType::Dynamic, Shape::Circle { radius: 2.0 },
+            100.0, 50.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+        );
+        world.add_constraint(pulley(heavy, light, (0.0, 0.0), (100.0, 0.0), ratio));
+        let length = 50.0 + ratio * 50.0;
+
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+        let h = world.get_body(heavy).unwrap();
+        let l = world.get_body(light).unwrap();
+        assert!(h.y > 52.0, "heavy side should descend (ratio {}), y={}", ratio, h.y);
+        assert!(l.y < 48.0, "light side should rise (ratio {}), y={}", ratio, l.y);
+        let len_a = (h.x * h.x + h.y * h.y).sqrt();
+        let len_b = ((l.x - 100.0).powi(2) + l.y * l.y).sqrt();
+        assert!(
+            (len_a + ratio * len_b - length).abs() < 1.0,
+            "rope length drifted (ratio {}): {}", ratio, len_a + ratio * len_b,
+        );
+    }
+}
+
+fn mouse(body: BodyId, x: f32, y: f32, max_force: f32) -> Constraint {
+    Constraint::Mouse {
+        id: 0,
+        body,
+        target: (x, y),
+        anchor: (0.0, 0.0),
+        max_force,
+        soft: SoftConstraintParams::rigid(),
+        accumulated_impulse: (0.0, 0.0),
+    }
+}
+
+#[test]
+fn test_mouse_joint_drags_body_to_moving_target() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let b = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let joint = world.add_constraint(mouse(b, 0.0, 0.0, 0.0));
+    // Rigid params fall back to the default drag spring, force to the per-mass default
+    match world.constraints().iter().find(|c| c.id() == joint) {
+        Some(Constraint::Mouse { soft, max_force, .. }) => {
+            assert_eq!(soft.frequency_hz, 5.0);
+            assert_eq!(*max_force, MOUSE_FORCE_PER_MASS);
+        }
+        other => panic!("expected mouse joint, got {:?}", other),
+    }
+
+    assert!(world.set_mouse_target(joint, 100.0, 50.0));
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    let body = world.get_body(b).unwrap();
+    assert!((body.x - 100.0).abs() < 1.0 && (body.y - 50.0).abs() < 1.0, "({}, {})", body.x, body.y);
+}
+
+#[test]
+fn test_mouse_joint_force_is_limited() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let heavy = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 10.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // 1000 N cannot hold up 10 kg against 400 px/s² of gravity (4000 N)
+    world.add_constraint(mouse(heavy, 0.0, 0.0, 1000.0));
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+    let y = world.get_body(heavy).unwrap().y;
+    // Net acceleration 300 px/s²: ~150 px after one second
+    assert!(y > 100.0, "weak drag should not hold the body, y={}", y);
+}
+
+#[test]
+fn test_mouse_joint_released_when_body_removed() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let b = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let joint = world.add_constraint(mouse(b, 0.0, 0.0, 0.0));
+    world.remove_body(b);
+    assert!(!world.set_mouse_target(joint, 10.0, 10.0));
+
+    // The reused ID must not inherit the drag
+    let reused = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    assert_eq!(reused, b);
+    world.step(1.0 / 60.0);
+    assert_eq!(world.get_body(reused).unwrap().vx, 0.0);
+}
+
+fn revolute(body_a: BodyId, body_b: BodyId, motor: Option<JointMotor>) -> Constraint {
+    Constraint::Revolute {
+        id: 0,
+        body_a,
+        body_b,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        soft: None,
+        accumulated_impulse: (0.0, 0.0),
+        motor,
+    }
+}
+
+#[test]
+fn test_revolute_motor_reaches_target_speed_within_torque_limit() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let axle = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 1.0 },
+        0.0, 0.0, 0.0, Material::default(), 0x0001, 0x0000,
+    );
+    // Inertia 0.5 * 2 * 10² = 100
+    let wheel = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 10.0 },
+        0.0, 0.0, 2.0, Material::default(), 0x0002, 0x0000,
+    );
+    let joint = world.add_constraint(revolute(axle, wheel, Some(JointMotor::new(4.0, 100.0))));
+
+    // 100 torque on inertia 100: 1 rad/s², so half a second reaches 0.5 rad/s
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let w = world.get_body(wheel).unwrap().angular_velocity;
+    assert!((w - 0.5).abs() < 0.05, "torque-limited spin-up, w={}", w);
+
+    // A strong motor holds the target speed and the wheel stays on its axle
+    assert!(world.set_joint_motor(joint, Some(JointMotor::new(-3.0, 1.0e6))));
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let body = world.get_body(wheel).unwrap();
+    assert!((body.angular_velocity + 3.0).abs() < 1e-3, "w={}", body.angular_velocity);
+    assert!(body.x.abs() < 0.01 && body.y.abs() < 0.01);
+
+    // Switched off, the wheel coasts
+    assert!(world.set_joint_motor(joint, None));
+    world.step(1.0 / 60.0);
+    assert!((world.get_body(wheel).unwrap().angular_velocity + 3.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_set_joint_motor_rejects_other_joints() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let a = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let b = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+        10.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let rope = world.add_constraint(Constraint::Rope {
+        id: 0,
+        body_a: a,
+        body_b: b,
+        max_length: 20.0,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        accumulated_impulse: 0.0,
+    });
+    assert!(!world.set_joint_motor(rope, Some(JointMotor::new(1.0, 10.0))));
+    assert!(!world.set_joint_motor(999, None));
+}
+
+fn prismatic(body_a: BodyId, body_b: BodyId, axis: (f32, f32), lower: f32, upper: f32) -> Constraint {
+    Constraint::Prismatic {
+        id: 0,
+        body_a,
+        body_b,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        axis,
+        reference_angle: 0.0,
+        lower,
+        upper,
+        motor: None,
+        accumulated_impulse: (0.0, 0.0),
+    }
+}
+
+#[test]
+fn test_prismatic_elevator_rides_motor_and_stops_at_limits() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let shaft = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 1.0 },
+        50.0, 200.0, 0.0, Material::default(), 0x0001, 0x0000,
+    );
+    let platform = world.add_body(
+        BodyType::Dynamic, Shape::Polygon { vertices: vec![(-20.0, -4.0), (20.0, -4.0), (20.0, 4.0), (-20.0, 4.0)] },
+        50.0, 200.0, 1.0, Material::default(), 0x0002, 0x0000,
+    );
+    // Unnormalized axis pointing up; translation 0..100 upward
+    let joint = world.add_constraint(prismatic(shaft, platform, (0.0, -3.0), 0.0, 100.0));
+    world.set_joint_motor(joint, Some(JointMotor::new(50.0, 1.0e5)));
+    // A sideways shove and a spin are taken by the joint
+    world.set_velocity(platform, 80.0, 0.0);
+    world.set_angular_velocity(platform, 2.0);
+
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+    let body = world.get_body(platform).unwrap();
+    assert!((body.y - 150.0).abs() < 2.0, "motor should lift ~50, y={}", body.y);
+    assert!((body.x - 50.0).abs() < 0.5, "slides only along the axis, x={}", body.x);
+    assert!(body.angle.abs() < 0.01, "keeps its rotation, angle={}", body.angle);
+
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    let y = world.get_body(platform).unwrap().y;
+    assert!((y - 100.0).abs() < 1.0, "upper limit at translation 100, y={}", y);
+
+    // Without the motor gravity lowers it back onto the lower limit
+    world.set_joint_motor(joint, None);
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    let y = world.get_body(platform).unwrap().y;
+    assert!((y - 200.0).abs() < 1.0, "lower limit at translation 0, y={}", y);
+}
+
+#[test]
+fn test_weld_keeps_offset_and_relative_angle() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let hull = world.add_body(
+        BodyType::Dynamic, Shape::Polygon { vertices: vec![(-10.0, -5.0), (10.0, -5.0), (10.0, 5.0), (-10.0, 5.0)] },
+        0.0, 0.0, 2.0, Material::default(), 0x0001, 0x0000,
+    );
+    let turret = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 4.0 },
+        20.0, 0.0, 1.0, Material::default(), 0x0002, 0x0000,
+    );
+    world.add_constraint(Constraint::Weld {
+        id: 0,
+        body_a: hull,
+        body_b: turret,
+        anchor_a: (10.0, 0.0),
+        anchor_b: (-10.0, 0.0),
+        reference_angle: 0.0,
+        accumulated_impulse: (0.0, 0.0),
+    });
+    world.set_angular_velocity(hull, 3.0);
+    world.set_velocity(turret, 0.0, 40.0);
+
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+    let a = world.get_body(hull).unwrap();
+    let b = world.get_body(turret).unwrap();
+    assert!(a.angle.abs() > 0.5, "the pair should spin, angle={}", a.angle);
+    assert!((b.angle - a.angle).abs() < 0.02, "relative angle drifted: {}", b.angle - a.angle);
+    // The turret stays 20 units along the hull's local X axis
+    let (sin, cos) = a.angle.sin_cos();
+    let expected = (a.x + 20.0 * cos, a.y + 20.0 * sin);
+    assert!(
+        (b.x - expected.0).abs() < 0.5 && (b.y - expected.1).abs() < 0.5,
+        "turret at ({}, {}), expected {:?}", b.x, b.y, expected,
+    );
+}
+
+// =========================================================================
+// World with collisions
+// =========================================================================
+
+#[test]
+fn test_ball_on_ground_contacts() {
+    let mut world = PhysicsWorld::new(0.0, 100.0);
+    // Ball starting just above ground
+    let _ball = world.add_body(
+        BodyType::Dynamic,
+        Shape::Circle { radius: 1.0 },
+        0.0,
+        -3.0,
+        1.0,
+        Material { restitution: 0.5, friction: 0.5 },
+        0xFFFF,
+        0xFFFF,
+    );
+    // Ground
+    let _ground = world.add_body(
+        BodyType::Static,
+        Shape::AABB { half_w: 100.0, half_h: 1.0 },
+        0.0,
+        0.0,
+        0.0,
+        Material { restitution: 0.5, friction: 0.5 },
+        0xFFFF,
+        0xFFFF,
+    );
+
+    // Step a few frames to let ball fall
+    for _ in 0..10 {
+        world.step(1.0 / 60.0);
+    }
+    // Eventually there should be contacts
+    // Note: contacts are only from the last sub-step
+    let _contacts = world.get_contacts();
+    // The ball should have fallen and hit the ground by now
+    let ball = world.get_body(_ball).unwrap();
+    assert!(ball.y > -3.0, "Ball should have fallen");
+}
+
+#[test]
+fn test_world_multiple_bodies() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let ids: Vec<u32> = (0..10)
+        .map(|i| {
+            world.add_body(
+                BodyType::Dynamic,
+                Shape::Circle { radius: 1.0 },
+                i as f32 * 100.0,
+                0.0,
+                1.0,
+                Material::default(),
+                0xFFFF,
+                0xFFFF,
+            )
+        })
+        .collect();
+    assert_eq!(ids.len(), 10);
+    for (i, &id) in ids.iter().enumerate() {
+        let body = world.get_body(id).unwrap();
+        assert!((body.x - i as f32 * 100.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_world_get_body_mut() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let id = world.add_body(
+        BodyType::Dynamic,
+        Shape::Circle { radius: 1.0 },
+        0.0,
+        0.0,
+        1.0,
+        Material::default(),
+        0xFFFF,
+        0xFFFF,
+    );
+    {
+        let body = world.get_body_mut(id).unwrap();
+        body.x = 42.0;
+    }
+    assert_eq!(world.get_body(id).unwrap().x, 42.0);
 }
 
 #[test]
@@ -1207,7 +2012,7 @@ fn test_constraint_id() {
     };
     assert_eq!(c.id(), 42);
 
-    let r = Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0),
+    let r = Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0), motor: None,
         id: 7,
         body_a: 0,
         body_b: 1,
@@ -1215,6 +2020,12 @@ fn test_constraint_id() {
         anchor_b: (0.0, 0.0),
     };
     assert_eq!(r.id(), 7);
+
+    let mut rope_c = rope(0, 1, 5.0);
+    if let Constraint::Rope { id, .. } = &mut rope_c {
+        *id = 3;
+    }
+    assert_eq!(rope_c.id(), 3);
 }
 
 // =========================================================================
@@ -1400,6 +2211,51 @@ fn test_physics_ops_revolute_joint() {
     .unwrap();
 }
 
+#[test]
+fn test_physics_ops_rope_and_pulley_joints() {
+    let mut rt = arcane_core::scripting::ArcaneRuntime::new();
+    rt.execute_script(
+        "<test>",
+        r#"
+        Deno.core.ops.op_create_physics_world(0.0, 100.0);
+        const a = Deno.core.ops.op_create_body(1, 0, 1.0, 0.0, 0.0, 50.0, 2.0, 0.3, 0.5, 65535, 65535);
+        const b = Deno.core.ops.op_create_body(1, 0, 1.0, 0.0, 100.0, 50.0, 1.0, 0.3, 0.5, 65535, 65535);
+        const rope = Deno.core.ops.op_create_rope_joint(a, b, 200.0);
+        const pulley = Deno.core.ops.op_create_pulley_joint(a, b, 0.0, 0.0, 100.0, 0.0, 1.0);
+        if (rope === 4294967295 || pulley === 4294967295) throw new Error("Joint creation failed");
+        for (let i = 0; i < 30; i++) Deno.core.ops.op_physics_step(1.0 / 60.0);
+        const sa = Deno.core.ops.op_get_body_state(a);
+        const sb = Deno.core.ops.op_get_body_state(b);
+        if (!(sa[1] > 50.0 && sb[1] < 50.0)) throw new Error("Pulley did not move: " + sa[1] + ", " + sb[1]);
+        "#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_physics_ops_mouse_joint() {
+    let mut rt = arcane_core::scripting::ArcaneRuntime::new();
+    rt.execute_script(
+        "<test>",
+        r#"
+        Deno.core.ops.op_create_physics_world(0.0, 0.0);
+        const b = Deno.core.ops.op_create_body(1, 0, 5.0, 0.0, 0.0, 0.0, 1.0, 0.3, 0.5, 65535, 65535);
+        const jid = Deno.core.ops.op_create_mouse_joint(b, 2.0, 0.0, 0.0);
+        if (jid === 4294967295) throw new Error("Mouse joint creation failed");
+        Deno.core.ops.op_set_mouse_joint_params(jid, 8.0, 1.0, 0.0);
+        for (let i = 0; i < 60; i++) {
+            if (!Deno.core.ops.op_set_mouse_joint_target(jid, 52.0, 0.0)) throw new Error("Joint lost");
+            Deno.core.ops.op_physics_step(1.0 / 60.0);
+        }
+        const s = Deno.core.ops.op_get_body_state(b);
+        if (Math.abs(s[0] - 50.0) > 2.0) throw new Error("Body not dragged: x=" + s[0]);
+        Deno.core.ops.op_remove_body(b);
+        if (Deno.core.ops.op_set_mouse_joint_target(jid, 0.0, 0.0)) throw new Error("Joint not released");
+        "#,
+    )
+    .unwrap();
+}
+
 #[test]
 fn test_physics_ops_set_position() {
     let mut rt = arcane_core::scripting::ArcaneRuntime::new();
@@ -1461,8 +2317,8 @@ fn test_physics_ops_get_contacts() {
         Deno.core.ops.op_create_body(1, 0, 5.0, 0.0, 3.0, 0.0, 1.0, 0.3, 0.5, 65535, 65535);
         Deno.core.ops.op_physics_step(1.0 / 60.0);
         const contacts = Deno.core.ops.op_get_contacts();
-        // Contacts are flattened in groups of 7
-        if (contacts.length < 7) throw new Error("Should have at least one contact, got " + contacts.length);
+        // Contacts are flattened in groups of 10
+        if (contacts.length < 10) throw new Error("Should have at least one contact, got " + contacts.length);
         "#,
     )
     .unwrap();
@@ -1503,6 +2359,26 @@ fn test_physics_ops_aabb_body() {
     .unwrap();
 }
 
+#[test]
+fn test_physics_ops_capsule_and_oriented_box_bodies() {
+    let mut rt = arcane_core::scripting::ArcaneRuntime::new();
+    rt.execute_script(
+        "<test>",
+        r#"
+        Deno.core.ops.op_create_physics_world(0.0, 100.0);
+        // Capsule (shape_type=3): half_height, radius
+        const capsule = Deno.core.ops.op_create_body(1, 3, 10.0, 4.0, 0.0, 0.0, 1.0, 0.3, 0.5, 65535, 65535);
+        // Oriented box (shape_type=4): half_w, half_h
+        const box = Deno.core.ops.op_create_body(1, 4, 5.0, 2.0, 50.0, 0.0, 1.0, 0.3, 0.5, 65535, 65535);
+        if (capsule === 0xFFFFFFFF || box === 0xFFFFFFFF) throw new Error("Bodies should be created");
+        if (Deno.core.ops.op_create_body(1, 9, 1.0, 1.0, 0.0, 0.0, 1.0, 0.3, 0.5, 65535, 65535) !== 0xFFFFFFFF) {
+            throw new Error("Unknown shape type should fail");
+        }
+        "#,
+    )
+    .unwrap();
+}
+
 #[test]
 fn test_physics_ops_static_body() {
     let mut rt = arcane_core::scripting::ArcaneRuntime::new();
@@ -2938,14 +3814,52 @@ fn test_contacts_accumulated_across_substeps() {
     );
 }
 
-/// Box at the edge of a platform must not clip through.
-/// This tests the AABB axis selection issue where horizontal overlap
-/// can be smaller than vertical, causing incorrect sideways resolution.
+/// Contacts report how hard the pair hit: a ball stopped dead by a wall
+/// receives an impulse of about m·v, and the relative velocity at the contact
+/// is the approach velocity.
 #[test]
-fn test_box_on_platform_edge_no_clipping() {
-    let mut world = PhysicsWorld::new(0.0, 300.0);
-    let box_size = 20.0;
-    let half = box_size / 2.0;
+fn test_contact_impulse_and_relative_velocity_scale_with_impact_speed() {
+    let mut impulses = Vec::new();
+    for speed in [100.0f32, 300.0] {
+        let mut world = PhysicsWorld::new(0.0, 0.0);
+        let wall = world.add_body(
+            BodyType::Static, Shape::AABB { half_w: 200.0, half_h: 10.0 },
+            0.0, 0.0, 1.0, Material { restitution: 0.0, friction: 0.0 }, 0xFFFF, 0xFFFF,
+        );
+        let ball = world.add_body(
+            BodyType::Dynamic, Shape::Circle { radius: 6.0 },
+            0.0, 30.0, 2.0, Material { restitution: 0.0, friction: 0.0 }, 0xFFFF, 0xFFFF,
+        );
+        world.set_velocity(ball, 0.0, -speed);
+
+        let mut total_impulse = 0.0;
+        let mut closing_speed = 0.0f32;
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+            for c in world.get_contacts() {
+                assert!((c.body_a == wall && c.body_b == ball) || (c.body_a == ball && c.body_b == wall));
+                assert!(c.impulse >= 0.0);
+                total_impulse += c.impulse;
+                let vn = c.relative_velocity.0 * c.normal.0 + c.relative_velocity.1 * c.normal.1;
+                closing_speed = closing_speed.max(-vn);
+            }
+        }
+        // Momentum removed from the ball: m·v
+        assert!((total_impulse - 2.0 * speed).abs() < 0.1 * 2.0 * speed, "speed {}: impulse {}", speed, total_impulse);
+        assert!((closing_speed - speed).abs() < 0.1 * speed, "speed {}: closing {}", speed, closing_speed);
+        impulses.push(total_impulse);
+    }
+    assert!(impulses[1] > impulses[0] * 2.5);
+}
+
+/// Box at the edge of a platform must not clip through.
+/// This tests the AABB axis selection issue where horizontal overlap
+/// can be smaller than vertical, causing incorrect sideways resolution.
+#[test]
+fn test_box_on_platform_edge_no_clipping() {
+    let mut world = PhysicsWorld::new(0.0, 300.0);
+    let box_size = 20.0;
+    let half = box_size / 2.0;
 
     // Platform: 100 units wide, centered at x=100
     let plat_center_x = 100.0;
@@ -3023,7 +3937,7 @@ fn test_revolute_joint_allows_rotation() {
     );
 
     // Revolute joint at pivot center (plank's anchor is at its center)
-    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0),
+    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0), motor: None,
         id: 0,
         body_a: plank_id,
         body_b: pivot_id,
@@ -3267,7 +4181,7 @@ fn test_seesaw_rotates_when_weight_lands() {
     );
 
     // Revolute joint attaching plank to pivot
-    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0),
+    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0), motor: None,
         id: 0,
         body_a: plank_id,
         body_b: pivot_id,
@@ -3409,7 +4323,7 @@ fn test_polygon_seesaw_rotates() {
     );
 
     // Revolute joint at pivot
-    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0),
+    world.add_constraint(Constraint::Revolute { soft: None, accumulated_impulse: (0.0, 0.0), motor: None,
         id: 0,
         body_a: plank,
         body_b: pivot,
@@ -3940,3 +4854,1245 @@ fn test_stacked_boxes_have_two_contacts_each() {
         stack_contacts,
     );
 }
+
+// =========================================================================
+// Solver configuration (sub-steps, iterations, sleep thresholds)
+// =========================================================================
+
+/// Drop a 5-box stack onto static ground with the given solver settings.
+/// Returns the world, the box IDs (bottom first) and the ground's top edge.
+fn box_stack_world(config: PhysicsConfig) -> (PhysicsWorld, Vec<BodyId>, f32) {
+    let mut world = PhysicsWorld::with_config(0.0, 400.0, config);
+    let ground_top = 300.0;
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 200.0, half_h: 10.0 },
+        200.0, ground_top + 10.0, 0.0,
+        Material { restitution: 0.0, friction: 0.5 },
+        0xFFFF, 0xFFFF,
+    );
+    let ids = (0..5)
+        .map(|i| {
+            world.add_body(
+                BodyType::Dynamic, Shape::AABB { half_w: 15.0, half_h: 15.0 },
+                200.0, ground_top - 15.0 - i as f32 * 31.0, 1.0,
+                Material { restitution: 0.0, friction: 0.5 },
+                0xFFFF, 0xFFFF,
+            )
+        })
+        .collect();
+    (world, ids, ground_top)
+}
+
+#[test]
+fn test_default_config_matches_builtin_solver_settings() {
+    let config = PhysicsConfig::default();
+    assert_eq!(config.substeps, 4);
+    assert_eq!(config.velocity_iterations, 10);
+    assert_eq!(config.position_iterations, 3);
+    assert_eq!(config.sleep, SleepConfig::default());
+    assert_eq!(PhysicsWorld::new(0.0, 9.81).config(), config);
+}
+
+#[test]
+fn test_config_is_clamped_to_supported_ranges() {
+    let world = PhysicsWorld::with_config(0.0, 0.0, PhysicsConfig {
+        substeps: 0,
+        velocity_iterations: 0,
+        position_iterations: 1000,
+        sleep: SleepConfig { velocity: -1.0, angular_velocity: f32::NAN, time: 2.0 },
+    });
+    let config = world.config();
+    assert_eq!(config.substeps, 1);
+    assert_eq!(config.velocity_iterations, 1);
+    assert_eq!(config.position_iterations, PhysicsConfig::MAX_ITERATIONS);
+    assert_eq!(config.sleep.velocity, SleepConfig::default().velocity);
+    assert_eq!(config.sleep.angular_velocity, SleepConfig::default().angular_velocity);
+    assert_eq!(config.sleep.time, 2.0);
+
+    let mut world = world;
+    world.set_substeps(1000);
+    assert_eq!(world.config().substeps, PhysicsConfig::MAX_SUBSTEPS);
+    world.set_solver_iterations(6, 0);
+    assert_eq!((world.config().velocity_iterations, world.config().position_iterations), (6, 0));
+    // Other settings are untouched by the individual setters
+    assert_eq!(world.config().substeps, PhysicsConfig::MAX_SUBSTEPS);
+}
+
+#[test]
+fn test_stack_stays_upright_across_solver_settings() {
+    for substeps in [1, 2, 4, 8] {
+        for velocity_iterations in [4, 10, 20] {
+            for position_iterations in [1, 3] {
+                let config = PhysicsConfig {
+                    substeps,
+                    velocity_iterations,
+                    position_iterations,
+                    ..PhysicsConfig::default()
+                };
+                let (mut world, ids, ground_top) = box_stack_world(config);
+                for _ in 0..180 {
+                    world.step(1.0 / 60.0);
+                }
+                let label = format!("substeps={substeps} vel={velocity_iterations} pos={position_iterations}");
+
+                let bottom = world.get_body(ids[0]).unwrap();
+                assert!(
+                    bottom.y + 15.0 <= ground_top + 2.0,
+                    "{label}: bottom box sank into the ground (bottom edge {:.2})",
+                    bottom.y + 15.0,
+                );
+                for pair in ids.windows(2) {
+                    let below = world.get_body(pair[0]).unwrap();
+                    let above = world.get_body(pair[1]).unwrap();
+                    assert!(
+                        below.y - above.y > 27.0,
+                        "{label}: boxes {} and {} overlap (dy {:.2})",
+                        pair[0], pair[1], below.y - above.y,
+                    );
+                    assert!(
+                        (above.x - 200.0).abs() < 5.0,
+                        "{label}: box {} drifted to x={:.2}",
+                        pair[1], above.x,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_more_substeps_track_free_fall_more_closely() {
+    // Analytic free fall: y = g t² / 2. Semi-implicit Euler overshoots by
+    // g·t·dt/2, so the error should shrink as sub-steps are added.
+    let error_for = |substeps: u32| {
+        let mut world = PhysicsWorld::with_config(
+            0.0, 400.0,
+            PhysicsConfig { substeps, ..PhysicsConfig::default() },
+        );
+        let id = world.add_body(
+            BodyType::Dynamic, Shape::Circle { radius: 1.0 },
+            0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+        );
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        (world.get_body(id).unwrap().y - 200.0).abs()
+    };
+    let errors: Vec<f32> = [1, 2, 4, 8].into_iter().map(error_for).collect();
+    for pair in errors.windows(2) {
+        assert!(pair[1] < pair[0], "free-fall error should shrink with sub-steps: {errors:?}");
+    }
+}
+
+#[test]
+fn test_sleep_thresholds_are_configurable() {
+    let sleeping_after = |sleep: SleepConfig, frames: usize| {
+        let (mut world, ids, _) = box_stack_world(PhysicsConfig { sleep, ..PhysicsConfig::default() });
+        for _ in 0..frames {
+            world.step(1.0 / 60.0);
+        }
+        ids.iter().all(|&id| world.get_body(id).unwrap().sleeping)
+    };
+
+    assert!(sleeping_after(SleepConfig::default(), 180));
+    // A zero velocity threshold disables sleeping entirely
+    assert!(!sleeping_after(SleepConfig { velocity: 0.0, ..SleepConfig::default() }, 180));
+    // A long still-time delays sleeping past the default
+    assert!(!sleeping_after(SleepConfig { time: 10.0, ..SleepConfig::default() }, 180));
+
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.set_sleep_config(SleepConfig { time: 1.5, ..SleepConfig::default() });
+    assert_eq!(world.config().sleep.time, 1.5);
+}
+
+// =========================================================================
+// Capsules and oriented boxes
+// =========================================================================
+
+#[test]
+fn test_capsule_and_obb_mass_inertia() {
+    // Oriented box: m(w² + h²) / 12, unlike the AABB's zero
+    let (_, inertia, _) =
+        compute_mass_and_inertia(&Shape::OBB { half_w: 3.0, half_h: 2.0 }, 12.0, BodyType::Dynamic);
+    assert!((inertia - 12.0 * (36.0 + 16.0) / 12.0).abs() < 1e-3);
+
+    // A capsule with no straight part is a circle
+    let (_, inertia, _) =
+        compute_mass_and_inertia(&Shape::Capsule { half_height: 0.0, radius: 2.0 }, 4.0, BodyType::Dynamic);
+    assert!((inertia - 0.5 * 4.0 * 4.0).abs() < 1e-4);
+
+    // Stretching it moves mass away from the center
+    let (_, long, _) =
+        compute_mass_and_inertia(&Shape::Capsule { half_height: 5.0, radius: 2.0 }, 4.0, BodyType::Dynamic);
+    assert!(long > inertia);
+}
+
+#[test]
+fn test_capsule_shape_aabb_rotates() {
+    let mut body = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 0.0, 0.0, 1.0);
+    let (min_x, min_y, max_x, max_y) = get_shape_aabb(&body);
+    assert!((min_x + 2.0).abs() < 1e-5 && (max_x - 2.0).abs() < 1e-5);
+    assert!((min_y + 12.0).abs() < 1e-5 && (max_y - 12.0).abs() < 1e-5);
+
+    body.angle = std::f32::consts::FRAC_PI_2;
+    let (min_x, min_y, _, _) = get_shape_aabb(&body);
+    assert!((min_x + 12.0).abs() < 1e-4);
+    assert!((min_y + 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_capsule_circle_overlap() {
+    let capsule = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 5.0 }, 0.0, 0.0, 1.0);
+    // Beside the straight part: closest core point is (0, 8)
+    let circle = make_body(1, BodyType::Dynamic, Shape::Circle { radius: 5.0 }, 8.0, 8.0, 1.0);
+    let c = test_collision(&capsule, &circle).unwrap();
+    assert!((c.normal.0 - 1.0).abs() < 1e-5 && c.normal.1.abs() < 1e-5);
+    assert!((c.penetration - 2.0).abs() < 1e-4);
+
+    // Reversed order flips the normal
+    let c = test_collision(&circle, &capsule).unwrap();
+    assert!((c.normal.0 + 1.0).abs() < 1e-5);
+    assert_eq!((c.body_a, c.body_b), (1, 0));
+
+    let far = make_body(1, BodyType::Dynamic, Shape::Circle { radius: 5.0 }, 11.0, 0.0, 1.0);
+    assert!(test_collision(&capsule, &far).is_none());
+}
+
+#[test]
+fn test_capsule_end_is_round_against_box_corner() {
+    // Capsule core ends at (0, 10); radius 5
+    let capsule = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 5.0 }, 0.0, 0.0, 1.0);
+    // Box corner at (4, 14): inside the capsule's bounding square but 5.66 from the core
+    let clear = make_body(1, BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 5.0 }, 9.0, 19.0, 0.0);
+    assert!(test_collision(&capsule, &clear).is_none());
+    assert!(test_collision_manifold(&capsule, &clear).is_none());
+
+    // Corner at (3, 13): 4.24 from the core, touching diagonally
+    let touching = make_body(1, BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 5.0 }, 8.0, 18.0, 0.0);
+    let c = test_collision(&capsule, &touching).unwrap();
+    assert!((c.normal.0 - c.normal.1).abs() < 1e-4 && c.normal.0 > 0.0);
+    assert!((c.penetration - (5.0 - 18f32.sqrt())).abs() < 1e-3);
+}
+
+#[test]
+fn test_capsule_capsule_crossing() {
+    let upright = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 0.0, 0.0, 1.0);
+    let mut lying = make_body(1, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 3.0, 0.0, 1.0);
+    lying.angle = std::f32::consts::FRAC_PI_2;
+    // Lying core runs from x=-7 to x=13 through the upright core
+    let m = test_collision_manifold(&upright, &lying).unwrap();
+    assert_eq!(m.points.len(), 1);
+    assert!(m.points[0].penetration > 0.0);
+
+    // Core 5 below the upright core's end: radii only add up to 4
+    lying.y = 15.0;
+    assert!(test_collision(&upright, &lying).is_none());
+}
+
+#[test]
+fn test_parallel_capsules_have_two_contacts() {
+    let a = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 0.0, 0.0, 1.0);
+    let b = make_body(1, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 3.5, 5.0, 1.0);
+    let m = test_collision_manifold(&a, &b).unwrap();
+    assert_eq!(m.points.len(), 2);
+    assert!((m.normal.0 - 1.0).abs() < 1e-5);
+    for p in &m.points {
+        assert!((p.penetration - 0.5).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_capsule_lying_on_ground_has_two_contacts() {
+    let ground = make_body(0, BodyType::Static, Shape::AABB { half_w: 100.0, half_h: 10.0 }, 0.0, 20.0, 0.0);
+    let mut capsule = make_body(1, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 5.0 }, 0.0, 5.5, 1.0);
+    capsule.angle = std::f32::consts::FRAC_PI_2;
+    let m = test_collision_manifold(&capsule, &ground).unwrap();
+    assert_eq!(m.points.len(), 2);
+    assert!((m.normal.1 - 1.0).abs() < 1e-5, "normal should point down into the ground: {:?}", m.normal);
+    for p in &m.points {
+        assert!((p.penetration - 0.5).abs() < 1e-4);
+    }
+
+    // Standing upright, only the bottom end touches
+    capsule.angle = 0.0;
+    capsule.y = -4.5;
+    let m = test_collision_manifold(&capsule, &ground).unwrap();
+    assert_eq!(m.points.len(), 1);
+}
+
+#[test]
+fn test_capsule_vs_polygon_and_obb() {
+    let capsule = make_body(0, BodyType::Dynamic, Shape::Capsule { half_height: 10.0, radius: 2.0 }, 0.0, 0.0, 1.0);
+    let triangle = make_body(
+        1,
+        BodyType::Static,
+        Shape::Polygon { vertices: vec![(0.0, -5.0), (5.0, 5.0), (-5.0, 5.0)] },
+        0.0, 16.0, 0.0,
+    );
+    // Triangle tip at (0, 11) pokes into the capsule's bottom end (core ends at 10)
+    let c = test_collision(&capsule, &triangle).unwrap();
+    assert!(c.normal.1 > 0.9);
+    assert!((c.penetration - 1.0).abs() < 1e-3);
+
+    let mut obb = make_body(1, BodyType::Static, Shape::OBB { half_w: 1.0, half_h: 11.0 }, 12.0, 0.0, 0.0);
+    assert!(test_collision(&capsule, &obb).is_none());
+    // Turned flat, its long side reaches the capsule
+    obb.angle = std::f32::consts::FRAC_PI_2;
+    let c = test_collision(&capsule, &obb).unwrap();
+    assert!(c.normal.0 > 0.9);
+}
+
+#[test]
+fn test_obb_collides_as_rotated_box() {
+    let mut obb = make_body(0, BodyType::Dynamic, Shape::OBB { half_w: 10.0, half_h: 1.0 }, 0.0, 0.0, 1.0);
+    let circle = make_body(1, BodyType::Dynamic, Shape::Circle { radius: 1.0 }, 0.0, 10.5, 1.0);
+    let aabb = make_body(2, BodyType::Dynamic, Shape::AABB { half_w: 1.0, half_h: 1.0 }, 0.0, -10.5, 1.0);
+    assert!(test_collision(&obb, &circle).is_none());
+    assert!(test_collision(&aabb, &obb).is_none());
+
+    obb.angle = std::f32::consts::FRAC_PI_2;
+    let c = test_collision(&obb, &circle).unwrap();
+    assert_eq!((c.body_a, c.body_b), (0, 1));
+    assert!(c.normal.1 > 0.9);
+    let m = test_collision_manifold(&aabb, &obb).unwrap();
+    assert_eq!((m.body_a, m.body_b), (2, 0));
+    assert!(m.normal.1 > 0.9);
+}
+
+#[test]
+fn test_raycast_hits_capsule_and_obb() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let capsule = world.add_body(
+        BodyType::Static, Shape::Capsule { half_height: 10.0, radius: 2.0 },
+        20.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Side of the straight part
+    let (id, _, _, dist) = world.raycast(0.0, 5.0, 1.0, 0.0, 100.0).unwrap();
+    assert_eq!(id, capsule);
+    assert!((dist - 18.0).abs() < 1e-3);
+    // Rounded end: from below, straight up the axis
+    let (_, _, hy, _) = world.raycast(20.0, 30.0, 0.0, -1.0, 100.0).unwrap();
+    assert!((hy - 12.0).abs() < 1e-3);
+
+    let obb = world.add_body(
+        BodyType::Static, Shape::OBB { half_w: 10.0, half_h: 1.0 },
+        -20.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.get_body_mut(obb).unwrap().angle = std::f32::consts::FRAC_PI_2;
+    // Rotated upright, the box is only 2 wide
+    let (id, _, _, dist) = world.raycast(0.0, 5.0, -1.0, 0.0, 100.0).unwrap();
+    assert_eq!(id, obb);
+    assert!((dist - 19.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_tilted_obb_falls_flat_and_rests() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 400.0, half_h: 20.0 },
+        0.0, 100.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let id = world.add_body(
+        BodyType::Dynamic, Shape::OBB { half_w: 20.0, half_h: 10.0 },
+        0.0, 30.0, 1.0, Material { restitution: 0.0, friction: 0.6 }, 0xFFFF, 0xFFFF,
+    );
+    world.get_body_mut(id).unwrap().angle = 0.3;
+    for _ in 0..240 {
+        world.step(1.0 / 60.0);
+    }
+    let body = world.get_body(id).unwrap();
+    assert!(body.angle.abs() < 0.02, "box should tip back onto its long side, angle {}", body.angle);
+    assert!((body.y - 70.0).abs() < 1.0, "box should rest on the ground, y {}", body.y);
+}
+
+#[test]
+fn test_capsule_lying_on_ground_settles() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 400.0, half_h: 20.0 },
+        0.0, 100.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let id = world.add_body(
+        BodyType::Dynamic, Shape::Capsule { half_height: 15.0, radius: 5.0 },
+        0.0, 60.0, 1.0, Material { restitution: 0.0, friction: 0.6 }, 0xFFFF, 0xFFFF,
+    );
+    world.get_body_mut(id).unwrap().angle = std::f32::consts::FRAC_PI_2;
+    for _ in 0..240 {
+        world.step(1.0 / 60.0);
+    }
+    let body = world.get_body(id).unwrap();
+    assert!((body.y - 75.0).abs() < 1.0, "capsule should rest on its side, y {}", body.y);
+    assert!((body.angle - std::f32::consts::FRAC_PI_2).abs() < 0.02, "angle {}", body.angle);
+    assert!(body.sleeping);
+}
+
+// =========================================================================
+// Colliders from sprite alpha
+// =========================================================================
+
+/// RGBA image with alpha 255 wherever `solid(x, y)`.
+fn alpha_image(w: u32, h: u32, solid: impl Fn(u32, u32) -> bool) -> Vec<u8> {
+    let mut rgba = vec![0u8; (w * h * 4) as usize];
+    for y in 0..h {
+        for x in 0..w {
+            if solid(x, y) {
+                rgba[((y * w + x) * 4 + 3) as usize] = 255;
+            }
+        }
+    }
+    rgba
+}
+
+fn piece_area(piece: &[(f32, f32)]) -> f32 {
+    let n = piece.len();
+    (0..n)
+        .map(|i| piece[i].0 * piece[(i + 1) % n].1 - piece[(i + 1) % n].0 * piece[i].1)
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Convex and wound like `Shape::Polygon` (positive area, outward edge normals).
+fn assert_convex(piece: &[(f32, f32)]) {
+    let n = piece.len();
+    assert!(n >= 3);
+    assert!(piece_area(piece) > 0.0, "piece {piece:?} is wound backwards");
+    for i in 0..n {
+        let (a, b, c) = (piece[i], piece[(i + 1) % n], piece[(i + 2) % n]);
+        let cross = (b.0 - a.0) * (c.1 - b.1) - (b.1 - a.1) * (c.0 - b.0);
+        assert!(cross >= -1e-3, "piece {piece:?} is concave at {b:?}");
+    }
+}
+
+#[test]
+fn test_sprite_collider_square_is_one_centered_piece() {
+    let rgba = alpha_image(16, 16, |x, y| (4..12).contains(&x) && (4..12).contains(&y));
+    let pieces = colliders_from_rgba(&rgba, 16, 16, 127, 0.0);
+    assert_eq!(pieces.len(), 1);
+    assert_convex(&pieces[0]);
+    // Pixel edges, with the corners cut by half a pixel
+    assert!((piece_area(&pieces[0]) - (64.0 - 4.0 * 0.125)).abs() < 1e-3);
+    for &(x, y) in &pieces[0] {
+        assert!(x.abs() <= 4.0 && y.abs() <= 4.0);
+    }
+    assert_eq!(pieces[0].len(), 8);
+}
+
+#[test]
+fn test_sprite_collider_concave_shape_splits_into_convex_pieces() {
+    // An L: 12x4 foot and 4x12 column
+    let rgba = alpha_image(16, 16, |x, y| {
+        (2..6).contains(&x) && (2..14).contains(&y) || (2..14).contains(&x) && (10..14).contains(&y)
+    });
+    let pieces = colliders_from_rgba(&rgba, 16, 16, 127, 0.0);
+    assert!(pieces.len() >= 2);
+    for piece in &pieces {
+        assert_convex(piece);
+    }
+    // Five outer corners cut off, the inner corner filled in
+    let total: f32 = pieces.iter().map(|p| piece_area(p)).sum();
+    assert!((total - 79.5).abs() < 1e-3, "total area {total}");
+
+    // Simplifying keeps the pieces convex and within a tolerance of the outline
+    let simplified = colliders_from_rgba(&rgba, 16, 16, 127, 0.5);
+    for piece in &simplified {
+        assert_convex(piece);
+    }
+    let total: f32 = simplified.iter().map(|p| piece_area(p)).sum();
+    assert!(total > 79.5 - 0.5 * 56.0 && total < 79.5, "total area {total}");
+}
+
+#[test]
+fn test_sprite_collider_simplifies_round_outlines() {
+    let rgba = alpha_image(64, 64, |x, y| {
+        let (dx, dy) = (x as f32 + 0.5 - 32.0, y as f32 + 0.5 - 32.0);
+        dx * dx + dy * dy < 28.0 * 28.0
+    });
+    let exact = colliders_from_rgba(&rgba, 64, 64, 127, 0.0);
+    let simplified = colliders_from_rgba(&rgba, 64, 64, 127, 1.0);
+    let vertices = |pieces: &[Vec<(f32, f32)>]| pieces.iter().map(Vec::len).sum::<usize>();
+    assert!(vertices(&simplified) < vertices(&exact) / 2);
+    // Convex, but more than 8 vertices: split into pieces within the limit
+    assert!(simplified.len() > 1);
+    for piece in &simplified {
+        assert_convex(piece);
+        assert!(piece.len() <= 8);
+    }
+    let area: f32 = simplified.iter().map(|p| piece_area(p)).sum();
+    assert!((area - std::f32::consts::PI * 28.0 * 28.0).abs() < 80.0, "area {area}");
+}
+
+#[test]
+fn test_sprite_collider_threshold_blobs_and_holes() {
+    // Two blobs, one of them a ring; faint pixels everywhere else
+    let mut rgba = alpha_image(32, 16, |x, y| {
+        let hole = (6..10).contains(&x) && (6..10).contains(&y);
+        let ring = (2..14).contains(&x) && (2..14).contains(&y) && !hole;
+        let blob = (20..28).contains(&x) && (4..12).contains(&y);
+        ring || blob
+    });
+    for px in rgba.chunks_exact_mut(4) {
+        if px[3] == 0 {
+            px[3] = 100;
+        }
+    }
+    let pieces = colliders_from_rgba(&rgba, 32, 16, 127, 0.0);
+    for piece in &pieces {
+        assert_convex(piece);
+    }
+    // The ring's hole is filled: 12x12 + 8x8, minus the cut corners
+    let total: f32 = pieces.iter().map(|p| piece_area(p)).sum();
+    assert!((total - 207.0).abs() < 1e-3, "total area {total}");
+    assert!(pieces.iter().flatten().any(|&(x, _)| x > 0.0));
+
+    // Below the threshold the faint pixels count too: one 32x16 rectangle
+    let pieces = colliders_from_rgba(&rgba, 32, 16, 50, 0.0);
+    assert_eq!(pieces.len(), 1);
+    assert!((piece_area(&pieces[0]) - 511.5).abs() < 1e-3);
+
+    assert!(colliders_from_rgba(&alpha_image(8, 8, |_, _| false), 8, 8, 0, 1.0).is_empty());
+    assert_eq!(
+        pieces_json(&[vec![(-1.0, -1.0), (1.5, -1.0), (0.0, 2.0)]]),
+        "[[-1,-1,1.5,-1,0,2]]"
+    );
+}
+
+// =========================================================================
+// Collision events
+// =========================================================================
+
+fn event_kinds(events: &[CollisionEvent]) -> Vec<(CollisionEventKind, BodyId, BodyId)> {
+    events.iter().map(|e| (e.kind, e.body_a, e.body_b)).collect()
+}
+
+#[test]
+fn test_collision_events_begin_stay_end() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let ground = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 200.0, half_h: 10.0 },
+        0.0, 100.0, 0.0, Material { restitution: 0.0, friction: 0.5 }, 0xFFFF, 0xFFFF,
+    );
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 50.0, 1.0, Material { restitution: 0.0, friction: 0.5 }, 0xFFFF, 0xFFFF,
+    );
+
+    // Falling: no events, even once within the speculative margin
+    let mut begins = Vec::new();
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+        let events = world.take_collision_events();
+        if events.is_empty() {
+            assert!(begins.is_empty(), "no events once touching");
+            continue;
+        }
+        if begins.is_empty() {
+            begins = events;
+        } else {
+            // Resting: a stay every step, never another begin or an end
+            assert_eq!(event_kinds(&events), vec![(CollisionEventKind::Stay, ground, ball)]);
+        }
+    }
+    assert_eq!(event_kinds(&begins), vec![(CollisionEventKind::Begin, ground, ball)]);
+    let body = world.get_body(ball).unwrap();
+    assert!(body.y > 80.0, "ball should have landed, y {}", body.y);
+    // Normal points from the ground (A) to the ball (B)
+    assert!(begins[0].normal.1 < -0.9, "normal {:?}", begins[0].normal);
+    assert!((begins[0].point.1 - 90.0).abs() < 2.0, "point {:?}", begins[0].point);
+
+    // Lift it away: one end, then nothing
+    world.set_position(ball, 0.0, 0.0);
+    world.set_velocity(ball, 0.0, 0.0);
+    world.step(1.0 / 60.0);
+    assert_eq!(event_kinds(&world.take_collision_events()), vec![(CollisionEventKind::End, ground, ball)]);
+    world.step(1.0 / 60.0);
+    assert!(world.take_collision_events().is_empty());
+}
+
+#[test]
+fn test_collision_events_accumulate_until_taken_and_skip_empty_steps() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let a = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 10.0 },
+        0.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let b = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 10.0 },
+        19.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Less than a fixed step: nothing tested, nothing reported
+    world.step(0.001);
+    assert!(world.take_collision_events().is_empty());
+
+    world.step(1.0 / 60.0);
+    world.step(1.0 / 60.0);
+    assert_eq!(
+        event_kinds(&world.take_collision_events()),
+        vec![(CollisionEventKind::Begin, a, b), (CollisionEventKind::Stay, a, b)]
+    );
+}
+
+#[test]
+fn test_collision_events_end_when_body_removed_and_while_asleep_persist() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let ground = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 200.0, half_h: 10.0 },
+        0.0, 100.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let crate_id = world.add_body(
+        BodyType::Dynamic, Shape::AABB { half_w: 10.0, half_h: 10.0 },
+        0.0, 80.0, 1.0, Material { restitution: 0.0, friction: 0.5 }, 0xFFFF, 0xFFFF,
+    );
+    let top = world.add_body(
+        BodyType::Dynamic, Shape::AABB { half_w: 10.0, half_h: 10.0 },
+        0.0, 60.0, 1.0, Material { restitution: 0.0, friction: 0.5 }, 0xFFFF, 0xFFFF,
+    );
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+        let ends: Vec<_> = world
+            .take_collision_events()
+            .into_iter()
+            .filter(|e| e.kind == CollisionEventKind::End)
+            .collect();
+        assert!(ends.is_empty(), "stack should stay in contact, even asleep: {ends:?}");
+    }
+    assert!(world.get_body(top).unwrap().sleeping);
+
+    world.remove_body(crate_id);
+    assert_eq!(
+        event_kinds(&world.take_collision_events()),
+        vec![(CollisionEventKind::End, ground, crate_id), (CollisionEventKind::End, crate_id, top)]
+    );
+    // The freed ID goes to a new body far away: no stale stay
+    let reused = world.add_body(
+        BodyType::Static, Shape::Circle { radius: 1.0 },
+        500.0, 0.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    assert_eq!(reused, crate_id);
+    world.step(1.0 / 60.0);
+    assert!(world
+        .take_collision_events()
+        .iter()
+        .all(|e| e.body_a != reused && e.body_b != reused));
+}
+
+// =========================================================================
+// Sensors
+// =========================================================================
+
+#[test]
+fn test_dynamic_body_falls_through_static_sensor_with_events() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let zone = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 50.0, half_h: 10.0 },
+        0.0, 100.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_sensor(zone, true);
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 50.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+
+    let mut kinds = Vec::new();
+    for _ in 0..90 {
+        world.step(1.0 / 60.0);
+        for (kind, a, b) in event_kinds(&world.take_collision_events()) {
+            assert_eq!((a, b), (zone, ball));
+            if kinds.last() != Some(&kind) {
+                kinds.push(kind);
+            }
+        }
+    }
+    assert!(world.get_body(ball).unwrap().y > 150.0, "ball should fall through the sensor");
+    assert_eq!(
+        kinds,
+        vec![CollisionEventKind::Begin, CollisionEventKind::Stay, CollisionEventKind::End]
+    );
+}
+
+#[test]
+fn test_sensor_overlap_reports_contact_without_response() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let pickup = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 10.0 },
+        0.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    let player = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 10.0 },
+        15.0, 0.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_sensor(pickup, true);
+
+    world.step(1.0 / 60.0);
+    assert_eq!(world.get_contacts().len(), 1);
+    assert_eq!(
+        event_kinds(&world.take_collision_events()),
+        vec![(CollisionEventKind::Begin, pickup, player)]
+    );
+    for id in [pickup, player] {
+        let body = world.get_body(id).unwrap();
+        assert_eq!((body.vx, body.vy), (0.0, 0.0), "sensor overlap must not push body {id}");
+    }
+    assert_eq!(world.get_body(player).unwrap().x, 15.0);
+
+    // Near but apart: no contact, unlike the speculative margin of solids
+    world.set_position(player, 20.5, 0.0);
+    world.step(1.0 / 60.0);
+    assert!(world.get_contacts().is_empty());
+    assert_eq!(
+        event_kinds(&world.take_collision_events()),
+        vec![(CollisionEventKind::End, pickup, player)]
+    );
+
+    // Turned back into a solid body, the overlap pushes them apart
+    world.set_sensor(pickup, false);
+    world.set_position(player, 15.0, 0.0);
+    world.step(1.0 / 60.0);
+    assert!(world.get_body(player).unwrap().x > 15.0);
+}
+
+// =========================================================================
+// One-way platforms
+// =========================================================================
+
+fn one_way_platform(world: &mut PhysicsWorld) -> BodyId {
+    let platform = world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 50.0, half_h: 5.0 },
+        0.0, 100.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_one_way(platform, Some((0.0, -2.0)));
+    platform
+}
+
+#[test]
+fn test_one_way_platform_holds_bodies_landing_from_above() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let platform = one_way_platform(&mut world);
+    assert_eq!(world.get_body(platform).unwrap().one_way, Some((0.0, -1.0)));
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 50.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    let y = world.get_body(ball).unwrap().y;
+    assert!((y - 90.0).abs() < 1.0, "ball should rest on top, y={y}");
+}
+
+#[test]
+fn test_one_way_platform_lets_bodies_jump_up_through_and_land() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    one_way_platform(&mut world);
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        0.0, 130.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    // Enough to clear the platform top (y = 95) by about 20 pixels
+    world.set_velocity(ball, 0.0, -300.0);
+    let mut min_y = f32::MAX;
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+        min_y = min_y.min(world.get_body(ball).unwrap().y);
+    }
+    assert!(min_y < 85.0, "ball should pass up through the platform, min y={min_y}");
+    let y = world.get_body(ball).unwrap().y;
+    assert!((y - 90.0).abs() < 1.0, "ball should land on top, y={y}");
+}
+
+#[test]
+fn test_one_way_platform_ignores_sideways_and_can_be_made_solid() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let platform = one_way_platform(&mut world);
+    let ball = world.add_body(
+        BodyType::Dynamic, Shape::Circle { radius: 5.0 },
+        -80.0, 100.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.set_velocity(ball, 200.0, 0.0);
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world.get_body(ball).unwrap().x > 60.0, "ball should cross the platform sideways");
+
+    world.set_one_way(platform, None);
+    world.set_position(ball, -80.0, 100.0);
+    world.set_velocity(ball, 200.0, 0.0);
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world.get_body(ball).unwrap().x < -50.0, "a solid platform should block the ball");
+}
+
+// =========================================================================
+// Deterministic mode and state hash
+// =========================================================================
+
+fn deterministic_pile() -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.set_deterministic(true);
+    world.add_body(
+        BodyType::Static, Shape::AABB { half_w: 200.0, half_h: 10.0 },
+        0.0, 200.0, 0.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    for i in 0..12 {
+        let x = (i % 4) as f32 * 22.0 - 33.0;
+        let y = 150.0 - (i / 4) as f32 * 25.0;
+        let shape = if i % 2 == 0 {
+            Shape::Circle { radius: 10.0 }
+        } else {
+            Shape::AABB { half_w: 10.0, half_h: 10.0 }
+        };
+        world.add_body(BodyType::Dynamic, shape, x, y, 1.0, Material::default(), 0xFFFF, 0xFFFF);
+    }
+    world
+}
+
+#[test]
+fn test_deterministic_worlds_hash_identically() {
+    let mut a = deterministic_pile();
+    let mut b = deterministic_pile();
+    assert!(a.is_deterministic());
+    assert_eq!(a.state_hash(), b.state_hash());
+    // Uneven frame times still run the same fixed steps
+    for i in 0..180 {
+        let dt = if i % 3 == 0 { 1.0 / 30.0 } else { 1.0 / 90.0 };
+        a.step(dt);
+        b.step(dt);
+        assert_eq!(a.state_hash(), b.state_hash(), "worlds diverged at frame {i}");
+    }
+    assert_eq!(a.step_count(), b.step_count());
+    assert!(a.step_count() > 0);
+}
+
+#[test]
+fn test_state_hash_changes_with_state() {
+    let mut world = deterministic_pile();
+    let before = world.state_hash();
+    world.set_velocity(1, 0.0, -1.0);
+    let moved = world.state_hash();
+    assert_ne!(before, moved);
+
+    // A partial frame only changes the accumulator
+    world.step(1.0 / 120.0);
+    assert_eq!(world.step_count(), 0);
+    assert_ne!(world.state_hash(), moved);
+    world.step(1.0 / 120.0);
+    assert_eq!(world.step_count(), 1);
+}
+
+// =========================================================================
+// Snapshot / restore
+// =========================================================================
+
+#[test]
+fn test_snapshot_restore_continues_identically() {
+    let mut world = deterministic_pile();
+    // Free a slot and hang a polygon from a joint so IDs, shapes and
+    // constraints all have to round-trip
+    world.remove_body(3);
+    let poly = world.add_body(
+        BodyType::Dynamic,
+        Shape::Polygon { vertices: vec![(-8.0, -8.0), (8.0, -8.0), (0.0, 8.0)] },
+        80.0, 50.0, 1.0, Material::default(), 0xFFFF, 0xFFFF,
+    );
+    world.remove_body(5);
+    world.add_constraint(Constraint::Revolute {
+        id: 0,
+        body_a: 0,
+        body_b: poly,
+        anchor_a: (80.0, -200.0),
+        anchor_b: (0.0, -8.0),
+        soft: None,
+        accumulated_impulse: (0.0, 0.0),
+        motor: Some(JointMotor::new(1.0, 100.0)),
+    });
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    world.step(1.0 / 200.0); // leave time in the accumulator
+
+    let snapshot = world.snapshot();
+    let mut restored = PhysicsWorld::from_snapshot(&snapshot).unwrap();
+    assert_eq!(restored.snapshot(), snapshot);
+    assert_eq!(restored.state_hash(), world.state_hash());
+    assert!(restored.get_body(5).is_none());
+
+    for i in 0..90 {
+        world.step(1.0 / 60.0);
+        restored.step(1.0 / 60.0);
+        assert_eq!(restored.state_hash(), world.state_hash(), "diverged at step {i}");
+    }
+    // The freed slot is reused the same way
+    let shape = Shape::Circle { radius: 4.0 };
+    let a = world.add_body(BodyType::Dynamic, shape.clone(), 0.0, 0.0, 1.0, Material::default(), 1, 1);
+    let b = restored.add_body(BodyType::Dynamic, shape, 0.0, 0.0, 1.0, Material::default(), 1, 1);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_restore_rejects_invalid_snapshots() {
+    let mut world = deterministic_pile();
+    world.step(1.0 / 60.0);
+    let snapshot = world.snapshot();
+    let hash = world.state_hash();
+
+    assert!(world.restore(b"nope").is_err());
+    assert!(world.restore(&snapshot[..snapshot.len() - 1]).is_err());
+    let mut trailing = snapshot.clone();
+    trailing.push(0);
+    assert!(world.restore(&trailing).is_err());
+    let mut future = snapshot.clone();
+    future[4] = 99; // version
+    assert!(world.restore(&future).unwrap_err().contains("version"));
+    assert_eq!(world.state_hash(), hash, "failed restores leave the world unchanged");
+
+    let mut other = PhysicsWorld::new(0.0, 0.0);
+    other.restore(&snapshot).unwrap();
+    assert_eq!(other.state_hash(), hash);
+}
+
+#[test]
+fn test_restore_rejects_snapshots_with_dangling_body_ids() {
+    let mut world = deterministic_pile();
+    world.add_constraint(Constraint::Distance {
+        id: 0,
+        body_a: 0,
+        body_b: 1,
+        distance: 1234.5,
+        anchor_a: (0.0, 0.0),
+        anchor_b: (0.0, 0.0),
+        soft: None,
+        accumulated_impulse: 0.0,
+    });
+    let snapshot = world.snapshot();
+    let hash = world.state_hash();
+    PhysicsWorld::from_snapshot(&snapshot).unwrap();
+
+    // The joint's body_b is stored just before its distance
+    let distance = 1234.5f32.to_bits().to_le_bytes();
+    let at = snapshot.windows(4).position(|w| w == distance).unwrap() - 4;
+    let mut dangling = snapshot.clone();
+    dangling[at..at + 4].copy_from_slice(&1000u32.to_le_bytes());
+    assert!(world.restore(&dangling).unwrap_err().contains("constraint"));
+
+    // next_id follows the header: magic, version, gravity, dt, accumulator,
+    // step count, deterministic flag, three iteration counts, sleep settings
+    let next_id_at = 4 + 4 + 8 + 4 + 4 + 8 + 1 + 12 + 12;
+    let mut reused = snapshot.clone();
+    reused[next_id_at..next_id_at + 4].copy_from_slice(&1u32.to_le_bytes());
+    assert!(world.restore(&reused).unwrap_err().contains("next body ID"));
+
+    assert_eq!(world.state_hash(), hash, "failed restores leave the world unchanged");
+}
+
+// =========================================================================
+// Rigs from JSON
+// =========================================================================
+
+const PENDULUM_RIG: &str = r#"{
+  "bodies": [
+    { "name": "pivot", "type": "static", "shape": { "type": "circle", "radius": 2 }, "layer": 4, "mask": 0 },
+    { "name": "arm", "shape": { "type": "box", "halfW": 20, "halfH": 2 }, "x": 20, "y": 0, "angle": 0.5, "layer": 2, "mask": 1 },
+    { "name": "bob", "shape": { "type": "circle", "radius": 5 }, "x": 40, "y": 0, "mass": 2, "layer": 2, "mask": 1 }
+  ],
+  "joints": [
+    { "name": "hinge", "type": "revolute", "bodyA": "pivot", "bodyB": "arm", "anchor": { "x": 0, "y": 0 } },
+    { "name": "tip", "type": "weld", "bodyA": "arm", "bodyB": "bob", "anchor": [40, 0] },
+    { "name": "leash", "type": "rope", "bodyA": "pivot", "bodyB": "bob", "maxLength": 60 }
+  ]
+}"#;
+
+#[test]
+fn test_rig_spawns_bodies_and_joints_by_name() {
+    let rig = Rig::parse(PENDULUM_RIG).unwrap();
+    assert_eq!(rig.bodies.len(), 3);
+    assert!(matches!(rig.joints[2].kind, RigJointKind::Rope { max_length: Some(l), .. } if l == 60.0));
+
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.add_body(BodyType::Static, Shape::Circle { radius: 1.0 }, 0.0, 0.0, 0.0, Material::default(), 1, 1);
+    let instance = rig.spawn(&mut world, 100.0, 50.0);
+    let names: Vec<&str> = instance.bodies.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["pivot", "arm", "bob"]);
+    assert_eq!(instance.joints.len(), 3);
+
+    let arm = world.get_body(instance.bodies[1].1).unwrap();
+    assert_eq!((arm.x, arm.y, arm.angle), (120.0, 50.0, 0.5));
+    assert_eq!(world.get_body(instance.bodies[2].1).unwrap().mass, 2.0);
+    assert_eq!(
+        instance.to_json(),
+        format!(
+            "{{\"bodies\":{{\"pivot\":{},\"arm\":{},\"bob\":{}}},\"joints\":{{\"hinge\":{},\"tip\":{},\"leash\":{}}}}}",
+            instance.bodies[0].1, instance.bodies[1].1, instance.bodies[2].1,
+            instance.joints[0].1, instance.joints[1].1, instance.joints[2].1,
+        )
+    );
+}
+
+#[test]
+fn test_rig_anchors_are_local_to_each_body() {
+    let rig = Rig::parse(PENDULUM_RIG).unwrap();
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let instance = rig.spawn(&mut world, 100.0, 50.0);
+    let hinge = world.constraints().iter().find(|c| c.id() == instance.joints[0].1).unwrap();
+    let Constraint::Revolute { anchor_a, anchor_b, .. } = hinge else { panic!("not revolute") };
+    assert_eq!(*anchor_a, (0.0, 0.0));
+    // The arm is rotated 0.5 rad; the pivot 20 units to its left, in arm space
+    assert!((anchor_b.0 + 20.0 * 0.5f32.cos()).abs() < 1e-4);
+    assert!((anchor_b.1 - 20.0 * 0.5f32.sin()).abs() < 1e-4);
+
+    // Without gravity the jointed rig holds still
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let bob = world.get_body(instance.bodies[2].1).unwrap();
+    assert!((bob.x - 140.0).abs() < 0.01 && (bob.y - 50.0).abs() < 0.01);
+}
+
+#[test]
+fn test_rig_errors_name_the_problem() {
+    let err = Rig::parse(r#"{ "bodies": [{ "name": "a", "shape": { "type": "blob" } }] }"#).unwrap_err();
+    assert!(err.contains("Body 'a'") && err.contains("blob"), "{err}");
+    let err = Rig::parse(
+        r#"{ "bodies": [{ "name": "a", "shape": { "type": "circle", "radius": 1 } }],
+             "joints": [{ "name": "j", "type": "weld", "bodyA": "a", "bodyB": "b", "anchor": [0, 0] }] }"#,
+    )
+    .unwrap_err();
+    assert!(err.contains("Joint 'j'") && err.contains("unknown body 'b'"), "{err}");
+    assert!(Rig::parse(r#"{ "joints": [] }"#).is_err());
+    assert!(Rig::parse("not json").is_err());
+}
+
+// =========================================================================
+// Vehicles
+// =========================================================================
+
+/// A 60x20 car (mass 10) dropped just above a long static floor at y = 100,
+/// with two wheels hanging below the chassis.
+fn side_view_car(world: &mut PhysicsWorld) -> (BodyId, u32) {
+    world.add_body(BodyType::Static, Shape::AABB { half_w: 5000.0, half_h: 10.0 }, 0.0, 110.0, 0.0, Material::default(), 1, 0xFFFF);
+    let chassis = world.add_body(BodyType::Dynamic, Shape::OBB { half_w: 30.0, half_h: 10.0 }, 0.0, 60.0, 10.0, Material::default(), 2, 1);
+    let wheel = |x: f32| WheelDef { anchor: (x, 10.0), radius: 8.0, suspension_length: 12.0, ..WheelDef::default() };
+    let def = VehicleDef { wheels: vec![wheel(-20.0), wheel(20.0)], ground_mask: 1, ..VehicleDef::default() };
+    let vehicle = world.add_vehicle(chassis, def).unwrap();
+    (chassis, vehicle)
+}
+
+fn run(world: &mut PhysicsWorld, seconds: f32) {
+    for _ in 0..(seconds * 60.0).round() as u32 {
+        world.step(1.0 / 60.0);
+    }
+}
+
+#[test]
+fn test_vehicle_rests_on_its_suspension() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let (chassis, vehicle) = side_view_car(&mut world);
+    run(&mut world, 3.0);
+
+    let body = world.get_body(chassis).unwrap();
+    // Held up by the springs, not the chassis box: its bottom stays above the floor
+    assert!(body.y + 10.0 < 100.0, "chassis sank to y = {}", body.y);
+    assert!(body.vy.abs() < 1.0 && body.angle.abs() < 0.01);
+    let v = world.get_vehicle(vehicle).unwrap();
+    for (i, wheel) in v.wheels.iter().enumerate() {
+        assert!(wheel.ground.is_some());
+        assert!(wheel.length < 12.0, "wheel {i} is not compressed");
+        // The wheel sits on the floor
+        let (_, y) = v.wheel_position(body, i).unwrap();
+        assert!((y + 8.0 - 100.0).abs() < 0.5, "wheel {i} center at y = {y}");
+    }
+}
+
+#[test]
+fn test_vehicle_drives_brakes_and_tops_out() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    let (chassis, vehicle) = side_view_car(&mut world);
+    run(&mut world, 1.0);
+
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: 1.0, ..VehicleInput::default() });
+    run(&mut world, 0.5);
+    let body = world.get_body(chassis).unwrap();
+    assert!(body.vx > 80.0, "slow start: vx = {}", body.vx);
+    assert!(world.get_vehicle(vehicle).unwrap().wheels[0].spin > 0.0);
+
+    // The engine fades out at the top speed
+    run(&mut world, 4.0);
+    let top = world.get_body(chassis).unwrap().vx;
+    assert!(top > 300.0 && top <= 401.0, "top speed {top}");
+
+    world.set_vehicle_input(vehicle, VehicleInput { brake: 1.0, ..VehicleInput::default() });
+    run(&mut world, 2.0);
+    assert!(world.get_body(chassis).unwrap().vx.abs() < 5.0);
+
+    // Reverse
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: -1.0, ..VehicleInput::default() });
+    run(&mut world, 0.5);
+    assert!(world.get_body(chassis).unwrap().vx < -50.0);
+}
+
+#[test]
+fn test_vehicle_wheels_spin_past_their_grip() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.add_body(BodyType::Static, Shape::AABB { half_w: 5000.0, half_h: 10.0 }, 0.0, 110.0, 0.0, Material::default(), 1, 0xFFFF);
+    let chassis = world.add_body(BodyType::Dynamic, Shape::OBB { half_w: 30.0, half_h: 10.0 }, 0.0, 60.0, 10.0, Material::default(), 2, 1);
+    let wheel = |x: f32| WheelDef { anchor: (x, 10.0), radius: 8.0, friction: 0.2, ..WheelDef::default() };
+    let def = VehicleDef { wheels: vec![wheel(-20.0), wheel(20.0)], acceleration: 2000.0, max_speed: 2000.0, ..VehicleDef::default() };
+    let vehicle = world.add_vehicle(chassis, def).unwrap();
+    run(&mut world, 1.0);
+
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: 1.0, ..VehicleInput::default() });
+    run(&mut world, 0.5);
+    let v = world.get_vehicle(vehicle).unwrap();
+    let body = world.get_body(chassis).unwrap();
+    // Grip caps the acceleration near friction * gravity; the wheels spin faster
+    assert!(body.vx < 0.2 * 400.0 * 0.5 * 1.1, "vx = {}", body.vx);
+    assert!(v.wheels[0].spin * 8.0 > body.vx * 2.0);
+    assert!(v.wheels[0].slip > 0.2);
+}
+
+#[test]
+fn test_top_down_vehicle_steers_without_sliding() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let chassis = world.add_body(BodyType::Dynamic, Shape::OBB { half_w: 20.0, half_h: 10.0 }, 0.0, 0.0, 5.0, Material::default(), 1, 1);
+    let front = |y: f32| WheelDef { anchor: (15.0, y), drive: false, steer: true, ..WheelDef::default() };
+    let rear = |y: f32| WheelDef { anchor: (-15.0, y), ..WheelDef::default() };
+    let def = VehicleDef {
+        view: VehicleView::TopDown,
+        wheels: vec![front(-8.0), front(8.0), rear(-8.0), rear(8.0)],
+        max_speed: 200.0,
+        ..VehicleDef::default()
+    };
+    let vehicle = world.add_vehicle(chassis, def).unwrap();
+
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: 0.5, ..VehicleInput::default() });
+    run(&mut world, 1.0);
+    let body = world.get_body(chassis).unwrap();
+    assert!(body.vx > 50.0 && body.vy.abs() < 1.0 && body.angle.abs() < 1e-3);
+
+    // Steering right turns clockwise, and the rear axle follows where the car points
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: 0.5, steer: 1.0, ..VehicleInput::default() });
+    run(&mut world, 0.5);
+    let body = world.get_body(chassis).unwrap();
+    assert!(body.angle > 0.2, "angle {}", body.angle);
+    let (sin, cos) = body.angle.sin_cos();
+    let (rx, ry) = (-15.0 * cos, -15.0 * sin);
+    let (vx, vy) = (body.vx - body.angular_velocity * ry, body.vy + body.angular_velocity * rx);
+    let side_speed = -vx * sin + vy * cos;
+    assert!(side_speed.abs() < 0.05 * vx.hypot(vy), "rear axle sliding at {side_speed}");
+}
+
+#[test]
+fn test_vehicle_survives_snapshots_and_leaves_with_its_chassis() {
+    let mut world = PhysicsWorld::new(0.0, 400.0);
+    world.set_deterministic(true);
+    let (chassis, vehicle) = side_view_car(&mut world);
+    world.set_vehicle_input(vehicle, VehicleInput { throttle: 1.0, ..VehicleInput::default() });
+    run(&mut world, 0.5);
+
+    let mut copy = PhysicsWorld::from_snapshot(&world.snapshot()).unwrap();
+    assert_eq!(copy.get_vehicle(vehicle), world.get_vehicle(vehicle));
+    run(&mut world, 0.5);
+    run(&mut copy, 0.5);
+    assert_eq!(copy.state_hash(), world.state_hash());
+
+    // Vehicles need a dynamic chassis and at least one wheel
+    assert!(world.add_vehicle(0, VehicleDef { wheels: vec![WheelDef::default()], ..VehicleDef::default() }).is_none());
+    assert!(world.add_vehicle(chassis, VehicleDef::default()).is_none());
+    world.remove_body(chassis);
+    assert!(world.get_vehicle(vehicle).is_none());
+}
+
+// =========================================================================
+// Projectiles
+// =========================================================================
+
+fn bullet(x: f32, y: f32, vx: f32, vy: f32, tag: u32) -> Projectile {
+    Projectile { x, y, vx, vy, ax: 0.0, ay: 0.0, life: 0.0, tag }
+}
+
+#[test]
+fn test_projectile_hits_the_first_body_on_its_path() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let near = world.add_body(BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 50.0 }, 100.0, 0.0, 0.0, Material::default(), 1, 0xFFFF);
+    world.add_body(BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 50.0 }, 120.0, 0.0, 0.0, Material::default(), 1, 0xFFFF);
+    let batch = world.add_projectile_batch(ProjectileBatchDef::default());
+    // Fast enough to pass both walls in one step: the sweep still stops it at the first
+    assert_eq!(world.spawn_projectiles(batch, [bullet(0.0, 0.0, 9000.0, 0.0, 7)]), 1);
+    world.step(1.0 / 60.0);
+
+    let hits = world.take_projectile_hits();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].batch, hits[0].tag, hits[0].body), (batch, 7, near));
+    assert!((hits[0].point.0 - 95.0).abs() < 0.01 && hits[0].point.1.abs() < 0.01);
+    assert_eq!(hits[0].velocity, (9000.0, 0.0));
+    assert!(world.get_projectile_batch(batch).unwrap().projectiles().is_empty());
+    assert!(world.take_projectile_hits().is_empty());
+}
+
+#[test]
+fn test_projectiles_accelerate_expire_and_respect_capacity() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    let batch = world.add_projectile_batch(ProjectileBatchDef { capacity: 2, ..ProjectileBatchDef::default() });
+    let arcing = Projectile { ay: 600.0, life: 0.5, ..bullet(0.0, 0.0, 60.0, 0.0, 1) };
+    let spawned = world.spawn_projectiles(batch, [arcing, bullet(0.0, 0.0, 0.0, 0.0, 2), bullet(0.0, 0.0, 0.0, 0.0, 3)]);
+    assert_eq!(spawned, 2);
+
+    run(&mut world, 0.25);
+    let p = world.get_projectile_batch(batch).unwrap().projectiles()[0];
+    assert!((p.x - 15.0).abs() < 0.01, "x = {}", p.x);
+    assert!((p.vy - 150.0).abs() < 0.01 && p.y > 15.0);
+
+    run(&mut world, 0.5);
+    let left = world.get_projectile_batch(batch).unwrap().projectiles();
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].tag, 2);
+    assert!(world.take_projectile_hits().is_empty());
+
+    world.clear_projectiles(batch);
+    assert!(world.get_projectile_batch(batch).unwrap().projectiles().is_empty());
+    assert_eq!(world.spawn_projectiles(batch + 1, [arcing]), 0);
+}
+
+#[test]
+fn test_projectile_radius_mask_and_sensors() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    world.add_body(BodyType::Static, Shape::Circle { radius: 10.0 }, 50.0, 0.0, 0.0, Material::default(), 2, 0xFFFF);
+    let sensor = world.add_body(BodyType::Static, Shape::Circle { radius: 10.0 }, 50.0, 40.0, 0.0, Material::default(), 1, 0xFFFF);
+    world.set_sensor(sensor, true);
+    let target = world.add_body(BodyType::Dynamic, Shape::Circle { radius: 10.0 }, 50.0, 80.0, 1.0, Material::default(), 1, 0xFFFF);
+
+    // Layer 2 is not in the mask; the sensor is never hit
+    let batch = world.add_projectile_batch(ProjectileBatchDef { radius: 4.0, mask: 1, ..ProjectileBatchDef::default() });
+    world.spawn_projectiles(batch, [bullet(0.0, 0.0, 600.0, 0.0, 1), bullet(0.0, 40.0, 600.0, 0.0, 2)]);
+    // Misses the center line by 12 but its radius reaches the target
+    world.spawn_projectiles(batch, [bullet(0.0, 92.0, 600.0, 0.0, 3)]);
+    run(&mut world, 0.5);
+
+    let hits = world.take_projectile_hits();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].tag, hits[0].body), (3, target));
+    // Projectiles never push bodies
+    assert_eq!(world.get_body(target).unwrap().vx, 0.0);
+}
+
+#[test]
+fn test_projectiles_survive_snapshots() {
+    let mut world = PhysicsWorld::new(0.0, 0.0);
+    world.set_deterministic(true);
+    world.add_body(BodyType::Static, Shape::AABB { half_w: 5.0, half_h: 500.0 }, 300.0, 0.0, 0.0, Material::default(), 1, 0xFFFF);
+    let batch = world.add_projectile_batch(ProjectileBatchDef { capacity: 64, radius: 2.0, mask: 1 });
+    let shots: Vec<_> = (0..40).map(|i| Projectile { ay: 50.0, life: 2.0, ..bullet(0.0, i as f32 * 5.0, 200.0 + i as f32, -20.0, i) }).collect();
+    world.spawn_projectiles(batch, shots);
+    run(&mut world, 0.5);
+
+    let mut copy = PhysicsWorld::from_snapshot(&world.snapshot()).unwrap();
+    assert_eq!(copy.get_projectile_batch(batch), world.get_projectile_batch(batch));
+    run(&mut world, 1.0);
+    run(&mut copy, 1.0);
+    assert_eq!(copy.state_hash(), world.state_hash());
+    let hits = world.take_projectile_hits();
+    assert!(!hits.is_empty());
+    assert_eq!(copy.take_projectile_hits(), hits);
+
+    world.remove_projectile_batch(batch);
+    assert!(world.get_projectile_batch(batch).is_none());
+}
@@ -6,7 +6,7 @@
 
 use arcane_core::renderer::camera::Camera2D;
 use arcane_core::renderer::postprocess::EffectType;
-use arcane_core::renderer::test_harness::{clear_target, TestGpu};
+use arcane_core::renderer::test_harness::{clear_target, TestGpu, TestRenderTarget};
 use arcane_core::scripting::geometry_ops::GeoCommand;
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -548,7 +548,7 @@ fn test_sprite_pipeline_prepare() {
 #[ignore] // requires GPU
 fn test_sprite_render_white_sprite() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -603,7 +603,7 @@ fn test_sprite_render_white_sprite() {
 #[ignore] // requires GPU
 fn test_sprite_render_with_tint() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -906,7 +906,7 @@ fn make_sprite(tex_id: u32, x: f32, y: f32, w: f32, h: f32, layer: i32) -> arcan
         tint_r: 1.0, tint_g: 1.0, tint_b: 1.0, tint_a: 1.0,
         rotation: 0.0, origin_x: 0.5, origin_y: 0.5,
         flip_x: false, flip_y: false, opacity: 1.0,
-        blend_mode: 0, shader_id: 0,
+        blend_mode: 0, shader_id: 0, nine_slice: None, outline: None, clip: None, mask: None, normal_map: None, flash: None, pixel_snap: None,
     }
 }
 
@@ -914,7 +914,7 @@ fn make_sprite(tex_id: u32, x: f32, y: f32, w: f32, h: f32, layer: i32) -> arcan
 #[ignore] // requires GPU
 fn test_sprite_opacity_half() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -947,11 +947,227 @@ fn test_sprite_opacity_half() {
         "50% opacity white over black should be ~127, got {}", center[0]);
 }
 
+/// Draw a full-target white sprite clipped to a triangle mask over the
+/// top-left corner, and return the pixels.
+fn render_masked_sprite(gpu: &TestGpu, target: &TestRenderTarget, invert: bool) -> Vec<u8> {
+    use arcane_core::renderer::{ColorTarget, MaskPipeline, MaskTarget, SpriteMask};
+
+    let mut sprites = gpu.create_sprite_pipeline();
+    let mut textures = gpu.create_texture_store();
+    let shaders = gpu.create_shader_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let lighting = arcane_core::renderer::LightingState::default().to_uniform();
+    let tex_id = textures.create_solid_color(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        "white", 255, 255, 255, 255,
+    );
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    let mut mask_pipeline = MaskPipeline::new(&gpu.device);
+    let mut mask_target = MaskTarget::default();
+    let stencil = mask_target.view(&gpu.device, 64, 64, 1);
+    let corner = GeoCommand::Triangle {
+        x1: 0.0, y1: 0.0, x2: 32.0, y2: 0.0, x3: 0.0, y3: 32.0,
+        r: 1.0, g: 1.0, b: 1.0, a: 1.0,
+        layer: 0,
+    };
+    let mut cmd = make_sprite(tex_id, 0.0, 0.0, 64.0, 64.0, 0);
+    cmd.mask = Some(SpriteMask { id: 1, invert });
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    mask_pipeline.write(&gpu.device, &mut encoder, &stencil, 1, sprites.camera_bind_group(), &[vec![corner]]);
+    sprites.render(
+        &gpu.device, &gpu.queue, &textures, &shaders,
+        &[cmd], ColorTarget::from(&target.view).with_stencil(&stencil), &mut encoder, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+    target.read_pixels(gpu).expect("Failed to read pixels")
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_masked_sprite_draws_only_inside_the_mask() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    let pixels = render_masked_sprite(&gpu, &target, false);
+    assert!(target.pixel_matches(&pixels, 4, 4, [255, 255, 255, 255], 5));
+    assert!(target.pixel_matches(&pixels, 60, 60, [0, 0, 0, 255], 5));
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_inverted_mask_draws_only_outside_the_mask() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    let pixels = render_masked_sprite(&gpu, &target, true);
+    assert!(target.pixel_matches(&pixels, 4, 4, [0, 0, 0, 255], 5));
+    assert!(target.pixel_matches(&pixels, 60, 60, [255, 255, 255, 255], 5));
+}
+
+/// A white sprite whose normal map faces left on its left half and right on
+/// its right half, lit only by a directional light shining to the right.
+fn render_normal_mapped_sprite(gpu: &TestGpu, target: &TestRenderTarget, flip_x: bool) -> Vec<u8> {
+    use arcane_core::renderer::{DirectionalLight, LightingState};
+
+    let mut sprites = gpu.create_sprite_pipeline();
+    let mut textures = gpu.create_texture_store();
+    let shaders = gpu.create_shader_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let sun = DirectionalLight { angle: 0.0, r: 1.0, g: 1.0, b: 1.0, intensity: 1.0 };
+    let lighting = LightingState { ambient: [0.0; 3], lights: Vec::new() }
+        .to_uniform()
+        .with_directional(&[sun]);
+    let tex_id = textures.create_solid_color(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        "white", 255, 255, 255, 255,
+    );
+    let normal_id = 2100;
+    textures.upload_raw_linear(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        normal_id, &[0, 128, 128, 255, 255, 128, 128, 255], 2, 1,
+    );
+    let mut cmd = make_sprite(tex_id, 0.0, 0.0, 64.0, 64.0, 0);
+    cmd.flip_x = flip_x;
+    if flip_x {
+        cmd.normal_map = Some(normal_id);
+    } else {
+        textures.set_normal_map(tex_id, normal_id);
+    }
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    sprites.render(
+        &gpu.device, &gpu.queue, &textures, &shaders,
+        &[cmd], &target.view, &mut encoder, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+    target.read_pixels(gpu).expect("Failed to read pixels")
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_normal_map_shades_the_side_facing_the_light() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    let pixels = render_normal_mapped_sprite(&gpu, &target, false);
+    // Facing the light at 45°: cos 45° of full white; facing away: unlit
+    assert!(target.pixel_matches(&pixels, 8, 32, [180, 180, 180, 255], 5));
+    assert!(target.pixel_matches(&pixels, 56, 32, [0, 0, 0, 255], 5));
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_flipped_normal_map_still_faces_the_light() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    // Flipped, the left half shows the right-facing texel, mirrored to face left
+    let pixels = render_normal_mapped_sprite(&gpu, &target, true);
+    assert!(target.pixel_matches(&pixels, 8, 32, [180, 180, 180, 255], 5));
+    assert!(target.pixel_matches(&pixels, 56, 32, [0, 0, 0, 255], 5));
+}
+
+/// A white 64x64 sprite lit by a point light at its left edge, with a wall
+/// (occluder) across the middle.
+fn render_wall_shadow(gpu: &TestGpu, target: &TestRenderTarget, shadow_softness: Option<f32>) -> Vec<u8> {
+    use arcane_core::renderer::{LightingState, Occluder, PointLight};
+
+    let mut sprites = gpu.create_sprite_pipeline();
+    let mut textures = gpu.create_texture_store();
+    let shaders = gpu.create_shader_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let lamp = PointLight {
+        x: 4.0, y: 32.0, radius: 400.0, r: 1.0, g: 1.0, b: 1.0, intensity: 1.0,
+        animation: None, shadow_softness,
+    };
+    let wall = Occluder { x: 30.0, y: 0.0, width: 4.0, height: 64.0 };
+    let lighting = LightingState { ambient: [0.0; 3], lights: vec![lamp] }
+        .to_uniform()
+        .with_occluders(&[wall]);
+    let tex_id = textures.create_solid_color(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        "white", 255, 255, 255, 255,
+    );
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    sprites.render(
+        &gpu.device, &gpu.queue, &textures, &shaders,
+        &[make_sprite(tex_id, 0.0, 0.0, 64.0, 64.0, 0)], &target.view, &mut encoder, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+    target.read_pixels(gpu).expect("Failed to read pixels")
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_point_light_shadow_behind_occluder() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    let pixels = render_wall_shadow(&gpu, &target, Some(0.0));
+    let lit = |x: u32| target.get_pixel(&pixels, x, 32)[0];
+    // In front of the wall and on the wall itself: lit; behind it: dark
+    assert!(lit(16) > 200, "front {}", lit(16));
+    assert!(lit(32) > 200, "wall {}", lit(32));
+    assert!(target.pixel_matches(&pixels, 56, 32, [0, 0, 0, 255], 2));
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_lights_without_shadows_ignore_occluders() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let target = gpu.create_target(64, 64);
+    let pixels = render_wall_shadow(&gpu, &target, None);
+    assert!(target.get_pixel(&pixels, 56, 32)[0] > 200);
+}
+
+#[test]
+#[ignore] // requires GPU
+fn test_sprite_flash_mixes_towards_its_color() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let mut sprites = gpu.create_sprite_pipeline();
+    let mut textures = gpu.create_texture_store();
+    let shaders = gpu.create_shader_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let lighting = arcane_core::renderer::LightingState::default().to_uniform();
+
+    // Solid blue texture, flashed half way to white
+    let tex_id = 2100;
+    textures.upload_raw(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        tex_id, &[0, 0, 255, 255], 1, 1,
+    );
+    let target = gpu.create_target(64, 64);
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    let mut cmd = make_sprite(tex_id, 0.0, 0.0, 64.0, 64.0, 0);
+    cmd.flash = Some(arcane_core::renderer::SpriteFlash { r: 1.0, g: 1.0, b: 1.0, amount: 0.5 });
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    sprites.render(
+        &gpu.device, &gpu.queue, &textures, &shaders,
+        &[cmd], &target.view, &mut encoder, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+
+    let pixels = target.read_pixels(&gpu).expect("Failed to read pixels");
+    let p = target.get_pixel(&pixels, 32, 32);
+    assert!((100..=160).contains(&p[0]), "Red should be about half, got {:?}", p);
+    assert!(p[2] > 240, "Blue should stay full, got {:?}", p);
+}
+
 #[test]
 #[ignore] // requires GPU
 fn test_sprite_flip_x() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -993,7 +1209,7 @@ fn test_sprite_flip_x() {
 #[ignore] // requires GPU
 fn test_sprite_flip_y() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -1034,7 +1250,7 @@ fn test_sprite_flip_y() {
 #[ignore] // requires GPU
 fn test_sprite_layer_ordering() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -1086,7 +1302,7 @@ fn test_sprite_layer_ordering() {
 #[ignore] // requires GPU
 fn test_sprite_rotation() {
     let gpu = TestGpu::new().expect("Failed to create GPU context");
-    let sprites = gpu.create_sprite_pipeline();
+    let mut sprites = gpu.create_sprite_pipeline();
     let mut textures = gpu.create_texture_store();
     let shaders = gpu.create_shader_store();
     let camera = gpu.create_camera(64.0, 64.0);
@@ -1125,6 +1341,53 @@ fn test_sprite_rotation() {
 // Expanded ShaderStore tests
 // ═══════════════════════════════════════════════════════════════════════════
 
+#[test]
+#[ignore] // requires GPU
+fn test_sprite_nine_slice_stretches_center_only() {
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let mut sprites = gpu.create_sprite_pipeline();
+    let mut textures = gpu.create_texture_store();
+    let shaders = gpu.create_shader_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let lighting = arcane_core::renderer::LightingState::default().to_uniform();
+
+    // 3x3 texture: red 1px border around a green center
+    let r = [255, 0, 0, 255];
+    let g = [0, 255, 0, 255];
+    let pixels_data: Vec<u8> = [r, r, r, r, g, r, r, r, r].concat();
+    let tex_id = 2000;
+    textures.upload_raw(
+        &gpu.device, &gpu.queue, &sprites.texture_bind_group_layout,
+        tex_id, &pixels_data, 3, 3,
+    );
+
+    let target = gpu.create_target(64, 64);
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    let mut cmd = make_sprite(tex_id, 0.0, 0.0, 64.0, 64.0, 0);
+    cmd.nine_slice = Some(arcane_core::renderer::NineSlice {
+        left: 1.0, top: 1.0, right: 1.0, bottom: 1.0, scale: 1.0,
+    });
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    sprites.render(
+        &gpu.device, &gpu.queue, &textures, &shaders,
+        &[cmd], &target.view, &mut encoder, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+
+    let pixels = target.read_pixels(&gpu).expect("Failed to read pixels");
+    // The border stays 1px wide; the green center fills the rest
+    let edge = target.get_pixel(&pixels, 0, 32);
+    let inside = target.get_pixel(&pixels, 4, 32);
+    let center = target.get_pixel(&pixels, 32, 32);
+    assert!(edge[0] > edge[1], "Border should be red, got {:?}", edge);
+    assert!(inside[1] > inside[0], "Just inside the border should be green, got {:?}", inside);
+    assert!(center[1] > 200 && center[0] < 50, "Center should be green, got {:?}", center);
+}
+
 #[test]
 #[ignore] // requires GPU
 fn test_shader_multiple_params() {
@@ -1269,3 +1532,88 @@ fn test_texture_overwrite() {
     assert!(textures.get_bind_group(tex_id).is_some());
     assert_eq!(textures.get_dimensions(tex_id), Some((8, 8)));
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// GPU particle tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+#[ignore] // requires GPU
+fn test_gpu_particle_burst_draws_at_emitter() {
+    use arcane_core::renderer::{EmitterDef, GpuParticleCommand};
+
+    let gpu = TestGpu::new().expect("Failed to create GPU context");
+    let sprites = gpu.create_sprite_pipeline();
+    let mut particles = gpu.create_gpu_particles();
+    let textures = gpu.create_texture_store();
+    let camera = gpu.create_camera(64.0, 64.0);
+    let lighting = arcane_core::renderer::LightingState::default().to_uniform();
+    let target = gpu.create_target(64, 64);
+    sprites.prepare(&gpu.device, &gpu.queue, &camera, &lighting);
+
+    // Stationary, long-lived red particles filling the center
+    let def = EmitterDef {
+        x: 32.0,
+        y: 32.0,
+        rate: 0.0,
+        speed: [0.0, 0.0],
+        lifetime: [10.0, 10.0],
+        size: [16.0, 16.0],
+        color_start: [1.0, 0.0, 0.0, 1.0],
+        color_end: [1.0, 0.0, 0.0, 1.0],
+        ..EmitterDef::default()
+    };
+    particles.apply(&gpu.device, GpuParticleCommand::Create { id: 1, def });
+    particles.apply(&gpu.device, GpuParticleCommand::Burst { id: 1, count: 8 });
+    assert_eq!(particles.emitter_count(), 1);
+
+    let mut encoder = gpu.device.create_command_encoder(
+        &wgpu::CommandEncoderDescriptor { label: None },
+    );
+    particles.simulate(&gpu.queue, &mut encoder, 1.0 / 60.0);
+    let drew = particles.render(
+        &mut encoder, &target.view, sprites.camera_bind_group(),
+        &textures, None, Some(wgpu::Color::BLACK),
+    );
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+    assert!(drew);
+
+    let pixels = target.read_pixels(&gpu).expect("Failed to read pixels");
+    let center = target.get_pixel(&pixels, 32, 32);
+    assert!(center[0] > 128, "expected red at the emitter, got {center:?}");
+    let corner = target.get_pixel(&pixels, 2, 2);
+    assert_eq!(corner[0], 0, "expected nothing far from the emitter, got {corner:?}");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Layered capture tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+#[ignore] // requires GPU
+fn test_layer_capture_writes_each_layer_alone() {
+    let mut renderer = arcane_core::renderer::Renderer::new_headless(32, 32, 8)
+        .expect("Failed to create headless renderer");
+    let tex_id = 3000;
+    renderer.textures.upload_raw(
+        &renderer.gpu.device, &renderer.gpu.queue, &renderer.sprites.texture_bind_group_layout,
+        tex_id, &[255, 255, 255, 255], 1, 1,
+    );
+    // Left half on layer 0, right half on layer 2
+    renderer.frame_commands = vec![
+        make_sprite(tex_id, 0.0, 0.0, 16.0, 32.0, 0),
+        make_sprite(tex_id, 16.0, 0.0, 16.0, 32.0, 2),
+    ];
+    let out = std::env::temp_dir().join(format!("arcane_layer_capture_{}", std::process::id()));
+    renderer.layer_capture_pending = Some(out.clone());
+    renderer.render_frame().expect("Failed to render");
+
+    let layer = |name: &str| image::open(out.join(name)).expect("missing layer image").to_rgba8();
+    let (bottom, top) = (layer("layer_0.png"), layer("layer_2.png"));
+    assert_eq!(bottom.get_pixel(8, 16)[3], 255);
+    assert_eq!(bottom.get_pixel(24, 16)[3], 0, "layer 0 must not contain layer 2");
+    assert_eq!(top.get_pixel(8, 16)[3], 0);
+    assert_eq!(top.get_pixel(24, 16)[3], 255);
+    assert!(out.join("merged.png").exists());
+    let _ = std::fs::remove_dir_all(&out);
+}
@@ -80,3 +80,21 @@ fn test_key_sanitization() {
     assert!(!"has spaces".chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-'));
     assert!(!"has.dots".chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-'));
 }
+
+#[test]
+fn test_local_backend_uses_the_same_layout() {
+    use arcane_core::saves::{valid_key, LocalDirBackend, SaveBackend};
+
+    let (save_dir, _tmp) = setup_save_dir();
+    fs::write(save_dir.join("save1.json"), "{}").unwrap();
+    // Sync manifests live next to the saves but are not saves
+    fs::write(save_dir.join(".sync-steam"), "save1 0\n").unwrap();
+    let local = LocalDirBackend::new(&save_dir);
+    local.write("save2", "{\"score\":1}", 1_000).unwrap();
+    let keys: Vec<String> = local.list().unwrap().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["save1", "save2"]);
+    assert_eq!(fs::read_to_string(save_dir.join("save2.json")).unwrap(), "{\"score\":1}");
+
+    assert!(valid_key("my-save") && valid_key("save_01"));
+    assert!(!valid_key("../etc/passwd") && !valid_key("has.dots") && !valid_key(""));
+}
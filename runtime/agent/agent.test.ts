@@ -318,6 +318,23 @@ describe("captureSnapshot", () => {
   });
 });
 
+// --- restoreSnapshot ---
+
+describe("restoreSnapshot", () => {
+  it("puts back a captured state without sharing references", () => {
+    const agent = makeAgent();
+    const snap = agent.captureSnapshot();
+    agent.executeAction("wait");
+    agent.executeAction("wait");
+    assert.equal(agent.getState().turn, 3);
+
+    agent.restoreSnapshot(snap);
+    assert.equal(agent.getState().turn, 1);
+    (snap.state as any).turn = 999;
+    assert.equal(agent.getState().turn, 1);
+  });
+});
+
 // --- defaultDescribe ---
 
 describe("defaultDescribe", () => {
@@ -9,6 +9,7 @@ import type {
 } from "./types.ts";
 import { defaultDescribe } from "./describe.ts";
 import { get } from "../state/query.ts";
+import { capturePhysicsSnapshot, restorePhysicsSnapshot } from "../physics/world.ts";
 
 declare const globalThis: { __arcaneAgent?: AgentProtocol<unknown> };
 
@@ -66,6 +67,8 @@ export function registerAgent<S>(config: AgentConfig<S>): AgentProtocol<S> {
     state: deepClone(getState()),
     timestamp: Date.now(),
   };
+  // TS state alone would leave physics bodies where they are on rewind
+  const initialPhysics = capturePhysicsSnapshot();
 
   const actions = config.actions ?? {};
   const describeFn = config.describe ?? defaultDescribe;
@@ -142,6 +145,7 @@ export function registerAgent<S>(config: AgentConfig<S>): AgentProtocol<S> {
     },
 
     rewind(): S {
+      if (initialPhysics) restorePhysicsSnapshot(initialPhysics);
       setState(deepClone(initialSnapshot.state));
       return deepClone(initialSnapshot.state);
     },
@@ -152,6 +156,11 @@ export function registerAgent<S>(config: AgentConfig<S>): AgentProtocol<S> {
         timestamp: Date.now(),
       };
     },
+
+    restoreSnapshot(snapshot: SnapshotData<S>): S {
+      setState(deepClone(snapshot.state));
+      return deepClone(snapshot.state);
+    },
   };
 
   (globalThis as any).__arcaneAgent = protocol;
@@ -151,8 +151,13 @@ export type AgentProtocol<S> = Readonly<{
   executeAction: (name: string, argsJson?: string) => ActionResult<S>;
   /** Simulate a named action without committing. Returns hypothetical state. */
   simulateAction: (name: string, argsJson?: string) => SimulateResult<S>;
-  /** Reset state to the initial snapshot captured at registerAgent() time. */
+  /**
+   * Reset state to the initial snapshot captured at registerAgent() time.
+   * The physics world, if one existed then, is restored with it.
+   */
   rewind: () => S;
   /** Capture a deep clone of the current state as a snapshot. */
   captureSnapshot: () => SnapshotData<S>;
+  /** Replace the current state with a deep clone of a captured snapshot (used by dev quick load). */
+  restoreSnapshot: (snapshot: SnapshotData<S>) => S;
 }>;
@@ -0,0 +1,30 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  createGpuEmitter,
+  emitGpuBurst,
+  setGpuEmitterParams,
+  destroyGpuEmitter,
+} from "./gpu.ts";
+
+describe("GPU particle emitters", () => {
+  it("createGpuEmitter returns 0 in headless mode", () => {
+    const id = createGpuEmitter({
+      x: 100,
+      y: 100,
+      maxParticles: 50000,
+      colorStart: { r: 1, g: 0.5, b: 0, a: 1 },
+      blend: "additive",
+    });
+    assert.equal(id, 0);
+  });
+
+  it("other calls are no-ops in headless mode", () => {
+    emitGpuBurst(0, 100);
+    setGpuEmitterParams(0, { rate: 10, gravity: [0, 200] });
+    destroyGpuEmitter(0);
+    emitGpuBurst(1, 100);
+    setGpuEmitterParams(1, { colorEnd: { r: 0, g: 0, b: 0, a: 0 } });
+    destroyGpuEmitter(1);
+    assert.ok(true);
+  });
+});
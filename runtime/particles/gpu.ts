@@ -0,0 +1,127 @@
+/**
+ * GPU particle emitters.
+ *
+ * Particles are simulated by a compute shader and drawn straight from the GPU
+ * buffer, so an emitter costs the same to script whether it has ten particles
+ * or fifty thousand. Behaviour is fixed by the emitter definition (lifetime,
+ * velocity, gravity, drag and size/color over life); there are no per-particle
+ * callbacks or affectors. Use the CPU emitters in `emitter.ts` when game code
+ * needs to read or steer individual particles.
+ *
+ * In headless mode every function is a no-op and {@link createGpuEmitter} returns 0.
+ */
+
+import type { Color } from "../ui/types.ts";
+
+const hasGpuParticleOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_gpu_emitter === "function";
+
+/** Definition of a GPU emitter. Every field is optional. */
+export type GpuEmitterDef = {
+  /** Emitter position in world pixels. Default: 0. */
+  x?: number;
+  y?: number;
+  /** Particle slots, fixed at creation. When full, the oldest particles are reused. Default: 1024. */
+  maxParticles?: number;
+  /** Particles spawned per second. 0 = bursts only. Default: 50. */
+  rate?: number;
+  /** Lifetime range in seconds, [min, max]. Default: [0.5, 1.5]. */
+  lifetime?: [number, number];
+  /** Initial speed range in pixels/second, [min, max]. Default: [20, 80]. */
+  speed?: [number, number];
+  /** Emission direction in radians. Default: -PI/2 (up). */
+  direction?: number;
+  /** Full cone angle around `direction`, in radians. Default: PI. */
+  spread?: number;
+  /** Particles start at a random point within this radius. Default: 0. */
+  spawnRadius?: number;
+  /** Acceleration in pixels/second², [x, y]. Default: [0, 0]. */
+  gravity?: [number, number];
+  /** Fraction of velocity lost per second. Default: 0. */
+  drag?: number;
+  /** Velocity multiplier at birth and at death, [birth, death]. Default: [1, 1]. */
+  speedCurve?: [number, number];
+  /** Quad size in pixels at birth and at death, [birth, death]. Default: [4, 4]. */
+  size?: [number, number];
+  /** Color at birth. Default: opaque white. */
+  colorStart?: Color;
+  /** Color at `colorMidTime`. Default: halfway between start and end. */
+  colorMid?: Color;
+  /** Color at death. Default: transparent white. */
+  colorEnd?: Color;
+  /** Point in life (0-1) where `colorMid` applies. Default: 0.5. */
+  colorMidTime?: number;
+  /** Texture for each particle. 0 = soft round dot. Default: 0. */
+  textureId?: number;
+  /** Blend mode. Default: "alpha". */
+  blend?: "alpha" | "additive";
+  /** Draw layer. The emitter draws after everything else on its layer. Default: 0. */
+  layer?: number;
+};
+
+/** Convert a definition value to the numbers the ops expect. */
+function toComponents(key: string, value: unknown): number[] {
+  if (key === "blend") return [value === "additive" ? 1 : 0];
+  if (Array.isArray(value)) return value;
+  if (typeof value === "object" && value !== null) {
+    const c = value as Color;
+    return [c.r, c.g, c.b, c.a];
+  }
+  return [value as number];
+}
+
+/**
+ * Create a GPU emitter. It starts emitting at `rate` on the next frame.
+ *
+ * @returns Emitter ID, or 0 in headless mode.
+ *
+ * @example
+ * const sparks = createGpuEmitter({
+ *   x: 400, y: 300, maxParticles: 20000, rate: 0,
+ *   speed: [100, 300], gravity: [0, 400], size: [3, 0],
+ *   colorStart: { r: 1, g: 0.9, b: 0.4, a: 1 },
+ *   colorEnd: { r: 1, g: 0.2, b: 0, a: 0 },
+ *   blend: "additive",
+ * });
+ * emitGpuBurst(sparks, 5000);
+ */
+export function createGpuEmitter(def: GpuEmitterDef = {}): number {
+  if (!hasGpuParticleOps) return 0;
+  const json: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(def)) {
+    if (value === undefined) continue;
+    json[key] = typeof value === "object" && !Array.isArray(value) ? toComponents(key, value) : value;
+  }
+  return (globalThis as any).Deno.core.ops.op_create_gpu_emitter(JSON.stringify(json));
+}
+
+/** Spawn `count` particles at once on the next frame, on top of the continuous rate. */
+export function emitGpuBurst(id: number, count: number): void {
+  if (!hasGpuParticleOps || id === 0) return;
+  (globalThis as any).Deno.core.ops.op_emit_gpu_burst(id, Math.max(0, Math.floor(count)));
+}
+
+/**
+ * Change parameters of a live emitter. Particles already in flight keep
+ * their lifetime and velocity; curves and colors apply to them immediately.
+ * `maxParticles` cannot be changed after creation.
+ *
+ * @example
+ * setGpuEmitterParams(smoke, { x: player.x, y: player.y, rate: moving ? 80 : 0 });
+ */
+export function setGpuEmitterParams(id: number, params: Omit<GpuEmitterDef, "maxParticles">): void {
+  if (!hasGpuParticleOps || id === 0) return;
+  const ops = (globalThis as any).Deno.core.ops;
+  for (const [key, value] of Object.entries(params)) {
+    if (value === undefined) continue;
+    const v = toComponents(key, value);
+    ops.op_set_gpu_emitter_param(id, key, v[0] ?? 0, v[1] ?? 0, v[2] ?? 0, v[3] ?? 0);
+  }
+}
+
+/** Destroy a GPU emitter. Its live particles disappear immediately. */
+export function destroyGpuEmitter(id: number): void {
+  if (!hasGpuParticleOps || id === 0) return;
+  (globalThis as any).Deno.core.ops.op_destroy_gpu_emitter(id);
+}
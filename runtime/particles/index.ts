@@ -39,3 +39,7 @@ export {
 // Presets
 export type { ParticleOptions } from "./presets.ts";
 export { burstParticles, streamParticles, ParticlePresets } from "./presets.ts";
+
+// GPU-simulated emitters
+export type { GpuEmitterDef } from "./gpu.ts";
+export { createGpuEmitter, emitGpuBurst, setGpuEmitterParams, destroyGpuEmitter } from "./gpu.ts";
@@ -0,0 +1,46 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  _parseSyncReport,
+  disableSaveSync,
+  enableSaveSync,
+  listSaveBackends,
+  onSaveConflict,
+  resolveSaveConflict,
+  syncSaves,
+} from "./cloud.ts";
+
+const hasSyncOps = typeof (globalThis as any).Deno?.core?.ops?.op_enable_save_sync === "function";
+
+describe("cloud saves", () => {
+  it("parses a sync report", () => {
+    const report = _parseSyncReport(
+      '{"ok":true,"pushed":["a"],"pulled":[],"removed":["b"],"conflicts":[{"key":"c","local":1000,"remote":null}],' +
+        '"skipped":["my save"],"failed":[{"key":"d","error":"disk full"}]}',
+    );
+    assert.deepEqual(report.pushed, ["a"]);
+    assert.deepEqual(report.removed, ["b"]);
+    assert.deepEqual(report.conflicts, [{ key: "c", localModified: 1000, remoteModified: null }]);
+    assert.deepEqual(report.skipped, ["my save"]);
+    assert.deepEqual(report.failed, [{ key: "d", error: "disk full" }]);
+    assert.equal(report.error, undefined);
+  });
+
+  it("parses a failed sync as an error", () => {
+    const report = _parseSyncReport('{"ok":false,"error":"offline"}');
+    assert.equal(report.error, "offline");
+    assert.deepEqual(report.conflicts, []);
+  });
+
+  it("is inert in headless mode", () => {
+    if (hasSyncOps) return;
+    assert.deepEqual(listSaveBackends(), []);
+    enableSaveSync("steam", { conflicts: "prompt" });
+    let calls = 0;
+    const off = onSaveConflict(() => calls++);
+    assert.deepEqual(syncSaves(), { pushed: [], pulled: [], removed: [], conflicts: [], skipped: [], failed: [] });
+    resolveSaveConflict("arcane_save_default", "local");
+    disableSaveSync();
+    off();
+    assert.equal(calls, 0);
+  });
+});
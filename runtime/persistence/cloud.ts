@@ -0,0 +1,116 @@
+/**
+ * Cloud saves: keep the save files in step with a remote copy.
+ *
+ * Remote storage (Steam Cloud, a game's own HTTP endpoint, ...) comes from
+ * native plugins, which register save backends by name. Once a backend is
+ * enabled, every write through {@link createFileStorage} is pushed to it as
+ * well, and {@link syncSaves} reconciles all saves: a save changed on one side
+ * is copied to the other, a deletion is repeated, and a save changed on both
+ * sides since the last sync is a conflict. Conflicts keep the newest copy with
+ * the `"latest"` policy; with `"prompt"` both copies stay untouched and each
+ * conflict is delivered to the {@link onSaveConflict} handlers until the game
+ * picks a copy with {@link resolveSaveConflict}.
+ *
+ * Keys are storage keys, i.e. `"arcane_save_" + slot` for {@link saveGame}.
+ * In headless mode every function is a no-op and nothing is ever synced.
+ *
+ * @example
+ * enableSaveSync("steam", { conflicts: "prompt" });
+ * onSaveConflict((c) => showDialog(`Keep this device's save (${c.key}) or the cloud one?`, (keepLocal) =>
+ *   resolveSaveConflict(c.key, keepLocal ? "local" : "remote")));
+ * syncSaves(); // at startup, then after reconnecting
+ */
+
+import type { SaveConflict, SaveConflictPolicy, SaveSyncReport } from "./types.ts";
+
+const hasSyncOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_enable_save_sync === "function";
+
+const conflictHandlers: ((conflict: SaveConflict) => void)[] = [];
+
+/** @internal Parse op_sync_saves' JSON. */
+export function _parseSyncReport(json: string): SaveSyncReport {
+  const raw = JSON.parse(json);
+  if (!raw.ok) {
+    return { pushed: [], pulled: [], removed: [], conflicts: [], skipped: [], failed: [], error: raw.error };
+  }
+  return {
+    pushed: raw.pushed,
+    pulled: raw.pulled,
+    removed: raw.removed,
+    conflicts: raw.conflicts.map((c: any) => ({ key: c.key, localModified: c.local, remoteModified: c.remote })),
+    skipped: raw.skipped,
+    failed: raw.failed,
+  };
+}
+
+/** Names of the save backends provided by loaded plugins. Empty in headless mode. */
+export function listSaveBackends(): string[] {
+  if (!hasSyncOps) return [];
+  return (globalThis as any).Deno.core.ops.op_list_save_backends();
+}
+
+/**
+ * Sync save files with a plugin's save backend from now on, replacing any
+ * backend enabled before. Nothing is copied until the next save write or
+ * {@link syncSaves}.
+ *
+ * @param options.conflicts - `"latest"` keeps the newer copy, `"prompt"` asks the game. Default: "latest".
+ * @throws If no loaded plugin provides a backend called `backend`.
+ */
+export function enableSaveSync(backend: string, options: { conflicts?: SaveConflictPolicy } = {}): void {
+  if (!hasSyncOps) return;
+  const ok: boolean = (globalThis as any).Deno.core.ops.op_enable_save_sync(backend, options.conflicts ?? "latest");
+  if (!ok) {
+    const known = listSaveBackends();
+    throw new Error(
+      `enableSaveSync: no save backend "${backend}" (available: ${known.length > 0 ? known.join(", ") : "none"})`,
+    );
+  }
+}
+
+/** Stop syncing save files. Unresolved conflicts are dropped; both copies stay as they are. */
+export function disableSaveSync(): void {
+  if (!hasSyncOps) return;
+  (globalThis as any).Deno.core.ops.op_disable_save_sync();
+}
+
+/**
+ * Reconcile every save with the remote backend, then call the
+ * {@link onSaveConflict} handlers with each conflict left unresolved. A
+ * failure (no backend enabled, remote offline) is returned in `error`, not
+ * thrown. A save that fails to copy is listed in `failed` and the others
+ * still sync.
+ */
+export function syncSaves(): SaveSyncReport {
+  if (!hasSyncOps) return { pushed: [], pulled: [], removed: [], conflicts: [], skipped: [], failed: [] };
+  const report = _parseSyncReport((globalThis as any).Deno.core.ops.op_sync_saves());
+  for (const conflict of report.conflicts) {
+    for (const handler of conflictHandlers.slice()) handler(conflict);
+  }
+  return report;
+}
+
+/**
+ * Be told about each save conflict found by {@link syncSaves} under the
+ * `"prompt"` policy. Returns a function that removes the handler.
+ */
+export function onSaveConflict(handler: (conflict: SaveConflict) => void): () => void {
+  conflictHandlers.push(handler);
+  return () => {
+    const i = conflictHandlers.indexOf(handler);
+    if (i >= 0) conflictHandlers.splice(i, 1);
+  };
+}
+
+/**
+ * Settle a conflict by copying the chosen side over the other.
+ *
+ * @throws If `key` has no unresolved conflict or the copy fails.
+ */
+export function resolveSaveConflict(key: string, keep: "local" | "remote"): void {
+  if (!hasSyncOps) return;
+  const error: string = (globalThis as any).Deno.core.ops.op_resolve_save_conflict(key, keep === "local");
+  if (error) throw new Error(`resolveSaveConflict: ${error}`);
+}
@@ -1,7 +1,7 @@
 /**
  * Persistence system
  *
- * Provides save/load with schema migrations and auto-save.
+ * Provides save/load with schema migrations, auto-save and cloud sync.
  */
 
 export type {
@@ -11,6 +11,9 @@ export type {
   SaveOptions,
   LoadResult,
   StorageBackend,
+  SaveConflictPolicy,
+  SaveConflict,
+  SaveSyncReport,
 } from "./types.ts";
 
 export {
@@ -38,3 +41,12 @@ export {
   triggerAutoSave,
   isAutoSaveEnabled,
 } from "./autosave.ts";
+
+export {
+  listSaveBackends,
+  enableSaveSync,
+  disableSaveSync,
+  syncSaves,
+  onSaveConflict,
+  resolveSaveConflict,
+} from "./cloud.ts";
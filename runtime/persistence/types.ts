@@ -71,3 +71,34 @@ export type StorageBackend = Readonly<{
   /** List all keys in storage. */
   list: () => string[];
 }>;
+
+/** What to do with a save changed both locally and remotely since the last sync. */
+export type SaveConflictPolicy = "latest" | "prompt";
+
+/** A save changed on both sides. A null time is a deleted copy. */
+export type SaveConflict = Readonly<{
+  /** Storage key of the save. */
+  key: string;
+  /** Unix timestamp (ms) of the local copy. */
+  localModified: number | null;
+  /** Unix timestamp (ms) of the remote copy. */
+  remoteModified: number | null;
+}>;
+
+/** Result of syncSaves(), by storage key. */
+export type SaveSyncReport = Readonly<{
+  /** Copied to the remote backend. */
+  pushed: string[];
+  /** Copied from the remote backend. */
+  pulled: string[];
+  /** Deleted on one side because they were deleted on the other. */
+  removed: string[];
+  /** Unresolved conflicts (only with the "prompt" policy). */
+  conflicts: SaveConflict[];
+  /** Files listed by a backend whose names aren't valid save keys, left alone. */
+  skipped: string[];
+  /** Saves that couldn't be copied, retried on the next sync. */
+  failed: { key: string; error: string }[];
+  /** Why the sync couldn't run, if it couldn't. */
+  error?: string;
+}>;
@@ -1,5 +1,5 @@
 import { describe, it, assert } from "../testing/harness.ts";
-import { createBody, _boxPolygonVertices, destroyBody, getBodyState, setBodyVelocity, applyForce, applyImpulse, setBodyPosition, getAllBodyStates } from "./body.ts";
+import { createBody, _boxPolygonVertices, destroyBody, getBodyState, setBodyVelocity, applyForce, applyImpulse, applyRadialImpulse, setBodyDamping, setBodyLocks, setBodyMaxSpeed, setBodySensor, setBodyOneWay, setBodyPosition, getAllBodyStates } from "./body.ts";
 
 describe("createBody", () => {
   it("returns 0 for circle shape in headless", () => {
@@ -18,6 +18,11 @@ describe("createBody", () => {
     assert.equal(id, 0);
   });
 
+  it("returns 0 for capsule and box shapes in headless", () => {
+    assert.equal(createBody({ type: "dynamic", shape: { type: "capsule", halfHeight: 12, radius: 6 }, x: 0, y: 0 }), 0);
+    assert.equal(createBody({ type: "dynamic", shape: { type: "box", halfW: 10, halfH: 5 }, x: 0, y: 0 }), 0);
+  });
+
   it("returns 0 for static body type", () => {
     const id = createBody({ type: "static", shape: { type: "circle", radius: 5 }, x: 0, y: 0 });
     assert.equal(id, 0);
@@ -83,6 +88,58 @@ describe("body operations headless", () => {
     applyImpulse(0, 5, 5);
   });
 
+  it("applyRadialImpulse returns 0 in headless", () => {
+    assert.equal(applyRadialImpulse(0, 0, 100, 500), 0);
+    assert.equal(applyRadialImpulse(0, 0, 100, 500, { falloff: 2, occlusion: true, mask: 0x0002 }), 0);
+  });
+
+  it("setBodyDamping does not throw", () => {
+    setBodyDamping(0, 5, 2);
+    setBodyDamping(0, 3);
+  });
+
+  it("setBodyLocks and setBodyMaxSpeed do not throw", () => {
+    setBodyLocks(0, { rotation: true, y: true });
+    setBodyLocks(0, {});
+    setBodyMaxSpeed(0, 300);
+    setBodyMaxSpeed(0, 300, 10);
+  });
+
+  it("setBodySensor does not throw and createBody accepts isSensor", () => {
+    setBodySensor(0, true);
+    setBodySensor(0, false);
+    const id = createBody({
+      type: "static",
+      shape: { type: "circle", radius: 6 },
+      x: 0, y: 0,
+      isSensor: true,
+    });
+    assert.equal(id, 0);
+  });
+
+  it("setBodyOneWay does not throw and createBody accepts oneWay", () => {
+    setBodyOneWay(0, { x: 0, y: -1 });
+    setBodyOneWay(0, null);
+    const id = createBody({
+      type: "static",
+      shape: { type: "aabb", halfW: 48, halfH: 4 },
+      x: 0, y: 0,
+      oneWay: { x: 0, y: -1 },
+    });
+    assert.equal(id, 0);
+  });
+
+  it("createBody with damping returns 0 in headless", () => {
+    const id = createBody({
+      type: "dynamic",
+      shape: { type: "circle", radius: 4 },
+      x: 0, y: 0,
+      linearDamping: 6,
+      angularDamping: 1,
+    });
+    assert.equal(id, 0);
+  });
+
   it("setBodyPosition does not throw", () => {
     setBodyPosition(0, 100, 200);
   });
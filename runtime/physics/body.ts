@@ -1,4 +1,4 @@
-import type { BodyId, BodyDef, BodyState } from "./types.ts";
+import type { BodyId, BodyDef, BodyLocks, BodyState, RadialImpulseOptions } from "./types.ts";
 
 const hasPhysicsOps =
   typeof (globalThis as any).Deno !== "undefined" &&
@@ -66,13 +66,14 @@ export function createBody(def: BodyDef): BodyId {
     for (const [vx, vy] of shape.vertices) {
       flatVerts.push(vx, vy);
     }
-    return (globalThis as any).Deno.core.ops.op_create_polygon_body(
+    const id: BodyId = (globalThis as any).Deno.core.ops.op_create_polygon_body(
       bodyTypeNum, flatVerts, x, y, mass, restitution, friction, layer, mask
     );
+    return applyBodyDefExtras(id, def);
   }
 
-  // Map shape type to u32: 0=circle, 1=aabb
-  // shape_p1/p2: circle=(radius, 0), aabb=(halfW, halfH)
+  // Map shape type to u32: 0=circle, 1=aabb, 3=capsule, 4=box (2 is polygon, above)
+  // shape_p1/p2: circle=(radius, 0), aabb/box=(halfW, halfH), capsule=(halfHeight, radius)
   let shapeType = 0;
   let p1 = 0;
   let p2 = 0;
@@ -84,11 +85,41 @@ export function createBody(def: BodyDef): BodyId {
     shapeType = 1;
     p1 = shape.halfW;
     p2 = shape.halfH;
+  } else if (shape.type === "capsule") {
+    shapeType = 3;
+    p1 = shape.halfHeight;
+    p2 = shape.radius;
+  } else if (shape.type === "box") {
+    shapeType = 4;
+    p1 = shape.halfW;
+    p2 = shape.halfH;
   }
 
-  return (globalThis as any).Deno.core.ops.op_create_body(
+  const id: BodyId = (globalThis as any).Deno.core.ops.op_create_body(
     bodyTypeNum, shapeType, p1, p2, x, y, mass, restitution, friction, layer, mask
   );
+  return applyBodyDefExtras(id, def);
+}
+
+/** Apply optional BodyDef settings to a freshly created body (skipped on creation failure). */
+function applyBodyDefExtras(id: BodyId, def: BodyDef): BodyId {
+  if (id === 0xFFFFFFFF) return id;
+  if (def.linearDamping || def.angularDamping) {
+    setBodyDamping(id, def.linearDamping ?? 0, def.angularDamping ?? 0);
+  }
+  if (def.lockRotation || def.lockX || def.lockY) {
+    setBodyLocks(id, { rotation: def.lockRotation, x: def.lockX, y: def.lockY });
+  }
+  if (def.maxSpeed || def.maxAngularSpeed) {
+    setBodyMaxSpeed(id, def.maxSpeed ?? 0, def.maxAngularSpeed ?? 0);
+  }
+  if (def.isSensor) {
+    setBodySensor(id, true);
+  }
+  if (def.oneWay) {
+    setBodyOneWay(id, def.oneWay);
+  }
+  return id;
 }
 
 /**
@@ -155,6 +186,101 @@ export function applyImpulse(id: BodyId, ix: number, iy: number): void {
   (globalThis as any).Deno.core.ops.op_apply_impulse(id, ix, iy);
 }
 
+/**
+ * Set linear and angular damping (per second, 0 = none) on a dynamic body.
+ * Damping slows bodies smoothly, e.g. ground friction in top-down games;
+ * damped bodies fall asleep once they are nearly still.
+ * No-op in headless mode.
+ *
+ * @example
+ * setBodyDamping(player, 8, 4);
+ */
+export function setBodyDamping(id: BodyId, linear: number, angular: number = 0): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_body_damping(id, Math.max(0, linear), Math.max(0, angular));
+}
+
+/**
+ * Lock a body's rotation and/or axes. Locks replace any previous locks;
+ * omitted fields are unlocked. No-op in headless mode.
+ *
+ * @example
+ * setBodyLocks(player, { rotation: true });    // platformer character
+ * setBodyLocks(car, { rotation: true, y: true }); // lane runner
+ */
+export function setBodyLocks(id: BodyId, locks: BodyLocks): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_body_locks(id, !!locks.rotation, !!locks.x, !!locks.y);
+}
+
+/**
+ * Cap a body's linear speed and angular speed (rad/s). 0 = unlimited.
+ * Enforced every physics sub-step, after forces and collisions. No-op in headless mode.
+ */
+export function setBodyMaxSpeed(id: BodyId, linear: number, angular: number = 0): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_body_max_speed(id, Math.max(0, linear), Math.max(0, angular));
+}
+
+/**
+ * Make a body a sensor (or a solid body again). Sensors show up in
+ * getContacts() and onCollision() events for whatever overlaps them, but
+ * nothing collides with them: use them for pickups, trigger zones and hitboxes.
+ * No-op in headless mode.
+ *
+ * @example
+ * const coin = createBody({ type: "static", shape: { type: "circle", radius: 6 }, x, y, isSensor: true });
+ * setBodySensor(hitbox, attacking);
+ */
+export function setBodySensor(id: BodyId, isSensor: boolean): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_body_sensor(id, !!isSensor);
+}
+
+/**
+ * Make a body a one-way platform: only bodies arriving from the side that
+ * `direction` points to collide with it; from any other side they pass
+ * through until fully clear. The direction is in the body's local frame, so
+ * it turns with the body. Pass `null` to make the body solid again.
+ * No-op in headless mode.
+ *
+ * @example
+ * // Jump-through ledge: land on top (y points down), pass up from below
+ * setBodyOneWay(ledge, { x: 0, y: -1 });
+ */
+export function setBodyOneWay(id: BodyId, direction: { x: number; y: number } | null): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_body_one_way(id, direction?.x ?? 0, direction?.y ?? 0);
+}
+
+/**
+ * Push dynamic bodies within `radius` of (x, y) away from the center, e.g. an explosion.
+ * Impulse magnitude is `strength * (1 - distance / radius) ^ falloff`, with `distance` measured to
+ * the closest point of each body. Wakes affected bodies.
+ * Returns the number of bodies pushed (0 in headless mode).
+ *
+ * @example
+ * applyRadialImpulse(bomb.x, bomb.y, 120, 800, { falloff: 2, occlusion: true });
+ */
+export function applyRadialImpulse(
+  x: number,
+  y: number,
+  radius: number,
+  strength: number,
+  options?: RadialImpulseOptions,
+): number {
+  if (!hasPhysicsOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_apply_radial_impulse(
+    x,
+    y,
+    radius,
+    strength,
+    options?.falloff ?? 1,
+    options?.occlusion ?? false,
+    options?.mask ?? 0xFFFF,
+  );
+}
+
 /**
  * Teleport a body to a new position. Wakes the body.
  * No-op in headless mode.
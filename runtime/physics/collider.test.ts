@@ -0,0 +1,9 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { generateColliderFromTexture } from "./collider.ts";
+
+describe("generateColliderFromTexture", () => {
+  it("returns no pieces in headless mode", () => {
+    assert.deepEqual(generateColliderFromTexture(1), []);
+    assert.deepEqual(generateColliderFromTexture(1, { alphaThreshold: 0.1, simplify: 0, scale: 2 }), []);
+  });
+});
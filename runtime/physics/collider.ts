@@ -0,0 +1,60 @@
+/**
+ * Polygon colliders generated from a sprite's alpha channel.
+ */
+
+import type { TextureId } from "../rendering/types.ts";
+
+const hasColliderOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_generate_collider_from_texture === "function";
+
+/** Options for {@link generateColliderFromTexture}. */
+export type ColliderFromTextureOptions = {
+  /** Pixels with alpha above this (0-1) are solid. Default: 0.5. */
+  alphaThreshold?: number;
+  /** How far, in texture pixels, the simplified outline may stray from the traced one. 0 keeps every corner. Default: 1. */
+  simplify?: number;
+  /** Multiplier applied to the vertices, for sprites drawn larger or smaller than the texture. Default: 1. */
+  scale?: number;
+};
+
+/**
+ * Trace the opaque pixels of a texture into convex polygons for
+ * `createBody({ shape: { type: "polygon", vertices } })`. Vertices are
+ * relative to the texture center, so they line up with a sprite drawn with
+ * the default centered origin at the body's position.
+ *
+ * Convex sprites give a single polygon; concave ones are cut into several.
+ * Holes are filled. Only textures loaded from files are supported. Results
+ * are cached per texture and options until the file changes.
+ *
+ * @returns One vertex list per convex piece. Empty in headless mode, for
+ * unknown or generated textures, and for fully transparent images.
+ *
+ * @example
+ * const rockTex = loadTexture("assets/rock.png");
+ * for (const vertices of generateColliderFromTexture(rockTex, { simplify: 2 })) {
+ *   createBody({ type: "static", shape: { type: "polygon", vertices }, x: 400, y: 300 });
+ * }
+ */
+export function generateColliderFromTexture(
+  textureId: TextureId,
+  options?: ColliderFromTextureOptions,
+): [number, number][][] {
+  if (!hasColliderOp) return [];
+  const scale = options?.scale ?? 1;
+  const pieces: number[][] = JSON.parse(
+    (globalThis as any).Deno.core.ops.op_generate_collider_from_texture(
+      textureId,
+      options?.alphaThreshold ?? 0.5,
+      options?.simplify ?? 1,
+    ),
+  );
+  return pieces.map((flat) => {
+    const vertices: [number, number][] = [];
+    for (let i = 0; i + 1 < flat.length; i += 2) {
+      vertices.push([flat[i] * scale, flat[i + 1] * scale]);
+    }
+    return vertices;
+  });
+}
@@ -1,5 +1,5 @@
 import { describe, it, assert } from "../testing/harness.ts";
-import { createDistanceJoint, createSoftDistanceJoint, createRevoluteJoint, createSoftRevoluteJoint, removeConstraint } from "./constraints.ts";
+import { createDistanceJoint, createSoftDistanceJoint, createRevoluteJoint, createSoftRevoluteJoint, createPrismaticJoint, createWeldJoint, setJointMotor, createRopeJoint, createPulleyJoint, createMouseJoint, setMouseJointTarget, setMouseJointOptions, removeConstraint } from "./constraints.ts";
 
 describe("physics constraints headless", () => {
   it("createDistanceJoint returns 0", () => {
@@ -18,6 +18,40 @@ describe("physics constraints headless", () => {
     assert.equal(createSoftRevoluteJoint(1, 2, 50, 50, { frequencyHz: 4, dampingRatio: 0.8 }), 0);
   });
 
+  it("prismatic, weld and motor functions are no-ops in headless mode", () => {
+    assert.equal(createRevoluteJoint(1, 2, 50, 50, { motor: { speed: 3, maxForce: 1000 } }), 0);
+    assert.equal(createPrismaticJoint(1, 2, { anchor: { x: 0, y: 0 }, axis: { x: 0, y: -1 } }), 0);
+    assert.equal(
+      createPrismaticJoint(1, 2, {
+        anchor: { x: 0, y: 0 },
+        axis: { x: 1, y: 0 },
+        lower: -50,
+        upper: 50,
+        motor: { speed: 20, maxForce: 500 },
+      }),
+      0,
+    );
+    assert.equal(createWeldJoint(1, 2, 10, 10), 0);
+    assert.equal(setJointMotor(0, { speed: 1, maxForce: 10 }), false);
+    assert.equal(setJointMotor(0, null), false);
+  });
+
+  it("createRopeJoint returns 0", () => {
+    assert.equal(createRopeJoint(1, 2, 120), 0);
+  });
+
+  it("createPulleyJoint returns 0", () => {
+    assert.equal(createPulleyJoint(1, 2, { groundA: { x: 0, y: 0 }, groundB: { x: 100, y: 0 } }), 0);
+    assert.equal(createPulleyJoint(1, 2, { groundA: { x: 0, y: 0 }, groundB: { x: 100, y: 0 }, ratio: 2 }), 0);
+  });
+
+  it("mouse joint functions are no-ops in headless mode", () => {
+    assert.equal(createMouseJoint(1, 10, 20), 0);
+    assert.equal(createMouseJoint(1, 10, 20, { maxForce: 500, frequencyHz: 8 }), 0);
+    assert.equal(setMouseJointTarget(0, 30, 40), false);
+    setMouseJointOptions(0, { dampingRatio: 1 });
+  });
+
   it("removeConstraint does not throw", () => {
     removeConstraint(0);
     removeConstraint(999);
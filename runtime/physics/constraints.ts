@@ -81,13 +81,123 @@ export function createSoftDistanceJoint(
   );
 }
 
+/**
+ * Joint motor: drives a revolute joint's rotation or a prismatic joint's
+ * translation toward a target speed, pushing with at most `maxForce`.
+ */
+export interface JointMotor {
+  /** Target speed of body B relative to body A: rad/s (revolute) or units/s along the axis (prismatic). */
+  speed: number;
+  /** Maximum torque (revolute) or force (prismatic). Low values accelerate slowly and stall under load. */
+  maxForce: number;
+}
+
+/** Options for {@link createRevoluteJoint}. */
+export interface RevoluteJointOptions {
+  /** Start with this motor running. See {@link setJointMotor}. */
+  motor?: JointMotor;
+}
+
 /**
  * Create a revolute (hinge) joint at a pivot point between two bodies.
  * Returns a ConstraintId for future reference. Returns 0 in headless mode.
+ *
+ * @example
+ * // Driven car wheel
+ * const axle = createRevoluteJoint(chassis, wheel, wheelX, wheelY, {
+ *   motor: { speed: 10, maxForce: 5e6 },
+ * });
  */
-export function createRevoluteJoint(bodyA: BodyId, bodyB: BodyId, pivotX: number, pivotY: number): ConstraintId {
+export function createRevoluteJoint(
+  bodyA: BodyId,
+  bodyB: BodyId,
+  pivotX: number,
+  pivotY: number,
+  options: RevoluteJointOptions = {},
+): ConstraintId {
   if (!hasPhysicsOps) return 0;
-  return (globalThis as any).Deno.core.ops.op_create_revolute_joint(bodyA, bodyB, pivotX, pivotY);
+  const id: ConstraintId = (globalThis as any).Deno.core.ops.op_create_revolute_joint(bodyA, bodyB, pivotX, pivotY);
+  if (options.motor) setJointMotor(id, options.motor);
+  return id;
+}
+
+/** Options for {@link createPrismaticJoint}. */
+export interface PrismaticJointOptions {
+  /** World point both bodies are anchored at; translation is measured from here. */
+  anchor: { x: number; y: number };
+  /** Slide direction in world space (normalized for you). It turns with body A. */
+  axis: { x: number; y: number };
+  /** Lowest translation along the axis. Default: unlimited. */
+  lower?: number;
+  /** Highest translation along the axis. Default: unlimited. */
+  upper?: number;
+  /** Start with this motor running. See {@link setJointMotor}. */
+  motor?: JointMotor;
+}
+
+/**
+ * Create a prismatic (slider) joint: body B keeps its rotation relative to
+ * body A and only moves along the axis, optionally between limits.
+ * Returns a ConstraintId for future reference. Returns 0 in headless mode.
+ *
+ * @example
+ * // Elevator riding up to 200 units above its start (y points down)
+ * const lift = createPrismaticJoint(shaft, platform, {
+ *   anchor: { x: 400, y: 500 },
+ *   axis: { x: 0, y: -1 },
+ *   lower: 0,
+ *   upper: 200,
+ *   motor: { speed: 60, maxForce: 1e6 },
+ * });
+ */
+export function createPrismaticJoint(bodyA: BodyId, bodyB: BodyId, options: PrismaticJointOptions): ConstraintId {
+  if (!hasPhysicsOps) return 0;
+  const limited = options.lower !== undefined || options.upper !== undefined;
+  const id: ConstraintId = (globalThis as any).Deno.core.ops.op_create_prismatic_joint(
+    bodyA,
+    bodyB,
+    options.anchor.x,
+    options.anchor.y,
+    options.axis.x,
+    options.axis.y,
+    limited,
+    options.lower ?? -Infinity,
+    options.upper ?? Infinity,
+  );
+  if (options.motor) setJointMotor(id, options.motor);
+  return id;
+}
+
+/**
+ * Create a weld joint gluing two bodies together at a world point, keeping
+ * their current relative angle (breakable crates, compound objects, turrets).
+ * Welded bodies still collide with each other; give them collision layers
+ * that don't overlap. Returns a ConstraintId. Returns 0 in headless mode.
+ */
+export function createWeldJoint(bodyA: BodyId, bodyB: BodyId, x: number, y: number): ConstraintId {
+  if (!hasPhysicsOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_weld_joint(bodyA, bodyB, x, y);
+}
+
+/**
+ * Start, retune or (with `null`) stop the motor of a revolute or prismatic
+ * joint. Wakes the joint's bodies. Returns false if `id` is not a revolute or
+ * prismatic joint, or in headless mode.
+ *
+ * @example
+ * // Door swinging open, then held shut by switching the motor direction
+ * setJointMotor(hinge, { speed: 2, maxForce: 50000 });
+ * setJointMotor(hinge, { speed: -2, maxForce: 50000 });
+ * setJointMotor(hinge, null); // swings freely
+ */
+export function setJointMotor(id: ConstraintId, motor: JointMotor | null): boolean {
+  if (!hasPhysicsOps) return false;
+  return (globalThis as any).Deno.core.ops.op_set_joint_motor(
+    id,
+    motor !== null,
+    motor?.speed ?? 0,
+    Math.max(0, motor?.maxForce ?? 0),
+  );
 }
 
 /**
@@ -128,6 +238,121 @@ export function createSoftRevoluteJoint(
   );
 }
 
+/**
+ * Create a rope joint: the bodies can move freely while closer than
+ * `maxLength`, and are pulled back (never pushed) once the rope is taut.
+ * Returns a ConstraintId for future reference. Returns 0 in headless mode.
+ *
+ * @example
+ * // Grappling hook: swing from a static anchor, reel in by recreating shorter
+ * const hook = createRopeJoint(anchorBody, player, 120);
+ */
+export function createRopeJoint(bodyA: BodyId, bodyB: BodyId, maxLength: number): ConstraintId {
+  if (!hasPhysicsOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_rope_joint(bodyA, bodyB, Math.max(0, maxLength));
+}
+
+/** Options for {@link createPulleyJoint}. */
+export interface PulleyJointOptions {
+  /** Fixed world point the rope to body A runs over. */
+  groundA: { x: number; y: number };
+  /** Fixed world point the rope to body B runs over. */
+  groundB: { x: number; y: number };
+  /** Mechanical advantage: B's side moves 1/ratio as far as A's. Default 1. */
+  ratio?: number;
+}
+
+/**
+ * Create a pulley joint: one rope over two fixed points, so when body A
+ * goes down body B goes up (`lengthA + ratio * lengthB` stays constant).
+ * The rope length is taken from the bodies' current positions.
+ * Returns a ConstraintId for future reference. Returns 0 in headless mode.
+ *
+ * @example
+ * // Counterweight platform
+ * createPulleyJoint(platform, weight, {
+ *   groundA: { x: platformX, y: 50 },
+ *   groundB: { x: weightX, y: 50 },
+ * });
+ */
+export function createPulleyJoint(bodyA: BodyId, bodyB: BodyId, options: PulleyJointOptions): ConstraintId {
+  if (!hasPhysicsOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_pulley_joint(
+    bodyA,
+    bodyB,
+    options.groundA.x,
+    options.groundA.y,
+    options.groundB.x,
+    options.groundB.y,
+    options.ratio ?? 1,
+  );
+}
+
+/** Options for {@link createMouseJoint}. */
+export interface MouseJointOptions {
+  /** Maximum pulling force. Default: 50000 × body mass (follows the cursor closely). */
+  maxForce?: number;
+  /** Drag spring stiffness in Hz. Default 5. */
+  frequencyHz?: number;
+  /** Drag spring damping. Default 0.7. */
+  dampingRatio?: number;
+}
+
+/**
+ * Create a mouse joint: grabs `body` at the world point (x, y) and pulls that
+ * point toward a target you move each frame with {@link setMouseJointTarget}.
+ * The pull is a force-limited spring, so dragged bodies still collide.
+ * Release with `removeConstraint()`; destroying the body releases it automatically.
+ * Returns a ConstraintId. Returns 0 in headless mode.
+ *
+ * @example
+ * let drag: ConstraintId | null = null;
+ * const m = getMouseWorldPosition();
+ * if (isMouseButtonPressed(0)) {
+ *   const [hit] = overlapCircle(m.x, m.y, 1);
+ *   if (hit) drag = createMouseJoint(hit.bodyId, m.x, m.y);
+ * }
+ * if (drag !== null && isMouseButtonDown(0)) setMouseJointTarget(drag, m.x, m.y);
+ * else if (drag !== null) { removeConstraint(drag); drag = null; }
+ */
+export function createMouseJoint(
+  body: BodyId,
+  x: number,
+  y: number,
+  options: MouseJointOptions = {},
+): ConstraintId {
+  if (!hasPhysicsOps) return 0;
+  const ops = (globalThis as any).Deno.core.ops;
+  const id: ConstraintId = ops.op_create_mouse_joint(body, x, y, options.maxForce ?? 0);
+  if (options.frequencyHz !== undefined || options.dampingRatio !== undefined) {
+    ops.op_set_mouse_joint_params(id, options.frequencyHz ?? 5, options.dampingRatio ?? 0.7, 0);
+  }
+  return id;
+}
+
+/**
+ * Move a mouse joint's target (world coordinates). Call every frame while dragging.
+ * Returns false if the joint is gone (released, or its body was destroyed).
+ */
+export function setMouseJointTarget(id: ConstraintId, x: number, y: number): boolean {
+  if (!hasPhysicsOps) return false;
+  return (globalThis as any).Deno.core.ops.op_set_mouse_joint_target(id, x, y);
+}
+
+/**
+ * Retune a mouse joint while dragging. Omitted fields keep their defaults
+ * (spring) or current value (maxForce). No-op in headless mode.
+ */
+export function setMouseJointOptions(id: ConstraintId, options: MouseJointOptions): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_mouse_joint_params(
+    id,
+    options.frequencyHz ?? 5,
+    options.dampingRatio ?? 0.7,
+    options.maxForce ?? 0,
+  );
+}
+
 /**
  * Remove a constraint from the physics world.
  * No-op in headless mode.
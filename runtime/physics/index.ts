@@ -10,16 +10,37 @@ export type {
   ShapeDef,
   MaterialDef,
   BodyDef,
+  BodyLocks,
   BodyState,
   Contact,
+  CollisionEvent,
   ContactManifold,
   ManifoldPoint,
   RayHit,
+  AABBQuery,
+  RayQuery,
+  QueryStats,
+  OverlapHit,
+  OverlapOptions,
+  RadialImpulseOptions,
   PhysicsWorldOptions,
+  PhysicsConfig,
 } from "./types.ts";
 
 // Physics world lifecycle
-export { createPhysicsWorld, stepPhysics, destroyPhysicsWorld } from "./world.ts";
+export {
+  createPhysicsWorld,
+  stepPhysics,
+  destroyPhysicsWorld,
+  setPhysicsConfig,
+  getPhysicsConfig,
+  setPhysicsDeterministic,
+  getPhysicsStateHash,
+  capturePhysicsSnapshot,
+  restorePhysicsSnapshot,
+  getCollisionEvents,
+  onCollision,
+} from "./world.ts";
 
 // Body management
 export {
@@ -31,6 +52,12 @@ export {
   setBodyAngularVelocity,
   applyForce,
   applyImpulse,
+  applyRadialImpulse,
+  setBodyDamping,
+  setBodyLocks,
+  setBodyMaxSpeed,
+  setBodySensor,
+  setBodyOneWay,
   setBodyPosition,
   setCollisionLayers,
   setKinematicVelocity,
@@ -38,14 +65,79 @@ export {
 } from "./body.ts";
 
 // Constraints / joints
-export type { SoftConstraintParams } from "./constraints.ts";
+export type {
+  SoftConstraintParams,
+  JointMotor,
+  RevoluteJointOptions,
+  PrismaticJointOptions,
+  PulleyJointOptions,
+  MouseJointOptions,
+} from "./constraints.ts";
 export {
   createDistanceJoint,
   createSoftDistanceJoint,
   createRevoluteJoint,
   createSoftRevoluteJoint,
+  createPrismaticJoint,
+  createWeldJoint,
+  setJointMotor,
+  createRopeJoint,
+  createPulleyJoint,
+  createMouseJoint,
+  setMouseJointTarget,
+  setMouseJointOptions,
   removeConstraint,
 } from "./constraints.ts";
 
+// Rigs of bodies and joints from JSON
+export type { PhysicsRig, PhysicsRigDef, RigBodyDef, RigJointDef } from "./rig.ts";
+export { loadPhysicsRig } from "./rig.ts";
+
+// Vehicles driven by raycast wheels
+export type {
+  VehicleId,
+  VehicleWheelDef,
+  VehicleOptions,
+  VehicleInput,
+  VehicleWheelState,
+  VehicleState,
+} from "./vehicle.ts";
+export { createVehicle, setVehicleInput, removeVehicle, getVehicleState } from "./vehicle.ts";
+
+// Pooled projectiles swept against the world
+export type {
+  ProjectileBatchId,
+  ProjectileBatchOptions,
+  ProjectileDef,
+  ProjectileState,
+  ProjectileHit,
+  DrawProjectilesOptions,
+} from "./projectile.ts";
+export {
+  createProjectileBatch,
+  removeProjectileBatch,
+  spawnProjectiles,
+  clearProjectiles,
+  getProjectileCount,
+  getProjectiles,
+  takeProjectileHits,
+  drawProjectiles,
+} from "./projectile.ts";
+
 // Spatial queries
-export { queryAABB, raycast, getContacts, getManifolds } from "./query.ts";
+export {
+  queryAABB,
+  overlapCircle,
+  overlapBox,
+  overlapPolygon,
+  raycast,
+  queryAABBBatch,
+  raycastBatch,
+  getQueryStats,
+  getContacts,
+  getManifolds,
+} from "./query.ts";
+
+// Colliders from sprite alpha
+export type { ColliderFromTextureOptions } from "./collider.ts";
+export { generateColliderFromTexture } from "./collider.ts";
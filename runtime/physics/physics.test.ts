@@ -1,16 +1,20 @@
 import { describe, it, assert } from "../testing/harness.ts";
 import {
   createPhysicsWorld, stepPhysics, destroyPhysicsWorld,
+  setPhysicsConfig, getPhysicsConfig,
+  setPhysicsDeterministic, getPhysicsStateHash,
+  capturePhysicsSnapshot, restorePhysicsSnapshot,
   createBody, destroyBody, getBodyState,
   setBodyVelocity, setBodyAngularVelocity,
   applyForce, applyImpulse, setBodyPosition,
   setCollisionLayers, setKinematicVelocity,
   createDistanceJoint, createRevoluteJoint, removeConstraint,
   queryAABB, raycast, getContacts,
+  getCollisionEvents, onCollision,
   _boxPolygonVertices,
 } from "./index.ts";
 import type {
-  BodyDef, BodyState, Contact, RayHit, ShapeDef, MaterialDef,
+  BodyDef, BodyState, Contact, CollisionEvent, RayHit, ShapeDef, MaterialDef,
   BodyId, ConstraintId, BodyType, PhysicsWorldOptions,
 } from "./types.ts";
 
@@ -48,6 +52,83 @@ describe("Physics API", () => {
     it("destroyPhysicsWorld does not throw", () => {
       destroyPhysicsWorld();
     });
+
+    it("solver config round-trips and clamps", () => {
+      createPhysicsWorld({ substeps: 2, velocityIterations: 6 });
+      const config = getPhysicsConfig();
+      if (config === null) {
+        setPhysicsConfig({ substeps: 8 });
+        destroyPhysicsWorld();
+        return;
+      }
+      assert.equal(config.substeps, 2);
+      assert.equal(config.velocityIterations, 6);
+      assert.equal(config.positionIterations, 3);
+
+      setPhysicsConfig({ substeps: 1000, sleepTime: 2 });
+      const updated = getPhysicsConfig()!;
+      assert.equal(updated.substeps, 32);
+      assert.equal(updated.velocityIterations, 6);
+      assert.equal(updated.sleepTime, 2);
+      destroyPhysicsWorld();
+      assert.equal(getPhysicsConfig(), null);
+    });
+
+    it("deterministic worlds hash identically", () => {
+      const run = () => {
+        createPhysicsWorld({ deterministic: true });
+        createBody({ type: "static", shape: { type: "aabb", halfW: 100, halfH: 10 }, x: 0, y: 100 });
+        createBody({ type: "dynamic", shape: { type: "circle", radius: 10 }, x: 5, y: 0 });
+        setPhysicsDeterministic(true);
+        for (let i = 0; i < 30; i++) stepPhysics(1 / 60);
+        const hash = getPhysicsStateHash();
+        destroyPhysicsWorld();
+        return hash;
+      };
+      const first = run();
+      assert.equal(run(), first);
+      if (first !== "") assert.equal(first.length, 16);
+      assert.equal(getPhysicsStateHash(), "");
+    });
+
+    it("physics snapshots restore bodies", () => {
+      createPhysicsWorld({ gravityY: 100 });
+      const ball = createBody({ type: "dynamic", shape: { type: "circle", radius: 5 }, x: 0, y: 0 });
+      const snapshot = capturePhysicsSnapshot();
+      if (snapshot === null) {
+        assert.equal(restorePhysicsSnapshot(new Uint8Array(0)), false);
+        destroyPhysicsWorld();
+        return;
+      }
+      stepPhysics(0.5);
+      assert.ok(getBodyState(ball).y > 0);
+      assert.equal(restorePhysicsSnapshot(snapshot), true);
+      assert.equal(getBodyState(ball).y, 0);
+      assert.equal(restorePhysicsSnapshot(new Uint8Array([1, 2, 3])), false);
+      destroyPhysicsWorld();
+    });
+
+    it("stepPhysics reports collision events to listeners", () => {
+      createPhysicsWorld({ gravityY: 0 });
+      const a = createBody({ type: "static", shape: { type: "circle", radius: 10 }, x: 0, y: 0 });
+      const b = createBody({ type: "dynamic", shape: { type: "circle", radius: 10 }, x: 19, y: 0 });
+      const seen: CollisionEvent[] = [];
+      const off = onCollision((e) => seen.push(e));
+      stepPhysics(1 / 60);
+      off();
+      stepPhysics(1 / 60);
+      if (a === 0) {
+        assert.deepEqual(getCollisionEvents(), []);
+        assert.equal(seen.length, 0);
+      } else {
+        assert.equal(seen.length, 1);
+        assert.equal(seen[0].type, "begin");
+        assert.equal(seen[0].bodyA, Math.min(a, b));
+        assert.equal(getCollisionEvents()[0].type, "stay");
+      }
+      destroyPhysicsWorld();
+      assert.deepEqual(getCollisionEvents(), []);
+    });
   });
 
   // ---- Body management ----
@@ -250,10 +331,13 @@ describe("Physics API", () => {
         normalX: 0, normalY: 1,
         penetration: 0.5,
         contactX: 10, contactY: 20,
+        impulse: 3, relativeVelocityX: 0, relativeVelocityY: -120,
       };
       assert.equal(c.bodyA, 1);
       assert.equal(c.bodyB, 2);
       assert.equal(c.penetration, 0.5);
+      assert.equal(c.impulse, 3);
+      assert.equal(c.relativeVelocityY, -120);
     });
 
     it("RayHit has all expected fields", () => {
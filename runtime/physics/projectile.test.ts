@@ -0,0 +1,41 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  _packProjectiles,
+  createProjectileBatch,
+  getProjectileCount,
+  getProjectiles,
+  spawnProjectiles,
+  takeProjectileHits,
+} from "./projectile.ts";
+
+const hasProjectileOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_projectile_batch === "function";
+
+describe("projectiles", () => {
+  it("packs 8 values per projectile with defaults", () => {
+    const packed = _packProjectiles([
+      { x: 1, y: 2, vx: 3, vy: 4 },
+      { x: 5, y: 6, vx: 7, vy: 8, ax: 0, ay: 9, life: 1.5, tag: 42 },
+    ]);
+    assert.equal(packed.length, 16);
+    assert.deepEqual(Array.from(packed.slice(0, 7)), [1, 2, 3, 4, 0, 0, 0]);
+    assert.deepEqual(Array.from(packed.slice(8, 15)), [5, 6, 7, 8, 0, 9, 1.5]);
+    const view = new DataView(packed.buffer);
+    assert.equal(view.getUint32(7 * 4, true), 0);
+    assert.equal(view.getUint32(15 * 4, true), 42);
+  });
+
+  it("keeps large tags exact", () => {
+    const view = new DataView(_packProjectiles([{ x: 0, y: 0, vx: 0, vy: 0, tag: 0xFFFFFFFF }]).buffer);
+    assert.equal(view.getUint32(7 * 4, true), 0xFFFFFFFF);
+  });
+
+  it("is inert in headless mode", () => {
+    if (hasProjectileOps) return;
+    assert.equal(createProjectileBatch(), 0);
+    assert.equal(spawnProjectiles(0, [{ x: 0, y: 0, vx: 1, vy: 0 }]), 0);
+    assert.equal(getProjectileCount(0), 0);
+    assert.deepEqual(getProjectiles(0), []);
+    assert.deepEqual(takeProjectileHits(), []);
+  });
+});
@@ -0,0 +1,244 @@
+/**
+ * Projectiles: bullets, arrows and shell casings simulated in bulk.
+ *
+ * A batch is a pool of up to `capacity` projectiles that are not physics
+ * bodies. Every physics step the engine moves them all in one native update:
+ * each one accelerates, ages, and sweeps its path against the bodies on the
+ * batch's collision layers, so fast bullets cannot tunnel through thin walls.
+ * The first body a projectile touches ends it and queues a hit; read hits
+ * with {@link takeProjectileHits}. Projectiles never push bodies and never
+ * hit sensors.
+ *
+ * In headless mode every function is a no-op: {@link createProjectileBatch}
+ * returns 0 and the getters return nothing.
+ *
+ * @example
+ * const bullets = createProjectileBatch({ capacity: 2000, radius: 2, mask: LAYER_ENEMIES | LAYER_WALLS });
+ * spawnProjectiles(bullets, [{ x: gun.x, y: gun.y, vx: Math.cos(aim) * 900, vy: Math.sin(aim) * 900, life: 2, tag: DAMAGE }]);
+ * for (const hit of takeProjectileHits()) damage(hit.body, hit.tag);
+ * drawProjectiles(bullets, { textureId: bulletTex, w: 8, h: 3, faceVelocity: true });
+ */
+
+import type { BodyId } from "./types.ts";
+import type { TextureId } from "../rendering/types.ts";
+import { _flushSpriteBatch } from "../rendering/sprites.ts";
+
+const hasProjectileOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_projectile_batch === "function";
+
+const hasDrawOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_draw_projectiles === "function";
+
+/** Opaque handle to a projectile batch. */
+export type ProjectileBatchId = number;
+
+/** Options for {@link createProjectileBatch}. */
+export type ProjectileBatchOptions = {
+  /** Most projectiles alive at once; spawns beyond it are dropped. Default: 1024. */
+  capacity?: number;
+  /** Collision radius in pixels. 0 tests only the path's center line. Default: 0. */
+  radius?: number;
+  /** Collision layers the projectiles hit. Default: 0xFFFF. */
+  mask?: number;
+};
+
+/** A projectile to spawn. */
+export type ProjectileDef = {
+  x: number;
+  y: number;
+  /** Velocity in pixels/s. */
+  vx: number;
+  vy: number;
+  /** Constant acceleration in pixels/s², e.g. gravity for arcing shots. Default: 0. */
+  ax?: number;
+  ay?: number;
+  /** Seconds before it expires. 0 = until it hits something. Default: 0. */
+  life?: number;
+  /** Integer (0 to 2³²-1) reported with its hit: owner, damage type, ... Default: 0. */
+  tag?: number;
+};
+
+/** A live projectile. */
+export type ProjectileState = { x: number; y: number; vx: number; vy: number };
+
+/** A projectile striking a body. */
+export type ProjectileHit = {
+  batch: ProjectileBatchId;
+  tag: number;
+  body: BodyId;
+  /** Where it struck. */
+  x: number;
+  y: number;
+  /** Its velocity at impact. */
+  vx: number;
+  vy: number;
+};
+
+/** Options for {@link drawProjectiles}. Each projectile is drawn centered on the sprite's origin. */
+export type DrawProjectilesOptions = {
+  textureId: TextureId;
+  w: number;
+  h: number;
+  /** Default: 0. */
+  layer?: number;
+  /** UV sub-rectangle, normalized 0-1. Default: the whole texture. */
+  uv?: { x: number; y: number; w: number; h: number };
+  /** Default: white. */
+  tint?: { r: number; g: number; b: number; a: number };
+  /** Default: 1. */
+  opacity?: number;
+  /** Default: "alpha". */
+  blendMode?: "alpha" | "additive" | "multiply" | "screen";
+  /** Rotation in radians, added to the velocity angle with `faceVelocity`. Default: 0. */
+  rotation?: number;
+  /** Turn each sprite to point along its projectile's velocity (texture facing +x). Default: false. */
+  faceVelocity?: boolean;
+  /** Point of the sprite placed on the projectile, 0-1. Default: 0.5 (center). */
+  originX?: number;
+  originY?: number;
+};
+
+// Values per projectile in op_spawn_projectiles' buffer
+const PROJECTILE_STRIDE = 8;
+// Values per hit in op_take_projectile_hits' output
+const HIT_STRIDE = 7;
+// One sprite record, same layout as the sprite batch in sprites.ts
+const SPRITE_STRIDE = 23;
+const _record = new Float32Array(SPRITE_STRIDE);
+
+const blendModeMap: Record<string, number> = {
+  alpha: 0,
+  additive: 1,
+  multiply: 2,
+  screen: 3,
+};
+
+/** @internal Pack projectiles into op_spawn_projectiles' buffer layout. */
+export function _packProjectiles(projectiles: ProjectileDef[]): Float32Array {
+  const packed = new Float32Array(projectiles.length * PROJECTILE_STRIDE);
+  const view = new DataView(packed.buffer);
+  projectiles.forEach((p, i) => {
+    const at = i * PROJECTILE_STRIDE;
+    packed.set([p.x, p.y, p.vx, p.vy, p.ax ?? 0, p.ay ?? 0, p.life ?? 0], at);
+    view.setUint32((at + 7) * 4, (p.tag ?? 0) >>> 0, true);
+  });
+  return packed;
+}
+
+/**
+ * Create an empty projectile batch in the current physics world. Its pool is
+ * allocated once, up front.
+ *
+ * @returns Batch ID, or 0 in headless mode.
+ * @throws If there is no physics world.
+ */
+export function createProjectileBatch(options: ProjectileBatchOptions = {}): ProjectileBatchId {
+  if (!hasProjectileOps) return 0;
+  const id: number = (globalThis as any).Deno.core.ops.op_create_projectile_batch(
+    options.capacity ?? 1024,
+    options.radius ?? 0,
+    options.mask ?? 0xFFFF,
+  );
+  if (id === 0xFFFFFFFF) {
+    throw new Error("createProjectileBatch: create a physics world first");
+  }
+  return id;
+}
+
+/** Remove a batch and its projectiles. Hits it already queued are still reported. */
+export function removeProjectileBatch(id: ProjectileBatchId): void {
+  if (!hasProjectileOps) return;
+  (globalThis as any).Deno.core.ops.op_remove_projectile_batch(id);
+}
+
+/**
+ * Spawn projectiles into a batch. They start moving on the next physics step.
+ *
+ * @returns How many fit; the rest are dropped once the batch is full.
+ */
+export function spawnProjectiles(id: ProjectileBatchId, projectiles: ProjectileDef[]): number {
+  if (!hasProjectileOps || projectiles.length === 0) return 0;
+  const packed = _packProjectiles(projectiles);
+  return (globalThis as any).Deno.core.ops.op_spawn_projectiles(id, new Uint8Array(packed.buffer));
+}
+
+/** Remove every projectile from a batch, keeping the batch. */
+export function clearProjectiles(id: ProjectileBatchId): void {
+  if (!hasProjectileOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_projectiles(id);
+}
+
+/** Number of live projectiles in a batch (0 for an unknown batch). */
+export function getProjectileCount(id: ProjectileBatchId): number {
+  if (!hasProjectileOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_get_projectile_count(id);
+}
+
+/** Live projectiles in a batch, oldest first. Empty for an unknown batch. */
+export function getProjectiles(id: ProjectileBatchId): ProjectileState[] {
+  if (!hasProjectileOps) return [];
+  const bytes: Uint8Array = (globalThis as any).Deno.core.ops.op_get_projectiles(id);
+  const data = new Float32Array(bytes.buffer, bytes.byteOffset, bytes.byteLength / 4);
+  const result: ProjectileState[] = [];
+  for (let i = 0; i + 4 <= data.length; i += 4) {
+    result.push({ x: data[i], y: data[i + 1], vx: data[i + 2], vy: data[i + 3] });
+  }
+  return result;
+}
+
+/**
+ * Take the hits of every batch queued since the last call, in the order they
+ * happened. Hits accumulate across physics steps until taken.
+ */
+export function takeProjectileHits(): ProjectileHit[] {
+  if (!hasProjectileOps) return [];
+  const data: number[] = (globalThis as any).Deno.core.ops.op_take_projectile_hits();
+  const hits: ProjectileHit[] = [];
+  for (let i = 0; i + HIT_STRIDE <= data.length; i += HIT_STRIDE) {
+    hits.push({
+      batch: data[i],
+      tag: data[i + 1],
+      body: data[i + 2],
+      x: data[i + 3],
+      y: data[i + 4],
+      vx: data[i + 5],
+      vy: data[i + 6],
+    });
+  }
+  return hits;
+}
+
+/**
+ * Draw every projectile of a batch with the same sprite, as one instanced
+ * batch queued natively. Must be called every frame, like drawSprite().
+ */
+export function drawProjectiles(id: ProjectileBatchId, opts: DrawProjectilesOptions): void {
+  if (!hasDrawOps) return;
+  const uv = opts.uv ?? { x: 0, y: 0, w: 1, h: 1 };
+  const tint = opts.tint ?? { r: 1, g: 1, b: 1, a: 1 };
+  const view = new DataView(_record.buffer);
+  view.setUint32(0, opts.textureId, true);
+  _record[1] = 0;
+  _record[2] = 0;
+  _record[3] = opts.w;
+  _record[4] = opts.h;
+  view.setInt32(5 * 4, opts.layer ?? 0, true);
+  _record.set([
+    uv.x, uv.y, uv.w, uv.h,
+    tint.r, tint.g, tint.b, tint.a,
+    opts.rotation ?? 0,
+    opts.originX ?? 0.5,
+    opts.originY ?? 0.5,
+    0,
+    0,
+    opts.opacity ?? 1,
+    blendModeMap[opts.blendMode ?? "alpha"] ?? 0,
+  ], 6);
+  view.setUint32(21 * 4, 0, true);
+  view.setUint32(22 * 4, 0, true);
+  // Keep draw order relative to sprites queued earlier this frame
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_draw_projectiles(id, opts.faceVelocity ?? false, new Uint8Array(_record.buffer));
+}
@@ -1,5 +1,16 @@
 import { describe, it, assert } from "../testing/harness.ts";
-import { queryAABB, raycast, getContacts, getManifolds } from "./query.ts";
+import {
+  queryAABB,
+  overlapCircle,
+  overlapBox,
+  overlapPolygon,
+  raycast,
+  queryAABBBatch,
+  raycastBatch,
+  getQueryStats,
+  getContacts,
+  getManifolds,
+} from "./query.ts";
 
 describe("physics queries headless", () => {
   it("queryAABB returns empty array", () => {
@@ -15,6 +26,16 @@ describe("physics queries headless", () => {
     assert.deepEqual(queryAABB(-10000, -10000, 10000, 10000), []);
   });
 
+  it("overlap queries return empty arrays", () => {
+    assert.deepEqual(overlapCircle(0, 0, 10), []);
+    assert.deepEqual(overlapBox(0, 0, 5, 5, Math.PI / 4, { mask: 0x0002 }), []);
+    assert.deepEqual(overlapPolygon(0, 0, [[0, 0], [10, 0], [0, 10]]), []);
+  });
+
+  it("overlapPolygon with fewer than 3 vertices returns empty", () => {
+    assert.deepEqual(overlapPolygon(0, 0, [[0, 0], [1, 1]]), []);
+  });
+
   it("raycast returns null", () => {
     assert.equal(raycast(0, 0, 1, 0), null);
   });
@@ -27,6 +48,19 @@ describe("physics queries headless", () => {
     assert.equal(raycast(0, 0, 1, 0, 5000), null);
   });
 
+  it("batched queries return one empty result per query", () => {
+    assert.deepEqual(queryAABBBatch([]), []);
+    assert.deepEqual(
+      queryAABBBatch([{ minX: 0, minY: 0, maxX: 10, maxY: 10 }, { minX: -5, minY: -5, maxX: 5, maxY: 5 }]),
+      [[], []],
+    );
+    assert.deepEqual(raycastBatch([{ originX: 0, originY: 0, dirX: 1, dirY: 0 }]), [null]);
+  });
+
+  it("getQueryStats returns zeros", () => {
+    assert.deepEqual(getQueryStats(), { aabb: 0, overlap: 0, raycast: 0, total: 0, candidates: 0, fullScans: 0 });
+  });
+
   it("getContacts returns empty array", () => {
     assert.deepEqual(getContacts(), []);
   });
@@ -1,4 +1,15 @@
-import type { BodyId, Contact, ContactManifold, ManifoldPoint, RayHit } from "./types.ts";
+import type {
+  AABBQuery,
+  BodyId,
+  Contact,
+  ContactManifold,
+  ManifoldPoint,
+  OverlapHit,
+  OverlapOptions,
+  QueryStats,
+  RayHit,
+  RayQuery,
+} from "./types.ts";
 
 const hasPhysicsOps =
   typeof (globalThis as any).Deno !== "undefined" &&
@@ -13,6 +24,75 @@ export function queryAABB(minX: number, minY: number, maxX: number, maxY: number
   return (globalThis as any).Deno.core.ops.op_query_aabb(minX, minY, maxX, maxY);
 }
 
+function unpackOverlaps(flat: number[]): OverlapHit[] {
+  const hits: OverlapHit[] = [];
+  for (let i = 0; i + 5 < flat.length; i += 6) {
+    hits.push({
+      bodyId: flat[i],
+      normalX: flat[i + 1],
+      normalY: flat[i + 2],
+      penetration: flat[i + 3],
+      pointX: flat[i + 4],
+      pointY: flat[i + 5],
+    });
+  }
+  return hits;
+}
+
+/**
+ * Find all bodies overlapping a circle, e.g. an explosion radius.
+ * Returns an empty array in headless mode.
+ *
+ * @example
+ * for (const hit of overlapCircle(bomb.x, bomb.y, 64, { mask: LAYER_ENEMY })) {
+ *   damage(hit.bodyId);
+ * }
+ */
+export function overlapCircle(x: number, y: number, radius: number, options?: OverlapOptions): OverlapHit[] {
+  if (!hasPhysicsOps) return [];
+  const mask = options?.mask ?? 0xFFFF;
+  return unpackOverlaps((globalThis as any).Deno.core.ops.op_overlap_circle(x, y, radius, mask));
+}
+
+/**
+ * Find all bodies overlapping a box centered at (x, y), rotated by `angle` radians.
+ * Useful for melee hitboxes. Returns an empty array in headless mode.
+ *
+ * @example
+ * const hits = overlapBox(player.x + 20 * facing, player.y, 16, 8, 0, { mask: LAYER_ENEMY });
+ */
+export function overlapBox(
+  x: number,
+  y: number,
+  halfW: number,
+  halfH: number,
+  angle: number = 0,
+  options?: OverlapOptions,
+): OverlapHit[] {
+  if (!hasPhysicsOps) return [];
+  const mask = options?.mask ?? 0xFFFF;
+  return unpackOverlaps((globalThis as any).Deno.core.ops.op_overlap_box(x, y, halfW, halfH, angle, mask));
+}
+
+/**
+ * Find all bodies overlapping a convex polygon. Vertices are relative to (x, y),
+ * counter-clockwise, and rotated by `angle` radians.
+ * Returns an empty array in headless mode or for fewer than 3 vertices.
+ */
+export function overlapPolygon(
+  x: number,
+  y: number,
+  vertices: [number, number][],
+  angle: number = 0,
+  options?: OverlapOptions,
+): OverlapHit[] {
+  if (!hasPhysicsOps || vertices.length < 3) return [];
+  const mask = options?.mask ?? 0xFFFF;
+  const flat: number[] = [];
+  for (const [vx, vy] of vertices) flat.push(vx, vy);
+  return unpackOverlaps((globalThis as any).Deno.core.ops.op_overlap_polygon(flat, x, y, angle, mask));
+}
+
 /**
  * Cast a ray and return the first hit, or null if nothing hit.
  * Direction does not need to be normalized.
@@ -37,15 +117,81 @@ export function raycast(originX: number, originY: number, dirX: number, dirY: nu
   };
 }
 
+/**
+ * Run many {@link queryAABB} calls in one op, e.g. one box per enemy for its
+ * aggro range. Results come back in the same order as `boxes`, each sorted by ID.
+ * Returns an empty array per box in headless mode.
+ */
+export function queryAABBBatch(boxes: AABBQuery[]): BodyId[][] {
+  if (!hasPhysicsOps || boxes.length === 0) return boxes.map(() => []);
+  const packed = new Float32Array(boxes.length * 4);
+  boxes.forEach((b, i) => packed.set([b.minX, b.minY, b.maxX, b.maxY], i * 4));
+  const flat: number[] = (globalThis as any).Deno.core.ops.op_query_aabb_batch(new Uint8Array(packed.buffer));
+  const results: BodyId[][] = [];
+  let at = 0;
+  for (let i = 0; i < boxes.length; i++) {
+    const count = flat[at] ?? 0;
+    results.push(flat.slice(at + 1, at + 1 + count));
+    at += 1 + count;
+  }
+  return results;
+}
+
+/**
+ * Cast many rays in one op, e.g. line-of-sight checks for a crowd. Returns
+ * the first hit of each ray in the same order as `rays`, null for a miss.
+ * Returns all nulls in headless mode.
+ */
+export function raycastBatch(rays: RayQuery[]): (RayHit | null)[] {
+  if (!hasPhysicsOps || rays.length === 0) return rays.map(() => null);
+  const packed = new Float32Array(rays.length * 5);
+  rays.forEach((r, i) => packed.set([r.originX, r.originY, r.dirX, r.dirY, r.maxDistance ?? 1000.0], i * 5));
+  const flat: number[] = (globalThis as any).Deno.core.ops.op_raycast_batch(new Uint8Array(packed.buffer));
+  const hits: (RayHit | null)[] = [];
+  for (let i = 0; i < rays.length; i++) {
+    const at = i * 4;
+    if (at + 3 >= flat.length || flat[at] < 0) {
+      hits.push(null);
+      continue;
+    }
+    hits.push({ bodyId: flat[at], hitX: flat[at + 1], hitY: flat[at + 2], distance: flat[at + 3] });
+  }
+  return hits;
+}
+
+/**
+ * Scene queries (AABB, overlap, raycast) made between the last two physics
+ * steps, and how much work they did. Queries share an index of body bounds
+ * rebuilt once per step; `fullScans` counts those that had to test every body
+ * instead, e.g. after many bodies were moved from game code since the step.
+ * All zeros in headless mode or without a physics world.
+ */
+export function getQueryStats(): QueryStats {
+  const empty: QueryStats = { aabb: 0, overlap: 0, raycast: 0, total: 0, candidates: 0, fullScans: 0 };
+  if (!hasPhysicsOps) return empty;
+  const json: string = (globalThis as any).Deno.core.ops.op_get_query_stats();
+  const s = JSON.parse(json);
+  return {
+    aabb: s.aabb,
+    overlap: s.overlap,
+    raycast: s.raycast,
+    total: s.total,
+    candidates: s.candidates,
+    fullScans: s.full_scans,
+  };
+}
+
 /**
  * Get all contacts from the last physics step.
+ * `impulse` is how hard the pair pushed on each other this step; the closing
+ * speed is `-(relativeVelocityX * normalX + relativeVelocityY * normalY)`.
  * Returns an empty array in headless mode.
  */
 export function getContacts(): Contact[] {
   if (!hasPhysicsOps) return [];
   const flat: number[] = (globalThis as any).Deno.core.ops.op_get_contacts();
   const contacts: Contact[] = [];
-  for (let i = 0; i + 6 < flat.length; i += 7) {
+  for (let i = 0; i + 9 < flat.length; i += 10) {
     contacts.push({
       bodyA: flat[i],
       bodyB: flat[i + 1],
@@ -54,6 +200,9 @@ export function getContacts(): Contact[] {
       penetration: flat[i + 4],
       contactX: flat[i + 5],
       contactY: flat[i + 6],
+      impulse: flat[i + 7],
+      relativeVelocityX: flat[i + 8],
+      relativeVelocityY: flat[i + 9],
     });
   }
   return contacts;
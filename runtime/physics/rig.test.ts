@@ -0,0 +1,22 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { loadPhysicsRig } from "./rig.ts";
+import type { PhysicsRigDef } from "./rig.ts";
+
+const hasRigOp =
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_physics_rig === "function";
+
+const pendulum: PhysicsRigDef = {
+  bodies: [
+    { name: "pivot", type: "static", shape: { type: "circle", radius: 2 } },
+    { name: "bob", shape: { type: "circle", radius: 5 }, x: 40 },
+  ],
+  joints: [{ name: "arm", type: "distance", bodyA: "pivot", bodyB: "bob" }],
+};
+
+describe("physics rigs", () => {
+  it("returns null in headless mode", () => {
+    if (hasRigOp) return;
+    assert.equal(loadPhysicsRig(pendulum, 100, 50), null);
+    assert.equal(loadPhysicsRig("assets/rigs/missing.json", 0, 0), null);
+  });
+});
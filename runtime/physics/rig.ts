@@ -0,0 +1,97 @@
+/**
+ * Physics rigs: bodies and joints spawned together from one JSON description.
+ *
+ * Ragdolls, vehicles and chain bridges can be authored in a tool and saved as
+ * a rig file instead of being assembled joint by joint in code. Positions and
+ * anchors are relative to the rig origin; anchors are converted into each
+ * body's local space when the rig spawns.
+ */
+
+import type { BodyId, BodyType, ConstraintId, MaterialDef, ShapeDef } from "./types.ts";
+import type { JointMotor, SoftConstraintParams } from "./constraints.ts";
+
+const hasRigOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_physics_rig === "function";
+
+type RigPoint = { x: number; y: number } | [number, number];
+
+/** A body in a rig file. Fields match {@link BodyDef} plus a name and angle. */
+export type RigBodyDef = {
+  /** Name the spawned body ID is returned under. Default: `body<index>`. */
+  name?: string;
+  /** Default: "dynamic". */
+  type?: BodyType;
+  shape: ShapeDef;
+  /** Position relative to the rig origin. Default: 0. */
+  x?: number;
+  y?: number;
+  /** Initial rotation in radians. Default: 0. */
+  angle?: number;
+  mass?: number;
+  material?: MaterialDef;
+  layer?: number;
+  mask?: number;
+  linearDamping?: number;
+  angularDamping?: number;
+  lockRotation?: boolean;
+  isSensor?: boolean;
+};
+
+/** A joint in a rig file, between two bodies named in the same rig. */
+export type RigJointDef = {
+  /** Name the joint ID is returned under. Default: `joint<index>`. */
+  name?: string;
+  bodyA: string;
+  bodyB: string;
+} & (
+  | {
+      type: "distance";
+      /** Attachment points relative to the rig origin. Default: the body centers. */
+      anchorA?: RigPoint;
+      anchorB?: RigPoint;
+      /** Default: the current distance between the anchors. */
+      length?: number;
+      soft?: SoftConstraintParams;
+    }
+  | { type: "rope"; anchorA?: RigPoint; anchorB?: RigPoint; maxLength?: number }
+  | { type: "revolute"; anchor: RigPoint; soft?: SoftConstraintParams; motor?: JointMotor }
+  | { type: "weld"; anchor: RigPoint }
+  | { type: "prismatic"; anchor: RigPoint; axis: RigPoint; lower?: number; upper?: number; motor?: JointMotor }
+);
+
+/** A rig file: bodies and the joints between them. */
+export type PhysicsRigDef = {
+  bodies: RigBodyDef[];
+  joints?: RigJointDef[];
+};
+
+/** IDs of a spawned rig, by the names used in the rig. */
+export type PhysicsRig = {
+  bodies: Record<string, BodyId>;
+  joints: Record<string, ConstraintId>;
+};
+
+/**
+ * Spawn a rig's bodies and joints in one call, with the rig origin at (x, y).
+ * Each call creates a new copy. Needs a physics world.
+ *
+ * Returns null in headless mode. Throws if the rig is invalid (the message
+ * names the body or joint) or no physics world exists.
+ *
+ * @param rig - Path to a rig `.json` file relative to the game directory, its
+ *   JSON text, or the rig itself.
+ * @param x - Spawn position of the rig origin.
+ * @param y - Spawn position of the rig origin.
+ *
+ * @example
+ * const doll = loadPhysicsRig("assets/rigs/ragdoll.json", 400, 120);
+ * applyImpulse(doll!.bodies.torso, 300, -200);
+ */
+export function loadPhysicsRig(rig: string | PhysicsRigDef, x: number, y: number): PhysicsRig | null {
+  if (!hasRigOp) return null;
+  const source = typeof rig === "string" ? rig : JSON.stringify(rig);
+  const raw = JSON.parse((globalThis as any).Deno.core.ops.op_load_physics_rig(source, x, y));
+  if (raw.error) throw new Error(`loadPhysicsRig: ${raw.error}`);
+  return { bodies: raw.bodies, joints: raw.joints };
+}
@@ -11,7 +11,11 @@ export type BodyType = "static" | "dynamic" | "kinematic";
 export type ShapeDef =
   | { type: "circle"; radius: number }
   | { type: "aabb"; halfW: number; halfH: number }
-  | { type: "polygon"; vertices: [number, number][] };
+  | { type: "polygon"; vertices: [number, number][] }
+  /** Vertical pill: a straight part of 2 × halfHeight capped by half circles. Rotates with the body. */
+  | { type: "capsule"; halfHeight: number; radius: number }
+  /** Box that rotates with the body, unlike "aabb". */
+  | { type: "box"; halfW: number; halfH: number };
 
 /** Physical material properties. */
 export type MaterialDef = {
@@ -29,6 +33,22 @@ export type BodyDef = {
   material?: MaterialDef;
   layer?: number;         // Default 0x0001
   mask?: number;          // Default 0xFFFF
+  linearDamping?: number; // Velocity damping per second, e.g. 5 for top-down friction. Default 0
+  angularDamping?: number; // Spin damping per second. Default 0
+  lockRotation?: boolean; // Never rotate (e.g. platformer characters). Default false
+  lockX?: boolean;        // Freeze X; body only moves vertically. Default false
+  lockY?: boolean;        // Freeze Y; body only moves horizontally (lanes). Default false
+  maxSpeed?: number;      // Linear speed cap. Default 0 (unlimited)
+  maxAngularSpeed?: number; // Angular speed cap in rad/s. Default 0 (unlimited)
+  isSensor?: boolean;     // Report overlaps (contacts, collision events) but never collide. Default false
+  oneWay?: { x: number; y: number }; // One-way platform: collide only with bodies arriving from this side (local frame). Default: solid
+};
+
+/** Axis locks for setBodyLocks(). Omitted fields are unlocked. */
+export type BodyLocks = {
+  rotation?: boolean;
+  x?: boolean;
+  y?: boolean;
 };
 
 /** Readonly body state snapshot. */
@@ -51,6 +71,27 @@ export type Contact = {
   readonly penetration: number;
   readonly contactX: number;
   readonly contactY: number;
+  readonly impulse: number;           // Total normal impulse this step (N·s); use for damage / impact volume
+  readonly relativeVelocityX: number; // Velocity of B relative to A at the contact point
+  readonly relativeVelocityY: number;
+};
+
+/**
+ * A change in which bodies touch, from {@link getCollisionEvents} or {@link onCollision}.
+ * "begin": the pair started touching this step. "stay": still touching.
+ * "end": separated, or one of the bodies was destroyed.
+ */
+export type CollisionEvent = {
+  readonly type: "begin" | "stay" | "end";
+  /** The lower body ID of the pair. */
+  readonly bodyA: BodyId;
+  readonly bodyB: BodyId;
+  /** Contact normal pointing from A to B. For "end", the last one seen. */
+  readonly normalX: number;
+  readonly normalY: number;
+  /** Contact point in world space. For "end", the last one seen. */
+  readonly pointX: number;
+  readonly pointY: number;
 };
 
 /** A single point within a contact manifold (TGS Soft). */
@@ -71,6 +112,35 @@ export type ContactManifold = {
   readonly points: ManifoldPoint[];
 };
 
+/** A body overlapping a query shape (overlapCircle / overlapBox / overlapPolygon). */
+export type OverlapHit = {
+  readonly bodyId: BodyId;
+  /** Unit normal pointing from the query shape toward the body. */
+  readonly normalX: number;
+  readonly normalY: number;
+  /** Overlap depth along the normal. */
+  readonly penetration: number;
+  /** Approximate world-space contact point. */
+  readonly pointX: number;
+  readonly pointY: number;
+};
+
+/** Options for overlap queries. */
+export type OverlapOptions = {
+  /** Only bodies whose collision layer intersects this mask are returned. Default: 0xFFFF (all). */
+  mask?: number;
+};
+
+/** Options for applyRadialImpulse(). */
+export type RadialImpulseOptions = {
+  /** Attenuation exponent: 0 = constant, 1 = linear (default), 2 = quadratic. */
+  falloff?: number;
+  /** Static bodies between the center and a body block the blast. Default: false. */
+  occlusion?: boolean;
+  /** Only bodies whose collision layer intersects this mask are pushed. Default: 0xFFFF (all). */
+  mask?: number;
+};
+
 /** Raycast hit result. */
 export type RayHit = {
   readonly bodyId: BodyId;
@@ -79,8 +149,52 @@ export type RayHit = {
   readonly distance: number;
 };
 
-/** Options for createPhysicsWorld(). */
-export type PhysicsWorldOptions = {
+/** A box for queryAABBBatch(). */
+export type AABBQuery = {
+  minX: number;
+  minY: number;
+  maxX: number;
+  maxY: number;
+};
+
+/** A ray for raycastBatch(). Direction does not need to be normalized. */
+export type RayQuery = {
+  originX: number;
+  originY: number;
+  dirX: number;
+  dirY: number;
+  /** Default: 1000. */
+  maxDistance?: number;
+};
+
+/** Scene query counts for the last physics frame, from getQueryStats(). */
+export type QueryStats = {
+  readonly aabb: number;
+  readonly overlap: number;
+  readonly raycast: number;
+  readonly total: number;
+  /** Bodies tested against query shapes after the broadphase. */
+  readonly candidates: number;
+  /** Queries that tested every body because the query index was not usable. */
+  readonly fullScans: number;
+};
+
+/**
+ * Solver settings: trade accuracy for speed. Omitted fields keep their current value.
+ * Fewer sub-steps/iterations are cheaper but make stacks and chains softer.
+ */
+export type PhysicsConfig = {
+  substeps?: number;            // Sub-steps per fixed 1/60s step. Default 4, range 1-32
+  velocityIterations?: number;  // Velocity solver passes per sub-step. Default 10, range 1-64
+  positionIterations?: number;  // Position correction passes per sub-step. Default 3, range 0-64
+  sleepVelocity?: number;       // Linear speed below which bodies may sleep. Default 8
+  sleepAngularVelocity?: number; // Angular speed (rad/s) below which bodies may sleep. Default 0.5
+  sleepTime?: number;           // Seconds below both thresholds before sleeping. Default 0.5
+};
+
+/** Options for createPhysicsWorld(). Solver fields are the same as {@link PhysicsConfig}. */
+export type PhysicsWorldOptions = PhysicsConfig & {
   gravityX?: number;  // Default 0
   gravityY?: number;  // Default 9.81 (downward)
+  deterministic?: boolean;  // Reproducible stepping, see setPhysicsDeterministic(). Default false
 };
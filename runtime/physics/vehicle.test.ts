@@ -0,0 +1,30 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { _vehicleArrays, createVehicle, getVehicleState, setVehicleInput } from "./vehicle.ts";
+
+const hasVehicleOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_vehicle === "function";
+
+describe("vehicles", () => {
+  it("fills in defaults when flattening options", () => {
+    const [params, wheels] = _vehicleArrays({ wheels: [{ x: -20, y: 10 }, { x: 20, y: 10, steer: true, drive: false }] });
+    assert.deepEqual(params, [0, 400, 400, 800, 0.6, 800, 0.2, 0.6, 0xFFFF]);
+    assert.equal(wheels.length, 18);
+    assert.deepEqual(wheels.slice(0, 9), [-20, 10, 10, 12, 5, 0.7, 1, 1, 0]);
+    assert.deepEqual(wheels.slice(9), [20, 10, 10, 12, 5, 0.7, 1, 0, 1]);
+  });
+
+  it("passes the view and traction curve", () => {
+    const [params] = _vehicleArrays({ view: "topdown", wheels: [], traction: { sliding: 0.3 }, maxSteer: 0.4 });
+    assert.equal(params[0], 1);
+    assert.equal(params[4], 0.4);
+    assert.equal(params[6], 0.2);
+    assert.equal(params[7], 0.3);
+  });
+
+  it("is inert in headless mode", () => {
+    if (hasVehicleOps) return;
+    assert.equal(createVehicle(1, { wheels: [{ x: 0, y: 10 }] }), 0);
+    assert.equal(setVehicleInput(0, { throttle: 1 }), false);
+    assert.equal(getVehicleState(0), null);
+  });
+});
@@ -0,0 +1,210 @@
+/**
+ * Vehicles: a dynamic chassis body driven by raycast wheels.
+ *
+ * Wheels are simulated natively every physics sub-step instead of being
+ * separate jointed bodies, which keeps fast cars stable. Side-view wheels hang
+ * from spring suspension and roll along the ground; top-down wheels are always
+ * on the ground and steer. Each wheel spins on its own: the engine and brakes
+ * act on the spin, and tire grip falls off once the wheel slips, so cars spin
+ * their wheels on takeoff and drift through hard turns.
+ *
+ * The chassis faces its local +x axis. Side-view suspension points down its
+ * local +y axis.
+ *
+ * @example
+ * const chassis = createBody({ type: "dynamic", shape: { type: "box", halfW: 30, halfH: 10 }, x: 100, y: 100, mass: 10 });
+ * const car = createVehicle(chassis, {
+ *   wheels: [
+ *     { x: -20, y: 10, radius: 8, drive: true },
+ *     { x: 20, y: 10, radius: 8 },
+ *   ],
+ * });
+ * setVehicleInput(car, { throttle: isKeyDown("ArrowRight") ? 1 : 0 });
+ */
+
+import type { BodyId } from "./types.ts";
+
+const hasVehicleOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_vehicle === "function";
+
+/** Opaque handle to a vehicle. */
+export type VehicleId = number;
+
+/** A wheel, mounted at (x, y) in the chassis' local space. */
+export type VehicleWheelDef = {
+  x: number;
+  y: number;
+  /** Default: 10. */
+  radius?: number;
+  /** Side view: mount-to-wheel-center distance with the spring relaxed. Default: 12. */
+  suspensionLength?: number;
+  /** Side view: suspension spring frequency in Hz. Default: 5. */
+  frequencyHz?: number;
+  /** Side view: suspension damping ratio (1 = no bounce). Default: 0.7. */
+  dampingRatio?: number;
+  /** Tire grip as a multiple of the wheel's load. Default: 1. */
+  friction?: number;
+  /** Driven by the engine. Default: true. */
+  drive?: boolean;
+  /** Top down: turned by the steer input. Default: false. */
+  steer?: boolean;
+};
+
+/** Options for {@link createVehicle}. */
+export type VehicleOptions = {
+  /** "side" (gravity holds the car on its wheels) or "topdown". Default: "side". */
+  view?: "side" | "topdown";
+  wheels: VehicleWheelDef[];
+  /** Acceleration in pixels/s² at full throttle, grip permitting. Independent of mass. Default: 400. */
+  acceleration?: number;
+  /** Wheel surface speed in pixels/s where the engine stops pushing. Default: 400. */
+  maxSpeed?: number;
+  /** Deceleration in pixels/s² at full brake, grip permitting. Default: 800. */
+  braking?: number;
+  /** Top down: wheel angle at full steer, in radians. Default: 0.6. */
+  maxSteer?: number;
+  /** Top down: downward acceleration pressing the wheels onto the ground (pixels/s²). Default: 800. */
+  load?: number;
+  /**
+   * How grip falls off with slip. Tires keep full grip up to `peakSlip` (slip
+   * speed relative to ground speed) and `sliding` of it from twice that on.
+   * Default: { peakSlip: 0.2, sliding: 0.6 }.
+   */
+  traction?: { peakSlip?: number; sliding?: number };
+  /** Side view: collision layers the wheels roll on. Default: 0xFFFF. */
+  groundMask?: number;
+};
+
+/** Driver controls. Omitted fields are released (0). */
+export type VehicleInput = {
+  /** -1 (full reverse) to 1 (full throttle). */
+  throttle?: number;
+  /** 0 to 1. */
+  brake?: number;
+  /** Top down: -1 to 1, positive turns clockwise (right on screen). */
+  steer?: number;
+};
+
+/** A wheel after the last physics step. */
+export type VehicleWheelState = {
+  /** Wheel center in world space. */
+  x: number;
+  y: number;
+  /** Spin angle in radians, for drawing. */
+  rotation: number;
+  /** Spin speed in rad/s; positive rolls forward. */
+  spin: number;
+  /** Touching the ground (always true top down). */
+  grounded: boolean;
+  /** Slip (see `traction`); above the peak the tire is skidding. */
+  slip: number;
+};
+
+export type VehicleState = {
+  /** Chassis speed along its forward axis, in pixels/s. */
+  speed: number;
+  wheels: VehicleWheelState[];
+};
+
+/** Values per wheel in op_get_vehicle_state's output. */
+const WHEEL_STATE_STRIDE = 6;
+
+/** @internal Flatten vehicle options into the op's params and wheel arrays. */
+export function _vehicleArrays(options: VehicleOptions): [number[], number[]] {
+  const params = [
+    options.view === "topdown" ? 1 : 0,
+    options.acceleration ?? 400,
+    options.maxSpeed ?? 400,
+    options.braking ?? 800,
+    options.maxSteer ?? 0.6,
+    options.load ?? 800,
+    options.traction?.peakSlip ?? 0.2,
+    options.traction?.sliding ?? 0.6,
+    options.groundMask ?? 0xFFFF,
+  ];
+  // 9 values per wheel, in op_create_vehicle's order
+  const wheels: number[] = [];
+  for (const w of options.wheels) {
+    wheels.push(
+      w.x,
+      w.y,
+      w.radius ?? 10,
+      w.suspensionLength ?? 12,
+      w.frequencyHz ?? 5,
+      w.dampingRatio ?? 0.7,
+      w.friction ?? 1,
+      (w.drive ?? true) ? 1 : 0,
+      (w.steer ?? false) ? 1 : 0,
+    );
+  }
+  return [params, wheels];
+}
+
+/**
+ * Drive a dynamic body with wheels. The body stays a normal physics body;
+ * the vehicle adds wheel forces to it every step. Returns 0 in headless mode.
+ *
+ * @param chassis - Dynamic body to drive.
+ * @param options - Wheels, drivetrain and grip.
+ * @returns VehicleId for setVehicleInput() and getVehicleState().
+ * @throws If the chassis is not a dynamic body or there are no wheels.
+ */
+export function createVehicle(chassis: BodyId, options: VehicleOptions): VehicleId {
+  if (!hasVehicleOps) return 0;
+  const [params, wheels] = _vehicleArrays(options);
+  const id: number = (globalThis as any).Deno.core.ops.op_create_vehicle(chassis, params, wheels);
+  if (id === 0xFFFFFFFF) {
+    throw new Error("createVehicle: the chassis must be a dynamic body and at least one wheel is needed");
+  }
+  return id;
+}
+
+/**
+ * Set a vehicle's controls. They stay applied every physics step until set
+ * again, and wake a sleeping chassis. Values are clamped to their ranges.
+ *
+ * @returns False for an unknown vehicle or in headless mode.
+ */
+export function setVehicleInput(id: VehicleId, input: VehicleInput): boolean {
+  if (!hasVehicleOps) return false;
+  return (globalThis as any).Deno.core.ops.op_set_vehicle_input(
+    id,
+    input.throttle ?? 0,
+    input.brake ?? 0,
+    input.steer ?? 0,
+  );
+}
+
+/**
+ * Stop driving a vehicle. Its chassis body stays in the world. Destroying the
+ * chassis removes its vehicle too.
+ */
+export function removeVehicle(id: VehicleId): void {
+  if (!hasVehicleOps) return;
+  (globalThis as any).Deno.core.ops.op_remove_vehicle(id);
+}
+
+/**
+ * Speed and wheel positions of a vehicle, e.g. for drawing wheels, engine
+ * pitch and skid marks.
+ *
+ * @returns Null for an unknown vehicle or in headless mode.
+ */
+export function getVehicleState(id: VehicleId): VehicleState | null {
+  if (!hasVehicleOps) return null;
+  const data: number[] = (globalThis as any).Deno.core.ops.op_get_vehicle_state(id);
+  if (data.length === 0) return null;
+  const wheels: VehicleWheelState[] = [];
+  for (let i = 1; i + WHEEL_STATE_STRIDE <= data.length; i += WHEEL_STATE_STRIDE) {
+    wheels.push({
+      x: data[i],
+      y: data[i + 1],
+      rotation: data[i + 2],
+      spin: data[i + 3],
+      grounded: data[i + 4] !== 0,
+      slip: data[i + 5],
+    });
+  }
+  return { speed: data[0], wheels };
+}
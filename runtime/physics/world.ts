@@ -1,4 +1,4 @@
-import type { PhysicsWorldOptions } from "./types.ts";
+import type { CollisionEvent, PhysicsConfig, PhysicsWorldOptions } from "./types.ts";
 
 const hasPhysicsOps =
   typeof (globalThis as any).Deno !== "undefined" &&
@@ -7,6 +7,7 @@ const hasPhysicsOps =
 /**
  * Create a physics world with gravity.
  * Call once before creating bodies. Default gravity is (0, 9.81) -- downward.
+ * Solver settings ({@link PhysicsConfig}) may be passed in the same options.
  * No-op in headless mode.
  */
 export function createPhysicsWorld(options?: PhysicsWorldOptions): void {
@@ -14,16 +15,168 @@ export function createPhysicsWorld(options?: PhysicsWorldOptions): void {
   const gx = options?.gravityX ?? 0;
   const gy = options?.gravityY ?? 9.81;
   (globalThis as any).Deno.core.ops.op_create_physics_world(gx, gy);
+  if (options) setPhysicsConfig(options);
+  if (options?.deterministic) setPhysicsDeterministic(true);
 }
 
+/**
+ * Make the current world reproducible: given the same bodies, calls and
+ * stepPhysics() dt values, it produces bit-identical results on every run.
+ * For lockstep netcode and replays. Results match across operating systems
+ * and CPUs only when the engine is built with the `deterministic-math`
+ * feature. No-op in headless mode or without a world.
+ */
+export function setPhysicsDeterministic(enabled: boolean): void {
+  if (!hasPhysicsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_physics_deterministic(enabled);
+}
+
+/**
+ * Hash of the whole simulation state (bodies, constraints, step count) as
+ * 16 hex digits. Compare hashes between peers or test runs to find the step
+ * where two simulations diverge.
+ * Returns "" in headless mode or without a world.
+ *
+ * @example
+ * stepPhysics(FIXED_DT);
+ * sendToPeer({ frame, hash: getPhysicsStateHash() });
+ */
+export function getPhysicsStateHash(): string {
+  if (!hasPhysicsOps) return "";
+  return (globalThis as any).Deno.core.ops.op_physics_state_hash();
+}
+
+/**
+ * Change the solver settings of the current world. Omitted fields are unchanged.
+ * Counts are clamped to supported ranges. No-op in headless mode or without a world.
+ *
+ * @example
+ * // Cheap physics for a particle-heavy arcade game
+ * setPhysicsConfig({ substeps: 2, velocityIterations: 4, positionIterations: 1 });
+ */
+export function setPhysicsConfig(config: PhysicsConfig): void {
+  if (!hasPhysicsOps) return;
+  const v = (n: number | undefined) => (n === undefined || !Number.isFinite(n) ? -1 : Math.max(0, n));
+  (globalThis as any).Deno.core.ops.op_physics_config(
+    v(config.substeps),
+    v(config.velocityIterations),
+    v(config.positionIterations),
+    v(config.sleepVelocity),
+    v(config.sleepAngularVelocity),
+    v(config.sleepTime),
+  );
+}
+
+/**
+ * Get the current world's solver settings. Returns null in headless mode or without a world.
+ */
+export function getPhysicsConfig(): Required<PhysicsConfig> | null {
+  if (!hasPhysicsOps) return null;
+  const c: number[] = (globalThis as any).Deno.core.ops.op_get_physics_config();
+  if (c.length < 6) return null;
+  return {
+    substeps: c[0],
+    velocityIterations: c[1],
+    positionIterations: c[2],
+    sleepVelocity: c[3],
+    sleepAngularVelocity: c[4],
+    sleepTime: c[5],
+  };
+}
+
+const collisionTypes = ["begin", "stay", "end"] as const;
+const collisionListeners: Array<(event: CollisionEvent) => void> = [];
+let lastCollisionEvents: CollisionEvent[] = [];
+
 /**
  * Advance the physics simulation by dt seconds.
  * Uses fixed timestep internally (1/60s) with accumulator.
+ * Collision events of the step are then passed to {@link onCollision} listeners.
  * No-op in headless mode.
  */
 export function stepPhysics(dt: number): void {
   if (!hasPhysicsOps) return;
   (globalThis as any).Deno.core.ops.op_physics_step(dt);
+
+  const flat: number[] = (globalThis as any).Deno.core.ops.op_get_collision_events();
+  const events: CollisionEvent[] = [];
+  for (let i = 0; i + 6 < flat.length; i += 7) {
+    events.push({
+      type: collisionTypes[flat[i]] ?? "stay",
+      bodyA: flat[i + 1],
+      bodyB: flat[i + 2],
+      normalX: flat[i + 3],
+      normalY: flat[i + 4],
+      pointX: flat[i + 5],
+      pointY: flat[i + 6],
+    });
+  }
+  lastCollisionEvents = events;
+  for (const event of events) {
+    for (const listener of [...collisionListeners]) listener(event);
+  }
+}
+
+/**
+ * Collision events from the last {@link stepPhysics} call: a "begin" for each
+ * pair that started touching, a "stay" for each pair still touching and an
+ * "end" for each pair that separated or lost a body to destroyBody().
+ * Returns an empty array in headless mode.
+ */
+export function getCollisionEvents(): CollisionEvent[] {
+  return lastCollisionEvents;
+}
+
+/**
+ * Listen for collision events. Listeners run inside {@link stepPhysics}, once
+ * per event, in the same order as {@link getCollisionEvents}.
+ *
+ * @returns A function that removes the listener.
+ *
+ * @example
+ * onCollision((e) => {
+ *   if (e.type !== "begin") return;
+ *   if (e.bodyA === coinBody || e.bodyB === coinBody) collectCoin();
+ * });
+ */
+export function onCollision(listener: (event: CollisionEvent) => void): () => void {
+  collisionListeners.push(listener);
+  return () => {
+    const index = collisionListeners.indexOf(listener);
+    if (index >= 0) collisionListeners.splice(index, 1);
+  };
+}
+
+/**
+ * Capture the whole physics world -- bodies, joints, solver state and the
+ * step accumulator -- as bytes. Restoring it with {@link restorePhysicsSnapshot}
+ * continues the simulation exactly where it was, with the same body and
+ * joint IDs. For rewind, quick saves and rollback netcode; snapshots are
+ * only guaranteed to load in the engine version that made them.
+ * Returns null in headless mode or without a world.
+ *
+ * @example
+ * const checkpoint = capturePhysicsSnapshot();
+ * // ... later
+ * if (checkpoint) restorePhysicsSnapshot(checkpoint);
+ */
+export function capturePhysicsSnapshot(): Uint8Array | null {
+  if (!hasPhysicsOps) return null;
+  const data: Uint8Array = (globalThis as any).Deno.core.ops.op_physics_snapshot();
+  return data.length > 0 ? data : null;
+}
+
+/**
+ * Replace the physics world with a snapshot from {@link capturePhysicsSnapshot},
+ * creating the world if none exists. Collision events of the last step are
+ * cleared. Returns false, leaving the world unchanged, if the data is not a
+ * valid snapshot; also false in headless mode.
+ */
+export function restorePhysicsSnapshot(snapshot: Uint8Array): boolean {
+  if (!hasPhysicsOps) return false;
+  const ok: boolean = (globalThis as any).Deno.core.ops.op_physics_restore(snapshot);
+  if (ok) lastCollisionEvents = [];
+  return ok;
 }
 
 /**
@@ -31,6 +184,7 @@ export function stepPhysics(dt: number): void {
  * No-op in headless mode.
  */
 export function destroyPhysicsWorld(): void {
+  lastCollisionEvents = [];
   if (!hasPhysicsOps) return;
   (globalThis as any).Deno.core.ops.op_destroy_physics_world();
 }
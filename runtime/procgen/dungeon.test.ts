@@ -0,0 +1,49 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  DungeonTile,
+  generateBspDungeon,
+  generateCaves,
+  generateDrunkardWalk,
+} from "./dungeon.ts";
+
+describe("Dungeon generators", () => {
+  it("tile constants match the Rust grid values", () => {
+    assert.equal(DungeonTile.Wall, 0);
+    assert.equal(DungeonTile.Floor, 1);
+    assert.equal(DungeonTile.Door, 2);
+  });
+
+  it("generateBspDungeon returns null headless or a full grid with rooms", () => {
+    const d = generateBspDungeon(60, 40, 1);
+    if (d === null) return;
+    assert.equal(d.width, 60);
+    assert.equal(d.tiles.length, 60 * 40);
+    assert.ok(d.rooms.length > 0);
+    assert.equal(d.spawns.length, d.rooms.length);
+  });
+
+  it("generateBspDungeon is deterministic for a seed", () => {
+    const a = generateBspDungeon(40, 30, 5);
+    const b = generateBspDungeon(40, 30, 5);
+    if (a === null || b === null) return;
+    assert.deepEqual(a, b);
+  });
+
+  it("generateCaves returns null headless or walkable spawns", () => {
+    const d = generateCaves(48, 32, 3, { maxSpawns: 4 });
+    if (d === null) return;
+    assert.equal(d.tiles.length, 48 * 32);
+    assert.ok(d.spawns.length <= 4);
+    for (const s of d.spawns) {
+      assert.notEqual(d.tiles[s.y * d.width + s.x], DungeonTile.Wall);
+    }
+  });
+
+  it("generateDrunkardWalk carves roughly the requested floor", () => {
+    const d = generateDrunkardWalk(40, 30, 9, { floorPercent: 0.3 });
+    if (d === null) return;
+    const floor = d.tiles.filter((t) => t !== DungeonTile.Wall).length;
+    assert.ok(floor >= Math.floor(38 * 28 * 0.3));
+    assert.equal(d.rooms.length, 0);
+  });
+});
@@ -0,0 +1,183 @@
+/**
+ * Classic roguelike dungeon generators backed by Rust ops.
+ *
+ * - `generateBspDungeon`: rectangular rooms joined by L-shaped corridors
+ * - `generateCaves`: cellular automata caves (largest region only)
+ * - `generateDrunkardWalk`: winding tunnels carved by a random walker
+ *
+ * All generators are seeded and deterministic. Every floor tile in the
+ * result is reachable from every other floor tile.
+ */
+
+const hasDungeonOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_dungeon_bsp === "function";
+
+/** Tile values in a generated dungeon grid. */
+export const DungeonTile = {
+  Wall: 0,
+  Floor: 1,
+  Door: 2,
+} as const;
+
+/** A rectangular room in tile coordinates. */
+export type DungeonRoom = { x: number; y: number; w: number; h: number };
+
+/** A tile position. */
+export type DungeonPoint = { x: number; y: number };
+
+/** Output of a dungeon generator. */
+export type DungeonResult = {
+  width: number;
+  height: number;
+  /** Row-major tiles, `width * height` entries. See {@link DungeonTile}. */
+  tiles: number[];
+  /** Room rectangles. Empty for caves and drunkard's walk. */
+  rooms: DungeonRoom[];
+  /** Door tiles where a corridor enters a room. BSP only. */
+  doors: DungeonPoint[];
+  /** Suggested spawn points: room centers (BSP) or open floor tiles. */
+  spawns: DungeonPoint[];
+};
+
+/** Options for {@link generateBspDungeon}. */
+export type BspOptions = {
+  /** Minimum room width/height in tiles. Default: 4. */
+  minRoomSize?: number;
+  /** Maximum split depth. Up to 2^depth rooms. Default: 4. */
+  maxDepth?: number;
+};
+
+/** Options for {@link generateCaves}. */
+export type CaveOptions = {
+  /** Initial wall probability (0-1). Default: 0.45. */
+  fillPercent?: number;
+  /** Number of smoothing passes (at most 32). Default: 5. */
+  iterations?: number;
+  /** Maximum spawn points to return. Default: 16. */
+  maxSpawns?: number;
+};
+
+/** Options for {@link generateDrunkardWalk}. */
+export type DrunkardOptions = {
+  /** Fraction of the interior to carve into floor (0-1). Default: 0.4. */
+  floorPercent?: number;
+  /** Maximum spawn points to return. Default: 16. */
+  maxSpawns?: number;
+};
+
+function parseDungeon(json: string): DungeonResult {
+  const raw = JSON.parse(json) as {
+    width: number;
+    height: number;
+    tiles: number[];
+    rooms: [number, number, number, number][];
+    doors: [number, number][];
+    spawns: [number, number][];
+  };
+  return {
+    width: raw.width,
+    height: raw.height,
+    tiles: raw.tiles,
+    rooms: raw.rooms.map(([x, y, w, h]) => ({ x, y, w, h })),
+    doors: raw.doors.map(([x, y]) => ({ x, y })),
+    spawns: raw.spawns.map(([x, y]) => ({ x, y })),
+  };
+}
+
+/**
+ * Generate a dungeon of rectangular rooms by binary space partitioning.
+ * Sibling partitions are connected with corridors, so all rooms are reachable.
+ *
+ * Returns null in headless mode (no Rust ops available).
+ *
+ * @param width  Grid width in tiles (at most 1024).
+ * @param height Grid height in tiles (at most 1024).
+ * @param seed   Random seed. Same seed = same dungeon.
+ * @param options Room size and split depth.
+ *
+ * @example
+ * const dungeon = generateBspDungeon(80, 50, 1234, { minRoomSize: 5 });
+ * if (dungeon) {
+ *   const start = dungeon.spawns[0];
+ *   player.x = start.x * TILE_SIZE;
+ * }
+ */
+export function generateBspDungeon(
+  width: number,
+  height: number,
+  seed: number,
+  options: BspOptions = {},
+): DungeonResult | null {
+  if (!hasDungeonOps) return null;
+  const json = (globalThis as any).Deno.core.ops.op_dungeon_bsp(
+    width >>> 0,
+    height >>> 0,
+    seed >>> 0,
+    (options.minRoomSize ?? 0) >>> 0,
+    (options.maxDepth ?? 0) >>> 0,
+  );
+  return parseDungeon(json);
+}
+
+/**
+ * Generate organic caves with a cellular automaton (4-5 rule).
+ * Disconnected pockets are filled in, leaving one connected cave.
+ *
+ * Returns null in headless mode (no Rust ops available).
+ *
+ * @param width  Grid width in tiles (at most 1024).
+ * @param height Grid height in tiles (at most 1024).
+ * @param seed   Random seed. Same seed = same caves.
+ * @param options Fill density, smoothing passes, spawn count.
+ *
+ * @example
+ * const cave = generateCaves(64, 48, 7, { fillPercent: 0.48 });
+ */
+export function generateCaves(
+  width: number,
+  height: number,
+  seed: number,
+  options: CaveOptions = {},
+): DungeonResult | null {
+  if (!hasDungeonOps) return null;
+  const json = (globalThis as any).Deno.core.ops.op_dungeon_caves(
+    width >>> 0,
+    height >>> 0,
+    seed >>> 0,
+    options.fillPercent ?? 0,
+    (options.iterations ?? 0) >>> 0,
+    (options.maxSpawns ?? 0) >>> 0,
+  );
+  return parseDungeon(json);
+}
+
+/**
+ * Carve winding tunnels with a random walk starting at the map center.
+ *
+ * Returns null in headless mode (no Rust ops available).
+ *
+ * @param width  Grid width in tiles (at most 1024).
+ * @param height Grid height in tiles (at most 1024).
+ * @param seed   Random seed. Same seed = same tunnels.
+ * @param options Target floor coverage and spawn count.
+ *
+ * @example
+ * const mine = generateDrunkardWalk(60, 40, 99, { floorPercent: 0.35 });
+ */
+export function generateDrunkardWalk(
+  width: number,
+  height: number,
+  seed: number,
+  options: DrunkardOptions = {},
+): DungeonResult | null {
+  if (!hasDungeonOps) return null;
+  const json = (globalThis as any).Deno.core.ops.op_dungeon_drunkard(
+    width >>> 0,
+    height >>> 0,
+    seed >>> 0,
+    options.floorPercent ?? 0,
+    (options.maxSpawns ?? 0) >>> 0,
+  );
+  return parseDungeon(json);
+}
@@ -29,3 +29,19 @@ export {
 
 // Validation & batch testing
 export { validateLevel, generateAndTest } from "./validate.ts";
+
+// Dungeon generators (Rust-backed)
+export type {
+  DungeonRoom,
+  DungeonPoint,
+  DungeonResult,
+  BspOptions,
+  CaveOptions,
+  DrunkardOptions,
+} from "./dungeon.ts";
+export {
+  DungeonTile,
+  generateBspDungeon,
+  generateCaves,
+  generateDrunkardWalk,
+} from "./dungeon.ts";
@@ -0,0 +1,35 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { createAsepriteAnimation, getAsepriteSlice, loadAseprite, type AsepriteSheet } from "./aseprite.ts";
+
+const sheet: AsepriteSheet = {
+  sheetId: 1,
+  textureId: 1,
+  width: 32,
+  height: 16,
+  frames: [],
+  tags: [{ name: "walk", from: 0, to: 1, direction: "forward", repeat: 0 }],
+  slices: [
+    {
+      name: "hitbox",
+      keys: [
+        { frame: 0, bounds: { x: 2, y: 2, w: 12, h: 14 } },
+        { frame: 3, bounds: { x: 4, y: 2, w: 8, h: 14 } },
+      ],
+    },
+  ],
+};
+
+describe("Aseprite sheets", () => {
+  it("getAsepriteSlice picks the last key at or before the frame", () => {
+    assert.equal(getAsepriteSlice(sheet, "hitbox", 0)!.bounds.w, 12);
+    assert.equal(getAsepriteSlice(sheet, "hitbox", 2)!.bounds.w, 12);
+    assert.equal(getAsepriteSlice(sheet, "hitbox", 5)!.bounds.w, 8);
+    assert.equal(getAsepriteSlice(sheet, "pivot"), null);
+  });
+
+  it("is inert in headless mode", () => {
+    assert.equal(loadAseprite("assets/hero.json"), null);
+    assert.equal(createAsepriteAnimation(null, "walk"), 0);
+    assert.equal(createAsepriteAnimation(sheet, "walk"), 0);
+  });
+});
@@ -0,0 +1,140 @@
+/**
+ * Sprite sheets exported from Aseprite.
+ *
+ * Export with File > Export Sprite Sheet and "JSON Data" checked (Hash or
+ * Array layout both work). {@link loadAseprite} reads the JSON natively and
+ * loads the sheet image; {@link createAsepriteAnimation} turns a frame tag
+ * into an engine-driven animation (see `sprite-animation.ts`) that keeps
+ * Aseprite's per-frame durations, direction and repeat count. Draw it with
+ * `drawAnimated()`.
+ *
+ * In headless mode {@link loadAseprite} returns null.
+ */
+
+import type { TextureId } from "./types.ts";
+import type { SpriteAnimationId } from "./sprite-animation.ts";
+
+const hasAsepriteOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_aseprite === "function";
+
+/** One frame. Rects are in sheet pixels. */
+export type AsepriteFrame = {
+  name: string;
+  x: number;
+  y: number;
+  w: number;
+  h: number;
+  /** Where a trimmed frame sits inside the untrimmed sprite. */
+  offsetX: number;
+  offsetY: number;
+  /** Size of the untrimmed sprite. */
+  sourceW: number;
+  sourceH: number;
+  /** Milliseconds the frame is shown. */
+  duration: number;
+};
+
+/** A named frame range (Aseprite "frame tag"). */
+export type AsepriteTag = {
+  name: string;
+  /** First and last frame, inclusive. */
+  from: number;
+  to: number;
+  direction: "forward" | "reverse" | "pingpong" | "pingpong_reverse";
+  /** Passes to play before stopping, 0 = forever. */
+  repeat: number;
+};
+
+type Rect = { x: number; y: number; w: number; h: number };
+
+/** A slice's shape from `frame` on. */
+export type AsepriteSliceKey = {
+  frame: number;
+  /** Rect relative to the frame. */
+  bounds: Rect;
+  /** Nine-slice center, relative to `bounds`. */
+  center?: Rect;
+  /** Pivot point, relative to `bounds`. */
+  pivot?: { x: number; y: number };
+};
+
+/** A named rect: hitboxes, attachment points, nine-slice borders. */
+export type AsepriteSlice = {
+  name: string;
+  /** User data text, if any. */
+  data?: string;
+  /** Keys in frame order. */
+  keys: AsepriteSliceKey[];
+};
+
+export type AsepriteSheet = {
+  sheetId: number;
+  /** Texture of the sheet image. */
+  textureId: TextureId;
+  /** Sheet image size in pixels. */
+  width: number;
+  height: number;
+  frames: AsepriteFrame[];
+  tags: AsepriteTag[];
+  slices: AsepriteSlice[];
+};
+
+/**
+ * Load an Aseprite sheet: the exported JSON file, or an `.aseprite`/`.ase`
+ * file with its JSON export saved next to it. The sheet image named in the
+ * JSON loads as a texture. Loading the same path again returns the same sheet.
+ *
+ * @returns The sheet, or null in headless mode.
+ * @throws If the file is missing or not an Aseprite export.
+ *
+ * @example
+ * const hero = loadAseprite("assets/hero.json")!;
+ * const walk = createAsepriteAnimation(hero, "walk");
+ * drawAnimated(walk, { x: player.x, y: player.y, w: 32, h: 32 });
+ */
+export function loadAseprite(path: string): AsepriteSheet | null {
+  if (!hasAsepriteOps) return null;
+  const result = JSON.parse((globalThis as any).Deno.core.ops.op_load_aseprite(path));
+  if (result.error) {
+    throw new Error(`Failed to load Aseprite sheet: ${result.error}`);
+  }
+  return result as AsepriteSheet;
+}
+
+/**
+ * Create an engine animation from a frame tag. Forward, reverse and
+ * ping-pong tags loop; tags with a repeat count play that many passes and
+ * finish. Without a tag, every frame of the sheet plays in a loop.
+ *
+ * @returns Animation ID for drawAnimated(), or 0 in headless mode.
+ * @throws If the sheet has no tag with that name.
+ */
+export function createAsepriteAnimation(sheet: AsepriteSheet | null, tag?: string): SpriteAnimationId {
+  if (!hasAsepriteOps || !sheet) return 0;
+  if (tag !== undefined && !sheet.tags.some((t) => t.name === tag)) {
+    const names = sheet.tags.map((t) => `"${t.name}"`).join(", ") || "none";
+    throw new Error(`createAsepriteAnimation: no tag "${tag}" (tags: ${names})`);
+  }
+  return (globalThis as any).Deno.core.ops.op_create_aseprite_animation(sheet.sheetId, tag ?? "");
+}
+
+/**
+ * The key of a slice in effect on sheet frame `frame` (the last key at or
+ * before it).
+ *
+ * @returns Null if the sheet has no such slice or it has no key yet.
+ *
+ * @example
+ * const hitbox = getAsepriteSlice(hero, "hitbox", 2)?.bounds;
+ */
+export function getAsepriteSlice(sheet: AsepriteSheet, name: string, frame: number = 0): AsepriteSliceKey | null {
+  const slice = sheet.slices.find((s) => s.name === name);
+  if (!slice) return null;
+  let found: AsepriteSliceKey | null = null;
+  for (const key of slice.keys) {
+    if (key.frame > frame) break;
+    found = key;
+  }
+  return found;
+}
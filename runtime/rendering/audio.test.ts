@@ -7,10 +7,21 @@ import {
   stopAll,
   setVolume,
   playSoundAt,
+  setSpatialParams,
+  setSoundOcclusion,
+  setSoundDoppler,
+  setSoundPosition,
   crossfadeMusic,
+  fadeInInstance,
+  fadeOutInstance,
+  fadeOutMusic,
+  stopInstanceAfter,
   stopInstance,
   setBusVolume,
   getBusVolume,
+  setBusEffect,
+  clearBusEffects,
+  getAudioLevels,
   setListenerPosition,
   updateSpatialAudio,
   setPoolConfig,
@@ -119,6 +130,28 @@ describe("audio", () => {
     updateSpatialAudio();
   });
 
+  it("spatial attenuation options do not throw in headless mode", () => {
+    const id = playSoundAt(0, { x: 10, y: 20, curve: "linear", rolloff: 2 });
+    setSpatialParams(id, { curve: "exponential", minDistance: 20, maxDistance: 800, rolloff: 1.5 });
+    setSpatialParams(id, {});
+  });
+
+  it("sound occlusion does not throw in headless mode", () => {
+    const id = playSoundAt(0, { x: 10, y: 20, loop: true, occlusion: { layers: 0b10 } });
+    setSoundOcclusion(id, { cutoff: 500, volume: 0.2, smoothing: 0 });
+    setSoundOcclusion(id, null);
+  });
+
+  it("doppler does not throw in headless mode", () => {
+    const id = playSoundAt(0, { x: 10, y: 20, loop: true, doppler: { factor: 2 } });
+    setSoundDoppler(id, { minPitch: 0.8, maxPitch: 1.2 });
+    setSoundPosition(id, 50, 20);
+    updateSpatialAudio();
+    setSoundDoppler(id, null);
+    // Unknown instances are ignored
+    setSoundPosition(99999, 0, 0);
+  });
+
   it("updateSpatialAudio with active spatial sounds does not throw", () => {
     playSoundAt(0, { x: 10, y: 20 });
     playSoundAt(0, { x: 30, y: 40 });
@@ -194,6 +227,30 @@ describe("audio", () => {
     assert.ok(id1 > 0 && id2 > 0 && id3 > 0, "All music plays should return IDs");
   });
 
+  it("fades and scheduled stops are no-ops in headless mode", () => {
+    const id = playSound(0, { loop: true });
+    fadeInInstance(id, 1.0);
+    fadeOutInstance(id, 0.5);
+    stopInstanceAfter(id, 2.0);
+    playMusic("music.ogg");
+    fadeOutMusic(2.0);
+    fadeOutMusic(); // no current music: nothing to fade
+  });
+
+  it("bus effects are no-ops in headless mode", () => {
+    setBusEffect("sfx", { type: "lowPass", cutoff: 800 });
+    setBusEffect("music", { type: "delay", mix: 0.5, delayMs: 300 });
+    setBusEffect("ambient", { type: "reverb" });
+    clearBusEffects("sfx");
+  });
+
+  it("getAudioLevels reports silence in headless mode", () => {
+    const levels = getAudioLevels();
+    for (const key of ["master", "sfx", "music", "ambient", "voice"] as const) {
+      assert.deepEqual(levels[key], { rms: 0, peak: 0 });
+    }
+  });
+
   it("spatial instances are tracked separately", () => {
     const regularId = playSound(0);
     const spatialId = playSoundAt(0, { x: 10, y: 20 });
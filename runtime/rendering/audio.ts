@@ -1,4 +1,4 @@
-import { tween } from "../tweening/tween.ts";
+import { curveId, type Curve } from "../tweening/curve.ts";
 
 /**
  * Opaque handle to a loaded sound. Returned by {@link loadSound}.
@@ -43,7 +43,62 @@ export type PlayOptions = {
   reverbDelay?: number;
 };
 
-/** Options for {@link playSoundAt} spatial audio. */
+/**
+ * Distance falloff curve for spatial sounds.
+ * - "linear" — straight line from full volume at minDistance to silence at maxDistance
+ * - "inverse" — `min / (min + rolloff * (d - min))`, loud up close with a long tail
+ * - "exponential" — `(d / min) ^ -rolloff`, steeper than inverse for rolloff > 1
+ */
+export type SpatialCurve = "linear" | "inverse" | "exponential";
+
+/** Distance attenuation for {@link setSpatialParams}. Distances are in world pixels. */
+export type SpatialParams = {
+  /** Falloff curve. Default: "inverse". */
+  curve?: SpatialCurve;
+  /** Full volume at or below this distance. Default: 50. */
+  minDistance?: number;
+  /** Silent beyond this distance. Default: 500. */
+  maxDistance?: number;
+  /** Steepness of the curve; 0 = no falloff until maxDistance. Default: 1. */
+  rolloff?: number;
+};
+
+/**
+ * Muffling of a spatial sound while level geometry blocks it, for
+ * {@link setSoundOcclusion}. Each {@link updateSpatialAudio} raycasts from the
+ * listener to the sound against physics bodies on `layers`.
+ */
+export type SoundOcclusion = {
+  /** Collision layers that block sound (bitmask, as in physics bodies). Default: 0xFFFF. */
+  layers?: number;
+  /** Low-pass cutoff in Hz when fully blocked. Default: 800. */
+  cutoff?: number;
+  /** Volume multiplier when fully blocked, 0-1. Default: 0.5. */
+  volume?: number;
+  /** Seconds to ease between open and blocked. Default: 0.15. */
+  smoothing?: number;
+};
+
+/**
+ * Doppler pitch shift of a moving spatial sound, for {@link setSoundDoppler}.
+ * Velocities are measured from how far {@link updateSpatialAudio} moves the
+ * sound and the listener between calls.
+ */
+export type SoundDoppler = {
+  /** Multiplier on both velocities; above 1 exaggerates the effect. Default: 1. */
+  factor?: number;
+  /** Lowest pitch multiplier the shift can reach. Default: 0.5. */
+  minPitch?: number;
+  /** Highest pitch multiplier the shift can reach. Default: 2. */
+  maxPitch?: number;
+};
+
+/**
+ * Options for {@link playSoundAt} spatial audio. Setting any of `maxDistance`,
+ * `refDistance`, `curve` or `rolloff` switches the sound to configurable
+ * attenuation (see {@link setSpatialParams}); otherwise it uses the audio
+ * backend's built-in inverse-square falloff.
+ */
 export type SpatialOptions = PlayOptions & {
   /** World X coordinate of sound source. */
   x: number;
@@ -51,10 +106,43 @@ export type SpatialOptions = PlayOptions & {
   y: number;
   /** Maximum audible distance from listener. Default: 500. */
   maxDistance?: number;
-  /** Reference distance for volume falloff. Default: 50. */
+  /** Reference distance for volume falloff: full volume within it. Default: 50. */
   refDistance?: number;
+  /** Falloff curve. Default: "inverse". */
+  curve?: SpatialCurve;
+  /** Steepness of the falloff. Default: 1. */
+  rolloff?: number;
+  /** Muffle the sound behind walls (see {@link setSoundOcclusion}). */
+  occlusion?: SoundOcclusion;
+  /** Shift the pitch with motion (see {@link setSoundDoppler}). */
+  doppler?: SoundDoppler;
+};
+
+/**
+ * One stage of a bus effect chain, for {@link setBusEffect}. Each bus runs
+ * low-pass → delay → reverb; setting a stage replaces its previous settings.
+ * - "lowPass" — cut frequencies above `cutoff` Hz (0 disables)
+ * - "delay" — echo every `delayMs`, each repeat `feedback` (0-0.95) as loud as the last
+ * - "reverb" — room reverb built from `delayMs`-long reflections ringing with `decay` (0-0.95)
+ *
+ * `mix` is the wet level, 0 (dry, disabled) to 1 (fully wet).
+ */
+export type BusEffect =
+  | { type: "lowPass"; cutoff: number }
+  | { type: "delay"; mix?: number; delayMs?: number; feedback?: number }
+  | { type: "reverb"; mix?: number; delayMs?: number; decay?: number };
+
+/** Signal level as linear amplitude, 0 (silent) to 1 (full scale). */
+export type AudioLevel = {
+  /** Smoothed RMS (loudness) over roughly the last 100 ms. */
+  rms: number;
+  /** Recent peak, held and released over about a second. */
+  peak: number;
 };
 
+/** Output levels of the master mix and each bus, from {@link getAudioLevels}. */
+export type AudioLevels = Record<AudioBus | "master", AudioLevel>;
+
 /** Pool configuration for limiting concurrent instances of a sound. */
 export type PoolConfig = {
   /** Maximum concurrent instances. Default: unlimited. */
@@ -276,52 +364,192 @@ export function playSoundAt(id: SoundId, options: SpatialOptions): InstanceId {
     listenerY
   );
 
+  const { maxDistance, refDistance, curve, rolloff } = options;
+  if (maxDistance !== undefined || refDistance !== undefined || curve !== undefined || rolloff !== undefined) {
+    setSpatialParams(instanceId, { curve, minDistance: refDistance, maxDistance, rolloff });
+  }
+  if (options.occlusion) {
+    setSoundOcclusion(instanceId, options.occlusion);
+  }
+  if (options.doppler) {
+    setSoundDoppler(instanceId, options.doppler);
+  }
+
   return instanceId;
 }
 
+/** Curve string to u32 mapping (matches `Attenuation` in the audio thread) */
+const CURVE_MAP: Record<SpatialCurve, number> = {
+  linear: 0,
+  inverse: 1,
+  exponential: 2,
+};
+
+/**
+ * Set how a spatial sound fades with distance from the listener, replacing
+ * the built-in inverse-square falloff. Applied on the audio thread, and
+ * re-evaluated whenever {@link updateSpatialAudio} moves the sound or listener.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSoundAt().
+ * @param params - Curve, min/max distance and rolloff. Missing fields use the defaults.
+ *
+ * @example
+ * // A waterfall audible across the level, fading linearly
+ * const falls = playSoundAt(waterSfx, { x: 1200, y: 300, loop: true, bus: "ambient" });
+ * setSpatialParams(falls, { curve: "linear", minDistance: 100, maxDistance: 1500 });
+ */
+export function setSpatialParams(instanceId: InstanceId, params: SpatialParams): void {
+  if (!hasRenderOps) return;
+  const minDistance = Math.max(0, params.minDistance ?? 50);
+  const maxDistance = Math.max(minDistance, params.maxDistance ?? 500);
+  (globalThis as any).Deno.core.ops.op_set_spatial_params(
+    instanceId,
+    CURVE_MAP[params.curve ?? "inverse"] ?? 1,
+    minDistance,
+    maxDistance,
+    Math.max(0, params.rolloff ?? 1),
+  );
+}
+
+/**
+ * Muffle a spatial sound while physics bodies stand between it and the
+ * listener: a low-pass closes and the volume drops, easing in and out as the
+ * line of sight changes. The raycast runs in Rust on every
+ * {@link updateSpatialAudio}; pass `null` to turn occlusion off.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSoundAt().
+ * @param occlusion - Blocking layers, muffled cutoff and volume, smoothing time. Missing fields use the defaults.
+ *
+ * @example
+ * // Only walls (layer 2) muffle the generator, not the player or enemies
+ * const hum = playSoundAt(generatorSfx, { x: 640, y: 200, loop: true });
+ * setSoundOcclusion(hum, { layers: 0b10, cutoff: 600, volume: 0.3 });
+ */
+export function setSoundOcclusion(instanceId: InstanceId, occlusion: SoundOcclusion | null): void {
+  if (!hasRenderOps) return;
+  if (!occlusion) {
+    (globalThis as any).Deno.core.ops.op_set_sound_occlusion(instanceId, 0, 0, 1, 0);
+    return;
+  }
+  (globalThis as any).Deno.core.ops.op_set_sound_occlusion(
+    instanceId,
+    (occlusion.layers ?? 0xffff) & 0xffff,
+    Math.max(1, occlusion.cutoff ?? 800),
+    Math.min(1, Math.max(0, occlusion.volume ?? 0.5)),
+    Math.max(0, occlusion.smoothing ?? 0.15),
+  );
+}
+
+/**
+ * Shift a spatial sound's pitch up while it and the listener close in and
+ * down while they separate. Speeds are measured in Rust from the moves each
+ * {@link updateSpatialAudio} makes, at 10 world pixels per metre (sound
+ * travels 3430 px/s); pass `null` to turn Doppler off.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSoundAt().
+ * @param doppler - Velocity factor and pitch limits. Missing fields use the defaults.
+ *
+ * @example
+ * // A rival car: exaggerated so the pass-by is audible at game speeds
+ * const engine = playSoundAt(engineSfx, { x: car.x, y: car.y, loop: true });
+ * setSoundDoppler(engine, { factor: 3, minPitch: 0.7, maxPitch: 1.4 });
+ */
+export function setSoundDoppler(instanceId: InstanceId, doppler: SoundDoppler | null): void {
+  if (!hasRenderOps) return;
+  if (!doppler) {
+    (globalThis as any).Deno.core.ops.op_set_sound_doppler(instanceId, 0, 1, 1);
+    return;
+  }
+  const minPitch = Math.max(0.01, doppler.minPitch ?? 0.5);
+  (globalThis as any).Deno.core.ops.op_set_sound_doppler(
+    instanceId,
+    Math.max(0, doppler.factor ?? 1),
+    minPitch,
+    Math.max(minPitch, doppler.maxPitch ?? 2),
+  );
+}
+
 /**
  * Crossfade from current music to a new track.
- * Tweens the old music volume down and new music volume up over the specified duration.
+ * The old music fades out (and stops) while the new one fades in; both ramps
+ * run on the audio thread, so they stay smooth when the frame rate dips.
  * No-op in headless mode (but still returns a unique InstanceId).
  *
  * @param path - File path to the new music file.
  * @param duration - Crossfade duration in milliseconds. Default: 2000.
  * @param volume - Target volume for the new music. Default: 1.0.
+ * @param curve - Shape of both ramps (see defineEasingCurve()). Default: linear.
  * @returns Instance ID of the new music track.
  */
-export function crossfadeMusic(path: string, duration: number = 2000, volume: number = 1.0): InstanceId {
+export function crossfadeMusic(path: string, duration: number = 2000, volume: number = 1.0, curve?: Curve): InstanceId {
   const oldMusicId = currentMusicInstance;
-
-  // Start new music at volume 0
-  const newMusicId = playMusic(path, 0.0);
-  currentMusicInstance = newMusicId;
+  const newMusicId = playMusic(path, volume);
 
   if (!hasRenderOps) return newMusicId;
+  (globalThis as any).Deno.core.ops.op_crossfade_instances(oldMusicId, newMusicId, duration / 1000, curveId(curve));
+  return newMusicId;
+}
 
-  const durationSec = duration / 1000;
-
-  // Tween old music volume down
-  if (oldMusicId !== 0) {
-    const oldVolume = { value: 1.0 };
-    tween(oldVolume, { value: 0.0 }, durationSec, {
-      onUpdate: (progress: number) => {
-        setInstanceVolume(oldMusicId, oldVolume.value);
-      },
-      onComplete: () => {
-        stopInstance(oldMusicId);
-      },
-    });
-  }
+/**
+ * Fade a sound instance in from silence to its volume. Start the sound and
+ * call this in the same frame to avoid a pop. The ramp runs on the audio thread.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSound() or playSoundAt().
+ * @param duration - Fade duration in seconds.
+ * @param curve - Shape of the ramp (see defineEasingCurve()). Default: linear.
+ *
+ * @example
+ * const rain = playSound(rainSound, { loop: true, bus: "ambient" });
+ * fadeInInstance(rain, 3.0, defineEasingCurve("easeOutSine"));
+ */
+export function fadeInInstance(instanceId: InstanceId, duration: number, curve?: Curve): void {
+  if (!hasRenderOps) return;
+  (globalThis as any).Deno.core.ops.op_fade_in_instance(instanceId, Math.max(0, duration), curveId(curve));
+}
 
-  // Tween new music volume up
-  const newVolume = { value: 0.0 };
-  tween(newVolume, { value: volume }, durationSec, {
-    onUpdate: (progress: number) => {
-      setInstanceVolume(newMusicId, newVolume.value);
-    },
-  });
+/**
+ * Fade a sound instance out to silence, then stop it.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSound() or playSoundAt().
+ * @param duration - Fade duration in seconds.
+ * @param curve - Shape of the ramp (see defineEasingCurve()). Default: linear.
+ */
+export function fadeOutInstance(instanceId: InstanceId, duration: number, curve?: Curve): void {
+  if (!hasRenderOps) return;
+  (globalThis as any).Deno.core.ops.op_fade_out_instance(instanceId, Math.max(0, duration), curveId(curve));
+}
 
-  return newMusicId;
+/**
+ * Fade out the current music (from playMusic() or crossfadeMusic()), then stop it.
+ * No-op in headless mode.
+ *
+ * @param duration - Fade duration in seconds. Default: 1.0.
+ * @param curve - Shape of the ramp (see defineEasingCurve()). Default: linear.
+ *
+ * @example
+ * onBossDefeated(() => fadeOutMusic(2.0));
+ */
+export function fadeOutMusic(duration: number = 1.0, curve?: Curve): void {
+  const musicId = currentMusicInstance;
+  currentMusicInstance = 0;
+  if (musicId !== 0) fadeOutInstance(musicId, duration, curve);
+}
+
+/**
+ * Stop a sound instance after a delay, timed on the audio thread.
+ * No-op in headless mode.
+ *
+ * @param instanceId - Instance ID from playSound() or playSoundAt().
+ * @param delay - Seconds until the sound stops.
+ */
+export function stopInstanceAfter(instanceId: InstanceId, delay: number): void {
+  if (!hasRenderOps) return;
+  (globalThis as any).Deno.core.ops.op_stop_instance_after(instanceId, Math.max(0, delay));
 }
 
 /**
@@ -354,6 +582,62 @@ export function setBusVolume(bus: AudioBus, volume: number): void {
   (globalThis as any).Deno.core.ops.op_set_bus_volume(busId, volume);
 }
 
+/**
+ * Configure one stage of a bus's effect chain. Applies to sounds already
+ * playing on the bus as well as new ones; echoes and reverb tails keep
+ * ringing after a sound ends. No-op in headless mode.
+ *
+ * Defaults: delay `{ mix: 0.35, delayMs: 250, feedback: 0.4 }`,
+ * reverb `{ mix: 0.3, delayMs: 40, decay: 0.7 }`.
+ *
+ * @example
+ * // Underwater: muffle and add a short room
+ * setBusEffect("sfx", { type: "lowPass", cutoff: 600 });
+ * setBusEffect("sfx", { type: "reverb", mix: 0.4, delayMs: 30, decay: 0.8 });
+ */
+export function setBusEffect(bus: AudioBus, effect: BusEffect): void {
+  if (!hasRenderOps) return;
+  const ops = (globalThis as any).Deno.core.ops;
+  const busId = BUS_MAP[bus];
+  switch (effect.type) {
+    case "lowPass":
+      ops.op_set_bus_effect(busId, 0, Math.max(0, effect.cutoff), 0, 0);
+      break;
+    case "delay":
+      ops.op_set_bus_effect(busId, 1, effect.mix ?? 0.35, effect.delayMs ?? 250, effect.feedback ?? 0.4);
+      break;
+    case "reverb":
+      ops.op_set_bus_effect(busId, 2, effect.mix ?? 0.3, effect.delayMs ?? 40, effect.decay ?? 0.7);
+      break;
+  }
+}
+
+/**
+ * Turn off a bus's low-pass, delay and reverb.
+ * No-op in headless mode.
+ */
+export function clearBusEffects(bus: AudioBus): void {
+  setBusEffect(bus, { type: "lowPass", cutoff: 0 });
+  setBusEffect(bus, { type: "delay", mix: 0 });
+  setBusEffect(bus, { type: "reverb", mix: 0 });
+}
+
+/**
+ * Current output levels of the master mix and each bus, measured on the
+ * audio thread after volume, fades and bus effects. Use them to drive
+ * visualizers or duck one bus when another is loud. All zero in headless mode.
+ *
+ * @example
+ * // Duck music while dialog plays
+ * const { voice } = getAudioLevels();
+ * setBusVolume("music", voice.rms > 0.05 ? 0.3 : 1.0);
+ */
+export function getAudioLevels(): AudioLevels {
+  const values: number[] = hasRenderOps ? (globalThis as any).Deno.core.ops.op_get_audio_levels() : [];
+  const level = (i: number): AudioLevel => ({ rms: values[i * 2] ?? 0, peak: values[i * 2 + 1] ?? 0 });
+  return { master: level(0), sfx: level(1), music: level(2), ambient: level(3), voice: level(4) };
+}
+
 /**
  * Get the current volume of a specific audio bus.
  *
@@ -377,6 +661,22 @@ export function setListenerPosition(x: number, y: number): void {
   listenerY = y;
 }
 
+/**
+ * Move a spatial sound, e.g. to follow the object emitting it. Takes effect
+ * on the next {@link updateSpatialAudio}. Ignored for instances that are not
+ * spatial or have stopped.
+ *
+ * @param instanceId - Instance ID from playSoundAt().
+ * @param x - New world X position of the sound source.
+ * @param y - New world Y position of the sound source.
+ */
+export function setSoundPosition(instanceId: InstanceId, x: number, y: number): void {
+  const pos = spatialInstances.get(instanceId);
+  if (!pos) return;
+  pos.x = x;
+  pos.y = y;
+}
+
 /**
  * Update spatial audio for all active spatial instances.
  * Should be called once per frame (typically in onFrame callback) if using spatial audio.
@@ -0,0 +1,19 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  clearBackgroundFill,
+  setBackgroundGradient,
+  setBackgroundTexture,
+  setLetterboxColor,
+} from "./background.ts";
+
+describe("Background fill", () => {
+  it("is a no-op in headless mode", () => {
+    setBackgroundGradient({ from: { r: 0, g: 0, b: 0.2 }, to: { r: 1, g: 0.5, b: 0.2 }, horizontal: true });
+    setBackgroundTexture(1, { tile: { w: 64, h: 64 } });
+    setBackgroundTexture(1);
+    clearBackgroundFill();
+    setLetterboxColor({ r: 0, g: 0, b: 0 });
+    setLetterboxColor(null);
+    assert.ok(true);
+  });
+});
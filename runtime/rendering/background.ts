@@ -0,0 +1,86 @@
+/**
+ * Background fills and letterbox bars.
+ *
+ * {@link setBackgroundColor} clears the screen to a flat color. A background
+ * fill draws a gradient or texture there instead, behind every layer, and the
+ * letterbox color paints the area outside the camera bounds (the bars you get
+ * when the view is bigger than the bounds), independent of both.
+ *
+ * Fills and bars are drawn as sprites, so ambient and point lights affect
+ * them like any other sprite. All functions are no-ops in headless mode.
+ */
+
+import type { TextureId } from "./types.ts";
+
+const hasBackgroundOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_background_texture === "function";
+
+/** RGBA color in 0.0-1.0; alpha defaults to 1. */
+export type BackgroundColor = { r: number; g: number; b: number; a?: number };
+
+/** Options for {@link setBackgroundGradient}. */
+export type BackgroundGradient = {
+  /** Color at the top (or left edge when horizontal). */
+  from: BackgroundColor;
+  /** Color at the bottom (or right edge when horizontal). */
+  to: BackgroundColor;
+  /** Run left to right instead of top to bottom. Default: false. */
+  horizontal?: boolean;
+};
+
+/**
+ * Fill the background with a two-color gradient, fixed to the screen.
+ *
+ * @example
+ * // Dusk sky
+ * setBackgroundGradient({ from: rgb(0.1, 0.1, 0.3), to: rgb(0.9, 0.5, 0.3) });
+ */
+export function setBackgroundGradient(gradient: BackgroundGradient): void {
+  if (!hasBackgroundOps) return;
+  const { from, to } = gradient;
+  (globalThis as any).Deno.core.ops.op_set_background_gradient(
+    from.r, from.g, from.b, from.a ?? 1,
+    to.r, to.g, to.b, to.a ?? 1,
+    gradient.horizontal ?? false,
+  );
+}
+
+/**
+ * Fill the background with a texture, fixed to the screen. Stretched to the
+ * screen by default; pass `tile` to repeat it at that size (in screen pixels)
+ * from the top-left corner.
+ *
+ * @example
+ * setBackgroundTexture(loadTexture("assets/stars.png"), { tile: { w: 128, h: 128 } });
+ */
+export function setBackgroundTexture(textureId: TextureId, options?: { tile?: { w: number; h: number } }): void {
+  if (!hasBackgroundOps) return;
+  const tile = options?.tile;
+  (globalThis as any).Deno.core.ops.op_set_background_texture(textureId, tile?.w ?? 0, tile?.h ?? 0);
+}
+
+/** Remove the gradient or texture fill; the screen clears to the background color again. */
+export function clearBackgroundFill(): void {
+  if (!hasBackgroundOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_background_fill();
+}
+
+/**
+ * Color the area outside the camera bounds (see `setCameraBounds`), drawn over
+ * anything there, or `null` to show the background again. Use it for
+ * letterbox/pillarbox bars when the window's aspect ratio differs from the level.
+ *
+ * @example
+ * setCameraBounds({ minX: 0, minY: 0, maxX: 320, maxY: 180 });
+ * setLetterboxColor({ r: 0, g: 0, b: 0 });
+ */
+export function setLetterboxColor(color: BackgroundColor | null): void {
+  if (!hasBackgroundOps) return;
+  const ops = (globalThis as any).Deno.core.ops;
+  if (color) {
+    ops.op_set_letterbox_color(true, color.r, color.g, color.b, color.a ?? 1);
+  } else {
+    ops.op_set_letterbox_color(false, 0, 0, 0, 0);
+  }
+}
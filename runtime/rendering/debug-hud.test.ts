@@ -0,0 +1,18 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { setDebugHud, isDebugHudEnabled } from "./debug-hud.ts";
+
+const hasDebugHudOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_debug_hud === "function";
+
+describe("Debug HUD", () => {
+  it("reads as off in headless mode", () => {
+    setDebugHud(true);
+    if (!hasDebugHudOps) {
+      assert.equal(isDebugHudEnabled(), false);
+      return;
+    }
+    assert.equal(isDebugHudEnabled(), true);
+    setDebugHud(false);
+    assert.equal(isDebugHudEnabled(), false);
+  });
+});
@@ -0,0 +1,24 @@
+/**
+ * Built-in debug HUD: fps, frame time, draw calls, physics bodies, playing
+ * sounds and texture memory in the top-left corner of the window, drawn by
+ * the engine itself. No game code is needed to read it; press F3 in
+ * `arcane dev` to toggle it, or switch it from code (e.g. a debug menu).
+ *
+ * In headless mode both functions are no-ops and the HUD reads as off.
+ */
+
+const hasDebugHudOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_debug_hud === "function";
+
+/** Show or hide the debug HUD. Stays as set across hot reloads. */
+export function setDebugHud(enabled: boolean): void {
+  if (!hasDebugHudOps) return;
+  (globalThis as any).Deno.core.ops.op_set_debug_hud(enabled);
+}
+
+/** Whether the debug HUD is shown. False in headless mode. */
+export function isDebugHudEnabled(): boolean {
+  if (!hasDebugHudOps) return false;
+  return (globalThis as any).Deno.core.ops.op_is_debug_hud_enabled();
+}
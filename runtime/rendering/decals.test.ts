@@ -0,0 +1,12 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { spawnDecal, clearDecals, setMaxDecals, getDecalCount } from "./decals.ts";
+
+describe("Decals", () => {
+  it("is inert in headless mode", () => {
+    spawnDecal(1, 100, 50, { w: 16, h: 16, lifetime: 5, fadeOut: 2 });
+    setMaxDecals(10);
+    assert.equal(getDecalCount(), 0);
+    clearDecals();
+    assert.equal(getDecalCount(), 0);
+  });
+});
@@ -0,0 +1,118 @@
+/**
+ * Decals: textures stamped onto the world that stay put and fade out, like
+ * blood splats, scorch marks and footprints.
+ *
+ * Spawn a decal once and the engine keeps drawing it: it ages with the
+ * frame's delta time (frozen while the simulation is paused), fades out over
+ * the end of its lifetime, and the oldest decals are evicted once the cap
+ * (1024 by default, see {@link setMaxDecals}) is reached. Decals are drawn as
+ * their own batched pass, under the sprites of their layer.
+ *
+ * In headless mode every function is a no-op and {@link getDecalCount} returns 0.
+ */
+
+import type { TextureId } from "./types.ts";
+
+const hasDecalOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_spawn_decals === "function";
+
+// One decal: a sprite record (see sprites.ts) followed by [lifetime, fadeOut]
+const DECAL_STRIDE = 25;
+const _record = new Float32Array(DECAL_STRIDE);
+
+const blendModeMap: Record<string, number> = {
+  alpha: 0,
+  additive: 1,
+  multiply: 2,
+  screen: 3,
+};
+
+/** Options for {@link spawnDecal}. */
+export type DecalOptions = {
+  /** Width in world units. */
+  w: number;
+  /** Height in world units. */
+  h: number;
+  /** Layer whose sprites draw over the decal. Default: 0. */
+  layer?: number;
+  /** Rotation in radians around the decal's center. Default: 0. */
+  rotation?: number;
+  /** UV sub-rectangle, normalized 0-1. Default: the whole texture. */
+  uv?: { x: number; y: number; w: number; h: number };
+  /** Tint color multiplied with the texture. Default: white. */
+  tint?: { r: number; g: number; b: number; a: number };
+  /** Opacity before the fade-out, 0-1. Default: 1. */
+  opacity?: number;
+  /** Mirror horizontally. Default: false. */
+  flipX?: boolean;
+  /** Mirror vertically. Default: false. */
+  flipY?: boolean;
+  /** Blend mode. "multiply" suits scorch marks and stains. Default: "alpha". */
+  blendMode?: "alpha" | "additive" | "multiply" | "screen";
+  /** Seconds before the decal disappears. 0 = until evicted or cleared. Default: 10. */
+  lifetime?: number;
+  /** Seconds at the end of the lifetime over which it fades to transparent. Default: 1. */
+  fadeOut?: number;
+};
+
+/**
+ * Stamp a decal centered on (x, y). Unlike drawSprite(), call it once: the
+ * engine draws the decal every frame until it expires or is evicted.
+ *
+ * @example
+ * onCollision((e) => {
+ *   if (e.type === "begin" && e.bodyA === bullet) {
+ *     spawnDecal(scorchTex, e.pointX, e.pointY, {
+ *       w: 24, h: 24, rotation: Math.random() * Math.PI * 2,
+ *       blendMode: "multiply", lifetime: 20, fadeOut: 5,
+ *     });
+ *   }
+ * });
+ */
+export function spawnDecal(textureId: TextureId, x: number, y: number, opts: DecalOptions): void {
+  if (!hasDecalOps) return;
+  const uv = opts.uv ?? { x: 0, y: 0, w: 1, h: 1 };
+  const tint = opts.tint ?? { r: 1, g: 1, b: 1, a: 1 };
+  const view = new DataView(_record.buffer);
+  view.setUint32(0, textureId, true);
+  _record[1] = x - opts.w / 2;
+  _record[2] = y - opts.h / 2;
+  _record[3] = opts.w;
+  _record[4] = opts.h;
+  view.setInt32(5 * 4, opts.layer ?? 0, true);
+  _record.set([
+    uv.x, uv.y, uv.w, uv.h,
+    tint.r, tint.g, tint.b, tint.a,
+    opts.rotation ?? 0,
+    0.5,
+    0.5,
+    opts.flipX ? 1 : 0,
+    opts.flipY ? 1 : 0,
+    opts.opacity ?? 1,
+    blendModeMap[opts.blendMode ?? "alpha"] ?? 0,
+  ], 6);
+  view.setUint32(21 * 4, 0, true);
+  view.setUint32(22 * 4, 0, true);
+  _record[23] = opts.lifetime ?? 10;
+  _record[24] = opts.fadeOut ?? 1;
+  (globalThis as any).Deno.core.ops.op_spawn_decals(new Uint8Array(_record.buffer));
+}
+
+/** Remove every decal, e.g. when a level restarts. */
+export function clearDecals(): void {
+  if (!hasDecalOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_decals();
+}
+
+/** Set how many decals are kept at once; the oldest are evicted first. Default: 1024. */
+export function setMaxDecals(max: number): void {
+  if (!hasDecalOps) return;
+  (globalThis as any).Deno.core.ops.op_set_max_decals(Math.max(0, Math.floor(max)));
+}
+
+/** Number of live decals, or 0 in headless mode. */
+export function getDecalCount(): number {
+  if (!hasDecalOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_get_decal_count();
+}
@@ -0,0 +1,55 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  createDeformGrid,
+  destroyDeformGrid,
+  setDeformOffsets,
+  getDeformOffsets,
+  deformSquash,
+  stepDeformGrid,
+  resetDeformGrid,
+} from "./deform.ts";
+
+const hasDeformOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_deform_grid === "function";
+
+describe("Deform grids", () => {
+  it("createDeformGrid returns 0 in headless mode", () => {
+    const id = createDeformGrid(2, 2, 32, 32);
+    if (!hasDeformOps) {
+      assert.equal(id, 0);
+      assert.deepEqual(getDeformOffsets(id), []);
+      return;
+    }
+    assert.ok(id > 0);
+    destroyDeformGrid(id);
+  });
+
+  it("offsets round-trip through the packed array", () => {
+    const id = createDeformGrid(1, 1, 10, 10);
+    if (!id) return;
+    setDeformOffsets(id, [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert.deepEqual(getDeformOffsets(id), [1, 2, 3, 4, 5, 6, 7, 8]);
+    resetDeformGrid(id);
+    assert.deepEqual(getDeformOffsets(id), [0, 0, 0, 0, 0, 0, 0, 0]);
+    destroyDeformGrid(id);
+  });
+
+  it("squash springs back towards rest", () => {
+    const id = createDeformGrid(2, 2, 20, 20);
+    if (!id) return;
+    deformSquash(id, 1.5, 0.5);
+    const before = Math.abs(getDeformOffsets(id)[0]);
+    for (let i = 0; i < 300; i++) stepDeformGrid(id, 1 / 60);
+    const after = Math.abs(getDeformOffsets(id)[0]);
+    assert.ok(before > 1);
+    assert.ok(after < 0.1);
+    destroyDeformGrid(id);
+  });
+
+  it("headless-safe no-ops", () => {
+    stepDeformGrid(0, 0.016);
+    setDeformOffsets(0, new Float32Array(4));
+    destroyDeformGrid(0);
+    assert.ok(true);
+  });
+});
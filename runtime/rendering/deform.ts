@@ -0,0 +1,181 @@
+/**
+ * Deformable sprite grids for squash & stretch, jelly wobble and cloth-like flags.
+ *
+ * A grid of `cols × rows` cells is laid over a sprite; each vertex carries an
+ * offset from its rest position. Offsets are either written directly
+ * ({@link setDeformOffsets}) or driven by a spring simulation in Rust
+ * ({@link stepDeformGrid}). The grid is drawn as a textured mesh through the
+ * geometry pipeline.
+ */
+import type { TextureId } from "./types.ts";
+
+const hasDeformOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_deform_grid === "function";
+
+/** Handle to a deform grid. 0 = invalid / headless. */
+export type DeformGridId = number;
+
+/** Spring parameters for {@link setDeformSprings}. */
+export type DeformSprings = {
+  /** Pull back to the rest shape. 0 lets the grid hang freely (flags). Default: 120. */
+  stiffness?: number;
+  /** Pull towards neighbouring vertices; spreads wobble across the grid. Default: 60. */
+  coupling?: number;
+  /** Velocity damping. Default: 6. */
+  damping?: number;
+};
+
+/** Options for {@link drawDeformGrid}. */
+export type DrawDeformOptions = {
+  /** Draw layer. Default: 0. */
+  layer?: number;
+  /** Opacity 0-1. Default: 1. */
+  opacity?: number;
+};
+
+/**
+ * Create a deform grid covering `width × height` pixels.
+ * Vertices are `(cols + 1) × (rows + 1)`, row-major from the top-left.
+ * Returns 0 in headless mode.
+ *
+ * @example
+ * const slime = createDeformGrid(6, 6, 48, 48);
+ */
+export function createDeformGrid(cols: number, rows: number, width: number, height: number): DeformGridId {
+  if (!hasDeformOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_deform_grid(cols >>> 0, rows >>> 0, width, height);
+}
+
+/** Destroy a deform grid. */
+export function destroyDeformGrid(id: DeformGridId): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_destroy_deform_grid(id >>> 0);
+}
+
+/**
+ * Overwrite vertex offsets with packed `[dx0, dy0, dx1, dy1, ...]` pairs (row-major).
+ * Use this to drive the grid from your own code instead of the springs.
+ */
+export function setDeformOffsets(id: DeformGridId, offsets: Float32Array | number[]): void {
+  if (!hasDeformOps) return;
+  const data = offsets instanceof Float32Array ? offsets : new Float32Array(offsets);
+  (globalThis as any).Deno.core.ops.op_set_deform_offsets(
+    id >>> 0,
+    new Uint8Array(data.buffer, data.byteOffset, data.byteLength),
+  );
+}
+
+/** Current vertex offsets as packed `[dx, dy]` pairs. Empty in headless mode. */
+export function getDeformOffsets(id: DeformGridId): number[] {
+  if (!hasDeformOps) return [];
+  return (globalThis as any).Deno.core.ops.op_get_deform_offsets(id >>> 0);
+}
+
+/** Configure the spring simulation. Omitted fields use the defaults. */
+export function setDeformSprings(id: DeformGridId, springs: DeformSprings): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_set_deform_springs(
+    id >>> 0,
+    springs.stiffness ?? 120,
+    springs.coupling ?? 60,
+    springs.damping ?? 6,
+  );
+}
+
+/** Constant acceleration on free vertices (gravity, wind), in pixels/s². */
+export function setDeformForce(id: DeformGridId, fx: number, fy: number): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_set_deform_force(id >>> 0, fx, fy);
+}
+
+/**
+ * Pin (or unpin) a vertex so the simulation never moves it.
+ *
+ * @example
+ * // Flag attached to a pole on its left edge
+ * for (let row = 0; row <= 4; row++) pinDeformVertex(flag, 0, row);
+ */
+export function pinDeformVertex(id: DeformGridId, col: number, row: number, pinned: boolean = true): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_pin_deform_vertex(id >>> 0, col >>> 0, row >>> 0, pinned);
+}
+
+/**
+ * Push vertices near a local point (grid space, top-left origin) with
+ * velocity `(vx, vy)`, falling off linearly to zero at `radius`.
+ *
+ * @example
+ * // Poke the top of a jelly block
+ * deformImpulse(jelly, 24, 0, 30, 0, 300);
+ */
+export function deformImpulse(
+  id: DeformGridId,
+  x: number,
+  y: number,
+  radius: number,
+  vx: number,
+  vy: number,
+): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_deform_impulse(id >>> 0, x, y, radius, vx, vy);
+}
+
+/**
+ * Instantly scale the grid around an anchor (normalized, default bottom centre);
+ * the springs then bounce it back to rest.
+ *
+ * @example
+ * // Landing squash
+ * deformSquash(player, 1.3, 0.7);
+ */
+export function deformSquash(
+  id: DeformGridId,
+  scaleX: number,
+  scaleY: number,
+  anchorX: number = 0.5,
+  anchorY: number = 1,
+): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_deform_squash(id >>> 0, scaleX, scaleY, anchorX, anchorY);
+}
+
+/** Clear all offsets and velocities. */
+export function resetDeformGrid(id: DeformGridId): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_reset_deform_grid(id >>> 0);
+}
+
+/** Advance the spring simulation by `dt` seconds. */
+export function stepDeformGrid(id: DeformGridId, dt: number): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_step_deform_grid(id >>> 0, dt);
+}
+
+/** Map a sub-rectangle of the texture (0-1 UVs, e.g. an atlas frame) across the grid. */
+export function setDeformUV(id: DeformGridId, uv: { x: number; y: number; w: number; h: number }): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_set_deform_uv(id >>> 0, uv.x, uv.y, uv.w, uv.h);
+}
+
+/**
+ * Draw the grid textured with `textureId`, top-left corner at `(x, y)`.
+ * Rendered with the geometry pipeline: on the same layer it draws after sprites.
+ */
+export function drawDeformGrid(
+  id: DeformGridId,
+  textureId: TextureId,
+  x: number,
+  y: number,
+  options: DrawDeformOptions = {},
+): void {
+  if (!hasDeformOps) return;
+  (globalThis as any).Deno.core.ops.op_draw_deform_grid(
+    id >>> 0,
+    textureId >>> 0,
+    x,
+    y,
+    (options.layer ?? 0) | 0,
+    options.opacity ?? 1,
+  );
+}
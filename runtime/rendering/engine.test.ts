@@ -0,0 +1,20 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { getEngineInfo, hasEngineFeature } from "./engine.ts";
+
+describe("Engine info", () => {
+  it("reports a complete shape", () => {
+    const info = getEngineInfo();
+    assert.equal(typeof info.version, "string");
+    assert.equal(typeof info.platform.os, "string");
+    assert.ok(Array.isArray(info.features));
+    assert.equal(typeof info.limits.maxTextureSize, "number");
+    assert.equal(typeof info.limits.maxLights, "number");
+  });
+
+  it("has no GPU or renderer feature in headless mode", () => {
+    const info = getEngineInfo();
+    assert.equal(info.gpu, null);
+    assert.equal(hasEngineFeature("renderer"), false);
+    assert.equal(info.limits.maxTextureSize, 0);
+  });
+});
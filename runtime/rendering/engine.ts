@@ -0,0 +1,56 @@
+/**
+ * Engine version and capability report. Use it to feature-detect instead of
+ * calling APIs the current runtime cannot serve (e.g. lighting in `arcane test`).
+ */
+
+const hasEngineInfoOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_engine_info === "function";
+
+/** Engine feature names reported by {@link getEngineInfo}. */
+export type EngineFeature = "renderer" | "audio" | "gamepad";
+
+/** Result of {@link getEngineInfo}. */
+export type EngineInfo = {
+  /** Engine version, e.g. `"0.23.1"`. `"unknown"` outside the Arcane runtime. */
+  version: string;
+  /** Host OS and CPU architecture, e.g. `{ os: "macos", arch: "aarch64" }`. */
+  platform: { os: string; arch: string };
+  /** Features this runtime provides. Empty in headless mode. */
+  features: EngineFeature[];
+  /** Audio backend, or null when audio is unavailable. */
+  audio: { backend: string } | null;
+  /** GPU adapter. Null in headless mode and before the first frame. */
+  gpu: { name: string; backend: string; deviceType: string; driver: string } | null;
+  /** Renderer limits. `maxTextureSize` is 0 until the GPU is known. */
+  limits: { maxTextureSize: number; maxLights: number };
+};
+
+function headlessInfo(): EngineInfo {
+  return {
+    version: "unknown",
+    platform: { os: "unknown", arch: "unknown" },
+    features: [],
+    audio: null,
+    gpu: null,
+    limits: { maxTextureSize: 0, maxLights: 0 },
+  };
+}
+
+/**
+ * Get the engine version, available features, GPU adapter and limits.
+ *
+ * @example
+ * if (hasEngineFeature("renderer")) addPointLight(x, y, 120, 1, 0.8, 0.6);
+ * const { limits } = getEngineInfo();
+ * const atlasSize = Math.min(4096, limits.maxTextureSize || 2048);
+ */
+export function getEngineInfo(): EngineInfo {
+  if (!hasEngineInfoOp) return headlessInfo();
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_engine_info());
+}
+
+/** Whether the current runtime provides a feature. False in headless mode. */
+export function hasEngineFeature(feature: EngineFeature): boolean {
+  return getEngineInfo().features.includes(feature);
+}
@@ -0,0 +1,19 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { flashSprite, clearFlashes, _flashKeyId } from "./flash.ts";
+
+describe("Hit flashes", () => {
+  it("interns keys to stable non-zero IDs", () => {
+    const a = _flashKeyId("player");
+    const b = _flashKeyId(42);
+    assert.ok(a > 0);
+    assert.ok(b > 0);
+    assert.notEqual(a, b);
+    assert.equal(_flashKeyId("player"), a);
+    assert.equal(_flashKeyId(undefined), 0);
+  });
+
+  it("is inert in headless mode", () => {
+    assert.equal(flashSprite("enemy", { r: 1, g: 0, b: 0, a: 1 }, 0.1, { cooldown: 0.2 }), false);
+    clearFlashes();
+  });
+});
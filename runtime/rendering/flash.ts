@@ -0,0 +1,78 @@
+/**
+ * Hit flashes: sprites that briefly turn a solid color and fade back.
+ *
+ * Flash a key once, then draw sprites with `flashKey` set to it. The engine
+ * tracks the flash natively: every sprite drawn with the key is mixed towards
+ * the color until it fades out, so game code needs no per-entity timers.
+ * Keys are entity IDs or any string.
+ *
+ * @example
+ * if (hit) flashSprite(enemy.id, { r: 1, g: 1, b: 1 }, 0.12, { cooldown: 0.2 });
+ * drawSprite({ textureId: enemyTex, x: enemy.x, y: enemy.y, w: 32, h: 32, flashKey: enemy.id });
+ */
+
+import type { Color } from "../ui/types.ts";
+
+const hasFlashOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_entity_flash === "function";
+
+/** Key sprites are flashed by: an entity ID or any name. */
+export type FlashKey = number | string;
+
+/** Options for {@link flashSprite}. */
+export type SpriteFlashOptions = {
+  /**
+   * Seconds after a flash starts before the key can flash again. Hits during
+   * the cooldown are ignored instead of restarting the flash. Default: 0.
+   */
+  cooldown?: number;
+};
+
+// Keys are interned to u32 IDs for the sprite record; 0 means "no key".
+const _keyIds = new Map<FlashKey, number>();
+
+/** @internal Sprite record ID for a flash key (0 for none). */
+export function _flashKeyId(key: FlashKey | undefined): number {
+  if (key === undefined) return 0;
+  let id = _keyIds.get(key);
+  if (id === undefined) {
+    id = _keyIds.size + 1;
+    _keyIds.set(key, id);
+  }
+  return id;
+}
+
+/**
+ * Flash every sprite drawn with `flashKey: key`, fading from `color` back to
+ * normal over `duration` seconds. The flash color is applied after lighting.
+ * Ignored for sprites with a custom shader. No-op in headless mode.
+ *
+ * @param key - Entity ID or name the sprites are drawn with.
+ * @param color - Flash color. Default: white.
+ * @param duration - Fade-out time in seconds. Default: 0.1.
+ * @param options - Cooldown between flashes.
+ * @returns True if the flash started, false during the key's cooldown or in headless mode.
+ */
+export function flashSprite(
+  key: FlashKey,
+  color: Color = { r: 1, g: 1, b: 1, a: 1 },
+  duration: number = 0.1,
+  options: SpriteFlashOptions = {},
+): boolean {
+  if (!hasFlashOps) return false;
+  return (globalThis as any).Deno.core.ops.op_entity_flash(
+    _flashKeyId(key),
+    color.r,
+    color.g,
+    color.b,
+    duration,
+    options.cooldown ?? 0,
+  );
+}
+
+/** Stop all hit flashes and reset their cooldowns, e.g. when a level restarts. */
+export function clearFlashes(): void {
+  if (!hasFlashOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_flashes();
+}
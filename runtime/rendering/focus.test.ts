@@ -0,0 +1,24 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  setFocusLossBehavior,
+  isWindowFocused,
+  isSimulationPaused,
+  onFocusChange,
+  _dispatchFocusEvents,
+} from "./focus.ts";
+
+describe("Focus loss", () => {
+  it("reports focused and running in headless mode", () => {
+    setFocusLossBehavior({ pause: true, audio: "mute", maxFps: 10 });
+    assert.equal(isWindowFocused(), true);
+    assert.equal(isSimulationPaused(), false);
+  });
+
+  it("delivers no events without a window", () => {
+    let calls = 0;
+    const off = onFocusChange(() => calls++);
+    _dispatchFocusEvents();
+    off();
+    assert.equal(calls, 0);
+  });
+});
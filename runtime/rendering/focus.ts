@@ -0,0 +1,88 @@
+/**
+ * Window focus loss handling. By default the game keeps running when the
+ * window loses focus; {@link setFocusLossBehavior} can pause the simulation,
+ * mute or duck audio, and cap the frame rate while it is in the background.
+ * The frame callback keeps running while paused so a pause menu can be drawn.
+ */
+
+const hasFocusOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_take_focus_events === "function";
+
+/** Options for {@link setFocusLossBehavior}. */
+export type FocusLossBehavior = {
+  /** Pause the simulation: getDeltaTime() returns 0 and physics does not step. Default: false. */
+  pause?: boolean;
+  /** Audio while unfocused: keep playing, mute, or duck to `duckVolume`. Default: "keep". */
+  audio?: "keep" | "mute" | "duck";
+  /** Output volume (0-1) when `audio` is "duck". Default: 0.3. */
+  duckVolume?: number;
+  /** Frame rate cap while unfocused. 0 = uncapped. Default: 0. */
+  maxFps?: number;
+};
+
+/** A window focus change, passed to {@link onFocusChange} listeners. */
+export type FocusChangeEvent = {
+  /** Whether the window now has focus. */
+  focused: boolean;
+  /** Whether the simulation is paused after this change. */
+  paused: boolean;
+};
+
+const listeners: Array<(event: FocusChangeEvent) => void> = [];
+
+/**
+ * Configure what happens while the window is unfocused. Each call replaces the
+ * previous behavior. No-op in headless mode.
+ *
+ * @example
+ * setFocusLossBehavior({ pause: true, audio: "duck", maxFps: 15 });
+ */
+export function setFocusLossBehavior(behavior: FocusLossBehavior): void {
+  if (!hasFocusOps) return;
+  const audio = behavior.audio ?? "keep";
+  const gain = audio === "mute" ? 0 : audio === "duck" ? (behavior.duckVolume ?? 0.3) : 1;
+  (globalThis as any).Deno.core.ops.op_set_focus_behavior(
+    behavior.pause ?? false,
+    gain,
+    behavior.maxFps ?? 0,
+  );
+}
+
+/** Whether the window has focus. Always true in headless mode. */
+export function isWindowFocused(): boolean {
+  if (!hasFocusOps) return true;
+  return (globalThis as any).Deno.core.ops.op_is_window_focused();
+}
+
+/** Whether the simulation is paused by the focus loss behavior. */
+export function isSimulationPaused(): boolean {
+  if (!hasFocusOps) return false;
+  return (globalThis as any).Deno.core.ops.op_is_simulation_paused();
+}
+
+/**
+ * Listen for window focus changes. Listeners run at the start of the next
+ * frame, before the {@link onFrame} callback.
+ *
+ * @returns A function that removes the listener.
+ *
+ * @example
+ * onFocusChange((e) => { if (!e.focused) showPauseMenu(); });
+ */
+export function onFocusChange(listener: (event: FocusChangeEvent) => void): () => void {
+  listeners.push(listener);
+  return () => {
+    const index = listeners.indexOf(listener);
+    if (index >= 0) listeners.splice(index, 1);
+  };
+}
+
+/** Deliver queued focus events to listeners. Called by the frame loop. @internal */
+export function _dispatchFocusEvents(): void {
+  if (!hasFocusOps) return;
+  const events: FocusChangeEvent[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_focus_events());
+  for (const event of events) {
+    for (const listener of [...listeners]) listener(event);
+  }
+}
@@ -0,0 +1,28 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { formatGpuError, onGpuError, setGpuErrorCapture, _dispatchGpuErrors } from "./gpu-errors.ts";
+
+describe("GPU errors", () => {
+  it("is a no-op without a GPU", () => {
+    let calls = 0;
+    const off = onGpuError(() => calls++);
+    setGpuErrorCapture("all");
+    _dispatchGpuErrors();
+    off();
+    assert.equal(calls, 0);
+  });
+
+  it("formats the origin and line offset", () => {
+    const text = formatGpuError({
+      source: "shader",
+      context: 'shader 3 "water"',
+      message: "expected ';'",
+      count: 1,
+      id: 3,
+      name: "water",
+      lineOffset: 57,
+    });
+    assert.ok(text.includes('shader 3 "water": expected \';\''));
+    assert.ok(text.includes("line N + 57"));
+    assert.ok(!formatGpuError({ source: "frame", context: "frame", message: "x", count: 1 }).includes("line N"));
+  });
+});
@@ -0,0 +1,96 @@
+/**
+ * GPU validation errors. When a custom shader, SDF expression, effect or
+ * transition shader fails to compile, the engine reports the wgpu/naga error
+ * with the op that caused it: printed with `console.error` at the start of the
+ * next frame and passed to {@link onGpuError} listeners. A shader that fails
+ * is dropped (its sprites are skipped; transitions fall back to a fade).
+ *
+ * The inspector lists recent errors at `GET /gpu_errors` (MCP tool
+ * `get_gpu_errors`).
+ */
+
+const hasGpuErrorOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_take_gpu_errors === "function";
+
+/**
+ * What runs inside validation error scopes:
+ * - "off": nothing; errors still arrive, but without their source.
+ * - "pipelines" (default): shader, SDF and effect pipeline creation.
+ * - "all": also every frame's render passes. Slower; use while debugging.
+ */
+export type GpuErrorCapture = "off" | "pipelines" | "all";
+
+/** A GPU validation error, passed to {@link onGpuError} listeners. */
+export type GpuError = {
+  /** What raised it. "uncaptured" errors came from outside any scope. */
+  source: "shader" | "sdf" | "effect" | "transition" | "frame" | "uncaptured";
+  /** Human-readable origin, e.g. `shader 3 "water"`. */
+  context: string;
+  /** The wgpu/naga error, including the offending WGSL when available. */
+  message: string;
+  /** Times raised; repeats of a recent error are not reported again. */
+  count: number;
+  /** Shader, effect or transition ID. */
+  id?: number;
+  /** Shader name passed to `createShader()`. */
+  name?: string;
+  /** SDF expression that failed to compile. */
+  expr?: string;
+  /** Effect type, e.g. "Bloom". */
+  effect?: string;
+  /**
+   * Generated preamble lines before your WGSL. Line numbers in `message`
+   * count these; subtract to get lines of your source.
+   */
+  lineOffset?: number;
+};
+
+const CAPTURE_MODES: Record<GpuErrorCapture, number> = { off: 0, pipelines: 1, all: 2 };
+
+const listeners: Array<(error: GpuError) => void> = [];
+
+/**
+ * Choose which GPU work is checked for validation errors. No-op in headless mode.
+ *
+ * @example
+ * // Chasing an error that only shows up while drawing:
+ * setGpuErrorCapture("all");
+ */
+export function setGpuErrorCapture(mode: GpuErrorCapture): void {
+  if (!hasGpuErrorOps) return;
+  (globalThis as any).Deno.core.ops.op_set_gpu_error_capture(CAPTURE_MODES[mode]);
+}
+
+/**
+ * Listen for GPU validation errors, e.g. to show them in an in-game console.
+ * Errors are printed with `console.error` either way.
+ *
+ * @returns A function that removes the listener.
+ */
+export function onGpuError(listener: (error: GpuError) => void): () => void {
+  listeners.push(listener);
+  return () => {
+    const index = listeners.indexOf(listener);
+    if (index >= 0) listeners.splice(index, 1);
+  };
+}
+
+/** Console text for an error: its origin, the message and the line offset hint. */
+export function formatGpuError(error: GpuError): string {
+  let text = `[arcane] GPU error in ${error.context}: ${error.message}`;
+  if (error.lineOffset) {
+    text += `\n  (WGSL line numbers include ${error.lineOffset} generated lines; line N of your source is line N + ${error.lineOffset})`;
+  }
+  return text;
+}
+
+/** Print new GPU errors and deliver them to listeners. Called by the frame loop. @internal */
+export function _dispatchGpuErrors(): void {
+  if (!hasGpuErrorOps) return;
+  const errors: GpuError[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_gpu_errors());
+  for (const error of errors) {
+    console.error(formatGpuError(error));
+    for (const listener of [...listeners]) listener(error);
+  }
+}
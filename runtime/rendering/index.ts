@@ -2,13 +2,20 @@
 export type {
   TextureId,
   SpriteOptions,
+  SpriteOrigin,
   CameraState,
   MousePosition,
   KeyName,
+  ActionBinding,
+  InputEvent,
 } from "./types.ts";
 
 // Sprites
-export { drawSprite, clearSprites, _resetColorTexCache } from "./sprites.ts";
+export { drawSprite, drawSpriteBuffer, SPRITE_STRIDE, clearSprites, pushClipRect, popClipRect, setLayerYSort, setPixelSnap, _resolveOrigin, _resetColorTexCache } from "./sprites.ts";
+
+// Stencil masks
+export type { MaskId, MaskOptions } from "./mask.ts";
+export { beginMask, endMask, drawMasked } from "./mask.ts";
 
 // Camera
 export type { CameraBounds, CameraDeadzone } from "./camera.ts";
@@ -41,6 +48,10 @@ export {
   setBackgroundColor,
   screenToWorld,
   getMouseWorldPosition,
+  // Input events
+  pollInputEvents,
+  startTextInput,
+  stopTextInput,
   // Gamepad
   getGamepadCount,
   isGamepadConnected,
@@ -48,6 +59,14 @@ export {
   isGamepadButtonDown,
   isGamepadButtonPressed,
   getGamepadAxis,
+  // Action map
+  loadActionMap,
+  bindAction,
+  getActionMap,
+  getAction,
+  wasActionPressed,
+  wasActionReleased,
+  getAxis,
   // Touch
   getTouchCount,
   isTouchActive,
@@ -55,11 +74,52 @@ export {
   getTouchWorldPosition,
 } from "./input.ts";
 
+// Background fill / letterbox
+export type { BackgroundColor, BackgroundGradient } from "./background.ts";
+export {
+  setBackgroundGradient,
+  setBackgroundTexture,
+  clearBackgroundFill,
+  setLetterboxColor,
+} from "./background.ts";
+
+// Screen recording
+export type { ScreenRecordingOptions } from "./recording.ts";
+export { startScreenRecording, stopScreenRecording, isScreenRecording, captureLayers } from "./recording.ts";
+
+// Anti-aliasing
+export type { MsaaSamples } from "./msaa.ts";
+export { setMsaa, getMsaa } from "./msaa.ts";
+
+// Runtime texture atlas
+export type { AtlasStats } from "./runtime-atlas.ts";
+export { setRuntimeAtlas, getAtlasStats } from "./runtime-atlas.ts";
+
+// Engine info / feature detection
+export type { EngineInfo, EngineFeature } from "./engine.ts";
+export { getEngineInfo, hasEngineFeature } from "./engine.ts";
+
+// Native plugins
+export type { PluginInfo, PluginCallResult } from "./plugins.ts";
+export { listPlugins, callPlugin } from "./plugins.ts";
+
 // Screen-space context
 export { withScreenSpace, isScreenSpaceActive } from "./context.ts";
 
 // Textures
-export { loadTexture, createSolidTexture, uploadRgbaTexture, preloadAssets, isTextureLoaded, getLoadingProgress } from "./texture.ts";
+export type { TextureLoadEvent, TextureStreamStats } from "./texture.ts";
+export {
+  loadTexture,
+  loadTexturePair,
+  createSolidTexture,
+  uploadRgbaTexture,
+  preloadAssets,
+  isTextureLoaded,
+  getLoadingProgress,
+  onTextureLoad,
+  setTextureUploadBudget,
+  getTextureStreamStats,
+} from "./texture.ts";
 
 // Sprite Atlas
 export type {
@@ -81,8 +141,16 @@ export { placeholder, quickPlaceholder, PLACEHOLDER_COLORS } from "./placeholder
 // Game loop
 export { onFrame, getDeltaTime } from "./loop.ts";
 
+// Focus loss
+export type { FocusLossBehavior, FocusChangeEvent } from "./focus.ts";
+export { setFocusLossBehavior, isWindowFocused, isSimulationPaused, onFocusChange } from "./focus.ts";
+
+// App lifecycle (suspend/resume, memory warnings, resize/orientation)
+export type { Orientation, LifecycleEvent } from "./lifecycle.ts";
+export { getOrientation, onLifecycleEvent } from "./lifecycle.ts";
+
 // Tilemap
-export type { TilemapId, TilemapOptions } from "./types.ts";
+export type { TilemapId, TilemapOptions, ChunkedTilemapOptions } from "./types.ts";
 export type {
   AnimatedTileDef,
   TileProperties,
@@ -96,6 +164,19 @@ export {
   getTile,
   drawTilemap,
   fillTiles,
+  // Native auto-tiling and tile animation
+  setTilemapAutotile,
+  clearTilemapAutotile,
+  setTilemapTileAnimation,
+  clearTilemapTileAnimation,
+  // Chunked tilemaps
+  TILEMAP_CHUNK_SIZE,
+  createChunkedTilemap,
+  setTilemapChunk,
+  removeTilemapChunk,
+  getTilemapChunkCount,
+  getMissingTilemapChunks,
+  retainTilemapChunks,
   // Layered tilemaps
   createLayeredTilemap,
   setLayerTile,
@@ -121,6 +202,16 @@ export {
   clearTileProperties,
 } from "./tilemap.ts";
 
+// Persistent tilemap worlds
+export {
+  attachWorld,
+  attachLayeredWorld,
+  detachWorld,
+  flushWorld,
+  clearWorld,
+  setWorldAutosave,
+} from "./world.ts";
+
 // Auto-tiling
 export type {
   NeighborCheck,
@@ -154,6 +245,8 @@ export type {
   SpotLightOptions,
   DayNightOptions,
   GIQualityOptions,
+  LightAnimation,
+  LightShadow,
 } from "./lighting.ts";
 export {
   setAmbientLight,
@@ -169,13 +262,37 @@ export {
   clearOccluders,
   addDirectionalLight,
   addSpotLight,
+  lightAnimations,
   colorTemp,
   setDayNightCycle,
 } from "./lighting.ts";
 
+// Adaptive quality
+export type { AdaptiveQualityOptions, QualityStatus } from "./quality.ts";
+export { enableAdaptiveQuality, disableAdaptiveQuality, setQualityTier, getQuality } from "./quality.ts";
+
+// Dev-mode drawSprite() validation
+export type { SpriteIssueKind, SpriteIssue } from "./sprite-validation.ts";
+export { setSpriteValidation, isSpriteValidationEnabled, getSpriteValidationIssues } from "./sprite-validation.ts";
+
+// GPU validation errors (shader/SDF/effect compile failures)
+export type { GpuErrorCapture, GpuError } from "./gpu-errors.ts";
+export { setGpuErrorCapture, onGpuError, formatGpuError } from "./gpu-errors.ts";
+
+// Op-call profiling
+export type { OpStat } from "./op-stats.ts";
+export { setOpProfiling, getOpStats, formatOpStats } from "./op-stats.ts";
+
+// Built-in debug HUD (F3 in arcane dev)
+export { setDebugHud, isDebugHudEnabled } from "./debug-hud.ts";
+
+// Renderer stats
+export type { SpriteStats } from "./stats.ts";
+export { getSpriteStats } from "./stats.ts";
+
 // Text
-export type { BitmapFont, TextOptions, TextMeasurement, TextOutline, TextShadow, MSDFFont, MSDFGlyph, TextAlign, TextLayoutOptions } from "./text.ts";
-export { loadFont, getDefaultFont, getDefaultMSDFFont, loadMSDFFont, measureText, drawText, wrapText, drawTextWrapped, drawTextAligned } from "./text.ts";
+export type { BitmapFont, BitmapGlyph, TextOptions, TextMeasurement, TextOutline, TextShadow, MSDFFont, MSDFGlyph, TextAlign, TextLayoutOptions, TextLayout, TextLayoutLine, FontFileOptions } from "./text.ts";
+export { loadFont, loadBitmapFont, getDefaultFont, getDefaultMSDFFont, loadMSDFFont, loadFontFile, measureText, drawText, layoutText, wrapText, drawTextWrapped, drawTextAligned } from "./text.ts";
 
 // Animation
 export type { AnimationId, AnimationDef, AnimationState, FrameEvent, FrameEventCallback } from "./animation.ts";
@@ -192,6 +309,53 @@ export {
   onFrameEvent,
 } from "./animation.ts";
 
+// Engine-driven sprite animations
+export type {
+  SpriteAnimationId,
+  SpriteAnimationMode,
+  SpriteAnimationFrame,
+  SpriteAnimationEvent,
+  DrawAnimatedOptions,
+} from "./sprite-animation.ts";
+export {
+  sheetFrames,
+  createSpriteAnimation,
+  drawAnimated,
+  restartSpriteAnimation,
+  getSpriteAnimationFrame,
+  destroySpriteAnimation,
+  onSpriteAnimationEvent,
+} from "./sprite-animation.ts";
+
+// Aseprite sheets
+export type {
+  AsepriteFrame,
+  AsepriteTag,
+  AsepriteSliceKey,
+  AsepriteSlice,
+  AsepriteSheet,
+} from "./aseprite.ts";
+export { loadAseprite, createAsepriteAnimation, getAsepriteSlice } from "./aseprite.ts";
+
+// Decals
+export type { DecalOptions } from "./decals.ts";
+export { spawnDecal, clearDecals, setMaxDecals, getDecalCount } from "./decals.ts";
+
+// Retained sprites
+export type { RetainedSpriteId, RetainedSpriteOptions, RetainedSpriteMove } from "./retained-sprites.ts";
+export {
+  createRetainedSprite,
+  updateRetainedSprite,
+  moveRetainedSprites,
+  setRetainedSpriteVisible,
+  destroyRetainedSprite,
+  getRetainedSpriteCount,
+} from "./retained-sprites.ts";
+
+// Hit flashes
+export type { FlashKey, SpriteFlashOptions } from "./flash.ts";
+export { flashSprite, clearFlashes } from "./flash.ts";
+
 // Animation State Machine
 export type {
   FSMStateDef,
@@ -217,7 +381,21 @@ export {
 } from "./animation-fsm.ts";
 
 // Audio
-export type { SoundId, InstanceId, AudioBus, PlayOptions, SpatialOptions, PoolConfig } from "./audio.ts";
+export type {
+  SoundId,
+  InstanceId,
+  AudioBus,
+  PlayOptions,
+  SpatialOptions,
+  SpatialCurve,
+  SpatialParams,
+  SoundOcclusion,
+  SoundDoppler,
+  BusEffect,
+  AudioLevel,
+  AudioLevels,
+  PoolConfig,
+} from "./audio.ts";
 export {
   loadSound,
   playSound,
@@ -226,11 +404,22 @@ export {
   stopAll,
   setVolume,
   playSoundAt,
+  setSpatialParams,
+  setSoundOcclusion,
+  setSoundDoppler,
   crossfadeMusic,
+  fadeInInstance,
+  fadeOutInstance,
+  fadeOutMusic,
+  stopInstanceAfter,
   stopInstance,
   setBusVolume,
   getBusVolume,
+  setBusEffect,
+  clearBusEffects,
+  getAudioLevels,
   setListenerPosition,
+  setSoundPosition,
   updateSpatialAudio,
   setPoolConfig,
   setInstanceVolume,
@@ -240,6 +429,7 @@ export {
 export type { ShaderId, UniformType, UniformDef } from "./shader.ts";
 export {
   createShaderFromSource,
+  createShaderFromFile,
   setShaderParam,
   createShader,
   setShaderUniform,
@@ -270,15 +460,94 @@ export {
 } from "./effects.ts";
 
 // Render Targets
-export type { RenderTargetId } from "./rendertarget.ts";
+export type { RenderTargetId, RenderTargetCamera } from "./rendertarget.ts";
 export {
   createRenderTarget,
   beginRenderTarget,
   endRenderTarget,
   getRenderTargetTextureId,
+  setRenderTargetCamera,
+  clearRenderTargetCamera,
+  addRenderTargetEffect,
+  clearRenderTargetEffects,
   destroyRenderTarget,
 } from "./rendertarget.ts";
 
+// Split-screen viewports
+export type { ViewportId, ViewportCamera } from "./viewport.ts";
+export { createViewport, setViewportCamera, setViewportRect, destroyViewport } from "./viewport.ts";
+
+// Minimaps (scene re-rendered into a texture)
+export type { MinimapId, MinimapWorld, MinimapOptions } from "./minimap.ts";
+export {
+  createMinimap,
+  setMinimapWorld,
+  setMinimapLayers,
+  setMinimapInterval,
+  refreshMinimap,
+  destroyMinimap,
+  getMinimapTextureId,
+  worldToMinimap,
+} from "./minimap.ts";
+
+// Sprite transform nodes (Rust-side parenting)
+export type { SpriteNodeId, SpriteNodeTransform, SpriteNodeTransformUpdate } from "./nodes.ts";
+export {
+  createSpriteNode,
+  destroySpriteNode,
+  setSpriteNodeTransform,
+  setSpriteNodeTransforms,
+  setSpriteNodeParent,
+  attachSprites,
+  getSpriteNodeWorldTransform,
+} from "./nodes.ts";
+
+// Skeletal animation (Spine JSON)
+export type {
+  SkeletonId,
+  SkeletonDataInfo,
+  BoneTransform,
+  PlaySkeletonOptions,
+  SkeletonMesh,
+} from "./skeleton.ts";
+export {
+  loadSkeleton,
+  setSkeletonRegion,
+  createSkeleton,
+  destroySkeleton,
+  setSkeletonTransform,
+  setSkeletonSkin,
+  playSkeletonAnimation,
+  clearSkeletonTrack,
+  setSkeletonTrackMix,
+  updateSkeleton,
+  drawSkeleton,
+  getSkeletonBone,
+  getSkeletonMeshes,
+} from "./skeleton.ts";
+
+// Deformable sprite grids (squash & stretch, jelly, flags)
+export type { DeformGridId, DeformSprings, DrawDeformOptions } from "./deform.ts";
+export {
+  createDeformGrid,
+  destroyDeformGrid,
+  setDeformOffsets,
+  getDeformOffsets,
+  setDeformSprings,
+  setDeformForce,
+  pinDeformVertex,
+  deformImpulse,
+  deformSquash,
+  resetDeformGrid,
+  stepDeformGrid,
+  setDeformUV,
+  drawDeformGrid,
+} from "./deform.ts";
+
+// Custom meshes (per-vertex colors and UVs)
+export type { MeshId, DrawMeshOptions } from "./mesh.ts";
+export { MESH_VERTEX_STRIDE, uploadMesh, updateMesh, drawMesh, destroyMesh } from "./mesh.ts";
+
 // Post-Processing
 export type { EffectId } from "./postprocess.ts";
 export {
@@ -379,6 +648,20 @@ export {
   isScreenTransitionActive,
   getScreenTransitionProgress,
 } from "./transition.ts";
+export type {
+  TransitionId,
+  TransitionShaderId,
+  TransitionKind,
+  TransitionOptions,
+  TransitionEvent,
+} from "./screen-transition.ts";
+export {
+  playTransition,
+  cancelTransition,
+  getTransitionCoverage,
+  createTransitionShader,
+  onTransitionEvent,
+} from "./screen-transition.ts";
 
 // Nine-Slice Sprites
 export type { NineSliceBorder, NineSliceOptions } from "./nineslice.ts";
@@ -403,6 +686,7 @@ export type {
   ImpactParticles,
   ImpactSound,
   ImpactConfig,
+  FreezeFrameOptions,
 } from "./juice.ts";
 export {
   impact,
@@ -411,6 +695,7 @@ export {
   hitstop,
   isHitstopActive,
   getHitstopFrames,
+  freezeFrame,
 } from "./juice.ts";
 
 // Floating Text (damage numbers)
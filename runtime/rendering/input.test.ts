@@ -1,5 +1,16 @@
 import { describe, it } from "../testing/harness.ts";
-import { screenToWorld } from "./input.ts";
+import {
+  bindAction,
+  getAction,
+  getActionMap,
+  getAxis,
+  loadActionMap,
+  pollInputEvents,
+  screenToWorld,
+  startTextInput,
+  stopTextInput,
+  wasActionPressed,
+} from "./input.ts";
 
 describe("input", () => {
   describe("screenToWorld", () => {
@@ -57,4 +68,31 @@ describe("input", () => {
       }
     });
   });
+
+  describe("action map", () => {
+    it("is inert in headless mode", () => {
+      bindAction("jump", ["Space", "GamepadA"]);
+      loadActionMap("missing-controls.json");
+      if (getAction("jump") || wasActionPressed("jump")) {
+        throw new Error("Expected no held actions in headless mode");
+      }
+      if (getAxis("move_x") !== 0) {
+        throw new Error(`Expected axis 0, got ${getAxis("move_x")}`);
+      }
+      if (Object.keys(getActionMap()).length !== 0) {
+        throw new Error("Expected an empty action map in headless mode");
+      }
+    });
+  });
+
+  describe("input events", () => {
+    it("are empty in headless mode", () => {
+      startTextInput(10, 20, 200, 24);
+      const events = pollInputEvents();
+      stopTextInput();
+      if (events.length !== 0) {
+        throw new Error(`Expected no events in headless mode, got ${events.length}`);
+      }
+    });
+  });
 });
@@ -1,4 +1,4 @@
-import type { MousePosition, KeyName } from "./types.ts";
+import type { ActionBinding, InputEvent, MousePosition, KeyName } from "./types.ts";
 import { getCamera } from "./camera.ts";
 
 const hasRenderOps =
@@ -200,7 +200,8 @@ export function getScaleFactor(): number {
  * No-op in headless mode.
  *
  * Accepts any object with r, g, b properties (0.0-1.0 floats), including
- * the Color type returned by `rgb()`. Alpha is ignored.
+ * the Color type returned by `rgb()`. Alpha is ignored. For a gradient or
+ * texture background use `setBackgroundGradient()` / `setBackgroundTexture()`.
  *
  * @param color - Background color. Use `rgb(r, g, b)` or `{ r, g, b }` with 0.0-1.0 values.
  */
@@ -235,6 +236,53 @@ export function getMouseWorldPosition(): MousePosition {
   return screenToWorld(screenPos.x, screenPos.y);
 }
 
+// --- Input event API ---
+
+const hasInputEventOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_poll_input_events === "function";
+
+/**
+ * Get the keyboard, text and wheel events since the last frame, in the order
+ * they happened. Each event is returned once: a second call in the same frame
+ * returns []. Use it for text boxes and chat, where {@link isKeyPressed} would
+ * miss repeats, key order and layout-dependent characters.
+ * Returns [] in headless mode.
+ *
+ * `text` events carry what the keyboard layout, dead keys and IME produced;
+ * Enter, Backspace and arrows come only as `keydown` events (with `repeat`
+ * set while held).
+ *
+ * @example
+ * for (const e of pollInputEvents()) {
+ *   if (e.type === "text") message += e.text;
+ *   else if (e.type === "keydown" && e.key === "Backspace") message = message.slice(0, -1);
+ * }
+ */
+export function pollInputEvents(): InputEvent[] {
+  if (!hasInputEventOps) return [];
+  return JSON.parse((globalThis as any).Deno.core.ops.op_poll_input_events());
+}
+
+/**
+ * Mark a text field as focused at a screen rect (pixels). Enables the IME,
+ * which then sends `composition` events while composing and `text` when
+ * committed, and places its candidate window beside the rect. Call again
+ * when the field moves. No-op in headless mode.
+ */
+export function startTextInput(x: number, y: number, w: number, h: number): void {
+  if (!hasInputEventOps) return;
+  (globalThis as any).Deno.core.ops.op_start_text_input(x, y, w, h);
+}
+
+/**
+ * Mark no text field as focused, disabling the IME. No-op in headless mode.
+ */
+export function stopTextInput(): void {
+  if (!hasInputEventOps) return;
+  (globalThis as any).Deno.core.ops.op_stop_text_input();
+}
+
 // --- Gamepad API ---
 
 const hasGamepadOp =
@@ -319,6 +367,101 @@ export function getGamepadAxis(axis: string): number {
   return (globalThis as any).Deno.core.ops.op_get_gamepad_axis(axis);
 }
 
+// --- Action map API ---
+
+const hasActionOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_action === "function";
+
+/**
+ * Load named actions from a JSON file (relative to the game directory), in
+ * the same shape as {@link bindAction}'s bindings per action. Actions the
+ * file doesn't mention keep their bindings. No-op in headless mode.
+ *
+ * @param path - Path to the action map JSON file.
+ * @throws If the file is missing or a binding is invalid.
+ *
+ * @example
+ * // controls.json: { "jump": ["Space", "GamepadA"], "move_x": [{ "type": "key", "key": "a", "scale": -1 }, "d"] }
+ * loadActionMap("controls.json");
+ */
+export function loadActionMap(path: string): void {
+  if (!hasActionOps) return;
+  const error = (globalThis as any).Deno.core.ops.op_load_action_map(path);
+  if (error) {
+    throw new Error(`Failed to load action map: ${error}`);
+  }
+}
+
+/**
+ * Bind a named action to keys, mouse buttons and gamepad buttons or axes,
+ * replacing its bindings. An empty list removes the action. Bindings are
+ * evaluated by the engine once per frame, from the next frame on.
+ * No-op in headless mode.
+ *
+ * @param action - Action name, e.g. "jump".
+ * @param bindings - Inputs that drive the action.
+ * @throws If a binding is invalid.
+ *
+ * @example
+ * bindAction("move_x", [
+ *   { type: "key", key: "a", scale: -1 }, "d",
+ *   { type: "gamepadAxis", axis: "LeftStickX", deadzone: 0.25 },
+ * ]);
+ */
+export function bindAction(action: string, bindings: ActionBinding[]): void {
+  if (!hasActionOps) return;
+  const error = (globalThis as any).Deno.core.ops.op_set_action_bindings(action, JSON.stringify(bindings));
+  if (error) {
+    throw new Error(`Failed to bind action: ${error}`);
+  }
+}
+
+/**
+ * Every action and its bindings, in the form {@link loadActionMap} reads.
+ * Save it to keep rebound controls. Returns {} in headless mode.
+ */
+export function getActionMap(): Record<string, ActionBinding[]> {
+  if (!hasActionOps) return {};
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_action_map());
+}
+
+/**
+ * Check if an action is held: its value is at least 0.5 either way.
+ * Returns false in headless mode.
+ */
+export function getAction(action: string): boolean {
+  if (!hasActionOps) return false;
+  return (globalThis as any).Deno.core.ops.op_get_action(action);
+}
+
+/**
+ * Check if an action became held this frame (or a bound key or button was
+ * tapped since the last frame). Returns false in headless mode.
+ */
+export function wasActionPressed(action: string): boolean {
+  if (!hasActionOps) return false;
+  return (globalThis as any).Deno.core.ops.op_is_action_pressed(action);
+}
+
+/**
+ * Check if an action stopped being held this frame.
+ * Returns false in headless mode.
+ */
+export function wasActionReleased(action: string): boolean {
+  if (!hasActionOps) return false;
+  return (globalThis as any).Deno.core.ops.op_is_action_released(action);
+}
+
+/**
+ * Get an action's value: the sum of its bindings, clamped to -1..1, so
+ * opposite keys cancel. Returns 0 in headless mode.
+ */
+export function getAxis(action: string): number {
+  if (!hasActionOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_get_axis(action);
+}
+
 // --- Touch API ---
 
 const hasTouchOp =
@@ -6,6 +6,7 @@ import {
   hitstop,
   isHitstopActive,
   getHitstopFrames,
+  freezeFrame,
   _consumeHitstopFrame,
   _resetJuice,
 } from "./juice.ts";
@@ -71,6 +72,34 @@ describe("Juice & Game Feel", () => {
     });
   });
 
+  describe("freezeFrame", () => {
+    const hasHitstopOps = typeof (globalThis as any).Deno?.core?.ops?.op_hitstop === "function";
+
+    it("falls back to frame hitstop and camera shake in headless mode", () => {
+      if (hasHitstopOps) return;
+      setup();
+      freezeFrame(50, { shake: 6 });
+      assert.equal(getHitstopFrames(), 3);
+      assert.equal(isCameraShaking(), true);
+    });
+
+    it("shakes without freezing for 0ms", () => {
+      if (hasHitstopOps) return;
+      setup();
+      freezeFrame(0, { shake: 4 });
+      assert.equal(isHitstopActive(), false);
+      assert.equal(isCameraShaking(), true);
+    });
+
+    it("does nothing without a duration or shake", () => {
+      if (hasHitstopOps) return;
+      setup();
+      freezeFrame(0);
+      assert.equal(isHitstopActive(), false);
+      assert.equal(isCameraShaking(), false);
+    });
+  });
+
   describe("impact", () => {
     it("triggers camera shake", () => {
       setup();
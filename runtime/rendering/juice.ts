@@ -22,6 +22,10 @@ import { spawnEmitter } from "../particles/emitter.ts";
 import { playSound } from "./audio.ts";
 import type { EmitterConfig } from "../particles/types.ts";
 
+const hasHitstopOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_hitstop === "function";
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -76,6 +80,14 @@ export type ImpactSound = {
   volume?: number;
 };
 
+/** Options for freezeFrame(). */
+export type FreezeFrameOptions = {
+  /** Camera shake amplitude in pixels. Default: 0 (no shake). */
+  shake?: number;
+  /** Gamepad rumble strength 0-1. Default: 0 (no rumble). */
+  rumble?: number;
+};
+
 /** Full configuration for the impact() combinator. All fields optional. */
 export type ImpactConfig = {
   /** Camera shake. Pass true for defaults, or an ImpactShake for custom. */
@@ -103,7 +115,8 @@ let hitstopFramesRemaining = 0;
  * @returns True if hitstop is in effect.
  */
 export function isHitstopActive(): boolean {
-  return hitstopFramesRemaining > 0;
+  if (hitstopFramesRemaining > 0) return true;
+  return hasHitstopOps && (globalThis as any).Deno.core.ops.op_get_hitstop_remaining() > 0;
 }
 
 /**
@@ -150,6 +163,38 @@ export function hitstop(frames: number): void {
   hitstopFramesRemaining = Math.max(hitstopFramesRemaining, Math.round(frames));
 }
 
+/**
+ * Engine-side hitstop: freeze the simulation for `ms` milliseconds while
+ * frames keep rendering, with a camera shake and gamepad rumble, in one call.
+ *
+ * Unlike {@link hitstop}, the game loop needs no changes: during the freeze
+ * the engine reports a delta time of 0 and skips physics steps, like a pause.
+ * The shake plays through the freeze and fades out after it; shake and
+ * rumble last at least 200ms. Overlapping calls keep the longer freeze and
+ * the stronger shake and rumble.
+ *
+ * In headless mode this falls back to {@link hitstop} (frames at 60 FPS) and
+ * shakeCamera().
+ *
+ * @param ms - Freeze duration in milliseconds. 0 = shake and rumble only.
+ * @param options - Shake amplitude and rumble strength.
+ *
+ * @example
+ * ```ts
+ * // Heavy hit: 80ms freeze, 6px shake, strong rumble
+ * freezeFrame(80, { shake: 6, rumble: 0.8 });
+ * ```
+ */
+export function freezeFrame(ms: number, options: FreezeFrameOptions = {}): void {
+  const shake = options.shake ?? 0;
+  if (hasHitstopOps) {
+    (globalThis as any).Deno.core.ops.op_hitstop(ms, shake, options.rumble ?? 0);
+    return;
+  }
+  if (ms > 0) hitstop(Math.ceil(ms / (1000 / 60)));
+  if (shake > 0) shakeCamera(shake, Math.max(ms / 1000, 0.2));
+}
+
 // ---------------------------------------------------------------------------
 // Impact combinator
 // ---------------------------------------------------------------------------
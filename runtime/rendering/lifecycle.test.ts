@@ -0,0 +1,16 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { getOrientation, onLifecycleEvent, _dispatchLifecycleEvents } from "./lifecycle.ts";
+
+describe("Lifecycle", () => {
+  it("reports landscape for the headless viewport", () => {
+    assert.equal(getOrientation(), "landscape");
+  });
+
+  it("delivers no events without a window", () => {
+    let calls = 0;
+    const off = onLifecycleEvent(() => calls++);
+    _dispatchLifecycleEvents();
+    off();
+    assert.equal(calls, 0);
+  });
+});
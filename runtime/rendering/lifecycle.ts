@@ -0,0 +1,72 @@
+/**
+ * App lifecycle events: suspend/resume, low-memory warnings, and viewport
+ * resizes with orientation. Desktop windows rarely suspend, but handling these
+ * the same way mobile builds will (save on suspend, free caches on a memory
+ * warning, re-layout on resize) keeps games ready for touch devices.
+ * Test touch controls on desktop with `arcane dev --emulate-touch`.
+ */
+
+import { getViewportSize } from "./input.ts";
+
+const hasLifecycleOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_take_lifecycle_events === "function";
+
+/** Screen orientation from the viewport's aspect ratio. Square counts as landscape. */
+export type Orientation = "portrait" | "landscape";
+
+/** A lifecycle change, passed to {@link onLifecycleEvent} listeners. */
+export type LifecycleEvent =
+  /** The app went to the background; save state here. */
+  | { type: "suspend" }
+  | { type: "resume" }
+  /** The OS is low on memory; free anything that can be reloaded. */
+  | { type: "memoryWarning" }
+  /** The viewport changed size (logical pixels). Also sent once at startup. */
+  | {
+      type: "resize";
+      width: number;
+      height: number;
+      scaleFactor: number;
+      orientation: Orientation;
+      /** Whether the orientation differs from the previous size. */
+      orientationChanged: boolean;
+    };
+
+const listeners: Array<(event: LifecycleEvent) => void> = [];
+
+/** Current orientation of the viewport. "landscape" in headless mode. */
+export function getOrientation(): Orientation {
+  const { width, height } = getViewportSize();
+  return height > width ? "portrait" : "landscape";
+}
+
+/**
+ * Listen for lifecycle events. Listeners run at the start of the next frame,
+ * before the {@link onFrame} callback; several resizes within one frame arrive
+ * as a single event.
+ *
+ * @returns A function that removes the listener.
+ *
+ * @example
+ * onLifecycleEvent((e) => {
+ *   if (e.type === "suspend") saveGame();
+ *   if (e.type === "resize" && e.orientationChanged) layoutHud(e.orientation);
+ * });
+ */
+export function onLifecycleEvent(listener: (event: LifecycleEvent) => void): () => void {
+  listeners.push(listener);
+  return () => {
+    const index = listeners.indexOf(listener);
+    if (index >= 0) listeners.splice(index, 1);
+  };
+}
+
+/** Deliver queued lifecycle events to listeners. Called by the frame loop. @internal */
+export function _dispatchLifecycleEvents(): void {
+  if (!hasLifecycleOps) return;
+  const events: LifecycleEvent[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_lifecycle_events());
+  for (const event of events) {
+    for (const listener of [...listeners]) listener(event);
+  }
+}
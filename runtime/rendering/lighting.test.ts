@@ -14,6 +14,7 @@ import {
   addDirectionalLight,
   addSpotLight,
   colorTemp,
+  lightAnimations,
   setDayNightCycle,
 } from "./lighting.ts";
 
@@ -135,6 +136,25 @@ describe("lighting — spot lights", () => {
   });
 });
 
+// --- Light Animation ---
+
+describe("lighting — animation", () => {
+  it("animated lights do not throw in headless mode", () => {
+    addPointLight(100, 200, 150, 1, 0.6, 0.2, 1, lightAnimations.torch);
+    addPointLight(100, 200, 150, 1, 1, 1, 1, { pulse: 0.5, pulseCurve: "square", hueSpeed: 0.2, phase: 0.3 });
+    addSpotLight({ x: 0, y: 0, angle: 0, animation: lightAnimations.neon });
+  });
+
+  it("presets keep amounts in 0-1 and speeds positive", () => {
+    for (const [name, anim] of Object.entries(lightAnimations)) {
+      for (const amount of [anim.flicker ?? 0, anim.pulse ?? 0]) {
+        assert.ok(amount >= 0 && amount <= 1, `${name}: amount ${amount} out of range`);
+      }
+      assert.ok((anim.flickerSpeed ?? 8) > 0 && (anim.pulseSpeed ?? 1) > 0, `${name}: speeds`);
+    }
+  });
+});
+
 // --- Color Temperature ---
 
 describe("lighting — color temperature presets", () => {
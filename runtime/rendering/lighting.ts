@@ -1,8 +1,72 @@
+import { curveId, type Curve } from "../tweening/curve.ts";
+
 const hasRenderOps =
   typeof (globalThis as any).Deno !== "undefined" &&
   typeof (globalThis as any).Deno?.core?.ops?.op_set_ambient_light ===
     "function";
 
+/**
+ * Animation for a point or spot light, evaluated by the renderer each frame so
+ * it stays smooth when the script's frame rate dips. Amounts are how far the
+ * light dims below its intensity, 0-1. Presets: {@link lightAnimations}.
+ */
+export type LightAnimation = {
+  /** Random flicker amount, 0-1. Default: 0. */
+  flicker?: number;
+  /** Flicker changes per second. Default: 8. */
+  flickerSpeed?: number;
+  /** Periodic pulse amount, 0-1. Default: 0. */
+  pulse?: number;
+  /** Pulses per second. Default: 1. */
+  pulseSpeed?: number;
+  /**
+   * Pulse waveform; each period starts at full intensity. A {@link Curve}
+   * (from defineEasingCurve() or defineBezierCurve()) shapes the dimming
+   * across each period instead, like a reshaped sawtooth. Default: "sine".
+   */
+  pulseCurve?: "sine" | "triangle" | "square" | "sawtooth" | Curve;
+  /** Color cycling speed in hue turns per second (negative reverses). Default: 0. */
+  hueSpeed?: number;
+  /** Time offset in seconds, so identical lights don't animate in sync. Default: 0. */
+  phase?: number;
+};
+
+const PULSE_CURVES = { sine: 0, triangle: 1, square: 2, sawtooth: 3 } as const;
+
+/**
+ * Shadows for a point light, cast by the occluders from addOccluder(). Works
+ * with or without global illumination. `true` gives hard shadows.
+ */
+export type LightShadow = boolean | {
+  /**
+   * Width of the light source in world units: shadow edges fade over a
+   * penumbra that grows with it. Default: 0 (hard edges).
+   */
+  softness?: number;
+};
+
+/** Trailing op arg for a light's shadow (negative = no shadows). */
+function shadowArg(shadow: LightShadow | undefined): number {
+  if (!shadow) return -1;
+  return shadow === true ? 0 : Math.max(shadow.softness ?? 0, 0);
+}
+
+/** Trailing op args for a light animation (all zero = static). */
+function animationArgs(a: LightAnimation | undefined): number[] {
+  if (!a) return [0, 0, 0, 0, 0, 0, 0, 0];
+  const curve = a.pulseCurve ?? "sine";
+  return [
+    a.flicker ?? 0,
+    a.flickerSpeed ?? 8,
+    a.pulse ?? 0,
+    a.pulseSpeed ?? 1,
+    typeof curve === "string" ? PULSE_CURVES[curve] ?? 0 : 0,
+    typeof curve === "string" ? 0 : curveId(curve),
+    a.hueSpeed ?? 0,
+    a.phase ?? 0,
+  ];
+}
+
 // --- Existing point-light API (backward compatible) ---
 
 /**
@@ -32,6 +96,7 @@ export function setAmbientLight(rOrColor: number | { r: number; g: number; b: nu
  * Add a point light at a world position.
  * Point lights illuminate sprites within their radius, blending with the ambient light.
  * Must be called every frame (lights are cleared at frame start).
+ * Over the light capacity (default 8, `--max-lights`), the most visible lights are kept.
  * No-op in headless mode.
  *
  * @param x - Light center X in world units.
@@ -41,6 +106,17 @@ export function setAmbientLight(rOrColor: number | { r: number; g: number; b: nu
  * @param g - Light color green channel, 0.0-1.0. Default: 1.
  * @param b - Light color blue channel, 0.0-1.0. Default: 1.
  * @param intensity - Light brightness multiplier, 0.0+. Default: 1.
+ * @param animation - Flicker, pulse or color cycling. Default: none.
+ * @param shadow - Cast shadows from occluders (`true` or `{ softness }`). Default: no shadows.
+ *
+ * @example
+ * // Torches that don't flicker in unison
+ * torches.forEach((t, i) => addPointLight(t.x, t.y, 120, 1, 0.6, 0.2, 1.2, { ...lightAnimations.torch, phase: i * 0.37 }));
+ *
+ * @example
+ * // A lantern whose light stops at walls, with soft shadow edges
+ * walls.forEach((w) => addOccluder(w));
+ * addPointLight(player.x, player.y, 200, 1, 0.9, 0.7, 1, undefined, { softness: 8 });
  */
 export function addPointLight(
   x: number,
@@ -50,6 +126,8 @@ export function addPointLight(
   g: number = 1,
   b: number = 1,
   intensity: number = 1,
+  animation?: LightAnimation,
+  shadow?: LightShadow,
 ): void {
   if (!hasRenderOps) return;
   (globalThis as any).Deno.core.ops.op_add_point_light(
@@ -60,6 +138,8 @@ export function addPointLight(
     g,
     b,
     intensity,
+    ...animationArgs(animation),
+    shadowArg(shadow),
   );
 }
 
@@ -211,10 +291,12 @@ export interface OccluderOptions {
 }
 
 /**
- * Add a rectangular occluder that blocks light in GI mode.
- * Occluders cast shadows when light rays encounter them.
+ * Add a rectangular occluder that blocks light.
+ * In GI mode occluders block all light rays. Without GI they shadow point
+ * lights added with a `shadow` option (up to 32 occluders near those lights).
+ * Sprites inside an occluder aren't shadowed by it, so walls stay lit.
  * Must be called every frame (cleared at frame start).
- * No-op in headless mode or when GI is disabled.
+ * No-op in headless mode.
  *
  * @param options - Occluder rectangle.
  */
@@ -256,6 +338,8 @@ export interface DirectionalLightOptions {
 /**
  * Add a directional light (sun/moon — infinite distance, parallel rays).
  * Directional lights affect the entire scene uniformly from a given angle.
+ * They shade normal-mapped sprites (see loadTexturePair()), up to 4 per
+ * frame; flat sprites ignore them.
  * Must be called every frame (cleared with clearLights()).
  * No-op in headless mode.
  *
@@ -298,6 +382,8 @@ export interface SpotLightOptions {
   b?: number;
   /** Light brightness. Default: 1. */
   intensity?: number;
+  /** Flicker, pulse or color cycling. Default: none. */
+  animation?: LightAnimation;
 }
 
 /**
@@ -325,9 +411,26 @@ export function addSpotLight(options: SpotLightOptions): void {
     g,
     b,
     intensity,
+    ...animationArgs(options.animation),
   );
 }
 
+// --- Light Animation Presets ---
+
+/** Ready-made {@link LightAnimation}s. Spread them with a `phase` to desync copies. */
+export const lightAnimations = {
+  /** Restless fire flicker. */
+  torch: { flicker: 0.3, flickerSpeed: 10 } as LightAnimation,
+  /** Gentle, slow flicker. */
+  candle: { flicker: 0.15, flickerSpeed: 4 } as LightAnimation,
+  /** Buzzing sign: fast sawtooth hum with the odd stutter. */
+  neon: { pulse: 0.15, pulseSpeed: 30, pulseCurve: "sawtooth", flicker: 0.1, flickerSpeed: 2 } as LightAnimation,
+  /** Slow breathing glow (crystals, pickups). */
+  pulse: { pulse: 0.5, pulseSpeed: 0.5 } as LightAnimation,
+  /** Alarm light switching on and off once a second. */
+  alarm: { pulse: 1, pulseSpeed: 1, pulseCurve: "square" } as LightAnimation,
+};
+
 // --- Color Temperature Presets ---
 
 /** Color temperature presets as [r, g, b] tuples (0.0-1.0 range). */
@@ -0,0 +1,28 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { onFrame } from "./loop.ts";
+import { drawSprite } from "./sprites.ts";
+
+describe("onFrame", () => {
+  it("rethrows callback errors so the dev loop can show them", () => {
+    onFrame(() => {
+      drawSprite({ textureId: 1, x: 0, y: 0, w: 8, h: 8 });
+      throw new Error("boom");
+    });
+    const cb = (globalThis as any).__frameCallback;
+    assert.throws(() => cb(), /boom/);
+  });
+
+  it("runs normally on the frame after a failure", () => {
+    let frames = 0;
+    let fail = true;
+    onFrame(() => {
+      frames++;
+      if (fail) throw new Error("broken");
+    });
+    const cb = (globalThis as any).__frameCallback;
+    assert.throws(() => cb(), /broken/);
+    fail = false;
+    cb();
+    assert.equal(frames, 2);
+  });
+});
@@ -1,4 +1,11 @@
-import { _flushSpriteBatch } from "./sprites.ts";
+import { _discardSpriteBatch, _flushSpriteBatch } from "./sprites.ts";
+import { _dispatchFocusEvents } from "./focus.ts";
+import { _dispatchLifecycleEvents } from "./lifecycle.ts";
+import { _dispatchGpuErrors } from "./gpu-errors.ts";
+import { _reportSpriteIssues } from "./sprite-validation.ts";
+import { _dispatchSpriteAnimationEvents } from "./sprite-animation.ts";
+import { _dispatchTransitionEvents } from "./screen-transition.ts";
+import { _dispatchTextureEvents } from "./texture.ts";
 
 const hasRenderOps =
   typeof (globalThis as any).Deno !== "undefined" &&
@@ -9,6 +16,9 @@ const hasRenderOps =
  * Only one callback can be active -- calling onFrame() again replaces the previous one.
  * The callback is invoked by the Rust game loop (not by requestAnimationFrame).
  * No-op in headless mode (the callback is stored but never invoked).
+ * If the callback throws, that frame is discarded: the dev window keeps the
+ * last good frame on screen with an error overlay, and resumes on the next
+ * frame that succeeds (e.g. after saving a fix).
  *
  * @param callback - Function to call each frame. Use {@link getDeltaTime} inside for timing.
  *
@@ -26,7 +36,22 @@ export function onFrame(callback: () => void): void {
     if (typeof (globalThis as any).__arcane_reset_msdf_cache === "function") {
       (globalThis as any).__arcane_reset_msdf_cache();
     }
-    callback();
+    _dispatchFocusEvents();
+    _dispatchLifecycleEvents();
+    _dispatchGpuErrors();
+    _dispatchSpriteAnimationEvents();
+    _dispatchTransitionEvents();
+    _dispatchTextureEvents();
+    try {
+      callback();
+    } catch (e) {
+      // Drop the partial frame; the dev loop keeps showing the last good one
+      // and overlays this error until a hot reload fixes it.
+      _discardSpriteBatch();
+      _reportSpriteIssues();
+      throw e;
+    }
+    _reportSpriteIssues();
     // Flush any batched sprites to Rust before the renderer draws this frame
     _flushSpriteBatch();
   };
@@ -35,7 +60,7 @@ export function onFrame(callback: () => void): void {
 /**
  * Get the time elapsed since the last frame, in seconds.
  * Typical values: ~0.016 at 60fps, ~0.033 at 30fps.
- * Returns 0 in headless mode.
+ * Returns 0 in headless mode and while the simulation is paused on focus loss.
  *
  * @returns Delta time in seconds (fractional).
  */
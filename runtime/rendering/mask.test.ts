@@ -0,0 +1,43 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { beginMask, endMask, drawMasked } from "./mask.ts";
+import { drawSprite } from "./sprites.ts";
+import {
+  enableDrawCallCapture,
+  disableDrawCallCapture,
+  getDrawCalls,
+  clearDrawCalls,
+} from "../testing/visual.ts";
+
+describe("Stencil masks", () => {
+  it("beginMask returns 0 in headless mode", () => {
+    assert.equal(beginMask(), 0);
+    endMask();
+  });
+
+  it("drawMasked runs its draw callback, nested or not", () => {
+    enableDrawCallCapture();
+    clearDrawCalls();
+
+    const mask = beginMask();
+    endMask();
+    drawMasked(mask, () => {
+      drawSprite({ textureId: 1, x: 0, y: 0, w: 16, h: 16 });
+      drawMasked(mask, () => drawSprite({ textureId: 1, x: 20, y: 0, w: 16, h: 16 }), { invert: true });
+    });
+
+    assert.equal(getDrawCalls().length, 2);
+    disableDrawCallCapture();
+  });
+
+  it("drawMasked rethrows errors from its callback", () => {
+    let threw = false;
+    try {
+      drawMasked(1, () => {
+        throw new Error("boom");
+      });
+    } catch {
+      threw = true;
+    }
+    assert.ok(threw);
+  });
+});
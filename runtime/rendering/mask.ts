@@ -0,0 +1,91 @@
+/**
+ * Stencil masks: clip sprites to arbitrary shapes.
+ *
+ * Geometry shapes drawn between {@link beginMask} and {@link endMask}
+ * (`drawCircle`, `drawPolygon`, `drawTriangle`, `drawLine`, `drawSector`, ...)
+ * don't appear on screen; they shape the mask. Sprites drawn inside
+ * {@link drawMasked} then only show inside the mask (or only outside it,
+ * with `invert`). Sprites, text and `drawRect` never shape a mask.
+ *
+ * Masks are rebuilt every frame, so define them in the frame callback before
+ * using them. Where masks overlap, the one begun later owns the pixels. Masks
+ * clip sprites, text and tilemaps drawn to the screen; shapes, SDFs,
+ * particles, custom-shader sprites and sprites drawn into render targets are
+ * not clipped. No-op in headless mode.
+ *
+ * @example
+ * ```ts
+ * // Circular minimap
+ * const lens = beginMask();
+ * drawCircle(700, 100, 80, { screenSpace: true });
+ * endMask();
+ * drawMasked(lens, () => drawSprite({ textureId: mapTex, x: 620, y: 20, w: 160, h: 160, screenSpace: true }));
+ * ```
+ */
+
+import { _flushSpriteBatch } from "./sprites.ts";
+
+/** A mask ID from {@link beginMask}; 0 is "no mask". Valid for the current frame. */
+export type MaskId = number;
+
+/** Options for {@link drawMasked}. */
+export type MaskOptions = {
+  /** Draw only outside the mask's shapes instead of inside. Default: false. */
+  invert?: boolean;
+};
+
+const hasMaskOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_begin_mask === "function";
+
+/** The mask sprites are drawn with now, restored when drawMasked returns. */
+let _active: { id: MaskId; invert: boolean } = { id: 0, invert: false };
+
+/**
+ * Start a mask: geometry shapes drawn until {@link endMask} shape it instead
+ * of drawing. Returns its ID, or 0 while another mask is being defined or
+ * when the frame's 255 masks are used up. Returns 0 in headless mode.
+ */
+export function beginMask(): MaskId {
+  if (!hasMaskOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_begin_mask();
+}
+
+/** Stop shaping the mask from {@link beginMask}; shapes draw again. */
+export function endMask(): void {
+  if (!hasMaskOps) return;
+  (globalThis as any).Deno.core.ops.op_end_mask();
+}
+
+/**
+ * Run `draw`, clipping the sprites it draws to `mask`. Calls nest: the inner
+ * mask replaces the outer one until it returns. A mask of 0 draws unclipped.
+ *
+ * @param mask - ID from {@link beginMask} this frame.
+ * @param draw - Draws the sprites to clip.
+ * @param options - `invert` to draw only outside the mask.
+ *
+ * @example
+ * // Flashlight: darkness everywhere except the cone
+ * drawMasked(cone, () => drawSprite({ textureId: dark, x: 0, y: 0, w: 800, h: 600, screenSpace: true }), { invert: true });
+ */
+export function drawMasked(mask: MaskId, draw: () => void, options?: MaskOptions): void {
+  if (!hasMaskOps) {
+    draw();
+    return;
+  }
+  const outer = _active;
+  _setMask({ id: mask, invert: options?.invert ?? false });
+  try {
+    draw();
+  } finally {
+    _setMask(outer);
+  }
+}
+
+function _setMask(mask: { id: MaskId; invert: boolean }): void {
+  // Sprites batched so far keep the mask they were drawn with
+  _flushSpriteBatch();
+  _active = mask;
+  (globalThis as any).Deno.core.ops.op_draw_masked(mask.id, mask.invert);
+}
@@ -0,0 +1,46 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { uploadMesh, updateMesh, drawMesh, destroyMesh, MESH_VERTEX_STRIDE } from "./mesh.ts";
+
+const hasMeshOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_geo_upload_mesh === "function";
+
+const triangle = [
+  0, 0, 0, 0, 1, 0, 0, 1,
+  10, 0, 1, 0, 0, 1, 0, 1,
+  0, 10, 0, 1, 0, 0, 1, 1,
+];
+
+describe("Custom meshes", () => {
+  it("uploadMesh returns 0 in headless mode", () => {
+    const id = uploadMesh(triangle, [0, 1, 2]);
+    if (!hasMeshOps) {
+      assert.equal(id, 0);
+      assert.equal(updateMesh(id, triangle, [0, 1, 2]), false);
+      return;
+    }
+    assert.ok(id > 0);
+    destroyMesh(id);
+  });
+
+  it("meshes without whole triangles are rejected", () => {
+    if (!hasMeshOps) return;
+    assert.equal(uploadMesh(triangle, [0, 1]), 0);
+    assert.equal(uploadMesh(triangle, [0, 1, 3]), 0);
+    assert.equal(uploadMesh(triangle.slice(0, MESH_VERTEX_STRIDE * 2), [0, 1, 2]), 0);
+  });
+
+  it("updateMesh keeps the handle and rejects unknown meshes", () => {
+    if (!hasMeshOps) return;
+    const id = uploadMesh(new Float32Array(triangle), new Uint16Array([0, 1, 2]));
+    assert.ok(updateMesh(id, triangle, new Uint32Array([2, 1, 0])));
+    assert.equal(updateMesh(id, triangle, []), false);
+    destroyMesh(id);
+    assert.equal(updateMesh(id, triangle, [0, 1, 2]), false);
+  });
+
+  it("headless-safe no-ops", () => {
+    drawMesh(0, { x: 10, y: 10, rotation: 1, layer: 3 });
+    destroyMesh(0);
+    assert.ok(true);
+  });
+});
@@ -0,0 +1,129 @@
+/**
+ * Custom meshes: arbitrary triangle geometry with per-vertex colors and UVs,
+ * uploaded once and drawn any number of times.
+ *
+ * Vertices are packed as `[x, y, u, v, r, g, b, a]` per vertex (colors 0-1),
+ * indices as triangle triples. A mesh with a texture samples it at each
+ * vertex's UV and multiplies by the vertex color; without one, vertex colors
+ * are drawn directly (gradients, terrain, procedural shapes). Meshes are
+ * rendered with the geometry pipeline: on the same layer they draw after
+ * sprites, and they follow render targets like drawTriangle() does.
+ *
+ * In headless mode every function is a no-op and {@link uploadMesh} returns 0.
+ */
+import type { TextureId } from "./types.ts";
+
+const hasMeshOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_geo_upload_mesh === "function";
+
+/** Handle to an uploaded mesh. 0 = invalid / headless. */
+export type MeshId = number;
+
+/** Number of floats per vertex: x, y, u, v, r, g, b, a. */
+export const MESH_VERTEX_STRIDE = 8;
+
+/** Options for {@link drawMesh}. */
+export type DrawMeshOptions = {
+  /** Position of the mesh's local origin. Default: 0. */
+  x?: number;
+  /** Position of the mesh's local origin. Default: 0. */
+  y?: number;
+  /** Rotation in radians around the local origin. Default: 0. */
+  rotation?: number;
+  /** Horizontal scale. Default: 1. */
+  scaleX?: number;
+  /** Vertical scale. Default: 1. */
+  scaleY?: number;
+  /** Multiplied with every vertex color. Default: white. */
+  tint?: { r: number; g: number; b: number; a: number };
+  /** Draw layer. Default: 0. */
+  layer?: number;
+};
+
+function vertexBytes(vertices: Float32Array | number[]): Uint8Array {
+  const data = vertices instanceof Float32Array ? vertices : new Float32Array(vertices);
+  return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+}
+
+function indexBytes(indices: Uint16Array | Uint32Array | number[]): Uint8Array {
+  const data = indices instanceof Uint32Array ? indices : Uint32Array.from(indices);
+  return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+}
+
+/**
+ * Upload a mesh. Triangles that reference missing vertices are dropped.
+ * Pass `textureId` to texture it; omit it to draw vertex colors only.
+ *
+ * @returns Mesh handle, or 0 if no triangle is left or in headless mode.
+ *
+ * @example
+ * // A quad fading from red (top) to blue (bottom)
+ * const quad = uploadMesh(
+ *   [0, 0, 0, 0, 1, 0, 0, 1,  64, 0, 1, 0, 1, 0, 0, 1,
+ *    64, 64, 1, 1, 0, 0, 1, 1,  0, 64, 0, 1, 0, 0, 1, 1],
+ *   [0, 1, 2, 0, 2, 3],
+ * );
+ */
+export function uploadMesh(
+  vertices: Float32Array | number[],
+  indices: Uint16Array | Uint32Array | number[],
+  textureId: TextureId = 0,
+): MeshId {
+  if (!hasMeshOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_geo_upload_mesh(
+    vertexBytes(vertices),
+    indexBytes(indices),
+    textureId >>> 0,
+  );
+}
+
+/**
+ * Replace a mesh's vertices, indices and texture, keeping its handle.
+ * Returns false for unknown meshes, when no triangle is left, or in headless mode.
+ */
+export function updateMesh(
+  id: MeshId,
+  vertices: Float32Array | number[],
+  indices: Uint16Array | Uint32Array | number[],
+  textureId: TextureId = 0,
+): boolean {
+  if (!hasMeshOps) return false;
+  return (globalThis as any).Deno.core.ops.op_geo_update_mesh(
+    id >>> 0,
+    vertexBytes(vertices),
+    indexBytes(indices),
+    textureId >>> 0,
+  );
+}
+
+/**
+ * Draw an uploaded mesh this frame: scaled, then rotated, then moved to
+ * `(x, y)`. Unknown meshes are ignored.
+ *
+ * @example
+ * drawMesh(quad, { x: 100, y: 80, rotation: t, layer: 2 });
+ */
+export function drawMesh(id: MeshId, options: DrawMeshOptions = {}): void {
+  if (!hasMeshOps) return;
+  const tint = options.tint ?? { r: 1, g: 1, b: 1, a: 1 };
+  (globalThis as any).Deno.core.ops.op_geo_draw_mesh(
+    id >>> 0,
+    options.x ?? 0,
+    options.y ?? 0,
+    options.rotation ?? 0,
+    options.scaleX ?? 1,
+    options.scaleY ?? 1,
+    tint.r,
+    tint.g,
+    tint.b,
+    tint.a,
+    options.layer ?? 0,
+  );
+}
+
+/** Free an uploaded mesh. The handle is not reused. */
+export function destroyMesh(id: MeshId): void {
+  if (!hasMeshOps) return;
+  (globalThis as any).Deno.core.ops.op_geo_destroy_mesh(id >>> 0);
+}
@@ -0,0 +1,35 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  createMinimap,
+  destroyMinimap,
+  getMinimapTextureId,
+  setMinimapWorld,
+  worldToMinimap,
+} from "./minimap.ts";
+
+const hasMinimapOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_minimap === "function";
+
+describe("Minimap", () => {
+  it("createMinimap returns 0 in headless mode", () => {
+    const id = createMinimap(100, 50, { x: 0, y: 0, w: 1000, h: 500 });
+    if (!hasMinimapOps) {
+      assert.equal(id, 0);
+      assert.equal(worldToMinimap(id, 10, 10), null);
+      return;
+    }
+    assert.ok(id > 0);
+    assert.equal(getMinimapTextureId(id), id);
+    destroyMinimap(id);
+  });
+
+  it("worldToMinimap maps world positions into texture pixels", () => {
+    const id = createMinimap(100, 50, { x: 1000, y: 0, w: 1000, h: 500 }, { interval: 4 });
+    if (!id) return;
+    assert.deepEqual(worldToMinimap(id, 1500, 250), { x: 50, y: 25 });
+    setMinimapWorld(id, { x: 0, y: 0, w: 2000, h: 1000 });
+    assert.deepEqual(worldToMinimap(id, 1000, 500), { x: 50, y: 25 });
+    destroyMinimap(id);
+    assert.equal(worldToMinimap(id, 0, 0), null);
+  });
+});
@@ -0,0 +1,106 @@
+/**
+ * Minimaps: the engine re-renders selected layers of the main scene, scaled
+ * to fit a world rectangle, into an off-screen texture every N frames.
+ * Draw the world once as usual; display the minimap like any texture.
+ */
+import type { TextureId } from "./types.ts";
+
+const hasMinimapOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_minimap === "function";
+
+/** Handle to a minimap. Doubles as a `TextureId`. 0 = headless. */
+export type MinimapId = number;
+
+/** World rectangle captured by a minimap. */
+export type MinimapWorld = { x: number; y: number; w: number; h: number };
+
+/** Options for {@link createMinimap}. */
+export type MinimapOptions = {
+  /** Inclusive layer range to capture, e.g. `[0, 10]` to skip HUD layers. Default: all layers. */
+  layers?: [number, number];
+  /** Re-render every N frames. Default: 1 (every frame). */
+  interval?: number;
+};
+
+// Size and world rectangle per minimap, for coordinate conversion on the TS side
+const minimaps = new Map<MinimapId, { width: number; height: number; world: MinimapWorld }>();
+
+/**
+ * Create a minimap texture of `width × height` pixels showing `world`.
+ * Only sprites are captured; sprites outside `world` are culled.
+ * Returns 0 in headless mode.
+ *
+ * @example
+ * const map = createMinimap(160, 120, { x: 0, y: 0, w: level.w, h: level.h }, { layers: [0, 10], interval: 4 });
+ * onFrame(() => {
+ *   drawLevel();
+ *   drawSprite({ textureId: map, x: 8, y: 8, w: 160, h: 120, layer: 100, screenSpace: true });
+ * });
+ */
+export function createMinimap(
+  width: number,
+  height: number,
+  world: MinimapWorld,
+  options: MinimapOptions = {},
+): MinimapId {
+  if (!hasMinimapOps) return 0;
+  const ops = (globalThis as any).Deno.core.ops;
+  const id: MinimapId = ops.op_create_minimap(width, height, world.x, world.y, world.w, world.h);
+  minimaps.set(id, { width, height, world: { ...world } });
+  if (options.layers) ops.op_set_minimap_layers(id, options.layers[0] | 0, options.layers[1] | 0);
+  if (options.interval !== undefined) ops.op_set_minimap_interval(id, Math.max(1, options.interval | 0));
+  return id;
+}
+
+/** Change the world rectangle shown by the minimap. */
+export function setMinimapWorld(id: MinimapId, world: MinimapWorld): void {
+  if (!hasMinimapOps) return;
+  const info = minimaps.get(id);
+  if (info) info.world = { ...world };
+  (globalThis as any).Deno.core.ops.op_set_minimap_world(id >>> 0, world.x, world.y, world.w, world.h);
+}
+
+/** Capture only layers in `[min, max]` (inclusive). */
+export function setMinimapLayers(id: MinimapId, min: number, max: number): void {
+  if (!hasMinimapOps) return;
+  (globalThis as any).Deno.core.ops.op_set_minimap_layers(id >>> 0, min | 0, max | 0);
+}
+
+/** Re-render every `frames` frames (minimum 1). */
+export function setMinimapInterval(id: MinimapId, frames: number): void {
+  if (!hasMinimapOps) return;
+  (globalThis as any).Deno.core.ops.op_set_minimap_interval(id >>> 0, Math.max(1, frames | 0));
+}
+
+/** Re-render on the next frame regardless of the interval (e.g. after a door opens). */
+export function refreshMinimap(id: MinimapId): void {
+  if (!hasMinimapOps) return;
+  (globalThis as any).Deno.core.ops.op_refresh_minimap(id >>> 0);
+}
+
+/** Destroy a minimap and free its texture. */
+export function destroyMinimap(id: MinimapId): void {
+  minimaps.delete(id);
+  if (!hasMinimapOps) return;
+  (globalThis as any).Deno.core.ops.op_destroy_minimap(id >>> 0);
+}
+
+/** The minimap's texture ID (same value as the handle). */
+export function getMinimapTextureId(id: MinimapId): TextureId {
+  return id;
+}
+
+/**
+ * Convert a world position to pixel coordinates inside the minimap texture,
+ * e.g. to draw a player marker or the camera viewport on top of it.
+ * Returns null for unknown minimaps (always null in headless mode).
+ */
+export function worldToMinimap(id: MinimapId, x: number, y: number): { x: number; y: number } | null {
+  const info = minimaps.get(id);
+  if (!info || info.world.w <= 0 || info.world.h <= 0) return null;
+  return {
+    x: ((x - info.world.x) / info.world.w) * info.width,
+    y: ((y - info.world.y) / info.world.h) * info.height,
+  };
+}
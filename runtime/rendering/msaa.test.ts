@@ -0,0 +1,11 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { setMsaa, getMsaa } from "./msaa.ts";
+
+describe("MSAA", () => {
+  it("is off and a no-op in headless mode", () => {
+    setMsaa(4);
+    assert.equal(getMsaa(), 1);
+    setMsaa(1);
+    assert.equal(getMsaa(), 1);
+  });
+});
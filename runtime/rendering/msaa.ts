@@ -0,0 +1,37 @@
+/**
+ * Multisample anti-aliasing (MSAA) for the scene.
+ *
+ * Smooths the edges of geometry (thin rotated lines, polygons), SDF shapes
+ * and sprite quads by shading several coverage samples per pixel. Texture
+ * interiors are unchanged, so pixel art stays sharp. Post-processing, GI and
+ * plugin passes work on the resolved image. Render targets are not
+ * multisampled. All functions are no-ops in headless mode.
+ */
+
+const hasMsaaOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_msaa === "function";
+
+/** Samples per pixel; 1 turns MSAA off. */
+export type MsaaSamples = 1 | 2 | 4 | 8;
+
+/**
+ * Draw the scene with `samples` per pixel. Off (1) by default. Counts the GPU
+ * doesn't support fall back to the next lower one (4 is always available).
+ *
+ * @example
+ * setMsaa(4); // smooth line and SDF outline edges at low zoom
+ */
+export function setMsaa(samples: MsaaSamples): void {
+  if (!hasMsaaOps) return;
+  (globalThis as any).Deno.core.ops.op_set_msaa(samples);
+}
+
+/**
+ * Samples per pixel in use. Reports the fallback from the next frame on when
+ * the requested count is unsupported. Always 1 in headless mode.
+ */
+export function getMsaa(): number {
+  if (!hasMsaaOps) return 1;
+  return (globalThis as any).Deno.core.ops.op_get_msaa();
+}
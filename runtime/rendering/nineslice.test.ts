@@ -130,6 +130,28 @@ describe("Nine-Slice Sprites", () => {
       disableDrawCallCapture();
     });
 
+    it("scales border sizes with borderScale", () => {
+      enableDrawCallCapture();
+      clearDrawCalls();
+
+      drawNineSlice(1, 0, 0, 200, 100, {
+        border: 8,
+        borderScale: 2,
+        textureWidth: 64,
+        textureHeight: 64,
+      });
+
+      const calls = getDrawCalls() as any[];
+      assert.equal(calls.length, 9);
+      // Corners are drawn at twice their texture size; the center fills the rest
+      assert.equal(calls[0].w, 16);
+      assert.equal(calls[0].h, 16);
+      assert.equal(calls[4].x, 16);
+      assert.equal(calls[4].w, 168);
+
+      disableDrawCallCapture();
+    });
+
     it("draws with custom layer and opacity", () => {
       enableDrawCallCapture();
       clearDrawCalls();
@@ -5,6 +5,10 @@
  * the four edges stretch in one dimension, and the center stretches in both.
  * This allows scalable UI panels without corner distortion.
  *
+ * With the renderer active, each panel is one `op_draw_nine_slice` call and
+ * Rust generates the nine quads from the texture's real size. In headless
+ * mode the slices are emitted as individual drawSprite calls.
+ *
  * ```
  *  ┌────┬────────┬────┐
  *  │ TL │  Top   │ TR │   Corners: fixed size
@@ -22,9 +26,17 @@
  */
 
 import type { TextureId } from "./types.ts";
-import { drawSprite } from "./sprites.ts";
+import { _flushSpriteBatch, drawSprite } from "./sprites.ts";
 import { getCamera } from "./camera.ts";
 
+const hasNineSliceOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_draw_nine_slice === "function";
+
+// One panel: a sprite record (see sprites.ts), the border insets and the border scale
+const NINE_SLICE_STRIDE = 28;
+const _panelBuffer = new Float32Array(NINE_SLICE_STRIDE);
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -48,6 +60,11 @@ export type NineSliceOptions = {
    * or a per-edge object.
    */
   border: number | NineSliceBorder;
+  /**
+   * Size of the drawn borders relative to their size in the texture, e.g. 2
+   * for a pixel-art frame at double size. Default: 1.
+   */
+  borderScale?: number;
   /** Draw order layer. Default: 0. */
   layer?: number;
   /** Tint color. Default: white (no tint). */
@@ -57,8 +74,8 @@ export type NineSliceOptions = {
   /** If true, x/y/w/h are in screen pixels. Default: false. */
   screenSpace?: boolean;
   /**
-   * Texture dimensions in pixels. Required for correct UV calculation.
-   * If not provided, assumes 1:1 mapping (UV border = border / 256).
+   * Texture dimensions in pixels, used for the UV calculation in headless
+   * mode. The renderer uses the texture's real size. Default: 256.
    */
   textureWidth?: number;
   /** Texture height in pixels. Default: 256. */
@@ -103,6 +120,7 @@ function toWorld(
  * The texture is divided into a 3x3 grid based on `border` insets. Corners
  * are drawn at fixed size, edges stretch, and the center fills the remainder.
  *
+ * Panels smaller than two opposite borders shrink those borders to fit.
  * No-op in headless mode (drawSprite is a no-op).
  *
  * @param textureId - Texture handle from loadTexture().
@@ -127,6 +145,28 @@ export function drawNineSlice(
   const ss = options.screenSpace ?? false;
   const texW = options.textureWidth ?? 256;
   const texH = options.textureHeight ?? 256;
+  const borderScale = options.borderScale ?? 1;
+
+  if (hasNineSliceOp) {
+    const world = toWorld(x, y, w, h, ss);
+    const view = new DataView(_panelBuffer.buffer);
+    view.setUint32(0, textureId, true);
+    _panelBuffer[1] = world.x;
+    _panelBuffer[2] = world.y;
+    _panelBuffer[3] = world.w;
+    _panelBuffer[4] = world.h;
+    view.setInt32(5 * 4, layerVal, true);
+    _panelBuffer.set([0, 0, 1, 1, tint.r, tint.g, tint.b, tint.a, 0, 0.5, 0.5, 0, 0, opacity, 0], 6);
+    view.setUint32(21 * 4, 0, true);
+    view.setUint32(22 * 4, 0, true);
+    // Screen-space borders keep their screen size, like the rest of the panel
+    const scale = ss ? borderScale / getCamera().zoom : borderScale;
+    _panelBuffer.set([border.left, border.top, border.right, border.bottom, scale], 23);
+    // Keep draw order relative to sprites queued earlier this frame
+    _flushSpriteBatch();
+    (globalThis as any).Deno.core.ops.op_draw_nine_slice(new Uint8Array(_panelBuffer.buffer));
+    return;
+  }
 
   // UV insets (normalized 0-1)
   const uvLeft = border.left / texW;
@@ -134,9 +174,17 @@ export function drawNineSlice(
   const uvTop = border.top / texH;
   const uvBottom = border.bottom / texH;
 
+  // Border sizes in draw space
+  const d = {
+    top: border.top * borderScale,
+    bottom: border.bottom * borderScale,
+    left: border.left * borderScale,
+    right: border.right * borderScale,
+  };
+
   // Sizes of the center region in draw space
-  const centerW = Math.max(0, w - border.left - border.right);
-  const centerH = Math.max(0, h - border.top - border.bottom);
+  const centerW = Math.max(0, w - d.left - d.right);
+  const centerH = Math.max(0, h - d.top - d.bottom);
 
   // UV center region
   const uvCenterW = Math.max(0, 1 - uvLeft - uvRight);
@@ -148,25 +196,25 @@ export function drawNineSlice(
     uvx: number; uvy: number; uvw: number; uvh: number;
   }> = [
     // Row 0 (top)
-    { sx: x, sy: y, sw: border.left, sh: border.top,
+    { sx: x, sy: y, sw: d.left, sh: d.top,
       uvx: 0, uvy: 0, uvw: uvLeft, uvh: uvTop },
-    { sx: x + border.left, sy: y, sw: centerW, sh: border.top,
+    { sx: x + d.left, sy: y, sw: centerW, sh: d.top,
       uvx: uvLeft, uvy: 0, uvw: uvCenterW, uvh: uvTop },
-    { sx: x + border.left + centerW, sy: y, sw: border.right, sh: border.top,
+    { sx: x + d.left + centerW, sy: y, sw: d.right, sh: d.top,
       uvx: 1 - uvRight, uvy: 0, uvw: uvRight, uvh: uvTop },
     // Row 1 (center)
-    { sx: x, sy: y + border.top, sw: border.left, sh: centerH,
+    { sx: x, sy: y + d.top, sw: d.left, sh: centerH,
       uvx: 0, uvy: uvTop, uvw: uvLeft, uvh: uvCenterH },
-    { sx: x + border.left, sy: y + border.top, sw: centerW, sh: centerH,
+    { sx: x + d.left, sy: y + d.top, sw: centerW, sh: centerH,
       uvx: uvLeft, uvy: uvTop, uvw: uvCenterW, uvh: uvCenterH },
-    { sx: x + border.left + centerW, sy: y + border.top, sw: border.right, sh: centerH,
+    { sx: x + d.left + centerW, sy: y + d.top, sw: d.right, sh: centerH,
       uvx: 1 - uvRight, uvy: uvTop, uvw: uvRight, uvh: uvCenterH },
     // Row 2 (bottom)
-    { sx: x, sy: y + border.top + centerH, sw: border.left, sh: border.bottom,
+    { sx: x, sy: y + d.top + centerH, sw: d.left, sh: d.bottom,
       uvx: 0, uvy: 1 - uvBottom, uvw: uvLeft, uvh: uvBottom },
-    { sx: x + border.left, sy: y + border.top + centerH, sw: centerW, sh: border.bottom,
+    { sx: x + d.left, sy: y + d.top + centerH, sw: centerW, sh: d.bottom,
       uvx: uvLeft, uvy: 1 - uvBottom, uvw: uvCenterW, uvh: uvBottom },
-    { sx: x + border.left + centerW, sy: y + border.top + centerH, sw: border.right, sh: border.bottom,
+    { sx: x + d.left + centerW, sy: y + d.top + centerH, sw: d.right, sh: d.bottom,
       uvx: 1 - uvRight, uvy: 1 - uvBottom, uvw: uvRight, uvh: uvBottom },
   ];
 
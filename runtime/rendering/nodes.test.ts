@@ -0,0 +1,67 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  createSpriteNode,
+  destroySpriteNode,
+  setSpriteNodeTransform,
+  setSpriteNodeTransforms,
+  setSpriteNodeParent,
+  attachSprites,
+  getSpriteNodeWorldTransform,
+} from "./nodes.ts";
+
+const hasNodeOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_node === "function";
+
+describe("Sprite nodes", () => {
+  it("createSpriteNode returns a number", () => {
+    const id = createSpriteNode();
+    assert.equal(typeof id, "number");
+    if (!hasNodeOps) assert.equal(id, 0);
+  });
+
+  it("child world transform composes parent offset", () => {
+    const parent = createSpriteNode();
+    const child = createSpriteNode(parent);
+    setSpriteNodeTransform(parent, 100, 50);
+    setSpriteNodeTransform(child, 10, 0);
+    const wt = getSpriteNodeWorldTransform(child);
+    if (!hasNodeOps) {
+      assert.equal(wt, null);
+      return;
+    }
+    assert.ok(wt !== null);
+    assert.equal(wt!.x, 110);
+    assert.equal(wt!.y, 50);
+    destroySpriteNode(child);
+    destroySpriteNode(parent);
+  });
+
+  it("setSpriteNodeTransforms sets several nodes at once", () => {
+    const parent = createSpriteNode();
+    const child = createSpriteNode(parent);
+    setSpriteNodeTransforms([
+      { id: parent, x: 100, y: 0, scaleX: 2, scaleY: 2 },
+      { id: child, x: 5, y: 1 },
+    ]);
+    setSpriteNodeTransforms([]);
+    const wt = getSpriteNodeWorldTransform(child);
+    if (!hasNodeOps) {
+      assert.equal(wt, null);
+      return;
+    }
+    assert.equal(wt!.x, 110);
+    assert.equal(wt!.y, 2);
+    destroySpriteNode(child);
+    destroySpriteNode(parent);
+  });
+
+  it("setSpriteNodeParent rejects cycles", () => {
+    const a = createSpriteNode();
+    const b = createSpriteNode(a);
+    assert.equal(setSpriteNodeParent(a, b), false);
+  });
+
+  it("attachSprites accepts 0 to detach", () => {
+    attachSprites(0);
+  });
+});
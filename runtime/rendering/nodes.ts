@@ -0,0 +1,180 @@
+import { _flushSpriteBatch } from "./sprites.ts";
+
+const hasNodeOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_node === "function";
+
+/**
+ * Submit the sprites batched so far before a node changes. Attached sprites
+ * are mapped through their node's world transform when the batch reaches
+ * Rust, so sprites queued before a move, reparent or destroy would
+ * otherwise be drawn with the node's new transform.
+ */
+function flushBeforeNodeChange(): void {
+  _flushSpriteBatch();
+}
+
+/**
+ * Opaque handle for a Rust-side sprite transform node.
+ * 0 means "no node" (world space).
+ */
+export type SpriteNodeId = number;
+
+/** World-space transform of a sprite node. */
+export type SpriteNodeTransform = {
+  x: number;
+  y: number;
+  rotation: number;
+  scaleX: number;
+  scaleY: number;
+};
+
+/**
+ * Create a transform node for parenting sprites. Nodes compose their local
+ * transform with their parent's, so moving a parent moves every descendant.
+ *
+ * Returns 0 in headless mode. For pure-TS hierarchies (game logic, tests),
+ * see `createNode()` in `game/transform.ts`.
+ *
+ * @param parent Parent node, or 0 / omitted for a root node.
+ * @returns Node handle.
+ *
+ * @example
+ * const body = createSpriteNode();
+ * const hand = createSpriteNode(body);
+ * setSpriteNodeTransform(hand, 12, -4);
+ */
+export function createSpriteNode(parent: SpriteNodeId = 0): SpriteNodeId {
+  if (!hasNodeOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_node(parent >>> 0);
+}
+
+/**
+ * Destroy a sprite node. Its children become root nodes.
+ *
+ * @param id Node handle from `createSpriteNode()`.
+ */
+export function destroySpriteNode(id: SpriteNodeId): void {
+  if (!hasNodeOps) return;
+  flushBeforeNodeChange();
+  (globalThis as any).Deno.core.ops.op_destroy_node(id >>> 0);
+}
+
+/**
+ * Set a node's transform relative to its parent.
+ *
+ * @param id       Node handle.
+ * @param x        Local X offset.
+ * @param y        Local Y offset.
+ * @param rotation Local rotation in radians. Default: 0.
+ * @param scaleX   Local X scale. Negative values mirror children. Default: 1.
+ * @param scaleY   Local Y scale. Default: 1.
+ */
+export function setSpriteNodeTransform(
+  id: SpriteNodeId,
+  x: number,
+  y: number,
+  rotation: number = 0,
+  scaleX: number = 1,
+  scaleY: number = 1,
+): void {
+  if (!hasNodeOps) return;
+  flushBeforeNodeChange();
+  (globalThis as any).Deno.core.ops.op_set_node_transform(id >>> 0, x, y, rotation, scaleX, scaleY);
+}
+
+/** One entry for {@link setSpriteNodeTransforms}. Omitted fields default as in `setSpriteNodeTransform()`. */
+export type SpriteNodeTransformUpdate = {
+  id: SpriteNodeId;
+  x: number;
+  y: number;
+  rotation?: number;
+  scaleX?: number;
+  scaleY?: number;
+};
+
+// [id as u32 bits, x, y, rotation, scaleX, scaleY] per node
+const NODE_TRANSFORM_STRIDE = 6;
+let _transformBuffer = new Float32Array(64 * NODE_TRANSFORM_STRIDE);
+
+/**
+ * Set the local transforms of many nodes in one call. Cheaper than calling
+ * `setSpriteNodeTransform()` per node when animating a character made of
+ * many parts. Unknown nodes are skipped.
+ *
+ * @param updates Node handles and their new local transforms.
+ *
+ * @example
+ * setSpriteNodeTransforms([
+ *   { id: body, x: player.x, y: player.y },
+ *   { id: leftArm, x: -6, y: -10, rotation: Math.sin(t) * 0.4 },
+ *   { id: rightArm, x: 6, y: -10, rotation: -Math.sin(t) * 0.4 },
+ * ]);
+ */
+export function setSpriteNodeTransforms(updates: readonly SpriteNodeTransformUpdate[]): void {
+  if (!hasNodeOps || updates.length === 0) return;
+  const needed = updates.length * NODE_TRANSFORM_STRIDE;
+  if (_transformBuffer.length < needed) {
+    _transformBuffer = new Float32Array(Math.max(needed, _transformBuffer.length * 2));
+  }
+  const view = new DataView(_transformBuffer.buffer);
+  for (let i = 0; i < updates.length; i++) {
+    const u = updates[i];
+    const base = i * NODE_TRANSFORM_STRIDE;
+    view.setUint32(base * 4, u.id >>> 0, true); // node id as u32 bits in f32 slot
+    _transformBuffer[base + 1] = u.x;
+    _transformBuffer[base + 2] = u.y;
+    _transformBuffer[base + 3] = u.rotation ?? 0;
+    _transformBuffer[base + 4] = u.scaleX ?? 1;
+    _transformBuffer[base + 5] = u.scaleY ?? 1;
+  }
+  flushBeforeNodeChange();
+  (globalThis as any).Deno.core.ops.op_set_node_transforms(new Uint8Array(_transformBuffer.buffer, 0, needed * 4));
+}
+
+/**
+ * Move a node under a new parent, or pass 0 to make it a root.
+ * Returns false if the change would create a cycle or a node does not exist.
+ * Always returns false in headless mode.
+ *
+ * @param id     Node handle.
+ * @param parent New parent node, or 0 to detach.
+ */
+export function setSpriteNodeParent(id: SpriteNodeId, parent: SpriteNodeId): boolean {
+  if (!hasNodeOps) return false;
+  flushBeforeNodeChange();
+  return (globalThis as any).Deno.core.ops.op_set_node_parent(id >>> 0, parent >>> 0);
+}
+
+/**
+ * Draw subsequent sprites relative to a node. Sprite positions, rotation and
+ * size are mapped through the node's world transform in Rust.
+ * Pass 0 to return to world space. The attachment persists across frames
+ * until changed, so always pair an attach with a detach.
+ *
+ * @param id Node handle, or 0 to detach.
+ *
+ * @example
+ * attachSprites(hand);
+ *   drawSprite({ textureId: swordTex, x: 0, y: -16, w: 8, h: 32 });
+ * attachSprites(0);
+ */
+export function attachSprites(id: SpriteNodeId): void {
+  if (!hasNodeOps) return;
+  // Sprites queued before this call belong to the previous attachment
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_attach_sprite_to_node(id >>> 0);
+}
+
+/**
+ * Get a node's composed world transform.
+ * Returns null in headless mode or for unknown nodes.
+ *
+ * @param id Node handle.
+ */
+export function getSpriteNodeWorldTransform(id: SpriteNodeId): SpriteNodeTransform | null {
+  if (!hasNodeOps) return null;
+  const t: number[] = (globalThis as any).Deno.core.ops.op_get_node_world_transform(id >>> 0);
+  if (t.length < 5) return null;
+  return { x: t[0], y: t[1], rotation: t[2], scaleX: t[3], scaleY: t[4] };
+}
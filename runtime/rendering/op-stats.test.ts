@@ -0,0 +1,22 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { formatOpStats, getOpStats, setOpProfiling } from "./op-stats.ts";
+
+describe("Op stats", () => {
+  it("is empty in headless mode", () => {
+    setOpProfiling(true);
+    assert.equal(getOpStats().length, 0);
+    setOpProfiling(false);
+  });
+
+  it("formats one aligned line per op", () => {
+    const text = formatOpStats([
+      { name: "op_draw_sprite", calls: 1200, totalMs: 1.5, avgUs: 1.25 },
+      { name: "op_set_tile", calls: 40, totalMs: 0.2, avgUs: 5 },
+    ]);
+    const lines = text.split("\n");
+    assert.equal(lines.length, 2);
+    assert.equal(lines[0], "op_draw_sprite    1200x     1.50ms     1.25us");
+    assert.equal(lines[1], "op_set_tile         40x     0.20ms     5.00us");
+    assert.equal(formatOpStats([]), "");
+  });
+});
@@ -0,0 +1,63 @@
+/**
+ * Op-call profiling. Every engine call from TS (drawSprite, setTile, physics
+ * queries, ...) crosses into Rust through an op; with profiling on, the
+ * engine counts calls and time per op each frame, so you can see which
+ * crossings dominate and replace them with batch APIs.
+ *
+ * The inspector reports the same table at `GET /op_stats?top=N` and in
+ * `GET /frame_stats` (MCP tools `get_op_stats`, `set_op_profiling`).
+ */
+
+const hasOpStatsOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_op_stats === "function";
+
+/** Calls and time of one op over the last frame. */
+export type OpStat = {
+  /** Op name, e.g. "op_draw_sprite". */
+  name: string;
+  calls: number;
+  /** Total time in the op, in milliseconds. */
+  totalMs: number;
+  /** Average time per call, in microseconds. */
+  avgUs: number;
+};
+
+/**
+ * Start or stop op profiling. Off by default; while on, every op call pays
+ * for a timer read. Stays on across hot reloads. No-op in headless mode.
+ */
+export function setOpProfiling(enabled: boolean): void {
+  if (!hasOpStatsOps) return;
+  (globalThis as any).Deno.core.ops.op_set_op_profiling(enabled);
+}
+
+/**
+ * The `top` ops of the last frame, most time first. Empty while profiling is
+ * off and in headless mode.
+ *
+ * @example
+ * setOpProfiling(true);
+ * // later, e.g. in an overlay:
+ * formatOpStats(getOpStats(5)).split("\n").forEach((line, i) => {
+ *   drawText(line, 8, 8 + i * 10, { screenSpace: true });
+ * });
+ */
+export function getOpStats(top: number = 10): OpStat[] {
+  if (!hasOpStatsOps) return [];
+  const json = (globalThis as any).Deno.core.ops.op_get_op_stats(Math.max(0, Math.floor(top)));
+  const report = JSON.parse(json) as {
+    ops: Array<{ name: string; calls: number; total_ms: number; avg_us: number }>;
+  };
+  return report.ops.map((op) => ({ name: op.name, calls: op.calls, totalMs: op.total_ms, avgUs: op.avg_us }));
+}
+
+/** One line per op: name, calls, total ms and µs per call, in aligned columns. */
+export function formatOpStats(stats: OpStat[]): string {
+  const width = Math.max(0, ...stats.map((s) => s.name.length));
+  return stats
+    .map((s) =>
+      `${s.name.padEnd(width)}  ${String(s.calls).padStart(6)}x  ${s.totalMs.toFixed(2).padStart(7)}ms  ${s.avgUs.toFixed(2).padStart(7)}us`,
+    )
+    .join("\n");
+}
@@ -0,0 +1,14 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { listPlugins, callPlugin } from "./plugins.ts";
+
+describe("Plugins", () => {
+  it("lists no plugins in headless mode", () => {
+    assert.deepEqual(listPlugins(), []);
+  });
+
+  it("fails plugin calls in headless mode without throwing", () => {
+    const res = callPlugin("navmesh", "findPath", { from: [0, 0] });
+    assert.equal(res.ok, false);
+    if (!res.ok) assert.equal(typeof res.error, "string");
+  });
+});
@@ -0,0 +1,59 @@
+/**
+ * Native engine plugins: list what is loaded and call their JSON functions.
+ * Plugins are registered by the CLI at startup (see `[plugins]` in arcane.toml).
+ */
+
+const hasPluginOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_list_plugins === "function";
+
+/** A loaded plugin, as reported by {@link listPlugins}. */
+export type PluginInfo = {
+  name: string;
+  version: string;
+  /** `"static"` when compiled into the engine, `"native"` when loaded from a shared library. */
+  source: "static" | "native";
+  /** Shared library path for native plugins, null for static ones. */
+  path: string | null;
+  /** CLI subcommands the plugin adds (`arcane <command>`). */
+  commands: string[];
+};
+
+/** Result of {@link callPlugin}. */
+export type PluginCallResult<T = unknown> =
+  | { ok: true; result: T }
+  | { ok: false; error: string };
+
+/**
+ * List the loaded plugins. Empty in headless mode and when none are loaded.
+ *
+ * @example
+ * const hasNav = listPlugins().some((p) => p.name === "navmesh");
+ */
+export function listPlugins(): PluginInfo[] {
+  if (!hasPluginOps) return [];
+  return JSON.parse((globalThis as any).Deno.core.ops.op_list_plugins());
+}
+
+/**
+ * Call a plugin function. `args` is sent as JSON and the plugin's JSON
+ * result is parsed. Fails (never throws) when the plugin or function is
+ * missing or the plugin reports an error.
+ *
+ * @example
+ * const res = callPlugin<{ path: number[] }>("navmesh", "findPath", { from: [0, 0], to: [10, 4] });
+ * if (res.ok) followPath(res.result.path);
+ */
+export function callPlugin<T = unknown>(
+  plugin: string,
+  fn: string,
+  args: unknown = null,
+): PluginCallResult<T> {
+  if (!hasPluginOps) return { ok: false, error: "plugins are not available in headless mode" };
+  const json = (globalThis as any).Deno.core.ops.op_call_plugin(plugin, fn, JSON.stringify(args ?? null));
+  try {
+    return JSON.parse(json);
+  } catch {
+    return { ok: false, error: `plugin "${plugin}" returned invalid JSON from "${fn}"` };
+  }
+}
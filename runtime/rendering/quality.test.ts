@@ -0,0 +1,21 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { enableAdaptiveQuality, disableAdaptiveQuality, setQualityTier, getQuality } from "./quality.ts";
+
+describe("Adaptive quality", () => {
+  it("reports the best tier in headless mode", () => {
+    const q = getQuality();
+    assert.equal(q.tier, 0);
+    assert.equal(q.name, "high");
+    assert.equal(q.enabled, false);
+    assert.equal(q.frameMs, null);
+    assert.equal(q.renderScale, 1);
+  });
+
+  it("is a no-op without the renderer", () => {
+    enableAdaptiveQuality({ budgetMs: 33 });
+    setQualityTier(2);
+    setQualityTier("auto");
+    disableAdaptiveQuality();
+    assert.equal(getQuality().tier, 0);
+  });
+});
@@ -0,0 +1,86 @@
+/**
+ * Adaptive quality scaling. When enabled, the renderer measures GPU frame time
+ * and steps through quality tiers (render scale, GI cascades, post effects) to
+ * stay within a frame budget. Tiers change with hysteresis: a drop needs a run
+ * of slow frames, a raise needs a longer run of fast ones.
+ */
+
+const hasQualityOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_quality === "function";
+
+/** Options for {@link enableAdaptiveQuality}. */
+export type AdaptiveQualityOptions = {
+  /** GPU time budget per frame in milliseconds. Default: 16.67 (60 fps). */
+  budgetMs?: number;
+};
+
+/** Result of {@link getQuality}. */
+export type QualityStatus = {
+  /** Active tier, 0 = best. */
+  tier: number;
+  /** Tier name: "high", "medium", "low" or "minimum". */
+  name: string;
+  /** True when the tier is chosen automatically, false when pinned with {@link setQualityTier}. */
+  auto: boolean;
+  /** Whether adaptive quality is enabled. */
+  enabled: boolean;
+  /** Frame budget in milliseconds. */
+  budgetMs: number;
+  /** Smoothed GPU frame time in milliseconds, or null before any measurement. */
+  frameMs: number | null;
+  /** Fraction of the window resolution the scene is rendered at. */
+  renderScale: number;
+  /** Whether post-processing effects run at this tier. */
+  postEffects: boolean;
+  /** Number of available tiers. */
+  tierCount: number;
+};
+
+function headlessStatus(): QualityStatus {
+  return {
+    tier: 0,
+    name: "high",
+    auto: true,
+    enabled: false,
+    budgetMs: 1000 / 60,
+    frameMs: null,
+    renderScale: 1,
+    postEffects: true,
+    tierCount: 4,
+  };
+}
+
+/**
+ * Let the renderer lower quality automatically when frames exceed the budget.
+ * No-op in headless mode.
+ *
+ * @example
+ * enableAdaptiveQuality({ budgetMs: 1000 / 30 });
+ */
+export function enableAdaptiveQuality(options: AdaptiveQualityOptions = {}): void {
+  if (!hasQualityOps) return;
+  (globalThis as any).Deno.core.ops.op_set_adaptive_quality(true, options.budgetMs ?? 0);
+}
+
+/** Stop adaptive quality and return to the best tier. No-op in headless mode. */
+export function disableAdaptiveQuality(): void {
+  if (!hasQualityOps) return;
+  (globalThis as any).Deno.core.ops.op_set_adaptive_quality(false, 0);
+}
+
+/**
+ * Pin a quality tier (0 = best, clamped to the last tier), or pass `"auto"` to
+ * let the controller choose again. Takes effect while adaptive quality is enabled.
+ */
+export function setQualityTier(tier: number | "auto"): void {
+  if (!hasQualityOps) return;
+  const value = tier === "auto" ? -1 : Math.max(0, Math.floor(tier));
+  (globalThis as any).Deno.core.ops.op_set_quality_tier(value);
+}
+
+/** Current quality tier and measured frame time. Reflects the last rendered frame. */
+export function getQuality(): QualityStatus {
+  if (!hasQualityOps) return headlessStatus();
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_quality());
+}
@@ -0,0 +1,12 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { captureLayers, isScreenRecording, startScreenRecording, stopScreenRecording } from "./recording.ts";
+
+describe("Screen recording", () => {
+  it("is a no-op in headless mode", () => {
+    startScreenRecording("clip.gif", { fps: 25 });
+    assert.equal(isScreenRecording(), false);
+    stopScreenRecording();
+    assert.equal(isScreenRecording(), false);
+    captureLayers("frame.ora");
+  });
+});
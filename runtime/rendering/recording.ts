@@ -0,0 +1,71 @@
+/**
+ * Screen recording to animated GIFs, and layered captures of single frames.
+ *
+ * Frames are sampled on game time, not wall time: a clip recorded at 30 fps
+ * holds one frame per 1/30 s of game time, so it plays back at game speed
+ * even if the window dropped frames, and nothing is captured while paused.
+ * Under `arcane render` (fixed time step) the clip is the same on every run.
+ *
+ * Encoding runs on a background thread; each captured frame costs one GPU
+ * readback on the render thread, so keep the window small for long clips.
+ * All functions are no-ops in headless mode.
+ */
+
+const hasRecordingOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_start_recording === "function";
+
+const hasCaptureOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_capture_layers === "function";
+
+/** Options for {@link startScreenRecording}. */
+export type ScreenRecordingOptions = {
+  /** Frames per second of game time, 1-50 (GIF delays are in 1/100 s). Default: 30. */
+  fps?: number;
+};
+
+/**
+ * Start recording the screen to a GIF. `path` is relative to the game
+ * directory and must end in `.gif`. Starting again finishes the current clip.
+ *
+ * @example
+ * if (isKeyPressed("F9")) {
+ *   if (isScreenRecording()) stopScreenRecording();
+ *   else startScreenRecording("clips/boss.gif", { fps: 25 });
+ * }
+ */
+export function startScreenRecording(path: string, options?: ScreenRecordingOptions): void {
+  if (!hasRecordingOps) return;
+  (globalThis as any).Deno.core.ops.op_start_recording(path, options?.fps ?? 30);
+}
+
+/** Stop recording. The GIF is finished in the background. */
+export function stopScreenRecording(): void {
+  if (!hasRecordingOps) return;
+  (globalThis as any).Deno.core.ops.op_stop_recording();
+}
+
+/** Whether a screen recording is in progress. Always false in headless mode. */
+export function isScreenRecording(): boolean {
+  if (!hasRecordingOps) return false;
+  return (globalThis as any).Deno.core.ops.op_is_recording();
+}
+
+/**
+ * Capture the next frame with every draw layer as its own image, for
+ * debugging draw order or pulling a scene apart for marketing art. `path` is
+ * relative to the game directory: a `.ora` path writes a layered OpenRaster
+ * file (GIMP, Krita), anything else a folder of `layer_<n>.png` files with the
+ * merged frame and a `manifest.json`.
+ *
+ * Layers hold sprites, shapes and SDF draws only; post-processing, lighting
+ * GI, decals and particles show in the merged image alone.
+ *
+ * @example
+ * if (isKeyPressed("F10")) captureLayers("captures/town.ora");
+ */
+export function captureLayers(path: string): void {
+  if (!hasCaptureOp) return;
+  (globalThis as any).Deno.core.ops.op_capture_layers(path);
+}
@@ -4,6 +4,10 @@ import {
   beginRenderTarget,
   endRenderTarget,
   getRenderTargetTextureId,
+  setRenderTargetCamera,
+  clearRenderTargetCamera,
+  addRenderTargetEffect,
+  clearRenderTargetEffects,
   destroyRenderTarget,
 } from "./rendertarget.ts";
 
@@ -24,6 +28,28 @@ describe("Render targets (headless)", () => {
     endRenderTarget();
   });
 
+  it("nested beginRenderTarget calls are no-ops in headless", () => {
+    const outer = createRenderTarget(64, 64);
+    const inner = createRenderTarget(32, 32);
+    beginRenderTarget(outer);
+    beginRenderTarget(inner);
+    endRenderTarget();
+    endRenderTarget();
+  });
+
+  it("setRenderTargetCamera / clearRenderTargetCamera are no-ops in headless", () => {
+    const id = createRenderTarget(64, 64);
+    setRenderTargetCamera(id, { x: 100, y: 50, zoom: 2, rotation: 0.5, layers: [0, 20], captureScene: true });
+    setRenderTargetCamera(id, {});
+    clearRenderTargetCamera(id);
+  });
+
+  it("render target effects are no-ops in headless", () => {
+    const id = createRenderTarget(64, 64);
+    assert.equal(addRenderTargetEffect(id, "blur"), 0);
+    clearRenderTargetEffects(id);
+  });
+
   it("destroyRenderTarget is a no-op in headless", () => {
     const id = createRenderTarget(64, 64);
     destroyRenderTarget(id);
@@ -1,4 +1,5 @@
 import type { TextureId } from "./types.ts";
+import type { EffectId } from "./postprocess.ts";
 
 const hasRenderTargetOps =
   typeof (globalThis as any).Deno !== "undefined" &&
@@ -45,13 +46,17 @@ export function createRenderTarget(width: number, height: number): RenderTargetI
 }
 
 /**
- * Route subsequent `drawSprite()` calls into this render target.
+ * Route subsequent `drawSprite()` calls, shapes (`drawCircle()`, `drawLine()`, ...)
+ * and SDF shapes into this render target.
  *
  * Coordinate system inside the target: `(0, 0)` = top-left corner.
- * Unlike the main surface, the camera transform is not applied inside a render target.
+ * Unlike the main surface, the camera transform is not applied inside a render
+ * target; give it its own with {@link setRenderTargetCamera}.
  *
  * Calls must be balanced: every `beginRenderTarget()` needs a matching `endRenderTarget()`.
- * Nesting is not supported.
+ * Calls nest: beginning another target inside one draws into the inner target
+ * until its `endRenderTarget()`, then resumes the outer one. A target that
+ * samples another target is rendered after it, so it shows this frame's contents.
  *
  * @param id Handle returned by `createRenderTarget()`
  *
@@ -59,6 +64,15 @@ export function createRenderTarget(width: number, height: number): RenderTargetI
  * beginRenderTarget(myTarget);
  *   drawSprite({ textureId: tex, x: 0, y: 0, w: 64, h: 64 });
  * endRenderTarget();
+ *
+ * @example
+ * // A minimap shown on an in-world CRT screen
+ * beginRenderTarget(crt);
+ *   beginRenderTarget(minimap);
+ *     drawCircle(player.x / 16, player.y / 16, 3, { color: { r: 0, g: 1, b: 0, a: 1 } });
+ *   endRenderTarget();
+ *   drawSprite({ textureId: minimap, x: 16, y: 16, w: 128, h: 128 });
+ * endRenderTarget();
  */
 export function beginRenderTarget(id: RenderTargetId): void {
   if (!hasRenderTargetOps) return;
@@ -66,7 +80,8 @@ export function beginRenderTarget(id: RenderTargetId): void {
 }
 
 /**
- * End the current render target pass and return to rendering on the main surface.
+ * End the current render target pass and return to rendering on the enclosing
+ * render target, or the main surface.
  *
  * @example
  * beginRenderTarget(myTarget);
@@ -96,6 +111,98 @@ export function getRenderTargetTextureId(id: RenderTargetId): TextureId {
   return id; // RenderTargetId IS the TextureId
 }
 
+/** Camera for {@link setRenderTargetCamera}. Every field is optional. */
+export type RenderTargetCamera = {
+  /** World position of the target's top-left corner. Default: 0. */
+  x?: number;
+  /** Default: 0. */
+  y?: number;
+  /** Larger values zoom in. Default: 1. */
+  zoom?: number;
+  /** Roll in radians around the target's center; positive turns the camera clockwise. Default: 0. */
+  rotation?: number;
+  /** Inclusive layer range drawn into the target, e.g. `[0, 20]` to skip HUD layers. Default: all layers. */
+  layers?: [number, number];
+  /** Also draw the main scene's sprites and shapes into the target (mirrors, portals, picture-in-picture). Default: false. */
+  captureScene?: boolean;
+};
+
+/**
+ * Set the camera a render target is drawn with. By default (0, 0) is the
+ * target's top-left and only what is drawn between `beginRenderTarget()` and
+ * `endRenderTarget()` appears. With `captureScene`, the main scene's sprites,
+ * shapes and SDF shapes are re-rendered through this camera every frame
+ * without drawing them twice (anything using the target's own texture is
+ * skipped). The camera persists until cleared or the target is destroyed.
+ *
+ * No-op in headless mode.
+ *
+ * @param id Handle returned by `createRenderTarget()`
+ * @param camera Position, zoom, rotation, layer filter and scene capture
+ *
+ * @example
+ * // Security-camera screen: the vault, at half zoom, without the HUD
+ * const cam = createRenderTarget(200, 150);
+ * setRenderTargetCamera(cam, { x: vault.x, y: vault.y, zoom: 0.5, layers: [0, 30], captureScene: true });
+ * onFrame(() => {
+ *   drawSprite({ textureId: cam, x: 580, y: 20, w: 200, h: 150, layer: 100, screenSpace: true });
+ * });
+ */
+export function setRenderTargetCamera(id: RenderTargetId, camera: RenderTargetCamera): void {
+  if (!hasRenderTargetOps) return;
+  const [min, max] = camera.layers ?? [-2147483648, 2147483647];
+  (globalThis as any).Deno.core.ops.op_set_render_target_camera(
+    id >>> 0,
+    camera.x ?? 0,
+    camera.y ?? 0,
+    camera.zoom ?? 1,
+    camera.rotation ?? 0,
+    min | 0,
+    max | 0,
+    camera.captureScene ?? false,
+  );
+}
+
+/**
+ * Go back to the default camera: (0, 0) at the top-left, all layers, own sprites only.
+ * No-op in headless mode.
+ */
+export function clearRenderTargetCamera(id: RenderTargetId): void {
+  if (!hasRenderTargetOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_render_target_camera(id >>> 0);
+}
+
+/**
+ * Append a post-process effect to a render target. The target's effects run,
+ * in the order added, each time it is drawn into, before it is sampled as a
+ * texture. The returned ID works with `setEffectParam()` and `removeEffect()`;
+ * `clearEffects()` removes target effects too. Returns 0 in headless mode.
+ *
+ * @param id Handle returned by `createRenderTarget()` or `createMinimap()`.
+ * @param effect Built-in effect type (see `addPostProcessEffect()`).
+ *
+ * @example
+ * // Soft, dim portal view
+ * const blur = addRenderTargetEffect(portal, "blur");
+ * setEffectParam(blur, 0, 2.0);
+ * addRenderTargetEffect(portal, "vignette");
+ */
+export function addRenderTargetEffect(
+  id: RenderTargetId,
+  effect: "bloom" | "blur" | "vignette" | "crt",
+): EffectId {
+  if (!hasRenderTargetOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_add_render_target_effect(id >>> 0, effect);
+}
+
+/**
+ * Remove every effect from a render target. No-op in headless mode.
+ */
+export function clearRenderTargetEffects(id: RenderTargetId): void {
+  if (!hasRenderTargetOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_render_target_effects(id >>> 0);
+}
+
 /**
  * Free the GPU resources for a render target.
  * After this call, using the ID as a TextureId will produce a transparent sprite.
@@ -0,0 +1,21 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  createRetainedSprite,
+  updateRetainedSprite,
+  moveRetainedSprites,
+  setRetainedSpriteVisible,
+  destroyRetainedSprite,
+  getRetainedSpriteCount,
+} from "./retained-sprites.ts";
+
+describe("Retained sprites", () => {
+  it("is inert in headless mode", () => {
+    const id = createRetainedSprite({ textureId: 1, x: 10, y: 20, w: 16, h: 16, origin: "bottom-center" });
+    assert.equal(id, 0);
+    updateRetainedSprite(id, { x: 12, tint: { r: 1, g: 0, b: 0, a: 1 } });
+    moveRetainedSprites([{ id, x: 1, y: 2 }, { id: 99, rotation: 0.5 }]);
+    setRetainedSpriteVisible(id, false);
+    destroyRetainedSprite(id);
+    assert.equal(getRetainedSpriteCount(), 0);
+  });
+});
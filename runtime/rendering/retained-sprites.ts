@@ -0,0 +1,174 @@
+/**
+ * Retained sprites: create a sprite once and the engine draws it every frame
+ * until it is destroyed.
+ *
+ * drawSprite() sends a sprite's whole record to Rust every frame, which
+ * dominates frame time in games with thousands of entities. Retained sprites
+ * live in Rust instead; afterwards only what changes crosses the bridge,
+ * usually position and rotation via {@link moveRetainedSprites}, one op call
+ * for all of them. They are drawn in world space on the main surface, in
+ * creation order within their layer, and ignore sprite nodes, clip rects,
+ * masks and render targets.
+ *
+ * In headless mode every function is a no-op and {@link createRetainedSprite}
+ * returns 0.
+ */
+
+import type { SpriteOptions, TextureId } from "./types.ts";
+import { _resolveOrigin } from "./sprites.ts";
+
+const hasRetainedOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_sprite_create === "function";
+
+/** Handle to a retained sprite. 0 means "no sprite" (headless mode). */
+export type RetainedSpriteId = number;
+
+/** Options for {@link createRetainedSprite}: the drawSprite() options that describe a single sprite. */
+export type RetainedSpriteOptions = Pick<
+  SpriteOptions,
+  | "x" | "y" | "w" | "h" | "layer" | "uv" | "tint" | "rotation"
+  | "origin" | "originX" | "originY" | "flipX" | "flipY" | "opacity" | "blendMode" | "shaderId"
+> & {
+  /** Texture handle from loadTexture() or createSolidTexture(). */
+  textureId: TextureId;
+};
+
+/** One entry for {@link moveRetainedSprites}. Omitted fields keep their value. */
+export type RetainedSpriteMove = {
+  id: RetainedSpriteId;
+  x?: number;
+  y?: number;
+  rotation?: number;
+};
+
+// One sprite record, laid out as in sprites.ts
+const SPRITE_STRIDE = 23;
+const _record = new Float32Array(SPRITE_STRIDE);
+
+// [id as u32 bits, x, y, rotation] per sprite, NaN = unchanged
+const SPRITE_UPDATE_STRIDE = 4;
+let _moveBuffer = new Float32Array(256 * SPRITE_UPDATE_STRIDE);
+
+// Last options sent per sprite, so updates can change single fields
+const _sprites = new Map<RetainedSpriteId, RetainedSpriteOptions>();
+
+const blendModeMap: Record<string, number> = {
+  alpha: 0,
+  additive: 1,
+  multiply: 2,
+  screen: 3,
+};
+
+function packRecord(opts: RetainedSpriteOptions): Uint8Array {
+  const uv = opts.uv ?? { x: 0, y: 0, w: 1, h: 1 };
+  const tint = opts.tint ?? { r: 1, g: 1, b: 1, a: 1 };
+  const [originX, originY] = _resolveOrigin(opts);
+  const view = new DataView(_record.buffer);
+  view.setUint32(0, opts.textureId, true);
+  _record[1] = opts.x;
+  _record[2] = opts.y;
+  _record[3] = opts.w;
+  _record[4] = opts.h;
+  view.setInt32(5 * 4, opts.layer ?? 0, true);
+  _record.set([
+    uv.x, uv.y, uv.w, uv.h,
+    tint.r, tint.g, tint.b, tint.a,
+    opts.rotation ?? 0,
+    originX,
+    originY,
+    opts.flipX ? 1 : 0,
+    opts.flipY ? 1 : 0,
+    opts.opacity ?? 1,
+    blendModeMap[opts.blendMode ?? "alpha"] ?? 0,
+  ], 6);
+  view.setUint32(21 * 4, opts.shaderId ?? 0, true);
+  view.setUint32(22 * 4, 0, true);
+  return new Uint8Array(_record.buffer);
+}
+
+/**
+ * Create a sprite the engine draws every frame until
+ * {@link destroyRetainedSprite}. Call it once, not every frame.
+ *
+ * @returns Sprite handle, or 0 in headless mode.
+ *
+ * @example
+ * const id = createRetainedSprite({ textureId: orcTex, x: orc.x, y: orc.y, w: 16, h: 16, layer: 5 });
+ * // Each frame, only positions cross the bridge
+ * moveRetainedSprites(orcs.map((o) => ({ id: o.sprite, x: o.x, y: o.y })));
+ */
+export function createRetainedSprite(opts: RetainedSpriteOptions): RetainedSpriteId {
+  if (!hasRetainedOps) return 0;
+  const id: RetainedSpriteId = (globalThis as any).Deno.core.ops.op_sprite_create(packRecord(opts));
+  if (id !== 0) _sprites.set(id, { ...opts });
+  return id;
+}
+
+/**
+ * Change some of a retained sprite's options. Changing only x, y and
+ * rotation is the cheap path; any other field re-sends the whole sprite.
+ * Unknown sprites are ignored.
+ */
+export function updateRetainedSprite(id: RetainedSpriteId, changes: Partial<RetainedSpriteOptions>): void {
+  if (!hasRetainedOps) return;
+  const current = _sprites.get(id);
+  if (!current) return;
+  Object.assign(current, changes);
+  const transformOnly = Object.keys(changes).every((k) => k === "x" || k === "y" || k === "rotation");
+  if (transformOnly) {
+    (globalThis as any).Deno.core.ops.op_sprite_update(id, changes.x ?? NaN, changes.y ?? NaN, changes.rotation ?? NaN);
+  } else {
+    (globalThis as any).Deno.core.ops.op_sprite_set(id, packRecord(current));
+  }
+}
+
+/**
+ * Move and rotate many retained sprites in one op call. Omitted fields keep
+ * their value; unknown sprites are skipped.
+ *
+ * @example
+ * moveRetainedSprites(bullets.map((b) => ({ id: b.sprite, x: b.x, y: b.y, rotation: b.angle })));
+ */
+export function moveRetainedSprites(moves: readonly RetainedSpriteMove[]): void {
+  if (!hasRetainedOps || moves.length === 0) return;
+  const needed = moves.length * SPRITE_UPDATE_STRIDE;
+  if (_moveBuffer.length < needed) {
+    _moveBuffer = new Float32Array(Math.max(needed, _moveBuffer.length * 2));
+  }
+  const view = new DataView(_moveBuffer.buffer);
+  for (let i = 0; i < moves.length; i++) {
+    const m = moves[i];
+    const base = i * SPRITE_UPDATE_STRIDE;
+    view.setUint32(base * 4, m.id >>> 0, true); // sprite id as u32 bits in f32 slot
+    _moveBuffer[base + 1] = m.x ?? NaN;
+    _moveBuffer[base + 2] = m.y ?? NaN;
+    _moveBuffer[base + 3] = m.rotation ?? NaN;
+    const current = _sprites.get(m.id);
+    if (current) {
+      if (m.x !== undefined) current.x = m.x;
+      if (m.y !== undefined) current.y = m.y;
+      if (m.rotation !== undefined) current.rotation = m.rotation;
+    }
+  }
+  (globalThis as any).Deno.core.ops.op_sprite_update_bulk(new Uint8Array(_moveBuffer.buffer, 0, needed * 4));
+}
+
+/** Hide a retained sprite without destroying it, or show it again. */
+export function setRetainedSpriteVisible(id: RetainedSpriteId, visible: boolean): void {
+  if (!hasRetainedOps) return;
+  (globalThis as any).Deno.core.ops.op_sprite_set_visible(id, visible);
+}
+
+/** Stop drawing a retained sprite. The handle is not reused. */
+export function destroyRetainedSprite(id: RetainedSpriteId): void {
+  if (!hasRetainedOps) return;
+  _sprites.delete(id);
+  (globalThis as any).Deno.core.ops.op_sprite_destroy(id);
+}
+
+/** Number of retained sprites, visible or hidden, or 0 in headless mode. */
+export function getRetainedSpriteCount(): number {
+  if (!hasRetainedOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_sprite_count();
+}
@@ -0,0 +1,12 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { setRuntimeAtlas, getAtlasStats } from "./runtime-atlas.ts";
+
+describe("Runtime atlas", () => {
+  it("is off and a no-op in headless mode", () => {
+    setRuntimeAtlas(true);
+    const stats = getAtlasStats();
+    assert.equal(stats.enabled, false);
+    assert.equal(stats.pages, 0);
+    assert.equal(stats.occupancy, 0);
+  });
+});
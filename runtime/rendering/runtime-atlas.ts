@@ -0,0 +1,55 @@
+/**
+ * Runtime texture atlas.
+ *
+ * Small textures (up to 256 pixels a side) are copied into shared 2048×2048
+ * pages on the GPU, and sprites drawn from them are redirected to their page,
+ * so sprites from different images batch into one draw call. Texture IDs and
+ * UVs don't change for the game. Hot-reloaded textures leave holes in the
+ * pages; once they waste more than a quarter of the packed area, the atlas
+ * repacks itself during idle frames. Sprites with custom shaders, nine-slice
+ * borders, outlines or normal maps keep drawing from their own texture.
+ * All functions are no-ops in headless mode.
+ */
+
+const hasAtlasOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_runtime_atlas === "function";
+
+/** Result of {@link getAtlasStats}. */
+export type AtlasStats = {
+  /** Whether the runtime atlas is on. */
+  enabled: boolean;
+  /** Atlas pages in use. */
+  pages: number;
+  /** Textures packed into the pages. */
+  textures: number;
+  /** Share of the pages' pixels in use, 0-1. */
+  occupancy: number;
+  /** Share of the packed area left as holes by replaced textures, 0-1. */
+  waste: number;
+  /** Repacks (defragmentations) since startup. */
+  defragmentations: number;
+};
+
+/**
+ * Pack small textures into shared atlas pages so sprites batch across
+ * images. Off by default. Turning it off drops the pages.
+ *
+ * @example
+ * setRuntimeAtlas(true); // many small sprite PNGs → fewer draw calls
+ */
+export function setRuntimeAtlas(enabled: boolean): void {
+  if (!hasAtlasOps) return;
+  (globalThis as any).Deno.core.ops.op_set_runtime_atlas(enabled);
+}
+
+/**
+ * Runtime atlas pages, packed textures and occupancy, as of the last frame.
+ * Disabled and all zeros in headless mode.
+ */
+export function getAtlasStats(): AtlasStats {
+  if (!hasAtlasOps) {
+    return { enabled: false, pages: 0, textures: 0, occupancy: 0, waste: 0, defragmentations: 0 };
+  }
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_atlas_stats());
+}
@@ -0,0 +1,27 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  playTransition,
+  cancelTransition,
+  getTransitionCoverage,
+  createTransitionShader,
+  onTransitionEvent,
+  _dispatchTransitionEvents,
+} from "./screen-transition.ts";
+
+describe("Engine screen transitions", () => {
+  it("is inert in headless mode", () => {
+    let midpoints = 0;
+    const id = playTransition("circle", 0.5, { onMidpoint: () => midpoints++ });
+    assert.equal(id, 0);
+    assert.equal(createTransitionShader("@fragment fn fs_main() {}"), 0);
+    assert.equal(getTransitionCoverage(), 0);
+
+    let events = 0;
+    const off = onTransitionEvent(() => events++);
+    _dispatchTransitionEvents();
+    cancelTransition();
+    off();
+    assert.equal(events, 0);
+    assert.equal(midpoints, 0);
+  });
+});
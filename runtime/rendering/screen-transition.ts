@@ -0,0 +1,175 @@
+/**
+ * Screen transitions drawn by the engine.
+ *
+ * Unlike {@link startScreenTransition} in `transition.ts`, which draws sprites
+ * on a layer, these run as the last post-process pass: they cover everything,
+ * UI and post effects included, and the pixelate look really pixelates the
+ * frame. The engine advances them with the frame's delta time and reports the
+ * midpoint (screen fully covered, the moment to swap scenes) and completion
+ * through {@link onTransitionEvent} or the per-transition callbacks.
+ *
+ * Only one transition runs at a time; starting another replaces it.
+ * In headless mode every function is a no-op and {@link playTransition} returns 0.
+ *
+ * @example
+ * playTransition("circle", 0.8, {
+ *   center: { x: player.screenX, y: player.screenY },
+ *   onMidpoint: () => replaceScene(nextLevel),
+ * });
+ */
+
+import { getViewportSize } from "./input.ts";
+
+const hasTransitionOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_start_transition === "function";
+
+/** Handle to an engine transition. 0 = none (headless mode or unknown kind). */
+export type TransitionId = number;
+
+/** Handle to a custom transition shader. */
+export type TransitionShaderId = number;
+
+/**
+ * How the transition covers the screen.
+ * - **fade** — blend to the color
+ * - **circle** — a circle around `center` closes, leaving the color outside
+ * - **pixelate** — blocks grow to `blockSize`, then the color takes over
+ * - **custom** — a shader from {@link createTransitionShader}
+ */
+export type TransitionKind = "fade" | "circle" | "pixelate" | "custom";
+
+/** Options for {@link playTransition}. */
+export type TransitionOptions = {
+  /** Cover color, 0-1 per channel. Default: opaque black. */
+  color?: { r: number; g: number; b: number; a?: number };
+  /** "circle": screen point the circle closes on, in pixels. Default: viewport center. */
+  center?: { x: number; y: number };
+  /** "pixelate": block size at full cover, in render target pixels. Default: 32. */
+  blockSize?: number;
+  /** "custom": the shader to draw with. */
+  shader?: TransitionShaderId;
+  /** "custom": values passed to the shader as `params.values[1]`. */
+  params?: [number, number, number, number];
+  /** Called when the screen is fully covered. */
+  onMidpoint?: () => void;
+  /** Called when the transition has finished. */
+  onComplete?: () => void;
+};
+
+/** A midpoint or completion, passed to {@link onTransitionEvent} listeners. */
+export type TransitionEvent = {
+  /** The transition that produced the event. */
+  id: TransitionId;
+  /** "midpoint": the screen is fully covered. "complete": the transition ended. */
+  event: "midpoint" | "complete";
+};
+
+const listeners: Array<(event: TransitionEvent) => void> = [];
+const callbacks = new Map<TransitionId, Pick<TransitionOptions, "onMidpoint" | "onComplete">>();
+
+/**
+ * Start a screen transition, replacing any running one. The screen is fully
+ * covered at half of `duration`; the frame that reports the midpoint is drawn
+ * fully covered, so swapping scenes there is never visible.
+ *
+ * @param kind - How the transition covers the screen.
+ * @param duration - Total duration in seconds (cover, then uncover).
+ * @param options - Color, kind-specific settings and callbacks.
+ * @returns The transition ID, or 0 in headless mode or for an unknown kind.
+ */
+export function playTransition(
+  kind: TransitionKind,
+  duration: number,
+  options: TransitionOptions = {},
+): TransitionId {
+  if (!hasTransitionOps) return 0;
+  const color = options.color ?? { r: 0, g: 0, b: 0 };
+  let values: [number, number, number, number] = [0, 0, 0, 0];
+  if (kind === "circle") {
+    const { width, height } = getViewportSize();
+    const center = options.center ?? { x: width / 2, y: height / 2 };
+    values = [center.x / width, center.y / height, 0, 0];
+  } else if (kind === "pixelate") {
+    values = [options.blockSize ?? 32, 0, 0, 0];
+  } else if (kind === "custom") {
+    values = options.params ?? values;
+  }
+  const id: TransitionId = (globalThis as any).Deno.core.ops.op_start_transition(
+    kind,
+    duration,
+    options.shader ?? 0,
+    color.r,
+    color.g,
+    color.b,
+    color.a ?? 1,
+    ...values,
+  );
+  callbacks.clear();
+  if (id !== 0 && (options.onMidpoint || options.onComplete)) {
+    callbacks.set(id, { onMidpoint: options.onMidpoint, onComplete: options.onComplete });
+  }
+  return id;
+}
+
+/** Stop the running transition immediately. Its remaining callbacks and events are dropped. */
+export function cancelTransition(): void {
+  if (!hasTransitionOps) return;
+  (globalThis as any).Deno.core.ops.op_cancel_transition();
+  callbacks.clear();
+}
+
+/** How much of the screen the running transition covers (0-1). 0 when idle or in headless mode. */
+export function getTransitionCoverage(): number {
+  if (!hasTransitionOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_get_transition_coverage();
+}
+
+/**
+ * Register a custom transition shader. `source` is WGSL defining
+ * `fs_main(in: VertexOutput) -> @location(0) vec4<f32>`, with the frame in
+ * `t_input`/`s_input` and these uniforms:
+ * - `params.resolution.xy` — render target size in pixels
+ * - `params.resolution.z` — coverage: 0 → 1 at the midpoint → 0
+ * - `params.resolution.w` — progress: 0 → 1 over the whole transition
+ * - `params.values[0]` — cover color (RGBA)
+ * - `params.values[1]` — `TransitionOptions.params`
+ *
+ * Cover the whole screen when coverage is 1, or the midpoint swap shows.
+ *
+ * @returns Shader ID for `playTransition("custom", ..., { shader })`, or 0 in headless mode.
+ */
+export function createTransitionShader(source: string): TransitionShaderId {
+  if (!hasTransitionOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_transition_shader(source);
+}
+
+/**
+ * Listen for transition midpoints and completions. Listeners run at the start
+ * of the frame in which the event happened, before the {@link onFrame} callback.
+ *
+ * @returns A function that removes the listener.
+ */
+export function onTransitionEvent(listener: (event: TransitionEvent) => void): () => void {
+  listeners.push(listener);
+  return () => {
+    const index = listeners.indexOf(listener);
+    if (index >= 0) listeners.splice(index, 1);
+  };
+}
+
+/** Deliver this frame's transition events to callbacks and listeners. Called by the frame loop. @internal */
+export function _dispatchTransitionEvents(): void {
+  if (!hasTransitionOps) return;
+  const events: TransitionEvent[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_transition_events());
+  for (const event of events) {
+    const entry = callbacks.get(event.id);
+    if (event.event === "midpoint") {
+      entry?.onMidpoint?.();
+    } else {
+      callbacks.delete(event.id);
+      entry?.onComplete?.();
+    }
+    for (const listener of [...listeners]) listener(event);
+  }
+}
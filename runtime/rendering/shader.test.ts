@@ -1,6 +1,7 @@
 import { describe, it, assert } from "../testing/harness.ts";
 import {
   createShaderFromSource,
+  createShaderFromFile,
   setShaderParam,
   createShader,
   setShaderUniform,
@@ -26,6 +27,14 @@ describe("Shader", () => {
     });
   });
 
+  describe("createShaderFromFile", () => {
+    it("registers uniform names", () => {
+      const id = createShaderFromFile("file", "shaders/water.wgsl", { speed: "float" });
+      assert.equal(typeof id, "number");
+      assert.equal(getShaderUniformNames(id).length, 1);
+    });
+  });
+
   describe("createShader (named uniforms)", () => {
     it("returns a numeric ShaderId", () => {
       const id = createShader(
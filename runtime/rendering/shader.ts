@@ -82,6 +82,33 @@ export function createShaderFromSource(
   return (globalThis as any).Deno.core.ops.op_create_shader(name, wgslSource);
 }
 
+/**
+ * Create a custom fragment shader from a `.wgsl` file (same rules as
+ * {@link createShaderFromSource}). Paths are relative to the game entry file;
+ * loading the same path again returns the same ID. Under `arcane dev`, saving
+ * the file recompiles the shader in place, keeping its ID and param values.
+ *
+ * @param name - Shader name (for debugging).
+ * @param path - Path to the WGSL fragment shader file.
+ * @param uniforms - Optional named uniform definitions, as in {@link createShader}.
+ * @returns ShaderId for use in {@link drawSprite}'s `shaderId` option.
+ *
+ * @example
+ * const water = createShaderFromFile("water", "shaders/water.wgsl", { speed: "float" });
+ * setShaderUniform(water, "speed", 2.0);
+ */
+export function createShaderFromFile(
+  name: string,
+  path: string,
+  uniforms?: UniformDef,
+): ShaderId {
+  const id = hasRenderOps
+    ? (globalThis as any).Deno.core.ops.op_create_shader_from_file(name, path)
+    : headlessIdCounter++;
+  if (uniforms) registerUniforms(id, uniforms);
+  return id;
+}
+
 /**
  * Set a vec4 parameter slot on a custom shader.
  * Values are accessible in the shader as `shader_params.values[index]`.
@@ -134,19 +161,21 @@ export function createShader(
   uniforms?: UniformDef,
 ): ShaderId {
   const id = createShaderFromSource(name, source);
-  if (uniforms) {
-    const map = new Map<string, { slot: number; type: UniformType }>();
-    let slot = 0;
-    for (const [uname, utype] of Object.entries(uniforms)) {
-      if (slot >= MAX_UNIFORM_SLOTS) break;
-      map.set(uname, { slot, type: utype });
-      slot++;
-    }
-    uniformRegistry.set(id, map);
-  }
+  if (uniforms) registerUniforms(id, uniforms);
   return id;
 }
 
+function registerUniforms(id: ShaderId, uniforms: UniformDef): void {
+  const map = new Map<string, { slot: number; type: UniformType }>();
+  let slot = 0;
+  for (const [uname, utype] of Object.entries(uniforms)) {
+    if (slot >= MAX_UNIFORM_SLOTS) break;
+    map.set(uname, { slot, type: utype });
+    slot++;
+  }
+  uniformRegistry.set(id, map);
+}
+
 /**
  * Set a named uniform on a custom shader.
  * The uniform name must match one declared in {@link createShader}'s `uniforms` parameter.
@@ -0,0 +1,53 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  loadSkeleton,
+  createSkeleton,
+  playSkeletonAnimation,
+  updateSkeleton,
+  getSkeletonBone,
+  getSkeletonMeshes,
+  destroySkeleton,
+} from "./skeleton.ts";
+
+const hasSkeletonOps =
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_skeleton === "function";
+
+const SPINE = JSON.stringify({
+  bones: [{ name: "root" }, { name: "arm", parent: "root", x: 10 }],
+  slots: [],
+  animations: {
+    lift: { bones: { arm: { translate: [{ time: 0, x: 0, y: 0 }, { time: 1, x: 0, y: 20 }] } } },
+  },
+});
+
+describe("Skeletal animation", () => {
+  it("loadSkeleton returns null in headless mode, metadata otherwise", () => {
+    const data = loadSkeleton(SPINE);
+    if (!hasSkeletonOps) {
+      assert.equal(data, null);
+      return;
+    }
+    assert.deepEqual(data!.bones, ["root", "arm"]);
+    assert.equal(data!.animations[0].name, "lift");
+    assert.equal(data!.animations[0].duration, 1);
+  });
+
+  it("bone positions follow the playing animation (y-down)", () => {
+    const data = loadSkeleton(SPINE);
+    if (!data) return;
+    const sk = createSkeleton(data.id);
+    assert.ok(playSkeletonAnimation(sk, "lift", { loop: false }));
+    assert.equal(playSkeletonAnimation(sk, "missing"), false);
+    updateSkeleton(sk, 1);
+    const arm = getSkeletonBone(sk, "arm")!;
+    assert.ok(Math.abs(arm.x - 10) < 1e-4);
+    assert.ok(Math.abs(arm.y + 20) < 1e-4);
+    assert.equal(getSkeletonBone(sk, "nope"), null);
+    destroySkeleton(sk);
+  });
+
+  it("headless-safe defaults", () => {
+    assert.equal(typeof createSkeleton(1), "number");
+    assert.ok(Array.isArray(getSkeletonMeshes(0)));
+  });
+});
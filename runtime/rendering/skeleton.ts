@@ -0,0 +1,266 @@
+/**
+ * Skeletal 2D animation (Spine JSON), evaluated in Rust.
+ *
+ * Load skeleton data once, map attachment image paths to textures, then
+ * create any number of instances that play, layer and crossfade animations.
+ * Region attachments are drawn as sprites and mesh attachments as textured
+ * geometry; bone positions can be queried to attach other sprites (weapons,
+ * particles) to the rig.
+ */
+import type { TextureId } from "./types.ts";
+import { _flushSpriteBatch } from "./sprites.ts";
+
+const hasSkeletonOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_skeleton === "function";
+
+/** Handle to a skeleton instance. 0 = invalid / headless. */
+export type SkeletonId = number;
+
+/** Metadata returned by {@link loadSkeleton}. */
+export type SkeletonDataInfo = {
+  /** Handle for {@link createSkeleton} and {@link setSkeletonRegion}. */
+  id: number;
+  bones: string[];
+  slots: string[];
+  skins: string[];
+  animations: { name: string; duration: number }[];
+};
+
+/** World-space bone transform (screen coordinates, radians). */
+export type BoneTransform = {
+  x: number;
+  y: number;
+  rotation: number;
+  scaleX: number;
+  scaleY: number;
+};
+
+/** Options for {@link playSkeletonAnimation}. */
+export type PlaySkeletonOptions = {
+  /** Track index, 0-15. Higher tracks layer over lower ones. Default: 0. */
+  track?: number;
+  /** Loop the animation. Default: true. */
+  loop?: boolean;
+  /** Crossfade duration from the track's previous animation, in seconds. Default: 0. */
+  mix?: number;
+};
+
+/** Posed mesh attachment returned by {@link getSkeletonMeshes}. */
+export type SkeletonMesh = {
+  /** World-space [x0, y0, x1, y1, ...]. */
+  vertices: number[];
+  /** [u0, v0, u1, v1, ...] within the attachment's region. */
+  uvs: number[];
+  /** Triangle indices into the vertex list. */
+  indices: number[];
+  color: { r: number; g: number; b: number; a: number };
+};
+
+/**
+ * Load a Spine JSON export (3.7 – 4.x). Accepts a path relative to the game
+ * directory or a raw JSON string.
+ *
+ * Returns null in headless mode. Throws if the data is invalid.
+ *
+ * @param jsonOrPath Path to a `.json` export, or the JSON text itself.
+ *
+ * @example
+ * const hero = loadSkeleton("assets/hero.json");
+ * for (const name of ["head", "torso", "arm"]) {
+ *   setSkeletonRegion(hero!.id, name, loadTexture(`assets/hero/${name}.png`));
+ * }
+ */
+export function loadSkeleton(jsonOrPath: string): SkeletonDataInfo | null {
+  if (!hasSkeletonOps) return null;
+  const raw = JSON.parse((globalThis as any).Deno.core.ops.op_load_skeleton(jsonOrPath));
+  if (raw.error) throw new Error(`loadSkeleton: ${raw.error}`);
+  return {
+    id: raw.dataId,
+    bones: raw.bones,
+    slots: raw.slots,
+    skins: raw.skins,
+    animations: raw.animations,
+  };
+}
+
+/**
+ * Map an attachment image path to a texture. Attachments without a texture
+ * are not drawn. Use `uv` when several attachments share one atlas texture.
+ *
+ * @param dataId    `id` from {@link loadSkeleton}.
+ * @param path      Attachment path (the image name in Spine).
+ * @param textureId Texture to draw the attachment with.
+ * @param uv        Optional sub-rectangle of the texture (0-1).
+ */
+export function setSkeletonRegion(
+  dataId: number,
+  path: string,
+  textureId: TextureId,
+  uv?: { x: number; y: number; w: number; h: number },
+): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_set_skeleton_region(
+    dataId >>> 0,
+    path,
+    textureId >>> 0,
+    uv?.x ?? 0,
+    uv?.y ?? 0,
+    uv?.w ?? 0,
+    uv?.h ?? 0,
+  );
+}
+
+/**
+ * Create a skeleton instance in its setup pose.
+ * Returns 0 in headless mode or for unknown data.
+ *
+ * @param dataId `id` from {@link loadSkeleton}.
+ */
+export function createSkeleton(dataId: number): SkeletonId {
+  if (!hasSkeletonOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_skeleton(dataId >>> 0);
+}
+
+/** Destroy a skeleton instance. */
+export function destroySkeleton(id: SkeletonId): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_destroy_skeleton(id >>> 0);
+}
+
+/**
+ * Position and scale the skeleton root. Negative `scaleX` mirrors the rig.
+ *
+ * @example
+ * setSkeletonTransform(hero, player.x, player.y, facingLeft ? -1 : 1);
+ */
+export function setSkeletonTransform(
+  id: SkeletonId,
+  x: number,
+  y: number,
+  scaleX: number = 1,
+  scaleY: number = 1,
+): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_set_skeleton_transform(id >>> 0, x, y, scaleX, scaleY);
+}
+
+/**
+ * Switch the active skin. Attachments missing from the skin fall back to "default".
+ * Returns false if the skin does not exist (always false in headless mode).
+ */
+export function setSkeletonSkin(id: SkeletonId, skin: string): boolean {
+  if (!hasSkeletonOps) return false;
+  return (globalThis as any).Deno.core.ops.op_set_skeleton_skin(id >>> 0, skin);
+}
+
+/**
+ * Play an animation, optionally crossfading from the track's current one.
+ * Returns false if the animation does not exist or the track is past 15
+ * (always false in headless mode).
+ *
+ * @example
+ * // Base locomotion on track 0, aiming layered on track 1
+ * playSkeletonAnimation(hero, "run", { mix: 0.2 });
+ * playSkeletonAnimation(hero, "aim", { track: 1 });
+ */
+export function playSkeletonAnimation(
+  id: SkeletonId,
+  animation: string,
+  options: PlaySkeletonOptions = {},
+): boolean {
+  if (!hasSkeletonOps) return false;
+  return (globalThis as any).Deno.core.ops.op_play_skeleton_animation(
+    id >>> 0,
+    (options.track ?? 0) >>> 0,
+    animation,
+    options.loop ?? true,
+    options.mix ?? 0,
+  );
+}
+
+/** Stop whatever is playing on a track. The pose returns to lower tracks / setup pose. */
+export function clearSkeletonTrack(id: SkeletonId, track: number = 0): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_skeleton_track(id >>> 0, track >>> 0);
+}
+
+/**
+ * Set a track's blend weight (0-1) and playback speed multiplier.
+ *
+ * @example
+ * // Fade an overlay in and out without restarting it
+ * setSkeletonTrackMix(hero, 1, aimWeight);
+ */
+export function setSkeletonTrackMix(
+  id: SkeletonId,
+  track: number,
+  alpha: number,
+  speed: number = 1,
+): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_set_skeleton_track_mix(id >>> 0, track >>> 0, alpha, speed);
+}
+
+/** Advance the skeleton's animations by `dt` seconds and recompute its pose. */
+export function updateSkeleton(id: SkeletonId, dt: number): void {
+  if (!hasSkeletonOps) return;
+  (globalThis as any).Deno.core.ops.op_update_skeleton(id >>> 0, dt);
+}
+
+/**
+ * Draw the skeleton this frame: region attachments as sprites, mesh
+ * attachments as textured geometry (sorted with other geometry on the layer).
+ *
+ * @param id    Skeleton instance.
+ * @param layer Draw layer. Default: 0.
+ */
+export function drawSkeleton(id: SkeletonId, layer: number = 0): void {
+  if (!hasSkeletonOps) return;
+  // Keep draw order relative to sprites queued earlier this frame
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_draw_skeleton(id >>> 0, layer | 0);
+}
+
+/**
+ * Get a bone's world transform, e.g. to attach a weapon to a hand.
+ * Returns null in headless mode or for unknown bones.
+ *
+ * @example
+ * const hand = getSkeletonBone(hero, "hand-r");
+ * if (hand) drawSprite({ textureId: sword, x: hand.x - 4, y: hand.y - 16, w: 8, h: 32, rotation: hand.rotation });
+ */
+export function getSkeletonBone(id: SkeletonId, bone: string): BoneTransform | null {
+  if (!hasSkeletonOps) return null;
+  const t: number[] = (globalThis as any).Deno.core.ops.op_get_skeleton_bone(id >>> 0, bone);
+  if (t.length < 5) return null;
+  return { x: t[0], y: t[1], rotation: t[2], scaleX: t[3], scaleY: t[4] };
+}
+
+/**
+ * Get posed, deformed mesh attachments in world space for custom drawing.
+ * Returns an empty array in headless mode.
+ */
+export function getSkeletonMeshes(id: SkeletonId): SkeletonMesh[] {
+  if (!hasSkeletonOps) return [];
+  const d: number[] = (globalThis as any).Deno.core.ops.op_get_skeleton_meshes(id >>> 0);
+  const meshes: SkeletonMesh[] = [];
+  let i = 0;
+  while (i + 6 <= d.length) {
+    const vertexCount = d[i];
+    const indexCount = d[i + 1];
+    const color = { r: d[i + 2], g: d[i + 3], b: d[i + 4], a: d[i + 5] };
+    i += 6;
+    const vertices: number[] = [];
+    const uvs: number[] = [];
+    for (let v = 0; v < vertexCount; v++) {
+      vertices.push(d[i], d[i + 1]);
+      uvs.push(d[i + 2], d[i + 3]);
+      i += 4;
+    }
+    const indices = d.slice(i, i + indexCount);
+    i += indexCount;
+    meshes.push({ vertices, uvs, indices, color });
+  }
+  return meshes;
+}
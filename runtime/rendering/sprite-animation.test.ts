@@ -0,0 +1,37 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  sheetFrames,
+  createSpriteAnimation,
+  drawAnimated,
+  getSpriteAnimationFrame,
+  onSpriteAnimationEvent,
+  _dispatchSpriteAnimationEvents,
+} from "./sprite-animation.ts";
+
+describe("Sprite animations", () => {
+  it("sheetFrames reads a grid left to right, top to bottom", () => {
+    const frames = sheetFrames(4, 2);
+    assert.equal(frames.length, 8);
+    assert.deepEqual(frames[1], { x: 0.25, y: 0, w: 0.25, h: 0.5 });
+    assert.deepEqual(frames[5], { x: 0.25, y: 0.5, w: 0.25, h: 0.5 });
+  });
+
+  it("sheetFrames takes a range and stops at the end of the sheet", () => {
+    const frames = sheetFrames(4, 2, 6, 4);
+    assert.equal(frames.length, 4);
+    assert.deepEqual(frames[0], { x: 0, y: 0.5, w: 0.25, h: 0.5 });
+  });
+
+  it("is inert in headless mode", () => {
+    const id = createSpriteAnimation(1, sheetFrames(4), 10, "once");
+    assert.equal(id, 0);
+    drawAnimated(id, { x: 0, y: 0, w: 16, h: 16 });
+    assert.equal(getSpriteAnimationFrame(id), -1);
+
+    let calls = 0;
+    const off = onSpriteAnimationEvent(() => calls++);
+    _dispatchSpriteAnimationEvents();
+    off();
+    assert.equal(calls, 0);
+  });
+});
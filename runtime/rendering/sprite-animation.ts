@@ -0,0 +1,193 @@
+/**
+ * Frame animations advanced by the engine.
+ *
+ * Unlike {@link createAnimation} in `animation.ts`, where game code holds the
+ * playback state and calls `updateAnimation()` every frame, these animations
+ * live in Rust: the engine steps them with the frame's delta time (so they
+ * freeze while the simulation is paused) and reports loops and completions
+ * through {@link onSpriteAnimationEvent}. Game code only draws them.
+ *
+ * In headless mode every function is a no-op and {@link createSpriteAnimation} returns 0.
+ */
+
+import type { SpriteOptions, TextureId } from "./types.ts";
+import { _flushSpriteBatch, _resolveOrigin } from "./sprites.ts";
+import { getCamera } from "./camera.ts";
+import { resolveScreenSpace } from "./context.ts";
+import { _flashKeyId } from "./flash.ts";
+
+const hasAnimationOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_animation === "function";
+
+// One sprite record, same layout as the sprite batch in sprites.ts
+const SPRITE_STRIDE = 23;
+const _record = new Float32Array(SPRITE_STRIDE);
+
+const blendModeMap: Record<string, number> = {
+  alpha: 0,
+  additive: 1,
+  multiply: 2,
+  screen: 3,
+};
+
+/** Handle to an engine-driven animation. 0 = none (headless mode or invalid input). */
+export type SpriteAnimationId = number;
+
+/** What happens after the last frame. */
+export type SpriteAnimationMode = "loop" | "pingpong" | "once";
+
+/** UV rect of one frame, normalized 0-1 like `SpriteOptions.uv`. */
+export type SpriteAnimationFrame = { x: number; y: number; w: number; h: number };
+
+/** A loop or completion, passed to {@link onSpriteAnimationEvent} listeners. */
+export type SpriteAnimationEvent = {
+  /** The animation that produced the event. */
+  id: SpriteAnimationId;
+  /** "loop": a loop or ping-pong animation started a new cycle. "finished": a "once" animation reached its last frame. */
+  event: "loop" | "finished";
+};
+
+/** Options for {@link drawAnimated}. Texture and UVs come from the animation. */
+export type DrawAnimatedOptions = Omit<
+  SpriteOptions,
+  "textureId" | "color" | "uv" | "tileW" | "tileH" | "parallax" | "shadow" | "outline" | "pixelSnap"
+>;
+
+const listeners: Array<(event: SpriteAnimationEvent) => void> = [];
+
+/**
+ * UV rects for the frames of a sprite sheet laid out in a grid, read left to
+ * right, top to bottom.
+ *
+ * @param cols - Columns in the sheet.
+ * @param rows - Rows in the sheet. Default: 1.
+ * @param count - Number of frames to take. Default: cols × rows.
+ * @param start - Index of the first frame. Default: 0.
+ */
+export function sheetFrames(
+  cols: number,
+  rows: number = 1,
+  count: number = cols * rows,
+  start: number = 0,
+): SpriteAnimationFrame[] {
+  const frames: SpriteAnimationFrame[] = [];
+  for (let i = start; i < start + count && i < cols * rows; i++) {
+    frames.push({ x: (i % cols) / cols, y: Math.floor(i / cols) / rows, w: 1 / cols, h: 1 / rows });
+  }
+  return frames;
+}
+
+/**
+ * Create an animation that the engine plays from the next frame on.
+ *
+ * @param textureId - Sprite sheet texture.
+ * @param frames - UV rect of each frame, in playback order (see {@link sheetFrames}).
+ * @param fps - Frames per second.
+ * @param mode - "loop" (default), "pingpong" (forwards then backwards) or "once" (stop on the last frame).
+ * @returns Animation ID, or 0 in headless mode or when `frames` is empty.
+ *
+ * @example
+ * const explosion = createSpriteAnimation(tex, sheetFrames(8, 2), 24, "once");
+ * onSpriteAnimationEvent((e) => {
+ *   if (e.id === explosion && e.event === "finished") destroySpriteAnimation(explosion);
+ * });
+ */
+export function createSpriteAnimation(
+  textureId: TextureId,
+  frames: SpriteAnimationFrame[],
+  fps: number,
+  mode: SpriteAnimationMode = "loop",
+): SpriteAnimationId {
+  if (!hasAnimationOps || frames.length === 0) return 0;
+  const packed = new Float32Array(frames.length * 4);
+  frames.forEach((f, i) => packed.set([f.x, f.y, f.w, f.h], i * 4));
+  return (globalThis as any).Deno.core.ops.op_create_animation(
+    textureId,
+    new Uint8Array(packed.buffer),
+    fps,
+    mode,
+  );
+}
+
+/**
+ * Draw the current frame of an animation. Must be called every frame, like drawSprite().
+ *
+ * @example
+ * drawAnimated(runAnim, { x: player.x, y: player.y, w: 32, h: 32, flipX: player.facingLeft, layer: 5 });
+ */
+export function drawAnimated(id: SpriteAnimationId, opts: DrawAnimatedOptions): void {
+  if (!hasAnimationOps || id === 0) return;
+  let { x, y, w, h } = opts;
+  if (resolveScreenSpace(opts.screenSpace)) {
+    const cam = getCamera();
+    x = cam.x + x / cam.zoom;
+    y = cam.y + y / cam.zoom;
+    w = w / cam.zoom;
+    h = h / cam.zoom;
+  }
+  const tint = opts.tint ?? { r: 1, g: 1, b: 1, a: 1 };
+  const view = new DataView(_record.buffer);
+  view.setUint32(0, 0, true);
+  _record[1] = x;
+  _record[2] = y;
+  _record[3] = w;
+  _record[4] = h;
+  view.setInt32(5 * 4, opts.layer ?? 0, true);
+  _record.set([
+    0, 0, 1, 1,
+    tint.r, tint.g, tint.b, tint.a,
+    opts.rotation ?? 0,
+    ..._resolveOrigin(opts),
+    opts.flipX ? 1 : 0,
+    opts.flipY ? 1 : 0,
+    opts.opacity ?? 1,
+    blendModeMap[opts.blendMode ?? "alpha"] ?? 0,
+  ], 6);
+  view.setUint32(21 * 4, opts.shaderId ?? 0, true);
+  view.setUint32(22 * 4, _flashKeyId(opts.flashKey), true);
+  // Keep draw order relative to sprites queued earlier this frame
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_draw_animated(id, new Uint8Array(_record.buffer));
+}
+
+/** Play an animation again from its first frame. Also un-finishes a "once" animation. */
+export function restartSpriteAnimation(id: SpriteAnimationId): void {
+  if (!hasAnimationOps || id === 0) return;
+  (globalThis as any).Deno.core.ops.op_restart_animation(id);
+}
+
+/** Index into the animation's frames currently shown, or -1 if the ID is unknown or in headless mode. */
+export function getSpriteAnimationFrame(id: SpriteAnimationId): number {
+  if (!hasAnimationOps || id === 0) return -1;
+  return (globalThis as any).Deno.core.ops.op_get_animation_frame(id);
+}
+
+/** Destroy an animation. Drawing it afterwards is a no-op. */
+export function destroySpriteAnimation(id: SpriteAnimationId): void {
+  if (!hasAnimationOps || id === 0) return;
+  (globalThis as any).Deno.core.ops.op_destroy_animation(id);
+}
+
+/**
+ * Listen for animation loops and completions. Listeners run at the start of
+ * the frame in which the event happened, before the {@link onFrame} callback.
+ *
+ * @returns A function that removes the listener.
+ */
+export function onSpriteAnimationEvent(listener: (event: SpriteAnimationEvent) => void): () => void {
+  listeners.push(listener);
+  return () => {
+    const index = listeners.indexOf(listener);
+    if (index >= 0) listeners.splice(index, 1);
+  };
+}
+
+/** Deliver this frame's animation events to listeners. Called by the frame loop. @internal */
+export function _dispatchSpriteAnimationEvents(): void {
+  if (!hasAnimationOps) return;
+  const events: SpriteAnimationEvent[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_animation_events());
+  for (const event of events) {
+    for (const listener of [...listeners]) listener(event);
+  }
+}
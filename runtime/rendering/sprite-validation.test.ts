@@ -0,0 +1,44 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { drawSprite } from "./sprites.ts";
+import {
+  getSpriteValidationIssues,
+  isSpriteValidationEnabled,
+  setSpriteValidation,
+  _reportSpriteIssues,
+} from "./sprite-validation.ts";
+
+describe("Sprite validation", () => {
+  it("is off in headless mode", () => {
+    assert.equal(isSpriteValidationEnabled(), false);
+    drawSprite({ textureId: 1, x: NaN, y: 0, w: 16, h: 16 });
+    _reportSpriteIssues();
+    assert.equal(getSpriteValidationIssues().length, 0);
+  });
+
+  it("aggregates bad input per kind and call site", () => {
+    setSpriteValidation(true);
+    for (let i = 0; i < 3; i++) {
+      drawSprite({ textureId: 1, x: NaN, y: 0, w: 16, h: 16 });
+    }
+    drawSprite({ textureId: 1, x: 0, y: 0, w: 0, h: 16 });
+    drawSprite({ textureId: 0, x: 0, y: 0, w: 16, h: 16 });
+    drawSprite({ textureId: 1, x: 0, y: 0, w: 16, h: 16, blendMode: "overlay" as any });
+    drawSprite({ textureId: 1, x: 0, y: 0, w: 16, h: 16 });
+    _reportSpriteIssues();
+    setSpriteValidation(false);
+
+    const issues = getSpriteValidationIssues();
+    assert.deepEqual(
+      issues.map((i) => i.kind),
+      ["nonFinite", "zeroSize", "unknownTexture", "unknownBlendMode"],
+    );
+    assert.equal(issues[0].count, 3);
+    assert.equal(issues[0].detail, "x is NaN");
+    assert.ok(issues[0].site.includes("sprite-validation.test.ts"));
+  });
+
+  it("starts each frame empty", () => {
+    _reportSpriteIssues();
+    assert.equal(getSpriteValidationIssues().length, 0);
+  });
+});
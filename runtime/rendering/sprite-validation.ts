@@ -0,0 +1,158 @@
+/**
+ * Dev-mode validation of drawSprite() input. Sprites with NaN/infinite
+ * numbers, zero size or a texture ID the engine never issued are skipped
+ * instead of rendering garbage, and unknown blend modes fall back to "alpha".
+ * Problems are aggregated per frame by kind and call site; each distinct one
+ * is printed once with `console.warn`, and {@link getSpriteValidationIssues}
+ * returns the last frame's full list.
+ *
+ * On by default when the renderer is running (`arcane dev`), off in headless
+ * tests.
+ */
+
+import type { SpriteOptions } from "./types.ts";
+
+const hasTextureLimitOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_texture_id_limit === "function";
+
+/** Kind of problem found in a drawSprite() call. */
+export type SpriteIssueKind = "nonFinite" | "zeroSize" | "unknownTexture" | "unknownBlendMode";
+
+/** An aggregated drawSprite() problem from the last frame. */
+export type SpriteIssue = {
+  kind: SpriteIssueKind;
+  /** What was wrong, e.g. `x is NaN` or `textureId 42 was never created`. */
+  detail: string;
+  /** Where drawSprite() was called from (`file:line:column`), or "unknown". */
+  site: string;
+  /** Calls with this problem at this site during the frame. */
+  count: number;
+};
+
+const BLEND_MODES = new Set(["alpha", "additive", "multiply", "screen"]);
+
+let enabled = hasTextureLimitOp;
+let textureIdLimit = 0;
+let frameIssues = new Map<string, SpriteIssue>();
+let lastFrameIssues: SpriteIssue[] = [];
+const reported = new Set<string>();
+
+/**
+ * Turn drawSprite() validation on or off. Costs a few comparisons per sprite;
+ * turn it off when profiling.
+ */
+export function setSpriteValidation(on: boolean): void {
+  enabled = on;
+}
+
+/** Whether drawSprite() input is being validated. */
+export function isSpriteValidationEnabled(): boolean {
+  return enabled;
+}
+
+/** Problems found during the last completed frame (empty when validation is off). */
+export function getSpriteValidationIssues(): SpriteIssue[] {
+  return lastFrameIssues;
+}
+
+function nonFinite(opts: SpriteOptions): string | null {
+  const fields: Array<[string, number | undefined]> = [
+    ["x", opts.x],
+    ["y", opts.y],
+    ["w", opts.w],
+    ["h", opts.h],
+    ["rotation", opts.rotation],
+    ["opacity", opts.opacity],
+    ["originX", opts.originX],
+    ["originY", opts.originY],
+  ];
+  if (opts.uv) {
+    fields.push(["uv.x", opts.uv.x], ["uv.y", opts.uv.y], ["uv.w", opts.uv.w], ["uv.h", opts.uv.h]);
+  }
+  if (opts.tint) {
+    fields.push(["tint.r", opts.tint.r], ["tint.g", opts.tint.g], ["tint.b", opts.tint.b], ["tint.a", opts.tint.a]);
+  }
+  for (const [name, value] of fields) {
+    if (value !== undefined && !Number.isFinite(value)) return `${name} is ${value}`;
+  }
+  return null;
+}
+
+function textureKnown(id: number): boolean {
+  if (!Number.isInteger(id) || id <= 0) return false;
+  if (!hasTextureLimitOp) return true;
+  if (id >= textureIdLimit) {
+    // IDs are issued in order; refresh only when one looks too new
+    textureIdLimit = (globalThis as any).Deno.core.ops.op_get_texture_id_limit();
+  }
+  return id < textureIdLimit;
+}
+
+/** First stack frame outside the runtime, so helpers report the game's call. */
+function callSite(): string {
+  const frames = (new Error().stack ?? "")
+    .split("\n")
+    .slice(1)
+    .map((line) => line.trim().replace(/^at\s+/, ""))
+    .filter((line) => !line.includes("sprite-validation.ts") && !line.includes("sprites.ts"));
+  const site = frames.find((line) => !line.includes("/runtime/")) ?? frames[0];
+  if (!site) return "unknown";
+  // "fn (file:///game/main.ts:12:5)" → "/game/main.ts:12:5"
+  const location = site.match(/\(([^)]+)\)$/)?.[1] ?? site;
+  return location.replace(/^file:\/\//, "");
+}
+
+function record(kind: SpriteIssueKind, detail: string): void {
+  const site = callSite();
+  const key = `${kind}|${detail}|${site}`;
+  const issue = frameIssues.get(key);
+  if (issue) {
+    issue.count++;
+  } else {
+    frameIssues.set(key, { kind, detail, site, count: 1 });
+  }
+}
+
+/**
+ * Check a drawSprite() call. Returns false if the sprite should be skipped.
+ * @internal
+ */
+export function _validateSprite(opts: SpriteOptions, textureId: number): boolean {
+  if (!enabled) return true;
+  const bad = nonFinite(opts);
+  if (bad) {
+    record("nonFinite", bad);
+    return false;
+  }
+  if (opts.w === 0 || opts.h === 0) {
+    record("zeroSize", `size is ${opts.w}×${opts.h}`);
+    return false;
+  }
+  if (!textureKnown(textureId)) {
+    record("unknownTexture", `textureId ${textureId} was never created`);
+    return false;
+  }
+  if (opts.blendMode !== undefined && !BLEND_MODES.has(opts.blendMode)) {
+    record("unknownBlendMode", `blendMode "${opts.blendMode}" (drawn with "alpha")`);
+  }
+  return true;
+}
+
+/**
+ * End the frame's validation: keep its issues for {@link getSpriteValidationIssues}
+ * and print the ones not reported before. Called by the frame loop.
+ * @internal
+ */
+export function _reportSpriteIssues(): void {
+  lastFrameIssues = [...frameIssues.values()];
+  frameIssues = new Map();
+  for (const issue of lastFrameIssues) {
+    const key = `${issue.kind}|${issue.detail}|${issue.site}`;
+    if (reported.has(key)) continue;
+    reported.add(key);
+    const times = issue.count > 1 ? ` (${issue.count}× this frame)` : "";
+    const action = issue.kind === "unknownBlendMode" ? "" : ", sprite skipped";
+    console.warn(`[arcane] drawSprite(): ${issue.detail}${action}${times} at ${issue.site}`);
+  }
+}
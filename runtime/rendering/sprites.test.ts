@@ -1,5 +1,5 @@
 import { describe, it, assert } from "../testing/harness.ts";
-import { drawSprite } from "./sprites.ts";
+import { drawSprite, drawSpriteBuffer, SPRITE_STRIDE, pushClipRect, popClipRect, setLayerYSort, setPixelSnap, _resolveOrigin } from "./sprites.ts";
 import {
   enableDrawCallCapture,
   disableDrawCallCapture,
@@ -163,4 +163,92 @@ describe("Sprites", () => {
       disableDrawCallCapture();
     });
   });
+
+  describe("outline option", () => {
+    it("draws the sprite once, with the outline left to the renderer", () => {
+      enableDrawCallCapture();
+      clearDrawCalls();
+
+      drawSprite({
+        textureId: 1,
+        x: 100,
+        y: 100,
+        w: 32,
+        h: 32,
+        outline: { color: { r: 1, g: 0.8, b: 0, a: 1 }, thickness: 2 },
+      });
+
+      const calls = getDrawCalls();
+      assert.equal(calls.length, 1);
+      assert.equal(calls[0].type, "sprite");
+
+      disableDrawCallCapture();
+    });
+  });
+
+  describe("clip rects", () => {
+    it("sprites drawn inside push/pop are still drawn", () => {
+      enableDrawCallCapture();
+      clearDrawCalls();
+
+      pushClipRect(0, 0, 100, 50);
+      pushClipRect(20, 20, 200, 200);
+      drawSprite({ textureId: 1, x: 10, y: 10, w: 32, h: 32 });
+      popClipRect();
+      popClipRect();
+      popClipRect(); // extra pops are harmless
+
+      assert.equal(getDrawCalls().length, 1);
+
+      disableDrawCallCapture();
+    });
+  });
+
+  describe("setLayerYSort", () => {
+    it("is a no-op in headless mode", () => {
+      setLayerYSort(5, true);
+      setLayerYSort(5, false);
+    });
+  });
+
+  describe("origin presets", () => {
+    it("resolves presets to originX/originY", () => {
+      assert.deepEqual(_resolveOrigin({}), [0.5, 0.5]);
+      assert.deepEqual(_resolveOrigin({ origin: "top-left" }), [0, 0]);
+      assert.deepEqual(_resolveOrigin({ origin: "bottom-center" }), [0.5, 1]);
+      assert.deepEqual(_resolveOrigin({ origin: "center-right" }), [1, 0.5]);
+    });
+
+    it("lets explicit originX/originY override the preset", () => {
+      assert.deepEqual(_resolveOrigin({ origin: "bottom-right", originX: 0.25 }), [0.25, 1]);
+      assert.deepEqual(_resolveOrigin({ origin: "top-left", originY: 0.75 }), [0, 0.75]);
+    });
+  });
+
+  describe("pixel snapping", () => {
+    it("is a no-op in headless mode and still draws sprites", () => {
+      enableDrawCallCapture();
+      clearDrawCalls();
+
+      setPixelSnap(true);
+      drawSprite({ textureId: 1, x: 10.4, y: 3.6, w: 16, h: 16, pixelSnap: false });
+      drawSprite({ textureId: 1, x: 10.4, y: 3.6, w: 16, h: 16, origin: "bottom-center" });
+      setPixelSnap(false);
+
+      assert.equal(getDrawCalls().length, 2);
+
+      disableDrawCallCapture();
+    });
+  });
+
+  describe("drawSpriteBuffer", () => {
+    it("is a no-op in headless mode", () => {
+      const buf = new Float32Array(3 * SPRITE_STRIDE);
+      drawSpriteBuffer(buf);
+      drawSpriteBuffer(buf, 2);
+      drawSpriteBuffer(new Float32Array(SPRITE_STRIDE - 1));
+      drawSpriteBuffer(buf, 2.5);
+      assert.equal(SPRITE_STRIDE, 23);
+    });
+  });
 });
@@ -1,22 +1,42 @@
-import type { SpriteOptions } from "./types.ts";
+import type { SpriteOptions, SpriteOrigin } from "./types.ts";
 import { getCamera } from "./camera.ts";
 import { _logDrawCall } from "../testing/visual.ts";
 import { createSolidTexture } from "./texture.ts";
 import { _warnColor } from "../ui/colors.ts";
 import { resolveScreenSpace } from "./context.ts";
+import { _validateSprite } from "./sprite-validation.ts";
+import { _flashKeyId } from "./flash.ts";
 
 // Detect if the sprite batch op is available (renderer active).
 const hasBatchOp =
   typeof (globalThis as any).Deno !== "undefined" &&
   typeof (globalThis as any).Deno?.core?.ops?.op_submit_sprite_batch === "function";
+const hasBulkOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_draw_sprites_bulk === "function";
 
 // --- Batch sprite buffer ---
-// Pre-allocate a Float32Array for batching sprites (22 f32s per sprite, max 16384 sprites).
-const SPRITE_STRIDE = 22;
+// Pre-allocate a Float32Array for batching sprites (23 f32s per sprite, max 16384 sprites).
+
+/**
+ * Number of f32 values per sprite in a buffer for {@link drawSpriteBuffer}:
+ * [textureId (u32 bits), x, y, w, h, layer (i32 bits), uvX, uvY, uvW, uvH,
+ * r, g, b, a, rotation, originX, originY, flipX, flipY, opacity, blendMode,
+ * shaderId (u32 bits), flash key (u32 bits, 0 = none)].
+ */
+export const SPRITE_STRIDE = 23;
 const MAX_BATCH_SPRITES = 16384;
 const _batchBuffer = new Float32Array(MAX_BATCH_SPRITES * SPRITE_STRIDE);
 let _batchCount = 0;
 
+// Outlined sprites go through their own op: one sprite record followed by
+// [r, g, b, a, thickness].
+const hasOutlineOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_draw_outlined_sprites === "function";
+const OUTLINED_SPRITE_STRIDE = SPRITE_STRIDE + 5;
+const _outlineRecord = new Float32Array(OUTLINED_SPRITE_STRIDE);
+
 // --- Color texture cache (moved from game/color-sprite.ts) ---
 /** @internal Color texture cache. Keyed by "r_g_b_a" string. */
 const _colorTexCache = new Map<string, number>();
@@ -39,6 +59,38 @@ export function _resetColorTexCache(): void {
 /** @internal Deduplicated warning for missing textureId/color. */
 const _noTexWarned = new Set<string>();
 
+const originPresets: Record<SpriteOrigin, [number, number]> = {
+  "top-left": [0, 0],
+  "top-center": [0.5, 0],
+  "top-right": [1, 0],
+  "center-left": [0, 0.5],
+  "center": [0.5, 0.5],
+  "center-right": [1, 0.5],
+  "bottom-left": [0, 1],
+  "bottom-center": [0.5, 1],
+  "bottom-right": [1, 1],
+};
+
+/**
+ * @internal Origin of a sprite as [originX, originY]: explicit originX/originY
+ * first, then the `origin` preset, then the center.
+ */
+export function _resolveOrigin(opts: {
+  origin?: SpriteOrigin;
+  originX?: number;
+  originY?: number;
+}): [number, number] {
+  const preset = (opts.origin && originPresets[opts.origin]) || originPresets.center;
+  return [opts.originX ?? preset[0], opts.originY ?? preset[1]];
+}
+
+// Per-draw pixelSnap is batch state on the Rust side: 0 follows setPixelSnap,
+// 1 snaps, 2 does not. Changing it flushes the sprites batched before.
+const hasPixelSnapOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_batch_pixel_snap === "function";
+let _batchSnapMode = 0;
+
 const blendModeMap: Record<string, number> = {
   alpha: 0,
   additive: 1,
@@ -71,6 +123,14 @@ const blendModeMap: Record<string, number> = {
  *   tint: { r: 1, g: 0.5, b: 0.5, a: 1 },
  *   layer: 5,
  * });
+ *
+ * @example
+ * // Highlight the selected unit with a 2px outline around its silhouette
+ * drawSprite({
+ *   textureId: unitTex,
+ *   x: unit.x, y: unit.y, w: 32, h: 32,
+ *   outline: { color: { r: 1, g: 0.85, b: 0.2, a: 1 }, thickness: 2 },
+ * });
  */
 export function drawSprite(opts: SpriteOptions): void {
   // --- Resolve texture from color if needed ---
@@ -88,6 +148,7 @@ export function drawSprite(opts: SpriteOptions): void {
     }
     return;
   }
+  if (!_validateSprite(opts, resolvedTexId)) return;
 
   // --- Tiling: compute UV repeat if tileW/tileH set ---
   let resolvedUV = opts.uv;
@@ -120,8 +181,10 @@ export function drawSprite(opts: SpriteOptions): void {
       uv: resolvedUV,
       tint: shadowColor,
       rotation: opts.rotation,
+      origin: opts.origin,
       originX: opts.originX,
       originY: opts.originY,
+      pixelSnap: opts.pixelSnap,
       flipX: opts.flipX,
       flipY: opts.flipY,
       opacity: shadowColor.a,
@@ -186,13 +249,20 @@ export function drawSprite(opts: SpriteOptions): void {
   const tintB = tint.b;
   const tintA = tint.a;
   const rotation = opts.rotation ?? 0;
-  const originX = opts.originX ?? 0.5;
-  const originY = opts.originY ?? 0.5;
+  const [originX, originY] = _resolveOrigin(opts);
   const flipX = opts.flipX ? 1 : 0;
   const flipY = opts.flipY ? 1 : 0;
   const opacity = opts.opacity ?? 1;
   const blendMode = blendModeMap[opts.blendMode ?? "alpha"] ?? 0;
   const shaderId = opts.shaderId ?? 0;
+  const flashKey = _flashKeyId(opts.flashKey);
+
+  const snapMode = opts.pixelSnap === undefined ? 0 : opts.pixelSnap ? 1 : 2;
+  if (snapMode !== _batchSnapMode && hasPixelSnapOps) {
+    _flushSpriteBatch();
+    _batchSnapMode = snapMode;
+    (globalThis as any).Deno.core.ops.op_set_batch_pixel_snap(snapMode);
+  }
 
   // Write sprite into the batch buffer
   if (_batchCount >= MAX_BATCH_SPRITES) {
@@ -225,7 +295,18 @@ export function drawSprite(opts: SpriteOptions): void {
   _batchBuffer[base + 19] = opacity;
   _batchBuffer[base + 20] = blendMode;
   view.setUint32((base + 21) * 4, shaderId, true); // shader_id as u32 bits in f32 slot
+  view.setUint32((base + 22) * 4, flashKey, true); // flash key as u32 bits in f32 slot
   _batchCount++;
+
+  if (opts.outline && hasOutlineOp) {
+    // Move the record out of the batch; flushing the rest first keeps draw order
+    const o = opts.outline;
+    _outlineRecord.set(_batchBuffer.subarray(base, base + SPRITE_STRIDE));
+    _outlineRecord.set([o.color.r, o.color.g, o.color.b, o.color.a, o.thickness ?? 1], SPRITE_STRIDE);
+    _batchCount--;
+    _flushSpriteBatch();
+    (globalThis as any).Deno.core.ops.op_draw_outlined_sprites(new Uint8Array(_outlineRecord.buffer));
+  }
 }
 
 /**
@@ -244,6 +325,53 @@ export function _flushSpriteBatch(): void {
   _batchCount = 0;
 }
 
+/**
+ * Draw sprites already packed into a Float32Array, {@link SPRITE_STRIDE}
+ * values each, in one op call. For games that keep their entities' sprite
+ * data in a typed array and rewrite it each frame, this skips building a
+ * SpriteOptions object per sprite. Like drawSprite(), call it every frame.
+ * Integer fields are stored as their bit patterns (write them through a
+ * DataView or a Uint32Array/Int32Array over the same buffer); blendMode is
+ * 0 alpha, 1 additive, 2 multiply, 3 screen. Sprites follow setPixelSnap().
+ * No-op in headless mode.
+ *
+ * @param data - Packed sprite records.
+ * @param count - Number of sprites to draw. Default: every whole record in `data`.
+ *
+ * @example
+ * const buf = new Float32Array(enemies.length * SPRITE_STRIDE);
+ * const bits = new Uint32Array(buf.buffer);
+ * enemies.forEach((e, i) => {
+ *   const o = i * SPRITE_STRIDE;
+ *   bits[o] = enemyTex;
+ *   buf.set([e.x, e.y, 16, 16], o + 1);
+ *   bits[o + 5] = 5; // layer
+ *   buf.set([0, 0, 1, 1, 1, 1, 1, 1, 0, 0.5, 0.5, 0, 0, 1, 0], o + 6);
+ * });
+ * drawSpriteBuffer(buf);
+ */
+export function drawSpriteBuffer(data: Float32Array, count?: number): void {
+  if (!hasBulkOp) return;
+  const n = Math.min(Math.floor(count ?? Infinity), Math.floor(data.length / SPRITE_STRIDE));
+  if (n <= 0) return;
+  // Keep draw order relative to sprites batched earlier this frame
+  _flushSpriteBatch();
+  if (_batchSnapMode !== 0 && hasPixelSnapOps) {
+    _batchSnapMode = 0;
+    (globalThis as any).Deno.core.ops.op_set_batch_pixel_snap(0);
+  }
+  (globalThis as any).Deno.core.ops.op_draw_sprites_bulk(data, n);
+}
+
+/**
+ * Drop sprites batched since the last flush without submitting them.
+ * Used when a frame callback throws, so a half-drawn frame does not leak
+ * into the next one.
+ */
+export function _discardSpriteBatch(): void {
+  _batchCount = 0;
+}
+
 /**
  * Clear all queued sprites for this frame.
  * Normally not needed -- the renderer clears automatically at frame start.
@@ -261,3 +389,79 @@ export function clearSprites(): void {
   (globalThis as any).Deno.core.ops.op_clear_sprites();
 }
 
+const hasClipOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_push_clip_rect === "function";
+
+/**
+ * Clip sprites drawn after this call to a screen-space rectangle (in
+ * viewport pixels, top-left origin), until the matching {@link popClipRect}.
+ * Nested clips intersect with the ones outside them. Applied by the GPU as a
+ * scissor rect, so it ignores the camera and rotation. Clips sprites, text and
+ * tilemaps, not shapes or SDFs. An unbalanced push is dropped at the end of
+ * the frame. No-op in headless mode.
+ *
+ * @example
+ * // Scroll view: only the rows inside the 300x200 panel are visible
+ * pushClipRect(50, 80, 300, 200);
+ * for (let i = 0; i < rows.length; i++) {
+ *   drawText(rows[i], 60, 90 + i * 24 - scrollY, { screenSpace: true, layer: 110 });
+ * }
+ * popClipRect();
+ */
+export function pushClipRect(x: number, y: number, w: number, h: number): void {
+  if (!hasClipOps) return;
+  // Sprites batched before the push must stay unclipped
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_push_clip_rect(x, y, w, h);
+}
+
+/**
+ * Restore the clip from before the last {@link pushClipRect}.
+ * No-op in headless mode.
+ */
+export function popClipRect(): void {
+  if (!hasClipOps) return;
+  _flushSpriteBatch();
+  (globalThis as any).Deno.core.ops.op_pop_clip_rect();
+}
+
+const hasYSortOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_set_layer_ysort === "function";
+
+/**
+ * Draw a layer's sprites sorted by the world y of their origin point
+ * (`y + h * originY`) instead of the order they were drawn in, so characters
+ * walk behind and in front of props on the same layer. Give sprites
+ * `originY: 1` to sort them by their feet. Sprites on other layers keep
+ * their draw order. Stays on until turned off. No-op in headless mode.
+ *
+ * @example
+ * setLayerYSort(10, true);
+ * drawSprite({ textureId: tree, x: 200, y: 100, w: 64, h: 96, layer: 10, originY: 1 });
+ * drawSprite({ textureId: hero, x: player.x, y: player.y, w: 16, h: 24, layer: 10, originY: 1 });
+ */
+export function setLayerYSort(layer: number, enabled: boolean): void {
+  if (!hasYSortOp) return;
+  (globalThis as any).Deno.core.ops.op_set_layer_ysort(layer, enabled);
+}
+
+/**
+ * Round sprite positions to whole screen pixels of the main camera, so pixel
+ * art stays crisp and does not shimmer when the camera scrolls or zooms by
+ * fractions of a pixel. Snapping happens after the camera follow, bounds and
+ * shake are applied, in logical pixels, and is skipped while the camera is
+ * rotated. A sprite's `pixelSnap` option overrides this setting for that
+ * sprite. Stays on until turned off. No-op in headless mode.
+ *
+ * @example
+ * setPixelSnap(true);
+ * drawSprite({ textureId: hero, x: player.x, y: player.y, w: 16, h: 16 });
+ * // Smoothly moving particles opt out
+ * drawSprite({ textureId: spark, x: p.x, y: p.y, w: 2, h: 2, pixelSnap: false });
+ */
+export function setPixelSnap(enabled: boolean): void {
+  if (!hasPixelSnapOps) return;
+  (globalThis as any).Deno.core.ops.op_set_pixel_snap(enabled);
+}
@@ -0,0 +1,12 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { getSpriteStats } from "./stats.ts";
+
+describe("Sprite stats", () => {
+  it("reports zeros in headless mode", () => {
+    const stats = getSpriteStats();
+    assert.equal(stats.drawCalls, 0);
+    assert.equal(stats.instances, 0);
+    assert.equal(stats.uploadBytes, 0);
+    assert.equal(stats.capacity, 0);
+  });
+});
@@ -0,0 +1,38 @@
+/**
+ * Renderer statistics. Sprites are drawn from an instance buffer that persists
+ * across frames; only instances that changed since the previous frame are
+ * uploaded, so a mostly static scene uploads few bytes per frame.
+ */
+
+const hasStatsOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_get_sprite_stats === "function";
+
+/** Result of {@link getSpriteStats}. */
+export type SpriteStats = {
+  /** Instanced draw calls issued (one per shader/blend mode/texture batch). */
+  drawCalls: number;
+  /** Sprites drawn. */
+  instances: number;
+  /** Bytes of instance data uploaded to the GPU. */
+  uploadBytes: number;
+  /** Instance buffer allocations since startup. Grows only when a frame needs more room. */
+  bufferAllocations: number;
+  /** Instance buffer capacity, in sprites. */
+  capacity: number;
+};
+
+/**
+ * Sprite batching and upload counters for the last rendered frame, including
+ * render target passes. All zeros in headless mode.
+ *
+ * @example
+ * const { drawCalls, uploadBytes } = getSpriteStats();
+ * drawText(`${drawCalls} draws, ${(uploadBytes / 1024).toFixed(1)} KB`, 8, 8);
+ */
+export function getSpriteStats(): SpriteStats {
+  if (!hasStatsOp) {
+    return { drawCalls: 0, instances: 0, uploadBytes: 0, bufferAllocations: 0, capacity: 0 };
+  }
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_sprite_stats());
+}
@@ -1,11 +1,14 @@
 import { describe, it, assert } from "../../runtime/testing/harness.ts";
 import {
   loadFont,
+  loadBitmapFont,
   getDefaultFont,
   getDefaultMSDFFont,
   loadMSDFFont,
+  loadFontFile,
   measureText,
   drawText,
+  layoutText,
   wrapText,
   drawTextWrapped,
   drawTextAligned,
@@ -100,6 +103,22 @@ describe("text", () => {
     assert.equal(col, 0);
     assert.equal(row, 0);
   });
+
+  it("loadBitmapFont measures with per-glyph advances from an inline manifest", () => {
+    const manifest = JSON.stringify({
+      cellWidth: 8, cellHeight: 10, columns: 2, rows: 2,
+      charset: "AB I", advances: [6, 6, 4, 2], spacing: 1,
+    });
+    const font = loadBitmapFont("fonts/pixel.png", manifest);
+    assert.equal(font.glyphH, 10);
+    assert.deepEqual(font.glyphs!.get(73)!.uv, { x: 0.5, y: 0.5, w: 0.5, h: 0.5 });
+    // A(7) + B(7) + space(5) + I(3)
+    assert.equal(measureText("AB I", { font }).width, 22);
+    // Lowercase falls back to uppercase; unknown characters take a full cell
+    assert.equal(measureText("ab", { font, scale: 2 }).width, 28);
+    assert.equal(measureText("?", { font }).width, 8);
+  });
+
 });
 
 // --- Text alignment tests ---
@@ -386,6 +405,14 @@ describe("msdf text", () => {
     assert.equal(font.shaderPool[0], 0);
   });
 
+  it("loadFontFile headless returns a variable-cell dummy", () => {
+    const font = loadFontFile("assets/fonts/missing.ttf", { minSize: 12, maxSize: 48 });
+    assert.equal(font.fontId, 0);
+    assert.equal(font.variableCells, true);
+    assert.equal(font.shaderPool.length, 1);
+    drawText("Grüße", 10, 10, { msdfFont: font, screenSpace: true });
+  });
+
   it("multiple drawText calls with different outline params don't throw", () => {
     const font = getDefaultMSDFFont();
     drawText("Red outline", 10, 10, {
@@ -462,6 +489,39 @@ describe("wrapText", () => {
     assert.equal(lines.length, 1);
     assert.equal(lines[0], "Hello World");
   });
+
+  it("breaks at newlines", () => {
+    const lines = wrapText("Hi\nthere", 0);
+    assert.deepEqual(lines, ["Hi", "there"]);
+  });
+});
+
+describe("layoutText", () => {
+  it("reports line offsets and widths", () => {
+    // Default font: 8px per char. "AB CD" at maxWidth 24 wraps after "AB"
+    const layout = layoutText("AB CD", 24);
+    assert.equal(layout.lines.length, 2);
+    assert.deepEqual(layout.lines[0], { text: "AB", start: 0, end: 2, width: 16 });
+    assert.deepEqual(layout.lines[1], { text: "CD", start: 3, end: 5, width: 16 });
+  });
+
+  it("reports overall size from the widest line", () => {
+    const layout = layoutText("A\nBCD", 0);
+    assert.equal(layout.width, 24);
+    assert.equal(layout.lineHeight, 8);
+    assert.equal(layout.height, 16);
+  });
+
+  it("counts offsets past newlines", () => {
+    const layout = layoutText("AB\nCD EF", 24);
+    assert.deepEqual(layout.lines.map((l) => [l.start, l.end]), [[0, 2], [3, 5], [6, 8]]);
+  });
+
+  it("applies scale", () => {
+    const layout = layoutText("AB", 0, { scale: 2 });
+    assert.equal(layout.width, 32);
+    assert.equal(layout.lineHeight, 16);
+  });
 });
 
 describe("drawTextWrapped", () => {
@@ -21,6 +21,19 @@ export type BitmapFont = {
   rows: number;
   /** ASCII code of the first glyph in the atlas. Default: 32 (space). */
   firstChar: number;
+  /**
+   * Glyphs by character code, for fonts from {@link loadBitmapFont}. When set,
+   * `columns`, `rows` and `firstChar` are unused and glyphs can have their own widths.
+   */
+  glyphs?: Map<number, BitmapGlyph>;
+};
+
+/** One glyph of a {@link loadBitmapFont} font. */
+export type BitmapGlyph = {
+  /** UV rect of the glyph's cell (normalized 0-1). */
+  uv: { x: number; y: number; w: number; h: number };
+  /** Cursor advance in pixels at scale 1. */
+  advance: number;
 };
 
 /** RGBA color (0.0-1.0 per channel). */
@@ -92,6 +105,12 @@ export type MSDFFont = {
   lineHeight: number;
   /** SDF distance range in pixels. */
   distanceRange: number;
+  /**
+   * Glyph cells have their own sizes (`width`/`height` include padding)
+   * instead of the uniform cell of the built-in font. Set for fonts from
+   * {@link loadFontFile}; bmfont atlases are detected from their glyphs.
+   */
+  variableCells?: boolean;
 };
 
 /** Options for {@link drawText} and {@link measureText}. */
@@ -145,10 +164,20 @@ const hasFontOp =
   typeof (globalThis as any).Deno?.core?.ops?.op_create_font_texture ===
   "function";
 
+const hasBitmapFontOp =
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_bitmap_font ===
+  "function";
+
 const hasMsdfOps =
   typeof (globalThis as any).Deno?.core?.ops?.op_create_msdf_builtin_font ===
   "function";
 
+const hasLoadFontOp =
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_font === "function";
+
+const hasMeasureOp =
+  typeof (globalThis as any).Deno?.core?.ops?.op_measure_text === "function";
+
 // --- Module state ---
 
 let defaultFont: BitmapFont | null = null;
@@ -225,6 +254,97 @@ export function loadFont(
   return { textureId, glyphW, glyphH, columns, rows, firstChar };
 }
 
+/**
+ * Load a bitmap font from an image of equally sized glyph cells and its
+ * manifest, which lists the characters in the cells and their widths.
+ * Generate the manifest with `arcane assets font-from-grid`. The font works
+ * with drawText(), measureText() and wrapText() through the `font` option.
+ * Characters missing from the font fall back to their uppercase form, and
+ * are otherwise skipped.
+ *
+ * In headless mode the font has no texture, but an inline JSON manifest still
+ * gives it real glyph widths for measuring.
+ *
+ * @param imagePath - Path to the font image (PNG).
+ * @param manifest - Path to the manifest JSON file, or the JSON itself.
+ * @returns BitmapFont descriptor for use with drawText().
+ *
+ * @example
+ * // arcane assets font-from-grid assets/fonts/pixel.png --cell 6x8 --proportional
+ * const pixel = loadBitmapFont("assets/fonts/pixel.png", "assets/fonts/pixel.font.json");
+ * drawText("SCORE 1200", 8, 8, { font: pixel, scale: 2, screenSpace: true });
+ */
+export function loadBitmapFont(imagePath: string, manifest: string): BitmapFont {
+  let result: {
+    textureId: number;
+    cellWidth: number;
+    cellHeight: number;
+    glyphs: Array<{ char: number; uv: [number, number, number, number]; advance: number }>;
+    error?: string;
+  };
+  if (hasBitmapFontOp) {
+    result = JSON.parse((globalThis as any).Deno.core.ops.op_load_bitmap_font(imagePath, manifest));
+    if (result.error) {
+      throw new Error(`Failed to load bitmap font: ${result.error}`);
+    }
+  } else if (manifest.trimStart().startsWith("{")) {
+    result = { textureId: 0, ...glyphsFromManifest(JSON.parse(manifest)) };
+  } else {
+    // Headless dummy: fixed 8x8 cells, nothing to draw
+    return { textureId: 0, glyphW: 8, glyphH: 8, columns: 16, rows: 6, firstChar: 32 };
+  }
+
+  const glyphs = new Map<number, BitmapGlyph>();
+  for (const g of result.glyphs) {
+    glyphs.set(g.char, { uv: { x: g.uv[0], y: g.uv[1], w: g.uv[2], h: g.uv[3] }, advance: g.advance });
+  }
+  return {
+    textureId: result.textureId,
+    glyphW: result.cellWidth,
+    glyphH: result.cellHeight,
+    columns: 1,
+    rows: 1,
+    firstChar: 0,
+    glyphs,
+  };
+}
+
+/** Headless counterpart of the glyph table op_load_bitmap_font builds in Rust. */
+function glyphsFromManifest(m: {
+  cellWidth: number;
+  cellHeight: number;
+  columns: number;
+  rows: number;
+  charset: string;
+  advances?: number[];
+  spacing?: number;
+}) {
+  const glyphs = [...m.charset].map((ch, i) => {
+    const uv: [number, number, number, number] = [
+      (i % m.columns) / m.columns,
+      Math.floor(i / m.columns) / m.rows,
+      1 / m.columns,
+      1 / m.rows,
+    ];
+    return { char: ch.codePointAt(0)!, uv, advance: (m.advances?.[i] ?? m.cellWidth) + (m.spacing ?? 0) };
+  });
+  return { cellWidth: m.cellWidth, cellHeight: m.cellHeight, glyphs };
+}
+
+/** Look up a glyph in a {@link loadBitmapFont} font, falling back to uppercase. */
+function lookupGlyph(glyphs: Map<number, BitmapGlyph>, ch: string): BitmapGlyph | undefined {
+  return glyphs.get(ch.codePointAt(0)!) ?? glyphs.get(ch.toUpperCase().codePointAt(0)!);
+}
+
+/** Width of text in a glyph-table font at scale 1. Unknown characters take a full cell. */
+function glyphTextWidth(font: BitmapFont, glyphs: Map<number, BitmapGlyph>, text: string): number {
+  let width = 0;
+  for (const ch of text) {
+    width += lookupGlyph(glyphs, ch)?.advance ?? font.glyphW;
+  }
+  return width;
+}
+
 /**
  * Get the default built-in 8x8 CP437 bitmap font, lazily initialized.
  * In headless mode returns a dummy font (textureId 0).
@@ -384,6 +504,76 @@ export function loadMSDFFont(
   };
 }
 
+/** Options for {@link loadFontFile}. */
+export type FontFileOptions = {
+  /** Smallest pixel size the font is drawn at. Default: 12. */
+  minSize?: number;
+  /** Largest pixel size the font is drawn at. Default: 64. */
+  maxSize?: number;
+};
+
+/**
+ * Load a TrueType (.ttf) or OpenType (.otf) font and render it as an MSDF
+ * font, with no offline atlas tools. The engine generates the distance field
+ * atlas on a worker thread: printable ASCII right away, any other character
+ * (accents, Cyrillic, symbols) the first time text uses it. Until its glyph
+ * is ready a character is left out, usually for a frame or two.
+ *
+ * The atlas is generated for the size range given; text stays sharp up to
+ * about twice the generated size (half of `maxSize`, between 16 and 64 px).
+ * Loading the same file for the same sizes again returns the same font.
+ *
+ * In headless mode returns a dummy font like {@link loadMSDFFont}.
+ *
+ * @param path - Path to the font file, relative to the game directory.
+ * @param options - Size range the text is drawn at.
+ * @returns MSDFFont descriptor for use with drawText() via `msdfFont`.
+ *
+ * @example
+ * const body = loadFontFile("assets/fonts/NotoSans-Regular.ttf", { maxSize: 48 });
+ * drawText("Grüße, мир!", 10, 10, { msdfFont: body, scale: 0.75, screenSpace: true });
+ */
+export function loadFontFile(path: string, options: FontFileOptions = {}): MSDFFont {
+  const minSize = options.minSize ?? 12;
+  const maxSize = options.maxSize ?? 64;
+  if (!hasLoadFontOp) {
+    return {
+      fontId: 0,
+      textureId: 0,
+      shaderId: 0,
+      shaderPool: [0],
+      fontSize: 32,
+      lineHeight: 38,
+      distanceRange: 4,
+      variableCells: true,
+    };
+  }
+
+  const result = JSON.parse(
+    (globalThis as any).Deno.core.ops.op_load_font(path, minSize, maxSize),
+  );
+  if (result.error) {
+    throw new Error(`Failed to load font: ${result.error}`);
+  }
+
+  const info = JSON.parse(
+    (globalThis as any).Deno.core.ops.op_get_msdf_font_info(result.fontId),
+  );
+  const pool: number[] = result.shaderPool ?? [result.shaderId];
+  if (msdfShaderPool.length === 0) msdfShaderPool = pool;
+
+  return {
+    fontId: result.fontId,
+    textureId: result.textureId,
+    shaderId: result.shaderId,
+    shaderPool: pool,
+    fontSize: info.fontSize,
+    lineHeight: info.lineHeight,
+    distanceRange: info.distanceRange,
+    variableCells: true,
+  };
+}
+
 /**
  * Get MSDF glyph metrics for a character from a loaded MSDF font.
  * Results are cached for performance.
@@ -391,8 +581,8 @@ export function loadMSDFFont(
 function getMSDFGlyphs(font: MSDFFont, text: string): MSDFGlyph[] {
   if (!hasMsdfOps) {
     // Headless: return dummy glyphs
-    return text.split("").map((ch) => ({
-      char: ch.charCodeAt(0),
+    return [...text].map((ch) => ({
+      char: ch.codePointAt(0)!,
       uv: [0, 0, 1, 1] as [number, number, number, number],
       advance: font.fontSize,
       width: font.fontSize,
@@ -407,7 +597,7 @@ function getMSDFGlyphs(font: MSDFFont, text: string): MSDFGlyph[] {
   const uncachedChars: string[] = [];
 
   for (const ch of text) {
-    const cacheKey = `${font.fontId}:${ch.charCodeAt(0)}`;
+    const cacheKey = `${font.fontId}:${ch.codePointAt(0)}`;
     const cached = msdfGlyphCache.get(cacheKey);
     if (cached) {
       glyphs.push(cached);
@@ -433,7 +623,7 @@ function getMSDFGlyphs(font: MSDFFont, text: string): MSDFGlyph[] {
     // Rebuild result with all glyphs in order
     glyphs.length = 0;
     for (const ch of text) {
-      const cacheKey = `${font.fontId}:${ch.charCodeAt(0)}`;
+      const cacheKey = `${font.fontId}:${ch.codePointAt(0)}`;
       const glyph = msdfGlyphCache.get(cacheKey);
       if (glyph) {
         glyphs.push(glyph);
@@ -492,6 +682,12 @@ export function measureText(
   // Bitmap path (fallback for headless or explicit bitmap font)
   const font = options?.font ?? getDefaultFont();
   const scale = options?.scale ?? 1;
+  if (font.glyphs) {
+    return {
+      width: glyphTextWidth(font, font.glyphs, text) * scale,
+      height: font.glyphH * scale,
+    };
+  }
   return {
     width: text.length * font.glyphW * scale,
     height: font.glyphH * scale,
@@ -582,21 +778,33 @@ export function drawText(
   const screenSpace = resolveScreenSpace(options?.screenSpace);
 
   const maxChar = font.columns * font.rows;
-
-  for (let i = 0; i < text.length; i++) {
-    const charCode = text.charCodeAt(i) - font.firstChar;
-    if (charCode < 0 || charCode >= maxChar) continue;
-
-    const col = charCode % font.columns;
-    const row = Math.floor(charCode / font.columns);
-    const uv = {
-      x: col / font.columns,
-      y: row / font.rows,
-      w: 1 / font.columns,
-      h: 1 / font.rows,
-    };
-
-    const drawX = alignedX + i * font.glyphW * scale;
+  // Glyph-table fonts (loadBitmapFont) place glyphs by their own advances
+  const chars = font.glyphs ? [...text] : text;
+  let cursor = alignedX;
+
+  for (let i = 0; i < chars.length; i++) {
+    let uv: { x: number; y: number; w: number; h: number };
+    let drawX: number;
+    if (font.glyphs) {
+      const glyph = lookupGlyph(font.glyphs, chars[i]);
+      drawX = cursor;
+      cursor += (glyph?.advance ?? font.glyphW) * scale;
+      if (!glyph) continue;
+      uv = glyph.uv;
+    } else {
+      drawX = alignedX + i * font.glyphW * scale;
+      const charCode = text.charCodeAt(i) - font.firstChar;
+      if (charCode < 0 || charCode >= maxChar) continue;
+
+      const col = charCode % font.columns;
+      const row = Math.floor(charCode / font.columns);
+      uv = {
+        x: col / font.columns,
+        y: row / font.rows,
+        w: 1 / font.columns,
+        h: 1 / font.rows,
+      };
+    }
 
     let worldX: number;
     let worldY: number;
@@ -719,9 +927,16 @@ function drawMSDFTextInternal(
 
     // Use per-glyph dimensions if they differ significantly from uniform cell
     // (bmfont format provides actual atlas cell size including padding)
-    const isVariableCell = Math.abs(g.width - msdfFont.fontSize) > 1 ||
+    const isVariableCell = msdfFont.variableCells === true ||
+                          Math.abs(g.width - msdfFont.fontSize) > 1 ||
                           Math.abs(g.height - msdfFont.fontSize) > 1;
 
+    // Glyphs without outline (spaces) only advance the cursor
+    if (isVariableCell && (g.width <= 0 || g.height <= 0)) {
+      cursorX += g.advance * scale;
+      continue;
+    }
+
     let spriteW: number;
     let spriteH: number;
 
@@ -790,9 +1005,116 @@ export type TextLayoutOptions = TextOptions & {
   layoutAlign?: TextAlign;
 };
 
+/** One line of a {@link TextLayout}. */
+export type TextLayoutLine = {
+  /** The line's text, without the space or newline it broke at. */
+  text: string;
+  /** Offset of the line in the source string (UTF-16 units, as for `slice`). */
+  start: number;
+  /** End offset of the line in the source string (exclusive). */
+  end: number;
+  /** Width in pixels. */
+  width: number;
+};
+
+/** Result of {@link layoutText}. Dimensions in pixels (before camera transform). */
+export type TextLayout = {
+  /** Width of the widest line. */
+  width: number;
+  /** Height of all lines: `lineHeight * lines.length`. */
+  height: number;
+  /** Height of one line. */
+  lineHeight: number;
+  lines: TextLayoutLine[];
+};
+
+/**
+ * Lay out text into lines without drawing it: line breaks, per-line widths and
+ * the overall size. Lines end at `\n` and wrap at the last space that fits in
+ * `maxWidth`; words wider than `maxWidth` get a line of their own.
+ *
+ * With an MSDF font (the default when rendering) the layout comes from the
+ * engine's own glyph metrics, so it matches what drawText() draws exactly,
+ * for any Unicode text: characters the font lacks take no space, and CJK
+ * text also wraps between characters. Bitmap fonts and headless mode use the
+ * same rules on measureText() widths.
+ *
+ * @param text - The text to lay out.
+ * @param maxWidth - Maximum line width in pixels. 0 or less wraps only at newlines.
+ * @param options - Font and scale options, as for drawText().
+ * @returns Lines with their source offsets and widths.
+ *
+ * @example
+ * // Center a two-line label in a 200px button
+ * const layout = layoutText("Start new game", 120, { scale: 2, screenSpace: true });
+ * layout.lines.forEach((line, i) => {
+ *   drawText(line.text, bx + (200 - line.width) / 2, by + i * layout.lineHeight, { scale: 2, screenSpace: true });
+ * });
+ */
+export function layoutText(
+  text: string,
+  maxWidth: number,
+  options?: TextOptions,
+): TextLayout {
+  const msdfFont = options?.msdfFont ?? ((!options?.font && hasMsdfOps) ? getDefaultMSDFFont() : undefined);
+  if (msdfFont && hasMeasureOp) {
+    const scale = options?.scale ?? 1;
+    const result = JSON.parse(
+      (globalThis as any).Deno.core.ops.op_measure_text(
+        msdfFont.fontId,
+        text,
+        msdfFont.fontSize * scale,
+        Math.max(maxWidth, 0),
+      ),
+    );
+    if (result) {
+      const lines = (result.lines as Array<{ start: number; end: number; width: number }>).map((l) => ({
+        text: text.slice(l.start, l.end),
+        ...l,
+      }));
+      return { width: result.width, height: result.height, lineHeight: result.lineHeight, lines };
+    }
+  }
+
+  // Bitmap fonts and headless mode: the same rules on measured widths
+  const lines: TextLayoutLine[] = [];
+  let offset = 0;
+  for (const paragraph of text.split("\n")) {
+    let lineStart = offset;
+    let lineEnd = offset;
+    let wordStart = offset;
+    let empty = true;
+    for (const word of paragraph.split(" ")) {
+      const wordEnd = wordStart + word.length;
+      const fits = maxWidth <= 0 || empty ||
+        measureText(text.slice(lineStart, wordEnd), options).width <= maxWidth;
+      if (!fits) {
+        lines.push({ text: text.slice(lineStart, lineEnd), start: lineStart, end: lineEnd, width: 0 });
+        lineStart = wordStart;
+      }
+      lineEnd = wordEnd;
+      empty = false;
+      wordStart = wordEnd + 1;
+    }
+    lines.push({ text: text.slice(lineStart, lineEnd), start: lineStart, end: lineEnd, width: 0 });
+    offset += paragraph.length + 1;
+  }
+  for (const line of lines) {
+    line.width = measureText(line.text, options).width;
+  }
+  const lineHeight = measureText("", options).height;
+  return {
+    width: Math.max(...lines.map((l) => l.width)),
+    height: lineHeight * lines.length,
+    lineHeight,
+    lines,
+  };
+}
+
 /**
  * Split text into lines that fit within maxWidth pixels.
- * Word-wraps at space boundaries. Words longer than maxWidth are placed on their own line.
+ * Word-wraps at space boundaries and breaks at newlines. Words longer than
+ * maxWidth are placed on their own line. See {@link layoutText} for widths.
  *
  * @param text - The text to wrap.
  * @param maxWidth - Maximum line width in pixels.
@@ -806,31 +1128,8 @@ export function wrapText(
   scale?: number,
   options?: TextOptions,
 ): string[] {
-  if (!text) return [""];
-  if (maxWidth <= 0) return [text];
-
-  const measureOpts = { ...options, scale: scale ?? options?.scale ?? 1 };
-  const words = text.split(" ");
-  const lines: string[] = [];
-  let currentLine = "";
-
-  for (let i = 0; i < words.length; i++) {
-    const word = words[i];
-    const testLine = currentLine ? currentLine + " " + word : word;
-    const measured = measureText(testLine, measureOpts);
-
-    if (measured.width <= maxWidth || !currentLine) {
-      // Fits, or it's the first word on the line (don't create empty lines)
-      currentLine = testLine;
-    } else {
-      // Doesn't fit, push current line and start new one
-      lines.push(currentLine);
-      currentLine = word;
-    }
-  }
-
-  lines.push(currentLine);
-  return lines;
+  const layoutOpts = { ...options, scale: scale ?? options?.scale ?? 1 };
+  return layoutText(text, maxWidth, layoutOpts).lines.map((l) => l.text);
 }
 
 /**
@@ -853,20 +1152,18 @@ export function drawTextWrapped(
   const layoutAlign = opts?.layoutAlign ?? "left";
   const scale = opts?.scale ?? 1;
 
-  const lines = wrapText(text, maxWidth, scale, opts);
-  const lineH = measureText("M", opts).height * lineHeightMult;
+  const layout = layoutText(text, maxWidth, { ...opts, scale });
+  const lineH = layout.lineHeight * lineHeightMult;
 
-  for (let i = 0; i < lines.length; i++) {
+  for (let i = 0; i < layout.lines.length; i++) {
+    const line = layout.lines[i];
     let lineX = x;
-    if (layoutAlign !== "left") {
-      const lineWidth = measureText(lines[i], opts).width;
-      if (layoutAlign === "center") {
-        lineX = x + (maxWidth - lineWidth) / 2;
-      } else {
-        lineX = x + (maxWidth - lineWidth);
-      }
+    if (layoutAlign === "center") {
+      lineX = x + (maxWidth - line.width) / 2;
+    } else if (layoutAlign === "right") {
+      lineX = x + (maxWidth - line.width);
     }
-    drawText(lines[i], lineX, y + i * lineH, opts);
+    drawText(line.text, lineX, y + i * lineH, opts);
   }
 }
 
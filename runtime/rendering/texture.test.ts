@@ -1,9 +1,14 @@
 import { describe, it, assert } from "../../runtime/testing/harness.ts";
 import {
   loadTexture,
+  loadTexturePair,
   isTextureLoaded,
   getLoadingProgress,
   preloadAssets,
+  onTextureLoad,
+  setTextureUploadBudget,
+  getTextureStreamStats,
+  _dispatchTextureEvents,
 } from "./texture.ts";
 
 describe("texture preloading", () => {
@@ -41,3 +46,25 @@ describe("texture preloading", () => {
     assert.equal(getLoadingProgress(), 1.0);
   });
 });
+
+describe("loadTexturePair", () => {
+  it("returns 0 in headless mode", () => {
+    assert.equal(loadTexturePair("assets/rock.png", "assets/rock_n.png"), 0);
+  });
+});
+
+describe("texture streaming", () => {
+  it("reports nothing in flight in headless mode", () => {
+    setTextureUploadBudget(1024 * 1024);
+    assert.deepEqual(getTextureStreamStats(), { decoding: 0, uploading: 0, bytesRemaining: 0 });
+  });
+
+  it("onTextureLoad returns an unsubscribe function and dispatch is a no-op", () => {
+    let calls = 0;
+    const off = onTextureLoad(() => calls++);
+    _dispatchTextureEvents();
+    off();
+    off();
+    assert.equal(calls, 0);
+  });
+});
@@ -43,6 +43,35 @@ export function loadTexture(
   return (globalThis as any).Deno.core.ops.op_load_texture(path);
 }
 
+const hasPairOp =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_load_texture_pair === "function";
+
+/**
+ * Load a texture together with its normal map. Sprites drawn with the returned
+ * handle (sprites, tilemaps, animations) get directional shading from point
+ * lights and directional lights instead of flat lighting.
+ *
+ * The normal map must have the same layout as the texture (same size, same
+ * frames) and use the common green-up (OpenGL) convention. It loads with
+ * linear filtering. Sprites draw flat until it has loaded, and custom-shader
+ * sprites ignore it. Returns 0 (no texture) in headless mode.
+ *
+ * @param path - PNG path of the color texture (relative to project root).
+ * @param normalPath - PNG path of its tangent-space normal map.
+ * @returns Handle of the color texture, for drawSprite() and friends.
+ *
+ * @example
+ * const rock = loadTexturePair("assets/rock.png", "assets/rock_n.png");
+ * setAmbientLight(0.2, 0.2, 0.25);
+ * addPointLight(300, 200, 200, 1, 0.9, 0.7);
+ * drawSprite({ textureId: rock, x: 100, y: 100, w: 64, h: 64 });
+ */
+export function loadTexturePair(path: string, normalPath: string): TextureId {
+  if (!hasPairOp) return 0;
+  return (globalThis as any).Deno.core.ops.op_load_texture_pair(path, normalPath);
+}
+
 /**
  * Create a 1x1 solid-color texture. Useful for rectangles, placeholder sprites,
  * and UI elements. Cached by name -- creating the same name twice returns the same handle.
@@ -142,3 +171,85 @@ export function isTextureLoaded(path: string): boolean {
 export function getLoadingProgress(): number {
   return loadingProgress;
 }
+
+// --- Streaming ---
+
+const hasStreamOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_take_texture_events === "function";
+
+/**
+ * Progress of a texture file loading in the background, passed to
+ * {@link onTextureLoad} listeners.
+ */
+export type TextureLoadEvent =
+  /** Part of the texture is on the GPU. `progress` is 0-1. */
+  | { id: TextureId; event: "progress"; progress: number }
+  /** The texture is fully uploaded; sprites using it draw from now on. */
+  | { id: TextureId; event: "loaded"; width: number; height: number }
+  /** The file couldn't be read or decoded. */
+  | { id: TextureId; event: "failed"; error: string };
+
+/** Textures still on their way, from {@link getTextureStreamStats}. */
+export type TextureStreamStats = {
+  /** Being read and decoded on worker threads. */
+  decoding: number;
+  /** Decoded and waiting for or partway through their upload. */
+  uploading: number;
+  /** Bytes of decoded pixels not yet on the GPU. */
+  bytesRemaining: number;
+};
+
+const textureListeners: Array<(event: TextureLoadEvent) => void> = [];
+
+/**
+ * Listen for textures loading in the background. loadTexture() returns a
+ * handle right away; the file is decoded on worker threads and uploaded over
+ * the next frames (a few MB per frame, see {@link setTextureUploadBudget}),
+ * so big tilesets don't stall the game. Sprites using the handle are skipped
+ * until its "loaded" event. Listeners run at the start of the frame, before
+ * the {@link onFrame} callback.
+ *
+ * @returns A function that removes the listener.
+ *
+ * @example
+ * const tiles = loadTexture("assets/world-4096.png");
+ * onTextureLoad((e) => {
+ *   if (e.id !== tiles) return;
+ *   if (e.event === "progress") loadingBar = e.progress;
+ *   if (e.event === "loaded") state = "playing";
+ * });
+ */
+export function onTextureLoad(listener: (event: TextureLoadEvent) => void): () => void {
+  textureListeners.push(listener);
+  return () => {
+    const index = textureListeners.indexOf(listener);
+    if (index >= 0) textureListeners.splice(index, 1);
+  };
+}
+
+/** Deliver this frame's texture load events to listeners. Called by the frame loop. @internal */
+export function _dispatchTextureEvents(): void {
+  if (!hasStreamOps) return;
+  const events: TextureLoadEvent[] = JSON.parse((globalThis as any).Deno.core.ops.op_take_texture_events());
+  for (const event of events) {
+    for (const listener of [...textureListeners]) listener(event);
+  }
+}
+
+/**
+ * Set how many bytes of texture pixels are uploaded to the GPU per frame.
+ * Lower values spread big textures over more frames; higher ones load them
+ * sooner at the cost of longer frames. Default: 8 MB (a 4096×4096 texture
+ * takes 8 frames). No-op in headless mode.
+ */
+export function setTextureUploadBudget(bytes: number): void {
+  if (!hasStreamOps) return;
+  (globalThis as any).Deno.core.ops.op_set_texture_upload_budget(bytes);
+}
+
+/** Textures still decoding or uploading. All zeros in headless mode. */
+export function getTextureStreamStats(): TextureStreamStats {
+  if (!hasStreamOps) return { decoding: 0, uploading: 0, bytesRemaining: 0 };
+  return JSON.parse((globalThis as any).Deno.core.ops.op_get_texture_stream_stats());
+}
@@ -1,4 +1,5 @@
 import { describe, it, assert } from "../testing/harness.ts";
+import { createAutotileMapping8, createAutotileRule, EAST, WEST } from "./autotile.ts";
 import {
   // Animated tiles
   registerAnimatedTile,
@@ -12,6 +13,18 @@ import {
   getTileProperties,
   getTileProperty,
   clearTileProperties,
+  // Native auto-tiling and tile animation
+  _packAutotileTable,
+  setTilemapAutotile,
+  setTilemapTileAnimation,
+  // Chunked tilemaps
+  TILEMAP_CHUNK_SIZE,
+  createChunkedTilemap,
+  setTilemapChunk,
+  removeTilemapChunk,
+  getTilemapChunkCount,
+  getMissingTilemapChunks,
+  retainTilemapChunks,
   // Layer types
   type LayeredTilemap,
   type TilemapLayer,
@@ -309,6 +322,38 @@ describe("fillTiles", () => {
   });
 });
 
+// ---------------------------------------------------------------------------
+// Native auto-tiling and tile animation
+// ---------------------------------------------------------------------------
+
+describe("native tilemap auto-tiling", () => {
+  it("packs a 4-neighbor mapping into 16 entries", () => {
+    const mapping = new Map([[EAST, 2], [EAST | WEST, 3]]);
+    const table = _packAutotileTable(createAutotileRule([1], 4, mapping, 9));
+    assert.equal(table.length, 16);
+    assert.equal(table[EAST], 2);
+    assert.equal(table[EAST | WEST], 3);
+    assert.equal(table[0], 0);
+  });
+
+  it("packs an 8-neighbor mapping into 256 entries", () => {
+    const table = _packAutotileTable(createAutotileRule([1], 8, createAutotileMapping8({ 255: 7 }), 0));
+    assert.equal(table.length, 256);
+    assert.equal(table[255], 7);
+  });
+
+  it("returns false in headless mode", () => {
+    assert.equal(setTilemapAutotile(1, 1, createAutotileRule([1], 4, new Map(), 0)), false);
+  });
+});
+
+describe("setTilemapTileAnimation", () => {
+  it("throws on empty frames or non-positive fps", () => {
+    assert.throws(() => setTilemapTileAnimation(1, 5, [], 8), /frames/);
+    assert.throws(() => setTilemapTileAnimation(1, 5, [5, 6], 0), /fps/);
+  });
+});
+
 // ---------------------------------------------------------------------------
 // Export verification
 // ---------------------------------------------------------------------------
@@ -353,3 +398,19 @@ describe("tilemap exports", () => {
     assert.equal(typeof mod.drawTilemap, "function");
   });
 });
+
+describe("chunked tilemaps", () => {
+  it("rejects chunks with too many tiles", () => {
+    assert.throws(() => setTilemapChunk(1, 0, 0, new Uint16Array(TILEMAP_CHUNK_SIZE * TILEMAP_CHUNK_SIZE + 1)), /1024/);
+  });
+
+  it("is inert in headless mode", () => {
+    const id = createChunkedTilemap({ textureId: 1, tileSize: 16, atlasColumns: 4, atlasRows: 4 });
+    assert.equal(id, 0);
+    setTilemapChunk(id, -1, 2, [1, 2, 3]);
+    assert.equal(getTilemapChunkCount(id), 0);
+    assert.equal(removeTilemapChunk(id, -1, 2), false);
+    assert.deepEqual(getMissingTilemapChunks(id, 0, 0, 32), []);
+    assert.equal(retainTilemapChunks(id, 0, 0, 64), 0);
+  });
+});
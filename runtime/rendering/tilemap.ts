@@ -1,4 +1,5 @@
-import type { TilemapId, TilemapOptions } from "./types.ts";
+import type { ChunkedTilemapOptions, TilemapId, TilemapOptions } from "./types.ts";
+import type { AutotileRule } from "./autotile.ts";
 import { _logDrawCall } from "../testing/visual.ts";
 
 const hasRenderOps =
@@ -580,3 +581,217 @@ export function fillLayerTiles(
   if (!layer) return;
   fillTiles(layer.tilemapId, startX, startY, endX, endY, tileId);
 }
+
+// ---------------------------------------------------------------------------
+// Native auto-tiling and tile animation
+// ---------------------------------------------------------------------------
+
+/**
+ * @internal Pack a rule's mapping into the per-bitmask table the renderer
+ * reads: 16 entries in 4-neighbor mode, 256 in 8-neighbor mode. Unmapped
+ * masks are 0 (use the fallback).
+ */
+export function _packAutotileTable(rule: AutotileRule): Uint16Array {
+  const table = new Uint16Array(rule.mode === 4 ? 16 : 256);
+  for (const [mask, tileId] of rule.mapping) {
+    if (mask >= 0 && mask < table.length) table[mask] = tileId;
+  }
+  return table;
+}
+
+/**
+ * Auto-tile a terrain in the renderer. Cells holding `terrainTileId` are
+ * drawn with the tile their neighbor bitmask maps to, recomputed every draw,
+ * so edits never need a re-tiling pass. Neighbors count when they hold the
+ * terrain tile or one of `rule.memberTileIds`. `getTile` still returns the
+ * terrain tile. Tile animations apply to the resolved tile.
+ *
+ * @param id - Tilemap handle from createTilemap().
+ * @param terrainTileId - Tile ID you place for this terrain.
+ * @param rule - Bitmask mode, mapping and fallback (see createAutotileRule()).
+ *   A fallback of 0 draws the terrain tile itself.
+ * @returns False for an unknown tilemap or in headless mode.
+ */
+export function setTilemapAutotile(
+  id: TilemapId,
+  terrainTileId: number,
+  rule: AutotileRule,
+): boolean {
+  if (!hasRenderOps) return false;
+  const connects = Uint16Array.from(
+    [...rule.memberTileIds].filter((tileId) => tileId !== terrainTileId),
+  );
+  return (globalThis as any).Deno.core.ops.op_set_autotile_rule(
+    id,
+    terrainTileId,
+    rule.mode,
+    rule.fallbackTileId,
+    new Uint8Array(_packAutotileTable(rule).buffer),
+    new Uint8Array(connects.buffer),
+  );
+}
+
+/** Stop auto-tiling a terrain; its cells draw the terrain tile again. */
+export function clearTilemapAutotile(id: TilemapId, terrainTileId: number): void {
+  if (!hasRenderOps) return;
+  (globalThis as any).Deno.core.ops.op_clear_autotile_rule(id, terrainTileId);
+}
+
+/**
+ * Animate a tile in the renderer: every cell of this tilemap that displays
+ * `tileId` cycles through `frames`, advanced by the engine each frame (paused
+ * with the simulation). Unlike registerAnimatedTile(), no per-frame tile
+ * rewrites happen in TypeScript. No-op in headless mode.
+ *
+ * @param id - Tilemap handle from createTilemap().
+ * @param tileId - Tile ID to animate (placed or produced by auto-tiling).
+ * @param frames - Tile IDs to cycle through.
+ * @param fps - Frames per second.
+ */
+export function setTilemapTileAnimation(
+  id: TilemapId,
+  tileId: number,
+  frames: number[],
+  fps: number,
+): void {
+  if (frames.length === 0) {
+    throw new Error("setTilemapTileAnimation: frames must not be empty");
+  }
+  if (fps <= 0) {
+    throw new Error("setTilemapTileAnimation: fps must be positive");
+  }
+  if (!hasRenderOps) return;
+  const packed = Uint16Array.from(frames);
+  (globalThis as any).Deno.core.ops.op_set_tile_animation(id, tileId, fps, new Uint8Array(packed.buffer));
+}
+
+/** Stop animating a tile set with setTilemapTileAnimation(). */
+export function clearTilemapTileAnimation(id: TilemapId, tileId: number): void {
+  if (!hasRenderOps) return;
+  (globalThis as any).Deno.core.ops.op_set_tile_animation(id, tileId, 0, new Uint8Array(0));
+}
+
+// ---------------------------------------------------------------------------
+// Chunked tilemaps
+// ---------------------------------------------------------------------------
+
+/** Tiles per side of a chunk in chunked tilemaps. */
+export const TILEMAP_CHUNK_SIZE = 32;
+
+/** A chunk position: chunk (cx, cy) covers cells cx*32..cx*32+31 by cy*32..cy*32+31. */
+export type TilemapChunkCoord = { cx: number; cy: number };
+
+/**
+ * Create an unbounded tilemap stored in 32x32 chunks that are only allocated
+ * when written, so huge or endless worlds cost memory only where tiles are.
+ * Cells may be negative. setTile(), getTile(), drawTilemap() and the native
+ * auto-tiling and tile animation work on it as on any tilemap; drawing only
+ * visits the allocated chunks in view. Returns 0 in headless mode.
+ *
+ * @example
+ * const ground = createChunkedTilemap({ textureId: atlas, tileSize: 16, atlasColumns: 8, atlasRows: 8 });
+ * for (const { cx, cy } of getMissingTilemapChunks(ground, 0, 0, 32)) {
+ *   setTilemapChunk(ground, cx, cy, generateChunk(cx, cy));
+ * }
+ * retainTilemapChunks(ground, 0, 0, 96);
+ * drawTilemap(ground);
+ */
+export function createChunkedTilemap(opts: ChunkedTilemapOptions): TilemapId {
+  if (!hasRenderOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_chunked_tilemap(
+    opts.textureId,
+    opts.tileSize,
+    opts.atlasColumns,
+    opts.atlasRows,
+  );
+}
+
+/**
+ * Write a whole 32x32 chunk in one call: `tiles` holds up to 1024 tile IDs,
+ * row-major, and missing entries are 0. On a chunked tilemap the chunk is
+ * allocated even if every tile is 0, so it stops counting as missing. On a
+ * dense tilemap it fills the cells at (cx * 32, cy * 32), clipped to the map.
+ * No-op in headless mode.
+ *
+ * @throws If `tiles` holds more than 1024 entries.
+ */
+export function setTilemapChunk(
+  id: TilemapId,
+  cx: number,
+  cy: number,
+  tiles: Uint16Array | number[],
+): void {
+  if (tiles.length > TILEMAP_CHUNK_SIZE * TILEMAP_CHUNK_SIZE) {
+    throw new Error(`setTilemapChunk: expected at most ${TILEMAP_CHUNK_SIZE * TILEMAP_CHUNK_SIZE} tiles, got ${tiles.length}`);
+  }
+  if (!hasRenderOps) return;
+  const packed = tiles instanceof Uint16Array ? tiles : Uint16Array.from(tiles);
+  (globalThis as any).Deno.core.ops.op_set_tilemap_chunk(
+    id,
+    cx,
+    cy,
+    new Uint8Array(packed.buffer, packed.byteOffset, packed.byteLength),
+  );
+}
+
+/**
+ * Free a chunk of a chunked tilemap; its cells read as 0 again. On an
+ * attached world, unsaved edits in it are saved first.
+ *
+ * @returns False if the chunk wasn't allocated, or in headless mode.
+ */
+export function removeTilemapChunk(id: TilemapId, cx: number, cy: number): boolean {
+  if (!hasRenderOps) return false;
+  return (globalThis as any).Deno.core.ops.op_remove_tilemap_chunk(id, cx, cy);
+}
+
+/** Number of allocated chunks of a chunked tilemap. 0 for dense tilemaps and in headless mode. */
+export function getTilemapChunkCount(id: TilemapId): number {
+  if (!hasRenderOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_get_tilemap_chunk_count(id);
+}
+
+/**
+ * Chunks of a chunked tilemap that the camera sees, plus `margin` tiles on
+ * every side, and that aren't allocated yet: the ones to generate this
+ * frame, row by row. On an attached world, saved chunks are loaded first and
+ * never returned. Empty in headless mode.
+ *
+ * @param x - World X the tilemap is drawn at. Default: 0.
+ * @param y - World Y the tilemap is drawn at. Default: 0.
+ * @param margin - Extra tiles around the view, to generate ahead. Default: 0.
+ */
+export function getMissingTilemapChunks(
+  id: TilemapId,
+  x: number = 0,
+  y: number = 0,
+  margin: number = 0,
+): TilemapChunkCoord[] {
+  if (!hasRenderOps) return [];
+  const flat: number[] = (globalThis as any).Deno.core.ops.op_get_missing_tilemap_chunks(id, x, y, margin);
+  const chunks: TilemapChunkCoord[] = [];
+  for (let i = 0; i + 1 < flat.length; i += 2) {
+    chunks.push({ cx: flat[i], cy: flat[i + 1] });
+  }
+  return chunks;
+}
+
+/**
+ * Free every chunk of a chunked tilemap outside the camera view plus
+ * `margin` tiles, keeping memory flat as the camera travels. On an attached
+ * world, edited chunks are saved first and load again when they come back
+ * into range; otherwise their tiles are gone. Use a larger margin than
+ * getMissingTilemapChunks() so chunks at the edge aren't regenerated every
+ * frame.
+ *
+ * @returns Number of chunks freed. 0 in headless mode.
+ */
+export function retainTilemapChunks(
+  id: TilemapId,
+  x: number = 0,
+  y: number = 0,
+  margin: number = 0,
+): number {
+  if (!hasRenderOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_retain_tilemap_chunks(id, x, y, margin);
+}
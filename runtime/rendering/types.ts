@@ -4,6 +4,18 @@
  */
 export type TextureId = number;
 
+/** Named sprite origins: the point of the sprite that rotates in place and is sorted by. */
+export type SpriteOrigin =
+  | "top-left"
+  | "top-center"
+  | "top-right"
+  | "center-left"
+  | "center"
+  | "center-right"
+  | "bottom-left"
+  | "bottom-center"
+  | "bottom-right";
+
 /**
  * Options for drawing a sprite via {@link drawSprite}.
  *
@@ -60,8 +72,14 @@ export type SpriteOptions = {
   rotation?: number;
   /** X origin for rotation, 0-1 relative to sprite width. Default: 0.5 (center). */
   originX?: number;
-  /** Y origin for rotation, 0-1 relative to sprite height. Default: 0.5 (center). */
+  /** Y origin for rotation, 0-1 relative to sprite height. Also the sort point on y-sorted layers (see setLayerYSort). Default: 0.5 (center). */
   originY?: number;
+  /**
+   * Named origin instead of originX/originY, e.g. "bottom-center" for a
+   * character's feet. An explicit originX or originY wins over the preset.
+   * Default: "center".
+   */
+  origin?: SpriteOrigin;
   /** Mirror the sprite horizontally. Default: false. */
   flipX?: boolean;
   /** Mirror the sprite vertically. Default: false. */
@@ -83,6 +101,17 @@ export type SpriteOptions = {
   screenSpace?: boolean;
   /** Custom shader handle from createShaderFromSource(). Default: 0 (built-in shader). */
   shaderId?: number;
+  /**
+   * Key this sprite is hit-flashed by (see {@link flashSprite}), e.g. its
+   * entity ID. Default: none.
+   */
+  flashKey?: number | string;
+  /**
+   * Round the sprite's position to whole screen pixels of the main camera
+   * (see setPixelSnap), so pixel art does not shimmer when the camera moves
+   * by fractions of a pixel. Default: the setPixelSnap setting.
+   */
+  pixelSnap?: boolean;
   /**
    * Simple 2D shadow: draws a squashed, tinted duplicate beneath the sprite.
    * No GPU changes — pure sprite duplication with transform.
@@ -97,6 +126,17 @@ export type SpriteOptions = {
     /** Vertical scale for the shadow (0.5 = squashed). Default: 0.5. */
     scaleY?: number;
   };
+  /**
+   * Outline around the sprite's opaque pixels (its alpha silhouette, not the
+   * quad), e.g. for selection highlights. Drawn just behind the sprite with
+   * the same blend mode and opacity. Ignored with a custom `shaderId`.
+   */
+  outline?: {
+    /** Outline color. */
+    color: { r: number; g: number; b: number; a: number };
+    /** Outline width in world units. Default: 1. */
+    thickness?: number;
+  };
 };
 
 /** Camera state returned by {@link getCamera}. */
@@ -135,6 +175,51 @@ export type KeyName =
   | ">" | "<" | "?" | ":" | "\"" | "{" | "}" | "|" | "+" | "_" | "~"
   | "!" | "@" | "#" | "$" | "%" | "^" | "&" | "*" | "(" | ")";
 
+/**
+ * A binding of an engine input action: a key name, a gamepad/mouse shorthand
+ * (`"GamepadA"`, `"MouseLeft"`), or an object. `scale` (default 1) is what a
+ * held key or button adds to the action's value; axes add their value times it.
+ */
+export type ActionBinding =
+  | string
+  | { type: "key"; key: string; scale?: number }
+  | { type: "mouseButton"; button: number; scale?: number }
+  | { type: "gamepadButton"; button: string; scale?: number }
+  | {
+      type: "gamepadAxis";
+      axis: string;
+      /** 1 or -1 reads only that half of the axis; 0 (default) reads both. */
+      direction?: 1 | -1 | 0;
+      /** Travel ignored near the center, 0-1. Default: 0.2. */
+      deadzone?: number;
+      scale?: number;
+    };
+
+/**
+ * A keyboard, text or wheel event from {@link pollInputEvents}. `key` uses the
+ * {@link KeyName} names; `code` is the physical key ("KeyA", "Digit1")
+ * whatever the layout, and is "" for releases sent when the window loses focus.
+ */
+export type InputEvent =
+  | { type: "keydown"; key: string; code: string; repeat: boolean }
+  | { type: "keyup"; key: string; code: string }
+  | { type: "text"; text: string }
+  | {
+      type: "composition";
+      /** Uncommitted IME text; "" when composition ends. */
+      text: string;
+      /** Cursor as a [start, end] range of `text` in UTF-16 units, or null when hidden. */
+      cursor: [number, number] | null;
+    }
+  | {
+      type: "wheel";
+      /** Positive scrolls right. */
+      dx: number;
+      /** Positive scrolls down. */
+      dy: number;
+      mode: "line" | "pixel";
+    };
+
 /** Mouse position in screen or world coordinates. */
 export type MousePosition = {
   /** X position in pixels (screen) or world units (world). */
@@ -164,3 +249,6 @@ export type TilemapOptions = {
   /** Number of tile rows in the texture atlas. Must be a positive integer. */
   atlasRows: number;
 };
+
+/** Options for createChunkedTilemap(): a TilemapOptions without a grid size. */
+export type ChunkedTilemapOptions = Omit<TilemapOptions, "width" | "height">;
@@ -0,0 +1,16 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { createViewport, setViewportCamera, setViewportRect, destroyViewport } from "./viewport.ts";
+
+describe("Viewports (headless)", () => {
+  it("createViewport returns 0 in headless mode", () => {
+    assert.equal(createViewport(0, 0, 400, 600), 0);
+  });
+
+  it("setViewportCamera / setViewportRect / destroyViewport are no-ops in headless", () => {
+    const id = createViewport(400, 0, 400, 600);
+    setViewportCamera(id, { x: 100, y: 50, zoom: 2, rotation: 0.5, layers: [0, 99] });
+    setViewportCamera(id, {});
+    setViewportRect(id, 0, 300, 800, 300);
+    destroyViewport(id);
+  });
+});
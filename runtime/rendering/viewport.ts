@@ -0,0 +1,90 @@
+const hasViewportOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_create_viewport === "function";
+
+/** Opaque handle for a split-screen viewport. */
+export type ViewportId = number;
+
+/** Camera for {@link setViewportCamera}. Every field is optional. */
+export type ViewportCamera = {
+  /** World position of the viewport's top-left corner. Default: 0. */
+  x?: number;
+  /** Default: 0. */
+  y?: number;
+  /** Larger values zoom in. Default: 1. */
+  zoom?: number;
+  /** Roll in radians around the viewport's center; positive turns the camera clockwise. Default: 0. */
+  rotation?: number;
+  /**
+   * Inclusive layer range drawn in the viewport. Layers above every
+   * viewport's range are drawn once over the whole window with the main
+   * camera, e.g. `[0, 99]` leaves layers from 100 up for a shared HUD.
+   * Default: all layers.
+   */
+  layers?: [number, number];
+};
+
+/**
+ * Add a split-screen viewport covering a rect of the window, in logical
+ * pixels from the top-left. Once any viewport exists, the scene is drawn
+ * once per viewport (in creation order) through that viewport's camera and
+ * clipped to its rect, instead of once with the main camera. Sprites,
+ * shapes, SDF shapes, decals and particles appear in every viewport that
+ * shows their layer; the background fill, masks, lighting GI and
+ * post-processing follow the main camera. Screen-space sprites are placed
+ * with the main camera, so draw HUDs on layers above the viewports.
+ *
+ * Returns 0 in headless mode.
+ *
+ * @example
+ * // Two players side by side, sharing a HUD on layers 100+
+ * const left = createViewport(0, 0, 400, 600);
+ * const right = createViewport(400, 0, 400, 600);
+ * onFrame(() => {
+ *   setViewportCamera(left, { x: p1.x - 200, y: p1.y - 300, layers: [0, 99] });
+ *   setViewportCamera(right, { x: p2.x - 200, y: p2.y - 300, layers: [0, 99] });
+ * });
+ */
+export function createViewport(x: number, y: number, w: number, h: number): ViewportId {
+  if (!hasViewportOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_create_viewport(x, y, w, h);
+}
+
+/**
+ * Set the camera a viewport draws with. Fields left out take their
+ * defaults, not their previous values. No-op in headless mode.
+ *
+ * @param id Handle returned by `createViewport()`
+ * @param camera Position, zoom, rotation and layer range
+ */
+export function setViewportCamera(id: ViewportId, camera: ViewportCamera): void {
+  if (!hasViewportOps) return;
+  const [min, max] = camera.layers ?? [-2147483648, 2147483647];
+  (globalThis as any).Deno.core.ops.op_set_viewport_camera(
+    id >>> 0,
+    camera.x ?? 0,
+    camera.y ?? 0,
+    camera.zoom ?? 1,
+    camera.rotation ?? 0,
+    min | 0,
+    max | 0,
+  );
+}
+
+/**
+ * Move or resize a viewport, e.g. when the window is resized or a player
+ * joins. No-op in headless mode.
+ */
+export function setViewportRect(id: ViewportId, x: number, y: number, w: number, h: number): void {
+  if (!hasViewportOps) return;
+  (globalThis as any).Deno.core.ops.op_set_viewport_rect(id >>> 0, x, y, w, h);
+}
+
+/**
+ * Remove a viewport. With none left, the scene is drawn once with the main
+ * camera again. No-op in headless mode.
+ */
+export function destroyViewport(id: ViewportId): void {
+  if (!hasViewportOps) return;
+  (globalThis as any).Deno.core.ops.op_destroy_viewport(id >>> 0);
+}
@@ -0,0 +1,21 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { createLayeredTilemap } from "./tilemap.ts";
+import { attachWorld, attachLayeredWorld, detachWorld, flushWorld, clearWorld, setWorldAutosave } from "./world.ts";
+
+describe("World persistence", () => {
+  it("is a no-op in headless mode", () => {
+    assert.equal(attachWorld(1, "overworld"), false);
+    detachWorld(1);
+    setWorldAutosave(5);
+    assert.equal(flushWorld(), 0);
+    assert.equal(clearWorld(), false);
+  });
+
+  it("attaches every layer of a layered tilemap", () => {
+    const map = createLayeredTilemap(
+      { textureId: 1, width: 4, height: 4, tileSize: 16, atlasColumns: 4, atlasRows: 4 },
+      [["ground", {}], ["walls", {}]],
+    );
+    assert.equal(attachLayeredWorld(map, "cave"), false);
+  });
+});
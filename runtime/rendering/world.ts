@@ -0,0 +1,77 @@
+/**
+ * Persistent worlds for tilemaps.
+ *
+ * Attach a tilemap to a named world and every tile edited from then on is
+ * saved under `.arcane/world/<name>/`, in compressed 32×32-tile chunks. Only
+ * edited chunks are stored. Saved chunks are loaded lazily, when a tile in
+ * them is first read, written or drawn, and replace what the game generated
+ * there. Edits are written every 10 seconds (see {@link setWorldAutosave}),
+ * on {@link flushWorld}, and when the game exits.
+ *
+ * Generate the base map first, then attach: edits made before attaching are
+ * not saved. All functions are no-ops in headless mode.
+ */
+
+import type { TilemapId } from "./types.ts";
+import type { LayeredTilemap } from "./tilemap.ts";
+
+const hasWorldOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_attach_world === "function";
+
+/**
+ * Persist a tilemap's edits as world `name` (letters, digits, `_` and `-`).
+ * Returns false for invalid names or in headless mode.
+ *
+ * @example
+ * const map = createTilemap({ textureId: atlas, width: 2048, height: 512, tileSize: 16, atlasColumns: 16, atlasRows: 16 });
+ * generateTerrain(map);              // same seed every run
+ * attachWorld(map, "overworld");      // player edits load over it
+ */
+export function attachWorld(tilemapId: TilemapId, name: string): boolean {
+  if (!hasWorldOps) return false;
+  return (globalThis as any).Deno.core.ops.op_attach_world(tilemapId, name);
+}
+
+/**
+ * Persist every layer of a layered tilemap, as worlds `<name>-<layer>`.
+ * Returns false if any layer could not be attached.
+ */
+export function attachLayeredWorld(tilemap: LayeredTilemap, name: string): boolean {
+  let ok = true;
+  for (const [layerName, layer] of tilemap.layers) {
+    ok = attachWorld(layer.tilemapId, `${name}-${layerName}`) && ok;
+  }
+  return ok;
+}
+
+/** Stop persisting a tilemap. Edits not yet saved are dropped; {@link flushWorld} first to keep them. */
+export function detachWorld(tilemapId: TilemapId): void {
+  if (!hasWorldOps) return;
+  (globalThis as any).Deno.core.ops.op_detach_world(tilemapId);
+}
+
+/**
+ * Save all edited chunks now (e.g. before a level transition). Returns the
+ * number of chunks written, or -1 if writing failed.
+ */
+export function flushWorld(): number {
+  if (!hasWorldOps) return 0;
+  return (globalThis as any).Deno.core.ops.op_flush_world();
+}
+
+/**
+ * Delete the saved chunks of world `name`, or of every world when omitted
+ * (e.g. for "New Game"). Attached tilemaps keep their current tiles; recreate
+ * them for a fresh map.
+ */
+export function clearWorld(name?: string): boolean {
+  if (!hasWorldOps) return false;
+  return (globalThis as any).Deno.core.ops.op_clear_world(name ?? "");
+}
+
+/** Seconds between automatic saves of edited chunks (default 10). 0 saves only on {@link flushWorld} and exit. */
+export function setWorldAutosave(seconds: number): void {
+  if (!hasWorldOps) return;
+  (globalThis as any).Deno.core.ops.op_set_world_autosave(seconds);
+}
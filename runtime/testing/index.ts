@@ -11,6 +11,8 @@ export {
   emptyFrame,
   replayWithSnapshots,
   compareReplaySnapshots,
+  saveReplay,
+  loadReplay,
 } from "./replay.ts";
 export type {
   InputFrame,
@@ -25,6 +27,8 @@ export type {
   SnapshotCaptureFn,
   ReplayWithSnapshotsOptions,
   ReplayWithSnapshotsResult,
+  ReplayInfo,
+  LoadedReplay,
 } from "./replay.ts";
 
 // World Snapshots
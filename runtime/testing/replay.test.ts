@@ -6,6 +6,9 @@ import {
   diffReplays,
   createRecording,
   emptyFrame,
+  saveReplay,
+  loadReplay,
+  _parseLoadedReplay,
   type InputFrame,
   type UpdateFn,
 } from "./replay.ts";
@@ -367,3 +370,43 @@ describe("diffReplays", () => {
     assert.equal(result.stateB!.y, 5);
   });
 });
+
+// --- Replay files ---
+
+describe("replay files", () => {
+  it("parses the header and recording of a loaded replay", () => {
+    const recording = createRecording([emptyFrame({ keysDown: ["ArrowRight"] }), emptyFrame()]);
+    const loaded = _parseLoadedReplay(JSON.stringify({
+      ok: true,
+      header: {
+        engine_version: "0.23.1",
+        content_hash: "00ff00ff00ff00ff",
+        frames: 2,
+        duration: 2 / 60,
+        created: 1700000000000,
+        thumbnail_bytes: 812,
+      },
+      recording,
+    }));
+    assert.equal(loaded.info.engineVersion, "0.23.1");
+    assert.equal(loaded.info.contentHash, "00ff00ff00ff00ff");
+    assert.equal(loaded.info.thumbnailBytes, 812);
+    assert.equal(loaded.recording.frameCount, 2);
+    assert.deepEqual(replay(loaded.recording, updateFn, initialState).finalState, { x: 5, y: 0, jumps: 0 });
+  });
+
+  it("throws the reason a replay is refused", () => {
+    let message = "";
+    try {
+      _parseLoadedReplay(JSON.stringify({ ok: false, error: "replay would desync: the game content changed" }));
+    } catch (e) {
+      message = (e as Error).message;
+    }
+    assert.equal(message, "replay would desync: the game content changed");
+  });
+
+  it("is inert in headless mode", () => {
+    assert.equal(saveReplay("replays/run.arcreplay", createRecording([])), false);
+    assert.equal(loadReplay("replays/run.arcreplay"), null);
+  });
+});
@@ -196,6 +196,8 @@ function findDifferingPaths(
  * @returns A RecordingSession handle for recording frames and snapshots.
  */
 export function startRecording<S = unknown>(defaultDt: number = 1 / 60): RecordingSession<S> {
+  // The first rendered frame becomes the thumbnail of a saved replay
+  if (hasReplayFileOps) (globalThis as any).Deno.core.ops.op_request_replay_thumbnail();
   const frames: InputFrame[] = [];
   const snapshots: StateSnapshot<S>[] = [];
   let currentFrame = 0;
@@ -425,6 +427,89 @@ export function emptyFrame(overrides: Partial<InputFrame> = {}): InputFrame {
   };
 }
 
+// ---------------------------------------------------------------------------
+// Replay files
+// ---------------------------------------------------------------------------
+
+const hasReplayFileOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_save_replay === "function";
+
+/** Header of a replay file. */
+export type ReplayInfo = Readonly<{
+  /** Engine version the replay was recorded with. */
+  engineVersion: string;
+  /** Hash of the game's files when recorded. */
+  contentHash: string;
+  /** Number of recorded frames. */
+  frames: number;
+  /** Game time covered, in seconds. */
+  duration: number;
+  /** When it was recorded, in Unix milliseconds. */
+  created: number;
+  /** Size of the first-frame thumbnail PNG in bytes, 0 without one. */
+  thumbnailBytes: number;
+}>;
+
+/** A replay file read by {@link loadReplay}. */
+export type LoadedReplay<S = unknown> = Readonly<{
+  info: ReplayInfo;
+  recording: Recording<S>;
+}>;
+
+/** @internal Parse op_load_replay's JSON, throwing its error. */
+export function _parseLoadedReplay<S>(json: string): LoadedReplay<S> {
+  const raw = JSON.parse(json);
+  if (!raw.ok) throw new Error(raw.error);
+  const h = raw.header;
+  return {
+    info: {
+      engineVersion: h.engine_version,
+      contentHash: h.content_hash,
+      frames: h.frames,
+      duration: h.duration,
+      created: h.created,
+      thumbnailBytes: h.thumbnail_bytes,
+    },
+    recording: raw.recording,
+  };
+}
+
+/**
+ * Save a recording as a replay file (`.arcreplay`) for sharing. The header
+ * holds the engine version, a hash of the game's files, the duration and a
+ * thumbnail of the first frame rendered after {@link startRecording}. Inspect
+ * it with `arcane replay info <file>`.
+ *
+ * @param path - File path relative to the game directory, e.g. `"replays/boss.arcreplay"`.
+ * @returns False in headless mode, where there is no game directory.
+ * @throws If the file can't be written.
+ */
+export function saveReplay<S>(path: string, recording: Recording<S>): boolean {
+  if (!hasReplayFileOps) return false;
+  const error: string = (globalThis as any).Deno.core.ops.op_save_replay(path, JSON.stringify(recording));
+  if (error) throw new Error(`saveReplay: ${error}`);
+  return true;
+}
+
+/**
+ * Read a replay file written by {@link saveReplay}.
+ *
+ * Input replays only reproduce a session on the engine and game they were
+ * recorded with, so a replay from another engine version, or recorded before
+ * the game's files changed, is refused with the reason instead of silently
+ * desyncing. Pass `force` to load it anyway.
+ *
+ * @param path - File path relative to the game directory.
+ * @returns The header and recording, or null in headless mode.
+ * @throws If the file is missing or not a replay, or would desync (without `force`).
+ */
+export function loadReplay<S = unknown>(path: string, options: { force?: boolean } = {}): LoadedReplay<S> | null {
+  if (!hasReplayFileOps) return null;
+  const json: string = (globalThis as any).Deno.core.ops.op_load_replay(path, options.force ?? false);
+  return _parseLoadedReplay<S>(json);
+}
+
 // ---------------------------------------------------------------------------
 // WorldSnapshot integration
 // ---------------------------------------------------------------------------
@@ -0,0 +1,66 @@
+/**
+ * Tests for shared easing curves
+ */
+
+import { describe, it, assert } from "../testing/harness.ts";
+import { defineEasingCurve, defineBezierCurve, sampleCurve, curveId } from "./curve.ts";
+import { easeOutQuad, easeOutBounce } from "./easing.ts";
+import { tween, updateTweens, stopAllTweens } from "./tween.ts";
+
+function near(a: number, b: number, eps = 1e-3): boolean {
+  return Math.abs(a - b) < eps;
+}
+
+describe("defineEasingCurve", () => {
+  it("matches the TS easing of the same name", () => {
+    const curve = defineEasingCurve("easeOutQuad");
+    for (const t of [0, 0.1, 0.5, 0.9, 1]) {
+      assert.ok(near(curve(t), easeOutQuad(t)), `t=${t}`);
+    }
+  });
+
+  it("keeps bouncy curves close between cached samples", () => {
+    const curve = defineEasingCurve("easeOutBounce");
+    for (let i = 0; i <= 100; i++) {
+      assert.ok(near(curve(i / 100), easeOutBounce(i / 100), 5e-3));
+    }
+  });
+});
+
+describe("defineBezierCurve", () => {
+  it("starts at 0 and ends at 1", () => {
+    const curve = defineBezierCurve(0.25, 0.1, 0.25, 1);
+    assert.ok(near(curve(0), 0));
+    assert.ok(near(curve(1), 1));
+  });
+
+  it("samples CSS ease at the midpoint (linear when headless)", () => {
+    const curve = defineBezierCurve(0.25, 0.1, 0.25, 1);
+    const mid = sampleCurve(curve, [0.5])[0];
+    assert.ok(curve.curveId === 0 ? near(mid, 0.5) : near(mid, 0.8024));
+  });
+});
+
+describe("sampleCurve", () => {
+  it("returns one value per t", () => {
+    const curve = defineEasingCurve("easeInQuad");
+    const values = sampleCurve(curve, [0, 0.5, 1]);
+    assert.equal(values.length, 3);
+    assert.ok(near(values[1], 0.25));
+  });
+});
+
+describe("curves as tween easings", () => {
+  it("drives a tween like the easing function", () => {
+    stopAllTweens();
+    const obj = { x: 0 };
+    tween(obj, { x: 100 }, 1, { easing: defineEasingCurve("easeInQuad") });
+    updateTweens(0.5);
+    assert.ok(near(obj.x, 25, 0.1));
+    stopAllTweens();
+  });
+
+  it("curveId is 0 for no curve", () => {
+    assert.equal(curveId(undefined), 0);
+  });
+});
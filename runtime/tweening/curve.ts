@@ -0,0 +1,96 @@
+/**
+ * Shared easing curves, defined once in Rust and referenced by ID.
+ *
+ * A {@link Curve} is an {@link EasingFunction} (so it works anywhere easings
+ * do: `tween()`, `zoomTo()`, `sequence()`) that also carries a Rust-side curve
+ * ID, so the same curve can shape light pulses and audio fades, which run in
+ * the renderer and on the audio thread.
+ *
+ * Outside the Arcane runtime (no ops), preset curves fall back to the TS
+ * easing of the same name and bezier curves to linear; their ID is 0.
+ *
+ * @example
+ * ```ts
+ * const snappy = defineBezierCurve(0.2, 0.9, 0.3, 1.2);   // overshoots a little
+ * const slow = defineEasingCurve("easeInOutSine");
+ *
+ * tween(panel, { y: 0 }, 0.4, { easing: snappy });
+ * zoomTo(2, 0.6, slow);
+ * fadeOutInstance(music, 2, slow);
+ * addPointLight(x, y, 120, 1, 0.6, 0.2, 1, { pulse: 0.5, pulseCurve: slow });
+ * ```
+ */
+
+import type { EasingFunction } from "./types.ts";
+import { Easing, linear } from "./easing.ts";
+
+/** An easing function backed by a Rust-side curve. `curveId` is 0 when headless. */
+export type Curve = EasingFunction & { readonly curveId: number };
+
+/** Names of the preset easings, e.g. "easeOutQuad". */
+export type EasingName = keyof typeof Easing;
+
+const hasCurveOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_curve_sample === "function";
+
+/** Samples cached per curve for TS-side evaluation; linear in between. */
+const TABLE_SIZE = 256;
+
+function makeCurve(id: number, fallback: EasingFunction): Curve {
+  if (id === 0) return Object.assign((t: number) => fallback(t), { curveId: 0 });
+  const ts = new Float32Array(TABLE_SIZE);
+  for (let i = 0; i < TABLE_SIZE; i++) ts[i] = i / (TABLE_SIZE - 1);
+  const table = sampleCurveIds(id, ts);
+  const ease = (t: number): number => {
+    const x = Math.min(Math.max(t, 0), 1) * (TABLE_SIZE - 1);
+    const i = Math.min(Math.floor(x), TABLE_SIZE - 2);
+    return table[i] + (table[i + 1] - table[i]) * (x - i);
+  };
+  return Object.assign(ease, { curveId: id });
+}
+
+function sampleCurveIds(id: number, ts: Float32Array): number[] {
+  return (globalThis as any).Deno.core.ops.op_curve_sample(id, new Uint8Array(ts.buffer, ts.byteOffset, ts.byteLength));
+}
+
+/**
+ * Define a curve from a preset easing, by name.
+ *
+ * @param name - Any key of {@link Easing}, e.g. "easeOutBack".
+ */
+export function defineEasingCurve(name: EasingName): Curve {
+  const fallback = Easing[name] ?? linear;
+  if (!hasCurveOps) return makeCurve(0, fallback);
+  return makeCurve((globalThis as any).Deno.core.ops.op_define_easing_curve(name), fallback);
+}
+
+/**
+ * Define a cubic bezier curve from (0, 0) to (1, 1), as CSS
+ * `cubic-bezier(x1, y1, x2, y2)`. x values are clamped to 0-1; y values
+ * outside 0-1 overshoot.
+ *
+ * @example
+ * const ease = defineBezierCurve(0.25, 0.1, 0.25, 1);   // CSS "ease"
+ */
+export function defineBezierCurve(x1: number, y1: number, x2: number, y2: number): Curve {
+  if (!hasCurveOps) return makeCurve(0, linear);
+  return makeCurve((globalThis as any).Deno.core.ops.op_define_bezier_curve(x1, y1, x2, y2), linear);
+}
+
+/**
+ * Sample a curve at many points at once (exactly, in Rust). `t` values are
+ * clamped to 0-1. Headless, evaluates the curve's TS fallback.
+ *
+ * @example
+ * const heights = sampleCurve(slow, [0, 0.25, 0.5, 0.75, 1]);
+ */
+export function sampleCurve(curve: Curve, ts: ArrayLike<number>): number[] {
+  if (!hasCurveOps || curve.curveId === 0) return Array.from(ts, (t) => curve(t));
+  return sampleCurveIds(curve.curveId, Float32Array.from(ts));
+}
+
+/** Rust-side ID of a curve, or 0 for none. For op arguments. */
+export function curveId(curve: Curve | undefined): number {
+  return curve?.curveId ?? 0;
+}
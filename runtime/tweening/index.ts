@@ -61,6 +61,9 @@ export {
   Easing,
 } from "./easing.ts";
 
+export type { Curve, EasingName } from "./curve.ts";
+export { defineEasingCurve, defineBezierCurve, sampleCurve, curveId } from "./curve.ts";
+
 export type { TweenConfig } from "./chain.ts";
 export { sequence, parallel, stagger } from "./chain.ts";
 
@@ -0,0 +1,92 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import {
+  srgbToLinear,
+  linearToSrgb,
+  colorToHsv,
+  hsvToColor,
+  colorToOklch,
+  oklchToColor,
+  mixColors,
+  colorRamp,
+  rampToCosinePalette,
+} from "./color-space.ts";
+
+const RED = { r: 1, g: 0, b: 0, a: 1 };
+const GREEN = { r: 0, g: 1, b: 0, a: 1 };
+const BLUE = { r: 0, g: 0, b: 1, a: 0.5 };
+
+function near(a: number, b: number, eps = 1e-3): boolean {
+  return Math.abs(a - b) < eps;
+}
+
+describe("color space conversions", () => {
+  it("sRGB and linear round-trip and keep alpha", () => {
+    const lin = srgbToLinear({ r: 0.5, g: 0.2, b: 0.9, a: 0.4 });
+    if (lin === null) return;
+    assert.ok(near(lin.r, 0.214));
+    assert.equal(lin.a, 0.4);
+    const back = linearToSrgb(lin)!;
+    assert.ok(near(back.r, 0.5) && near(back.g, 0.2) && near(back.b, 0.9));
+  });
+
+  it("HSV of pure red and back", () => {
+    const hsv = colorToHsv(RED);
+    if (hsv === null) return;
+    assert.ok(near(hsv.h, 0) && near(hsv.s, 1) && near(hsv.v, 1));
+    const c = hsvToColor(120, 1, 1)!;
+    assert.ok(near(c.r, 0) && near(c.g, 1) && near(c.b, 0));
+  });
+
+  it("OKLCH round-trips in-gamut colors", () => {
+    const lch = colorToOklch(BLUE);
+    if (lch === null) return;
+    assert.equal(lch.a, 0.5);
+    const back = oklchToColor(lch.l, lch.c, lch.h, lch.a)!;
+    assert.ok(near(back.r, 0, 1e-2) && near(back.g, 0, 1e-2) && near(back.b, 1, 1e-2));
+  });
+
+  it("oklchToColor pulls out-of-gamut chroma into 0-1", () => {
+    const c = oklchToColor(0.7, 0.5, 140);
+    if (c === null) return;
+    for (const ch of [c.r, c.g, c.b]) assert.ok(ch >= 0 && ch <= 1);
+  });
+});
+
+describe("mixing and ramps", () => {
+  it("mixColors hits the endpoints and mixes alpha linearly", () => {
+    const start = mixColors(RED, BLUE, 0);
+    if (start === null) return;
+    assert.ok(near(start.r, 1) && near(start.b, 0));
+    const mid = mixColors(RED, BLUE, 0.5)!;
+    assert.ok(near(mid.a, 0.75));
+  });
+
+  it("red to green stays brighter than a channel lerp", () => {
+    const mid = mixColors(RED, GREEN, 0.5);
+    if (mid === null) return;
+    assert.ok(mid.r + mid.g > 1);
+  });
+
+  it("colorRamp returns count colors from first to last stop", () => {
+    const ramp = colorRamp([RED, GREEN, BLUE], 5);
+    if (ramp === null) return;
+    assert.equal(ramp.length, 5);
+    assert.ok(near(ramp[0].r, 1));
+    assert.ok(near(ramp[2].g, 1));
+    assert.ok(near(ramp[4].b, 1) && near(ramp[4].a, 0.5));
+  });
+
+  it("colorRamp of no stops is empty", () => {
+    const ramp = colorRamp([], 4);
+    if (ramp === null) return;
+    assert.equal(ramp.length, 0);
+  });
+
+  it("rampToCosinePalette returns a cosine palette fill", () => {
+    const fill = rampToCosinePalette([RED, BLUE]);
+    if (fill === null) return;
+    assert.equal(fill.type, "cosine_palette");
+    assert.equal(fill.a.length, 3);
+    assert.equal(fill.d.length, 3);
+  });
+});
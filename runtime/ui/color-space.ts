@@ -0,0 +1,171 @@
+/**
+ * Color space conversions, perceptual mixing and palette ramps, backed by Rust ops.
+ *
+ * `Color` values are sRGB (what sprites, text and shapes take). Mix and ramp
+ * in OKLab instead of lerping channels: a channel lerp between red and green
+ * passes through a muddy olive, while {@link mixColors} stays bright. Use
+ * OKLCH to pick colors by lightness, chroma and hue, and linear light for
+ * physically based math (adding light intensities, averaging).
+ *
+ * Every function returns `null` outside the Arcane runtime (no ops).
+ *
+ * @example
+ * ```ts
+ * import { colorRamp, rampToCosinePalette, oklchToColor } from "@arcane/runtime/ui";
+ *
+ * // 8 evenly spaced health-bar colors from red through yellow to green
+ * const health = colorRamp([Colors.DANGER, Colors.WARNING, Colors.SUCCESS], 8);
+ *
+ * // The same ramp as SDF cosine palette parameters
+ * const fill = rampToCosinePalette([Colors.DANGER, Colors.WARNING, Colors.SUCCESS]);
+ *
+ * // A pastel at a given hue: lightness 0.85, low chroma
+ * const pastel = oklchToColor(0.85, 0.08, 200);
+ * ```
+ */
+
+import type { Color } from "./types.ts";
+import type { CosinePaletteFill } from "../rendering/sdf.ts";
+
+const hasColorOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_rgb_to_oklch === "function";
+
+/** A color in HSV: hue in degrees (0-360), saturation and value 0-1. */
+export type Hsv = { h: number; s: number; v: number; a: number };
+
+/** A color in OKLCH: lightness 0-1, chroma 0 to about 0.37, hue in degrees (0-360). */
+export type Oklch = { l: number; c: number; h: number; a: number };
+
+function ops(): any {
+  return (globalThis as any).Deno.core.ops;
+}
+
+function toColor(rgb: number[], a: number): Color {
+  return { r: rgb[0], g: rgb[1], b: rgb[2], a };
+}
+
+function packStops(stops: Color[]): Float32Array {
+  const packed = new Float32Array(stops.length * 4);
+  stops.forEach((c, i) => packed.set([c.r, c.g, c.b, c.a], i * 4));
+  return packed;
+}
+
+/**
+ * Decode an sRGB color to linear light. Alpha is unchanged.
+ * Returns null outside the Arcane runtime.
+ */
+export function srgbToLinear(color: Color): Color | null {
+  if (!hasColorOps) return null;
+  return toColor(ops().op_srgb_to_linear(color.r, color.g, color.b), color.a);
+}
+
+/**
+ * Encode a linear-light color as sRGB. Alpha is unchanged.
+ * Returns null outside the Arcane runtime.
+ */
+export function linearToSrgb(color: Color): Color | null {
+  if (!hasColorOps) return null;
+  return toColor(ops().op_linear_to_srgb(color.r, color.g, color.b), color.a);
+}
+
+/**
+ * Convert a color to HSV. Returns null outside the Arcane runtime.
+ *
+ * @example
+ * const hsv = colorToHsv(Colors.PRIMARY)!;
+ * const shifted = hsvToColor(hsv.h + 30, hsv.s, hsv.v);
+ */
+export function colorToHsv(color: Color): Hsv | null {
+  if (!hasColorOps) return null;
+  const [h, s, v] = ops().op_rgb_to_hsv(color.r, color.g, color.b);
+  return { h, s, v, a: color.a };
+}
+
+/**
+ * Create a color from HSV. Hue wraps around; saturation and value are clamped to 0-1.
+ * Returns null outside the Arcane runtime.
+ *
+ * @param h - Hue in degrees.
+ * @param s - Saturation, 0-1.
+ * @param v - Value (brightness), 0-1.
+ * @param a - Alpha. Default: 1.
+ */
+export function hsvToColor(h: number, s: number, v: number, a: number = 1): Color | null {
+  if (!hasColorOps) return null;
+  return toColor(ops().op_hsv_to_rgb(h, s, v), a);
+}
+
+/**
+ * Convert a color to OKLCH. Returns null outside the Arcane runtime.
+ */
+export function colorToOklch(color: Color): Oklch | null {
+  if (!hasColorOps) return null;
+  const [l, c, h] = ops().op_rgb_to_oklch(color.r, color.g, color.b);
+  return { l, c, h, a: color.a };
+}
+
+/**
+ * Create a color from OKLCH. Colors sRGB can't show keep their lightness and
+ * hue and lose chroma until they fit. Returns null outside the Arcane runtime.
+ *
+ * @param l - Lightness, 0 (black) to 1 (white).
+ * @param c - Chroma, 0 (gray) to about 0.37.
+ * @param h - Hue in degrees.
+ * @param a - Alpha. Default: 1.
+ */
+export function oklchToColor(l: number, c: number, h: number, a: number = 1): Color | null {
+  if (!hasColorOps) return null;
+  return toColor(ops().op_oklch_to_rgb(l, c, h), a);
+}
+
+/**
+ * Mix two colors perceptually (in OKLab). `t` = 0 gives `from`, 1 gives `to`;
+ * alpha mixes linearly. Returns null outside the Arcane runtime.
+ */
+export function mixColors(from: Color, to: Color, t: number): Color | null {
+  if (!hasColorOps) return null;
+  const [r, g, b, a] = ops().op_mix_oklab(from.r, from.g, from.b, from.a, to.r, to.g, to.b, to.a, t);
+  return { r, g, b, a };
+}
+
+/**
+ * `count` colors evenly spaced along `stops` (which are evenly spaced too),
+ * mixed in OKLab. The first and last colors are the first and last stops.
+ * Use for tint gradients, light color cycles, heat maps and health bars.
+ * Returns null outside the Arcane runtime.
+ *
+ * @example
+ * const fire = colorRamp([rgb(40, 0, 0), rgb(255, 90, 0), rgb(255, 240, 180)], 16)!;
+ * const tint = fire[Math.floor(heat * 15)];
+ */
+export function colorRamp(stops: Color[], count: number): Color[] | null {
+  if (!hasColorOps) return null;
+  const flat: number[] = ops().op_color_ramp(packStops(stops), Math.max(0, count) >>> 0);
+  const colors: Color[] = [];
+  for (let i = 0; i + 3 < flat.length; i += 4) {
+    colors.push({ r: flat[i], g: flat[i + 1], b: flat[i + 2], a: flat[i + 3] });
+  }
+  return colors;
+}
+
+/**
+ * Fit SDF cosine palette parameters to the ramp through `stops` (see
+ * {@link colorRamp}), so `t` from 0 to 1 walks the ramp. A close
+ * approximation, not exact: a cosine can't bend like every ramp.
+ * Returns null outside the Arcane runtime.
+ *
+ * @example
+ * sdfEntity({ shape: sdfCircle(40), fill: rampToCosinePalette([Colors.PRIMARY, Colors.WHITE])! });
+ */
+export function rampToCosinePalette(stops: Color[]): CosinePaletteFill | null {
+  if (!hasColorOps) return null;
+  const p: number[] = ops().op_fit_cosine_palette(packStops(stops));
+  return {
+    type: "cosine_palette",
+    a: [p[0], p[1], p[2]],
+    b: [p[3], p[4], p[5]],
+    c: [p[6], p[7], p[8]],
+    d: [p[9], p[10], p[11]],
+  };
+}
@@ -0,0 +1,54 @@
+import { describe, it, assert } from "../testing/harness.ts";
+import { formatNumber, formatDuration } from "./format.ts";
+
+describe("formatNumber", () => {
+  it("groups and separates by locale", () => {
+    const en = formatNumber(1234567.891, "en-US");
+    if (en === null) return;
+    assert.equal(en, "1,234,567.891");
+    assert.equal(formatNumber(1234567.891, "de"), "1.234.567,891");
+    assert.equal(formatNumber(1234567, "en-IN"), "12,34,567");
+  });
+
+  it("defaults to English and falls back for unknown locales", () => {
+    const n = formatNumber(9876.5);
+    if (n === null) return;
+    assert.equal(n, "9,876.5");
+    assert.equal(formatNumber(9876.5, "xx-YY"), "9,876.5");
+    assert.equal(formatNumber(9876.5, "de-AT"), "9.876,5");
+  });
+
+  it("pads and rounds fraction digits", () => {
+    const padded = formatNumber(3, "en", { minimumFractionDigits: 2 });
+    if (padded === null) return;
+    assert.equal(padded, "3.00");
+    assert.equal(formatNumber(2.5, "en", { maximumFractionDigits: 0 }), "3");
+    assert.equal(formatNumber(1234, "en", { useGrouping: false }), "1234");
+  });
+
+  it("formats percents and signs", () => {
+    const pct = formatNumber(0.256, "en", { style: "percent" });
+    if (pct === null) return;
+    assert.equal(pct, "26%");
+    assert.equal(formatNumber(12, "en", { signDisplay: "always" }), "+12");
+    assert.equal(formatNumber(0, "en", { signDisplay: "always" }), "0");
+  });
+});
+
+describe("formatDuration", () => {
+  it("formats clock, timer and short styles", () => {
+    const clock = formatDuration(65_000);
+    if (clock === null) return;
+    assert.equal(clock, "1:05");
+    assert.equal(formatDuration(3_725_000, "clock"), "1:02:05");
+    assert.equal(formatDuration(65_259, "timer"), "1:05.25");
+    assert.equal(formatDuration(3_725_000, "short"), "1h 2m 5s");
+  });
+
+  it("truncates partial seconds and signs negative spans", () => {
+    const almost = formatDuration(59_999);
+    if (almost === null) return;
+    assert.equal(almost, "0:59");
+    assert.equal(formatDuration(-65_000), "-1:05");
+  });
+});
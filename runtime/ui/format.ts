@@ -0,0 +1,88 @@
+/**
+ * Locale-aware number and duration formatting, backed by Rust ops.
+ *
+ * `toLocaleString()` depends on the ICU data compiled into V8, which the
+ * embedded runtime doesn't fully ship: separators and grouping can differ
+ * between platforms, or fall back to plain English. These helpers format
+ * from a built-in locale table instead, so a score reads the same
+ * everywhere.
+ *
+ * Built-in locales: en, en-IN, hi, de, de-CH, fr, es, it, pt, pt-PT, nl, ru,
+ * pl, sv, tr, ja, zh, ko. Other tags fall back to their language
+ * ("de-AT" → "de"), then to English.
+ *
+ * Both functions return `null` outside the Arcane runtime (no ops).
+ *
+ * @example
+ * ```ts
+ * import { formatNumber, formatDuration } from "@arcane/runtime/ui";
+ *
+ * formatNumber(1234567.5, "de");                           // "1.234.567,5"
+ * formatNumber(0.256, "en", { style: "percent" });         // "26%"
+ * formatNumber(12, "en", { signDisplay: "always" });       // "+12"
+ * formatDuration(65_250, "timer");                         // "1:05.25"
+ * ```
+ */
+
+/** Options for {@link formatNumber}, a subset of `Intl.NumberFormat`'s. */
+export type FormatNumberOptions = {
+  /** "percent" multiplies by 100 and adds the locale's percent sign. Default: "decimal". */
+  style?: "decimal" | "percent";
+  /** Fraction digits always shown (zero-padded). Default: 0. */
+  minimumFractionDigits?: number;
+  /** Fraction digits at most; rounds half away from zero. Default: 3 (0 for percent). */
+  maximumFractionDigits?: number;
+  /** Insert group separators (1,234). Default: true. */
+  useGrouping?: boolean;
+  /** "always" adds "+" to positives, "never" drops the sign. Zero is never signed. Default: "auto". */
+  signDisplay?: "auto" | "always" | "never";
+};
+
+/**
+ * How {@link formatDuration} lays out a time span:
+ * - `"clock"`: "1:05", or "1:02:05" past an hour
+ * - `"timer"`: clock with hundredths, "1:05.25"
+ * - `"short"`: units, "1h 2m 5s"
+ */
+export type DurationStyle = "clock" | "timer" | "short";
+
+const hasFormatOps =
+  typeof (globalThis as any).Deno !== "undefined" &&
+  typeof (globalThis as any).Deno?.core?.ops?.op_format_number === "function";
+
+/**
+ * Format a number with a locale's separators and grouping.
+ * Returns null outside the Arcane runtime.
+ *
+ * @param value - The number. NaN formats as "NaN", infinities as "∞".
+ * @param locale - BCP 47 tag ("en-US", "fr"). Default: "en".
+ * @param options - Fraction digits, grouping, percent and sign options.
+ */
+export function formatNumber(
+  value: number,
+  locale: string = "en",
+  options?: FormatNumberOptions,
+): string | null {
+  if (!hasFormatOps) return null;
+  return (globalThis as any).Deno.core.ops.op_format_number(
+    value,
+    locale,
+    JSON.stringify(options ?? {}),
+  );
+}
+
+/**
+ * Format a duration in milliseconds. Partial units are truncated, so a
+ * timer never shows a second that hasn't fully passed. Negative spans get
+ * a leading "-". Returns null outside the Arcane runtime.
+ *
+ * @param ms - Duration in milliseconds.
+ * @param style - Layout. Default: "clock".
+ */
+export function formatDuration(
+  ms: number,
+  style: DurationStyle = "clock",
+): string | null {
+  if (!hasFormatOps) return null;
+  return (globalThis as any).Deno.core.ops.op_format_duration(ms, style);
+}
@@ -3,6 +3,10 @@ export { rgb } from "./types.ts";
 export { drawRect, drawPanel, drawBar, drawLabel } from "./primitives.ts";
 export { drawCircle, drawEllipse, drawRing, drawLine, drawTriangle, drawArc, drawSector, drawCapsule, drawPolygon, drawRectangle } from "./shapes.ts";
 export { Colors, HUDLayout, withAlpha, setAlpha, setRgb, lerpColorInto, lighten, darken } from "./colors.ts";
+export type { Hsv, Oklch } from "./color-space.ts";
+export { srgbToLinear, linearToSrgb, colorToHsv, hsvToColor, colorToOklch, oklchToColor, mixColors, colorRamp, rampToCosinePalette } from "./color-space.ts";
+export type { FormatNumberOptions, DurationStyle } from "./format.ts";
+export { formatNumber, formatDuration } from "./format.ts";
 
 // Interactive UI widgets (Phase 16)
 export type { ButtonVisual, ButtonStyle, ButtonState } from "./button.ts";
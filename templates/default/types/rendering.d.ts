@@ -2778,6 +2778,10 @@ declare module "@arcane/runtime/rendering" {
    * the four edges stretch in one dimension, and the center stretches in both.
    * This allows scalable UI panels without corner distortion.
    *
+   * With the renderer active, each panel is one `op_draw_nine_slice` call and
+   * Rust generates the nine quads from the texture's real size. In headless
+   * mode the slices are emitted as individual drawSprite calls.
+   *
    * ```
    *  ┌────┬────────┬────┐
    *  │ TL │  Top   │ TR │   Corners: fixed size
@@ -2811,6 +2815,11 @@ declare module "@arcane/runtime/rendering" {
        * or a per-edge object.
        */
       border: number | NineSliceBorder;
+      /**
+       * Size of the drawn borders relative to their size in the texture, e.g. 2
+       * for a pixel-art frame at double size. Default: 1.
+       */
+      borderScale?: number;
       /** Draw order layer. Default: 0. */
       layer?: number;
       /** Tint color. Default: white (no tint). */
@@ -2825,8 +2834,8 @@ declare module "@arcane/runtime/rendering" {
       /** If true, x/y/w/h are in screen pixels. Default: false. */
       screenSpace?: boolean;
       /**
-       * Texture dimensions in pixels. Required for correct UV calculation.
-       * If not provided, assumes 1:1 mapping (UV border = border / 256).
+       * Texture dimensions in pixels, used for the UV calculation in headless
+       * mode. The renderer uses the texture's real size. Default: 256.
        */
       textureWidth?: number;
       /** Texture height in pixels. Default: 256. */
@@ -2838,6 +2847,7 @@ declare module "@arcane/runtime/rendering" {
    * The texture is divided into a 3x3 grid based on `border` insets. Corners
    * are drawn at fixed size, edges stretch, and the center fills the remainder.
    *
+   * Panels smaller than two opposite borders shrink those borders to fit.
    * No-op in headless mode (drawSprite is a no-op).
    *
    * @param textureId - Texture handle from loadTexture().